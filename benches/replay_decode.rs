@@ -0,0 +1,136 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Replay decode throughput
+//!
+//! Compares three ways of decoding a stored event stream:
+//!
+//! - `serde tagged` - `serde_json::from_slice::<EventEnvelope>` (baseline)
+//! - `decoder` - `decode_envelope` (borrowed discriminator, direct variant)
+//! - `state only` - `decode_envelope_if(is_state_changing)`, which is what an
+//!   aggregate rebuild needs
+//!
+//! The stream mimics production: each agent is deployed, configured and
+//! activated, then handles conversations of ~20 chunk events each.
+//!
+//! ```bash
+//! REPLAY_EVENTS=1000000 cargo bench --bench replay_decode
+//! ```
+
+use chrono::Utc;
+use cim_domain_agent::events::*;
+use cim_domain_agent::infrastructure::{
+    decode_envelope, decode_envelope_if, is_state_changing, EventEnvelope,
+};
+use cim_domain_agent::value_objects::{
+    AgentId, FinishReason, MessageId, ModelConfig, PersonId, StreamingChunk, TokenUsage,
+};
+use std::hint::black_box;
+use std::time::Instant;
+use uuid::Uuid;
+
+fn envelope(sequence: u64, event: AgentEvent) -> Vec<u8> {
+    serde_json::to_vec(&EventEnvelope {
+        aggregate_id: event.agent_id(),
+        sequence,
+        event,
+        timestamp: Utc::now(),
+        correlation_id: Uuid::now_v7(),
+        causation_id: Uuid::now_v7(),
+        metadata: None,
+    })
+    .expect("envelope serializes")
+}
+
+/// Build roughly `target` serialized envelopes
+fn build_stream(target: usize) -> Vec<Vec<u8>> {
+    let mut payloads = Vec::with_capacity(target);
+
+    while payloads.len() < target {
+        let agent_id = AgentId::new();
+        let mut sequence = 0;
+        let mut push = |event: AgentEvent| {
+            sequence += 1;
+            payloads.push(envelope(sequence, event));
+        };
+
+        push(AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            "bench-agent",
+            None,
+        )));
+        push(AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+            agent_id,
+            ModelConfig::mock(),
+        )));
+        push(AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id)));
+
+        for _ in 0..50 {
+            let message_id = MessageId::new();
+            push(AgentEvent::MessageSent(MessageSentEvent::new(
+                agent_id,
+                message_id,
+                "How should we size the event stream?",
+            )));
+            for index in 0..20 {
+                push(AgentEvent::ResponseChunkReceived(ResponseChunkReceivedEvent::new(
+                    agent_id,
+                    message_id,
+                    StreamingChunk::new(index, "a few words of streamed response "),
+                )));
+            }
+            push(AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+                agent_id,
+                message_id,
+                20,
+                TokenUsage::default(),
+                FinishReason::Stop,
+                250,
+            )));
+        }
+    }
+
+    payloads
+}
+
+fn run(label: &str, payloads: &[Vec<u8>], decode: impl Fn(&[u8]) -> bool) {
+    let start = Instant::now();
+    let decoded = payloads.iter().filter(|p| decode(p)).count();
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<13} {:>9} events in {:>8.2?}  {:>12.0} events/s  ({} fully decoded)",
+        label,
+        payloads.len(),
+        elapsed,
+        payloads.len() as f64 / elapsed.as_secs_f64(),
+        decoded
+    );
+}
+
+fn main() {
+    let target = std::env::var("REPLAY_EVENTS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1_000_000);
+
+    let payloads = build_stream(target);
+    let bytes: usize = payloads.iter().map(Vec::len).sum();
+    println!(
+        "replaying {} events ({:.1} MiB)",
+        payloads.len(),
+        bytes as f64 / (1024.0 * 1024.0)
+    );
+
+    run("serde tagged", &payloads, |p| {
+        black_box(serde_json::from_slice::<EventEnvelope>(p).expect("decodes"));
+        true
+    });
+    run("decoder", &payloads, |p| {
+        black_box(decode_envelope(p).expect("decodes"));
+        true
+    });
+    run("state only", &payloads, |p| {
+        black_box(decode_envelope_if(p, is_state_changing).expect("decodes")).is_some()
+    });
+}
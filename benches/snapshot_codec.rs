@@ -0,0 +1,125 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Snapshot codec overhead
+//!
+//! Encodes and decodes the same agent snapshot four ways and reports the
+//! time per snapshot and the stored size:
+//!
+//! - `plain` - header + JSON (baseline)
+//! - `zstd` - compressed at the default level
+//! - `aes-gcm` - sealed with a tenant key
+//! - `zstd+aes-gcm` - compressed, then sealed
+//!
+//! The agent carries a long system prompt, as configured agents usually do.
+//!
+//! ```bash
+//! SNAPSHOT_ROUNDS=10000 cargo bench --bench snapshot_codec
+//! ```
+
+use chrono::Utc;
+use cim_domain_agent::events::*;
+use cim_domain_agent::infrastructure::{
+    InMemorySnapshotKeyring, Snapshot, SnapshotCodec, SnapshotEncryptionKey,
+};
+use cim_domain_agent::value_objects::{AgentId, ModelConfig, PersonId};
+use cim_domain_agent::Agent;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::Instant;
+
+fn snapshot() -> Snapshot {
+    let agent_id = AgentId::new();
+    let prompt = "You are a careful assistant for the infrastructure team. ".repeat(80);
+    let agent = Agent::empty()
+        .apply_events(&[
+            AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                agent_id,
+                PersonId::new(),
+                "bench-agent",
+                None,
+            )),
+            AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+                agent_id,
+                ModelConfig::mock().with_system_prompt(prompt),
+            )),
+            AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id)),
+        ])
+        .expect("agent builds");
+
+    Snapshot {
+        aggregate_id: agent_id,
+        version: agent.version(),
+        agent,
+        created_at: Utc::now(),
+    }
+}
+
+fn run(label: &str, codec: &SnapshotCodec, snapshot: &Snapshot, rounds: u32) {
+    let encoded = codec
+        .encode_for_tenant(snapshot, Some("acme"))
+        .expect("encodes");
+
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(
+            codec
+                .encode_for_tenant(black_box(snapshot), Some("acme"))
+                .expect("encodes"),
+        );
+    }
+    let encode = start.elapsed() / rounds;
+
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(
+            codec
+                .decode::<Snapshot>(black_box(&encoded))
+                .expect("decodes"),
+        );
+    }
+    let decode = start.elapsed() / rounds;
+
+    println!(
+        "{:<13} encode {:>9.2?}  decode {:>9.2?}  {:>7} bytes",
+        label,
+        encode,
+        decode,
+        encoded.len()
+    );
+}
+
+fn main() {
+    let rounds = std::env::var("SNAPSHOT_ROUNDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10_000);
+
+    let keyring = Arc::new(InMemorySnapshotKeyring::new());
+    keyring.add_key(SnapshotEncryptionKey::generate("acme").expect("key generates"));
+    keyring.assign("acme", "acme");
+
+    let snapshot = snapshot();
+    println!("{} rounds per codec", rounds);
+
+    run("plain", &SnapshotCodec::new(), &snapshot, rounds);
+    run(
+        "zstd",
+        &SnapshotCodec::new().with_compression(),
+        &snapshot,
+        rounds,
+    );
+    run(
+        "aes-gcm",
+        &SnapshotCodec::new().with_encryption(keyring.clone()),
+        &snapshot,
+        rounds,
+    );
+    run(
+        "zstd+aes-gcm",
+        &SnapshotCodec::new()
+            .with_compression()
+            .with_encryption(keyring),
+        &snapshot,
+        rounds,
+    );
+}
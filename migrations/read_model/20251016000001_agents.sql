@@ -0,0 +1,39 @@
+-- Agent read model: current state, bitemporal revisions and capabilities
+
+CREATE TABLE IF NOT EXISTS agents (
+    agent_id    UUID PRIMARY KEY,
+    person_id   UUID NOT NULL,
+    name        TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    provider    TEXT,
+    model_name  TEXT,
+    created_at  TIMESTAMPTZ NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL,
+    version     BIGINT NOT NULL,
+    -- Projected aggregate, replayed forward by the next event
+    state       JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS agents_page_idx ON agents (created_at, agent_id);
+CREATE INDEX IF NOT EXISTS agents_status_idx ON agents (status, created_at, agent_id);
+CREATE INDEX IF NOT EXISTS agents_person_idx ON agents (person_id);
+
+CREATE TABLE IF NOT EXISTS agent_revisions (
+    agent_id    UUID NOT NULL REFERENCES agents (agent_id) ON DELETE CASCADE,
+    version     BIGINT NOT NULL,
+    status      TEXT NOT NULL,
+    valid_from  TIMESTAMPTZ NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    view        JSONB NOT NULL,
+    PRIMARY KEY (agent_id, version)
+);
+
+CREATE INDEX IF NOT EXISTS agent_revisions_valid_idx ON agent_revisions (valid_from);
+
+CREATE TABLE IF NOT EXISTS agent_capabilities (
+    agent_id    UUID NOT NULL REFERENCES agents (agent_id) ON DELETE CASCADE,
+    capability  TEXT NOT NULL,
+    PRIMARY KEY (agent_id, capability)
+);
+
+CREATE INDEX IF NOT EXISTS agent_capabilities_idx ON agent_capabilities (capability);
@@ -0,0 +1,50 @@
+-- Agent read model: messages, usage totals, archived conversations and tool selections
+
+CREATE TABLE IF NOT EXISTS agent_messages (
+    message_id      UUID PRIMARY KEY,
+    agent_id        UUID NOT NULL,
+    content         TEXT NOT NULL,
+    sent_at         TIMESTAMPTZ NOT NULL,
+    status          TEXT NOT NULL,
+    chunks          INTEGER NOT NULL DEFAULT 0,
+    finish_reason   JSONB,
+    token_usage     JSONB,
+    -- Set once the message's conversation is archived; hidden from recent messages
+    archived_at     TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS agent_messages_recent_idx ON agent_messages (agent_id, sent_at DESC);
+
+CREATE TABLE IF NOT EXISTS agent_usage (
+    agent_id            UUID PRIMARY KEY,
+    messages_sent       BIGINT NOT NULL DEFAULT 0,
+    responses_completed BIGINT NOT NULL DEFAULT 0,
+    responses_failed    BIGINT NOT NULL DEFAULT 0,
+    responses_cancelled BIGINT NOT NULL DEFAULT 0,
+    prompt_tokens       BIGINT NOT NULL DEFAULT 0,
+    completion_tokens   BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS agent_conversations (
+    conversation_id  UUID PRIMARY KEY,
+    agent_id         UUID NOT NULL,
+    message_count    INTEGER NOT NULL,
+    first_message_at TIMESTAMPTZ NOT NULL,
+    last_message_at  TIMESTAMPTZ NOT NULL,
+    archived_at      TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS agent_conversations_agent_idx
+    ON agent_conversations (agent_id, archived_at DESC);
+
+CREATE TABLE IF NOT EXISTS agent_tool_selections (
+    agent_id    UUID NOT NULL,
+    message_id  UUID NOT NULL,
+    step_id     INTEGER NOT NULL,
+    summary     TEXT NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (message_id, step_id)
+);
+
+CREATE INDEX IF NOT EXISTS agent_tool_selections_agent_idx
+    ON agent_tool_selections (agent_id, recorded_at);
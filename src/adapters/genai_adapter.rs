@@ -8,73 +8,90 @@
 #[cfg(feature = "genai-adapter")]
 mod inner {
     use crate::capabilities::RuntimeCapabilities;
-    use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream};
+    use crate::ports::{
+        api_key_secret_name, ChatError, ChatPort, ChatResult, ChatStream, Secret, SecretsProvider,
+    };
     use crate::value_objects::{ContextMessage, FinishReason, ModelConfig, ProviderType, StreamingChunk};
     use async_trait::async_trait;
     use futures::stream;
     use genai::adapter::AdapterKind;
-    use genai::chat::{ChatMessage, ChatRequest, MessageContent};
+    use genai::chat::{ChatMessage, ChatOptions, ChatRequest, MessageContent};
     use genai::resolver::{AuthData, Endpoint, ServiceTargetResolver};
     use genai::{Client, ModelIden, ServiceTarget};
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+
+    /// API keys resolved from the secrets provider, read by the genai resolver
+    type ApiKeys = Arc<RwLock<HashMap<AdapterKind, Secret>>>;
 
     /// genai-based adapter for multi-provider AI
     ///
     /// Uses the genai crate to support OpenAI, Anthropic, Ollama, Gemini, etc.
+    /// API keys come from the provider SDKs' environment variables unless a
+    /// [`SecretsProvider`] is configured with [`GenaiAdapter::with_secrets`].
     pub struct GenaiAdapter {
         client: Client,
         capabilities: RuntimeCapabilities,
+        secrets: Option<Arc<dyn SecretsProvider>>,
+        api_keys: ApiKeys,
+        provider: Option<ProviderType>,
     }
 
     impl GenaiAdapter {
         /// Create a new genai adapter
         pub fn new() -> ChatResult<Self> {
-            // Create a resolver that supports custom Ollama endpoints
-            let target_resolver = ServiceTargetResolver::from_resolver_fn(
-                |service_target: ServiceTarget| -> Result<ServiceTarget, genai::resolver::Error> {
-                    let ServiceTarget { model, endpoint, auth } = service_target;
+            Self::with_capabilities(RuntimeCapabilities::BASIC_CHAT)
+        }
 
-                    // If this is an Ollama model and OLLAMA_HOST is set, use custom endpoint
-                    if model.adapter_kind == AdapterKind::Ollama {
-                        if let Ok(ollama_host) = std::env::var("OLLAMA_HOST") {
-                            let custom_endpoint = Endpoint::from_owned(ollama_host);
-                            // For Ollama's OpenAI-compatible API:
-                            // 1. Strip the adapter prefix: "mistral:7b" not "ollama/mistral:7b"
-                            // 2. Use OpenAI adapter since Ollama uses OpenAI-compatible API
-                            let model_name_stripped = model.model_name.trim_start_matches("ollama/");
-                            let corrected_model = ModelIden::new(AdapterKind::OpenAI, model_name_stripped);
-                            return Ok(ServiceTarget {
-                                endpoint: custom_endpoint,
-                                auth, // Keep original auth
-                                model: corrected_model,
-                            });
-                        }
-                    }
+        /// Create with specific capabilities
+        pub fn with_capabilities(capabilities: RuntimeCapabilities) -> ChatResult<Self> {
+            let api_keys = ApiKeys::default();
+            Ok(Self {
+                client: Self::build_client(api_keys.clone(), None),
+                capabilities,
+                secrets: None,
+                api_keys,
+                provider: None,
+            })
+        }
 
-                    // Otherwise use default
-                    Ok(ServiceTarget { model, endpoint, auth })
-                },
-            );
+        /// Builder: look API keys up in `secrets` before every request
+        ///
+        /// Keys rotated in the backing store are used on the next request.
+        pub fn with_secrets(mut self, secrets: Arc<dyn SecretsProvider>) -> Self {
+            self.secrets = Some(secrets);
+            self
+        }
 
-            let client = Client::builder()
-                .with_service_target_resolver(target_resolver)
-                .build();
+        /// Builder: the provider this adapter is registered for
+        ///
+        /// `health_check` and `has_model` list that provider's models, which
+        /// proves it is reachable and accepts the key without spending tokens.
+        pub fn with_provider(mut self, provider: ProviderType) -> Self {
+            self.provider = Some(provider);
+            self
+        }
 
-            Ok(Self {
-                client,
-                capabilities: RuntimeCapabilities::BASIC_CHAT,
-            })
+        /// Builder: send requests to `endpoint` instead of the provider's default
+        ///
+        /// For Ollama this takes precedence over `OLLAMA_HOST`.
+        pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+            self.client = Self::build_client(self.api_keys.clone(), Some(endpoint.into()));
+            self
         }
 
-        /// Create with specific capabilities
-        pub fn with_capabilities(capabilities: RuntimeCapabilities) -> ChatResult<Self> {
+        fn build_client(api_keys: ApiKeys, configured_endpoint: Option<String>) -> Client {
             // Create a resolver that supports custom Ollama endpoints
             let target_resolver = ServiceTargetResolver::from_resolver_fn(
-                |service_target: ServiceTarget| -> Result<ServiceTarget, genai::resolver::Error> {
+                move |service_target: ServiceTarget| -> Result<ServiceTarget, genai::resolver::Error> {
                     let ServiceTarget { model, endpoint, auth } = service_target;
 
-                    // If this is an Ollama model and OLLAMA_HOST is set, use custom endpoint
+                    // If this is an Ollama model and an endpoint or OLLAMA_HOST is set, use it
                     if model.adapter_kind == AdapterKind::Ollama {
-                        if let Ok(ollama_host) = std::env::var("OLLAMA_HOST") {
+                        let host = configured_endpoint
+                            .clone()
+                            .or_else(|| std::env::var("OLLAMA_HOST").ok());
+                        if let Some(ollama_host) = host {
                             let custom_endpoint = Endpoint::from_owned(ollama_host);
                             // For Ollama's OpenAI-compatible API:
                             // 1. Strip the adapter prefix: "mistral:7b" not "ollama/mistral:7b"
@@ -89,19 +106,77 @@ mod inner {
                         }
                     }
 
-                    // Otherwise use default
+                    // Prefer a key resolved through the secrets provider
+                    let auth = match api_keys
+                        .read()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .get(&model.adapter_kind)
+                    {
+                        Some(key) => AuthData::from_single(key.expose().to_string()),
+                        None => auth,
+                    };
+
+                    // Otherwise use the configured endpoint or the default
+                    let endpoint = match &configured_endpoint {
+                        Some(url) => Endpoint::from_owned(url.clone()),
+                        None => endpoint,
+                    };
                     Ok(ServiceTarget { model, endpoint, auth })
                 },
             );
 
-            let client = Client::builder()
+            Client::builder()
                 .with_service_target_resolver(target_resolver)
-                .build();
+                .build()
+        }
 
-            Ok(Self {
-                client,
-                capabilities,
-            })
+        /// Fetch the current API key for `provider` from the secrets provider
+        async fn refresh_api_key(&self, provider: ProviderType) -> ChatResult<()> {
+            let Some(secrets) = &self.secrets else {
+                return Ok(());
+            };
+            let (Some(name), Some(kind)) = (api_key_secret_name(provider), Self::adapter_kind(provider))
+            else {
+                return Ok(());
+            };
+            let key = secrets
+                .get(name)
+                .await
+                .map_err(|e| ChatError::AuthenticationFailed(e.to_string()))?;
+            self.api_keys
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(kind, key);
+            Ok(())
+        }
+
+        fn adapter_kind(provider: ProviderType) -> Option<AdapterKind> {
+            match provider {
+                ProviderType::OpenAI => Some(AdapterKind::OpenAI),
+                ProviderType::Anthropic => Some(AdapterKind::Anthropic),
+                ProviderType::Gemini => Some(AdapterKind::Gemini),
+                ProviderType::Ollama | ProviderType::Mock => None,
+            }
+        }
+
+        /// Models the configured provider lists, asked with its current API key
+        async fn list_models(&self) -> ChatResult<Vec<String>> {
+            let provider = self.provider.ok_or_else(|| {
+                ChatError::ConfigurationError(
+                    "genai adapter has no provider to check; use with_provider".to_string(),
+                )
+            })?;
+            let kind = match provider {
+                ProviderType::Ollama => AdapterKind::Ollama,
+                provider => Self::adapter_kind(provider).ok_or_else(|| {
+                    ChatError::ConfigurationError(format!("genai does not serve {}", provider))
+                })?,
+            };
+            self.refresh_api_key(provider).await?;
+            self.client
+                .all_model_names(kind)
+                .await
+                .map_err(|e| ChatError::ProviderError(e.to_string()))
         }
 
         /// Get the capabilities this adapter supports
@@ -131,24 +206,47 @@ mod inner {
                 ProviderType::OpenAI => format!("openai/{}", config.model_name),
                 ProviderType::Anthropic => format!("anthropic/{}", config.model_name),
                 ProviderType::Ollama => format!("ollama/{}", config.model_name),
+                ProviderType::Gemini => format!("gemini/{}", config.model_name),
                 ProviderType::Mock => "mock/mock-model".to_string(),
             }
         }
 
+        /// Translate the effective sampling parameters to genai chat options
+        ///
+        /// genai normalizes names across providers, so only the parameters
+        /// it exposes are forwarded.
+        fn chat_options(config: &ModelConfig) -> ChatOptions {
+            let params = config.sampling_params();
+            let mut options = ChatOptions::default();
+            if let Some(temperature) = params.temperature {
+                options = options.with_temperature(temperature as f64);
+            }
+            if let Some(top_p) = params.top_p {
+                options = options.with_top_p(top_p as f64);
+            }
+            if let Some(max_tokens) = params.max_tokens {
+                options = options.with_max_tokens(max_tokens);
+            }
+            options
+        }
+
         /// Execute a non-streaming chat
         async fn execute_chat_non_streaming(
             &self,
             config: &ModelConfig,
             context: Vec<ContextMessage>,
         ) -> ChatResult<ChatStream> {
+            self.refresh_api_key(config.provider).await?;
+
             let messages = Self::convert_context(&context);
             let model = Self::model_string(config);
             let request = ChatRequest::new(messages);
+            let options = Self::chat_options(config);
 
             // Non-streaming response
             let response = self
                 .client
-                .exec_chat(&model, request, None)
+                .exec_chat(&model, request, Some(&options))
                 .await
                 .map_err(|e| ChatError::ProviderError(e.to_string()))?;
 
@@ -182,9 +280,15 @@ mod inner {
             self.execute_chat_non_streaming(config, context).await
         }
 
+        async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+            let models = self.list_models().await?;
+            // Ollama lists `llama3.2:latest` for a model requested as `llama3.2`
+            let latest = format!("{}:latest", model);
+            Ok(Some(models.iter().any(|m| *m == model || *m == latest)))
+        }
+
         async fn health_check(&self) -> ChatResult<()> {
-            // genai doesn't have a health check, so we just return Ok
-            Ok(())
+            self.list_models().await.map(|_| ())
         }
 
         fn provider_name(&self) -> &'static str {
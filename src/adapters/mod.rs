@@ -27,6 +27,9 @@
 //! - `GenaiAdapter` - Multi-provider adapter using genai crate (recommended)
 //! - Legacy adapters - Individual provider adapters (via `ai-providers` feature)
 //!
+//! `ProvidersConfig` builds a fully populated registry from one TOML/YAML
+//! file of named provider profiles.
+//!
 //! ## Usage
 //!
 //! ```ignore
@@ -36,7 +39,7 @@
 //! let mut registry = ProviderRegistry::new();
 //!
 //! // Register genai adapter with capabilities
-//! let adapter = GenaiAdapter::new()?;
+//! let adapter = GenaiAdapter::new()?.with_provider(ProviderType::OpenAI);
 //! registry.register(
 //!     ProviderType::OpenAI,
 //!     adapter,
@@ -50,6 +53,11 @@
 
 mod genai_adapter;
 mod provider_registry;
+mod providers_config;
 
 pub use genai_adapter::GenaiAdapter;
 pub use provider_registry::ProviderRegistry;
+pub use providers_config::{
+    ProviderProfile, ProvidersConfig, ProvidersConfigError, ProvidersConfigResult, RateLimit,
+    RateLimitedAdapter,
+};
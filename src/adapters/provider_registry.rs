@@ -6,8 +6,8 @@
 //! Used for capability-based routing.
 
 use crate::capabilities::{CapabilityRequirements, ProviderCapabilities, RuntimeCapabilities};
-use crate::ports::{ChatError, ChatPort, ChatResult};
-use crate::value_objects::ProviderType;
+use crate::ports::{ChatError, ChatPort, ChatResult, EmbeddingPort};
+use crate::value_objects::{ModelConfig, ProviderType};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -19,6 +19,10 @@ use std::sync::Arc;
 pub struct ProviderRegistry {
     /// Registered providers with their adapters
     providers: HashMap<ProviderType, RegisteredProvider>,
+    /// Embedding adapters for providers that also embed
+    embedders: HashMap<ProviderType, Arc<dyn EmbeddingPort>>,
+    /// Model configuration used when a caller names only the provider
+    default_models: HashMap<ProviderType, ModelConfig>,
 }
 
 /// A registered provider with its adapter and capabilities
@@ -32,6 +36,8 @@ impl ProviderRegistry {
     pub fn new() -> Self {
         Self {
             providers: HashMap::new(),
+            embedders: HashMap::new(),
+            default_models: HashMap::new(),
         }
     }
 
@@ -41,16 +47,68 @@ impl ProviderRegistry {
         provider_type: ProviderType,
         adapter: A,
         capabilities: ProviderCapabilities,
+    ) {
+        self.register_shared(provider_type, Arc::new(adapter), capabilities);
+    }
+
+    /// Register an adapter that is already shared or wrapped in decorators
+    pub fn register_shared(
+        &mut self,
+        provider_type: ProviderType,
+        adapter: Arc<dyn ChatPort>,
+        capabilities: ProviderCapabilities,
     ) {
         self.providers.insert(
             provider_type,
             RegisteredProvider {
-                adapter: Arc::new(adapter),
+                adapter,
                 capabilities,
             },
         );
     }
 
+    /// Register the embedding adapter for an already registered provider
+    ///
+    /// The provider's capabilities should include `EMBEDDINGS`.
+    pub fn register_embedder(
+        &mut self,
+        provider_type: ProviderType,
+        embedder: Arc<dyn EmbeddingPort>,
+    ) {
+        self.embedders.insert(provider_type, embedder);
+    }
+
+    /// Set the model configuration used for a provider by default
+    pub fn set_default_model(&mut self, provider_type: ProviderType, config: ModelConfig) {
+        self.default_models.insert(provider_type, config);
+    }
+
+    /// Model configuration used for a provider by default, if one is set
+    pub fn default_model(&self, provider_type: &ProviderType) -> Option<&ModelConfig> {
+        self.default_models.get(provider_type)
+    }
+
+    /// Get the embedding adapter for a specific provider
+    pub fn get_embedder(&self, provider_type: &ProviderType) -> Option<Arc<dyn EmbeddingPort>> {
+        self.embedders.get(provider_type).cloned()
+    }
+
+    /// Select the best-fit provider that has an embedding adapter
+    pub fn select_embedder(
+        &self,
+        requirements: &CapabilityRequirements,
+    ) -> ChatResult<Arc<dyn EmbeddingPort>> {
+        self.find_capable_providers(requirements)
+            .into_iter()
+            .find_map(|(provider_type, _)| self.get_embedder(provider_type))
+            .ok_or_else(|| {
+                ChatError::ConfigurationError(format!(
+                    "No embedding provider satisfies requirements: {:?}",
+                    requirements.capabilities.to_vec()
+                ))
+            })
+    }
+
     /// Check if a provider is registered
     pub fn has_provider(&self, provider_type: &ProviderType) -> bool {
         self.providers.contains_key(provider_type)
@@ -0,0 +1,581 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Providers Config
+//!
+//! One file describes every provider the service talks to, as named
+//! profiles. API keys are never written inline; a profile names the secret
+//! that holds its key and the key is resolved through a [`SecretsProvider`].
+//!
+//! ```yaml
+//! profiles:
+//!   primary:
+//!     provider: anthropic
+//!     model: claude-3-opus-20240229
+//!     api_key: prod/llm#ANTHROPIC_API_KEY
+//!     rate_limit:
+//!       requests_per_minute: 50
+//!   local:
+//!     provider: ollama
+//!     model: llama3
+//!     endpoint: http://gpu-box:11434
+//!     keep_alive: 30m
+//!     max_concurrency: 2
+//!     model_concurrency:
+//!       llama3:70b: 1
+//! ```
+//!
+//! The same structure is accepted as TOML (`[profiles.primary]`). The
+//! registry holds one adapter per provider type, so each provider may
+//! appear in at most one profile.
+//!
+//! An `openai` profile with an `endpoint` is treated as a self-hosted
+//! OpenAI-compatible server (vLLM, TGI, ...); set `capabilities` to what
+//! the served model can actually do.
+
+use crate::adapters::ProviderRegistry;
+use crate::capabilities::{ProviderCapabilities, RuntimeCapabilities};
+use crate::intent::ImageInput;
+use crate::ports::{
+    api_key_secret_name, ChatError, ChatPort, ChatResult, ChatStream, SecretsError,
+    SecretsProvider,
+};
+use crate::value_objects::{ContextMessage, ModelConfig, ProviderType};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Errors from loading a providers file or building a registry from it
+#[derive(Debug, Error)]
+pub enum ProvidersConfigError {
+    #[error("Failed to read {path}: {reason}")]
+    Io { path: String, reason: String },
+
+    #[error("Failed to parse providers config: {0}")]
+    Parse(String),
+
+    #[error("Provider {provider} is configured by both {first} and {second}")]
+    DuplicateProvider {
+        provider: ProviderType,
+        first: String,
+        second: String,
+    },
+
+    #[error("Profile {profile}: {reason}")]
+    InvalidProfile { profile: String, reason: String },
+
+    #[error("Profile {profile}: API key {secret} could not be resolved: {source}")]
+    MissingKey {
+        profile: String,
+        secret: String,
+        source: SecretsError,
+    },
+
+    #[error("Profile {profile}: {source}")]
+    Adapter { profile: String, source: ChatError },
+}
+
+/// Result type for providers config operations
+pub type ProvidersConfigResult<T> = Result<T, ProvidersConfigError>;
+
+/// Request rate limit for one profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Maximum requests started per minute
+    pub requests_per_minute: u32,
+}
+
+impl RateLimit {
+    /// Minimum spacing between request starts
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(60) / self.requests_per_minute.max(1)
+    }
+}
+
+/// A named provider profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderProfile {
+    /// Provider type (`openai`, `anthropic`, `ollama`, `gemini`, `mock`)
+    pub provider: ProviderType,
+
+    /// Default model for this profile
+    pub model: String,
+
+    /// Custom API endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Name of the secret holding the API key
+    ///
+    /// Defaults to the provider's conventional name (`OPENAI_API_KEY`, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+
+    /// Request rate limit enforced in front of the adapter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+
+    /// Override for the provider preset's context length
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_length: Option<u32>,
+
+    /// Override for the provider preset's capabilities, e.g.
+    /// `"TEXT_CHAT | STREAMING | VISION"` for a self-hosted vision model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<RuntimeCapabilities>,
+
+    /// Forward request cost tags as provider metadata, for self-hosted
+    /// OpenAI-compatible endpoints that accept the `metadata` field
+    #[serde(default)]
+    pub cost_tag_metadata: bool,
+
+    /// Ask self-hosted OpenAI-compatible endpoints to report token usage on
+    /// the stream (`stream_options.include_usage`)
+    #[serde(default)]
+    pub stream_usage: bool,
+
+    /// Ollama: how long a model stays loaded after a request (`30m`, `-1`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<String>,
+
+    /// Ollama: concurrent requests allowed per model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+
+    /// Ollama: per-model overrides of `max_concurrency`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub model_concurrency: BTreeMap<String, usize>,
+}
+
+impl ProviderProfile {
+    /// Secret name holding this profile's API key, if the provider needs one
+    pub fn api_key_name(&self) -> Option<&str> {
+        match (&self.api_key, &self.endpoint) {
+            (Some(name), _) => Some(name),
+            // Self-hosted OpenAI-compatible servers only authenticate when a key is named
+            (None, Some(_)) if self.provider == ProviderType::OpenAI => None,
+            (None, _) => api_key_secret_name(self.provider),
+        }
+    }
+
+    /// Default model configuration for this profile
+    pub fn model_config(&self) -> ModelConfig {
+        let config = ModelConfig::new(self.provider, &self.model);
+        match &self.endpoint {
+            Some(endpoint) => config.with_api_endpoint(endpoint),
+            None => config,
+        }
+    }
+
+    /// Capability preset for the provider, with profile overrides applied
+    pub fn capabilities(&self) -> ProviderCapabilities {
+        let mut capabilities = ProviderCapabilities::for_provider(self.provider);
+        if let Some(max) = self.max_context_length {
+            capabilities.max_context_length = Some(max);
+        }
+        if let Some(overridden) = self.capabilities {
+            capabilities.capabilities = overridden;
+        }
+        capabilities
+    }
+}
+
+/// Named provider profiles loaded from a TOML or YAML file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProvidersConfig {
+    /// Profiles by name
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProviderProfile>,
+}
+
+impl ProvidersConfig {
+    /// Parse a YAML document
+    pub fn from_yaml_str(content: &str) -> ProvidersConfigResult<Self> {
+        let config: Self = serde_yaml::from_str(content)
+            .map_err(|e| ProvidersConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse a TOML document
+    pub fn from_toml_str(content: &str) -> ProvidersConfigResult<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| ProvidersConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a file, choosing the format by extension (`.toml`, otherwise YAML)
+    pub fn load(path: impl AsRef<Path>) -> ProvidersConfigResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| ProvidersConfigError::Io {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&content),
+            _ => Self::from_yaml_str(&content),
+        }
+    }
+
+    /// Look up a profile by name
+    pub fn profile(&self, name: &str) -> Option<&ProviderProfile> {
+        self.profiles.get(name)
+    }
+
+    /// Check profiles for empty fields and providers configured twice
+    pub fn validate(&self) -> ProvidersConfigResult<()> {
+        let mut seen: HashMap<ProviderType, &str> = HashMap::new();
+        for (name, profile) in &self.profiles {
+            let invalid = |reason: &str| ProvidersConfigError::InvalidProfile {
+                profile: name.clone(),
+                reason: reason.to_string(),
+            };
+            if profile.model.trim().is_empty() {
+                return Err(invalid("model must not be empty"));
+            }
+            if profile.rate_limit.is_some_and(|limit| limit.requests_per_minute == 0) {
+                return Err(invalid("rate_limit.requests_per_minute must be positive"));
+            }
+            if let Some(first) = seen.insert(profile.provider, name) {
+                return Err(ProvidersConfigError::DuplicateProvider {
+                    provider: profile.provider,
+                    first: first.to_string(),
+                    second: name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a registry with an adapter for every profile
+    ///
+    /// Every referenced API key is resolved up front so a missing secret
+    /// fails here rather than on the first request. Adapters keep looking
+    /// keys up through `secrets`, so rotation still applies afterwards.
+    /// Adapters are pointed at the profile's endpoint, and the profile's
+    /// model becomes the provider's default model.
+    pub async fn build_registry(
+        &self,
+        secrets: Arc<dyn SecretsProvider>,
+    ) -> ProvidersConfigResult<ProviderRegistry> {
+        self.validate()?;
+        let mut registry = ProviderRegistry::new();
+        for (name, profile) in &self.profiles {
+            if let Some(secret) = profile.api_key_name() {
+                secrets
+                    .get(secret)
+                    .await
+                    .map_err(|source| ProvidersConfigError::MissingKey {
+                        profile: name.clone(),
+                        secret: secret.to_string(),
+                        source,
+                    })?;
+            }
+
+            let adapter = build_adapter(profile, secrets.clone()).map_err(|source| {
+                ProvidersConfigError::Adapter {
+                    profile: name.clone(),
+                    source,
+                }
+            })?;
+            let adapter: Arc<dyn ChatPort> = match profile.rate_limit {
+                Some(limit) => Arc::new(RateLimitedAdapter::new(adapter, limit)),
+                None => adapter,
+            };
+            registry.register_shared(profile.provider, adapter, profile.capabilities());
+            registry.set_default_model(profile.provider, profile.model_config());
+
+            #[cfg(feature = "ai-providers")]
+            if profile.provider == ProviderType::Gemini {
+                let embedder = gemini_adapter(profile, secrets.clone()).map_err(|source| {
+                    ProvidersConfigError::Adapter {
+                        profile: name.clone(),
+                        source,
+                    }
+                })?;
+                registry.register_embedder(profile.provider, embedder);
+            }
+        }
+        Ok(registry)
+    }
+}
+
+/// Pick the adapter implementation for a profile
+fn build_adapter(
+    profile: &ProviderProfile,
+    secrets: Arc<dyn SecretsProvider>,
+) -> ChatResult<Arc<dyn ChatPort>> {
+    match profile.provider {
+        ProviderType::Mock => Ok(Arc::new(crate::ports::MockChatAdapter::new())),
+        #[cfg(feature = "ai-providers")]
+        ProviderType::Ollama => {
+            let url = profile.endpoint.as_deref().unwrap_or("http://localhost:11434");
+            let mut adapter = crate::ports::OllamaChatAdapter::with_url(url)?;
+            if let Some(keep_alive) = &profile.keep_alive {
+                adapter = adapter.with_keep_alive(keep_alive.clone());
+            }
+            if let Some(limit) = profile.max_concurrency {
+                adapter = adapter.with_concurrency(limit);
+            }
+            for (model, limit) in &profile.model_concurrency {
+                adapter = adapter.with_model_concurrency(model.clone(), *limit);
+            }
+            Ok(Arc::new(adapter))
+        }
+        #[cfg(feature = "ai-providers")]
+        ProviderType::Gemini => Ok(gemini_adapter(profile, secrets)?),
+        #[cfg(feature = "ai-providers")]
+        ProviderType::OpenAI if profile.endpoint.is_some() => {
+            let endpoint = profile.endpoint.as_deref().unwrap_or_default();
+            let capabilities = profile.capabilities();
+            let mut adapter = crate::ports::OpenAICompatibleAdapter::new(endpoint)?
+                .with_capabilities(capabilities.capabilities)
+                .with_expected_model(&profile.model);
+            if let Some(max) = capabilities.max_context_length {
+                adapter = adapter.with_max_context_length(max);
+            }
+            if let Some(key) = &profile.api_key {
+                adapter = adapter.with_api_key(secrets, key);
+            }
+            if profile.cost_tag_metadata {
+                adapter = adapter.with_cost_tag_metadata();
+            }
+            if profile.stream_usage {
+                adapter = adapter.with_stream_usage();
+            }
+            Ok(Arc::new(adapter))
+        }
+        #[cfg(feature = "genai-adapter")]
+        provider => {
+            let conventional = api_key_secret_name(provider);
+            let secrets: Arc<dyn SecretsProvider> = match (&profile.api_key, conventional) {
+                (Some(reference), Some(conventional)) => Arc::new(AliasedSecrets {
+                    inner: secrets,
+                    alias: conventional,
+                    target: reference.clone(),
+                }),
+                _ => secrets,
+            };
+            let mut adapter = crate::adapters::GenaiAdapter::new()?
+                .with_secrets(secrets)
+                .with_provider(provider);
+            if let Some(endpoint) = &profile.endpoint {
+                adapter = adapter.with_endpoint(endpoint);
+            }
+            Ok(Arc::new(adapter))
+        }
+        #[cfg(not(feature = "genai-adapter"))]
+        provider => {
+            let _ = secrets;
+            Err(ChatError::ConfigurationError(format!(
+                "{} requires the genai-adapter feature",
+                provider
+            )))
+        }
+    }
+}
+
+/// Native Gemini adapter, which also serves embeddings
+#[cfg(feature = "ai-providers")]
+fn gemini_adapter(
+    profile: &ProviderProfile,
+    secrets: Arc<dyn SecretsProvider>,
+) -> ChatResult<Arc<crate::ports::GeminiChatAdapter>> {
+    let mut adapter = crate::ports::GeminiChatAdapter::new(secrets)?;
+    if let Some(endpoint) = &profile.endpoint {
+        adapter = adapter.with_base_url(endpoint);
+    }
+    if let Some(name) = &profile.api_key {
+        adapter = adapter.with_api_key_name(name);
+    }
+    Ok(Arc::new(adapter))
+}
+
+/// Redirects the conventional key name to the profile's secret reference
+#[cfg(feature = "genai-adapter")]
+struct AliasedSecrets {
+    inner: Arc<dyn SecretsProvider>,
+    alias: &'static str,
+    target: String,
+}
+
+#[cfg(feature = "genai-adapter")]
+#[async_trait]
+impl SecretsProvider for AliasedSecrets {
+    async fn get(&self, name: &str) -> crate::ports::SecretsResult<crate::ports::Secret> {
+        if name == self.alias {
+            self.inner.get(&self.target).await
+        } else {
+            self.inner.get(name).await
+        }
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.inner.provider_name()
+    }
+}
+
+/// ChatPort decorator spacing request starts to a profile's rate limit
+///
+/// Callers over the limit wait for their slot instead of failing.
+pub struct RateLimitedAdapter {
+    inner: Arc<dyn ChatPort>,
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimitedAdapter {
+    /// Wrap an adapter
+    pub fn new(inner: Arc<dyn ChatPort>, limit: RateLimit) -> Self {
+        Self {
+            inner,
+            interval: limit.interval(),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[async_trait]
+impl ChatPort for RateLimitedAdapter {
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        self.acquire().await;
+        self.inner.send(config, context).await
+    }
+
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        self.acquire().await;
+        self.inner.send_vision(config, context, images).await
+    }
+
+    async fn prewarm(&self, config: &ModelConfig) -> ChatResult<()> {
+        self.inner.prewarm(config).await
+    }
+
+    async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+        self.inner.has_model(model).await
+    }
+
+    async fn context_window(&self, model: &str) -> ChatResult<Option<u32>> {
+        self.inner.context_window(model).await
+    }
+
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        self.inner.count_tokens(config, context).await
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        self.inner.health_check().await
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.inner.provider_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::DotenvSecretsProvider;
+
+    const YAML: &str = r#"
+profiles:
+  primary:
+    provider: anthropic
+    model: claude-3-opus-20240229
+    api_key: LLM_PRIMARY_KEY
+    rate_limit:
+      requests_per_minute: 30
+  test:
+    provider: mock
+    model: mock-model
+"#;
+
+    #[test]
+    fn test_yaml_and_toml_agree() {
+        let from_yaml = ProvidersConfig::from_yaml_str(YAML).unwrap();
+        let from_toml = ProvidersConfig::from_toml_str(
+            r#"
+[profiles.primary]
+provider = "anthropic"
+model = "claude-3-opus-20240229"
+api_key = "LLM_PRIMARY_KEY"
+rate_limit = { requests_per_minute = 30 }
+
+[profiles.test]
+provider = "mock"
+model = "mock-model"
+"#,
+        )
+        .unwrap();
+        assert_eq!(from_yaml, from_toml);
+
+        let primary = from_yaml.profile("primary").unwrap();
+        assert_eq!(primary.api_key_name(), Some("LLM_PRIMARY_KEY"));
+        assert_eq!(primary.rate_limit.unwrap().interval(), Duration::from_secs(2));
+        assert_eq!(from_yaml.profile("test").unwrap().api_key_name(), None);
+    }
+
+    #[test]
+    fn test_duplicate_provider_rejected() {
+        let result = ProvidersConfig::from_yaml_str(
+            "profiles:\n  a: {provider: mock, model: m1}\n  b: {provider: mock, model: m2}\n",
+        );
+        assert!(matches!(
+            result,
+            Err(ProvidersConfigError::DuplicateProvider { provider: ProviderType::Mock, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_build_registry_resolves_keys() {
+        let path = std::env::temp_dir().join(format!("providers-{}.env", uuid::Uuid::now_v7()));
+        std::fs::write(&path, "OTHER=1\n").unwrap();
+        let secrets: Arc<dyn SecretsProvider> = Arc::new(DotenvSecretsProvider::new(&path));
+
+        let mock_only = ProvidersConfig::from_yaml_str(
+            "profiles:\n  test:\n    provider: mock\n    model: mock-model\n    rate_limit: {requests_per_minute: 600}\n",
+        )
+        .unwrap();
+        let registry = mock_only.build_registry(secrets.clone()).await.unwrap();
+        assert!(registry.has_provider(&ProviderType::Mock));
+        let default_model = registry.default_model(&ProviderType::Mock).unwrap();
+        assert_eq!(default_model.model_name, "mock-model");
+        assert!(registry.get_adapter(&ProviderType::Mock).is_some());
+
+        let config = ProvidersConfig::from_yaml_str(YAML).unwrap();
+        assert!(matches!(
+            config.build_registry(secrets).await,
+            Err(ProvidersConfigError::MissingKey { secret, .. }) if secret == "LLM_PRIMARY_KEY"
+        ));
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,730 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation aggregate
+//!
+//! Pure functional event-sourced aggregate tracking who takes part in a
+//! conversation, what each participant may do there, and which messages
+//! have been posted for participants to acknowledge.
+//!
+//! # Design Principles
+//!
+//! 1. **Separate Aggregate**: Agents stay stateless about conversations
+//! 2. **Role-Based**: Every action is checked against the actor's role
+//! 3. **Always Owned**: The last owner cannot leave or be demoted
+//! 4. **Budgeted**: A conversation closes itself once its budget runs out
+
+use crate::commands::{
+    AcknowledgeMessage, Acknowledgement, AddParticipant, ChangeParticipantRole, CloseConversation,
+    ConversationCommand, PostMessage, RemoveParticipant, StartConversation, TransferConversation,
+};
+use crate::events::{
+    ClosureReason, ConversationClosedEvent, ConversationEvent, ConversationStartedEvent,
+    ConversationTransferredEvent, MessageDeliveredEvent, MessagePostedEvent, MessageReadEvent,
+    ParticipantJoinedEvent, ParticipantLeftEvent, ParticipantRoleChangedEvent,
+};
+use crate::value_objects::{
+    clock_now, AgentId, BudgetLimit, ConversationBudget, ConversationHandoff, ConversationId,
+    ConversationPermission, MessageId, Participant, ParticipantId, ParticipantRole,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Conversation aggregate - participants and their roles
+///
+/// # Lifecycle
+///
+/// ```text
+/// StartConversation ──> Started (owner joins)
+///                          │
+///   AddParticipant / RemoveParticipant / ChangeParticipantRole
+///   (owners manage; anyone may leave)
+///                          │
+///   PostMessage ──> MessagePosted (+ Closed once the budget is spent)
+///   AcknowledgeMessage ──> MessageDelivered / MessageRead
+///                          │
+///   TransferConversation (owner or the agent itself) ──> Transferred
+///                          │
+///   CloseConversation (owner, inactivity timeout or budget) ──> Closed
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    /// Conversation unique identifier
+    id: ConversationId,
+
+    /// Current participants, in join order
+    participants: Vec<Participant>,
+
+    /// Messages posted, in order
+    #[serde(default)]
+    messages: Vec<MessageId>,
+
+    /// Limits set when the conversation started
+    #[serde(default)]
+    budget: ConversationBudget,
+
+    /// Tokens spent by posted messages
+    #[serde(default)]
+    tokens_used: u64,
+
+    /// Why the conversation was closed, once it is
+    #[serde(default)]
+    closed: Option<ClosureReason>,
+
+    /// Agent a transfer handed the conversation to, and what it was handed,
+    /// until that agent posts
+    #[serde(default)]
+    handoff: Option<(AgentId, ConversationHandoff)>,
+
+    /// When the conversation started
+    started_at: DateTime<Utc>,
+
+    /// When last updated
+    updated_at: DateTime<Utc>,
+
+    /// Event sourcing version
+    version: u64,
+}
+
+impl Conversation {
+    /// Create an empty conversation for event replay
+    pub fn empty() -> Self {
+        Self {
+            id: ConversationId::new(),
+            participants: Vec::new(),
+            messages: Vec::new(),
+            budget: ConversationBudget::unlimited(),
+            tokens_used: 0,
+            closed: None,
+            handoff: None,
+            started_at: clock_now(),
+            updated_at: clock_now(),
+            version: 0,
+        }
+    }
+
+    // ========================================================================
+    // Accessors
+    // ========================================================================
+
+    /// Get the conversation ID
+    pub fn id(&self) -> ConversationId {
+        self.id
+    }
+
+    /// Get the current participants
+    pub fn participants(&self) -> &[Participant] {
+        &self.participants
+    }
+
+    /// Get one participant
+    pub fn participant(&self, id: &ParticipantId) -> Option<&Participant> {
+        self.participants.iter().find(|p| &p.id == id)
+    }
+
+    /// Get the messages posted, in order
+    pub fn messages(&self) -> &[MessageId] {
+        &self.messages
+    }
+
+    /// Get the limits the conversation runs under
+    pub fn budget(&self) -> ConversationBudget {
+        self.budget
+    }
+
+    /// Get the tokens spent by posted messages
+    pub fn tokens_used(&self) -> u64 {
+        self.tokens_used
+    }
+
+    /// Get when the conversation started
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Get when last updated
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    /// Get the event sourcing version
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    // ========================================================================
+    // State Queries
+    // ========================================================================
+
+    /// Check if the conversation has been started
+    pub fn is_started(&self) -> bool {
+        self.version > 0
+    }
+
+    /// Why the conversation was closed, if it was
+    pub fn closed(&self) -> Option<ClosureReason> {
+        self.closed
+    }
+
+    /// The handoff `agent` was given, while it has not posted yet
+    pub fn pending_handoff(&self, agent: AgentId) -> Option<&ConversationHandoff> {
+        self.handoff
+            .as_ref()
+            .filter(|(to, _)| *to == agent)
+            .map(|(_, handoff)| handoff)
+    }
+
+    /// The budget limit reached at `now`, if any
+    pub fn budget_exhausted(&self, now: DateTime<Utc>) -> Option<BudgetLimit> {
+        self.budget.exhausted(self.tokens_used, self.message_count(), self.elapsed(now))
+    }
+
+    fn message_count(&self) -> u32 {
+        u32::try_from(self.messages.len()).unwrap_or(u32::MAX)
+    }
+
+    fn elapsed(&self, now: DateTime<Utc>) -> Duration {
+        (now - self.started_at).to_std().unwrap_or(Duration::ZERO)
+    }
+
+    /// Check that `actor` is a participant whose role grants `permission`
+    pub fn authorize(
+        &self,
+        actor: &ParticipantId,
+        permission: ConversationPermission,
+    ) -> Result<(), String> {
+        match self.participant(actor) {
+            Some(p) if p.can(permission) => Ok(()),
+            Some(p) => Err(format!(
+                "{} is {} in conversation {} and may not {:?}",
+                actor, p.role, self.id, permission
+            )),
+            None => Err(format!(
+                "{} is not a participant in conversation {}",
+                actor, self.id
+            )),
+        }
+    }
+
+    /// Check if `actor` may post messages
+    pub fn can_post(&self, actor: &ParticipantId) -> bool {
+        self.authorize(actor, ConversationPermission::Post).is_ok()
+    }
+
+    /// Check if `actor` may approve tool calls
+    pub fn can_approve_tools(&self, actor: &ParticipantId) -> bool {
+        self.authorize(actor, ConversationPermission::ApproveTools)
+            .is_ok()
+    }
+
+    fn owners(&self) -> usize {
+        self.participants
+            .iter()
+            .filter(|p| p.role == ParticipantRole::Owner)
+            .count()
+    }
+
+    fn is_last_owner(&self, id: &ParticipantId) -> bool {
+        self.participant(id)
+            .is_some_and(|p| p.role == ParticipantRole::Owner)
+            && self.owners() == 1
+    }
+
+    // ========================================================================
+    // Command Handling
+    // ========================================================================
+
+    /// Decide the events a command produces, enforcing participant roles
+    pub fn handle(&self, cmd: &ConversationCommand) -> Result<Vec<ConversationEvent>, String> {
+        cmd.validate()?;
+        if let ConversationCommand::Start(cmd) = cmd {
+            return self.handle_start(cmd);
+        }
+        if !self.is_started() {
+            return Err(format!(
+                "Conversation {} has not started",
+                cmd.conversation_id()
+            ));
+        }
+        if let Some(reason) = self.closed {
+            return Err(format!("Conversation {} is closed ({:?})", self.id, reason));
+        }
+        match cmd {
+            ConversationCommand::Start(_) => unreachable!("handled above"),
+            ConversationCommand::AddParticipant(cmd) => self.handle_add(cmd),
+            ConversationCommand::RemoveParticipant(cmd) => self.handle_remove(cmd),
+            ConversationCommand::ChangeParticipantRole(cmd) => self.handle_change_role(cmd),
+            ConversationCommand::PostMessage(cmd) => self.handle_post(cmd),
+            ConversationCommand::AcknowledgeMessage(cmd) => self.handle_acknowledge(cmd),
+            ConversationCommand::Close(cmd) => self.handle_close(cmd),
+            ConversationCommand::Transfer(cmd) => self.handle_transfer(cmd),
+        }
+    }
+
+    fn handle_start(&self, cmd: &StartConversation) -> Result<Vec<ConversationEvent>, String> {
+        if self.is_started() {
+            return Err(format!("Conversation {} already started", self.id));
+        }
+        Ok(vec![ConversationEvent::Started(
+            ConversationStartedEvent::new(cmd.conversation_id, cmd.owner).with_budget(cmd.budget),
+        )])
+    }
+
+    fn handle_add(&self, cmd: &AddParticipant) -> Result<Vec<ConversationEvent>, String> {
+        self.authorize(&cmd.actor, ConversationPermission::ManageParticipants)?;
+        if self.participant(&cmd.participant).is_some() {
+            return Err(format!("{} is already a participant", cmd.participant));
+        }
+        Ok(vec![ConversationEvent::ParticipantJoined(
+            ParticipantJoinedEvent::new(
+                self.id,
+                Participant::new(cmd.participant.clone(), cmd.role),
+                cmd.actor.clone(),
+            ),
+        )])
+    }
+
+    fn handle_remove(&self, cmd: &RemoveParticipant) -> Result<Vec<ConversationEvent>, String> {
+        if cmd.actor != cmd.participant {
+            self.authorize(&cmd.actor, ConversationPermission::ManageParticipants)?;
+        }
+        if self.participant(&cmd.participant).is_none() {
+            return Err(format!("{} is not a participant", cmd.participant));
+        }
+        if self.is_last_owner(&cmd.participant) {
+            return Err("The last owner cannot leave the conversation".to_string());
+        }
+        Ok(vec![ConversationEvent::ParticipantLeft(
+            ParticipantLeftEvent::new(self.id, cmd.participant.clone(), cmd.actor.clone()),
+        )])
+    }
+
+    fn handle_change_role(
+        &self,
+        cmd: &ChangeParticipantRole,
+    ) -> Result<Vec<ConversationEvent>, String> {
+        self.authorize(&cmd.actor, ConversationPermission::ManageParticipants)?;
+        let Some(current) = self.participant(&cmd.participant) else {
+            return Err(format!("{} is not a participant", cmd.participant));
+        };
+        if current.role == cmd.role {
+            return Ok(vec![]);
+        }
+        if self.is_last_owner(&cmd.participant) {
+            return Err("The last owner cannot be given another role".to_string());
+        }
+        Ok(vec![ConversationEvent::ParticipantRoleChanged(
+            ParticipantRoleChangedEvent::new(
+                self.id,
+                cmd.participant.clone(),
+                current.role,
+                cmd.role,
+                cmd.actor.clone(),
+            ),
+        )])
+    }
+
+    /// Posting into a spent budget closes the conversation instead; the
+    /// message that spends the budget is posted and then closes it.
+    fn handle_post(&self, cmd: &PostMessage) -> Result<Vec<ConversationEvent>, String> {
+        self.authorize(&cmd.author, ConversationPermission::Post)?;
+        if self.messages.contains(&cmd.message_id) {
+            return Err(format!("Message {} was already posted", cmd.message_id));
+        }
+        let elapsed = self.elapsed(clock_now());
+        let close = |limit| {
+            ConversationEvent::Closed(ConversationClosedEvent::budget_exhausted(self.id, limit))
+        };
+        let spent = self.budget.exhausted(self.tokens_used, self.message_count(), elapsed);
+        if let Some(limit) = spent {
+            return Ok(vec![close(limit)]);
+        }
+
+        let mut events = vec![ConversationEvent::MessagePosted(
+            MessagePostedEvent::new(self.id, cmd.message_id, cmd.author.clone())
+                .with_tokens(cmd.tokens)
+                .with_provenance(cmd.provenance.clone()),
+        )];
+        let spent = self.budget.exhausted(
+            self.tokens_used.saturating_add(cmd.tokens),
+            self.message_count().saturating_add(1),
+            elapsed,
+        );
+        events.extend(spent.map(close));
+        Ok(events)
+    }
+
+    fn handle_acknowledge(
+        &self,
+        cmd: &AcknowledgeMessage,
+    ) -> Result<Vec<ConversationEvent>, String> {
+        self.authorize(&cmd.participant, ConversationPermission::Read)?;
+        if !self.messages.contains(&cmd.message_id) {
+            return Err(format!(
+                "Message {} was not posted in conversation {}",
+                cmd.message_id, self.id
+            ));
+        }
+        let (id, message, participant) = (self.id, cmd.message_id, cmd.participant.clone());
+        Ok(vec![match cmd.acknowledgement {
+            Acknowledgement::Delivered => ConversationEvent::MessageDelivered(
+                MessageDeliveredEvent::new(id, message, participant),
+            ),
+            Acknowledgement::Read => {
+                ConversationEvent::MessageRead(MessageReadEvent::new(id, message, participant))
+            }
+        }])
+    }
+
+    fn handle_close(&self, cmd: &CloseConversation) -> Result<Vec<ConversationEvent>, String> {
+        if let Some(actor) = &cmd.actor {
+            self.authorize(actor, ConversationPermission::ManageParticipants)?;
+        }
+        let mut closed = ConversationClosedEvent::new(self.id, cmd.reason, cmd.actor.clone());
+        closed.exhausted = cmd.exhausted;
+        Ok(vec![ConversationEvent::Closed(closed)])
+    }
+
+    /// An agent may hand off its own place; anyone else needs to manage
+    /// participants.
+    fn handle_transfer(
+        &self,
+        cmd: &TransferConversation,
+    ) -> Result<Vec<ConversationEvent>, String> {
+        let (from, to) = (ParticipantId::Agent(cmd.from), ParticipantId::Agent(cmd.to));
+        if cmd.actor != from {
+            self.authorize(&cmd.actor, ConversationPermission::ManageParticipants)?;
+        }
+        let Some(current) = self.participant(&from) else {
+            return Err(format!("{} is not a participant", from));
+        };
+        if self.participant(&to).is_some() {
+            return Err(format!("{} is already a participant", to));
+        }
+        Ok(vec![ConversationEvent::Transferred(
+            ConversationTransferredEvent::new(
+                self.id,
+                cmd.from,
+                cmd.to,
+                current.role,
+                cmd.handoff.clone(),
+                cmd.actor.clone(),
+            )
+            .after(self.messages.last().copied()),
+        )])
+    }
+
+    // ========================================================================
+    // Event Application (Pure Functional)
+    // ========================================================================
+
+    /// Apply an event to produce a new conversation state
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be applied to the current state.
+    pub fn apply_event(&self, event: &ConversationEvent) -> Result<Self, String> {
+        let mut new_conversation = self.clone();
+
+        match event {
+            ConversationEvent::Started(e) => {
+                new_conversation.id = e.conversation_id;
+                new_conversation.participants = vec![Participant {
+                    id: ParticipantId::Person(e.owner),
+                    role: ParticipantRole::Owner,
+                    joined_at: e.started_at,
+                }];
+                new_conversation.budget = e.budget;
+                new_conversation.started_at = e.started_at;
+            }
+
+            ConversationEvent::ParticipantJoined(e) => {
+                if new_conversation.participant(&e.participant.id).is_some() {
+                    return Err(format!("{} is already a participant", e.participant.id));
+                }
+                new_conversation.participants.push(e.participant.clone());
+            }
+
+            ConversationEvent::ParticipantLeft(e) => {
+                let before = new_conversation.participants.len();
+                new_conversation
+                    .participants
+                    .retain(|p| p.id != e.participant_id);
+                if new_conversation.participants.len() == before {
+                    return Err(format!("{} is not a participant", e.participant_id));
+                }
+            }
+
+            ConversationEvent::ParticipantRoleChanged(e) => {
+                let participant = new_conversation
+                    .participants
+                    .iter_mut()
+                    .find(|p| p.id == e.participant_id)
+                    .ok_or_else(|| format!("{} is not a participant", e.participant_id))?;
+                participant.role = e.new_role;
+            }
+
+            ConversationEvent::MessagePosted(e) => {
+                if new_conversation.messages.contains(&e.message_id) {
+                    return Err(format!("Message {} was already posted", e.message_id));
+                }
+                new_conversation.messages.push(e.message_id);
+                new_conversation.tokens_used =
+                    new_conversation.tokens_used.saturating_add(e.tokens);
+                if matches!(
+                    &new_conversation.handoff,
+                    Some((to, _)) if e.author == ParticipantId::Agent(*to)
+                ) {
+                    new_conversation.handoff = None;
+                }
+            }
+
+            // Receipts are tracked by the conversation projection
+            ConversationEvent::MessageDelivered(_) | ConversationEvent::MessageRead(_) => {}
+
+            ConversationEvent::Closed(e) => {
+                new_conversation.closed = Some(e.reason);
+            }
+
+            ConversationEvent::Transferred(e) => {
+                let from = ParticipantId::Agent(e.from);
+                let participant = new_conversation
+                    .participants
+                    .iter_mut()
+                    .find(|p| p.id == from)
+                    .ok_or_else(|| format!("{} is not a participant", from))?;
+                *participant = Participant {
+                    id: ParticipantId::Agent(e.to),
+                    role: e.role,
+                    joined_at: e.transferred_at,
+                };
+                new_conversation.handoff = Some((e.to, e.handoff.clone()));
+            }
+        }
+
+        new_conversation.updated_at = event.timestamp();
+        new_conversation.version += 1;
+        Ok(new_conversation)
+    }
+
+    /// Apply multiple events in sequence
+    pub fn apply_events(&self, events: &[ConversationEvent]) -> Result<Self, String> {
+        let mut current = self.clone();
+        for event in events {
+            current = current.apply_event(event)?;
+        }
+        Ok(current)
+    }
+
+    /// Handle a command and apply the events it produces
+    pub fn execute(
+        &self,
+        cmd: &ConversationCommand,
+    ) -> Result<(Self, Vec<ConversationEvent>), String> {
+        let events = self.handle(cmd)?;
+        Ok((self.apply_events(&events)?, events))
+    }
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::PersonId;
+
+    fn started() -> (Conversation, ParticipantId) {
+        let owner = PersonId::new();
+        let cmd = ConversationCommand::Start(StartConversation::new(owner));
+        let (conversation, _) = Conversation::empty().execute(&cmd).unwrap();
+        (conversation, ParticipantId::Person(owner))
+    }
+
+    #[test]
+    fn test_owner_manages_participants_and_roles_gate_actions() {
+        let (conversation, owner) = started();
+        let id = conversation.id();
+        let agent = ParticipantId::Agent(AgentId::new());
+        let observer = ParticipantId::Person(PersonId::new());
+
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::AddParticipant(AddParticipant::new(
+                id,
+                owner.clone(),
+                agent.clone(),
+                ParticipantRole::Agent,
+            )))
+            .unwrap();
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::AddParticipant(AddParticipant::new(
+                id,
+                owner.clone(),
+                observer.clone(),
+                ParticipantRole::Observer,
+            )))
+            .unwrap();
+
+        assert!(conversation.can_post(&agent));
+        assert!(!conversation.can_post(&observer));
+        assert!(conversation.can_approve_tools(&owner));
+        assert!(!conversation.can_approve_tools(&agent));
+
+        // Only owners manage participants, but anyone may leave
+        let tool = ParticipantId::Tool("search".to_string());
+        let by_agent = AddParticipant::new(id, agent.clone(), tool, ParticipantRole::Tool);
+        assert!(conversation
+            .handle(&ConversationCommand::AddParticipant(by_agent))
+            .is_err());
+        let (conversation, events) = conversation
+            .execute(&ConversationCommand::RemoveParticipant(
+                RemoveParticipant::leave(id, observer.clone()),
+            ))
+            .unwrap();
+        assert!(matches!(events[0], ConversationEvent::ParticipantLeft(_)));
+        assert!(conversation.participant(&observer).is_none());
+    }
+
+    #[test]
+    fn test_only_posted_messages_can_be_acknowledged_by_participants() {
+        let (conversation, owner) = started();
+        let id = conversation.id();
+        let message = MessageId::new();
+        let stranger = ParticipantId::Person(PersonId::new());
+
+        let ack = AcknowledgeMessage::read(id, owner.clone(), message);
+        assert!(conversation
+            .handle(&ConversationCommand::AcknowledgeMessage(ack.clone()))
+            .is_err());
+
+        let post = PostMessage::new(id, owner.clone(), message);
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::PostMessage(post))
+            .unwrap();
+        assert_eq!(conversation.messages(), &[message]);
+
+        let events = conversation
+            .handle(&ConversationCommand::AcknowledgeMessage(ack))
+            .unwrap();
+        assert!(matches!(events[0], ConversationEvent::MessageRead(_)));
+        let by_stranger = AcknowledgeMessage::delivered(id, stranger, message);
+        assert!(conversation
+            .handle(&ConversationCommand::AcknowledgeMessage(by_stranger))
+            .is_err());
+    }
+
+    #[test]
+    fn test_last_owner_is_kept_and_role_changes_are_recorded() {
+        let (conversation, owner) = started();
+        let id = conversation.id();
+        let leave = RemoveParticipant::leave(id, owner.clone());
+        assert!(conversation
+            .handle(&ConversationCommand::RemoveParticipant(leave))
+            .is_err());
+
+        let second = ParticipantId::Person(PersonId::new());
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::AddParticipant(AddParticipant::new(
+                id,
+                owner.clone(),
+                second.clone(),
+                ParticipantRole::Observer,
+            )))
+            .unwrap();
+        let promote = ChangeParticipantRole::new(id, owner, second.clone(), ParticipantRole::Owner);
+        let (conversation, events) = conversation
+            .execute(&ConversationCommand::ChangeParticipantRole(promote))
+            .unwrap();
+        assert!(matches!(
+            &events[0],
+            ConversationEvent::ParticipantRoleChanged(e)
+                if e.previous_role == ParticipantRole::Observer
+        ));
+        assert!(conversation.can_approve_tools(&second));
+    }
+
+    #[test]
+    fn test_agent_hands_off_its_place_at_the_latest_message() {
+        let (conversation, owner) = started();
+        let id = conversation.id();
+        let (triage, specialist) = (AgentId::new(), AgentId::new());
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::AddParticipant(AddParticipant::new(
+                id,
+                owner.clone(),
+                ParticipantId::Agent(triage),
+                ParticipantRole::Agent,
+            )))
+            .unwrap();
+        let message = MessageId::new();
+        let post = PostMessage::new(id, ParticipantId::Agent(triage), message);
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::PostMessage(post))
+            .unwrap();
+
+        let handoff = ConversationHandoff::new("needs a billing specialist");
+        let transfer = TransferConversation::handoff(id, triage, specialist, handoff);
+        let (conversation, events) = conversation
+            .execute(&ConversationCommand::Transfer(transfer.clone()))
+            .unwrap();
+        assert!(matches!(
+            &events[0],
+            ConversationEvent::Transferred(e) if e.after_message == Some(message)
+        ));
+        assert!(conversation.can_post(&ParticipantId::Agent(specialist)));
+        assert!(conversation.participant(&ParticipantId::Agent(triage)).is_none());
+        assert!(conversation.pending_handoff(specialist).is_some());
+
+        // The specialist's first post consumes the handoff
+        let reply = PostMessage::new(id, ParticipantId::Agent(specialist), MessageId::new());
+        let (conversation, _) = conversation
+            .execute(&ConversationCommand::PostMessage(reply))
+            .unwrap();
+        assert!(conversation.pending_handoff(specialist).is_none());
+
+        // The departed agent can no longer hand the conversation off
+        assert!(conversation
+            .handle(&ConversationCommand::Transfer(transfer))
+            .is_err());
+    }
+
+    #[test]
+    fn test_spent_budget_closes_conversation() {
+        let owner = PersonId::new();
+        let budget = ConversationBudget::unlimited()
+            .with_max_tokens(100)
+            .with_max_messages(5);
+        let start = StartConversation::new(owner).with_budget(budget);
+        let (conversation, _) = Conversation::empty()
+            .execute(&ConversationCommand::Start(start))
+            .unwrap();
+        let (id, author) = (conversation.id(), ParticipantId::Person(owner));
+
+        let first = PostMessage::new(id, author.clone(), MessageId::new()).with_tokens(30);
+        let (conversation, events) = conversation
+            .execute(&ConversationCommand::PostMessage(first))
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(conversation.tokens_used(), 30);
+
+        let second = PostMessage::new(id, author.clone(), MessageId::new()).with_tokens(80);
+        let (conversation, events) = conversation
+            .execute(&ConversationCommand::PostMessage(second))
+            .unwrap();
+        assert!(matches!(events[0], ConversationEvent::MessagePosted(_)));
+        assert!(matches!(
+            &events[1],
+            ConversationEvent::Closed(e) if e.exhausted == Some(BudgetLimit::Tokens)
+        ));
+        assert_eq!(conversation.closed(), Some(ClosureReason::BudgetExhausted));
+        assert_eq!(conversation.messages().len(), 2);
+
+        let third = PostMessage::new(id, author, MessageId::new());
+        assert!(conversation
+            .handle(&ConversationCommand::PostMessage(third))
+            .is_err());
+    }
+}
@@ -8,22 +8,27 @@
 //!
 //! - **Agent**: Person's automaton for AI model interaction
 //! - **ModelConfiguration**: AI model configuration lifecycle
+//! - **Conversation**: Participants and their roles in a conversation
 //!
 //! # Design Principles
 //!
 //! 1. **Agent = Person's Automaton**: Every agent is bound to a PersonId
 //! 2. **Configuration Reuse**: Multiple agents can reference same ModelConfiguration
-//! 3. **Stateless Messages**: No conversation state maintained
+//! 3. **Stateless Messages**: Agents keep no conversation state; `Conversation` owns it
 //! 4. **Event-Sourced**: All state changes through immutable events
 
+mod conversation;
 mod model_configuration;
 // Temporarily disabled - over-engineered, being replaced
 // mod agent_definition;
 
+pub use conversation::Conversation;
 pub use model_configuration::ModelConfiguration;
 // Temporarily disabled
 // pub use agent_definition::{AgentDefinition, KnowledgeSection, ExampleSection};
 
+use crate::capabilities::{ProviderCapabilities, RuntimeCapabilities};
+use crate::commands::AgentCommand;
 use crate::events::*;
 use crate::value_objects::*;
 use chrono::{DateTime, Utc};
@@ -71,6 +76,22 @@ pub struct Agent {
     #[serde(skip_serializing_if = "Option::is_none")]
     system_prompt: Option<String>,
 
+    /// Runtime feature flags set for this agent
+    #[serde(default, skip_serializing_if = "FeatureFlags::is_empty")]
+    feature_flags: FeatureFlags,
+
+    /// Locale and timezone of the agent's users
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    locale: Option<LocalePreferences>,
+
+    /// Compliance hold freezing the agent's data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    legal_hold: Option<LegalHold>,
+
+    /// Declared capabilities that failed a runtime probe since the model was configured
+    #[serde(default, skip_serializing_if = "RuntimeCapabilities::is_empty")]
+    unverified_capabilities: RuntimeCapabilities,
+
     /// When the agent was created
     created_at: DateTime<Utc>,
 
@@ -92,7 +113,11 @@ impl Agent {
             model_configuration_id: None,
             model_config: None,
             system_prompt: None,
-            created_at: Utc::now(),
+            feature_flags: FeatureFlags::new(),
+            locale: None,
+            legal_hold: None,
+            unverified_capabilities: RuntimeCapabilities::empty(),
+            created_at: clock_now(),
             version: 0,
         }
     }
@@ -111,7 +136,11 @@ impl Agent {
             model_configuration_id: None,
             model_config: None,
             system_prompt: None,
-            created_at: Utc::now(),
+            feature_flags: FeatureFlags::new(),
+            locale: None,
+            legal_hold: None,
+            unverified_capabilities: RuntimeCapabilities::empty(),
+            created_at: clock_now(),
             version: 0,
         }
     }
@@ -161,6 +190,39 @@ impl Agent {
         self.system_prompt.as_deref()
     }
 
+    /// Get the agent's feature flags
+    pub fn feature_flags(&self) -> &FeatureFlags {
+        &self.feature_flags
+    }
+
+    /// Get the agent's locale preferences, if set
+    pub fn locale(&self) -> Option<&LocalePreferences> {
+        self.locale.as_ref()
+    }
+
+    /// Get the legal hold on the agent's data, if any
+    pub fn legal_hold(&self) -> Option<&LegalHold> {
+        self.legal_hold.as_ref()
+    }
+
+    /// Get the declared capabilities that failed a runtime probe
+    pub fn unverified_capabilities(&self) -> RuntimeCapabilities {
+        self.unverified_capabilities
+    }
+
+    /// Get the capabilities the agent advertises
+    ///
+    /// Its provider's declared capabilities, less any that failed a probe.
+    /// `None` without an embedded model configuration.
+    #[allow(deprecated)]
+    pub fn advertised_capabilities(&self) -> Option<RuntimeCapabilities> {
+        self.model_config.as_ref().map(|config| {
+            ProviderCapabilities::for_provider(config.provider)
+                .capabilities
+                .difference(self.unverified_capabilities)
+        })
+    }
+
     /// Get when the agent was created
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
@@ -205,6 +267,11 @@ impl Agent {
         self.status == AgentStatus::Decommissioned
     }
 
+    /// Check if archival, crypto-shredding and retention must skip the agent
+    pub fn is_on_legal_hold(&self) -> bool {
+        self.legal_hold.is_some()
+    }
+
     // ========================================================================
     // Event Application (Pure Functional)
     // ========================================================================
@@ -234,6 +301,7 @@ impl Agent {
                     return Err("Cannot configure model for decommissioned agent".to_string());
                 }
                 new_agent.model_config = Some(e.config.clone());
+                new_agent.unverified_capabilities = RuntimeCapabilities::empty();
             }
 
             AgentEvent::ModelConfigurationAssigned(e) => {
@@ -241,6 +309,7 @@ impl Agent {
                     return Err("Cannot assign configuration to decommissioned agent".to_string());
                 }
                 new_agent.model_configuration_id = Some(e.configuration_id);
+                new_agent.unverified_capabilities = RuntimeCapabilities::empty();
             }
 
             AgentEvent::SystemPromptConfigured(e) => {
@@ -250,6 +319,20 @@ impl Agent {
                 new_agent.system_prompt = Some(e.system_prompt.clone());
             }
 
+            AgentEvent::FeatureFlagsChanged(e) => {
+                if new_agent.is_decommissioned() {
+                    return Err("Cannot change feature flags of decommissioned agent".to_string());
+                }
+                new_agent.feature_flags.apply(&e.changes);
+            }
+
+            AgentEvent::LocalePreferencesChanged(e) => {
+                if new_agent.is_decommissioned() {
+                    return Err("Cannot change locale of decommissioned agent".to_string());
+                }
+                new_agent.locale = Some(e.preferences.clone());
+            }
+
             AgentEvent::AgentActivated(_) => {
                 if !new_agent.has_model_config() {
                     return Err("Cannot activate agent without model configuration".to_string());
@@ -271,12 +354,65 @@ impl Agent {
                 new_agent.status = AgentStatus::Decommissioned;
             }
 
-            // Message events do NOT modify agent state
+            // Holds apply to decommissioned agents too: their data outlives them
+            AgentEvent::LegalHoldPlaced(e) => {
+                if new_agent.is_on_legal_hold() {
+                    return Err("Agent is already on legal hold".to_string());
+                }
+                new_agent.legal_hold = Some(e.hold());
+            }
+
+            AgentEvent::LegalHoldReleased(_) => {
+                if !new_agent.is_on_legal_hold() {
+                    return Err("Agent is not on legal hold".to_string());
+                }
+                new_agent.legal_hold = None;
+            }
+
+            // Withdrawn until the model is configured again
+            AgentEvent::CapabilityVerificationFailed(e) => {
+                new_agent.unverified_capabilities.insert(e.capability);
+            }
+
+            // Message and operational events do NOT modify agent state
             // They are purely for NATS consumers
             AgentEvent::MessageSent(_)
             | AgentEvent::ResponseChunkReceived(_)
             | AgentEvent::ResponseCompleted(_)
-            | AgentEvent::ResponseFailed(_) => {
+            | AgentEvent::ResponseFailed(_)
+            | AgentEvent::OutputLimitEnforced(_)
+            | AgentEvent::ResponseCancelled(_)
+            | AgentEvent::SloViolated(_)
+            | AgentEvent::DailyDigestReady(_)
+            | AgentEvent::ConversationArchived(_)
+            | AgentEvent::ConfigurationDriftDetected(_)
+            | AgentEvent::CanaryDecided(_)
+            | AgentEvent::EvaluationCompleted(_)
+            | AgentEvent::CapabilityNegotiated(_)
+            | AgentEvent::DeadlineExceeded(_)
+            | AgentEvent::LongContextFallback(_)
+            | AgentEvent::ContextTruncated(_)
+            | AgentEvent::CandidateSelected(_)
+            | AgentEvent::ResponseDrafted(_)
+            | AgentEvent::ReasoningTraceRecorded(_)
+            | AgentEvent::DeprecatedToolInUse(_)
+            | AgentEvent::ToolVersionMigrated(_)
+            | AgentEvent::ToolInvoked(_)
+            | AgentEvent::ToolSucceeded(_)
+            | AgentEvent::ToolFailed(_)
+            | AgentEvent::LegalHoldRefused(_)
+            | AgentEvent::PermissionEscalationRequested(_)
+            | AgentEvent::PermissionEscalationApproved(_)
+            | AgentEvent::PermissionEscalationExpired(_)
+            | AgentEvent::AnomalyDetected(_)
+            | AgentEvent::BatchProgress(_)
+            | AgentEvent::BatchCompleted(_)
+            | AgentEvent::MessageRetryScheduled(_)
+            | AgentEvent::MessageQuarantined(_)
+            | AgentEvent::QuarantinedMessageReleased(_)
+            | AgentEvent::ChangeRateExceeded(_)
+            | AgentEvent::OutputConstraintEnforced(_)
+            | AgentEvent::SpendThresholdCrossed(_) => {
                 // No state change - these are side-effect events
             }
         }
@@ -295,6 +431,107 @@ impl Agent {
         }
         Ok(current)
     }
+
+    // ========================================================================
+    // Command Handling
+    // ========================================================================
+
+    /// Decide the events a lifecycle command produces
+    ///
+    /// `DeployAgent` is handled by [`Agent::empty`]; every other command needs
+    /// a deployed agent. Message commands call out to a model and are not
+    /// decided here.
+    pub fn handle(&self, cmd: &AgentCommand) -> Result<Vec<AgentEvent>, String> {
+        cmd.validate()?;
+        if let AgentCommand::DeployAgent(cmd) = cmd {
+            if self.version > 0 {
+                return Err(format!("Agent already deployed: {}", cmd.agent_id));
+            }
+            return Ok(vec![AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                cmd.agent_id,
+                cmd.person_id,
+                &cmd.name,
+                cmd.description.clone(),
+            ))]);
+        }
+        if self.version == 0 {
+            return Err(format!("Agent not found: {}", cmd.agent_id()));
+        }
+
+        let event = match cmd {
+            AgentCommand::DeployAgent(_) => unreachable!("handled above"),
+            AgentCommand::ConfigureModel(cmd) => {
+                if self.is_decommissioned() {
+                    return Err("Cannot configure model for decommissioned agent".to_string());
+                }
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+                    cmd.agent_id,
+                    cmd.config.clone(),
+                ))
+            }
+            AgentCommand::ActivateAgent(cmd) => {
+                if !self.can_activate() {
+                    if !self.has_model_config() {
+                        return Err("Cannot activate agent without model configuration".to_string());
+                    }
+                    if self.is_decommissioned() {
+                        return Err("Cannot activate decommissioned agent".to_string());
+                    }
+                    return Err("Agent cannot be activated in current state".to_string());
+                }
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(cmd.agent_id))
+            }
+            AgentCommand::SuspendAgent(cmd) => {
+                if !self.can_suspend() {
+                    return Err("Agent cannot be suspended in current state".to_string());
+                }
+                AgentEvent::AgentSuspended(AgentSuspendedEvent::new(cmd.agent_id, &cmd.reason))
+            }
+            AgentCommand::DecommissionAgent(cmd) => AgentEvent::AgentDecommissioned(
+                AgentDecommissionedEvent::new(cmd.agent_id, cmd.reason.clone()),
+            ),
+            AgentCommand::UpdateConfiguration(cmd) => {
+                // Only what actually changes is recorded; no change, no events
+                let mut events = Vec::new();
+                let changes = self.feature_flags.changes(&cmd.feature_flags);
+                if !changes.is_empty() {
+                    events.push(AgentEvent::FeatureFlagsChanged(FeatureFlagsChangedEvent::new(
+                        cmd.agent_id,
+                        changes,
+                    )));
+                }
+                if let Some(locale) = cmd.locale.as_ref().filter(|l| self.locale() != Some(*l)) {
+                    events.push(AgentEvent::LocalePreferencesChanged(
+                        LocalePreferencesChangedEvent::new(cmd.agent_id, locale.clone()),
+                    ));
+                }
+                return Ok(events);
+            }
+            AgentCommand::PlaceLegalHold(cmd) => AgentEvent::LegalHoldPlaced(
+                LegalHoldPlacedEvent::new(cmd.agent_id, cmd.placed_by, &cmd.reason),
+            ),
+            AgentCommand::ReleaseLegalHold(cmd) => AgentEvent::LegalHoldReleased(
+                LegalHoldReleasedEvent::new(cmd.agent_id, cmd.released_by),
+            ),
+            AgentCommand::SendMessage(_)
+            | AgentCommand::CancelMessage(_)
+            | AgentCommand::ResumeStream(_)
+            | AgentCommand::ApproveEscalation(_)
+            | AgentCommand::ReleaseQuarantinedMessage(_) => {
+                return Err("Only lifecycle commands are decided by the agent".to_string());
+            }
+        };
+        Ok(vec![event])
+    }
+
+    /// Handle a command and apply the events it produces
+    ///
+    /// Pure: the result is a new agent, so a command can be tried out
+    /// without saving or publishing anything.
+    pub fn execute(&self, cmd: &AgentCommand) -> Result<(Self, Vec<AgentEvent>), String> {
+        let events = self.handle(cmd)?;
+        Ok((self.apply_events(&events)?, events))
+    }
 }
 
 impl Default for Agent {
@@ -330,6 +567,36 @@ mod tests {
         assert_eq!(agent.version(), 1);
     }
 
+    #[test]
+    fn test_execute_decides_without_touching_the_agent() {
+        use crate::commands::{ActivateAgent, ConfigureModel, DeployAgent};
+
+        let (agent_id, person_id) = (AgentId::new(), PersonId::new());
+        let deploy = AgentCommand::DeployAgent(
+            DeployAgent::new(person_id, "sage").with_agent_id(agent_id),
+        );
+        let (agent, events) = Agent::empty().execute(&deploy).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(agent.version(), 1);
+        assert!(agent.handle(&deploy).is_err());
+
+        let activate = AgentCommand::ActivateAgent(ActivateAgent::new(agent_id));
+        assert_eq!(
+            agent.execute(&activate).unwrap_err(),
+            "Cannot activate agent without model configuration"
+        );
+        let configure =
+            AgentCommand::ConfigureModel(ConfigureModel::new(agent_id, ModelConfig::mock()));
+        let (configured, _) = agent.execute(&configure).unwrap();
+        let (active, _) = configured.execute(&activate).unwrap();
+        assert_eq!(active.status(), AgentStatus::Active);
+        assert_eq!(agent.status(), AgentStatus::Deployed);
+        assert!(!agent.has_model_config());
+
+        let other = AgentCommand::ActivateAgent(ActivateAgent::new(AgentId::new()));
+        assert!(Agent::empty().handle(&other).is_err());
+    }
+
     #[test]
     fn test_model_configuration() {
         let (agent, agent_id, _) = create_deployed_agent();
@@ -344,6 +611,22 @@ mod tests {
         assert_eq!(agent.version(), 2);
     }
 
+    #[test]
+    fn test_feature_flags_merge_over_defaults() {
+        let (agent, agent_id, _) = create_deployed_agent();
+        assert!(agent.feature_flags().is_enabled(FeatureFlags::ENABLE_RAG));
+
+        let changes =
+            std::collections::BTreeMap::from([(FeatureFlags::ENABLE_RAG.to_string(), false)]);
+        let agent = agent
+            .apply_event(&AgentEvent::FeatureFlagsChanged(FeatureFlagsChangedEvent::new(
+                agent_id, changes,
+            )))
+            .unwrap();
+        assert!(!agent.feature_flags().is_enabled(FeatureFlags::ENABLE_RAG));
+        assert!(!agent.feature_flags().is_enabled(FeatureFlags::GUARDRAILS_STRICT));
+    }
+
     #[test]
     fn test_agent_activation_requires_model() {
         let (agent, agent_id, _) = create_deployed_agent();
@@ -445,6 +728,30 @@ mod tests {
         assert_eq!(agent.version(), 3);
     }
 
+    #[test]
+    fn test_legal_hold_survives_decommissioning() {
+        let (agent, agent_id, person_id) = create_deployed_agent();
+        let placed = AgentEvent::LegalHoldPlaced(LegalHoldPlacedEvent::new(
+            agent_id,
+            person_id,
+            "Matter 2025-114",
+        ));
+        let agent = agent.apply_event(&placed).unwrap();
+        assert_eq!(agent.legal_hold().unwrap().reason, "Matter 2025-114");
+        assert!(agent.apply_event(&placed).is_err());
+
+        let decommissioned =
+            AgentEvent::AgentDecommissioned(AgentDecommissionedEvent::new(agent_id, None));
+        let agent = agent.apply_event(&decommissioned).unwrap();
+        assert!(agent.is_on_legal_hold());
+
+        let released =
+            AgentEvent::LegalHoldReleased(LegalHoldReleasedEvent::new(agent_id, person_id));
+        let agent = agent.apply_event(&released).unwrap();
+        assert!(!agent.is_on_legal_hold());
+        assert!(agent.apply_event(&released).is_err());
+    }
+
     #[test]
     fn test_agent_serialization() {
         let (agent, _, _) = create_deployed_agent();
@@ -22,7 +22,8 @@ use crate::events::{
     ModelConfigurationEvent, ModelParametersUpdatedEvent, ModelProviderChangedEvent,
 };
 use crate::value_objects::{
-    ConfigurationStatus, ModelConfig, ModelConfigurationId, ModelConstraints, ProviderType,
+    clock_now, ConfigurationStatus, ModelConfig, ModelConfigurationId, ModelConstraints,
+    ProviderType,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -87,8 +88,8 @@ impl ModelConfiguration {
             constraints: ModelConstraints::default(),
             description: None,
             status: ConfigurationStatus::Draft,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            created_at: clock_now(),
+            updated_at: clock_now(),
             version: 0,
         }
     }
@@ -108,8 +109,8 @@ impl ModelConfiguration {
             constraints: cmd.constraints,
             description: cmd.description,
             status: ConfigurationStatus::Draft,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            created_at: clock_now(),
+            updated_at: clock_now(),
             version: 0,
         })
     }
@@ -208,6 +209,12 @@ impl ModelConfiguration {
             presence_penalty: self.parameters.presence_penalty,
             stop_sequences: vec![],
             system_prompt: String::new(), // Set per-agent
+            generation: None,
+            intent_overrides: Default::default(),
+            truncation: Default::default(),
+            best_of: Default::default(),
+            reflection: None,
+            output_constraints: None,
         }
     }
 
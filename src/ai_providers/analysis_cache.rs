@@ -0,0 +1,358 @@
+//! Caching of graph analyses by graph content
+//!
+//! Analysing an unchanged graph again should cost nothing.
+//! [`GraphData::content_hash`] hashes the graph's canonical form (see
+//! [`canonical`](super::canonical)) without its id, so the same content
+//! always hashes the same. [`CachedAnalysisProvider`]
+//! wraps any provider and answers repeat requests from a cache keyed by
+//! (content hash, capability, parameters):
+//!
+//! ```text
+//! analyze_graph(graph, capability, params)
+//!     │ key = (graph.content_hash(), capability, params)
+//!     ├── hit  ──> cached AnalysisResult
+//!     └── miss ──> inner provider ──> cache ──> AnalysisResult
+//!
+//! graph delta arrives ──> invalidate_graph(graph_id) ──> entries for that graph dropped
+//! ```
+//!
+//! Failed analyses are not cached. The cache holds at most `capacity`
+//! results between both kinds of request, dropping the least recently used
+//! when full.
+
+use super::*;
+use crate::value_objects::{AnalysisResult, TransformationSuggestion};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// Canonical text of request parameters, for cache keys
+fn canonical_params(parameters: &HashMap<String, Value>) -> String {
+    let sorted: BTreeMap<&String, &Value> = parameters.iter().collect();
+    json!(sorted).to_string()
+}
+
+/// Results kept by default, analyses and suggestions together
+pub const DEFAULT_ANALYSIS_CACHE_CAPACITY: usize = 1_000;
+
+/// Cache key: content hash, request kind and canonical parameters
+type CacheKey = (String, String, String);
+
+#[derive(Default)]
+struct CacheState {
+    analyses: HashMap<CacheKey, AnalysisResult>,
+    suggestions: HashMap<CacheKey, Vec<TransformationSuggestion>>,
+    /// Content hashes cached for each graph, for invalidation
+    graphs: HashMap<Uuid, HashSet<String>>,
+    /// Last use of each key, and keys by last use
+    last_used: HashMap<CacheKey, u64>,
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl CacheState {
+    fn remove_hash(&mut self, hash: &str) {
+        self.analyses.retain(|(h, _, _), _| h != hash);
+        self.suggestions.retain(|(h, _, _), _| h != hash);
+        let recency = &mut self.recency;
+        self.last_used.retain(|(h, _, _), tick| {
+            if h == hash {
+                recency.remove(tick);
+            }
+            h != hash
+        });
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        if let Some(previous) = self.last_used.insert(key.clone(), self.tick) {
+            self.recency.remove(&previous);
+        }
+        self.recency.insert(self.tick, key.clone());
+    }
+
+    /// Drop least recently used results until at most `capacity` remain
+    fn evict(&mut self, capacity: usize) {
+        while self.last_used.len() > capacity {
+            let Some((_, key)) = self.recency.pop_first() else {
+                break;
+            };
+            self.last_used.remove(&key);
+            self.analyses.remove(&key);
+            self.suggestions.remove(&key);
+            self.evictions += 1;
+
+            // Stop tracking the content once none of its results are left
+            let hash = &key.0;
+            if !self.last_used.keys().any(|(h, _, _)| h == hash) {
+                self.graphs.retain(|_, hashes| {
+                    hashes.remove(hash);
+                    !hashes.is_empty()
+                });
+            }
+        }
+    }
+}
+
+/// Hit and miss counts of a [`CachedAnalysisProvider`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalysisCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+/// Provider wrapper that reuses results for unchanged graphs
+pub struct CachedAnalysisProvider {
+    inner: Arc<dyn GraphAnalysisProvider>,
+    capacity: usize,
+    state: RwLock<CacheState>,
+}
+
+impl CachedAnalysisProvider {
+    /// Cache the results of `inner`
+    pub fn new(inner: Arc<dyn GraphAnalysisProvider>) -> Self {
+        Self {
+            inner,
+            capacity: DEFAULT_ANALYSIS_CACHE_CAPACITY,
+            state: RwLock::new(CacheState::default()),
+        }
+    }
+
+    /// Builder: keep at most `capacity` results
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Maximum number of cached results
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Drop every result cached for `graph_id`; call when a delta for it arrives
+    pub fn invalidate_graph(&self, graph_id: Uuid) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        let Some(hashes) = state.graphs.remove(&graph_id) else {
+            return;
+        };
+        for hash in hashes {
+            // Another graph with the same content keeps its entries
+            if !state.graphs.values().any(|others| others.contains(&hash)) {
+                state.remove_hash(&hash);
+            }
+        }
+    }
+
+    /// Drop every cached result
+    pub fn clear(&self) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.analyses.clear();
+        state.suggestions.clear();
+        state.graphs.clear();
+        state.last_used.clear();
+        state.recency.clear();
+    }
+
+    /// Hits, misses and cached entries so far
+    pub fn stats(&self) -> AnalysisCacheStats {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        AnalysisCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+            entries: state.analyses.len() + state.suggestions.len(),
+        }
+    }
+
+    /// Look up `key`, counting the hit or miss
+    fn cached<T: Clone>(
+        &self,
+        key: &CacheKey,
+        table: impl Fn(&CacheState) -> &HashMap<CacheKey, T>,
+    ) -> Option<T> {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        let found = table(&state).get(key).cloned();
+        match found {
+            Some(_) => {
+                state.hits += 1;
+                state.touch(key);
+            }
+            None => state.misses += 1,
+        }
+        found
+    }
+
+    /// Store a result under `key` for `graph_id`, evicting to stay in capacity
+    fn store<T>(
+        &self,
+        graph_id: Uuid,
+        key: CacheKey,
+        value: T,
+        table: impl Fn(&mut CacheState) -> &mut HashMap<CacheKey, T>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state
+            .graphs
+            .entry(graph_id)
+            .or_default()
+            .insert(key.0.clone());
+        state.touch(&key);
+        table(&mut state).insert(key, value);
+        state.evict(self.capacity);
+    }
+}
+
+#[async_trait]
+impl GraphAnalysisProvider for CachedAnalysisProvider {
+    async fn analyze_graph(
+        &self,
+        graph_data: GraphData,
+        analysis_type: AnalysisCapability,
+        parameters: HashMap<String, Value>,
+    ) -> AIProviderResult<AnalysisResult> {
+        let graph_id = graph_data.graph_id;
+        let key = (
+            graph_data.content_hash(),
+            format!("analysis:{analysis_type:?}"),
+            canonical_params(&parameters),
+        );
+        if let Some(result) = self.cached(&key, |state| &state.analyses) {
+            return Ok(result);
+        }
+
+        let result = self
+            .inner
+            .analyze_graph(graph_data, analysis_type, parameters)
+            .await?;
+        self.store(graph_id, key, result.clone(), |state| &mut state.analyses);
+        Ok(result)
+    }
+
+    async fn suggest_transformations(
+        &self,
+        graph_data: GraphData,
+        optimization_goals: Vec<String>,
+        constraints: HashMap<String, Value>,
+    ) -> AIProviderResult<Vec<TransformationSuggestion>> {
+        let graph_id = graph_data.graph_id;
+        let key = (
+            graph_data.content_hash(),
+            format!("suggestions:{}", json!(optimization_goals)),
+            canonical_params(&constraints),
+        );
+        if let Some(suggestions) = self.cached(&key, |state| &state.suggestions) {
+            return Ok(suggestions);
+        }
+
+        let suggestions = self
+            .inner
+            .suggest_transformations(graph_data, optimization_goals, constraints)
+            .await?;
+        self.store(graph_id, key, suggestions.clone(), |state| {
+            &mut state.suggestions
+        });
+        Ok(suggestions)
+    }
+
+    fn supports_capability(&self, capability: &AnalysisCapability) -> bool {
+        self.inner.supports_capability(capability)
+    }
+
+    fn get_metadata(&self) -> ProviderMetadata {
+        self.inner.get_metadata()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_providers::mock::MockAIProvider;
+
+    fn graph(graph_id: Uuid, order: &[&str]) -> GraphData {
+        GraphData {
+            graph_id,
+            nodes: order
+                .iter()
+                .map(|id| NodeData {
+                    id: id.to_string(),
+                    node_type: "process".to_string(),
+                    label: id.to_uppercase(),
+                    properties: HashMap::from([
+                        ("weight".to_string(), json!(1)),
+                        ("owner".to_string(), json!("ops")),
+                    ]),
+                    position: None,
+                })
+                .collect(),
+            edges: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ignores_order_and_id() {
+        let a = graph(Uuid::new_v4(), &["a", "b"]);
+        let b = graph(Uuid::new_v4(), &["b", "a"]);
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        let mut changed = a.clone();
+        changed.nodes[0].label = "renamed".to_string();
+        assert_ne!(a.content_hash(), changed.content_hash());
+    }
+
+    #[tokio::test]
+    async fn test_repeat_analyses_hit_until_invalidated() {
+        let provider = CachedAnalysisProvider::new(Arc::new(MockAIProvider::new()));
+        let graph_id = Uuid::new_v4();
+        let analyze = |order: &'static [&'static str]| {
+            provider.analyze_graph(
+                graph(graph_id, order),
+                AnalysisCapability::GraphAnalysis,
+                HashMap::new(),
+            )
+        };
+
+        analyze(&["a", "b"]).await.unwrap();
+        analyze(&["b", "a"]).await.unwrap();
+        let stats = provider.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+        provider.invalidate_graph(graph_id);
+        analyze(&["a", "b"]).await.unwrap();
+        assert_eq!(provider.stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_results_are_evicted() {
+        let provider =
+            CachedAnalysisProvider::new(Arc::new(MockAIProvider::new())).with_capacity(2);
+        let analyze = |order: &'static [&'static str]| {
+            provider.analyze_graph(
+                graph(Uuid::new_v4(), order),
+                AnalysisCapability::GraphAnalysis,
+                HashMap::new(),
+            )
+        };
+
+        analyze(&["a"]).await.unwrap();
+        analyze(&["b"]).await.unwrap();
+        analyze(&["a"]).await.unwrap();
+        analyze(&["c"]).await.unwrap();
+        let stats = provider.stats();
+        assert_eq!((stats.entries, stats.evictions), (2, 1));
+
+        // "b" went, "a" was used since and stayed
+        analyze(&["a"]).await.unwrap();
+        assert_eq!(provider.stats().hits, 2);
+        analyze(&["b"]).await.unwrap();
+        assert_eq!(provider.stats().misses, 4);
+    }
+}
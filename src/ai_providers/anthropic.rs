@@ -279,7 +279,12 @@ impl GraphAnalysisProvider for AnthropicProvider {
         let message_response: MessageResponse = response.json().await
             .map_err(|e| AIProviderError::InvalidResponse(e.to_string()))?;
         
-        self.parse_analysis_response(&message_response, analysis_type)
+        let optimize = matches!(analysis_type, AnalysisCapability::WorkflowOptimization);
+        let mut result = self.parse_analysis_response(&message_response, analysis_type)?;
+        if optimize {
+            attach_workflow_optimization(&mut result, &graph_data);
+        }
+        Ok(result)
     }
     
     async fn suggest_transformations(
@@ -0,0 +1,164 @@
+//! Canonical form of graph data
+//!
+//! [`GraphData`] serializes canonically: nodes and edges sorted by id and
+//! every property map sorted by key, so one graph always produces the same
+//! bytes whatever order it was built in. Equality, hashing and the content
+//! hash are all defined on that form:
+//!
+//! ```text
+//! GraphData ──serde──> canonical JSON ──> PartialEq / Eq / Hash
+//!     │                      └── minus graph_id ──> SHA-256 ──> content_hash()
+//!     └── to_bytes() ──> DAG-CBOR ──> from_bytes() ──> GraphData
+//! ```
+//!
+//! The DAG-CBOR form is compact and itself canonical, which makes it suitable
+//! for embedding a graph in an event.
+
+use super::*;
+use serde::Serializer;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+/// Errors converting graph data to or from its binary form
+#[derive(Debug, Error)]
+pub enum GraphCodecError {
+    #[error("Failed to encode graph: {0}")]
+    Encode(String),
+
+    #[error("Failed to decode graph: {0}")]
+    Decode(String),
+}
+
+/// Serialize a property map with its keys sorted
+pub(super) fn sorted_map<S: Serializer>(
+    map: &HashMap<String, Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let sorted: BTreeMap<&String, &Value> = map.iter().collect();
+    sorted.serialize(serializer)
+}
+
+/// Serialize nodes ordered by id
+pub(super) fn sorted_nodes<S: Serializer>(
+    nodes: &[NodeData],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&NodeData> = nodes.iter().collect();
+    sorted.sort_by(|a, b| a.id.cmp(&b.id));
+    sorted.serialize(serializer)
+}
+
+/// Serialize edges ordered by id
+pub(super) fn sorted_edges<S: Serializer>(
+    edges: &[EdgeData],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&EdgeData> = edges.iter().collect();
+    sorted.sort_by(|a, b| a.id.cmp(&b.id));
+    sorted.serialize(serializer)
+}
+
+impl GraphData {
+    /// The canonical JSON form
+    pub fn canonical_json(&self) -> Value {
+        serde_json::to_value(self).expect("graph data has string keys")
+    }
+
+    /// SHA-256 of the canonical form without `graph_id`, hex encoded
+    ///
+    /// Two graphs with the same nodes, edges and metadata share a content
+    /// hash whatever their ids or ordering.
+    pub fn content_hash(&self) -> String {
+        let mut canonical = self.canonical_json();
+        if let Some(fields) = canonical.as_object_mut() {
+            fields.remove("graph_id");
+        }
+        let digest = Sha256::digest(canonical.to_string().as_bytes());
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Whether `other` has the same content, ignoring `graph_id`
+    pub fn same_content(&self, other: &GraphData) -> bool {
+        self.content_hash() == other.content_hash()
+    }
+
+    /// Compact canonical binary form (DAG-CBOR)
+    pub fn to_bytes(&self) -> Result<Vec<u8>, GraphCodecError> {
+        serde_ipld_dagcbor::to_vec(self).map_err(|e| GraphCodecError::Encode(e.to_string()))
+    }
+
+    /// Read the binary form written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GraphCodecError> {
+        serde_ipld_dagcbor::from_slice(bytes).map_err(|e| GraphCodecError::Decode(e.to_string()))
+    }
+}
+
+impl PartialEq for GraphData {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical_json() == other.canonical_json()
+    }
+}
+
+impl Eq for GraphData {}
+
+impl Hash for GraphData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_json().to_string().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn graph(order: &[&str]) -> GraphData {
+        GraphData {
+            graph_id: uuid::Uuid::nil(),
+            nodes: order
+                .iter()
+                .map(|id| NodeData {
+                    id: id.to_string(),
+                    node_type: "process".to_string(),
+                    label: id.to_uppercase(),
+                    properties: HashMap::from([
+                        ("weight".to_string(), json!(1)),
+                        ("owner".to_string(), json!("ops")),
+                    ]),
+                    position: Some((1.0, 2.0, 0.5)),
+                })
+                .collect(),
+            edges: vec![EdgeData {
+                id: "a-b".to_string(),
+                source: "a".to_string(),
+                target: "b".to_string(),
+                edge_type: "flow".to_string(),
+                properties: HashMap::new(),
+            }],
+            metadata: HashMap::from([("source".to_string(), json!("test"))]),
+        }
+    }
+
+    #[test]
+    fn test_equality_and_hash_ignore_order() {
+        let (a, b) = (graph(&["a", "b"]), graph(&["b", "a"]));
+        assert_eq!(a, b);
+        assert_eq!(serde_json::to_string(&a).unwrap(), serde_json::to_string(&b).unwrap());
+        assert_eq!(HashSet::from([a.clone(), b]).len(), 1);
+
+        let mut renumbered = a.clone();
+        renumbered.graph_id = uuid::Uuid::new_v4();
+        assert_ne!(a, renumbered);
+        assert!(a.same_content(&renumbered));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let original = graph(&["b", "a"]);
+        let bytes = original.to_bytes().unwrap();
+        assert_eq!(bytes, graph(&["a", "b"]).to_bytes().unwrap());
+        assert_eq!(GraphData::from_bytes(&bytes).unwrap(), original);
+        assert!(GraphData::from_bytes(&bytes[1..]).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Comparison of two graph revisions
+//!
+//! [`GraphAnalysisProvider::compare_graphs`] diffs two revisions of a graph
+//! structurally, measures both, and asks the provider to comment on the
+//! change:
+//!
+//! ```text
+//! before, after ──> GraphDiff { added / removed / changed nodes and edges }
+//!               ──> MetricChange per metric (node count ... critical path)
+//!               ──> analyze_graph(after, capability, { comparison }) ──> commentary
+//!                         └─────────────── GraphComparison ───────────────┘
+//! ```
+//!
+//! Nodes and edges are matched by id and compared in canonical form, so
+//! property order never shows up as a change. Workflow metrics are included
+//! when both revisions are DAGs.
+
+use super::workflow_optimizer::WorkflowMetrics;
+use super::*;
+use std::collections::BTreeMap;
+
+/// A node present in both revisions with different content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeChange {
+    pub before: NodeData,
+    pub after: NodeData,
+}
+
+/// An edge present in both revisions with different content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeChange {
+    pub before: EdgeData,
+    pub after: EdgeData,
+}
+
+/// Structural differences between two revisions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<NodeData>,
+    pub removed_nodes: Vec<NodeData>,
+    pub changed_nodes: Vec<NodeChange>,
+    pub added_edges: Vec<EdgeData>,
+    pub removed_edges: Vec<EdgeData>,
+    pub changed_edges: Vec<EdgeChange>,
+}
+
+/// Pair items of two revisions by id: (only before, only after, changed)
+fn diff_by_id<T: Clone + Serialize>(
+    before: &[T],
+    after: &[T],
+    id: impl Fn(&T) -> &str,
+) -> (Vec<T>, Vec<T>, Vec<(T, T)>) {
+    let old: BTreeMap<&str, &T> = before.iter().map(|item| (id(item), item)).collect();
+    let new: BTreeMap<&str, &T> = after.iter().map(|item| (id(item), item)).collect();
+    let canonical = |item: &T| serde_json::to_value(item).ok();
+
+    let removed = old
+        .iter()
+        .filter(|(key, _)| !new.contains_key(*key))
+        .map(|(_, item)| (*item).clone())
+        .collect();
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (key, item) in &new {
+        match old.get(key) {
+            None => added.push((*item).clone()),
+            Some(previous) if canonical(previous) != canonical(item) => {
+                changed.push(((*previous).clone(), (*item).clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    (removed, added, changed)
+}
+
+impl GraphDiff {
+    /// What changed from `before` to `after`
+    pub fn between(before: &GraphData, after: &GraphData) -> Self {
+        let (removed_nodes, added_nodes, changed_nodes) =
+            diff_by_id(&before.nodes, &after.nodes, |node| node.id.as_str());
+        let (removed_edges, added_edges, changed_edges) =
+            diff_by_id(&before.edges, &after.edges, |edge| edge.id.as_str());
+        Self {
+            added_nodes,
+            removed_nodes,
+            changed_nodes: changed_nodes
+                .into_iter()
+                .map(|(before, after)| NodeChange { before, after })
+                .collect(),
+            added_edges,
+            removed_edges,
+            changed_edges: changed_edges
+                .into_iter()
+                .map(|(before, after)| EdgeChange { before, after })
+                .collect(),
+        }
+    }
+
+    /// Whether the revisions are structurally identical
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_edges.is_empty()
+    }
+}
+
+/// One metric measured on both revisions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricChange {
+    pub name: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl MetricChange {
+    /// `after - before`
+    pub fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// Structured comparison of two graph revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphComparison {
+    /// Content hashes of the two revisions
+    pub before_hash: String,
+    pub after_hash: String,
+
+    pub diff: GraphDiff,
+    pub metrics: Vec<MetricChange>,
+
+    /// The provider's remarks on the change; empty for a purely structural
+    /// comparison
+    #[serde(default)]
+    pub commentary: Vec<String>,
+}
+
+impl GraphComparison {
+    /// Diff and metrics, without asking a provider
+    pub fn structural(before: &GraphData, after: &GraphData) -> Self {
+        let metric = |name: &str, before: f64, after: f64| MetricChange {
+            name: name.to_string(),
+            before,
+            after,
+        };
+        let mut metrics = vec![
+            metric(
+                "node_count",
+                before.nodes.len() as f64,
+                after.nodes.len() as f64,
+            ),
+            metric(
+                "edge_count",
+                before.edges.len() as f64,
+                after.edges.len() as f64,
+            ),
+        ];
+        if let (Ok(old), Ok(new)) = (
+            WorkflowMetrics::measure(before),
+            WorkflowMetrics::measure(after),
+        ) {
+            metrics.push(metric(
+                "critical_path_secs",
+                old.critical_path_secs,
+                new.critical_path_secs,
+            ));
+            metrics.push(metric(
+                "total_work_secs",
+                old.total_work_secs,
+                new.total_work_secs,
+            ));
+            metrics.push(metric("parallelism", old.parallelism, new.parallelism));
+        }
+
+        Self {
+            before_hash: before.content_hash(),
+            after_hash: after.content_hash(),
+            diff: GraphDiff::between(before, after),
+            metrics,
+            commentary: Vec::new(),
+        }
+    }
+
+    /// The named metric, if it was measured
+    pub fn metric(&self, name: &str) -> Option<&MetricChange> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+
+    /// Whether the content changed at all
+    pub fn is_unchanged(&self) -> bool {
+        self.before_hash == self.after_hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_providers::mock::MockAIProvider;
+
+    fn step(id: &str, secs: f64) -> NodeData {
+        NodeData {
+            id: id.to_string(),
+            node_type: "task".to_string(),
+            label: id.to_string(),
+            properties: HashMap::from([("duration_secs".to_string(), json!(secs))]),
+            position: None,
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> EdgeData {
+        EdgeData {
+            id: format!("{source}-{target}"),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type: "data".to_string(),
+            properties: HashMap::new(),
+        }
+    }
+
+    fn revision(nodes: Vec<NodeData>, edges: Vec<EdgeData>) -> GraphData {
+        GraphData {
+            graph_id: uuid::Uuid::nil(),
+            nodes,
+            edges,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_revisions() {
+        let before = revision(
+            vec![step("a", 2.0), step("b", 3.0), step("c", 1.0)],
+            vec![edge("a", "b"), edge("b", "c")],
+        );
+        let after = revision(
+            vec![step("a", 2.0), step("b", 1.0), step("d", 1.0)],
+            vec![edge("a", "b"), edge("a", "d")],
+        );
+
+        let comparison = MockAIProvider::new()
+            .compare_graphs(before, after, AnalysisCapability::WorkflowOptimization)
+            .await
+            .unwrap();
+        let diff = &comparison.diff;
+        assert_eq!(diff.added_nodes[0].id, "d");
+        assert_eq!(diff.removed_nodes[0].id, "c");
+        assert_eq!(diff.changed_nodes[0].after.id, "b");
+        assert_eq!(diff.added_edges[0].id, "a-d");
+        assert_eq!(diff.removed_edges[0].id, "b-c");
+        assert!(diff.changed_edges.is_empty());
+
+        assert_eq!(
+            comparison.metric("critical_path_secs").unwrap().delta(),
+            -3.0
+        );
+        assert_eq!(comparison.metric("node_count").unwrap().delta(), 0.0);
+        assert!(!comparison.is_unchanged());
+        assert!(!comparison.commentary.is_empty());
+    }
+}
@@ -1,13 +1,27 @@
 //! Configuration for AI providers
+//!
+//! Provider, model and host come from environment variables; API keys are
+//! only ever looked up through a [`SecretsProvider`].
 
 use super::{ProviderConfig, AIProviderError, AIProviderResult};
+use crate::ports::SecretsProvider;
 use std::env;
 
-/// Load provider configuration from environment variables
-pub fn load_provider_config() -> AIProviderResult<ProviderConfig> {
-    // Try to load .env file if it exists
-    let _ = dotenvy::dotenv();
-    
+/// Look an API key up in the secret source
+async fn api_key(secrets: &dyn SecretsProvider, name: &str) -> AIProviderResult<String> {
+    secrets
+        .get(name)
+        .await
+        .map(|key| key.expose().to_string())
+        .map_err(|e| AIProviderError::ConfigurationError(
+            format!("{name} could not be resolved from {}: {e}", secrets.provider_name())
+        ))
+}
+
+/// Load provider configuration from environment variables and `secrets`
+pub async fn load_provider_config(
+    secrets: &dyn SecretsProvider,
+) -> AIProviderResult<ProviderConfig> {
     // Check for default provider
     let provider_type = env::var("DEFAULT_AI_PROVIDER")
         .unwrap_or_else(|_| "mock".to_string());
@@ -16,10 +30,7 @@ pub fn load_provider_config() -> AIProviderResult<ProviderConfig> {
         "mock" => Ok(ProviderConfig::Mock),
         
         "openai" => {
-            let api_key = env::var("OPENAI_API_KEY")
-                .map_err(|_| AIProviderError::ConfigurationError(
-                    "OPENAI_API_KEY environment variable not set".to_string()
-                ))?;
+            let api_key = api_key(secrets, "OPENAI_API_KEY").await?;
             
             let model = env::var("OPENAI_MODEL")
                 .unwrap_or_else(|_| "gpt-4-turbo".to_string());
@@ -28,10 +39,7 @@ pub fn load_provider_config() -> AIProviderResult<ProviderConfig> {
         }
         
         "anthropic" => {
-            let api_key = env::var("ANTHROPIC_API_KEY")
-                .map_err(|_| AIProviderError::ConfigurationError(
-                    "ANTHROPIC_API_KEY environment variable not set".to_string()
-                ))?;
+            let api_key = api_key(secrets, "ANTHROPIC_API_KEY").await?;
             
             let model = env::var("ANTHROPIC_MODEL")
                 .unwrap_or_else(|_| "claude-3-5-sonnet-20241022".to_string());
@@ -64,27 +72,32 @@ pub enum ProviderType {
     Ollama,
 }
 
-/// Create a provider configuration from a provider type using environment variables
-pub fn create_provider_config_from_type(provider_type: &ProviderType) -> ProviderConfig {
+/// Create a provider configuration from a provider type
+///
+/// Models and hosts come from environment variables, API keys from `secrets`.
+pub async fn create_provider_config_from_type(
+    provider_type: &ProviderType,
+    secrets: &dyn SecretsProvider,
+) -> AIProviderResult<ProviderConfig> {
     match provider_type {
-        ProviderType::Mock => ProviderConfig::Mock,
+        ProviderType::Mock => Ok(ProviderConfig::Mock),
         ProviderType::OpenAI => {
-            let api_key = env::var("OPENAI_API_KEY").unwrap_or_default();
+            let api_key = api_key(secrets, "OPENAI_API_KEY").await?;
             let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4-turbo".to_string());
-            ProviderConfig::OpenAI { api_key, model }
+            Ok(ProviderConfig::OpenAI { api_key, model })
         }
         ProviderType::Anthropic => {
-            let api_key = env::var("ANTHROPIC_API_KEY").unwrap_or_default();
+            let api_key = api_key(secrets, "ANTHROPIC_API_KEY").await?;
             let model = env::var("ANTHROPIC_MODEL")
                 .unwrap_or_else(|_| "claude-3-5-sonnet-20241022".to_string());
-            ProviderConfig::Anthropic { api_key, model }
+            Ok(ProviderConfig::Anthropic { api_key, model })
         }
         ProviderType::Ollama => {
             let host = env::var("OLLAMA_HOST")
                 .unwrap_or_else(|_| "http://localhost:11434".to_string());
             let model = env::var("OLLAMA_MODEL")
                 .unwrap_or_else(|_| "llama2".to_string());
-            ProviderConfig::Ollama { host, model }
+            Ok(ProviderConfig::Ollama { host, model })
         }
     }
 }
@@ -130,23 +143,32 @@ pub fn create_provider_config(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ports::DotenvSecretsProvider;
     
-    #[test]
-    fn test_create_mock_config() {
-        let config = create_provider_config_from_type(&ProviderType::Mock);
+    #[tokio::test]
+    async fn test_create_mock_config() {
+        let secrets = DotenvSecretsProvider::new(std::env::temp_dir().join("no-such-secrets.env"));
+        let config = create_provider_config_from_type(&ProviderType::Mock, &secrets)
+            .await
+            .unwrap();
         match config {
             ProviderConfig::Mock => (),
             _ => panic!("Expected mock config"),
         }
     }
     
-    #[test]
-    fn test_create_openai_config() {
-        // Set env vars for test
-        env::set_var("OPENAI_API_KEY", "test-key");
+    #[tokio::test]
+    async fn test_create_openai_config() {
+        // The key comes from the secret source, never from the environment
+        let path = std::env::temp_dir().join(format!("openai-{}.env", uuid::Uuid::now_v7()));
+        std::fs::write(&path, "OPENAI_API_KEY=test-key\n").unwrap();
+        let secrets = DotenvSecretsProvider::new(&path);
+        env::set_var("OPENAI_API_KEY", "env-key");
         env::set_var("OPENAI_MODEL", "gpt-4");
         
-        let config = create_provider_config_from_type(&ProviderType::OpenAI);
+        let config = create_provider_config_from_type(&ProviderType::OpenAI, &secrets)
+            .await
+            .unwrap();
         
         match config {
             ProviderConfig::OpenAI { api_key, model } => {
@@ -159,5 +181,6 @@ mod tests {
         // Clean up
         env::remove_var("OPENAI_API_KEY");
         env::remove_var("OPENAI_MODEL");
+        std::fs::remove_file(path).ok();
     }
 } 
\ No newline at end of file
@@ -0,0 +1,381 @@
+//! Insight deduplication and confidence calibration across runs
+//!
+//! Analyzing the same graph repeatedly yields the same findings worded a
+//! little differently and with confidences that wander. [`InsightLedger`]
+//! embeds each run's insights, merges near-duplicates into one ledger entry
+//! per graph, and calibrates confidence by how consistently a finding
+//! recurs:
+//!
+//! ```text
+//! AnalysisResult.insights ──> embed "category: description"
+//!                                   │ same category, cosine >= threshold?
+//!                     yes ──> merge into entry   no ──> new entry (fingerprint)
+//!                                   │
+//!                                   v
+//! calibrated = sqrt(mean reported confidence × (runs seen + 1) / (runs + 2))
+//!                                   │
+//!                                   v
+//!               merged insights, one per entry, with calibrated confidence
+//! ```
+//!
+//! An entry's fingerprint is fixed by the first insight that created it, so
+//! it stays stable as rewordings are merged in. Duplicates within one run
+//! count once towards recurrence, and insights without a description are
+//! dropped before embedding.
+
+use super::*;
+use crate::ports::EmbeddingPort;
+use crate::services::cosine_similarity;
+use crate::value_objects::{Impact, Insight};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// Cosine similarity at which two insights are the same finding, by default
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.9;
+
+/// Stable fingerprint of an insight: its normalized category and description
+pub fn insight_fingerprint(category: &str, description: &str) -> String {
+    let normalize = |text: &str| {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let digest = Sha256::digest(format!(
+        "{}|{}",
+        normalize(category),
+        normalize(description)
+    ));
+    digest[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// One finding, merged across runs
+#[derive(Debug, Clone)]
+pub struct LedgerEntry {
+    pub fingerprint: String,
+
+    /// Id of the insight that created the entry
+    pub insight_id: Uuid,
+
+    pub category: String,
+
+    /// Wording of the most confident sighting
+    pub description: String,
+
+    pub impact: Impact,
+
+    /// Evidence from every sighting, without repeats
+    pub evidence: Vec<String>,
+
+    /// Runs the finding appeared in
+    pub runs_seen: u32,
+
+    /// Sum of the best confidence reported in each of those runs
+    pub confidence_sum: f64,
+
+    /// Confidence after calibrating for recurrence
+    pub calibrated_confidence: f64,
+
+    embedding: Vec<f32>,
+    best_confidence: f64,
+    last_run: u32,
+    run_confidence: f64,
+}
+
+impl LedgerEntry {
+    /// Mean confidence reported for the finding
+    pub fn mean_confidence(&self) -> f64 {
+        self.confidence_sum / f64::from(self.runs_seen.max(1))
+    }
+
+    fn calibrate(&mut self, runs: u32) {
+        let recurrence = f64::from(self.runs_seen + 1) / f64::from(runs + 2);
+        self.calibrated_confidence = (self.mean_confidence() * recurrence).sqrt();
+    }
+
+    /// The entry as a single insight with calibrated confidence
+    pub fn to_insight(&self) -> Insight {
+        Insight {
+            id: self.insight_id,
+            category: self.category.clone(),
+            description: self.description.clone(),
+            evidence: self.evidence.clone(),
+            confidence: self.calibrated_confidence as _,
+            impact: self.impact.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct GraphLedger {
+    runs: u32,
+    entries: Vec<LedgerEntry>,
+}
+
+/// Per-graph ledger of deduplicated, calibrated insights
+pub struct InsightLedger {
+    embeddings: Arc<dyn EmbeddingPort>,
+    threshold: f32,
+    graphs: RwLock<HashMap<Uuid, GraphLedger>>,
+}
+
+impl InsightLedger {
+    /// Embed insights with `embeddings`
+    pub fn new(embeddings: Arc<dyn EmbeddingPort>) -> Self {
+        Self {
+            embeddings,
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            graphs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Builder: cosine similarity at which insights are merged
+    pub fn with_similarity_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Fold one run's insights into the graph's ledger, returning them
+    /// deduplicated with calibrated confidence
+    pub async fn record(
+        &self,
+        graph_id: Uuid,
+        result: &AnalysisResult,
+    ) -> AIProviderResult<Vec<Insight>> {
+        let insights: Vec<&Insight> = result
+            .insights
+            .iter()
+            .filter(|insight| !insight.description.trim().is_empty())
+            .collect();
+        let embeddings = if insights.is_empty() {
+            Vec::new()
+        } else {
+            let texts = insights
+                .iter()
+                .map(|insight| format!("{}: {}", insight.category, insight.description))
+                .collect();
+            self.embeddings
+                .embed(texts, None)
+                .await
+                .map_err(|e| AIProviderError::ApiError(e.to_string()))?
+                .embeddings
+        };
+        if embeddings.len() != insights.len() {
+            return Err(AIProviderError::InvalidResponse(format!(
+                "{} embeddings for {} insights",
+                embeddings.len(),
+                insights.len()
+            )));
+        }
+
+        let mut graphs = self.graphs.write().unwrap_or_else(|e| e.into_inner());
+        let ledger = graphs.entry(graph_id).or_default();
+        ledger.runs += 1;
+        let run = ledger.runs;
+        let mut touched = Vec::new();
+
+        for (insight, embedding) in insights.into_iter().zip(embeddings) {
+            let confidence = f64::from(insight.confidence);
+            let matched = ledger
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.category == insight.category)
+                .map(|(i, entry)| (i, cosine_similarity(&entry.embedding, &embedding)))
+                .filter(|(_, similarity)| *similarity >= self.threshold)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i);
+
+            let index = match matched {
+                Some(i) => {
+                    let entry = &mut ledger.entries[i];
+                    if entry.last_run != run {
+                        entry.last_run = run;
+                        entry.run_confidence = confidence;
+                        entry.runs_seen += 1;
+                        entry.confidence_sum += confidence;
+                    } else if confidence > entry.run_confidence {
+                        // A second sighting in this run: keep the run's best
+                        entry.confidence_sum += confidence - entry.run_confidence;
+                        entry.run_confidence = confidence;
+                    }
+                    if confidence >= entry.best_confidence {
+                        entry.best_confidence = confidence;
+                        entry.description = insight.description.clone();
+                        entry.impact = insight.impact.clone();
+                    }
+                    for evidence in &insight.evidence {
+                        if !entry.evidence.contains(evidence) {
+                            entry.evidence.push(evidence.clone());
+                        }
+                    }
+                    i
+                }
+                None => {
+                    ledger.entries.push(LedgerEntry {
+                        fingerprint: insight_fingerprint(&insight.category, &insight.description),
+                        insight_id: insight.id,
+                        category: insight.category.clone(),
+                        description: insight.description.clone(),
+                        impact: insight.impact.clone(),
+                        evidence: insight.evidence.clone(),
+                        runs_seen: 1,
+                        confidence_sum: confidence,
+                        calibrated_confidence: 0.0,
+                        embedding,
+                        best_confidence: confidence,
+                        last_run: run,
+                        run_confidence: confidence,
+                    });
+                    ledger.entries.len() - 1
+                }
+            };
+            if !touched.contains(&index) {
+                touched.push(index);
+            }
+        }
+
+        for entry in &mut ledger.entries {
+            entry.calibrate(run);
+        }
+        Ok(touched
+            .into_iter()
+            .map(|i| ledger.entries[i].to_insight())
+            .collect())
+    }
+
+    /// Every finding recorded for a graph, most credible first
+    pub fn entries(&self, graph_id: Uuid) -> Vec<LedgerEntry> {
+        let graphs = self.graphs.read().unwrap_or_else(|e| e.into_inner());
+        let mut entries = graphs
+            .get(&graph_id)
+            .map(|ledger| ledger.entries.clone())
+            .unwrap_or_default();
+        entries.sort_by(|a, b| b.calibrated_confidence.total_cmp(&a.calibrated_confidence));
+        entries
+    }
+
+    /// The finding with `fingerprint`
+    pub fn entry(&self, graph_id: Uuid, fingerprint: &str) -> Option<LedgerEntry> {
+        let graphs = self.graphs.read().unwrap_or_else(|e| e.into_inner());
+        graphs
+            .get(&graph_id)?
+            .entries
+            .iter()
+            .find(|entry| entry.fingerprint == fingerprint)
+            .cloned()
+    }
+
+    /// Runs recorded for a graph
+    pub fn runs(&self, graph_id: Uuid) -> u32 {
+        let graphs = self.graphs.read().unwrap_or_else(|e| e.into_inner());
+        graphs.get(&graph_id).map_or(0, |ledger| ledger.runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::EmbeddingResponse;
+    use crate::ports::ChatResult;
+
+    /// Embeds by counting a few keywords
+    struct Keywords;
+
+    #[async_trait]
+    impl EmbeddingPort for Keywords {
+        async fn embed(
+            &self,
+            input: Vec<String>,
+            _: Option<&str>,
+        ) -> ChatResult<EmbeddingResponse> {
+            let embeddings = input
+                .iter()
+                .map(|text| {
+                    ["parallel", "payment", "monitor"]
+                        .iter()
+                        .map(|k| text.to_lowercase().matches(k).count() as f32)
+                        .collect()
+                })
+                .collect();
+            Ok(EmbeddingResponse::new(embeddings, "keywords"))
+        }
+
+        fn provider_name(&self) -> &'static str {
+            "keywords"
+        }
+    }
+
+    fn insight(description: &str, confidence: f64) -> Insight {
+        Insight {
+            id: Uuid::new_v4(),
+            category: "performance".to_string(),
+            description: description.to_string(),
+            evidence: vec![],
+            confidence: confidence as _,
+            impact: Impact::High,
+        }
+    }
+
+    fn run(insights: Vec<Insight>) -> AnalysisResult {
+        AnalysisResult {
+            id: Uuid::new_v4(),
+            confidence_score: 0.8,
+            summary: String::new(),
+            recommendations: vec![],
+            insights,
+            metadata: HashMap::new(),
+            timestamp: std::time::SystemTime::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_merges_rewordings_and_calibrates_by_recurrence() {
+        let ledger = InsightLedger::new(Arc::new(Keywords));
+        let graph_id = Uuid::new_v4();
+
+        let first = ledger
+            .record(
+                graph_id,
+                &run(vec![
+                    insight("Payment checks could run in parallel", 0.9),
+                    insight("Add monitoring", 0.5),
+                    insight("  ", 0.9),
+                ]),
+            )
+            .await
+            .unwrap();
+        let second = ledger
+            .record(
+                graph_id,
+                &run(vec![
+                    insight("Run the payment check in parallel", 0.7),
+                    insight("Payment validation can go parallel", 0.8),
+                ]),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].id, first[0].id);
+        assert_eq!(ledger.runs(graph_id), 2);
+
+        let entries = ledger.entries(graph_id);
+        assert_eq!(entries.len(), 2);
+        let parallel = &entries[0];
+        assert_eq!(parallel.runs_seen, 2);
+        assert!((parallel.mean_confidence() - 0.85).abs() < 1e-6);
+        assert_eq!(parallel.description, "Payment checks could run in parallel");
+        // Seen every run vs once in two
+        assert!(parallel.calibrated_confidence > entries[1].calibrated_confidence);
+        let fingerprint =
+            insight_fingerprint("performance", "Payment checks could run in parallel");
+        assert_eq!(ledger.entry(graph_id, &fingerprint).unwrap().runs_seen, 2);
+    }
+}
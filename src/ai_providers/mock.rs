@@ -117,7 +117,7 @@ impl GraphAnalysisProvider for MockAIProvider {
         metadata.insert("node_count".to_string(), json!(graph_data.nodes.len()));
         metadata.insert("edge_count".to_string(), json!(graph_data.edges.len()));
 
-        Ok(AnalysisResult {
+        let mut result = AnalysisResult {
             id: Uuid::new_v4(),
             confidence_score: 0.75,
             summary: format!("Mock analysis of graph with {} nodes and {} edges", 
@@ -128,7 +128,11 @@ impl GraphAnalysisProvider for MockAIProvider {
             insights,
             metadata,
             timestamp: std::time::SystemTime::now(),
-        })
+        };
+        if matches!(analysis_type, AnalysisCapability::WorkflowOptimization) {
+            attach_workflow_optimization(&mut result, &graph_data);
+        }
+        Ok(result)
     }
     
     async fn suggest_transformations(
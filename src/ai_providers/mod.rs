@@ -18,10 +18,27 @@ pub mod anthropic;
 pub mod ollama;
 pub mod config;
 pub mod provider_manager;
+pub mod analysis_cache;
+pub mod canonical;
+pub mod workflow_optimizer;
+pub mod comparison;
+pub mod reanalysis;
+pub mod insight_ledger;
 
 // Re-export commonly used types
 pub use config::{create_provider_config, ProviderType, load_provider_config};
 pub use provider_manager::{AIProviderManager, SelectionStrategy};
+pub use analysis_cache::{
+    AnalysisCacheStats, CachedAnalysisProvider, DEFAULT_ANALYSIS_CACHE_CAPACITY,
+};
+pub use canonical::GraphCodecError;
+pub use comparison::{EdgeChange, GraphComparison, GraphDiff, MetricChange, NodeChange};
+pub use insight_ledger::{insight_fingerprint, InsightLedger, LedgerEntry};
+pub use reanalysis::{AnalysisChangedSignificantly, ReanalysisScheduler, SignificanceThresholds};
+pub use workflow_optimizer::{
+    attach_workflow_optimization, Improvement, OptimizationProposal, WorkflowAnalysisError,
+    WorkflowChange, WorkflowMetrics, WorkflowOptimization,
+};
 
 /// Errors that can occur during AI provider operations
 #[derive(Debug, Error)]
@@ -54,6 +71,24 @@ pub enum AIProviderError {
     ConnectionError(String),
 }
 
+impl AIProviderError {
+    /// The taxonomy category of this error
+    pub fn category(&self) -> crate::ports::ErrorCategory {
+        use crate::ports::ErrorCategory;
+        match self {
+            AIProviderError::ConnectionError(_) => ErrorCategory::Transient,
+            AIProviderError::RateLimitExceeded => ErrorCategory::RateLimited,
+            AIProviderError::AuthenticationFailed(_) => ErrorCategory::Auth,
+            AIProviderError::ModelNotAvailable(_)
+            | AIProviderError::ConfigurationError(_)
+            | AIProviderError::UnsupportedCapability(_) => ErrorCategory::InvalidRequest,
+            AIProviderError::ApiError(_)
+            | AIProviderError::InvalidResponse(_)
+            | AIProviderError::Generic(_) => ErrorCategory::ProviderBug,
+        }
+    }
+}
+
 /// Result type for AI provider operations
 pub type AIProviderResult<T> = Result<T, AIProviderError>;
 
@@ -76,6 +111,26 @@ pub trait GraphAnalysisProvider: Send + Sync {
         constraints: HashMap<String, Value>,
     ) -> AIProviderResult<Vec<TransformationSuggestion>>;
     
+    /// Compare two revisions of a graph
+    ///
+    /// The structural diff and metrics are computed locally; the provider
+    /// analyzes `after` with the comparison in its parameters and its
+    /// summary and insights become the commentary.
+    async fn compare_graphs(
+        &self,
+        before: GraphData,
+        after: GraphData,
+        capability: AnalysisCapability,
+    ) -> AIProviderResult<GraphComparison> {
+        let mut comparison = GraphComparison::structural(&before, &after);
+        let parameters = HashMap::from([("comparison".to_string(), json!(comparison))]);
+        let analysis = self.analyze_graph(after, capability, parameters).await?;
+        comparison.commentary = std::iter::once(analysis.summary)
+            .chain(analysis.insights.into_iter().map(|insight| insight.description))
+            .collect();
+        Ok(comparison)
+    }
+    
     /// Check if the provider supports a specific capability
     fn supports_capability(&self, capability: &AnalysisCapability) -> bool;
     
@@ -84,38 +139,46 @@ pub trait GraphAnalysisProvider: Send + Sync {
 }
 
 /// Data structure representing a graph for analysis
-#[derive(Debug, Clone)]
+///
+/// Serializes canonically; equality and hashing follow the canonical form
+/// (see [`canonical`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphData {
     /// Graph identifier
     pub graph_id: uuid::Uuid,
     
     /// Nodes in the graph
+    #[serde(serialize_with = "canonical::sorted_nodes")]
     pub nodes: Vec<NodeData>,
     
     /// Edges in the graph
+    #[serde(serialize_with = "canonical::sorted_edges")]
     pub edges: Vec<EdgeData>,
     
     /// Graph metadata
+    #[serde(serialize_with = "canonical::sorted_map")]
     pub metadata: HashMap<String, Value>,
 }
 
 /// Data structure representing a node
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeData {
     pub id: String,
     pub node_type: String,
     pub label: String,
+    #[serde(serialize_with = "canonical::sorted_map")]
     pub properties: HashMap<String, Value>,
     pub position: Option<(f32, f32, f32)>,
 }
 
 /// Data structure representing an edge
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeData {
     pub id: String,
     pub source: String,
     pub target: String,
     pub edge_type: String,
+    #[serde(serialize_with = "canonical::sorted_map")]
     pub properties: HashMap<String, Value>,
 }
 
@@ -236,4 +299,53 @@ pub fn graph_to_prompt(graph: &GraphData) -> String {
     }
     
     prompt
-} 
\ No newline at end of file
+} 
+
+/// Expose the fleet relationship graph for visualization and analysis
+impl From<&crate::read_model::FleetGraph> for GraphData {
+    fn from(fleet: &crate::read_model::FleetGraph) -> Self {
+        let nodes = fleet
+            .nodes
+            .iter()
+            .map(|node| NodeData {
+                id: node.agent_id.to_string(),
+                node_type: "agent".to_string(),
+                label: node.name.clone(),
+                properties: HashMap::from([
+                    ("person_id".to_string(), json!(node.person_id.to_string())),
+                    ("decommissioned".to_string(), json!(node.decommissioned)),
+                ]),
+                position: None,
+            })
+            .collect();
+
+        let edges = fleet
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge)| {
+                let mut properties = HashMap::new();
+                if edge.count > 0 {
+                    properties.insert("count".to_string(), json!(edge.count));
+                }
+                if !edge.shared.is_empty() {
+                    properties.insert("shared".to_string(), json!(edge.shared));
+                }
+                EdgeData {
+                    id: format!("{}-{}", edge.kind.as_str(), i),
+                    source: edge.source.to_string(),
+                    target: edge.target.to_string(),
+                    edge_type: edge.kind.as_str().to_string(),
+                    properties,
+                }
+            })
+            .collect();
+
+        GraphData {
+            graph_id: uuid::Uuid::now_v7(),
+            nodes,
+            edges,
+            metadata: HashMap::from([("source".to_string(), json!("agent_fleet"))]),
+        }
+    }
+}
@@ -68,7 +68,15 @@ impl OllamaProvider {
     }
     
     /// Create a prompt for graph analysis
-    fn create_analysis_prompt(&self, graph_data: &GraphData, analysis_type: &AnalysisCapability) -> String {
+    ///
+    /// A `comparison` parameter (see `compare_graphs`) is appended so the
+    /// model comments on the changes rather than the graph alone.
+    fn create_analysis_prompt(
+        &self,
+        graph_data: &GraphData,
+        analysis_type: &AnalysisCapability,
+        parameters: &HashMap<String, Value>,
+    ) -> String {
         let analysis_instruction = match analysis_type {
             AnalysisCapability::GraphAnalysis => {
                 "Analyze this graph structure and identify patterns, issues, and optimization opportunities."
@@ -88,9 +96,15 @@ impl OllamaProvider {
             AnalysisCapability::Custom(prompt) => prompt.as_str(),
         };
         
-        format!("{}\n\nGraph Structure:\n{}\n\nProvide your analysis in JSON format with 'findings' and 'recommendations' arrays. Each finding should have: id, type, description, severity (0-1), related_elements, and evidence. Each recommendation should have: id, type, description, expected_impact, effort (low/medium/high), and actions.",
+        let comparison = parameters
+            .get("comparison")
+            .map(|comparison| format!("\n\nChanges from the previous revision:\n{comparison}"))
+            .unwrap_or_default();
+
+        format!("{}\n\nGraph Structure:\n{}{}\n\nProvide your analysis in JSON format with 'findings' and 'recommendations' arrays. Each finding should have: id, type, description, severity (0-1), related_elements, and evidence. Each recommendation should have: id, type, description, expected_impact, effort (low/medium/high), and actions.",
             analysis_instruction,
-            graph_to_prompt(graph_data)
+            graph_to_prompt(graph_data),
+            comparison
         )
     }
     
@@ -361,7 +375,7 @@ impl GraphAnalysisProvider for OllamaProvider {
         // Check health first
         self.check_health().await?;
         
-        let prompt = self.create_analysis_prompt(&graph_data, &analysis_type);
+        let prompt = self.create_analysis_prompt(&graph_data, &analysis_type, &parameters);
         
         let request = GenerateRequest {
             model: self.model.clone(),
@@ -393,7 +407,12 @@ impl GraphAnalysisProvider for OllamaProvider {
         let generate_response: GenerateResponse = response.json().await
             .map_err(|e| AIProviderError::InvalidResponse(e.to_string()))?;
         
-        self.parse_analysis_response(&generate_response.response, analysis_type)
+        let optimize = matches!(analysis_type, AnalysisCapability::WorkflowOptimization);
+        let mut result = self.parse_analysis_response(&generate_response.response, analysis_type)?;
+        if optimize {
+            attach_workflow_optimization(&mut result, &graph_data);
+        }
+        Ok(result)
     }
     
     async fn suggest_transformations(
@@ -546,4 +565,25 @@ mod tests {
         assert!(provider.supports_capability(&AnalysisCapability::WorkflowOptimization));
         assert!(provider.supports_capability(&AnalysisCapability::Custom("test".to_string())));
     }
+
+    #[test]
+    fn test_comparison_reaches_the_prompt() {
+        let provider = OllamaProvider::new("llama2".to_string(), None).unwrap();
+        let graph = GraphData {
+            graph_id: uuid::Uuid::new_v4(),
+            nodes: vec![],
+            edges: vec![],
+            metadata: HashMap::new(),
+        };
+        let capability = AnalysisCapability::WorkflowOptimization;
+
+        let plain = provider.create_analysis_prompt(&graph, &capability, &HashMap::new());
+        assert!(!plain.contains("previous revision"));
+
+        let comparison = GraphComparison::structural(&graph, &graph);
+        let parameters = HashMap::from([("comparison".to_string(), json!(comparison))]);
+        let prompt = provider.create_analysis_prompt(&graph, &capability, &parameters);
+        assert!(prompt.contains("Changes from the previous revision"));
+        assert!(prompt.contains("\"diff\""));
+    }
 } 
\ No newline at end of file
@@ -276,7 +276,12 @@ impl GraphAnalysisProvider for OpenAIProvider {
         let chat_response: ChatResponse = response.json().await
             .map_err(|e| AIProviderError::InvalidResponse(e.to_string()))?;
         
-        self.parse_analysis_response(&chat_response, analysis_type)
+        let optimize = matches!(analysis_type, AnalysisCapability::WorkflowOptimization);
+        let mut result = self.parse_analysis_response(&chat_response, analysis_type)?;
+        if optimize {
+            attach_workflow_optimization(&mut result, &graph_data);
+        }
+        Ok(result)
     }
     
     async fn suggest_transformations(
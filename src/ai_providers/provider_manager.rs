@@ -102,9 +102,14 @@ impl AIProviderManager {
         Ok(())
     }
     
-    /// Initialize providers from environment variables
-    pub async fn initialize_from_env(&mut self) -> Result<(), AIProviderError> {
-        info!("Initializing AI providers from environment");
+    /// Initialize providers whose API keys `secrets` holds
+    ///
+    /// Models and the Ollama host come from environment variables.
+    pub async fn initialize_from_secrets(
+        &mut self,
+        secrets: &dyn crate::ports::SecretsProvider,
+    ) -> Result<(), AIProviderError> {
+        info!("Initializing AI providers from {} secrets", secrets.provider_name());
         
         // Always register mock provider
         self.register_provider(
@@ -114,8 +119,9 @@ impl AIProviderManager {
         )?;
         
         // Try to initialize OpenAI
-        if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
+        if let Ok(api_key) = secrets.get("OPENAI_API_KEY").await {
             info!("Found OpenAI API key, initializing provider");
+            let api_key = api_key.expose().to_string();
             let model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4".to_string());
             
             match openai::OpenAIProvider::new(api_key, model) {
@@ -133,8 +139,9 @@ impl AIProviderManager {
         }
         
         // Try to initialize Anthropic
-        if let Ok(api_key) = std::env::var("ANTHROPIC_API_KEY") {
+        if let Ok(api_key) = secrets.get("ANTHROPIC_API_KEY").await {
             info!("Found Anthropic API key, initializing provider");
+            let api_key = api_key.expose().to_string();
             let model = std::env::var("ANTHROPIC_MODEL").unwrap_or_else(|_| "claude-3-opus-20240229".to_string());
             
             match anthropic::AnthropicProvider::new(api_key, model) {
@@ -310,7 +317,8 @@ mod tests {
         let mut manager = AIProviderManager::new(SelectionStrategy::Default);
         
         // Should always have mock provider
-        manager.initialize_from_env().await.unwrap();
+        let secrets = crate::ports::EnvSecretsProvider::new();
+        manager.initialize_from_secrets(&secrets).await.unwrap();
         
         let providers = manager.get_available_providers();
         assert!(!providers.is_empty());
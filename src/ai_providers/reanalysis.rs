@@ -0,0 +1,374 @@
+//! Scheduled re-analysis of evolving graphs
+//!
+//! A graph registered with [`ReanalysisScheduler`] is analyzed again every
+//! interval, but only when its content has changed since the last run. When
+//! the new insights differ enough from the previous ones the scheduler
+//! reports an [`AnalysisChangedSignificantly`] event, which
+//! [`spawn`](ReanalysisScheduler::spawn) publishes on NATS:
+//!
+//! ```text
+//! update(graph) ──> latest revision (per graph)
+//!                         │
+//! spawn: every tick ──> run_due(clock_now())
+//!                         │ interval elapsed?          no ──> wait
+//!                         │ content_hash changed?      no ──> skip, reschedule
+//!                         v
+//!                 analyze_graph(latest, capability)
+//!                         │ insights added / resolved, confidence delta
+//!                         │ over thresholds?           no ──> keep as baseline
+//!                         v
+//!                 AnalysisChangedSignificantly ──> {prefix}.{graph_id}.changed
+//! ```
+//!
+//! Insights are matched by category and description, case and surrounding
+//! whitespace ignored. A failed analysis is logged and retried on the next
+//! due tick.
+
+use super::*;
+use crate::value_objects::{clock_now, Insight};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How different two analyses must be to be reported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignificanceThresholds {
+    /// Insights added plus insights resolved
+    pub min_insight_changes: usize,
+
+    /// Absolute change in the analysis confidence score
+    pub min_confidence_delta: f64,
+}
+
+impl Default for SignificanceThresholds {
+    fn default() -> Self {
+        Self {
+            min_insight_changes: 2,
+            min_confidence_delta: 0.2,
+        }
+    }
+}
+
+/// A re-analysis whose insights moved past the thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisChangedSignificantly {
+    pub graph_id: Uuid,
+    pub capability: AnalysisCapability,
+
+    /// Content hash of the revision analyzed before
+    pub previous_hash: String,
+
+    /// Content hash of the revision analyzed now
+    pub current_hash: String,
+
+    /// Insights the new analysis has that the previous one did not
+    pub added_insights: Vec<Insight>,
+
+    /// Insights of the previous analysis that are gone
+    pub resolved_insights: Vec<Insight>,
+
+    /// New confidence score minus the previous one
+    pub confidence_delta: f64,
+
+    pub analyzed_at: DateTime<Utc>,
+}
+
+struct Registration {
+    capability: AnalysisCapability,
+    interval: Duration,
+    thresholds: SignificanceThresholds,
+    latest: GraphData,
+    next_run: DateTime<Utc>,
+    /// Content hash and result of the last successful analysis
+    analyzed: Option<(String, AnalysisResult)>,
+}
+
+/// Periodically re-analyzes registered graphs
+pub struct ReanalysisScheduler {
+    provider: Arc<dyn GraphAnalysisProvider>,
+    graphs: Mutex<HashMap<Uuid, Registration>>,
+}
+
+impl ReanalysisScheduler {
+    /// Analyze with `provider`
+    pub fn new(provider: Arc<dyn GraphAnalysisProvider>) -> Self {
+        Self {
+            provider,
+            graphs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Re-analyze `graph` every `interval`, first on the next tick
+    pub fn register(
+        &self,
+        graph: GraphData,
+        capability: AnalysisCapability,
+        interval: Duration,
+        thresholds: SignificanceThresholds,
+    ) {
+        let mut graphs = self.graphs.lock().unwrap_or_else(|e| e.into_inner());
+        graphs.insert(
+            graph.graph_id,
+            Registration {
+                capability,
+                interval,
+                thresholds,
+                latest: graph,
+                next_run: DateTime::<Utc>::MIN_UTC,
+                analyzed: None,
+            },
+        );
+    }
+
+    /// Stop re-analyzing a graph
+    pub fn unregister(&self, graph_id: Uuid) -> bool {
+        let mut graphs = self.graphs.lock().unwrap_or_else(|e| e.into_inner());
+        graphs.remove(&graph_id).is_some()
+    }
+
+    /// Replace the revision analyzed on the next due run; false if the graph
+    /// is not registered
+    pub fn update(&self, graph: GraphData) -> bool {
+        let mut graphs = self.graphs.lock().unwrap_or_else(|e| e.into_inner());
+        match graphs.get_mut(&graph.graph_id) {
+            Some(registration) => {
+                registration.latest = graph;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Run [`run_due`](Self::run_due) every `tick`, publishing each
+    /// significant change as JSON on `{subject_prefix}.{graph_id}.changed`
+    ///
+    /// A failed publish is logged; the change is not reported again.
+    pub fn spawn(
+        self: Arc<Self>,
+        tick: Duration,
+        client: async_nats::Client,
+        subject_prefix: impl Into<String>,
+    ) -> tokio::task::JoinHandle<()> {
+        let subject_prefix = subject_prefix.into();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(tick);
+            loop {
+                ticker.tick().await;
+                for change in self.run_due(clock_now()).await {
+                    let graph_id = change.graph_id;
+                    let subject = format!("{}.{}.changed", subject_prefix, graph_id);
+                    let published = match serde_json::to_vec(&change) {
+                        Ok(payload) => client
+                            .publish(subject, payload.into())
+                            .await
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    if let Err(e) = published {
+                        tracing::warn!(%graph_id, error = %e, "Failed to publish analysis change");
+                    }
+                }
+            }
+        })
+    }
+
+    /// Analyze every due graph whose content changed, returning the
+    /// significant changes
+    pub async fn run_due(&self, now: DateTime<Utc>) -> Vec<AnalysisChangedSignificantly> {
+        let due: Vec<(GraphData, AnalysisCapability)> = {
+            let mut graphs = self.graphs.lock().unwrap_or_else(|e| e.into_inner());
+            graphs
+                .values_mut()
+                .filter(|registration| registration.next_run <= now)
+                .filter_map(|registration| {
+                    registration.next_run = chrono::Duration::from_std(registration.interval)
+                        .ok()
+                        .and_then(|interval| now.checked_add_signed(interval))
+                        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                    let hash = registration.latest.content_hash();
+                    let unchanged = matches!(&registration.analyzed, Some((h, _)) if *h == hash);
+                    (!unchanged)
+                        .then(|| (registration.latest.clone(), registration.capability.clone()))
+                })
+                .collect()
+        };
+
+        let mut changes = Vec::new();
+        for (graph, capability) in due {
+            let graph_id = graph.graph_id;
+            let hash = graph.content_hash();
+            let result = match self
+                .provider
+                .analyze_graph(graph, capability.clone(), HashMap::new())
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!(%graph_id, error = %e, "Scheduled re-analysis failed");
+                    continue;
+                }
+            };
+
+            let mut graphs = self.graphs.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(registration) = graphs.get_mut(&graph_id) else {
+                continue;
+            };
+            if let Some((previous_hash, previous)) = registration.analyzed.take() {
+                let change = AnalysisChangedSignificantly {
+                    graph_id,
+                    capability,
+                    previous_hash,
+                    current_hash: hash.clone(),
+                    added_insights: missing_from(&result.insights, &previous.insights),
+                    resolved_insights: missing_from(&previous.insights, &result.insights),
+                    confidence_delta: result.confidence_score - previous.confidence_score,
+                    analyzed_at: now,
+                };
+                let thresholds = registration.thresholds;
+                if change.added_insights.len() + change.resolved_insights.len()
+                    >= thresholds.min_insight_changes
+                    || change.confidence_delta.abs() >= thresholds.min_confidence_delta
+                {
+                    changes.push(change);
+                }
+            }
+            registration.analyzed = Some((hash, result));
+        }
+        changes
+    }
+}
+
+fn insight_key(insight: &Insight) -> (String, String) {
+    (
+        insight.category.trim().to_lowercase(),
+        insight.description.trim().to_lowercase(),
+    )
+}
+
+/// Insights in `these` with no match in `others`
+fn missing_from(these: &[Insight], others: &[Insight]) -> Vec<Insight> {
+    let known: HashSet<(String, String)> = others.iter().map(insight_key).collect();
+    these
+        .iter()
+        .filter(|insight| !known.contains(&insight_key(insight)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::Impact;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Reports one insight per node, counting calls
+    struct NodeInsights(AtomicUsize);
+
+    #[async_trait]
+    impl GraphAnalysisProvider for NodeInsights {
+        async fn analyze_graph(
+            &self,
+            graph_data: GraphData,
+            _analysis_type: AnalysisCapability,
+            _parameters: HashMap<String, Value>,
+        ) -> AIProviderResult<AnalysisResult> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(AnalysisResult {
+                id: Uuid::new_v4(),
+                confidence_score: 0.8,
+                summary: String::new(),
+                recommendations: vec![],
+                insights: graph_data
+                    .nodes
+                    .iter()
+                    .map(|node| Insight {
+                        id: Uuid::new_v4(),
+                        category: "step".to_string(),
+                        description: format!("{} is slow", node.label),
+                        evidence: vec![],
+                        confidence: 0.8,
+                        impact: Impact::Medium,
+                    })
+                    .collect(),
+                metadata: HashMap::new(),
+                timestamp: std::time::SystemTime::now(),
+            })
+        }
+
+        async fn suggest_transformations(
+            &self,
+            _graph_data: GraphData,
+            _optimization_goals: Vec<String>,
+            _constraints: HashMap<String, Value>,
+        ) -> AIProviderResult<Vec<TransformationSuggestion>> {
+            Ok(vec![])
+        }
+
+        fn supports_capability(&self, _capability: &AnalysisCapability) -> bool {
+            true
+        }
+
+        fn get_metadata(&self) -> ProviderMetadata {
+            ProviderMetadata {
+                name: "node-insights".to_string(),
+                version: "test".to_string(),
+                model: "test".to_string(),
+                capabilities: vec![],
+                rate_limits: None,
+            }
+        }
+    }
+
+    fn graph(labels: &[&str]) -> GraphData {
+        GraphData {
+            graph_id: Uuid::nil(),
+            nodes: labels
+                .iter()
+                .map(|label| NodeData {
+                    id: label.to_string(),
+                    node_type: "task".to_string(),
+                    label: label.to_string(),
+                    properties: HashMap::new(),
+                    position: None,
+                })
+                .collect(),
+            edges: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reanalyzes_changed_graphs_and_reports_significant_changes() {
+        let provider = Arc::new(NodeInsights(AtomicUsize::new(0)));
+        let scheduler = ReanalysisScheduler::new(provider.clone());
+        scheduler.register(
+            graph(&["a", "b"]),
+            AnalysisCapability::WorkflowOptimization,
+            Duration::from_secs(60),
+            SignificanceThresholds::default(),
+        );
+        let t0 = Utc::now();
+        let minutes = |m: i64| t0 + chrono::Duration::minutes(m);
+
+        // First run sets the baseline; unchanged content is skipped
+        assert!(scheduler.run_due(t0).await.is_empty());
+        assert!(scheduler.run_due(minutes(2)).await.is_empty());
+        assert_eq!(provider.0.load(Ordering::SeqCst), 1);
+
+        // Not due yet, then one insight added: below the threshold
+        scheduler.update(graph(&["a", "b", "c"]));
+        assert!(scheduler.run_due(minutes(2)).await.is_empty());
+        assert!(scheduler.run_due(minutes(4)).await.is_empty());
+
+        // Two insights added and two resolved
+        scheduler.update(graph(&["a", "d", "e"]));
+        let changes = scheduler.run_due(minutes(6)).await;
+        assert_eq!(provider.0.load(Ordering::SeqCst), 3);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].added_insights.len(), 2);
+        assert_eq!(changes[0].resolved_insights.len(), 2);
+        assert_eq!(changes[0].resolved_insights[0].description, "b is slow");
+    }
+}
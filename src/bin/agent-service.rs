@@ -9,17 +9,186 @@
 //! - Streaming message responses via AgentMessageService
 //! - Capability-based provider routing
 //! - Graceful shutdown
+//! - NATS micro service registration (`nats micro ls` shows `cim-agent`)
 //!
 //! # Environment Variables
 //!
 //! - `NATS_URL` - NATS server URL (default: nats://localhost:4222)
+//! - `NATS_NKEY_SEED` / `NATS_CREDS_FILE` - NATS authentication
+//! - `NATS_TLS_CA`, `NATS_TLS_CERT`, `NATS_TLS_KEY` - TLS / mutual TLS
+//! - `NATS_MAX_RECONNECTS` - Reconnect attempts before giving up (default: unlimited)
 //! - `STREAM_NAME` - JetStream stream name (default: AGENT_EVENTS)
+//! - `STREAM_PROVISIONING` - `check` or `apply` the events/commands/chunks stream layout
+//!   instead of a single `STREAM_NAME` stream (unset: single stream). Either way,
+//!   an events stream left with a max age is cleared or refused at startup
+//! - `STREAM_PREFIX`, `STREAM_REPLICAS` - Provisioned stream names and replicas
+//!   (default: AGENT, 1)
 //! - `LOG_LEVEL` - Logging level (default: info)
 //! - `SNAPSHOT_FREQUENCY` - How often to create snapshots (default: 100)
+//! - `SNAPSHOT_BUCKET` - NATS KV bucket keeping agent snapshots across restarts
+//!   (unset: in-memory)
+//! - `AGGREGATE_CACHE_CAPACITY` - Agents kept in the repository LRU cache (default: 1000, 0 disables)
 //! - `AGENT_NAME` - Agent name (REQUIRED for conversations)
 //! - `AGENT_ID` - Agent UUID (REQUIRED for unified architecture)
 //! - `CAPABILITY_CLUSTER` - Agent capability cluster (REQUIRED for unified architecture)
 //! - `ENABLE_UNIFIED_SUBJECTS` - Enable dual publishing (default: false, for migration)
+//! - `SUBJECT_COMPATIBILITY` - `legacy`, `dual` or `unified`: which command subject layouts
+//!   to subscribe to; dual skips republished copies (default: unified)
+//! - `FIRST_TOKEN_SLO_MS` - First-token latency SLO threshold (default: 2000)
+//! - `FIRST_TOKEN_SLO_OBJECTIVE` - Fraction of requests that must meet it (default: 0.95)
+//! - `FIRST_TOKEN_SLO_BURN_RATE` - Burn rate that raises `SloViolated` (default: 2.0)
+//! - `FIRST_TOKEN_SLO_WINDOW` - Requests per evaluation window (default: 100)
+//! - `READ_MODEL_BUCKET` - NATS KV bucket prefix for a read model that persists across
+//!   restarts (unset: in-memory, rebuilt on start)
+//! - `READ_MODEL_HISTORY_DAYS` - Days of agent revisions the KV read model keeps for as-of
+//!   queries (default: 365)
+//! - `READ_MODEL_DATABASE_URL` - PostgreSQL read model, migrated on start; takes precedence
+//!   over `READ_MODEL_BUCKET` (requires the `sql` feature)
+//! - `READ_MODEL_MAX_WAIT_MS` - Cap on how long a `min_version` query may wait (default: 2000)
+//! - `GRAPHQL_SUBJECT` - Serve the read model's GraphQL schema over request-reply on this
+//!   subject, with subscriptions fed by projected events (requires the `graphql` feature)
+//! - `AGENT_REGION` - Region stamped on published events (unset: single region)
+//! - `AGENT_REGION_ROLE` - `active` or `passive`; passive regions reject writes (default: active)
+//! - `AGENT_SIGNING_SEED` - nkey seed used to sign published events (default: fresh keypair)
+//! - `EVENT_TRUSTED_KEYS` - Comma-separated key IDs; when set, unsigned or untrusted
+//!   events are not projected (this service's signing key is always trusted)
+//! - `DIGEST_ENABLED` - Publish `DailyDigestReady` after each UTC midnight (default: false;
+//!   enable on one instance only)
+//! - `CONVERSATION_TTL_SECS` - Archive conversations idle this long (unset: keep forever)
+//! - `LEGAL_HOLD_BUCKET` - NATS KV bucket indexing agents under legal hold
+//!   (default: AGENT_LEGAL_HOLDS)
+//! - `EVENT_RETENTION_DAYS` - Purge agents' events older than this, except under legal hold
+//!   (default: 365; 0 keeps events forever)
+//! - `CONVERSATION_STREAM` - JetStream stream holding conversation events
+//!   (default: AGENT_CONVERSATIONS)
+//! - `CONVERSATION_IDLE_SECS` - Close conversations without activity this long (default: 1800)
+//! - `CONVERSATION_IDLE_CHECK_SECS` - Seconds between idle and wall-clock budget checks
+//!   (default: 30)
+//! - `EXTERNAL_AGENTS` - YAML list of external agents (`agent_id`, `system`, and the
+//!   `secret` naming the environment variable with their webhook key) whose signed
+//!   deliveries on `{domain}.ingress.external.{agent_id}` are accepted (unset: none)
+//! - `DIGEST_PROMPT_USD_PER_1K`, `DIGEST_COMPLETION_USD_PER_1K` - Token prices for digest
+//!   and cost-tag spend estimates (unset: no estimate)
+//! - `SPEND_ALERT_USD_PER_DAY` - Publish `SpendThresholdCrossed` when an agent's estimated
+//!   spend for a UTC day reaches this; needs token prices (unset: no alert; enable on one
+//!   instance only)
+//! - `DRIFT_MANIFEST` - Fleet manifest to check live agents against (unset: no drift checks)
+//! - `DRIFT_CHECK_SECS` - Seconds between drift checks (default: 300)
+//! - `DRIFT_AUTO_REMEDIATE` - Dispatch the commands that undo drift (default: false)
+//! - `TOOL_CATALOG` - Path to a JSON file with an array of versioned tool definitions
+//!   loaded into the tool catalog at startup; `describe` queries report the schema of each
+//!   tool's newest supported version in the catalog (unset: the catalog holds only versions
+//!   published through the `tools` endpoint; tools not in it are described by name only)
+//! - `TOOL_CATALOG_BUCKET` - NATS KV bucket holding tool versions published or deprecated
+//!   through the `tools` endpoint (default: AGENT_TOOL_CATALOG)
+//! - `OWNER_NOTIFICATIONS` - Send person-domain notifications for owners' agent events
+//!   (default: false; enable on one instance only unless `DEDUPE_BUCKET` is set)
+//! - `OWNER_NOTIFICATION_PREFS` - YAML map of person ID to `events:` list overriding
+//!   the default alerts (suspended, decommissioned, slo_violated,
+//!   permission_escalation_requested, change_rate_exceeded)
+//! - `DEDUPE_BUCKET` - NATS KV bucket shared by bridges so each event fires once, and
+//!   each command `idempotency_key` runs once, across instances (unset: per-instance dedupe)
+//! - `DEDUPE_TTL_SECS` - How long handled events and idempotency keys are remembered
+//!   (default: 600)
+//! - `LONG_CONTEXT_PROVIDER`, `LONG_CONTEXT_MODEL` - Model that answers when a context
+//!   outgrows the agent's model (unset: oversized contexts are rejected)
+//! - `LONG_CONTEXT_MAX_TOKENS` - Context window of that model (default: 128000)
+//! - `REASONING_TRACES` - `off`, `redacted` or `full`: publish `ReasoningTraceRecorded`
+//!   for each answered message, with or without step details (default: off)
+//! - `SELF_TEST` - Check NATS, streams, providers and the long-context model before
+//!   subscribing, and exit on a hard failure (default: true)
+//! - `SELF_TEST_TIMEOUT_MS` - Time each self-test check may take (default: 10000)
+//! - `BLOB_BUCKET` - Object store bucket holding uploaded message content (default: AGENT_BLOBS)
+//! - `BLOB_CHUNK_BYTES` - Size of each chunk blobs are stored in (default: 131072)
+//! - `ARTIFACT_GC_SECS` - Seconds between sweeps deleting blobs no stored event or
+//!   conversation event references (unset: never; enable on one instance only)
+//! - `ARTIFACT_GC_GRACE_SECS` - Age below which unreferenced blobs are kept (default: 86400)
+//! - `MAX_INLINE_MESSAGE_BYTES` - Largest content accepted inside a command; larger content
+//!   must be uploaded (default: 262144)
+//! - `MAX_MESSAGE_CONTENT_BYTES` - Largest message content accepted at all (default: 16777216)
+//! - `TOXICITY_FLAG_THRESHOLD` - Toxicity score at which messages are listed for moderators
+//!   (default: 0.5)
+//! - `TOXICITY_BLOCK_THRESHOLD` - Toxicity score at which messages are refused (default: 0.9;
+//!   agents with `guardrails_strict` refuse at the flag threshold)
+//! - `PRINCIPAL_KEYS` - YAML list of the nkeys commands may be signed with, each with
+//!   the `class` and `name` it acts as (unset: none; unsigned commands act as `agent`)
+//! - `PRINCIPAL_PERMISSIONS` - YAML permission sets for `owner`, `operator` and `agent`
+//!   (unset: the built-in defaults)
+//! - `ESCALATION_TIMEOUT_SECS` - How long a command lacking a permission is held for the
+//!   owner's approval (default: 300; 0 refuses such commands outright)
+//! - `ESCALATION_GRANT_SECS` - How long an approved permission lasts (default: 900)
+//! - `CHANGE_RATE_GUARDRAILS` - Block or hold bursts of configuration changes and
+//!   permission grants, per agent and per actor, and publish `ChangeRateExceeded`
+//!   (default: true; counts are per instance)
+//! - `CHANGE_RATE_WINDOW_SECS` - Window the default change limits count over (default: 3600)
+//! - `CHANGE_RATE_LIMITS` - YAML list of change rate limits replacing the defaults (unset:
+//!   10 configuration changes per agent and 30 per actor held for approval, 3 permission
+//!   grants per agent and 10 per actor refused)
+//! - `RETRY_BUCKET` - NATS KV bucket holding pending retries and quarantined messages
+//!   (default: AGENT_RETRIES)
+//! - `MESSAGE_RETRY_BUDGET` - Retries a failed message makes before it is quarantined,
+//!   unless it sets its own budget (default: 4)
+//! - `MESSAGE_RETRY_DRAIN` - Send due retries from this instance (default: true; retries
+//!   are leased, so any number of instances may drain)
+//! - `CAPABILITY_PROBE_SECS` - Seconds between probes of each operational agent's
+//!   advertised capabilities; failures are withdrawn (unset: no probes, which cost tokens)
+//! - `ANOMALY_DETECTION` - Publish `AnomalyDetected` when an agent's error rate, latency or
+//!   token usage strays from its baseline (default: false; enable on one instance only)
+//! - `ANOMALY_WINDOW`, `ANOMALY_Z_THRESHOLD` - Responses per window and the standard
+//!   deviations that count as anomalous (default: 20, 3.0)
+//! - `LOG_FORWARDING` - Publish logs written while handling an agent's commands as
+//!   `LogBatch`es on `{domain}.logs.agent.{agent_id}` (default: false)
+//! - `LOG_FORWARD_LEVEL`, `LOG_FORWARD_SECS` - Least severe level forwarded and seconds
+//!   between batches (default: INFO, 5)
+//! - `AUTOMATION_RULES` - YAML list of automation rules loaded on start (unset: none;
+//!   evaluate rules on one instance only, or each instance dispatches their commands)
+//! - `AUTOMATION_RULES_BUCKET` - NATS KV bucket the managed automation rules are kept in
+//!   (default: AGENT_AUTOMATION_RULES)
+//! - `SHADOW_EVALUATION_SECS` - Seconds between comparisons of shadow agents' answers
+//!   with their primaries' (default: 300; needs a registered embedding provider)
+//! - `COMPARISON_BUCKET` - NATS KV bucket holding comparison reports
+//!   (default: AGENT_COMPARISONS)
+//!
+//! # NATS Service
+//!
+//! Commands are also served by the `cim-agent` NATS micro service on
+//! `{domain}.services.agent.commands`. Service endpoints use a queue group,
+//! so running several instances load-balances requests across them, and
+//! `$SRV.PING`, `$SRV.INFO` and `$SRV.STATS` discovery works out of the box.
+//! Failed commands still reply `{"status":"error",...}` and are counted in
+//! the endpoint's error stats. `$SRV.STATS` also carries a `metrics` snapshot of
+//! event counts, response latency and token histograms, in-flight streams and
+//! aggregate cache counters.
+//!
+//! A command sent with `Cim-Dry-Run: true` is only simulated: the reply lists
+//! the events it would produce and the agent after them, and nothing is saved
+//! or published. Messages are checked and scored but not answered.
+//!
+//! Conversations are driven on `{domain}.services.agent.conversations` with
+//! a signed `ConversationCommand`; a person acting in it must be the person
+//! the signing key is registered to, and a transfer is checked against the
+//! target agent first. Messages sent with the `conversation_id` of a started
+//! conversation are only answered by participating agents with budget left,
+//! and each answer is posted with its token usage. `GetUnreadCounts` and
+//! `GetReceipts` queries read the receipts.
+//!
+//! Automation rules are managed on `{domain}.services.agent.rules` with
+//! `{"op":"put","rule":{...}}`, `{"op":"remove","name":...}` or
+//! `{"op":"list"}`; each replies with the rules in force. Rules are kept in
+//! `AUTOMATION_RULES_BUCKET`, which every instance follows. A rule put here
+//! acts as the class of the key that signed the request, so it can do no
+//! more than its author.
+//!
+//! Batches of prompts are run on `{domain}.services.agent.batches` with a
+//! `BatchRequest` naming the agent and the JSON Lines prompts uploaded to the
+//! blob store; the reply carries the batch id, and `BatchProgress` and
+//! `BatchCompleted` are published as the run goes on.
+//!
+//! Shadow agents are managed on `{domain}.services.agent.shadows` with
+//! `{"op":"start","primary":...,"definition":{...},"traffic_share":0.1}`,
+//! `{"op":"stop","primary":...}` or `{"op":"list"}`; each replies with the
+//! running deployments. A deployment is held by the instance that started
+//! it, which mirrors the messages it handles for the primary.
 //!
 //! # Example
 //!
@@ -31,41 +200,216 @@
 //! ```
 
 use cim_domain_agent::{
-    aggregate::Agent,
+    aggregate::{Agent, Conversation},
     commands::*,
     events::*,
     infrastructure::{
-        AgentRepository, AgentSubjectFactory, InMemorySnapshotStore, NatsEventPublisher,
-        NatsEventStore,
+        command_dedupe_key, command_dry_run, command_envelope, decode_envelope, dedupe_key,
+        decode_envelope_if, is_state_changing, NatsComparisonStore,
+        AgentHost, AgentRepository, AgentSubjectFactory, CompatibilityMode, DomainError,
+        ConversationEventEnvelope, ConversationRepository, NatsConversationEventStore,
+        EventEnvelope,
+        DedupeStore, SubjectMigrator, BlobStore, NatsBlobStore, DEFAULT_BLOB_CHUNK_BYTES,
+        ExternalAgentRegistration, InMemoryWebhookSecrets, WebhookIngress,
+        WEBHOOK_SIGNATURE_HEADER,
+        ArchiveStore, BlobArchiveStore, InMemoryDedupeStore, InMemorySnapshotStore, LogCapture,
+        MetricsRegistry, NatsDedupeStore, NatsRetryStore, NatsSnapshotStore, NotReady,
+        SnapshotStore,
+        EventSigner, EventVerifier, InMemoryKeyRegistry, NatsConnectionBuilder, NatsEventPublisher,
+        NatsEventStore, NatsStreamResumer, ParsedAgentSubject, PrincipalDirectory,
+        ProvisioningConfig, RegionConfig, RegisteredPrincipal,
+        ReplicationFilter, ResourceStatus, StreamPlan, StreamProvisioner, StreamRole, SubjectParser,
     },
     // v0.9 additions for capability-based routing
     adapters::ProviderRegistry,
-    capabilities::ProviderCapabilities,
-    intent::MessageIntent,
-    ports::MockChatAdapter,
-    services::{AgentMessageService, CapabilityRouter},
-    value_objects::{ContextMessage, FinishReason, ProviderType, TokenUsage},
+    capabilities::{CapabilityRequirements, ProviderCapabilities},
+    intent::{MessageIntent, ToolDefinition},
+    ports::{estimate_tokens, ChatError, EnvSecretsProvider, ErrorCategory, MockChatAdapter},
+    read_model::{
+        AgentDescription, AgentGraphProjection, AgentQuery, AgentReadModel, CapabilityIndex,
+        ConsistencyToken, ConversationProjection, CostAttributionProjection, DigestProjection,
+        InMemoryAgentReadModel,
+        KvAgentReadModel, ModerationProjection, PageRequest, ReasoningTraceProjection,
+        TokenPricing, ToolUsageProjection, MAX_PAGE_LIMIT,
+    },
+    services::{
+        answer_negotiation, context_tokens, AgentMessageService, AnomalyDetector, AnomalyPolicy,
+        ArtifactCollector, AutomationRules, CapabilityProber, CapabilityRouter,
+        ChangeRateGuardrails, ConversationRetention, ConversationTransfer, InactivityTimers,
+        DriftDetector, FailedAttempt, FirstTokenLatencyTracker, FirstTokenSlo, FleetManifest,
+        EventRetention, GuardrailVerdict, InFlightStreams, IndicatorSink, LegalHoldBucket,
+        LegalHolds, LiveReferences,
+        LongContextProfile,
+        MessageRetryQueue, ModerationStage, NegotiationRequest, NotificationPreferences,
+        NotifyPerson, OwnerNotifier, PermissionEscalations, ProcessingIndicator,
+        ProcessingIndicators, ResumedCommand, RetentionPolicy, RetryDecision, RetryPolicy,
+        BatchInference, BatchRequest, ResponseDiffer, RoutedStream, RuleBucket, RuleRequest,
+        ShadowDeployment, ShadowDeployments, ShadowRequest, ToolCatalog, ToolCatalogBucket,
+        ToolRequest,
+        DEFAULT_CHANGE_RATE_WINDOW_SECS,
+        DEFAULT_ESCALATION_GRANT_SECS, DEFAULT_ESCALATION_TIMEOUT_SECS, DEFAULT_RETRY_BUDGET,
+    },
+    value_objects::{
+        clock_now, next_id, with_clock, with_id_generator, with_principal, AgentId, AgentPermission,
+        AuthenticatedPrincipal, Clock, ContextMessage, ConversationHandoff, ConversationPermission,
+        FeatureFlags, FinishReason, GenerationParams, IdGenerator, MessageSizeError,
+        MessageSizeLimits, ModelConfig, ParticipantId, PermissionDenied, PersonId, PrincipalClass,
+        PrincipalPermissions, ProviderType, SystemClock, TokenUsage, ToxicityThresholds,
+        TraceContext, UuidV7Generator,
+    },
 };
+use async_nats::service::ServiceExt;
 use futures::StreamExt;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::signal;
-use std::time::Instant;
-use tracing::{error, info, warn};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn, Instrument};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+/// Shared state handed to every command handler
+#[derive(Clone)]
+struct HandlerContext {
+    repository: Arc<AgentRepository>,
+    event_publisher: Arc<NatsEventPublisher>,
+    message_service: Arc<AgentMessageService>,
+    in_flight: InFlightStreams,
+    stream_resumer: Arc<NatsStreamResumer>,
+    latency_tracker: Arc<FirstTokenLatencyTracker>,
+    reasoning_traces: ReasoningTraces,
+    indicators: Arc<dyn IndicatorSink>,
+    blobs: Arc<dyn BlobStore>,
+    size_limits: MessageSizeLimits,
+    moderation: ModerationStage,
+    permissions: Arc<PrincipalPermissions>,
+    principals: Arc<PrincipalDirectory>,
+    escalations: Option<Arc<PermissionEscalations>>,
+    guardrails: Option<Arc<ChangeRateGuardrails>>,
+    retries: Arc<MessageRetryQueue>,
+    conversations: Arc<ConversationRepository>,
+    transfers: Arc<ConversationTransfer>,
+    shadows: Arc<ShadowDeployments>,
+    shadow_copies: tokio::sync::mpsc::Sender<(SendMessage, AuthenticatedPrincipal)>,
+    commands_seen: Arc<dyn DedupeStore>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+/// Whether answered messages publish their reasoning trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReasoningTraces {
+    Off,
+    Redacted,
+    Full,
+}
+
+impl std::str::FromStr for ReasoningTraces {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "false" | "0" => Ok(Self::Off),
+            "redacted" => Ok(Self::Redacted),
+            "full" | "true" | "1" => Ok(Self::Full),
+            other => Err(format!("Unknown reasoning trace mode: {}", other)),
+        }
+    }
+}
+
+/// Result of a command handler: the consistency token for state-changing commands
+type HandlerResult = Result<Option<ConsistencyToken>, Box<dyn std::error::Error + Send + Sync>>;
+
+/// A command held until the agent's owner approves the permission it lacked
+#[derive(Debug)]
+struct EscalationPending {
+    escalation_id: uuid::Uuid,
+    denied: PermissionDenied,
+}
+
+impl std::fmt::Display for EscalationPending {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}; escalation {} awaits the owner's approval",
+            self.denied, self.escalation_id
+        )
+    }
+}
+
+impl std::error::Error for EscalationPending {}
+
+/// A change over a rate limit, refused or held for the owner's approval
+#[derive(Debug)]
+struct ChangeRateRefused {
+    exceeded: ChangeRateExceededEvent,
+    escalation_id: Option<uuid::Uuid>,
+}
+
+impl std::fmt::Display for ChangeRateRefused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} for agent {} is over {}",
+            self.exceeded.permission, self.exceeded.agent_id, self.exceeded.limit
+        )?;
+        match self.escalation_id {
+            Some(escalation_id) => write!(
+                f,
+                "; escalation {} awaits the owner's approval",
+                escalation_id
+            ),
+            None => write!(f, "; refused"),
+        }
+    }
+}
+
+impl std::error::Error for ChangeRateRefused {}
+
+/// A send that reached the provider and failed; the retry queue has it already
+#[derive(Debug)]
+struct AttemptRecorded(String);
+
+impl std::fmt::Display for AttemptRecorded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for AttemptRecorded {}
+
+/// NATS micro service name
+const SERVICE_NAME: &str = "cim-agent";
+
+/// Dedupe consumer under which command idempotency keys are recorded
+const COMMANDS_CONSUMER: &str = "commands";
+
+/// Read an environment variable, falling back to `default` when unset or invalid
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+    // Initialize tracing; logs inside agent spans are also buffered for forwarding
+    let log_capture = env_or("LOG_FORWARDING", false).then(|| {
+        LogCapture::new().with_min_level(env_or("LOG_FORWARD_LEVEL", tracing::Level::INFO))
+    });
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .with(log_capture.clone())
+        .init();
 
     info!("Starting agent service v0.9.2...");
 
     // Connect to NATS
-    let nats_url =
-        std::env::var("NATS_URL").unwrap_or_else(|_| "nats://localhost:4222".to_string());
-    info!("Connecting to NATS at {}", nats_url);
+    let connection = NatsConnectionBuilder::from_env()?.with_name("agent-service");
+    info!("Connecting to NATS at {}", connection.urls());
 
-    let client = async_nats::connect(&nats_url).await?;
+    let client = connection.connect().await?;
     info!("Connected to NATS");
 
     // Create JetStream context
@@ -75,28 +419,112 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let stream_name =
         std::env::var("STREAM_NAME").unwrap_or_else(|_| "AGENT_EVENTS".to_string());
 
-    // Ensure stream exists
-    info!("Ensuring JetStream stream: {}", stream_name);
-    NatsEventStore::ensure_stream(&jetstream, &stream_name).await?;
+    // Provision the full stream layout, or ensure the single legacy stream
+    let provisioning = std::env::var("STREAM_PROVISIONING").unwrap_or_default();
+    let (stream_name, chunk_stream_name) = match provisioning.as_str() {
+        "check" | "apply" => {
+            let config = ProvisioningConfig::default()
+                .with_stream_prefix(env_or("STREAM_PREFIX", "AGENT".to_string()))
+                .with_replicas(env_or("STREAM_REPLICAS", 1));
+            let plan = StreamPlan::new(&AgentSubjectFactory::default(), &config);
+            let names = (
+                plan.stream_name(StreamRole::Events).to_string(),
+                plan.stream_name(StreamRole::Chunks).to_string(),
+            );
+            let provisioner = StreamProvisioner::new(jetstream.clone(), plan);
+            let report = if provisioning == "apply" {
+                provisioner.apply().await?
+            } else {
+                provisioner.validate().await?
+            };
+            for (resource, status) in &report.resources {
+                info!("Stream provisioning: {} {:?}", resource, status);
+            }
+            if !report.is_in_sync() {
+                warn!("JetStream streams differ from the provisioning plan");
+            }
+            // A stream-wide max age would age agents under legal hold out
+            let events_aged = report.resources.iter().any(|(name, status)| {
+                *name == names.0
+                    && matches!(
+                        status,
+                        ResourceStatus::Drifted(drift) | ResourceStatus::Unfixable(drift)
+                            if drift.iter().any(|d| d.field == "max_age")
+                    )
+            });
+            if events_aged {
+                error!("Stream {} has a max age, refusing to start", names.0);
+                return Err(format!("Stream {} ages events out past legal holds", names.0).into());
+            }
+            names
+        }
+        _ => {
+            info!("Ensuring JetStream stream: {}", stream_name);
+            NatsEventStore::ensure_stream(&jetstream, &stream_name).await?;
+            (stream_name.clone(), stream_name)
+        }
+    };
     info!("JetStream stream ready");
 
+    // Region stamping for mirrored multi-region streams
+    let region = RegionConfig::from_env()?;
+    if let Some(region) = &region {
+        info!("Region {} ({})", region.region, region.role);
+    }
+
+    // Every event published by this host is signed
+    let signer = match std::env::var("AGENT_SIGNING_SEED") {
+        Ok(seed) => EventSigner::from_seed(&seed)?,
+        Err(_) => EventSigner::generate(),
+    };
+    info!("Signing events with key {}", signer.key_id());
+
     // Create event store and repository
-    let event_store = Arc::new(NatsEventStore::new(jetstream.clone(), stream_name.clone()));
-    let snapshot_store = Arc::new(InMemorySnapshotStore::new());
+    let mut event_store = NatsEventStore::new(jetstream.clone(), stream_name.clone())
+        .with_signer(signer.clone());
+    if let Some(region) = region.clone() {
+        event_store = event_store.with_region(region);
+    }
+    let event_store = Arc::new(event_store);
+    let snapshot_store: Arc<dyn SnapshotStore> = match std::env::var("SNAPSHOT_BUCKET") {
+        Ok(bucket) => {
+            let kv = NatsSnapshotStore::ensure_bucket(&jetstream, &bucket).await?;
+            info!("Keeping agent snapshots in KV bucket {}", bucket);
+            Arc::new(NatsSnapshotStore::new(kv))
+        }
+        Err(_) => Arc::new(InMemorySnapshotStore::new()),
+    };
 
     let snapshot_frequency = std::env::var("SNAPSHOT_FREQUENCY")
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or(100);
 
-    let repository = Arc::new(AgentRepository::new(
-        event_store.clone(),
-        snapshot_store,
-        snapshot_frequency,
-    ));
+    let cache_capacity: usize = env_or("AGGREGATE_CACHE_CAPACITY", 1_000);
+    let repository = Arc::new(
+        AgentRepository::new(event_store.clone(), snapshot_store, snapshot_frequency)
+            .with_cache(cache_capacity),
+    );
 
     // Create event publisher
-    let event_publisher = Arc::new(NatsEventPublisher::new(jetstream.clone()));
+    let mut event_publisher =
+        NatsEventPublisher::new(jetstream.clone()).with_signer(signer.clone());
+    if let Some(region) = region.clone() {
+        event_publisher = event_publisher.with_region(region);
+    }
+    let event_publisher = Arc::new(event_publisher);
+
+    // Startup self-test; hard failures stop the service before it subscribes
+    let mut self_test = AgentHost::new()
+        .with_check_timeout(Duration::from_millis(env_or("SELF_TEST_TIMEOUT_MS", 10_000)))
+        .with_nats(client.clone())
+        .with_stream(jetstream.clone(), stream_name.clone());
+    if chunk_stream_name != stream_name {
+        self_test = self_test.with_stream(jetstream.clone(), chunk_stream_name.clone());
+    }
+
+    // Replays stored chunks for clients that reconnect mid-response
+    let stream_resumer = Arc::new(NatsStreamResumer::new(jetstream.clone(), chunk_stream_name));
 
     // Create message service with capability routing (v0.9)
     let mut provider_registry = ProviderRegistry::new();
@@ -108,10 +536,183 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Note: Additional providers can be registered here when configured via environment
     // e.g., GenaiAdapter for OpenAI, Anthropic, Ollama with proper API keys
 
+    // Oversized contexts are rerouted to a long-context model when one is configured
+    let long_context_provider = std::env::var("LONG_CONTEXT_PROVIDER").ok().and_then(|p| {
+        serde_json::from_value::<ProviderType>(serde_json::Value::String(p.to_lowercase())).ok()
+    });
+    let long_context = match (long_context_provider, std::env::var("LONG_CONTEXT_MODEL")) {
+        (Some(provider), Ok(model)) => Some(LongContextProfile {
+            config: ModelConfig::new(provider, model),
+            max_context_length: env_or("LONG_CONTEXT_MAX_TOKENS", 128_000u32),
+        }),
+        _ => None,
+    };
+
+    self_test = self_test.with_providers(&provider_registry);
+    if let Some(profile) = &long_context {
+        self_test = self_test.with_required_model(&provider_registry, profile.config.clone());
+    }
+
     let capability_router = CapabilityRouter::new(provider_registry);
-    let message_service = Arc::new(AgentMessageService::new(capability_router));
+    let mut message_service = AgentMessageService::new(capability_router);
+    if let Some(profile) = long_context {
+        info!(
+            "Long-context fallback: {} {} ({} tokens)",
+            profile.config.provider, profile.config.model_name, profile.max_context_length
+        );
+        message_service = message_service.with_long_context_fallback(profile);
+    }
+    let message_service = Arc::new(message_service);
     info!("Message service initialized with {} provider(s)", 1);
 
+    // Track streaming responses so CancelMessage can abort them
+    let in_flight = InFlightStreams::new();
+
+    // Processing indicators are published to conversation status subjects
+    let (indicator_tx, mut indicator_rx) =
+        tokio::sync::mpsc::unbounded_channel::<ProcessingIndicator>();
+
+    // First-token latency SLO
+    let slo = FirstTokenSlo::new(env_or("FIRST_TOKEN_SLO_MS", 2_000))
+        .with_objective(env_or("FIRST_TOKEN_SLO_OBJECTIVE", 0.95))
+        .with_burn_rate_threshold(env_or("FIRST_TOKEN_SLO_BURN_RATE", 2.0));
+    let window: usize = env_or("FIRST_TOKEN_SLO_WINDOW", 100);
+    let slo = slo.with_window(window).with_min_samples(window.min(20));
+    slo.validate()?;
+    info!(
+        "First-token SLO: {}ms at {:.1}% (alert at burn rate {})",
+        slo.threshold_ms,
+        slo.objective * 100.0,
+        slo.burn_rate_threshold
+    );
+    let latency_tracker = Arc::new(FirstTokenLatencyTracker::new(slo));
+
+    // Oversized message content is uploaded to the blob store by clients
+    let blob_bucket = std::env::var("BLOB_BUCKET").unwrap_or_else(|_| "AGENT_BLOBS".to_string());
+    let blob_store = Arc::new(
+        NatsBlobStore::new(NatsBlobStore::ensure_bucket(&jetstream, &blob_bucket).await?)
+            .with_chunk_bytes(env_or("BLOB_CHUNK_BYTES", DEFAULT_BLOB_CHUNK_BYTES).max(1)),
+    );
+    let blobs: Arc<dyn BlobStore> = blob_store.clone();
+    let defaults = MessageSizeLimits::default();
+    let size_limits = MessageSizeLimits {
+        max_inline_bytes: env_or("MAX_INLINE_MESSAGE_BYTES", defaults.max_inline_bytes),
+        max_content_bytes: env_or("MAX_MESSAGE_CONTENT_BYTES", defaults.max_content_bytes),
+    };
+    let defaults = ToxicityThresholds::default();
+    let thresholds = ToxicityThresholds::new(
+        env_or("TOXICITY_FLAG_THRESHOLD", defaults.flag),
+        env_or("TOXICITY_BLOCK_THRESHOLD", defaults.block),
+    );
+    thresholds.validate()?;
+    let moderation = ModerationStage::default().with_thresholds(thresholds);
+    let principals: PrincipalDirectory = match std::env::var("PRINCIPAL_KEYS") {
+        Ok(path) => serde_yaml::from_str::<Vec<RegisteredPrincipal>>(
+            &std::fs::read_to_string(&path)?,
+        )?
+        .into_iter()
+        .collect(),
+        Err(_) => PrincipalDirectory::new(),
+    };
+    info!(
+        "Accepting commands signed by {} key(s); unsigned commands act as {}",
+        principals.len(),
+        PrincipalClass::LEAST_PRIVILEGED
+    );
+    let permissions: PrincipalPermissions = match std::env::var("PRINCIPAL_PERMISSIONS") {
+        Ok(path) => serde_yaml::from_str(&std::fs::read_to_string(&path)?)?,
+        Err(_) => PrincipalPermissions::default(),
+    };
+    let escalation_timeout = env_or("ESCALATION_TIMEOUT_SECS", DEFAULT_ESCALATION_TIMEOUT_SECS);
+    let escalations = (escalation_timeout > 0).then(|| {
+        Arc::new(PermissionEscalations::new(
+            chrono::Duration::seconds(escalation_timeout),
+            chrono::Duration::seconds(env_or(
+                "ESCALATION_GRANT_SECS",
+                DEFAULT_ESCALATION_GRANT_SECS,
+            )),
+        ))
+    });
+    let guardrails = if env_or("CHANGE_RATE_GUARDRAILS", true) {
+        let limits: Vec<ChangeRateLimit> = match std::env::var("CHANGE_RATE_LIMITS") {
+            Ok(path) => serde_yaml::from_str(&std::fs::read_to_string(&path)?)?,
+            Err(_) => ChangeRateGuardrails::default_limits(env_or(
+                "CHANGE_RATE_WINDOW_SECS",
+                DEFAULT_CHANGE_RATE_WINDOW_SECS,
+            )),
+        };
+        info!("Enforcing {} change rate limits", limits.len());
+        let guardrails = ChangeRateGuardrails::new(limits).with_approvals(escalations.is_some());
+        Some(Arc::new(guardrails))
+    } else {
+        None
+    };
+    let retry_bucket =
+        std::env::var("RETRY_BUCKET").unwrap_or_else(|_| "AGENT_RETRIES".to_string());
+    let retry_store = NatsRetryStore::new(
+        NatsRetryStore::ensure_bucket(&jetstream, &retry_bucket).await?,
+    );
+    let retries = MessageRetryQueue::new(Arc::new(retry_store)).with_policy(RetryPolicy {
+        retry_budget: env_or("MESSAGE_RETRY_BUDGET", DEFAULT_RETRY_BUDGET),
+        ..RetryPolicy::default()
+    });
+
+    // Conversations are event sourced on their own stream, shared by all instances
+    let conversation_stream =
+        std::env::var("CONVERSATION_STREAM").unwrap_or_else(|_| "AGENT_CONVERSATIONS".to_string());
+    NatsConversationEventStore::ensure_stream(
+        &jetstream,
+        &conversation_stream,
+        &AgentSubjectFactory::default(),
+    )
+    .await?;
+    let conversations = ConversationRepository::new(Arc::new(NatsConversationEventStore::new(
+        jetstream.clone(),
+        conversation_stream.clone(),
+    )));
+    let transfers = ConversationTransfer::new(CapabilityRequirements::text_chat())
+        .with_blob_store(blobs.clone());
+
+    // Shadow agents answer copies of their primaries' messages, sent on by a dispatcher
+    let shadows = Arc::new(ShadowDeployments::new());
+    let (shadow_tx, mut shadow_rx) = tokio::sync::mpsc::channel(1_024);
+
+    // Redelivered events, and retried commands, are recognized and skipped
+    let dedupe_ttl = Duration::from_secs(env_or("DEDUPE_TTL_SECS", 600));
+    let projection_dedupe = InMemoryDedupeStore::new().with_ttl(dedupe_ttl);
+    let bridge_dedupe: Arc<dyn DedupeStore> = match std::env::var("DEDUPE_BUCKET") {
+        Ok(bucket) => Arc::new(NatsDedupeStore::new(
+            NatsDedupeStore::ensure_bucket(&jetstream, &bucket, dedupe_ttl).await?,
+        )),
+        Err(_) => Arc::new(projection_dedupe.clone()),
+    };
+
+    let ctx = HandlerContext {
+        repository,
+        event_publisher,
+        message_service,
+        in_flight,
+        stream_resumer,
+        latency_tracker,
+        reasoning_traces: env_or("REASONING_TRACES", ReasoningTraces::Off),
+        indicators: Arc::new(indicator_tx),
+        blobs,
+        size_limits,
+        moderation,
+        permissions: Arc::new(permissions),
+        principals: Arc::new(principals),
+        escalations: escalations.clone(),
+        guardrails,
+        retries: Arc::new(retries),
+        conversations: Arc::new(conversations),
+        transfers: Arc::new(transfers),
+        shadows: shadows.clone(),
+        shadow_copies: shadow_tx,
+        commands_seen: bridge_dedupe.clone(),
+        clock: Arc::new(SystemClock),
+        ids: Arc::new(UuidV7Generator),
+    };
+
     // Load agent configuration from environment (REQUIRED for conversations)
     let agent_name = std::env::var("AGENT_NAME")
         .expect("AGENT_NAME environment variable must be set for agent conversations");
@@ -151,6 +752,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     // Create subject factory for type-safe NATS subjects (v0.9.2)
     let subject_factory = AgentSubjectFactory::default();
+    let subject_parser = SubjectParser::new(&subject_factory);
+
+    let indicator_client = client.clone();
+    let indicator_factory = subject_factory.clone();
+    tokio::spawn(async move {
+        while let Some(indicator) = indicator_rx.recv().await {
+            let published = async {
+                let subject = indicator_factory.conversation_status(indicator.conversation_id())?;
+                indicator_client
+                    .publish(subject.to_string(), serde_json::to_vec(&indicator)?.into())
+                    .await?;
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+            };
+            if let Err(e) = published.await {
+                warn!("Failed to publish processing indicator: {}", e);
+            }
+        }
+    });
+
+    if env_or("SELF_TEST", true) {
+        match self_test.ensure_ready().await {
+            Ok(report) => info!("Self-test passed:\n{}", report),
+            Err(NotReady(report)) => {
+                error!("Self-test failed, refusing to serve:\n{}", report);
+                return Err(NotReady(report).into());
+            }
+        }
+    }
 
     // Subscribe to agent-specific subjects (for conversations)
     info!("Subscribing to agent-specific subjects...");
@@ -163,353 +792,2685 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut broadcast_subscriber = client.subscribe(broadcast_pattern.to_string()).await?;
     info!("Subscribed to: {} (broadcast)", broadcast_pattern);
 
-    // Subscribe to agent-ref subjects (unified architecture v1.0.0)
-    // This allows commands to be sent via capability.name.id pattern
-    let agent_ref_commands = subject_factory.agent_commands_by_id_pattern(agent_id)?;
-    let mut agent_ref_subscriber = client.subscribe(agent_ref_commands.to_string()).await?;
-    info!("Subscribed to: {} (agent-ref commands)", agent_ref_commands);
-
-    info!("Agent '{}' v0.9.2 is ready for conversations", agent_name);
+    // Project stored events into the read model
+    let read_model: Arc<dyn AgentReadModel> = match (
+        sql_read_model().await?,
+        std::env::var("READ_MODEL_BUCKET"),
+    ) {
+        (Some(sql), _) => sql,
+        (None, Ok(bucket)) => {
+            let history_days: u64 = env_or("READ_MODEL_HISTORY_DAYS", 365);
+            let kv = KvAgentReadModel::ensure_buckets(&jetstream, &bucket)
+                .await?
+                .with_history_max_age(Duration::from_secs(history_days * 24 * 60 * 60));
+            Arc::new(kv)
+        }
+        (None, Err(_)) => Arc::new(InMemoryAgentReadModel::new()),
+    };
+    let fleet_graph = Arc::new(AgentGraphProjection::new());
+    let capability_index = Arc::new(CapabilityIndex::new());
+    let pricing = match (
+        std::env::var("DIGEST_PROMPT_USD_PER_1K").ok().and_then(|s| s.parse().ok()),
+        std::env::var("DIGEST_COMPLETION_USD_PER_1K").ok().and_then(|s| s.parse().ok()),
+    ) {
+        (Some(prompt), Some(completion)) => Some(TokenPricing::new(prompt, completion)),
+        _ => None,
+    };
+    let mut digests = match pricing {
+        Some(pricing) => DigestProjection::new().with_pricing(pricing),
+        None => DigestProjection::new(),
+    };
+    if let Some(threshold) =
+        std::env::var("SPEND_ALERT_USD_PER_DAY").ok().and_then(|s| s.parse().ok())
+    {
+        if pricing.is_none() {
+            warn!("SPEND_ALERT_USD_PER_DAY is set without token prices; no alert will fire");
+        }
+        digests = digests.with_spend_alert(threshold);
+    }
+    let digests = Arc::new(digests);
+    let cost_attribution = Arc::new(match pricing {
+        Some(pricing) => CostAttributionProjection::new().with_pricing(pricing),
+        None => CostAttributionProjection::new(),
+    });
+    let max_query_wait = Duration::from_millis(env_or("READ_MODEL_MAX_WAIT_MS", 2_000));
+    // Every published version is loaded; `describe` reports the newest supported one
+    let tool_catalog = Arc::new(match std::env::var("TOOL_CATALOG") {
+        Ok(path) => {
+            let definitions: Vec<ToolDefinition> =
+                serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            ToolCatalog::from_definitions(definitions)?
+        }
+        Err(_) => ToolCatalog::new(),
+    });
+    let tools_bucket = std::env::var("TOOL_CATALOG_BUCKET")
+        .unwrap_or_else(|_| "AGENT_TOOL_CATALOG".to_string());
+    let tool_bucket =
+        ToolCatalogBucket::new(ToolCatalogBucket::ensure_bucket(&jetstream, &tools_bucket).await?);
+    {
+        let tool_bucket = tool_bucket.clone();
+        let tool_catalog = tool_catalog.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tool_bucket.follow(&tool_catalog).await {
+                error!("Stopped following the tool catalog: {}", e);
+            }
+        });
+    }
+    // Consumed from the stream so projections version agents by stream sequence,
+    // the same sequence publish acks put in consistency tokens
+    let mut event_subscriber = jetstream
+        .get_stream(&stream_name)
+        .await?
+        .create_consumer(async_nats::jetstream::consumer::pull::OrderedConfig {
+            filter_subject: subject_factory.all_events_pattern()?.to_string(),
+            deliver_policy: async_nats::jetstream::consumer::DeliverPolicy::New,
+            ..Default::default()
+        })
+        .await?
+        .messages()
+        .await?;
+    let projector = read_model.clone();
+    let graphql_feed = graphql_service(&client, read_model.clone());
+    let graph_projector = fleet_graph.clone();
+    let shadow_observer = shadows.clone();
+    let digest_projector = digests.clone();
+    let cost_projector = cost_attribution.clone();
+    let flagged_messages = Arc::new(ModerationProjection::new());
+    let moderation_projector = flagged_messages.clone();
+    let reasoning = Arc::new(ReasoningTraceProjection::new());
+    let reasoning_projector = reasoning.clone();
+    let tool_usage = Arc::new(ToolUsageProjection::new());
+    let tool_usage_projector = tool_usage.clone();
+    let metrics = Arc::new(MetricsRegistry::new());
+    let event_metrics = metrics.clone();
+    let legal_holds = Arc::new(LegalHolds::new());
+    let hold_observer = legal_holds.clone();
+    let holds_bucket =
+        std::env::var("LEGAL_HOLD_BUCKET").unwrap_or_else(|_| "AGENT_LEGAL_HOLDS".to_string());
+    let hold_index =
+        LegalHoldBucket::new(LegalHoldBucket::ensure_bucket(&jetstream, &holds_bucket).await?);
+    {
+        let hold_index = hold_index.clone();
+        let legal_holds = legal_holds.clone();
+        tokio::spawn(async move {
+            if let Err(e) = hold_index.follow(&legal_holds).await {
+                error!("Stopped following legal holds: {}", e);
+            }
+        });
+    }
+    let escalation_observer = escalations.clone();
+    let retention = std::env::var("CONVERSATION_TTL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(|secs| {
+            Arc::new(
+                ConversationRetention::new(RetentionPolicy::new(Duration::from_secs(secs)))
+                    .with_legal_holds(legal_holds.clone()),
+            )
+        });
+    let retention_observer = retention.clone();
+    // Archived conversations are kept as blobs; their events index them
+    let archive = Arc::new(BlobArchiveStore::new(ctx.blobs.clone()));
+    let archive_observer = archive.clone();
+    let notifier = if env_or("OWNER_NOTIFICATIONS", false) {
+        let mut notifier = OwnerNotifier::new();
+        if escalations.is_some() {
+            let commands = format!("{}.commands", subject_factory.service_group()?);
+            notifier = notifier.with_approval_subject(commands);
+        }
+        if let Ok(path) = std::env::var("OWNER_NOTIFICATION_PREFS") {
+            let owners: std::collections::HashMap<PersonId, NotificationPreferences> =
+                serde_yaml::from_str(&std::fs::read_to_string(&path)?)?;
+            for (owner, preferences) in owners {
+                notifier.set_preferences(owner, preferences);
+            }
+        }
+        Some(notifier)
+    } else {
+        None
+    };
+    let notify_client = client.clone();
+    let notify_factory = subject_factory.clone();
+
+    // Automations count projected events and act as each rule's principal class
+    let automation = Arc::new(match std::env::var("AUTOMATION_RULES") {
+        Ok(path) => AutomationRules::from_yaml(&std::fs::read_to_string(&path)?)?,
+        Err(_) => AutomationRules::new(),
+    });
+    info!("Loaded {} automation rules", automation.rules().len());
+    let rules_bucket = std::env::var("AUTOMATION_RULES_BUCKET")
+        .unwrap_or_else(|_| "AGENT_AUTOMATION_RULES".to_string());
+    let rule_bucket =
+        RuleBucket::new(RuleBucket::ensure_bucket(&jetstream, &rules_bucket).await?);
+    {
+        let rule_bucket = rule_bucket.clone();
+        let automation = automation.clone();
+        tokio::spawn(async move {
+            if let Err(e) = rule_bucket.follow(&automation).await {
+                error!("Stopped following automation rules: {}", e);
+            }
+        });
+    }
+    let rule_engine = automation.clone();
+    let anomaly_publisher = ctx.event_publisher.clone();
+    let spend_publisher = ctx.event_publisher.clone();
+    let anomalies = env_or("ANOMALY_DETECTION", false).then(|| {
+        AnomalyDetector::new().with_policy(AnomalyPolicy {
+            window_size: env_or("ANOMALY_WINDOW", 20),
+            z_threshold: env_or("ANOMALY_Z_THRESHOLD", 3.0),
+            ..AnomalyPolicy::default()
+        })
+    });
+    let rule_ctx = ctx.clone();
+    let rule_client = client.clone();
+
+    let mut replication_filter = region.as_ref().map(|r| ReplicationFilter::new(&r.region));
+    let verifier = std::env::var("EVENT_TRUSTED_KEYS").ok().map(|keys| {
+        // This service's own events are always trusted
+        let registry = InMemoryKeyRegistry::new();
+        registry.trust(signer.key_id());
+        keys.split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .for_each(|k| registry.trust(k));
+        EventVerifier::new(Arc::new(registry))
+    });
+    tokio::spawn(async move {
+        while let Some(message) = event_subscriber.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("Failed to receive event: {}", e);
+                    continue;
+                }
+            };
+            let decoded = decode_envelope(&message.payload).map(|mut envelope| {
+                if let Ok(info) = message.info() {
+                    envelope.sequence = info.stream_sequence;
+                }
+                envelope
+            });
+            let projected = match decoded {
+                Ok(envelope) => {
+                    // Reject spoofed events before they reach the read model
+                    if let Some(verifier) = &verifier {
+                        let headers = message.headers.as_ref();
+                        if let Err(e) =
+                            verifier.verify(headers, &message.payload, envelope.aggregate_id)
+                        {
+                            warn!("Dropping event on {}: {}", message.subject, e);
+                            continue;
+                        }
+                    }
 
-    // Metrics tracking for dual publishing analysis
-    let metrics_inbox_count = Arc::new(AtomicU64::new(0));
-    let metrics_broadcast_count = Arc::new(AtomicU64::new(0));
-    let metrics_agent_ref_count = Arc::new(AtomicU64::new(0));
+                    // Skip copies of events that mirrored back from another region
+                    if let Some(filter) = replication_filter.as_mut() {
+                        if !filter.admit(message.headers.as_ref(), &envelope) {
+                            continue;
+                        }
+                    }
+                    let key = dedupe_key(&envelope);
+                    match projection_dedupe.first_delivery("projections", &key).await {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(e) => warn!("Dedupe check failed on {}: {}", message.subject, e),
+                    }
+                    graph_projector.project(&envelope);
+                    shadow_observer.observe(&envelope);
+                    if let Some(event) = digest_projector.project(&envelope) {
+                        let agent_id = event.agent_id;
+                        let (day, spent) = (event.day, event.spent_usd);
+                        info!("Agent {} spent ${:.2} on {}", agent_id, spent, day);
+                        let id = next_id();
+                        let event = AgentEvent::SpendThresholdCrossed(event);
+                        if let Err(e) = spend_publisher.publish(agent_id, event, id, id).await {
+                            warn!("Failed to publish spend alert for agent {}: {}", agent_id, e);
+                        }
+                    }
+                    cost_projector.project(&envelope);
+                    moderation_projector.project(&envelope);
+                    reasoning_projector.project(&envelope);
+                    tool_usage_projector.project(&envelope);
+                    event_metrics.observe_event(&envelope.event);
+                    hold_observer.observe(&envelope);
+                    if let Err(e) = hold_index.record(&envelope).await {
+                        warn!("Failed to index legal hold from {}: {}", message.subject, e);
+                    }
+                    if let Some(escalations) = &escalation_observer {
+                        escalations.observe(&envelope);
+                    }
+                    if let Some(retention) = &retention_observer {
+                        retention.observe(&envelope);
+                    }
+                    archive_observer.observe(&envelope);
+                    let detected = anomalies.as_ref().map(|d| d.observe(&envelope));
+                    for event in detected.unwrap_or_default() {
+                        let agent_id = event.agent_id;
+                        let (metric, value) = (event.metric, event.value);
+                        info!("Agent {} {} is anomalous: {:.2}", agent_id, metric, value);
+                        let id = next_id();
+                        let event = AgentEvent::AnomalyDetected(event);
+                        if let Err(e) = anomaly_publisher.publish(agent_id, event, id, id).await {
+                            warn!("Failed to publish anomaly for agent {}: {}", agent_id, e);
+                        }
+                    }
+                    for firing in rule_engine.evaluate(&envelope) {
+                        info!("Rule {} fired for agent {}", firing.rule, firing.agent_id);
+                        let ctx = rule_ctx.clone();
+                        let client = rule_client.clone();
+                        tokio::spawn(async move {
+                            let outcome = match serde_json::to_vec(&firing.command) {
+                                Ok(payload) => execute_command(
+                                    &payload,
+                                    None,
+                                    AuthenticatedPrincipal::internal(
+                                        firing.run_as,
+                                        format!("rule:{}", firing.rule),
+                                    ),
+                                    None,
+                                    ctx,
+                                    &client,
+                                )
+                                .await
+                                .and_then(|result| result.map(|_| ())),
+                                Err(e) => Err(e.into()),
+                            };
+                            if let Err(e) = outcome {
+                                let (rule, agent_id) = (&firing.rule, firing.agent_id);
+                                warn!("Rule {} failed for agent {}: {}", rule, agent_id, e);
+                            }
+                        });
+                    }
+                    let projected = projector.project(&envelope).await.map_err(|e| e.to_string());
+                    if projected.is_err() {
+                        // Not projected: let a redelivery try again
+                        if let Err(e) = projection_dedupe.release("projections", &key).await {
+                            warn!("Failed to release {}: {}", key, e);
+                        }
+                    } else if let Some(feed) = &graphql_feed {
+                        // Subscribers hear of the event once queries can see it
+                        feed(envelope.clone());
+                    }
 
-    // Log metrics every 100 messages
-    let log_interval = 100u64;
+                    // Alert the owner once the view reflects the event
+                    if let Some(notifier) = &notifier {
+                        if let Ok(Some(view)) = projector.get(envelope.aggregate_id).await {
+                            let command =
+                                notifier.notification(view.person_id, &view.name, &envelope.event);
+                            if let Some(command) = command {
+                                // Another instance may already have sent it
+                                let first = bridge_dedupe
+                                    .first_delivery("owner-notifications", &key)
+                                    .await
+                                    .unwrap_or(true);
+                                // The claim only stands once the notification is sent
+                                if first
+                                    && !notify_owner(&notify_client, &notify_factory, command).await
+                                {
+                                    let released =
+                                        bridge_dedupe.release("owner-notifications", &key).await;
+                                    if let Err(e) = released {
+                                        warn!("Failed to release {}: {}", key, e);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    projected
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = projected {
+                warn!("Failed to project event on {}: {}", message.subject, e);
+            }
+        }
+    });
+
+    // Drop escalations the owner did not approve in time
+    if let Some(escalations) = escalations.clone() {
+        let publisher = ctx.event_publisher.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(15));
+            loop {
+                ticker.tick().await;
+                for event in escalations.expire() {
+                    let agent_id = event.agent_id;
+                    info!("Escalation {} for agent {} expired", event.escalation_id, agent_id);
+                    let id = next_id();
+                    let event = AgentEvent::PermissionEscalationExpired(event);
+                    if let Err(e) = publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to publish expiry for agent {}: {}", agent_id, e);
+                    }
+                }
+            }
+        });
+    }
 
-    // Handle commands in a loop
-    loop {
-        tokio::select! {
-            // Handle incoming agent-specific messages (legacy inbox pattern)
-            Some(message) = command_subscriber.next() => {
-                let count = metrics_inbox_count.fetch_add(1, Ordering::Relaxed) + 1;
-                if count % log_interval == 0 {
-                    info!("Metrics: inbox={}, broadcast={}, agent-ref={}",
-                        count,
-                        metrics_broadcast_count.load(Ordering::Relaxed),
-                        metrics_agent_ref_count.load(Ordering::Relaxed));
+    // Replay, then follow, agent events into the capability index, so agents
+    // configured before this instance started are still found
+    let mut capability_events = jetstream
+        .get_stream(&stream_name)
+        .await?
+        .create_consumer(async_nats::jetstream::consumer::pull::OrderedConfig {
+            filter_subject: subject_factory.all_events_pattern()?.to_string(),
+            deliver_policy: async_nats::jetstream::consumer::DeliverPolicy::All,
+            ..Default::default()
+        })
+        .await?
+        .messages()
+        .await?;
+    let capability_projector = capability_index.clone();
+    tokio::spawn(async move {
+        while let Some(message) = capability_events.next().await {
+            let decoded = match message {
+                // Only lifecycle and configuration events move the index
+                Ok(message) => decode_envelope_if(&message.payload, is_state_changing)
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            match decoded {
+                Ok(Some(envelope)) => capability_projector.project(&envelope),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to index agent capabilities: {}", e),
+            }
+        }
+    });
+
+    // Replay, then follow, conversation events into the unread view and the
+    // inactivity timers
+    let conversation_view = Arc::new(ConversationProjection::new());
+    let timers = Arc::new(InactivityTimers::new(Duration::from_secs(env_or(
+        "CONVERSATION_IDLE_SECS",
+        30 * 60,
+    ))));
+    let mut conversation_events = jetstream
+        .get_stream(&conversation_stream)
+        .await?
+        .create_consumer(async_nats::jetstream::consumer::pull::OrderedConfig {
+            deliver_policy: async_nats::jetstream::consumer::DeliverPolicy::All,
+            ..Default::default()
+        })
+        .await?
+        .messages()
+        .await?;
+    let conversation_projector = conversation_view.clone();
+    let timer_observer = timers.clone();
+    tokio::spawn(async move {
+        while let Some(message) = conversation_events.next().await {
+            let observed = match message {
+                Ok(message) => {
+                    match serde_json::from_slice::<ConversationEventEnvelope>(&message.payload) {
+                        Ok(envelope) => {
+                            conversation_projector.project(&envelope.event);
+                            timer_observer
+                                .observe(&envelope.event)
+                                .await
+                                .map_err(|e| e.to_string())
+                        }
+                        Err(e) => Err(e.to_string()),
+                    }
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = observed {
+                warn!("Failed to observe conversation event: {}", e);
+            }
+        }
+    });
+
+    // Close idle conversations and those out of time; appends are conditional,
+    // so when several instances tick only one close is stored
+    {
+        let conversations = ctx.conversations.clone();
+        let period = Duration::from_secs(env_or("CONVERSATION_IDLE_CHECK_SECS", 30).max(1));
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                tick.tick().await;
+                for close in timers.close_idle(clock_now()) {
+                    let conversation_id = close.conversation_id;
+                    let reason = close.reason;
+                    match conversations.execute(&ConversationCommand::Close(close)).await {
+                        Ok(_) => info!("Conversation {} closed: {:?}", conversation_id, reason),
+                        Err(e) => warn!("Failed to close conversation {}: {}", conversation_id, e),
+                    }
                 }
+            }
+        });
+    }
 
-                let repository = repository.clone();
-                let event_publisher = event_publisher.clone();
-                let message_service = message_service.clone();
-                let client_clone = client.clone();
+    // Delete blobs no event references; a failed scan skips the sweep, since
+    // the references it missed would be deleted
+    if let Ok(secs) = std::env::var("ARTIFACT_GC_SECS") {
+        let period = Duration::from_secs(secs.parse::<u64>()?.max(1));
+        let grace = Duration::from_secs(env_or("ARTIFACT_GC_GRACE_SECS", 24 * 3600));
+        let collector = ArtifactCollector::new(grace).with_store(blob_store.clone());
+        let mut streams = vec![stream_name.clone(), conversation_stream.clone()];
+        streams.dedup();
+        let jetstream = jetstream.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                tick.tick().await;
+                let mut live = LiveReferences::new();
+                let mut scanned = Ok(0);
+                for name in &streams {
+                    scanned = match jetstream.get_stream(name).await {
+                        Ok(mut stream) => live
+                            .scan_stream(&mut stream)
+                            .await
+                            .map_err(|e| format!("{}: {}", name, e)),
+                        Err(e) => Err(format!("{}: {}", name, e)),
+                    };
+                    if scanned.is_err() {
+                        break;
+                    }
+                }
+                match scanned {
+                    Ok(_) => {
+                        collector.collect(&live, clock_now()).await;
+                    }
+                    Err(e) => warn!("Skipped artifact collection; reference scan failed: {}", e),
+                }
+            }
+        });
+    }
 
+    // Copies mirrored to shadow agents run like any message from the same caller
+    {
+        let ctx = ctx.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            while let Some((copy, caller)) = shadow_rx.recv().await {
+                let (ctx, client) = (ctx.clone(), client.clone());
                 tokio::spawn(async move {
-                    if let Err(e) = handle_command(message, repository, event_publisher, message_service, client_clone).await {
-                        error!("Error handling inbox command: {}", e);
+                    let message_id = copy.message_id;
+                    let outcome = match serde_json::to_vec(&AgentCommand::SendMessage(copy)) {
+                        Ok(payload) => execute_command(&payload, None, caller, None, ctx, &client)
+                            .await
+                            .and_then(|result| result.map(|_| ())),
+                        Err(e) => Err(e.into()),
+                    };
+                    if let Err(e) = outcome {
+                        warn!("Shadow copy {} failed: {}", message_id, e);
                     }
                 });
             }
+        });
+    }
 
-            // Handle incoming broadcast messages
-            Some(message) = broadcast_subscriber.next() => {
-                let _count = metrics_broadcast_count.fetch_add(1, Ordering::Relaxed) + 1;
-
-                let repository = repository.clone();
-                let event_publisher = event_publisher.clone();
-                let message_service = message_service.clone();
-                let client_clone = client.clone();
+    // Finished shadow exchanges are diffed into comparison reports
+    let registry = ctx.message_service.router().registry();
+    let comparers = (
+        registry.select_provider(&CapabilityRequirements::text_chat()),
+        registry.select_embedder(&CapabilityRequirements::embeddings()),
+    );
+    match comparers {
+        (Ok(chat), Ok(embeddings)) => {
+            let comparison_bucket = std::env::var("COMPARISON_BUCKET")
+                .unwrap_or_else(|_| "AGENT_COMPARISONS".to_string());
+            let comparisons = NatsComparisonStore::new(
+                NatsComparisonStore::ensure_bucket(&jetstream, &comparison_bucket).await?,
+            );
+            let differ = ResponseDiffer::new(chat, embeddings).with_store(Arc::new(comparisons));
+            let period = Duration::from_secs(env_or("SHADOW_EVALUATION_SECS", 300u64).max(1));
+            let shadows = shadows.clone();
+            tokio::spawn(async move {
+                let mut tick = tokio::time::interval(period);
+                loop {
+                    tick.tick().await;
+                    for deployment in shadows.list() {
+                        let (primary, shadow) = (deployment.primary_id(), deployment.shadow_id());
+                        match deployment.evaluate(&differ).await {
+                            Ok(Some(report)) => info!(
+                                "Compared {} answers of shadow {} with agent {}",
+                                report.comparisons.len(),
+                                shadow,
+                                primary
+                            ),
+                            Ok(None) => {}
+                            Err(e) => warn!("Failed to compare shadow {}: {}", shadow, e),
+                        }
+                    }
+                }
+            });
+        }
+        _ => warn!("No embedding provider registered; shadow answers will not be compared"),
+    }
 
+    // Verified deliveries from external agents become conversation commands;
+    // the queue group hands each delivery to one instance
+    let external_agents: Vec<ExternalAgentRegistration> = match std::env::var("EXTERNAL_AGENTS") {
+        Ok(path) => serde_yaml::from_str(&std::fs::read_to_string(&path)?)?,
+        Err(_) => Vec::new(),
+    };
+    if !external_agents.is_empty() {
+        let secrets =
+            InMemoryWebhookSecrets::load(&external_agents, &EnvSecretsProvider::new()).await?;
+        let ingress =
+            Arc::new(WebhookIngress::new(Arc::new(secrets)).with_blob_store(ctx.blobs.clone()));
+        let mut deliveries = client
+            .queue_subscribe(
+                subject_factory.external_ingress_pattern()?.to_string(),
+                SERVICE_NAME.to_string(),
+            )
+            .await?;
+        info!("Accepting webhooks from {} external agent(s)", external_agents.len());
+        let (ctx, client) = (ctx.clone(), client.clone());
+        tokio::spawn(async move {
+            while let Some(message) = deliveries.next().await {
+                let (ingress, ctx, client) = (ingress.clone(), ctx.clone(), client.clone());
                 tokio::spawn(async move {
-                    info!("Received broadcast message on: {}", message.subject);
-                    if let Err(e) = handle_command(message, repository, event_publisher, message_service, client_clone).await {
-                        error!("Error handling broadcast: {}", e);
+                    if let Err(e) = handle_external_delivery(message, &ingress, &ctx, &client).await
+                    {
+                        warn!("Failed to handle external delivery: {}", e);
                     }
                 });
             }
+        });
+    }
 
-            // Handle incoming agent-ref commands (unified architecture)
-            Some(message) = agent_ref_subscriber.next() => {
-                let _count = metrics_agent_ref_count.fetch_add(1, Ordering::Relaxed) + 1;
-
-                let repository = repository.clone();
-                let event_publisher = event_publisher.clone();
-                let message_service = message_service.clone();
-                let client_clone = client.clone();
+    // Send failed messages again once their backoff has passed
+    if env_or("MESSAGE_RETRY_DRAIN", true) {
+        let ctx = ctx.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                ticker.tick().await;
+                let due = match ctx.retries.due().await {
+                    Ok(due) => due,
+                    Err(e) => {
+                        warn!("Failed to read due retries: {}", e);
+                        continue;
+                    }
+                };
+                for message in due {
+                    let (ctx, client) = (ctx.clone(), client.clone());
+                    tokio::spawn(async move {
+                        let message_id = message.retry_key();
+                        info!(
+                            "Retrying message {} as {} for agent {}",
+                            message_id, message.message_id, message.agent_id
+                        );
+                        let command = AgentCommand::SendMessage(message);
+                        let outcome = match serde_json::to_vec(&command) {
+                            Ok(payload) => execute_command(
+                                &payload,
+                                None,
+                                AuthenticatedPrincipal::internal(
+                                    PrincipalClass::Agent,
+                                    "message-retry",
+                                ),
+                                None,
+                                ctx,
+                                &client,
+                            )
+                            .await
+                            .and_then(|result| result.map(|_| ())),
+                            Err(e) => Err(e.into()),
+                        };
+                        // A failed retry has already been rescheduled or quarantined
+                        if let Err(e) = outcome {
+                            warn!("Retry of message {} failed: {}", message_id, e);
+                        }
+                    });
+                }
+            }
+        });
+    }
 
-                tokio::spawn(async move {
-                    info!("Received agent-ref command on: {}", message.subject);
-                    if let Err(e) = handle_command(message, repository, event_publisher, message_service, client_clone).await {
-                        error!("Error handling agent-ref command: {}", e);
+    // Forward captured logs to each agent's log subject
+    if let Some(capture) = log_capture {
+        let client = client.clone();
+        let factory = subject_factory.clone();
+        let period = Duration::from_secs(env_or("LOG_FORWARD_SECS", 5).max(1));
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                tick.tick().await;
+                for batch in capture.drain() {
+                    let agent_id = batch.agent_id;
+                    let subject = match factory.logs_subject(agent_id) {
+                        Ok(subject) => subject.to_string(),
+                        Err(e) => {
+                            warn!("No log subject for agent {}: {}", agent_id, e);
+                            continue;
+                        }
+                    };
+                    let published = match serde_json::to_vec(&batch) {
+                        Ok(payload) => client
+                            .publish(subject, payload.into())
+                            .await
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    if let Err(e) = published {
+                        warn!("Failed to forward logs for agent {}: {}", agent_id, e);
                     }
-                });
+                }
             }
+        });
+        info!("Log forwarding enabled");
+    }
 
-            // Handle shutdown signal
-            _ = signal::ctrl_c() => {
-                info!("Received shutdown signal, gracefully shutting down...");
-                break;
+    // Publish yesterday's digests shortly after each UTC midnight
+    if env_or("DIGEST_ENABLED", false) {
+        let publisher = ctx.event_publisher.clone();
+        tokio::spawn(async move {
+            loop {
+                let now = chrono::Utc::now();
+                let next_midnight = (now.date_naive() + chrono::Days::new(1))
+                    .and_hms_opt(0, 5, 0)
+                    .expect("valid time")
+                    .and_utc();
+                let wait = (next_midnight - now).to_std().unwrap_or(Duration::from_secs(60));
+                tokio::time::sleep(wait).await;
+
+                let yesterday = chrono::Utc::now().date_naive() - chrono::Days::new(1);
+                for event in digests.close_day(yesterday) {
+                    let agent_id = event.agent_id;
+                    let id = uuid::Uuid::now_v7();
+                    let event = AgentEvent::DailyDigestReady(event);
+                    if let Err(e) = publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to publish digest for agent {}: {}", agent_id, e);
+                    }
+                }
+                digests.prune_before(yesterday);
             }
-        }
+        });
+        info!("Daily digests enabled");
     }
 
-    // Final metrics report
-    info!("Final metrics - inbox: {}, broadcast: {}, agent-ref: {}",
-        metrics_inbox_count.load(Ordering::Relaxed),
-        metrics_broadcast_count.load(Ordering::Relaxed),
-        metrics_agent_ref_count.load(Ordering::Relaxed));
+    // Archive idle conversations; agents under legal hold are skipped
+    if let Some(retention) = retention {
+        let publisher = ctx.event_publisher.clone();
+        let archive = archive.clone();
+        tokio::spawn(async move {
+            let mut sweep = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                sweep.tick().await;
+
+                for event in retention.refuse_held(clock_now()) {
+                    let agent_id = event.agent_id;
+                    let id = next_id();
+                    let event = AgentEvent::LegalHoldRefused(event);
+                    if let Err(e) = publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to publish hold refusal for agent {}: {}", agent_id, e);
+                    }
+                }
 
-    info!("Agent service stopped");
-    Ok(())
-}
+                let archived = match retention.archive_idle(archive.as_ref(), clock_now()).await {
+                    Ok(archived) => archived,
+                    Err(e) => {
+                        warn!("Failed to archive idle conversations: {}", e);
+                        continue;
+                    }
+                };
+                for event in archived {
+                    let agent_id = event.agent_id;
+                    let id = next_id();
+                    let event = AgentEvent::ConversationArchived(event);
+                    if let Err(e) = publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to publish archival for agent {}: {}", agent_id, e);
+                    }
+                }
+            }
+        });
+        info!("Conversation archival enabled");
+    }
 
-/// Handle a command message
-async fn handle_command(
-    message: async_nats::Message,
-    repository: Arc<AgentRepository>,
-    event_publisher: Arc<NatsEventPublisher>,
-    message_service: Arc<AgentMessageService>,
-    client: async_nats::Client,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Parse command
-    let command: AgentCommand = serde_json::from_slice(&message.payload)?;
+    // Age agents' events out of the stream; agents under legal hold are spared
+    let retention_days: u64 = env_or("EVENT_RETENTION_DAYS", 365);
+    if retention_days > 0 {
+        let retention = EventRetention::new(
+            Duration::from_secs(retention_days * 24 * 60 * 60),
+            legal_holds.clone(),
+        );
+        let publisher = ctx.event_publisher.clone();
+        let read_model = read_model.clone();
+        let event_store = event_store.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_secs(60 * 60));
+            loop {
+                tick.tick().await;
+                let mut agents = std::collections::HashSet::new();
+                let mut request = PageRequest::first(MAX_PAGE_LIMIT);
+                let listed = loop {
+                    match read_model.list(None, &request).await {
+                        Ok(page) => {
+                            agents.extend(page.items.into_iter().map(|view| view.agent_id));
+                            match page.next_cursor {
+                                Some(cursor) => request = request.with_cursor(cursor),
+                                None => break Ok(()),
+                            }
+                        }
+                        Err(e) => break Err(e),
+                    }
+                };
+                if let Err(e) = listed {
+                    warn!("Failed to list agents for event retention: {}", e);
+                    continue;
+                }
+                // Agents gone from the read model still have events to age out
+                match event_store.stored_agents().await {
+                    Ok(stored) => agents.extend(stored),
+                    Err(e) => {
+                        warn!("Failed to list stored agents for event retention: {}", e);
+                        continue;
+                    }
+                }
 
-    info!("Received command: {:?}", command);
+                let sweep = retention.sweep(agents, clock_now());
+                for event in sweep.refused {
+                    let agent_id = event.agent_id;
+                    let id = next_id();
+                    let event = AgentEvent::LegalHoldRefused(event);
+                    if let Err(e) = publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to publish hold refusal for agent {}: {}", agent_id, e);
+                    }
+                }
+                for agent_id in sweep.purge {
+                    match event_store.purge_before(agent_id, sweep.cutoff).await {
+                        Ok(0) => {}
+                        Ok(purged) => {
+                            info!("Purged {} expired events of agent {}", purged, agent_id)
+                        }
+                        Err(e) => warn!("Failed to purge events of agent {}: {}", agent_id, e),
+                    }
+                }
+            }
+        });
+        info!("Event retention enabled ({} days)", retention_days);
+    }
 
-    // Process command based on type
-    let result = match command {
-        AgentCommand::DeployAgent(cmd) => {
-            handle_deploy_agent(cmd, repository, event_publisher).await
-        }
-        AgentCommand::ConfigureModel(cmd) => {
-            handle_configure_model(cmd, repository, event_publisher).await
-        }
-        AgentCommand::ActivateAgent(cmd) => {
-            handle_activate_agent(cmd, repository, event_publisher).await
-        }
-        AgentCommand::SuspendAgent(cmd) => {
-            handle_suspend_agent(cmd, repository, event_publisher).await
-        }
-        AgentCommand::DecommissionAgent(cmd) => {
-            handle_decommission_agent(cmd, repository, event_publisher).await
+    // Check live agents against the declared fleet
+    if let Ok(manifest_path) = std::env::var("DRIFT_MANIFEST") {
+        let detector =
+            DriftDetector::new().with_auto_remediate(env_or("DRIFT_AUTO_REMEDIATE", false));
+        let period = Duration::from_secs(env_or("DRIFT_CHECK_SECS", 300));
+        let read_model = read_model.clone();
+        let fleet_graph = fleet_graph.clone();
+        let ctx = ctx.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                tick.tick().await;
+                let manifest = match std::fs::read_to_string(&manifest_path) {
+                    Ok(yaml) => FleetManifest::from_yaml(&yaml).map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                let checked = match manifest {
+                    Ok(manifest) => match live_agents(read_model.as_ref(), &ctx.repository).await {
+                        Ok(agents) => detector
+                            .check(&manifest, &agents, &fleet_graph)
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    },
+                    Err(e) => Err(e),
+                };
+                let report = match checked {
+                    Ok(report) => report,
+                    Err(e) => {
+                        warn!("Drift check against {} failed: {}", manifest_path, e);
+                        continue;
+                    }
+                };
+
+                for event in report.events {
+                    let agent_id = event.agent_id;
+                    let id = uuid::Uuid::now_v7();
+                    let event = AgentEvent::ConfigurationDriftDetected(event);
+                    if let Err(e) = ctx.event_publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to publish drift for agent {}: {}", agent_id, e);
+                    }
+                }
+                let Some(plan) = report.remediation else {
+                    continue;
+                };
+                for command in plan.commands {
+                    let agent_id = command.agent_id();
+                    let outcome = match serde_json::to_vec(&command) {
+                        Ok(payload) => execute_command(
+                            &payload,
+                            None,
+                            AuthenticatedPrincipal::internal(
+                                PrincipalClass::Operator,
+                                "drift-remediation",
+                            ),
+                            None,
+                            ctx.clone(),
+                            &client,
+                        )
+                        .await
+                        .and_then(|result| result.map(|_| ())),
+                        Err(e) => Err(e.into()),
+                    };
+                    if let Err(e) = outcome {
+                        warn!("Drift remediation failed for agent {}: {}", agent_id, e);
+                    }
+                }
+                for assignment in plan.tools {
+                    fleet_graph.set_tools(assignment.agent_id, assignment.tools);
+                }
+                for drift in plan.drift {
+                    warn!("Drift needs manual attention: {}", drift);
+                }
+            }
+        });
+        info!("Drift detection enabled");
+    }
+
+    // Re-verify advertised capabilities against the live models (costs tokens: opt-in)
+    if let Some(period) = std::env::var("CAPABILITY_PROBE_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_secs)
+    {
+        let prober = CapabilityProber::new();
+        let read_model = read_model.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                tick.tick().await;
+                let agents = match live_agents(read_model.as_ref(), &ctx.repository).await {
+                    Ok(agents) => agents,
+                    Err(e) => {
+                        warn!("Capability probe could not list agents: {}", e);
+                        continue;
+                    }
+                };
+                for agent in agents.into_iter().filter(Agent::is_operational) {
+                    if let Err(e) = probe_capabilities(&prober, &agent, &ctx).await {
+                        warn!("Capability probe failed for agent {}: {}", agent.id(), e);
+                    }
+                }
+            }
+        });
+        info!("Capability probes enabled every {:?}", period);
+    }
+
+    // Serve read model queries
+    let queries_pattern = subject_factory.queries_pattern()?;
+    let mut query_subscriber = client.subscribe(queries_pattern.to_string()).await?;
+    info!("Subscribed to: {} (queries)", queries_pattern);
+
+    // Subscribe to agent-ref subjects (unified architecture v1.0.0)
+    // This allows commands to be sent via capability.name.id pattern; the
+    // compatibility mode decides whether legacy command subjects are kept
+    let subject_compatibility = env_or("SUBJECT_COMPATIBILITY", CompatibilityMode::Unified);
+    let legacy_commands = subject_factory.legacy_agent_commands_pattern(agent_id)?;
+    let command_patterns = SubjectMigrator::new(subject_factory.clone())
+        .subscriptions(&legacy_commands.to_string(), subject_compatibility)?;
+    let mut command_subscribers = Vec::new();
+    for pattern in command_patterns {
+        command_subscribers.push(client.subscribe(pattern.clone()).await?);
+        info!("Subscribed to: {} (agent-ref commands, {} mode)", pattern, subject_compatibility);
+    }
+    let mut agent_ref_subscriber = futures::stream::select_all(command_subscribers);
+
+    // Metrics tracking for dual publishing analysis
+    let metrics_inbox_count = Arc::new(AtomicU64::new(0));
+    let metrics_broadcast_count = Arc::new(AtomicU64::new(0));
+    let metrics_agent_ref_count = Arc::new(AtomicU64::new(0));
+
+    // Register as a discoverable NATS micro service
+    let stats_counts = (
+        metrics_inbox_count.clone(),
+        metrics_broadcast_count.clone(),
+        metrics_agent_ref_count.clone(),
+    );
+    let stats_streams = ctx.in_flight.clone();
+    let stats_repository = ctx.repository.clone();
+    let service = client
+        .service_builder()
+        .description("CIM agent domain command handler")
+        .metadata(std::collections::HashMap::from([
+            ("agent_name".to_string(), agent_name.clone()),
+            ("agent_id".to_string(), agent_id.to_string()),
+            ("capability".to_string(), agent_ref.capability().as_str().to_string()),
+        ]))
+        .stats_handler(move |_endpoint, _stats| {
+            metrics.set_gauge("streams.in_flight", stats_streams.len() as f64);
+            if let Some(cache) = stats_repository.cache_stats() {
+                metrics.record_cache_stats(cache);
+            }
+            serde_json::json!({
+                "inbox": stats_counts.0.load(Ordering::Relaxed),
+                "broadcast": stats_counts.1.load(Ordering::Relaxed),
+                "agent_ref": stats_counts.2.load(Ordering::Relaxed),
+                "metrics": metrics.snapshot(),
+            })
+        })
+        .start(SERVICE_NAME, env!("CARGO_PKG_VERSION"))
+        .await?;
+    let service_group = subject_factory.service_group()?;
+    let mut command_endpoint = service
+        .group(service_group.to_string())
+        .endpoint("commands")
+        .await?;
+    info!("Registered NATS service {} on {}.commands", SERVICE_NAME, service_group);
+    let mut rules_endpoint = service
+        .group(service_group.to_string())
+        .endpoint("rules")
+        .await?;
+    let mut conversation_endpoint = service
+        .group(service_group.to_string())
+        .endpoint("conversations")
+        .await?;
+    let mut shadow_endpoint = service
+        .group(service_group.to_string())
+        .endpoint("shadows")
+        .await?;
+    let mut batch_endpoint = service
+        .group(service_group.to_string())
+        .endpoint("batches")
+        .await?;
+    let mut tools_endpoint = service
+        .group(service_group.to_string())
+        .endpoint("tools")
+        .await?;
+
+    info!("Agent '{}' v0.9.2 is ready for conversations", agent_name);
+
+    // Log metrics every 100 messages
+    let log_interval = 100u64;
+
+    // Handle commands in a loop
+    loop {
+        tokio::select! {
+            // Handle incoming agent-specific messages (legacy inbox pattern)
+            Some(message) = command_subscriber.next() => {
+                let count = metrics_inbox_count.fetch_add(1, Ordering::Relaxed) + 1;
+                if count % log_interval == 0 {
+                    info!("Metrics: inbox={}, broadcast={}, agent-ref={}",
+                        count,
+                        metrics_broadcast_count.load(Ordering::Relaxed),
+                        metrics_agent_ref_count.load(Ordering::Relaxed));
+                }
+
+                if let Ok(ParsedAgentSubject::AgentToAgent { to, from, .. }) =
+                    subject_parser.parse(&message.subject)
+                {
+                    if let Err(e) = fleet_graph.record_delegation(&from, &to) {
+                        warn!("Unattributed agent-to-agent message on {}: {}", message.subject, e);
+                    }
+                }
+
+                let ctx = ctx.clone();
+                let client_clone = client.clone();
+
+                tokio::spawn(async move {
+                    if let Err(e) = handle_command(message, ctx, client_clone).await {
+                        error!("Error handling inbox command: {}", e);
+                    }
+                });
+            }
+
+            // Handle incoming broadcast messages
+            Some(message) = broadcast_subscriber.next() => {
+                let _count = metrics_broadcast_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+                let ctx = ctx.clone();
+                let client_clone = client.clone();
+
+                tokio::spawn(async move {
+                    info!("Received broadcast message on: {}", message.subject);
+                    if let Err(e) = handle_command(message, ctx, client_clone).await {
+                        error!("Error handling broadcast: {}", e);
+                    }
+                });
+            }
+
+            // Handle incoming agent-ref commands (unified architecture)
+            Some(message) = agent_ref_subscriber.next() => {
+                if !subject_compatibility.accepts(message.headers.as_ref()) {
+                    continue;
+                }
+                let _count = metrics_agent_ref_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+                let ctx = ctx.clone();
+                let client_clone = client.clone();
+
+                tokio::spawn(async move {
+                    info!("Received agent-ref command on: {}", message.subject);
+                    if let Err(e) = handle_command(message, ctx, client_clone).await {
+                        error!("Error handling agent-ref command: {}", e);
+                    }
+                });
+            }
+
+            // Handle commands sent to the NATS micro service endpoint
+            Some(request) = command_endpoint.next() => {
+                let ctx = ctx.clone();
+                let client_clone = client.clone();
+
+                tokio::spawn(async move {
+                    if let Err(e) = handle_service_request(request, ctx, client_clone).await {
+                        error!("Error handling service command: {}", e);
+                    }
+                });
+            }
+
+            // Handle conversation commands from participants
+            Some(request) = conversation_endpoint.next() => {
+                let ctx = ctx.clone();
+                let client = client.clone();
+
+                tokio::spawn(async move {
+                    if let Err(e) = handle_conversation_request(request, ctx, client).await {
+                        error!("Error handling conversation command: {}", e);
+                    }
+                });
+            }
+
+            // Start batch runs
+            Some(request) = batch_endpoint.next() => {
+                let ctx = ctx.clone();
+                let client = client.clone();
+
+                tokio::spawn(async move {
+                    if let Err(e) = handle_batch_request(request, ctx, client).await {
+                        error!("Error handling batch request: {}", e);
+                    }
+                });
+            }
+
+            // Manage shadow deployments
+            Some(request) = shadow_endpoint.next() => {
+                let ctx = ctx.clone();
+                let client = client.clone();
+
+                tokio::spawn(async move {
+                    if let Err(e) = handle_shadow_request(request, ctx, client).await {
+                        error!("Error handling shadow request: {}", e);
+                    }
+                });
+            }
+
+            // Manage automation rules
+            Some(request) = rules_endpoint.next() => {
+                let automation = automation.clone();
+                let rule_bucket = rule_bucket.clone();
+                let principals = ctx.principals.clone();
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let handled = handle_rules_request(
+                        request,
+                        &automation,
+                        &rule_bucket,
+                        &principals,
+                        client,
+                    );
+                    if let Err(e) = handled.await {
+                        error!("Error handling rules request: {}", e);
+                    }
+                });
+            }
+
+            // Manage the tool catalog
+            Some(request) = tools_endpoint.next() => {
+                let ctx = ctx.clone();
+                let tool_catalog = tool_catalog.clone();
+                let tool_bucket = tool_bucket.clone();
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let handled =
+                        handle_tools_request(request, ctx, &tool_catalog, &tool_bucket, client);
+                    if let Err(e) = handled.await {
+                        error!("Error handling tools request: {}", e);
+                    }
+                });
+            }
+
+            // Handle read model queries
+            Some(message) = query_subscriber.next() => {
+                let read_model = read_model.clone();
+                let fleet_graph = fleet_graph.clone();
+                let capability_index = capability_index.clone();
+                let reasoning = reasoning.clone();
+                let tool_usage = tool_usage.clone();
+                let cost_attribution = cost_attribution.clone();
+                let flagged_messages = flagged_messages.clone();
+                let repository = ctx.repository.clone();
+                let conversation_view = conversation_view.clone();
+                let tool_catalog = tool_catalog.clone();
+                let archive = archive.clone();
+                let signer = signer.clone();
+                let parser = subject_parser.clone();
+                let client_clone = client.clone();
+
+                tokio::spawn(async move {
+                    let sources = QuerySources {
+                        parser,
+                        read_model,
+                        fleet_graph,
+                        capability_index,
+                        reasoning,
+                        tool_usage,
+                        cost_attribution,
+                        flagged_messages,
+                        repository,
+                        conversation_view,
+                        tool_catalog,
+                        archive,
+                        signer,
+                    };
+                    if let Err(e) =
+                        handle_query(message, sources, max_query_wait, client_clone).await
+                    {
+                        error!("Error handling query: {}", e);
+                    }
+                });
+            }
+
+            // Handle shutdown signal
+            _ = signal::ctrl_c() => {
+                info!("Received shutdown signal, gracefully shutting down...");
+                break;
+            }
         }
-        AgentCommand::SendMessage(cmd) => {
-            handle_send_message(cmd, repository, event_publisher, message_service).await
+    }
+
+    // Final metrics report
+    info!("Final metrics - inbox: {}, broadcast: {}, agent-ref: {}",
+        metrics_inbox_count.load(Ordering::Relaxed),
+        metrics_broadcast_count.load(Ordering::Relaxed),
+        metrics_agent_ref_count.load(Ordering::Relaxed));
+
+    if let Err(e) = service.stop().await {
+        warn!("Failed to stop NATS service: {}", e);
+    }
+
+    info!("Agent service stopped");
+    Ok(())
+}
+
+/// Handle a command message
+async fn handle_command(
+    message: async_nats::Message,
+    ctx: HandlerContext,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let principal = match ctx.principals.authenticate(message.headers.as_ref(), &message.payload)
+    {
+        Ok(principal) => principal,
+        Err(e) => {
+            warn!("Rejected unauthenticated command: {}", e);
+            if let Some(reply_to) = message.reply {
+                let body = serde_json::json!({ "status": "error", "message": e.to_string() });
+                client.publish(reply_to, serde_json::to_vec(&body)?.into()).await?;
+            }
+            return Err(e.into());
         }
     };
+    if command_dry_run(message.headers.as_ref()) {
+        let body = dry_run_command(&message.payload, principal, ctx).await;
+        if let Some(reply_to) = message.reply {
+            client.publish(reply_to, serde_json::to_vec(&body)?.into()).await?;
+        }
+        return Ok(());
+    }
+    let result = execute_command(
+        &message.payload,
+        message.headers.as_ref(),
+        principal,
+        message.reply.clone(),
+        ctx,
+        &client,
+    )
+    .await?;
 
     // Reply with result
     if let Some(reply_to) = message.reply {
-        let response = match result {
-            Ok(_) => serde_json::json!({ "status": "ok" }),
-            Err(ref e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
-        };
-
         if let Err(e) = client
-            .publish(reply_to, serde_json::to_vec(&response)?.into())
+            .publish(reply_to, serde_json::to_vec(&command_reply(&result))?.into())
             .await
         {
             error!("Failed to send reply: {}", e);
         }
     }
 
-    result
+    result.map(|_| ())
 }
 
-// ============================================================================
-// Command Handlers
-// ============================================================================
+/// Reply to a service request with a JSON body
+///
+/// `Request::respond` sends a failure as the service error headers alone;
+/// the `{"status":"error"}` body callers of the inbox subjects rely on is
+/// published alongside them instead.
+async fn respond(
+    request: async_nats::service::Request,
+    client: &async_nats::Client,
+    reply: Result<serde_json::Value, async_nats::service::error::Error>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let error = match reply {
+        Ok(body) => {
+            request.respond(Ok(serde_json::to_vec(&body)?.into())).await?;
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+    let Some(reply_to) = request.message.reply.clone() else {
+        return Ok(());
+    };
+    let mut headers = async_nats::HeaderMap::new();
+    headers.insert("Nats-Service-Error", error.status.as_str());
+    headers.insert("Nats-Service-Error-Code", error.code.to_string().as_str());
+    let body = serde_json::json!({ "status": "error", "message": error.status });
+    client
+        .publish_with_headers(reply_to, headers, serde_json::to_vec(&body)?.into())
+        .await?;
+    Ok(())
+}
 
-/// Deploy a new agent bound to a Person
-async fn handle_deploy_agent(
-    cmd: DeployAgent,
-    repository: Arc<AgentRepository>,
-    event_publisher: Arc<NatsEventPublisher>,
+/// Handle a command sent to the NATS micro service endpoint
+///
+/// Replies with the same JSON body as the inbox subjects; failures also
+/// carry the service error headers.
+async fn handle_service_request(
+    request: async_nats::service::Request,
+    ctx: HandlerContext,
+    client: async_nats::Client,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Validate command
-    cmd.validate()?;
+    let authenticated = ctx
+        .principals
+        .authenticate(request.message.headers.as_ref(), &request.message.payload);
+    let principal = match authenticated {
+        Ok(principal) => principal,
+        Err(e) => {
+            warn!("Rejected unauthenticated command: {}", e);
+            let error = async_nats::service::error::Error {
+                status: format!("Unauthenticated command: {}", e),
+                code: 401,
+            };
+            respond(request, &client, Err(error)).await?;
+            return Err(e.into());
+        }
+    };
+    if command_dry_run(request.message.headers.as_ref()) {
+        let body = dry_run_command(&request.message.payload, principal, ctx).await;
+        return respond(request, &client, Ok(body)).await;
+    }
+    let result = match execute_command(
+        &request.message.payload,
+        request.message.headers.as_ref(),
+        principal,
+        request.message.reply.clone(),
+        ctx,
+        &client,
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            let error = async_nats::service::error::Error {
+                status: format!("Invalid command: {}", e),
+                code: 400,
+            };
+            respond(request, &client, Err(error)).await?;
+            return Err(e);
+        }
+    };
 
-    // Create event
-    let event = AgentEvent::AgentDeployed(AgentDeployedEvent::new(
-        cmd.agent_id,
-        cmd.person_id,
-        &cmd.name,
-        cmd.description.clone(),
-    ));
+    let reply = match &result {
+        Ok(_) => Ok(command_reply(&result)),
+        Err(e) if e.is::<EscalationPending>() => Ok(command_reply(&result)),
+        Err(e) => Err(async_nats::service::error::Error {
+            status: e.to_string(),
+            code: 500,
+        }),
+    };
+    respond(request, &client, reply).await?;
 
-    // Create agent by applying event to empty state
-    let agent = Agent::empty().apply_event(&event)?;
+    result.map(|_| ())
+}
+
+/// Apply an automation rule change and reply with the rules in force
+///
+/// Changes are stored in the rule bucket so every instance picks them up. A
+/// rule put here acts as the caller's class, never more.
+async fn handle_rules_request(
+    request: async_nats::service::Request,
+    automation: &AutomationRules,
+    rule_bucket: &RuleBucket,
+    principals: &PrincipalDirectory,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let applied = async {
+        let message = &request.message;
+        let caller = principals.authenticate(message.headers.as_ref(), &message.payload)?;
+        match serde_json::from_slice::<RuleRequest>(&message.payload)? {
+            RuleRequest::Put { rule } => {
+                let rule = rule.run_as(caller.class);
+                automation.put(rule.clone())?;
+                rule_bucket.put(&rule).await?;
+            }
+            RuleRequest::Remove { name } => {
+                automation.remove(&name)?;
+                rule_bucket.remove(&name).await?;
+            }
+            RuleRequest::List => {}
+        }
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(automation.rules())
+    }
+    .await
+    .map_err(|e| e.to_string());
+    let reply = match applied {
+        Ok(rules) => {
+            let body = serde_json::json!({ "status": "ok", "rules": rules });
+            Ok(body)
+        }
+        Err(e) => Err(async_nats::service::error::Error {
+            status: format!("Invalid rule request: {}", e),
+            code: 400,
+        }),
+    };
+    respond(request, &client, reply).await
+}
+
+/// Publish or deprecate a tool version and reply with the published versions
+///
+/// Changes are stored in the tool catalog bucket so every instance picks them
+/// up. Deprecating a version notifies each agent still using it.
+async fn handle_tools_request(
+    request: async_nats::service::Request,
+    ctx: HandlerContext,
+    catalog: &ToolCatalog,
+    tool_bucket: &ToolCatalogBucket,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let applied = async {
+        let message = &request.message;
+        let caller = ctx
+            .principals
+            .authenticate(message.headers.as_ref(), &message.payload)?;
+        let tool_request: ToolRequest = serde_json::from_slice(&message.payload)?;
+        if !matches!(tool_request, ToolRequest::List) {
+            ctx.permissions
+                .check(caller.class, AgentPermission::UpdateConfiguration)?;
+        }
+        match tool_request {
+            ToolRequest::Register { definition } => {
+                let (name, version) = (definition.name.clone(), definition.version.clone());
+                catalog.register(definition)?;
+                if let Some(published) = catalog.published(&name, &version) {
+                    tool_bucket.put(&published).await?;
+                }
+            }
+            ToolRequest::Deprecate(cmd) => {
+                let notices = catalog.deprecate(&cmd)?;
+                if let Some(published) = catalog.published(&cmd.tool_name, &cmd.version) {
+                    tool_bucket.put(&published).await?;
+                }
+                for notice in notices {
+                    let agent_id = notice.agent_id;
+                    let id = next_id();
+                    let event = AgentEvent::DeprecatedToolInUse(notice);
+                    if let Err(e) = ctx.event_publisher.publish(agent_id, event, id, id).await {
+                        warn!("Failed to notify agent {} of deprecation: {}", agent_id, e);
+                    }
+                }
+            }
+            ToolRequest::List => {}
+        }
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(catalog.published_tools())
+    }
+    .await
+    .map_err(|e| e.to_string());
+    let reply = match applied {
+        Ok(tools) => {
+            let body = serde_json::json!({ "status": "ok", "tools": tools });
+            Ok(body)
+        }
+        Err(e) => Err(async_nats::service::error::Error {
+            status: format!("Invalid tools request: {}", e),
+            code: 400,
+        }),
+    };
+    respond(request, &client, reply).await
+}
+
+/// Start a batch run and reply with its id
+///
+/// The caller needs permission to send the agent messages. The run goes on
+/// in the background and publishes its progress and completion as events.
+async fn handle_batch_request(
+    request: async_nats::service::Request,
+    ctx: HandlerContext,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let accepted = async {
+        let message = &request.message;
+        let caller = ctx
+            .principals
+            .authenticate(message.headers.as_ref(), &message.payload)?;
+        ctx.permissions
+            .check(caller.class, AgentPermission::SendMessage)?;
+        let batch: BatchRequest = serde_json::from_slice(&message.payload)?;
+        let agent_id = batch.agent_id;
+        let agent = ctx
+            .repository
+            .load(agent_id)
+            .await?
+            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+        let config = batch
+            .model
+            .clone()
+            .or_else(|| agent.model_config().cloned())
+            .ok_or_else(|| format!("Agent {} has no model configured", agent_id))?;
+        let chat = ctx
+            .message_service
+            .router()
+            .registry()
+            .get_adapter(&config.provider)
+            .ok_or_else(|| format!("Provider {} is not registered", config.provider))?;
+
+        let batch_id = next_id();
+        let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut runner = BatchInference::new(chat, ctx.blobs.clone())
+            .with_events(Arc::new(events_tx))
+            .with_moderation(ctx.moderation.clone())
+            .with_budget(batch.budget)
+            .with_batch_id(batch_id)
+            .with_model(config);
+        if let Some(concurrency) = batch.concurrency {
+            runner = runner.with_concurrency(concurrency);
+        }
+        let publisher = ctx.event_publisher.clone();
+        tokio::spawn(async move {
+            while let Some(event) = events_rx.recv().await {
+                if let Err(e) = publisher.publish(agent_id, event, batch_id, batch_id).await {
+                    warn!("Failed to publish progress of batch {}: {}", batch_id, e);
+                }
+            }
+        });
+        tokio::spawn(async move {
+            if let Err(e) = runner.run_file(&agent, &batch.input).await {
+                warn!("Batch {} for agent {} failed: {}", batch_id, agent_id, e);
+            }
+        });
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(batch_id)
+    }
+    .await
+    .map_err(|e| e.to_string());
+    let reply = match accepted {
+        Ok(batch_id) => {
+            let body = serde_json::json!({ "status": "ok", "batch_id": batch_id });
+            Ok(body)
+        }
+        Err(e) => Err(async_nats::service::error::Error {
+            status: format!("Invalid batch request: {}", e),
+            code: 400,
+        }),
+    };
+    respond(request, &client, reply).await
+}
+
+/// Start, stop or list shadow deployments and reply with those running
+///
+/// Shadows are brought up and taken down with the caller's own permissions.
+async fn handle_shadow_request(
+    request: async_nats::service::Request,
+    ctx: HandlerContext,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let applied = async {
+        let message = &request.message;
+        let caller = ctx
+            .principals
+            .authenticate(message.headers.as_ref(), &message.payload)?;
+        let shadow_request: ShadowRequest = serde_json::from_slice(&message.payload)?;
+        let (deployment, commands) = match shadow_request {
+            ShadowRequest::Start {
+                primary,
+                definition,
+                traffic_share,
+            } => {
+                if ctx.shadows.list().iter().any(|d| d.primary_id() == primary) {
+                    return Err(format!("Agent {} is already shadowed", primary).into());
+                }
+                let agent = ctx
+                    .repository
+                    .load(primary)
+                    .await?
+                    .ok_or_else(|| format!("Agent not found: {}", primary))?;
+                let (deployment, commands) = ShadowDeployment::start(&agent, &definition)?;
+                let deployment = deployment.with_traffic_share(traffic_share.unwrap_or(1.0));
+                (Some(deployment), commands)
+            }
+            ShadowRequest::Stop { primary } => {
+                let deployment = ctx
+                    .shadows
+                    .remove(primary)
+                    .ok_or_else(|| format!("Agent {} has no shadow", primary))?;
+                (None, vec![deployment.stop()])
+            }
+            ShadowRequest::List => (None, Vec::new()),
+        };
+        for command in commands {
+            let payload = serde_json::to_vec(&command)?;
+            execute_command(&payload, None, caller.clone(), None, ctx.clone(), &client).await??;
+        }
+        if let Some(deployment) = deployment {
+            ctx.shadows.insert(deployment);
+        }
+        let running: Vec<_> = ctx
+            .shadows
+            .list()
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "primary": d.primary_id(),
+                    "shadow": d.shadow_id(),
+                    "traffic_share": d.traffic_share(),
+                    "pending": d.pending(),
+                })
+            })
+            .collect();
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(running)
+    }
+    .await
+    .map_err(|e| e.to_string());
+    let reply = match applied {
+        Ok(shadows) => {
+            let body = serde_json::json!({ "status": "ok", "shadows": shadows });
+            Ok(body)
+        }
+        Err(e) => Err(async_nats::service::error::Error {
+            status: format!("Invalid shadow request: {}", e),
+            code: 400,
+        }),
+    };
+    respond(request, &client, reply).await
+}
+
+/// Run a conversation command sent to the service endpoint
+///
+/// A person acting in the command must be the person the caller's key is
+/// registered to. Agents and tools act in conversations only through this
+/// service, and timeouts and budget closures are issued by it, so such
+/// commands are refused here.
+async fn handle_conversation_request(
+    request: async_nats::service::Request,
+    ctx: HandlerContext,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let executed = async {
+        let caller = ctx
+            .principals
+            .authenticate(request.message.headers.as_ref(), &request.message.payload)?;
+        let command: ConversationCommand = serde_json::from_slice(&request.message.payload)?;
+        match command.actor() {
+            Some(ParticipantId::Person(person)) => {
+                caller.acting_for(person)?;
+            }
+            Some(actor) => return Err(format!("{} acts only through agent-service", actor).into()),
+            None => return Err("Only agent-service closes conversations without an actor".into()),
+        }
+        execute_conversation_command(command, &ctx).await
+    };
+    let reply = match executed.await {
+        Ok(conversation) => {
+            let body = serde_json::json!({
+                "status": "ok",
+                "conversation_id": conversation.id(),
+                "version": conversation.version(),
+            });
+            Ok(body)
+        }
+        Err(e) => {
+            warn!("Refused conversation command: {}", e);
+            Err(async_nats::service::error::Error {
+                status: format!("Invalid conversation command: {}", e),
+                code: 400,
+            })
+        }
+    };
+    respond(request, &client, reply).await
+}
+
+/// Apply a conversation command to the stored conversation
+///
+/// A transfer is first checked against its target agent, whose context
+/// window the handoff is fitted to.
+async fn execute_conversation_command(
+    command: ConversationCommand,
+    ctx: &HandlerContext,
+) -> Result<Conversation, Box<dyn std::error::Error + Send + Sync>> {
+    let command = match command {
+        ConversationCommand::Transfer(cmd) => {
+            let target = ctx
+                .repository
+                .load(cmd.to)
+                .await?
+                .ok_or_else(|| format!("Agent not found: {}", cmd.to))?;
+            let prepared = ctx
+                .transfers
+                .prepare(cmd.conversation_id, cmd.actor, cmd.from, &target, cmd.handoff)
+                .await?;
+            ConversationCommand::Transfer(prepared)
+        }
+        command => command,
+    };
+    let (conversation, stored) = ctx.conversations.execute(&command).await?;
+    info!(
+        "Conversation {} at version {}: {} event(s)",
+        conversation.id(),
+        conversation.version(),
+        stored.len()
+    );
+    Ok(conversation)
+}
+
+/// Verify an external agent's webhook delivery and apply it to its conversation
+///
+/// The agent is the last token of the ingress subject. A gateway that sent
+/// a request gets `{"status":"ok",...}` or `{"status":"error",...}` back.
+async fn handle_external_delivery(
+    message: async_nats::Message,
+    ingress: &WebhookIngress,
+    ctx: &HandlerContext,
+    client: &async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let applied = async {
+        let agent_id = message
+            .subject
+            .rsplit('.')
+            .next()
+            .and_then(|token| token.parse::<uuid::Uuid>().ok())
+            .map(AgentId::from_uuid)
+            .ok_or_else(|| format!("No agent ID in {}", message.subject))?;
+        let signature = message
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(WEBHOOK_SIGNATURE_HEADER))
+            .map(|value| value.as_str());
+        let command = ingress.ingest(agent_id, signature, &message.payload).await?;
+        execute_conversation_command(command, ctx).await
+    };
+    let body = match applied.await {
+        Ok(conversation) => serde_json::json!({
+            "status": "ok",
+            "conversation_id": conversation.id(),
+            "version": conversation.version(),
+        }),
+        Err(e) => {
+            warn!("Refused external delivery on {}: {}", message.subject, e);
+            serde_json::json!({ "status": "error", "message": e.to_string() })
+        }
+    };
+    if let Some(reply_to) = message.reply {
+        client.publish(reply_to, serde_json::to_vec(&body)?.into()).await?;
+    }
+    Ok(())
+}
+
+/// Reply body for a handled command
+fn command_reply(result: &HandlerResult) -> serde_json::Value {
+    match result {
+        Ok(Some(token)) => serde_json::json!({ "status": "ok", "consistency": token }),
+        Ok(None) => serde_json::json!({ "status": "ok" }),
+        Err(e) => match e.downcast_ref::<EscalationPending>() {
+            Some(pending) => serde_json::json!({
+                "status": "escalated",
+                "escalation_id": pending.escalation_id,
+                "message": e.to_string(),
+            }),
+            None => serde_json::json!({ "status": "error", "message": e.to_string() }),
+        },
+    }
+}
+
+/// Decode and dispatch a command
+///
+/// Fails only when the payload is not a command; handler failures, including
+/// a principal lacking the command's permission, are returned in the inner
+/// result. `principal` is who sent the command, as authenticated from its
+/// signature, or the service itself for commands it issues. With
+/// escalation on, a command lacking a permission is held for the owner's
+/// approval instead, and an approval runs the command it releases.
+///
+/// The payload may be a bare command or a `CommandEnvelope`; the envelope's
+/// metadata is in scope while the command is handled, so every event it
+/// produces carries it, and its deadline bounds the handling.
+async fn execute_command(
+    payload: &[u8],
+    headers: Option<&async_nats::HeaderMap>,
+    principal: AuthenticatedPrincipal,
+    reply: Option<async_nats::Subject>,
+    ctx: HandlerContext,
+    client: &async_nats::Client,
+) -> Result<HandlerResult, Box<dyn std::error::Error + Send + Sync>> {
+    let HandlerContext {
+        repository,
+        event_publisher,
+        message_service,
+        in_flight,
+        stream_resumer,
+        latency_tracker,
+        reasoning_traces,
+        indicators,
+        blobs,
+        size_limits,
+        moderation,
+        permissions,
+        principals: _,
+        escalations,
+        guardrails,
+        retries,
+        conversations,
+        transfers: _,
+        shadows,
+        shadow_copies,
+        commands_seen,
+        clock,
+        ids,
+    } = ctx;
+
+    // Parse command
+    let CommandEnvelope { command, metadata } = command_envelope(payload, headers)?;
+    let caller = principal;
+    let (principal, actor) = (caller.class, caller.actor.clone());
+
+    info!("Received command from {}: {:?}", principal, command);
+
+    let agent_id = command.agent_id();
+    let permission = command.required_permission();
+    let permitted = match &escalations {
+        Some(escalations) => escalations.check(&permissions, agent_id, principal, permission),
+        None => permissions.check(principal, permission),
+    };
+    if let Err(denied) = permitted {
+        warn!("Refused command for agent {}: {}", agent_id, denied);
+        let Some(requested) = escalations.as_ref().and_then(|e| e.escalate(command, denied)) else {
+            return Ok(Err(denied.into()));
+        };
+        let escalation_id = requested.escalation_id;
+        let event = AgentEvent::PermissionEscalationRequested(requested);
+        let correlation_id = next_id();
+        let published = metadata
+            .clone()
+            .scope(event_publisher.publish(agent_id, event, correlation_id, correlation_id))
+            .await;
+        if let Err(e) = published {
+            return Ok(Err(e.into()));
+        }
+        return Ok(Err(Box::new(EscalationPending {
+            escalation_id,
+            denied,
+        })));
+    }
+
+    // Bursts of configuration changes or permission grants are refused or held.
+    // Approving a change the guardrails held grants nothing its issuer lacked,
+    // so it is not counted as a permission grant
+    let rate_limited = match (&command, &escalations) {
+        (AgentCommand::ApproveEscalation(approval), Some(escalations)) => escalations
+            .pending_denial(approval.escalation_id)
+            .is_none_or(|denied| permissions.check(denied.class, denied.permission).is_err()),
+        _ => true,
+    };
+    let verdict = match &guardrails {
+        Some(guardrails) if rate_limited => guardrails.check(&command, &caller),
+        _ => GuardrailVerdict::Allow,
+    };
+    // Counted once the change succeeds
+    let mut change = guardrails
+        .filter(|_| rate_limited)
+        .map(|guardrails| (guardrails, command.clone(), caller.clone()));
+    if let Some(exceeded) = verdict.exceeded().cloned() {
+        warn!("Change rate exceeded for agent {}: over {}", agent_id, exceeded.limit);
+        let held = match verdict {
+            GuardrailVerdict::RequireApproval(_) => escalations.as_ref().and_then(|e| {
+                let change = PermissionDenied {
+                    class: principal,
+                    permission,
+                };
+                e.escalate(command, change)
+            }),
+            _ => None,
+        };
+        let escalation_id = held.as_ref().map(|requested| requested.escalation_id);
+        let mut events = vec![AgentEvent::ChangeRateExceeded(exceeded.clone())];
+        events.extend(held.map(AgentEvent::PermissionEscalationRequested));
+        let correlation_id = next_id();
+        for event in events {
+            let published = metadata
+                .clone()
+                .scope(event_publisher.publish(agent_id, event, correlation_id, correlation_id))
+                .await;
+            if let Err(e) = published {
+                return Ok(Err(e.into()));
+            }
+        }
+        return Ok(Err(Box::new(ChangeRateRefused {
+            exceeded,
+            escalation_id,
+        })));
+    }
 
-    // Save to repository
-    repository.save(&agent, vec![event.clone()], None).await?;
+    // An approval releases the command it was escalated for
+    let (command, principal) = match command {
+        AgentCommand::ApproveEscalation(approval) => {
+            let resumed =
+                resume_escalation(approval, &caller, escalations, &repository, &event_publisher);
+            match metadata.clone().scope(resumed).await {
+                Ok(resumed) => {
+                    if let Some((guardrails, approval, approver)) = change.take() {
+                        guardrails.record(&approval, &approver);
+                    }
+                    (resumed.command, resumed.principal)
+                }
+                Err(e) => return Ok(Err(e)),
+            }
+        }
+        command => (command, principal),
+    };
+
+    // Retries sharing an idempotency key run once; a failed run frees the key
+    let idempotency_key = command_dedupe_key(agent_id, &metadata);
+    if let Some(key) = &idempotency_key {
+        if !commands_seen.first_delivery(COMMANDS_CONSUMER, key).await? {
+            info!("Skipping retried command for agent {}", agent_id);
+            return Ok(Ok(None));
+        }
+    }
+
+    // The envelope's deadline also bounds a message's response
+    let command = match (command, metadata.deadline) {
+        (AgentCommand::SendMessage(mut cmd), Some(deadline)) => {
+            cmd.deadline = Some(deadline.earliest(cmd.deadline));
+            AgentCommand::SendMessage(cmd)
+        }
+        (command, _) => command,
+    };
+
+    // Logs written while handling are attributed to the agent and conversation
+    let span = tracing::info_span!(
+        "command",
+        agent_id = %agent_id,
+        conversation_id = tracing::field::Empty,
+        actor = actor.as_deref(),
+        tenant = metadata.tenant.as_deref(),
+        trace_id = metadata.trace.as_ref().map(TraceContext::trace_id),
+    );
+    if let AgentCommand::SendMessage(SendMessage {
+        conversation_id: Some(conversation_id),
+        ..
+    }) = &command
+    {
+        span.record("conversation_id", tracing::field::display(conversation_id));
+    }
+
+    // Process command based on type; events take their timestamps from `clock`,
+    // new identifiers from `ids` and `performed_by` from `principal`
+    let handled = async move {
+        match command {
+            command @ AgentCommand::ActivateAgent(_) => {
+                let activated = handle_agent_command(command, repository.clone(), event_publisher)
+                    .await;
+                if activated.is_ok() {
+                    tokio::spawn(prewarm_model(agent_id, repository, message_service));
+                }
+                activated
+            }
+            command @ (AgentCommand::DeployAgent(_)
+            | AgentCommand::ConfigureModel(_)
+            | AgentCommand::SuspendAgent(_)
+            | AgentCommand::DecommissionAgent(_)
+            | AgentCommand::UpdateConfiguration(_)
+            | AgentCommand::PlaceLegalHold(_)
+            | AgentCommand::ReleaseLegalHold(_)) => {
+                handle_agent_command(command, repository, event_publisher).await
+            }
+            AgentCommand::ApproveEscalation(cmd) => {
+                Err(format!("Escalation {} cannot approve an approval", cmd.escalation_id).into())
+            }
+            AgentCommand::ReleaseQuarantinedMessage(cmd) => {
+                handle_release_quarantined(cmd, &caller, retries, event_publisher).await
+            }
+            AgentCommand::SendMessage(cmd) => {
+                // A shadowed agent's copy is answered alongside it
+                if let Some(copy) = shadows.mirror(&cmd) {
+                    if let Err(e) = shadow_copies.try_send((copy, caller.clone())) {
+                        warn!("Dropped shadow copy of message {}: {}", cmd.message_id, e);
+                    }
+                }
+                let attempt = cmd.clone();
+                let sent = handle_send_message(
+                    cmd,
+                    repository,
+                    event_publisher.clone(),
+                    message_service,
+                    in_flight,
+                    latency_tracker,
+                    reasoning_traces,
+                    indicators,
+                    blobs,
+                    size_limits,
+                    moderation,
+                    retries.clone(),
+                    conversations,
+                )
+                .await;
+                // A retry that failed before reaching the provider still spends its budget
+                if let Err(e) = &sent {
+                    if !e.is::<AttemptRecorded>() {
+                        let failed = retries.failed_before_send(&attempt, e).await;
+                        record_retry_decision(&event_publisher, &attempt, failed, next_id()).await;
+                    }
+                }
+                sent
+            }
+            AgentCommand::CancelMessage(cmd) => handle_cancel_message(cmd, in_flight).await,
+            AgentCommand::ResumeStream(cmd) => {
+                handle_resume_stream(cmd, stream_resumer, client, reply).await
+            }
+        }
+    };
+    let deadline = metadata.deadline;
+    let scoped = with_clock(clock, with_id_generator(ids, handled));
+    let scoped = metadata.scope(with_principal(principal, scoped));
+    let result = match deadline {
+        Some(deadline) => deadline.scope(scoped).instrument(span).await,
+        None => scoped.instrument(span).await,
+    };
+    if let (Ok(_), Some((guardrails, command, caller))) = (&result, change) {
+        guardrails.record(&command, &caller);
+    }
+    if let (Err(_), Some(key)) = (&result, &idempotency_key) {
+        if let Err(e) = commands_seen.release(COMMANDS_CONSUMER, key).await {
+            warn!("Failed to free idempotency key for agent {}: {}", agent_id, e);
+        }
+    }
+
+    Ok(result)
+}
 
-    // Publish event
-    let correlation_id = uuid::Uuid::now_v7();
+/// Check `caller` is the person approving, and that they own the agent
+fn check_approver(
+    agent: &Agent,
+    approval: &ApproveEscalation,
+    caller: &AuthenticatedPrincipal,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let approver = caller.acting_for(approval.approved_by)?;
+    if agent.person_id() != approver {
+        return Err(format!(
+            "Only the owner of agent {} may approve its escalations",
+            approval.agent_id
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Approve an escalation on the owner's behalf and release its held command
+///
+/// `approved_by` must be the person the caller's key is registered to, and
+/// that person must own the agent; an agent that cannot be loaded refuses.
+async fn resume_escalation(
+    approval: ApproveEscalation,
+    caller: &AuthenticatedPrincipal,
+    escalations: Option<Arc<PermissionEscalations>>,
+    repository: &AgentRepository,
+    event_publisher: &NatsEventPublisher,
+) -> Result<ResumedCommand, Box<dyn std::error::Error + Send + Sync>> {
+    let escalations = escalations.ok_or("Permission escalation is disabled")?;
+    let agent = repository
+        .load(approval.agent_id)
+        .await?
+        .ok_or_else(|| format!("Agent not found: {}", approval.agent_id))?;
+    check_approver(&agent, &approval, caller)?;
+    let resumed = escalations.approve(&approval)?;
+
+    let event = AgentEvent::PermissionEscalationApproved(resumed.approved.clone());
+    let correlation_id = next_id();
     event_publisher
-        .publish(cmd.agent_id, event, correlation_id, correlation_id)
+        .publish(approval.agent_id, event, correlation_id, correlation_id)
         .await?;
 
-    info!("Agent deployed: {} for person {}", cmd.agent_id, cmd.person_id);
-    Ok(())
+    info!(
+        "Escalation {} approved; resuming {} for agent {}",
+        approval.escalation_id, resumed.approved.permission, approval.agent_id
+    );
+    Ok(resumed)
+}
+
+/// PostgreSQL read model from `READ_MODEL_DATABASE_URL`, migrated before use
+#[cfg(feature = "sql")]
+async fn sql_read_model(
+) -> Result<Option<Arc<dyn AgentReadModel>>, Box<dyn std::error::Error + Send + Sync>> {
+    let Ok(url) = std::env::var("READ_MODEL_DATABASE_URL") else {
+        return Ok(None);
+    };
+    let model = cim_domain_agent::read_model::SqlAgentReadModel::connect(&url).await?;
+    model.migrate().await?;
+    info!("Read model: PostgreSQL");
+    Ok(Some(Arc::new(model)))
+}
+
+#[cfg(not(feature = "sql"))]
+async fn sql_read_model(
+) -> Result<Option<Arc<dyn AgentReadModel>>, Box<dyn std::error::Error + Send + Sync>> {
+    if std::env::var("READ_MODEL_DATABASE_URL").is_ok() {
+        warn!("READ_MODEL_DATABASE_URL is set but the `sql` feature is not enabled; ignoring");
+    }
+    Ok(None)
+}
+
+/// GraphQL over NATS on `GRAPHQL_SUBJECT`; returns the hook feeding its subscriptions
+#[cfg(feature = "graphql")]
+fn graphql_service(
+    client: &async_nats::Client,
+    read_model: Arc<dyn AgentReadModel>,
+) -> Option<Box<dyn Fn(EventEnvelope) + Send + Sync>> {
+    use cim_domain_agent::read_model::{build_schema, serve_nats, EventFeed};
+
+    let subject = std::env::var("GRAPHQL_SUBJECT").ok()?;
+    let feed = EventFeed::default();
+    let schema = build_schema(read_model, feed.clone());
+    let client = client.clone();
+    info!("Serving GraphQL on {}", subject);
+    tokio::spawn(async move {
+        if let Err(e) = serve_nats(schema, client, subject).await {
+            warn!("GraphQL service stopped: {}", e);
+        }
+    });
+    Some(Box::new(move |envelope| feed.publish(envelope)))
+}
+
+#[cfg(not(feature = "graphql"))]
+fn graphql_service(
+    _client: &async_nats::Client,
+    _read_model: Arc<dyn AgentReadModel>,
+) -> Option<Box<dyn Fn(EventEnvelope) + Send + Sync>> {
+    if std::env::var("GRAPHQL_SUBJECT").is_ok() {
+        warn!("GRAPHQL_SUBJECT is set but the `graphql` feature is not enabled; ignoring");
+    }
+    None
+}
+
+/// Load every agent the read model knows about
+async fn live_agents(
+    read_model: &dyn AgentReadModel,
+    repository: &AgentRepository,
+) -> Result<Vec<Agent>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut agents = Vec::new();
+    let mut request = PageRequest::first(MAX_PAGE_LIMIT);
+    loop {
+        let page = read_model.list(None, &request).await?;
+        for view in page.items {
+            if let Some(agent) = repository.load(view.agent_id).await? {
+                agents.push(agent);
+            }
+        }
+        match page.next_cursor {
+            Some(cursor) => request = request.with_cursor(cursor),
+            None => return Ok(agents),
+        }
+    }
+}
+
+/// State a query may read
+struct QuerySources {
+    parser: SubjectParser,
+    read_model: Arc<dyn AgentReadModel>,
+    fleet_graph: Arc<AgentGraphProjection>,
+    capability_index: Arc<CapabilityIndex>,
+    reasoning: Arc<ReasoningTraceProjection>,
+    tool_usage: Arc<ToolUsageProjection>,
+    cost_attribution: Arc<CostAttributionProjection>,
+    flagged_messages: Arc<ModerationProjection>,
+    repository: Arc<AgentRepository>,
+    conversation_view: Arc<ConversationProjection>,
+    tool_catalog: Arc<ToolCatalog>,
+    archive: Arc<BlobArchiveStore>,
+    signer: EventSigner,
+}
+
+/// Handle a read model query
+///
+/// A `min_version` wait is bounded by the smaller of the query's `timeout_ms`
+/// and `max_wait`. Describe requests are recognized by subject alone;
+/// capability handshakes arrive on `{domain}.queries.agent.{id}.negotiate`.
+async fn handle_query(
+    message: async_nats::Message,
+    sources: QuerySources,
+    max_wait: Duration,
+    client: async_nats::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let QuerySources {
+        parser,
+        read_model,
+        fleet_graph,
+        capability_index,
+        reasoning,
+        tool_usage,
+        cost_attribution,
+        flagged_messages,
+        repository,
+        conversation_view,
+        tool_catalog,
+        archive,
+        signer,
+    } = sources;
+    let Some(reply_to) = message.reply else {
+        return Err("Query requires a reply subject".into());
+    };
+
+    let query = match parser.parse(message.subject.as_str()) {
+        Ok(ParsedAgentSubject::Negotiate { .. }) => {
+            let response = negotiate(&message.payload, &repository, &signer).await;
+            client
+                .publish(reply_to, serde_json::to_vec(&response)?.into())
+                .await?;
+            return Ok(());
+        }
+        Ok(ParsedAgentSubject::Describe { agent_id }) => Ok(AgentQuery::DescribeAgent { agent_id }),
+        _ => serde_json::from_slice::<AgentQuery>(&message.payload),
+    };
+    let response = match query {
+        Ok(AgentQuery::GetAgent {
+            agent_id,
+            min_version,
+            timeout_ms,
+            as_of,
+            fields,
+        }) => {
+            let timeout = timeout_ms
+                .map(Duration::from_millis)
+                .map_or(max_wait, |t| t.min(max_wait));
+            let view = match as_of {
+                Some(as_of) => read_model.get_as_of(agent_id, &as_of).await,
+                None => {
+                    read_model
+                        .get_consistent(agent_id, min_version, timeout)
+                        .await
+                }
+            };
+            match view {
+                Ok(view) => serde_json::json!({
+                    "status": "ok",
+                    "agent": view.map(|v| fields.apply(&v)),
+                }),
+                Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+            }
+        }
+        Ok(AgentQuery::ListAgents {
+            status,
+            page,
+            as_of,
+            fields,
+        }) => {
+            let listed = match as_of {
+                Some(as_of) => read_model.list_as_of(status, &as_of, &page).await,
+                None => read_model.list(status, &page).await,
+            };
+            match listed {
+                Ok(page) => serde_json::json!({
+                    "status": "ok",
+                    "page": page.map(|v| fields.apply(&v)),
+                }),
+                Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+            }
+        }
+        Ok(AgentQuery::GetFleetGraph) => {
+            serde_json::json!({ "status": "ok", "graph": fleet_graph.graph() })
+        }
+        Ok(AgentQuery::FindCapableAgents {
+            capabilities,
+            min_context_length,
+            requires_streaming,
+        }) => {
+            let mut requirements = CapabilityRequirements::new(capabilities);
+            requirements.min_context_length = min_context_length;
+            requirements.requires_streaming = requires_streaming;
+            let agents = capability_index.agents_meeting(&requirements);
+            serde_json::json!({ "status": "ok", "agents": agents })
+        }
+        Ok(AgentQuery::GetMessages { agent_id, limit }) => {
+            match read_model.messages(agent_id, limit).await {
+                Ok(messages) => serde_json::json!({ "status": "ok", "messages": messages }),
+                Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+            }
+        }
+        Ok(AgentQuery::GetUsage { agent_id }) => match read_model.usage(agent_id).await {
+            Ok(usage) => serde_json::json!({ "status": "ok", "usage": usage }),
+            Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+        },
+        Ok(AgentQuery::GetCostsByTag { key }) => {
+            serde_json::json!({ "status": "ok", "costs": cost_attribution.costs_by(&key) })
+        }
+        Ok(AgentQuery::GetFlaggedMessages { agent_id, tenant }) => {
+            let flagged = flagged_messages.flagged(agent_id, tenant.as_deref());
+            serde_json::json!({ "status": "ok", "flagged": flagged })
+        }
+        Ok(AgentQuery::GetToolUsage { agent_id }) => {
+            let tools: Vec<serde_json::Value> = tool_usage
+                .tool_usage(agent_id)
+                .iter()
+                .map(|stats| {
+                    serde_json::json!({
+                        "stats": stats,
+                        "failure_rate": stats.failure_rate(),
+                        "mean_duration_ms": stats.mean_duration_ms(),
+                        "mean_argument_bytes": stats.mean_argument_bytes(),
+                    })
+                })
+                .collect();
+            serde_json::json!({ "status": "ok", "tools": tools })
+        }
+        Ok(AgentQuery::GetArchivedConversation { conversation_id }) => {
+            match archive.load(conversation_id).await {
+                Ok(Some(conversation)) => {
+                    serde_json::json!({ "status": "ok", "conversation": conversation })
+                }
+                Ok(None) => serde_json::json!({
+                    "status": "error",
+                    "message": format!("Archived conversation not found: {}", conversation_id),
+                }),
+                Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+            }
+        }
+        Ok(AgentQuery::GetDecisionTree { message_id }) => {
+            match reasoning.decision_tree(message_id) {
+                Some(tree) => serde_json::json!({ "status": "ok", "decisions": tree }),
+                None => serde_json::json!({
+                    "status": "error",
+                    "message": format!("No reasoning trace for message {}", message_id),
+                }),
+            }
+        }
+        Ok(AgentQuery::GetUnreadCounts { participant }) => {
+            let unread = conversation_view.unread_counts(&participant);
+            serde_json::json!({ "status": "ok", "unread": unread })
+        }
+        Ok(AgentQuery::GetReceipts {
+            conversation_id,
+            message_id,
+        }) => {
+            let receipts = conversation_view.receipts(conversation_id, message_id);
+            serde_json::json!({ "status": "ok", "receipts": receipts })
+        }
+        Ok(AgentQuery::DescribeAgent { agent_id }) => match repository.load(agent_id).await {
+            Ok(Some(agent)) => {
+                let tools = fleet_graph.tools(agent_id);
+                let definitions = tool_catalog.current_definitions();
+                let description = AgentDescription::from_agent(&agent, tools, &definitions);
+                serde_json::json!({ "status": "ok", "agent": description })
+            }
+            Ok(None) => serde_json::json!({
+                "status": "error",
+                "message": format!("Agent not found: {}", agent_id),
+            }),
+            Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+        },
+        Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+    };
+
+    client
+        .publish(reply_to, serde_json::to_vec(&response)?.into())
+        .await?;
+    Ok(())
+}
+
+/// Send a notification command to the person domain, reporting whether it went
+async fn notify_owner(
+    client: &async_nats::Client,
+    factory: &AgentSubjectFactory,
+    command: NotifyPerson,
+) -> bool {
+    let sent = async {
+        let subject = factory.person_notification_subject(command.person_id)?;
+        client
+            .publish(subject.to_string(), serde_json::to_vec(&command)?.into())
+            .await?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+    };
+    match sent.await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Failed to notify owner {}: {}", command.person_id, e);
+            false
+        }
+    }
+}
+
+/// Answer a capability handshake with a signed statement for the target
+async fn negotiate(
+    payload: &[u8],
+    repository: &AgentRepository,
+    signer: &EventSigner,
+) -> serde_json::Value {
+    let request: NegotiationRequest = match serde_json::from_slice(payload) {
+        Ok(request) => request,
+        Err(e) => return serde_json::json!({ "status": "error", "message": e.to_string() }),
+    };
+    match repository.load(request.target).await {
+        Ok(Some(agent)) => match answer_negotiation(&agent, &request, signer) {
+            Ok(statement) => serde_json::json!({ "status": "ok", "statement": statement }),
+            Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+        },
+        Ok(None) => serde_json::json!({
+            "status": "error",
+            "message": format!("Agent not found: {}", request.target),
+        }),
+        Err(e) => serde_json::json!({ "status": "error", "message": e.to_string() }),
+    }
 }
 
-/// Configure the model for an agent
-async fn handle_configure_model(
-    cmd: ConfigureModel,
+// ============================================================================
+// Command Handlers
+// ============================================================================
+
+/// Decide a lifecycle command on the agent, then save and publish its events
+///
+/// An update that changes nothing saves and publishes nothing.
+async fn handle_agent_command(
+    command: AgentCommand,
     repository: Arc<AgentRepository>,
     event_publisher: Arc<NatsEventPublisher>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Validate
-    cmd.validate()?;
-
-    // Load agent
-    let agent = repository
-        .load(cmd.agent_id)
-        .await?
-        .ok_or_else(|| format!("Agent not found: {}", cmd.agent_id))?;
-
-    // Check if agent can be configured
-    if agent.is_decommissioned() {
-        return Err("Cannot configure model for decommissioned agent".into());
+) -> HandlerResult {
+    let agent_id = command.agent_id();
+    let current = repository.load(agent_id).await?;
+    let expected_version = current.as_ref().map(Agent::version);
+    let agent = current.unwrap_or_default();
+
+    let (new_agent, events) = agent.execute(&command)?;
+    if events.is_empty() {
+        // Nothing was written, so there is nothing to wait for
+        info!("Command changed nothing for agent {}", agent_id);
+        return Ok(None);
     }
 
-    // Create event
-    let event = AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
-        cmd.agent_id,
-        cmd.config.clone(),
-    ));
-
-    // Apply event
-    let new_agent = agent.apply_event(&event)?;
-
-    // Save
-    let version = agent.version();
     repository
-        .save(&new_agent, vec![event.clone()], Some(version))
+        .save(&new_agent, events.clone(), expected_version)
         .await?;
 
-    // Publish
-    let correlation_id = uuid::Uuid::now_v7();
-    event_publisher
-        .publish(cmd.agent_id, event, correlation_id, correlation_id)
-        .await?;
+    let correlation_id = next_id();
+    let names: Vec<&str> = events.iter().map(AgentEvent::event_type_name).collect();
+    let mut sequence = 0;
+    for event in events {
+        sequence = event_publisher
+            .publish_acked(agent_id, event, correlation_id, correlation_id)
+            .await?;
+    }
 
-    info!(
-        "Model configured for agent {}: {} ({})",
-        cmd.agent_id, cmd.config.model_name, cmd.config.provider
-    );
-    Ok(())
+    info!("Agent {}: {} ({:?})", agent_id, names.join(", "), new_agent.status());
+    Ok(Some(ConsistencyToken::new(agent_id, sequence)))
 }
 
-/// Activate an agent (requires model configuration)
-async fn handle_activate_agent(
-    cmd: ActivateAgent,
+/// Load a newly activated agent's model so its first message is not a cold start
+async fn prewarm_model(
+    agent_id: AgentId,
     repository: Arc<AgentRepository>,
-    event_publisher: Arc<NatsEventPublisher>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Load agent
-    let agent = repository
-        .load(cmd.agent_id)
-        .await?
-        .ok_or_else(|| format!("Agent not found: {}", cmd.agent_id))?;
-
-    // Validate activation preconditions
-    if !agent.can_activate() {
-        if agent.model_config().is_none() {
-            return Err("Cannot activate agent without model configuration".into());
-        }
-        if agent.is_decommissioned() {
-            return Err("Cannot activate decommissioned agent".into());
-        }
-        return Err("Agent cannot be activated in current state".into());
+    message_service: Arc<AgentMessageService>,
+) {
+    let prewarmed = match repository.load(agent_id).await {
+        Ok(Some(agent)) => message_service.prewarm(&agent).await.map_err(|e| e.to_string()),
+        Ok(None) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = prewarmed {
+        warn!("Failed to prewarm model for agent {}: {}", agent_id, e);
     }
+}
 
-    // Create event
-    let event = AgentEvent::AgentActivated(AgentActivatedEvent::new(cmd.agent_id));
-
-    // Apply event
-    let new_agent = agent.apply_event(&event)?;
-
-    // Save
-    let version = agent.version();
-    repository
-        .save(&new_agent, vec![event.clone()], Some(version))
-        .await?;
+/// Probe `agent`'s advertised capabilities; save and publish any that failed
+async fn probe_capabilities(
+    prober: &CapabilityProber,
+    agent: &Agent,
+    ctx: &HandlerContext,
+) -> HandlerResult {
+    let Some(chat) = agent
+        .model_config()
+        .and_then(|config| ctx.message_service.router().registry().get_adapter(&config.provider))
+    else {
+        return Ok(None);
+    };
+    let report = prober.probe(chat.as_ref(), agent).await;
+    if report.failed.is_empty() {
+        return Ok(None);
+    }
 
-    // Publish
-    let correlation_id = uuid::Uuid::now_v7();
-    event_publisher
-        .publish(cmd.agent_id, event, correlation_id, correlation_id)
+    let events: Vec<AgentEvent> = report
+        .failed
+        .into_iter()
+        .map(AgentEvent::CapabilityVerificationFailed)
+        .collect();
+    let probed = agent.apply_events(&events)?;
+    ctx.repository
+        .save(&probed, events.clone(), Some(agent.version()))
         .await?;
 
-    info!("Agent activated: {}", cmd.agent_id);
-    Ok(())
+    let correlation_id = next_id();
+    let mut sequence = 0;
+    for event in events {
+        if let AgentEvent::CapabilityVerificationFailed(failed) = &event {
+            warn!(
+                "Agent {} lost {} on {}: {}",
+                agent.id(),
+                failed.capability,
+                failed.model,
+                failed.error
+            );
+        }
+        sequence = ctx
+            .event_publisher
+            .publish_acked(agent.id(), event, correlation_id, correlation_id)
+            .await?;
+    }
+    Ok(Some(ConsistencyToken::new(agent.id(), sequence)))
 }
 
-/// Suspend an agent temporarily
-async fn handle_suspend_agent(
-    cmd: SuspendAgent,
-    repository: Arc<AgentRepository>,
-    event_publisher: Arc<NatsEventPublisher>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Validate
-    cmd.validate()?;
-
-    // Load agent
-    let agent = repository
-        .load(cmd.agent_id)
-        .await?
-        .ok_or_else(|| format!("Agent not found: {}", cmd.agent_id))?;
-
-    // Validate suspension preconditions
-    if !agent.can_suspend() {
-        return Err("Agent cannot be suspended in current state".into());
+/// Reply for a dry run: the events a command would produce and the agent after them
+///
+/// Nothing is saved or published. The command is checked against the
+/// principal's permissions like a real one, but is never escalated. A
+/// message is checked and scored but not answered, so only its
+/// `MessageSent` is listed; an approval lists the approval, not the
+/// command it releases.
+async fn dry_run_command(
+    payload: &[u8],
+    caller: AuthenticatedPrincipal,
+    ctx: HandlerContext,
+) -> serde_json::Value {
+    let simulated = async {
+        let command = CommandEnvelope::decode(payload)?.command;
+        let principal = caller.class;
+        let (agent_id, permission) = (command.agent_id(), command.required_permission());
+        match &ctx.escalations {
+            Some(escalations) => {
+                escalations.check(&ctx.permissions, agent_id, principal, permission)?
+            }
+            None => ctx.permissions.check(principal, permission)?,
+        }
+        let agent = ctx.repository.load(agent_id).await?.unwrap_or_default();
+        let decided = async {
+            match &command {
+                AgentCommand::SendMessage(cmd) => {
+                    let accepted = accept_message(
+                        cmd,
+                        &ctx.repository,
+                        ctx.blobs.as_ref(),
+                        ctx.size_limits,
+                        &ctx.moderation,
+                        &ctx.conversations,
+                    )
+                    .await?;
+                    if accepted.blocked {
+                        let id = cmd.message_id;
+                        return Err(format!("Message {} blocked by content guardrail", id).into());
+                    }
+                    Ok((accepted.agent, vec![accepted.sent]))
+                }
+                AgentCommand::CancelMessage(cmd) => {
+                    cmd.validate()?;
+                    if !ctx.in_flight.is_in_flight(cmd.message_id) {
+                        let id = cmd.message_id;
+                        return Err(format!("No in-flight response for message {}", id).into());
+                    }
+                    Ok((agent, Vec::new()))
+                }
+                AgentCommand::ResumeStream(cmd) => {
+                    // Replaying stored chunks records nothing
+                    cmd.validate()?;
+                    Ok((agent, Vec::new()))
+                }
+                AgentCommand::ApproveEscalation(approval) => {
+                    let escalations =
+                        ctx.escalations.as_ref().ok_or("Permission escalation is disabled")?;
+                    if agent.version() == 0 {
+                        return Err(format!("Agent not found: {}", approval.agent_id).into());
+                    }
+                    check_approver(&agent, approval, &caller)?;
+                    let approved = escalations.preview(approval)?;
+                    Ok((agent, vec![AgentEvent::PermissionEscalationApproved(approved)]))
+                }
+                AgentCommand::ReleaseQuarantinedMessage(cmd) => {
+                    caller.acting_for(cmd.released_by)?;
+                    let released = ctx.retries.preview_release(cmd).await?;
+                    Ok((agent, vec![AgentEvent::QuarantinedMessageReleased(released)]))
+                }
+                command => agent
+                    .execute(command)
+                    .map_err(Box::<dyn std::error::Error + Send + Sync>::from),
+            }
+        };
+        let (clock, ids) = (ctx.clock.clone(), ctx.ids.clone());
+        let scoped = with_clock(clock, with_id_generator(ids, decided));
+        let (agent, events) = with_principal(principal, scoped).await?;
+        info!("Dry run for agent {}: {} event(s)", agent_id, events.len());
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>((agent, events))
+    };
+    match simulated.await {
+        Ok((agent, events)) => serde_json::json!({
+            "status": "ok",
+            "dry_run": true,
+            "events": events,
+            "agent": agent,
+        }),
+        Err(e) => serde_json::json!({
+            "status": "error",
+            "dry_run": true,
+            "message": e.to_string(),
+        }),
     }
+}
 
-    // Create event
-    let event = AgentEvent::AgentSuspended(AgentSuspendedEvent::new(cmd.agent_id, &cmd.reason));
-
-    // Apply and save
-    let new_agent = agent.apply_event(&event)?;
-    let version = agent.version();
-    repository
-        .save(&new_agent, vec![event.clone()], Some(version))
-        .await?;
+/// A message that passed every check before it is recorded
+struct AcceptedMessage {
+    agent: Agent,
+    content: String,
+    conversation: Option<Conversation>,
+    shadow: bool,
+    sent: AgentEvent,
+    blocked: bool,
+}
 
-    // Publish
-    let correlation_id = uuid::Uuid::now_v7();
-    event_publisher
-        .publish(cmd.agent_id, event, correlation_id, correlation_id)
-        .await?;
+/// Check a message the way sending it would, and score it
+///
+/// Loads uploaded content, the agent and its conversation, but records
+/// nothing; a dry run stops here.
+async fn accept_message(
+    cmd: &SendMessage,
+    repository: &AgentRepository,
+    blobs: &dyn BlobStore,
+    size_limits: MessageSizeLimits,
+    moderation: &ModerationStage,
+    conversations: &ConversationRepository,
+) -> Result<AcceptedMessage, Box<dyn std::error::Error + Send + Sync>> {
+    // Validate command
+    cmd.validate()?;
 
-    info!("Agent suspended: {} - {}", cmd.agent_id, cmd.reason);
-    Ok(())
-}
+    // Oversized content arrives uploaded; inline content must fit the limit
+    let content = match &cmd.content_ref {
+        None => {
+            size_limits.check_inline(&cmd.content)?;
+            cmd.content.clone()
+        }
+        Some(reference) => {
+            size_limits.check_content(reference.size_bytes)?;
+            let bytes = blobs
+                .get(reference)
+                .await?
+                .ok_or_else(|| MessageSizeError::ContentMissing(reference.cid.clone()))?;
+            reference.verify(&bytes)?;
+            String::from_utf8(bytes)
+                .map_err(|_| format!("Uploaded content {} is not UTF-8 text", reference.cid))?
+        }
+    };
 
-/// Decommission an agent permanently
-async fn handle_decommission_agent(
-    cmd: DecommissionAgent,
-    repository: Arc<AgentRepository>,
-    event_publisher: Arc<NatsEventPublisher>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Load agent
     let agent = repository
         .load(cmd.agent_id)
         .await?
         .ok_or_else(|| format!("Agent not found: {}", cmd.agent_id))?;
 
-    // Create event
-    let event =
-        AgentEvent::AgentDecommissioned(AgentDecommissionedEvent::new(cmd.agent_id, cmd.reason));
+    // Validate agent is operational (redundant with message_service but provides better error message)
+    if !agent.is_operational() {
+        return Err("Agent is not operational - must be active with model configured".into());
+    }
 
-    // Apply and save
-    let new_agent = agent.apply_event(&event)?;
-    let version = agent.version();
-    repository
-        .save(&new_agent, vec![event.clone()], Some(version))
-        .await?;
+    // Shadow agents answer mirrored copies only, and nothing they say is shown
+    let shadow = agent.feature_flags().is_enabled(FeatureFlags::SHADOW_MODE);
+    if shadow && cmd.shadow_of.is_none() {
+        return Err(format!(
+            "Agent {} runs in shadow mode and answers mirrored messages only",
+            cmd.agent_id
+        )
+        .into());
+    }
 
-    // Publish
-    let correlation_id = uuid::Uuid::now_v7();
-    event_publisher
-        .publish(cmd.agent_id, event, correlation_id, correlation_id)
-        .await?;
+    // In a started conversation the agent must be a participant allowed to
+    // post, and the conversation must have budget left; conversations never
+    // started through the service are not enforced
+    let conversation = match cmd.conversation_id.filter(|_| cmd.shadow_of.is_none()) {
+        Some(conversation_id) => conversations.load(conversation_id).await?,
+        None => None,
+    };
+    if let Some(conversation) = &conversation {
+        if let Some(reason) = conversation.closed() {
+            let id = conversation.id();
+            return Err(format!("Conversation {} is closed ({:?})", id, reason).into());
+        }
+        conversation.authorize(&ParticipantId::Agent(cmd.agent_id), ConversationPermission::Post)?;
+        if let Some(limit) = conversation.budget_exhausted(clock_now()) {
+            return Err(format!(
+                "Conversation {} has spent its {} budget",
+                conversation.id(),
+                limit
+            )
+            .into());
+        }
+    }
 
-    info!("Agent decommissioned: {}", cmd.agent_id);
-    Ok(())
+    // Score the message; strict guardrails refuse anything flagged
+    let strict = agent.feature_flags().is_enabled(FeatureFlags::GUARDRAILS_STRICT);
+    let toxicity = moderation.assess(&content, strict).await;
+    let blocked = toxicity.is_blocked();
+
+    // The MessageSent event to record
+    let sent = AgentEvent::MessageSent(
+        MessageSentEvent::new(cmd.agent_id, cmd.message_id, &cmd.content)
+            .with_content_ref(cmd.content_ref.clone())
+            .with_cost_tags(cmd.cost_tags.clone())
+            .with_toxicity(toxicity)
+            .with_shadow_of(cmd.shadow_of)
+            .with_retry_of(cmd.retry_of),
+    );
+
+    Ok(AcceptedMessage {
+        agent,
+        content,
+        conversation,
+        shadow,
+        sent,
+        blocked,
+    })
 }
 
 /// Send a message to the model (v0.9.2 - uses AgentMessageService)
@@ -519,32 +3480,43 @@ async fn handle_decommission_agent(
 /// 2. Publishes MessageSent event
 /// 3. Routes to appropriate provider via capability matching
 /// 4. Streams response chunks and publishes events
+/// 5. Records first-token latency and publishes SloViolated on budget burn
+/// 6. Publishes the reasoning trace when `reasoning_traces` is not off
+///
+/// Messages and completed responses carry their toxicity score; a message
+/// over the block threshold is recorded and then refused.
 async fn handle_send_message(
     cmd: SendMessage,
     repository: Arc<AgentRepository>,
     event_publisher: Arc<NatsEventPublisher>,
     message_service: Arc<AgentMessageService>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Validate command
-    cmd.validate()?;
-
-    // Load agent
-    let agent = repository
-        .load(cmd.agent_id)
-        .await?
-        .ok_or_else(|| format!("Agent not found: {}", cmd.agent_id))?;
-
-    // Validate agent is operational (redundant with message_service but provides better error message)
-    if !agent.is_operational() {
-        return Err("Agent is not operational - must be active with model configured".into());
-    }
-
-    // Create and publish MessageSent event
-    let message_sent_event = AgentEvent::MessageSent(MessageSentEvent::new(
-        cmd.agent_id,
-        cmd.message_id,
-        &cmd.content,
-    ));
+    in_flight: InFlightStreams,
+    latency_tracker: Arc<FirstTokenLatencyTracker>,
+    reasoning_traces: ReasoningTraces,
+    indicators: Arc<dyn IndicatorSink>,
+    blobs: Arc<dyn BlobStore>,
+    size_limits: MessageSizeLimits,
+    moderation: ModerationStage,
+    retries: Arc<MessageRetryQueue>,
+    conversations: Arc<ConversationRepository>,
+) -> HandlerResult {
+    // Validate, load uploaded content, the agent and its conversation, and score
+    let AcceptedMessage {
+        agent,
+        content,
+        conversation,
+        shadow,
+        sent: message_sent_event,
+        blocked,
+    } = accept_message(
+        &cmd,
+        &repository,
+        blobs.as_ref(),
+        size_limits,
+        &moderation,
+        &conversations,
+    )
+    .await?;
 
     // Note: Message events don't change agent state, but we track them in the event store
     let version = agent.version();
@@ -552,7 +3524,7 @@ async fn handle_send_message(
         .save(&agent, vec![message_sent_event.clone()], Some(version))
         .await?;
 
-    let correlation_id = uuid::Uuid::now_v7();
+    let correlation_id = next_id();
     let causation_id = correlation_id; // MessageSent is the root of this causal chain
     event_publisher
         .publish(cmd.agent_id, message_sent_event, correlation_id, causation_id)
@@ -563,15 +3535,169 @@ async fn handle_send_message(
         cmd.agent_id, cmd.message_id
     );
 
-    // v0.9.2: Use AgentMessageService for capability-based routing
-    let context = vec![ContextMessage::user(&cmd.content)];
+    // Blocked messages stay on record for moderators but are never answered
+    if blocked {
+        warn!("Message {} blocked by the toxicity guardrail", cmd.message_id);
+        return Err(format!("Message {} blocked by content guardrail", cmd.message_id).into());
+    }
+
+    let processing = cmd.conversation_id.filter(|_| !shadow).map(|conversation_id| {
+        ProcessingIndicators::start(cmd.agent_id, conversation_id, cmd.message_id, indicators)
+    });
+
+    // v0.9.2: Use AgentMessageService for capability-based routing; an agent
+    // a conversation was just handed to starts from the handoff
+    let mut context = conversation
+        .as_ref()
+        .and_then(|c| c.pending_handoff(cmd.agent_id))
+        .map(ConversationHandoff::to_context)
+        .unwrap_or_default();
+    context.push(ContextMessage::user(&content));
+    let prompt_tokens = context_tokens(&context);
     let intent = MessageIntent::chat(context);
 
+    let mut provider = agent
+        .model_config()
+        .map(|c| c.provider.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
     let start_time = Instant::now();
 
-    match message_service.send(&agent, intent).await {
-        Ok(mut stream) => {
+    let routed = cmd
+        .cost_tags
+        .clone()
+        .scope(message_service.send_routed(&agent, intent, GenerationParams::default()));
+    let sent = match cmd.deadline {
+        Some(deadline) => deadline.scope(routed).await,
+        None => routed.await,
+    };
+
+    match sent {
+        Ok(RoutedStream {
+            stream,
+            long_context,
+            truncations,
+            best_of,
+            reflection,
+            constraint_enforcements,
+            trace,
+        }) => {
+            if let Some(switch) = long_context {
+                info!(
+                    "Message {} moved from {} to {}: {} tokens over a {} token window",
+                    cmd.message_id,
+                    switch.from.model_name,
+                    switch.to.model_name,
+                    switch.context_tokens,
+                    switch.limit
+                );
+                provider = switch.to.provider.to_string();
+                let fallback_event = AgentEvent::LongContextFallback(
+                    LongContextFallbackEvent::new(
+                        cmd.agent_id,
+                        cmd.message_id,
+                        &switch.from,
+                        &switch.to,
+                        switch.context_tokens,
+                        switch.limit,
+                    ),
+                );
+                event_publisher
+                    .publish(cmd.agent_id, fallback_event, correlation_id, causation_id)
+                    .await?;
+            }
+            for truncation in truncations {
+                warn!(
+                    "Context for message {} truncated by {}: {} messages, {} tokens",
+                    cmd.message_id,
+                    truncation.strategy,
+                    truncation.dropped_messages,
+                    truncation.dropped_tokens
+                );
+                let truncated_event = AgentEvent::ContextTruncated(ContextTruncatedEvent::new(
+                    cmd.agent_id,
+                    cmd.message_id,
+                    truncation,
+                ));
+                event_publisher
+                    .publish(cmd.agent_id, truncated_event, correlation_id, causation_id)
+                    .await?;
+            }
+            if let Some(judged) = best_of {
+                info!(
+                    "Message {} answered by candidate {} of {} ({})",
+                    cmd.message_id,
+                    judged.selected + 1,
+                    judged.candidates.len(),
+                    judged.judge
+                );
+                let selected_event = AgentEvent::CandidateSelected(CandidateSelectedEvent::new(
+                    cmd.agent_id,
+                    cmd.message_id,
+                    judged.judge,
+                    judged.candidates,
+                    judged.selected as u32,
+                    judged.rationale,
+                ));
+                event_publisher
+                    .publish(cmd.agent_id, selected_event, correlation_id, causation_id)
+                    .await?;
+            }
+            if let Some(outcome) = reflection {
+                info!(
+                    "Message {} answered after {} revision(s), ~{} tokens",
+                    cmd.message_id,
+                    outcome.superseded().len(),
+                    outcome.tokens_spent
+                );
+                for draft in outcome.superseded() {
+                    let drafted_event = AgentEvent::ResponseDrafted(ResponseDraftedEvent::new(
+                        cmd.agent_id,
+                        cmd.message_id,
+                        draft.clone(),
+                    ));
+                    event_publisher
+                        .publish(cmd.agent_id, drafted_event, correlation_id, causation_id)
+                        .await?;
+                }
+            }
+            for enforcement in constraint_enforcements {
+                warn!(
+                    "Answer {} to message {} broke {} output constraint(s): {:?}",
+                    enforcement.attempt,
+                    cmd.message_id,
+                    enforcement.violations.len(),
+                    enforcement.action
+                );
+                let enforced_event =
+                    AgentEvent::OutputConstraintEnforced(OutputConstraintEnforcedEvent::new(
+                        cmd.agent_id,
+                        cmd.message_id,
+                        enforcement,
+                    ));
+                event_publisher
+                    .publish(cmd.agent_id, enforced_event, correlation_id, causation_id)
+                    .await?;
+            }
+            if reasoning_traces != ReasoningTraces::Off {
+                let trace_event =
+                    AgentEvent::ReasoningTraceRecorded(ReasoningTraceRecordedEvent::new(
+                        cmd.agent_id,
+                        cmd.message_id,
+                        &trace,
+                        reasoning_traces == ReasoningTraces::Redacted,
+                    ));
+                event_publisher
+                    .publish(cmd.agent_id, trace_event, correlation_id, causation_id)
+                    .await?;
+            }
+
+            let stream = match processing {
+                Some(processing) => processing.track(stream),
+                None => stream,
+            };
+            let mut stream = in_flight.track(cmd.message_id, stream);
             let mut chunk_count: u32 = 0;
+            let mut response_text = String::new();
             let mut last_event_id = causation_id;
             let mut final_finish_reason = FinishReason::Stop;
 
@@ -580,9 +3706,38 @@ async fn handle_send_message(
                 match chunk_result {
                     Ok(chunk) => {
                         let is_final = chunk.is_final;
+
+                        // Time to first chunk feeds the latency SLO
+                        if chunk_count == 0 {
+                            let first_token_ms = start_time.elapsed().as_millis() as u64;
+                            if let Some(violation) =
+                                latency_tracker.record(cmd.agent_id, &provider, first_token_ms)
+                            {
+                                warn!(
+                                    "First-token SLO violated for agent {} on {}: burn rate {:.2}, p95 {}ms",
+                                    cmd.agent_id,
+                                    violation.provider,
+                                    violation.stats.burn_rate,
+                                    violation.stats.p95_ms
+                                );
+                                let slo_event = AgentEvent::SloViolated(SloViolatedEvent::new(
+                                    cmd.agent_id,
+                                    violation.provider,
+                                    violation.threshold_ms,
+                                    violation.stats.burn_rate,
+                                    violation.stats.samples as u32,
+                                    violation.stats.p95_ms,
+                                ));
+                                event_publisher
+                                    .publish(cmd.agent_id, slo_event, correlation_id, causation_id)
+                                    .await?;
+                            }
+                        }
+
                         if let Some(reason) = chunk.finish_reason {
                             final_finish_reason = reason;
                         }
+                        response_text.push_str(&chunk.content);
 
                         // Create and publish chunk event
                         let chunk_event = AgentEvent::ResponseChunkReceived(
@@ -594,19 +3749,57 @@ async fn handle_send_message(
                         );
 
                         // Chain causation: each chunk is caused by the previous event
-                        let this_event_id = uuid::Uuid::now_v7();
+                        let this_event_id = next_id();
                         event_publisher
                             .publish(cmd.agent_id, chunk_event, correlation_id, last_event_id)
                             .await?;
                         last_event_id = this_event_id;
                         chunk_count += 1;
 
+                        // Flag client-side stop-sequence / max-token enforcement
+                        if let Some(enforcement) = chunk.enforcement.clone() {
+                            let enforced_event = AgentEvent::OutputLimitEnforced(
+                                OutputLimitEnforcedEvent::new(
+                                    cmd.agent_id,
+                                    cmd.message_id,
+                                    enforcement,
+                                    chunk.chunk_index,
+                                ),
+                            );
+                            let this_event_id = next_id();
+                            event_publisher
+                                .publish(cmd.agent_id, enforced_event, correlation_id, last_event_id)
+                                .await?;
+                            last_event_id = this_event_id;
+                        }
+
+                        // Cancelled streams end with a marker chunk instead of completing
+                        if is_final && final_finish_reason.is_cancelled() {
+                            let cancelled_event = AgentEvent::ResponseCancelled(
+                                ResponseCancelledEvent::new(cmd.agent_id, cmd.message_id, chunk_count),
+                            );
+                            event_publisher
+                                .publish(cmd.agent_id, cancelled_event, correlation_id, last_event_id)
+                                .await?;
+
+                            info!(
+                                "Response cancelled for message {} after {} chunks",
+                                cmd.message_id, chunk_count
+                            );
+                            break;
+                        }
+
                         // Check if this is the final chunk
                         if is_final {
                             let duration_ms = start_time.elapsed().as_millis() as u64;
 
-                            // Create completion event with usage stats
-                            let token_usage = TokenUsage::default();
+                            // The response has already streamed, so its score only annotates
+                            let toxicity = moderation.assess(&response_text, strict).await;
+
+                            // Reported usage, or an estimate when the provider reports none
+                            let token_usage = chunk.usage.unwrap_or_else(|| {
+                                TokenUsage::new(prompt_tokens, estimate_tokens(&response_text))
+                            });
                             let completed_event = AgentEvent::ResponseCompleted(
                                 ResponseCompletedEvent::new(
                                     cmd.agent_id,
@@ -615,7 +3808,9 @@ async fn handle_send_message(
                                     token_usage,
                                     final_finish_reason,
                                     duration_ms,
-                                ),
+                                )
+                                .with_cost_tags(cmd.cost_tags.clone())
+                                .with_toxicity(toxicity),
                             );
                             event_publisher
                                 .publish(cmd.agent_id, completed_event, correlation_id, last_event_id)
@@ -625,53 +3820,244 @@ async fn handle_send_message(
                                 "Response completed for message {}: {} chunks in {}ms",
                                 cmd.message_id, chunk_count, duration_ms
                             );
+
+                            // The answer counts against the conversation's budget
+                            if let Some(conversation) = &conversation {
+                                let post = PostMessage::new(
+                                    conversation.id(),
+                                    ParticipantId::Agent(cmd.agent_id),
+                                    cmd.message_id,
+                                )
+                                .with_tokens(token_usage.total_tokens.into());
+                                post_to_conversation(&conversations, post).await;
+                            }
                             break;
                         }
                     }
                     Err(e) => {
                         // Publish failure event
-                        let error_type = ResponseErrorType::Unknown;
-                        let recoverable = e.is_recoverable();
-                        let failed_event = AgentEvent::ResponseFailed(
-                            ResponseFailedEvent::new(
-                                cmd.agent_id,
-                                cmd.message_id,
-                                error_type,
-                                e.to_string(),
-                                recoverable,
-                            ),
-                        );
-                        event_publisher
-                            .publish(cmd.agent_id, failed_event, correlation_id, last_event_id)
-                            .await?;
+                        publish_response_failure(
+                            &event_publisher,
+                            &cmd,
+                            &e,
+                            correlation_id,
+                            last_event_id,
+                        )
+                        .await?;
+                        retry_later(&retries, &event_publisher, &cmd, &e, correlation_id).await;
 
                         error!("Response stream error for message {}: {}", cmd.message_id, e);
-                        return Err(format!("Response stream error: {}", e).into());
+                        return Err(Box::new(AttemptRecorded(format!(
+                            "Response stream error: {}",
+                            e
+                        ))));
                     }
                 }
             }
         }
         Err(e) => {
             // Provider routing or execution failed
-            let error_type = ResponseErrorType::Unknown;
-            let recoverable = e.is_recoverable();
-            let failed_event = AgentEvent::ResponseFailed(
-                ResponseFailedEvent::new(
-                    cmd.agent_id,
-                    cmd.message_id,
-                    error_type,
-                    e.to_string(),
-                    recoverable,
-                ),
-            );
-            event_publisher
-                .publish(cmd.agent_id, failed_event, correlation_id, causation_id)
+            if let Some(processing) = processing {
+                processing.fail(e.to_string());
+            }
+            publish_response_failure(&event_publisher, &cmd, &e, correlation_id, causation_id)
                 .await?;
+            retry_later(&retries, &event_publisher, &cmd, &e, correlation_id).await;
 
             error!("Message service error for {}: {}", cmd.message_id, e);
-            return Err(format!("Message service error: {}", e).into());
+            return Err(Box::new(AttemptRecorded(format!("Message service error: {}", e))));
+        }
+    }
+
+    // An answered retry leaves the queue
+    if let Err(e) = retries.succeeded(&cmd).await {
+        warn!("Failed to clear retries of message {}: {}", cmd.message_id, e);
+    }
+    Ok(None)
+}
+
+/// Record an agent's answer in its conversation
+///
+/// Retried when another instance appended first; a failure is only logged,
+/// since the answer was already delivered.
+async fn post_to_conversation(conversations: &ConversationRepository, post: PostMessage) {
+    let command = ConversationCommand::PostMessage(post);
+    for _ in 0..3 {
+        match conversations.execute(&command).await {
+            Ok((conversation, _)) => {
+                if let Some(reason) = conversation.closed() {
+                    info!("Conversation {} closed: {:?}", conversation.id(), reason);
+                }
+                return;
+            }
+            Err(DomainError::ConcurrencyConflict { .. }) => continue,
+            Err(e) => {
+                warn!("Failed to post to conversation {}: {}", command.conversation_id(), e);
+                return;
+            }
         }
     }
+    warn!("Gave up posting to conversation {}: kept conflicting", command.conversation_id());
+}
+
+/// Schedule another attempt at a failed send, or quarantine it
+///
+/// Failing to record the decision is only logged; the send already failed.
+async fn retry_later(
+    retries: &MessageRetryQueue,
+    event_publisher: &NatsEventPublisher,
+    cmd: &SendMessage,
+    error: &ChatError,
+    correlation_id: uuid::Uuid,
+) {
+    let decision = retries.failed(cmd, &FailedAttempt::from(error)).await.map(Some);
+    record_retry_decision(event_publisher, cmd, decision, correlation_id).await;
+}
+
+/// Log and publish what the retry queue decided about a failed attempt
+async fn record_retry_decision(
+    event_publisher: &NatsEventPublisher,
+    cmd: &SendMessage,
+    decision: Result<Option<RetryDecision>, DomainError>,
+    correlation_id: uuid::Uuid,
+) {
+    let decision = match decision {
+        Ok(Some(decision)) => decision,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Failed to schedule a retry of message {}: {}", cmd.retry_key(), e);
+            return;
+        }
+    };
+    match &decision {
+        RetryDecision::Scheduled(s) => info!("Message {} retries at {}", s.message_id, s.retry_at),
+        RetryDecision::Quarantined(q) => {
+            warn!("Message {} quarantined: {}", q.message_id, q.reason)
+        }
+    }
+    let event = decision.into_event();
+    let published = event_publisher
+        .publish(cmd.agent_id, event, correlation_id, correlation_id)
+        .await;
+    if let Err(e) = published {
+        warn!("Failed to publish retry decision for message {}: {}", cmd.message_id, e);
+    }
+}
+
+/// Release a quarantined message; the retry loop sends it again
+///
+/// `released_by` must be the person the caller's key is registered to.
+async fn handle_release_quarantined(
+    cmd: ReleaseQuarantinedMessage,
+    caller: &AuthenticatedPrincipal,
+    retries: Arc<MessageRetryQueue>,
+    event_publisher: Arc<NatsEventPublisher>,
+) -> HandlerResult {
+    caller.acting_for(cmd.released_by)?;
+    let released = retries.release(&cmd).await?;
+    info!("Message {} released from quarantine", cmd.message_id);
+    let correlation_id = next_id();
+    let event = AgentEvent::QuarantinedMessageReleased(released);
+    event_publisher
+        .publish(cmd.agent_id, event, correlation_id, correlation_id)
+        .await?;
+    Ok(None)
+}
+
+/// Publish `ResponseFailed` for a send that ended in `error`
+///
+/// A missed deadline is also recorded as `DeadlineExceeded`, naming the
+/// stage that ran out of time.
+async fn publish_response_failure(
+    event_publisher: &NatsEventPublisher,
+    cmd: &SendMessage,
+    error: &ChatError,
+    correlation_id: uuid::Uuid,
+    causation_id: uuid::Uuid,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let error_type = match (error, error.category()) {
+        (ChatError::DeadlineExceeded(_), _) => ResponseErrorType::DeadlineExceeded,
+        (ChatError::ModelNotAvailable(_), _) => ResponseErrorType::ModelUnavailable,
+        (_, ErrorCategory::Transient) => ResponseErrorType::NetworkError,
+        (_, ErrorCategory::RateLimited) => ResponseErrorType::RateLimit,
+        (_, ErrorCategory::Auth) => ResponseErrorType::AuthenticationError,
+        (_, ErrorCategory::InvalidRequest) => ResponseErrorType::InvalidRequest,
+        (_, ErrorCategory::Timeout) => ResponseErrorType::Timeout,
+        (_, ErrorCategory::ProviderBug | ErrorCategory::Cancelled) => ResponseErrorType::Unknown,
+    };
+    let failed_event = AgentEvent::ResponseFailed(ResponseFailedEvent::new(
+        cmd.agent_id,
+        cmd.message_id,
+        error_type,
+        error.to_string(),
+        error.is_recoverable(),
+    ));
+    event_publisher
+        .publish(cmd.agent_id, failed_event, correlation_id, causation_id)
+        .await?;
 
+    if let (ChatError::DeadlineExceeded(stage), Some(deadline)) = (error, cmd.deadline) {
+        warn!("Message {} missed its deadline during {}", cmd.message_id, stage);
+        let exceeded_event = AgentEvent::DeadlineExceeded(DeadlineExceededEvent::new(
+            cmd.agent_id,
+            Some(cmd.message_id),
+            deadline.expires_at,
+            stage.as_str(),
+        ));
+        event_publisher
+            .publish(cmd.agent_id, exceeded_event, correlation_id, causation_id)
+            .await?;
+    }
     Ok(())
 }
+
+/// Cancel the in-flight response to a message
+///
+/// Aborting the tracked stream makes `handle_send_message` observe a final
+/// `Cancelled` chunk, which it finalizes with a `ResponseCancelled` event.
+async fn handle_cancel_message(
+    cmd: CancelMessage,
+    in_flight: InFlightStreams,
+) -> HandlerResult {
+    cmd.validate()?;
+
+    if !in_flight.cancel(cmd.message_id) {
+        return Err(format!("No in-flight response for message {}", cmd.message_id).into());
+    }
+
+    info!("Cancelled response for message {}", cmd.message_id);
+    Ok(None)
+}
+
+/// Replay a response to the requester's inbox, then follow it live
+///
+/// Each chunk is published to the reply subject as JSON; the usual status
+/// reply follows once the response has ended.
+async fn handle_resume_stream(
+    cmd: ResumeStream,
+    stream_resumer: Arc<NatsStreamResumer>,
+    client: &async_nats::Client,
+    reply_to: Option<async_nats::Subject>,
+) -> HandlerResult {
+    cmd.validate()?;
+
+    let reply_to = reply_to.ok_or("ResumeStream requires a reply subject")?;
+    let mut chunks = stream_resumer
+        .resume(cmd.agent_id, cmd.message_id, cmd.from_index)
+        .await?;
+
+    let mut replayed: u32 = 0;
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        client
+            .publish(reply_to.clone(), serde_json::to_vec(&chunk)?.into())
+            .await?;
+        replayed += 1;
+    }
+
+    info!(
+        "Resumed message {} from chunk {}: {} chunks delivered",
+        cmd.message_id, cmd.from_index, replayed
+    );
+    Ok(None)
+}
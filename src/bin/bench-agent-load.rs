@@ -0,0 +1,280 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Load-test harness for the agent command pipeline
+//!
+//! Drives a running `agent-service` through NATS exactly as clients do:
+//! deploys synthetic agents bound to the mock provider, then runs
+//! concurrent conversations and reports throughput and latency
+//! distributions. Use the numbers to size JetStream and service hosts.
+//!
+//! ```text
+//! bench-agent-load ──request──> {domain}.to.{AGENT_NAME}.chat.bench ──> agent-service
+//!        │                                                                  │
+//!        └──subscribe── {domain}.events.agent.{id}.message.{mid}.chunk.0 <──┘
+//! ```
+//!
+//! Two latencies are measured per message:
+//! - **first chunk**: request sent → chunk 0 event observed
+//! - **round trip**: request sent → command reply (response fully published)
+//!
+//! # Environment Variables
+//!
+//! - `NATS_URL` - NATS server URL (default: nats://localhost:4222); credentials
+//!   and TLS are read as described on `NatsConnectionBuilder::from_env`
+//! - `AGENT_NAME` - Name of the target agent-service inbox (default: bench)
+//! - `BENCH_AGENTS` - Synthetic agents to deploy (default: 10)
+//! - `BENCH_CONVERSATIONS` - Concurrent conversations (default: 50)
+//! - `BENCH_MESSAGES` - Messages per conversation (default: 10)
+//!
+//! # Example
+//!
+//! ```bash
+//! AGENT_NAME=bench AGENT_ID=$(uuidgen) CAPABILITY_CLUSTER=conceptual-analysis \
+//!   cargo run --release --bin agent-service &
+//! AGENT_NAME=bench BENCH_AGENTS=20 BENCH_CONVERSATIONS=200 \
+//!   cargo run --release --bin bench-agent-load
+//! ```
+
+use cim_domain_agent::{
+    commands::*,
+    infrastructure::{AgentSubjectFactory, NatsConnectionBuilder},
+    value_objects::{AgentId, ContextMessage, ModelConfig, PersonId},
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+type BenchResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Read an environment variable, falling back to `default` when unset or invalid
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Latency samples for one measurement
+#[derive(Debug, Default)]
+struct Samples {
+    values: Vec<Duration>,
+}
+
+impl Samples {
+    fn extend(&mut self, other: Samples) {
+        self.values.extend(other.values);
+    }
+
+    fn percentile(sorted: &[Duration], p: f64) -> Duration {
+        if sorted.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = ((sorted.len() as f64) * p).ceil() as usize;
+        sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+    }
+
+    fn report(&self, label: &str) {
+        let mut sorted = self.values.clone();
+        sorted.sort_unstable();
+        let mean = if sorted.is_empty() {
+            Duration::ZERO
+        } else {
+            sorted.iter().sum::<Duration>() / sorted.len() as u32
+        };
+        println!(
+            "{:<12} n={:<7} mean={:>8.1?} p50={:>8.1?} p90={:>8.1?} p99={:>8.1?} max={:>8.1?}",
+            label,
+            sorted.len(),
+            mean,
+            Self::percentile(&sorted, 0.50),
+            Self::percentile(&sorted, 0.90),
+            Self::percentile(&sorted, 0.99),
+            sorted.last().copied().unwrap_or_default(),
+        );
+    }
+}
+
+/// Per-conversation results
+#[derive(Debug, Default)]
+struct ConversationStats {
+    first_chunk: Samples,
+    round_trip: Samples,
+    errors: u64,
+}
+
+/// Send a command and fail unless the service replies `{"status":"ok"}`
+async fn send_command(
+    client: &async_nats::Client,
+    subject: &str,
+    command: &AgentCommand,
+) -> BenchResult<()> {
+    let reply = client
+        .request(subject.to_string(), serde_json::to_vec(command)?.into())
+        .await?;
+    let body: serde_json::Value = serde_json::from_slice(&reply.payload)?;
+    match body["status"].as_str() {
+        Some("ok") => Ok(()),
+        _ => Err(format!("Command rejected: {}", body["message"]).into()),
+    }
+}
+
+/// Deploy, configure (mock provider) and activate one synthetic agent
+async fn deploy_agent(
+    client: &async_nats::Client,
+    subject: &str,
+    index: usize,
+) -> BenchResult<AgentId> {
+    let deploy = DeployAgent::new(PersonId::new(), format!("bench-agent-{}", index))
+        .with_description("Synthetic agent for load testing");
+    let agent_id = deploy.agent_id;
+
+    send_command(client, subject, &AgentCommand::DeployAgent(deploy)).await?;
+    send_command(
+        client,
+        subject,
+        &AgentCommand::ConfigureModel(ConfigureModel::new(agent_id, ModelConfig::mock())),
+    )
+    .await?;
+    send_command(
+        client,
+        subject,
+        &AgentCommand::ActivateAgent(ActivateAgent::new(agent_id)),
+    )
+    .await?;
+
+    Ok(agent_id)
+}
+
+/// Send one message and measure (first chunk, round trip)
+async fn measure_message(
+    client: &async_nats::Client,
+    subject: &str,
+    factory: &AgentSubjectFactory,
+    cmd: SendMessage,
+) -> BenchResult<(Duration, Duration)> {
+    // Chunk indexes are renumbered from zero, so chunk 0 is always the first
+    let first_chunk_subject = factory.response_chunk_event(cmd.agent_id, cmd.message_id, 0)?;
+    let mut first_chunks = client.subscribe(first_chunk_subject.to_string()).await?;
+
+    let command = AgentCommand::SendMessage(cmd);
+    let start = Instant::now();
+    let request = send_command(client, subject, &command);
+    let first_chunk = async {
+        tokio::time::timeout(Duration::from_secs(30), first_chunks.next())
+            .await
+            .ok()
+            .flatten()
+            .map(|_| start.elapsed())
+    };
+
+    let (reply, first_chunk) = tokio::join!(request, first_chunk);
+    reply?;
+    let first_chunk = first_chunk.ok_or("No response chunk observed")?;
+    Ok((first_chunk, start.elapsed()))
+}
+
+/// Run one conversation of `messages` turns against `agent_id`
+async fn run_conversation(
+    client: async_nats::Client,
+    subject: Arc<String>,
+    factory: Arc<AgentSubjectFactory>,
+    agent_id: AgentId,
+    conversation: usize,
+    messages: usize,
+) -> ConversationStats {
+    let mut stats = ConversationStats::default();
+    let mut context: Vec<ContextMessage> = Vec::new();
+
+    for turn in 0..messages {
+        let content = format!("conversation {} turn {}: summarize the plan", conversation, turn);
+        let cmd = SendMessage::new(agent_id, &content).with_context(context.clone());
+
+        match measure_message(&client, &subject, &factory, cmd).await {
+            Ok((first_chunk, round_trip)) => {
+                stats.first_chunk.values.push(first_chunk);
+                stats.round_trip.values.push(round_trip);
+            }
+            Err(e) => {
+                stats.errors += 1;
+                error!("Conversation {} turn {} failed: {}", conversation, turn, e);
+            }
+        }
+
+        context.push(ContextMessage::user(content));
+    }
+
+    stats
+}
+
+#[tokio::main]
+async fn main() -> BenchResult<()> {
+    tracing_subscriber::fmt::init();
+
+    let connection = NatsConnectionBuilder::from_env()?.with_name("bench-agent-load");
+    let nats_url = connection.urls().to_string();
+    let agent_name = std::env::var("AGENT_NAME").unwrap_or_else(|_| "bench".to_string());
+    let agent_count: usize = env_or("BENCH_AGENTS", 10).max(1);
+    let conversations: usize = env_or("BENCH_CONVERSATIONS", 50);
+    let messages: usize = env_or("BENCH_MESSAGES", 10);
+
+    let client = connection.connect().await?;
+    let factory = Arc::new(AgentSubjectFactory::default());
+    let subject = Arc::new(factory.agent_chat(&agent_name, "bench")?.to_string());
+    info!("Connected to {}; driving agent-service inbox {}", nats_url, subject);
+
+    // Phase 1: deploy synthetic agents
+    let deploy_start = Instant::now();
+    let mut agents = Vec::with_capacity(agent_count);
+    for index in 0..agent_count {
+        agents.push(deploy_agent(&client, &subject, index).await?);
+    }
+    let deploy_elapsed = deploy_start.elapsed();
+    info!("Deployed {} agents in {:.2?}", agent_count, deploy_elapsed);
+
+    // Phase 2: concurrent conversations
+    let run_start = Instant::now();
+    let handles: Vec<_> = (0..conversations)
+        .map(|conversation| {
+            tokio::spawn(run_conversation(
+                client.clone(),
+                subject.clone(),
+                factory.clone(),
+                agents[conversation % agents.len()],
+                conversation,
+                messages,
+            ))
+        })
+        .collect();
+
+    let mut first_chunk = Samples::default();
+    let mut round_trip = Samples::default();
+    let mut errors = 0u64;
+    for handle in handles {
+        let stats = handle.await?;
+        first_chunk.extend(stats.first_chunk);
+        round_trip.extend(stats.round_trip);
+        errors += stats.errors;
+    }
+    let run_elapsed = run_start.elapsed();
+
+    let completed = round_trip.values.len();
+    println!();
+    println!("agents={} conversations={} messages/conversation={}", agent_count, conversations, messages);
+    println!(
+        "deploy: {:.2?} ({:.1} agents/s)",
+        deploy_elapsed,
+        agent_count as f64 / deploy_elapsed.as_secs_f64()
+    );
+    println!(
+        "run:    {:.2?} ({:.1} msg/s, {} ok, {} errors)",
+        run_elapsed,
+        completed as f64 / run_elapsed.as_secs_f64(),
+        completed,
+        errors
+    );
+    first_chunk.report("first chunk");
+    round_trip.report("round trip");
+
+    Ok(())
+}
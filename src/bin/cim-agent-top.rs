@@ -0,0 +1,436 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Terminal admin console for a running agent fleet
+//!
+//! A `top`-style view over the agent read model and event stream. Every
+//! piece of data comes from the same NATS APIs clients use: read model
+//! queries for the agent list, usage and conversations, the event stream
+//! for live activity, and commands for suspend/activate.
+//!
+//! ```text
+//! ┌ Agents ─────────────────────────────────────────────────────────────┐
+//! │ NAME        STATUS     MODEL          MSG/MIN  DONE  FAILED  TOKENS │
+//! │>sage        ACTIVE     llama3.1:8b        4.0    31       2   18204 │
+//! │ ddd-expert  SUSPENDED  llama3.1:8b        0.0    12       0    6110 │
+//! └─────────────────────────────────────────────────────────────────────┘
+//! ┌ Recent events ─────────────┐┌ Conversation ─────────────────────────┐
+//! │ 12:01:07 response_completed││ 12:01:05 [completed] Summarize the... │
+//! └────────────────────────────┘└───────────────────────────────────────┘
+//! ```
+//!
+//! # Key Bindings
+//!
+//! - `↑`/`↓` (`k`/`j`) - select agent
+//! - `s` - suspend the selected agent
+//! - `a` - activate the selected agent
+//! - `r` - refresh now
+//! - `q` / `Esc` - quit
+//!
+//! # Environment Variables
+//!
+//! - `NATS_URL` - NATS server URL (default: nats://localhost:4222); credentials
+//!   and TLS are read as described on `NatsConnectionBuilder::from_env`
+//! - `AGENT_NAME` - agent-service inbox that receives commands (default: agent)
+//! - `TOP_REFRESH_MS` - Query refresh interval (default: 1000)
+//!
+//! # Example
+//!
+//! ```bash
+//! AGENT_NAME=sage cargo run --features tui --bin cim-agent-top
+//! ```
+
+use cim_domain_agent::{
+    commands::*,
+    infrastructure::{decode_envelope, AgentSubjectFactory, EventEnvelope, NatsConnectionBuilder},
+    read_model::{
+        AgentQuery, AgentView, MessageView, Page, PageRequest, UsageView, MAX_PAGE_LIMIT,
+    },
+    value_objects::{AgentId, AgentStatus},
+};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind};
+use futures::StreamExt;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table, TableState},
+    DefaultTerminal, Frame,
+};
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+type TopResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Events kept per agent for the drill-down pane
+const RECENT_EVENTS: usize = 50;
+
+/// Messages fetched for the conversation pane
+const CONVERSATION_LIMIT: usize = 20;
+
+/// Window over which throughput is measured
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Read an environment variable, falling back to `default` when unset or invalid
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Live activity for one agent, built from the event stream
+#[derive(Debug, Default)]
+struct Activity {
+    recent: VecDeque<EventEnvelope>,
+    messages: VecDeque<Instant>,
+}
+
+impl Activity {
+    fn record(&mut self, envelope: EventEnvelope) {
+        if envelope.event.event_type_name() == "message_sent" {
+            self.messages.push_back(Instant::now());
+        }
+        if self.recent.len() == RECENT_EVENTS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(envelope);
+    }
+
+    /// Messages per minute over the throughput window
+    fn throughput(&mut self) -> f64 {
+        while self
+            .messages
+            .front()
+            .is_some_and(|t| t.elapsed() > THROUGHPUT_WINDOW)
+        {
+            self.messages.pop_front();
+        }
+        self.messages.len() as f64 * 60.0 / THROUGHPUT_WINDOW.as_secs_f64()
+    }
+}
+
+/// Everything shown on screen
+struct App {
+    client: async_nats::Client,
+    factory: AgentSubjectFactory,
+    command_subject: String,
+    agents: Vec<AgentView>,
+    usage: HashMap<AgentId, UsageView>,
+    activity: HashMap<AgentId, Activity>,
+    conversation: Vec<MessageView>,
+    table: TableState,
+    status_line: String,
+}
+
+impl App {
+    fn selected(&self) -> Option<&AgentView> {
+        self.table.selected().and_then(|i| self.agents.get(i))
+    }
+
+    /// Send a query and extract `field` from an `{"status":"ok"}` reply
+    async fn query<T: DeserializeOwned>(
+        &self,
+        query: &AgentQuery,
+        name: &str,
+        field: &str,
+    ) -> TopResult<T> {
+        let subject = self.factory.query_subject(name)?.to_string();
+        let reply = self
+            .client
+            .request(subject, serde_json::to_vec(query)?.into())
+            .await?;
+        let mut body: serde_json::Value = serde_json::from_slice(&reply.payload)?;
+        match body["status"].as_str() {
+            Some("ok") => Ok(serde_json::from_value(body[field].take())?),
+            _ => Err(format!("Query failed: {}", body["message"]).into()),
+        }
+    }
+
+    /// Reload the agent list, usage and the selected agent's conversation
+    async fn refresh(&mut self) -> TopResult<()> {
+        let selected_id = self.selected().map(|a| a.agent_id);
+
+        let mut agents = Vec::new();
+        let mut request = PageRequest::first(MAX_PAGE_LIMIT);
+        loop {
+            let query = AgentQuery::list_agents(None, request.clone());
+            let page: Page<AgentView> = self.query(&query, "list_agents", "page").await?;
+            agents.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => request = request.with_cursor(cursor),
+                None => break,
+            }
+        }
+        agents.retain(|a| a.status != AgentStatus::Decommissioned);
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for agent in &agents {
+            let query = AgentQuery::GetUsage {
+                agent_id: agent.agent_id,
+            };
+            let usage: UsageView = self.query(&query, "get_usage", "usage").await?;
+            self.usage.insert(agent.agent_id, usage);
+        }
+
+        let index = selected_id
+            .and_then(|id| agents.iter().position(|a| a.agent_id == id))
+            .or(if agents.is_empty() { None } else { Some(0) });
+        self.agents = agents;
+        self.table.select(index);
+
+        self.conversation = match self.selected().map(|a| a.agent_id) {
+            Some(agent_id) => {
+                let query = AgentQuery::GetMessages {
+                    agent_id,
+                    limit: CONVERSATION_LIMIT,
+                };
+                self.query(&query, "get_messages", "messages").await?
+            }
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+
+    /// Suspend or activate the selected agent through the command inbox
+    async fn send_lifecycle(&mut self, suspend: bool) -> TopResult<()> {
+        let Some(agent) = self.selected() else {
+            return Ok(());
+        };
+        let (command, verb) = if suspend {
+            let cmd = SuspendAgent::new(agent.agent_id, "Suspended from cim-agent-top");
+            (AgentCommand::SuspendAgent(cmd), "Suspended")
+        } else {
+            let cmd = ActivateAgent::new(agent.agent_id);
+            (AgentCommand::ActivateAgent(cmd), "Activated")
+        };
+        let name = agent.name.clone();
+
+        let payload = serde_json::to_vec(&command)?;
+        let reply = self
+            .client
+            .request(self.command_subject.clone(), payload.into())
+            .await?;
+        let body: serde_json::Value = serde_json::from_slice(&reply.payload)?;
+        self.status_line = match body["status"].as_str() {
+            Some("ok") => format!("{} {}", verb, name),
+            _ => format!("Command rejected: {}", body["message"]),
+        };
+        Ok(())
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.agents.is_empty() {
+            return;
+        }
+        let last = self.agents.len() as isize - 1;
+        let current = self.table.selected().unwrap_or(0) as isize;
+        self.table.select(Some((current + delta).clamp(0, last) as usize));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(45),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rows[1]);
+
+        // Agent table
+        let header = Row::new(["NAME", "STATUS", "MODEL", "MSG/MIN", "DONE", "FAILED", "TOKENS"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let table_rows: Vec<Row> = self
+            .agents
+            .iter()
+            .map(|agent| {
+                let usage = self
+                    .usage
+                    .get(&agent.agent_id)
+                    .copied()
+                    .unwrap_or_else(|| UsageView::empty(agent.agent_id));
+                let throughput = self
+                    .activity
+                    .get_mut(&agent.agent_id)
+                    .map_or(0.0, Activity::throughput);
+                let failed_style = if usage.responses_failed > 0 {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    Cell::from(agent.name.clone()),
+                    Cell::from(agent.status.code()).style(status_style(agent.status)),
+                    Cell::from(agent.model_name.clone().unwrap_or_else(|| "-".to_string())),
+                    Cell::from(format!("{:.1}", throughput)),
+                    Cell::from(usage.responses_completed.to_string()),
+                    Cell::from(usage.responses_failed.to_string()).style(failed_style),
+                    Cell::from(usage.total_tokens().to_string()),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            table_rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(14),
+                Constraint::Min(16),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(" Agents "))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, rows[0], &mut self.table);
+
+        // Recent events for the selected agent
+        let selected_id = self.selected().map(|a| a.agent_id);
+        let events: Vec<ListItem> = selected_id
+            .and_then(|id| self.activity.get(&id))
+            .map(|activity| {
+                activity
+                    .recent
+                    .iter()
+                    .rev()
+                    .map(|e| {
+                        ListItem::new(format!(
+                            "{} #{} {}",
+                            e.timestamp.format("%H:%M:%S"),
+                            e.sequence,
+                            e.event.event_type_name()
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        frame.render_widget(
+            List::new(events)
+                .block(Block::default().borders(Borders::ALL).title(" Recent events ")),
+            panes[0],
+        );
+
+        // Current conversation for the selected agent
+        let messages: Vec<ListItem> = self
+            .conversation
+            .iter()
+            .map(|m| {
+                ListItem::new(Line::from(format!(
+                    "{} [{:?}] {}",
+                    m.sent_at.format("%H:%M:%S"),
+                    m.status,
+                    m.content.lines().next().unwrap_or_default()
+                )))
+            })
+            .collect();
+        frame.render_widget(
+            List::new(messages)
+                .block(Block::default().borders(Borders::ALL).title(" Conversation ")),
+            panes[1],
+        );
+
+        let help = "↑/↓ select  s suspend  a activate  r refresh  q quit";
+        let footer = if self.status_line.is_empty() {
+            help.to_string()
+        } else {
+            format!("{}  |  {}", self.status_line, help)
+        };
+        frame.render_widget(Paragraph::new(footer), rows[2]);
+    }
+}
+
+fn status_style(status: AgentStatus) -> Style {
+    let color = match status {
+        AgentStatus::Active => Color::Green,
+        AgentStatus::Suspended => Color::Yellow,
+        AgentStatus::Offline => Color::Red,
+        AgentStatus::Deployed | AgentStatus::Decommissioned => Color::Gray,
+    };
+    Style::default().fg(color)
+}
+
+async fn run(terminal: &mut DefaultTerminal, mut app: App) -> TopResult<()> {
+    let events_pattern = app.factory.all_events_pattern()?;
+    let mut events = app.client.subscribe(events_pattern.to_string()).await?;
+    let mut keys = EventStream::new();
+    let refresh_every = Duration::from_millis(env_or("TOP_REFRESH_MS", 1000));
+    let mut refresh = tokio::time::interval(refresh_every);
+
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        tokio::select! {
+            _ = refresh.tick() => {
+                if let Err(e) = app.refresh().await {
+                    app.status_line = e.to_string();
+                }
+            }
+            Some(message) = events.next() => {
+                if let Ok(envelope) = decode_envelope(&message.payload) {
+                    app.activity.entry(envelope.aggregate_id).or_default().record(envelope);
+                }
+            }
+            Some(key) = keys.next() => {
+                let Event::Key(key) = key? else { continue };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let outcome = match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.move_selection(-1);
+                        refresh.reset_immediately();
+                        Ok(())
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.move_selection(1);
+                        refresh.reset_immediately();
+                        Ok(())
+                    }
+                    KeyCode::Char('s') => app.send_lifecycle(true).await,
+                    KeyCode::Char('a') => app.send_lifecycle(false).await,
+                    KeyCode::Char('r') => app.refresh().await,
+                    _ => Ok(()),
+                };
+                if let Err(e) = outcome {
+                    app.status_line = e.to_string();
+                }
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> TopResult<()> {
+    let connection = NatsConnectionBuilder::from_env()?.with_name("cim-agent-top");
+    let nats_url = connection.urls().to_string();
+    let agent_name = std::env::var("AGENT_NAME").unwrap_or_else(|_| "agent".to_string());
+
+    let client = connection.connect().await?;
+    let factory = AgentSubjectFactory::default();
+    let command_subject = factory.agent_chat(&agent_name, "admin")?.to_string();
+
+    let app = App {
+        client,
+        factory,
+        command_subject,
+        agents: Vec::new(),
+        usage: HashMap::new(),
+        activity: HashMap::new(),
+        conversation: Vec::new(),
+        table: TableState::default(),
+        status_line: format!("Connected to {}", nats_url),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, app).await;
+    ratatui::restore();
+    result
+}
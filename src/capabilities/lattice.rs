@@ -23,6 +23,7 @@
 //! - Absorption: A ∧ (A ∨ B) = A
 //! - Identity: A ∧ ⊤ = A, A ∨ ⊥ = A
 
+use crate::value_objects::ProviderType;
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
@@ -206,6 +207,17 @@ impl ProviderCapabilities {
         }
     }
 
+    /// Capability preset for a provider type
+    pub fn for_provider(provider: ProviderType) -> Self {
+        match provider {
+            ProviderType::OpenAI => Self::openai_gpt4(),
+            ProviderType::Anthropic => Self::anthropic_claude(),
+            ProviderType::Ollama => Self::ollama(),
+            ProviderType::Gemini => Self::google_gemini(),
+            ProviderType::Mock => Self::mock(),
+        }
+    }
+
     /// Create capabilities for OpenAI GPT-4
     pub fn openai_gpt4() -> Self {
         Self {
@@ -231,6 +243,20 @@ impl ProviderCapabilities {
         }
     }
 
+    /// Create capabilities for Google Gemini 1.5
+    pub fn google_gemini() -> Self {
+        Self {
+            provider_name: "google-gemini".to_string(),
+            capabilities: RuntimeCapabilities::ADVANCED_CHAT
+                | RuntimeCapabilities::VISION
+                | RuntimeCapabilities::LONG_CONTEXT
+                | RuntimeCapabilities::EMBEDDINGS
+                | RuntimeCapabilities::AUDIO_INPUT,
+            max_context_length: Some(1_048_576),
+            streaming_default: true,
+        }
+    }
+
     /// Create capabilities for Ollama (local models)
     pub fn ollama() -> Self {
         Self {
@@ -0,0 +1,479 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Commands for the Conversation aggregate
+//!
+//! Every command names the participant issuing it; the aggregate checks
+//! that participant's role before producing events.
+
+use crate::events::ClosureReason;
+use crate::value_objects::{
+    AgentId, BudgetLimit, ConversationBudget, ConversationHandoff, ConversationId, MessageId,
+    MessageProvenance, ParticipantId, ParticipantRole, PersonId,
+};
+use serde::{Deserialize, Serialize};
+
+/// All conversation commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ConversationCommand {
+    /// Start a conversation
+    Start(StartConversation),
+    /// Add a participant
+    AddParticipant(AddParticipant),
+    /// Remove a participant, or leave
+    RemoveParticipant(RemoveParticipant),
+    /// Change a participant's role
+    ChangeParticipantRole(ChangeParticipantRole),
+    /// Post a message
+    PostMessage(PostMessage),
+    /// Acknowledge delivery or reading of a message
+    AcknowledgeMessage(AcknowledgeMessage),
+    /// Close the conversation
+    Close(CloseConversation),
+    /// Hand the conversation from one agent to another
+    Transfer(TransferConversation),
+}
+
+impl ConversationCommand {
+    /// Get the conversation ID this command targets
+    pub fn conversation_id(&self) -> ConversationId {
+        match self {
+            ConversationCommand::Start(cmd) => cmd.conversation_id,
+            ConversationCommand::AddParticipant(cmd) => cmd.conversation_id,
+            ConversationCommand::RemoveParticipant(cmd) => cmd.conversation_id,
+            ConversationCommand::ChangeParticipantRole(cmd) => cmd.conversation_id,
+            ConversationCommand::PostMessage(cmd) => cmd.conversation_id,
+            ConversationCommand::AcknowledgeMessage(cmd) => cmd.conversation_id,
+            ConversationCommand::Close(cmd) => cmd.conversation_id,
+            ConversationCommand::Transfer(cmd) => cmd.conversation_id,
+        }
+    }
+
+    /// The participant the command acts as
+    ///
+    /// The owner for `Start`; none for timeouts and budget closures.
+    pub fn actor(&self) -> Option<ParticipantId> {
+        match self {
+            ConversationCommand::Start(cmd) => Some(ParticipantId::Person(cmd.owner)),
+            ConversationCommand::AddParticipant(cmd) => Some(cmd.actor.clone()),
+            ConversationCommand::RemoveParticipant(cmd) => Some(cmd.actor.clone()),
+            ConversationCommand::ChangeParticipantRole(cmd) => Some(cmd.actor.clone()),
+            ConversationCommand::PostMessage(cmd) => Some(cmd.author.clone()),
+            ConversationCommand::AcknowledgeMessage(cmd) => Some(cmd.participant.clone()),
+            ConversationCommand::Close(cmd) => cmd.actor.clone(),
+            ConversationCommand::Transfer(cmd) => Some(cmd.actor.clone()),
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ConversationCommand::Start(_) => Ok(()),
+            ConversationCommand::AddParticipant(cmd) => cmd.validate(),
+            ConversationCommand::RemoveParticipant(_) => Ok(()),
+            ConversationCommand::ChangeParticipantRole(cmd) => cmd.validate(),
+            ConversationCommand::PostMessage(_) => Ok(()),
+            ConversationCommand::AcknowledgeMessage(_) => Ok(()),
+            ConversationCommand::Close(cmd) => cmd.validate(),
+            ConversationCommand::Transfer(cmd) => cmd.validate(),
+        }
+    }
+}
+
+/// Start a conversation owned by a person
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartConversation {
+    /// The new conversation's ID
+    pub conversation_id: ConversationId,
+
+    /// The person the conversation belongs to
+    pub owner: PersonId,
+
+    /// Limits the conversation runs under
+    #[serde(default, skip_serializing_if = "ConversationBudget::is_unlimited")]
+    pub budget: ConversationBudget,
+}
+
+impl StartConversation {
+    /// Create a new StartConversation command
+    pub fn new(owner: PersonId) -> Self {
+        Self {
+            conversation_id: ConversationId::new(),
+            owner,
+            budget: ConversationBudget::unlimited(),
+        }
+    }
+
+    /// Builder: set specific conversation_id
+    pub fn with_conversation_id(mut self, conversation_id: ConversationId) -> Self {
+        self.conversation_id = conversation_id;
+        self
+    }
+
+    /// Builder: token, message and wall-clock limits
+    pub fn with_budget(mut self, budget: ConversationBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+}
+
+/// Add a participant to a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddParticipant {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant issuing the command
+    pub actor: ParticipantId,
+
+    /// The participant to add
+    pub participant: ParticipantId,
+
+    /// Role to give them
+    pub role: ParticipantRole,
+}
+
+impl AddParticipant {
+    /// Create a new AddParticipant command
+    pub fn new(
+        conversation_id: ConversationId,
+        actor: ParticipantId,
+        participant: ParticipantId,
+        role: ParticipantRole,
+    ) -> Self {
+        Self {
+            conversation_id,
+            actor,
+            participant,
+            role,
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.role.accepts(&self.participant) {
+            return Err(format!(
+                "{} cannot take the {} role",
+                self.participant, self.role
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Remove a participant from a conversation
+///
+/// A participant removing themselves is leaving, which needs no permission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveParticipant {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant issuing the command
+    pub actor: ParticipantId,
+
+    /// The participant to remove
+    pub participant: ParticipantId,
+}
+
+impl RemoveParticipant {
+    /// Create a new RemoveParticipant command
+    pub fn new(
+        conversation_id: ConversationId,
+        actor: ParticipantId,
+        participant: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            actor,
+            participant,
+        }
+    }
+
+    /// A participant leaving on their own
+    pub fn leave(conversation_id: ConversationId, participant: ParticipantId) -> Self {
+        Self::new(conversation_id, participant.clone(), participant)
+    }
+}
+
+/// Give a participant another role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeParticipantRole {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant issuing the command
+    pub actor: ParticipantId,
+
+    /// The participant whose role changes
+    pub participant: ParticipantId,
+
+    /// Their new role
+    pub role: ParticipantRole,
+}
+
+impl ChangeParticipantRole {
+    /// Create a new ChangeParticipantRole command
+    pub fn new(
+        conversation_id: ConversationId,
+        actor: ParticipantId,
+        participant: ParticipantId,
+        role: ParticipantRole,
+    ) -> Self {
+        Self {
+            conversation_id,
+            actor,
+            participant,
+            role,
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.role.accepts(&self.participant) {
+            return Err(format!(
+                "{} cannot take the {} role",
+                self.participant, self.role
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Post a message to a conversation
+///
+/// The message itself travels through the agent as usual; the conversation
+/// records that it was posted so receipts can be tracked against it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostMessage {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant posting the message
+    pub author: ParticipantId,
+
+    /// The message
+    pub message_id: MessageId,
+
+    /// Tokens the message spent, counted against the conversation budget
+    #[serde(default)]
+    pub tokens: u64,
+
+    /// Whether the message is internal or posted by an external system
+    #[serde(default, skip_serializing_if = "MessageProvenance::is_internal")]
+    pub provenance: MessageProvenance,
+}
+
+impl PostMessage {
+    /// Create a new PostMessage command
+    pub fn new(
+        conversation_id: ConversationId,
+        author: ParticipantId,
+        message_id: MessageId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            author,
+            message_id,
+            tokens: 0,
+            provenance: MessageProvenance::Internal,
+        }
+    }
+
+    /// Builder: tokens the message spent
+    pub fn with_tokens(mut self, tokens: u64) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Builder: where the message came from
+    pub fn with_provenance(mut self, provenance: MessageProvenance) -> Self {
+        self.provenance = provenance;
+        self
+    }
+}
+
+/// How far a message has got to a participant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Acknowledgement {
+    /// The participant's client received the message
+    Delivered,
+    /// The participant saw the message
+    Read,
+}
+
+/// Acknowledge that a message reached a participant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcknowledgeMessage {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant acknowledging
+    pub participant: ParticipantId,
+
+    /// The message
+    pub message_id: MessageId,
+
+    /// Delivered or read
+    pub acknowledgement: Acknowledgement,
+}
+
+impl AcknowledgeMessage {
+    /// The participant's client received the message
+    pub fn delivered(
+        conversation_id: ConversationId,
+        participant: ParticipantId,
+        message_id: MessageId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            participant,
+            message_id,
+            acknowledgement: Acknowledgement::Delivered,
+        }
+    }
+
+    /// The participant saw the message
+    pub fn read(
+        conversation_id: ConversationId,
+        participant: ParticipantId,
+        message_id: MessageId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            participant,
+            message_id,
+            acknowledgement: Acknowledgement::Read,
+        }
+    }
+}
+
+/// Close a conversation
+///
+/// Owners close conversations by hand; the inactivity timers close idle
+/// ones with [`ClosureReason::Timeout`] and no actor, and those out of time
+/// with [`ClosureReason::BudgetExhausted`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseConversation {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant closing it (none for timeouts and budgets)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<ParticipantId>,
+
+    /// Why it is closed
+    pub reason: ClosureReason,
+
+    /// The budget limit that ran out, for budget closures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exhausted: Option<BudgetLimit>,
+}
+
+impl CloseConversation {
+    /// An owner closing the conversation
+    pub fn by(conversation_id: ConversationId, actor: ParticipantId) -> Self {
+        Self {
+            conversation_id,
+            actor: Some(actor),
+            reason: ClosureReason::Closed,
+            exhausted: None,
+        }
+    }
+
+    /// The conversation sat idle past its inactivity timeout
+    pub fn timed_out(conversation_id: ConversationId) -> Self {
+        Self {
+            conversation_id,
+            actor: None,
+            reason: ClosureReason::Timeout,
+            exhausted: None,
+        }
+    }
+
+    /// A limit of the conversation's budget ran out
+    pub fn budget_exhausted(conversation_id: ConversationId, limit: BudgetLimit) -> Self {
+        Self {
+            conversation_id,
+            actor: None,
+            reason: ClosureReason::BudgetExhausted,
+            exhausted: Some(limit),
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reason == ClosureReason::Closed && self.actor.is_none() {
+            return Err("Closing a conversation by hand needs an actor".to_string());
+        }
+        if (self.reason == ClosureReason::BudgetExhausted) != self.exhausted.is_some() {
+            return Err("Only budget closures name an exhausted limit".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Hand a conversation from one agent to another
+///
+/// The receiving agent takes the departing agent's place and role, and
+/// starts from the handoff's context. Owners may transfer any agent; an
+/// agent may hand off its own conversations, e.g. triage escalating to a
+/// specialist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferConversation {
+    /// The conversation
+    pub conversation_id: ConversationId,
+
+    /// Participant issuing the command
+    pub actor: ParticipantId,
+
+    /// The agent handing the conversation off
+    pub from: AgentId,
+
+    /// The agent taking it over
+    pub to: AgentId,
+
+    /// Reason, recent turns and memory for the receiving agent
+    pub handoff: ConversationHandoff,
+}
+
+impl TransferConversation {
+    /// Create a new TransferConversation command
+    pub fn new(
+        conversation_id: ConversationId,
+        actor: ParticipantId,
+        from: AgentId,
+        to: AgentId,
+        handoff: ConversationHandoff,
+    ) -> Self {
+        Self {
+            conversation_id,
+            actor,
+            from,
+            to,
+            handoff,
+        }
+    }
+
+    /// An agent handing off its own conversation
+    pub fn handoff(
+        conversation_id: ConversationId,
+        from: AgentId,
+        to: AgentId,
+        handoff: ConversationHandoff,
+    ) -> Self {
+        Self::new(conversation_id, ParticipantId::Agent(from), from, to, handoff)
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if self.from == self.to {
+            return Err(format!("Agent {} cannot transfer to itself", self.from));
+        }
+        if self.handoff.reason.trim().is_empty() {
+            return Err("A transfer needs a reason for the receiving agent".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Command envelope
+//!
+//! Wraps an [`AgentCommand`] with the [`CommandMetadata`] of the request
+//! that issued it. On the wire the metadata sits beside the command:
+//!
+//! ```json
+//! {
+//!   "command": { "type": "ActivateAgent", "agent_id": "..." },
+//!   "actor": "person:7f3a...",
+//!   "tenant": "acme",
+//!   "correlation_id": "...",
+//!   "deadline": { "expires_at": "2025-06-01T12:00:30Z" },
+//!   "trace": { "traceparent": "00-4bf9...-00f0...-01" },
+//!   "idempotency_key": "activate-7f3a-1"
+//! }
+//! ```
+//!
+//! A bare command is still accepted and decodes to an envelope without
+//! metadata.
+
+use super::AgentCommand;
+use crate::value_objects::{CommandMetadata, Deadline, TraceContext};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A command with its actor, tenant, causation, deadline and trace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandEnvelope {
+    pub command: AgentCommand,
+
+    #[serde(flatten)]
+    pub metadata: CommandMetadata,
+}
+
+impl CommandEnvelope {
+    /// Envelope without metadata
+    pub fn new(command: AgentCommand) -> Self {
+        Self {
+            command,
+            metadata: CommandMetadata::default(),
+        }
+    }
+
+    /// Decode an envelope, or a bare command
+    pub fn decode(payload: &[u8]) -> serde_json::Result<Self> {
+        let value: serde_json::Value = serde_json::from_slice(payload)?;
+        if value.get("command").is_some() {
+            serde_json::from_value(value)
+        } else {
+            serde_json::from_value(value).map(Self::new)
+        }
+    }
+
+    /// Builder: who issued the command
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.metadata.actor = Some(actor.into());
+        self
+    }
+
+    /// Builder: tenant the command acts for
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.metadata.tenant = Some(tenant.into());
+        self
+    }
+
+    /// Builder: correlation id for the events the command produces
+    pub fn with_correlation_id(mut self, correlation_id: Uuid) -> Self {
+        self.metadata.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Builder: id of the message or event that caused the command
+    pub fn with_causation_id(mut self, causation_id: Uuid) -> Self {
+        self.metadata.causation_id = Some(causation_id);
+        self
+    }
+
+    /// Builder: when the caller stops waiting
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.metadata.deadline = Some(deadline);
+        self
+    }
+
+    /// Builder: trace the command belongs to
+    pub fn with_trace(mut self, trace: TraceContext) -> Self {
+        self.metadata.trace = Some(trace);
+        self
+    }
+
+    /// Builder: key identifying retries of the same command
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata.idempotency_key = Some(key.into());
+        self
+    }
+}
+
+impl From<AgentCommand> for CommandEnvelope {
+    fn from(command: AgentCommand) -> Self {
+        Self::new(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::ActivateAgent;
+    use crate::value_objects::AgentId;
+
+    #[test]
+    fn test_decode_envelope_or_bare_command() {
+        let command = AgentCommand::ActivateAgent(ActivateAgent::new(AgentId::new()));
+        let envelope = CommandEnvelope::new(command.clone())
+            .with_tenant("acme")
+            .with_idempotency_key("activate-1");
+
+        let decoded = CommandEnvelope::decode(&serde_json::to_vec(&envelope).unwrap()).unwrap();
+        assert_eq!(decoded.metadata, envelope.metadata);
+        assert_eq!(decoded.command.agent_id(), command.agent_id());
+
+        let bare = CommandEnvelope::decode(&serde_json::to_vec(&command).unwrap()).unwrap();
+        assert!(bare.metadata.is_empty());
+        assert_eq!(bare.command.agent_id(), command.agent_id());
+    }
+}
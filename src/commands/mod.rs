@@ -13,7 +13,14 @@
 //! - `ActivateAgent` - Activate the agent (requires model config)
 //! - `SuspendAgent` - Temporarily pause the agent
 //! - `DecommissionAgent` - Permanently remove the agent
+//! - `UpdateConfiguration` - Toggle feature flags or set locale without redeploying
+//! - `PlaceLegalHold` - Freeze the agent's data against archival and deletion
+//! - `ReleaseLegalHold` - Lift a legal hold
+//! - `ApproveEscalation` - Grant a held command's missing permission and resume it
+//! - `ReleaseQuarantinedMessage` - Send a quarantined message again with a fresh retry budget
 //! - `SendMessage` - Send a message to the model
+//! - `CancelMessage` - Abort the in-flight response to a message
+//! - `ResumeStream` - Replay a response from a chunk index, then follow it live
 //!
 //! ### Model Configuration Commands
 //! - `CreateModelConfiguration` - Create a new model configuration
@@ -22,19 +29,49 @@
 //! - `ActivateModelConfiguration` - Activate configuration
 //! - `DeprecateModelConfiguration` - Phase out configuration
 //! - `ArchiveModelConfiguration` - Move to history
+//!
+//! ### Conversation Commands
+//! - `StartConversation` - Start a conversation owned by a person
+//! - `AddParticipant` - Add a participant with a role (owners only)
+//! - `RemoveParticipant` - Remove a participant, or leave
+//! - `ChangeParticipantRole` - Give a participant another role (owners only)
+//! - `PostMessage` - Record a message in the conversation (posting roles only)
+//! - `AcknowledgeMessage` - Report a message as delivered to or read by a participant
+//! - `CloseConversation` - Close a conversation by hand or after an inactivity timeout
+//! - `TransferConversation` - Hand a conversation from one agent to another with its context
+//!
+//! ### Tool Catalog Commands
+//! - `DeprecateTool` - Phase out a tool version and notify agents still using it
+//!
+//! ### Envelope
+//! - `CommandEnvelope` - An agent command with its actor, tenant, causation,
+//!   deadline, trace context and idempotency key
 
+mod conversation;
+mod envelope;
 mod model_configuration;
+mod tool_catalog;
 
+pub use conversation::{
+    AcknowledgeMessage, Acknowledgement, AddParticipant, ChangeParticipantRole,
+    CloseConversation, ConversationCommand, PostMessage, RemoveParticipant, StartConversation,
+    TransferConversation,
+};
+pub use envelope::CommandEnvelope;
 pub use model_configuration::{
     ActivateModelConfiguration, ArchiveModelConfiguration, CreateModelConfiguration,
     DeprecateModelConfiguration, ModelConfigurationCommand, ModelParameters,
     UpdateModelParameters, UpdateModelProvider,
 };
+pub use tool_catalog::DeprecateTool;
 
 use crate::value_objects::{
-    AgentId, ContextMessage, MessageId, ModelConfig, PersonId,
+    AgentId, AgentPermission, ContentRef, ContextMessage, ConversationId, CostTags, Deadline,
+    FeatureFlags, LocalePreferences, MessageId, ModelConfig, PersonId,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
 
 /// All agent commands
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,8 +87,22 @@ pub enum AgentCommand {
     SuspendAgent(SuspendAgent),
     /// Decommission the agent
     DecommissionAgent(DecommissionAgent),
+    /// Change runtime configuration (feature flags)
+    UpdateConfiguration(UpdateConfiguration),
+    /// Freeze the agent's data for compliance
+    PlaceLegalHold(PlaceLegalHold),
+    /// Lift a legal hold
+    ReleaseLegalHold(ReleaseLegalHold),
+    /// Approve a permission escalation
+    ApproveEscalation(ApproveEscalation),
+    /// Release a quarantined message for retry
+    ReleaseQuarantinedMessage(ReleaseQuarantinedMessage),
     /// Send a message to the model
     SendMessage(SendMessage),
+    /// Cancel an in-flight response
+    CancelMessage(CancelMessage),
+    /// Replay a response for a reconnecting client
+    ResumeStream(ResumeStream),
 }
 
 impl AgentCommand {
@@ -63,7 +114,33 @@ impl AgentCommand {
             AgentCommand::ActivateAgent(cmd) => cmd.agent_id,
             AgentCommand::SuspendAgent(cmd) => cmd.agent_id,
             AgentCommand::DecommissionAgent(cmd) => cmd.agent_id,
+            AgentCommand::UpdateConfiguration(cmd) => cmd.agent_id,
+            AgentCommand::PlaceLegalHold(cmd) => cmd.agent_id,
+            AgentCommand::ReleaseLegalHold(cmd) => cmd.agent_id,
+            AgentCommand::ApproveEscalation(cmd) => cmd.agent_id,
+            AgentCommand::ReleaseQuarantinedMessage(cmd) => cmd.agent_id,
             AgentCommand::SendMessage(cmd) => cmd.agent_id,
+            AgentCommand::CancelMessage(cmd) => cmd.agent_id,
+            AgentCommand::ResumeStream(cmd) => cmd.agent_id,
+        }
+    }
+
+    /// Permission the issuing principal needs for this command
+    pub fn required_permission(&self) -> AgentPermission {
+        match self {
+            AgentCommand::DeployAgent(_) => AgentPermission::Deploy,
+            AgentCommand::ConfigureModel(_) => AgentPermission::ConfigureModel,
+            AgentCommand::ActivateAgent(_) => AgentPermission::Activate,
+            AgentCommand::SuspendAgent(_) => AgentPermission::Suspend,
+            AgentCommand::DecommissionAgent(_) => AgentPermission::Decommission,
+            AgentCommand::UpdateConfiguration(_) => AgentPermission::UpdateConfiguration,
+            AgentCommand::PlaceLegalHold(_) => AgentPermission::ManageLegalHold,
+            AgentCommand::ReleaseLegalHold(_) => AgentPermission::ManageLegalHold,
+            AgentCommand::ApproveEscalation(_) => AgentPermission::ApproveEscalations,
+            AgentCommand::ReleaseQuarantinedMessage(_) => AgentPermission::ReleaseQuarantine,
+            AgentCommand::SendMessage(_) => AgentPermission::SendMessage,
+            AgentCommand::CancelMessage(_) => AgentPermission::CancelMessage,
+            AgentCommand::ResumeStream(_) => AgentPermission::ResumeStream,
         }
     }
 
@@ -75,7 +152,14 @@ impl AgentCommand {
             AgentCommand::ActivateAgent(cmd) => cmd.validate(),
             AgentCommand::SuspendAgent(cmd) => cmd.validate(),
             AgentCommand::DecommissionAgent(cmd) => cmd.validate(),
+            AgentCommand::UpdateConfiguration(cmd) => cmd.validate(),
+            AgentCommand::PlaceLegalHold(cmd) => cmd.validate(),
+            AgentCommand::ReleaseLegalHold(_) => Ok(()),
+            AgentCommand::ApproveEscalation(_) => Ok(()),
+            AgentCommand::ReleaseQuarantinedMessage(_) => Ok(()),
             AgentCommand::SendMessage(cmd) => cmd.validate(),
+            AgentCommand::CancelMessage(cmd) => cmd.validate(),
+            AgentCommand::ResumeStream(cmd) => cmd.validate(),
         }
     }
 }
@@ -243,6 +327,171 @@ impl DecommissionAgent {
     }
 }
 
+/// Change an agent's runtime configuration
+///
+/// Sets feature flags or locale preferences on a live agent; takes effect
+/// on the next message without a redeploy. Flags not named keep their
+/// current value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateConfiguration {
+    /// The agent to reconfigure
+    pub agent_id: AgentId,
+
+    /// Feature flags to set, by name
+    #[serde(default)]
+    pub feature_flags: BTreeMap<String, bool>,
+
+    /// Locale and timezone to use from now on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<LocalePreferences>,
+}
+
+impl UpdateConfiguration {
+    /// Create a new UpdateConfiguration command with no changes
+    pub fn new(agent_id: AgentId) -> Self {
+        Self {
+            agent_id,
+            feature_flags: BTreeMap::new(),
+            locale: None,
+        }
+    }
+
+    /// Builder: set a feature flag
+    pub fn with_flag(mut self, flag: impl Into<String>, enabled: bool) -> Self {
+        self.feature_flags.insert(flag.into(), enabled);
+        self
+    }
+
+    /// Builder: set locale preferences
+    pub fn with_locale(mut self, locale: LocalePreferences) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if self.feature_flags.is_empty() && self.locale.is_none() {
+            return Err(
+                "Configuration update must set a feature flag or locale preferences".to_string()
+            );
+        }
+        if let Some(locale) = &self.locale {
+            locale.validate()?;
+        }
+        self.feature_flags
+            .keys()
+            .try_for_each(|flag| FeatureFlags::validate_name(flag))
+    }
+}
+
+/// Place a legal hold on an agent's data
+///
+/// While held, archival, crypto-shredding and retention skip the agent's
+/// streams. Holds outlive decommissioning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaceLegalHold {
+    /// The agent whose data to hold
+    pub agent_id: AgentId,
+
+    /// Who is placing the hold
+    pub placed_by: PersonId,
+
+    /// Why the data is held (matter or ticket reference)
+    pub reason: String,
+}
+
+impl PlaceLegalHold {
+    /// Create a new PlaceLegalHold command
+    pub fn new(agent_id: AgentId, placed_by: PersonId, reason: impl Into<String>) -> Self {
+        Self {
+            agent_id,
+            placed_by,
+            reason: reason.into(),
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reason.trim().is_empty() {
+            return Err("Legal hold reason cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Release a legal hold on an agent's data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseLegalHold {
+    /// The held agent
+    pub agent_id: AgentId,
+
+    /// Who is releasing the hold
+    pub released_by: PersonId,
+}
+
+impl ReleaseLegalHold {
+    /// Create a new ReleaseLegalHold command
+    pub fn new(agent_id: AgentId, released_by: PersonId) -> Self {
+        Self {
+            agent_id,
+            released_by,
+        }
+    }
+}
+
+/// Approve a permission escalation
+///
+/// Grants the escalated permission for a while and resumes the held command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApproveEscalation {
+    /// The agent the held command targets
+    pub agent_id: AgentId,
+
+    /// From `PermissionEscalationRequested`
+    pub escalation_id: Uuid,
+
+    /// The approving owner; the command must be signed with their key
+    pub approved_by: PersonId,
+}
+
+impl ApproveEscalation {
+    /// Create a new ApproveEscalation command
+    pub fn new(agent_id: AgentId, escalation_id: Uuid, approved_by: PersonId) -> Self {
+        Self {
+            agent_id,
+            escalation_id,
+            approved_by,
+        }
+    }
+}
+
+/// Release a quarantined message
+///
+/// The message leaves the review queue and is sent again with a fresh
+/// retry budget.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseQuarantinedMessage {
+    /// The agent the message was sent through
+    pub agent_id: AgentId,
+
+    /// From `MessageQuarantined`
+    pub message_id: MessageId,
+
+    /// The operator releasing the message; the command must be signed with their key
+    pub released_by: PersonId,
+}
+
+impl ReleaseQuarantinedMessage {
+    /// Create a new ReleaseQuarantinedMessage command
+    pub fn new(agent_id: AgentId, message_id: MessageId, released_by: PersonId) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            released_by,
+        }
+    }
+}
+
 /// Send a message to the model
 ///
 /// Stateless message - full conversation context must be provided
@@ -261,6 +510,34 @@ pub struct SendMessage {
     /// Optional conversation context (previous messages)
     #[serde(default)]
     pub context: Vec<ContextMessage>,
+
+    /// When the sender stops waiting for the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<Deadline>,
+
+    /// Labels attributing the message's cost (project, team, ...)
+    #[serde(default, skip_serializing_if = "CostTags::is_empty")]
+    pub cost_tags: CostTags,
+
+    /// Conversation to publish processing indicators to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<ConversationId>,
+
+    /// Content uploaded to the blob store, in place of inline `content`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_ref: Option<ContentRef>,
+
+    /// Primary agent's message this is a shadow copy of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_of: Option<MessageId>,
+
+    /// Retries allowed after a failed attempt, in place of the service default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<u32>,
+
+    /// First attempt's message ID, when this is a retry under its own ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<MessageId>,
 }
 
 impl SendMessage {
@@ -271,6 +548,13 @@ impl SendMessage {
             message_id: MessageId::new(),
             content: content.into(),
             context: vec![],
+            deadline: None,
+            cost_tags: CostTags::new(),
+            conversation_id: None,
+            content_ref: None,
+            shadow_of: None,
+            retry_budget: None,
+            retry_of: None,
         }
     }
 
@@ -286,11 +570,135 @@ impl SendMessage {
         self
     }
 
+    /// Builder: answer within `deadline`
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Builder: attribute the message's cost to `key = value`
+    pub fn with_cost_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.cost_tags = self.cost_tags.with(key, value);
+        self
+    }
+
+    /// Builder: show the agent's progress in `conversation_id`
+    pub fn in_conversation(mut self, conversation_id: ConversationId) -> Self {
+        self.conversation_id = Some(conversation_id);
+        self
+    }
+
+    /// Builder: the content was uploaded as `content_ref`; nothing is sent inline
+    pub fn with_content_ref(mut self, content_ref: ContentRef) -> Self {
+        self.content = String::new();
+        self.content_ref = Some(content_ref);
+        self
+    }
+
+    /// Builder: a copy of the primary agent's `message_id` for a shadow agent
+    pub fn shadowing(mut self, message_id: MessageId) -> Self {
+        self.shadow_of = Some(message_id);
+        self
+    }
+
+    /// Builder: retry the message at most `retries` times after a failure
+    pub fn with_retry_budget(mut self, retries: u32) -> Self {
+        self.retry_budget = Some(retries);
+        self
+    }
+
+    /// ID the message's retries are tracked under: the first attempt's
+    pub fn retry_key(&self) -> MessageId {
+        self.retry_of.unwrap_or(self.message_id)
+    }
+
+    /// Another attempt at the message, under a fresh message ID
+    ///
+    /// Each attempt's events and response chunks stay apart from the
+    /// attempts before it; `retry_of` links them.
+    pub fn next_attempt(&self) -> Self {
+        Self {
+            message_id: MessageId::new(),
+            retry_of: Some(self.retry_key()),
+            ..self.clone()
+        }
+    }
+
     /// Validate the command
     pub fn validate(&self) -> Result<(), String> {
-        if self.content.is_empty() {
-            return Err("Message content cannot be empty".to_string());
+        if self.shadow_of.is_some() && self.conversation_id.is_some() {
+            return Err("Shadow copies are never shown in a conversation".to_string());
         }
+        match (&self.content_ref, self.content.is_empty()) {
+            (None, true) => return Err("Message content cannot be empty".to_string()),
+            (Some(_), false) => {
+                return Err("Message content is either inline or uploaded, not both".to_string())
+            }
+            _ => {}
+        }
+        self.cost_tags.validate()
+    }
+}
+
+/// Cancel the in-flight response to a message
+///
+/// Aborts the provider stream for `message_id`. The response ends with a
+/// final chunk marked `FinishReason::Cancelled` and a `ResponseCancelled`
+/// event instead of `ResponseCompleted`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelMessage {
+    /// The agent the message was sent through
+    pub agent_id: AgentId,
+
+    /// The message whose response should be aborted
+    pub message_id: MessageId,
+}
+
+impl CancelMessage {
+    /// Create a new CancelMessage command
+    pub fn new(agent_id: AgentId, message_id: MessageId) -> Self {
+        Self {
+            agent_id,
+            message_id,
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Replay a response starting at a chunk index
+///
+/// Stored chunks from `from_index` onward are delivered first, then new
+/// chunks as they arrive, until the response completes. This does not
+/// change agent state and produces no events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeStream {
+    /// The agent the message was sent through
+    pub agent_id: AgentId,
+
+    /// The message whose response to replay
+    pub message_id: MessageId,
+
+    /// First chunk index the client is missing
+    #[serde(default)]
+    pub from_index: u32,
+}
+
+impl ResumeStream {
+    /// Create a new ResumeStream command
+    pub fn new(agent_id: AgentId, message_id: MessageId, from_index: u32) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            from_index,
+        }
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
         Ok(())
     }
 }
@@ -323,6 +731,30 @@ mod tests {
         assert!(invalid.validate().is_err());
     }
 
+    #[test]
+    fn test_update_configuration_validation() {
+        let valid = UpdateConfiguration::new(AgentId::new()).with_flag("enable_rag", true);
+        assert!(valid.validate().is_ok());
+
+        assert!(UpdateConfiguration::new(AgentId::new()).validate().is_err());
+        let invalid = UpdateConfiguration::new(AgentId::new()).with_flag("Enable RAG", true);
+        assert!(invalid.validate().is_err());
+
+        let locale = LocalePreferences::new("de-DE", chrono_tz::Europe::Berlin);
+        assert!(UpdateConfiguration::new(AgentId::new()).with_locale(locale).validate().is_ok());
+    }
+
+    #[test]
+    fn test_place_legal_hold_validation() {
+        let placed_by = PersonId::new();
+        assert!(PlaceLegalHold::new(AgentId::new(), placed_by, "Matter 2025-114")
+            .validate()
+            .is_ok());
+        assert!(PlaceLegalHold::new(AgentId::new(), placed_by, "  ")
+            .validate()
+            .is_err());
+    }
+
     #[test]
     fn test_send_message_validation() {
         let valid = SendMessage::new(AgentId::new(), "Hello!");
@@ -332,6 +764,20 @@ mod tests {
         assert!(invalid.validate().is_err());
     }
 
+    #[test]
+    fn test_cancel_message_serialization() {
+        let message_id = MessageId::new();
+        let cmd = AgentCommand::CancelMessage(CancelMessage::new(AgentId::new(), message_id));
+        let json = serde_json::to_value(&cmd).unwrap();
+        assert_eq!(json["type"], "CancelMessage");
+
+        let deserialized: AgentCommand = serde_json::from_value(json).unwrap();
+        match deserialized {
+            AgentCommand::CancelMessage(c) => assert_eq!(c.message_id, message_id),
+            other => panic!("Expected CancelMessage, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_command_serialization() {
         let cmd = AgentCommand::DeployAgent(DeployAgent::new(PersonId::new(), "Test"));
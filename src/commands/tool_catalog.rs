@@ -0,0 +1,82 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Commands for the tool catalog
+//!
+//! Tool definitions are shared by the fleet, so these commands name a tool
+//! version rather than an agent.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+/// Phase out one version of a tool
+///
+/// Agents that still have the version enabled are notified; the version
+/// keeps working until they migrate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecateTool {
+    /// The tool's name
+    pub tool_name: String,
+
+    /// The version to deprecate
+    pub version: Version,
+
+    /// Version agents should migrate to (default: latest supported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<Version>,
+
+    /// Reason for deprecation
+    pub reason: String,
+}
+
+impl DeprecateTool {
+    /// Create a new DeprecateTool command
+    pub fn new(tool_name: impl Into<String>, version: Version, reason: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            version,
+            replacement: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Builder: name the version to migrate to
+    pub fn with_replacement(mut self, replacement: Version) -> Self {
+        self.replacement = Some(replacement);
+        self
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tool_name.is_empty() {
+            return Err("Tool name cannot be empty".to_string());
+        }
+        if self.reason.is_empty() {
+            return Err("Deprecation reason cannot be empty".to_string());
+        }
+        if self.replacement.as_ref() == Some(&self.version) {
+            return Err("A tool version cannot replace itself".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deprecate_tool_validation() {
+        let v1 = Version::new(1, 0, 0);
+        assert!(DeprecateTool::new("search", v1.clone(), "schema changed")
+            .with_replacement(Version::new(2, 0, 0))
+            .validate()
+            .is_ok());
+        assert!(DeprecateTool::new("search", v1.clone(), "")
+            .validate()
+            .is_err());
+        assert!(DeprecateTool::new("search", v1.clone(), "loop")
+            .with_replacement(v1)
+            .validate()
+            .is_err());
+    }
+}
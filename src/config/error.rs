@@ -44,6 +44,17 @@ pub enum ParseError {
     #[error("Invalid version format: {version}")]
     InvalidVersion { version: String },
 
+    /// Definition targets a newer schema than this crate understands
+    #[error(
+        "Definition targets schema version {found}, but cim-domain-agent {crate_version} \
+         supports up to version {supported}"
+    )]
+    UnsupportedSchemaVersion {
+        found: u32,
+        supported: u32,
+        crate_version: &'static str,
+    },
+
     /// Model configuration is invalid
     #[error("Invalid model configuration: {reason}")]
     InvalidModelConfig { reason: String },
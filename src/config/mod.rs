@@ -18,6 +18,7 @@
 //! ```text
 //! File Content (String)
 //!   → split_front_matter ⟶ (front_matter: &str, body: &str)
+//!   → migrate_definition ⟶ front-matter at DEFINITION_SCHEMA_VERSION
 //!   → parse_front_matter ⟶ AgentConfig
 //!   → extract_sections ⟶ (AgentConfig, Sections)
 //!   → validate ⟶ ValidatedConfig
@@ -38,6 +39,7 @@
 mod parser;
 mod types;
 mod error;
+mod schema;
 mod sections;
 mod validator;
 
@@ -48,6 +50,7 @@ pub use types::{
     NatsConfig, NatsSubjects, DeploymentConfig, ConfigMetadata,
 };
 pub use error::{ParseError, ParseResult};
+pub use schema::{migrate_definition, DEFINITION_SCHEMA_VERSION};
 pub use sections::{MarkdownSections, extract_sections};
 pub use validator::{ValidatedConfig, validate_config};
 
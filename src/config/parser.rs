@@ -6,6 +6,7 @@
 //! Following FP Axiom 1: Pure functions (no side effects)
 
 use super::error::{ParseError, ParseResult};
+use super::schema::migrate_definition;
 use super::types::AgentConfig;
 use itertools::Itertools;
 
@@ -66,13 +67,19 @@ pub fn split_front_matter(content: &str) -> ParseResult<(&str, &str)> {
 ///
 /// # Type Safety
 /// Leverages serde_yaml for type-safe parsing with compile-time guarantees
+///
+/// # Versioning
+/// Older schema versions are migrated first; newer ones are rejected with
+/// [`ParseError::UnsupportedSchemaVersion`]
 pub fn parse_front_matter(yaml: &str) -> ParseResult<AgentConfig> {
-    // Parse YAML into temporary structure
-    let mut config: AgentConfig = serde_yaml::from_str(yaml).map_err(|e| {
-        ParseError::YamlError {
-            message: e.to_string(),
-        }
-    })?;
+    let yaml_error = |e: serde_yaml::Error| ParseError::YamlError {
+        message: e.to_string(),
+    };
+
+    // Parse YAML, bring it up to the current schema, then type it
+    let document = serde_yaml::from_str(yaml).map_err(yaml_error)?;
+    let mut config: AgentConfig =
+        serde_yaml::from_value(migrate_definition(document)?).map_err(yaml_error)?;
 
     // Body will be filled in by extract_sections
     config.system_prompt = String::new();
@@ -138,6 +145,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DEFINITION_SCHEMA_VERSION;
 
     const VALID_CONFIG: &str = r#"---
 agent:
@@ -205,6 +213,18 @@ This is the system prompt.
         assert!(config.system_prompt.contains("Test Agent"));
     }
 
+    #[test]
+    fn test_parse_stamps_schema_version() {
+        let config = parse_agent_file(VALID_CONFIG.to_string()).unwrap();
+        assert_eq!(config.agent.schema_version, DEFINITION_SCHEMA_VERSION);
+
+        let newer = VALID_CONFIG.replace("version: \"1.0.0\"", "schema_version: 3");
+        assert!(matches!(
+            parse_agent_file(newer),
+            Err(ParseError::UnsupportedSchemaVersion { found: 3, .. })
+        ));
+    }
+
     #[test]
     fn test_parse_multiple() {
         let contents = vec![
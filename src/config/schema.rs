@@ -0,0 +1,214 @@
+// Copyright (c) 2025 - Cowboy AI, Inc.
+
+//! Definition schema versions and migrations
+//!
+//! Every definition targets a schema version in `agent.schema_version`.
+//! Older definitions are migrated forward one version at a time before they
+//! are deserialized; definitions newer than this crate are rejected.
+//!
+//! ```text
+//! v1 (flat)                         v2 (current)
+//! ─────────                         ────────────
+//! id, name, display_name,     ──>   agent: { id, name, display_name,
+//! version, ...                               version, ..., schema_version }
+//! description, tags, author,  ──>   metadata: { description, tags, author, created }
+//! created
+//! model: { temperature, ... } ──>   model: { parameters: { temperature, ... } }
+//! ```
+//!
+//! A v1 key that is not a v2 section or model setting belongs to the agent
+//! (top level) or is a model parameter (under `model`), so unknown keys such
+//! as `seed` or `locale` are carried over rather than dropped.
+//!
+//! Definitions written before versioning carry no `schema_version`; their
+//! version is inferred from their shape.
+
+use super::error::{ParseError, ParseResult};
+use serde_yaml::{Mapping, Value};
+
+/// Schema version written and understood by this crate
+pub const DEFINITION_SCHEMA_VERSION: u32 = 2;
+
+/// Top-level sections of a v2 definition; other v1 top-level keys move under `agent`
+const V2_SECTIONS: [&str; 9] = [
+    "agent",
+    "model",
+    "nats",
+    "deployment",
+    "metadata",
+    "features",
+    "system_prompt",
+    "knowledge_base",
+    "examples",
+];
+
+/// v1 top-level keys that moved under `metadata` in v2
+const V1_METADATA_KEYS: [&str; 4] = ["description", "tags", "author", "created"];
+
+/// Settings of a v2 `model`; other v1 model keys move under `model.parameters`
+const V2_MODEL_KEYS: [&str; 10] = [
+    "provider",
+    "ollama",
+    "parameters",
+    "rationale",
+    "preset",
+    "intent_overrides",
+    "truncation",
+    "best_of",
+    "reflection",
+    "output_constraints",
+];
+
+/// Bring parsed front-matter up to [`DEFINITION_SCHEMA_VERSION`]
+///
+/// Pure function: returns the migrated document, stamped with the current
+/// schema version.
+pub fn migrate_definition(front_matter: Value) -> ParseResult<Value> {
+    let Value::Mapping(mut document) = front_matter else {
+        return Err(ParseError::YamlError {
+            message: "front-matter must be a mapping".to_string(),
+        });
+    };
+
+    let found = schema_version(&document)?;
+    if found > DEFINITION_SCHEMA_VERSION {
+        return Err(ParseError::UnsupportedSchemaVersion {
+            found,
+            supported: DEFINITION_SCHEMA_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION"),
+        });
+    }
+
+    if found < 2 {
+        document = migrate_v1(document);
+    }
+
+    if let Some(Value::Mapping(agent)) = document.get_mut("agent") {
+        agent.insert("schema_version".into(), DEFINITION_SCHEMA_VERSION.into());
+    }
+    Ok(Value::Mapping(document))
+}
+
+/// Declared schema version, or the one implied by the document's shape
+fn schema_version(document: &Mapping) -> ParseResult<u32> {
+    let declared = document
+        .get("agent")
+        .and_then(|agent| agent.get("schema_version"))
+        .or_else(|| document.get("schema_version"));
+
+    match declared {
+        Some(value) => value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| ParseError::InvalidValue {
+                field: "agent.schema_version".to_string(),
+                reason: format!("expected a positive integer, got {:?}", value),
+            }),
+        None if document.contains_key("agent") => Ok(2),
+        None => Ok(1),
+    }
+}
+
+/// v1 → v2: nest identity under `agent`, descriptive keys under `metadata`
+/// and parameters under `model.parameters`
+fn migrate_v1(document: Mapping) -> Mapping {
+    let mut migrated = Mapping::new();
+    let mut agent = Mapping::new();
+    let mut metadata = Mapping::new();
+    for (key, value) in document {
+        match key.as_str() {
+            Some("schema_version") => {}
+            Some(section) if V2_SECTIONS.contains(&section) => {
+                migrated.insert(key, value);
+            }
+            Some(field) if V1_METADATA_KEYS.contains(&field) => {
+                metadata.insert(key, value);
+            }
+            _ => {
+                agent.insert(key, value);
+            }
+        }
+    }
+    migrated.insert("agent".into(), Value::Mapping(agent));
+
+    if !metadata.is_empty() {
+        match migrated.get_mut("metadata") {
+            // An explicit section wins over the flat keys
+            Some(Value::Mapping(section)) => {
+                for (key, value) in metadata {
+                    if !section.contains_key(&key) {
+                        section.insert(key, value);
+                    }
+                }
+            }
+            _ => {
+                if !metadata.contains_key("tags") {
+                    metadata.insert("tags".into(), Value::Sequence(Vec::new()));
+                }
+                migrated.insert("metadata".into(), Value::Mapping(metadata));
+            }
+        }
+    }
+
+    if let Some(Value::Mapping(model)) = migrated.get_mut("model") {
+        let mut parameters = match model.remove("parameters") {
+            Some(Value::Mapping(parameters)) => parameters,
+            _ => Mapping::new(),
+        };
+        let moved: Vec<Value> = model
+            .keys()
+            .filter(|key| !key.as_str().is_some_and(|key| V2_MODEL_KEYS.contains(&key)))
+            .cloned()
+            .collect();
+        for key in moved {
+            if let Some(value) = model.remove(&key) {
+                parameters.insert(key, value);
+            }
+        }
+        model.insert("parameters".into(), Value::Mapping(parameters));
+    }
+
+    migrated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Value {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_migrates_flat_v1_definition() {
+        let migrated = migrate_definition(yaml(concat!(
+            "id: abc\nname: sage\nversion: 0.1.0\nlocale: de-DE\n",
+            "description: Answers questions\nauthor: ops\n",
+            "model:\n  provider: ollama\n  temperature: 0.5\n  seed: 7\n  rationale: small\n",
+        )))
+        .unwrap();
+
+        assert_eq!(migrated["agent"]["name"], "sage");
+        assert_eq!(migrated["agent"]["locale"], "de-DE");
+        assert_eq!(migrated["agent"]["schema_version"], DEFINITION_SCHEMA_VERSION);
+        assert_eq!(migrated["metadata"]["description"], "Answers questions");
+        assert_eq!(migrated["metadata"]["tags"], Value::Sequence(Vec::new()));
+        assert_eq!(migrated["model"]["parameters"]["temperature"], 0.5);
+        assert_eq!(migrated["model"]["parameters"]["seed"], 7);
+        assert_eq!(migrated["model"]["rationale"], "small");
+        assert!(migrated.get("name").is_none() && migrated.get("author").is_none());
+    }
+
+    #[test]
+    fn test_rejects_newer_and_malformed_versions() {
+        let newer = migrate_definition(yaml("agent:\n  name: sage\n  schema_version: 99\n"));
+        assert!(matches!(
+            newer,
+            Err(ParseError::UnsupportedSchemaVersion { found: 99, .. })
+        ));
+
+        let malformed = migrate_definition(yaml("agent:\n  schema_version: two\n"));
+        assert!(matches!(malformed, Err(ParseError::InvalidValue { .. })));
+    }
+}
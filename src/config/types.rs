@@ -5,6 +5,12 @@
 //! Following FP Axiom 2: Algebraic Data Types as foundation
 //! All types are immutable value objects (Product and Sum types)
 
+use super::schema::DEFINITION_SCHEMA_VERSION;
+use crate::value_objects::{
+    BestOfN, FeatureFlags, GenerationParams, GenerationPreset, LocalePreferences,
+    OutputConstraints, ReflectionConfig, TruncationPolicy,
+};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -19,6 +25,9 @@ pub struct AgentConfig {
     pub nats: Option<NatsConfig>,
     pub deployment: Option<DeploymentConfig>,
     pub metadata: Option<ConfigMetadata>,
+    /// Initial runtime feature flags (`features:` in front-matter)
+    #[serde(default, skip_serializing_if = "FeatureFlags::is_empty")]
+    pub features: FeatureFlags,
     #[serde(default)]
     pub system_prompt: String,
     pub knowledge_base: Option<String>,
@@ -34,6 +43,19 @@ pub struct AgentMetadata {
     pub name: String,
     pub display_name: Option<String>,
     pub version: String,
+    /// Definition schema this file targets (see [`DEFINITION_SCHEMA_VERSION`])
+    #[serde(default = "current_schema_version")]
+    pub schema_version: u32,
+    /// Users' locale as a BCP 47 tag, e.g. `de-DE`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Users' IANA timezone, e.g. `Europe/Berlin`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<Tz>,
+}
+
+fn current_schema_version() -> u32 {
+    DEFINITION_SCHEMA_VERSION
 }
 
 /// Model configuration section (from agent config file)
@@ -46,6 +68,24 @@ pub struct AgentModelConfig {
     pub ollama: Option<OllamaConfig>,
     pub parameters: ModelParameters,
     pub rationale: Option<String>,
+    /// Named generation preset (creative, precise, deterministic)
+    #[serde(default)]
+    pub preset: Option<GenerationPreset>,
+    /// Generation overrides keyed by intent name
+    #[serde(default)]
+    pub intent_overrides: HashMap<String, GenerationParams>,
+    /// Strategies allowed to shrink an oversized context, in order
+    #[serde(default)]
+    pub truncation: TruncationPolicy,
+    /// Best-of-N sampling keyed by intent name
+    #[serde(default)]
+    pub best_of: HashMap<String, BestOfN>,
+    /// Self-critique loop run before answering
+    #[serde(default)]
+    pub reflection: Option<ReflectionConfig>,
+    /// Rules answers must follow, enforced by regenerating them
+    #[serde(default)]
+    pub output_constraints: Option<OutputConstraints>,
 }
 
 /// Ollama-specific configuration
@@ -121,6 +161,9 @@ impl AgentMetadata {
             name,
             display_name: None,
             version,
+            schema_version: DEFINITION_SCHEMA_VERSION,
+            locale: None,
+            timezone: None,
         }
     }
 
@@ -133,6 +176,29 @@ impl AgentMetadata {
             ..self
         }
     }
+
+    /// Add locale and timezone preferences
+    pub fn with_locale(self, locale: String, timezone: Tz) -> Self {
+        Self {
+            locale: Some(locale),
+            timezone: Some(timezone),
+            ..self
+        }
+    }
+
+    /// Locale preferences, if either was set; the other takes its default
+    ///
+    /// Pure function: derived from the metadata fields
+    pub fn locale_preferences(&self) -> Option<LocalePreferences> {
+        if self.locale.is_none() && self.timezone.is_none() {
+            return None;
+        }
+        let defaults = LocalePreferences::default();
+        Some(LocalePreferences::new(
+            self.locale.clone().unwrap_or(defaults.locale),
+            self.timezone.unwrap_or(defaults.timezone),
+        ))
+    }
 }
 
 impl AgentModelConfig {
@@ -145,6 +211,12 @@ impl AgentModelConfig {
             ollama: None,
             parameters,
             rationale: None,
+            preset: None,
+            intent_overrides: HashMap::new(),
+            truncation: TruncationPolicy::default(),
+            best_of: HashMap::new(),
+            reflection: None,
+            output_constraints: None,
         }
     }
 
@@ -165,6 +237,14 @@ impl AgentModelConfig {
             ..self
         }
     }
+
+    /// Select a generation preset
+    pub fn with_preset(self, preset: GenerationPreset) -> Self {
+        Self {
+            preset: Some(preset),
+            ..self
+        }
+    }
 }
 
 impl ModelParameters {
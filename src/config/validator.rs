@@ -7,6 +7,7 @@
 
 use super::error::{collect_results, validate_non_empty, validate_uuid, ParseError, ParseResult};
 use super::types::AgentConfig;
+use crate::value_objects::{FeatureFlags, GenerationParams};
 
 /// Validated configuration (newtype pattern)
 ///
@@ -54,6 +55,8 @@ pub fn validate_config(config: AgentConfig) -> ParseResult<ValidatedConfig> {
         validate_model_config(&config),
         validate_version(&config),
         validate_system_prompt(&config),
+        validate_features(&config),
+        validate_locale(&config),
     ];
 
     // If all pass, wrap in validated newtype
@@ -89,15 +92,30 @@ fn validate_agent_id(id: &str) -> ParseResult<()> {
 ///
 /// Pure function: nested validation
 fn validate_model_config(config: &AgentConfig) -> ParseResult<()> {
-    let validations = vec![
+    let mut validations = vec![
         validate_non_empty("model.provider", &config.model.provider),
         validate_temperature(config.model.parameters.temperature),
         validate_max_tokens(config.model.parameters.max_tokens),
     ];
+    validations.extend(
+        config
+            .model
+            .intent_overrides
+            .iter()
+            .map(|(intent, params)| validate_intent_override(intent, params)),
+    );
 
     collect_results(validations)
 }
 
+/// Validate a per-intent generation override
+fn validate_intent_override(intent: &str, params: &GenerationParams) -> ParseResult<()> {
+    params.validate().map_err(|reason| ParseError::InvalidValue {
+        field: format!("model.intent_overrides.{}", intent),
+        reason,
+    })
+}
+
 /// Validate temperature range [0.0, 2.0]
 fn validate_temperature(temp: f64) -> ParseResult<()> {
     if (0.0..=2.0).contains(&temp) {
@@ -152,6 +170,32 @@ fn validate_system_prompt(config: &AgentConfig) -> ParseResult<()> {
     validate_non_empty("system_prompt", &config.system_prompt)
 }
 
+/// Validate feature flag names
+fn validate_features(config: &AgentConfig) -> ParseResult<()> {
+    config
+        .features
+        .overrides()
+        .keys()
+        .try_for_each(|flag| {
+            FeatureFlags::validate_name(flag).map_err(|reason| ParseError::InvalidValue {
+                field: "features".to_string(),
+                reason,
+            })
+        })
+}
+
+/// Validate the locale tag, when one is set
+fn validate_locale(config: &AgentConfig) -> ParseResult<()> {
+    config
+        .agent
+        .locale_preferences()
+        .map_or(Ok(()), |preferences| preferences.validate())
+        .map_err(|reason| ParseError::InvalidValue {
+            field: "agent.locale".to_string(),
+            reason,
+        })
+}
+
 /// Validate multiple configurations
 ///
 /// Pure function: Iterator transformation
@@ -196,6 +240,7 @@ mod tests {
             nats: None,
             deployment: None,
             metadata: None,
+            features: FeatureFlags::default(),
             system_prompt: "System prompt content".to_string(),
             knowledge_base: None,
             examples: None,
@@ -227,6 +272,33 @@ mod tests {
         assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
     }
 
+    #[test]
+    fn test_validate_invalid_intent_override() {
+        let mut config = valid_config();
+        config.model.intent_overrides.insert(
+            "completion".to_string(),
+            GenerationParams::new().with_temperature(0.4).with_top_p(0.9),
+        );
+
+        match validate_config(config) {
+            Err(ParseError::InvalidValue { field, .. }) => {
+                assert_eq!(field, "model.intent_overrides.completion");
+            }
+            other => panic!("Expected InvalidValue, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_invalid_locale() {
+        let mut config = valid_config();
+        config.agent.locale = Some("not a locale".to_string());
+
+        match validate_config(config) {
+            Err(ParseError::InvalidValue { field, .. }) => assert_eq!(field, "agent.locale"),
+            other => panic!("Expected InvalidValue, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_validate_invalid_version() {
         let mut config = valid_config();
@@ -0,0 +1,469 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Agent domain assembly
+//!
+//! Wires the event store, snapshot store, repository, publisher, provider
+//! registry and services that a host needs, so embedding the domain does not
+//! take dozens of lines of glue. Everything defaults to in-memory; each piece
+//! can be swapped:
+//!
+//! ```text
+//! AgentDomainBuilder::new()
+//!     .with_nats(jetstream, "AGENT_EVENTS")   InMemoryEventStore ──> NatsEventStore
+//!                                             no publisher ──> NatsEventPublisher
+//!     .with_provider(type, adapter, caps)     MockChatAdapter ──> registered providers
+//!     .with_vector_store(store)               swept by the ArtifactCollector
+//!     .build().await
+//!         │
+//!         v
+//! AgentDomain ── repository() / messages() / metrics() / save()
+//!     │
+//!     └── collect_artifacts(now) ── LiveReferences from the event store ──> GcReport
+//! ```
+//!
+//! The mock provider is only registered when no provider is given.
+//! Cancellation and first-token latency are left to the host, which owns the
+//! loop that consumes response streams.
+
+use crate::adapters::ProviderRegistry;
+use crate::aggregate::Agent;
+use crate::capabilities::ProviderCapabilities;
+use crate::events::AgentEvent;
+use crate::infrastructure::{
+    AgentRepository, ArtifactKind, ArtifactStore, BlobStore, DomainError, DomainResult,
+    EventSigner, EventStore, InMemoryBlobStore, InMemoryEventStore, InMemorySnapshotStore,
+    MetricsRegistry, NatsEventPublisher, NatsEventStore, SnapshotStore,
+};
+use crate::ports::{ChatPort, EmbeddingPort, MockChatAdapter};
+use crate::services::{
+    AgentMessageService, ArtifactCollector, CapabilityRouter, GcReport, LiveReferences,
+    LongContextProfile,
+};
+use crate::value_objects::{next_id, AgentId, ProviderType};
+use async_nats::jetstream;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Events between snapshots unless overridden
+pub const DEFAULT_SNAPSHOT_FREQUENCY: u64 = 100;
+
+/// Agents kept in the repository cache unless overridden
+pub const DEFAULT_CACHE_CAPACITY: usize = 1_000;
+
+/// Age below which unreferenced artifacts are kept, unless overridden
+pub const DEFAULT_ARTIFACT_GRACE: Duration = Duration::from_secs(24 * 3600);
+
+/// JetStream stream backing the event store and publisher
+#[derive(Clone)]
+struct NatsSettings {
+    jetstream: jetstream::Context,
+    stream_name: String,
+}
+
+/// Where [`AgentDomain::collect_artifacts`] reads the events to mark from
+#[derive(Clone)]
+enum EventSource {
+    Nats(NatsSettings),
+    Memory(Arc<InMemoryEventStore>),
+}
+
+/// Assembles an [`AgentDomain`], in-memory unless told otherwise
+pub struct AgentDomainBuilder {
+    nats: Option<NatsSettings>,
+    signer: Option<EventSigner>,
+    snapshot_store: Arc<dyn SnapshotStore>,
+    snapshot_frequency: u64,
+    cache_capacity: usize,
+    providers: ProviderRegistry,
+    long_context: Option<LongContextProfile>,
+    blobs: Arc<dyn BlobStore>,
+    blob_artifacts: Arc<dyn ArtifactStore>,
+    vector_stores: Vec<Arc<dyn ArtifactStore>>,
+    references: LiveReferences,
+    artifact_grace: Duration,
+}
+
+impl AgentDomainBuilder {
+    /// In-memory stores, the mock provider and default services
+    pub fn new() -> Self {
+        let blobs = Arc::new(InMemoryBlobStore::new());
+        Self {
+            nats: None,
+            signer: None,
+            snapshot_store: Arc::new(InMemorySnapshotStore::new()),
+            snapshot_frequency: DEFAULT_SNAPSHOT_FREQUENCY,
+            cache_capacity: DEFAULT_CACHE_CAPACITY,
+            providers: ProviderRegistry::new(),
+            long_context: None,
+            blobs: blobs.clone(),
+            blob_artifacts: blobs,
+            vector_stores: Vec::new(),
+            references: LiveReferences::new(),
+            artifact_grace: DEFAULT_ARTIFACT_GRACE,
+        }
+    }
+
+    /// Builder: store and publish events on JetStream, in `stream_name`
+    ///
+    /// The stream is created on [`build`](Self::build) if it does not exist.
+    pub fn with_nats(
+        mut self,
+        jetstream: jetstream::Context,
+        stream_name: impl Into<String>,
+    ) -> Self {
+        self.nats = Some(NatsSettings {
+            jetstream,
+            stream_name: stream_name.into(),
+        });
+        self
+    }
+
+    /// Builder: sign events stored and published on NATS
+    pub fn with_signer(mut self, signer: EventSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Builder: keep snapshots in `store`
+    pub fn with_snapshot_store(mut self, store: Arc<dyn SnapshotStore>) -> Self {
+        self.snapshot_store = store;
+        self
+    }
+
+    /// Builder: snapshot every `frequency` events
+    pub fn with_snapshot_frequency(mut self, frequency: u64) -> Self {
+        self.snapshot_frequency = frequency;
+        self
+    }
+
+    /// Builder: cache up to `capacity` rehydrated agents (0 disables)
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Builder: route to `adapter` for requirements within `capabilities`
+    pub fn with_provider(
+        mut self,
+        provider_type: ProviderType,
+        adapter: Arc<dyn ChatPort>,
+        capabilities: ProviderCapabilities,
+    ) -> Self {
+        self.providers
+            .register_shared(provider_type, adapter, capabilities);
+        self
+    }
+
+    /// Builder: embed with `embedder` for an already added provider
+    pub fn with_embedder(
+        mut self,
+        provider_type: ProviderType,
+        embedder: Arc<dyn EmbeddingPort>,
+    ) -> Self {
+        self.providers.register_embedder(provider_type, embedder);
+        self
+    }
+
+    /// Builder: a store of embedding vectors, swept by the artifact collector
+    ///
+    /// Only swept once a [reference field](Self::with_reference_field) names
+    /// embeddings; [`build`](Self::build) refuses stores that hold another
+    /// kind of artifact.
+    pub fn with_vector_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.vector_stores.push(store);
+        self
+    }
+
+    /// Builder: treat values of the event field `field` as references to `kind`
+    ///
+    /// Events only record blobs (`cid`) by default; see [`LiveReferences`].
+    pub fn with_reference_field(mut self, field: impl Into<String>, kind: ArtifactKind) -> Self {
+        self.references = self.references.with_field(field, kind);
+        self
+    }
+
+    /// Builder: keep uploaded message content in `store` instead of memory
+    pub fn with_blob_store<S>(mut self, store: Arc<S>) -> Self
+    where
+        S: BlobStore + ArtifactStore + 'static,
+    {
+        self.blob_artifacts = store.clone();
+        self.blobs = store;
+        self
+    }
+
+    /// Builder: answer oversized contexts with a long-context model
+    pub fn with_long_context_fallback(mut self, profile: LongContextProfile) -> Self {
+        self.long_context = Some(profile);
+        self
+    }
+
+    /// Builder: keep unreferenced artifacts younger than `grace`
+    pub fn with_artifact_grace(mut self, grace: Duration) -> Self {
+        self.artifact_grace = grace;
+        self
+    }
+
+    /// Assemble the domain, creating the JetStream stream when NATS is used
+    pub async fn build(self) -> DomainResult<AgentDomain> {
+        if let Some(store) = self
+            .vector_stores
+            .iter()
+            .find(|s| s.kind() != ArtifactKind::Embedding)
+        {
+            return Err(DomainError::ValidationError(format!(
+                "vector store holds {} artifacts, not embeddings",
+                store.kind()
+            )));
+        }
+
+        let (event_store, publisher, source): (Arc<dyn EventStore>, _, _) = match self.nats {
+            Some(settings) => {
+                let NatsSettings {
+                    jetstream,
+                    stream_name,
+                } = settings.clone();
+                NatsEventStore::ensure_stream(&jetstream, &stream_name)
+                    .await
+                    .map_err(|e| DomainError::EventStoreError(e.to_string()))?;
+                let mut store = NatsEventStore::new(jetstream.clone(), stream_name);
+                let mut publisher = NatsEventPublisher::new(jetstream);
+                if let Some(signer) = self.signer {
+                    store = store.with_signer(signer.clone());
+                    publisher = publisher.with_signer(signer);
+                }
+                (
+                    Arc::new(store),
+                    Some(Arc::new(publisher)),
+                    EventSource::Nats(settings),
+                )
+            }
+            None => {
+                let store = Arc::new(InMemoryEventStore::new());
+                (store.clone(), None, EventSource::Memory(store))
+            }
+        };
+        let repository = AgentRepository::new(
+            event_store.clone(),
+            self.snapshot_store,
+            self.snapshot_frequency,
+        )
+        .with_cache(self.cache_capacity);
+
+        let mut providers = self.providers;
+        if providers.list_providers().is_empty() {
+            providers.register(
+                ProviderType::Mock,
+                MockChatAdapter::new(),
+                ProviderCapabilities::mock(),
+            );
+        }
+        let mut messages = AgentMessageService::new(CapabilityRouter::new(providers));
+        if let Some(profile) = self.long_context {
+            messages = messages.with_long_context_fallback(profile);
+        }
+
+        let artifacts = self.vector_stores.into_iter().fold(
+            ArtifactCollector::new(self.artifact_grace).with_store(self.blob_artifacts),
+            ArtifactCollector::with_store,
+        );
+
+        Ok(AgentDomain {
+            event_store,
+            repository: Arc::new(repository),
+            publisher,
+            messages: Arc::new(messages),
+            metrics: Arc::new(MetricsRegistry::new()),
+            blobs: self.blobs,
+            artifacts: Arc::new(artifacts),
+            references: self.references,
+            source,
+        })
+    }
+}
+
+impl Default for AgentDomainBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stores, adapters and services of the agent domain, wired together
+#[derive(Clone)]
+pub struct AgentDomain {
+    event_store: Arc<dyn EventStore>,
+    repository: Arc<AgentRepository>,
+    publisher: Option<Arc<NatsEventPublisher>>,
+    messages: Arc<AgentMessageService>,
+    metrics: Arc<MetricsRegistry>,
+    blobs: Arc<dyn BlobStore>,
+    artifacts: Arc<ArtifactCollector>,
+    references: LiveReferences,
+    source: EventSource,
+}
+
+impl AgentDomain {
+    /// Start assembling a domain
+    pub fn builder() -> AgentDomainBuilder {
+        AgentDomainBuilder::new()
+    }
+
+    /// Event store behind the repository
+    pub fn event_store(&self) -> &Arc<dyn EventStore> {
+        &self.event_store
+    }
+
+    /// Loads and saves agents
+    pub fn repository(&self) -> &Arc<AgentRepository> {
+        &self.repository
+    }
+
+    /// Publishes events to NATS subjects, when built with NATS
+    pub fn publisher(&self) -> Option<&Arc<NatsEventPublisher>> {
+        self.publisher.as_ref()
+    }
+
+    /// Routes messages to providers and streams their responses
+    pub fn messages(&self) -> &Arc<AgentMessageService> {
+        &self.messages
+    }
+
+    /// Metrics fed by [`save`](Self::save)
+    pub fn metrics(&self) -> &Arc<MetricsRegistry> {
+        &self.metrics
+    }
+
+    /// Uploaded message content
+    pub fn blobs(&self) -> &Arc<dyn BlobStore> {
+        &self.blobs
+    }
+
+    /// Delete blobs and vectors no stored event references
+    ///
+    /// Marks references by replaying every event in the event store, so an
+    /// artifact is only swept once no agent's history names it. A failed
+    /// replay sweeps nothing.
+    pub async fn collect_artifacts(&self, now: DateTime<Utc>) -> DomainResult<GcReport> {
+        let mut live = self.references.clone();
+        match &self.source {
+            EventSource::Nats(NatsSettings {
+                jetstream,
+                stream_name,
+            }) => {
+                let mut stream = jetstream
+                    .get_stream(stream_name)
+                    .await
+                    .map_err(|e| DomainError::EventStoreError(e.to_string()))?;
+                live.scan_stream(&mut stream).await?;
+            }
+            EventSource::Memory(store) => {
+                for envelope in store.all_events() {
+                    live.observe_event(&envelope)?;
+                }
+            }
+        }
+        Ok(self.artifacts.collect(&live, now).await)
+    }
+
+    /// Load an agent by ID
+    pub async fn load(&self, agent_id: AgentId) -> DomainResult<Option<Agent>> {
+        self.repository.load(agent_id).await
+    }
+
+    /// Save `agent` with the `events` that produced it, then publish them
+    ///
+    /// Returns the event stream version after the append. Events are
+    /// counted in [`metrics`](Self::metrics) and, with NATS, published under
+    /// one correlation ID.
+    pub async fn save(
+        &self,
+        agent: &Agent,
+        events: Vec<AgentEvent>,
+        expected_version: Option<u64>,
+    ) -> DomainResult<u64> {
+        let version = self
+            .repository
+            .save(agent, events.clone(), expected_version)
+            .await?;
+        let correlation_id = next_id();
+        for event in events {
+            self.metrics.observe_event(&event);
+            if let Some(publisher) = &self.publisher {
+                publisher
+                    .publish(agent.id(), event, correlation_id, correlation_id)
+                    .await
+                    .map_err(|e| DomainError::EventStoreError(e.to_string()))?;
+            }
+        }
+        Ok(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, MessageSentEvent};
+    use crate::value_objects::{ContentRef, MessageId, PersonId};
+
+    #[tokio::test]
+    async fn test_default_domain_is_in_memory_with_mock_provider() {
+        let domain = AgentDomain::builder().build().await.unwrap();
+        assert!(domain.publisher().is_none());
+        assert!(domain
+            .messages()
+            .router()
+            .registry()
+            .has_provider(&ProviderType::Mock));
+
+        let agent_id = AgentId::new();
+        let deployed = AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            "Embedded",
+            None,
+        ));
+        let agent = Agent::empty().apply_event(&deployed).unwrap();
+        assert_eq!(domain.save(&agent, vec![deployed], None).await.unwrap(), 1);
+
+        assert_eq!(domain.load(agent_id).await.unwrap().unwrap().id(), agent_id);
+        assert_eq!(domain.metrics().snapshot().counter("events.deployed"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_collect_artifacts_keeps_blobs_stored_events_reference() {
+        let domain = AgentDomain::builder().build().await.unwrap();
+        let (kept, orphan) = (ContentRef::of(b"contract"), ContentRef::of(b"draft"));
+        domain.blobs().put(&kept, b"contract".to_vec()).await.unwrap();
+        domain.blobs().put(&orphan, b"draft".to_vec()).await.unwrap();
+
+        let agent_id = AgentId::new();
+        let deployed = AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            "Archivist",
+            None,
+        ));
+        let agent = Agent::empty().apply_event(&deployed).unwrap();
+        let sent = AgentEvent::MessageSent(
+            MessageSentEvent::new(agent_id, MessageId::new(), "")
+                .with_content_ref(Some(kept.clone())),
+        );
+        domain.save(&agent, vec![deployed, sent], None).await.unwrap();
+
+        let later = Utc::now() + chrono::Duration::from_std(DEFAULT_ARTIFACT_GRACE * 2).unwrap();
+        let report = domain.collect_artifacts(later).await.unwrap();
+        assert_eq!((report.live, report.total_deleted()), (1, 1));
+        assert!(domain.blobs().get(&kept).await.unwrap().is_some());
+        assert!(domain.blobs().get(&orphan).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_store_of_other_artifacts_is_not_a_vector_store() {
+        let built = AgentDomain::builder()
+            .with_vector_store(Arc::new(InMemoryBlobStore::new()))
+            .build()
+            .await;
+        assert!(matches!(built, Err(DomainError::ValidationError(_))));
+    }
+}
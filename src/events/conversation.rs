@@ -0,0 +1,486 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Events for the Conversation aggregate
+//!
+//! Events record who took part in a conversation and in what role, which
+//! messages were posted, and when each participant received and read them.
+
+use crate::value_objects::{
+    clock_now, AgentId, BudgetLimit, ConversationBudget, ConversationHandoff, ConversationId,
+    MessageId, MessageProvenance, Participant, ParticipantId, ParticipantRole, PersonId,
+};
+use chrono::{DateTime, Utc};
+use cim_domain::DomainEvent;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// All conversation events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ConversationEvent {
+    /// Conversation was started by its owner
+    Started(ConversationStartedEvent),
+    /// A participant joined
+    ParticipantJoined(ParticipantJoinedEvent),
+    /// A participant left or was removed
+    ParticipantLeft(ParticipantLeftEvent),
+    /// A participant's role changed
+    ParticipantRoleChanged(ParticipantRoleChangedEvent),
+    /// A message was posted
+    MessagePosted(MessagePostedEvent),
+    /// A participant received a message
+    MessageDelivered(MessageDeliveredEvent),
+    /// A participant read a message
+    MessageRead(MessageReadEvent),
+    /// The conversation was closed
+    Closed(ConversationClosedEvent),
+    /// Another agent took the conversation over
+    Transferred(ConversationTransferredEvent),
+}
+
+impl ConversationEvent {
+    /// Get the conversation ID this event relates to
+    pub fn conversation_id(&self) -> ConversationId {
+        match self {
+            ConversationEvent::Started(e) => e.conversation_id,
+            ConversationEvent::ParticipantJoined(e) => e.conversation_id,
+            ConversationEvent::ParticipantLeft(e) => e.conversation_id,
+            ConversationEvent::ParticipantRoleChanged(e) => e.conversation_id,
+            ConversationEvent::MessagePosted(e) => e.conversation_id,
+            ConversationEvent::MessageDelivered(e) => e.conversation_id,
+            ConversationEvent::MessageRead(e) => e.conversation_id,
+            ConversationEvent::Closed(e) => e.conversation_id,
+            ConversationEvent::Transferred(e) => e.conversation_id,
+        }
+    }
+
+    /// Get the timestamp of this event
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            ConversationEvent::Started(e) => e.started_at,
+            ConversationEvent::ParticipantJoined(e) => e.participant.joined_at,
+            ConversationEvent::ParticipantLeft(e) => e.left_at,
+            ConversationEvent::ParticipantRoleChanged(e) => e.changed_at,
+            ConversationEvent::MessagePosted(e) => e.posted_at,
+            ConversationEvent::MessageDelivered(e) => e.delivered_at,
+            ConversationEvent::MessageRead(e) => e.read_at,
+            ConversationEvent::Closed(e) => e.closed_at,
+            ConversationEvent::Transferred(e) => e.transferred_at,
+        }
+    }
+
+    /// Get the event type name for NATS subjects
+    pub fn event_type_name(&self) -> &'static str {
+        match self {
+            ConversationEvent::Started(_) => "started",
+            ConversationEvent::ParticipantJoined(_) => "participant_joined",
+            ConversationEvent::ParticipantLeft(_) => "participant_left",
+            ConversationEvent::ParticipantRoleChanged(_) => "participant_role_changed",
+            ConversationEvent::MessagePosted(_) => "message_posted",
+            ConversationEvent::MessageDelivered(_) => "message_delivered",
+            ConversationEvent::MessageRead(_) => "message_read",
+            ConversationEvent::Closed(_) => "closed",
+            ConversationEvent::Transferred(_) => "transferred",
+        }
+    }
+}
+
+impl DomainEvent for ConversationEvent {
+    fn aggregate_id(&self) -> Uuid {
+        self.conversation_id().as_uuid()
+    }
+
+    fn event_type(&self) -> &'static str {
+        match self {
+            ConversationEvent::Started(_) => "ConversationStarted",
+            ConversationEvent::ParticipantJoined(_) => "ParticipantJoined",
+            ConversationEvent::ParticipantLeft(_) => "ParticipantLeft",
+            ConversationEvent::ParticipantRoleChanged(_) => "ParticipantRoleChanged",
+            ConversationEvent::MessagePosted(_) => "MessagePosted",
+            ConversationEvent::MessageDelivered(_) => "MessageDelivered",
+            ConversationEvent::MessageRead(_) => "MessageRead",
+            ConversationEvent::Closed(_) => "ConversationClosed",
+            ConversationEvent::Transferred(_) => "ConversationTransferred",
+        }
+    }
+}
+
+/// Conversation was started
+///
+/// The owner is the conversation's first participant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationStartedEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The person the conversation belongs to
+    pub owner: PersonId,
+
+    /// Limits the conversation runs under
+    #[serde(default, skip_serializing_if = "ConversationBudget::is_unlimited")]
+    pub budget: ConversationBudget,
+
+    /// When the conversation started
+    pub started_at: DateTime<Utc>,
+}
+
+impl ConversationStartedEvent {
+    /// Create a new ConversationStarted event
+    pub fn new(conversation_id: ConversationId, owner: PersonId) -> Self {
+        Self {
+            conversation_id,
+            owner,
+            budget: ConversationBudget::unlimited(),
+            started_at: clock_now(),
+        }
+    }
+
+    /// Builder: limits the conversation runs under
+    pub fn with_budget(mut self, budget: ConversationBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+}
+
+/// A participant joined the conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantJoinedEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The new participant, with role and join time
+    pub participant: Participant,
+
+    /// Participant who added them
+    pub added_by: ParticipantId,
+}
+
+impl ParticipantJoinedEvent {
+    /// Create a new ParticipantJoined event
+    pub fn new(
+        conversation_id: ConversationId,
+        participant: Participant,
+        added_by: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            participant,
+            added_by,
+        }
+    }
+}
+
+/// A participant left the conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantLeftEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The departing participant
+    pub participant_id: ParticipantId,
+
+    /// Participant who removed them (themselves when leaving)
+    pub removed_by: ParticipantId,
+
+    /// When they left
+    pub left_at: DateTime<Utc>,
+}
+
+impl ParticipantLeftEvent {
+    /// Create a new ParticipantLeft event
+    pub fn new(
+        conversation_id: ConversationId,
+        participant_id: ParticipantId,
+        removed_by: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            participant_id,
+            removed_by,
+            left_at: clock_now(),
+        }
+    }
+}
+
+/// A participant's role changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantRoleChangedEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The participant whose role changed
+    pub participant_id: ParticipantId,
+
+    /// Previous role
+    pub previous_role: ParticipantRole,
+
+    /// New role
+    pub new_role: ParticipantRole,
+
+    /// Participant who made the change
+    pub changed_by: ParticipantId,
+
+    /// When the role changed
+    pub changed_at: DateTime<Utc>,
+}
+
+impl ParticipantRoleChangedEvent {
+    /// Create a new ParticipantRoleChanged event
+    pub fn new(
+        conversation_id: ConversationId,
+        participant_id: ParticipantId,
+        previous_role: ParticipantRole,
+        new_role: ParticipantRole,
+        changed_by: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            participant_id,
+            previous_role,
+            new_role,
+            changed_by,
+            changed_at: clock_now(),
+        }
+    }
+}
+
+/// A message was posted to the conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagePostedEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The message
+    pub message_id: MessageId,
+
+    /// Participant who posted it
+    pub author: ParticipantId,
+
+    /// Tokens the message spent, counted against the conversation budget
+    #[serde(default)]
+    pub tokens: u64,
+
+    /// Whether the message is internal or posted by an external system
+    #[serde(default, skip_serializing_if = "MessageProvenance::is_internal")]
+    pub provenance: MessageProvenance,
+
+    /// When it was posted
+    pub posted_at: DateTime<Utc>,
+}
+
+impl MessagePostedEvent {
+    /// Create a new MessagePosted event
+    pub fn new(
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        author: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            message_id,
+            author,
+            tokens: 0,
+            provenance: MessageProvenance::Internal,
+            posted_at: clock_now(),
+        }
+    }
+
+    /// Builder: tokens the message spent
+    pub fn with_tokens(mut self, tokens: u64) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Builder: where the message came from
+    pub fn with_provenance(mut self, provenance: MessageProvenance) -> Self {
+        self.provenance = provenance;
+        self
+    }
+}
+
+/// A participant's client received a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDeliveredEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The message
+    pub message_id: MessageId,
+
+    /// Participant who received it
+    pub participant_id: ParticipantId,
+
+    /// When it was delivered
+    pub delivered_at: DateTime<Utc>,
+}
+
+impl MessageDeliveredEvent {
+    /// Create a new MessageDelivered event
+    pub fn new(
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        participant_id: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            message_id,
+            participant_id,
+            delivered_at: clock_now(),
+        }
+    }
+}
+
+/// A participant saw a message
+///
+/// Reading implies delivery; no separate `MessageDelivered` is required.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageReadEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The message
+    pub message_id: MessageId,
+
+    /// Participant who read it
+    pub participant_id: ParticipantId,
+
+    /// When it was read
+    pub read_at: DateTime<Utc>,
+}
+
+impl MessageReadEvent {
+    /// Create a new MessageRead event
+    pub fn new(
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        participant_id: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            message_id,
+            participant_id,
+            read_at: clock_now(),
+        }
+    }
+}
+
+/// Another agent took the conversation over
+///
+/// Marks the handoff point: messages up to `after_message` were answered by
+/// `from`, later ones by `to`, which takes `from`'s place and role.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationTransferredEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// The agent that handed the conversation off
+    pub from: AgentId,
+
+    /// The agent that took it over
+    pub to: AgentId,
+
+    /// Role `to` takes over from `from`
+    pub role: ParticipantRole,
+
+    /// Last message posted before the handoff, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_message: Option<MessageId>,
+
+    /// Reason, recent turns and memory passed to `to`
+    pub handoff: ConversationHandoff,
+
+    /// Participant who transferred it
+    pub transferred_by: ParticipantId,
+
+    /// When it was transferred
+    pub transferred_at: DateTime<Utc>,
+}
+
+impl ConversationTransferredEvent {
+    /// Create a new ConversationTransferred event
+    pub fn new(
+        conversation_id: ConversationId,
+        from: AgentId,
+        to: AgentId,
+        role: ParticipantRole,
+        handoff: ConversationHandoff,
+        transferred_by: ParticipantId,
+    ) -> Self {
+        Self {
+            conversation_id,
+            from,
+            to,
+            role,
+            after_message: None,
+            handoff,
+            transferred_by,
+            transferred_at: clock_now(),
+        }
+    }
+
+    /// Builder: the last message posted before the handoff
+    pub fn after(mut self, message_id: Option<MessageId>) -> Self {
+        self.after_message = message_id;
+        self
+    }
+}
+
+/// Why a conversation was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosureReason {
+    /// An owner closed it
+    Closed,
+    /// Nothing happened in it for its inactivity timeout
+    Timeout,
+    /// A limit of its budget was reached
+    BudgetExhausted,
+}
+
+/// The conversation was closed
+///
+/// A closed conversation accepts no further commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationClosedEvent {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// Why it was closed
+    pub reason: ClosureReason,
+
+    /// Participant who closed it (none for timeouts and budgets)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_by: Option<ParticipantId>,
+
+    /// The budget limit that ran out, for budget closures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exhausted: Option<BudgetLimit>,
+
+    /// When it was closed
+    pub closed_at: DateTime<Utc>,
+}
+
+impl ConversationClosedEvent {
+    /// Create a new ConversationClosed event
+    pub fn new(
+        conversation_id: ConversationId,
+        reason: ClosureReason,
+        closed_by: Option<ParticipantId>,
+    ) -> Self {
+        Self {
+            conversation_id,
+            reason,
+            closed_by,
+            exhausted: None,
+            closed_at: clock_now(),
+        }
+    }
+
+    /// The conversation ran out of its budget
+    pub fn budget_exhausted(conversation_id: ConversationId, limit: BudgetLimit) -> Self {
+        Self {
+            exhausted: Some(limit),
+            ..Self::new(conversation_id, ClosureReason::BudgetExhausted, None)
+        }
+    }
+}
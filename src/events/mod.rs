@@ -12,15 +12,55 @@
 //! - `ModelConfigured` - Model configuration was set (deprecated)
 //! - `ModelConfigurationAssigned` - Model configuration ID was assigned (new pattern)
 //! - `SystemPromptConfigured` - System prompt was configured for agent
+//! - `FeatureFlagsChanged` - Runtime feature flags were toggled
+//! - `LocalePreferencesChanged` - Agent locale and timezone preferences were set
 //! - `AgentActivated` - Agent was activated
 //! - `AgentSuspended` - Agent was suspended
 //! - `AgentDecommissioned` - Agent was permanently decommissioned
+//! - `LegalHoldPlaced` - A legal hold froze the agent's data
+//! - `LegalHoldReleased` - A legal hold on the agent's data was lifted
 //!
 //! ### Agent Message Events (streaming)
 //! - `MessageSent` - Message was sent to model
 //! - `ResponseChunkReceived` - Streaming chunk received from model
 //! - `ResponseCompleted` - Full response completed
 //! - `ResponseFailed` - Response generation failed
+//! - `OutputLimitEnforced` - Client-side stop sequence or max tokens cut a response
+//! - `ResponseCancelled` - Response stream was aborted by `CancelMessage`
+//!
+//! ### Operational Events
+//! - `SloViolated` - First-token latency is burning the SLO error budget
+//! - `DailyDigestReady` - A day's activity for an agent has been rolled up
+//! - `ConversationArchived` - An idle conversation was moved to the archival store
+//! - `ConfigurationDriftDetected` - A live agent no longer matches its declared definition
+//! - `CanaryDecided` - A canary rollout was promoted or rolled back
+//! - `EvaluationCompleted` - An evaluation suite was scored against an agent
+//! - `CapabilityNegotiated` - Capability handshake with a delegation target concluded
+//! - `DeadlineExceeded` - A request missed its caller's deadline
+//! - `LongContextFallback` - A request moved to the long-context model
+//! - `ContextTruncated` - Context was truncated to fit the model's window
+//! - `CandidateSelected` - A judge picked one of several candidate answers
+//! - `ResponseDrafted` - A reflected answer was superseded by a revision
+//! - `ReasoningTraceRecorded` - Structured decision trace for a message was recorded
+//! - `DeprecatedToolInUse` - Agent has a deprecated tool version enabled
+//! - `ToolVersionMigrated` - Agent's enabled tool moved to another version
+//! - `ToolInvoked` - An agent's tool was called
+//! - `ToolSucceeded` - A tool call returned a result
+//! - `ToolFailed` - A tool call failed
+//! - `LegalHoldRefused` - Archival, shredding or retention was refused by a legal hold
+//! - `PermissionEscalationRequested` - A principal lacked a permission; the owner was asked
+//! - `PermissionEscalationApproved` - The owner approved an escalation with a temporary grant
+//! - `PermissionEscalationExpired` - An escalation was not approved in time
+//! - `AnomalyDetected` - An agent's behavior metric deviated from its baseline
+//! - `BatchProgress` - An offline batch run through the agent reported progress
+//! - `BatchCompleted` - An offline batch finished and its results were stored
+//! - `MessageRetryScheduled` - A failed message will be sent again after a backoff
+//! - `MessageQuarantined` - A message kept failing and was set aside for review
+//! - `QuarantinedMessageReleased` - An operator released a quarantined message for retry
+//! - `CapabilityVerificationFailed` - A declared capability failed its runtime probe
+//! - `ChangeRateExceeded` - Configuration or permission changes came faster than allowed
+//! - `OutputConstraintEnforced` - An answer broke output constraints and was regenerated or failed
+//! - `SpendThresholdCrossed` - An agent's estimated spend for the day passed its alert threshold
 //!
 //! ### Model Configuration Events
 //! - `ModelConfigurationCreated` - Configuration was created
@@ -29,21 +69,46 @@
 //! - `ModelConfigurationActivated` - Configuration was activated
 //! - `ModelConfigurationDeprecated` - Configuration was deprecated
 //! - `ModelConfigurationArchived` - Configuration was archived
-
+//!
+//! ### Conversation Events
+//! - `ConversationStarted` - Conversation was started by its owner
+//! - `ParticipantJoined` - A participant joined with a role
+//! - `ParticipantLeft` - A participant left or was removed
+//! - `ParticipantRoleChanged` - A participant's role changed
+//! - `MessagePosted` - A message was posted to the conversation
+//! - `MessageDelivered` - A participant received a message
+//! - `MessageRead` - A participant read a message
+//! - `ConversationClosed` - An owner closed the conversation, or it timed out
+//! - `ConversationTransferred` - Another agent took the conversation over at a handoff point
+
+mod conversation;
 mod model_configuration;
 
+pub use conversation::{
+    ClosureReason, ConversationClosedEvent, ConversationEvent, ConversationStartedEvent,
+    ConversationTransferredEvent, MessageDeliveredEvent, MessagePostedEvent, MessageReadEvent,
+    ParticipantJoinedEvent, ParticipantLeftEvent, ParticipantRoleChangedEvent,
+};
 pub use model_configuration::{
     ModelConfigurationActivatedEvent, ModelConfigurationArchivedEvent,
     ModelConfigurationCreatedEvent, ModelConfigurationDeprecatedEvent,
     ModelConfigurationEvent, ModelParametersUpdatedEvent, ModelProviderChangedEvent,
 };
 
+use crate::capabilities::RuntimeCapabilities;
+use crate::commands::AgentCommand;
 use crate::value_objects::{
-    AgentId, FinishReason, MessageId, ModelConfig, ModelConfigurationId, PersonId, StreamingChunk, TokenUsage,
+    acting_principal, clock_now, AgentId, AgentPermission, ConstraintEnforcement, ContentRef,
+    ConversationId, CostTags, FinishReason, HeldOperation, LegalHold, LocalePreferences,
+    MessageId, ModelConfig, ModelConfigurationId, OutputEnforcement, PermissionDenied, PersonId,
+    PrincipalClass, ReasoningTrace, ReflectionDraft, ResponseCandidate, StreamingChunk,
+    TokenUsage, ToxicityScore, TruncationRecord,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use cim_domain::DomainEvent;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use uuid::Uuid;
 
 /// All agent events
@@ -64,6 +129,46 @@ pub enum AgentEvent {
     ResponseChunkReceived(ResponseChunkReceivedEvent),
     ResponseCompleted(ResponseCompletedEvent),
     ResponseFailed(ResponseFailedEvent),
+    OutputLimitEnforced(OutputLimitEnforcedEvent),
+    ResponseCancelled(ResponseCancelledEvent),
+
+    // Operational events
+    SloViolated(SloViolatedEvent),
+    DailyDigestReady(DailyDigestReadyEvent),
+    ConversationArchived(ConversationArchivedEvent),
+    ConfigurationDriftDetected(ConfigurationDriftDetectedEvent),
+    CanaryDecided(CanaryDecidedEvent),
+    EvaluationCompleted(EvaluationCompletedEvent),
+    CapabilityNegotiated(CapabilityNegotiatedEvent),
+    DeadlineExceeded(DeadlineExceededEvent),
+    FeatureFlagsChanged(FeatureFlagsChangedEvent),
+    LongContextFallback(LongContextFallbackEvent),
+    ContextTruncated(ContextTruncatedEvent),
+    CandidateSelected(CandidateSelectedEvent),
+    ResponseDrafted(ResponseDraftedEvent),
+    ReasoningTraceRecorded(ReasoningTraceRecordedEvent),
+    LocalePreferencesChanged(LocalePreferencesChangedEvent),
+    DeprecatedToolInUse(DeprecatedToolInUseEvent),
+    ToolVersionMigrated(ToolVersionMigratedEvent),
+    ToolInvoked(ToolInvokedEvent),
+    ToolSucceeded(ToolSucceededEvent),
+    ToolFailed(ToolFailedEvent),
+    LegalHoldRefused(LegalHoldRefusedEvent),
+    LegalHoldPlaced(LegalHoldPlacedEvent),
+    LegalHoldReleased(LegalHoldReleasedEvent),
+    PermissionEscalationRequested(PermissionEscalationRequestedEvent),
+    PermissionEscalationApproved(PermissionEscalationApprovedEvent),
+    PermissionEscalationExpired(PermissionEscalationExpiredEvent),
+    AnomalyDetected(AnomalyDetectedEvent),
+    BatchProgress(BatchProgressEvent),
+    BatchCompleted(BatchCompletedEvent),
+    MessageRetryScheduled(MessageRetryScheduledEvent),
+    MessageQuarantined(MessageQuarantinedEvent),
+    QuarantinedMessageReleased(QuarantinedMessageReleasedEvent),
+    CapabilityVerificationFailed(CapabilityVerificationFailedEvent),
+    ChangeRateExceeded(ChangeRateExceededEvent),
+    OutputConstraintEnforced(OutputConstraintEnforcedEvent),
+    SpendThresholdCrossed(SpendThresholdCrossedEvent),
 }
 
 impl AgentEvent {
@@ -81,6 +186,44 @@ impl AgentEvent {
             AgentEvent::ResponseChunkReceived(e) => e.agent_id,
             AgentEvent::ResponseCompleted(e) => e.agent_id,
             AgentEvent::ResponseFailed(e) => e.agent_id,
+            AgentEvent::OutputLimitEnforced(e) => e.agent_id,
+            AgentEvent::ResponseCancelled(e) => e.agent_id,
+            AgentEvent::SloViolated(e) => e.agent_id,
+            AgentEvent::DailyDigestReady(e) => e.agent_id,
+            AgentEvent::ConversationArchived(e) => e.agent_id,
+            AgentEvent::ConfigurationDriftDetected(e) => e.agent_id,
+            AgentEvent::CanaryDecided(e) => e.agent_id,
+            AgentEvent::EvaluationCompleted(e) => e.agent_id,
+            AgentEvent::CapabilityNegotiated(e) => e.agent_id,
+            AgentEvent::DeadlineExceeded(e) => e.agent_id,
+            AgentEvent::FeatureFlagsChanged(e) => e.agent_id,
+            AgentEvent::LongContextFallback(e) => e.agent_id,
+            AgentEvent::ContextTruncated(e) => e.agent_id,
+            AgentEvent::CandidateSelected(e) => e.agent_id,
+            AgentEvent::ResponseDrafted(e) => e.agent_id,
+            AgentEvent::ReasoningTraceRecorded(e) => e.agent_id,
+            AgentEvent::LocalePreferencesChanged(e) => e.agent_id,
+            AgentEvent::DeprecatedToolInUse(e) => e.agent_id,
+            AgentEvent::ToolVersionMigrated(e) => e.agent_id,
+            AgentEvent::ToolInvoked(e) => e.agent_id,
+            AgentEvent::ToolSucceeded(e) => e.agent_id,
+            AgentEvent::ToolFailed(e) => e.agent_id,
+            AgentEvent::LegalHoldRefused(e) => e.agent_id,
+            AgentEvent::LegalHoldPlaced(e) => e.agent_id,
+            AgentEvent::LegalHoldReleased(e) => e.agent_id,
+            AgentEvent::PermissionEscalationRequested(e) => e.agent_id,
+            AgentEvent::PermissionEscalationApproved(e) => e.agent_id,
+            AgentEvent::PermissionEscalationExpired(e) => e.agent_id,
+            AgentEvent::AnomalyDetected(e) => e.agent_id,
+            AgentEvent::BatchProgress(e) => e.agent_id,
+            AgentEvent::BatchCompleted(e) => e.agent_id,
+            AgentEvent::MessageRetryScheduled(e) => e.agent_id,
+            AgentEvent::MessageQuarantined(e) => e.agent_id,
+            AgentEvent::QuarantinedMessageReleased(e) => e.agent_id,
+            AgentEvent::CapabilityVerificationFailed(e) => e.agent_id,
+            AgentEvent::ChangeRateExceeded(e) => e.agent_id,
+            AgentEvent::OutputConstraintEnforced(e) => e.agent_id,
+            AgentEvent::SpendThresholdCrossed(e) => e.agent_id,
         }
     }
 
@@ -98,6 +241,64 @@ impl AgentEvent {
             AgentEvent::ResponseChunkReceived(e) => e.received_at,
             AgentEvent::ResponseCompleted(e) => e.completed_at,
             AgentEvent::ResponseFailed(e) => e.failed_at,
+            AgentEvent::OutputLimitEnforced(e) => e.enforced_at,
+            AgentEvent::ResponseCancelled(e) => e.cancelled_at,
+            AgentEvent::SloViolated(e) => e.violated_at,
+            AgentEvent::DailyDigestReady(e) => e.ready_at,
+            AgentEvent::ConversationArchived(e) => e.archived_at,
+            AgentEvent::ConfigurationDriftDetected(e) => e.detected_at,
+            AgentEvent::CanaryDecided(e) => e.decided_at,
+            AgentEvent::EvaluationCompleted(e) => e.completed_at,
+            AgentEvent::CapabilityNegotiated(e) => e.negotiated_at,
+            AgentEvent::DeadlineExceeded(e) => e.exceeded_at,
+            AgentEvent::FeatureFlagsChanged(e) => e.changed_at,
+            AgentEvent::LongContextFallback(e) => e.switched_at,
+            AgentEvent::ContextTruncated(e) => e.truncated_at,
+            AgentEvent::CandidateSelected(e) => e.selected_at,
+            AgentEvent::ResponseDrafted(e) => e.drafted_at,
+            AgentEvent::ReasoningTraceRecorded(e) => e.recorded_at,
+            AgentEvent::LocalePreferencesChanged(e) => e.changed_at,
+            AgentEvent::DeprecatedToolInUse(e) => e.notified_at,
+            AgentEvent::ToolVersionMigrated(e) => e.migrated_at,
+            AgentEvent::ToolInvoked(e) => e.invoked_at,
+            AgentEvent::ToolSucceeded(e) => e.completed_at,
+            AgentEvent::ToolFailed(e) => e.failed_at,
+            AgentEvent::LegalHoldRefused(e) => e.refused_at,
+            AgentEvent::LegalHoldPlaced(e) => e.placed_at,
+            AgentEvent::LegalHoldReleased(e) => e.released_at,
+            AgentEvent::PermissionEscalationRequested(e) => e.requested_at,
+            AgentEvent::PermissionEscalationApproved(e) => e.approved_at,
+            AgentEvent::PermissionEscalationExpired(e) => e.expired_at,
+            AgentEvent::AnomalyDetected(e) => e.detected_at,
+            AgentEvent::BatchProgress(e) => e.reported_at,
+            AgentEvent::BatchCompleted(e) => e.completed_at,
+            AgentEvent::MessageRetryScheduled(e) => e.scheduled_at,
+            AgentEvent::MessageQuarantined(e) => e.quarantined_at,
+            AgentEvent::QuarantinedMessageReleased(e) => e.released_at,
+            AgentEvent::CapabilityVerificationFailed(e) => e.failed_at,
+            AgentEvent::ChangeRateExceeded(e) => e.detected_at,
+            AgentEvent::OutputConstraintEnforced(e) => e.enforced_at,
+            AgentEvent::SpendThresholdCrossed(e) => e.crossed_at,
+        }
+    }
+
+    /// Principal class that issued the command behind this event
+    ///
+    /// `None` for events the service raises on its own, and for command
+    /// events stored before principals were recorded.
+    pub fn performed_by(&self) -> Option<PrincipalClass> {
+        match self {
+            AgentEvent::AgentDeployed(e) => e.performed_by,
+            AgentEvent::ModelConfigured(e) => e.performed_by,
+            AgentEvent::AgentActivated(e) => e.performed_by,
+            AgentEvent::AgentSuspended(e) => e.performed_by,
+            AgentEvent::AgentDecommissioned(e) => e.performed_by,
+            AgentEvent::FeatureFlagsChanged(e) => e.performed_by,
+            AgentEvent::LocalePreferencesChanged(e) => e.performed_by,
+            AgentEvent::LegalHoldPlaced(e) => e.performed_by,
+            AgentEvent::LegalHoldReleased(e) => e.performed_by,
+            AgentEvent::MessageSent(e) => e.performed_by,
+            _ => None,
         }
     }
 
@@ -115,6 +316,44 @@ impl AgentEvent {
             AgentEvent::ResponseChunkReceived(_) => "response_chunk",
             AgentEvent::ResponseCompleted(_) => "response_completed",
             AgentEvent::ResponseFailed(_) => "response_failed",
+            AgentEvent::OutputLimitEnforced(_) => "output_limit_enforced",
+            AgentEvent::ResponseCancelled(_) => "response_cancelled",
+            AgentEvent::SloViolated(_) => "slo_violated",
+            AgentEvent::DailyDigestReady(_) => "daily_digest_ready",
+            AgentEvent::ConversationArchived(_) => "conversation_archived",
+            AgentEvent::ConfigurationDriftDetected(_) => "configuration_drift_detected",
+            AgentEvent::CanaryDecided(_) => "canary_decided",
+            AgentEvent::EvaluationCompleted(_) => "evaluation_completed",
+            AgentEvent::CapabilityNegotiated(_) => "capability_negotiated",
+            AgentEvent::DeadlineExceeded(_) => "deadline_exceeded",
+            AgentEvent::FeatureFlagsChanged(_) => "feature_flags_changed",
+            AgentEvent::LongContextFallback(_) => "long_context_fallback",
+            AgentEvent::ContextTruncated(_) => "context_truncated",
+            AgentEvent::CandidateSelected(_) => "candidate_selected",
+            AgentEvent::ResponseDrafted(_) => "response_drafted",
+            AgentEvent::ReasoningTraceRecorded(_) => "reasoning_trace_recorded",
+            AgentEvent::LocalePreferencesChanged(_) => "locale_preferences_changed",
+            AgentEvent::DeprecatedToolInUse(_) => "deprecated_tool_in_use",
+            AgentEvent::ToolVersionMigrated(_) => "tool_version_migrated",
+            AgentEvent::ToolInvoked(_) => "tool_invoked",
+            AgentEvent::ToolSucceeded(_) => "tool_succeeded",
+            AgentEvent::ToolFailed(_) => "tool_failed",
+            AgentEvent::LegalHoldRefused(_) => "legal_hold_refused",
+            AgentEvent::LegalHoldPlaced(_) => "legal_hold_placed",
+            AgentEvent::LegalHoldReleased(_) => "legal_hold_released",
+            AgentEvent::PermissionEscalationRequested(_) => "permission_escalation_requested",
+            AgentEvent::PermissionEscalationApproved(_) => "permission_escalation_approved",
+            AgentEvent::PermissionEscalationExpired(_) => "permission_escalation_expired",
+            AgentEvent::AnomalyDetected(_) => "anomaly_detected",
+            AgentEvent::BatchProgress(_) => "batch_progress",
+            AgentEvent::BatchCompleted(_) => "batch_completed",
+            AgentEvent::MessageRetryScheduled(_) => "message_retry_scheduled",
+            AgentEvent::MessageQuarantined(_) => "message_quarantined",
+            AgentEvent::QuarantinedMessageReleased(_) => "quarantined_message_released",
+            AgentEvent::CapabilityVerificationFailed(_) => "capability_verification_failed",
+            AgentEvent::ChangeRateExceeded(_) => "change_rate_exceeded",
+            AgentEvent::OutputConstraintEnforced(_) => "output_constraint_enforced",
+            AgentEvent::SpendThresholdCrossed(_) => "spend_threshold_crossed",
         }
     }
 }
@@ -137,6 +376,44 @@ impl DomainEvent for AgentEvent {
             AgentEvent::ResponseChunkReceived(_) => "ResponseChunkReceived",
             AgentEvent::ResponseCompleted(_) => "ResponseCompleted",
             AgentEvent::ResponseFailed(_) => "ResponseFailed",
+            AgentEvent::OutputLimitEnforced(_) => "OutputLimitEnforced",
+            AgentEvent::ResponseCancelled(_) => "ResponseCancelled",
+            AgentEvent::SloViolated(_) => "SloViolated",
+            AgentEvent::DailyDigestReady(_) => "DailyDigestReady",
+            AgentEvent::ConversationArchived(_) => "ConversationArchived",
+            AgentEvent::ConfigurationDriftDetected(_) => "ConfigurationDriftDetected",
+            AgentEvent::CanaryDecided(_) => "CanaryDecided",
+            AgentEvent::EvaluationCompleted(_) => "EvaluationCompleted",
+            AgentEvent::CapabilityNegotiated(_) => "CapabilityNegotiated",
+            AgentEvent::DeadlineExceeded(_) => "DeadlineExceeded",
+            AgentEvent::FeatureFlagsChanged(_) => "FeatureFlagsChanged",
+            AgentEvent::LongContextFallback(_) => "LongContextFallback",
+            AgentEvent::ContextTruncated(_) => "ContextTruncated",
+            AgentEvent::CandidateSelected(_) => "CandidateSelected",
+            AgentEvent::ResponseDrafted(_) => "ResponseDrafted",
+            AgentEvent::ReasoningTraceRecorded(_) => "ReasoningTraceRecorded",
+            AgentEvent::LocalePreferencesChanged(_) => "LocalePreferencesChanged",
+            AgentEvent::DeprecatedToolInUse(_) => "DeprecatedToolInUse",
+            AgentEvent::ToolVersionMigrated(_) => "ToolVersionMigrated",
+            AgentEvent::ToolInvoked(_) => "ToolInvoked",
+            AgentEvent::ToolSucceeded(_) => "ToolSucceeded",
+            AgentEvent::ToolFailed(_) => "ToolFailed",
+            AgentEvent::LegalHoldRefused(_) => "LegalHoldRefused",
+            AgentEvent::LegalHoldPlaced(_) => "LegalHoldPlaced",
+            AgentEvent::LegalHoldReleased(_) => "LegalHoldReleased",
+            AgentEvent::PermissionEscalationRequested(_) => "PermissionEscalationRequested",
+            AgentEvent::PermissionEscalationApproved(_) => "PermissionEscalationApproved",
+            AgentEvent::PermissionEscalationExpired(_) => "PermissionEscalationExpired",
+            AgentEvent::AnomalyDetected(_) => "AnomalyDetected",
+            AgentEvent::BatchProgress(_) => "BatchProgress",
+            AgentEvent::BatchCompleted(_) => "BatchCompleted",
+            AgentEvent::MessageRetryScheduled(_) => "MessageRetryScheduled",
+            AgentEvent::MessageQuarantined(_) => "MessageQuarantined",
+            AgentEvent::QuarantinedMessageReleased(_) => "QuarantinedMessageReleased",
+            AgentEvent::CapabilityVerificationFailed(_) => "CapabilityVerificationFailed",
+            AgentEvent::ChangeRateExceeded(_) => "ChangeRateExceeded",
+            AgentEvent::OutputConstraintEnforced(_) => "OutputConstraintEnforced",
+            AgentEvent::SpendThresholdCrossed(_) => "SpendThresholdCrossed",
         }
     }
 }
@@ -163,6 +440,10 @@ pub struct AgentDeployedEvent {
 
     /// When the agent was deployed
     pub deployed_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
 }
 
 impl AgentDeployedEvent {
@@ -178,7 +459,8 @@ impl AgentDeployedEvent {
             person_id,
             name: name.into(),
             description,
-            deployed_at: Utc::now(),
+            deployed_at: clock_now(),
+            performed_by: acting_principal(),
         }
     }
 }
@@ -195,6 +477,10 @@ pub struct ModelConfiguredEvent {
 
     /// When configuration was set
     pub configured_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
 }
 
 impl ModelConfiguredEvent {
@@ -203,7 +489,8 @@ impl ModelConfiguredEvent {
         Self {
             agent_id,
             config,
-            configured_at: Utc::now(),
+            configured_at: clock_now(),
+            performed_by: acting_principal(),
         }
     }
 }
@@ -231,7 +518,7 @@ impl ModelConfigurationAssignedEvent {
         Self {
             agent_id,
             configuration_id,
-            assigned_at: Utc::now(),
+            assigned_at: clock_now(),
         }
     }
 }
@@ -258,7 +545,7 @@ impl SystemPromptConfiguredEvent {
         Self {
             agent_id,
             system_prompt: system_prompt.into(),
-            configured_at: Utc::now(),
+            configured_at: clock_now(),
         }
     }
 }
@@ -271,6 +558,10 @@ pub struct AgentActivatedEvent {
 
     /// When the agent was activated
     pub activated_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
 }
 
 impl AgentActivatedEvent {
@@ -278,7 +569,8 @@ impl AgentActivatedEvent {
     pub fn new(agent_id: AgentId) -> Self {
         Self {
             agent_id,
-            activated_at: Utc::now(),
+            activated_at: clock_now(),
+            performed_by: acting_principal(),
         }
     }
 }
@@ -294,6 +586,10 @@ pub struct AgentSuspendedEvent {
 
     /// When the agent was suspended
     pub suspended_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
 }
 
 impl AgentSuspendedEvent {
@@ -302,7 +598,8 @@ impl AgentSuspendedEvent {
         Self {
             agent_id,
             reason: reason.into(),
-            suspended_at: Utc::now(),
+            suspended_at: clock_now(),
+            performed_by: acting_principal(),
         }
     }
 }
@@ -319,6 +616,10 @@ pub struct AgentDecommissionedEvent {
 
     /// When the agent was decommissioned
     pub decommissioned_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
 }
 
 impl AgentDecommissionedEvent {
@@ -327,7 +628,8 @@ impl AgentDecommissionedEvent {
         Self {
             agent_id,
             reason,
-            decommissioned_at: Utc::now(),
+            decommissioned_at: clock_now(),
+            performed_by: acting_principal(),
         }
     }
 }
@@ -345,11 +647,35 @@ pub struct MessageSentEvent {
     /// The message ID
     pub message_id: MessageId,
 
-    /// The message content
+    /// The message content (empty when uploaded)
     pub content: String,
 
+    /// Uploaded content, kept out of the event stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_ref: Option<ContentRef>,
+
     /// When the message was sent
     pub sent_at: DateTime<Utc>,
+
+    /// Labels attributing the message's cost
+    #[serde(default, skip_serializing_if = "CostTags::is_empty")]
+    pub cost_tags: CostTags,
+
+    /// Moderation score of the message, when scored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toxicity: Option<ToxicityScore>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
+
+    /// Primary agent's message this is a shadow copy of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_of: Option<MessageId>,
+
+    /// First attempt's message, when this is a retry of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<MessageId>,
 }
 
 impl MessageSentEvent {
@@ -359,9 +685,45 @@ impl MessageSentEvent {
             agent_id,
             message_id,
             content: content.into(),
-            sent_at: Utc::now(),
+            content_ref: None,
+            sent_at: clock_now(),
+            cost_tags: CostTags::new(),
+            toxicity: None,
+            performed_by: acting_principal(),
+            shadow_of: None,
+            retry_of: None,
         }
     }
+
+    /// Builder: the content was uploaded rather than sent inline
+    pub fn with_content_ref(mut self, content_ref: Option<ContentRef>) -> Self {
+        self.content_ref = content_ref;
+        self
+    }
+
+    /// Builder: attribute the message's cost
+    pub fn with_cost_tags(mut self, cost_tags: CostTags) -> Self {
+        self.cost_tags = cost_tags;
+        self
+    }
+
+    /// Builder: record the message's moderation score
+    pub fn with_toxicity(mut self, toxicity: ToxicityScore) -> Self {
+        self.toxicity = Some(toxicity);
+        self
+    }
+
+    /// Builder: the message is a shadow copy of the primary agent's `shadow_of`
+    pub fn with_shadow_of(mut self, shadow_of: Option<MessageId>) -> Self {
+        self.shadow_of = shadow_of;
+        self
+    }
+
+    /// Builder: the message is another attempt at `retry_of`
+    pub fn with_retry_of(mut self, retry_of: Option<MessageId>) -> Self {
+        self.retry_of = retry_of;
+        self
+    }
 }
 
 /// A streaming response chunk was received
@@ -387,7 +749,7 @@ impl ResponseChunkReceivedEvent {
             agent_id,
             message_id,
             chunk,
-            received_at: Utc::now(),
+            received_at: clock_now(),
         }
     }
 }
@@ -415,6 +777,14 @@ pub struct ResponseCompletedEvent {
 
     /// When the response completed
     pub completed_at: DateTime<Utc>,
+
+    /// Labels the response's token usage is attributed to
+    #[serde(default, skip_serializing_if = "CostTags::is_empty")]
+    pub cost_tags: CostTags,
+
+    /// Moderation score of the full response, when scored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toxicity: Option<ToxicityScore>,
 }
 
 impl ResponseCompletedEvent {
@@ -434,9 +804,23 @@ impl ResponseCompletedEvent {
             token_usage,
             finish_reason,
             duration_ms,
-            completed_at: Utc::now(),
+            completed_at: clock_now(),
+            cost_tags: CostTags::new(),
+            toxicity: None,
         }
     }
+
+    /// Builder: attribute the response's usage
+    pub fn with_cost_tags(mut self, cost_tags: CostTags) -> Self {
+        self.cost_tags = cost_tags;
+        self
+    }
+
+    /// Builder: record the response's moderation score
+    pub fn with_toxicity(mut self, toxicity: ToxicityScore) -> Self {
+        self.toxicity = Some(toxicity);
+        self
+    }
 }
 
 /// Response generation failed
@@ -476,48 +860,1934 @@ impl ResponseFailedEvent {
             error_type,
             error_message: error_message.into(),
             recoverable,
-            failed_at: Utc::now(),
+            failed_at: clock_now(),
         }
     }
 }
 
-/// Types of response errors
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Response stream was cancelled before completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseCancelledEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message ID whose response was cancelled
+    pub message_id: MessageId,
+
+    /// Chunks delivered before cancellation (including the final marker)
+    pub chunks_delivered: u32,
+
+    /// When the response was cancelled
+    pub cancelled_at: DateTime<Utc>,
+}
+
+impl ResponseCancelledEvent {
+    /// Create a new ResponseCancelled event
+    pub fn new(agent_id: AgentId, message_id: MessageId, chunks_delivered: u32) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            chunks_delivered,
+            cancelled_at: clock_now(),
+        }
+    }
+}
+
+/// Client-side output enforcement ended a response
+///
+/// Emitted alongside `ResponseCompleted` when the adapter layer, not the
+/// provider, stopped generation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputLimitEnforcedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message ID whose response was cut
+    pub message_id: MessageId,
+
+    /// Which limit triggered
+    pub enforcement: OutputEnforcement,
+
+    /// Index of the final (truncated) chunk
+    pub chunk_index: u32,
+
+    /// When enforcement triggered
+    pub enforced_at: DateTime<Utc>,
+}
+
+impl OutputLimitEnforcedEvent {
+    /// Create a new OutputLimitEnforced event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        enforcement: OutputEnforcement,
+        chunk_index: u32,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            enforcement,
+            chunk_index,
+            enforced_at: clock_now(),
+        }
+    }
+}
+
+// ============================================================================
+// Operational Events
+// ============================================================================
+
+/// First-token latency for an agent's provider exceeded its SLO burn rate
+///
+/// Emitted once when the burn rate crosses the threshold; not repeated until
+/// the provider recovers and breaches again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SloViolatedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Provider serving the slow responses
+    pub provider: String,
+
+    /// First-token latency threshold in milliseconds
+    pub threshold_ms: u64,
+
+    /// Error budget burn rate over the window (1.0 = budget spent exactly)
+    pub burn_rate: f64,
+
+    /// Number of requests in the evaluation window
+    pub window_samples: u32,
+
+    /// Observed 95th percentile first-token latency in milliseconds
+    pub observed_p95_ms: u64,
+
+    /// When the violation was detected
+    pub violated_at: DateTime<Utc>,
+}
+
+impl SloViolatedEvent {
+    /// Create a new SloViolated event
+    pub fn new(
+        agent_id: AgentId,
+        provider: impl Into<String>,
+        threshold_ms: u64,
+        burn_rate: f64,
+        window_samples: u32,
+        observed_p95_ms: u64,
+    ) -> Self {
+        Self {
+            agent_id,
+            provider: provider.into(),
+            threshold_ms,
+            burn_rate,
+            window_samples,
+            observed_p95_ms,
+            violated_at: clock_now(),
+        }
+    }
+}
+
+/// One agent's activity over one UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyDigest {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// UTC day the digest covers
+    pub day: NaiveDate,
+
+    /// `AgentDeployed` events
+    pub deployments: u32,
+
+    /// `AgentActivated` events
+    pub activations: u32,
+
+    /// `AgentSuspended` events
+    pub suspensions: u32,
+
+    /// `AgentDecommissioned` events
+    pub decommissions: u32,
+
+    /// Messages sent to the model
+    pub messages: u32,
+
+    /// Responses that completed
+    pub completed: u32,
+
+    /// Responses that failed
+    pub errors: u32,
+
+    /// Responses cancelled by the caller
+    pub cancellations: u32,
+
+    /// First-token SLO violations
+    pub slo_violations: u32,
+
+    /// Prompt tokens across completed responses
+    pub prompt_tokens: u64,
+
+    /// Completion tokens across completed responses
+    pub completion_tokens: u64,
+
+    /// Estimated spend in USD, when token pricing is known
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl DailyDigest {
+    /// Create an empty digest
+    pub fn new(agent_id: AgentId, day: NaiveDate) -> Self {
+        Self {
+            agent_id,
+            day,
+            deployments: 0,
+            activations: 0,
+            suspensions: 0,
+            decommissions: 0,
+            messages: 0,
+            completed: 0,
+            errors: 0,
+            cancellations: 0,
+            slo_violations: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            estimated_cost_usd: None,
+        }
+    }
+}
+
+/// A day's digest for an agent is complete
+///
+/// Published once per agent and day by the digest projection, for
+/// notification integrations to forward.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyDigestReadyEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The rolled-up activity
+    pub digest: DailyDigest,
+
+    /// When the digest was closed
+    pub ready_at: DateTime<Utc>,
+}
+
+impl DailyDigestReadyEvent {
+    /// Create a new DailyDigestReady event
+    pub fn new(digest: DailyDigest) -> Self {
+        Self {
+            agent_id: digest.agent_id,
+            digest,
+            ready_at: clock_now(),
+        }
+    }
+}
+
+/// An agent's messages went idle past its retention TTL and were archived
+///
+/// Hot read models drop the archived messages; the conversation can still be
+/// read back from the archival store by `conversation_id`, or from the blob
+/// `archive` names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationArchivedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Archive key for the conversation
+    pub conversation_id: ConversationId,
+
+    /// Messages in the conversation
+    pub message_count: u32,
+
+    /// When the first archived message was sent
+    pub first_message_at: DateTime<Utc>,
+
+    /// When the last archived message was sent
+    pub last_message_at: DateTime<Utc>,
+
+    /// When the conversation was archived
+    pub archived_at: DateTime<Utc>,
+
+    /// Blob holding the archived conversation, when archived to a blob store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<ContentRef>,
+}
+
+impl ConversationArchivedEvent {
+    /// Create a new ConversationArchived event
+    pub fn new(
+        agent_id: AgentId,
+        conversation_id: ConversationId,
+        message_count: u32,
+        first_message_at: DateTime<Utc>,
+        last_message_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            agent_id,
+            conversation_id,
+            message_count,
+            first_message_at,
+            last_message_at,
+            archived_at: clock_now(),
+            archive: None,
+        }
+    }
+
+    /// Builder: name the blob holding the conversation
+    pub fn with_archive(mut self, archive: ContentRef) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+}
+
+/// One field where a live agent differs from its declaration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldDrift {
+    /// Field path, e.g. `config.temperature`
+    pub field: String,
+
+    /// Value in the agent definition
+    pub declared: String,
+
+    /// Value on the live aggregate
+    pub actual: String,
+}
+
+impl FieldDrift {
+    /// Create a new field drift
+    pub fn new(
+        field: impl Into<String>,
+        declared: impl Into<String>,
+        actual: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            declared: declared.into(),
+            actual: actual.into(),
+        }
+    }
+}
+
+/// A live agent has drifted from its declared definition
+///
+/// Raised when the set of differing fields changes, not on every check.
+/// An empty `fields` list reports that the agent has converged again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigurationDriftDetectedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Fields that differ
+    pub fields: Vec<FieldDrift>,
+
+    /// Whether commands were planned to bring the agent back in line
+    pub remediation_planned: bool,
+
+    /// When the drift was detected
+    pub detected_at: DateTime<Utc>,
+}
+
+impl ConfigurationDriftDetectedEvent {
+    /// Create a new ConfigurationDriftDetected event
+    pub fn new(agent_id: AgentId, fields: Vec<FieldDrift>, remediation_planned: bool) -> Self {
+        Self {
+            agent_id,
+            fields,
+            remediation_planned,
+            detected_at: clock_now(),
+        }
+    }
+}
+
+/// Outcome of a canary rollout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
-pub enum ResponseErrorType {
-    /// Request timed out
-    Timeout,
-    /// Rate limit exceeded
-    RateLimit,
-    /// Authentication failed
-    AuthenticationError,
-    /// Model not available
-    ModelUnavailable,
-    /// Content policy violation
-    ContentPolicy,
-    /// Invalid request
-    InvalidRequest,
-    /// Network error
-    NetworkError,
-    /// Unknown error
-    Unknown,
+pub enum CanaryDecision {
+    /// The canary's definition replaces the stable one
+    Promoted,
+    /// The canary is retired and the stable definition kept
+    RolledBack,
 }
 
-impl ResponseErrorType {
-    /// Check if this error is typically recoverable
-    pub fn is_typically_recoverable(&self) -> bool {
-        matches!(
-            self,
-            ResponseErrorType::Timeout
-                | ResponseErrorType::RateLimit
-                | ResponseErrorType::NetworkError
-        )
+/// Traffic served by one side of a canary rollout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CanaryStats {
+    /// Responses that finished, successfully or not
+    pub responses: u32,
+
+    /// Responses that failed
+    pub errors: u32,
+
+    /// Mean time from message sent to response finished
+    pub mean_latency_ms: Option<u64>,
+}
+
+impl CanaryStats {
+    /// Share of responses that failed
+    pub fn error_rate(&self) -> f64 {
+        if self.responses == 0 {
+            0.0
+        } else {
+            f64::from(self.errors) / f64::from(self.responses)
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A canary rollout was promoted or rolled back
+///
+/// Raised on the stable agent, with the figures the decision was based on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryDecidedEvent {
+    /// The stable agent ID
+    pub agent_id: AgentId,
+
+    /// The canary agent ID
+    pub canary_id: AgentId,
+
+    /// What was decided
+    pub decision: CanaryDecision,
+
+    /// Why
+    pub reason: String,
+
+    /// Traffic served by the stable agent during the bake
+    pub stable: CanaryStats,
+
+    /// Traffic served by the canary during the bake
+    pub canary: CanaryStats,
+
+    /// When the decision was made
+    pub decided_at: DateTime<Utc>,
+}
+
+impl CanaryDecidedEvent {
+    /// Create a new CanaryDecided event
+    pub fn new(
+        agent_id: AgentId,
+        canary_id: AgentId,
+        decision: CanaryDecision,
+        reason: impl Into<String>,
+        stable: CanaryStats,
+        canary: CanaryStats,
+    ) -> Self {
+        Self {
+            agent_id,
+            canary_id,
+            decision,
+            reason: reason.into(),
+            stable,
+            canary,
+            decided_at: clock_now(),
+        }
+    }
+}
+
+/// An evaluation suite was run against an agent
+///
+/// `score` is the fraction of assertions that held; `passed` says whether
+/// it met the suite's threshold, which is what rollout gates check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationCompletedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Suite name
+    pub suite: String,
+
+    /// Model evaluated, as `provider/model_name`
+    pub model: String,
+
+    /// Fraction of assertions that held, 0.0 - 1.0
+    pub score: f64,
+
+    /// Whether the score met the suite's threshold
+    pub passed: bool,
+
+    /// Golden prompts run
+    pub prompts_total: u32,
+
+    /// Golden prompts whose assertions all held
+    pub prompts_passed: u32,
+
+    /// When the run finished
+    pub completed_at: DateTime<Utc>,
+}
+
+impl EvaluationCompletedEvent {
+    /// Create a new EvaluationCompleted event
+    pub fn new(
+        agent_id: AgentId,
+        suite: impl Into<String>,
+        model: impl Into<String>,
+        score: f64,
+        passed: bool,
+        prompts_total: u32,
+        prompts_passed: u32,
+    ) -> Self {
+        Self {
+            agent_id,
+            suite: suite.into(),
+            model: model.into(),
+            score,
+            passed,
+            prompts_total,
+            prompts_passed,
+            completed_at: clock_now(),
+        }
+    }
+}
+
+/// A capability negotiation between two agents concluded
+///
+/// Recorded on the requester's stream before it delegates. `offered` is the
+/// target's signed capability statement, absent when no valid statement was
+/// received; `missing` names required capabilities the statement lacks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityNegotiatedEvent {
+    /// The requesting agent
+    pub agent_id: AgentId,
+
+    /// Correlates request, statement and outcome
+    pub negotiation_id: Uuid,
+
+    /// The agent asked to take the work
+    pub target_id: AgentId,
+
+    /// Capabilities the requester needs
+    pub required: RuntimeCapabilities,
+
+    /// Capabilities the target stated, if its statement verified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offered: Option<RuntimeCapabilities>,
+
+    /// Key that signed the statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+
+    /// Whether the target may be delegated to
+    pub accepted: bool,
+
+    /// Requirements the target does not meet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+
+    /// Why the statement was not accepted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// When the negotiation concluded
+    pub negotiated_at: DateTime<Utc>,
+}
+
+impl CapabilityNegotiatedEvent {
+    /// Create a new CapabilityNegotiated event
+    pub fn new(
+        agent_id: AgentId,
+        negotiation_id: Uuid,
+        target_id: AgentId,
+        required: RuntimeCapabilities,
+    ) -> Self {
+        Self {
+            agent_id,
+            negotiation_id,
+            target_id,
+            required,
+            offered: None,
+            key_id: None,
+            accepted: false,
+            missing: Vec::new(),
+            reason: None,
+            negotiated_at: clock_now(),
+        }
+    }
+}
+
+/// A request ran past its caller's deadline
+///
+/// Recorded alongside the `ResponseFailed` event for the message, so that
+/// deadline misses can be told apart from provider timeouts. `stage` names
+/// where the budget ran out: "routing", "provider" or "stream".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadlineExceededEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message that missed its deadline, if one was assigned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<MessageId>,
+
+    /// When the caller stopped waiting
+    pub deadline: DateTime<Utc>,
+
+    /// Pipeline stage that ran out of time
+    pub stage: String,
+
+    /// When the miss was detected
+    pub exceeded_at: DateTime<Utc>,
+}
+
+impl DeadlineExceededEvent {
+    /// Create a new DeadlineExceeded event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: Option<MessageId>,
+        deadline: DateTime<Utc>,
+        stage: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            deadline,
+            stage: stage.into(),
+            exceeded_at: clock_now(),
+        }
+    }
+}
+
+/// Feature flags were set for an agent
+///
+/// `changes` holds only the flags whose effective value changed; the
+/// aggregate merges them over its current flags.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlagsChangedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Flag name to new value
+    pub changes: BTreeMap<String, bool>,
+
+    /// When the flags were changed
+    pub changed_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
+}
+
+impl FeatureFlagsChangedEvent {
+    /// Create a new FeatureFlagsChanged event
+    pub fn new(agent_id: AgentId, changes: BTreeMap<String, bool>) -> Self {
+        Self {
+            agent_id,
+            changes,
+            changed_at: clock_now(),
+            performed_by: acting_principal(),
+        }
+    }
+}
+
+/// A request was moved to the long-context model
+///
+/// Emitted when the assembled context did not fit the agent's configured
+/// model and the long-context profile answered instead. Providers are the
+/// display names of the two providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LongContextFallbackEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message that was rerouted
+    pub message_id: MessageId,
+
+    /// Provider of the agent's configured model
+    pub from_provider: String,
+
+    /// The agent's configured model
+    pub from_model: String,
+
+    /// Provider of the long-context model
+    pub to_provider: String,
+
+    /// The long-context model that answered
+    pub to_model: String,
+
+    /// Estimated tokens in the assembled context
+    pub context_tokens: u32,
+
+    /// Context window of the configured model
+    pub limit: u32,
+
+    /// When the switch was made
+    pub switched_at: DateTime<Utc>,
+}
+
+impl LongContextFallbackEvent {
+    /// Create a new LongContextFallback event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        from: &ModelConfig,
+        to: &ModelConfig,
+        context_tokens: u32,
+        limit: u32,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            from_provider: from.provider.to_string(),
+            from_model: from.model_name.clone(),
+            to_provider: to.provider.to_string(),
+            to_model: to.model_name.clone(),
+            context_tokens,
+            limit,
+            switched_at: clock_now(),
+        }
+    }
+}
+
+/// Content was removed from a context to fit the model's window
+///
+/// One event per truncation strategy that removed something, so a
+/// surprising answer can be traced back to what the model never saw.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextTruncatedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message whose context was truncated
+    pub message_id: MessageId,
+
+    /// What the strategy removed
+    pub truncation: TruncationRecord,
+
+    /// When the context was truncated
+    pub truncated_at: DateTime<Utc>,
+}
+
+impl ContextTruncatedEvent {
+    /// Create a new ContextTruncated event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        truncation: TruncationRecord,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            truncation,
+            truncated_at: clock_now(),
+        }
+    }
+}
+
+/// A judge picked one of several candidate answers
+///
+/// Records every candidate, including failed ones, along with the judge's
+/// reasoning, so best-of-N selections can be audited and their cost
+/// weighed against the answers they produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateSelectedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message that was answered
+    pub message_id: MessageId,
+
+    /// Judge that chose ("heuristic" or "model:{provider}/{model}")
+    pub judge: String,
+
+    /// Every candidate, in generation order
+    pub candidates: Vec<ResponseCandidate>,
+
+    /// Index of the chosen candidate
+    pub selected: u32,
+
+    /// Why it was chosen
+    pub rationale: String,
+
+    /// When the choice was made
+    pub selected_at: DateTime<Utc>,
+}
+
+impl CandidateSelectedEvent {
+    /// Create a new CandidateSelected event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        judge: impl Into<String>,
+        candidates: Vec<ResponseCandidate>,
+        selected: u32,
+        rationale: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            judge: judge.into(),
+            candidates,
+            selected,
+            rationale: rationale.into(),
+            selected_at: clock_now(),
+        }
+    }
+}
+
+/// A reflected answer was superseded by a revision
+///
+/// One event per intermediate draft, carrying the critique that led to its
+/// revision. The final answer is recorded by the usual response events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseDraftedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message being answered
+    pub message_id: MessageId,
+
+    /// The superseded draft and its critique
+    pub draft: ReflectionDraft,
+
+    /// When the draft was recorded
+    pub drafted_at: DateTime<Utc>,
+}
+
+impl ResponseDraftedEvent {
+    /// Create a new ResponseDrafted event
+    pub fn new(agent_id: AgentId, message_id: MessageId, draft: ReflectionDraft) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            draft,
+            drafted_at: clock_now(),
+        }
+    }
+}
+
+/// Structured reasoning trace recorded for a message
+///
+/// Emitted when reasoning traces are enabled for debugging. The steps form a
+/// tree through their parent links; redacted traces keep the tree but drop
+/// each step's detail text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningTraceRecordedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message the decisions were made for
+    pub message_id: MessageId,
+
+    /// Plan, tool selection, retrieval and judging steps
+    pub trace: ReasoningTrace,
+
+    /// Whether step details were removed before recording
+    #[serde(default)]
+    pub redacted: bool,
+
+    /// When the trace was recorded
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl ReasoningTraceRecordedEvent {
+    /// Create a new ReasoningTraceRecorded event, redacting the trace if asked
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        trace: &ReasoningTrace,
+        redacted: bool,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            trace: if redacted { trace.redacted() } else { trace.clone() },
+            redacted,
+            recorded_at: clock_now(),
+        }
+    }
+}
+
+/// Locale and timezone preferences were set for an agent
+///
+/// The context pipeline tells the model the local time and formatting
+/// conventions from these preferences on every following message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalePreferencesChangedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The new preferences
+    pub preferences: LocalePreferences,
+
+    /// When the preferences were changed
+    pub changed_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
+}
+
+impl LocalePreferencesChangedEvent {
+    /// Create a new LocalePreferencesChanged event
+    pub fn new(agent_id: AgentId, preferences: LocalePreferences) -> Self {
+        Self {
+            agent_id,
+            preferences,
+            changed_at: clock_now(),
+            performed_by: acting_principal(),
+        }
+    }
+}
+
+/// An agent still has a deprecated tool version enabled
+///
+/// Published to each affected agent when a tool version is deprecated, so
+/// the agent (or its owner) can migrate before the version is removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecatedToolInUseEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The deprecated tool
+    pub tool_name: String,
+
+    /// The deprecated version the agent has enabled
+    pub version: semver::Version,
+
+    /// Version to migrate to, if one was named
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<semver::Version>,
+
+    /// Why the version was deprecated
+    pub reason: String,
+
+    /// When the agent was notified
+    pub notified_at: DateTime<Utc>,
+}
+
+impl DeprecatedToolInUseEvent {
+    /// Create a new DeprecatedToolInUse event
+    pub fn new(
+        agent_id: AgentId,
+        tool_name: impl Into<String>,
+        version: semver::Version,
+        replacement: Option<semver::Version>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            tool_name: tool_name.into(),
+            version,
+            replacement,
+            reason: reason.into(),
+            notified_at: clock_now(),
+        }
+    }
+}
+
+/// An agent's enabled tool was moved to another version
+///
+/// Usage statistics recorded against the old version carry over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolVersionMigratedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The migrated tool
+    pub tool_name: String,
+
+    /// Version the agent had enabled
+    pub from_version: semver::Version,
+
+    /// Version now enabled
+    pub to_version: semver::Version,
+
+    /// Invocations carried over from earlier versions
+    pub invocations: u64,
+
+    /// When the migration happened
+    pub migrated_at: DateTime<Utc>,
+}
+
+impl ToolVersionMigratedEvent {
+    /// Create a new ToolVersionMigrated event
+    pub fn new(
+        agent_id: AgentId,
+        tool_name: impl Into<String>,
+        from_version: semver::Version,
+        to_version: semver::Version,
+        invocations: u64,
+    ) -> Self {
+        Self {
+            agent_id,
+            tool_name: tool_name.into(),
+            from_version,
+            to_version,
+            invocations,
+            migrated_at: clock_now(),
+        }
+    }
+}
+
+/// An agent's tool was called
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInvokedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// ID the model gave the call
+    pub call_id: String,
+
+    /// The called tool
+    pub tool_name: String,
+
+    /// Size of the JSON arguments, in bytes
+    pub argument_bytes: u64,
+
+    /// When the call started
+    pub invoked_at: DateTime<Utc>,
+}
+
+impl ToolInvokedEvent {
+    /// Create a new ToolInvoked event
+    pub fn new(
+        agent_id: AgentId,
+        call_id: impl Into<String>,
+        tool_name: impl Into<String>,
+        argument_bytes: u64,
+    ) -> Self {
+        Self {
+            agent_id,
+            call_id: call_id.into(),
+            tool_name: tool_name.into(),
+            argument_bytes,
+            invoked_at: clock_now(),
+        }
+    }
+}
+
+/// A tool call returned a result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSucceededEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// ID the model gave the call
+    pub call_id: String,
+
+    /// The called tool
+    pub tool_name: String,
+
+    /// Time the tool took, in milliseconds
+    pub duration_ms: u64,
+
+    /// Size of the JSON result, in bytes
+    pub result_bytes: u64,
+
+    /// When the call returned
+    pub completed_at: DateTime<Utc>,
+}
+
+impl ToolSucceededEvent {
+    /// Create a new ToolSucceeded event
+    pub fn new(
+        agent_id: AgentId,
+        call_id: impl Into<String>,
+        tool_name: impl Into<String>,
+        duration_ms: u64,
+        result_bytes: u64,
+    ) -> Self {
+        Self {
+            agent_id,
+            call_id: call_id.into(),
+            tool_name: tool_name.into(),
+            duration_ms,
+            result_bytes,
+            completed_at: clock_now(),
+        }
+    }
+}
+
+/// A tool call failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolFailedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// ID the model gave the call
+    pub call_id: String,
+
+    /// The called tool
+    pub tool_name: String,
+
+    /// Time until the failure, in milliseconds
+    pub duration_ms: u64,
+
+    /// Error description
+    pub error: String,
+
+    /// When the call failed
+    pub failed_at: DateTime<Utc>,
+}
+
+impl ToolFailedEvent {
+    /// Create a new ToolFailed event
+    pub fn new(
+        agent_id: AgentId,
+        call_id: impl Into<String>,
+        tool_name: impl Into<String>,
+        duration_ms: u64,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            call_id: call_id.into(),
+            tool_name: tool_name.into(),
+            duration_ms,
+            error: error.into(),
+            failed_at: clock_now(),
+        }
+    }
+}
+
+/// A held agent's data was spared an archival, shredding or retention run
+///
+/// Recorded once per refused attempt so compliance can show that the hold
+/// was honoured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHoldRefusedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The operation that was refused
+    pub operation: HeldOperation,
+
+    /// Reason given for the hold
+    pub hold_reason: String,
+
+    /// When the attempt was refused
+    pub refused_at: DateTime<Utc>,
+}
+
+impl LegalHoldRefusedEvent {
+    /// Create a new LegalHoldRefused event
+    pub fn new(
+        agent_id: AgentId,
+        operation: HeldOperation,
+        hold_reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            operation,
+            hold_reason: hold_reason.into(),
+            refused_at: clock_now(),
+        }
+    }
+}
+
+/// A legal hold was placed on an agent's data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHoldPlacedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Who placed the hold
+    pub placed_by: PersonId,
+
+    /// Why the data is held
+    pub reason: String,
+
+    /// When the hold was placed
+    pub placed_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
+}
+
+impl LegalHoldPlacedEvent {
+    /// Create a new LegalHoldPlaced event
+    pub fn new(agent_id: AgentId, placed_by: PersonId, reason: impl Into<String>) -> Self {
+        Self {
+            agent_id,
+            placed_by,
+            reason: reason.into(),
+            placed_at: clock_now(),
+            performed_by: acting_principal(),
+        }
+    }
+
+    /// The hold this event places
+    pub fn hold(&self) -> LegalHold {
+        LegalHold {
+            reason: self.reason.clone(),
+            placed_by: self.placed_by,
+            placed_at: self.placed_at,
+        }
+    }
+}
+
+/// A legal hold on an agent's data was lifted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHoldReleasedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Who released the hold
+    pub released_by: PersonId,
+
+    /// When the hold was released
+    pub released_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
+}
+
+impl LegalHoldReleasedEvent {
+    /// Create a new LegalHoldReleased event
+    pub fn new(agent_id: AgentId, released_by: PersonId) -> Self {
+        Self {
+            agent_id,
+            released_by,
+            released_at: clock_now(),
+            performed_by: acting_principal(),
+        }
+    }
+}
+
+/// A principal lacked permission for a command, and the owner was asked
+///
+/// The command is held until the escalation is approved or `expires_at`
+/// passes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionEscalationRequestedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Identifies the escalation for approval
+    pub escalation_id: Uuid,
+
+    /// Class of the principal whose command was held
+    pub principal: PrincipalClass,
+
+    /// The permission it lacked
+    pub permission: AgentPermission,
+
+    /// When the escalation was raised
+    pub requested_at: DateTime<Utc>,
+
+    /// Approval after this is refused and the command dropped
+    pub expires_at: DateTime<Utc>,
+
+    /// The held command, so whichever instance gets the approval can run it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Box<AgentCommand>>,
+}
+
+impl PermissionEscalationRequestedEvent {
+    /// Create a new PermissionEscalationRequested event
+    pub fn new(
+        agent_id: AgentId,
+        escalation_id: Uuid,
+        denied: PermissionDenied,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            agent_id,
+            escalation_id,
+            principal: denied.class,
+            permission: denied.permission,
+            requested_at: clock_now(),
+            expires_at,
+            command: None,
+        }
+    }
+
+    /// Builder: the command held for approval
+    pub fn with_command(mut self, command: AgentCommand) -> Self {
+        self.command = Some(Box::new(command));
+        self
+    }
+}
+
+/// The owner approved a permission escalation
+///
+/// The principal class holds the permission on this agent until
+/// `grant_expires_at`, and the held command is resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionEscalationApprovedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The escalation approved
+    pub escalation_id: Uuid,
+
+    /// Who approved it
+    pub approved_by: PersonId,
+
+    /// Class granted the permission
+    pub principal: PrincipalClass,
+
+    /// The permission granted
+    pub permission: AgentPermission,
+
+    /// When the temporary grant lapses
+    pub grant_expires_at: DateTime<Utc>,
+
+    /// When the escalation was approved
+    pub approved_at: DateTime<Utc>,
+}
+
+impl PermissionEscalationApprovedEvent {
+    /// Create a new PermissionEscalationApproved event
+    pub fn new(
+        agent_id: AgentId,
+        escalation_id: Uuid,
+        approved_by: PersonId,
+        denied: PermissionDenied,
+        grant_expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            agent_id,
+            escalation_id,
+            approved_by,
+            principal: denied.class,
+            permission: denied.permission,
+            grant_expires_at,
+            approved_at: clock_now(),
+        }
+    }
+}
+
+/// A permission escalation was not approved in time; its command was dropped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionEscalationExpiredEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The escalation that lapsed
+    pub escalation_id: Uuid,
+
+    /// Class of the principal whose command was dropped
+    pub principal: PrincipalClass,
+
+    /// The permission it lacked
+    pub permission: AgentPermission,
+
+    /// When the escalation lapsed
+    pub expired_at: DateTime<Utc>,
+}
+
+impl PermissionEscalationExpiredEvent {
+    /// Create a new PermissionEscalationExpired event
+    pub fn new(agent_id: AgentId, escalation_id: Uuid, denied: PermissionDenied) -> Self {
+        Self {
+            agent_id,
+            escalation_id,
+            principal: denied.class,
+            permission: denied.permission,
+            expired_at: clock_now(),
+        }
+    }
+}
+
+/// A behavior metric watched for anomalies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BehaviorMetric {
+    /// Fraction of responses that failed
+    ErrorRate,
+    /// Mean response duration in milliseconds
+    LatencyMs,
+    /// Mean total tokens per response
+    TokenUsage,
+}
+
+impl fmt::Display for BehaviorMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BehaviorMetric::ErrorRate => write!(f, "error rate"),
+            BehaviorMetric::LatencyMs => write!(f, "latency"),
+            BehaviorMetric::TokenUsage => write!(f, "token usage"),
+        }
+    }
+}
+
+/// Responses a metric was measured over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricWindow {
+    /// First response in the window
+    pub start: DateTime<Utc>,
+
+    /// Last response in the window
+    pub end: DateTime<Utc>,
+
+    /// Responses in the window
+    pub samples: u32,
+}
+
+/// An agent's behavior deviated from its own baseline
+///
+/// Raised when a window's value lies more standard deviations from the
+/// agent's exponentially weighted baseline than the detector allows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyDetectedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// Metric that deviated
+    pub metric: BehaviorMetric,
+
+    /// Value over the window
+    pub value: f64,
+
+    /// Baseline mean before the window
+    pub baseline: f64,
+
+    /// Baseline standard deviation before the window
+    pub std_dev: f64,
+
+    /// Standard deviations from the baseline (negative below it)
+    pub z_score: f64,
+
+    /// Responses the value was measured over
+    pub window: MetricWindow,
+
+    /// When the anomaly was detected
+    pub detected_at: DateTime<Utc>,
+}
+
+impl AnomalyDetectedEvent {
+    /// Create a new AnomalyDetected event
+    pub fn new(
+        agent_id: AgentId,
+        metric: BehaviorMetric,
+        value: f64,
+        baseline: f64,
+        std_dev: f64,
+        window: MetricWindow,
+    ) -> Self {
+        Self {
+            agent_id,
+            metric,
+            value,
+            baseline,
+            std_dev,
+            z_score: (value - baseline) / std_dev,
+            window,
+            detected_at: clock_now(),
+        }
+    }
+}
+
+/// Progress of an offline batch run through the agent
+///
+/// Raised every few prompts while a `BatchInference` run is underway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgressEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The batch run
+    pub batch_id: Uuid,
+
+    /// Prompts answered so far
+    pub succeeded: u64,
+
+    /// Prompts that failed after their retries
+    pub failed: u64,
+
+    /// Prompts in the batch, when known up front
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+
+    /// When progress was reported
+    pub reported_at: DateTime<Utc>,
+}
+
+impl BatchProgressEvent {
+    /// Create a new BatchProgress event
+    pub fn new(
+        agent_id: AgentId,
+        batch_id: Uuid,
+        succeeded: u64,
+        failed: u64,
+        total: Option<u64>,
+    ) -> Self {
+        Self {
+            agent_id,
+            batch_id,
+            succeeded,
+            failed,
+            total,
+            reported_at: clock_now(),
+        }
+    }
+}
+
+/// An offline batch finished; its results are in the blob store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCompletedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The batch run
+    pub batch_id: Uuid,
+
+    /// Prompts answered
+    pub succeeded: u64,
+
+    /// Prompts that failed after their retries
+    pub failed: u64,
+
+    /// JSON Lines results in input order, stored a part at a time
+    pub results: Vec<ContentRef>,
+
+    /// Times a rate limit paused the batch
+    pub rate_limited: u64,
+
+    /// Wall-clock time of the whole run
+    pub duration_ms: u64,
+
+    /// When the batch completed
+    pub completed_at: DateTime<Utc>,
+}
+
+impl BatchCompletedEvent {
+    /// Create a new BatchCompleted event
+    pub fn new(
+        agent_id: AgentId,
+        batch_id: Uuid,
+        succeeded: u64,
+        failed: u64,
+        results: Vec<ContentRef>,
+        rate_limited: u64,
+        duration_ms: u64,
+    ) -> Self {
+        Self {
+            agent_id,
+            batch_id,
+            succeeded,
+            failed,
+            results,
+            rate_limited,
+            duration_ms,
+            completed_at: clock_now(),
+        }
+    }
+}
+
+/// A message failed and will be sent again once `retry_at` passes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageRetryScheduledEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message being retried
+    pub message_id: MessageId,
+
+    /// Attempts that have failed so far
+    pub attempt: u32,
+
+    /// Attempts the message may make before it is quarantined
+    pub max_attempts: u32,
+
+    /// Why the last attempt failed
+    pub error: String,
+
+    /// When the message will be sent again
+    pub retry_at: DateTime<Utc>,
+
+    /// When the retry was scheduled
+    pub scheduled_at: DateTime<Utc>,
+}
+
+impl MessageRetryScheduledEvent {
+    /// Create a new MessageRetryScheduled event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        attempt: u32,
+        max_attempts: u32,
+        error: impl Into<String>,
+        retry_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            attempt,
+            max_attempts,
+            error: error.into(),
+            retry_at,
+            scheduled_at: clock_now(),
+        }
+    }
+}
+
+/// A message exhausted its retries, or cannot succeed, and awaits review
+///
+/// It is not sent again until an operator releases it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageQuarantinedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The quarantined message
+    pub message_id: MessageId,
+
+    /// Attempts made before quarantine
+    pub attempts: u32,
+
+    /// Why the message was quarantined
+    pub reason: String,
+
+    /// When the message was quarantined
+    pub quarantined_at: DateTime<Utc>,
+}
+
+impl MessageQuarantinedEvent {
+    /// Create a new MessageQuarantined event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        attempts: u32,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            attempts,
+            reason: reason.into(),
+            quarantined_at: clock_now(),
+        }
+    }
+}
+
+/// An operator released a quarantined message; it is sent again with a fresh budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedMessageReleasedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The released message
+    pub message_id: MessageId,
+
+    /// Who released the message
+    pub released_by: PersonId,
+
+    /// When the message was released
+    pub released_at: DateTime<Utc>,
+
+    /// Principal class that issued the command, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<PrincipalClass>,
+}
+
+impl QuarantinedMessageReleasedEvent {
+    /// Create a new QuarantinedMessageReleased event
+    pub fn new(agent_id: AgentId, message_id: MessageId, released_by: PersonId) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            released_by,
+            released_at: clock_now(),
+            performed_by: acting_principal(),
+        }
+    }
+}
+
+/// A capability the agent's model declares failed a minimal probe request
+///
+/// The capability is withdrawn from what the agent advertises until its
+/// model is configured again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityVerificationFailedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The capability that failed its probe
+    pub capability: RuntimeCapabilities,
+
+    /// Model the probe was sent to
+    pub model: String,
+
+    /// Why the probe failed
+    pub error: String,
+
+    /// When the probe failed
+    pub failed_at: DateTime<Utc>,
+}
+
+impl CapabilityVerificationFailedEvent {
+    /// Create a new CapabilityVerificationFailed event
+    pub fn new(
+        agent_id: AgentId,
+        capability: RuntimeCapabilities,
+        model: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            agent_id,
+            capability,
+            model: model.into(),
+            error: error.into(),
+            failed_at: clock_now(),
+        }
+    }
+}
+
+/// A kind of change watched for bursts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Model or agent configuration changed
+    Configuration,
+    /// A permission was granted, by approving an escalation
+    Permission,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Configuration => write!(f, "configuration"),
+            ChangeKind::Permission => write!(f, "permission"),
+        }
+    }
+}
+
+/// Whose changes a rate limit counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeScope {
+    /// Changes to one agent, by anyone
+    Agent,
+    /// Changes by one actor, to any agent
+    Actor,
+}
+
+/// What happens to a change over its rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardrailAction {
+    /// Refuse the change
+    Block,
+    /// Hold the change for the owner's approval
+    RequireApproval,
+}
+
+/// How many changes of a kind a scope may make within a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeRateLimit {
+    /// Changes counted
+    pub kind: ChangeKind,
+
+    /// Whether changes are counted per agent or per actor
+    pub scope: ChangeScope,
+
+    /// Changes allowed within the window
+    pub max_changes: u32,
+
+    /// Length of the sliding window in seconds
+    pub window_secs: u64,
+
+    /// What happens to the change that exceeds the limit
+    pub action: GuardrailAction,
+}
+
+impl fmt::Display for ChangeRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self.scope {
+            ChangeScope::Agent => "agent",
+            ChangeScope::Actor => "actor",
+        };
+        write!(
+            f,
+            "{} {} changes per {} within {}s",
+            self.max_changes, self.kind, scope, self.window_secs
+        )
+    }
+}
+
+/// Configuration or permission changes came faster than a rate limit allows
+///
+/// The change that tripped the limit was refused or held for the owner's
+/// approval, as the limit's action says. Sudden bursts of such changes are
+/// a security signal, so owners are alerted by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeRateExceededEvent {
+    /// The agent the change was for
+    pub agent_id: AgentId,
+
+    /// The limit that was exceeded
+    pub limit: ChangeRateLimit,
+
+    /// Changes within the window, counting the one refused or held
+    pub changes: u32,
+
+    /// Who issued the change, when the command said
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+
+    /// Class of the principal that issued the change
+    pub principal: PrincipalClass,
+
+    /// The permission the change required
+    pub permission: AgentPermission,
+
+    /// What was done with the change
+    pub action: GuardrailAction,
+
+    /// When the limit was exceeded
+    pub detected_at: DateTime<Utc>,
+}
+
+impl ChangeRateExceededEvent {
+    /// Create a new ChangeRateExceeded event
+    pub fn new(
+        agent_id: AgentId,
+        limit: ChangeRateLimit,
+        changes: u32,
+        actor: Option<String>,
+        principal: PrincipalClass,
+        permission: AgentPermission,
+        action: GuardrailAction,
+    ) -> Self {
+        Self {
+            agent_id,
+            limit,
+            changes,
+            actor,
+            principal,
+            permission,
+            action,
+            detected_at: clock_now(),
+        }
+    }
+}
+
+/// An answer broke the agent's output constraints
+///
+/// One event per answer that broke a rule, saying which rules and whether
+/// it was regenerated, delivered anyway or failed the response. The final
+/// answer is recorded by the usual response events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConstraintEnforcedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// The message being answered
+    pub message_id: MessageId,
+
+    /// The rules broken and what was done about it
+    pub enforcement: ConstraintEnforcement,
+
+    /// When the enforcement was recorded
+    pub enforced_at: DateTime<Utc>,
+}
+
+impl OutputConstraintEnforcedEvent {
+    /// Create a new OutputConstraintEnforced event
+    pub fn new(
+        agent_id: AgentId,
+        message_id: MessageId,
+        enforcement: ConstraintEnforcement,
+    ) -> Self {
+        Self {
+            agent_id,
+            message_id,
+            enforcement,
+            enforced_at: clock_now(),
+        }
+    }
+}
+
+/// An agent's estimated spend for a UTC day passed the alert threshold
+///
+/// Emitted once per agent and day, by the response that crossed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendThresholdCrossedEvent {
+    /// The agent ID
+    pub agent_id: AgentId,
+
+    /// UTC day the spend accrued on
+    pub day: NaiveDate,
+
+    /// Alert threshold in USD
+    pub threshold_usd: f64,
+
+    /// Estimated spend for the day in USD, including the crossing response
+    pub spent_usd: f64,
+
+    /// When the crossing was detected
+    pub crossed_at: DateTime<Utc>,
+}
+
+impl SpendThresholdCrossedEvent {
+    /// Create a new SpendThresholdCrossed event
+    pub fn new(agent_id: AgentId, day: NaiveDate, threshold_usd: f64, spent_usd: f64) -> Self {
+        Self {
+            agent_id,
+            day,
+            threshold_usd,
+            spent_usd,
+            crossed_at: clock_now(),
+        }
+    }
+}
+
+/// Types of response errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseErrorType {
+    /// Request timed out
+    Timeout,
+    /// Rate limit exceeded
+    RateLimit,
+    /// Authentication failed
+    AuthenticationError,
+    /// Model not available
+    ModelUnavailable,
+    /// Content policy violation
+    ContentPolicy,
+    /// Invalid request
+    InvalidRequest,
+    /// Network error
+    NetworkError,
+    /// The caller's deadline passed before the response completed
+    DeadlineExceeded,
+    /// Unknown error
+    Unknown,
+}
+
+impl ResponseErrorType {
+    /// Check if this error is typically recoverable
+    pub fn is_typically_recoverable(&self) -> bool {
+        matches!(
+            self,
+            ResponseErrorType::Timeout
+                | ResponseErrorType::RateLimit
+                | ResponseErrorType::NetworkError
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{with_clock_sync, with_principal, FixedClock};
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_event_timestamps_follow_the_clock() {
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 9, 30, 0).unwrap();
+        let event = with_clock_sync(Arc::new(FixedClock::new(at)), || {
+            AgentActivatedEvent::new(AgentId::new())
+        });
+        assert_eq!(event.activated_at, at);
+    }
+
+    #[tokio::test]
+    async fn test_command_events_record_the_acting_principal() {
+        let agent_id = AgentId::new();
+        let event = with_principal(PrincipalClass::Operator, async {
+            AgentEvent::AgentSuspended(AgentSuspendedEvent::new(agent_id, "maintenance"))
+        })
+        .await;
+        assert_eq!(event.performed_by(), Some(PrincipalClass::Operator));
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["performed_by"], "operator");
+
+        let unattributed = AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id));
+        assert_eq!(unattributed.performed_by(), None);
+    }
 
     #[test]
     fn test_agent_deployed_event() {
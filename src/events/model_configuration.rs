@@ -5,7 +5,7 @@
 //! Events represent immutable facts about configuration lifecycle changes.
 
 use crate::commands::ModelParameters;
-use crate::value_objects::{ModelConfigurationId, ModelConstraints, ProviderType};
+use crate::value_objects::{clock_now, ModelConfigurationId, ModelConstraints, ProviderType};
 use chrono::{DateTime, Utc};
 use cim_domain::DomainEvent;
 use serde::{Deserialize, Serialize};
@@ -131,7 +131,7 @@ impl ModelConfigurationCreatedEvent {
             parameters,
             constraints,
             description,
-            created_at: Utc::now(),
+            created_at: clock_now(),
         }
     }
 }
@@ -168,7 +168,7 @@ impl ModelParametersUpdatedEvent {
             version,
             previous_parameters,
             new_parameters,
-            updated_at: Utc::now(),
+            updated_at: clock_now(),
         }
     }
 }
@@ -215,7 +215,7 @@ impl ModelProviderChangedEvent {
             new_provider,
             new_model_name: new_model_name.into(),
             new_constraints,
-            changed_at: Utc::now(),
+            changed_at: clock_now(),
         }
     }
 }
@@ -239,7 +239,7 @@ impl ModelConfigurationActivatedEvent {
         Self {
             id,
             version,
-            activated_at: Utc::now(),
+            activated_at: clock_now(),
         }
     }
 }
@@ -267,7 +267,7 @@ impl ModelConfigurationDeprecatedEvent {
             id,
             version,
             reason: reason.into(),
-            deprecated_at: Utc::now(),
+            deprecated_at: clock_now(),
         }
     }
 }
@@ -291,7 +291,7 @@ impl ModelConfigurationArchivedEvent {
         Self {
             id,
             version,
-            archived_at: Utc::now(),
+            archived_at: clock_now(),
         }
     }
 }
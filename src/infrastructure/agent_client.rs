@@ -0,0 +1,307 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Typed agent command client
+//!
+//! Sends [`AgentCommand`]s to an agent-service inbox and decodes the reply.
+//! Message content over the inline limit is uploaded to the [`BlobStore`]
+//! first and replaced by its [`ContentRef`], so callers can send documents
+//! of any size up to [`MessageSizeLimits::max_content_bytes`] without
+//! tripping the NATS payload limit:
+//!
+//! ```text
+//! send(SendMessage { content: 3 MiB })
+//!     │ content > max_inline_bytes
+//!     v
+//! BlobStore::put(cid) ──> SendMessage { content: "", content_ref: cid } ──> inbox
+//! ```
+//!
+//! A client with an identity signs each command with its nkey
+//! ([`AgentClient::with_identity`]); the service looks the key up in its
+//! [`PrincipalDirectory`](super::PrincipalDirectory) and checks the command
+//! against that principal's permissions. Unsigned commands get the least
+//! privileged class. [`AgentClient::dry_run`] sets the [`DRY_RUN_HEADER`] to
+//! preview a command's events without applying them.
+//!
+//! [`AgentClient::send_envelope`] sends a [`CommandEnvelope`] carrying the
+//! actor, tenant, causation, deadline, trace and idempotency key; its trace
+//! context also goes out as W3C `traceparent` / `tracestate` headers, and the
+//! service reads those headers for commands that arrive without one.
+
+use super::{BlobStore, DomainError, EventSigner};
+use crate::commands::{AgentCommand, CommandEnvelope, SendMessage};
+use crate::value_objects::{ContentRef, MessageSizeError, MessageSizeLimits, TraceContext};
+use serde::Serialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Header asking for a command to be simulated rather than applied
+pub const DRY_RUN_HEADER: &str = "Cim-Dry-Run";
+
+/// W3C trace context header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// W3C vendor trace state header
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Errors from the agent client
+#[derive(Debug, Error)]
+pub enum AgentClientError {
+    #[error(transparent)]
+    Size(#[from] MessageSizeError),
+
+    #[error("Content upload failed: {0}")]
+    Upload(#[from] DomainError),
+
+    #[error("Request failed: {0}")]
+    Request(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Command rejected: {0}")]
+    Rejected(String),
+
+    #[error("Signing failed: {0}")]
+    Signing(#[from] super::SignatureError),
+}
+
+/// Result type for agent client operations
+pub type AgentClientResult<T> = Result<T, AgentClientError>;
+
+/// Whether a command was sent as a dry run
+pub fn command_dry_run(headers: Option<&async_nats::HeaderMap>) -> bool {
+    headers
+        .and_then(|h| h.get(DRY_RUN_HEADER))
+        .is_some_and(|value| matches!(value.as_str(), "true" | "1"))
+}
+
+/// Decode a command payload, envelope or bare, with its trace context
+///
+/// A payload without a trace takes it from the `traceparent` header.
+pub fn command_envelope(
+    payload: &[u8],
+    headers: Option<&async_nats::HeaderMap>,
+) -> serde_json::Result<CommandEnvelope> {
+    let mut envelope = CommandEnvelope::decode(payload)?;
+    if envelope.metadata.trace.is_none() {
+        let header = |name| headers.and_then(|h| h.get(name)).map(|v| v.as_str().to_string());
+        envelope.metadata.trace = header(TRACEPARENT_HEADER)
+            .and_then(|parent| TraceContext::parse(&parent))
+            .map(|trace| match header(TRACESTATE_HEADER) {
+                Some(state) => trace.with_tracestate(state),
+                None => trace,
+            });
+    }
+    Ok(envelope)
+}
+
+/// Upload `cmd`'s content if it is too large to send inline
+///
+/// Content within the inline limit is left as it is.
+pub async fn offload_content(
+    mut cmd: SendMessage,
+    blobs: &dyn BlobStore,
+    limits: &MessageSizeLimits,
+) -> AgentClientResult<SendMessage> {
+    if !limits.needs_upload(&cmd.content) {
+        return Ok(cmd);
+    }
+    limits.check_content(cmd.content.len() as u64)?;
+    let content = std::mem::take(&mut cmd.content).into_bytes();
+    let reference = ContentRef::of(&content);
+    blobs.put(&reference, content).await?;
+    Ok(cmd.with_content_ref(reference))
+}
+
+/// Client for one agent-service command inbox
+pub struct AgentClient {
+    client: async_nats::Client,
+    subject: String,
+    blobs: Arc<dyn BlobStore>,
+    limits: MessageSizeLimits,
+    identity: Option<EventSigner>,
+}
+
+impl AgentClient {
+    /// Send commands to `subject` (e.g. `AgentSubjectFactory::agent_chat`)
+    pub fn new(
+        client: async_nats::Client,
+        subject: impl Into<String>,
+        blobs: Arc<dyn BlobStore>,
+    ) -> Self {
+        Self {
+            client,
+            subject: subject.into(),
+            blobs,
+            limits: MessageSizeLimits::default(),
+            identity: None,
+        }
+    }
+
+    /// Builder: size limits, matching the service's
+    pub fn with_limits(mut self, limits: MessageSizeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Size limits in force
+    pub fn limits(&self) -> MessageSizeLimits {
+        self.limits
+    }
+
+    /// Builder: sign commands with `identity`'s nkey
+    ///
+    /// The service acts on them as whoever it registered the key for.
+    /// Without one, commands are anonymous and get the least privileged class.
+    pub fn with_identity(mut self, identity: EventSigner) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Send a command and wait for the service's reply
+    ///
+    /// Returns the reply body; a reply with `"status": "error"` becomes
+    /// [`AgentClientError::Rejected`]. A command held for the owner's approval
+    /// replies `"status": "escalated"` with its `escalation_id`.
+    pub async fn send(&self, command: AgentCommand) -> AgentClientResult<serde_json::Value> {
+        let command = match command {
+            AgentCommand::SendMessage(cmd) => AgentCommand::SendMessage(
+                offload_content(cmd, self.blobs.as_ref(), &self.limits).await?,
+            ),
+            command => command,
+        };
+        self.request(&command, None, false).await
+    }
+
+    /// Send a command with its metadata and wait for the service's reply
+    ///
+    /// Replies as [`send`](Self::send) does.
+    pub async fn send_envelope(
+        &self,
+        mut envelope: CommandEnvelope,
+    ) -> AgentClientResult<serde_json::Value> {
+        if let AgentCommand::SendMessage(cmd) = envelope.command {
+            let cmd = offload_content(cmd, self.blobs.as_ref(), &self.limits).await?;
+            envelope.command = AgentCommand::SendMessage(cmd);
+        }
+        let trace = envelope.metadata.trace.clone();
+        self.request(&envelope, trace.as_ref(), false).await
+    }
+
+    /// Preview a command without applying it
+    ///
+    /// The reply carries the `events` the command would produce and the
+    /// `agent` after them; nothing is saved or published. A message is
+    /// checked and scored but not answered.
+    pub async fn dry_run(&self, command: &AgentCommand) -> AgentClientResult<serde_json::Value> {
+        self.request(command, None, true).await
+    }
+
+    async fn request(
+        &self,
+        body: &impl Serialize,
+        trace: Option<&TraceContext>,
+        dry_run: bool,
+    ) -> AgentClientResult<serde_json::Value> {
+        let payload = serde_json::to_vec(body)?;
+        let mut headers = async_nats::HeaderMap::new();
+        if let Some(identity) = &self.identity {
+            identity.sign_headers(&mut headers, &payload)?;
+        }
+        if dry_run {
+            headers.insert(DRY_RUN_HEADER, "true");
+        }
+        if let Some(trace) = trace {
+            headers.insert(TRACEPARENT_HEADER, trace.traceparent.as_str());
+            if let Some(state) = &trace.tracestate {
+                headers.insert(TRACESTATE_HEADER, state.as_str());
+            }
+        }
+        let reply = self
+            .client
+            .request_with_headers(self.subject.clone(), headers, payload.into())
+            .await
+            .map_err(|e| AgentClientError::Request(e.to_string()))?;
+        let reply: serde_json::Value = serde_json::from_slice(&reply.payload)?;
+        if reply["status"] == "error" {
+            let message = reply["message"].as_str().unwrap_or("unknown error");
+            return Err(AgentClientError::Rejected(message.to_string()));
+        }
+        Ok(reply)
+    }
+
+    /// Send a message, uploading its content first if it is too large
+    pub async fn send_message(&self, cmd: SendMessage) -> AgentClientResult<serde_json::Value> {
+        self.send(AgentCommand::SendMessage(cmd)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::InMemoryBlobStore;
+    use crate::value_objects::AgentId;
+
+    #[tokio::test]
+    async fn test_offload_uploads_only_oversized_content() {
+        let blobs = InMemoryBlobStore::new();
+        let limits = MessageSizeLimits {
+            max_inline_bytes: 16,
+            max_content_bytes: 64,
+        };
+        let agent_id = AgentId::new();
+
+        let small = offload_content(SendMessage::new(agent_id, "hello"), &blobs, &limits)
+            .await
+            .unwrap();
+        assert_eq!(small.content, "hello");
+        assert!(small.content_ref.is_none());
+
+        let document = "x".repeat(40);
+        let large = offload_content(SendMessage::new(agent_id, &document), &blobs, &limits)
+            .await
+            .unwrap();
+        let reference = large.content_ref.clone().unwrap();
+        assert!(large.content.is_empty());
+        assert!(large.validate().is_ok());
+        assert_eq!(
+            blobs.get(&reference).await.unwrap(),
+            Some(document.into_bytes())
+        );
+
+        let too_large = SendMessage::new(agent_id, "x".repeat(65));
+        assert!(matches!(
+            offload_content(too_large, &blobs, &limits).await,
+            Err(AgentClientError::Size(
+                MessageSizeError::ContentTooLarge { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_dry_run_header() {
+        let mut headers = async_nats::HeaderMap::new();
+        assert!(!command_dry_run(None));
+        assert!(!command_dry_run(Some(&headers)));
+        headers.insert(DRY_RUN_HEADER, "true");
+        assert!(command_dry_run(Some(&headers)));
+    }
+
+    #[test]
+    fn test_command_envelope_takes_trace_from_headers() {
+        let command = AgentCommand::ActivateAgent(crate::commands::ActivateAgent::new(
+            AgentId::new(),
+        ));
+        let payload = serde_json::to_vec(&command).unwrap();
+        let parent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(TRACEPARENT_HEADER, parent);
+        headers.insert(TRACESTATE_HEADER, "vendor=1");
+
+        let envelope = command_envelope(&payload, Some(&headers)).unwrap();
+        let trace = envelope.metadata.trace.unwrap();
+        assert_eq!(trace.traceparent, parent);
+        assert_eq!(trace.tracestate.as_deref(), Some("vendor=1"));
+        assert!(command_envelope(&payload, None).unwrap().metadata.is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Bounded LRU cache of rehydrated agents
+//!
+//! Each entry remembers the event stream head it was built from. On load the
+//! repository compares that against the store's current head:
+//!
+//! ```text
+//! cached head == store head  → hit, no replay
+//! cached head <  store head  → external write: replay only the new events
+//! cached head >  store head  → store was reset: evict, full load
+//! ```
+
+use super::{Agent, AgentId};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Cache hit/miss counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AggregateCacheStats {
+    /// Loads served without replay
+    pub hits: u64,
+    /// Loads that replayed events on top of a cached agent
+    pub catch_ups: u64,
+    /// Loads with no usable entry
+    pub misses: u64,
+    /// Entries dropped to stay within capacity
+    pub evictions: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    agent: Agent,
+    stream_version: u64,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<AgentId, Entry>,
+    recency: BTreeMap<u64, AgentId>,
+    tick: u64,
+    stats: AggregateCacheStats,
+}
+
+impl Inner {
+    fn touch(&mut self, agent_id: AgentId) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(&agent_id) {
+            self.recency.remove(&entry.last_used);
+            entry.last_used = tick;
+            self.recency.insert(tick, agent_id);
+        }
+    }
+}
+
+/// Least-recently-used cache of agents keyed by `AgentId`
+#[derive(Debug)]
+pub struct AggregateCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl AggregateCache {
+    /// Create a cache holding at most `capacity` agents
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Maximum number of cached agents
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached agents
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.lock().entries.is_empty()
+    }
+
+    /// Current hit/miss counters
+    pub fn stats(&self) -> AggregateCacheStats {
+        self.lock().stats
+    }
+
+    /// Get a cached agent and the stream version it reflects
+    pub fn get(&self, agent_id: AgentId) -> Option<(Agent, u64)> {
+        let mut inner = self.lock();
+        let found = inner
+            .entries
+            .get(&agent_id)
+            .map(|e| (e.agent.clone(), e.stream_version));
+        if found.is_some() {
+            inner.touch(agent_id);
+        }
+        found
+    }
+
+    /// Cache an agent as of `stream_version`
+    ///
+    /// An older version never replaces a newer one.
+    pub fn put(&self, agent: Agent, stream_version: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let agent_id = agent.id();
+        let mut inner = self.lock();
+
+        match inner.entries.get_mut(&agent_id) {
+            Some(entry) if entry.stream_version > stream_version => return,
+            Some(entry) => {
+                entry.agent = agent;
+                entry.stream_version = stream_version;
+            }
+            None => {
+                inner.entries.insert(
+                    agent_id,
+                    Entry {
+                        agent,
+                        stream_version,
+                        last_used: 0,
+                    },
+                );
+            }
+        }
+        inner.touch(agent_id);
+
+        while inner.entries.len() > self.capacity {
+            let Some((_, oldest)) = inner.recency.pop_first() else {
+                break;
+            };
+            inner.entries.remove(&oldest);
+            inner.stats.evictions += 1;
+        }
+    }
+
+    /// Drop the entry for an agent
+    pub fn invalidate(&self, agent_id: AgentId) {
+        let mut inner = self.lock();
+        if let Some(entry) = inner.entries.remove(&agent_id) {
+            inner.recency.remove(&entry.last_used);
+        }
+    }
+
+    /// Drop all entries
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.recency.clear();
+    }
+
+    pub(super) fn record_hit(&self) {
+        self.lock().stats.hits += 1;
+    }
+
+    pub(super) fn record_catch_up(&self) {
+        self.lock().stats.catch_ups += 1;
+    }
+
+    pub(super) fn record_miss(&self) {
+        self.lock().stats.misses += 1;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, AgentEvent};
+    use crate::value_objects::PersonId;
+
+    fn agent() -> Agent {
+        let event = AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            AgentId::new(),
+            PersonId::new(),
+            "Cached",
+            None,
+        ));
+        Agent::empty().apply_event(&event).unwrap()
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = AggregateCache::new(2);
+        let (a, b, c) = (agent(), agent(), agent());
+
+        cache.put(a.clone(), 1);
+        cache.put(b.clone(), 1);
+        assert!(cache.get(a.id()).is_some()); // a is now most recent
+        cache.put(c.clone(), 1);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(b.id()).is_none());
+        assert!(cache.get(a.id()).is_some());
+        assert!(cache.get(c.id()).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn test_older_version_does_not_replace_newer() {
+        let cache = AggregateCache::new(4);
+        let a = agent();
+
+        cache.put(a.clone(), 5);
+        cache.put(a.clone(), 3);
+
+        assert_eq!(cache.get(a.id()).unwrap().1, 5);
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_cache() {
+        let cache = AggregateCache::new(0);
+        let a = agent();
+
+        cache.put(a.clone(), 1);
+
+        assert!(cache.is_empty());
+        assert!(cache.get(a.id()).is_none());
+    }
+}
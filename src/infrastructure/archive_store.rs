@@ -0,0 +1,258 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Archive store trait and implementations
+//!
+//! Holds the events of conversations retired from the hot read models, keyed
+//! by [`ConversationId`], so they can be read back later.
+//!
+//! [`BlobArchiveStore`] keeps each conversation as a blob and hands back its
+//! [`ContentRef`] for the `ConversationArchived` event. The event is what
+//! makes the archive findable after a restart, and what keeps the blob from
+//! being garbage collected.
+
+use super::{AgentId, BlobStore, DomainError, DomainResult, EventEnvelope};
+use crate::events::AgentEvent;
+use crate::value_objects::{ContentRef, ConversationId};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// A conversation moved out of the hot path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedConversation {
+    /// Archive key
+    pub conversation_id: ConversationId,
+
+    /// Agent the conversation belongs to
+    pub agent_id: AgentId,
+
+    /// Message events, in the order they were observed
+    pub events: Vec<EventEnvelope>,
+
+    /// When the conversation was archived
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Archive store trait
+///
+/// Stores archived conversations for later restoration.
+#[async_trait]
+pub trait ArchiveStore: Send + Sync {
+    /// Store an archived conversation
+    ///
+    /// Returns the blob holding it, for stores that keep conversations as blobs.
+    async fn archive(
+        &self,
+        conversation: ArchivedConversation,
+    ) -> DomainResult<Option<ContentRef>>;
+
+    /// Load an archived conversation
+    async fn load(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Option<ArchivedConversation>>;
+
+    /// IDs of an agent's archived conversations, oldest first
+    async fn list(&self, agent_id: AgentId) -> DomainResult<Vec<ConversationId>>;
+}
+
+/// In-memory archive store (for testing and development)
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryArchiveStore {
+    conversations: Arc<RwLock<HashMap<ConversationId, ArchivedConversation>>>,
+}
+
+impl InMemoryArchiveStore {
+    /// Create a new in-memory archive store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ArchiveStore for InMemoryArchiveStore {
+    async fn archive(
+        &self,
+        conversation: ArchivedConversation,
+    ) -> DomainResult<Option<ContentRef>> {
+        let mut store = self.conversations.write().unwrap();
+        if store.contains_key(&conversation.conversation_id) {
+            return Err(DomainError::ArchiveStoreError(format!(
+                "Conversation {} is already archived",
+                conversation.conversation_id
+            )));
+        }
+        store.insert(conversation.conversation_id, conversation);
+        Ok(None)
+    }
+
+    async fn load(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Option<ArchivedConversation>> {
+        Ok(self.conversations.read().unwrap().get(&conversation_id).cloned())
+    }
+
+    async fn list(&self, agent_id: AgentId) -> DomainResult<Vec<ConversationId>> {
+        let store = self.conversations.read().unwrap();
+        let mut archived: Vec<&ArchivedConversation> =
+            store.values().filter(|c| c.agent_id == agent_id).collect();
+        archived.sort_by_key(|c| c.archived_at);
+        Ok(archived.into_iter().map(|c| c.conversation_id).collect())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArchivedBlob {
+    agent_id: AgentId,
+    archive: ContentRef,
+    archived_at: DateTime<Utc>,
+}
+
+/// Archive store keeping each conversation as a JSON blob
+///
+/// The index from conversation to blob is rebuilt from `ConversationArchived`
+/// events passed to [`observe`](Self::observe).
+#[derive(Clone)]
+pub struct BlobArchiveStore {
+    blobs: Arc<dyn BlobStore>,
+    index: Arc<RwLock<HashMap<ConversationId, ArchivedBlob>>>,
+}
+
+impl BlobArchiveStore {
+    /// Archive into `blobs`
+    pub fn new(blobs: Arc<dyn BlobStore>) -> Self {
+        Self {
+            blobs,
+            index: Arc::default(),
+        }
+    }
+
+    /// Record a stored event; only archivals naming a blob matter
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        if let AgentEvent::ConversationArchived(e) = &envelope.event {
+            if let Some(archive) = &e.archive {
+                self.index.write().unwrap_or_else(|e| e.into_inner()).insert(
+                    e.conversation_id,
+                    ArchivedBlob {
+                        agent_id: e.agent_id,
+                        archive: archive.clone(),
+                        archived_at: e.archived_at,
+                    },
+                );
+            }
+        }
+    }
+}
+
+fn archive_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::ArchiveStoreError(e.to_string())
+}
+
+#[async_trait]
+impl ArchiveStore for BlobArchiveStore {
+    async fn archive(
+        &self,
+        conversation: ArchivedConversation,
+    ) -> DomainResult<Option<ContentRef>> {
+        let content = serde_json::to_vec(&conversation).map_err(archive_error)?;
+        let archive = ContentRef::of(&content);
+        self.blobs.put(&archive, content).await?;
+        self.index.write().unwrap_or_else(|e| e.into_inner()).insert(
+            conversation.conversation_id,
+            ArchivedBlob {
+                agent_id: conversation.agent_id,
+                archive: archive.clone(),
+                archived_at: conversation.archived_at,
+            },
+        );
+        Ok(Some(archive))
+    }
+
+    async fn load(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Option<ArchivedConversation>> {
+        let archive = {
+            let index = self.index.read().unwrap_or_else(|e| e.into_inner());
+            match index.get(&conversation_id) {
+                Some(blob) => blob.archive.clone(),
+                None => return Ok(None),
+            }
+        };
+        match self.blobs.get(&archive).await? {
+            Some(content) => serde_json::from_slice(&content).map(Some).map_err(archive_error),
+            None => Err(archive_error(format!(
+                "Blob {} of conversation {} is gone",
+                archive.cid, conversation_id
+            ))),
+        }
+    }
+
+    async fn list(&self, agent_id: AgentId) -> DomainResult<Vec<ConversationId>> {
+        let index = self.index.read().unwrap_or_else(|e| e.into_inner());
+        let mut archived: Vec<(&ConversationId, &ArchivedBlob)> =
+            index.iter().filter(|(_, blob)| blob.agent_id == agent_id).collect();
+        archived.sort_by_key(|(_, blob)| blob.archived_at);
+        Ok(archived.into_iter().map(|(id, _)| *id).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ConversationArchivedEvent;
+    use crate::infrastructure::InMemoryBlobStore;
+    use crate::test_support::envelope;
+
+    fn conversation(agent_id: AgentId) -> ArchivedConversation {
+        ArchivedConversation {
+            conversation_id: ConversationId::new(),
+            agent_id,
+            events: Vec::new(),
+            archived_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_load() {
+        let store = InMemoryArchiveStore::new();
+        let agent_id = AgentId::new();
+        let archived = conversation(agent_id);
+        let id = archived.conversation_id;
+
+        store.archive(archived.clone()).await.unwrap();
+        store.archive(conversation(AgentId::new())).await.unwrap();
+
+        assert!(store.load(id).await.unwrap().is_some());
+        assert_eq!(store.list(agent_id).await.unwrap(), vec![id]);
+        assert!(matches!(
+            store.archive(archived).await,
+            Err(DomainError::ArchiveStoreError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blob_archive_is_found_from_its_event() {
+        let blobs: Arc<dyn BlobStore> = Arc::new(InMemoryBlobStore::new());
+        let agent_id = AgentId::new();
+        let archived = conversation(agent_id);
+        let id = archived.conversation_id;
+        let archive = BlobArchiveStore::new(blobs.clone())
+            .archive(archived)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Another instance learns of the archive from the event
+        let restarted = BlobArchiveStore::new(blobs);
+        assert!(restarted.load(id).await.unwrap().is_none());
+        let event = ConversationArchivedEvent::new(agent_id, id, 0, Utc::now(), Utc::now())
+            .with_archive(archive);
+        restarted.observe(&envelope(0, AgentEvent::ConversationArchived(event)));
+        assert_eq!(restarted.load(id).await.unwrap().unwrap().agent_id, agent_id);
+        assert_eq!(restarted.list(agent_id).await.unwrap(), vec![id]);
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Artifact store trait
+//!
+//! Blobs, embeddings and workspace files are written once and referenced
+//! from events by id. Stores that hold them implement [`ArtifactStore`] so
+//! the garbage collector can list what is stored and delete what no event
+//! references any more.
+
+use super::DomainResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What kind of artifact a store holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// Message content uploaded out of band, named by CID
+    Blob,
+    /// Embedding vectors, named by embedding id
+    Embedding,
+    /// Files written to a conversation's workspace
+    WorkspaceFile,
+}
+
+impl fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactKind::Blob => write!(f, "blob"),
+            ArtifactKind::Embedding => write!(f, "embedding"),
+            ArtifactKind::WorkspaceFile => write!(f, "workspace file"),
+        }
+    }
+}
+
+/// One stored artifact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredArtifact {
+    /// Id events reference it by (CID, embedding id, file path)
+    pub id: String,
+
+    /// Bytes it occupies
+    pub size_bytes: u64,
+
+    /// When it was stored, if the store knows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_at: Option<DateTime<Utc>>,
+}
+
+/// A store of artifacts the garbage collector may reclaim
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// Kind of artifact held
+    fn kind(&self) -> ArtifactKind;
+
+    /// Everything currently stored
+    async fn list_artifacts(&self) -> DomainResult<Vec<StoredArtifact>>;
+
+    /// Delete one artifact; deleting a missing artifact is not an error
+    async fn delete_artifact(&self, id: &str) -> DomainResult<()>;
+}
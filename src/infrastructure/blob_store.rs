@@ -0,0 +1,245 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Blob store trait and implementations
+//!
+//! Holds message content too large to send inline, keyed by the CID in its
+//! [`ContentRef`]. Content is checked against its CID on the way in, so a
+//! stored blob is always what its reference names.
+//!
+//! [`NatsBlobStore`] keeps blobs in a JetStream object store, which uploads
+//! them as a sequence of chunk messages each well under the payload limit.
+//!
+//! Both stores are [`ArtifactStore`]s, so blobs no event references any
+//! more can be garbage collected.
+
+use super::{ArtifactKind, ArtifactStore, DomainError, DomainResult, StoredArtifact};
+use crate::value_objects::{clock_now, ContentRef};
+use async_nats::jetstream::{self, object_store};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::io::AsyncReadExt;
+
+/// Default size of each uploaded chunk
+pub const DEFAULT_BLOB_CHUNK_BYTES: usize = 128 * 1024;
+
+/// Blob store trait
+///
+/// Stores content by CID for later reading.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Store `content` under `reference`, rejecting content that does not match
+    async fn put(&self, reference: &ContentRef, content: Vec<u8>) -> DomainResult<()>;
+
+    /// Load the content a reference names
+    async fn get(&self, reference: &ContentRef) -> DomainResult<Option<Vec<u8>>>;
+}
+
+fn verified(reference: &ContentRef, content: &[u8]) -> DomainResult<()> {
+    reference
+        .verify(content)
+        .map_err(|e| DomainError::BlobStoreError(e.to_string()))
+}
+
+/// In-memory blob store (for testing and development)
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryBlobStore {
+    blobs: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+    stored_at: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
+}
+
+impl InMemoryBlobStore {
+    /// Create a new in-memory blob store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BlobStore for InMemoryBlobStore {
+    async fn put(&self, reference: &ContentRef, content: Vec<u8>) -> DomainResult<()> {
+        verified(reference, &content)?;
+        self.blobs
+            .write()
+            .unwrap()
+            .insert(reference.cid.clone(), content);
+        self.stored_at
+            .write()
+            .unwrap()
+            .insert(reference.cid.clone(), clock_now());
+        Ok(())
+    }
+
+    async fn get(&self, reference: &ContentRef) -> DomainResult<Option<Vec<u8>>> {
+        Ok(self.blobs.read().unwrap().get(&reference.cid).cloned())
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for InMemoryBlobStore {
+    fn kind(&self) -> ArtifactKind {
+        ArtifactKind::Blob
+    }
+
+    async fn list_artifacts(&self) -> DomainResult<Vec<StoredArtifact>> {
+        let stored_at = self.stored_at.read().unwrap();
+        Ok(self
+            .blobs
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(cid, content)| StoredArtifact {
+                id: cid.clone(),
+                size_bytes: content.len() as u64,
+                stored_at: stored_at.get(cid).copied(),
+            })
+            .collect())
+    }
+
+    async fn delete_artifact(&self, id: &str) -> DomainResult<()> {
+        self.blobs.write().unwrap().remove(id);
+        self.stored_at.write().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// NATS object store blob store
+pub struct NatsBlobStore {
+    store: object_store::ObjectStore,
+    chunk_bytes: usize,
+}
+
+impl NatsBlobStore {
+    /// Create a store over an existing object store bucket
+    pub fn new(store: object_store::ObjectStore) -> Self {
+        Self {
+            store,
+            chunk_bytes: DEFAULT_BLOB_CHUNK_BYTES,
+        }
+    }
+
+    /// Builder: upload in chunks of `chunk_bytes`
+    pub fn with_chunk_bytes(mut self, chunk_bytes: usize) -> Self {
+        self.chunk_bytes = chunk_bytes;
+        self
+    }
+
+    /// Create or get the object store bucket
+    ///
+    /// # Arguments
+    ///
+    /// * `jetstream` - JetStream context
+    /// * `bucket_name` - Name of the bucket (e.g., "AGENT_BLOBS")
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<object_store::ObjectStore, async_nats::Error> {
+        match jetstream.get_object_store(bucket_name).await {
+            Ok(store) => Ok(store),
+            Err(_) => {
+                let store = jetstream
+                    .create_object_store(object_store::Config {
+                        bucket: bucket_name.to_string(),
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(store)
+            }
+        }
+    }
+}
+
+fn store_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::BlobStoreError(e.to_string())
+}
+
+#[async_trait]
+impl BlobStore for NatsBlobStore {
+    async fn put(&self, reference: &ContentRef, content: Vec<u8>) -> DomainResult<()> {
+        verified(reference, &content)?;
+        let metadata = object_store::ObjectMetadata {
+            name: reference.cid.clone(),
+            chunk_size: Some(self.chunk_bytes),
+            ..Default::default()
+        };
+        self.store
+            .put(metadata, &mut content.as_slice())
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, reference: &ContentRef) -> DomainResult<Option<Vec<u8>>> {
+        let mut object = match self.store.get(&reference.cid).await {
+            Ok(object) => object,
+            Err(e) if e.kind() == object_store::GetErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(store_error(e)),
+        };
+        let mut content = Vec::with_capacity(reference.size_bytes as usize);
+        object
+            .read_to_end(&mut content)
+            .await
+            .map_err(store_error)?;
+        Ok(Some(content))
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for NatsBlobStore {
+    fn kind(&self) -> ArtifactKind {
+        ArtifactKind::Blob
+    }
+
+    async fn list_artifacts(&self) -> DomainResult<Vec<StoredArtifact>> {
+        let mut objects = self.store.list().await.map_err(store_error)?;
+        let mut artifacts = Vec::new();
+        while let Some(info) = objects.next().await {
+            let info = info.map_err(store_error)?;
+            if info.deleted {
+                continue;
+            }
+            artifacts.push(StoredArtifact {
+                id: info.name,
+                size_bytes: info.size as u64,
+                stored_at: info
+                    .modified
+                    .and_then(|at| DateTime::from_timestamp(at.unix_timestamp(), 0)),
+            });
+        }
+        Ok(artifacts)
+    }
+
+    async fn delete_artifact(&self, id: &str) -> DomainResult<()> {
+        match self.store.delete(id).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == object_store::DeleteErrorKind::NotFound => Ok(()),
+            Err(e) => Err(store_error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_verifies_content_against_its_cid() {
+        let store = InMemoryBlobStore::new();
+        let reference = ContentRef::of(b"document");
+
+        assert!(matches!(
+            store.put(&reference, b"forgery".to_vec()).await,
+            Err(DomainError::BlobStoreError(_))
+        ));
+        assert_eq!(store.get(&reference).await.unwrap(), None);
+
+        store.put(&reference, b"document".to_vec()).await.unwrap();
+        assert_eq!(
+            store.get(&reference).await.unwrap(),
+            Some(b"document".to_vec())
+        );
+    }
+}
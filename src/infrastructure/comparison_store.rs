@@ -0,0 +1,362 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Comparison store trait and implementations
+//!
+//! Keeps the reports produced by comparing two model configurations on the
+//! same prompts, keyed by run ID, so a comparison can be read back after the
+//! run that produced it has gone.
+
+use super::{DomainError, DomainResult};
+use crate::value_objects::FinishReason;
+use async_nats::jetstream::{self, kv::Store as KvStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// One configuration's answer to a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseSample {
+    /// Full response text
+    pub text: String,
+
+    /// Response length in characters
+    pub chars: usize,
+
+    /// Why generation stopped, if the provider said
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
+
+    /// Set when the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Time from request to the last chunk, when measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
+
+impl ResponseSample {
+    /// Whether the model stopped to call a tool
+    pub fn called_tool(&self) -> bool {
+        self.finish_reason == Some(FinishReason::ToolCalls)
+    }
+}
+
+/// How the two configurations answered one prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptComparison {
+    /// The prompt sent to both configurations
+    pub prompt: String,
+
+    /// Baseline configuration's response
+    pub baseline: ResponseSample,
+
+    /// Candidate configuration's response
+    pub candidate: ResponseSample,
+
+    /// Cosine distance between the response embeddings (absent if either
+    /// request failed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_distance: Option<f32>,
+
+    /// Candidate length minus baseline length, in characters
+    pub length_delta: i64,
+
+    /// Exactly one of the responses stopped to call a tool
+    pub tool_call_changed: bool,
+}
+
+/// Aggregates over every prompt in a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonSummary {
+    /// Prompts compared
+    pub prompts: usize,
+
+    /// Mean embedding distance over prompts both configurations answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_distance: Option<f32>,
+
+    /// Largest embedding distance seen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_distance: Option<f32>,
+
+    /// Mean candidate length minus baseline length, in characters
+    pub mean_length_delta: f64,
+
+    /// Prompts where tool calling differed
+    pub tool_call_differences: usize,
+
+    /// Prompts where either request failed
+    pub failures: usize,
+}
+
+impl ComparisonSummary {
+    /// Summarize per-prompt comparisons
+    pub fn from_comparisons(comparisons: &[PromptComparison]) -> Self {
+        let distances: Vec<f32> = comparisons.iter().filter_map(|c| c.embedding_distance).collect();
+        let mean_distance = (!distances.is_empty())
+            .then(|| distances.iter().sum::<f32>() / distances.len() as f32);
+        let mean_length_delta = if comparisons.is_empty() {
+            0.0
+        } else {
+            comparisons.iter().map(|c| c.length_delta as f64).sum::<f64>()
+                / comparisons.len() as f64
+        };
+
+        Self {
+            prompts: comparisons.len(),
+            mean_distance,
+            max_distance: distances.iter().copied().reduce(f32::max),
+            mean_length_delta,
+            tool_call_differences: comparisons.iter().filter(|c| c.tool_call_changed).count(),
+            failures: comparisons
+                .iter()
+                .filter(|c| c.baseline.error.is_some() || c.candidate.error.is_some())
+                .count(),
+        }
+    }
+}
+
+/// The report of one comparison run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// Store key (UUID v7, so keys sort by creation time)
+    pub run_id: Uuid,
+
+    /// Baseline configuration, as `provider/model`
+    pub baseline: String,
+
+    /// Candidate configuration, as `provider/model`
+    pub candidate: String,
+
+    /// Per-prompt results, in prompt order
+    pub comparisons: Vec<PromptComparison>,
+
+    /// Aggregates over `comparisons`
+    pub summary: ComparisonSummary,
+
+    /// When the run finished
+    pub created_at: DateTime<Utc>,
+}
+
+/// Comparison store trait
+///
+/// Persists comparison reports as immutable artifacts.
+#[async_trait]
+pub trait ComparisonStore: Send + Sync {
+    /// Store a report; a run can only be stored once
+    async fn save(&self, report: ComparisonReport) -> DomainResult<()>;
+
+    /// Load the report for a run
+    async fn load(&self, run_id: Uuid) -> DomainResult<Option<ComparisonReport>>;
+
+    /// IDs of every stored run, oldest first
+    async fn list(&self) -> DomainResult<Vec<Uuid>>;
+}
+
+/// In-memory comparison store (for testing and development)
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryComparisonStore {
+    reports: Arc<RwLock<HashMap<Uuid, ComparisonReport>>>,
+}
+
+impl InMemoryComparisonStore {
+    /// Create a new in-memory comparison store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ComparisonStore for InMemoryComparisonStore {
+    async fn save(&self, report: ComparisonReport) -> DomainResult<()> {
+        let mut store = self.reports.write().unwrap();
+        if store.contains_key(&report.run_id) {
+            return Err(DomainError::ComparisonStoreError(format!(
+                "Comparison run {} is already stored",
+                report.run_id
+            )));
+        }
+        store.insert(report.run_id, report);
+        Ok(())
+    }
+
+    async fn load(&self, run_id: Uuid) -> DomainResult<Option<ComparisonReport>> {
+        Ok(self.reports.read().unwrap().get(&run_id).cloned())
+    }
+
+    async fn list(&self) -> DomainResult<Vec<Uuid>> {
+        let mut ids: Vec<Uuid> = self.reports.read().unwrap().keys().copied().collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+/// NATS KV comparison store
+///
+/// One key per run (`run.<run_id>`).
+pub struct NatsComparisonStore {
+    kv: KvStore,
+}
+
+impl NatsComparisonStore {
+    /// Create a store over an existing KV bucket
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Create or get the KV bucket for comparison reports
+    ///
+    /// # Arguments
+    ///
+    /// * `jetstream` - JetStream context
+    /// * `bucket_name` - Name of the KV bucket (e.g., "AGENT_COMPARISONS")
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1, // Reports are never rewritten
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+
+    fn key(run_id: Uuid) -> String {
+        format!("run.{}", run_id)
+    }
+}
+
+#[async_trait]
+impl ComparisonStore for NatsComparisonStore {
+    async fn save(&self, report: ComparisonReport) -> DomainResult<()> {
+        let payload = serde_json::to_vec(&report)
+            .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+
+        // `create` refuses to overwrite an existing run
+        self.kv
+            .create(Self::key(report.run_id), payload.into())
+            .await
+            .map_err(|e| DomainError::ComparisonStoreError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load(&self, run_id: Uuid) -> DomainResult<Option<ComparisonReport>> {
+        let entry = self
+            .kv
+            .get(Self::key(run_id))
+            .await
+            .map_err(|e| DomainError::ComparisonStoreError(e.to_string()))?;
+        entry
+            .map(|payload| {
+                serde_json::from_slice(&payload)
+                    .map_err(|e| DomainError::SerializationError(e.to_string()))
+            })
+            .transpose()
+    }
+
+    async fn list(&self) -> DomainResult<Vec<Uuid>> {
+        let keys: Vec<String> = self
+            .kv
+            .keys()
+            .await
+            .map_err(|e| DomainError::ComparisonStoreError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DomainError::ComparisonStoreError(e.to_string()))?;
+
+        let mut ids: Vec<Uuid> = keys
+            .iter()
+            .filter_map(|key| key.strip_prefix("run."))
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(text: &str, finish_reason: FinishReason) -> ResponseSample {
+        ResponseSample {
+            text: text.to_string(),
+            chars: text.chars().count(),
+            finish_reason: Some(finish_reason),
+            error: None,
+            latency_ms: None,
+        }
+    }
+
+    fn report(comparisons: Vec<PromptComparison>) -> ComparisonReport {
+        ComparisonReport {
+            run_id: Uuid::now_v7(),
+            baseline: "ollama/llama3".to_string(),
+            candidate: "ollama/llama3.1".to_string(),
+            summary: ComparisonSummary::from_comparisons(&comparisons),
+            comparisons,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_summary_aggregates_comparisons() {
+        let comparisons = vec![
+            PromptComparison {
+                prompt: "a".to_string(),
+                baseline: sample("hello", FinishReason::Stop),
+                candidate: sample("hello there", FinishReason::ToolCalls),
+                embedding_distance: Some(0.2),
+                length_delta: 6,
+                tool_call_changed: true,
+            },
+            PromptComparison {
+                prompt: "b".to_string(),
+                baseline: sample("bye", FinishReason::Stop),
+                candidate: sample("b", FinishReason::Stop),
+                embedding_distance: Some(0.4),
+                length_delta: -2,
+                tool_call_changed: false,
+            },
+        ];
+
+        let summary = ComparisonSummary::from_comparisons(&comparisons);
+        assert_eq!(summary.prompts, 2);
+        assert!((summary.mean_distance.unwrap() - 0.3).abs() < 1e-6);
+        assert_eq!(summary.max_distance, Some(0.4));
+        assert_eq!(summary.mean_length_delta, 2.0);
+        assert_eq!(summary.tool_call_differences, 1);
+        assert_eq!(summary.failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_save_load_and_list() {
+        let store = InMemoryComparisonStore::new();
+        let first = report(Vec::new());
+        let second = report(Vec::new());
+
+        store.save(second.clone()).await.unwrap();
+        store.save(first.clone()).await.unwrap();
+
+        assert_eq!(store.load(first.run_id).await.unwrap(), Some(first.clone()));
+        assert_eq!(store.list().await.unwrap(), vec![first.run_id, second.run_id]);
+        assert!(matches!(
+            store.save(first).await,
+            Err(DomainError::ComparisonStoreError(_))
+        ));
+    }
+}
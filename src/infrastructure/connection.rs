@@ -0,0 +1,390 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! NATS connection setup
+//!
+//! One place to turn deployment configuration into an `async_nats::Client`:
+//! credentials (NKey seed, JWT creds file, user/password, token), TLS and
+//! mutual TLS, reconnect policy, and connection health events.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use cim_domain_agent::infrastructure::NatsConnectionBuilder;
+//!
+//! let builder = NatsConnectionBuilder::from_env()?.with_name("agent-service");
+//! let mut health = builder.subscribe_events();
+//! let client = builder.connect().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Environment Variables
+//!
+//! - `NATS_URL` - Server URL(s), comma-separated (default: nats://localhost:4222)
+//! - `NATS_NKEY_SEED` - NKey seed for challenge authentication
+//! - `NATS_CREDS_FILE` - JWT credentials file
+//! - `NATS_TLS_CA` - CA certificate used to verify the server
+//! - `NATS_TLS_CERT` / `NATS_TLS_KEY` - Client certificate and key for mutual TLS
+//! - `NATS_MAX_RECONNECTS` - Reconnect attempts before giving up (default: unlimited)
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Default server URL
+pub const DEFAULT_NATS_URL: &str = "nats://localhost:4222";
+
+/// Connection setup errors
+#[derive(Debug, thiserror::Error)]
+pub enum NatsConnectionError {
+    #[error("Invalid connection configuration: {0}")]
+    Config(String),
+
+    #[error("Failed to load credentials: {0}")]
+    Credentials(String),
+
+    #[error("Failed to connect: {0}")]
+    Connect(String),
+}
+
+/// Result type for connection setup
+pub type NatsConnectionResult<T> = Result<T, NatsConnectionError>;
+
+/// How the client authenticates
+#[derive(Clone, PartialEq, Eq)]
+pub enum NatsCredentials {
+    /// NKey seed (`SU...`)
+    NKeySeed(String),
+    /// JWT credentials file (`.creds`)
+    CredsFile(PathBuf),
+    /// Username and password
+    UserPassword { user: String, password: String },
+    /// Auth token
+    Token(String),
+}
+
+impl fmt::Debug for NatsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NatsCredentials::NKeySeed(_) => write!(f, "NKeySeed(<redacted>)"),
+            NatsCredentials::CredsFile(path) => write!(f, "CredsFile({})", path.display()),
+            NatsCredentials::UserPassword { user, .. } => {
+                write!(f, "UserPassword({}, <redacted>)", user)
+            }
+            NatsCredentials::Token(_) => write!(f, "Token(<redacted>)"),
+        }
+    }
+}
+
+/// TLS settings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsSettings {
+    /// CA certificate (PEM) used to verify the server
+    pub ca_file: Option<PathBuf>,
+    /// Client certificate and private key (PEM) for mutual TLS
+    pub client_cert: Option<(PathBuf, PathBuf)>,
+}
+
+/// Reconnect behaviour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Attempts before giving up (None = retry forever)
+    pub max_reconnects: Option<usize>,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound on the delay between retries
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_reconnects: None,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before reconnect attempt `attempt` (exponential, capped)
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let factor = 1u32 << attempt.min(16) as u32;
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Connection state change reported to health subscribers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConnectionEvent {
+    /// Connected (or reconnected) to a server
+    Connected,
+    /// Lost the connection; the client is reconnecting
+    Disconnected,
+    /// The server is shutting down and asked clients to move
+    LameDuckMode,
+    /// A subscriber fell behind and messages were dropped
+    SlowConsumer { sid: u64 },
+    /// The server reported an error
+    ServerError { message: String },
+    /// The client hit an error
+    ClientError { message: String },
+    /// The connection was closed for good
+    Closed,
+}
+
+impl ConnectionEvent {
+    /// Whether the connection is usable after this event
+    pub fn is_healthy(&self) -> bool {
+        matches!(
+            self,
+            ConnectionEvent::Connected | ConnectionEvent::SlowConsumer { .. }
+        )
+    }
+}
+
+impl From<async_nats::Event> for ConnectionEvent {
+    fn from(event: async_nats::Event) -> Self {
+        match event {
+            async_nats::Event::Connected => ConnectionEvent::Connected,
+            async_nats::Event::Disconnected => ConnectionEvent::Disconnected,
+            async_nats::Event::LameDuckMode => ConnectionEvent::LameDuckMode,
+            async_nats::Event::SlowConsumer(sid) => ConnectionEvent::SlowConsumer { sid },
+            async_nats::Event::ServerError(e) => ConnectionEvent::ServerError {
+                message: e.to_string(),
+            },
+            async_nats::Event::ClientError(e) => ConnectionEvent::ClientError {
+                message: e.to_string(),
+            },
+            async_nats::Event::Closed => ConnectionEvent::Closed,
+            other => ConnectionEvent::ClientError {
+                message: format!("{:?}", other),
+            },
+        }
+    }
+}
+
+/// Builds NATS clients from credentials, TLS and reconnect settings
+#[derive(Debug, Clone)]
+pub struct NatsConnectionBuilder {
+    urls: String,
+    name: Option<String>,
+    credentials: Option<NatsCredentials>,
+    tls: TlsSettings,
+    require_tls: bool,
+    reconnect: ReconnectPolicy,
+    connection_timeout: Duration,
+    events: broadcast::Sender<ConnectionEvent>,
+}
+
+impl NatsConnectionBuilder {
+    /// Builder for `urls` (comma-separated for a cluster)
+    pub fn new(urls: impl Into<String>) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            urls: urls.into(),
+            name: None,
+            credentials: None,
+            tls: TlsSettings::default(),
+            require_tls: false,
+            reconnect: ReconnectPolicy::default(),
+            connection_timeout: Duration::from_secs(5),
+            events,
+        }
+    }
+
+    /// Builder configured from the `NATS_*` environment variables
+    pub fn from_env() -> NatsConnectionResult<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let mut builder = Self::new(var("NATS_URL").unwrap_or_else(|| DEFAULT_NATS_URL.into()));
+
+        builder.credentials = match (var("NATS_NKEY_SEED"), var("NATS_CREDS_FILE")) {
+            (Some(_), Some(_)) => {
+                return Err(NatsConnectionError::Config(
+                    "NATS_NKEY_SEED and NATS_CREDS_FILE are mutually exclusive".to_string(),
+                ))
+            }
+            (Some(seed), None) => Some(NatsCredentials::NKeySeed(seed)),
+            (None, Some(path)) => Some(NatsCredentials::CredsFile(path.into())),
+            (None, None) => None,
+        };
+
+        if let Some(ca) = var("NATS_TLS_CA") {
+            builder = builder.with_ca_file(ca);
+        }
+        match (var("NATS_TLS_CERT"), var("NATS_TLS_KEY")) {
+            (Some(cert), Some(key)) => builder = builder.with_client_cert(cert, key),
+            (None, None) => {}
+            _ => {
+                return Err(NatsConnectionError::Config(
+                    "NATS_TLS_CERT and NATS_TLS_KEY must be set together".to_string(),
+                ))
+            }
+        }
+
+        if let Some(max) = var("NATS_MAX_RECONNECTS") {
+            let max = max.parse().map_err(|_| {
+                NatsConnectionError::Config(format!("Invalid NATS_MAX_RECONNECTS: {}", max))
+            })?;
+            builder.reconnect.max_reconnects = Some(max);
+        }
+
+        Ok(builder)
+    }
+
+    /// Builder: client name shown in server monitoring
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Builder: authentication
+    pub fn with_credentials(mut self, credentials: NatsCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Builder: verify the server against this CA (implies TLS)
+    pub fn with_ca_file(mut self, ca_file: impl Into<PathBuf>) -> Self {
+        self.tls.ca_file = Some(ca_file.into());
+        self.require_tls = true;
+        self
+    }
+
+    /// Builder: present a client certificate for mutual TLS (implies TLS)
+    pub fn with_client_cert(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.tls.client_cert = Some((cert.into(), key.into()));
+        self.require_tls = true;
+        self
+    }
+
+    /// Builder: require TLS even without custom certificates
+    pub fn with_require_tls(mut self, require: bool) -> Self {
+        self.require_tls = require;
+        self
+    }
+
+    /// Builder: reconnect behaviour
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Builder: timeout for the initial connection
+    pub fn with_connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = timeout;
+        self
+    }
+
+    /// Server URL(s)
+    pub fn urls(&self) -> &str {
+        &self.urls
+    }
+
+    /// Receive connection health events from clients built here
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Assemble the async-nats options
+    pub async fn options(&self) -> NatsConnectionResult<async_nats::ConnectOptions> {
+        let mut options = async_nats::ConnectOptions::new();
+
+        options = match &self.credentials {
+            Some(NatsCredentials::NKeySeed(seed)) => options.nkey(seed.clone()),
+            Some(NatsCredentials::CredsFile(path)) => options
+                .credentials_file(path)
+                .await
+                .map_err(|e| {
+                    NatsConnectionError::Credentials(format!("{}: {}", path.display(), e))
+                })?,
+            Some(NatsCredentials::UserPassword { user, password }) => {
+                options.user_and_password(user.clone(), password.clone())
+            }
+            Some(NatsCredentials::Token(token)) => options.token(token.clone()),
+            None => options,
+        };
+
+        if let Some(ca) = &self.tls.ca_file {
+            options = options.add_root_certificates(ca.clone());
+        }
+        if let Some((cert, key)) = &self.tls.client_cert {
+            options = options.add_client_certificate(cert.clone(), key.clone());
+        }
+        if let Some(name) = &self.name {
+            options = options.name(name.clone());
+        }
+
+        let reconnect = self.reconnect;
+        let events = self.events.clone();
+        Ok(options
+            .require_tls(self.require_tls)
+            .connection_timeout(self.connection_timeout)
+            .max_reconnects(reconnect.max_reconnects)
+            .reconnect_delay_callback(move |attempt| reconnect.delay(attempt))
+            .event_callback(move |event| {
+                let events = events.clone();
+                async move {
+                    let event = ConnectionEvent::from(event);
+                    if event.is_healthy() {
+                        tracing::info!("NATS connection event: {:?}", event);
+                    } else {
+                        tracing::warn!("NATS connection event: {:?}", event);
+                    }
+                    // No subscribers is fine; health events are advisory
+                    let _ = events.send(event);
+                }
+            }))
+    }
+
+    /// Connect to the configured servers
+    pub async fn connect(&self) -> NatsConnectionResult<async_nats::Client> {
+        self.options()
+            .await?
+            .connect(self.urls.as_str())
+            .await
+            .map_err(|e| NatsConnectionError::Connect(e.to_string()))
+    }
+}
+
+impl Default for NatsConnectionBuilder {
+    fn default() -> Self {
+        Self::new(DEFAULT_NATS_URL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backs_off_to_cap() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(800));
+        assert_eq!(policy.delay(50), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_credentials_debug_redacts_secrets() {
+        let seed = NatsCredentials::NKeySeed("SUAMLK2ZNL35WSMW37E7UD4VZ7ELPKW7".into());
+        let password = NatsCredentials::UserPassword {
+            user: "ops".into(),
+            password: "hunter2".into(),
+        };
+        assert!(!format!("{:?}", seed).contains("SUAM"));
+        assert_eq!(format!("{:?}", password), "UserPassword(ops, <redacted>)");
+    }
+
+    #[test]
+    fn test_tls_settings_require_tls() {
+        let builder = NatsConnectionBuilder::new("tls://nats:4222")
+            .with_client_cert("/etc/nats/client.pem", "/etc/nats/client-key.pem");
+        assert!(builder.require_tls);
+        assert!(builder.tls.client_cert.is_some());
+        assert!(ConnectionEvent::Connected.is_healthy());
+        assert!(!ConnectionEvent::Disconnected.is_healthy());
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation repository
+//!
+//! Persists the `Conversation` aggregate as its events, one subject per
+//! conversation, so every host replays the same participants, receipts and
+//! budget:
+//!
+//! ```text
+//! ConversationCommand ──> execute() ──> load ──> Conversation::execute
+//!                                                        │
+//!             {domain}.conversations.{id}.events <── append (expected version)
+//!                        │
+//!                        └──> subscribers: ConversationProjection, InactivityTimers
+//! ```
+//!
+//! Appends are conditional on the conversation's last stored event, so two
+//! hosts acting on the same conversation cannot both succeed; the loser
+//! gets `ConcurrencyConflict` and may retry against the new state.
+
+use super::{AgentSubjectFactory, DomainError, DomainResult};
+use crate::aggregate::Conversation;
+use crate::commands::ConversationCommand;
+use crate::events::ConversationEvent;
+use crate::value_objects::{clock_now, next_id, ConversationId};
+use async_nats::jetstream::{self, stream::Stream};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// Event envelope with metadata for Conversation events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationEventEnvelope {
+    /// Conversation ID
+    pub conversation_id: ConversationId,
+
+    /// Event sequence number within the conversation
+    pub sequence: u64,
+
+    /// The actual event
+    pub event: ConversationEvent,
+
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+
+    /// Correlation ID for tracing
+    pub correlation_id: Uuid,
+}
+
+/// Event store trait for Conversation
+#[async_trait]
+pub trait ConversationEventStore: Send + Sync {
+    /// Append events, failing with `ConcurrencyConflict` unless the
+    /// conversation is at `expected_version`
+    async fn append_events(
+        &self,
+        conversation_id: ConversationId,
+        events: Vec<ConversationEvent>,
+        expected_version: u64,
+    ) -> DomainResult<Vec<ConversationEventEnvelope>>;
+
+    /// Get all events for a conversation, in order
+    async fn get_events(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Vec<ConversationEventEnvelope>>;
+}
+
+fn envelopes(
+    conversation_id: ConversationId,
+    events: Vec<ConversationEvent>,
+    after: u64,
+) -> Vec<ConversationEventEnvelope> {
+    let correlation_id = next_id();
+    events
+        .into_iter()
+        .enumerate()
+        .map(|(i, event)| ConversationEventEnvelope {
+            conversation_id,
+            sequence: after + i as u64 + 1,
+            event,
+            timestamp: clock_now(),
+            correlation_id,
+        })
+        .collect()
+}
+
+/// In-memory conversation event store for tests and single processes
+#[derive(Default)]
+pub struct InMemoryConversationEventStore {
+    events: RwLock<HashMap<ConversationId, Vec<ConversationEventEnvelope>>>,
+}
+
+impl InMemoryConversationEventStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ConversationEventStore for InMemoryConversationEventStore {
+    async fn append_events(
+        &self,
+        conversation_id: ConversationId,
+        events: Vec<ConversationEvent>,
+        expected_version: u64,
+    ) -> DomainResult<Vec<ConversationEventEnvelope>> {
+        let mut stored = self.events.write().unwrap_or_else(|e| e.into_inner());
+        let history = stored.entry(conversation_id).or_default();
+        let actual = history.len() as u64;
+        if actual != expected_version {
+            return Err(DomainError::ConcurrencyConflict {
+                expected: expected_version,
+                actual,
+            });
+        }
+        let appended = envelopes(conversation_id, events, actual);
+        history.extend(appended.iter().cloned());
+        Ok(appended)
+    }
+
+    async fn get_events(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Vec<ConversationEventEnvelope>> {
+        let stored = self.events.read().unwrap_or_else(|e| e.into_inner());
+        Ok(stored.get(&conversation_id).cloned().unwrap_or_default())
+    }
+}
+
+/// NATS JetStream event store for Conversation
+///
+/// Each conversation's events live on `{domain}.conversations.{id}.events`;
+/// appends carry `Nats-Expected-Last-Subject-Sequence`, so the stream itself
+/// rejects an append that raced another.
+pub struct NatsConversationEventStore {
+    jetstream: jetstream::Context,
+    stream_name: String,
+    subject_factory: AgentSubjectFactory,
+}
+
+impl NatsConversationEventStore {
+    /// Create a store over `stream_name`
+    pub fn new(jetstream: jetstream::Context, stream_name: impl Into<String>) -> Self {
+        Self {
+            jetstream,
+            stream_name: stream_name.into(),
+            subject_factory: AgentSubjectFactory::default(),
+        }
+    }
+
+    /// Builder: use a custom subject factory
+    pub fn with_factory(mut self, subject_factory: AgentSubjectFactory) -> Self {
+        self.subject_factory = subject_factory;
+        self
+    }
+
+    /// Create the conversation stream if it does not exist
+    pub async fn ensure_stream(
+        jetstream: &jetstream::Context,
+        stream_name: &str,
+        factory: &AgentSubjectFactory,
+    ) -> Result<Stream, async_nats::Error> {
+        match jetstream.get_stream(stream_name).await {
+            Ok(stream) => Ok(stream),
+            Err(_) => {
+                let stream = jetstream
+                    .create_stream(jetstream::stream::Config {
+                        name: stream_name.to_string(),
+                        subjects: vec![factory.conversation_events_pattern()?.to_string()],
+                        storage: jetstream::stream::StorageType::File,
+                        retention: jetstream::stream::RetentionPolicy::Limits,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(stream)
+            }
+        }
+    }
+
+    fn subject(&self, conversation_id: ConversationId) -> DomainResult<String> {
+        self.subject_factory
+            .conversation_events(conversation_id)
+            .map(|s| s.to_string())
+            .map_err(|e| DomainError::ValidationError(format!("Invalid subject: {}", e)))
+    }
+
+    /// Stream sequence and version of the conversation's last stored event
+    async fn last_stored(&self, stream: &Stream, subject: &str) -> DomainResult<(u64, u64)> {
+        match stream.get_last_raw_message_by_subject(subject).await {
+            Ok(message) => {
+                let envelope: ConversationEventEnvelope = decode(&message.payload)?;
+                Ok((message.sequence, envelope.sequence))
+            }
+            Err(e) if e.kind() == jetstream::stream::LastRawMessageErrorKind::NoMessageFound => {
+                Ok((0, 0))
+            }
+            Err(e) => Err(store_error(e)),
+        }
+    }
+
+    async fn stream(&self) -> DomainResult<Stream> {
+        self.jetstream
+            .get_stream(&self.stream_name)
+            .await
+            .map_err(store_error)
+    }
+}
+
+fn store_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::EventStoreError(e.to_string())
+}
+
+fn decode(payload: &[u8]) -> DomainResult<ConversationEventEnvelope> {
+    serde_json::from_slice(payload).map_err(|e| DomainError::SerializationError(e.to_string()))
+}
+
+#[async_trait]
+impl ConversationEventStore for NatsConversationEventStore {
+    async fn append_events(
+        &self,
+        conversation_id: ConversationId,
+        events: Vec<ConversationEvent>,
+        expected_version: u64,
+    ) -> DomainResult<Vec<ConversationEventEnvelope>> {
+        let subject = self.subject(conversation_id)?;
+        let stream = self.stream().await?;
+        let (mut last_sequence, actual) = self.last_stored(&stream, &subject).await?;
+        if actual != expected_version {
+            return Err(DomainError::ConcurrencyConflict {
+                expected: expected_version,
+                actual,
+            });
+        }
+
+        let appended = envelopes(conversation_id, events, actual);
+        for envelope in &appended {
+            let payload = serde_json::to_vec(envelope)
+                .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+            let mut headers = async_nats::HeaderMap::new();
+            headers.insert(
+                "Nats-Expected-Last-Subject-Sequence",
+                last_sequence.to_string().as_str(),
+            );
+            let ack = self
+                .jetstream
+                .publish_with_headers(subject.clone(), headers, payload.into())
+                .await
+                .map_err(store_error)?
+                .await;
+            last_sequence = match ack {
+                Ok(ack) => ack.sequence,
+                Err(e) if e.kind() == jetstream::context::PublishErrorKind::WrongLastSequence => {
+                    return Err(DomainError::ConcurrencyConflict {
+                        expected: expected_version,
+                        actual: expected_version + 1,
+                    });
+                }
+                Err(e) => return Err(store_error(e)),
+            };
+        }
+        Ok(appended)
+    }
+
+    async fn get_events(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Vec<ConversationEventEnvelope>> {
+        let subject = self.subject(conversation_id)?;
+        let consumer = self
+            .stream()
+            .await?
+            .create_consumer(jetstream::consumer::pull::Config {
+                filter_subject: subject,
+                deliver_policy: jetstream::consumer::DeliverPolicy::All,
+                ack_policy: jetstream::consumer::AckPolicy::None,
+                ..Default::default()
+            })
+            .await
+            .map_err(store_error)?;
+
+        let mut envelopes = Vec::new();
+        loop {
+            let mut batch = consumer
+                .fetch()
+                .max_messages(256)
+                .messages()
+                .await
+                .map_err(store_error)?;
+            let before = envelopes.len();
+            while let Some(message) = batch.next().await {
+                envelopes.push(decode(&message.map_err(store_error)?.payload)?);
+            }
+            if envelopes.len() == before {
+                break;
+            }
+        }
+        Ok(envelopes)
+    }
+}
+
+/// Conversation repository
+///
+/// Loads conversations by replaying their events and runs commands against
+/// them.
+pub struct ConversationRepository {
+    event_store: Arc<dyn ConversationEventStore>,
+}
+
+impl ConversationRepository {
+    /// Create a repository over `event_store`
+    pub fn new(event_store: Arc<dyn ConversationEventStore>) -> Self {
+        Self { event_store }
+    }
+
+    /// Load a conversation by ID
+    ///
+    /// # Returns
+    ///
+    /// Some(conversation) if it was ever started, None otherwise
+    pub async fn load(
+        &self,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Option<Conversation>> {
+        let events = self.event_store.get_events(conversation_id).await?;
+        if events.is_empty() {
+            return Ok(None);
+        }
+        let mut conversation = Conversation::empty();
+        for envelope in events {
+            conversation = conversation
+                .apply_event(&envelope.event)
+                .map_err(DomainError::InvalidStateTransition)?;
+        }
+        Ok(Some(conversation))
+    }
+
+    /// Run `cmd` against the stored conversation and append its events
+    ///
+    /// Returns the conversation after the command and the stored events.
+    pub async fn execute(
+        &self,
+        cmd: &ConversationCommand,
+    ) -> DomainResult<(Conversation, Vec<ConversationEventEnvelope>)> {
+        let current = self.load(cmd.conversation_id()).await?.unwrap_or_default();
+        let (conversation, events) = current.execute(cmd).map_err(DomainError::ValidationError)?;
+        let stored = self
+            .event_store
+            .append_events(cmd.conversation_id(), events, current.version())
+            .await?;
+        Ok((conversation, stored))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{PostMessage, StartConversation};
+    use crate::value_objects::{ConversationBudget, MessageId, ParticipantId, PersonId};
+
+    #[tokio::test]
+    async fn test_conversation_is_replayed_from_stored_events() {
+        let store = Arc::new(InMemoryConversationEventStore::new());
+        let repository = ConversationRepository::new(store.clone());
+        let owner = PersonId::new();
+        let start = StartConversation::new(owner)
+            .with_budget(ConversationBudget::unlimited().with_max_tokens(100));
+        let id = start.conversation_id;
+        assert!(repository.load(id).await.unwrap().is_none());
+
+        repository
+            .execute(&ConversationCommand::Start(start))
+            .await
+            .unwrap();
+        let post =
+            PostMessage::new(id, ParticipantId::Person(owner), MessageId::new()).with_tokens(40);
+        let (_, stored) = repository
+            .execute(&ConversationCommand::PostMessage(post))
+            .await
+            .unwrap();
+        assert_eq!(stored[0].sequence, 2);
+
+        let loaded = repository.load(id).await.unwrap().unwrap();
+        assert_eq!(loaded.version(), 2);
+        assert_eq!(loaded.tokens_used(), 40);
+    }
+
+    #[tokio::test]
+    async fn test_stale_append_is_a_conflict() {
+        let store = InMemoryConversationEventStore::new();
+        let start = StartConversation::new(PersonId::new());
+        let id = start.conversation_id;
+        let events = Conversation::empty()
+            .handle(&ConversationCommand::Start(start))
+            .unwrap();
+        store.append_events(id, events.clone(), 0).await.unwrap();
+
+        let result = store.append_events(id, events, 0).await;
+        assert!(matches!(
+            result,
+            Err(DomainError::ConcurrencyConflict { expected: 0, .. })
+        ));
+    }
+}
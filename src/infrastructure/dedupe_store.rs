@@ -0,0 +1,253 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Consumer-side dedupe store
+//!
+//! JetStream delivers at least once, so a consumer that applies effects
+//! (projections, bridges to other domains) can apply the same event twice.
+//! A [`DedupeStore`] remembers what each named consumer has already handled:
+//!
+//! ```text
+//! delivery ──> first_delivery(consumer, key) ── TTL-bounded
+//!                 │
+//!        true ────┴──── false
+//!          │              │
+//!      apply effect     skip (redelivery)
+//!          │ failed
+//!          v
+//!      release(consumer, key) ──> next delivery applies it again
+//! ```
+//!
+//! Keys come from [`dedupe_key`]; they identify an event by aggregate,
+//! sequence, correlation, type and timestamp, which tells streamed chunks
+//! of one response apart. Consumers sharing a store share what has been
+//! handled, so a bridge running on several hosts fires once per event.
+
+use super::{DomainError, DomainResult, EventEnvelope};
+use crate::value_objects::{AgentId, CommandMetadata};
+use async_nats::jetstream::{self, kv::Store as KvStore};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long event keys are remembered by default
+pub const DEFAULT_DEDUPE_TTL: Duration = Duration::from_secs(600);
+
+/// Idempotency key for an event
+///
+/// Uses only characters valid in NATS KV keys.
+pub fn dedupe_key(envelope: &EventEnvelope) -> String {
+    format!(
+        "{}_{}_{}_{}_{}",
+        envelope.aggregate_id,
+        envelope.sequence,
+        envelope.correlation_id,
+        envelope.event.event_type_name(),
+        envelope.timestamp.timestamp_nanos_opt().unwrap_or_default(),
+    )
+}
+
+/// Idempotency key for a command, if its envelope carries one
+///
+/// Scoped to the command's tenant and agent. The caller's key and tenant are
+/// hex-encoded, since they may hold characters NATS KV keys do not allow.
+pub fn command_dedupe_key(agent_id: AgentId, metadata: &CommandMetadata) -> Option<String> {
+    let hex = |s: &str| s.bytes().map(|b| format!("{:02x}", b)).collect::<String>();
+    let key = metadata.idempotency_key.as_deref()?;
+    let tenant = metadata.tenant.as_deref().unwrap_or_default();
+    Some(format!("{}_{}_{}", hex(tenant), agent_id, hex(key)))
+}
+
+/// Dedupe store trait
+///
+/// Tracks, per consumer, the event keys already handled.
+#[async_trait]
+pub trait DedupeStore: Send + Sync {
+    /// Record `key` for `consumer`; false if it was recorded within the TTL
+    async fn first_delivery(&self, consumer: &str, key: &str) -> DomainResult<bool>;
+
+    /// Forget `key` for `consumer`, so its next delivery is handled again
+    async fn release(&self, consumer: &str, key: &str) -> DomainResult<()>;
+}
+
+#[derive(Debug, Default)]
+struct ConsumerState {
+    seen: HashSet<String>,
+    order: VecDeque<(Instant, String)>,
+}
+
+/// In-memory dedupe store, for consumers local to one process
+#[derive(Debug, Clone)]
+pub struct InMemoryDedupeStore {
+    consumers: Arc<Mutex<HashMap<String, ConsumerState>>>,
+    ttl: Duration,
+}
+
+impl InMemoryDedupeStore {
+    /// Create a store remembering keys for [`DEFAULT_DEDUPE_TTL`]
+    pub fn new() -> Self {
+        Self {
+            consumers: Arc::default(),
+            ttl: DEFAULT_DEDUPE_TTL,
+        }
+    }
+
+    /// Builder: how long to remember event keys
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl Default for InMemoryDedupeStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DedupeStore for InMemoryDedupeStore {
+    async fn first_delivery(&self, consumer: &str, key: &str) -> DomainResult<bool> {
+        let now = Instant::now();
+        let mut consumers = self.consumers.lock().unwrap_or_else(|e| e.into_inner());
+        let state = consumers.entry(consumer.to_string()).or_default();
+
+        while let Some((at, _)) = state.order.front() {
+            if now.duration_since(*at) < self.ttl {
+                break;
+            }
+            if let Some((_, expired)) = state.order.pop_front() {
+                state.seen.remove(&expired);
+            }
+        }
+
+        if !state.seen.insert(key.to_string()) {
+            return Ok(false);
+        }
+        state.order.push_back((now, key.to_string()));
+        Ok(true)
+    }
+
+    async fn release(&self, consumer: &str, key: &str) -> DomainResult<()> {
+        let mut consumers = self.consumers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = consumers.get_mut(consumer) {
+            state.seen.remove(key);
+            state.order.retain(|(_, seen)| seen != key);
+        }
+        Ok(())
+    }
+}
+
+/// NATS KV dedupe store, shared by every host using the bucket
+///
+/// Keys expire with the bucket's max age. Keys are created atomically, so
+/// of several hosts seeing one event only the first to record it applies it.
+pub struct NatsDedupeStore {
+    kv: KvStore,
+}
+
+impl NatsDedupeStore {
+    /// Create a store over an existing KV bucket
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Create or get the KV bucket, expiring entries after `ttl`
+    ///
+    /// # Arguments
+    ///
+    /// * `jetstream` - JetStream context
+    /// * `bucket_name` - Name of the KV bucket (e.g., "AGENT_DEDUPE")
+    /// * `ttl` - How long event keys are remembered
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+        ttl: Duration,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        max_age: ttl,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+
+    fn key(consumer: &str, suffix: &str) -> String {
+        let consumer: String = consumer
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        format!("{}.{}", consumer, suffix)
+    }
+}
+
+fn store_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::DedupeStoreError(e.to_string())
+}
+
+#[async_trait]
+impl DedupeStore for NatsDedupeStore {
+    async fn first_delivery(&self, consumer: &str, key: &str) -> DomainResult<bool> {
+        match self.kv.create(Self::key(consumer, key), Vec::new().into()).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == jetstream::kv::CreateErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(store_error(e)),
+        }
+    }
+
+    async fn release(&self, consumer: &str, key: &str) -> DomainResult<()> {
+        self.kv.delete(Self::key(consumer, key)).await.map_err(store_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentEvent};
+    use crate::test_support::envelope;
+
+    #[tokio::test]
+    async fn test_keys_are_remembered_per_consumer_until_ttl() {
+        let store = InMemoryDedupeStore::new().with_ttl(Duration::from_millis(20));
+        let agent_id = AgentId::new();
+        let activated = AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id));
+        let key = dedupe_key(&envelope(1, activated));
+
+        assert!(store.first_delivery("projections", &key).await.unwrap());
+        assert!(!store.first_delivery("projections", &key).await.unwrap());
+        assert!(store.first_delivery("notifications", &key).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(store.first_delivery("projections", &key).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_released_keys_are_delivered_again() {
+        let store = InMemoryDedupeStore::new();
+        let mut metadata = CommandMetadata {
+            tenant: Some("acme corp".to_string()),
+            idempotency_key: Some("activate/1".to_string()),
+            ..CommandMetadata::default()
+        };
+        let agent_id = AgentId::new();
+        let key = command_dedupe_key(agent_id, &metadata).unwrap();
+        assert!(key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        assert!(store.first_delivery("commands", &key).await.unwrap());
+        assert!(!store.first_delivery("commands", &key).await.unwrap());
+        store.release("commands", &key).await.unwrap();
+        assert!(store.first_delivery("commands", &key).await.unwrap());
+
+        metadata.idempotency_key = None;
+        assert!(command_dedupe_key(agent_id, &metadata).is_none());
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Fast event envelope decoding for replay
+//!
+//! `AgentEvent` is internally tagged (`#[serde(tag = "type")]`). Serde
+//! decodes internally tagged enums by first buffering the whole object into
+//! an intermediate tree, then searching it for the tag, then deserializing
+//! the variant from the buffer. On replay loops over millions of events that
+//! intermediate dominates the cost.
+//!
+//! This decoder avoids it:
+//!
+//! ```text
+//! payload ──> RawEnvelope { event: &RawValue }     (borrowed, event unparsed)
+//!                   │
+//!                   v
+//!             EventTag { type }                    (borrowed discriminator)
+//!                   │
+//!                   ├─ filter rejects ──> skipped, event body never parsed
+//!                   v
+//!             concrete event struct                (direct, no intermediate)
+//! ```
+//!
+//! Replays that only rebuild aggregate state can skip message events
+//! entirely with [`is_state_changing`], since `Agent::apply_event` ignores
+//! them.
+
+use super::{DomainError, DomainResult, EventEnvelope};
+use crate::events::AgentEvent;
+use crate::value_objects::{AgentId, CommandMetadata};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use uuid::Uuid;
+
+/// Envelope with the event body left as raw JSON
+#[derive(Deserialize)]
+struct RawEnvelope<'a> {
+    aggregate_id: AgentId,
+    sequence: u64,
+    #[serde(borrow)]
+    event: &'a RawValue,
+    timestamp: DateTime<Utc>,
+    correlation_id: Uuid,
+    causation_id: Uuid,
+    #[serde(default)]
+    metadata: Option<CommandMetadata>,
+}
+
+/// Just the discriminator of an event
+#[derive(Deserialize)]
+struct EventTag<'a> {
+    #[serde(borrow, rename = "type")]
+    event_type: Cow<'a, str>,
+}
+
+/// Check if an event type (the serialized `type` tag) changes agent state
+///
+/// Message, response and operational events are side-effect events that
+/// `Agent::apply_event` passes over.
+pub fn is_state_changing(event_type: &str) -> bool {
+    matches!(
+        event_type,
+        "AgentDeployed"
+            | "ModelConfigured"
+            | "ModelConfigurationAssigned"
+            | "SystemPromptConfigured"
+            | "FeatureFlagsChanged"
+            | "LocalePreferencesChanged"
+            | "AgentActivated"
+            | "AgentSuspended"
+            | "AgentDecommissioned"
+            | "LegalHoldPlaced"
+            | "LegalHoldReleased"
+            | "CapabilityVerificationFailed"
+    )
+}
+
+/// Read the event type of a stored envelope without decoding the event
+pub fn peek_event_type(payload: &[u8]) -> DomainResult<String> {
+    let raw: RawEnvelope<'_> = serde_json::from_slice(payload).map_err(serialization_error)?;
+    let tag: EventTag<'_> = serde_json::from_str(raw.event.get()).map_err(serialization_error)?;
+    Ok(tag.event_type.into_owned())
+}
+
+/// Decode a stored envelope
+///
+/// Produces the same result as `serde_json::from_slice::<EventEnvelope>`.
+pub fn decode_envelope(payload: &[u8]) -> DomainResult<EventEnvelope> {
+    decode_envelope_if(payload, |_| true).map(|envelope| {
+        envelope.expect("filter accepting every event type always decodes")
+    })
+}
+
+/// Decode a stored envelope only if `filter` accepts its event type
+///
+/// Returns `Ok(None)` for rejected events; their bodies are never parsed.
+pub fn decode_envelope_if(
+    payload: &[u8],
+    filter: impl FnOnce(&str) -> bool,
+) -> DomainResult<Option<EventEnvelope>> {
+    let raw: RawEnvelope<'_> = serde_json::from_slice(payload).map_err(serialization_error)?;
+    let json = raw.event.get();
+    let tag: EventTag<'_> = serde_json::from_str(json).map_err(serialization_error)?;
+
+    if !filter(&tag.event_type) {
+        return Ok(None);
+    }
+
+    let event = decode_event(&tag.event_type, json).map_err(serialization_error)?;
+    Ok(Some(EventEnvelope {
+        aggregate_id: raw.aggregate_id,
+        sequence: raw.sequence,
+        event,
+        timestamp: raw.timestamp,
+        correlation_id: raw.correlation_id,
+        causation_id: raw.causation_id,
+        metadata: raw.metadata,
+    }))
+}
+
+/// Deserialize the variant named by `event_type` directly from `json`
+///
+/// The `type` field is ignored by the concrete event structs. Unknown tags
+/// fall back to the tagged enum so the error message stays serde's own.
+fn decode_event(event_type: &str, json: &str) -> serde_json::Result<AgentEvent> {
+    use serde_json::from_str;
+
+    Ok(match event_type {
+        "AgentDeployed" => AgentEvent::AgentDeployed(from_str(json)?),
+        "ModelConfigured" => AgentEvent::ModelConfigured(from_str(json)?),
+        "ModelConfigurationAssigned" => AgentEvent::ModelConfigurationAssigned(from_str(json)?),
+        "SystemPromptConfigured" => AgentEvent::SystemPromptConfigured(from_str(json)?),
+        "AgentActivated" => AgentEvent::AgentActivated(from_str(json)?),
+        "AgentSuspended" => AgentEvent::AgentSuspended(from_str(json)?),
+        "AgentDecommissioned" => AgentEvent::AgentDecommissioned(from_str(json)?),
+        "MessageSent" => AgentEvent::MessageSent(from_str(json)?),
+        "ResponseChunkReceived" => AgentEvent::ResponseChunkReceived(from_str(json)?),
+        "ResponseCompleted" => AgentEvent::ResponseCompleted(from_str(json)?),
+        "ResponseFailed" => AgentEvent::ResponseFailed(from_str(json)?),
+        "OutputLimitEnforced" => AgentEvent::OutputLimitEnforced(from_str(json)?),
+        "ResponseCancelled" => AgentEvent::ResponseCancelled(from_str(json)?),
+        "SloViolated" => AgentEvent::SloViolated(from_str(json)?),
+        "DailyDigestReady" => AgentEvent::DailyDigestReady(from_str(json)?),
+        "ConversationArchived" => AgentEvent::ConversationArchived(from_str(json)?),
+        "ConfigurationDriftDetected" => AgentEvent::ConfigurationDriftDetected(from_str(json)?),
+        "CanaryDecided" => AgentEvent::CanaryDecided(from_str(json)?),
+        "EvaluationCompleted" => AgentEvent::EvaluationCompleted(from_str(json)?),
+        "CapabilityNegotiated" => AgentEvent::CapabilityNegotiated(from_str(json)?),
+        "DeadlineExceeded" => AgentEvent::DeadlineExceeded(from_str(json)?),
+        "FeatureFlagsChanged" => AgentEvent::FeatureFlagsChanged(from_str(json)?),
+        "LongContextFallback" => AgentEvent::LongContextFallback(from_str(json)?),
+        "ContextTruncated" => AgentEvent::ContextTruncated(from_str(json)?),
+        "CandidateSelected" => AgentEvent::CandidateSelected(from_str(json)?),
+        "ResponseDrafted" => AgentEvent::ResponseDrafted(from_str(json)?),
+        "ReasoningTraceRecorded" => AgentEvent::ReasoningTraceRecorded(from_str(json)?),
+        "LocalePreferencesChanged" => AgentEvent::LocalePreferencesChanged(from_str(json)?),
+        "DeprecatedToolInUse" => AgentEvent::DeprecatedToolInUse(from_str(json)?),
+        "ToolVersionMigrated" => AgentEvent::ToolVersionMigrated(from_str(json)?),
+        "ToolInvoked" => AgentEvent::ToolInvoked(from_str(json)?),
+        "ToolSucceeded" => AgentEvent::ToolSucceeded(from_str(json)?),
+        "ToolFailed" => AgentEvent::ToolFailed(from_str(json)?),
+        "LegalHoldRefused" => AgentEvent::LegalHoldRefused(from_str(json)?),
+        "LegalHoldPlaced" => AgentEvent::LegalHoldPlaced(from_str(json)?),
+        "LegalHoldReleased" => AgentEvent::LegalHoldReleased(from_str(json)?),
+        "PermissionEscalationRequested" => {
+            AgentEvent::PermissionEscalationRequested(from_str(json)?)
+        }
+        "PermissionEscalationApproved" => AgentEvent::PermissionEscalationApproved(from_str(json)?),
+        "PermissionEscalationExpired" => AgentEvent::PermissionEscalationExpired(from_str(json)?),
+        "AnomalyDetected" => AgentEvent::AnomalyDetected(from_str(json)?),
+        "BatchProgress" => AgentEvent::BatchProgress(from_str(json)?),
+        "BatchCompleted" => AgentEvent::BatchCompleted(from_str(json)?),
+        "MessageRetryScheduled" => AgentEvent::MessageRetryScheduled(from_str(json)?),
+        "MessageQuarantined" => AgentEvent::MessageQuarantined(from_str(json)?),
+        "QuarantinedMessageReleased" => AgentEvent::QuarantinedMessageReleased(from_str(json)?),
+        "CapabilityVerificationFailed" => {
+            AgentEvent::CapabilityVerificationFailed(from_str(json)?)
+        }
+        "ChangeRateExceeded" => AgentEvent::ChangeRateExceeded(from_str(json)?),
+        "OutputConstraintEnforced" => AgentEvent::OutputConstraintEnforced(from_str(json)?),
+        "SpendThresholdCrossed" => AgentEvent::SpendThresholdCrossed(from_str(json)?),
+        _ => from_str(json)?,
+    })
+}
+
+fn serialization_error(e: serde_json::Error) -> DomainError {
+    DomainError::SerializationError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, ResponseChunkReceivedEvent};
+    use crate::value_objects::{MessageId, PersonId, StreamingChunk};
+
+    fn envelope(event: AgentEvent) -> Vec<u8> {
+        serde_json::to_vec(&EventEnvelope {
+            aggregate_id: event.agent_id(),
+            sequence: 7,
+            event,
+            timestamp: Utc::now(),
+            correlation_id: Uuid::now_v7(),
+            causation_id: Uuid::now_v7(),
+            metadata: None,
+        })
+        .unwrap()
+    }
+
+    fn deployed() -> AgentEvent {
+        AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            AgentId::new(),
+            PersonId::new(),
+            "replayed",
+            Some("with \"escaped\" text".to_string()),
+        ))
+    }
+
+    fn chunk() -> AgentEvent {
+        AgentEvent::ResponseChunkReceived(ResponseChunkReceivedEvent::new(
+            AgentId::new(),
+            MessageId::new(),
+            StreamingChunk::new(3, "partial"),
+        ))
+    }
+
+    #[test]
+    fn test_matches_tagged_deserialization() {
+        for payload in [envelope(deployed()), envelope(chunk())] {
+            let fast = decode_envelope(&payload).unwrap();
+            let slow: EventEnvelope = serde_json::from_slice(&payload).unwrap();
+
+            assert_eq!(
+                serde_json::to_value(&fast).unwrap(),
+                serde_json::to_value(&slow).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_filter_skips_event_body() {
+        let payload = envelope(chunk());
+
+        assert_eq!(peek_event_type(&payload).unwrap(), "ResponseChunkReceived");
+        assert!(decode_envelope_if(&payload, is_state_changing).unwrap().is_none());
+        assert!(decode_envelope_if(&envelope(deployed()), is_state_changing)
+            .unwrap()
+            .is_some());
+        assert!(is_state_changing("CapabilityVerificationFailed"));
+    }
+
+    #[test]
+    fn test_unknown_event_type_is_an_error() {
+        let mut value = serde_json::from_slice::<serde_json::Value>(&envelope(chunk())).unwrap();
+        value["event"]["type"] = serde_json::json!("NoSuchEvent");
+        let payload = serde_json::to_vec(&value).unwrap();
+
+        assert!(matches!(
+            decode_envelope(&payload),
+            Err(DomainError::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,780 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Server-side event filtering for JetStream consumers
+//!
+//! Consumers describe the events they want with a small filter expression;
+//! [`SubscriptionBuilder`] compiles it into JetStream subject filters so the
+//! server never delivers the rest.
+//!
+//! ```text
+//! type=activated,suspended and label.team=search
+//!        │                            │
+//!        │                   AgentLabelIndex ──> {id1, id2}
+//!        v                            v
+//! agent.events.agent.{id1}.activated   agent.events.agent.{id2}.activated
+//! agent.events.agent.{id1}.suspended   agent.events.agent.{id2}.suspended
+//! ```
+//!
+//! # Grammar
+//!
+//! Clauses are joined with `and`; values within a clause are alternatives.
+//!
+//! - `type=<kind>[,<kind>...]` - event kinds, or the groups `lifecycle` / `message`
+//! - `agent=<uuid>[,<uuid>...]` - specific agents
+//! - `cluster=<capability>[,...]` - agents in these capability clusters, via an
+//!   [`AgentLabelIndex`]
+//! - `label.<key>=<value>[,...]` - agents whose label matches, via an [`AgentLabelIndex`]
+//!
+//! Events are published on `{domain}.events.agent.{id}.*` only, so cluster
+//! clauses select agents just as label clauses do.
+
+use super::AgentSubjectFactory;
+use crate::events::AgentEvent;
+use crate::value_objects::{AgentId, CapabilityCluster};
+use async_nats::jetstream;
+use cim_domain::SubjectPattern;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+/// Most subject filters a single consumer is given
+pub const MAX_FILTER_SUBJECTS: usize = 256;
+
+/// Event filter errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EventFilterError {
+    #[error("Invalid filter expression: {0}")]
+    Parse(String),
+
+    #[error("Unknown event type: {0}")]
+    UnknownEventType(String),
+
+    #[error("Unknown capability cluster: {0}")]
+    UnknownCluster(String),
+
+    #[error("Label filter on '{0}' requires an agent label index")]
+    MissingLabelIndex(String),
+
+    #[error("Filter matches no agents")]
+    NoMatchingAgents,
+
+    #[error("Filter expands to {count} subjects (max {max})")]
+    TooManySubjects { count: usize, max: usize },
+
+    #[error("Invalid subject filter: {0}")]
+    Subject(String),
+
+    #[error("Consumer error: {0}")]
+    Consumer(String),
+}
+
+/// Event filter result type
+pub type EventFilterResult<T> = Result<T, EventFilterError>;
+
+/// Kinds of agent events, as they appear in subjects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventKind {
+    Deployed,
+    ModelConfigured,
+    Activated,
+    Suspended,
+    Decommissioned,
+    SloViolated,
+    DailyDigestReady,
+    ConversationArchived,
+    ConfigurationDriftDetected,
+    CanaryDecided,
+    EvaluationCompleted,
+    CapabilityNegotiated,
+    DeadlineExceeded,
+    FeatureFlagsChanged,
+    LongContextFallback,
+    ContextTruncated,
+    CandidateSelected,
+    ResponseDrafted,
+    ReasoningTraceRecorded,
+    LocalePreferencesChanged,
+    DeprecatedToolInUse,
+    ToolVersionMigrated,
+    ToolInvoked,
+    ToolSucceeded,
+    ToolFailed,
+    LegalHoldRefused,
+    LegalHoldPlaced,
+    LegalHoldReleased,
+    PermissionEscalationRequested,
+    PermissionEscalationApproved,
+    PermissionEscalationExpired,
+    AnomalyDetected,
+    BatchProgress,
+    BatchCompleted,
+    MessageRetryScheduled,
+    MessageQuarantined,
+    QuarantinedMessageReleased,
+    CapabilityVerificationFailed,
+    ChangeRateExceeded,
+    OutputConstraintEnforced,
+    SpendThresholdCrossed,
+    MessageSent,
+    ResponseChunk,
+    ResponseCompleted,
+    ResponseFailed,
+    ResponseCancelled,
+    OutputLimitEnforced,
+}
+
+impl EventKind {
+    /// Lifecycle events (persisted, change agent state)
+    pub const LIFECYCLE: [EventKind; 5] = [
+        EventKind::Deployed,
+        EventKind::ModelConfigured,
+        EventKind::Activated,
+        EventKind::Suspended,
+        EventKind::Decommissioned,
+    ];
+
+    /// Per-message events
+    pub const MESSAGE: [EventKind; 6] = [
+        EventKind::MessageSent,
+        EventKind::ResponseChunk,
+        EventKind::ResponseCompleted,
+        EventKind::ResponseFailed,
+        EventKind::ResponseCancelled,
+        EventKind::OutputLimitEnforced,
+    ];
+
+    /// Operational events (not part of either group)
+    const OPERATIONAL: [EventKind; 36] = [
+        EventKind::SloViolated,
+        EventKind::DailyDigestReady,
+        EventKind::ConversationArchived,
+        EventKind::ConfigurationDriftDetected,
+        EventKind::CanaryDecided,
+        EventKind::EvaluationCompleted,
+        EventKind::CapabilityNegotiated,
+        EventKind::DeadlineExceeded,
+        EventKind::FeatureFlagsChanged,
+        EventKind::LongContextFallback,
+        EventKind::ContextTruncated,
+        EventKind::CandidateSelected,
+        EventKind::ResponseDrafted,
+        EventKind::ReasoningTraceRecorded,
+        EventKind::LocalePreferencesChanged,
+        EventKind::DeprecatedToolInUse,
+        EventKind::ToolVersionMigrated,
+        EventKind::ToolInvoked,
+        EventKind::ToolSucceeded,
+        EventKind::ToolFailed,
+        EventKind::LegalHoldRefused,
+        EventKind::LegalHoldPlaced,
+        EventKind::LegalHoldReleased,
+        EventKind::PermissionEscalationRequested,
+        EventKind::PermissionEscalationApproved,
+        EventKind::PermissionEscalationExpired,
+        EventKind::AnomalyDetected,
+        EventKind::BatchProgress,
+        EventKind::BatchCompleted,
+        EventKind::MessageRetryScheduled,
+        EventKind::MessageQuarantined,
+        EventKind::QuarantinedMessageReleased,
+        EventKind::CapabilityVerificationFailed,
+        EventKind::ChangeRateExceeded,
+        EventKind::OutputConstraintEnforced,
+        EventKind::SpendThresholdCrossed,
+    ];
+
+    /// Kind of a stored event
+    ///
+    /// Model configuration assignments and system prompt changes are published
+    /// on the `model_configured` subject, so they share its kind.
+    pub fn of(event: &AgentEvent) -> EventKind {
+        match event {
+            AgentEvent::AgentDeployed(_) => EventKind::Deployed,
+            AgentEvent::ModelConfigured(_)
+            | AgentEvent::ModelConfigurationAssigned(_)
+            | AgentEvent::SystemPromptConfigured(_) => EventKind::ModelConfigured,
+            AgentEvent::AgentActivated(_) => EventKind::Activated,
+            AgentEvent::AgentSuspended(_) => EventKind::Suspended,
+            AgentEvent::AgentDecommissioned(_) => EventKind::Decommissioned,
+            AgentEvent::MessageSent(_) => EventKind::MessageSent,
+            AgentEvent::ResponseChunkReceived(_) => EventKind::ResponseChunk,
+            AgentEvent::ResponseCompleted(_) => EventKind::ResponseCompleted,
+            AgentEvent::ResponseFailed(_) => EventKind::ResponseFailed,
+            AgentEvent::OutputLimitEnforced(_) => EventKind::OutputLimitEnforced,
+            AgentEvent::ResponseCancelled(_) => EventKind::ResponseCancelled,
+            AgentEvent::SloViolated(_) => EventKind::SloViolated,
+            AgentEvent::DailyDigestReady(_) => EventKind::DailyDigestReady,
+            AgentEvent::ConversationArchived(_) => EventKind::ConversationArchived,
+            AgentEvent::ConfigurationDriftDetected(_) => EventKind::ConfigurationDriftDetected,
+            AgentEvent::CanaryDecided(_) => EventKind::CanaryDecided,
+            AgentEvent::EvaluationCompleted(_) => EventKind::EvaluationCompleted,
+            AgentEvent::CapabilityNegotiated(_) => EventKind::CapabilityNegotiated,
+            AgentEvent::DeadlineExceeded(_) => EventKind::DeadlineExceeded,
+            AgentEvent::FeatureFlagsChanged(_) => EventKind::FeatureFlagsChanged,
+            AgentEvent::LongContextFallback(_) => EventKind::LongContextFallback,
+            AgentEvent::ContextTruncated(_) => EventKind::ContextTruncated,
+            AgentEvent::CandidateSelected(_) => EventKind::CandidateSelected,
+            AgentEvent::ResponseDrafted(_) => EventKind::ResponseDrafted,
+            AgentEvent::ReasoningTraceRecorded(_) => EventKind::ReasoningTraceRecorded,
+            AgentEvent::LocalePreferencesChanged(_) => EventKind::LocalePreferencesChanged,
+            AgentEvent::DeprecatedToolInUse(_) => EventKind::DeprecatedToolInUse,
+            AgentEvent::ToolVersionMigrated(_) => EventKind::ToolVersionMigrated,
+            AgentEvent::ToolInvoked(_) => EventKind::ToolInvoked,
+            AgentEvent::ToolSucceeded(_) => EventKind::ToolSucceeded,
+            AgentEvent::ToolFailed(_) => EventKind::ToolFailed,
+            AgentEvent::LegalHoldRefused(_) => EventKind::LegalHoldRefused,
+            AgentEvent::LegalHoldPlaced(_) => EventKind::LegalHoldPlaced,
+            AgentEvent::LegalHoldReleased(_) => EventKind::LegalHoldReleased,
+            AgentEvent::PermissionEscalationRequested(_) => {
+                EventKind::PermissionEscalationRequested
+            }
+            AgentEvent::PermissionEscalationApproved(_) => EventKind::PermissionEscalationApproved,
+            AgentEvent::PermissionEscalationExpired(_) => EventKind::PermissionEscalationExpired,
+            AgentEvent::AnomalyDetected(_) => EventKind::AnomalyDetected,
+            AgentEvent::BatchProgress(_) => EventKind::BatchProgress,
+            AgentEvent::BatchCompleted(_) => EventKind::BatchCompleted,
+            AgentEvent::MessageRetryScheduled(_) => EventKind::MessageRetryScheduled,
+            AgentEvent::MessageQuarantined(_) => EventKind::MessageQuarantined,
+            AgentEvent::QuarantinedMessageReleased(_) => EventKind::QuarantinedMessageReleased,
+            AgentEvent::CapabilityVerificationFailed(_) => EventKind::CapabilityVerificationFailed,
+            AgentEvent::ChangeRateExceeded(_) => EventKind::ChangeRateExceeded,
+            AgentEvent::OutputConstraintEnforced(_) => EventKind::OutputConstraintEnforced,
+            AgentEvent::SpendThresholdCrossed(_) => EventKind::SpendThresholdCrossed,
+        }
+    }
+
+    /// Every kind, lifecycle first
+    pub fn all() -> impl Iterator<Item = EventKind> {
+        Self::LIFECYCLE
+            .into_iter()
+            .chain(Self::MESSAGE)
+            .chain(Self::OPERATIONAL)
+    }
+
+    /// Name used in filter expressions
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Deployed => "deployed",
+            EventKind::ModelConfigured => "model_configured",
+            EventKind::Activated => "activated",
+            EventKind::Suspended => "suspended",
+            EventKind::Decommissioned => "decommissioned",
+            EventKind::SloViolated => "slo_violated",
+            EventKind::DailyDigestReady => "daily_digest_ready",
+            EventKind::ConversationArchived => "conversation_archived",
+            EventKind::ConfigurationDriftDetected => "configuration_drift_detected",
+            EventKind::CanaryDecided => "canary_decided",
+            EventKind::EvaluationCompleted => "evaluation_completed",
+            EventKind::CapabilityNegotiated => "capability_negotiated",
+            EventKind::DeadlineExceeded => "deadline_exceeded",
+            EventKind::FeatureFlagsChanged => "feature_flags_changed",
+            EventKind::LongContextFallback => "long_context_fallback",
+            EventKind::ContextTruncated => "context_truncated",
+            EventKind::CandidateSelected => "candidate_selected",
+            EventKind::ResponseDrafted => "response_drafted",
+            EventKind::ReasoningTraceRecorded => "reasoning_trace_recorded",
+            EventKind::LocalePreferencesChanged => "locale_preferences_changed",
+            EventKind::DeprecatedToolInUse => "deprecated_tool_in_use",
+            EventKind::ToolVersionMigrated => "tool_version_migrated",
+            EventKind::ToolInvoked => "tool_invoked",
+            EventKind::ToolSucceeded => "tool_succeeded",
+            EventKind::ToolFailed => "tool_failed",
+            EventKind::LegalHoldRefused => "legal_hold_refused",
+            EventKind::LegalHoldPlaced => "legal_hold_placed",
+            EventKind::LegalHoldReleased => "legal_hold_released",
+            EventKind::PermissionEscalationRequested => "permission_escalation_requested",
+            EventKind::PermissionEscalationApproved => "permission_escalation_approved",
+            EventKind::PermissionEscalationExpired => "permission_escalation_expired",
+            EventKind::AnomalyDetected => "anomaly_detected",
+            EventKind::BatchProgress => "batch_progress",
+            EventKind::BatchCompleted => "batch_completed",
+            EventKind::MessageRetryScheduled => "message_retry_scheduled",
+            EventKind::MessageQuarantined => "message_quarantined",
+            EventKind::QuarantinedMessageReleased => "quarantined_message_released",
+            EventKind::CapabilityVerificationFailed => "capability_verification_failed",
+            EventKind::ChangeRateExceeded => "change_rate_exceeded",
+            EventKind::OutputConstraintEnforced => "output_constraint_enforced",
+            EventKind::SpendThresholdCrossed => "spend_threshold_crossed",
+            EventKind::MessageSent => "message_sent",
+            EventKind::ResponseChunk => "response_chunk",
+            EventKind::ResponseCompleted => "response_completed",
+            EventKind::ResponseFailed => "response_failed",
+            EventKind::ResponseCancelled => "response_cancelled",
+            EventKind::OutputLimitEnforced => "output_limit_enforced",
+        }
+    }
+
+    /// Subject suffix after the agent ID (see `AgentSubjectFactory`)
+    fn subject_tail(&self) -> &'static str {
+        match self {
+            EventKind::Deployed => "deployed",
+            EventKind::ModelConfigured => "model_configured",
+            EventKind::Activated => "activated",
+            EventKind::Suspended => "suspended",
+            EventKind::Decommissioned => "decommissioned",
+            EventKind::SloViolated => "slo_violated",
+            EventKind::DailyDigestReady => "daily_digest_ready",
+            EventKind::ConversationArchived => "conversation_archived",
+            EventKind::ConfigurationDriftDetected => "configuration_drift_detected",
+            EventKind::CanaryDecided => "canary_decided",
+            EventKind::EvaluationCompleted => "evaluation_completed",
+            EventKind::CapabilityNegotiated => "capability_negotiated",
+            EventKind::DeadlineExceeded => "deadline_exceeded",
+            EventKind::FeatureFlagsChanged => "feature_flags_changed",
+            EventKind::LongContextFallback => "long_context_fallback",
+            EventKind::ContextTruncated => "context_truncated",
+            EventKind::CandidateSelected => "candidate_selected",
+            EventKind::ResponseDrafted => "response_drafted",
+            EventKind::ReasoningTraceRecorded => "reasoning_trace_recorded",
+            EventKind::LocalePreferencesChanged => "locale_preferences_changed",
+            EventKind::DeprecatedToolInUse => "deprecated_tool_in_use",
+            EventKind::ToolVersionMigrated => "tool_version_migrated",
+            EventKind::ToolInvoked => "tool_invoked",
+            EventKind::ToolSucceeded => "tool_succeeded",
+            EventKind::ToolFailed => "tool_failed",
+            EventKind::LegalHoldRefused => "legal_hold_refused",
+            EventKind::LegalHoldPlaced => "legal_hold_placed",
+            EventKind::LegalHoldReleased => "legal_hold_released",
+            EventKind::PermissionEscalationRequested => "permission_escalation_requested",
+            EventKind::PermissionEscalationApproved => "permission_escalation_approved",
+            EventKind::PermissionEscalationExpired => "permission_escalation_expired",
+            EventKind::AnomalyDetected => "anomaly_detected",
+            EventKind::BatchProgress => "batch_progress",
+            EventKind::BatchCompleted => "batch_completed",
+            EventKind::MessageRetryScheduled => "message_retry_scheduled",
+            EventKind::MessageQuarantined => "message_quarantined",
+            EventKind::QuarantinedMessageReleased => "quarantined_message_released",
+            EventKind::CapabilityVerificationFailed => "capability_verification_failed",
+            EventKind::ChangeRateExceeded => "change_rate_exceeded",
+            EventKind::OutputConstraintEnforced => "output_constraint_enforced",
+            EventKind::SpendThresholdCrossed => "spend_threshold_crossed",
+            EventKind::MessageSent => "message.*.sent",
+            EventKind::ResponseChunk => "message.*.chunk.*",
+            EventKind::ResponseCompleted => "message.*.completed",
+            EventKind::ResponseFailed => "message.*.failed",
+            EventKind::ResponseCancelled => "message.*.cancelled",
+            EventKind::OutputLimitEnforced => "message.*.limit_enforced",
+        }
+    }
+
+    /// Parse a kind or group name
+    fn parse_group(name: &str) -> EventFilterResult<Vec<EventKind>> {
+        match name {
+            "lifecycle" => Ok(Self::LIFECYCLE.to_vec()),
+            "message" => Ok(Self::MESSAGE.to_vec()),
+            _ => Self::all()
+                .find(|k| k.name() == name)
+                .map(|k| vec![k])
+                .ok_or_else(|| EventFilterError::UnknownEventType(name.to_string())),
+        }
+    }
+}
+
+/// Which events a consumer wants
+///
+/// Each populated dimension narrows the selection; an empty filter selects
+/// every event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    kinds: BTreeSet<EventKind>,
+    agents: BTreeSet<uuid::Uuid>,
+    clusters: Vec<CapabilityCluster>,
+    labels: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl EventFilter {
+    /// Select every event
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Builder: only these event kinds
+    pub fn with_kinds(mut self, kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        self.kinds.extend(kinds);
+        self
+    }
+
+    /// Builder: only these agents
+    pub fn for_agents(mut self, agents: impl IntoIterator<Item = AgentId>) -> Self {
+        self.agents.extend(agents.into_iter().map(|a| *a.as_uuid()));
+        self
+    }
+
+    /// Builder: only agents in these capability clusters
+    pub fn in_clusters(mut self, clusters: impl IntoIterator<Item = CapabilityCluster>) -> Self {
+        for cluster in clusters {
+            if !self.clusters.contains(&cluster) {
+                self.clusters.push(cluster);
+            }
+        }
+        self
+    }
+
+    /// Builder: only agents whose label `key` is one of `values`
+    pub fn with_label(
+        mut self,
+        key: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.labels
+            .entry(key.into())
+            .or_default()
+            .extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    /// Parse a filter expression (see module docs for the grammar)
+    pub fn parse(expression: &str) -> EventFilterResult<Self> {
+        let mut filter = Self::all();
+        if expression.trim().is_empty() {
+            return Ok(filter);
+        }
+
+        for clause in split_clauses(expression) {
+            let (key, values) = clause
+                .split_once('=')
+                .ok_or_else(|| EventFilterError::Parse(format!("expected key=value in '{}'", clause)))?;
+            let key = key.trim();
+            let values: Vec<&str> = values.split(',').map(str::trim).collect();
+            if values.iter().any(|v| v.is_empty()) {
+                return Err(EventFilterError::Parse(format!("empty value in '{}'", clause)));
+            }
+
+            match key {
+                "type" => {
+                    for value in values {
+                        filter.kinds.extend(EventKind::parse_group(value)?);
+                    }
+                }
+                "agent" => {
+                    for value in values {
+                        let id = uuid::Uuid::parse_str(value).map_err(|_| {
+                            EventFilterError::Parse(format!("invalid agent id '{}'", value))
+                        })?;
+                        filter.agents.insert(id);
+                    }
+                }
+                "cluster" => {
+                    let clusters = values
+                        .into_iter()
+                        .map(|v| {
+                            CapabilityCluster::from_str(v)
+                                .ok_or_else(|| EventFilterError::UnknownCluster(v.to_string()))
+                        })
+                        .collect::<EventFilterResult<Vec<_>>>()?;
+                    filter = filter.in_clusters(clusters);
+                }
+                _ => match key.strip_prefix("label.") {
+                    Some(label) if !label.is_empty() => filter = filter.with_label(label, values),
+                    _ => {
+                        return Err(EventFilterError::Parse(format!("unknown key '{}'", key)));
+                    }
+                },
+            }
+        }
+
+        Ok(filter)
+    }
+
+    /// Check if the filter selects every event
+    pub fn is_all(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl FromStr for EventFilter {
+    type Err = EventFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Split on the `and` keyword, case-insensitively
+fn split_clauses(expression: &str) -> Vec<String> {
+    let mut clauses = vec![String::new()];
+    for word in expression.split_whitespace() {
+        if word.eq_ignore_ascii_case("and") {
+            clauses.push(String::new());
+        } else if let Some(current) = clauses.last_mut() {
+            current.push_str(word);
+        }
+    }
+    clauses
+}
+
+/// Agent labels used to resolve `label.*` and `cluster=` clauses
+///
+/// Labels are deployment metadata, not aggregate state, so the index is
+/// supplied by whoever owns that metadata (fleet manifest, registry, ...).
+/// An agent's capability cluster is kept as its [`CLUSTER_LABEL`] label.
+#[derive(Debug, Clone, Default)]
+pub struct AgentLabelIndex {
+    labels: HashMap<AgentId, HashMap<String, String>>,
+}
+
+/// Label holding an agent's capability cluster
+pub const CLUSTER_LABEL: &str = "cluster";
+
+impl AgentLabelIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a label on an agent
+    pub fn insert(&mut self, agent_id: AgentId, key: impl Into<String>, value: impl Into<String>) {
+        self.labels
+            .entry(agent_id)
+            .or_default()
+            .insert(key.into(), value.into());
+    }
+
+    /// Set an agent's capability cluster
+    pub fn insert_cluster(&mut self, agent_id: AgentId, cluster: &CapabilityCluster) {
+        self.insert(agent_id, CLUSTER_LABEL, cluster.as_str());
+    }
+
+    /// Agents whose label `key` is one of `values`
+    pub fn matching(&self, key: &str, values: &BTreeSet<String>) -> BTreeSet<uuid::Uuid> {
+        self.labels
+            .iter()
+            .filter(|(_, labels)| labels.get(key).is_some_and(|v| values.contains(v)))
+            .map(|(id, _)| *id.as_uuid())
+            .collect()
+    }
+}
+
+/// Compiles an [`EventFilter`] into JetStream subject filters and consumers
+#[derive(Debug, Clone)]
+pub struct SubscriptionBuilder<'a> {
+    factory: &'a AgentSubjectFactory,
+    filter: EventFilter,
+    labels: Option<&'a AgentLabelIndex>,
+    durable_name: Option<String>,
+    deliver_policy: jetstream::consumer::DeliverPolicy,
+}
+
+impl<'a> SubscriptionBuilder<'a> {
+    /// Start from a filter
+    pub fn new(factory: &'a AgentSubjectFactory, filter: EventFilter) -> Self {
+        Self {
+            factory,
+            filter,
+            labels: None,
+            durable_name: None,
+            deliver_policy: jetstream::consumer::DeliverPolicy::New,
+        }
+    }
+
+    /// Builder: resolve `label.*` clauses against an index
+    pub fn with_labels(mut self, labels: &'a AgentLabelIndex) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Builder: make the consumer durable
+    pub fn with_durable_name(mut self, name: impl Into<String>) -> Self {
+        self.durable_name = Some(name.into());
+        self
+    }
+
+    /// Builder: where delivery starts (default: new events only)
+    pub fn with_deliver_policy(mut self, policy: jetstream::consumer::DeliverPolicy) -> Self {
+        self.deliver_policy = policy;
+        self
+    }
+
+    /// Agents selected by ID and label clauses (None means any agent)
+    fn resolve_agents(&self) -> EventFilterResult<Option<BTreeSet<uuid::Uuid>>> {
+        let mut selected = (!self.filter.agents.is_empty()).then(|| self.filter.agents.clone());
+
+        let clusters: BTreeSet<String> = self
+            .filter
+            .clusters
+            .iter()
+            .map(|cluster| cluster.as_str().to_string())
+            .collect();
+        let cluster_clause = (!clusters.is_empty()).then_some((CLUSTER_LABEL, &clusters));
+        let label_clauses = self.filter.labels.iter().map(|(k, v)| (k.as_str(), v));
+        for (key, values) in cluster_clause.into_iter().chain(label_clauses) {
+            let index = self
+                .labels
+                .ok_or_else(|| EventFilterError::MissingLabelIndex(key.to_string()))?;
+            let matching = index.matching(key, values);
+            selected = Some(match selected {
+                Some(agents) => agents.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+
+        match selected {
+            Some(agents) if agents.is_empty() => Err(EventFilterError::NoMatchingAgents),
+            other => Ok(other),
+        }
+    }
+
+    /// Subject filters that select exactly the filtered events
+    pub fn subjects(&self) -> EventFilterResult<Vec<String>> {
+        let domain = self.factory.domain();
+        let agents: Vec<String> = match self.resolve_agents()? {
+            Some(agents) => agents.iter().map(ToString::to_string).collect(),
+            None => vec!["*".to_string()],
+        };
+
+        let prefixes: Vec<String> = agents
+            .iter()
+            .map(|id| format!("{}.events.agent.{}", domain, id))
+            .collect();
+
+        let tails: Vec<&str> = if self.filter.kinds.is_empty() {
+            vec![">"]
+        } else {
+            self.filter.kinds.iter().map(EventKind::subject_tail).collect()
+        };
+
+        let count = prefixes.len() * tails.len();
+        if count > MAX_FILTER_SUBJECTS {
+            return Err(EventFilterError::TooManySubjects {
+                count,
+                max: MAX_FILTER_SUBJECTS,
+            });
+        }
+
+        prefixes
+            .iter()
+            .flat_map(|prefix| tails.iter().map(move |tail| format!("{}.{}", prefix, tail)))
+            .map(|subject| {
+                SubjectPattern::parse(&subject)
+                    .map(|p| p.to_string())
+                    .map_err(|e| EventFilterError::Subject(format!("{}: {}", subject, e)))
+            })
+            .collect()
+    }
+
+    /// Pull consumer configuration carrying the subject filters
+    pub fn consumer_config(&self) -> EventFilterResult<jetstream::consumer::pull::Config> {
+        let mut subjects = self.subjects()?;
+        let mut config = jetstream::consumer::pull::Config {
+            durable_name: self.durable_name.clone(),
+            deliver_policy: self.deliver_policy,
+            ack_policy: jetstream::consumer::AckPolicy::Explicit,
+            ..Default::default()
+        };
+
+        if subjects.len() == 1 {
+            config.filter_subject = subjects.remove(0);
+        } else {
+            config.filter_subjects = subjects;
+        }
+        Ok(config)
+    }
+
+    /// Create the filtered consumer on `stream_name`
+    pub async fn create_consumer(
+        &self,
+        jetstream: &jetstream::Context,
+        stream_name: &str,
+    ) -> EventFilterResult<jetstream::consumer::PullConsumer> {
+        let config = self.consumer_config()?;
+        let stream = jetstream
+            .get_stream(stream_name)
+            .await
+            .map_err(|e| EventFilterError::Consumer(format!("Failed to get stream: {}", e)))?;
+        stream
+            .create_consumer(config)
+            .await
+            .map_err(|e| EventFilterError::Consumer(format!("Failed to create consumer: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_filter_compiles_to_subject_tails() {
+        let factory = AgentSubjectFactory::new("cim");
+        let filter = EventFilter::parse("type=activated,response_chunk").unwrap();
+
+        let subjects = SubscriptionBuilder::new(&factory, filter).subjects().unwrap();
+
+        assert_eq!(
+            subjects,
+            vec![
+                "cim.events.agent.*.activated",
+                "cim.events.agent.*.message.*.chunk.*",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_label_and_cluster_resolve_to_agents() {
+        let factory = AgentSubjectFactory::new("cim");
+        let (search, other) = (AgentId::new(), AgentId::new());
+        let mut labels = AgentLabelIndex::new();
+        labels.insert(search, "team", "search");
+        labels.insert(other, "team", "billing");
+        labels.insert_cluster(search, &CapabilityCluster::Orchestration);
+        labels.insert_cluster(other, &CapabilityCluster::Orchestration);
+
+        let filter =
+            EventFilter::parse("cluster=orchestration AND label.team=search and type=deployed")
+                .unwrap();
+        let subjects = SubscriptionBuilder::new(&factory, filter)
+            .with_labels(&labels)
+            .subjects()
+            .unwrap();
+
+        assert_eq!(
+            subjects,
+            vec![format!("cim.events.agent.{}.deployed", search)]
+        );
+
+        let unindexed = EventFilter::parse("cluster=orchestration").unwrap();
+        assert_eq!(
+            SubscriptionBuilder::new(&factory, unindexed).subjects(),
+            Err(EventFilterError::MissingLabelIndex(CLUSTER_LABEL.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_every_kind_has_a_unique_name() {
+        let names: BTreeSet<&str> = EventKind::all().map(|kind| kind.name()).collect();
+        assert_eq!(names.len(), EventKind::all().count());
+
+        let event = AgentEvent::AgentActivated(crate::events::AgentActivatedEvent::new(
+            AgentId::new(),
+        ));
+        assert_eq!(EventKind::of(&event), EventKind::Activated);
+    }
+
+    #[test]
+    fn test_empty_filter_is_single_wildcard() {
+        let factory = AgentSubjectFactory::new("cim");
+        let config = SubscriptionBuilder::new(&factory, EventFilter::all())
+            .consumer_config()
+            .unwrap();
+
+        assert_eq!(config.filter_subject, "cim.events.agent.*.>");
+        assert!(config.filter_subjects.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_bad_expressions() {
+        assert!(matches!(
+            EventFilter::parse("type=exploded"),
+            Err(EventFilterError::UnknownEventType(_))
+        ));
+        assert!(matches!(
+            EventFilter::parse("colour=blue"),
+            Err(EventFilterError::Parse(_))
+        ));
+
+        let factory = AgentSubjectFactory::new("cim");
+        let labelled = EventFilter::all().with_label("team", ["search"]);
+        assert_eq!(
+            SubscriptionBuilder::new(&factory, labelled).subjects(),
+            Err(EventFilterError::MissingLabelIndex("team".to_string()))
+        );
+    }
+}
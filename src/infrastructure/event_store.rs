@@ -3,6 +3,7 @@
 //! Event store trait and implementations
 
 use super::{AgentEvent, AgentId, DomainError, DomainResult};
+use crate::value_objects::{clock_now, next_id, CommandMetadata};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -30,6 +31,31 @@ pub struct EventEnvelope {
 
     /// Causation ID (ID of command/event that caused this)
     pub causation_id: Uuid,
+
+    /// Actor, tenant, deadline, trace and idempotency key of the command
+    /// that produced the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CommandMetadata>,
+}
+
+impl EventEnvelope {
+    /// Stamp the envelope with the metadata of the command being handled
+    ///
+    /// Outside a command scope the envelope is returned unchanged. Ids are
+    /// adjusted as described by [`CommandMetadata::event_ids`].
+    pub fn within_command(mut self) -> Self {
+        if let Some(metadata) = CommandMetadata::current() {
+            (self.correlation_id, self.causation_id) =
+                metadata.event_ids(self.correlation_id, self.causation_id);
+            self.metadata = Some(metadata);
+        }
+        self
+    }
+
+    /// Tenant of the command that produced the event
+    pub fn tenant(&self) -> Option<&str> {
+        self.metadata.as_ref()?.tenant.as_deref()
+    }
 }
 
 /// Event store trait
@@ -82,6 +108,12 @@ impl InMemoryEventStore {
             events: Arc::new(RwLock::new(HashMap::new())),
         }
     }
+
+    /// Every stored envelope, across agents
+    pub fn all_events(&self) -> Vec<EventEnvelope> {
+        let store = self.events.read().unwrap();
+        store.values().flatten().cloned().collect()
+    }
 }
 
 impl Default for InMemoryEventStore {
@@ -121,10 +153,12 @@ impl EventStore for InMemoryEventStore {
                 aggregate_id,
                 sequence,
                 event,
-                timestamp: Utc::now(),
-                correlation_id: Uuid::now_v7(),
-                causation_id: Uuid::now_v7(),
-            };
+                timestamp: clock_now(),
+                correlation_id: next_id(),
+                causation_id: next_id(),
+                metadata: None,
+            }
+            .within_command();
             current_events.push(envelope);
         }
 
@@ -196,6 +230,26 @@ mod tests {
         assert_eq!(events[0].sequence, 1);
     }
 
+    #[tokio::test]
+    async fn test_append_within_command_carries_its_metadata() {
+        let store = InMemoryEventStore::new();
+        let agent_id = AgentId::new();
+        let correlation_id = Uuid::now_v7();
+        let metadata = CommandMetadata {
+            actor: Some("person:ada".to_string()),
+            correlation_id: Some(correlation_id),
+            ..CommandMetadata::default()
+        };
+
+        let event = create_test_deployed_event(agent_id);
+        let append = store.append_events(agent_id, vec![event], None);
+        metadata.clone().scope(append).await.unwrap();
+
+        let events = store.get_events(agent_id).await.unwrap();
+        assert_eq!(events[0].correlation_id, correlation_id);
+        assert_eq!(events[0].metadata, Some(metadata));
+    }
+
     #[tokio::test]
     async fn test_optimistic_concurrency() {
         let store = InMemoryEventStore::new();
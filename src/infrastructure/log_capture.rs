@@ -0,0 +1,286 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Per-agent log capture
+//!
+//! Adapter and tool logs normally go to stdout with nothing saying which
+//! agent they belong to. [`LogCapture`] is a tracing layer that attributes
+//! each log line to the agent named by the nearest enclosing span, buffers
+//! it per agent, and hands the buffers back as [`LogBatch`]es for publishing
+//! on `{domain}.logs.agent.{agent_id}`:
+//!
+//! ```text
+//! info_span!("command", agent_id = %id, conversation_id = %conv)
+//!     └── warn!("provider timed out")   ──> LogCapture::on_event
+//!                                               │ tagged with agent_id / conversation_id
+//!                                               v
+//!                        per-agent buffer (bounded, oldest dropped first)
+//!                                               │ drain()
+//!                                               v
+//!                   LogBatch { agent_id, records, dropped } ──> logs.agent.{agent_id}
+//! ```
+//!
+//! Logs outside any span carrying `agent_id` are left to the other layers.
+//! An event may also carry `agent_id` itself.
+
+use crate::value_objects::{AgentId, ConversationId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use uuid::Uuid;
+
+/// Records buffered per agent before the oldest are dropped, by default
+pub const DEFAULT_LOG_BUFFER: usize = 1_000;
+
+/// One captured log line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// When it was logged
+    pub at: DateTime<Utc>,
+
+    /// Level name (`ERROR` ... `TRACE`)
+    pub level: String,
+
+    /// Module that logged it
+    pub target: String,
+
+    /// The formatted message
+    pub message: String,
+
+    /// Conversation the log was written in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<ConversationId>,
+
+    /// Other fields on the event
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+/// An agent's logs since the last drain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogBatch {
+    /// The agent
+    pub agent_id: AgentId,
+
+    /// Captured lines, oldest first
+    pub records: Vec<LogRecord>,
+
+    /// Lines dropped because the buffer was full
+    #[serde(default)]
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct AgentBuffer {
+    records: VecDeque<LogRecord>,
+    dropped: u64,
+}
+
+/// Tracing layer capturing logs per agent
+#[derive(Debug, Clone)]
+pub struct LogCapture {
+    min_level: Level,
+    capacity: usize,
+    buffers: Arc<Mutex<HashMap<AgentId, AgentBuffer>>>,
+}
+
+impl LogCapture {
+    /// Capture `INFO` and above, up to [`DEFAULT_LOG_BUFFER`] lines per agent
+    pub fn new() -> Self {
+        Self {
+            min_level: Level::INFO,
+            capacity: DEFAULT_LOG_BUFFER,
+            buffers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Builder: least severe level captured
+    pub fn with_min_level(mut self, level: Level) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// Builder: lines buffered per agent between drains
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Take every agent's buffered lines
+    pub fn drain(&self) -> Vec<LogBatch> {
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        buffers
+            .drain()
+            .map(|(agent_id, buffer)| LogBatch {
+                agent_id,
+                records: buffer.records.into(),
+                dropped: buffer.dropped,
+            })
+            .collect()
+    }
+
+    fn push(&self, agent_id: AgentId, record: LogRecord) {
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        let buffer = buffers.entry(agent_id).or_default();
+        if buffer.records.len() >= self.capacity {
+            buffer.records.pop_front();
+            buffer.dropped += 1;
+        }
+        buffer.records.push_back(record);
+    }
+}
+
+impl Default for LogCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Agent and conversation a span or event is about
+#[derive(Debug, Clone, Copy, Default)]
+struct LogTags {
+    agent_id: Option<AgentId>,
+    conversation_id: Option<ConversationId>,
+}
+
+impl LogTags {
+    fn or(self, outer: LogTags) -> LogTags {
+        LogTags {
+            agent_id: self.agent_id.or(outer.agent_id),
+            conversation_id: self.conversation_id.or(outer.conversation_id),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    tags: LogTags,
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl FieldVisitor {
+    fn record(&mut self, field: &Field, value: String) {
+        let parsed = || Uuid::parse_str(value.trim_matches('"')).ok();
+        match field.name() {
+            "message" => self.message = value,
+            "agent_id" => self.tags.agent_id = parsed().map(AgentId::from_uuid),
+            "conversation_id" => {
+                self.tags.conversation_id = parsed().map(ConversationId::from_uuid)
+            }
+            name => {
+                self.fields.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
+
+impl<S> Layer<S> for LogCapture
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(visitor.tags);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            let tags = extensions.get::<LogTags>().copied().unwrap_or_default();
+            extensions.replace(visitor.tags.or(tags));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // Levels compare by verbosity: TRACE is the greatest
+        if *metadata.level() > self.min_level {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let mut tags = visitor.tags;
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                if let Some(outer) = span.extensions().get::<LogTags>() {
+                    tags = tags.or(*outer);
+                }
+            }
+        }
+        let Some(agent_id) = tags.agent_id else {
+            return;
+        };
+        self.push(
+            agent_id,
+            LogRecord {
+                at: Utc::now(),
+                level: metadata.level().to_string(),
+                target: metadata.target().to_string(),
+                message: visitor.message,
+                conversation_id: tags.conversation_id,
+                fields: visitor.fields,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_captures_logs_inside_agent_spans() {
+        let capture = LogCapture::new().with_capacity(2);
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        let (agent_id, conversation_id) = (AgentId::new(), ConversationId::new());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("not about any agent");
+            let span = tracing::info_span!(
+                "command",
+                agent_id = %agent_id,
+                conversation_id = %conversation_id
+            );
+            let _entered = span.enter();
+            tracing::debug!("below the capture level");
+            tracing::info!(tool = "search", "calling tool");
+            tracing::warn!("provider slow");
+            tracing::error!(attempt = 3, "provider timed out");
+        });
+
+        let batches = capture.drain();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.agent_id, agent_id);
+        assert_eq!(batch.dropped, 1);
+        assert_eq!(batch.records[0].message, "provider slow");
+        assert_eq!(batch.records[1].level, "ERROR");
+        assert_eq!(batch.records[1].fields["attempt"], "3");
+        assert_eq!(batch.records[1].conversation_id, Some(conversation_id));
+        assert!(capture.drain().is_empty());
+    }
+}
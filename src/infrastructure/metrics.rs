@@ -0,0 +1,377 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Metrics registry
+//!
+//! Counters, gauges and histograms kept in process, for host applications
+//! that embed this crate and want agent metrics in their own dashboards
+//! rather than scraped by Prometheus:
+//!
+//! ```text
+//! AgentEvent ──> observe_event() ──┐
+//! increment() / set_gauge() ───────┤
+//! observe() ───────────────────────┤
+//!                                  v
+//!                          MetricsRegistry ──> snapshot() ──> MetricsSnapshot (serde)
+//! ```
+//!
+//! Metric names are dotted, e.g. `events.message_sent` or
+//! `responses.duration_ms`. Histogram buckets are cumulative: each counts
+//! the observations at or below its bound, and observations above the last
+//! bound only show in the histogram's `count`.
+
+use super::AggregateCacheStats;
+use crate::events::AgentEvent;
+use crate::value_objects::clock_now;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+/// Bucket bounds for histograms registered without their own, suited to milliseconds
+pub const DEFAULT_HISTOGRAM_BUCKETS: [f64; 12] = [
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0,
+];
+
+/// Bucket bounds of the `responses.total_tokens` histogram
+pub const TOKEN_HISTOGRAM_BUCKETS: [f64; 10] = [
+    64.0, 128.0, 256.0, 512.0, 1024.0, 2048.0, 4096.0, 8192.0, 16384.0, 32768.0,
+];
+
+/// One cumulative histogram bucket
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    /// Upper bound, inclusive
+    pub le: f64,
+
+    /// Observations at or below `le`
+    pub count: u64,
+}
+
+/// A histogram at the moment of a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistogramSnapshot {
+    /// Observations made
+    pub count: u64,
+
+    /// Sum of the observations
+    pub sum: f64,
+
+    /// Smallest observation, if any were made
+    pub min: Option<f64>,
+
+    /// Largest observation, if any were made
+    pub max: Option<f64>,
+
+    /// Cumulative buckets, by ascending bound
+    pub buckets: Vec<HistogramBucket>,
+}
+
+impl HistogramSnapshot {
+    /// Mean observation, if any were made
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile (0.0 to 1.0)
+    ///
+    /// Past the last bucket the largest observation is returned instead.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * self.count as f64).ceil().max(1.0) as u64;
+        self.buckets
+            .iter()
+            .find(|bucket| bucket.count >= rank)
+            .map(|bucket| bucket.le)
+            .or(self.max)
+    }
+}
+
+/// Every metric at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+
+    /// Monotonic counts, by name
+    pub counters: BTreeMap<String, u64>,
+
+    /// Current values, by name
+    pub gauges: BTreeMap<String, f64>,
+
+    /// Distributions, by name
+    pub histograms: BTreeMap<String, HistogramSnapshot>,
+}
+
+impl MetricsSnapshot {
+    /// A counter's value; counters never incremented read 0
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+
+    /// A gauge's value, if it was ever set
+    pub fn gauge(&self, name: &str) -> Option<f64> {
+        self.gauges.get(name).copied()
+    }
+
+    /// A histogram, if anything was observed into it
+    pub fn histogram(&self, name: &str) -> Option<&HistogramSnapshot> {
+        self.histograms.get(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: Vec<f64>,
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Histogram {
+    fn new(bounds: Vec<f64>) -> Self {
+        Self {
+            counts: vec![0; bounds.len()],
+            bounds,
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Record `value`; NaN and infinities are dropped, as they would poison `sum`
+    fn observe(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        if let Some(i) = self.bounds.iter().position(|le| value <= *le) {
+            self.counts[i] += 1;
+        }
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn snapshot(&self) -> HistogramSnapshot {
+        let mut seen = 0;
+        let buckets = self
+            .bounds
+            .iter()
+            .zip(&self.counts)
+            .map(|(le, count)| {
+                seen += count;
+                HistogramBucket {
+                    le: *le,
+                    count: seen,
+                }
+            })
+            .collect();
+        let observed = self.count > 0;
+        HistogramSnapshot {
+            count: self.count,
+            sum: self.sum,
+            min: observed.then_some(self.min),
+            max: observed.then_some(self.max),
+            buckets,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    counters: HashMap<String, u64>,
+    gauges: HashMap<String, f64>,
+    histograms: HashMap<String, Histogram>,
+}
+
+/// In-process metrics, readable as a serializable snapshot
+#[derive(Debug)]
+pub struct MetricsRegistry {
+    buckets: HashMap<String, Vec<f64>>,
+    metrics: RwLock<Metrics>,
+}
+
+impl MetricsRegistry {
+    /// Empty registry, with token buckets for `responses.total_tokens`
+    pub fn new() -> Self {
+        Self {
+            buckets: HashMap::new(),
+            metrics: RwLock::new(Metrics::default()),
+        }
+        .with_buckets("responses.total_tokens", TOKEN_HISTOGRAM_BUCKETS.to_vec())
+    }
+
+    /// Builder: bucket bounds for the histogram `name`, instead of the defaults
+    pub fn with_buckets(mut self, name: impl Into<String>, mut bounds: Vec<f64>) -> Self {
+        bounds.retain(|le| le.is_finite());
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        self.buckets.insert(name.into(), bounds);
+        self
+    }
+
+    /// Add `by` to the counter `name`
+    pub fn increment(&self, name: &str, by: u64) {
+        let mut metrics = self.metrics.write().unwrap_or_else(|e| e.into_inner());
+        match metrics.counters.get_mut(name) {
+            Some(counter) => *counter = counter.saturating_add(by),
+            None => {
+                metrics.counters.insert(name.to_string(), by);
+            }
+        }
+    }
+
+    /// Set the gauge `name` to `value`
+    pub fn set_gauge(&self, name: &str, value: f64) {
+        let mut metrics = self.metrics.write().unwrap_or_else(|e| e.into_inner());
+        metrics.gauges.insert(name.to_string(), value);
+    }
+
+    /// Record `value` in the histogram `name`
+    pub fn observe(&self, name: &str, value: f64) {
+        let mut metrics = self.metrics.write().unwrap_or_else(|e| e.into_inner());
+        let bounds = || {
+            self.buckets
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_HISTOGRAM_BUCKETS.to_vec())
+        };
+        metrics
+            .histograms
+            .entry(name.to_string())
+            .or_insert_with(|| Histogram::new(bounds()))
+            .observe(value);
+    }
+
+    /// Record what an agent event says about the agents
+    ///
+    /// Counts every event as `events.{event type}`. Completed responses are
+    /// also observed into `responses.duration_ms` and `responses.total_tokens`
+    /// and counted into `tokens.prompt` and `tokens.completion`.
+    pub fn observe_event(&self, event: &AgentEvent) {
+        self.increment(&format!("events.{}", event.event_type_name()), 1);
+        if let AgentEvent::ResponseCompleted(e) = event {
+            let usage = &e.token_usage;
+            self.observe("responses.duration_ms", e.duration_ms as f64);
+            self.observe("responses.total_tokens", f64::from(usage.total_tokens));
+            self.increment("tokens.prompt", u64::from(usage.prompt_tokens));
+            self.increment("tokens.completion", u64::from(usage.completion_tokens));
+        }
+    }
+
+    /// Publish the aggregate cache counters as `aggregate_cache.*` counters
+    ///
+    /// The cache counts from its creation, so each counter is raised to the
+    /// cache's total rather than incremented by it.
+    pub fn record_cache_stats(&self, stats: AggregateCacheStats) {
+        let mut metrics = self.metrics.write().unwrap_or_else(|e| e.into_inner());
+        for (name, total) in [
+            ("aggregate_cache.hits", stats.hits),
+            ("aggregate_cache.catch_ups", stats.catch_ups),
+            ("aggregate_cache.misses", stats.misses),
+            ("aggregate_cache.evictions", stats.evictions),
+        ] {
+            let counter = metrics.counters.entry(name.to_string()).or_insert(0);
+            *counter = (*counter).max(total);
+        }
+    }
+
+    /// Every metric as it stands now
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let metrics = self.metrics.read().unwrap_or_else(|e| e.into_inner());
+        MetricsSnapshot {
+            taken_at: clock_now(),
+            counters: metrics.counters.clone().into_iter().collect(),
+            gauges: metrics.gauges.clone().into_iter().collect(),
+            histograms: metrics
+                .histograms
+                .iter()
+                .map(|(name, histogram)| (name.clone(), histogram.snapshot()))
+                .collect(),
+        }
+    }
+
+    /// Forget every value, keeping the bucket configuration
+    pub fn reset(&self) {
+        *self.metrics.write().unwrap_or_else(|e| e.into_inner()) = Metrics::default();
+    }
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, ResponseCompletedEvent};
+    use crate::value_objects::{AgentId, FinishReason, MessageId, TokenUsage};
+
+    #[test]
+    fn test_snapshot_counts_events_and_buckets_latencies() {
+        let registry = MetricsRegistry::new();
+        let agent_id = AgentId::new();
+        registry.observe_event(&AgentEvent::AgentActivated(AgentActivatedEvent::new(
+            agent_id,
+        )));
+        for duration_ms in [40, 400, 60_000] {
+            let completed = ResponseCompletedEvent::new(
+                agent_id,
+                MessageId::new(),
+                3,
+                TokenUsage::new(100, 50),
+                FinishReason::Stop,
+                duration_ms,
+            );
+            registry.observe_event(&AgentEvent::ResponseCompleted(completed));
+        }
+        registry.set_gauge("streams.in_flight", 2.0);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.counter("events.activated"), 1);
+        assert_eq!(snapshot.counter("events.response_completed"), 3);
+        assert_eq!(snapshot.counter("tokens.prompt"), 300);
+        assert_eq!(snapshot.gauge("streams.in_flight"), Some(2.0));
+
+        let latency = snapshot.histogram("responses.duration_ms").unwrap();
+        assert_eq!(latency.count, 3);
+        assert_eq!(latency.quantile(0.5), Some(500.0));
+        assert_eq!(latency.quantile(1.0), Some(60_000.0));
+        assert_eq!(latency.buckets.last().unwrap().count, 2);
+        let tokens = snapshot.histogram("responses.total_tokens").unwrap();
+        assert_eq!(tokens.buckets[0].le, 64.0);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: MetricsSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+    }
+
+    #[test]
+    fn test_cache_stats_are_counters_and_non_finite_observations_are_dropped() {
+        let registry = MetricsRegistry::new();
+        let stats = AggregateCacheStats {
+            hits: 7,
+            catch_ups: 1,
+            misses: 2,
+            evictions: 0,
+        };
+        registry.record_cache_stats(stats);
+        registry.record_cache_stats(stats);
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.counter("aggregate_cache.hits"), 7);
+        assert_eq!(snapshot.gauge("aggregate_cache.hits"), None);
+
+        registry.observe("responses.duration_ms", 120.0);
+        registry.observe("responses.duration_ms", f64::NAN);
+        registry.observe("responses.duration_ms", f64::INFINITY);
+        let latency = registry.snapshot().histograms["responses.duration_ms"].clone();
+        assert_eq!((latency.count, latency.sum), (1, 120.0));
+    }
+}
@@ -5,26 +5,112 @@
 //! ## Components
 //!
 //! - `EventStore` - Trait for event persistence
-//! - `SnapshotStore` - Trait for agent snapshots
+//! - `SnapshotStore` - Trait for agent snapshots, with a codec-encoded NATS KV implementation
+//! - `SnapshotCodec` - Self-describing snapshot bytes, optionally zstd and per-tenant AES-GCM
+//! - `ArchiveStore` - Trait for archived conversations
+//! - `BlobStore` - Content-addressed storage for message content too large to send inline
+//! - `ArtifactStore` - Blobs, embeddings and workspace files the garbage collector may reclaim
+//! - `AgentClient` - Typed command client that uploads oversized message content first
+//! - `ComparisonStore` - Trait for model comparison reports, with a NATS KV implementation
+//! - `ConversationRepository` - Event-sourced conversations, stored per conversation subject
+//! - `DedupeStore` - Per-consumer record of handled events, making redelivery harmless
+//! - `RetryStore` - Durable pending retries and quarantined messages awaiting review
+//! - `NatsConnectionBuilder` - Credentials, TLS, reconnect policy and health events for NATS clients
 //! - `AgentRepository` - High-level agent loading/saving
+//! - `AggregateCache` - Bounded LRU of rehydrated agents used by `AgentRepository`
 //! - `NatsEventStore` - NATS JetStream event store
 //! - `NatsEventPublisher` - NATS event publisher
 //! - `AgentSubjectFactory` - Type-safe NATS subjects using cim-domain Subject algebra
+//! - `SubjectParser` - Parses received subjects back into typed `ParsedAgentSubject` parts
+//! - `SubjectMigrator` - Legacy-to-unified subject migration: republish, alias, dual subscribe
+//! - `NatsPermissions` - Least-privilege publish/subscribe permissions derived from agent subjects
 //! - `AgentSubjects` - Legacy subject patterns (deprecated, use AgentSubjectFactory)
+//! - `StreamProvisioner` - Creates and checks the events, commands and chunks streams
+//! - `NatsStreamResumer` - Replays stored response chunks for reconnecting clients
+//! - `decode_envelope` - Fast envelope decoding for replay loops
+//! - `SubscriptionBuilder` - Compiles event filter expressions into JetStream consumers
+//! - `RegionConfig` / `ReplicationFilter` - Origin-region stamping and mirror echo suppression
+//! - `PrincipalDirectory` - Registered command-signing keys and the principal each acts as
+//! - `EventSigner` / `EventVerifier` - Detached event signatures checked against a `KeyRegistry`
+//! - `MetricsRegistry` - Counters, gauges and histograms readable as a serializable snapshot
+//! - `LogCapture` - Tracing layer that buffers logs per agent for forwarding to `logs.agent.{id}`
+//! - `AgentHost` - Startup self-test of NATS, streams, providers and models before serving
+//! - `StoreAndForwardEventStore` - Queues appends on leaf nodes while the hub is unreachable
+//! - `WebhookIngress` - HMAC-verified deliveries from external agents, mapped onto conversations
 
 use crate::aggregate::Agent;
 use crate::events::AgentEvent;
 use crate::value_objects::AgentId;
 
+mod agent_client;
+mod aggregate_cache;
+mod archive_store;
+mod artifact_store;
+mod blob_store;
+mod comparison_store;
+mod connection;
+mod conversation_repository;
+mod dedupe_store;
+mod event_decoder;
+mod event_filter;
 mod event_store;
+mod log_capture;
+mod metrics;
 mod model_configuration_repository;
 mod nats_integration;
 mod nats_model_configuration;
+mod nats_permissions;
+mod principal_auth;
+mod replication;
 mod repository;
+mod retry_store;
+mod self_test;
+mod signing;
+mod snapshot_codec;
 mod snapshot_store;
+mod store_and_forward;
+mod stream_provisioning;
+mod stream_resume;
 mod subject_factory;
+mod subject_migration;
+mod subject_parser;
+mod webhook_ingress;
 
+pub use agent_client::{
+    command_dry_run, command_envelope, offload_content, AgentClient, AgentClientError,
+    AgentClientResult, DRY_RUN_HEADER, TRACEPARENT_HEADER, TRACESTATE_HEADER,
+};
+pub use aggregate_cache::{AggregateCache, AggregateCacheStats};
+pub use archive_store::{ArchiveStore, ArchivedConversation, BlobArchiveStore, InMemoryArchiveStore};
+pub use artifact_store::{ArtifactKind, ArtifactStore, StoredArtifact};
+pub use blob_store::{BlobStore, InMemoryBlobStore, NatsBlobStore, DEFAULT_BLOB_CHUNK_BYTES};
+pub use comparison_store::{
+    ComparisonReport, ComparisonStore, ComparisonSummary, InMemoryComparisonStore,
+    NatsComparisonStore, PromptComparison, ResponseSample,
+};
+pub use connection::{
+    ConnectionEvent, NatsConnectionBuilder, NatsConnectionError, NatsConnectionResult,
+    NatsCredentials, ReconnectPolicy, TlsSettings, DEFAULT_NATS_URL,
+};
+pub use conversation_repository::{
+    ConversationEventEnvelope, ConversationEventStore, ConversationRepository,
+    InMemoryConversationEventStore, NatsConversationEventStore,
+};
+pub use dedupe_store::{
+    command_dedupe_key, dedupe_key, DedupeStore, InMemoryDedupeStore, NatsDedupeStore,
+    DEFAULT_DEDUPE_TTL,
+};
+pub use event_decoder::{decode_envelope, decode_envelope_if, is_state_changing, peek_event_type};
+pub use event_filter::{
+    AgentLabelIndex, EventFilter, EventFilterError, EventFilterResult, EventKind,
+    SubscriptionBuilder, CLUSTER_LABEL, MAX_FILTER_SUBJECTS,
+};
 pub use event_store::{EventEnvelope, EventStore, InMemoryEventStore};
+pub use log_capture::{LogBatch, LogCapture, LogRecord, DEFAULT_LOG_BUFFER};
+pub use metrics::{
+    HistogramBucket, HistogramSnapshot, MetricsRegistry, MetricsSnapshot,
+    DEFAULT_HISTOGRAM_BUCKETS, TOKEN_HISTOGRAM_BUCKETS,
+};
 pub use model_configuration_repository::{
     ConfigurationEventEnvelope, ConfigurationSnapshot, InMemoryConfigurationEventStore,
     InMemoryConfigurationSnapshotStore, ModelConfigurationEventStore,
@@ -38,9 +124,53 @@ pub use nats_model_configuration::{
     NatsModelConfigurationEventPublisher, NatsModelConfigurationEventStore,
     NatsModelConfigurationSnapshotStore,
 };
+pub use nats_permissions::{MessagingCapability, NatsPermissions, SubjectPermissions};
+pub use principal_auth::{PrincipalDirectory, RegisteredPrincipal};
+pub use replication::{
+    event_origin, EventOrigin, RegionConfig, RegionRole, ReplicationFilter,
+    DEFAULT_DEDUPE_WINDOW, ORIGIN_REGION_HEADER,
+};
 pub use repository::AgentRepository;
-pub use snapshot_store::{InMemorySnapshotStore, Snapshot, SnapshotStore};
+pub use retry_store::{
+    InMemoryRetryStore, NatsRetryStore, PendingRetry, QuarantinedMessage, RetryPriority, RetryStore,
+};
+pub use self_test::{
+    AgentHost, CheckResult, CheckSeverity, CheckStatus, EmbeddingCheck, ModelAvailableCheck,
+    NatsConnectivityCheck, NotReady, ProviderCheck, ReadinessReport, SelfTestCheck,
+    StreamExistsCheck, VectorStoreCheck, DEFAULT_SELF_TEST_TIMEOUT,
+};
+pub use signing::{
+    EventSigner, EventVerifier, InMemoryKeyRegistry, KeyRegistry, SignatureError,
+    SignatureResult, KEY_ID_HEADER, SIGNATURE_HEADER,
+};
+pub use snapshot_codec::{
+    InMemorySnapshotKeyring, SnapshotCodec, SnapshotEncryptionKey, SnapshotHeader,
+    SnapshotKeyring, DEFAULT_SNAPSHOT_COMPRESSION_LEVEL, SNAPSHOT_FORMAT_VERSION, SNAPSHOT_MAGIC,
+};
+pub use snapshot_store::{InMemorySnapshotStore, NatsSnapshotStore, Snapshot, SnapshotStore};
+pub use store_and_forward::{
+    ConflictPolicy, ConflictResolver, InMemoryOutboxStore, NatsOutboxStore, OutboxStore,
+    PendingBatch, Resolution, StoreAndForwardEventStore, SyncConflict, SyncReport,
+};
+pub use stream_provisioning::{
+    stream_drift, ConfigDrift, ProvisionError, ProvisionReport, ProvisionResult,
+    ProvisioningConfig, ResourceStatus, StreamPlan, StreamProvisioner, StreamRole,
+};
+pub use stream_resume::{NatsStreamResumer, ResumeCursor, ResumeStep, ResumedChunkStream};
 pub use subject_factory::{AgentSubjectFactory, SubjectFactoryError, SubjectFactoryResult};
+pub use subject_migration::{
+    CompatibilityMode, MigratedPattern, SubjectMigrationError, SubjectMigrationResult,
+    SubjectMigrator, MIGRATED_FROM_HEADER,
+};
+pub use subject_parser::{
+    ConversationSubjectKind, MessageEventKind, ParsedAgentSubject, SubjectParseError,
+    SubjectParseResult, SubjectParser,
+};
+pub use webhook_ingress::{
+    sign_webhook, ExternalAgentRegistration, ExternalAgentSecret, InMemoryWebhookSecrets,
+    WebhookDelivery, WebhookError, WebhookIngress, WebhookPayload, WebhookSecrets,
+    DEFAULT_WEBHOOK_TOLERANCE, WEBHOOK_SIGNATURE_HEADER,
+};
 
 /// Domain result type
 pub type DomainResult<T> = Result<T, DomainError>;
@@ -60,6 +190,24 @@ pub enum DomainError {
     #[error("Snapshot store error: {0}")]
     SnapshotStoreError(String),
 
+    #[error("Archive store error: {0}")]
+    ArchiveStoreError(String),
+
+    #[error("Comparison store error: {0}")]
+    ComparisonStoreError(String),
+
+    #[error("Dedupe store error: {0}")]
+    DedupeStoreError(String),
+
+    #[error("Retry store error: {0}")]
+    RetryStoreError(String),
+
+    #[error("Blob store error: {0}")]
+    BlobStoreError(String),
+
+    #[error("Resource release error: {0}")]
+    ResourceReleaseError(String),
+
     #[error("Serialization error: {0}")]
     SerializationError(String),
 
@@ -69,3 +217,28 @@ pub enum DomainError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 }
+
+impl DomainError {
+    /// The taxonomy category of this error
+    ///
+    /// Store failures are taken as transient; a concurrency conflict can
+    /// succeed once the caller reloads the aggregate.
+    pub fn category(&self) -> crate::ports::ErrorCategory {
+        use crate::ports::ErrorCategory;
+        match self {
+            DomainError::ConcurrencyConflict { .. }
+            | DomainError::EventStoreError(_)
+            | DomainError::SnapshotStoreError(_)
+            | DomainError::ArchiveStoreError(_)
+            | DomainError::ComparisonStoreError(_)
+            | DomainError::DedupeStoreError(_)
+            | DomainError::RetryStoreError(_)
+            | DomainError::BlobStoreError(_)
+            | DomainError::ResourceReleaseError(_) => ErrorCategory::Transient,
+            DomainError::AgentNotFound(_)
+            | DomainError::InvalidStateTransition(_)
+            | DomainError::ValidationError(_) => ErrorCategory::InvalidRequest,
+            DomainError::SerializationError(_) => ErrorCategory::ProviderBug,
+        }
+    }
+}
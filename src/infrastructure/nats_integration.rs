@@ -5,13 +5,16 @@
 //! Provides NATS subjects, event store, and command handling for the agent domain.
 
 use super::{
-    AgentEvent, AgentId, AgentSubjectFactory, DomainError, DomainResult, EventEnvelope, EventStore,
+    decode_envelope, AgentEvent, AgentId, AgentSubjectFactory, DomainError, DomainResult,
+    EventEnvelope, EventSigner, EventStore, RegionConfig, SubjectParser,
 };
 use crate::commands::AgentCommand;
-use crate::value_objects::MessageId;
+use crate::value_objects::{clock_now, next_id, MessageId};
 use async_nats::jetstream::{self, stream::Stream};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 /// NATS subject patterns for agent domain v0.9
@@ -155,9 +158,10 @@ impl AgentSubjects {
 /// Uses the `AgentSubjectFactory` for type-safe subject generation.
 pub struct NatsEventStore {
     jetstream: jetstream::Context,
-    #[allow(dead_code)] // Will be used for stream queries in full implementation
     stream_name: String,
     subject_factory: AgentSubjectFactory,
+    region: Option<RegionConfig>,
+    signer: Option<EventSigner>,
 }
 
 impl NatsEventStore {
@@ -172,6 +176,8 @@ impl NatsEventStore {
             jetstream,
             stream_name,
             subject_factory: AgentSubjectFactory::default(),
+            region: None,
+            signer: None,
         }
     }
 
@@ -191,14 +197,126 @@ impl NatsEventStore {
             jetstream,
             stream_name,
             subject_factory,
+            region: None,
+            signer: None,
         }
     }
 
+    /// Builder: stamp events with this region and enforce its write role
+    pub fn with_region(mut self, region: RegionConfig) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Builder: sign every published event
+    pub fn with_signer(mut self, signer: EventSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
     /// Get a reference to the subject factory
     pub fn subject_factory(&self) -> &AgentSubjectFactory {
         &self.subject_factory
     }
 
+    /// Purge an agent's events recorded before `cutoff`
+    ///
+    /// Events are walked in stream order up to the first one at or after the
+    /// cutoff; one that cannot be decoded is kept, with everything after it.
+    /// Returns how many messages were purged.
+    pub async fn purge_before(
+        &self,
+        agent_id: AgentId,
+        cutoff: DateTime<Utc>,
+    ) -> DomainResult<u64> {
+        let store_error = |e: String| DomainError::EventStoreError(e);
+        let filter = self
+            .subject_factory
+            .events_for_agent_pattern(agent_id)
+            .map_err(|e| store_error(e.to_string()))?
+            .to_string();
+        let stream = self
+            .jetstream
+            .get_stream(&self.stream_name)
+            .await
+            .map_err(|e| store_error(e.to_string()))?;
+        let mut consumer = stream
+            .create_consumer(jetstream::consumer::pull::OrderedConfig {
+                filter_subject: filter.clone(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| store_error(e.to_string()))?;
+        let pending = consumer
+            .info()
+            .await
+            .map_err(|e| store_error(e.to_string()))?
+            .num_pending;
+        let mut messages = consumer
+            .messages()
+            .await
+            .map_err(|e| store_error(e.to_string()))?
+            .take(pending as usize);
+
+        let (mut expired, mut keep_from) = (0, None);
+        while let Some(message) = messages.next().await {
+            let message = message.map_err(|e| store_error(e.to_string()))?;
+            let sequence = message
+                .info()
+                .map_err(|e| store_error(e.to_string()))?
+                .stream_sequence;
+            match decode_envelope(&message.payload) {
+                Ok(envelope) if envelope.timestamp < cutoff => expired += 1,
+                _ => {
+                    keep_from = Some(sequence);
+                    break;
+                }
+            }
+        }
+        if expired == 0 {
+            return Ok(0);
+        }
+
+        let purge = stream.purge().filter(filter);
+        let purged = match keep_from {
+            Some(sequence) => purge.sequence(sequence).await,
+            None => purge.await,
+        };
+        Ok(purged.map_err(|e| store_error(e.to_string()))?.purged)
+    }
+
+    /// Agents with events in the stream
+    ///
+    /// Read from event subjects, so it includes agents the read model no
+    /// longer (or never) listed, such as decommissioned ones.
+    pub async fn stored_agents(&self) -> DomainResult<HashSet<AgentId>> {
+        let store_error = |e: String| DomainError::EventStoreError(e);
+        let filter = self
+            .subject_factory
+            .all_events_pattern()
+            .map_err(|e| store_error(e.to_string()))?
+            .to_string();
+        let stream = self
+            .jetstream
+            .get_stream(&self.stream_name)
+            .await
+            .map_err(|e| store_error(e.to_string()))?;
+        let mut subjects = stream
+            .info_with_subjects(filter)
+            .await
+            .map_err(|e| store_error(e.to_string()))?;
+
+        let parser = SubjectParser::new(&self.subject_factory);
+        let mut agents = HashSet::new();
+        while let Some(subject) = subjects.next().await {
+            let (subject, _) = subject.map_err(|e| store_error(e.to_string()))?;
+            if let Some(agent_id) = parser.parse(&subject).ok().and_then(|p| p.agent_id()) {
+                agents.insert(agent_id);
+            }
+        }
+        Ok(agents)
+    }
+
     /// Create or get the JetStream stream for agent events
     ///
     /// # Arguments
@@ -209,13 +327,23 @@ impl NatsEventStore {
     /// # Returns
     ///
     /// The stream instance
+    ///
+    /// An existing stream with a max age (as created before per-agent
+    /// retention) has it cleared, so agents under legal hold are not aged out.
     pub async fn ensure_stream(
         jetstream: &jetstream::Context,
         stream_name: &str,
     ) -> Result<Stream, async_nats::Error> {
         // Try to get existing stream
         match jetstream.get_stream(stream_name).await {
-            Ok(stream) => Ok(stream),
+            Ok(stream) => match without_max_age(&stream.cached_info().config) {
+                Some(config) => {
+                    tracing::info!("Clearing max age of stream {}", stream_name);
+                    jetstream.update_stream(config).await?;
+                    Ok(jetstream.get_stream(stream_name).await?)
+                }
+                None => Ok(stream),
+            },
             Err(_) => {
                 // Create new stream
                 let stream = jetstream
@@ -225,7 +353,8 @@ impl NatsEventStore {
                             AgentSubjects::events().to_string(),
                             AgentSubjects::commands().to_string(),
                         ],
-                        max_age: std::time::Duration::from_secs(365 * 24 * 60 * 60), // 1 year
+                        // No max age: EventRetention ages events out per agent,
+                        // sparing agents under legal hold
                         storage: jetstream::stream::StorageType::File,
                         retention: jetstream::stream::RetentionPolicy::Limits,
                         ..Default::default()
@@ -243,10 +372,17 @@ impl NatsEventStore {
         let payload = serde_json::to_vec(envelope)
             .map_err(|e| DomainError::SerializationError(e.to_string()))?;
 
-        self.jetstream
-            .publish(subject, payload.into())
-            .await
+        let headers = publish_headers(self.region.as_ref(), self.signer.as_ref(), &payload)
             .map_err(|e| DomainError::EventStoreError(e.to_string()))?;
+        let published = match headers {
+            Some(headers) => {
+                self.jetstream
+                    .publish_with_headers(subject, headers, payload.into())
+                    .await
+            }
+            None => self.jetstream.publish(subject, payload.into()).await,
+        };
+        published.map_err(|e| DomainError::EventStoreError(e.to_string()))?;
 
         Ok(())
     }
@@ -275,6 +411,68 @@ impl NatsEventStore {
             AgentEvent::ResponseFailed(e) => {
                 factory.response_failed_event(agent_id, e.message_id)
             }
+            AgentEvent::OutputLimitEnforced(e) => {
+                factory.output_limit_enforced_event(agent_id, e.message_id)
+            }
+            AgentEvent::ResponseCancelled(e) => {
+                factory.response_cancelled_event(agent_id, e.message_id)
+            }
+            AgentEvent::SloViolated(_) => factory.slo_violated_event(agent_id),
+            AgentEvent::DailyDigestReady(_) => factory.daily_digest_ready_event(agent_id),
+            AgentEvent::ConversationArchived(_) => {
+                factory.conversation_archived_event(agent_id)
+            }
+            AgentEvent::ConfigurationDriftDetected(_) => {
+                factory.configuration_drift_detected_event(agent_id)
+            }
+            AgentEvent::CanaryDecided(_) => factory.canary_decided_event(agent_id),
+            AgentEvent::EvaluationCompleted(_) => factory.evaluation_completed_event(agent_id),
+            AgentEvent::CapabilityNegotiated(_) => factory.capability_negotiated_event(agent_id),
+            AgentEvent::DeadlineExceeded(_) => factory.deadline_exceeded_event(agent_id),
+            AgentEvent::FeatureFlagsChanged(_) => factory.feature_flags_changed_event(agent_id),
+            AgentEvent::LongContextFallback(_) => factory.long_context_fallback_event(agent_id),
+            AgentEvent::ContextTruncated(_) => factory.context_truncated_event(agent_id),
+            AgentEvent::CandidateSelected(_) => factory.candidate_selected_event(agent_id),
+            AgentEvent::ResponseDrafted(_) => factory.response_drafted_event(agent_id),
+            AgentEvent::ReasoningTraceRecorded(_) => {
+                factory.reasoning_trace_recorded_event(agent_id)
+            }
+            AgentEvent::LocalePreferencesChanged(_) => {
+                factory.locale_preferences_changed_event(agent_id)
+            }
+            AgentEvent::DeprecatedToolInUse(_) => factory.deprecated_tool_in_use_event(agent_id),
+            AgentEvent::ToolVersionMigrated(_) => factory.tool_version_migrated_event(agent_id),
+            AgentEvent::ToolInvoked(_) => factory.tool_invoked_event(agent_id),
+            AgentEvent::ToolSucceeded(_) => factory.tool_succeeded_event(agent_id),
+            AgentEvent::ToolFailed(_) => factory.tool_failed_event(agent_id),
+            AgentEvent::LegalHoldRefused(_) => factory.legal_hold_refused_event(agent_id),
+            AgentEvent::LegalHoldPlaced(_) => factory.legal_hold_placed_event(agent_id),
+            AgentEvent::LegalHoldReleased(_) => factory.legal_hold_released_event(agent_id),
+            AgentEvent::PermissionEscalationRequested(_) => {
+                factory.permission_escalation_requested_event(agent_id)
+            }
+            AgentEvent::PermissionEscalationApproved(_) => {
+                factory.permission_escalation_approved_event(agent_id)
+            }
+            AgentEvent::PermissionEscalationExpired(_) => {
+                factory.permission_escalation_expired_event(agent_id)
+            }
+            AgentEvent::AnomalyDetected(_) => factory.anomaly_detected_event(agent_id),
+            AgentEvent::BatchProgress(_) => factory.batch_progress_event(agent_id),
+            AgentEvent::BatchCompleted(_) => factory.batch_completed_event(agent_id),
+            AgentEvent::MessageRetryScheduled(_) => factory.message_retry_scheduled_event(agent_id),
+            AgentEvent::MessageQuarantined(_) => factory.message_quarantined_event(agent_id),
+            AgentEvent::QuarantinedMessageReleased(_) => {
+                factory.quarantined_message_released_event(agent_id)
+            }
+            AgentEvent::CapabilityVerificationFailed(_) => {
+                factory.capability_verification_failed_event(agent_id)
+            }
+            AgentEvent::ChangeRateExceeded(_) => factory.change_rate_exceeded_event(agent_id),
+            AgentEvent::OutputConstraintEnforced(_) => {
+                factory.output_constraint_enforced_event(agent_id)
+            }
+            AgentEvent::SpendThresholdCrossed(_) => factory.spend_threshold_crossed_event(agent_id),
         };
 
         subject
@@ -291,6 +489,10 @@ impl EventStore for NatsEventStore {
         events: Vec<AgentEvent>,
         expected_version: Option<u64>,
     ) -> DomainResult<()> {
+        if let Some(region) = &self.region {
+            region.ensure_writable()?;
+        }
+
         // Get current version
         let current_version = self.get_current_version(aggregate_id).await?;
 
@@ -311,10 +513,12 @@ impl EventStore for NatsEventStore {
                 aggregate_id,
                 sequence,
                 event,
-                timestamp: Utc::now(),
-                correlation_id: Uuid::now_v7(),
-                causation_id: Uuid::now_v7(),
-            };
+                timestamp: clock_now(),
+                correlation_id: next_id(),
+                causation_id: next_id(),
+                metadata: None,
+            }
+            .within_command();
 
             self.publish_event(&envelope).await?;
         }
@@ -346,12 +550,22 @@ impl EventStore for NatsEventStore {
     }
 }
 
+/// `config` without its max age, or `None` if it has none
+fn without_max_age(config: &jetstream::stream::Config) -> Option<jetstream::stream::Config> {
+    (!config.max_age.is_zero()).then(|| jetstream::stream::Config {
+        max_age: std::time::Duration::ZERO,
+        ..config.clone()
+    })
+}
+
 /// Event publisher for publishing agent events to NATS
 ///
 /// Uses the `AgentSubjectFactory` for type-safe subject generation.
 pub struct NatsEventPublisher {
     jetstream: jetstream::Context,
     subject_factory: AgentSubjectFactory,
+    region: Option<RegionConfig>,
+    signer: Option<EventSigner>,
 }
 
 impl NatsEventPublisher {
@@ -360,6 +574,8 @@ impl NatsEventPublisher {
         Self {
             jetstream,
             subject_factory: AgentSubjectFactory::default(),
+            region: None,
+            signer: None,
         }
     }
 
@@ -368,9 +584,23 @@ impl NatsEventPublisher {
         Self {
             jetstream,
             subject_factory,
+            region: None,
+            signer: None,
         }
     }
 
+    /// Builder: stamp events with this region and enforce its write role
+    pub fn with_region(mut self, region: RegionConfig) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Builder: sign every published event
+    pub fn with_signer(mut self, signer: EventSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
     /// Get a reference to the subject factory
     pub fn subject_factory(&self) -> &AgentSubjectFactory {
         &self.subject_factory
@@ -384,22 +614,51 @@ impl NatsEventPublisher {
         correlation_id: Uuid,
         causation_id: Uuid,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.publish_acked(agent_id, event, correlation_id, causation_id)
+            .await
+            .map(|_| ())
+    }
+
+    /// Publish an event and return the stream sequence JetStream acknowledged
+    ///
+    /// Projections version agents by stream sequence, so this is what a
+    /// consistency token must carry.
+    pub async fn publish_acked(
+        &self,
+        agent_id: AgentId,
+        event: AgentEvent,
+        correlation_id: Uuid,
+        causation_id: Uuid,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(region) = &self.region {
+            region.ensure_writable()?;
+        }
+
         let subject = self.subject_for_event(&event, agent_id)?;
 
         let envelope = EventEnvelope {
             aggregate_id: agent_id,
             sequence: 0, // Will be set by event store
             event,
-            timestamp: Utc::now(),
+            timestamp: clock_now(),
             correlation_id,
             causation_id,
-        };
+            metadata: None,
+        }
+        .within_command();
 
         let payload = serde_json::to_vec(&envelope)?;
 
-        self.jetstream.publish(subject, payload.into()).await?;
+        let ack = match publish_headers(self.region.as_ref(), self.signer.as_ref(), &payload)? {
+            Some(headers) => {
+                self.jetstream
+                    .publish_with_headers(subject, headers, payload.into())
+                    .await?
+            }
+            None => self.jetstream.publish(subject, payload.into()).await?,
+        };
 
-        Ok(())
+        Ok(ack.await?.sequence)
     }
 
     /// Get the NATS subject for an event using the Subject algebra
@@ -428,6 +687,68 @@ impl NatsEventPublisher {
             AgentEvent::ResponseFailed(e) => {
                 factory.response_failed_event(agent_id, e.message_id)
             }
+            AgentEvent::OutputLimitEnforced(e) => {
+                factory.output_limit_enforced_event(agent_id, e.message_id)
+            }
+            AgentEvent::ResponseCancelled(e) => {
+                factory.response_cancelled_event(agent_id, e.message_id)
+            }
+            AgentEvent::SloViolated(_) => factory.slo_violated_event(agent_id),
+            AgentEvent::DailyDigestReady(_) => factory.daily_digest_ready_event(agent_id),
+            AgentEvent::ConversationArchived(_) => {
+                factory.conversation_archived_event(agent_id)
+            }
+            AgentEvent::ConfigurationDriftDetected(_) => {
+                factory.configuration_drift_detected_event(agent_id)
+            }
+            AgentEvent::CanaryDecided(_) => factory.canary_decided_event(agent_id),
+            AgentEvent::EvaluationCompleted(_) => factory.evaluation_completed_event(agent_id),
+            AgentEvent::CapabilityNegotiated(_) => factory.capability_negotiated_event(agent_id),
+            AgentEvent::DeadlineExceeded(_) => factory.deadline_exceeded_event(agent_id),
+            AgentEvent::FeatureFlagsChanged(_) => factory.feature_flags_changed_event(agent_id),
+            AgentEvent::LongContextFallback(_) => factory.long_context_fallback_event(agent_id),
+            AgentEvent::ContextTruncated(_) => factory.context_truncated_event(agent_id),
+            AgentEvent::CandidateSelected(_) => factory.candidate_selected_event(agent_id),
+            AgentEvent::ResponseDrafted(_) => factory.response_drafted_event(agent_id),
+            AgentEvent::ReasoningTraceRecorded(_) => {
+                factory.reasoning_trace_recorded_event(agent_id)
+            }
+            AgentEvent::LocalePreferencesChanged(_) => {
+                factory.locale_preferences_changed_event(agent_id)
+            }
+            AgentEvent::DeprecatedToolInUse(_) => factory.deprecated_tool_in_use_event(agent_id),
+            AgentEvent::ToolVersionMigrated(_) => factory.tool_version_migrated_event(agent_id),
+            AgentEvent::ToolInvoked(_) => factory.tool_invoked_event(agent_id),
+            AgentEvent::ToolSucceeded(_) => factory.tool_succeeded_event(agent_id),
+            AgentEvent::ToolFailed(_) => factory.tool_failed_event(agent_id),
+            AgentEvent::LegalHoldRefused(_) => factory.legal_hold_refused_event(agent_id),
+            AgentEvent::LegalHoldPlaced(_) => factory.legal_hold_placed_event(agent_id),
+            AgentEvent::LegalHoldReleased(_) => factory.legal_hold_released_event(agent_id),
+            AgentEvent::PermissionEscalationRequested(_) => {
+                factory.permission_escalation_requested_event(agent_id)
+            }
+            AgentEvent::PermissionEscalationApproved(_) => {
+                factory.permission_escalation_approved_event(agent_id)
+            }
+            AgentEvent::PermissionEscalationExpired(_) => {
+                factory.permission_escalation_expired_event(agent_id)
+            }
+            AgentEvent::AnomalyDetected(_) => factory.anomaly_detected_event(agent_id),
+            AgentEvent::BatchProgress(_) => factory.batch_progress_event(agent_id),
+            AgentEvent::BatchCompleted(_) => factory.batch_completed_event(agent_id),
+            AgentEvent::MessageRetryScheduled(_) => factory.message_retry_scheduled_event(agent_id),
+            AgentEvent::MessageQuarantined(_) => factory.message_quarantined_event(agent_id),
+            AgentEvent::QuarantinedMessageReleased(_) => {
+                factory.quarantined_message_released_event(agent_id)
+            }
+            AgentEvent::CapabilityVerificationFailed(_) => {
+                factory.capability_verification_failed_event(agent_id)
+            }
+            AgentEvent::ChangeRateExceeded(_) => factory.change_rate_exceeded_event(agent_id),
+            AgentEvent::OutputConstraintEnforced(_) => {
+                factory.output_constraint_enforced_event(agent_id)
+            }
+            AgentEvent::SpendThresholdCrossed(_) => factory.spend_threshold_crossed_event(agent_id),
         };
 
         subject
@@ -436,6 +757,22 @@ impl NatsEventPublisher {
     }
 }
 
+/// Headers for a published event: origin region and signature, if configured
+fn publish_headers(
+    region: Option<&RegionConfig>,
+    signer: Option<&EventSigner>,
+    payload: &[u8],
+) -> Result<Option<async_nats::HeaderMap>, super::SignatureError> {
+    if region.is_none() && signer.is_none() {
+        return Ok(None);
+    }
+    let mut headers = region.map(RegionConfig::headers).unwrap_or_default();
+    if let Some(signer) = signer {
+        signer.sign_headers(&mut headers, payload)?;
+    }
+    Ok(Some(headers))
+}
+
 /// Command handler for processing agent commands via NATS
 ///
 /// Uses the `AgentSubjectFactory` for type-safe subject generation.
@@ -539,4 +876,19 @@ mod tests {
             format!("agent.events.{}.message.{}.completed", agent_id, message_id)
         );
     }
+
+    #[test]
+    fn test_baseline_stream_max_age_is_cleared() {
+        let baseline = jetstream::stream::Config {
+            name: "AGENT_EVENTS".to_string(),
+            subjects: vec![AgentSubjects::events().to_string()],
+            max_age: std::time::Duration::from_secs(365 * 24 * 60 * 60),
+            ..Default::default()
+        };
+
+        let cleared = without_max_age(&baseline).expect("max age to clear");
+        assert!(cleared.max_age.is_zero());
+        assert_eq!(cleared.subjects, baseline.subjects);
+        assert!(without_max_age(&cleared).is_none());
+    }
 }
@@ -10,11 +10,10 @@ use super::{
 };
 use crate::commands::ModelConfigurationCommand;
 use crate::events::ModelConfigurationEvent;
-use crate::infrastructure::ConfigurationSnapshot;
-use crate::value_objects::ModelConfigurationId;
+use crate::infrastructure::{ConfigurationSnapshot, SnapshotCodec};
+use crate::value_objects::{clock_now, ModelConfigurationId};
 use async_nats::jetstream::{self, kv::Store as KvStore, stream::Stream};
 use async_trait::async_trait;
-use chrono::Utc;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -224,7 +223,7 @@ impl ModelConfigurationEventStore for NatsModelConfigurationEventStore {
                 aggregate_id,
                 sequence,
                 event,
-                timestamp: Utc::now(),
+                timestamp: clock_now(),
                 correlation_id: Uuid::now_v7(),
                 causation_id: Uuid::now_v7(),
             };
@@ -339,8 +338,13 @@ impl SnapshotKey {
 }
 
 /// NATS KV snapshot store for ModelConfiguration
+///
+/// Snapshots are written through a [`SnapshotCodec`], plain by default;
+/// give it one with compression or encryption to protect stored
+/// configuration.
 pub struct NatsModelConfigurationSnapshotStore {
     kv: KvStore,
+    codec: SnapshotCodec,
 }
 
 impl NatsModelConfigurationSnapshotStore {
@@ -350,7 +354,16 @@ impl NatsModelConfigurationSnapshotStore {
     ///
     /// * `kv` - NATS KV store instance
     pub fn new(kv: KvStore) -> Self {
-        Self { kv }
+        Self {
+            kv,
+            codec: SnapshotCodec::new(),
+        }
+    }
+
+    /// Builder: encode snapshots with `codec`
+    pub fn with_codec(mut self, codec: SnapshotCodec) -> Self {
+        self.codec = codec;
+        self
     }
 
     /// Create or get the KV bucket for snapshots
@@ -390,8 +403,7 @@ impl NatsModelConfigurationSnapshotStore {
 impl ModelConfigurationSnapshotStore for NatsModelConfigurationSnapshotStore {
     async fn save_snapshot(&self, snapshot: ConfigurationSnapshot) -> DomainResult<()> {
         // Serialize snapshot
-        let payload = serde_json::to_vec(&snapshot)
-            .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+        let payload = self.codec.encode(&snapshot)?;
 
         // Save with version key
         let key = SnapshotKey {
@@ -423,8 +435,7 @@ impl ModelConfigurationSnapshotStore for NatsModelConfigurationSnapshotStore {
 
         match self.kv.get(&key).await {
             Ok(Some(entry)) => {
-                let snapshot: ConfigurationSnapshot = serde_json::from_slice(&entry)
-                    .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+                let snapshot: ConfigurationSnapshot = self.codec.decode(&entry)?;
                 Ok(Some(snapshot))
             }
             Ok(None) => Ok(None),
@@ -473,7 +484,7 @@ impl NatsModelConfigurationEventPublisher {
             aggregate_id: config_id,
             sequence: 0, // Will be set by event store
             event,
-            timestamp: Utc::now(),
+            timestamp: clock_now(),
             correlation_id,
             causation_id,
         };
@@ -0,0 +1,250 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Capability-scoped NATS permissions
+//!
+//! Derives the smallest publish/subscribe permission set an agent needs
+//! from its [`AgentReference`] and the messaging capabilities it uses. The
+//! subjects come from [`AgentSubjectFactory`], so permissions cannot drift
+//! from the subjects the agent actually talks on:
+//!
+//! ```text
+//! AgentReference { cluster, name, id } ─┐
+//! [MessagingCapability]                 ├─> NatsPermissions ──> server config block
+//! AgentSubjectFactory                   ┘        │
+//!                                                └────────────> user JWT `nats` claims
+//! ```
+//!
+//! Anything not allowed is denied; an agent with no capabilities may not
+//! publish or subscribe at all.
+
+use super::{AgentSubjectFactory, SubjectFactoryResult};
+use crate::value_objects::{AgentReference, PersonId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Subject prefix used for request-reply inboxes
+const INBOX_PATTERN: &str = "_INBOX.>";
+
+/// NATS micro discovery subjects (`$SRV.PING`, `$SRV.INFO`, `$SRV.STATS`)
+const SERVICE_DISCOVERY_PATTERN: &str = "$SRV.>";
+
+/// One kind of messaging an agent takes part in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessagingCapability {
+    /// Receive messages in its inbox and broadcasts; take part in
+    /// conversations, reply to other agents and await their replies
+    Converse,
+
+    /// Receive commands addressed to it or its capability cluster, on
+    /// legacy subjects and through the agent service
+    ReceiveCommands,
+
+    /// Publish its own events
+    PublishEvents,
+
+    /// Answer `describe` and `negotiate` queries about itself
+    ServeQueries,
+
+    /// Query the agent read model
+    Query,
+
+    /// Send notifications to its owner in the person domain
+    NotifyOwner(PersonId),
+}
+
+impl MessagingCapability {
+    /// What a deployed conversational agent needs
+    pub fn defaults() -> Vec<MessagingCapability> {
+        vec![
+            MessagingCapability::Converse,
+            MessagingCapability::ReceiveCommands,
+            MessagingCapability::PublishEvents,
+            MessagingCapability::ServeQueries,
+        ]
+    }
+}
+
+/// Allowed and denied subjects for one direction
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubjectPermissions {
+    /// Subjects (or wildcards) permitted
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub allow: BTreeSet<String>,
+
+    /// Subjects (or wildcards) refused even when allowed
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub deny: BTreeSet<String>,
+}
+
+impl SubjectPermissions {
+    fn allow(&mut self, subject: impl ToString) {
+        self.allow.insert(subject.to_string());
+    }
+}
+
+/// Publish/subscribe permissions for one agent's NATS user
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NatsPermissions {
+    /// Subjects the agent may publish to
+    pub publish: SubjectPermissions,
+
+    /// Subjects the agent may subscribe to
+    pub subscribe: SubjectPermissions,
+
+    /// Whether the agent may reply to requests it receives
+    pub allow_responses: bool,
+}
+
+impl NatsPermissions {
+    /// Least-privilege permissions for `agent` using `capabilities`
+    pub fn for_agent(
+        factory: &AgentSubjectFactory,
+        agent: &AgentReference,
+        capabilities: &[MessagingCapability],
+    ) -> SubjectFactoryResult<Self> {
+        let mut permissions = Self::default();
+        let (publish, subscribe) = (&mut permissions.publish, &mut permissions.subscribe);
+        let domain = factory.domain();
+
+        for capability in capabilities {
+            match capability {
+                MessagingCapability::Converse => {
+                    subscribe.allow(factory.agent_pattern(&agent.name)?);
+                    subscribe.allow(factory.broadcast_pattern()?);
+                    subscribe.allow(factory.all_conversations_pattern()?);
+                    publish.allow(factory.all_conversations_pattern()?);
+                    publish.allow(format!("{}.to.*.from.{}.>", domain, agent.name));
+                    subscribe.allow(INBOX_PATTERN);
+                }
+                MessagingCapability::ReceiveCommands => {
+                    subscribe.allow(factory.agent_commands_by_id_pattern(agent.id)?);
+                    subscribe.allow(factory.cluster_commands_pattern(&agent.capability)?);
+                    subscribe.allow(factory.legacy_agent_commands_pattern(agent.id)?);
+                    subscribe.allow(format!("{}.commands", factory.service_group()?));
+                    subscribe.allow(SERVICE_DISCOVERY_PATTERN);
+                    permissions.allow_responses = true;
+                }
+                MessagingCapability::PublishEvents => {
+                    publish.allow(factory.events_for_agent_pattern(agent.id)?);
+                    publish.allow(format!(
+                        "{}.{}.{}.{}.event.>",
+                        domain,
+                        agent.capability.as_str(),
+                        agent.name,
+                        agent.id
+                    ));
+                }
+                MessagingCapability::ServeQueries => {
+                    subscribe.allow(factory.describe_subject(agent.id)?);
+                    subscribe.allow(factory.negotiate_subject(agent.id)?);
+                    permissions.allow_responses = true;
+                }
+                MessagingCapability::Query => {
+                    publish.allow(factory.queries_pattern()?);
+                    subscribe.allow(INBOX_PATTERN);
+                }
+                MessagingCapability::NotifyOwner(owner) => {
+                    publish.allow(factory.person_notification_subject(*owner)?);
+                    subscribe.allow(INBOX_PATTERN);
+                }
+            }
+        }
+        Ok(permissions)
+    }
+
+    /// Authorization block for a `users` entry in the NATS server config
+    pub fn to_server_config(&self, user: &str) -> String {
+        let mut out = format!("{{\n  user: {:?}\n  permissions: {{\n", user);
+        for (direction, subjects) in [("publish", &self.publish), ("subscribe", &self.subscribe)] {
+            let _ = writeln!(out, "    {}: {{", direction);
+            for (rule, list) in [("allow", &subjects.allow), ("deny", &subjects.deny)] {
+                if !list.is_empty() {
+                    let quoted: Vec<String> = list.iter().map(|s| format!("{:?}", s)).collect();
+                    let _ = writeln!(out, "      {}: [{}]", rule, quoted.join(", "));
+                }
+            }
+            out.push_str("    }\n");
+        }
+        if self.allow_responses {
+            out.push_str("    allow_responses: true\n");
+        }
+        out.push_str("  }\n}\n");
+        out
+    }
+
+    /// The `nats` claim of a user JWT (pub, sub, resp), for signing with nsc
+    /// or an account signing key
+    pub fn to_jwt_claims(&self) -> serde_json::Value {
+        let mut claims = serde_json::json!({
+            "pub": self.publish,
+            "sub": self.subscribe,
+            "type": "user",
+            "version": 2,
+        });
+        if self.allow_responses {
+            claims["resp"] = serde_json::json!({ "max": 1 });
+        }
+        claims
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{AgentId, CapabilityCluster};
+
+    fn sage() -> AgentReference {
+        AgentReference::new(CapabilityCluster::Orchestration, "sage".to_string(), AgentId::new())
+    }
+
+    #[test]
+    fn test_permissions_follow_capabilities() {
+        let factory = AgentSubjectFactory::default();
+        let agent = sage();
+
+        let events_only =
+            NatsPermissions::for_agent(&factory, &agent, &[MessagingCapability::PublishEvents])
+                .unwrap();
+        assert!(events_only
+            .publish
+            .allow
+            .contains(&format!("agent.events.agent.{}.>", agent.id)));
+        assert!(events_only.subscribe.allow.is_empty());
+        assert!(!events_only.allow_responses);
+
+        let defaults =
+            NatsPermissions::for_agent(&factory, &agent, &MessagingCapability::defaults()).unwrap();
+        assert!(defaults.subscribe.allow.contains("agent.to.sage.>"));
+        assert!(defaults
+            .subscribe
+            .allow
+            .contains("agent.orchestration.*.*.command.>"));
+        for subject in [
+            INBOX_PATTERN.to_string(),
+            SERVICE_DISCOVERY_PATTERN.to_string(),
+            "agent.services.agent.commands".to_string(),
+            format!("agent.commands.agent.{}.>", agent.id),
+        ] {
+            assert!(defaults.subscribe.allow.contains(&subject), "{}", subject);
+        }
+        assert!(defaults.allow_responses);
+    }
+
+    #[test]
+    fn test_exports_server_config_and_jwt_claims() {
+        let factory = AgentSubjectFactory::default();
+        let capabilities = [MessagingCapability::Query];
+        let permissions = NatsPermissions::for_agent(&factory, &sage(), &capabilities).unwrap();
+
+        let config = permissions.to_server_config("sage");
+        assert!(config.contains("user: \"sage\""));
+        assert!(config.contains("allow: [\"agent.queries.agent.>\"]"));
+        assert!(config.contains("allow: [\"_INBOX.>\"]"));
+
+        let claims = permissions.to_jwt_claims();
+        assert_eq!(claims["pub"]["allow"][0], "agent.queries.agent.>");
+        assert!(claims.get("resp").is_none());
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Command authentication
+//!
+//! A command's principal is established from the key that signed it, never
+//! from what the command claims. Callers hold an nkey and sign each command
+//! payload; the service holds a directory of registered public keys and the
+//! class and name each one speaks for:
+//!
+//! ```text
+//! Cim-Key-Id: UABC...    ──> PrincipalDirectory ──> { class: operator, name: "ops-bot" }
+//! Cim-Signature: 9f2c... ──> verify(payload) ─────> AuthenticatedPrincipal
+//!
+//! no signature ──────────────> anonymous, PrincipalClass::LEAST_PRIVILEGED
+//! unknown key / bad signature ──> rejected
+//! ```
+//!
+//! The headers are the ones events are signed with, so a client signs
+//! commands with the same [`EventSigner`](super::EventSigner) type.
+
+use super::signing::from_hex;
+use super::{SignatureError, SignatureResult, KEY_ID_HEADER, SIGNATURE_HEADER};
+use crate::value_objects::{AuthenticatedPrincipal, PersonId, PrincipalClass};
+use nkeys::KeyPair;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A key the service accepts commands from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredPrincipal {
+    /// nkey public key (`U...`)
+    pub key_id: String,
+
+    /// Class whose permissions the key's commands get
+    pub class: PrincipalClass,
+
+    /// Name recorded as the actor of the key's commands
+    pub name: String,
+
+    /// The person the key belongs to, for owners approving escalations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person_id: Option<PersonId>,
+}
+
+impl RegisteredPrincipal {
+    /// Register `key_id` as `name`, acting as `class`
+    pub fn new(key_id: impl Into<String>, class: PrincipalClass, name: impl Into<String>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            class,
+            name: name.into(),
+            person_id: None,
+        }
+    }
+
+    /// Builder: the person the key belongs to
+    pub fn with_person(mut self, person_id: PersonId) -> Self {
+        self.person_id = Some(person_id);
+        self
+    }
+
+    fn principal(&self) -> AuthenticatedPrincipal {
+        let principal = AuthenticatedPrincipal::internal(self.class, self.name.clone());
+        match self.person_id {
+            Some(person_id) => principal.with_person(person_id),
+            None => principal,
+        }
+    }
+}
+
+/// Registered keys, and the principal each authenticates as
+#[derive(Debug, Clone, Default)]
+pub struct PrincipalDirectory {
+    keys: HashMap<String, RegisteredPrincipal>,
+}
+
+impl PrincipalDirectory {
+    /// A directory with no keys: every caller is anonymous
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: accept commands signed by `entry`'s key
+    pub fn with(mut self, entry: RegisteredPrincipal) -> Self {
+        self.keys.insert(entry.key_id.clone(), entry);
+        self
+    }
+
+    /// Number of registered keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check if no keys are registered
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Who sent `payload`, from its signature headers
+    ///
+    /// Unsigned commands are anonymous. A signature that names an unknown
+    /// key or does not match the payload is an error, not a downgrade, so
+    /// a caller with a broken key finds out.
+    pub fn authenticate(
+        &self,
+        headers: Option<&async_nats::HeaderMap>,
+        payload: &[u8],
+    ) -> SignatureResult<AuthenticatedPrincipal> {
+        let header = |name: &str| {
+            headers
+                .and_then(|h| h.get(name))
+                .map(|v| v.as_str().to_string())
+        };
+        let (key_id, signature) = match (header(KEY_ID_HEADER), header(SIGNATURE_HEADER)) {
+            (None, None) => return Ok(AuthenticatedPrincipal::anonymous()),
+            (Some(key_id), Some(signature)) => (key_id, signature),
+            _ => {
+                return Err(SignatureError::Malformed(
+                    "key ID and signature must be sent together".to_string(),
+                ))
+            }
+        };
+        let entry = self
+            .keys
+            .get(&key_id)
+            .ok_or_else(|| SignatureError::UnknownKey(key_id.clone()))?;
+
+        let public = KeyPair::from_public_key(&key_id)
+            .map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        let signature = from_hex(&signature)
+            .ok_or_else(|| SignatureError::Malformed("signature is not hex".to_string()))?;
+        public
+            .verify(payload, &signature)
+            .map_err(|_| SignatureError::Invalid)?;
+        Ok(entry.principal())
+    }
+}
+
+impl FromIterator<RegisteredPrincipal> for PrincipalDirectory {
+    fn from_iter<I: IntoIterator<Item = RegisteredPrincipal>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::EventSigner;
+
+    #[test]
+    fn test_principal_comes_from_the_signing_key() {
+        let operator = EventSigner::generate();
+        let directory = PrincipalDirectory::new().with(RegisteredPrincipal::new(
+            operator.key_id(),
+            PrincipalClass::Operator,
+            "ops-bot",
+        ));
+        let payload = br#"{"type":"SuspendAgent"}"#;
+
+        let mut headers = async_nats::HeaderMap::new();
+        operator.sign_headers(&mut headers, payload).unwrap();
+        let principal = directory.authenticate(Some(&headers), payload).unwrap();
+        assert_eq!(principal.class, PrincipalClass::Operator);
+        assert_eq!(principal.actor.as_deref(), Some("ops-bot"));
+        assert_eq!(
+            directory.authenticate(Some(&headers), b"tampered"),
+            Err(SignatureError::Invalid)
+        );
+
+        let anonymous = directory.authenticate(None, payload).unwrap();
+        assert_eq!(anonymous.class, PrincipalClass::LEAST_PRIVILEGED);
+        assert!(anonymous.is_anonymous());
+
+        let stranger = EventSigner::generate();
+        let mut headers = async_nats::HeaderMap::new();
+        stranger.sign_headers(&mut headers, payload).unwrap();
+        assert!(matches!(
+            directory.authenticate(Some(&headers), payload),
+            Err(SignatureError::UnknownKey(_))
+        ));
+    }
+}
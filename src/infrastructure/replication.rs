@@ -0,0 +1,256 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Multi-region replication awareness
+//!
+//! When agent event streams are mirrored between regions, the same event
+//! can arrive more than once: once from the local publisher and again
+//! through a mirror, or bounced back by a bidirectional source. Every
+//! published event carries its origin region in a header so consumers can
+//! tell the copies apart:
+//!
+//! ```text
+//! region us-east (active)                region eu-west (passive)
+//! publish ──> AGENT_EVENTS ──mirror──>  AGENT_EVENTS ──> consumer
+//!   Cim-Origin-Region: us-east                           └─ ReplicationFilter
+//!                                                           skips foreign duplicates
+//! ```
+//!
+//! Only regions in the [`RegionRole::Active`] role accept writes; passive
+//! regions serve reads from their mirror.
+
+use super::{DomainError, DomainResult, EventEnvelope};
+use crate::value_objects::AgentId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use uuid::Uuid;
+
+/// Header naming the region an event was first published in
+pub const ORIGIN_REGION_HEADER: &str = "Cim-Origin-Region";
+
+/// Event identities remembered for duplicate detection
+pub const DEFAULT_DEDUPE_WINDOW: usize = 10_000;
+
+/// Whether a region accepts writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionRole {
+    /// Accepts commands and publishes events
+    #[default]
+    Active,
+    /// Read-only replica fed by mirroring
+    Passive,
+}
+
+impl RegionRole {
+    /// Parse a role name (case-insensitive)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "active" => Some(RegionRole::Active),
+            "passive" => Some(RegionRole::Passive),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RegionRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionRole::Active => write!(f, "active"),
+            RegionRole::Passive => write!(f, "passive"),
+        }
+    }
+}
+
+/// This deployment's region and role
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionConfig {
+    /// Region name stamped on published events
+    pub region: String,
+
+    /// Write role for this region
+    #[serde(default)]
+    pub role: RegionRole,
+}
+
+impl RegionConfig {
+    /// Active region named `region`
+    pub fn new(region: impl Into<String>) -> Self {
+        Self {
+            region: region.into(),
+            role: RegionRole::Active,
+        }
+    }
+
+    /// Builder: set the role
+    pub fn with_role(mut self, role: RegionRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Read `AGENT_REGION` and `AGENT_REGION_ROLE` (default: active)
+    ///
+    /// Returns None when no region is configured, i.e. a single-region
+    /// deployment.
+    pub fn from_env() -> DomainResult<Option<Self>> {
+        let Ok(region) = std::env::var("AGENT_REGION") else {
+            return Ok(None);
+        };
+        let role = match std::env::var("AGENT_REGION_ROLE") {
+            Ok(role) => RegionRole::from_str(&role).ok_or_else(|| {
+                DomainError::ValidationError(format!("Unknown region role: {}", role))
+            })?,
+            Err(_) => RegionRole::Active,
+        };
+        Ok(Some(Self::new(region).with_role(role)))
+    }
+
+    /// Fail unless this region accepts writes
+    pub fn ensure_writable(&self) -> DomainResult<()> {
+        match self.role {
+            RegionRole::Active => Ok(()),
+            RegionRole::Passive => Err(DomainError::ValidationError(format!(
+                "Region {} is passive and does not accept writes",
+                self.region
+            ))),
+        }
+    }
+
+    /// Headers to attach to published events
+    pub fn headers(&self) -> async_nats::HeaderMap {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(ORIGIN_REGION_HEADER, self.region.as_str());
+        headers
+    }
+}
+
+/// Where a received event was published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventOrigin {
+    /// Published in this region
+    Local,
+    /// Published in another region and replicated here
+    Foreign(String),
+    /// No origin header (pre-replication publisher)
+    Unknown,
+}
+
+/// Read the origin of a received event
+pub fn event_origin(headers: Option<&async_nats::HeaderMap>, local_region: &str) -> EventOrigin {
+    match headers.and_then(|h| h.get(ORIGIN_REGION_HEADER)) {
+        Some(region) if region.as_str() == local_region => EventOrigin::Local,
+        Some(region) => EventOrigin::Foreign(region.as_str().to_string()),
+        None => EventOrigin::Unknown,
+    }
+}
+
+type EventIdentity = (AgentId, u64, Uuid, &'static str);
+
+/// Consumer-side filter that drops replicated copies of seen events
+///
+/// Every event is remembered by its identity (aggregate, sequence,
+/// correlation ID and type). A foreign-origin event whose identity was
+/// already processed is a mirror echo and is skipped; local and unknown
+/// origins are always processed so nothing published here is lost.
+#[derive(Debug)]
+pub struct ReplicationFilter {
+    local_region: String,
+    seen: HashSet<EventIdentity>,
+    order: VecDeque<EventIdentity>,
+    window: usize,
+}
+
+impl ReplicationFilter {
+    /// Create a filter for consumers in `local_region`
+    pub fn new(local_region: impl Into<String>) -> Self {
+        Self {
+            local_region: local_region.into(),
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            window: DEFAULT_DEDUPE_WINDOW,
+        }
+    }
+
+    /// Builder: number of event identities to remember
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Whether to process an event, recording it as seen
+    pub fn admit(
+        &mut self,
+        headers: Option<&async_nats::HeaderMap>,
+        envelope: &EventEnvelope,
+    ) -> bool {
+        let identity = (
+            envelope.aggregate_id,
+            envelope.sequence,
+            envelope.correlation_id,
+            envelope.event.event_type_name(),
+        );
+        let first_sighting = self.remember(identity);
+        match event_origin(headers, &self.local_region) {
+            EventOrigin::Foreign(_) => first_sighting,
+            EventOrigin::Local | EventOrigin::Unknown => true,
+        }
+    }
+
+    fn remember(&mut self, identity: EventIdentity) -> bool {
+        if !self.seen.insert(identity) {
+            return false;
+        }
+        self.order.push_back(identity);
+        if self.order.len() > self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentEvent};
+    use crate::test_support::envelope;
+
+    fn activated() -> EventEnvelope {
+        let agent_id = AgentId::new();
+        envelope(3, AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id)))
+    }
+
+    #[test]
+    fn test_foreign_echo_is_skipped() {
+        let local = RegionConfig::new("eu-west");
+        let remote = RegionConfig::new("us-east");
+        let mut filter = ReplicationFilter::new(&local.region);
+        let event = activated();
+
+        assert!(filter.admit(Some(&local.headers()), &event));
+        assert!(!filter.admit(Some(&remote.headers()), &event));
+
+        // A foreign event seen for the first time is processed
+        assert!(filter.admit(Some(&remote.headers()), &activated()));
+    }
+
+    #[test]
+    fn test_origin_classification() {
+        let headers = RegionConfig::new("us-east").headers();
+        assert_eq!(event_origin(Some(&headers), "us-east"), EventOrigin::Local);
+        assert_eq!(
+            event_origin(Some(&headers), "eu-west"),
+            EventOrigin::Foreign("us-east".to_string())
+        );
+        assert_eq!(event_origin(None, "eu-west"), EventOrigin::Unknown);
+    }
+
+    #[test]
+    fn test_passive_region_rejects_writes() {
+        let passive = RegionConfig::new("eu-west").with_role(RegionRole::Passive);
+        assert!(passive.ensure_writable().is_err());
+        assert!(RegionConfig::new("us-east").ensure_writable().is_ok());
+        assert_eq!(RegionRole::from_str("PASSIVE"), Some(RegionRole::Passive));
+    }
+}
@@ -2,7 +2,10 @@
 
 //! Agent repository
 
-use super::{Agent, AgentEvent, AgentId, DomainError, DomainResult, EventStore, Snapshot, SnapshotStore};
+use super::{
+    Agent, AgentEvent, AgentId, AggregateCache, AggregateCacheStats, DomainError, DomainResult,
+    EventStore, Snapshot, SnapshotStore,
+};
 use std::sync::Arc;
 
 /// Agent repository
@@ -12,6 +15,7 @@ pub struct AgentRepository {
     event_store: Arc<dyn EventStore>,
     snapshot_store: Arc<dyn SnapshotStore>,
     snapshot_frequency: u64,
+    cache: Option<AggregateCache>,
 }
 
 impl AgentRepository {
@@ -31,9 +35,25 @@ impl AgentRepository {
             event_store,
             snapshot_store,
             snapshot_frequency,
+            cache: None,
         }
     }
 
+    /// Builder: keep up to `capacity` rehydrated agents in an LRU cache
+    ///
+    /// Cached agents are validated against the event stream head on every
+    /// load, so writes from other processes are picked up by replaying only
+    /// the events the cache has not seen.
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = (capacity > 0).then(|| AggregateCache::new(capacity));
+        self
+    }
+
+    /// Aggregate cache counters, if caching is enabled
+    pub fn cache_stats(&self) -> Option<AggregateCacheStats> {
+        self.cache.as_ref().map(AggregateCache::stats)
+    }
+
     /// Load an agent by ID
     ///
     /// Loads from snapshot (if available) + subsequent events for optimal performance.
@@ -46,6 +66,53 @@ impl AgentRepository {
     ///
     /// Some(agent) if found, None if not found
     pub async fn load(&self, agent_id: AgentId) -> DomainResult<Option<Agent>> {
+        let Some(cache) = &self.cache else {
+            return Ok(self.load_uncached(agent_id).await?.map(|(agent, _)| agent));
+        };
+
+        let head = self.event_store.get_current_version(agent_id).await?;
+
+        if let Some((agent, cached_version)) = cache.get(agent_id) {
+            if cached_version == head {
+                cache.record_hit();
+                return Ok(Some(agent));
+            }
+
+            if cached_version < head {
+                // Written elsewhere since we cached it: replay just the gap
+                let events = self
+                    .event_store
+                    .get_events_from_version(agent_id, cached_version + 1)
+                    .await?;
+                let mut agent = agent;
+                for envelope in &events {
+                    agent = agent
+                        .apply_event(&envelope.event)
+                        .map_err(DomainError::InvalidStateTransition)?;
+                }
+                // Cache at what was replayed, not the head read before it
+                let version = events.last().map_or(cached_version, |e| e.sequence);
+                cache.record_catch_up();
+                cache.put(agent.clone(), version);
+                return Ok(Some(agent));
+            }
+
+            // Stream is behind the cache (store reset or truncated)
+            cache.invalidate(agent_id);
+        }
+
+        cache.record_miss();
+        let Some((agent, version)) = self.load_uncached(agent_id).await? else {
+            return Ok(None);
+        };
+        cache.put(agent.clone(), version);
+        Ok(Some(agent))
+    }
+
+    /// Rebuild an agent from its snapshot and events, bypassing the cache
+    ///
+    /// Returns the agent with the version of the last event applied to it.
+    async fn load_uncached(&self, agent_id: AgentId) -> DomainResult<Option<(Agent, u64)>> {
         // Try to load from snapshot first
         let (mut agent, from_version) =
             if let Some(snapshot) = self.snapshot_store.get_latest_snapshot(agent_id).await? {
@@ -69,13 +136,14 @@ impl AgentRepository {
         }
 
         // Apply events to reconstruct state
+        let version = events.last().map_or(from_version.saturating_sub(1), |e| e.sequence);
         for envelope in events {
             agent = agent
                 .apply_event(&envelope.event)
                 .map_err(DomainError::InvalidStateTransition)?;
         }
 
-        Ok(Some(agent))
+        Ok(Some((agent, version)))
     }
 
     /// Save an agent
@@ -87,16 +155,46 @@ impl AgentRepository {
     /// * `agent` - The agent to save
     /// * `events` - New events to append
     /// * `expected_version` - Expected current version (for optimistic concurrency)
+    ///
+    /// # Returns
+    ///
+    /// The event stream version after the append, for consistency tokens
     pub async fn save(
         &self,
         agent: &Agent,
         events: Vec<AgentEvent>,
         expected_version: Option<u64>,
-    ) -> DomainResult<()> {
+    ) -> DomainResult<u64> {
         // Append events
-        self.event_store
+        let appended = events.len() as u64;
+        let result = self
+            .event_store
             .append_events(agent.id(), events, expected_version)
-            .await?;
+            .await;
+
+        let stream_version = match (result, expected_version) {
+            (Ok(()), Some(expected)) => {
+                let stream_version = expected + appended;
+                if let Some(cache) = &self.cache {
+                    cache.put(agent.clone(), stream_version);
+                }
+                stream_version
+            }
+            (Ok(()), None) => {
+                // Head may include concurrent writes we did not apply
+                if let Some(cache) = &self.cache {
+                    cache.invalidate(agent.id());
+                }
+                self.event_store.get_current_version(agent.id()).await?
+            }
+            (Err(e), _) => {
+                // A conflict proves the cached copy is stale
+                if let Some(cache) = &self.cache {
+                    cache.invalidate(agent.id());
+                }
+                return Err(e);
+            }
+        };
 
         // Check if we should create a snapshot
         let new_version = agent.version();
@@ -119,7 +217,7 @@ impl AgentRepository {
             }
         }
 
-        Ok(())
+        Ok(stream_version)
     }
 
     /// Check if an agent exists
@@ -138,7 +236,7 @@ impl AgentRepository {
 mod tests {
     use super::*;
     use crate::events::{AgentActivatedEvent, AgentDeployedEvent, ModelConfiguredEvent};
-    use crate::infrastructure::{InMemoryEventStore, InMemorySnapshotStore};
+    use crate::infrastructure::{EventEnvelope, InMemoryEventStore, InMemorySnapshotStore};
     use crate::value_objects::{ModelConfig, PersonId};
 
     fn create_deployed_event(agent_id: AgentId, person_id: PersonId) -> AgentEvent {
@@ -163,7 +261,8 @@ mod tests {
         let event = create_deployed_event(agent_id, person_id);
         let agent = Agent::empty().apply_event(&event).unwrap();
 
-        repo.save(&agent, vec![event], None).await.unwrap();
+        let version = repo.save(&agent, vec![event], None).await.unwrap();
+        assert_eq!(version, 1);
 
         let loaded = repo.load(agent_id).await.unwrap();
         assert!(loaded.is_some());
@@ -240,6 +339,121 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_cache_hit_and_external_write() {
+        let event_store = Arc::new(InMemoryEventStore::new());
+        let snapshot_store = Arc::new(InMemorySnapshotStore::new());
+        let repo = AgentRepository::new(event_store.clone(), snapshot_store, 10).with_cache(8);
+
+        let agent_id = AgentId::new();
+        let event = create_deployed_event(agent_id, PersonId::new());
+        let agent = Agent::empty().apply_event(&event).unwrap();
+        repo.save(&agent, vec![event], None).await.unwrap();
+
+        // First load misses, second hits
+        repo.load(agent_id).await.unwrap().unwrap();
+        repo.load(agent_id).await.unwrap().unwrap();
+
+        // Another writer appends directly to the store
+        let config_event = AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+            agent_id,
+            ModelConfig::mock(),
+        ));
+        event_store
+            .append_events(agent_id, vec![config_event], Some(1))
+            .await
+            .unwrap();
+
+        let loaded = repo.load(agent_id).await.unwrap().unwrap();
+        assert!(loaded.model_config().is_some());
+
+        let stats = repo.cache_stats().unwrap();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.catch_ups, 1);
+    }
+
+    /// Store whose head lags its events, like a stream queried mid-append
+    struct LaggingHead(InMemoryEventStore);
+
+    #[async_trait::async_trait]
+    impl EventStore for LaggingHead {
+        async fn append_events(
+            &self,
+            aggregate_id: AgentId,
+            events: Vec<AgentEvent>,
+            expected_version: Option<u64>,
+        ) -> DomainResult<()> {
+            self.0.append_events(aggregate_id, events, expected_version).await
+        }
+
+        async fn get_events(&self, aggregate_id: AgentId) -> DomainResult<Vec<EventEnvelope>> {
+            self.0.get_events(aggregate_id).await
+        }
+
+        async fn get_events_from_version(
+            &self,
+            aggregate_id: AgentId,
+            from_version: u64,
+        ) -> DomainResult<Vec<EventEnvelope>> {
+            self.0.get_events_from_version(aggregate_id, from_version).await
+        }
+
+        async fn get_current_version(&self, _aggregate_id: AgentId) -> DomainResult<u64> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_versioned_by_loaded_events() {
+        let event_store = Arc::new(LaggingHead(InMemoryEventStore::new()));
+        let snapshot_store = Arc::new(InMemorySnapshotStore::new());
+        let repo = AgentRepository::new(event_store.clone(), snapshot_store, 10).with_cache(8);
+
+        let agent_id = AgentId::new();
+        let event = create_deployed_event(agent_id, PersonId::new());
+        event_store.append_events(agent_id, vec![event], None).await.unwrap();
+        repo.load(agent_id).await.unwrap().unwrap();
+
+        // Cached at version 1, so the lagging head of 0 cannot make it a hit
+        let config_event = AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+            agent_id,
+            ModelConfig::mock(),
+        ));
+        event_store
+            .append_events(agent_id, vec![config_event], Some(1))
+            .await
+            .unwrap();
+
+        let loaded = repo.load(agent_id).await.unwrap().unwrap();
+        assert!(loaded.model_config().is_some());
+        assert_eq!(repo.cache_stats().unwrap().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_updated_on_save() {
+        let event_store = Arc::new(InMemoryEventStore::new());
+        let snapshot_store = Arc::new(InMemorySnapshotStore::new());
+        let repo = AgentRepository::new(event_store, snapshot_store, 10).with_cache(8);
+
+        let agent_id = AgentId::new();
+        let event = create_deployed_event(agent_id, PersonId::new());
+        let agent = Agent::empty().apply_event(&event).unwrap();
+        repo.save(&agent, vec![event], None).await.unwrap();
+        repo.load(agent_id).await.unwrap();
+
+        let configure = AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+            agent_id,
+            ModelConfig::mock(),
+        ));
+        let agent = agent.apply_event(&configure).unwrap();
+        repo.save(&agent, vec![configure], Some(1)).await.unwrap();
+
+        let loaded = repo.load(agent_id).await.unwrap().unwrap();
+        assert!(loaded.model_config().is_some());
+        assert_eq!(repo.cache_stats().unwrap().hits, 1);
+    }
+
     #[tokio::test]
     async fn test_concurrency_conflict() {
         let event_store = Arc::new(InMemoryEventStore::new());
@@ -0,0 +1,444 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Durable message retry store
+//!
+//! Messages whose send failed wait here for their next attempt, and
+//! messages that keep failing wait here for an operator:
+//!
+//! ```text
+//! send fails ──> schedule(PendingRetry)          pending.{message_id}
+//!                    │
+//!          retry_at passes ──> lease() ──> send again ──> complete()
+//!                    │
+//!       budget spent or poison ──> quarantine()   quarantine.{message_id}
+//!                                      │
+//!                        operator ──> release() ──> send again
+//! ```
+//!
+//! A NATS KV bucket keeps both queues across restarts and shares them
+//! between hosts; the in-memory store is for tests and single processes.
+
+use super::{DomainError, DomainResult};
+use crate::commands::SendMessage;
+use crate::value_objects::MessageId;
+use async_nats::jetstream::{self, kv::Store as KvStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Order in which due retries are sent
+///
+/// Someone waiting in a conversation goes first; shadow copies nobody
+/// sees go last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryPriority {
+    /// Mirrored traffic whose answer is never delivered
+    Background,
+    /// Messages sent outside a conversation
+    Normal,
+    /// Messages a participant is waiting on
+    Interactive,
+}
+
+impl RetryPriority {
+    /// Priority of a message's retries
+    pub fn of(message: &SendMessage) -> Self {
+        if message.shadow_of.is_some() {
+            RetryPriority::Background
+        } else if message.conversation_id.is_some() {
+            RetryPriority::Interactive
+        } else {
+            RetryPriority::Normal
+        }
+    }
+}
+
+/// A failed message waiting to be sent again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRetry {
+    /// The command to send again
+    pub message: SendMessage,
+
+    /// Order among retries due at the same time
+    pub priority: RetryPriority,
+
+    /// Attempts that have failed so far
+    pub attempts: u32,
+
+    /// Why the last attempt failed
+    pub last_error: String,
+
+    /// When the message may be sent again
+    pub retry_at: DateTime<Utc>,
+
+    /// When the first attempt failed
+    pub first_failed_at: DateTime<Utc>,
+}
+
+/// A message set aside for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedMessage {
+    /// The command that kept failing
+    pub message: SendMessage,
+
+    /// Attempts made before quarantine
+    pub attempts: u32,
+
+    /// Why the message was quarantined
+    pub reason: String,
+
+    /// When the message was quarantined
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// Retry store trait
+///
+/// Holds pending retries and the quarantine review queue, keyed by message.
+#[async_trait]
+pub trait RetryStore: Send + Sync {
+    /// Save or replace the pending retry of a message
+    async fn schedule(&self, retry: PendingRetry) -> DomainResult<()>;
+
+    /// The pending retry of a message, if any
+    async fn get(&self, message_id: MessageId) -> DomainResult<Option<PendingRetry>>;
+
+    /// Every pending retry
+    async fn pending(&self) -> DomainResult<Vec<PendingRetry>>;
+
+    /// Take a retry due at `now`, holding it until `until` so no one else sends it
+    ///
+    /// None if it is gone, not due, or another host leased it first.
+    async fn lease(
+        &self,
+        message_id: MessageId,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> DomainResult<Option<PendingRetry>>;
+
+    /// Forget a message's pending retry once it has been answered
+    async fn complete(&self, message_id: MessageId) -> DomainResult<()>;
+
+    /// Move a message from the pending queue to quarantine
+    async fn quarantine(&self, message: QuarantinedMessage) -> DomainResult<()>;
+
+    /// Every quarantined message, oldest first
+    async fn quarantined(&self) -> DomainResult<Vec<QuarantinedMessage>>;
+
+    /// Take a message out of quarantine; None if it is not quarantined
+    async fn release(&self, message_id: MessageId) -> DomainResult<Option<QuarantinedMessage>>;
+}
+
+#[derive(Debug, Default)]
+struct Queues {
+    pending: HashMap<MessageId, PendingRetry>,
+    quarantined: HashMap<MessageId, QuarantinedMessage>,
+}
+
+/// In-memory retry store; retries are lost when the process exits
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryRetryStore {
+    queues: Arc<Mutex<Queues>>,
+}
+
+impl InMemoryRetryStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RetryStore for InMemoryRetryStore {
+    async fn schedule(&self, retry: PendingRetry) -> DomainResult<()> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        queues.pending.insert(retry.message.message_id, retry);
+        Ok(())
+    }
+
+    async fn get(&self, message_id: MessageId) -> DomainResult<Option<PendingRetry>> {
+        let queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(queues.pending.get(&message_id).cloned())
+    }
+
+    async fn pending(&self) -> DomainResult<Vec<PendingRetry>> {
+        let queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(queues.pending.values().cloned().collect())
+    }
+
+    async fn lease(
+        &self,
+        message_id: MessageId,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> DomainResult<Option<PendingRetry>> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(retry) = queues.pending.get_mut(&message_id) else {
+            return Ok(None);
+        };
+        if retry.retry_at > now {
+            return Ok(None);
+        }
+        retry.retry_at = until;
+        Ok(Some(retry.clone()))
+    }
+
+    async fn complete(&self, message_id: MessageId) -> DomainResult<()> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        queues.pending.remove(&message_id);
+        Ok(())
+    }
+
+    async fn quarantine(&self, message: QuarantinedMessage) -> DomainResult<()> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        let message_id = message.message.message_id;
+        queues.pending.remove(&message_id);
+        queues.quarantined.insert(message_id, message);
+        Ok(())
+    }
+
+    async fn quarantined(&self) -> DomainResult<Vec<QuarantinedMessage>> {
+        let queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        let mut quarantined: Vec<_> = queues.quarantined.values().cloned().collect();
+        quarantined.sort_by_key(|q| q.quarantined_at);
+        Ok(quarantined)
+    }
+
+    async fn release(&self, message_id: MessageId) -> DomainResult<Option<QuarantinedMessage>> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(queues.quarantined.remove(&message_id))
+    }
+}
+
+/// NATS KV retry store, shared by every host using the bucket
+///
+/// Pending retries live under `pending.{message_id}` and quarantined
+/// messages under `quarantine.{message_id}`. Leasing and releasing write
+/// with the revision they read, so two hosts cannot both send a retry and
+/// two operators cannot both release a message.
+pub struct NatsRetryStore {
+    kv: KvStore,
+}
+
+impl NatsRetryStore {
+    /// Create a store over an existing KV bucket
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Create or get the KV bucket
+    ///
+    /// # Arguments
+    ///
+    /// * `jetstream` - JetStream context
+    /// * `bucket_name` - Name of the KV bucket (e.g., "AGENT_RETRIES")
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+
+    fn pending_key(message_id: MessageId) -> String {
+        format!("pending.{}", message_id)
+    }
+
+    fn quarantine_key(message_id: MessageId) -> String {
+        format!("quarantine.{}", message_id)
+    }
+
+    async fn load<T: serde::de::DeserializeOwned>(&self, key: &str) -> DomainResult<Option<T>> {
+        let entry = self.kv.get(key).await.map_err(store_error)?;
+        entry.map(|payload| decode(&payload)).transpose()
+    }
+
+    async fn load_all<T: serde::de::DeserializeOwned>(&self, prefix: &str) -> DomainResult<Vec<T>> {
+        let keys: Vec<String> = self
+            .kv
+            .keys()
+            .await
+            .map_err(store_error)?
+            .try_collect()
+            .await
+            .map_err(store_error)?;
+
+        let mut values = Vec::new();
+        for key in keys.iter().filter(|key| key.starts_with(prefix)) {
+            // A key deleted since listing is simply skipped
+            if let Some(value) = self.load(key).await? {
+                values.push(value);
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn store_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::RetryStoreError(e.to_string())
+}
+
+fn encode(value: &impl Serialize) -> DomainResult<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| DomainError::SerializationError(e.to_string()))
+}
+
+fn decode<T: serde::de::DeserializeOwned>(payload: &[u8]) -> DomainResult<T> {
+    serde_json::from_slice(payload).map_err(|e| DomainError::SerializationError(e.to_string()))
+}
+
+#[async_trait]
+impl RetryStore for NatsRetryStore {
+    async fn schedule(&self, retry: PendingRetry) -> DomainResult<()> {
+        let key = Self::pending_key(retry.message.message_id);
+        self.kv
+            .put(key, encode(&retry)?.into())
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, message_id: MessageId) -> DomainResult<Option<PendingRetry>> {
+        self.load(&Self::pending_key(message_id)).await
+    }
+
+    async fn pending(&self) -> DomainResult<Vec<PendingRetry>> {
+        self.load_all("pending.").await
+    }
+
+    async fn lease(
+        &self,
+        message_id: MessageId,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> DomainResult<Option<PendingRetry>> {
+        let key = Self::pending_key(message_id);
+        let Some(entry) = self.kv.entry(&key).await.map_err(store_error)? else {
+            return Ok(None);
+        };
+        if entry.operation != jetstream::kv::Operation::Put {
+            return Ok(None);
+        }
+        let mut retry: PendingRetry = decode(&entry.value)?;
+        if retry.retry_at > now {
+            return Ok(None);
+        }
+        retry.retry_at = until;
+        match self
+            .kv
+            .update(&key, encode(&retry)?.into(), entry.revision)
+            .await
+        {
+            Ok(_) => Ok(Some(retry)),
+            Err(e) if e.kind() == jetstream::kv::UpdateErrorKind::WrongLastRevision => Ok(None),
+            Err(e) => Err(store_error(e)),
+        }
+    }
+
+    async fn complete(&self, message_id: MessageId) -> DomainResult<()> {
+        self.kv
+            .purge(Self::pending_key(message_id))
+            .await
+            .map_err(store_error)
+    }
+
+    async fn quarantine(&self, message: QuarantinedMessage) -> DomainResult<()> {
+        let message_id = message.message.message_id;
+        // Quarantine first: a crash in between leaves a duplicate, never a loss
+        self.kv
+            .put(Self::quarantine_key(message_id), encode(&message)?.into())
+            .await
+            .map_err(store_error)?;
+        self.complete(message_id).await
+    }
+
+    async fn quarantined(&self) -> DomainResult<Vec<QuarantinedMessage>> {
+        let mut quarantined: Vec<QuarantinedMessage> = self.load_all("quarantine.").await?;
+        quarantined.sort_by_key(|q| q.quarantined_at);
+        Ok(quarantined)
+    }
+
+    async fn release(&self, message_id: MessageId) -> DomainResult<Option<QuarantinedMessage>> {
+        let key = Self::quarantine_key(message_id);
+        let Some(entry) = self.kv.entry(&key).await.map_err(store_error)? else {
+            return Ok(None);
+        };
+        if entry.operation != jetstream::kv::Operation::Put {
+            return Ok(None);
+        }
+        let message = decode(&entry.value)?;
+        self.kv
+            .purge_expect_revision(&key, Some(entry.revision))
+            .await
+            .map_err(store_error)?;
+        Ok(Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{AgentId, ConversationId};
+
+    fn pending(message: SendMessage) -> PendingRetry {
+        PendingRetry {
+            priority: RetryPriority::of(&message),
+            message,
+            attempts: 1,
+            last_error: "connection reset".to_string(),
+            retry_at: Utc::now(),
+            first_failed_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_moves_message_out_of_pending_until_released() {
+        let store = InMemoryRetryStore::new();
+        let message = SendMessage::new(AgentId::new(), "Hello");
+        let message_id = message.message_id;
+        store.schedule(pending(message.clone())).await.unwrap();
+        assert_eq!(store.get(message_id).await.unwrap().unwrap().attempts, 1);
+
+        store
+            .quarantine(QuarantinedMessage {
+                message,
+                attempts: 3,
+                reason: "retry budget spent".to_string(),
+                quarantined_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        assert!(store.pending().await.unwrap().is_empty());
+        assert_eq!(store.quarantined().await.unwrap().len(), 1);
+
+        assert!(store.release(message_id).await.unwrap().is_some());
+        assert!(store.release(message_id).await.unwrap().is_none());
+        assert!(store.quarantined().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_conversation_messages_retry_first() {
+        let agent_id = AgentId::new();
+        let interactive = SendMessage::new(agent_id, "Hi").in_conversation(ConversationId::new());
+        let shadow = SendMessage::new(agent_id, "Hi").shadowing(MessageId::new());
+
+        assert_eq!(RetryPriority::of(&interactive), RetryPriority::Interactive);
+        assert_eq!(RetryPriority::of(&shadow), RetryPriority::Background);
+        assert!(RetryPriority::Interactive > RetryPriority::Normal);
+        assert!(RetryPriority::Normal > RetryPriority::Background);
+    }
+}
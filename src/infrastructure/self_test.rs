@@ -0,0 +1,572 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Startup Self-Test
+//!
+//! A misconfigured agent host usually fails on its first real request: a
+//! stream that was never created, an expired provider key, a model that was
+//! never pulled. [`AgentHost::self_test`] runs cheap probes for each of these
+//! before any subscription is opened and collects the outcome into a
+//! [`ReadinessReport`]:
+//!
+//! ```text
+//! AgentHost::self_test()
+//!   ├── nats           connection state + flush round trip
+//!   ├── stream:<name>  JetStream stream info
+//!   ├── provider:<p>   ChatPort::health_check (no tokens spent; soft unless default)
+//!   ├── embeddings:<p> EmbeddingPort::embed(["self-test"])
+//!   ├── vectors:<k>    ArtifactStore::list_artifacts
+//!   └── model:<m>      ChatPort::has_model (listing only, nothing loaded)
+//!            │
+//!            v
+//!   ReadinessReport ── hard failure? ──> ensure_ready() = Err(NotReady)
+//! ```
+//!
+//! Checks run concurrently, each under the host's check timeout. A failed
+//! hard check means the host must not serve traffic; a failed soft check
+//! leaves it ready but degraded.
+
+use super::ArtifactStore;
+use crate::adapters::ProviderRegistry;
+use crate::ports::{ChatPort, EmbeddingPort};
+use crate::value_objects::{ModelConfig, ProviderType};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default time each check may take before it counts as failed
+pub const DEFAULT_SELF_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether a failed check stops the host from serving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckSeverity {
+    /// The host cannot do its job without it
+    Hard,
+    /// The host works, with a feature unavailable
+    Soft,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+}
+
+/// A probe run by [`AgentHost::self_test`]
+#[async_trait]
+pub trait SelfTestCheck: Send + Sync {
+    /// Name shown in the report, e.g. `stream:AGENT_EVENTS`
+    fn name(&self) -> String;
+
+    /// Whether failing stops the host from serving
+    fn severity(&self) -> CheckSeverity {
+        CheckSeverity::Hard
+    }
+
+    /// Run the probe, returning an optional detail on success or the reason it failed
+    async fn run(&self) -> Result<Option<String>, String>;
+}
+
+/// Result of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub severity: CheckSeverity,
+    pub status: CheckStatus,
+
+    /// Failure reason, or extra information on success
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+
+    pub elapsed_ms: u64,
+}
+
+impl CheckResult {
+    /// Whether this result stops the host from serving
+    pub fn is_blocking(&self) -> bool {
+        self.status == CheckStatus::Failed && self.severity == CheckSeverity::Hard
+    }
+}
+
+/// Every check's result from one self-test
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReadinessReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl ReadinessReport {
+    /// No hard check failed
+    pub fn is_ready(&self) -> bool {
+        !self.checks.iter().any(CheckResult::is_blocking)
+    }
+
+    /// Ready, but some soft check failed
+    pub fn is_degraded(&self) -> bool {
+        self.is_ready() && self.checks.iter().any(|c| c.status == CheckStatus::Failed)
+    }
+
+    /// Checks that failed, hard and soft
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Failed)
+    }
+}
+
+impl fmt::Display for ReadinessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = match (check.status, check.severity) {
+                (CheckStatus::Passed, _) => "ok",
+                (CheckStatus::Failed, CheckSeverity::Hard) => "FAIL",
+                (CheckStatus::Failed, CheckSeverity::Soft) => "warn",
+            };
+            write!(f, "{:<4} {} ({}ms)", status, check.name, check.elapsed_ms)?;
+            if let Some(detail) = &check.detail {
+                write!(f, ": {}", detail)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The self-test found a hard failure; the host must not serve traffic
+#[derive(Debug, thiserror::Error)]
+#[error("Self-test failed: {}", blocking_names(.0))]
+pub struct NotReady(pub ReadinessReport);
+
+fn blocking_names(report: &ReadinessReport) -> String {
+    report
+        .checks
+        .iter()
+        .filter(|c| c.is_blocking())
+        .map(|c| c.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The NATS connection is up and answers a flush
+pub struct NatsConnectivityCheck {
+    client: async_nats::Client,
+}
+
+impl NatsConnectivityCheck {
+    pub fn new(client: async_nats::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl SelfTestCheck for NatsConnectivityCheck {
+    fn name(&self) -> String {
+        "nats".to_string()
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        let state = self.client.connection_state();
+        if state != async_nats::connection::State::Connected {
+            return Err(format!("connection is {:?}", state));
+        }
+        self.client.flush().await.map_err(|e| e.to_string())?;
+        Ok(Some(self.client.server_info().server_name))
+    }
+}
+
+/// A JetStream stream exists
+pub struct StreamExistsCheck {
+    jetstream: async_nats::jetstream::Context,
+    stream: String,
+}
+
+impl StreamExistsCheck {
+    pub fn new(jetstream: async_nats::jetstream::Context, stream: impl Into<String>) -> Self {
+        Self {
+            jetstream,
+            stream: stream.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl SelfTestCheck for StreamExistsCheck {
+    fn name(&self) -> String {
+        format!("stream:{}", self.stream)
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        let mut stream = self
+            .jetstream
+            .get_stream(&self.stream)
+            .await
+            .map_err(|e| e.to_string())?;
+        let info = stream.info().await.map_err(|e| e.to_string())?;
+        Ok(Some(format!("{} messages", info.state.messages)))
+    }
+}
+
+/// A chat provider is reachable and accepts its credentials
+pub struct ProviderCheck {
+    provider: ProviderType,
+    adapter: Arc<dyn ChatPort>,
+    severity: CheckSeverity,
+}
+
+impl ProviderCheck {
+    /// A hard check
+    pub fn new(provider: ProviderType, adapter: Arc<dyn ChatPort>) -> Self {
+        Self {
+            provider,
+            adapter,
+            severity: CheckSeverity::Hard,
+        }
+    }
+
+    /// Builder: override the severity
+    pub fn with_severity(mut self, severity: CheckSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+#[async_trait]
+impl SelfTestCheck for ProviderCheck {
+    fn name(&self) -> String {
+        format!("provider:{}", self.provider)
+    }
+
+    fn severity(&self) -> CheckSeverity {
+        self.severity
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        self.adapter
+            .health_check()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(None)
+    }
+}
+
+/// An embedding provider answers
+pub struct EmbeddingCheck {
+    embedder: Arc<dyn EmbeddingPort>,
+    severity: CheckSeverity,
+}
+
+impl EmbeddingCheck {
+    /// A soft check: without embeddings the host still chats
+    pub fn new(embedder: Arc<dyn EmbeddingPort>) -> Self {
+        Self {
+            embedder,
+            severity: CheckSeverity::Soft,
+        }
+    }
+
+    /// Builder: override the severity
+    pub fn with_severity(mut self, severity: CheckSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+#[async_trait]
+impl SelfTestCheck for EmbeddingCheck {
+    fn name(&self) -> String {
+        format!("embeddings:{}", self.embedder.provider_name())
+    }
+
+    fn severity(&self) -> CheckSeverity {
+        self.severity
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        let response = self
+            .embedder
+            .embed(vec!["self-test".to_string()], None)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Some(response.model))
+    }
+}
+
+/// A vector store can be listed
+pub struct VectorStoreCheck {
+    store: Arc<dyn ArtifactStore>,
+}
+
+impl VectorStoreCheck {
+    /// A soft check: without vectors the host still chats
+    pub fn new(store: Arc<dyn ArtifactStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl SelfTestCheck for VectorStoreCheck {
+    fn name(&self) -> String {
+        format!("vectors:{}", self.store.kind())
+    }
+
+    fn severity(&self) -> CheckSeverity {
+        CheckSeverity::Soft
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        let stored = self
+            .store
+            .list_artifacts()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Some(format!("{} stored", stored.len())))
+    }
+}
+
+/// A model an agent is configured for is available from its provider
+pub struct ModelAvailableCheck {
+    config: ModelConfig,
+    adapter: Arc<dyn ChatPort>,
+}
+
+impl ModelAvailableCheck {
+    pub fn new(config: ModelConfig, adapter: Arc<dyn ChatPort>) -> Self {
+        Self { config, adapter }
+    }
+}
+
+#[async_trait]
+impl SelfTestCheck for ModelAvailableCheck {
+    fn name(&self) -> String {
+        format!("model:{}/{}", self.config.provider, self.config.model_name)
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        match self.adapter.has_model(&self.config.model_name).await {
+            Ok(Some(true)) => Ok(None),
+            Ok(Some(false)) => Err("provider does not serve this model".to_string()),
+            Ok(None) => Ok(Some(
+                "provider cannot list models; not verified".to_string(),
+            )),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// The set of checks a host runs before serving
+pub struct AgentHost {
+    checks: Vec<Arc<dyn SelfTestCheck>>,
+    check_timeout: Duration,
+}
+
+impl AgentHost {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            check_timeout: DEFAULT_SELF_TEST_TIMEOUT,
+        }
+    }
+
+    /// Builder: run `check`
+    pub fn with_check(mut self, check: impl SelfTestCheck + 'static) -> Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    /// Builder: fail any check that takes longer than `timeout`
+    pub fn with_check_timeout(mut self, timeout: Duration) -> Self {
+        self.check_timeout = timeout;
+        self
+    }
+
+    /// Builder: check the NATS connection
+    pub fn with_nats(self, client: async_nats::Client) -> Self {
+        self.with_check(NatsConnectivityCheck::new(client))
+    }
+
+    /// Builder: check that `stream` exists
+    pub fn with_stream(
+        self,
+        jetstream: async_nats::jetstream::Context,
+        stream: impl Into<String>,
+    ) -> Self {
+        self.with_check(StreamExistsCheck::new(jetstream, stream))
+    }
+
+    /// Builder: check every chat provider in `registry`
+    ///
+    /// These are soft checks: routing sends requests to the providers that
+    /// answer. Use [`with_default_provider`](Self::with_default_provider)
+    /// for the one the host cannot do without.
+    pub fn with_providers(mut self, registry: &ProviderRegistry) -> Self {
+        for provider in registry.list_providers() {
+            if let Some(adapter) = registry.get_adapter(&provider) {
+                let check =
+                    ProviderCheck::new(provider, adapter).with_severity(CheckSeverity::Soft);
+                self = self.with_check(check);
+            }
+        }
+        self
+    }
+
+    /// Builder: hard check of the provider agents use by default
+    ///
+    /// A provider that is not registered fails outright.
+    pub fn with_default_provider(
+        self,
+        registry: &ProviderRegistry,
+        provider: ProviderType,
+    ) -> Self {
+        match registry.get_adapter(&provider) {
+            Some(adapter) => self.with_check(ProviderCheck::new(provider, adapter)),
+            None => self.with_check(UnregisteredProvider {
+                name: format!("provider:{}", provider),
+                provider,
+            }),
+        }
+    }
+
+    /// Builder: check that `config`'s model is available
+    ///
+    /// A model whose provider is not registered fails outright.
+    pub fn with_required_model(self, registry: &ProviderRegistry, config: ModelConfig) -> Self {
+        match registry.get_adapter(&config.provider) {
+            Some(adapter) => self.with_check(ModelAvailableCheck::new(config, adapter)),
+            None => self.with_check(UnregisteredProvider {
+                name: format!("model:{}/{}", config.provider, config.model_name),
+                provider: config.provider,
+            }),
+        }
+    }
+
+    /// Builder: check an embedding provider
+    pub fn with_embedder(self, embedder: Arc<dyn EmbeddingPort>) -> Self {
+        self.with_check(EmbeddingCheck::new(embedder))
+    }
+
+    /// Builder: check the vector store embeddings are kept in
+    pub fn with_vector_store(self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.with_check(VectorStoreCheck::new(store))
+    }
+
+    /// Run every check concurrently and report the outcomes in registration order
+    pub async fn self_test(&self) -> ReadinessReport {
+        let runs = self.checks.iter().map(|check| async move {
+            let started = Instant::now();
+            let outcome = match tokio::time::timeout(self.check_timeout, check.run()).await {
+                Ok(outcome) => outcome,
+                Err(_) => Err(format!("timed out after {:?}", self.check_timeout)),
+            };
+            let (status, detail) = match outcome {
+                Ok(detail) => (CheckStatus::Passed, detail),
+                Err(reason) => (CheckStatus::Failed, Some(reason)),
+            };
+            CheckResult {
+                name: check.name(),
+                severity: check.severity(),
+                status,
+                detail,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            }
+        });
+        let report = ReadinessReport {
+            checks: futures::future::join_all(runs).await,
+        };
+
+        for failure in report.failures() {
+            tracing::warn!(
+                check = %failure.name,
+                severity = ?failure.severity,
+                "Self-test check failed: {}",
+                failure.detail.as_deref().unwrap_or("")
+            );
+        }
+        report
+    }
+
+    /// Run the self-test, failing when any hard check failed
+    pub async fn ensure_ready(&self) -> Result<ReadinessReport, NotReady> {
+        let report = self.self_test().await;
+        if report.is_ready() {
+            Ok(report)
+        } else {
+            Err(NotReady(report))
+        }
+    }
+}
+
+impl Default for AgentHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct UnregisteredProvider {
+    name: String,
+    provider: ProviderType,
+}
+
+#[async_trait]
+impl SelfTestCheck for UnregisteredProvider {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn run(&self) -> Result<Option<String>, String> {
+        Err(format!("provider {} is not registered", self.provider))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::ProviderCapabilities;
+    use crate::ports::MockChatAdapter;
+
+    struct Unreachable(CheckSeverity);
+
+    #[async_trait]
+    impl SelfTestCheck for Unreachable {
+        fn name(&self) -> String {
+            "vector-store".to_string()
+        }
+
+        fn severity(&self) -> CheckSeverity {
+            self.0
+        }
+
+        async fn run(&self) -> Result<Option<String>, String> {
+            Err("connection refused".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hard_failures_block_serving() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(
+            ProviderType::Mock,
+            MockChatAdapter::new(),
+            ProviderCapabilities::mock(),
+        );
+
+        let host = AgentHost::new()
+            .with_providers(&registry)
+            .with_required_model(&registry, ModelConfig::mock())
+            .with_check(Unreachable(CheckSeverity::Soft));
+        let report = host.ensure_ready().await.unwrap();
+        assert!(report.is_degraded());
+        assert_eq!(report.checks.len(), 3);
+
+        let host = host.with_required_model(
+            &registry,
+            ModelConfig::new(ProviderType::Ollama, "llama3.2"),
+        );
+        let NotReady(report) = host.ensure_ready().await.unwrap_err();
+        let blocking: Vec<_> = report.checks.iter().filter(|c| c.is_blocking()).collect();
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].name, "model:Ollama/llama3.2");
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Event signing and verification
+//!
+//! On a shared NATS cluster anyone with publish rights on
+//! `{domain}.events.agent.>` could forge a lifecycle event. Each agent host
+//! therefore holds an Ed25519 keypair (an NATS nkey) and attaches a detached
+//! signature over the serialized envelope:
+//!
+//! ```text
+//! Cim-Key-Id:    UDXU4RCSJNZOIQHZNWXHXORDPRTGNJAHAHFRGZNEEJCPQTT2M7NLCNF4
+//! Cim-Signature: 9f2c...e1  (hex Ed25519 signature of the payload)
+//! ```
+//!
+//! The key ID is the nkey public key, so verification needs no key
+//! exchange. Whether a key may speak for an agent is decided by a
+//! pluggable [`KeyRegistry`].
+
+use crate::value_objects::AgentId;
+use nkeys::KeyPair;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Header carrying the signer's public key
+pub const KEY_ID_HEADER: &str = "Cim-Key-Id";
+
+/// Header carrying the hex-encoded detached signature
+pub const SIGNATURE_HEADER: &str = "Cim-Signature";
+
+/// Signature verification errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("Event is not signed")]
+    Unsigned,
+
+    #[error("Malformed signature headers: {0}")]
+    Malformed(String),
+
+    #[error("Key {0} is not registered")]
+    UnknownKey(String),
+
+    #[error("Key {key_id} is not trusted for agent {agent_id}")]
+    Untrusted { key_id: String, agent_id: AgentId },
+
+    #[error("Signature does not match payload")]
+    Invalid,
+
+    #[error("Signing failed: {0}")]
+    Signing(String),
+}
+
+/// Result type for signing operations
+pub type SignatureResult<T> = Result<T, SignatureError>;
+
+/// Signs events published by this host
+#[derive(Clone)]
+pub struct EventSigner {
+    keypair: Arc<KeyPair>,
+}
+
+impl EventSigner {
+    /// Generate a fresh keypair
+    pub fn generate() -> Self {
+        Self {
+            keypair: Arc::new(KeyPair::new_user()),
+        }
+    }
+
+    /// Load a keypair from an nkey seed (`SU...`)
+    pub fn from_seed(seed: &str) -> SignatureResult<Self> {
+        let keypair =
+            KeyPair::from_seed(seed).map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        Ok(Self {
+            keypair: Arc::new(keypair),
+        })
+    }
+
+    /// Public key identifying this signer
+    pub fn key_id(&self) -> String {
+        self.keypair.public_key()
+    }
+
+    /// Hex-encoded detached signature of `payload`
+    pub fn sign(&self, payload: &[u8]) -> SignatureResult<String> {
+        let signature = self
+            .keypair
+            .sign(payload)
+            .map_err(|e| SignatureError::Signing(e.to_string()))?;
+        Ok(to_hex(&signature))
+    }
+
+    /// Add key ID and signature headers for `payload`
+    pub fn sign_headers(
+        &self,
+        headers: &mut async_nats::HeaderMap,
+        payload: &[u8],
+    ) -> SignatureResult<()> {
+        headers.insert(KEY_ID_HEADER, self.key_id().as_str());
+        headers.insert(SIGNATURE_HEADER, self.sign(payload)?.as_str());
+        Ok(())
+    }
+}
+
+impl fmt::Debug for EventSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSigner")
+            .field("key_id", &self.key_id())
+            .finish()
+    }
+}
+
+/// Decides which keys may sign for which agents
+pub trait KeyRegistry: Send + Sync {
+    /// Whether `key_id` may sign events for `agent_id`
+    fn is_trusted(&self, key_id: &str, agent_id: AgentId) -> bool;
+}
+
+/// Key ID -> agents it may sign for (None = any agent)
+type KeyTable = HashMap<String, Option<HashSet<AgentId>>>;
+
+/// In-memory key registry
+///
+/// A key is trusted either for every agent or for an explicit set.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryKeyRegistry {
+    keys: Arc<RwLock<KeyTable>>,
+}
+
+impl InMemoryKeyRegistry {
+    /// Create an empty registry (trusts nothing)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust `key_id` for every agent
+    pub fn trust(&self, key_id: impl Into<String>) {
+        self.write().insert(key_id.into(), None);
+    }
+
+    /// Trust `key_id` for the given agents only (adds to any existing set)
+    pub fn trust_for(
+        &self,
+        key_id: impl Into<String>,
+        agents: impl IntoIterator<Item = AgentId>,
+    ) {
+        let mut keys = self.write();
+        let entry = keys.entry(key_id.into()).or_insert_with(|| Some(HashSet::new()));
+        if let Some(set) = entry {
+            set.extend(agents);
+        }
+    }
+
+    /// Stop trusting `key_id`
+    pub fn revoke(&self, key_id: &str) {
+        self.write().remove(key_id);
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, KeyTable> {
+        self.keys.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl KeyRegistry for InMemoryKeyRegistry {
+    fn is_trusted(&self, key_id: &str, agent_id: AgentId) -> bool {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        match keys.get(key_id) {
+            Some(None) => true,
+            Some(Some(agents)) => agents.contains(&agent_id),
+            None => false,
+        }
+    }
+}
+
+/// Verifies event signatures against a key registry
+#[derive(Clone)]
+pub struct EventVerifier {
+    registry: Arc<dyn KeyRegistry>,
+}
+
+impl EventVerifier {
+    /// Create a verifier backed by `registry`
+    pub fn new(registry: Arc<dyn KeyRegistry>) -> Self {
+        Self { registry }
+    }
+
+    /// Check that `payload` was signed by a key trusted for `agent_id`
+    pub fn verify(
+        &self,
+        headers: Option<&async_nats::HeaderMap>,
+        payload: &[u8],
+        agent_id: AgentId,
+    ) -> SignatureResult<()> {
+        let header = |name: &str| {
+            headers
+                .and_then(|h| h.get(name))
+                .map(|v| v.as_str().to_string())
+        };
+        let (Some(key_id), Some(signature)) = (header(KEY_ID_HEADER), header(SIGNATURE_HEADER))
+        else {
+            return Err(SignatureError::Unsigned);
+        };
+        self.verify_detached(&key_id, &signature, payload, agent_id)
+    }
+
+    /// Check a detached signature carried outside message headers
+    pub fn verify_detached(
+        &self,
+        key_id: &str,
+        signature: &str,
+        payload: &[u8],
+        agent_id: AgentId,
+    ) -> SignatureResult<()> {
+        if !self.registry.is_trusted(key_id, agent_id) {
+            return Err(SignatureError::Untrusted {
+                key_id: key_id.to_string(),
+                agent_id,
+            });
+        }
+
+        let public = KeyPair::from_public_key(key_id)
+            .map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        let signature = from_hex(signature)
+            .ok_or_else(|| SignatureError::Malformed("signature is not hex".to_string()))?;
+        public
+            .verify(payload, &signature)
+            .map_err(|_| SignatureError::Invalid)
+    }
+}
+
+impl fmt::Debug for EventVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventVerifier").finish_non_exhaustive()
+    }
+}
+
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(super) fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(signer: &EventSigner, payload: &[u8]) -> async_nats::HeaderMap {
+        let mut headers = async_nats::HeaderMap::new();
+        signer.sign_headers(&mut headers, payload).unwrap();
+        headers
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = EventSigner::generate();
+        let registry = Arc::new(InMemoryKeyRegistry::new());
+        registry.trust(signer.key_id());
+        let verifier = EventVerifier::new(registry);
+
+        let payload = br#"{"type":"AgentActivated"}"#;
+        let headers = signed(&signer, payload);
+        assert!(verifier.verify(Some(&headers), payload, AgentId::new()).is_ok());
+        assert_eq!(
+            verifier.verify(Some(&headers), b"tampered", AgentId::new()),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_untrusted_and_unsigned_events_rejected() {
+        let signer = EventSigner::generate();
+        let agent = AgentId::new();
+        let registry = Arc::new(InMemoryKeyRegistry::new());
+        registry.trust_for(signer.key_id(), [agent]);
+        let verifier = EventVerifier::new(registry);
+
+        let headers = signed(&signer, b"payload");
+        assert!(verifier.verify(Some(&headers), b"payload", agent).is_ok());
+        assert!(matches!(
+            verifier.verify(Some(&headers), b"payload", AgentId::new()),
+            Err(SignatureError::Untrusted { .. })
+        ));
+        assert_eq!(
+            verifier.verify(None, b"payload", agent),
+            Err(SignatureError::Unsigned)
+        );
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = vec![0x00, 0x7f, 0xff, 0x10];
+        assert_eq!(from_hex(&to_hex(&bytes)), Some(bytes));
+        assert_eq!(from_hex("abc"), None);
+    }
+}
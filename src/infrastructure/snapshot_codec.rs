@@ -0,0 +1,532 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Snapshot encoding
+//!
+//! Snapshots can carry sensitive configuration (system prompts, provider
+//! settings), so the bytes a snapshot store writes can be compressed with
+//! zstd and sealed with AES-256-GCM. Every encoded snapshot starts with a
+//! header saying how to read it back:
+//!
+//! ```text
+//! ┌────────┬─────────┬───────┬────────────┬────────┬───────┬──────────────┐
+//! │ "CIMS" │ version │ flags │ key id len │ key id │ nonce │ body         │
+//! │ 4 B    │ 1 B     │ 1 B   │ 1 B        │ ≤255 B │ 12 B  │ (zstd) JSON  │
+//! └────────┴─────────┴───────┴────────────┴────────┴───────┴──────────────┘
+//!                       │      └─────── only when encrypted ───────┘
+//!                       └─ 0x01 zstd, 0x02 AES-256-GCM
+//! ```
+//!
+//! The key is chosen by a [`SnapshotKeyring`] for the tenant the codec is
+//! scoped to ([`SnapshotCodec::with_key_scope`]), never for a tenant named by
+//! the command being handled. The header is authenticated along with the
+//! body, and snapshots written before headers existed (plain JSON) still
+//! decode. Decompressed bodies are capped, so a small snapshot cannot expand
+//! without bound.
+//!
+//! Compression needs the `snapshot-compression` feature and encryption the
+//! `snapshot-encryption` feature; without them, snapshots using either are
+//! refused rather than misread.
+
+use super::{DomainError, DomainResult};
+#[cfg(feature = "snapshot-encryption")]
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+#[cfg(feature = "snapshot-encryption")]
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// First bytes of every encoded snapshot
+pub const SNAPSHOT_MAGIC: &[u8; 4] = b"CIMS";
+
+/// Header layout written by this codec
+pub const SNAPSHOT_FORMAT_VERSION: u8 = 1;
+
+/// Default zstd level, favouring speed
+pub const DEFAULT_SNAPSHOT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Largest snapshot body decompression may produce, unless overridden
+pub const DEFAULT_MAX_SNAPSHOT_BYTES: usize = 64 * 1024 * 1024;
+
+const FLAG_ZSTD: u8 = 0x01;
+const FLAG_AES_GCM: u8 = 0x02;
+const NONCE_BYTES: usize = 12;
+
+/// A 256-bit snapshot encryption key and the id recorded in headers
+#[derive(Clone)]
+pub struct SnapshotEncryptionKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl SnapshotEncryptionKey {
+    /// Key `id` with the given bytes; ids longer than 255 bytes are rejected
+    pub fn new(id: impl Into<String>, key: [u8; 32]) -> DomainResult<Self> {
+        let id = id.into();
+        if id.is_empty() || id.len() > u8::MAX as usize {
+            return Err(codec_error(format!(
+                "Snapshot key id must be 1-255 bytes, got {}",
+                id.len()
+            )));
+        }
+        Ok(Self { id, key })
+    }
+
+    /// A fresh random key
+    #[cfg(feature = "snapshot-encryption")]
+    pub fn generate(id: impl Into<String>) -> DomainResult<Self> {
+        let key = Aes256Gcm::generate_key(OsRng);
+        Self::new(id, key.into())
+    }
+
+    /// Id recorded in the headers of snapshots sealed with this key
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[cfg(feature = "snapshot-encryption")]
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key))
+    }
+}
+
+impl fmt::Debug for SnapshotEncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapshotEncryptionKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decides which key seals each tenant's snapshots
+pub trait SnapshotKeyring: Send + Sync {
+    /// Key for writing a snapshot of `tenant` (None = no tenant);
+    /// `None` writes the snapshot unencrypted
+    fn key_for_tenant(&self, tenant: Option<&str>) -> Option<SnapshotEncryptionKey>;
+
+    /// Key named in a snapshot header, for reading
+    fn key(&self, key_id: &str) -> Option<SnapshotEncryptionKey>;
+}
+
+#[derive(Debug, Default)]
+struct Keys {
+    keys: HashMap<String, SnapshotEncryptionKey>,
+    tenants: HashMap<String, String>,
+    default: Option<String>,
+}
+
+/// In-memory keyring
+///
+/// Tenants map to key ids; tenants without their own key use the default
+/// key, if one is set. Retired keys stay available for reading.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySnapshotKeyring {
+    keys: Arc<RwLock<Keys>>,
+}
+
+impl InMemorySnapshotKeyring {
+    /// Create an empty keyring (encrypts nothing)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `key` available for reading and assignment
+    pub fn add_key(&self, key: SnapshotEncryptionKey) {
+        self.write().keys.insert(key.id.clone(), key);
+    }
+
+    /// Seal `tenant`'s snapshots with `key_id`
+    pub fn assign(&self, tenant: impl Into<String>, key_id: impl Into<String>) {
+        self.write().tenants.insert(tenant.into(), key_id.into());
+    }
+
+    /// Seal snapshots of unassigned tenants, and without a tenant, with `key_id`
+    pub fn set_default(&self, key_id: impl Into<String>) {
+        self.write().default = Some(key_id.into());
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Keys> {
+        self.keys.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SnapshotKeyring for InMemorySnapshotKeyring {
+    fn key_for_tenant(&self, tenant: Option<&str>) -> Option<SnapshotEncryptionKey> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        let key_id = tenant
+            .and_then(|t| keys.tenants.get(t))
+            .or(keys.default.as_ref())?;
+        keys.keys.get(key_id).cloned()
+    }
+
+    fn key(&self, key_id: &str) -> Option<SnapshotEncryptionKey> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        keys.keys.get(key_id).cloned()
+    }
+}
+
+/// Encodes snapshots for storage and decodes them back
+#[derive(Clone)]
+pub struct SnapshotCodec {
+    compression: Option<i32>,
+    keyring: Option<Arc<dyn SnapshotKeyring>>,
+    key_scope: Option<String>,
+    max_decoded_bytes: usize,
+}
+
+impl SnapshotCodec {
+    /// Codec writing headed, uncompressed, unencrypted JSON
+    pub fn new() -> Self {
+        Self {
+            compression: None,
+            keyring: None,
+            key_scope: None,
+            max_decoded_bytes: DEFAULT_MAX_SNAPSHOT_BYTES,
+        }
+    }
+
+    /// Builder: compress with zstd at [`DEFAULT_SNAPSHOT_COMPRESSION_LEVEL`]
+    #[cfg(feature = "snapshot-compression")]
+    pub fn with_compression(self) -> Self {
+        self.with_compression_level(DEFAULT_SNAPSHOT_COMPRESSION_LEVEL)
+    }
+
+    /// Builder: compress with zstd at `level`
+    #[cfg(feature = "snapshot-compression")]
+    pub fn with_compression_level(mut self, level: i32) -> Self {
+        self.compression = Some(level);
+        self
+    }
+
+    /// Builder: encrypt with the key `keyring` gives the codec's key scope
+    #[cfg(feature = "snapshot-encryption")]
+    pub fn with_encryption(mut self, keyring: Arc<dyn SnapshotKeyring>) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    /// Builder: seal [`encode`](Self::encode)d snapshots with `tenant`'s key
+    ///
+    /// Without a scope the keyring's default key is used.
+    pub fn with_key_scope(mut self, tenant: impl Into<String>) -> Self {
+        self.key_scope = Some(tenant.into());
+        self
+    }
+
+    /// Builder: refuse snapshots whose body decompresses past `max_bytes`
+    pub fn with_max_decoded_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_decoded_bytes = max_bytes;
+        self
+    }
+
+    /// Encode `snapshot` with the key of the configured key scope
+    pub fn encode<T: Serialize>(&self, snapshot: &T) -> DomainResult<Vec<u8>> {
+        self.encode_for_tenant(snapshot, self.key_scope.as_deref())
+    }
+
+    /// Encode `snapshot` with `tenant`'s key
+    pub fn encode_for_tenant<T: Serialize>(
+        &self,
+        snapshot: &T,
+        tenant: Option<&str>,
+    ) -> DomainResult<Vec<u8>> {
+        let json = serde_json::to_vec(snapshot)
+            .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+        let key = self
+            .keyring
+            .as_ref()
+            .and_then(|keyring| keyring.key_for_tenant(tenant));
+
+        let mut flags = 0;
+        let body = match self.compression {
+            Some(level) => {
+                flags |= FLAG_ZSTD;
+                compress(&json, level)?
+            }
+            None => json,
+        };
+
+        let mut out = Vec::with_capacity(body.len() + 64);
+        out.extend_from_slice(SNAPSHOT_MAGIC);
+        out.push(SNAPSHOT_FORMAT_VERSION);
+        let Some(key) = key else {
+            out.push(flags);
+            out.extend_from_slice(&body);
+            return Ok(out);
+        };
+
+        out.push(flags | FLAG_AES_GCM);
+        out.push(key.id.len() as u8);
+        out.extend_from_slice(key.id.as_bytes());
+        seal_body(&key, &body, &mut out)?;
+        Ok(out)
+    }
+
+    /// Decode a snapshot written by any codec, or plain JSON
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> DomainResult<T> {
+        let json = match bytes.strip_prefix(SNAPSHOT_MAGIC) {
+            Some(rest) => self.open(bytes, rest)?,
+            None => bytes.to_vec(),
+        };
+        serde_json::from_slice(&json).map_err(|e| DomainError::SerializationError(e.to_string()))
+    }
+
+    /// Header of an encoded snapshot, or `None` for plain JSON
+    pub fn header(bytes: &[u8]) -> DomainResult<Option<SnapshotHeader>> {
+        match bytes.strip_prefix(SNAPSHOT_MAGIC) {
+            Some(rest) => parse_header(rest).map(|(header, _)| Some(header)),
+            None => Ok(None),
+        }
+    }
+
+    fn open(&self, bytes: &[u8], rest: &[u8]) -> DomainResult<Vec<u8>> {
+        let (header, body_at) = parse_header(rest)?;
+        let body = &rest[body_at..];
+        let body = match &header.key_id {
+            Some(key_id) => {
+                let key = self
+                    .keyring
+                    .as_ref()
+                    .and_then(|keyring| keyring.key(key_id))
+                    .ok_or_else(|| codec_error(format!("Unknown snapshot key {}", key_id)))?;
+                open_body(&key, &bytes[..SNAPSHOT_MAGIC.len() + body_at], body)?
+            }
+            None => body.to_vec(),
+        };
+        if header.compressed {
+            decompress(&body, self.max_decoded_bytes)
+        } else {
+            Ok(body)
+        }
+    }
+}
+
+impl Default for SnapshotCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SnapshotCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapshotCodec")
+            .field("compression", &self.compression)
+            .field("encrypted", &self.keyring.is_some())
+            .field("key_scope", &self.key_scope)
+            .finish()
+    }
+}
+
+/// What an encoded snapshot's header says about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotHeader {
+    /// Format version
+    pub version: u8,
+
+    /// Whether the body is zstd-compressed
+    pub compressed: bool,
+
+    /// Key the body is sealed with, when encrypted
+    pub key_id: Option<String>,
+}
+
+/// Parse the header after the magic; returns it and the body offset
+fn parse_header(rest: &[u8]) -> DomainResult<(SnapshotHeader, usize)> {
+    let truncated = || codec_error("Snapshot header is truncated");
+    let (&version, &flags) = match rest {
+        [version, flags, ..] => (version, flags),
+        _ => return Err(truncated()),
+    };
+    if version != SNAPSHOT_FORMAT_VERSION {
+        return Err(codec_error(format!(
+            "Unsupported snapshot format version {}",
+            version
+        )));
+    }
+    if flags & !(FLAG_ZSTD | FLAG_AES_GCM) != 0 {
+        return Err(codec_error(format!(
+            "Unknown snapshot flags {:#04x}",
+            flags
+        )));
+    }
+
+    let mut at = 2;
+    let key_id = if flags & FLAG_AES_GCM != 0 {
+        let len = *rest.get(at).ok_or_else(truncated)? as usize;
+        at += 1;
+        let id = rest.get(at..at + len).ok_or_else(truncated)?;
+        let id = String::from_utf8(id.to_vec()).map_err(codec_error)?;
+        at += len;
+        rest.get(at..at + NONCE_BYTES).ok_or_else(truncated)?;
+        at += NONCE_BYTES;
+        Some(id)
+    } else {
+        None
+    };
+
+    let header = SnapshotHeader {
+        version,
+        compressed: flags & FLAG_ZSTD != 0,
+        key_id,
+    };
+    Ok((header, at))
+}
+
+#[cfg(feature = "snapshot-compression")]
+fn compress(json: &[u8], level: i32) -> DomainResult<Vec<u8>> {
+    zstd::bulk::compress(json, level).map_err(codec_error)
+}
+
+#[cfg(not(feature = "snapshot-compression"))]
+fn compress(_json: &[u8], _level: i32) -> DomainResult<Vec<u8>> {
+    Err(unsupported("snapshot-compression"))
+}
+
+/// Decompress `body`, failing once the output passes `max_bytes`
+#[cfg(feature = "snapshot-compression")]
+fn decompress(body: &[u8], max_bytes: usize) -> DomainResult<Vec<u8>> {
+    use std::io::Read;
+
+    let decoder = zstd::stream::read::Decoder::new(body).map_err(codec_error)?;
+    let mut out = Vec::new();
+    decoder
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(codec_error)?;
+    if out.len() > max_bytes {
+        return Err(codec_error(format!(
+            "Snapshot decompresses past {} bytes",
+            max_bytes
+        )));
+    }
+    Ok(out)
+}
+
+#[cfg(not(feature = "snapshot-compression"))]
+fn decompress(_body: &[u8], _max_bytes: usize) -> DomainResult<Vec<u8>> {
+    Err(unsupported("snapshot-compression"))
+}
+
+/// Append a fresh nonce and `body` sealed under `out` (the header so far)
+#[cfg(feature = "snapshot-encryption")]
+fn seal_body(key: &SnapshotEncryptionKey, body: &[u8], out: &mut Vec<u8>) -> DomainResult<()> {
+    let nonce = Aes256Gcm::generate_nonce(OsRng);
+    out.extend_from_slice(&nonce);
+    let sealed = key
+        .cipher()
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: body,
+                aad: out.as_slice(),
+            },
+        )
+        .map_err(|_| codec_error("Snapshot encryption failed"))?;
+    out.extend_from_slice(&sealed);
+    Ok(())
+}
+
+#[cfg(not(feature = "snapshot-encryption"))]
+fn seal_body(_key: &SnapshotEncryptionKey, _body: &[u8], _out: &mut Vec<u8>) -> DomainResult<()> {
+    Err(unsupported("snapshot-encryption"))
+}
+
+/// Authenticate and decrypt `body` against `header`, which ends in the nonce
+#[cfg(feature = "snapshot-encryption")]
+fn open_body(key: &SnapshotEncryptionKey, header: &[u8], body: &[u8]) -> DomainResult<Vec<u8>> {
+    let nonce = Nonce::from_slice(&header[header.len() - NONCE_BYTES..]);
+    key.cipher()
+        .decrypt(
+            nonce,
+            Payload {
+                msg: body,
+                aad: header,
+            },
+        )
+        .map_err(|_| codec_error("Snapshot failed authentication"))
+}
+
+#[cfg(not(feature = "snapshot-encryption"))]
+fn open_body(_key: &SnapshotEncryptionKey, _header: &[u8], _body: &[u8]) -> DomainResult<Vec<u8>> {
+    Err(unsupported("snapshot-encryption"))
+}
+
+#[cfg(not(all(feature = "snapshot-compression", feature = "snapshot-encryption")))]
+fn unsupported(feature: &str) -> DomainError {
+    codec_error(format!("Built without the {} feature", feature))
+}
+
+fn codec_error(e: impl fmt::Display) -> DomainError {
+    DomainError::SnapshotStoreError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[cfg(all(feature = "snapshot-compression", feature = "snapshot-encryption"))]
+    #[test]
+    fn test_round_trip_with_tenant_keys() {
+        let keyring = Arc::new(InMemorySnapshotKeyring::new());
+        keyring.add_key(SnapshotEncryptionKey::generate("acme-2025").unwrap());
+        keyring.add_key(SnapshotEncryptionKey::generate("shared").unwrap());
+        keyring.assign("acme", "acme-2025");
+        keyring.set_default("shared");
+        let codec = SnapshotCodec::new()
+            .with_compression()
+            .with_encryption(keyring.clone());
+        let snapshot = json!({"system_prompt": "secret ".repeat(100), "version": 7});
+
+        let sealed = codec.encode_for_tenant(&snapshot, Some("acme")).unwrap();
+        let header = SnapshotCodec::header(&sealed).unwrap().unwrap();
+        assert!(header.compressed);
+        assert_eq!(header.key_id.as_deref(), Some("acme-2025"));
+        assert!(!sealed.windows(6).any(|w| w == b"secret"));
+        assert_eq!(codec.decode::<Value>(&sealed).unwrap(), snapshot);
+
+        let other = codec.encode_for_tenant(&snapshot, Some("globex")).unwrap();
+        let header = SnapshotCodec::header(&other).unwrap().unwrap();
+        assert_eq!(header.key_id.as_deref(), Some("shared"));
+
+        // encode seals with the configured scope's key
+        let scoped = codec.clone().with_key_scope("acme").encode(&snapshot).unwrap();
+        let header = SnapshotCodec::header(&scoped).unwrap().unwrap();
+        assert_eq!(header.key_id.as_deref(), Some("acme-2025"));
+
+        // Tampering and missing keys are refused
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(codec.decode::<Value>(&tampered).is_err());
+        assert!(SnapshotCodec::new().decode::<Value>(&sealed).is_err());
+    }
+
+    #[cfg(feature = "snapshot-compression")]
+    #[test]
+    fn test_decompression_is_capped() {
+        let codec = SnapshotCodec::new().with_compression();
+        let snapshot = json!({"system_prompt": "x".repeat(10_000)});
+        let encoded = codec.encode(&snapshot).unwrap();
+
+        assert_eq!(codec.decode::<Value>(&encoded).unwrap(), snapshot);
+        let capped = codec.with_max_decoded_bytes(1024);
+        assert!(capped.decode::<Value>(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_plain_and_legacy_snapshots_decode() {
+        let codec = SnapshotCodec::new();
+        let snapshot = json!({"version": 1});
+
+        let plain = codec.encode(&snapshot).unwrap();
+        assert!(plain.starts_with(SNAPSHOT_MAGIC));
+        assert_eq!(SnapshotCodec::header(&plain).unwrap().unwrap().key_id, None);
+        assert_eq!(codec.decode::<Value>(&plain).unwrap(), snapshot);
+
+        let legacy = serde_json::to_vec(&snapshot).unwrap();
+        assert_eq!(SnapshotCodec::header(&legacy).unwrap(), None);
+        assert_eq!(codec.decode::<Value>(&legacy).unwrap(), snapshot);
+    }
+}
@@ -2,7 +2,8 @@
 
 //! Snapshot store trait and implementations
 
-use super::{Agent, AgentId, DomainResult};
+use super::{Agent, AgentId, DomainError, DomainResult, SnapshotCodec};
+use async_nats::jetstream::{self, kv::Store as KvStore};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -94,6 +95,94 @@ impl SnapshotStore for InMemorySnapshotStore {
     }
 }
 
+/// NATS KV snapshot store, keeping each agent's latest snapshot
+///
+/// Snapshots are written through a [`SnapshotCodec`], plain by default;
+/// give it one with compression or encryption to protect stored agents'
+/// system prompts and provider settings.
+pub struct NatsSnapshotStore {
+    kv: KvStore,
+    codec: SnapshotCodec,
+}
+
+impl NatsSnapshotStore {
+    /// Store snapshots in `kv`, keyed by agent ID
+    pub fn new(kv: KvStore) -> Self {
+        Self {
+            kv,
+            codec: SnapshotCodec::new(),
+        }
+    }
+
+    /// Builder: encode snapshots with `codec`
+    pub fn with_codec(mut self, codec: SnapshotCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Create or get the KV bucket for agent snapshots
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+}
+
+fn snapshot_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::SnapshotStoreError(e.to_string())
+}
+
+#[async_trait]
+impl SnapshotStore for NatsSnapshotStore {
+    async fn save_snapshot(&self, snapshot: Snapshot) -> DomainResult<()> {
+        let payload = self.codec.encode(&snapshot)?;
+        self.kv
+            .put(snapshot.aggregate_id.to_string(), payload.into())
+            .await
+            .map_err(snapshot_error)?;
+        Ok(())
+    }
+
+    async fn get_latest_snapshot(&self, aggregate_id: AgentId) -> DomainResult<Option<Snapshot>> {
+        match self.kv.get(aggregate_id.to_string()).await {
+            Ok(Some(entry)) => Ok(Some(self.codec.decode(&entry)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(snapshot_error(e)),
+        }
+    }
+
+    async fn delete_snapshots_before(
+        &self,
+        aggregate_id: AgentId,
+        before_version: u64,
+    ) -> DomainResult<()> {
+        // Only the latest snapshot is kept; drop it if it is older
+        if let Some(snapshot) = self.get_latest_snapshot(aggregate_id).await? {
+            if snapshot.version < before_version {
+                self.kv
+                    .delete(aggregate_id.to_string())
+                    .await
+                    .map_err(snapshot_error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
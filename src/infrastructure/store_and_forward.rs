@@ -0,0 +1,629 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Store-and-forward event store for leaf nodes
+//!
+//! Edge sites keep working when the link to the hub drops. While online,
+//! appends go to the hub and are mirrored into the embedded local store.
+//! While offline, commands are applied against the local store and their
+//! events queue in an outbox until [`StoreAndForwardEventStore::sync`]
+//! forwards them:
+//!
+//! ```text
+//!             online                          offline
+//! append ──> hub ──> mirror to local     append ──> local ──> outbox
+//!                                                             │
+//!                          sync (link restored) <─────────────┘
+//!                           │
+//!          hub version == base version ?
+//!            yes: append to hub (expected = base)
+//!            no:  SyncConflict ──> ConflictResolver ──> Rebase | DiscardLocal | Defer
+//! ```
+//!
+//! A conflict means the hub moved past the version the edge last saw: the
+//! agent's history diverged. Resolution is a policy decision, so it is
+//! delegated to a [`ConflictResolver`].
+//!
+//! The outbox is written through to an [`OutboxStore`] when one is
+//! configured; a restarted node calls
+//! [`restore`](StoreAndForwardEventStore::restore) to queue again whatever it
+//! had not forwarded. Without one, the outbox lives in memory only.
+
+use super::{AgentEvent, AgentId, DomainError, DomainResult, EventEnvelope, EventStore};
+use async_nats::jetstream::{self, kv::Store as KvStore};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Events queued for one agent while offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBatch {
+    /// Hub version the queued events were written against
+    pub base_version: u64,
+    /// Queued events, in append order
+    pub events: Vec<AgentEvent>,
+}
+
+/// Divergence between queued local events and the hub
+#[derive(Debug, Clone)]
+pub struct SyncConflict {
+    /// The agent whose history diverged
+    pub aggregate_id: AgentId,
+    /// Version the local events were written against
+    pub base_version: u64,
+    /// Version the hub is at now
+    pub hub_version: u64,
+    /// Events queued locally
+    pub local_events: Vec<AgentEvent>,
+    /// Events the hub accepted after `base_version`
+    pub hub_events: Vec<EventEnvelope>,
+}
+
+/// How to settle a [`SyncConflict`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Append the local events after the hub's
+    Rebase,
+    /// Drop the local events; the hub's history stands
+    ///
+    /// The local store still holds them, so reload the agent from the hub
+    /// before serving further local reads for it.
+    DiscardLocal,
+    /// Keep the events queued and report the conflict
+    Defer,
+}
+
+/// Policy hook for diverged histories
+pub trait ConflictResolver: Send + Sync {
+    /// Decide how to settle a conflict
+    fn resolve(&self, conflict: &SyncConflict) -> Resolution;
+}
+
+impl<F> ConflictResolver for F
+where
+    F: Fn(&SyncConflict) -> Resolution + Send + Sync,
+{
+    fn resolve(&self, conflict: &SyncConflict) -> Resolution {
+        self(conflict)
+    }
+}
+
+/// Fixed resolution applied to every conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConflictPolicy(pub Resolution);
+
+impl ConflictResolver for ConflictPolicy {
+    fn resolve(&self, _conflict: &SyncConflict) -> Resolution {
+        self.0
+    }
+}
+
+/// Outcome of one sync pass
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Events forwarded to the hub
+    pub forwarded: usize,
+    /// Agents rebased onto the hub's history
+    pub rebased: Vec<AgentId>,
+    /// Agents whose queued events were discarded
+    pub discarded: Vec<AgentId>,
+    /// Agents still queued because their conflict was deferred
+    pub deferred: Vec<AgentId>,
+}
+
+/// Durable home for the outbox, so queued events survive a restart
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Every persisted batch
+    async fn load(&self) -> DomainResult<HashMap<AgentId, PendingBatch>>;
+
+    /// Persist an agent's batch, or forget it once nothing is queued
+    async fn save(&self, aggregate_id: AgentId, batch: Option<&PendingBatch>) -> DomainResult<()>;
+}
+
+/// In-memory outbox store, for tests and single-process use
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryOutboxStore {
+    batches: Arc<Mutex<HashMap<AgentId, PendingBatch>>>,
+}
+
+impl InMemoryOutboxStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OutboxStore for InMemoryOutboxStore {
+    async fn load(&self) -> DomainResult<HashMap<AgentId, PendingBatch>> {
+        Ok(self.batches.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    async fn save(&self, aggregate_id: AgentId, batch: Option<&PendingBatch>) -> DomainResult<()> {
+        let mut batches = self.batches.lock().unwrap_or_else(|e| e.into_inner());
+        match batch {
+            Some(batch) => batches.insert(aggregate_id, batch.clone()),
+            None => batches.remove(&aggregate_id),
+        };
+        Ok(())
+    }
+}
+
+/// NATS KV outbox store, keyed by agent ID
+///
+/// Meant for the leaf node's own JetStream domain, which stays reachable
+/// while the hub is not.
+pub struct NatsOutboxStore {
+    kv: KvStore,
+}
+
+impl NatsOutboxStore {
+    /// Create a store over an existing KV bucket
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Create or get the KV bucket; entries never expire
+    ///
+    /// # Arguments
+    ///
+    /// * `jetstream` - JetStream context of the leaf node
+    /// * `bucket_name` - Name of the KV bucket (e.g., "AGENT_OUTBOX")
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+}
+
+fn store_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::EventStoreError(e.to_string())
+}
+
+#[async_trait]
+impl OutboxStore for NatsOutboxStore {
+    async fn load(&self) -> DomainResult<HashMap<AgentId, PendingBatch>> {
+        let mut batches = HashMap::new();
+        let mut keys = self.kv.keys().await.map_err(store_error)?;
+        while let Some(key) = keys.next().await {
+            let key = key.map_err(store_error)?;
+            let Ok(aggregate_id) = key.parse::<uuid::Uuid>().map(AgentId::from_uuid) else {
+                tracing::warn!("Ignoring outbox entry under key {}", key);
+                continue;
+            };
+            let Some(value) = self.kv.get(&key).await.map_err(store_error)? else {
+                continue;
+            };
+            let batch = serde_json::from_slice(&value).map_err(store_error)?;
+            batches.insert(aggregate_id, batch);
+        }
+        Ok(batches)
+    }
+
+    async fn save(&self, aggregate_id: AgentId, batch: Option<&PendingBatch>) -> DomainResult<()> {
+        let key = aggregate_id.to_string();
+        match batch {
+            Some(batch) => {
+                let value = serde_json::to_vec(batch).map_err(store_error)?;
+                self.kv.put(key, value.into()).await.map_err(store_error)?;
+            }
+            None => self.kv.delete(key).await.map_err(store_error)?,
+        }
+        Ok(())
+    }
+}
+
+/// Event store that queues appends while the hub is unreachable
+pub struct StoreAndForwardEventStore {
+    hub: Arc<dyn EventStore>,
+    local: Arc<dyn EventStore>,
+    resolver: Arc<dyn ConflictResolver>,
+    outbox: Mutex<HashMap<AgentId, PendingBatch>>,
+    outbox_store: Option<Arc<dyn OutboxStore>>,
+    // Serializes writes to the outbox store, so the last one holds the latest batch
+    persisting: tokio::sync::Mutex<()>,
+    online: AtomicBool,
+}
+
+impl StoreAndForwardEventStore {
+    /// Create a store that forwards from `local` to `hub`
+    ///
+    /// Conflicts are deferred until a resolver is configured.
+    pub fn new(hub: Arc<dyn EventStore>, local: Arc<dyn EventStore>) -> Self {
+        Self {
+            hub,
+            local,
+            resolver: Arc::new(ConflictPolicy(Resolution::Defer)),
+            outbox: Mutex::new(HashMap::new()),
+            outbox_store: None,
+            persisting: tokio::sync::Mutex::new(()),
+            online: AtomicBool::new(true),
+        }
+    }
+
+    /// Builder: write the outbox through to `store`
+    pub fn with_outbox_store(mut self, store: Arc<dyn OutboxStore>) -> Self {
+        self.outbox_store = Some(store);
+        self
+    }
+
+    /// Queue again the batches persisted before a restart
+    ///
+    /// Call once at startup, before serving appends. Returns the number of
+    /// events queued.
+    pub async fn restore(&self) -> DomainResult<usize> {
+        let Some(store) = &self.outbox_store else {
+            return Ok(0);
+        };
+        let persisted = store.load().await?;
+        let mut outbox = self.lock_outbox();
+        for (aggregate_id, batch) in persisted {
+            outbox.entry(aggregate_id).or_insert(batch);
+        }
+        Ok(outbox.values().map(|b| b.events.len()).sum())
+    }
+
+    /// Builder: set the conflict resolution policy
+    pub fn with_resolver(mut self, resolver: impl ConflictResolver + 'static) -> Self {
+        self.resolver = Arc::new(resolver);
+        self
+    }
+
+    /// Whether appends currently go to the hub
+    pub fn is_online(&self) -> bool {
+        self.online.load(Ordering::SeqCst)
+    }
+
+    /// Force offline mode (or clear it; the next successful sync also does)
+    pub fn set_online(&self, online: bool) {
+        self.online.store(online, Ordering::SeqCst);
+    }
+
+    /// Events queued for forwarding, per agent
+    pub fn pending(&self) -> HashMap<AgentId, PendingBatch> {
+        self.lock_outbox().clone()
+    }
+
+    /// Number of queued events across all agents
+    pub fn pending_count(&self) -> usize {
+        self.lock_outbox().values().map(|b| b.events.len()).sum()
+    }
+
+    /// Forward queued events to the hub
+    ///
+    /// A hub error stops the pass, marks the store offline and leaves the
+    /// remaining events queued. A clean pass marks the store online.
+    pub async fn sync(&self) -> DomainResult<SyncReport> {
+        let mut report = SyncReport::default();
+        let agents: Vec<AgentId> = self.lock_outbox().keys().copied().collect();
+
+        for aggregate_id in agents {
+            let Some(batch) = self.lock_outbox().get(&aggregate_id).cloned() else {
+                continue;
+            };
+            if let Err(e) = self.forward(aggregate_id, batch, &mut report).await {
+                self.set_online(false);
+                return Err(e);
+            }
+        }
+
+        self.set_online(true);
+        Ok(report)
+    }
+
+    /// Run [`sync`](Self::sync) every `interval` while offline or queued
+    ///
+    /// With nothing queued, a pass simply returns to online mode; the next
+    /// append probes the hub and drops back offline if it is still down.
+    pub fn spawn_sync(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if self.is_online() && self.pending_count() == 0 {
+                    continue;
+                }
+                match self.sync().await {
+                    Ok(report) if !report.deferred.is_empty() => tracing::warn!(
+                        "Sync forwarded {} events; {} agents have deferred conflicts",
+                        report.forwarded,
+                        report.deferred.len()
+                    ),
+                    Ok(report) => tracing::info!("Sync forwarded {} events", report.forwarded),
+                    Err(e) => tracing::debug!("Hub unreachable, events stay queued: {}", e),
+                }
+            }
+        })
+    }
+
+    async fn forward(
+        &self,
+        aggregate_id: AgentId,
+        batch: PendingBatch,
+        report: &mut SyncReport,
+    ) -> DomainResult<()> {
+        let hub_version = self.hub.get_current_version(aggregate_id).await?;
+        let count = batch.events.len();
+
+        if hub_version == batch.base_version {
+            self.hub
+                .append_events(aggregate_id, batch.events, Some(hub_version))
+                .await?;
+            self.dequeue(aggregate_id, count).await?;
+            report.forwarded += count;
+            return Ok(());
+        }
+
+        let conflict = SyncConflict {
+            aggregate_id,
+            base_version: batch.base_version,
+            hub_version,
+            hub_events: self
+                .hub
+                .get_events_from_version(aggregate_id, batch.base_version + 1)
+                .await?,
+            local_events: batch.events,
+        };
+
+        match self.resolver.resolve(&conflict) {
+            Resolution::Rebase => {
+                self.hub
+                    .append_events(aggregate_id, conflict.local_events, Some(hub_version))
+                    .await?;
+                self.dequeue(aggregate_id, count).await?;
+                report.forwarded += count;
+                report.rebased.push(aggregate_id);
+            }
+            Resolution::DiscardLocal => {
+                self.dequeue(aggregate_id, count).await?;
+                report.discarded.push(aggregate_id);
+            }
+            Resolution::Defer => report.deferred.push(aggregate_id),
+        }
+        Ok(())
+    }
+
+    /// Remove forwarded events, keeping any queued since the pass began
+    async fn dequeue(&self, aggregate_id: AgentId, count: usize) -> DomainResult<()> {
+        {
+            let mut outbox = self.lock_outbox();
+            if let Some(batch) = outbox.get_mut(&aggregate_id) {
+                batch.events.drain(..count.min(batch.events.len()));
+                if batch.events.is_empty() {
+                    outbox.remove(&aggregate_id);
+                } else {
+                    batch.base_version += count as u64;
+                }
+            }
+        }
+        self.persist(aggregate_id).await
+    }
+
+    /// Write an agent's current batch through to the outbox store
+    async fn persist(&self, aggregate_id: AgentId) -> DomainResult<()> {
+        let Some(store) = &self.outbox_store else {
+            return Ok(());
+        };
+        let _persisting = self.persisting.lock().await;
+        let batch = self.lock_outbox().get(&aggregate_id).cloned();
+        store.save(aggregate_id, batch.as_ref()).await
+    }
+
+    async fn append_offline(
+        &self,
+        aggregate_id: AgentId,
+        events: Vec<AgentEvent>,
+        expected_version: Option<u64>,
+    ) -> DomainResult<()> {
+        let local_version = self.local.get_current_version(aggregate_id).await?;
+        self.local
+            .append_events(aggregate_id, events.clone(), expected_version)
+            .await?;
+
+        self.lock_outbox()
+            .entry(aggregate_id)
+            .or_insert_with(|| PendingBatch {
+                base_version: local_version,
+                events: Vec::new(),
+            })
+            .events
+            .extend(events);
+        self.persist(aggregate_id).await
+    }
+
+    fn lock_outbox(&self) -> std::sync::MutexGuard<'_, HashMap<AgentId, PendingBatch>> {
+        self.outbox.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl EventStore for StoreAndForwardEventStore {
+    async fn append_events(
+        &self,
+        aggregate_id: AgentId,
+        events: Vec<AgentEvent>,
+        expected_version: Option<u64>,
+    ) -> DomainResult<()> {
+        // Queued events must reach the hub first to keep ordering
+        let queued = self.lock_outbox().contains_key(&aggregate_id);
+        if !self.is_online() || queued {
+            return self
+                .append_offline(aggregate_id, events, expected_version)
+                .await;
+        }
+
+        match self
+            .hub
+            .append_events(aggregate_id, events.clone(), expected_version)
+            .await
+        {
+            Ok(()) => self.local.append_events(aggregate_id, events, None).await,
+            Err(DomainError::EventStoreError(e)) => {
+                tracing::warn!("Hub append failed, switching to offline mode: {}", e);
+                self.set_online(false);
+                self.append_offline(aggregate_id, events, expected_version)
+                    .await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_events(&self, aggregate_id: AgentId) -> DomainResult<Vec<EventEnvelope>> {
+        self.local.get_events(aggregate_id).await
+    }
+
+    async fn get_events_from_version(
+        &self,
+        aggregate_id: AgentId,
+        from_version: u64,
+    ) -> DomainResult<Vec<EventEnvelope>> {
+        self.local
+            .get_events_from_version(aggregate_id, from_version)
+            .await
+    }
+
+    async fn get_current_version(&self, aggregate_id: AgentId) -> DomainResult<u64> {
+        self.local.get_current_version(aggregate_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentDeployedEvent};
+    use crate::infrastructure::InMemoryEventStore;
+    use crate::value_objects::PersonId;
+
+    fn deployed(agent_id: AgentId) -> AgentEvent {
+        AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            "edge-agent",
+            None,
+        ))
+    }
+
+    fn activated(agent_id: AgentId) -> AgentEvent {
+        AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id))
+    }
+
+    fn stores() -> (Arc<InMemoryEventStore>, StoreAndForwardEventStore) {
+        let hub = Arc::new(InMemoryEventStore::new());
+        let local = Arc::new(InMemoryEventStore::new());
+        let store = StoreAndForwardEventStore::new(hub.clone(), local);
+        (hub, store)
+    }
+
+    #[tokio::test]
+    async fn test_offline_appends_forward_on_sync() {
+        let (hub, store) = stores();
+        let agent_id = AgentId::new();
+        store
+            .append_events(agent_id, vec![deployed(agent_id)], Some(0))
+            .await
+            .unwrap();
+
+        store.set_online(false);
+        store
+            .append_events(agent_id, vec![activated(agent_id)], Some(1))
+            .await
+            .unwrap();
+        assert_eq!(hub.get_current_version(agent_id).await.unwrap(), 1);
+        assert_eq!(store.pending_count(), 1);
+
+        let report = store.sync().await.unwrap();
+        assert_eq!(report.forwarded, 1);
+        assert!(store.is_online());
+        assert_eq!(hub.get_current_version(agent_id).await.unwrap(), 2);
+        assert_eq!(store.pending_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_divergence_is_deferred_by_default() {
+        let (hub, store) = stores();
+        let agent_id = AgentId::new();
+        store.set_online(false);
+        store
+            .append_events(agent_id, vec![deployed(agent_id)], Some(0))
+            .await
+            .unwrap();
+
+        // Another writer reached the hub first
+        hub.append_events(agent_id, vec![deployed(agent_id)], Some(0))
+            .await
+            .unwrap();
+
+        let report = store.sync().await.unwrap();
+        assert_eq!(report.deferred, vec![agent_id]);
+        assert_eq!(store.pending_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resolver_rebases_onto_hub() {
+        let hub = Arc::new(InMemoryEventStore::new());
+        let local = Arc::new(InMemoryEventStore::new());
+        let store = StoreAndForwardEventStore::new(hub.clone(), local).with_resolver(
+            |conflict: &SyncConflict| {
+                assert_eq!((conflict.base_version, conflict.hub_version), (0, 1));
+                assert_eq!(conflict.hub_events.len(), 1);
+                Resolution::Rebase
+            },
+        );
+        let agent_id = AgentId::new();
+        store.set_online(false);
+        store
+            .append_events(agent_id, vec![activated(agent_id)], None)
+            .await
+            .unwrap();
+        hub.append_events(agent_id, vec![deployed(agent_id)], Some(0))
+            .await
+            .unwrap();
+
+        let report = store.sync().await.unwrap();
+        assert_eq!(report.rebased, vec![agent_id]);
+        assert_eq!(hub.get_current_version(agent_id).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_restarted_node_forwards_persisted_outbox() {
+        let hub = Arc::new(InMemoryEventStore::new());
+        let local = Arc::new(InMemoryEventStore::new());
+        let outbox = Arc::new(InMemoryOutboxStore::new());
+        let store = StoreAndForwardEventStore::new(hub.clone(), local.clone())
+            .with_outbox_store(outbox.clone());
+        let agent_id = AgentId::new();
+        store.set_online(false);
+        store
+            .append_events(agent_id, vec![deployed(agent_id)], Some(0))
+            .await
+            .unwrap();
+        drop(store);
+
+        let restarted =
+            StoreAndForwardEventStore::new(hub.clone(), local).with_outbox_store(outbox.clone());
+        assert_eq!(restarted.restore().await.unwrap(), 1);
+
+        let report = restarted.sync().await.unwrap();
+        assert_eq!(report.forwarded, 1);
+        assert_eq!(hub.get_current_version(agent_id).await.unwrap(), 1);
+        assert!(outbox.load().await.unwrap().is_empty());
+    }
+}
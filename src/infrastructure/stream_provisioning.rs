@@ -0,0 +1,423 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! JetStream stream provisioning
+//!
+//! Declares the streams a deployment needs, with subjects taken from
+//! [`AgentSubjectFactory`] and retention/replica settings from a
+//! [`ProvisioningConfig`], then checks live streams against them:
+//!
+//! ```text
+//! {prefix}_EVENTS    {domain}.events.agent.*.*          lifecycle events (long)
+//!                    {domain}.*.*.*.event.>
+//! {prefix}_COMMANDS  {domain}.commands.agent.*(.*)      work queue, no acks to requesters
+//!                    {domain}.*.*.*.command.>
+//! {prefix}_CHUNKS    {domain}.events.agent.*.message.>  response chunks and their framing
+//!
+//! StreamPlan ──> StreamProvisioner::validate ──> ProvisionReport (missing / drifted)
+//!           └──> StreamProvisioner::apply    ──> create, update, or flag unfixable
+//! ```
+//!
+//! Subjects never overlap between streams, so all three can live in one
+//! account. Consumers are left to the services reading the streams.
+//! Applying is idempotent: settings the plan does not manage (byte limits,
+//! discard policy, ...) are left as they are, and storage or retention
+//! changes, which JetStream cannot make in place, are reported rather than
+//! attempted.
+
+use super::AgentSubjectFactory;
+use async_nats::jetstream::{
+    self,
+    stream::{RetentionPolicy, StorageType},
+};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// Provisioning errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProvisionError {
+    #[error("JetStream request failed for {resource}: {message}")]
+    JetStream { resource: String, message: String },
+}
+
+/// Result type for provisioning
+pub type ProvisionResult<T> = Result<T, ProvisionError>;
+
+/// What a provisioned stream is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamRole {
+    Events,
+    Commands,
+    Chunks,
+}
+
+impl StreamRole {
+    /// All roles, in the order they are applied
+    pub const ALL: [StreamRole; 3] = [StreamRole::Events, StreamRole::Commands, StreamRole::Chunks];
+
+    /// Suffix of the stream name
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            StreamRole::Events => "EVENTS",
+            StreamRole::Commands => "COMMANDS",
+            StreamRole::Chunks => "CHUNKS",
+        }
+    }
+}
+
+/// Retention and replica settings for the provisioned streams
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvisioningConfig {
+    /// Stream names are `{stream_prefix}_{role}`
+    pub stream_prefix: String,
+
+    /// Replicas per stream (1, 3 or 5 in a cluster)
+    pub replicas: usize,
+
+    /// File or memory storage
+    pub storage: StorageType,
+
+    /// How long lifecycle events are kept; zero keeps them until
+    /// `EventRetention` ages them out per agent, sparing legal holds
+    pub event_max_age: Duration,
+
+    /// How long unconsumed commands are kept
+    pub command_max_age: Duration,
+
+    /// How long response chunks are kept for resumption
+    pub chunk_max_age: Duration,
+}
+
+impl Default for ProvisioningConfig {
+    fn default() -> Self {
+        const DAY: u64 = 24 * 60 * 60;
+        Self {
+            stream_prefix: "AGENT".to_string(),
+            replicas: 1,
+            storage: StorageType::File,
+            event_max_age: Duration::ZERO,
+            command_max_age: Duration::from_secs(DAY),
+            chunk_max_age: Duration::from_secs(DAY),
+        }
+    }
+}
+
+impl ProvisioningConfig {
+    /// Builder: name streams `{prefix}_{role}`
+    pub fn with_stream_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.stream_prefix = prefix.into();
+        self
+    }
+
+    /// Builder: replicate every stream `replicas` times
+    pub fn with_replicas(mut self, replicas: usize) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    /// Builder: use `storage` for every stream
+    pub fn with_storage(mut self, storage: StorageType) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Builder: keep messages of `role` for `max_age`
+    pub fn with_max_age(mut self, role: StreamRole, max_age: Duration) -> Self {
+        match role {
+            StreamRole::Events => self.event_max_age = max_age,
+            StreamRole::Commands => self.command_max_age = max_age,
+            StreamRole::Chunks => self.chunk_max_age = max_age,
+        }
+        self
+    }
+}
+
+/// The streams a deployment needs
+#[derive(Debug, Clone)]
+pub struct StreamPlan {
+    streams: Vec<(StreamRole, jetstream::stream::Config)>,
+}
+
+impl StreamPlan {
+    /// Derive the plan for `factory`'s domain from `config`
+    pub fn new(factory: &AgentSubjectFactory, config: &ProvisioningConfig) -> Self {
+        let domain = factory.domain();
+        let name = |role: StreamRole| format!("{}_{}", config.stream_prefix, role.suffix());
+
+        let streams = StreamRole::ALL
+            .iter()
+            .map(|&role| {
+                let (subjects, max_age) = match role {
+                    StreamRole::Events => (
+                        vec![
+                            format!("{}.events.agent.*.*", domain),
+                            format!("{}.*.*.*.event.>", domain),
+                        ],
+                        config.event_max_age,
+                    ),
+                    StreamRole::Commands => (
+                        vec![
+                            format!("{}.commands.agent.*", domain),
+                            format!("{}.commands.agent.*.*", domain),
+                            format!("{}.*.*.*.command.>", domain),
+                        ],
+                        config.command_max_age,
+                    ),
+                    StreamRole::Chunks => (
+                        vec![format!("{}.events.agent.*.message.>", domain)],
+                        config.chunk_max_age,
+                    ),
+                };
+                let stream = jetstream::stream::Config {
+                    name: name(role),
+                    subjects,
+                    max_age,
+                    num_replicas: config.replicas,
+                    storage: config.storage,
+                    retention: match role {
+                        StreamRole::Commands => RetentionPolicy::WorkQueue,
+                        _ => RetentionPolicy::Limits,
+                    },
+                    // Commands arrive by request-reply; a PubAck must not answer them
+                    no_ack: role == StreamRole::Commands,
+                    ..Default::default()
+                };
+                (role, stream)
+            })
+            .collect();
+
+        Self { streams }
+    }
+
+    /// Name of the stream for `role`
+    pub fn stream_name(&self, role: StreamRole) -> &str {
+        self.streams
+            .iter()
+            .find(|(r, _)| *r == role)
+            .map(|(_, config)| config.name.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Declared streams, in the order they are applied
+    pub fn streams(&self) -> &[(StreamRole, jetstream::stream::Config)] {
+        &self.streams
+    }
+}
+
+/// One setting that differs from the plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDrift {
+    /// Setting name, as in the JetStream config
+    pub field: &'static str,
+
+    /// Planned value
+    pub expected: String,
+
+    /// Live value
+    pub actual: String,
+
+    /// Whether JetStream can change it in place
+    pub fixable: bool,
+}
+
+impl ConfigDrift {
+    fn check<T: std::fmt::Debug + PartialEq>(
+        drift: &mut Vec<ConfigDrift>,
+        field: &'static str,
+        expected: T,
+        actual: T,
+        fixable: bool,
+    ) {
+        if expected != actual {
+            drift.push(ConfigDrift {
+                field,
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", actual),
+                fixable,
+            });
+        }
+    }
+}
+
+/// Planned settings that differ in a live stream
+pub fn stream_drift(
+    expected: &jetstream::stream::Config,
+    actual: &jetstream::stream::Config,
+) -> Vec<ConfigDrift> {
+    let subjects = |c: &jetstream::stream::Config| c.subjects.iter().cloned().collect();
+    let mut drift = Vec::new();
+    ConfigDrift::check::<BTreeSet<String>>(
+        &mut drift,
+        "subjects",
+        subjects(expected),
+        subjects(actual),
+        true,
+    );
+    ConfigDrift::check(&mut drift, "max_age", expected.max_age, actual.max_age, true);
+    let (replicas, live_replicas) = (expected.num_replicas, actual.num_replicas);
+    ConfigDrift::check(&mut drift, "num_replicas", replicas, live_replicas, true);
+    ConfigDrift::check(&mut drift, "no_ack", expected.no_ack, actual.no_ack, true);
+    ConfigDrift::check(&mut drift, "storage", expected.storage, actual.storage, false);
+    ConfigDrift::check(&mut drift, "retention", expected.retention, actual.retention, false);
+    drift
+}
+
+/// State of one stream, before or after applying
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceStatus {
+    /// Matches the plan
+    InSync,
+
+    /// Does not exist (validation only)
+    Missing,
+
+    /// Differs from the plan (validation only)
+    Drifted(Vec<ConfigDrift>),
+
+    /// Was created
+    Created,
+
+    /// Was brought back in line
+    Updated(Vec<ConfigDrift>),
+
+    /// Differs in ways JetStream cannot change in place
+    Unfixable(Vec<ConfigDrift>),
+}
+
+/// Outcome of validating or applying a plan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProvisionReport {
+    /// Status per stream name
+    pub resources: Vec<(String, ResourceStatus)>,
+}
+
+impl ProvisionReport {
+    /// Whether everything matches the plan (after any changes applied)
+    pub fn is_in_sync(&self) -> bool {
+        self.resources.iter().all(|(_, status)| {
+            matches!(
+                status,
+                ResourceStatus::InSync | ResourceStatus::Created | ResourceStatus::Updated(_)
+            )
+        })
+    }
+}
+
+/// Creates and validates the streams of a [`StreamPlan`]
+pub struct StreamProvisioner {
+    jetstream: jetstream::Context,
+    plan: StreamPlan,
+}
+
+impl StreamProvisioner {
+    /// Provision `plan` through `jetstream`
+    pub fn new(jetstream: jetstream::Context, plan: StreamPlan) -> Self {
+        Self { jetstream, plan }
+    }
+
+    /// The plan being provisioned
+    pub fn plan(&self) -> &StreamPlan {
+        &self.plan
+    }
+
+    /// Compare live streams with the plan, changing nothing
+    pub async fn validate(&self) -> ProvisionResult<ProvisionReport> {
+        self.run(false).await
+    }
+
+    /// Create what is missing and fix what drifted
+    pub async fn apply(&self) -> ProvisionResult<ProvisionReport> {
+        self.run(true).await
+    }
+
+    async fn run(&self, fix: bool) -> ProvisionResult<ProvisionReport> {
+        let mut report = ProvisionReport::default();
+
+        for (_, expected) in self.plan.streams() {
+            let status = match self.jetstream.get_stream(&expected.name).await {
+                Err(_) if !fix => ResourceStatus::Missing,
+                Err(_) => {
+                    self.jetstream
+                        .create_stream(expected.clone())
+                        .await
+                        .map_err(|e| failed(&expected.name, e))?;
+                    ResourceStatus::Created
+                }
+                Ok(stream) => {
+                    let actual = &stream.cached_info().config;
+                    let drift = stream_drift(expected, actual);
+                    if drift.is_empty() {
+                        ResourceStatus::InSync
+                    } else if drift.iter().any(|d| !d.fixable) {
+                        ResourceStatus::Unfixable(drift)
+                    } else if !fix {
+                        ResourceStatus::Drifted(drift)
+                    } else {
+                        let updated = jetstream::stream::Config {
+                            subjects: expected.subjects.clone(),
+                            max_age: expected.max_age,
+                            num_replicas: expected.num_replicas,
+                            no_ack: expected.no_ack,
+                            ..actual.clone()
+                        };
+                        self.jetstream
+                            .update_stream(updated)
+                            .await
+                            .map_err(|e| failed(&expected.name, e))?;
+                        ResourceStatus::Updated(drift)
+                    }
+                }
+            };
+            report.resources.push((expected.name.clone(), status));
+        }
+
+        Ok(report)
+    }
+}
+
+fn failed(resource: &str, e: impl std::fmt::Display) -> ProvisionError {
+    ProvisionError::JetStream {
+        resource: resource.to_string(),
+        message: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_derives_streams_from_config() {
+        let config = ProvisioningConfig::default()
+            .with_replicas(3)
+            .with_max_age(StreamRole::Chunks, Duration::from_secs(3600));
+        let plan = StreamPlan::new(&AgentSubjectFactory::default(), &config);
+
+        assert_eq!(plan.stream_name(StreamRole::Commands), "AGENT_COMMANDS");
+        let (_, chunks) = &plan.streams()[2];
+        assert_eq!(chunks.subjects, vec!["agent.events.agent.*.message.>".to_string()]);
+        assert_eq!(chunks.max_age, Duration::from_secs(3600));
+        assert!(plan.streams().iter().all(|(_, s)| s.num_replicas == 3));
+
+        let (_, commands) = &plan.streams()[1];
+        assert_eq!(commands.retention, RetentionPolicy::WorkQueue);
+        assert!(commands.no_ack);
+        assert_eq!(plan.streams().len(), 3);
+    }
+
+    #[test]
+    fn test_drift_separates_fixable_settings() {
+        let plan = StreamPlan::new(&AgentSubjectFactory::default(), &ProvisioningConfig::default());
+        let (_, expected) = &plan.streams()[0];
+        assert!(stream_drift(expected, expected).is_empty());
+
+        // A hand-made stream from before provisioning
+        let legacy = jetstream::stream::Config {
+            subjects: vec!["agent.events.>".to_string(), "agent.commands.>".to_string()],
+            storage: StorageType::Memory,
+            ..expected.clone()
+        };
+        let drift = stream_drift(expected, &legacy);
+        let fields: Vec<(&str, bool)> = drift.iter().map(|d| (d.field, d.fixable)).collect();
+        assert_eq!(fields, vec![("subjects", true), ("storage", false)]);
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Resumable response streams
+//!
+//! Every response chunk is published to JetStream on
+//! `{domain}.events.agent.{agent_id}.message.{message_id}.chunk.{index}`,
+//! so the stream already holds a durable, per-message copy of the response.
+//! A client that reconnects mid-response can resume from the last chunk it
+//! saw instead of losing the beginning of the answer.
+//!
+//! ```text
+//! ResumeStream { message_id, from_index }
+//!       │
+//!       v
+//! ephemeral consumer on {domain}.events.agent.{id}.message.{mid}.>
+//!       │   (DeliverPolicy::All)
+//!       v
+//! ResumeCursor ── skips index < from_index and duplicates
+//!       │
+//!       v
+//! stored chunks ──> live chunks ──> ends at final chunk / completed / failed / cancelled
+//! ```
+
+use super::{decode_envelope_if, AgentSubjectFactory, DomainError, DomainResult};
+use crate::events::AgentEvent;
+use crate::value_objects::{AgentId, MessageId, StreamingChunk};
+use async_nats::jetstream;
+use futures::{stream, Stream, StreamExt};
+use std::pin::Pin;
+
+/// Stream of replayed-then-live response chunks
+pub type ResumedChunkStream = Pin<Box<dyn Stream<Item = DomainResult<StreamingChunk>> + Send>>;
+
+/// Outcome of feeding one stored event to a [`ResumeCursor`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeStep {
+    /// Event is not needed by the resuming client
+    Skip,
+    /// Deliver this chunk
+    Chunk(StreamingChunk),
+    /// The response is over
+    End,
+}
+
+/// Pure replay state for a single message's response
+///
+/// Chunks arrive from JetStream in publish order; the cursor filters out
+/// chunks the client already has and stops at the terminal event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeCursor {
+    next_index: u32,
+    finished: bool,
+}
+
+impl ResumeCursor {
+    /// Start delivering at `from_index`
+    pub fn new(from_index: u32) -> Self {
+        Self {
+            next_index: from_index,
+            finished: false,
+        }
+    }
+
+    /// Check if the response has ended
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Index of the next chunk the client expects
+    pub fn next_index(&self) -> u32 {
+        self.next_index
+    }
+
+    /// Feed one stored event
+    pub fn accept(&mut self, event: &AgentEvent) -> ResumeStep {
+        if self.finished {
+            return ResumeStep::End;
+        }
+
+        match event {
+            AgentEvent::ResponseChunkReceived(e) => {
+                if e.chunk.chunk_index < self.next_index {
+                    return ResumeStep::Skip;
+                }
+                self.next_index = e.chunk.chunk_index + 1;
+                self.finished = e.chunk.is_final;
+                ResumeStep::Chunk(e.chunk.clone())
+            }
+            AgentEvent::ResponseCompleted(_)
+            | AgentEvent::ResponseFailed(_)
+            | AgentEvent::ResponseCancelled(_) => {
+                self.finished = true;
+                ResumeStep::End
+            }
+            _ => ResumeStep::Skip,
+        }
+    }
+}
+
+/// Event types the cursor acts on
+fn is_response_event(event_type: &str) -> bool {
+    matches!(
+        event_type,
+        "ResponseChunkReceived" | "ResponseCompleted" | "ResponseFailed" | "ResponseCancelled"
+    )
+}
+
+/// Replays stored response chunks from JetStream and continues live
+pub struct NatsStreamResumer {
+    jetstream: jetstream::Context,
+    stream_name: String,
+    subject_factory: AgentSubjectFactory,
+}
+
+impl NatsStreamResumer {
+    /// Create a resumer reading from `stream_name`
+    pub fn new(jetstream: jetstream::Context, stream_name: impl Into<String>) -> Self {
+        Self {
+            jetstream,
+            stream_name: stream_name.into(),
+            subject_factory: AgentSubjectFactory::default(),
+        }
+    }
+
+    /// Create a resumer with a custom subject factory
+    pub fn with_factory(
+        jetstream: jetstream::Context,
+        stream_name: impl Into<String>,
+        subject_factory: AgentSubjectFactory,
+    ) -> Self {
+        Self {
+            jetstream,
+            stream_name: stream_name.into(),
+            subject_factory,
+        }
+    }
+
+    /// Resume the response to `message_id` starting at chunk `from_index`
+    ///
+    /// The returned stream yields stored chunks first, then follows new
+    /// chunks as they are published, and ends with the response.
+    pub async fn resume(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+        from_index: u32,
+    ) -> DomainResult<ResumedChunkStream> {
+        let filter = self
+            .subject_factory
+            .message_response_pattern(agent_id, message_id)
+            .map_err(|e| DomainError::ValidationError(format!("Invalid subject: {}", e)))?;
+
+        let stream = self
+            .jetstream
+            .get_stream(&self.stream_name)
+            .await
+            .map_err(|e| DomainError::EventStoreError(format!("Stream unavailable: {}", e)))?;
+
+        let consumer = stream
+            .create_consumer(jetstream::consumer::pull::Config {
+                filter_subject: filter.to_string(),
+                deliver_policy: jetstream::consumer::DeliverPolicy::All,
+                ack_policy: jetstream::consumer::AckPolicy::None,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| DomainError::EventStoreError(format!("Failed to create consumer: {}", e)))?;
+
+        let messages = consumer
+            .messages()
+            .await
+            .map_err(|e| DomainError::EventStoreError(format!("Failed to fetch messages: {}", e)))?;
+
+        let state = (messages, ResumeCursor::new(from_index));
+        Ok(Box::pin(stream::unfold(state, |(mut messages, mut cursor)| async move {
+            while !cursor.is_finished() {
+                let message = match messages.next().await? {
+                    Ok(message) => message,
+                    Err(e) => {
+                        cursor.finished = true;
+                        let error = DomainError::EventStoreError(format!("Failed to read message: {}", e));
+                        return Some((Err(error), (messages, cursor)));
+                    }
+                };
+
+                // Only chunk and terminal events matter; skip the rest undecoded
+                let envelope = match decode_envelope_if(&message.payload, is_response_event) {
+                    Ok(Some(envelope)) => envelope,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::warn!("Skipping undecodable event during resume: {}", e);
+                        continue;
+                    }
+                };
+
+                match cursor.accept(&envelope.event) {
+                    ResumeStep::Chunk(chunk) => return Some((Ok(chunk), (messages, cursor))),
+                    ResumeStep::Skip => continue,
+                    ResumeStep::End => return None,
+                }
+            }
+            None
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ResponseChunkReceivedEvent, ResponseCompletedEvent};
+    use crate::value_objects::{FinishReason, TokenUsage};
+
+    fn chunk_event(index: u32, is_final: bool) -> AgentEvent {
+        let chunk = if is_final {
+            StreamingChunk::final_chunk(index, "end", FinishReason::Stop)
+        } else {
+            StreamingChunk::new(index, format!("c{}", index))
+        };
+        AgentEvent::ResponseChunkReceived(ResponseChunkReceivedEvent::new(
+            AgentId::new(),
+            MessageId::new(),
+            chunk,
+        ))
+    }
+
+    #[test]
+    fn test_cursor_skips_already_seen_chunks() {
+        let mut cursor = ResumeCursor::new(2);
+
+        assert_eq!(cursor.accept(&chunk_event(0, false)), ResumeStep::Skip);
+        assert_eq!(cursor.accept(&chunk_event(1, false)), ResumeStep::Skip);
+        assert!(matches!(cursor.accept(&chunk_event(2, false)), ResumeStep::Chunk(c) if c.chunk_index == 2));
+
+        // Redelivered chunk is deduplicated
+        assert_eq!(cursor.accept(&chunk_event(2, false)), ResumeStep::Skip);
+        assert_eq!(cursor.next_index(), 3);
+    }
+
+    #[test]
+    fn test_cursor_finishes_on_final_chunk() {
+        let mut cursor = ResumeCursor::new(0);
+
+        assert!(matches!(cursor.accept(&chunk_event(0, true)), ResumeStep::Chunk(_)));
+        assert!(cursor.is_finished());
+        assert_eq!(cursor.accept(&chunk_event(1, false)), ResumeStep::End);
+    }
+
+    #[test]
+    fn test_cursor_finishes_on_terminal_event() {
+        let mut cursor = ResumeCursor::new(0);
+        let completed = AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+            AgentId::new(),
+            MessageId::new(),
+            3,
+            TokenUsage::default(),
+            FinishReason::Stop,
+            10,
+        ));
+
+        assert_eq!(cursor.accept(&completed), ResumeStep::End);
+        assert!(cursor.is_finished());
+    }
+}
@@ -24,7 +24,9 @@
 //! - `{domain}.events.agent.{agent_id}.{event_type}`
 //! - `{domain}.events.agent.{agent_id}.message.{message_id}.{event_type}`
 
-use crate::value_objects::{AgentId, AgentReference, CapabilityCluster, ConversationId, MessageId};
+use crate::value_objects::{
+    AgentId, AgentReference, CapabilityCluster, ConversationId, MessageId, PersonId,
+};
 use cim_domain::{Subject, SubjectError, SubjectPattern, SubjectSegment};
 use once_cell::sync::Lazy;
 use std::fmt;
@@ -37,6 +39,12 @@ mod segments {
     pub static COMMANDS: Lazy<SubjectSegment> =
         Lazy::new(|| SubjectSegment::new("commands").expect("valid segment"));
 
+    pub static INGRESS: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("ingress").expect("valid segment"));
+
+    pub static EXTERNAL: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("external").expect("valid segment"));
+
     pub static EVENTS: Lazy<SubjectSegment> =
         Lazy::new(|| SubjectSegment::new("events").expect("valid segment"));
 
@@ -108,6 +116,122 @@ mod segments {
 
     pub static FAILED: Lazy<SubjectSegment> =
         Lazy::new(|| SubjectSegment::new("failed").expect("valid segment"));
+
+    pub static LIMIT_ENFORCED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("limit_enforced").expect("valid segment"));
+
+    pub static CANCELLED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("cancelled").expect("valid segment"));
+
+    pub static SLO_VIOLATED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("slo_violated").expect("valid segment"));
+
+    pub static DAILY_DIGEST_READY: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("daily_digest_ready").expect("valid segment"));
+
+    pub static CONVERSATION_ARCHIVED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("conversation_archived").expect("valid segment"));
+
+    pub static CONFIGURATION_DRIFT_DETECTED: Lazy<SubjectSegment> = Lazy::new(|| {
+        SubjectSegment::new("configuration_drift_detected").expect("valid segment")
+    });
+
+    pub static CANARY_DECIDED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("canary_decided").expect("valid segment"));
+
+    pub static EVALUATION_COMPLETED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("evaluation_completed").expect("valid segment"));
+
+    pub static CAPABILITY_NEGOTIATED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("capability_negotiated").expect("valid segment"));
+
+    pub static DEADLINE_EXCEEDED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("deadline_exceeded").expect("valid segment"));
+
+    pub static FEATURE_FLAGS_CHANGED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("feature_flags_changed").expect("valid segment"));
+
+    pub static LONG_CONTEXT_FALLBACK: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("long_context_fallback").expect("valid segment"));
+
+    pub static CONTEXT_TRUNCATED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("context_truncated").expect("valid segment"));
+
+    pub static CANDIDATE_SELECTED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("candidate_selected").expect("valid segment"));
+
+    pub static RESPONSE_DRAFTED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("response_drafted").expect("valid segment"));
+
+    pub static REASONING_TRACE_RECORDED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("reasoning_trace_recorded").expect("valid segment"));
+
+    pub static LOCALE_PREFERENCES_CHANGED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("locale_preferences_changed").expect("valid segment"));
+
+    pub static DEPRECATED_TOOL_IN_USE: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("deprecated_tool_in_use").expect("valid segment"));
+
+    pub static TOOL_VERSION_MIGRATED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("tool_version_migrated").expect("valid segment"));
+
+    pub static TOOL_INVOKED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("tool_invoked").expect("valid segment"));
+
+    pub static TOOL_SUCCEEDED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("tool_succeeded").expect("valid segment"));
+
+    pub static TOOL_FAILED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("tool_failed").expect("valid segment"));
+
+    pub static LEGAL_HOLD_REFUSED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("legal_hold_refused").expect("valid segment"));
+
+    pub static LEGAL_HOLD_PLACED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("legal_hold_placed").expect("valid segment"));
+
+    pub static LEGAL_HOLD_RELEASED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("legal_hold_released").expect("valid segment"));
+
+    pub static PERMISSION_ESCALATION_REQUESTED: Lazy<SubjectSegment> = Lazy::new(|| {
+        SubjectSegment::new("permission_escalation_requested").expect("valid segment")
+    });
+
+    pub static PERMISSION_ESCALATION_APPROVED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("permission_escalation_approved").expect("valid segment"));
+
+    pub static PERMISSION_ESCALATION_EXPIRED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("permission_escalation_expired").expect("valid segment"));
+
+    pub static ANOMALY_DETECTED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("anomaly_detected").expect("valid segment"));
+
+    pub static BATCH_PROGRESS: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("batch_progress").expect("valid segment"));
+
+    pub static BATCH_COMPLETED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("batch_completed").expect("valid segment"));
+
+    pub static MESSAGE_RETRY_SCHEDULED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("message_retry_scheduled").expect("valid segment"));
+
+    pub static MESSAGE_QUARANTINED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("message_quarantined").expect("valid segment"));
+
+    pub static QUARANTINED_MESSAGE_RELEASED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("quarantined_message_released").expect("valid segment"));
+
+    pub static CAPABILITY_VERIFICATION_FAILED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("capability_verification_failed").expect("valid segment"));
+
+    pub static CHANGE_RATE_EXCEEDED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("change_rate_exceeded").expect("valid segment"));
+
+    pub static OUTPUT_CONSTRAINT_ENFORCED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("output_constraint_enforced").expect("valid segment"));
+
+    pub static SPEND_THRESHOLD_CROSSED: Lazy<SubjectSegment> =
+        Lazy::new(|| SubjectSegment::new("spend_threshold_crossed").expect("valid segment"));
 }
 
 /// Subject factory for agent domain NATS subjects
@@ -373,6 +497,26 @@ impl AgentSubjectFactory {
         SubjectPattern::parse(&pattern_str).map_err(Into::into)
     }
 
+    /// Conversation events subject: `{domain}.conversations.{conv_id}.events`
+    ///
+    /// Where the `Conversation` aggregate's events are stored, in order. Covered
+    /// by [`conversation_pattern`](Self::conversation_pattern), so participants
+    /// see membership and receipts alongside the messages.
+    pub fn conversation_events(&self, conv_id: ConversationId) -> SubjectFactoryResult<Subject> {
+        let conv_segment = SubjectSegment::new(conv_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::CONVERSATIONS.clone())
+            .append(conv_segment)
+            .append(segments::EVENTS.clone()))
+    }
+
+    /// All conversation events: `{domain}.conversations.*.events`
+    pub fn conversation_events_pattern(&self) -> SubjectFactoryResult<SubjectPattern> {
+        let pattern_str = format!("{}.conversations.*.events", self.domain);
+        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    }
+
     /// All conversations pattern: `{domain}.conversations.>`
     ///
     /// Subscribe to ALL conversations (admin/monitoring use case).
@@ -388,6 +532,29 @@ impl AgentSubjectFactory {
         SubjectPattern::parse(&pattern_str).map_err(Into::into)
     }
 
+    // ========================================================================
+    // External Agent Ingress
+    // ========================================================================
+
+    /// External agent ingress subject: `{domain}.ingress.external.{agent_id}`
+    ///
+    /// Where a webhook gateway forwards the signed deliveries of an external
+    /// system acting as `agent_id`, for `WebhookIngress` to verify.
+    pub fn external_ingress(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::INGRESS.clone())
+            .append(segments::EXTERNAL.clone())
+            .append(agent_segment))
+    }
+
+    /// All external agent ingress: `{domain}.ingress.external.*`
+    pub fn external_ingress_pattern(&self) -> SubjectFactoryResult<SubjectPattern> {
+        let pattern_str = format!("{}.ingress.external.*", self.domain);
+        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    }
+
     // ========================================================================
     // Agent Reference Subjects (Unified Architecture v1.0.0)
     // ========================================================================
@@ -533,6 +700,109 @@ impl AgentSubjectFactory {
         SubjectPattern::parse(&pattern_str).map_err(Into::into)
     }
 
+    // ========================================================================
+    // Query Subjects
+    // ========================================================================
+
+    /// All agent queries pattern: `{domain}.queries.agent.>`
+    pub fn queries_pattern(&self) -> SubjectFactoryResult<SubjectPattern> {
+        let pattern_str = format!("{}.queries.agent.>", self.domain);
+        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    }
+
+    /// Query subject: `{domain}.queries.agent.{query}`
+    pub fn query_subject(&self, query: &str) -> SubjectFactoryResult<Subject> {
+        let queries = SubjectSegment::new("queries")?;
+        let query_segment = SubjectSegment::new(query)?;
+        Ok(self
+            .domain
+            .append(queries)
+            .append(segments::AGENT.clone())
+            .append(query_segment))
+    }
+
+    /// Self-description subject: `{domain}.queries.agent.{agent_id}.describe`
+    ///
+    /// Covered by [`queries_pattern`](Self::queries_pattern).
+    pub fn describe_subject(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let queries = SubjectSegment::new("queries")?;
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let describe = SubjectSegment::new("describe")?;
+        Ok(self
+            .domain
+            .append(queries)
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(describe))
+    }
+
+    /// Capability handshake subject: `{domain}.queries.agent.{agent_id}.negotiate`
+    ///
+    /// Covered by [`queries_pattern`](Self::queries_pattern).
+    pub fn negotiate_subject(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let queries = SubjectSegment::new("queries")?;
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let negotiate = SubjectSegment::new("negotiate")?;
+        Ok(self
+            .domain
+            .append(queries)
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(negotiate))
+    }
+
+    // ========================================================================
+    // Log Subjects
+    // ========================================================================
+
+    /// Forwarded log batches: `{domain}.logs.agent.{agent_id}`
+    pub fn logs_subject(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let logs = SubjectSegment::new("logs")?;
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(logs)
+            .append(segments::AGENT.clone())
+            .append(agent_segment))
+    }
+
+    // ========================================================================
+    // Service Subjects (NATS micro)
+    // ========================================================================
+
+    /// Service endpoint group: `{domain}.services.agent`
+    ///
+    /// Endpoints registered on this group answer on
+    /// `{domain}.services.agent.{endpoint}`.
+    pub fn service_group(&self) -> SubjectFactoryResult<Subject> {
+        let services = SubjectSegment::new("services")?;
+        Ok(self
+            .domain
+            .append(services)
+            .append(segments::AGENT.clone()))
+    }
+
+    // ========================================================================
+    // Person Domain Subjects
+    // ========================================================================
+
+    /// Owner notification command: `{domain}.commands.person.{person_id}.notify`
+    pub fn person_notification_subject(
+        &self,
+        person_id: PersonId,
+    ) -> SubjectFactoryResult<Subject> {
+        let commands = SubjectSegment::new("commands")?;
+        let person = SubjectSegment::new("person")?;
+        let person_segment = SubjectSegment::new(person_id.to_string())?;
+        let notify = SubjectSegment::new("notify")?;
+        Ok(self
+            .domain
+            .append(commands)
+            .append(person)
+            .append(person_segment)
+            .append(notify))
+    }
+
     // ========================================================================
     // Legacy Command Subjects (Backward Compatibility)
     // ========================================================================
@@ -547,6 +817,17 @@ impl AgentSubjectFactory {
         SubjectPattern::parse(&pattern_str).map_err(Into::into)
     }
 
+    /// Legacy commands for one agent: `{domain}.commands.agent.{agent_id}.>`
+    ///
+    /// Still published by pre-unified clients; see `SubjectMigrator`.
+    pub fn legacy_agent_commands_pattern(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<SubjectPattern> {
+        let pattern_str = format!("{}.commands.agent.{}.>", self.domain, agent_id);
+        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    }
+
     /// Deploy command (global, before agent_id exists): `{domain}.commands.agent.deploy`
     pub fn deploy_command(&self) -> Subject {
         self.domain
@@ -684,140 +965,853 @@ impl AgentSubjectFactory {
             .append(segments::DECOMMISSIONED.clone()))
     }
 
-    // ========================================================================
-    // Message Event Subjects
-    // ========================================================================
-
-    /// Message sent event: `{domain}.events.agent.{agent_id}.message.{message_id}.sent`
-    pub fn message_sent_event(
-        &self,
-        agent_id: AgentId,
-        message_id: MessageId,
-    ) -> SubjectFactoryResult<Subject> {
+    /// SLO violated event: `{domain}.events.agent.{agent_id}.slo_violated`
+    pub fn slo_violated_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
         let agent_segment = SubjectSegment::new(agent_id.to_string())?;
-        let message_segment = SubjectSegment::new(message_id.to_string())?;
         Ok(self
             .domain
             .append(segments::EVENTS.clone())
             .append(segments::AGENT.clone())
             .append(agent_segment)
-            .append(segments::MESSAGE.clone())
-            .append(message_segment)
-            .append(segments::SENT.clone()))
+            .append(segments::SLO_VIOLATED.clone()))
     }
 
-    /// Response chunk event: `{domain}.events.agent.{agent_id}.message.{message_id}.chunk.{index}`
-    pub fn response_chunk_event(
-        &self,
-        agent_id: AgentId,
-        message_id: MessageId,
-        chunk_index: u32,
-    ) -> SubjectFactoryResult<Subject> {
+    /// Daily digest event: `{domain}.events.agent.{agent_id}.daily_digest_ready`
+    pub fn daily_digest_ready_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
         let agent_segment = SubjectSegment::new(agent_id.to_string())?;
-        let message_segment = SubjectSegment::new(message_id.to_string())?;
-        let index_segment = SubjectSegment::new(chunk_index.to_string())?;
         Ok(self
             .domain
             .append(segments::EVENTS.clone())
             .append(segments::AGENT.clone())
             .append(agent_segment)
-            .append(segments::MESSAGE.clone())
-            .append(message_segment)
-            .append(segments::CHUNK.clone())
-            .append(index_segment))
+            .append(segments::DAILY_DIGEST_READY.clone()))
     }
 
-    /// Response completed event: `{domain}.events.agent.{agent_id}.message.{message_id}.completed`
-    pub fn response_completed_event(
-        &self,
-        agent_id: AgentId,
-        message_id: MessageId,
-    ) -> SubjectFactoryResult<Subject> {
+    /// Conversation archived event: `{domain}.events.agent.{agent_id}.conversation_archived`
+    pub fn conversation_archived_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
         let agent_segment = SubjectSegment::new(agent_id.to_string())?;
-        let message_segment = SubjectSegment::new(message_id.to_string())?;
         Ok(self
             .domain
             .append(segments::EVENTS.clone())
             .append(segments::AGENT.clone())
             .append(agent_segment)
-            .append(segments::MESSAGE.clone())
-            .append(message_segment)
-            .append(segments::COMPLETED.clone()))
+            .append(segments::CONVERSATION_ARCHIVED.clone()))
     }
 
-    /// Response failed event: `{domain}.events.agent.{agent_id}.message.{message_id}.failed`
-    pub fn response_failed_event(
+    /// Drift event: `{domain}.events.agent.{agent_id}.configuration_drift_detected`
+    pub fn configuration_drift_detected_event(
         &self,
         agent_id: AgentId,
-        message_id: MessageId,
     ) -> SubjectFactoryResult<Subject> {
         let agent_segment = SubjectSegment::new(agent_id.to_string())?;
-        let message_segment = SubjectSegment::new(message_id.to_string())?;
         Ok(self
             .domain
             .append(segments::EVENTS.clone())
             .append(segments::AGENT.clone())
             .append(agent_segment)
-            .append(segments::MESSAGE.clone())
-            .append(message_segment)
-            .append(segments::FAILED.clone()))
+            .append(segments::CONFIGURATION_DRIFT_DETECTED.clone()))
     }
 
-    /// Message events pattern: `{domain}.events.agent.{agent_id}.message.>`
-    pub fn message_events_pattern(
-        &self,
-        agent_id: AgentId,
-    ) -> SubjectFactoryResult<SubjectPattern> {
-        let pattern_str = format!("{}.events.agent.{}.message.>", self.domain, agent_id);
-        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    /// Canary decision event: `{domain}.events.agent.{agent_id}.canary_decided`
+    pub fn canary_decided_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::CANARY_DECIDED.clone()))
     }
-}
 
-impl Default for AgentSubjectFactory {
-    fn default() -> Self {
-        Self::new("agent")
+    /// Evaluation completed event: `{domain}.events.agent.{agent_id}.evaluation_completed`
+    pub fn evaluation_completed_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::EVALUATION_COMPLETED.clone()))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_command_subjects() {
-        let factory = AgentSubjectFactory::new("cim");
-        let agent_id = AgentId::new();
 
-        // Deploy command
-        let subject = factory.deploy_command();
-        assert_eq!(subject.to_string(), "cim.commands.agent.deploy");
-
-        // Configure model command
-        let subject = factory.configure_model_command(agent_id).unwrap();
-        assert!(subject.to_string().starts_with("cim.commands.agent."));
-        assert!(subject.to_string().ends_with(".configure_model"));
+    /// Capability negotiated event: `{domain}.events.agent.{agent_id}.capability_negotiated`
+    pub fn capability_negotiated_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::CAPABILITY_NEGOTIATED.clone()))
+    }
 
-        // Activate command
-        let subject = factory.activate_command(agent_id).unwrap();
-        assert!(subject.to_string().ends_with(".activate"));
+    /// Deadline exceeded event: `{domain}.events.agent.{agent_id}.deadline_exceeded`
+    pub fn deadline_exceeded_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::DEADLINE_EXCEEDED.clone()))
     }
 
-    #[test]
-    fn test_event_subjects() {
-        let factory = AgentSubjectFactory::new("cim");
-        let agent_id = AgentId::new();
+    /// Feature flags changed event: `{domain}.events.agent.{agent_id}.feature_flags_changed`
+    pub fn feature_flags_changed_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::FEATURE_FLAGS_CHANGED.clone()))
+    }
 
-        // Agent deployed
-        let subject = factory.agent_deployed_event(agent_id).unwrap();
-        assert!(subject.to_string().starts_with("cim.events.agent."));
-        assert!(subject.to_string().ends_with(".deployed"));
+    /// Long context fallback event: `{domain}.events.agent.{agent_id}.long_context_fallback`
+    pub fn long_context_fallback_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::LONG_CONTEXT_FALLBACK.clone()))
+    }
 
-        // Model configured
-        let subject = factory.model_configured_event(agent_id).unwrap();
-        assert!(subject.to_string().ends_with(".model_configured"));
+    /// Context truncated event: `{domain}.events.agent.{agent_id}.context_truncated`
+    pub fn context_truncated_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::CONTEXT_TRUNCATED.clone()))
     }
 
-    #[test]
+    /// Candidate selected event: `{domain}.events.agent.{agent_id}.candidate_selected`
+    pub fn candidate_selected_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::CANDIDATE_SELECTED.clone()))
+    }
+
+    /// Response drafted event: `{domain}.events.agent.{agent_id}.response_drafted`
+    pub fn response_drafted_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::RESPONSE_DRAFTED.clone()))
+    }
+
+    /// Reasoning trace recorded event: `{domain}.events.agent.{agent_id}.reasoning_trace_recorded`
+    pub fn reasoning_trace_recorded_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::REASONING_TRACE_RECORDED.clone()))
+    }
+
+    /// Locale changed event: `{domain}.events.agent.{agent_id}.locale_preferences_changed`
+    pub fn locale_preferences_changed_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::LOCALE_PREFERENCES_CHANGED.clone()))
+    }
+
+    /// Deprecated tool in use event: `{domain}.events.agent.{agent_id}.deprecated_tool_in_use`
+    pub fn deprecated_tool_in_use_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::DEPRECATED_TOOL_IN_USE.clone()))
+    }
+
+    /// Tool version migrated event: `{domain}.events.agent.{agent_id}.tool_version_migrated`
+    pub fn tool_version_migrated_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::TOOL_VERSION_MIGRATED.clone()))
+    }
+
+    /// Tool invoked event: `{domain}.events.agent.{agent_id}.tool_invoked`
+    pub fn tool_invoked_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::TOOL_INVOKED.clone()))
+    }
+
+    /// Tool succeeded event: `{domain}.events.agent.{agent_id}.tool_succeeded`
+    pub fn tool_succeeded_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::TOOL_SUCCEEDED.clone()))
+    }
+
+    /// Tool failed event: `{domain}.events.agent.{agent_id}.tool_failed`
+    pub fn tool_failed_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::TOOL_FAILED.clone()))
+    }
+
+    /// Legal hold refused event: `{domain}.events.agent.{agent_id}.legal_hold_refused`
+    pub fn legal_hold_refused_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::LEGAL_HOLD_REFUSED.clone()))
+    }
+
+    /// Legal hold placed event: `{domain}.events.agent.{agent_id}.legal_hold_placed`
+    pub fn legal_hold_placed_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::LEGAL_HOLD_PLACED.clone()))
+    }
+
+    /// Legal hold released event: `{domain}.events.agent.{agent_id}.legal_hold_released`
+    pub fn legal_hold_released_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::LEGAL_HOLD_RELEASED.clone()))
+    }
+
+    /// Escalation requested: `{domain}.events.agent.{agent_id}.permission_escalation_requested`
+    pub fn permission_escalation_requested_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::PERMISSION_ESCALATION_REQUESTED.clone()))
+    }
+
+    /// Escalation approved: `{domain}.events.agent.{agent_id}.permission_escalation_approved`
+    pub fn permission_escalation_approved_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::PERMISSION_ESCALATION_APPROVED.clone()))
+    }
+
+    /// Escalation expired: `{domain}.events.agent.{agent_id}.permission_escalation_expired`
+    pub fn permission_escalation_expired_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::PERMISSION_ESCALATION_EXPIRED.clone()))
+    }
+
+    /// Anomaly detected event: `{domain}.events.agent.{agent_id}.anomaly_detected`
+    pub fn anomaly_detected_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::ANOMALY_DETECTED.clone()))
+    }
+
+    /// Batch progress event: `{domain}.events.agent.{agent_id}.batch_progress`
+    pub fn batch_progress_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::BATCH_PROGRESS.clone()))
+    }
+
+    /// Batch completed event: `{domain}.events.agent.{agent_id}.batch_completed`
+    pub fn batch_completed_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::BATCH_COMPLETED.clone()))
+    }
+
+    /// Message retry scheduled event: `{domain}.events.agent.{agent_id}.message_retry_scheduled`
+    pub fn message_retry_scheduled_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE_RETRY_SCHEDULED.clone()))
+    }
+
+    /// Message quarantined event: `{domain}.events.agent.{agent_id}.message_quarantined`
+    pub fn message_quarantined_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE_QUARANTINED.clone()))
+    }
+
+    /// Quarantined message released event: `{domain}.events.agent.{agent_id}.quarantined_message_released`
+    pub fn quarantined_message_released_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::QUARANTINED_MESSAGE_RELEASED.clone()))
+    }
+
+    /// Capability verification failed event:
+    /// `{domain}.events.agent.{agent_id}.capability_verification_failed`
+    pub fn capability_verification_failed_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::CAPABILITY_VERIFICATION_FAILED.clone()))
+    }
+
+    /// Change rate exceeded event: `{domain}.events.agent.{agent_id}.change_rate_exceeded`
+    pub fn change_rate_exceeded_event(&self, agent_id: AgentId) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::CHANGE_RATE_EXCEEDED.clone()))
+    }
+
+    /// Output constraint enforced event:
+    /// `{domain}.events.agent.{agent_id}.output_constraint_enforced`
+    pub fn output_constraint_enforced_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::OUTPUT_CONSTRAINT_ENFORCED.clone()))
+    }
+
+    /// Spend threshold event: `{domain}.events.agent.{agent_id}.spend_threshold_crossed`
+    pub fn spend_threshold_crossed_event(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::SPEND_THRESHOLD_CROSSED.clone()))
+    }
+
+    // ========================================================================
+    // Message Event Subjects
+    // ========================================================================
+
+    /// Message sent event: `{domain}.events.agent.{agent_id}.message.{message_id}.sent`
+    pub fn message_sent_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let message_segment = SubjectSegment::new(message_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE.clone())
+            .append(message_segment)
+            .append(segments::SENT.clone()))
+    }
+
+    /// Response chunk event: `{domain}.events.agent.{agent_id}.message.{message_id}.chunk.{index}`
+    pub fn response_chunk_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+        chunk_index: u32,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let message_segment = SubjectSegment::new(message_id.to_string())?;
+        let index_segment = SubjectSegment::new(chunk_index.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE.clone())
+            .append(message_segment)
+            .append(segments::CHUNK.clone())
+            .append(index_segment))
+    }
+
+    /// Response completed event: `{domain}.events.agent.{agent_id}.message.{message_id}.completed`
+    pub fn response_completed_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let message_segment = SubjectSegment::new(message_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE.clone())
+            .append(message_segment)
+            .append(segments::COMPLETED.clone()))
+    }
+
+    /// Response failed event: `{domain}.events.agent.{agent_id}.message.{message_id}.failed`
+    pub fn response_failed_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let message_segment = SubjectSegment::new(message_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE.clone())
+            .append(message_segment)
+            .append(segments::FAILED.clone()))
+    }
+
+    /// Output limit enforced event: `{domain}.events.agent.{agent_id}.message.{message_id}.limit_enforced`
+    pub fn output_limit_enforced_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+    ) -> SubjectFactoryResult<Subject> {
+        self.message_event(agent_id, message_id, &segments::LIMIT_ENFORCED)
+    }
+
+    /// Response cancelled event: `{domain}.events.agent.{agent_id}.message.{message_id}.cancelled`
+    pub fn response_cancelled_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+    ) -> SubjectFactoryResult<Subject> {
+        self.message_event(agent_id, message_id, &segments::CANCELLED)
+    }
+
+    /// Build `{domain}.events.agent.{agent_id}.message.{message_id}.{kind}`
+    fn message_event(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+        kind: &SubjectSegment,
+    ) -> SubjectFactoryResult<Subject> {
+        let agent_segment = SubjectSegment::new(agent_id.to_string())?;
+        let message_segment = SubjectSegment::new(message_id.to_string())?;
+        Ok(self
+            .domain
+            .append(segments::EVENTS.clone())
+            .append(segments::AGENT.clone())
+            .append(agent_segment)
+            .append(segments::MESSAGE.clone())
+            .append(message_segment)
+            .append(kind.clone()))
+    }
+
+    /// Single message response pattern: `{domain}.events.agent.{agent_id}.message.{message_id}.>`
+    pub fn message_response_pattern(
+        &self,
+        agent_id: AgentId,
+        message_id: MessageId,
+    ) -> SubjectFactoryResult<SubjectPattern> {
+        let pattern_str = format!(
+            "{}.events.agent.{}.message.{}.>",
+            self.domain, agent_id, message_id
+        );
+        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    }
+
+    /// Message events pattern: `{domain}.events.agent.{agent_id}.message.>`
+    pub fn message_events_pattern(
+        &self,
+        agent_id: AgentId,
+    ) -> SubjectFactoryResult<SubjectPattern> {
+        let pattern_str = format!("{}.events.agent.{}.message.>", self.domain, agent_id);
+        SubjectPattern::parse(&pattern_str).map_err(Into::into)
+    }
+}
+
+impl Default for AgentSubjectFactory {
+    fn default() -> Self {
+        Self::new("agent")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_model::AgentQuery;
+
+    #[test]
+    fn test_command_subjects() {
+        let factory = AgentSubjectFactory::new("cim");
+        let agent_id = AgentId::new();
+
+        // Deploy command
+        let subject = factory.deploy_command();
+        assert_eq!(subject.to_string(), "cim.commands.agent.deploy");
+
+        // Configure model command
+        let subject = factory.configure_model_command(agent_id).unwrap();
+        assert!(subject.to_string().starts_with("cim.commands.agent."));
+        assert!(subject.to_string().ends_with(".configure_model"));
+
+        // Activate command
+        let subject = factory.activate_command(agent_id).unwrap();
+        assert!(subject.to_string().ends_with(".activate"));
+    }
+
+    #[test]
+    fn test_event_subjects() {
+        let factory = AgentSubjectFactory::new("cim");
+        let agent_id = AgentId::new();
+
+        // Agent deployed
+        let subject = factory.agent_deployed_event(agent_id).unwrap();
+        assert!(subject.to_string().starts_with("cim.events.agent."));
+        assert!(subject.to_string().ends_with(".deployed"));
+
+        // Model configured
+        let subject = factory.model_configured_event(agent_id).unwrap();
+        assert!(subject.to_string().ends_with(".model_configured"));
+
+        // SLO violated
+        let subject = factory.slo_violated_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.slo_violated", agent_id)
+        );
+
+        // Daily digest
+        let subject = factory.daily_digest_ready_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.daily_digest_ready", agent_id)
+        );
+
+        // Conversation archived
+        let subject = factory.conversation_archived_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.conversation_archived", agent_id)
+        );
+
+        // Configuration drift
+        let subject = factory.configuration_drift_detected_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.configuration_drift_detected", agent_id)
+        );
+
+        // Canary decision
+        let subject = factory.canary_decided_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.canary_decided", agent_id)
+        );
+
+        // Evaluation completed
+        let subject = factory.evaluation_completed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.evaluation_completed", agent_id)
+        );
+
+        // Capability negotiated
+        let subject = factory.capability_negotiated_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.capability_negotiated", agent_id)
+        );
+
+        // Deadline exceeded
+        let subject = factory.deadline_exceeded_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.deadline_exceeded", agent_id)
+        );
+
+        // Feature flags changed
+        let subject = factory.feature_flags_changed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.feature_flags_changed", agent_id)
+        );
+
+        // Long context fallback
+        let subject = factory.long_context_fallback_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.long_context_fallback", agent_id)
+        );
+
+        // Context truncated
+        let subject = factory.context_truncated_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.context_truncated", agent_id)
+        );
+
+        // Candidate selected
+        let subject = factory.candidate_selected_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.candidate_selected", agent_id)
+        );
+
+        // Response drafted
+        let subject = factory.response_drafted_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.response_drafted", agent_id)
+        );
+
+        // Reasoning trace recorded
+        let subject = factory.reasoning_trace_recorded_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.reasoning_trace_recorded", agent_id)
+        );
+
+        // Locale preferences changed
+        let subject = factory.locale_preferences_changed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.locale_preferences_changed", agent_id)
+        );
+
+        // Deprecated tool in use
+        let subject = factory.deprecated_tool_in_use_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.deprecated_tool_in_use", agent_id)
+        );
+
+        // Tool version migrated
+        let subject = factory.tool_version_migrated_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.tool_version_migrated", agent_id)
+        );
+
+        // Tool invoked
+        let subject = factory.tool_invoked_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.tool_invoked", agent_id)
+        );
+
+        // Tool succeeded
+        let subject = factory.tool_succeeded_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.tool_succeeded", agent_id)
+        );
+
+        // Tool failed
+        let subject = factory.tool_failed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.tool_failed", agent_id)
+        );
+
+        // Legal hold refused
+        let subject = factory.legal_hold_refused_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.legal_hold_refused", agent_id)
+        );
+
+        // Legal hold placed
+        let subject = factory.legal_hold_placed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.legal_hold_placed", agent_id)
+        );
+
+        // Legal hold released
+        let subject = factory.legal_hold_released_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.legal_hold_released", agent_id)
+        );
+
+        // Permission escalation requested
+        let subject = factory.permission_escalation_requested_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.permission_escalation_requested", agent_id)
+        );
+
+        // Permission escalation approved
+        let subject = factory.permission_escalation_approved_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.permission_escalation_approved", agent_id)
+        );
+
+        // Permission escalation expired
+        let subject = factory.permission_escalation_expired_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.permission_escalation_expired", agent_id)
+        );
+
+        // Anomaly detected
+        let subject = factory.anomaly_detected_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.anomaly_detected", agent_id)
+        );
+
+        // Batch completed
+        let subject = factory.batch_completed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.batch_completed", agent_id)
+        );
+
+        // Message quarantined
+        let subject = factory.message_quarantined_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.message_quarantined", agent_id)
+        );
+
+        // Capability verification failed
+        let subject = factory.capability_verification_failed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.capability_verification_failed", agent_id)
+        );
+
+        // Change rate exceeded
+        let subject = factory.change_rate_exceeded_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.change_rate_exceeded", agent_id)
+        );
+
+        // Output constraint enforced
+        let subject = factory.output_constraint_enforced_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.output_constraint_enforced", agent_id)
+        );
+
+        // Spend threshold crossed
+        let subject = factory.spend_threshold_crossed_event(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.spend_threshold_crossed", agent_id)
+        );
+    }
+
+    #[test]
     fn test_message_event_subjects() {
         let factory = AgentSubjectFactory::new("cim");
         let agent_id = AgentId::new();
@@ -839,6 +1833,79 @@ mod tests {
             .response_completed_event(agent_id, message_id)
             .unwrap();
         assert!(subject.to_string().ends_with(".completed"));
+
+        // Output limit enforced
+        let subject = factory
+            .output_limit_enforced_event(agent_id, message_id)
+            .unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("cim.events.agent.{}.message.{}.limit_enforced", agent_id, message_id)
+        );
+
+        // Response cancelled
+        let subject = factory
+            .response_cancelled_event(agent_id, message_id)
+            .unwrap();
+        assert!(subject.to_string().ends_with(".cancelled"));
+
+        // Single message response pattern
+        let pattern = factory
+            .message_response_pattern(agent_id, message_id)
+            .unwrap();
+        assert_eq!(
+            pattern.to_string(),
+            format!("cim.events.agent.{}.message.{}.>", agent_id, message_id)
+        );
+    }
+
+    #[test]
+    fn test_query_subjects() {
+        let factory = AgentSubjectFactory::new("cim");
+
+        let subject = factory.query_subject("get_agent").unwrap();
+        assert_eq!(subject.to_string(), "cim.queries.agent.get_agent");
+
+        let pattern = factory.queries_pattern().unwrap();
+        assert_eq!(pattern.to_string(), "cim.queries.agent.>");
+
+        let agent_id = AgentId::new();
+        let describe = factory.describe_subject(agent_id).unwrap();
+        assert_eq!(describe.to_string(), format!("cim.queries.agent.{}.describe", agent_id));
+        assert!(matches!(
+            AgentQuery::from_describe_subject(&describe.to_string()),
+            Some(AgentQuery::DescribeAgent { agent_id: id }) if id == agent_id
+        ));
+        assert!(AgentQuery::from_describe_subject("cim.queries.agent.get_agent").is_none());
+
+        let negotiate = factory.negotiate_subject(agent_id).unwrap();
+        assert_eq!(negotiate.to_string(), format!("cim.queries.agent.{}.negotiate", agent_id));
+    }
+
+    #[test]
+    fn test_logs_subject() {
+        let factory = AgentSubjectFactory::new("cim");
+        let agent_id = AgentId::new();
+        assert_eq!(
+            factory.logs_subject(agent_id).unwrap().to_string(),
+            format!("cim.logs.agent.{}", agent_id)
+        );
+    }
+
+    #[test]
+    fn test_service_group() {
+        let factory = AgentSubjectFactory::new("cim");
+        assert_eq!(factory.service_group().unwrap().to_string(), "cim.services.agent");
+    }
+
+    #[test]
+    fn test_person_notification_subject() {
+        let factory = AgentSubjectFactory::new("cim");
+        let person_id = PersonId::new();
+        assert_eq!(
+            factory.person_notification_subject(person_id).unwrap().to_string(),
+            format!("cim.commands.person.{}.notify", person_id)
+        );
     }
 
     #[test]
@@ -926,6 +1993,12 @@ mod tests {
         let subject_str = subject.to_string();
         assert!(subject_str.starts_with("agent.conversations."));
         assert!(subject_str.ends_with(".status"));
+
+        // Conversation events
+        let subject = factory.conversation_events(conv_id).unwrap();
+        assert_eq!(subject.to_string(), format!("agent.conversations.{}.events", conv_id));
+        let pattern = factory.conversation_events_pattern().unwrap();
+        assert_eq!(pattern.to_string(), "agent.conversations.*.events");
     }
 
     #[test]
@@ -944,6 +2017,21 @@ mod tests {
         assert_eq!(pattern.to_string(), "agent.conversations.>");
     }
 
+    #[test]
+    fn test_external_ingress_subjects() {
+        let factory = AgentSubjectFactory::default();
+        let agent_id = AgentId::new();
+
+        let subject = factory.external_ingress(agent_id).unwrap();
+        assert_eq!(
+            subject.to_string(),
+            format!("agent.ingress.external.{}", agent_id)
+        );
+
+        let pattern = factory.external_ingress_pattern().unwrap();
+        assert_eq!(pattern.to_string(), "agent.ingress.external.*");
+    }
+
     #[test]
     fn test_conversation_free_monoid_properties() {
         use cim_domain::Subject;
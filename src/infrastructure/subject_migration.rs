@@ -0,0 +1,354 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Migration from legacy subjects to the unified subject architecture
+//!
+//! Legacy streams address agents as `{domain}.commands.agent.{id}.{type}`
+//! and `{domain}.events.agent.{id}.{type}`. The unified architecture puts
+//! the capability cluster and name first:
+//!
+//! ```text
+//! agent.commands.agent.{id}.activate ──> agent.orchestration.sage.{id}.command.activate
+//! agent.events.agent.{id}.activated  ──> agent.orchestration.sage.{id}.event.activated
+//! agent.commands.agent.*.>           ──> agent.*.*.*.command.>
+//! ```
+//!
+//! Three ways to move traffic across:
+//!
+//! - **Republish**: [`SubjectMigrator::republish`] copies a legacy message to
+//!   its unified subject, tagged with [`MIGRATED_FROM_HEADER`]
+//! - **Alias**: [`SubjectMigrator::server_mappings`] emits NATS subject
+//!   mappings, so the server rewrites legacy publishes in flight
+//! - **Dual subscribe**: [`SubjectMigrator::subscriptions`] lists the
+//!   patterns a consumer needs in each [`CompatibilityMode`]
+//!
+//! Patterns migrate wildcard-safely: the unified pattern never matches more
+//! than the legacy one did. When it matches less (message events and the
+//! agent-less `deploy` command have no unified form), the legacy pattern is
+//! kept alongside it even in unified mode.
+
+use super::{AgentSubjectFactory, ParsedAgentSubject, SubjectParseError, SubjectParser};
+use crate::value_objects::{AgentId, AgentReference};
+use cim_domain::Subject;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// Header naming the legacy subject a republished message came from
+pub const MIGRATED_FROM_HEADER: &str = "Agent-Migrated-From";
+
+/// Subject migration errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubjectMigrationError {
+    #[error(transparent)]
+    Parse(#[from] SubjectParseError),
+
+    #[error("No agent reference registered for {0}")]
+    UnknownAgent(AgentId),
+
+    #[error("Pattern '{0}' has no unified equivalent")]
+    Unmappable(String),
+
+    #[error("Subject construction failed: {0}")]
+    Subject(String),
+
+    #[error("Republish failed: {0}")]
+    Publish(String),
+}
+
+/// Result type for subject migration
+pub type SubjectMigrationResult<T> = Result<T, SubjectMigrationError>;
+
+/// Which subject layouts a consumer listens on during the transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompatibilityMode {
+    /// Legacy subjects only (before migration)
+    Legacy,
+
+    /// Both layouts; republished copies are skipped in favour of originals
+    Dual,
+
+    /// Unified subjects, plus legacy ones with no unified form
+    #[default]
+    Unified,
+}
+
+impl CompatibilityMode {
+    /// Mode name as used in configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompatibilityMode::Legacy => "legacy",
+            CompatibilityMode::Dual => "dual",
+            CompatibilityMode::Unified => "unified",
+        }
+    }
+
+    /// Whether a consumer in this mode should handle a message with `headers`
+    ///
+    /// In dual mode the legacy original of a republished message is also
+    /// received, so the copy is dropped.
+    pub fn accepts(&self, headers: Option<&async_nats::HeaderMap>) -> bool {
+        let republished = headers.is_some_and(|h| h.get(MIGRATED_FROM_HEADER).is_some());
+        !(republished && *self == CompatibilityMode::Dual)
+    }
+}
+
+impl std::str::FromStr for CompatibilityMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "legacy" => Ok(CompatibilityMode::Legacy),
+            "dual" => Ok(CompatibilityMode::Dual),
+            "unified" => Ok(CompatibilityMode::Unified),
+            other => Err(format!("Unknown subject compatibility mode: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for CompatibilityMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A legacy pattern rewritten for the unified layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigratedPattern {
+    /// The unified pattern
+    pub pattern: String,
+
+    /// Whether it matches everything the legacy pattern did, once all
+    /// legacy traffic is migrated
+    pub covers_legacy: bool,
+}
+
+/// Maps legacy agent subjects to the unified layout
+#[derive(Debug, Clone)]
+pub struct SubjectMigrator {
+    factory: AgentSubjectFactory,
+    parser: SubjectParser,
+    agents: HashMap<AgentId, AgentReference>,
+}
+
+impl SubjectMigrator {
+    /// Migrator for subjects under `factory`'s domain
+    pub fn new(factory: AgentSubjectFactory) -> Self {
+        Self {
+            parser: SubjectParser::new(&factory),
+            factory,
+            agents: HashMap::new(),
+        }
+    }
+
+    /// Builder: register the cluster and name of an agent
+    ///
+    /// Concrete subjects need them; patterns do not.
+    pub fn with_agent(mut self, agent: AgentReference) -> Self {
+        self.agents.insert(agent.id, agent);
+        self
+    }
+
+    /// The unified subject for a legacy one
+    ///
+    /// Returns `None` for subjects that are not legacy agent commands or
+    /// events, or have no unified form (message events, `deploy`).
+    pub fn migrate_subject(&self, subject: &str) -> SubjectMigrationResult<Option<Subject>> {
+        let migrated = match self.parser.parse(subject)? {
+            ParsedAgentSubject::LegacyCommand {
+                agent_id: Some(agent_id),
+                command_type,
+            } => self
+                .factory
+                .agent_command_ref(self.agent(agent_id)?, &command_type),
+            ParsedAgentSubject::LegacyEvent {
+                agent_id,
+                event_type,
+            } => self
+                .factory
+                .agent_event_ref(self.agent(agent_id)?, &event_type),
+            _ => return Ok(None),
+        };
+        migrated
+            .map(Some)
+            .map_err(|e| SubjectMigrationError::Subject(e.to_string()))
+    }
+
+    /// The unified pattern for a legacy command or event pattern
+    ///
+    /// Agents are matched by id alone, so registration is not needed and
+    /// renamed agents still match.
+    pub fn migrate_pattern(&self, pattern: &str) -> SubjectMigrationResult<MigratedPattern> {
+        let domain = self.factory.domain().to_string();
+        let unmappable = || SubjectMigrationError::Unmappable(pattern.to_string());
+        let tokens: Vec<&str> = pattern
+            .strip_prefix(domain.as_str())
+            .and_then(|rest| rest.strip_prefix('.'))
+            .ok_or_else(unmappable)?
+            .split('.')
+            .collect();
+
+        let (operation, id, tail) = match tokens.as_slice() {
+            [kind @ ("commands" | "events"), "agent", rest @ ..] => {
+                let operation = if *kind == "commands" { "command" } else { "event" };
+                match rest {
+                    [">"] => (operation, "*", ">"),
+                    [id, tail] => (operation, *id, *tail),
+                    _ => return Err(unmappable()),
+                }
+            }
+            _ => return Err(unmappable()),
+        };
+        if id != "*" && Uuid::parse_str(id).is_err() {
+            return Err(unmappable());
+        }
+        // Under events, `>` also spans message events; directly under
+        // `commands.agent` it also spans the agent-less `deploy`
+        let covers_legacy = match tail {
+            ">" => operation == "command" && tokens.len() == 4,
+            _ => true,
+        };
+        Ok(MigratedPattern {
+            pattern: format!("{}.*.*.{}.{}.{}", domain, id, operation, tail),
+            covers_legacy,
+        })
+    }
+
+    /// Patterns to subscribe to for `legacy_pattern` in `mode`
+    pub fn subscriptions(
+        &self,
+        legacy_pattern: &str,
+        mode: CompatibilityMode,
+    ) -> SubjectMigrationResult<Vec<String>> {
+        if mode == CompatibilityMode::Legacy {
+            return Ok(vec![legacy_pattern.to_string()]);
+        }
+        let migrated = self.migrate_pattern(legacy_pattern)?;
+        Ok(match (mode, migrated.covers_legacy) {
+            (CompatibilityMode::Unified, true) => vec![migrated.pattern],
+            _ => vec![legacy_pattern.to_string(), migrated.pattern],
+        })
+    }
+
+    /// NATS subject mappings aliasing each registered agent's legacy subjects
+    ///
+    /// Goes in the account's `mappings` block. Mapped publishes are rewritten,
+    /// not copied, so legacy-only consumers stop receiving them.
+    pub fn server_mappings(&self) -> BTreeMap<String, String> {
+        let domain = self.factory.domain();
+        let mut mappings = BTreeMap::new();
+        for agent in self.agents.values() {
+            for (legacy, operation) in [("commands", "command"), ("events", "event")] {
+                mappings.insert(
+                    format!("{}.{}.agent.{}.*", domain, legacy, agent.id),
+                    format!(
+                        "{}.{}.{}.{}.{}.{{{{wildcard(1)}}}}",
+                        domain,
+                        agent.capability.as_str(),
+                        agent.name,
+                        agent.id,
+                        operation
+                    ),
+                );
+            }
+        }
+        mappings
+    }
+
+    /// Copy a legacy message to its unified subject
+    ///
+    /// Headers are kept and [`MIGRATED_FROM_HEADER`] added. Returns the new
+    /// subject, or `None` if the message needed no migration.
+    pub async fn republish(
+        &self,
+        client: &async_nats::Client,
+        message: &async_nats::Message,
+    ) -> SubjectMigrationResult<Option<Subject>> {
+        let Some(subject) = self.migrate_subject(message.subject.as_str())? else {
+            return Ok(None);
+        };
+        let mut headers = message.headers.clone().unwrap_or_default();
+        headers.insert(MIGRATED_FROM_HEADER, message.subject.as_str());
+        client
+            .publish_with_headers(subject.to_string(), headers, message.payload.clone())
+            .await
+            .map_err(|e| SubjectMigrationError::Publish(e.to_string()))?;
+        Ok(Some(subject))
+    }
+
+    fn agent(&self, agent_id: AgentId) -> SubjectMigrationResult<&AgentReference> {
+        self.agents
+            .get(&agent_id)
+            .ok_or(SubjectMigrationError::UnknownAgent(agent_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{CapabilityCluster, MessageId};
+
+    fn migrator() -> (SubjectMigrator, AgentReference) {
+        let sage = AgentReference::new(
+            CapabilityCluster::Orchestration,
+            "sage".to_string(),
+            AgentId::new(),
+        );
+        let factory = AgentSubjectFactory::default();
+        (SubjectMigrator::new(factory).with_agent(sage.clone()), sage)
+    }
+
+    #[test]
+    fn test_migrates_subjects_and_aliases() {
+        let (migrator, sage) = migrator();
+        let factory = AgentSubjectFactory::default();
+
+        let legacy = factory.activate_command(sage.id).unwrap().to_string();
+        let unified = migrator.migrate_subject(&legacy).unwrap().unwrap();
+        assert_eq!(
+            unified.to_string(),
+            format!("agent.orchestration.sage.{}.command.activate", sage.id)
+        );
+
+        let chunk = factory.response_chunk_event(sage.id, MessageId::new(), 0).unwrap();
+        assert!(migrator.migrate_subject(&chunk.to_string()).unwrap().is_none());
+
+        let stranger = factory.activate_command(AgentId::new()).unwrap().to_string();
+        assert!(matches!(
+            migrator.migrate_subject(&stranger),
+            Err(SubjectMigrationError::UnknownAgent(_))
+        ));
+
+        let mappings = migrator.server_mappings();
+        assert_eq!(
+            mappings[&format!("agent.commands.agent.{}.*", sage.id)],
+            format!("agent.orchestration.sage.{}.command.{{{{wildcard(1)}}}}", sage.id)
+        );
+    }
+
+    #[test]
+    fn test_patterns_never_widen() {
+        let (migrator, sage) = migrator();
+
+        let commands = format!("agent.commands.agent.{}.>", sage.id);
+        let migrated = migrator.migrate_pattern(&commands).unwrap();
+        assert_eq!(migrated.pattern, format!("agent.*.*.{}.command.>", sage.id));
+        assert!(migrated.covers_legacy);
+        assert_eq!(
+            migrator
+                .subscriptions(&commands, CompatibilityMode::Unified)
+                .unwrap(),
+            vec![migrated.pattern.clone()]
+        );
+        assert_eq!(
+            migrator.subscriptions(&commands, CompatibilityMode::Dual).unwrap().len(),
+            2
+        );
+
+        let events = migrator.migrate_pattern("agent.events.agent.*.>").unwrap();
+        assert_eq!(events.pattern, "agent.*.*.*.event.>");
+        assert!(!events.covers_legacy);
+        assert!(!migrator.migrate_pattern("agent.commands.agent.>").unwrap().covers_legacy);
+
+        assert!(migrator.migrate_pattern("agent.commands.agent.sage.>").is_err());
+        assert!(migrator.migrate_pattern("agent.queries.agent.>").is_err());
+    }
+}
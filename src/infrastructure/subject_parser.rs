@@ -0,0 +1,373 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Subject parser: the inverse of [`AgentSubjectFactory`]
+//!
+//! Consumers receive subjects as strings. Rather than splitting them by
+//! hand, parse them back into the typed parts the factory put in:
+//!
+//! ```text
+//! AgentSubjectFactory ──build──> "agent.events.agent.{id}.message.{mid}.chunk.3"
+//!                                                 │
+//! SubjectParser ─────parse──────────────────────────┘
+//!        │
+//!        v
+//! ParsedAgentSubject::MessageEvent { agent_id, message_id, kind: Chunk(3) }
+//! ```
+//!
+//! A parser only accepts subjects under its factory's domain, and rejects
+//! wildcard patterns; it parses concrete subjects a message arrived on.
+
+use super::AgentSubjectFactory;
+use crate::value_objects::{
+    AgentId, AgentReference, CapabilityCluster, ConversationId, MessageId, PersonId,
+};
+use uuid::Uuid;
+
+/// Subject parsing errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubjectParseError {
+    #[error("Subject '{0}' is not under domain '{1}'")]
+    ForeignDomain(String, String),
+
+    #[error("Subject '{0}' contains a wildcard or empty token")]
+    NotConcrete(String),
+
+    #[error("Unrecognized agent subject: {0}")]
+    Unrecognized(String),
+
+    #[error("Invalid {kind} '{value}' in subject")]
+    InvalidToken { kind: &'static str, value: String },
+}
+
+/// Result type for subject parsing
+pub type SubjectParseResult<T> = Result<T, SubjectParseError>;
+
+/// Which conversation subject a message arrived on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversationSubjectKind {
+    Request,
+    Response,
+    Error,
+    Status,
+}
+
+/// What happened to a message, from its event subject
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageEventKind {
+    Sent,
+    Chunk(u32),
+    Completed,
+    Failed,
+    LimitEnforced,
+    Cancelled,
+    /// A message event this parser does not know by name
+    Other(String),
+}
+
+/// Typed meaning of an agent subject
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedAgentSubject {
+    /// `{domain}.to.{agent_name}.chat.{topic}`
+    Chat { agent_name: String, topic: String },
+
+    /// `{domain}.to.{to}.from.{from}.{message_type}`
+    AgentToAgent {
+        to: String,
+        from: String,
+        message_type: String,
+    },
+
+    /// `{domain}.broadcast.{topic...}`
+    Broadcast { topic: String },
+
+    /// `{domain}.conversations.{conversation_id}.{kind}`
+    Conversation {
+        conversation_id: ConversationId,
+        kind: ConversationSubjectKind,
+    },
+
+    /// `{domain}.{capability}.{name}.{id}.command.{command_type}`
+    Command {
+        agent: AgentReference,
+        command_type: String,
+    },
+
+    /// `{domain}.{capability}.{name}.{id}.event.{event_type}`
+    Event {
+        agent: AgentReference,
+        event_type: String,
+    },
+
+    /// `{domain}.commands.agent.{agent_id}.{command_type}`, or
+    /// `{domain}.commands.agent.deploy` before the agent exists
+    LegacyCommand {
+        agent_id: Option<AgentId>,
+        command_type: String,
+    },
+
+    /// `{domain}.events.agent.{agent_id}.{event_type}`
+    LegacyEvent {
+        agent_id: AgentId,
+        event_type: String,
+    },
+
+    /// `{domain}.events.agent.{agent_id}.message.{message_id}.{kind}`
+    MessageEvent {
+        agent_id: AgentId,
+        message_id: MessageId,
+        kind: MessageEventKind,
+    },
+
+    /// `{domain}.queries.agent.{query}`
+    Query { query: String },
+
+    /// `{domain}.queries.agent.{agent_id}.describe`
+    Describe { agent_id: AgentId },
+
+    /// `{domain}.queries.agent.{agent_id}.negotiate`
+    Negotiate { agent_id: AgentId },
+
+    /// `{domain}.services.agent.{endpoint}`
+    ServiceEndpoint { endpoint: String },
+
+    /// `{domain}.commands.person.{person_id}.notify`
+    PersonNotification { person_id: PersonId },
+}
+
+impl ParsedAgentSubject {
+    /// The agent the subject is about, when it names one by id
+    pub fn agent_id(&self) -> Option<AgentId> {
+        match self {
+            ParsedAgentSubject::Command { agent, .. } | ParsedAgentSubject::Event { agent, .. } => {
+                Some(agent.id)
+            }
+            ParsedAgentSubject::LegacyCommand { agent_id, .. } => *agent_id,
+            ParsedAgentSubject::LegacyEvent { agent_id, .. }
+            | ParsedAgentSubject::MessageEvent { agent_id, .. }
+            | ParsedAgentSubject::Describe { agent_id }
+            | ParsedAgentSubject::Negotiate { agent_id } => Some(*agent_id),
+            _ => None,
+        }
+    }
+}
+
+/// Parses subjects built by an [`AgentSubjectFactory`]
+#[derive(Debug, Clone)]
+pub struct SubjectParser {
+    domain: String,
+}
+
+impl SubjectParser {
+    /// Parser for subjects under `factory`'s domain
+    pub fn new(factory: &AgentSubjectFactory) -> Self {
+        Self {
+            domain: factory.domain().to_string(),
+        }
+    }
+
+    /// Parse a concrete subject into its typed parts
+    pub fn parse(&self, subject: &str) -> SubjectParseResult<ParsedAgentSubject> {
+        let rest = subject
+            .strip_prefix(self.domain.as_str())
+            .and_then(|rest| rest.strip_prefix('.'))
+            .ok_or_else(|| {
+                SubjectParseError::ForeignDomain(subject.to_string(), self.domain.clone())
+            })?;
+        let tokens: Vec<&str> = rest.split('.').collect();
+        if tokens.iter().any(|t| t.is_empty() || *t == "*" || *t == ">") {
+            return Err(SubjectParseError::NotConcrete(subject.to_string()));
+        }
+        let unrecognized = || SubjectParseError::Unrecognized(subject.to_string());
+
+        let parsed = match tokens.as_slice() {
+            ["to", agent_name, "chat", topic] => ParsedAgentSubject::Chat {
+                agent_name: agent_name.to_string(),
+                topic: topic.to_string(),
+            },
+            ["to", to, "from", from, message_type] => ParsedAgentSubject::AgentToAgent {
+                to: to.to_string(),
+                from: from.to_string(),
+                message_type: message_type.to_string(),
+            },
+            ["broadcast", topic @ ..] if !topic.is_empty() => ParsedAgentSubject::Broadcast {
+                topic: topic.join("."),
+            },
+            ["conversations", id, kind] => ParsedAgentSubject::Conversation {
+                conversation_id: ConversationId::from_uuid(uuid("conversation id", id)?),
+                kind: match *kind {
+                    "request" => ConversationSubjectKind::Request,
+                    "response" => ConversationSubjectKind::Response,
+                    "error" => ConversationSubjectKind::Error,
+                    "status" => ConversationSubjectKind::Status,
+                    _ => return Err(unrecognized()),
+                },
+            },
+            ["commands", "agent", command_type] => ParsedAgentSubject::LegacyCommand {
+                agent_id: None,
+                command_type: command_type.to_string(),
+            },
+            ["commands", "agent", id, command_type] => ParsedAgentSubject::LegacyCommand {
+                agent_id: Some(agent_id(id)?),
+                command_type: command_type.to_string(),
+            },
+            ["commands", "person", id, "notify"] => ParsedAgentSubject::PersonNotification {
+                person_id: PersonId::from_uuid(uuid("person id", id)?),
+            },
+            ["events", "agent", id, "message", message_id, kind @ ..] => {
+                let kind = match kind {
+                    ["sent"] => MessageEventKind::Sent,
+                    ["chunk", index] => MessageEventKind::Chunk(index.parse().map_err(|_| {
+                        SubjectParseError::InvalidToken {
+                            kind: "chunk index",
+                            value: index.to_string(),
+                        }
+                    })?),
+                    ["completed"] => MessageEventKind::Completed,
+                    ["failed"] => MessageEventKind::Failed,
+                    ["limit_enforced"] => MessageEventKind::LimitEnforced,
+                    ["cancelled"] => MessageEventKind::Cancelled,
+                    [other] => MessageEventKind::Other(other.to_string()),
+                    _ => return Err(unrecognized()),
+                };
+                ParsedAgentSubject::MessageEvent {
+                    agent_id: agent_id(id)?,
+                    message_id: MessageId::from_uuid(uuid("message id", message_id)?),
+                    kind,
+                }
+            }
+            ["events", "agent", id, event_type] => ParsedAgentSubject::LegacyEvent {
+                agent_id: agent_id(id)?,
+                event_type: event_type.to_string(),
+            },
+            ["queries", "agent", id, "describe"] => ParsedAgentSubject::Describe {
+                agent_id: agent_id(id)?,
+            },
+            ["queries", "agent", id, "negotiate"] => ParsedAgentSubject::Negotiate {
+                agent_id: agent_id(id)?,
+            },
+            ["queries", "agent", query] => ParsedAgentSubject::Query {
+                query: query.to_string(),
+            },
+            ["services", "agent", endpoint] => ParsedAgentSubject::ServiceEndpoint {
+                endpoint: endpoint.to_string(),
+            },
+            [capability, name, id, operation @ ("command" | "event"), kind] => {
+                let capability = CapabilityCluster::from_str(capability).ok_or_else(|| {
+                    SubjectParseError::InvalidToken {
+                        kind: "capability cluster",
+                        value: capability.to_string(),
+                    }
+                })?;
+                let agent = AgentReference::new(capability, name.to_string(), agent_id(id)?);
+                if *operation == "command" {
+                    ParsedAgentSubject::Command {
+                        agent,
+                        command_type: kind.to_string(),
+                    }
+                } else {
+                    ParsedAgentSubject::Event {
+                        agent,
+                        event_type: kind.to_string(),
+                    }
+                }
+            }
+            _ => return Err(unrecognized()),
+        };
+        Ok(parsed)
+    }
+}
+
+impl Default for SubjectParser {
+    fn default() -> Self {
+        Self::new(&AgentSubjectFactory::default())
+    }
+}
+
+fn uuid(kind: &'static str, value: &str) -> SubjectParseResult<Uuid> {
+    Uuid::parse_str(value).map_err(|_| SubjectParseError::InvalidToken {
+        kind,
+        value: value.to_string(),
+    })
+}
+
+fn agent_id(value: &str) -> SubjectParseResult<AgentId> {
+    uuid("agent id", value).map(AgentId::from_uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips_factory_subjects() {
+        let factory = AgentSubjectFactory::new("cim");
+        let parser = SubjectParser::new(&factory);
+        let agent_id = AgentId::new();
+        let message_id = MessageId::new();
+        let conv_id = ConversationId::new();
+        let sage = AgentReference::new(CapabilityCluster::Orchestration, "sage".into(), agent_id);
+
+        let parse = |subject: String| parser.parse(&subject).unwrap();
+        let chunk = factory.response_chunk_event(agent_id, message_id, 3).unwrap();
+        assert_eq!(
+            parse(chunk.to_string()),
+            ParsedAgentSubject::MessageEvent {
+                agent_id,
+                message_id,
+                kind: MessageEventKind::Chunk(3),
+            }
+        );
+        let command = factory.agent_command_ref(&sage, "deploy").unwrap();
+        assert_eq!(
+            parse(command.to_string()),
+            ParsedAgentSubject::Command {
+                agent: sage.clone(),
+                command_type: "deploy".to_string(),
+            }
+        );
+        let status = factory.conversation_status(conv_id).unwrap();
+        assert_eq!(
+            parse(status.to_string()),
+            ParsedAgentSubject::Conversation {
+                conversation_id: conv_id,
+                kind: ConversationSubjectKind::Status,
+            }
+        );
+        let a2a = factory.agent_to_agent("sage", "ddd", "request").unwrap();
+        assert!(matches!(
+            parse(a2a.to_string()),
+            ParsedAgentSubject::AgentToAgent { ref from, .. } if from == "sage"
+        ));
+        let describe = factory.describe_subject(agent_id).unwrap();
+        assert_eq!(parse(describe.to_string()).agent_id(), Some(agent_id));
+        assert_eq!(
+            parse(factory.deploy_command().to_string()),
+            ParsedAgentSubject::LegacyCommand {
+                agent_id: None,
+                command_type: "deploy".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_foreign_patterns_and_bad_ids() {
+        let parser = SubjectParser::default();
+
+        assert!(matches!(
+            parser.parse("cim.events.agent.x.deployed"),
+            Err(SubjectParseError::ForeignDomain(..))
+        ));
+        assert!(matches!(
+            parser.parse("agent.events.agent.>"),
+            Err(SubjectParseError::NotConcrete(_))
+        ));
+        assert!(matches!(
+            parser.parse("agent.events.agent.not-a-uuid.deployed"),
+            Err(SubjectParseError::InvalidToken { kind: "agent id", .. })
+        ));
+        assert!(matches!(
+            parser.parse("agent.unknown.shape"),
+            Err(SubjectParseError::Unrecognized(_))
+        ));
+    }
+}
@@ -0,0 +1,412 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! External agent webhook ingress
+//!
+//! An external system can take part in conversations as an agent: it is
+//! given an agent ID and a shared secret, and posts messages and receipts
+//! as webhooks. A gateway forwards each body, with its signature header,
+//! to `{domain}.ingress.external.{agent_id}`:
+//!
+//! ```text
+//! Cim-Webhook-Signature: t=1767225600,v1=5bdc...43
+//!                          │             └── hex HMAC-SHA256(secret, "{t}.{body}")
+//!                          └── unix seconds when the system signed it
+//!
+//! body ──> WebhookIngress::ingest(agent_id, signature, body)
+//!            ├── unknown agent / bad or stale signature ──> WebhookError
+//!            ├── message ──> content to BlobStore ──> PostMessage { External }
+//!            └── receipt ──> AcknowledgeMessage
+//! ```
+//!
+//! Signatures older than the tolerance are refused so a captured delivery
+//! cannot be replayed later; within it, the conversation refuses a message
+//! ID it has already seen.
+
+use super::{BlobStore, DomainError};
+use crate::commands::{AcknowledgeMessage, Acknowledgement, ConversationCommand, PostMessage};
+use crate::ports::{SecretsError, SecretsProvider};
+use crate::value_objects::{
+    clock_now, AgentId, ContentRef, ConversationId, MessageId, MessageProvenance, ParticipantId,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Header carrying a delivery's timestamp and HMAC signature
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "Cim-Webhook-Signature";
+
+/// How old a delivery's signature may be, by default
+pub const DEFAULT_WEBHOOK_TOLERANCE: Duration = Duration::from_secs(300);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Webhook ingestion errors
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Delivery is not signed")]
+    Unsigned,
+
+    #[error("Agent {0} is not a registered external agent")]
+    UnknownAgent(AgentId),
+
+    #[error("Malformed signature header: {0}")]
+    Malformed(String),
+
+    #[error("Signature does not match delivery")]
+    InvalidSignature,
+
+    #[error("Signature is {age_secs}s old, outside the allowed window")]
+    Stale { age_secs: i64 },
+
+    #[error("Invalid delivery: {0}")]
+    InvalidDelivery(String),
+
+    #[error("No blob store is configured to hold message content")]
+    NoContentStore,
+
+    #[error(transparent)]
+    Store(#[from] DomainError),
+}
+
+/// What an external system posts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    /// The system's own identifier for this delivery
+    pub delivery_id: String,
+
+    /// The conversation it concerns
+    pub conversation_id: ConversationId,
+
+    /// The message or receipt
+    #[serde(flatten)]
+    pub payload: WebhookPayload,
+}
+
+/// The body of a delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebhookPayload {
+    /// The external agent posts a message
+    Message {
+        message_id: MessageId,
+        content: String,
+        #[serde(default)]
+        tokens: u64,
+    },
+    /// The external agent received or read a message
+    Receipt {
+        message_id: MessageId,
+        acknowledgement: Acknowledgement,
+    },
+}
+
+/// Signing secret of one external agent
+#[derive(Clone)]
+pub struct ExternalAgentSecret {
+    /// Name of the external system, recorded as message provenance
+    pub system: String,
+
+    /// Shared HMAC secret
+    pub secret: Vec<u8>,
+}
+
+impl std::fmt::Debug for ExternalAgentSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalAgentSecret")
+            .field("system", &self.system)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An external agent as configured: its secret is named, not inlined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalAgentRegistration {
+    /// Agent ID the external system acts as
+    pub agent_id: AgentId,
+
+    /// Name of the external system
+    pub system: String,
+
+    /// Name of the secret holding its HMAC key, looked up in a `SecretsProvider`
+    pub secret: String,
+}
+
+/// Looks up the signing secret of an external agent
+pub trait WebhookSecrets: Send + Sync {
+    /// The secret `agent_id` signs with, if it is an external agent
+    fn secret(&self, agent_id: AgentId) -> Option<ExternalAgentSecret>;
+}
+
+/// In-memory external agent secrets
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryWebhookSecrets {
+    secrets: Arc<RwLock<HashMap<AgentId, ExternalAgentSecret>>>,
+}
+
+impl InMemoryWebhookSecrets {
+    /// Create an empty registry (accepts nothing)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `agent_id` as `system`, signing with `secret`; replaces any previous secret
+    pub fn register(
+        &self,
+        agent_id: AgentId,
+        system: impl Into<String>,
+        secret: impl Into<Vec<u8>>,
+    ) {
+        let secret = ExternalAgentSecret {
+            system: system.into(),
+            secret: secret.into(),
+        };
+        self.secrets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(agent_id, secret);
+    }
+
+    /// Register each of `agents`, fetching its HMAC key from `secrets`
+    pub async fn load(
+        agents: &[ExternalAgentRegistration],
+        secrets: &dyn SecretsProvider,
+    ) -> Result<Self, SecretsError> {
+        let registry = Self::new();
+        for agent in agents {
+            let key = secrets.get(&agent.secret).await?;
+            registry.register(agent.agent_id, agent.system.clone(), key.expose().as_bytes());
+        }
+        Ok(registry)
+    }
+
+    /// Stop accepting deliveries from `agent_id`
+    pub fn revoke(&self, agent_id: AgentId) {
+        self.secrets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&agent_id);
+    }
+}
+
+impl WebhookSecrets for InMemoryWebhookSecrets {
+    fn secret(&self, agent_id: AgentId) -> Option<ExternalAgentSecret> {
+        self.secrets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&agent_id)
+            .cloned()
+    }
+}
+
+/// Signature header value for `body` signed at `timestamp` (unix seconds)
+///
+/// What an external system, or a test, sends in [`WEBHOOK_SIGNATURE_HEADER`].
+pub fn sign_webhook(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mac = webhook_mac(secret, timestamp, body).finalize().into_bytes();
+    format!("t={},v1={}", timestamp, super::signing::to_hex(&mac))
+}
+
+/// HMAC-SHA256 over `"{timestamp}.{body}"`, ready to finalize or verify
+fn webhook_mac(secret: &[u8], timestamp: i64, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Verifies external agent deliveries and maps them onto conversation commands
+pub struct WebhookIngress {
+    secrets: Arc<dyn WebhookSecrets>,
+    blobs: Option<Arc<dyn BlobStore>>,
+    tolerance: Duration,
+}
+
+impl WebhookIngress {
+    /// Create an ingress accepting agents registered in `secrets`
+    pub fn new(secrets: Arc<dyn WebhookSecrets>) -> Self {
+        Self {
+            secrets,
+            blobs: None,
+            tolerance: DEFAULT_WEBHOOK_TOLERANCE,
+        }
+    }
+
+    /// Builder: upload message content to `blobs`
+    pub fn with_blob_store(mut self, blobs: Arc<dyn BlobStore>) -> Self {
+        self.blobs = Some(blobs);
+        self
+    }
+
+    /// Builder: refuse signatures older (or further ahead) than `tolerance`
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Check `signature` over `body` for `agent_id`, returning the agent's system name
+    pub fn verify(
+        &self,
+        agent_id: AgentId,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<String, WebhookError> {
+        let signature = signature.ok_or(WebhookError::Unsigned)?;
+        let registered = self
+            .secrets
+            .secret(agent_id)
+            .ok_or(WebhookError::UnknownAgent(agent_id))?;
+
+        let mut timestamp = None;
+        let mut candidates = Vec::new();
+        for part in signature.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", t)) => {
+                    let t = t
+                        .parse::<i64>()
+                        .map_err(|e| WebhookError::Malformed(e.to_string()))?;
+                    timestamp = Some(t);
+                }
+                Some(("v1", mac)) => candidates.push(mac),
+                _ => {}
+            }
+        }
+        let timestamp =
+            timestamp.ok_or_else(|| WebhookError::Malformed("missing t=".to_string()))?;
+        if candidates.is_empty() {
+            return Err(WebhookError::Malformed("missing v1=".to_string()));
+        }
+
+        let now = clock_now().timestamp();
+        if now.abs_diff(timestamp) > self.tolerance.as_secs() {
+            return Err(WebhookError::Stale {
+                age_secs: now.saturating_sub(timestamp),
+            });
+        }
+
+        // verify_slice compares in constant time
+        let expected = webhook_mac(&registered.secret, timestamp, body);
+        let matches = candidates.into_iter().any(|mac| {
+            super::signing::from_hex(mac)
+                .is_some_and(|mac| expected.clone().verify_slice(&mac).is_ok())
+        });
+        if !matches {
+            return Err(WebhookError::InvalidSignature);
+        }
+        Ok(registered.system)
+    }
+
+    /// Verify a delivery from `agent_id` and turn it into a conversation command
+    ///
+    /// Messages are posted by `ParticipantId::Agent(agent_id)` with external
+    /// provenance; the agent must be a participant of the conversation.
+    /// Message content is kept in the blob store, so messages are refused
+    /// when none is configured.
+    pub async fn ingest(
+        &self,
+        agent_id: AgentId,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<ConversationCommand, WebhookError> {
+        let system = self.verify(agent_id, signature, body)?;
+        let verified_at = clock_now();
+        let delivery: WebhookDelivery = serde_json::from_slice(body)
+            .map_err(|e| WebhookError::InvalidDelivery(e.to_string()))?;
+        let author = ParticipantId::Agent(agent_id);
+
+        let command = match delivery.payload {
+            WebhookPayload::Message {
+                message_id,
+                content,
+                tokens,
+            } => {
+                let blobs = self.blobs.as_ref().ok_or(WebhookError::NoContentStore)?;
+                let reference = ContentRef::of(content.as_bytes());
+                blobs.put(&reference, content.into_bytes()).await?;
+                let provenance = MessageProvenance::External {
+                    system,
+                    delivery_id: delivery.delivery_id,
+                    content: Some(reference),
+                    verified_at,
+                };
+                ConversationCommand::PostMessage(
+                    PostMessage::new(delivery.conversation_id, author, message_id)
+                        .with_tokens(tokens)
+                        .with_provenance(provenance),
+                )
+            }
+            WebhookPayload::Receipt {
+                message_id,
+                acknowledgement,
+            } => ConversationCommand::AcknowledgeMessage(match acknowledgement {
+                Acknowledgement::Delivered => {
+                    AcknowledgeMessage::delivered(delivery.conversation_id, author, message_id)
+                }
+                Acknowledgement::Read => {
+                    AcknowledgeMessage::read(delivery.conversation_id, author, message_id)
+                }
+            }),
+        };
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::InMemoryBlobStore;
+
+    #[tokio::test]
+    async fn test_signed_message_is_posted_with_external_provenance() {
+        let agent_id = AgentId::new();
+        let secrets = InMemoryWebhookSecrets::new();
+        secrets.register(agent_id, "crm", b"s3cret".to_vec());
+        let blobs = Arc::new(InMemoryBlobStore::new());
+        let ingress = WebhookIngress::new(Arc::new(secrets)).with_blob_store(blobs.clone());
+
+        let delivery = WebhookDelivery {
+            delivery_id: "evt_1".to_string(),
+            conversation_id: ConversationId::new(),
+            payload: WebhookPayload::Message {
+                message_id: MessageId::new(),
+                content: "Ticket #42 was closed".to_string(),
+                tokens: 6,
+            },
+        };
+        let body = serde_json::to_vec(&delivery).unwrap();
+        let now = clock_now().timestamp();
+
+        let forged = sign_webhook(b"guess", now, &body);
+        let refused = ingress.ingest(agent_id, Some(&forged), &body).await;
+        assert!(matches!(refused, Err(WebhookError::InvalidSignature)));
+        let stale = sign_webhook(b"s3cret", now - 3600, &body);
+        let refused = ingress.ingest(agent_id, Some(&stale), &body).await;
+        assert!(matches!(refused, Err(WebhookError::Stale { .. })));
+
+        let signature = sign_webhook(b"s3cret", now, &body);
+        let without_store = WebhookIngress::new(ingress.secrets.clone());
+        let refused = without_store.ingest(agent_id, Some(&signature), &body).await;
+        assert!(matches!(refused, Err(WebhookError::NoContentStore)));
+        let command = ingress
+            .ingest(agent_id, Some(&signature), &body)
+            .await
+            .unwrap();
+        let ConversationCommand::PostMessage(post) = command else {
+            panic!("expected a posted message");
+        };
+        assert_eq!(post.author, ParticipantId::Agent(agent_id));
+        assert_eq!(post.tokens, 6);
+        let MessageProvenance::External {
+            system, content, ..
+        } = post.provenance
+        else {
+            panic!("expected external provenance");
+        };
+        assert_eq!(system, "crm");
+        let stored = blobs.get(&content.unwrap()).await.unwrap().unwrap();
+        assert_eq!(stored, b"Ticket #42 was closed");
+    }
+}
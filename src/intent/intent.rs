@@ -5,10 +5,17 @@
 //! Defines the different types of messages that can be sent to AI providers.
 //! Each intent type has different input requirements and response formats.
 
-use crate::capabilities::{CapabilityRequirements, RuntimeCapabilities};
+use crate::capabilities::{CapabilityRequirements, RequirementSource, RuntimeCapabilities};
+use crate::ports::estimate_tokens;
 use crate::value_objects::ContextMessage;
 use serde::{Deserialize, Serialize};
 
+/// Context tokens any chat-capable provider is assumed to handle
+///
+/// Intents estimated at or below this size place no context-length
+/// requirement on the provider.
+pub const BASELINE_CONTEXT_TOKENS: u32 = 4_096;
+
 /// Message intent representing what type of AI interaction is requested
 ///
 /// Each variant captures the specific inputs needed for that type of request.
@@ -122,38 +129,74 @@ impl MessageIntent {
     }
 
     /// Infer capability requirements from this intent
-    pub fn capability_requirements(&self) -> CapabilityRequirements {
-        match self {
+    ///
+    /// Chat needs `TEXT_CHAT`, plus `FUNCTION_CALLING` when it offers tools;
+    /// vision needs `TEXT_CHAT | VISION`; a streamed intent requires
+    /// streaming. A minimum context length is only demanded once the
+    /// estimated prompt outgrows [`BASELINE_CONTEXT_TOKENS`], so providers
+    /// that never declared a context length stay eligible for ordinary
+    /// requests.
+    pub fn infer_requirements(&self) -> CapabilityRequirements {
+        let (capabilities, stream) = match self {
             Self::Chat { tools, stream, .. } => {
                 let mut caps = RuntimeCapabilities::TEXT_CHAT;
-                if *stream {
-                    caps |= RuntimeCapabilities::STREAMING;
-                }
-                if tools.is_some() {
+                if tools.as_ref().is_some_and(|tools| !tools.is_empty()) {
                     caps |= RuntimeCapabilities::FUNCTION_CALLING;
                 }
-                CapabilityRequirements::new(caps)
-            }
-
-            Self::Completion { .. } => {
-                CapabilityRequirements::new(RuntimeCapabilities::TEXT_CHAT)
+                (caps, *stream)
             }
+            Self::Completion { .. } => (RuntimeCapabilities::TEXT_CHAT, false),
+            Self::Vision { stream, .. } => (
+                RuntimeCapabilities::TEXT_CHAT | RuntimeCapabilities::VISION,
+                *stream,
+            ),
+            Self::Embedding { .. } => (RuntimeCapabilities::EMBEDDINGS, false),
+            Self::ImageGeneration { .. } => (RuntimeCapabilities::IMAGE_GENERATION, false),
+        };
+
+        let mut requirements = CapabilityRequirements::new(capabilities);
+        requirements.source = RequirementSource::Inferred;
+        if stream {
+            requirements = requirements.with_streaming();
+        }
+        let estimate = self.estimated_context_tokens();
+        if estimate > BASELINE_CONTEXT_TOKENS {
+            requirements = requirements.with_min_context(estimate);
+        }
+        requirements
+    }
 
-            Self::Vision { stream, .. } => {
-                let mut caps = RuntimeCapabilities::TEXT_CHAT | RuntimeCapabilities::VISION;
-                if *stream {
-                    caps |= RuntimeCapabilities::STREAMING;
-                }
-                CapabilityRequirements::new(caps)
-            }
+    /// Infer capability requirements from this intent
+    ///
+    /// Same as [`MessageIntent::infer_requirements`].
+    pub fn capability_requirements(&self) -> CapabilityRequirements {
+        self.infer_requirements()
+    }
 
-            Self::Embedding { .. } => {
-                CapabilityRequirements::new(RuntimeCapabilities::EMBEDDINGS)
+    /// Rough number of context tokens this intent needs
+    ///
+    /// Counts the prompt text, plus the requested output for completions.
+    /// Embedding inputs are embedded one at a time, so only the longest
+    /// counts.
+    pub fn estimated_context_tokens(&self) -> u32 {
+        let context_tokens = |context: &[ContextMessage]| -> u32 {
+            context.iter().map(|m| estimate_tokens(&m.content)).sum()
+        };
+        match self {
+            Self::Chat { context, .. } | Self::Vision { context, .. } => context_tokens(context),
+            Self::Completion {
+                prompt,
+                suffix,
+                max_tokens,
+            } => {
+                estimate_tokens(prompt)
+                    + suffix.as_deref().map(estimate_tokens).unwrap_or(0)
+                    + max_tokens.unwrap_or(0)
             }
-
-            Self::ImageGeneration { .. } => {
-                CapabilityRequirements::new(RuntimeCapabilities::IMAGE_GENERATION)
+            Self::Embedding { input, .. } => {
+                input.iter().map(|text| estimate_tokens(text)).max().unwrap_or(0)
             }
+            Self::ImageGeneration { prompt, .. } => estimate_tokens(prompt),
         }
     }
 
@@ -181,18 +224,28 @@ impl MessageIntent {
 }
 
 /// Tool/function definition for function calling
+///
+/// A tool is identified by name and version; a changed parameter schema is
+/// published as a new version rather than edited in place.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolDefinition {
     /// Tool name
     pub name: String,
+    /// Tool version (1.0.0 for definitions that predate versioning)
+    #[serde(default = "initial_tool_version")]
+    pub version: semver::Version,
     /// Tool description
     pub description: String,
     /// JSON schema for parameters
     pub parameters: serde_json::Value,
 }
 
+fn initial_tool_version() -> semver::Version {
+    semver::Version::new(1, 0, 0)
+}
+
 impl ToolDefinition {
-    /// Create a new tool definition
+    /// Create a new tool definition at version 1.0.0
     pub fn new(
         name: impl Into<String>,
         description: impl Into<String>,
@@ -200,10 +253,17 @@ impl ToolDefinition {
     ) -> Self {
         Self {
             name: name.into(),
+            version: initial_tool_version(),
             description: description.into(),
             parameters,
         }
     }
+
+    /// Builder: set the version
+    pub fn with_version(mut self, version: semver::Version) -> Self {
+        self.version = version;
+        self
+    }
 }
 
 /// Image input for vision requests
@@ -343,6 +403,26 @@ mod tests {
         assert!(reqs.capabilities.contains(RuntimeCapabilities::VISION));
     }
 
+    #[test]
+    fn test_infer_requirements() {
+        let quiet = MessageIntent::Chat {
+            context: vec![ContextMessage::user("Hello")],
+            tools: Some(vec![]),
+            stream: false,
+        };
+        let reqs = quiet.infer_requirements();
+        assert_eq!(reqs.capabilities, RuntimeCapabilities::TEXT_CHAT);
+        assert!(!reqs.requires_streaming);
+        assert_eq!(reqs.min_context_length, None);
+        assert!(matches!(reqs.source, RequirementSource::Inferred));
+
+        let long = MessageIntent::chat(vec![ContextMessage::user("word ".repeat(40_000))]);
+        let reqs = long.infer_requirements();
+        assert!(reqs.requires_streaming);
+        assert_eq!(reqs.min_context_length, Some(50_000));
+        assert!(reqs.capabilities.contains(RuntimeCapabilities::LONG_CONTEXT));
+    }
+
     #[test]
     fn test_embedding_intent_requirements() {
         let intent = MessageIntent::embedding(vec!["Hello world".to_string()]);
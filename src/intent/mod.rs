@@ -12,7 +12,7 @@
 //! ┌─────────────────────────────────────────────────────────────────────┐
 //! │                        Message Intent                               │
 //! │                                                                     │
-//! │   User Request ──> MessageIntent ──> capability_requirements()      │
+//! │   User Request ──> MessageIntent ──> infer_requirements()           │
 //! │                          │                    │                     │
 //! │                          │                    v                     │
 //! │                          │           CapabilityRequirements         │
@@ -62,7 +62,9 @@
 mod intent;
 mod response;
 
-pub use intent::{ImageInput, ImageSize, ImageStyle, MessageIntent, ToolDefinition};
+pub use intent::{
+    ImageInput, ImageSize, ImageStyle, MessageIntent, ToolDefinition, BASELINE_CONTEXT_TOKENS,
+};
 pub use response::{
     ChatResponse, EmbeddingResponse, GeneratedImage, ImageGenerationResponse, ToolCall,
 };
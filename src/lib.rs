@@ -71,6 +71,8 @@
 //! - `commands`/`events`: CQRS command and event types
 //! - `value_objects`: Domain value objects
 //! - `infrastructure`: Event store, NATS integration
+//! - `read_model`: Agent projections and read-your-writes queries
+//! - `domain`: `AgentDomainBuilder`, wiring stores, providers and services into an `AgentDomain`
 
 // Core domain modules
 pub mod aggregate;
@@ -78,6 +80,7 @@ pub mod commands;
 pub mod events;
 pub mod value_objects;
 pub mod infrastructure;
+pub mod read_model;
 
 // State machine for agent lifecycle
 pub mod state_machine;
@@ -100,12 +103,20 @@ pub mod services;
 // Pure functional configuration parser
 pub mod config;
 
+// Stores, adapters and services wired together for embedding hosts
+pub mod domain;
+
+// Fixtures shared by unit tests
+#[cfg(test)]
+mod test_support;
+
 // Re-export primary types
 pub use aggregate::Agent;
 pub use commands::*;
 pub use events::*;
 pub use value_objects::*;
 pub use infrastructure::*;
+pub use read_model::*;
 pub use ports::*;
 pub use state_machine::*;
 pub use capabilities::*;
@@ -113,3 +124,4 @@ pub use intent::*;
 pub use adapters::*;
 pub use services::*;
 pub use config::*;
+pub use domain::{AgentDomain, AgentDomainBuilder};
@@ -0,0 +1,74 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! AWS Secrets Manager adapter
+//!
+//! Secret names are secret IDs (name or ARN). A `#key` suffix reads one
+//! field from a JSON secret string: `prod/llm#OPENAI_API_KEY`.
+
+use crate::ports::{Secret, SecretsError, SecretsProvider, SecretsResult};
+use async_trait::async_trait;
+
+/// Secrets from AWS Secrets Manager
+#[derive(Debug, Clone)]
+pub struct AwsSecretsManagerProvider {
+    client: aws_sdk_secretsmanager::Client,
+}
+
+impl AwsSecretsManagerProvider {
+    /// Use an existing client
+    pub fn new(client: aws_sdk_secretsmanager::Client) -> Self {
+        Self { client }
+    }
+
+    /// Build a client from the standard AWS environment and profile chain
+    pub async fn from_env() -> Self {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::new(aws_sdk_secretsmanager::Client::new(&config))
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    async fn get(&self, name: &str) -> SecretsResult<Secret> {
+        let (secret_id, key) = match name.split_once('#') {
+            Some((id, key)) => (id, Some(key)),
+            None => (name, None),
+        };
+
+        let output = self
+            .client
+            .get_secret_value()
+            .secret_id(secret_id)
+            .send()
+            .await
+            .map_err(|e| match e.as_service_error() {
+                Some(err) if err.is_resource_not_found_exception() => {
+                    SecretsError::NotFound(name.to_string())
+                }
+                _ => SecretsError::Backend(e.to_string()),
+            })?;
+        let value = output
+            .secret_string()
+            .ok_or_else(|| SecretsError::Invalid {
+                name: name.to_string(),
+                reason: "binary secrets are not supported".to_string(),
+            })?;
+
+        let Some(key) = key else {
+            return Ok(Secret::new(value));
+        };
+        let fields: serde_json::Value =
+            serde_json::from_str(value).map_err(|e| SecretsError::Invalid {
+                name: name.to_string(),
+                reason: e.to_string(),
+            })?;
+        fields[key]
+            .as_str()
+            .map(Secret::new)
+            .ok_or_else(|| SecretsError::NotFound(name.to_string()))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "aws-secrets-manager"
+    }
+}
@@ -0,0 +1,407 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Chaos Layer - fault injection for ChatPort
+//!
+//! Wraps any [`ChatPort`] and injects the failures real providers produce,
+//! so retry, circuit-breaker, and chunk reassembly logic can be exercised
+//! without a misbehaving upstream.
+//!
+//! ```text
+//! caller ──> ChaosLayer ──> inner adapter
+//!               │
+//!               ├─ latency          before the request and between chunks
+//!               ├─ rate limit       send() fails with RateLimitExceeded
+//!               ├─ disconnect       stream ends with StreamInterrupted
+//!               └─ malformed chunk  duplicate/skipped index or garbled text
+//! ```
+//!
+//! Available in tests and behind the `chaos` feature. Faults are drawn from
+//! a seeded generator, so a failing run can be replayed with the same seed.
+
+use crate::intent::ImageInput;
+use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream};
+use crate::value_objects::{ContextMessage, ModelConfig, StreamingChunk};
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Ways a chunk can be corrupted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MalformedChunk {
+    /// Repeat the previous chunk index
+    DuplicateIndex,
+    /// Jump one index ahead, leaving a gap
+    SkippedIndex,
+    /// Replace content with replacement characters and a NUL byte
+    GarbledContent,
+}
+
+/// Fault injection settings
+///
+/// Rates are probabilities in `0.0..=1.0`; a rate of zero disables that fault.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosConfig {
+    /// Delay before the request and before every chunk
+    pub latency: Duration,
+    /// Probability that `send()` fails with `RateLimitExceeded`
+    pub rate_limit_rate: f64,
+    /// `retry_after_secs` reported by injected rate-limit errors
+    pub retry_after_secs: Option<u64>,
+    /// Per-chunk probability of a mid-stream disconnect
+    pub disconnect_rate: f64,
+    /// Always disconnect after this many chunks
+    pub disconnect_after: Option<u32>,
+    /// Per-chunk probability of corrupting the chunk
+    pub malformed_rate: f64,
+    /// Seed for the fault generator
+    pub seed: u64,
+}
+
+impl ChaosConfig {
+    /// No faults; enable them with the builders
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: add latency
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Builder: inject rate-limit errors
+    pub fn with_rate_limit_rate(mut self, rate: f64, retry_after_secs: Option<u64>) -> Self {
+        self.rate_limit_rate = rate;
+        self.retry_after_secs = retry_after_secs;
+        self
+    }
+
+    /// Builder: inject random mid-stream disconnects
+    pub fn with_disconnect_rate(mut self, rate: f64) -> Self {
+        self.disconnect_rate = rate;
+        self
+    }
+
+    /// Builder: disconnect after `chunks` chunks
+    pub fn with_disconnect_after(mut self, chunks: u32) -> Self {
+        self.disconnect_after = Some(chunks);
+        self
+    }
+
+    /// Builder: inject malformed chunks
+    pub fn with_malformed_rate(mut self, rate: f64) -> Self {
+        self.malformed_rate = rate;
+        self
+    }
+
+    /// Builder: set the generator seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, rate) in [
+            ("rate_limit_rate", self.rate_limit_rate),
+            ("disconnect_rate", self.disconnect_rate),
+            ("malformed_rate", self.malformed_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!("{} must be between 0.0 and 1.0, got {}", name, rate));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            rate_limit_rate: 0.0,
+            retry_after_secs: None,
+            disconnect_rate: 0.0,
+            disconnect_after: None,
+            malformed_rate: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Deterministic SplitMix64 generator
+#[derive(Debug, Clone)]
+struct ChaosRng(u64);
+
+impl ChaosRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn roll(&mut self, rate: f64) -> bool {
+        rate > 0.0 && ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < rate
+    }
+
+    fn malformation(&mut self) -> MalformedChunk {
+        match self.next_u64() % 3 {
+            0 => MalformedChunk::DuplicateIndex,
+            1 => MalformedChunk::SkippedIndex,
+            _ => MalformedChunk::GarbledContent,
+        }
+    }
+}
+
+/// ChatPort decorator that injects faults
+pub struct ChaosLayer {
+    inner: Arc<dyn ChatPort>,
+    config: ChaosConfig,
+    rng: Mutex<ChaosRng>,
+}
+
+impl ChaosLayer {
+    /// Wrap an adapter
+    pub fn new(inner: Arc<dyn ChatPort>, config: ChaosConfig) -> Self {
+        let rng = Mutex::new(ChaosRng(config.seed));
+        Self { inner, config, rng }
+    }
+
+    /// The active configuration
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    fn rng(&self) -> std::sync::MutexGuard<'_, ChaosRng> {
+        self.rng.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl ChatPort for ChaosLayer {
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+
+        // Each stream gets its own generator so concurrent requests stay reproducible
+        let (rate_limited, stream_seed) = {
+            let mut rng = self.rng();
+            (rng.roll(self.config.rate_limit_rate), rng.next_u64())
+        };
+        if rate_limited {
+            tracing::debug!("chaos: injecting rate limit");
+            return Err(ChatError::RateLimitExceeded {
+                retry_after_secs: self.config.retry_after_secs,
+            });
+        }
+
+        let inner = self.inner.send(config, context).await?;
+        let state = ChaosStream {
+            inner,
+            config: self.config.clone(),
+            rng: ChaosRng(stream_seed),
+            delivered: 0,
+            done: false,
+        };
+
+        Ok(Box::pin(stream::unfold(state, |mut state| async move {
+            state.next_chunk().await.map(|item| (item, state))
+        })))
+    }
+
+    /// Image requests pass through without injected faults
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        self.inner.send_vision(config, context, images).await
+    }
+
+    async fn prewarm(&self, config: &ModelConfig) -> ChatResult<()> {
+        self.inner.prewarm(config).await
+    }
+
+    async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+        self.inner.has_model(model).await
+    }
+
+    async fn context_window(&self, model: &str) -> ChatResult<Option<u32>> {
+        self.inner.context_window(model).await
+    }
+
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        self.inner.count_tokens(config, context).await
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        self.inner.health_check().await
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.inner.provider_name()
+    }
+}
+
+/// Stream state for [`ChaosLayer::send`]
+struct ChaosStream {
+    inner: ChatStream,
+    config: ChaosConfig,
+    rng: ChaosRng,
+    delivered: u32,
+    done: bool,
+}
+
+impl ChaosStream {
+    async fn next_chunk(&mut self) -> Option<ChatResult<StreamingChunk>> {
+        if self.done {
+            return None;
+        }
+
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+
+        let disconnect = self
+            .config
+            .disconnect_after
+            .is_some_and(|n| self.delivered >= n)
+            || self.rng.roll(self.config.disconnect_rate);
+        if disconnect {
+            tracing::debug!("chaos: injecting disconnect after {} chunks", self.delivered);
+            self.done = true;
+            return Some(Err(ChatError::StreamInterrupted(
+                "chaos: injected disconnect".into(),
+            )));
+        }
+
+        let mut chunk = match self.inner.next().await? {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        self.done = chunk.is_final;
+        self.delivered += 1;
+
+        if self.rng.roll(self.config.malformed_rate) {
+            let kind = self.rng.malformation();
+            tracing::debug!("chaos: corrupting chunk {} ({:?})", chunk.chunk_index, kind);
+            match kind {
+                MalformedChunk::DuplicateIndex => {
+                    chunk.chunk_index = chunk.chunk_index.saturating_sub(1)
+                }
+                MalformedChunk::SkippedIndex => chunk.chunk_index += 1,
+                MalformedChunk::GarbledContent => {
+                    chunk.content = format!("\u{FFFD}\u{0}{}\u{FFFD}", chunk.content.len())
+                }
+            }
+        }
+
+        Some(Ok(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::MockChatAdapter;
+
+    fn layer(config: ChaosConfig) -> ChaosLayer {
+        ChaosLayer::new(Arc::new(MockChatAdapter::new()), config)
+    }
+
+    fn request() -> (ModelConfig, Vec<ContextMessage>) {
+        (
+            ModelConfig::mock(),
+            vec![ContextMessage::user("one two three four")],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_no_faults_passes_through() {
+        let (config, context) = request();
+        let expected: Vec<_> = MockChatAdapter::new()
+            .send(&config, context.clone())
+            .await
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+
+        let chunks: Vec<_> = layer(ChaosConfig::new())
+            .send(&config, context)
+            .await
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks, expected);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_injected() {
+        let (config, context) = request();
+        let chaos = layer(ChaosConfig::new().with_rate_limit_rate(1.0, Some(7)));
+
+        let result = chaos.send(&config, context).await;
+
+        assert!(matches!(
+            result,
+            Err(ChatError::RateLimitExceeded { retry_after_secs: Some(7) })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_after_chunks() {
+        let (config, context) = request();
+        let chaos = layer(ChaosConfig::new().with_disconnect_after(2));
+
+        let items: Vec<_> = chaos.send(&config, context).await.unwrap().collect().await;
+
+        assert_eq!(items.len(), 3);
+        assert!(items[..2].iter().all(|i| i.is_ok()));
+        assert!(matches!(items[2], Err(ChatError::StreamInterrupted(_))));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_chunks_are_reproducible() {
+        let (config, context) = request();
+        let run = |seed| {
+            let chaos = layer(ChaosConfig::new().with_malformed_rate(0.5).with_seed(seed));
+            let (config, context) = (config.clone(), context.clone());
+            async move {
+                chaos
+                    .send(&config, context)
+                    .await
+                    .unwrap()
+                    .map(|c| c.unwrap())
+                    .collect::<Vec<_>>()
+                    .await
+            }
+        };
+
+        assert_eq!(run(42).await, run(42).await);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(ChaosConfig::new().validate().is_ok());
+        assert!(ChaosConfig::new().with_malformed_rate(1.5).validate().is_err());
+    }
+}
@@ -0,0 +1,569 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Gemini Chat Adapter
+//!
+//! Connects to Google's Generative Language API for chat, vision and
+//! embeddings. Responses stream over server-sent events from
+//! `models/{model}:streamGenerateContent?alt=sse`.
+//!
+//! The API key is looked up through a [`SecretsProvider`] on every request
+//! (`GEMINI_API_KEY` by default), so rotated keys apply immediately.
+//!
+//! Prompts are counted with `models/{model}:countTokens`, and the final chunk
+//! carries the token usage from the response's `usageMetadata`.
+
+use crate::intent::{EmbeddingResponse, ImageInput};
+use crate::ports::{
+    api_key_secret_name, ChatError, ChatPort, ChatResult, ChatStream, EmbeddingPort,
+    EnvSecretsProvider, ProviderFailure, SecretsProvider,
+};
+use crate::value_objects::{
+    ContextMessage, FinishReason, MessageRole, ModelConfig, ProviderType, StreamingChunk,
+    TokenUsage,
+};
+use async_trait::async_trait;
+use super::sse;
+use super::http_deadline::{timeout_error, within_deadline};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Default Generative Language API base URL
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Default embedding model
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-004";
+
+/// Gemini chat adapter
+///
+/// Implements [`ChatPort`] (including image input) and [`EmbeddingPort`].
+#[derive(Clone)]
+pub struct GeminiChatAdapter {
+    base_url: String,
+    client: reqwest::Client,
+    secrets: Arc<dyn SecretsProvider>,
+    api_key_name: String,
+}
+
+impl GeminiChatAdapter {
+    /// Create adapter reading `GEMINI_API_KEY` from `secrets`
+    pub fn new(secrets: Arc<dyn SecretsProvider>) -> ChatResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(300))
+            .build()
+            .map_err(|e| ChatError::ConfigurationError(e.to_string()))?;
+
+        Ok(Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            client,
+            secrets,
+            api_key_name: api_key_secret_name(ProviderType::Gemini)
+                .unwrap_or("GEMINI_API_KEY")
+                .to_string(),
+        })
+    }
+
+    /// Create adapter reading `GEMINI_API_KEY` from the environment
+    pub fn from_env() -> ChatResult<Self> {
+        Self::new(Arc::new(EnvSecretsProvider::new()))
+    }
+
+    /// Builder: use a different API base URL (proxies, regional endpoints)
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Builder: read the API key from a different secret name
+    pub fn with_api_key_name(mut self, name: impl Into<String>) -> Self {
+        self.api_key_name = name.into();
+        self
+    }
+
+    async fn api_key(&self) -> ChatResult<String> {
+        self.secrets
+            .get(&self.api_key_name)
+            .await
+            .map(|key| key.expose().to_string())
+            .map_err(|e| ChatError::AuthenticationFailed(e.to_string()))
+    }
+
+    /// Build the request body; images are attached to the last user turn
+    fn to_request(
+        config: &ModelConfig,
+        context: &[ContextMessage],
+        images: &[ImageInput],
+    ) -> GeminiRequest {
+        let mut system = Vec::new();
+        if !config.system_prompt.is_empty() {
+            system.push(GeminiPart::text(&config.system_prompt));
+        }
+
+        let mut contents: Vec<GeminiContent> = Vec::new();
+        for msg in context {
+            let role = match msg.role {
+                MessageRole::System => {
+                    system.push(GeminiPart::text(&msg.content));
+                    continue;
+                }
+                MessageRole::User => "user",
+                MessageRole::Assistant => "model",
+            };
+            contents.push(GeminiContent {
+                role: Some(role.to_string()),
+                parts: vec![GeminiPart::text(&msg.content)],
+            });
+        }
+
+        if !images.is_empty() {
+            let parts = images.iter().map(GeminiPart::image);
+            match contents
+                .iter_mut()
+                .rev()
+                .find(|c| c.role.as_deref() == Some("user"))
+            {
+                Some(last_user) => last_user.parts.extend(parts),
+                None => contents.push(GeminiContent {
+                    role: Some("user".to_string()),
+                    parts: parts.collect(),
+                }),
+            }
+        }
+
+        GeminiRequest {
+            contents,
+            system_instruction: (!system.is_empty()).then_some(GeminiContent {
+                role: None,
+                parts: system,
+            }),
+            generation_config: config
+                .sampling_params()
+                .provider_parameters(ProviderType::Gemini),
+        }
+    }
+
+    fn finish_reason(reason: &str) -> FinishReason {
+        match reason {
+            "STOP" => FinishReason::Stop,
+            "MAX_TOKENS" => FinishReason::Length,
+            "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => {
+                FinishReason::ContentFilter
+            }
+            _ => FinishReason::Error,
+        }
+    }
+
+    fn parse_event(data: &str) -> ChatResult<GeminiResponse> {
+        serde_json::from_str(data)
+            .map_err(|e| ChatError::StreamInterrupted(format!("bad Gemini event: {}", e)))
+    }
+
+    fn to_chunk(index: u32, response: GeminiResponse) -> StreamingChunk {
+        let usage = response
+            .usage_metadata
+            .map(|u| TokenUsage::new(u.prompt_token_count, u.candidates_token_count));
+        let candidate = response.candidates.into_iter().next();
+        let text: String = candidate
+            .as_ref()
+            .and_then(|c| c.content.as_ref())
+            .map(|content| {
+                content
+                    .parts
+                    .iter()
+                    .filter_map(|p| p.text.as_deref())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let Some(reason) = candidate.and_then(|c| c.finish_reason) else {
+            return StreamingChunk::new(index, text);
+        };
+        let chunk = StreamingChunk::final_chunk(index, text, Self::finish_reason(&reason));
+        match usage {
+            Some(usage) => chunk.with_usage(usage),
+            None => chunk,
+        }
+    }
+
+    fn map_status(status: reqwest::StatusCode, body: String, model: &str) -> ChatError {
+        match status.as_u16() {
+            400 => ChatError::InvalidRequest(body),
+            401 | 403 => ChatError::AuthenticationFailed(body),
+            404 => ChatError::ModelNotAvailable(model.to_string()),
+            429 => ChatError::RateLimitExceeded { retry_after_secs: None },
+            _ => ChatError::Provider(ProviderFailure::from_http("gemini", status.as_u16(), body)),
+        }
+    }
+
+    fn map_send_error(e: reqwest::Error) -> ChatError {
+        if e.is_connect() {
+            ChatError::ConnectionFailed(format!("Cannot connect to Gemini: {}", e))
+        } else if e.is_timeout() {
+            timeout_error(300)
+        } else {
+            ChatError::ProviderError(e.to_string())
+        }
+    }
+
+    async fn stream(
+        &self,
+        config: &ModelConfig,
+        request: GeminiRequest,
+    ) -> ChatResult<ChatStream> {
+        let url = format!(
+            "{}/models/{}:streamGenerateContent?alt=sse",
+            self.base_url, config.model_name
+        );
+        let response = within_deadline(self.client.post(url))
+            .header("x-goog-api-key", self.api_key().await?)
+            .json(&request)
+            .send()
+            .await
+            .map_err(Self::map_send_error)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Self::map_status(status, body, &config.model_name));
+        }
+
+        let chunks = sse::data_events(response.bytes_stream())
+            .enumerate()
+            .map(|(idx, event)| {
+                event
+                    .and_then(|data| Self::parse_event(&data))
+                    .map(|resp| Self::to_chunk(idx as u32, resp))
+            });
+        Ok(Box::pin(chunks))
+    }
+}
+
+#[async_trait]
+impl ChatPort for GeminiChatAdapter {
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        self.stream(config, Self::to_request(config, &context, &[])).await
+    }
+
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        self.stream(config, Self::to_request(config, &context, &images)).await
+    }
+
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        let request = GeminiCountRequest {
+            generate_content_request: GeminiCountContent {
+                model: format!("models/{}", config.model_name),
+                request: Self::to_request(config, context, &[]),
+            },
+        };
+        let url = format!("{}/models/{}:countTokens", self.base_url, config.model_name);
+        let response = within_deadline(self.client.post(url))
+            .header("x-goog-api-key", self.api_key().await?)
+            .json(&request)
+            .send()
+            .await
+            .map_err(Self::map_send_error)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Self::map_status(status, body, &config.model_name));
+        }
+        let counted: GeminiCountResponse = response
+            .json()
+            .await
+            .map_err(|e| ChatError::ProviderError(format!("bad Gemini token count: {}", e)))?;
+        Ok(Some(counted.total_tokens))
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        let response = self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .header("x-goog-api-key", self.api_key().await?)
+            .send()
+            .await
+            .map_err(|e| ChatError::ConnectionFailed(format!("Gemini not reachable: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body = response.text().await.unwrap_or_default();
+            Err(Self::map_status(status, body, "models"))
+        }
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "gemini"
+    }
+}
+
+#[async_trait]
+impl EmbeddingPort for GeminiChatAdapter {
+    async fn embed(
+        &self,
+        input: Vec<String>,
+        model: Option<&str>,
+    ) -> ChatResult<EmbeddingResponse> {
+        let model = model.unwrap_or(DEFAULT_EMBEDDING_MODEL);
+        let request = GeminiEmbedRequest {
+            requests: input
+                .iter()
+                .map(|text| GeminiEmbedContent {
+                    model: format!("models/{}", model),
+                    content: GeminiContent {
+                        role: None,
+                        parts: vec![GeminiPart::text(text)],
+                    },
+                })
+                .collect(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/models/{}:batchEmbedContents", self.base_url, model))
+            .header("x-goog-api-key", self.api_key().await?)
+            .json(&request)
+            .send()
+            .await
+            .map_err(Self::map_send_error)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Self::map_status(status, body, model));
+        }
+
+        let body: GeminiEmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| ChatError::ProviderError(format!("bad Gemini embedding: {}", e)))?;
+        Ok(EmbeddingResponse::new(
+            body.embeddings.into_iter().map(|e| e.values).collect(),
+            model,
+        ))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "gemini"
+    }
+}
+
+// Gemini API types
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    generation_config: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GeminiContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline_data: Option<GeminiBlob>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_data: Option<GeminiFileData>,
+}
+
+impl GeminiPart {
+    fn text(text: &str) -> Self {
+        Self {
+            text: Some(text.to_string()),
+            ..Self::default()
+        }
+    }
+
+    fn image(image: &ImageInput) -> Self {
+        match image {
+            ImageInput::Base64 { data, media_type } => Self {
+                inline_data: Some(GeminiBlob {
+                    mime_type: media_type.clone(),
+                    data: data.clone(),
+                }),
+                ..Self::default()
+            },
+            ImageInput::Url { url } => Self {
+                file_data: Some(GeminiFileData {
+                    mime_type: mime_type_for(url).to_string(),
+                    file_uri: url.clone(),
+                }),
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// Guess an image MIME type from a URL's extension
+fn mime_type_for(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_ascii_lowercase();
+    match path.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("heic") => "image/heic",
+        _ => "image/jpeg",
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiBlob {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFileData {
+    mime_type: String,
+    file_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCountRequest {
+    generate_content_request: GeminiCountContent,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiCountContent {
+    model: String,
+    #[serde(flatten)]
+    request: GeminiRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCountResponse {
+    total_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiEmbedRequest {
+    requests: Vec<GeminiEmbedContent>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiEmbedContent {
+    model: String,
+    content: GeminiContent,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<GeminiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiEmbedding {
+    values: Vec<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_shape() {
+        let config = ModelConfig::gemini_pro().with_max_tokens(256);
+        let context = vec![
+            ContextMessage::system("You are helpful"),
+            ContextMessage::user("What's in this image?"),
+            ContextMessage::assistant("Which one?"),
+            ContextMessage::user("The first"),
+        ];
+        let images = vec![
+            ImageInput::base64("aGVsbG8=", "image/png"),
+            ImageInput::url("https://example.com/cat.webp?size=large"),
+        ];
+
+        let body = serde_json::to_value(GeminiChatAdapter::to_request(&config, &context, &images))
+            .unwrap();
+
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "You are helpful");
+        assert_eq!(body["contents"].as_array().unwrap().len(), 3);
+        assert_eq!(body["contents"][1]["role"], "model");
+        let last = &body["contents"][2]["parts"];
+        assert_eq!(last[1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(last[2]["fileData"]["mimeType"], "image/webp");
+        assert_eq!(body["generationConfig"]["max_output_tokens"], 256);
+    }
+
+    #[test]
+    fn test_sse_event_to_chunk() {
+        let line = concat!(
+            r#"data: {"candidates":[{"content":{"role":"model","parts":[{"text":"Hi"}]},"#,
+            r#""finishReason":"MAX_TOKENS"}],"#,
+            r#""usageMetadata":{"promptTokenCount":12,"candidatesTokenCount":256}}"#,
+        );
+        let data = sse::data_payload(line.as_bytes()).unwrap();
+        let response = GeminiChatAdapter::parse_event(&data).unwrap();
+        let chunk = GeminiChatAdapter::to_chunk(3, response);
+
+        assert_eq!(chunk.content, "Hi");
+        assert!(chunk.is_final);
+        assert_eq!(chunk.finish_reason, Some(FinishReason::Length));
+        assert_eq!(chunk.usage, Some(TokenUsage::new(12, 256)));
+        assert!(sse::data_payload(b": keep-alive\n").is_none());
+    }
+
+    #[test]
+    fn test_safety_stop_is_content_filter() {
+        assert_eq!(GeminiChatAdapter::finish_reason("SAFETY"), FinishReason::ContentFilter);
+        assert_eq!(GeminiChatAdapter::finish_reason("STOP"), FinishReason::Stop);
+    }
+}
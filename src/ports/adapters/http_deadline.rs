@@ -0,0 +1,30 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! HTTP timeouts derived from the caller's deadline
+//!
+//! The HTTP adapters build their clients with a generous timeout for slow
+//! models. When a request runs under [`Deadline::scope`], its timeout is
+//! lowered to what is left of the caller's budget, and a timeout caused by
+//! the deadline is reported as such rather than as a provider timeout.
+
+use crate::ports::ChatError;
+use crate::value_objects::Deadline;
+use reqwest::RequestBuilder;
+
+/// Cap `request`'s timeout at the remaining budget of the deadline in scope
+pub(crate) fn within_deadline(request: RequestBuilder) -> RequestBuilder {
+    match Deadline::current() {
+        Some(deadline) => request.timeout(deadline.remaining()),
+        None => request,
+    }
+}
+
+/// Error for a request that timed out, given the client's own timeout
+pub(crate) fn timeout_error(client_timeout_secs: u64) -> ChatError {
+    match Deadline::current() {
+        Some(deadline) if deadline.is_expired() => {
+            ChatError::DeadlineExceeded("provider".to_string())
+        }
+        _ => ChatError::Timeout(client_timeout_secs),
+    }
+}
@@ -0,0 +1,488 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! llama.cpp In-Process Adapter
+//!
+//! Runs a local GGUF model inside the service process, for deployments with
+//! no network access at all. Inference runs on a blocking thread and tokens
+//! are streamed back as they are sampled.
+//!
+//! The model is loaded once and shared; each request gets a fresh context
+//! of `context_size` tokens, clamped to the length the model was trained
+//! on. Prompts that do not fit are rejected with
+//! [`ChatError::ContextTooLong`], and generation is capped at whatever room
+//! the prompt leaves. Prompts are counted with the model's own tokenizer,
+//! and the final chunk reports the tokens used.
+
+use crate::intent::EmbeddingResponse;
+use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream, EmbeddingPort};
+use crate::value_objects::{
+    ContextMessage, FinishReason, MessageRole, ModelConfig, StreamingChunk, TokenUsage,
+};
+use async_trait::async_trait;
+use futures::stream;
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::{AddBos, LlamaChatMessage, LlamaModel, Special};
+use llama_cpp_2::sampling::LlamaSampler;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc;
+
+/// llama.cpp may only be initialised once per process
+static BACKEND: OnceLock<Result<LlamaBackend, String>> = OnceLock::new();
+
+fn backend() -> ChatResult<&'static LlamaBackend> {
+    BACKEND
+        .get_or_init(|| LlamaBackend::init().map_err(|e| e.to_string()))
+        .as_ref()
+        .map_err(|e| ChatError::ConfigurationError(format!("llama.cpp backend: {}", e)))
+}
+
+/// Local model configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlamaCppConfig {
+    /// Path to the GGUF model file
+    pub model_path: PathBuf,
+    /// Context window per request, in tokens
+    pub context_size: u32,
+    /// Layers to offload to the GPU (0 = CPU only)
+    pub gpu_layers: u32,
+    /// Inference threads (None = llama.cpp default)
+    pub threads: Option<i32>,
+}
+
+impl LlamaCppConfig {
+    /// Configure a model file with a 4096-token context on the CPU
+    pub fn new(model_path: impl Into<PathBuf>) -> Self {
+        Self {
+            model_path: model_path.into(),
+            context_size: 4096,
+            gpu_layers: 0,
+            threads: None,
+        }
+    }
+
+    /// Configure from `LLAMA_MODEL_PATH`, `LLAMA_CONTEXT_SIZE`,
+    /// `LLAMA_GPU_LAYERS` and `LLAMA_THREADS`
+    pub fn from_env() -> ChatResult<Self> {
+        let model_path = std::env::var("LLAMA_MODEL_PATH").map_err(|_| {
+            ChatError::ConfigurationError("LLAMA_MODEL_PATH is not set".to_string())
+        })?;
+        let parse = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+
+        let mut config = Self::new(model_path);
+        if let Some(size) = parse("LLAMA_CONTEXT_SIZE") {
+            config = config.with_context_size(size);
+        }
+        if let Some(layers) = parse("LLAMA_GPU_LAYERS") {
+            config.gpu_layers = layers;
+        }
+        config.threads = parse("LLAMA_THREADS").map(|t: u32| t as i32);
+        Ok(config)
+    }
+
+    /// Builder: set the per-request context window (at least 64 tokens)
+    ///
+    /// Clamped to the model's trained context length when it is loaded.
+    pub fn with_context_size(mut self, context_size: u32) -> Self {
+        self.context_size = context_size.max(64);
+        self
+    }
+
+    /// Builder: offload layers to the GPU
+    pub fn with_gpu_layers(mut self, gpu_layers: u32) -> Self {
+        self.gpu_layers = gpu_layers;
+        self
+    }
+
+    /// Builder: set the inference thread count
+    pub fn with_threads(mut self, threads: i32) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    fn context_params(&self, embeddings: bool) -> LlamaContextParams {
+        let mut params = LlamaContextParams::default()
+            .with_n_ctx(NonZeroU32::new(self.context_size))
+            .with_n_batch(self.context_size)
+            .with_embeddings(embeddings);
+        if let Some(threads) = self.threads {
+            params = params.with_n_threads(threads).with_n_threads_batch(threads);
+        }
+        params
+    }
+}
+
+/// In-process GGUF inference adapter
+///
+/// Implements [`ChatPort`] and [`EmbeddingPort`]. Clones share the loaded model.
+#[derive(Clone)]
+pub struct LlamaCppAdapter {
+    model: Arc<LlamaModel>,
+    config: LlamaCppConfig,
+}
+
+impl LlamaCppAdapter {
+    /// Load the model file (blocking; call from a blocking context or at startup)
+    pub fn load(mut config: LlamaCppConfig) -> ChatResult<Self> {
+        let params = LlamaModelParams::default().with_n_gpu_layers(config.gpu_layers);
+        let model = LlamaModel::load_from_file(backend()?, &config.model_path, &params)
+            .map_err(|e| {
+                ChatError::ModelNotAvailable(format!("{}: {}", config.model_path.display(), e))
+            })?;
+        let context_size = clamp_context_size(config.context_size, model.n_ctx_train());
+        if context_size < config.context_size {
+            tracing::warn!(
+                "Context size {} exceeds {}'s trained {} tokens; using {}",
+                config.context_size,
+                config.model_path.display(),
+                model.n_ctx_train(),
+                context_size
+            );
+            config.context_size = context_size;
+        }
+        Ok(Self {
+            model: Arc::new(model),
+            config,
+        })
+    }
+
+    /// The model's context length as trained
+    pub fn trained_context_size(&self) -> u32 {
+        self.model.n_ctx_train()
+    }
+
+    /// Render the conversation with the model's chat template
+    ///
+    /// Models without an embedded template fall back to a plain
+    /// role-prefixed transcript.
+    fn render_prompt(&self, config: &ModelConfig, context: &[ContextMessage]) -> String {
+        let mut turns: Vec<(&str, &str)> = Vec::new();
+        if !config.system_prompt.is_empty() {
+            turns.push(("system", config.system_prompt.as_str()));
+        }
+        turns.extend(context.iter().map(|msg| (role_name(msg.role), msg.content.as_str())));
+
+        let templated = self.model.chat_template(None).ok().and_then(|template| {
+            let messages = turns
+                .iter()
+                .map(|(role, content)| {
+                    LlamaChatMessage::new(role.to_string(), content.to_string())
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .ok()?;
+            self.model.apply_chat_template(&template, &messages, true).ok()
+        });
+        templated.unwrap_or_else(|| plain_transcript(&turns))
+    }
+
+    /// Generate on the current thread, sending chunks as tokens are sampled
+    fn generate(
+        &self,
+        prompt: String,
+        max_tokens: u32,
+        sampling: Sampling,
+        tx: mpsc::Sender<ChatResult<StreamingChunk>>,
+    ) -> ChatResult<()> {
+        let model = self.model.as_ref();
+        let mut ctx = model
+            .new_context(backend()?, self.config.context_params(false))
+            .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+        let tokens = model
+            .str_to_token(&prompt, AddBos::Always)
+            .map_err(|e| ChatError::InvalidRequest(e.to_string()))?;
+        let budget = generation_budget(tokens.len(), max_tokens, self.config.context_size)?;
+        let prompt_tokens = tokens.len() as u32;
+
+        let mut batch = LlamaBatch::new(self.config.context_size as usize, 1);
+        let last = tokens.len() as i32 - 1;
+        for (pos, token) in (0_i32..).zip(tokens) {
+            batch
+                .add(token, pos, &[0], pos == last)
+                .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+        }
+        ctx.decode(&mut batch)
+            .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+
+        let mut sampler = sampling.sampler();
+        let mut position = batch.n_tokens();
+        let mut pending = Vec::new();
+        for index in 0..budget {
+            let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+            sampler.accept(token);
+
+            if model.is_eog_token(token) {
+                let chunk = StreamingChunk::completion(index, FinishReason::Stop)
+                    .with_usage(TokenUsage::new(prompt_tokens, index));
+                let _ = tx.blocking_send(Ok(chunk));
+                return Ok(());
+            }
+
+            // A token can end mid-character; hold the bytes until it completes
+            pending.extend(
+                model
+                    .token_to_bytes(token, Special::Tokenize)
+                    .map_err(|e| ChatError::ProviderError(e.to_string()))?,
+            );
+            let piece = take_utf8(&mut pending);
+            if tx.blocking_send(Ok(StreamingChunk::new(index, piece))).is_err() {
+                // Receiver dropped: the caller cancelled
+                return Ok(());
+            }
+
+            batch.clear();
+            batch
+                .add(token, position, &[0], true)
+                .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+            position += 1;
+            ctx.decode(&mut batch)
+                .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+        }
+
+        let chunk = StreamingChunk::completion(budget, FinishReason::Length)
+            .with_usage(TokenUsage::new(prompt_tokens, budget));
+        let _ = tx.blocking_send(Ok(chunk));
+        Ok(())
+    }
+
+    /// Embed each input in its own sequence (blocking)
+    fn embed_blocking(&self, input: &[String]) -> ChatResult<Vec<Vec<f32>>> {
+        let model = self.model.as_ref();
+        let mut ctx = model
+            .new_context(backend()?, self.config.context_params(true))
+            .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+
+        let mut embeddings = Vec::with_capacity(input.len());
+        for text in input {
+            let tokens = model
+                .str_to_token(text, AddBos::Always)
+                .map_err(|e| ChatError::InvalidRequest(e.to_string()))?;
+            if tokens.len() > self.config.context_size as usize {
+                return Err(ChatError::ContextTooLong {
+                    tokens: tokens.len(),
+                    limit: self.config.context_size as usize,
+                });
+            }
+
+            ctx.clear_kv_cache();
+            let mut batch = LlamaBatch::new(tokens.len(), 1);
+            batch
+                .add_sequence(&tokens, 0, false)
+                .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+            ctx.decode(&mut batch)
+                .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+            let embedding = ctx
+                .embeddings_seq_ith(0)
+                .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+            embeddings.push(embedding.to_vec());
+        }
+        Ok(embeddings)
+    }
+}
+
+/// Sampling settings copied out of the request config
+#[derive(Debug, Clone, Copy)]
+struct Sampling {
+    temperature: f32,
+    top_p: f32,
+    top_k: Option<u32>,
+    seed: u32,
+}
+
+impl Sampling {
+    /// Temperature and top_p are exclusive; the unset one stays neutral
+    fn from_config(config: &ModelConfig) -> Self {
+        let params = config.sampling_params();
+        Self {
+            temperature: params.temperature.unwrap_or(1.0),
+            top_p: params.top_p.unwrap_or(1.0),
+            top_k: params.top_k,
+            seed: params.seed.map(|s| s as u32).unwrap_or_else(rand_seed),
+        }
+    }
+
+    fn sampler(&self) -> LlamaSampler {
+        if self.temperature <= 0.0 {
+            return LlamaSampler::greedy();
+        }
+        let mut chain = Vec::new();
+        if let Some(k) = self.top_k {
+            chain.push(LlamaSampler::top_k(k as i32));
+        }
+        chain.push(LlamaSampler::top_p(self.top_p, 1));
+        chain.push(LlamaSampler::temp(self.temperature));
+        chain.push(LlamaSampler::dist(self.seed));
+        LlamaSampler::chain_simple(chain)
+    }
+}
+
+fn rand_seed() -> u32 {
+    uuid::Uuid::now_v7().as_u128() as u32
+}
+
+fn role_name(role: MessageRole) -> &'static str {
+    match role {
+        MessageRole::System => "system",
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+    }
+}
+
+/// Role-prefixed transcript ending in an open assistant turn
+fn plain_transcript(turns: &[(&str, &str)]) -> String {
+    let mut prompt: String = turns
+        .iter()
+        .map(|(role, content)| format!("{}: {}\n", role, content))
+        .collect();
+    prompt.push_str("assistant: ");
+    prompt
+}
+
+/// Remove and return the longest valid UTF-8 prefix of `bytes`
+///
+/// Invalid sequences are replaced; an incomplete trailing sequence is kept
+/// for the next token.
+fn take_utf8(bytes: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => {
+            let text = String::from_utf8_lossy(bytes).into_owned();
+            bytes.clear();
+            return text;
+        }
+    };
+    let text = String::from_utf8_lossy(&bytes[..valid]).into_owned();
+    bytes.drain(..valid);
+    text
+}
+
+/// Context window to allocate: `requested`, but no more than the model was
+/// trained on (0 = unknown)
+fn clamp_context_size(requested: u32, trained: u32) -> u32 {
+    match trained {
+        0 => requested,
+        trained => requested.min(trained),
+    }
+}
+
+/// Tokens left for generation once the prompt is in the context window
+fn generation_budget(prompt_tokens: usize, max_tokens: u32, context_size: u32) -> ChatResult<u32> {
+    let limit = context_size as usize;
+    if prompt_tokens >= limit {
+        return Err(ChatError::ContextTooLong {
+            tokens: prompt_tokens,
+            limit,
+        });
+    }
+    Ok(max_tokens.min((limit - prompt_tokens) as u32))
+}
+
+#[async_trait]
+impl ChatPort for LlamaCppAdapter {
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        let prompt = self.render_prompt(config, &context);
+        let max_tokens = config.sampling_params().max_tokens.unwrap_or(config.max_tokens);
+        let sampling = Sampling::from_config(config);
+
+        let (tx, rx) = mpsc::channel(64);
+        let adapter = self.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = adapter.generate(prompt, max_tokens, sampling, tx.clone()) {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+
+        Ok(Box::pin(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        })))
+    }
+
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        let prompt = self.render_prompt(config, context);
+        let tokens = self
+            .model
+            .str_to_token(&prompt, AddBos::Always)
+            .map_err(|e| ChatError::InvalidRequest(e.to_string()))?;
+        Ok(Some(tokens.len() as u32))
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        // The model is loaded up front, so a constructed adapter is healthy
+        Ok(())
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "llama-cpp"
+    }
+}
+
+#[async_trait]
+impl EmbeddingPort for LlamaCppAdapter {
+    async fn embed(
+        &self,
+        input: Vec<String>,
+        model: Option<&str>,
+    ) -> ChatResult<EmbeddingResponse> {
+        let model_name = model
+            .map(str::to_string)
+            .unwrap_or_else(|| self.config.model_path.display().to_string());
+        let adapter = self.clone();
+        let embeddings = tokio::task::spawn_blocking(move || adapter.embed_blocking(&input))
+            .await
+            .map_err(|e| ChatError::ProviderError(e.to_string()))??;
+        Ok(EmbeddingResponse::new(embeddings, model_name))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "llama-cpp"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_budget() {
+        assert_eq!(generation_budget(100, 512, 4096).unwrap(), 512);
+        assert_eq!(generation_budget(4000, 512, 4096).unwrap(), 96);
+        assert!(matches!(
+            generation_budget(4096, 512, 4096),
+            Err(ChatError::ContextTooLong { tokens: 4096, limit: 4096 })
+        ));
+    }
+
+    #[test]
+    fn test_context_size_is_clamped_to_training() {
+        assert_eq!(clamp_context_size(32_768, 4096), 4096);
+        assert_eq!(clamp_context_size(2048, 4096), 2048);
+        assert_eq!(clamp_context_size(2048, 0), 2048);
+    }
+
+    #[test]
+    fn test_split_utf8_is_held_back() {
+        let mut pending = "é".as_bytes()[..1].to_vec();
+        assert_eq!(take_utf8(&mut pending), "");
+        pending.extend_from_slice(&"é".as_bytes()[1..]);
+        assert_eq!(take_utf8(&mut pending), "é");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_plain_transcript_fallback() {
+        let prompt = plain_transcript(&[("system", "Be brief"), ("user", "Hi")]);
+        assert_eq!(prompt, "system: Be brief\nuser: Hi\nassistant: ");
+    }
+}
@@ -104,6 +104,11 @@ impl ChatPort for MockChatAdapter {
         Ok(Box::pin(stream::iter(chunks)))
     }
 
+    async fn context_window(&self, _model: &str) -> ChatResult<Option<u32>> {
+        // Same window as the registered mock capabilities
+        Ok(Some(4_096))
+    }
+
     async fn health_check(&self) -> ChatResult<()> {
         // Mock is always healthy
         Ok(())
@@ -8,12 +8,68 @@
 mod mock;
 pub use mock::MockChatAdapter;
 
+// Client-side stop-sequence and max-token enforcement (all providers)
+mod output_limits;
+pub use output_limits::{estimate_tokens, OutputLimitAdapter, OutputLimits};
+
+// Secrets backends for provider credentials
+mod secrets;
+pub use secrets::{CachingSecretsProvider, DotenvSecretsProvider, EnvSecretsProvider};
+
+#[cfg(feature = "secrets-vault")]
+mod vault;
+#[cfg(feature = "secrets-vault")]
+pub use vault::VaultSecretsProvider;
+
+#[cfg(feature = "secrets-aws")]
+mod aws_secrets;
+#[cfg(feature = "secrets-aws")]
+pub use aws_secrets::AwsSecretsManagerProvider;
+
+// Fault injection for resilience testing (tests or `chaos` feature)
+#[cfg(any(test, feature = "chaos"))]
+mod chaos;
+#[cfg(any(test, feature = "chaos"))]
+pub use chaos::{ChaosConfig, ChaosLayer, MalformedChunk};
+
 // Ollama requires reqwest (ai-providers feature)
 #[cfg(feature = "ai-providers")]
 mod ollama;
 #[cfg(feature = "ai-providers")]
 pub use ollama::OllamaChatAdapter;
 
+// Server-sent event framing for the HTTP streaming adapters
+#[cfg(feature = "ai-providers")]
+mod sse;
+
+// Per-request HTTP timeouts from the caller's deadline
+#[cfg(feature = "ai-providers")]
+mod http_deadline;
+
+// Gemini chat, vision and embeddings over the Generative Language API
+#[cfg(feature = "ai-providers")]
+mod gemini;
+#[cfg(feature = "ai-providers")]
+pub use gemini::GeminiChatAdapter;
+
+// Hosted and local rerankers for search refinement
+#[cfg(feature = "ai-providers")]
+mod rerank;
+#[cfg(feature = "ai-providers")]
+pub use rerank::{CohereReranker, CrossEncoderReranker, VoyageReranker};
+
+// Self-hosted OpenAI-compatible servers (vLLM, TGI, LocalAI, llama.cpp server)
+#[cfg(feature = "ai-providers")]
+mod openai_compatible;
+#[cfg(feature = "ai-providers")]
+pub use openai_compatible::{EndpointProbe, OpenAICompatibleAdapter};
+
+// In-process GGUF inference (llama.cpp, heavy native build)
+#[cfg(feature = "llama-cpp")]
+mod llama_cpp;
+#[cfg(feature = "llama-cpp")]
+pub use llama_cpp::{LlamaCppAdapter, LlamaCppConfig};
+
 // OpenAI and Anthropic adapters removed - use GenaiAdapter instead
 // #[cfg(feature = "adapter-openai")]
 // mod openai;
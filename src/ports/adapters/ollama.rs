@@ -4,13 +4,32 @@
 //!
 //! Connects to a local Ollama instance for AI chat.
 //! Supports streaming responses via the `/api/chat` endpoint.
-
-use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream};
-use crate::value_objects::{ContextMessage, FinishReason, MessageRole, ModelConfig, StreamingChunk};
+//!
+//! Ollama unloads an idle model after a few minutes and the next request
+//! pays a multi-second cold start. The adapter can ask for models to stay
+//! loaded longer (`keep_alive`), load a model before it is needed
+//! ([`ChatPort::prewarm`], called when an agent is activated), and cap the
+//! requests running at once per model so a busy model queues here rather
+//! than in Ollama:
+//!
+//! ```text
+//! send(config) ──> semaphore[model] (cap) ──> POST /api/chat { keep_alive }
+//!                        └── permit held until the stream is dropped
+//! prewarm(config) ─────────────────────────> POST /api/generate { model, keep_alive }
+//! ```
+
+use super::http_deadline::{timeout_error, within_deadline};
+use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream, ProviderFailure};
+use crate::value_objects::{
+    ContextMessage, FinishReason, MessageRole, ModelConfig, StreamingChunk, TokenUsage,
+};
 use async_trait::async_trait;
 use futures::{stream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 /// Ollama chat adapter
 ///
@@ -20,6 +39,11 @@ use std::pin::Pin;
 pub struct OllamaChatAdapter {
     base_url: String,
     client: reqwest::Client,
+    keep_alive: Option<String>,
+    default_concurrency: Option<usize>,
+    model_concurrency: HashMap<String, usize>,
+    permits: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+    context_windows: Arc<Mutex<HashMap<String, u32>>>,
 }
 
 impl OllamaChatAdapter {
@@ -38,9 +62,68 @@ impl OllamaChatAdapter {
         Ok(Self {
             base_url: base_url.trim_end_matches('/').to_string(),
             client,
+            keep_alive: None,
+            default_concurrency: None,
+            model_concurrency: HashMap::new(),
+            permits: Arc::new(Mutex::new(HashMap::new())),
+            context_windows: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Builder: how long models stay loaded after a request
+    ///
+    /// A duration such as `"30m"` or `"24h"`, or seconds as a number; a
+    /// negative value (`"-1"`) keeps the model loaded indefinitely.
+    pub fn with_keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+        self.keep_alive = Some(keep_alive.into());
+        self
+    }
+
+    /// Builder: requests run at once against any one model
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.default_concurrency = Some(limit.max(1));
+        self
+    }
+
+    /// Builder: requests run at once against `model`, overriding
+    /// [`with_concurrency`](Self::with_concurrency)
+    pub fn with_model_concurrency(mut self, model: impl Into<String>, limit: usize) -> Self {
+        self.model_concurrency.insert(model.into(), limit.max(1));
+        self
+    }
+
+    /// `keep_alive` as Ollama expects it: seconds as a number, else a duration string
+    fn keep_alive_value(&self) -> Option<serde_json::Value> {
+        self.keep_alive.as_deref().map(|keep_alive| match keep_alive.parse::<f64>() {
+            Ok(secs) => serde_json::json!(secs),
+            Err(_) => serde_json::json!(keep_alive),
         })
     }
 
+    /// Wait for a slot on `model`; `None` when the model is uncapped
+    async fn acquire(&self, model: &str) -> ChatResult<Option<OwnedSemaphorePermit>> {
+        let Some(limit) = self
+            .model_concurrency
+            .get(model)
+            .copied()
+            .or(self.default_concurrency)
+        else {
+            return Ok(None);
+        };
+        let semaphore = self
+            .permits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(model.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+            .clone();
+        semaphore
+            .acquire_owned()
+            .await
+            .map(Some)
+            .map_err(|e| ChatError::ProviderError(e.to_string()))
+    }
+
     /// Convert our context messages to Ollama format
     fn to_ollama_messages(context: &[ContextMessage]) -> Vec<OllamaMessage> {
         context
@@ -55,6 +138,28 @@ impl OllamaChatAdapter {
             })
             .collect()
     }
+
+    /// Convert one streamed response line; the last carries the token counts
+    fn to_chunk(index: u32, response: OllamaChatResponse) -> StreamingChunk {
+        if !response.done {
+            return StreamingChunk::new(index, response.message.content);
+        }
+        let content = response.message.content;
+        let chunk = StreamingChunk::final_chunk(index, content, FinishReason::Stop);
+        match (response.prompt_eval_count, response.eval_count) {
+            (Some(prompt), Some(completion)) => {
+                chunk.with_usage(TokenUsage::new(prompt, completion))
+            }
+            _ => chunk,
+        }
+    }
+
+    /// Translate the effective sampling parameters to Ollama option names
+    fn to_ollama_options(config: &ModelConfig) -> serde_json::Map<String, serde_json::Value> {
+        config
+            .sampling_params()
+            .provider_parameters(crate::value_objects::ProviderType::Ollama)
+    }
 }
 
 impl Default for OllamaChatAdapter {
@@ -71,21 +176,17 @@ impl ChatPort for OllamaChatAdapter {
         context: Vec<ContextMessage>,
     ) -> ChatResult<ChatStream> {
         let messages = Self::to_ollama_messages(&context);
+        let permit = self.acquire(&config.model_name).await?;
 
         let request = OllamaChatRequest {
             model: config.model_name.clone(),
             messages,
             stream: true,
-            options: Some(OllamaOptions {
-                temperature: Some(config.temperature),
-                num_predict: Some(config.max_tokens as i32),
-                top_p: Some(config.top_p),
-            }),
+            options: Some(Self::to_ollama_options(config)),
+            keep_alive: self.keep_alive_value(),
         };
 
-        let response = self
-            .client
-            .post(format!("{}/api/chat", self.base_url))
+        let response = within_deadline(self.client.post(format!("{}/api/chat", self.base_url)))
             .json(&request)
             .send()
             .await
@@ -93,7 +194,7 @@ impl ChatPort for OllamaChatAdapter {
                 if e.is_connect() {
                     ChatError::ConnectionFailed(format!("Cannot connect to Ollama: {}", e))
                 } else if e.is_timeout() {
-                    ChatError::Timeout(300)
+                    timeout_error(300)
                 } else {
                     ChatError::ProviderError(e.to_string())
                 }
@@ -106,7 +207,11 @@ impl ChatPort for OllamaChatAdapter {
             return Err(if status.as_u16() == 404 {
                 ChatError::ModelNotAvailable(config.model_name.clone())
             } else {
-                ChatError::ProviderError(format!("{}: {}", status, error_text))
+                ChatError::Provider(ProviderFailure::from_http(
+                    "ollama",
+                    status.as_u16(),
+                    error_text,
+                ))
             });
         }
 
@@ -125,18 +230,7 @@ impl ChatPort for OllamaChatAdapter {
                                 continue;
                             }
                             match serde_json::from_str::<OllamaChatResponse>(line) {
-                                Ok(resp) => {
-                                    let chunk = if resp.done {
-                                        StreamingChunk::final_chunk(
-                                            idx as u32,
-                                            &resp.message.content,
-                                            FinishReason::Stop,
-                                        )
-                                    } else {
-                                        StreamingChunk::new(idx as u32, &resp.message.content)
-                                    };
-                                    return Some(Ok(chunk));
-                                }
+                                Ok(resp) => return Some(Ok(Self::to_chunk(idx as u32, resp))),
                                 Err(e) => {
                                     tracing::warn!("Failed to parse Ollama response: {}", e);
                                 }
@@ -148,9 +242,99 @@ impl ChatPort for OllamaChatAdapter {
                 }
             });
 
+        // The model's slot is released when the caller drops the stream
+        let chunk_stream = chunk_stream.map(move |chunk| {
+            let _slot = &permit;
+            chunk
+        });
+
         Ok(Box::pin(chunk_stream))
     }
 
+    async fn prewarm(&self, config: &ModelConfig) -> ChatResult<()> {
+        // A generate request without a prompt only loads the model
+        let request = OllamaLoadRequest {
+            model: config.model_name.clone(),
+            keep_alive: self.keep_alive_value(),
+        };
+        let response = within_deadline(self.client.post(format!("{}/api/generate", self.base_url)))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| ChatError::ConnectionFailed(format!("Cannot connect to Ollama: {}", e)))?;
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            status if status.as_u16() == 404 => {
+                Err(ChatError::ModelNotAvailable(config.model_name.clone()))
+            }
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                let failure = ProviderFailure::from_http("ollama", status.as_u16(), body);
+                Err(ChatError::Provider(failure))
+            }
+        }
+    }
+
+    async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+        let response = within_deadline(self.client.get(format!("{}/api/tags", self.base_url)))
+            .send()
+            .await
+            .map_err(|e| ChatError::ConnectionFailed(format!("Ollama not reachable: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(ChatError::ConnectionFailed("Ollama returned error status".into()));
+        }
+
+        let tags: OllamaTagsResponse = response
+            .json()
+            .await
+            .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+        // Ollama lists `llama3.2:latest` for a model requested as `llama3.2`
+        let wanted = if model.contains(':') {
+            model.to_string()
+        } else {
+            format!("{}:latest", model)
+        };
+        Ok(Some(tags.models.iter().any(|m| m.name == model || m.name == wanted)))
+    }
+
+    async fn context_window(&self, model: &str) -> ChatResult<Option<u32>> {
+        if let Some(window) =
+            self.context_windows.lock().unwrap_or_else(|e| e.into_inner()).get(model)
+        {
+            return Ok(Some(*window));
+        }
+
+        let request = OllamaShowRequest { model: model.to_string() };
+        let response =
+            within_deadline(self.client.post(format!("{}/api/show", self.base_url)).json(&request))
+                .send()
+                .await
+                .map_err(|e| ChatError::ConnectionFailed(format!("Ollama not reachable: {}", e)))?;
+        if !response.status().is_success() {
+            // Unknown model: leave the check to the chat request itself
+            return Ok(None);
+        }
+
+        let show: OllamaShowResponse = response
+            .json()
+            .await
+            .map_err(|e| ChatError::ProviderError(e.to_string()))?;
+        // Reported per architecture, e.g. `llama.context_length`
+        let window = show
+            .model_info
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+            .map(|value| value.min(u32::MAX as u64) as u32);
+        if let Some(window) = window {
+            self.context_windows
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(model.to_string(), window);
+        }
+        Ok(window)
+    }
+
     async fn health_check(&self) -> ChatResult<()> {
         let response = self
             .client
@@ -179,7 +363,16 @@ struct OllamaChatRequest {
     messages: Vec<OllamaMessage>,
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    options: Option<OllamaOptions>,
+    options: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaLoadRequest {
+    model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -188,14 +381,26 @@ struct OllamaMessage {
     content: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModelTag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModelTag {
+    name: String,
+}
+
 #[derive(Debug, Serialize)]
-struct OllamaOptions {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    temperature: Option<f32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    num_predict: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    top_p: Option<f32>,
+struct OllamaShowRequest {
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaShowResponse {
+    #[serde(default)]
+    model_info: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -205,6 +410,8 @@ struct OllamaChatResponse {
     #[serde(default)]
     total_duration: Option<u64>,
     #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
     eval_count: Option<u32>,
 }
 
@@ -241,6 +448,58 @@ mod tests {
         assert_eq!(messages[2].role, "assistant");
     }
 
+    #[test]
+    fn test_final_line_reports_token_usage() {
+        let line = r#"{"message":{"role":"assistant","content":""},"done":true,
+            "prompt_eval_count":26,"eval_count":298}"#;
+        let response: OllamaChatResponse = serde_json::from_str(line).unwrap();
+        let chunk = OllamaChatAdapter::to_chunk(4, response);
+
+        assert!(chunk.is_final);
+        assert_eq!(chunk.usage, Some(TokenUsage::new(26, 298)));
+    }
+
+    #[test]
+    fn test_options_use_ollama_names() {
+        let config = ModelConfig::ollama("llama3")
+            .with_max_tokens(256)
+            .with_generation_params(crate::value_objects::GenerationPreset::Deterministic);
+
+        let options = OllamaChatAdapter::to_ollama_options(&config);
+
+        assert_eq!(options["num_predict"], serde_json::json!(256));
+        assert_eq!(options["top_k"], serde_json::json!(1));
+        assert_eq!(options["seed"], serde_json::json!(0));
+        assert!(!options.contains_key("max_tokens"));
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_and_per_model_concurrency() {
+        let adapter = OllamaChatAdapter::new()
+            .unwrap()
+            .with_keep_alive("-1")
+            .with_concurrency(2)
+            .with_model_concurrency("llama3:70b", 1);
+        assert_eq!(adapter.keep_alive_value(), Some(serde_json::json!(-1.0)));
+        let adapter = adapter.with_keep_alive("30m");
+        assert_eq!(adapter.keep_alive_value(), Some(serde_json::json!("30m")));
+
+        let large = adapter.acquire("llama3:70b").await.unwrap();
+        assert!(large.is_some());
+        let waiting = tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            adapter.acquire("llama3:70b"),
+        );
+        assert!(waiting.await.is_err());
+
+        // Other models keep their own slots; a clone shares them
+        let _small = adapter.acquire("llama3").await.unwrap();
+        let _second = adapter.clone().acquire("llama3").await.unwrap();
+        drop(large);
+        assert!(adapter.acquire("llama3:70b").await.unwrap().is_some());
+        assert!(OllamaChatAdapter::new().unwrap().acquire("llama3").await.unwrap().is_none());
+    }
+
     // Integration test - only runs if Ollama is available
     #[tokio::test]
     #[ignore = "requires running Ollama instance"]
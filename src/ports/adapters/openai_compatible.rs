@@ -0,0 +1,527 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! OpenAI-Compatible Adapter
+//!
+//! Talks to self-hosted servers that expose the OpenAI `/v1` API: vLLM,
+//! TGI, LocalAI, the llama.cpp server and similar. The base URL includes
+//! the version prefix, e.g. `http://gpu-node:8000/v1`.
+//!
+//! Self-hosted models vary widely, so the capabilities reported to the
+//! `ProviderRegistry` are set per endpoint rather than assumed.
+//!
+//! Cost tags in scope are sent as request `metadata` only when enabled with
+//! [`with_cost_tag_metadata`](OpenAICompatibleAdapter::with_cost_tag_metadata):
+//! OpenAI accepts the field, but some self-hosted servers reject it.
+//!
+//! Token usage reported on the stream is attached to the final chunk. Servers
+//! that only report it on request are asked with
+//! [`with_stream_usage`](OpenAICompatibleAdapter::with_stream_usage), which
+//! sends `stream_options.include_usage`; the usage then follows the finishing
+//! chunk in an event of its own.
+
+use super::http_deadline::{timeout_error, within_deadline};
+use super::sse;
+use crate::capabilities::{ProviderCapabilities, RuntimeCapabilities};
+use crate::intent::ImageInput;
+use crate::ports::{
+    ChatError, ChatPort, ChatResult, ChatStream, ProviderFailure, SecretsProvider,
+};
+use crate::value_objects::{
+    ContextMessage, CostTags, FinishReason, MessageRole, ModelConfig, ProviderType,
+    StreamingChunk, TokenUsage,
+};
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Result of probing an endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointProbe {
+    /// Round-trip time of the model listing request
+    pub latency: Duration,
+    /// Models the server reports as loaded
+    pub models: Vec<String>,
+}
+
+impl EndpointProbe {
+    /// Whether the server has `model` loaded
+    pub fn serves(&self, model: &str) -> bool {
+        self.models.iter().any(|m| m == model)
+    }
+}
+
+/// Adapter for OpenAI-compatible self-hosted servers
+#[derive(Clone)]
+pub struct OpenAICompatibleAdapter {
+    name: &'static str,
+    base_url: String,
+    client: reqwest::Client,
+    api_key: Option<(Arc<dyn SecretsProvider>, String)>,
+    capabilities: ProviderCapabilities,
+    expected_model: Option<String>,
+    cost_tag_metadata: bool,
+    stream_usage: bool,
+}
+
+impl OpenAICompatibleAdapter {
+    /// Create an adapter for the server at `base_url`
+    ///
+    /// Capabilities default to basic chat; declare what the served model
+    /// can actually do with [`with_capabilities`](Self::with_capabilities).
+    pub fn new(base_url: &str) -> ChatResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(300))
+            .build()
+            .map_err(|e| ChatError::ConfigurationError(e.to_string()))?;
+
+        Ok(Self {
+            name: "openai-compatible",
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+            api_key: None,
+            capabilities: ProviderCapabilities::new(
+                "openai-compatible",
+                RuntimeCapabilities::BASIC_CHAT,
+            ),
+            expected_model: None,
+            cost_tag_metadata: false,
+            stream_usage: false,
+        })
+    }
+
+    /// Builder: name used in logs, metrics and capability reports
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self.capabilities.provider_name = name.to_string();
+        self
+    }
+
+    /// Builder: send `Authorization: Bearer` with the secret `name`
+    pub fn with_api_key(
+        mut self,
+        secrets: Arc<dyn SecretsProvider>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.api_key = Some((secrets, name.into()));
+        self
+    }
+
+    /// Builder: capabilities of the model served by this endpoint
+    pub fn with_capabilities(mut self, capabilities: RuntimeCapabilities) -> Self {
+        self.capabilities.capabilities = capabilities;
+        self
+    }
+
+    /// Builder: context window of the served model
+    pub fn with_max_context_length(mut self, max_context_length: u32) -> Self {
+        self.capabilities.max_context_length = Some(max_context_length);
+        self
+    }
+
+    /// Builder: fail health checks unless the server has `model` loaded
+    pub fn with_expected_model(mut self, model: impl Into<String>) -> Self {
+        self.expected_model = Some(model.into());
+        self
+    }
+
+    /// Builder: send the cost tags in scope as request `metadata`
+    pub fn with_cost_tag_metadata(mut self) -> Self {
+        self.cost_tag_metadata = true;
+        self
+    }
+
+    /// Builder: ask the server to report token usage on the stream
+    pub fn with_stream_usage(mut self) -> Self {
+        self.stream_usage = true;
+        self
+    }
+
+    /// Capabilities to register this endpoint with
+    pub fn provider_capabilities(&self) -> ProviderCapabilities {
+        self.capabilities.clone()
+    }
+
+    /// List the models the server has loaded
+    pub async fn list_models(&self) -> ChatResult<Vec<String>> {
+        let response = self
+            .authorized(self.client.get(format!("{}/models", self.base_url)))
+            .await?
+            .send()
+            .await
+            .map_err(|e| {
+                ChatError::ConnectionFailed(format!("{} not reachable: {}", self.name, e))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_status(self.name, status, body, "models"));
+        }
+        let list: ModelList = response
+            .json()
+            .await
+            .map_err(|e| ChatError::ProviderError(format!("bad model list: {}", e)))?;
+        Ok(list.data.into_iter().map(|m| m.id).collect())
+    }
+
+    /// Measure the endpoint and report its loaded models
+    pub async fn probe(&self) -> ChatResult<EndpointProbe> {
+        let started = Instant::now();
+        let models = self.list_models().await?;
+        Ok(EndpointProbe {
+            latency: started.elapsed(),
+            models,
+        })
+    }
+
+    async fn authorized(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> ChatResult<reqwest::RequestBuilder> {
+        let Some((secrets, name)) = &self.api_key else {
+            return Ok(request);
+        };
+        let key = secrets
+            .get(name)
+            .await
+            .map_err(|e| ChatError::AuthenticationFailed(e.to_string()))?;
+        Ok(request.bearer_auth(key.expose()))
+    }
+
+    /// Build the chat completion request body
+    fn to_request(
+        config: &ModelConfig,
+        context: &[ContextMessage],
+        images: &[ImageInput],
+    ) -> Value {
+        let mut messages = Vec::new();
+        if !config.system_prompt.is_empty() {
+            messages.push(json!({"role": "system", "content": config.system_prompt}));
+        }
+        messages.extend(context.iter().map(|msg| {
+            let role = match msg.role {
+                MessageRole::System => "system",
+                MessageRole::User => "user",
+                MessageRole::Assistant => "assistant",
+            };
+            json!({"role": role, "content": msg.content})
+        }));
+
+        if !images.is_empty() {
+            let parts = images.iter().map(|image| {
+                let url = match image {
+                    ImageInput::Base64 { data, media_type } => {
+                        format!("data:{};base64,{}", media_type, data)
+                    }
+                    ImageInput::Url { url } => url.clone(),
+                };
+                json!({"type": "image_url", "image_url": {"url": url}})
+            });
+            if let Some(last_user) = messages.iter_mut().rev().find(|m| m["role"] == "user") {
+                let text = last_user["content"].take();
+                let mut content = vec![json!({"type": "text", "text": text})];
+                content.extend(parts);
+                last_user["content"] = Value::Array(content);
+            } else {
+                messages.push(json!({"role": "user", "content": parts.collect::<Vec<_>>()}));
+            }
+        }
+
+        let mut body = config
+            .sampling_params()
+            .provider_parameters(ProviderType::OpenAI);
+        body.insert("model".to_string(), json!(config.model_name));
+        body.insert("messages".to_string(), Value::Array(messages));
+        body.insert("stream".to_string(), json!(true));
+        if !config.stop_sequences.is_empty() {
+            body.insert("stop".to_string(), json!(config.stop_sequences));
+        }
+        Value::Object(body)
+    }
+
+    fn to_chunk(index: u32, data: &str) -> ChatResult<Option<StreamingChunk>> {
+        if data == "[DONE]" {
+            return Ok(None);
+        }
+        let event: CompletionChunk = serde_json::from_str(data)
+            .map_err(|e| ChatError::StreamInterrupted(format!("bad completion chunk: {}", e)))?;
+        let usage = event
+            .usage
+            .map(|u| TokenUsage::new(u.prompt_tokens, u.completion_tokens));
+        let Some(choice) = event.choices.into_iter().next() else {
+            // The usage event requested by `include_usage` has no choices
+            let completion = StreamingChunk::completion(index, FinishReason::Stop);
+            return Ok(usage.map(|usage| completion.with_usage(usage)));
+        };
+        let text = choice.delta.content.unwrap_or_default();
+        let chunk = match choice.finish_reason.as_deref() {
+            Some("stop") => StreamingChunk::final_chunk(index, text, FinishReason::Stop),
+            Some("length") => StreamingChunk::final_chunk(index, text, FinishReason::Length),
+            Some("tool_calls") => StreamingChunk::final_chunk(index, text, FinishReason::ToolCalls),
+            Some("content_filter") => {
+                StreamingChunk::final_chunk(index, text, FinishReason::ContentFilter)
+            }
+            Some(_) => StreamingChunk::final_chunk(index, text, FinishReason::Error),
+            None => StreamingChunk::new(index, text),
+        };
+        Ok(Some(match usage {
+            Some(usage) if chunk.is_final => chunk.with_usage(usage),
+            _ => chunk,
+        }))
+    }
+
+    /// Hold a final chunk without usage until the usage event after it
+    ///
+    /// Chunks are renumbered as they leave, so the indices stay contiguous
+    /// whatever events were dropped or merged on the way.
+    fn attach_usage(
+        chunks: impl Stream<Item = ChatResult<StreamingChunk>> + Send + 'static,
+    ) -> ChatStream {
+        let state = (Box::pin(chunks.fuse()), None::<StreamingChunk>, 0u32);
+        Box::pin(stream::unfold(state, |(mut chunks, mut held, next)| async move {
+            loop {
+                let chunk = match chunks.next().await {
+                    Some(Ok(chunk)) => chunk,
+                    Some(Err(e)) => return Some((Err(e), (chunks, held, next))),
+                    None => {
+                        return held.take().map(|mut chunk| {
+                            chunk.chunk_index = next;
+                            (Ok(chunk), (chunks, None, next + 1))
+                        })
+                    }
+                };
+                let chunk = match held.take() {
+                    Some(finished) => match chunk.usage {
+                        Some(usage) => finished.with_usage(usage),
+                        None => finished,
+                    },
+                    None if chunk.is_final && chunk.usage.is_none() => {
+                        held = Some(chunk);
+                        continue;
+                    }
+                    None => chunk,
+                };
+                let chunk = StreamingChunk {
+                    chunk_index: next,
+                    ..chunk
+                };
+                return Some((Ok(chunk), (chunks, held, next + 1)));
+            }
+        }))
+    }
+
+    async fn stream(&self, config: &ModelConfig, mut body: Value) -> ChatResult<ChatStream> {
+        let cost_tags = CostTags::current();
+        if self.cost_tag_metadata && !cost_tags.is_empty() {
+            body["metadata"] = json!(cost_tags);
+        }
+        if self.stream_usage {
+            body["stream_options"] = json!({"include_usage": true});
+        }
+        let response = self
+            .authorized(self.client.post(format!("{}/chat/completions", self.base_url)))
+            .await
+            .map(within_deadline)?
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    ChatError::ConnectionFailed(format!("Cannot connect to {}: {}", self.name, e))
+                } else if e.is_timeout() {
+                    timeout_error(300)
+                } else {
+                    ChatError::ProviderError(e.to_string())
+                }
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_status(self.name, status, body, &config.model_name));
+        }
+
+        let chunks = sse::data_events(response.bytes_stream())
+            .filter_map(|event| async move {
+                event.and_then(|data| Self::to_chunk(0, &data)).transpose()
+            });
+        Ok(Self::attach_usage(chunks))
+    }
+}
+
+fn map_status(provider: &str, status: reqwest::StatusCode, body: String, model: &str) -> ChatError {
+    match status.as_u16() {
+        400 => ChatError::InvalidRequest(body),
+        401 | 403 => ChatError::AuthenticationFailed(body),
+        404 => ChatError::ModelNotAvailable(model.to_string()),
+        429 => ChatError::RateLimitExceeded { retry_after_secs: None },
+        _ => ChatError::Provider(ProviderFailure::from_http(provider, status.as_u16(), body)),
+    }
+}
+
+#[async_trait]
+impl ChatPort for OpenAICompatibleAdapter {
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        self.stream(config, Self::to_request(config, &context, &[])).await
+    }
+
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        if !self.capabilities.capabilities.contains(RuntimeCapabilities::VISION) {
+            return Err(ChatError::InvalidRequest(format!(
+                "{} is not configured for image input",
+                self.name
+            )));
+        }
+        self.stream(config, Self::to_request(config, &context, &images)).await
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        let probe = self.probe().await?;
+        match &self.expected_model {
+            Some(model) if !probe.serves(model) => Err(ChatError::ModelNotAvailable(model.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.name
+    }
+}
+
+// OpenAI API types
+
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    usage: Option<CompletionUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionUsage {
+    #[serde(default)]
+    prompt_tokens: u32,
+    #[serde(default)]
+    completion_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_overrides() {
+        let adapter = OpenAICompatibleAdapter::new("http://gpu-node:8000/v1/")
+            .unwrap()
+            .with_name("vllm-qwen")
+            .with_capabilities(RuntimeCapabilities::ADVANCED_CHAT | RuntimeCapabilities::VISION)
+            .with_max_context_length(32_768);
+
+        let caps = adapter.provider_capabilities();
+        assert_eq!(adapter.base_url, "http://gpu-node:8000/v1");
+        assert_eq!(caps.provider_name, "vllm-qwen");
+        assert!(caps.satisfies(&RuntimeCapabilities::VISION));
+        assert_eq!(caps.max_context_length, Some(32_768));
+    }
+
+    #[test]
+    fn test_request_attaches_images_to_last_user_turn() {
+        let config = ModelConfig::new(ProviderType::OpenAI, "qwen2-vl").with_stop_sequence("END");
+        let context = vec![ContextMessage::user("Describe this")];
+        let images = vec![ImageInput::base64("aGVsbG8=", "image/png")];
+
+        let body = OpenAICompatibleAdapter::to_request(&config, &context, &images);
+
+        assert_eq!(body["model"], "qwen2-vl");
+        assert_eq!(body["stop"][0], "END");
+        let content = &body["messages"][0]["content"];
+        assert_eq!(content[0]["text"], "Describe this");
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,aGVsbG8=");
+    }
+
+    #[test]
+    fn test_chunk_parsing() {
+        let chunk = OpenAICompatibleAdapter::to_chunk(
+            0,
+            r#"{"choices":[{"delta":{"content":"Hi"},"finish_reason":null}]}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(chunk.content, "Hi");
+        assert!(!chunk.is_final);
+
+        let last = OpenAICompatibleAdapter::to_chunk(
+            1,
+            r#"{"choices":[{"delta":{},"finish_reason":"length"}]}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(last.finish_reason, Some(FinishReason::Length));
+        assert!(OpenAICompatibleAdapter::to_chunk(2, "[DONE]").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_usage_event_joins_the_final_chunk() {
+        let events = [
+            r#"{"choices":[{"delta":{"content":"Hi"},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":null}],"usage":null}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":"length"}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":9,"completion_tokens":1}}"#,
+            "[DONE]",
+        ];
+        let parsed: Vec<_> = events
+            .iter()
+            .enumerate()
+            .filter_map(|(i, data)| OpenAICompatibleAdapter::to_chunk(i as u32, data).transpose())
+            .collect();
+
+        let chunks: Vec<StreamingChunk> =
+            OpenAICompatibleAdapter::attach_usage(stream::iter(parsed))
+                .map(|c| c.unwrap())
+                .collect()
+                .await;
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].finish_reason, Some(FinishReason::Length));
+        assert_eq!(chunks[2].usage, Some(TokenUsage::new(9, 1)));
+        let indices: Vec<u32> = chunks.iter().map(|c| c.chunk_index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,393 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Output Limit Enforcement
+//!
+//! Providers disagree on stop sequences (some ignore them when streaming,
+//! some include the sequence in the output) and several ignore `max_tokens`
+//! in streaming mode. This module enforces both uniformly on the client side
+//! by rewriting the chunk stream:
+//!
+//! ```text
+//! provider ChatStream ──> OutputLimits::enforce() ──> ChatStream
+//!                              │
+//!                              ├─ stop sequence seen  → final chunk, FinishReason::Stop
+//!                              └─ token budget spent  → final chunk, FinishReason::Length
+//! ```
+//!
+//! When enforcement ends a stream, the final chunk carries an
+//! [`OutputEnforcement`] so callers can publish `OutputLimitEnforced`. Token
+//! usage the provider reported is kept on the final chunk.
+//!
+//! Token counts are estimated (~4 characters per token); the goal is to
+//! bound runaway output, not to match the provider's tokenizer exactly.
+
+use crate::intent::ImageInput;
+use crate::ports::{ChatPort, ChatResult, ChatStream};
+use crate::value_objects::{
+    ContextMessage, FinishReason, ModelConfig, OutputEnforcement, StreamingChunk, TokenUsage,
+};
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use std::sync::Arc;
+
+/// Approximate characters per token used for budget estimation
+const CHARS_PER_TOKEN: u32 = 4;
+
+/// Estimate the token count of a piece of text
+pub fn estimate_tokens(text: &str) -> u32 {
+    (text.chars().count() as u32).div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Client-side output limits applied to a response stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputLimits {
+    max_tokens: Option<u32>,
+    stop_sequences: Vec<String>,
+}
+
+impl OutputLimits {
+    /// Create limits that enforce nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Derive limits from a model configuration
+    pub fn from_config(config: &ModelConfig) -> Self {
+        Self {
+            max_tokens: config.sampling_params().max_tokens,
+            stop_sequences: config
+                .stop_sequences
+                .iter()
+                .filter(|s| !s.is_empty())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Builder: set the max output token budget
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Builder: add a stop sequence
+    pub fn with_stop_sequence(mut self, sequence: impl Into<String>) -> Self {
+        let sequence = sequence.into();
+        if !sequence.is_empty() {
+            self.stop_sequences.push(sequence);
+        }
+        self
+    }
+
+    /// Check if these limits enforce nothing
+    pub fn is_unbounded(&self) -> bool {
+        self.max_tokens.is_none() && self.stop_sequences.is_empty()
+    }
+
+    /// Wrap a stream so it honours these limits
+    pub fn enforce(&self, inner: ChatStream) -> ChatStream {
+        if self.is_unbounded() {
+            return inner;
+        }
+
+        let state = Enforcer {
+            inner,
+            holdback: self
+                .stop_sequences
+                .iter()
+                .map(|s| s.chars().count().saturating_sub(1))
+                .max()
+                .unwrap_or(0),
+            limits: self.clone(),
+            pending: String::new(),
+            emitted_tokens: 0,
+            next_index: 0,
+            usage: None,
+            done: false,
+        };
+
+        Box::pin(stream::unfold(state, |mut state| async move {
+            state.next_chunk().await.map(|item| (item, state))
+        }))
+    }
+}
+
+type Finish = (FinishReason, Option<OutputEnforcement>);
+
+/// Stream state for [`OutputLimits::enforce`]
+///
+/// Text that could be the start of a stop sequence split across chunks is
+/// held back in `pending` until the next chunk disambiguates it.
+struct Enforcer {
+    inner: ChatStream,
+    limits: OutputLimits,
+    holdback: usize,
+    pending: String,
+    emitted_tokens: u32,
+    next_index: u32,
+    usage: Option<TokenUsage>,
+    done: bool,
+}
+
+impl Enforcer {
+    async fn next_chunk(&mut self) -> Option<ChatResult<StreamingChunk>> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            let chunk = match self.inner.next().await {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None => {
+                    // Provider ended without a final chunk; flush what we hold
+                    let text = std::mem::take(&mut self.pending);
+                    return Some(Ok(self.emit(text, Some((FinishReason::Stop, None)))));
+                }
+            };
+
+            self.pending.push_str(&chunk.content);
+            self.usage = chunk.usage.or(self.usage);
+
+            let (text, finish) = if let Some((pos, sequence)) = self.find_stop() {
+                self.pending.truncate(pos);
+                let finish = (
+                    FinishReason::Stop,
+                    Some(OutputEnforcement::StopSequence { sequence }),
+                );
+                (std::mem::take(&mut self.pending), Some(finish))
+            } else if chunk.is_final {
+                let finish = (
+                    chunk.finish_reason.unwrap_or(FinishReason::Stop),
+                    chunk.enforcement.clone(),
+                );
+                (std::mem::take(&mut self.pending), Some(finish))
+            } else {
+                (self.release_pending(), None)
+            };
+
+            let (text, finish) = self.apply_budget(text, finish);
+            if text.is_empty() && finish.is_none() {
+                continue;
+            }
+
+            return Some(Ok(self.emit(text, finish)));
+        }
+    }
+
+    /// Earliest stop sequence match in the pending text
+    fn find_stop(&self) -> Option<(usize, String)> {
+        self.limits
+            .stop_sequences
+            .iter()
+            .filter_map(|s| self.pending.find(s.as_str()).map(|pos| (pos, s.clone())))
+            .min_by_key(|(pos, _)| *pos)
+    }
+
+    /// Take everything except the held-back tail from the pending text
+    fn release_pending(&mut self) -> String {
+        let total = self.pending.chars().count();
+        if total <= self.holdback {
+            return String::new();
+        }
+        let split = self
+            .pending
+            .char_indices()
+            .nth(total - self.holdback)
+            .map(|(i, _)| i)
+            .unwrap_or(self.pending.len());
+        let rest = self.pending.split_off(split);
+        std::mem::replace(&mut self.pending, rest)
+    }
+
+    /// Truncate text that would exceed the token budget
+    fn apply_budget(&self, mut text: String, finish: Option<Finish>) -> (String, Option<Finish>) {
+        let Some(limit) = self.limits.max_tokens else {
+            return (text, finish);
+        };
+
+        let remaining = limit.saturating_sub(self.emitted_tokens);
+        if estimate_tokens(&text) <= remaining {
+            return (text, finish);
+        }
+
+        let max_chars = (remaining * CHARS_PER_TOKEN) as usize;
+        if let Some((cut, _)) = text.char_indices().nth(max_chars) {
+            text.truncate(cut);
+        }
+        (
+            text,
+            Some((FinishReason::Length, Some(OutputEnforcement::MaxTokens { limit }))),
+        )
+    }
+
+    /// Build the outgoing chunk with a contiguous index
+    fn emit(&mut self, text: String, finish: Option<Finish>) -> StreamingChunk {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.emitted_tokens += estimate_tokens(&text);
+
+        match finish {
+            Some((reason, enforcement)) => {
+                self.done = true;
+                let mut chunk = StreamingChunk::final_chunk(index, text, reason);
+                chunk.enforcement = enforcement;
+                chunk.usage = self.usage;
+                chunk
+            }
+            None => StreamingChunk::new(index, text),
+        }
+    }
+}
+
+/// ChatPort decorator applying [`OutputLimits`] derived from each request's config
+pub struct OutputLimitAdapter {
+    inner: Arc<dyn ChatPort>,
+}
+
+impl OutputLimitAdapter {
+    /// Wrap an adapter
+    pub fn new(inner: Arc<dyn ChatPort>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl ChatPort for OutputLimitAdapter {
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        let stream = self.inner.send(config, context).await?;
+        Ok(OutputLimits::from_config(config).enforce(stream))
+    }
+
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        let stream = self.inner.send_vision(config, context, images).await?;
+        Ok(OutputLimits::from_config(config).enforce(stream))
+    }
+
+    async fn prewarm(&self, config: &ModelConfig) -> ChatResult<()> {
+        self.inner.prewarm(config).await
+    }
+
+    async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+        self.inner.has_model(model).await
+    }
+
+    async fn context_window(&self, model: &str) -> ChatResult<Option<u32>> {
+        self.inner.context_window(model).await
+    }
+
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        self.inner.count_tokens(config, context).await
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        self.inner.health_check().await
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.inner.provider_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream_of(parts: &[&str]) -> ChatStream {
+        let last = parts.len() - 1;
+        let chunks: Vec<ChatResult<StreamingChunk>> = parts
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                Ok(if i == last {
+                    StreamingChunk::final_chunk(i as u32, *p, FinishReason::Stop)
+                } else {
+                    StreamingChunk::new(i as u32, *p)
+                })
+            })
+            .collect();
+        Box::pin(stream::iter(chunks))
+    }
+
+    async fn collect(stream: ChatStream) -> Vec<StreamingChunk> {
+        stream.map(|c| c.unwrap()).collect().await
+    }
+
+    fn text(chunks: &[StreamingChunk]) -> String {
+        chunks.iter().map(|c| c.content.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_stop_sequence_split_across_chunks() {
+        let limits = OutputLimits::new().with_stop_sequence("END");
+        let chunks = collect(limits.enforce(stream_of(&["Hello E", "ND ignored", " tail"]))).await;
+
+        assert_eq!(text(&chunks), "Hello ");
+        let last = chunks.last().unwrap();
+        assert!(last.is_final);
+        assert_eq!(
+            last.enforcement,
+            Some(OutputEnforcement::StopSequence { sequence: "END".into() })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_tokens_truncates() {
+        let limits = OutputLimits::new().with_max_tokens(2);
+        let chunks = collect(limits.enforce(stream_of(&["abcd", "efgh", "ijkl"]))).await;
+
+        assert_eq!(text(&chunks), "abcdefgh");
+        let last = chunks.last().unwrap();
+        assert_eq!(last.finish_reason, Some(FinishReason::Length));
+        assert_eq!(last.enforcement, Some(OutputEnforcement::MaxTokens { limit: 2 }));
+    }
+
+    #[tokio::test]
+    async fn test_within_limits_passes_through() {
+        let limits = OutputLimits::new().with_max_tokens(100).with_stop_sequence("###");
+        let chunks = collect(limits.enforce(stream_of(&["one ", "two"]))).await;
+
+        assert_eq!(text(&chunks), "one two");
+        let last = chunks.last().unwrap();
+        assert_eq!(last.finish_reason, Some(FinishReason::Stop));
+        assert!(last.enforcement.is_none());
+        assert!(last.usage.is_none());
+        assert!(chunks.iter().enumerate().all(|(i, c)| c.chunk_index == i as u32));
+    }
+
+    #[tokio::test]
+    async fn test_reported_usage_reaches_the_final_chunk() {
+        let limits = OutputLimits::new().with_max_tokens(100);
+        let chunks: Vec<ChatResult<StreamingChunk>> = vec![
+            Ok(StreamingChunk::new(0, "Hi")),
+            Ok(StreamingChunk::completion(1, FinishReason::Stop)
+                .with_usage(TokenUsage::new(12, 1))),
+        ];
+        let chunks = collect(limits.enforce(Box::pin(stream::iter(chunks)))).await;
+
+        assert_eq!(chunks.last().unwrap().usage, Some(TokenUsage::new(12, 1)));
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abc"), 1);
+        assert_eq!(estimate_tokens("abcdefghi"), 3);
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Reranker adapters
+//!
+//! - `CohereReranker` - Cohere `/v2/rerank` (`COHERE_API_KEY`)
+//! - `VoyageReranker` - Voyage AI `/v1/rerank` (`VOYAGE_API_KEY`)
+//! - `CrossEncoderReranker` - a local cross-encoder served by Hugging Face
+//!   text-embeddings-inference (`/rerank`), for fully offline deployments
+
+use crate::ports::{
+    ChatError, ChatResult, EnvSecretsProvider, RerankResult, RerankerPort, SecretsProvider,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Shared HTTP plumbing for hosted rerank APIs
+#[derive(Clone)]
+struct RerankClient {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<(Arc<dyn SecretsProvider>, String)>,
+}
+
+impl RerankClient {
+    fn new(url: String, api_key: Option<(Arc<dyn SecretsProvider>, String)>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            api_key,
+        }
+    }
+
+    async fn post<T: for<'de> Deserialize<'de>>(&self, body: Value) -> ChatResult<T> {
+        let mut request = self.client.post(&self.url).json(&body);
+        if let Some((secrets, name)) = &self.api_key {
+            let key = secrets
+                .get(name)
+                .await
+                .map_err(|e| ChatError::AuthenticationFailed(e.to_string()))?;
+            request = request.bearer_auth(key.expose());
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ChatError::ConnectionFailed(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(match status.as_u16() {
+                401 | 403 => ChatError::AuthenticationFailed(body),
+                429 => ChatError::RateLimitExceeded { retry_after_secs: None },
+                _ => ChatError::ProviderError(format!("{}: {}", status, body)),
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| ChatError::ProviderError(format!("bad rerank response: {}", e)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoredIndex {
+    index: usize,
+    #[serde(alias = "relevance_score")]
+    score: f32,
+}
+
+impl From<ScoredIndex> for RerankResult {
+    fn from(scored: ScoredIndex) -> Self {
+        RerankResult {
+            index: scored.index,
+            score: scored.score,
+        }
+    }
+}
+
+/// Cohere rerank API
+#[derive(Clone)]
+pub struct CohereReranker {
+    http: RerankClient,
+    model: String,
+}
+
+impl CohereReranker {
+    /// Use `model` (e.g. `rerank-v3.5`) with the key from `COHERE_API_KEY` in `secrets`
+    pub fn new(secrets: Arc<dyn SecretsProvider>, model: impl Into<String>) -> Self {
+        Self {
+            http: RerankClient::new(
+                "https://api.cohere.com/v2/rerank".to_string(),
+                Some((secrets, "COHERE_API_KEY".to_string())),
+            ),
+            model: model.into(),
+        }
+    }
+
+    /// Use `rerank-v3.5` with the key from the environment
+    pub fn from_env() -> Self {
+        Self::new(Arc::new(EnvSecretsProvider::new()), "rerank-v3.5")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CohereResponse {
+    results: Vec<ScoredIndex>,
+}
+
+#[async_trait]
+impl RerankerPort for CohereReranker {
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: Option<usize>,
+    ) -> ChatResult<Vec<RerankResult>> {
+        let mut body = json!({"model": self.model, "query": query, "documents": documents});
+        if let Some(top_n) = top_n {
+            body["top_n"] = json!(top_n);
+        }
+        let response: CohereResponse = self.http.post(body).await?;
+        Ok(response.results.into_iter().map(Into::into).collect())
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "cohere"
+    }
+}
+
+/// Voyage AI rerank API
+#[derive(Clone)]
+pub struct VoyageReranker {
+    http: RerankClient,
+    model: String,
+}
+
+impl VoyageReranker {
+    /// Use `model` (e.g. `rerank-2`) with the key from `VOYAGE_API_KEY` in `secrets`
+    pub fn new(secrets: Arc<dyn SecretsProvider>, model: impl Into<String>) -> Self {
+        Self {
+            http: RerankClient::new(
+                "https://api.voyageai.com/v1/rerank".to_string(),
+                Some((secrets, "VOYAGE_API_KEY".to_string())),
+            ),
+            model: model.into(),
+        }
+    }
+
+    /// Use `rerank-2` with the key from the environment
+    pub fn from_env() -> Self {
+        Self::new(Arc::new(EnvSecretsProvider::new()), "rerank-2")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VoyageResponse {
+    data: Vec<ScoredIndex>,
+}
+
+#[async_trait]
+impl RerankerPort for VoyageReranker {
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: Option<usize>,
+    ) -> ChatResult<Vec<RerankResult>> {
+        let mut body = json!({"model": self.model, "query": query, "documents": documents});
+        if let Some(top_n) = top_n {
+            body["top_k"] = json!(top_n);
+        }
+        let response: VoyageResponse = self.http.post(body).await?;
+        Ok(response.data.into_iter().map(Into::into).collect())
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "voyage"
+    }
+}
+
+/// Local cross-encoder served by text-embeddings-inference
+///
+/// Run e.g. `BAAI/bge-reranker-base` with TEI and point this at it.
+#[derive(Clone)]
+pub struct CrossEncoderReranker {
+    http: RerankClient,
+}
+
+impl CrossEncoderReranker {
+    /// Use the TEI server at `base_url`
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: RerankClient::new(
+                format!("{}/rerank", base_url.trim_end_matches('/')),
+                None,
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl RerankerPort for CrossEncoderReranker {
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: Option<usize>,
+    ) -> ChatResult<Vec<RerankResult>> {
+        let response: Vec<ScoredIndex> =
+            self.http.post(json!({"query": query, "texts": documents})).await?;
+        let mut results: Vec<RerankResult> = response.into_iter().map(Into::into).collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        if let Some(top_n) = top_n {
+            results.truncate(top_n);
+        }
+        Ok(results)
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "cross-encoder"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_shapes() {
+        let cohere: CohereResponse = serde_json::from_str(
+            r#"{"id":"x","results":[{"index":2,"relevance_score":0.91}]}"#,
+        )
+        .unwrap();
+        let tei: Vec<ScoredIndex> =
+            serde_json::from_str(r#"[{"index":0,"score":0.5}]"#).unwrap();
+
+        let first: RerankResult = cohere.results.into_iter().next().unwrap().into();
+        assert_eq!(first, RerankResult { index: 2, score: 0.91 });
+        assert_eq!(tei[0].score, 0.5);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Local secrets adapters
+//!
+//! - `EnvSecretsProvider` - process environment, optionally prefixed
+//! - `DotenvSecretsProvider` - a `.env` file, re-read on every lookup
+//! - `CachingSecretsProvider` - TTL cache in front of any provider
+
+use crate::ports::{Secret, SecretsError, SecretsProvider, SecretsResult};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Secrets from environment variables
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretsProvider {
+    prefix: String,
+}
+
+impl EnvSecretsProvider {
+    /// Look names up verbatim
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look names up as `{prefix}{name}`
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for EnvSecretsProvider {
+    async fn get(&self, name: &str) -> SecretsResult<Secret> {
+        let var = format!("{}{}", self.prefix, name);
+        match std::env::var(&var) {
+            Ok(value) if !value.is_empty() => Ok(Secret::new(value)),
+            _ => Err(SecretsError::NotFound(var)),
+        }
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "env"
+    }
+}
+
+/// Secrets from a dotenv-style file
+///
+/// The file is read on every lookup, so editing it rotates keys in place.
+#[derive(Debug, Clone)]
+pub struct DotenvSecretsProvider {
+    path: PathBuf,
+}
+
+impl DotenvSecretsProvider {
+    /// Read secrets from `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Parse `KEY=value` lines, skipping blanks and `#` comments
+    fn parse(content: &str) -> HashMap<String, String> {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let line = line.strip_prefix("export ").unwrap_or(line);
+                let (key, value) = line.split_once('=')?;
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                    .unwrap_or(value);
+                Some((key.trim().to_string(), value.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for DotenvSecretsProvider {
+    async fn get(&self, name: &str) -> SecretsResult<Secret> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| SecretsError::Backend(format!("{}: {}", self.path.display(), e)))?;
+        Self::parse(&content)
+            .remove(name)
+            .filter(|v| !v.is_empty())
+            .map(Secret::new)
+            .ok_or_else(|| SecretsError::NotFound(name.to_string()))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "dotenv"
+    }
+}
+
+/// TTL cache in front of another provider
+///
+/// Rotated values are picked up once the cached entry expires.
+pub struct CachingSecretsProvider {
+    inner: Arc<dyn SecretsProvider>,
+    ttl: Duration,
+    cache: RwLock<HashMap<String, (Secret, Instant)>>,
+}
+
+impl CachingSecretsProvider {
+    /// Cache lookups from `inner` for `ttl`
+    pub fn new(inner: Arc<dyn SecretsProvider>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Drop all cached values
+    pub fn invalidate(&self) {
+        self.cache.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for CachingSecretsProvider {
+    async fn get(&self, name: &str) -> SecretsResult<Secret> {
+        let cached = self
+            .cache
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .filter(|(_, fetched)| fetched.elapsed() < self.ttl)
+            .map(|(secret, _)| secret.clone());
+        if let Some(secret) = cached {
+            return Ok(secret);
+        }
+
+        let secret = self.inner.get(name).await?;
+        self.cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), (secret.clone(), Instant::now()));
+        Ok(secret)
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.inner.provider_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dotenv_parsing() {
+        let parsed = DotenvSecretsProvider::parse(
+            "# keys\nOPENAI_API_KEY=sk-1\nexport ANTHROPIC_API_KEY=\"sk-ant\"\n\nBROKEN\n",
+        );
+        assert_eq!(parsed.get("OPENAI_API_KEY").map(String::as_str), Some("sk-1"));
+        assert_eq!(parsed.get("ANTHROPIC_API_KEY").map(String::as_str), Some("sk-ant"));
+        assert_eq!(parsed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dotenv_rereads_file() {
+        let path = std::env::temp_dir().join(format!("secrets-{}.env", uuid::Uuid::now_v7()));
+        std::fs::write(&path, "API_KEY=old\n").unwrap();
+        let provider = DotenvSecretsProvider::new(&path);
+        assert_eq!(provider.get("API_KEY").await.unwrap().expose(), "old");
+
+        std::fs::write(&path, "API_KEY=rotated\n").unwrap();
+        assert_eq!(provider.get("API_KEY").await.unwrap().expose(), "rotated");
+        assert!(matches!(
+            provider.get("MISSING").await,
+            Err(SecretsError::NotFound(_))
+        ));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_cache_expires() {
+        let path = std::env::temp_dir().join(format!("secrets-{}.env", uuid::Uuid::now_v7()));
+        std::fs::write(&path, "API_KEY=old\n").unwrap();
+        let cached = CachingSecretsProvider::new(
+            Arc::new(DotenvSecretsProvider::new(&path)),
+            Duration::from_millis(20),
+        );
+        assert_eq!(cached.get("API_KEY").await.unwrap().expose(), "old");
+
+        std::fs::write(&path, "API_KEY=rotated\n").unwrap();
+        assert_eq!(cached.get("API_KEY").await.unwrap().expose(), "old");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cached.get("API_KEY").await.unwrap().expose(), "rotated");
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Server-sent event framing shared by the HTTP streaming adapters
+//!
+//! Network chunks do not respect line boundaries, so bytes are buffered and
+//! only complete lines are interpreted.
+
+use crate::ports::{ChatError, ChatResult};
+use futures::{future, stream, Stream, StreamExt};
+use std::fmt::Display;
+
+/// Turn a response byte stream into the payloads of its `data:` lines
+pub(crate) fn data_events<S, B, E>(bytes: S) -> impl Stream<Item = ChatResult<String>> + Send
+where
+    S: Stream<Item = Result<B, E>> + Send,
+    B: AsRef<[u8]>,
+    E: Display,
+{
+    bytes
+        .scan(Vec::new(), |buffer: &mut Vec<u8>, result| {
+            let events = match result {
+                Ok(bytes) => {
+                    buffer.extend_from_slice(bytes.as_ref());
+                    let mut events = Vec::new();
+                    while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                        let line: Vec<u8> = buffer.drain(..=pos).collect();
+                        events.extend(data_payload(&line).map(Ok));
+                    }
+                    events
+                }
+                Err(e) => vec![Err(ChatError::StreamInterrupted(e.to_string()))],
+            };
+            future::ready(Some(stream::iter(events)))
+        })
+        .flatten()
+}
+
+/// Payload of a `data:` line; comments, other fields and blanks yield nothing
+pub(crate) fn data_payload(line: &[u8]) -> Option<String> {
+    let line = std::str::from_utf8(line).ok()?.trim();
+    let data = line.strip_prefix("data:")?.trim();
+    (!data.is_empty()).then(|| data.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_events_split_across_chunks() {
+        let chunks: Vec<Result<&[u8], String>> = vec![
+            Ok(&b": keep-alive\n\ndata: {\"a\""[..]),
+            Ok(&b":1}\n\nevent: ping\ndata: [DONE]\n"[..]),
+        ];
+        let events: Vec<String> = data_events(stream::iter(chunks))
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+        assert_eq!(events, vec!["{\"a\":1}", "[DONE]"]);
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! HashiCorp Vault secrets adapter (KV v2)
+//!
+//! Secret names are keys inside one KV secret, or `path#key` to read from
+//! another path under the same mount:
+//!
+//! ```text
+//! get("OPENAI_API_KEY")          -> GET {addr}/v1/{mount}/data/{path}   .data.data.OPENAI_API_KEY
+//! get("shared/llm#ANTHROPIC")    -> GET {addr}/v1/{mount}/data/shared/llm .data.data.ANTHROPIC
+//! ```
+
+use crate::ports::{Secret, SecretsError, SecretsProvider, SecretsResult};
+use async_trait::async_trait;
+
+/// Secrets from a Vault KV v2 engine
+#[derive(Debug, Clone)]
+pub struct VaultSecretsProvider {
+    client: reqwest::Client,
+    addr: String,
+    token: Secret,
+    mount: String,
+    path: String,
+}
+
+impl VaultSecretsProvider {
+    /// Read keys from `{mount}/{path}` on the Vault at `addr`
+    pub fn new(
+        addr: impl Into<String>,
+        token: Secret,
+        mount: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            addr: addr.into().trim_end_matches('/').to_string(),
+            token,
+            mount: mount.into(),
+            path: path.into(),
+        }
+    }
+
+    /// Configure from `VAULT_ADDR`, `VAULT_TOKEN`, `VAULT_KV_MOUNT` (default:
+    /// `secret`) and `VAULT_SECRET_PATH`
+    pub fn from_env() -> SecretsResult<Self> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| SecretsError::NotFound(name.to_string()))
+        };
+        Ok(Self::new(
+            var("VAULT_ADDR")?,
+            Secret::new(var("VAULT_TOKEN")?),
+            var("VAULT_KV_MOUNT").unwrap_or_else(|_| "secret".to_string()),
+            var("VAULT_SECRET_PATH")?,
+        ))
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultSecretsProvider {
+    async fn get(&self, name: &str) -> SecretsResult<Secret> {
+        let (path, key) = name.split_once('#').unwrap_or((self.path.as_str(), name));
+        let url = format!("{}/v1/{}/data/{}", self.addr, self.mount, path);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Vault-Token", self.token.expose())
+            .send()
+            .await
+            .map_err(|e| SecretsError::Backend(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(SecretsError::NotFound(name.to_string()));
+        }
+        let body: serde_json::Value = response
+            .error_for_status()
+            .map_err(|e| SecretsError::Backend(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| SecretsError::Backend(e.to_string()))?;
+
+        match &body["data"]["data"][key] {
+            serde_json::Value::String(value) => Ok(Secret::new(value.clone())),
+            serde_json::Value::Null => Err(SecretsError::NotFound(name.to_string())),
+            _ => Err(SecretsError::Invalid {
+                name: name.to_string(),
+                reason: "value is not a string".to_string(),
+            }),
+        }
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "vault"
+    }
+}
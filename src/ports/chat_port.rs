@@ -24,9 +24,11 @@
 //! └── Embedding { input }
 //! ```
 
-use crate::value_objects::{ContextMessage, ModelConfig, StreamingChunk};
+use super::error_taxonomy::{ErrorCategory, ProviderFailure};
+use crate::intent::ImageInput;
+use crate::value_objects::{ContextMessage, Deadline, ModelConfig, StreamingChunk};
 use async_trait::async_trait;
-use futures::Stream;
+use futures::{stream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::pin::Pin;
@@ -64,18 +66,52 @@ pub enum ChatError {
 
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
+
+    /// The caller's deadline passed; the string names the stage that ran out
+    #[error("Deadline exceeded during {0}")]
+    DeadlineExceeded(String),
+
+    /// No answer met the agent's output constraints
+    #[error("Output constraints not met: {0}")]
+    ConstraintViolation(String),
+
+    /// A classified provider failure, with the provider's detail kept
+    #[error("{0}")]
+    Provider(ProviderFailure),
 }
 
 impl ChatError {
+    /// The taxonomy category of this error
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ChatError::ConnectionFailed(_) | ChatError::StreamInterrupted(_) => {
+                ErrorCategory::Transient
+            }
+            ChatError::AuthenticationFailed(_) => ErrorCategory::Auth,
+            ChatError::RateLimitExceeded { .. } => ErrorCategory::RateLimited,
+            ChatError::ModelNotAvailable(_)
+            | ChatError::ContextTooLong { .. }
+            | ChatError::InvalidRequest(_)
+            | ChatError::ConfigurationError(_)
+            | ChatError::ConstraintViolation(_) => ErrorCategory::InvalidRequest,
+            ChatError::ProviderError(_) => ErrorCategory::ProviderBug,
+            ChatError::Timeout(_) => ErrorCategory::Timeout,
+            ChatError::DeadlineExceeded(_) => ErrorCategory::Cancelled,
+            ChatError::Provider(failure) => failure.category,
+        }
+    }
+
+    /// The provider's own detail, for classified provider failures
+    pub fn provider_failure(&self) -> Option<&ProviderFailure> {
+        match self {
+            ChatError::Provider(failure) => Some(failure),
+            _ => None,
+        }
+    }
+
     /// Whether this error is recoverable (can retry)
     pub fn is_recoverable(&self) -> bool {
-        matches!(
-            self,
-            ChatError::RateLimitExceeded { .. }
-                | ChatError::ConnectionFailed(_)
-                | ChatError::StreamInterrupted(_)
-                | ChatError::Timeout(_)
-        )
+        self.category().is_retryable()
     }
 
     /// Suggested retry delay in milliseconds
@@ -84,10 +120,12 @@ impl ChatError {
             ChatError::RateLimitExceeded { retry_after_secs } => {
                 retry_after_secs.map(|s| s * 1000)
             }
-            ChatError::ConnectionFailed(_) => Some(1000),
             ChatError::StreamInterrupted(_) => Some(500),
-            ChatError::Timeout(_) => Some(2000),
-            _ => None,
+            ChatError::Provider(failure) if failure.category.is_retryable() => failure
+                .retry_after_secs
+                .map(|s| s * 1000)
+                .or_else(|| failure.category.default_retry_delay_ms()),
+            error => error.category().default_retry_delay_ms(),
         }
     }
 }
@@ -98,6 +136,21 @@ pub type ChatResult<T> = Result<T, ChatError>;
 /// Stream of response chunks from a provider
 pub type ChatStream = Pin<Box<dyn Stream<Item = ChatResult<StreamingChunk>> + Send>>;
 
+/// End `inner` with [`ChatError::DeadlineExceeded`] once `deadline` passes
+///
+/// Chunks arriving in time pass through untouched; a provider still
+/// streaming at the deadline is dropped after the error is yielded.
+pub fn until_deadline(inner: ChatStream, deadline: Deadline) -> ChatStream {
+    Box::pin(stream::unfold(Some(inner), move |inner| async move {
+        let mut inner = inner?;
+        match tokio::time::timeout(deadline.remaining(), inner.next()).await {
+            Ok(Some(item)) => Some((item, Some(inner))),
+            Ok(None) => None,
+            Err(_) => Some((Err(ChatError::DeadlineExceeded("stream".to_string())), None)),
+        }
+    }))
+}
+
 /// Capabilities that different AI providers may support
 ///
 /// The Port defines the **product** (union) of all capabilities.
@@ -202,6 +255,62 @@ pub trait ChatPort: Send + Sync {
     /// with `is_final: true` is received, or on error.
     async fn send(&self, config: &ModelConfig, context: Vec<ContextMessage>) -> ChatResult<ChatStream>;
 
+    /// Send a message with image inputs attached to the last user turn
+    ///
+    /// Providers without the `VISION` capability keep the default, which
+    /// rejects the request.
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        let _ = (config, context, images);
+        Err(ChatError::InvalidRequest(format!(
+            "{} does not accept image input",
+            self.provider_name()
+        )))
+    }
+
+    /// Load the model ahead of its first request, to avoid a cold start
+    ///
+    /// Providers without loadable models keep the default, which does nothing.
+    async fn prewarm(&self, config: &ModelConfig) -> ChatResult<()> {
+        let _ = config;
+        Ok(())
+    }
+
+    /// Whether the provider serves `model`, or `None` when it cannot tell
+    ///
+    /// Used by the startup self-test; must not load the model.
+    async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+        let _ = model;
+        Ok(None)
+    }
+
+    /// Context window `model` reports, in tokens, or `None` when it cannot tell
+    ///
+    /// Preferred over the registered capability limit, which is only a guess
+    /// for providers whose models are configured locally.
+    async fn context_window(&self, model: &str) -> ChatResult<Option<u32>> {
+        let _ = model;
+        Ok(None)
+    }
+
+    /// Prompt tokens `context` takes by the model's own tokenizer, or `None`
+    /// when the provider cannot count them ahead of a request
+    ///
+    /// Context truncation falls back to an estimate calibrated by the token
+    /// usage providers report on final chunks.
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        let _ = (config, context);
+        Ok(None)
+    }
+
     /// Check if the provider is available and configured correctly
     async fn health_check(&self) -> ChatResult<()>;
 
@@ -221,6 +330,20 @@ mod tests {
 
         assert!(!ChatError::AuthenticationFailed("bad key".into()).is_recoverable());
         assert!(!ChatError::ModelNotAvailable("gpt-5".into()).is_recoverable());
+        assert!(!ChatError::DeadlineExceeded("stream".into()).is_recoverable());
+    }
+
+    #[tokio::test]
+    async fn test_until_deadline_cuts_off_a_stalled_stream() {
+        let stalled: ChatStream = Box::pin(stream::pending());
+        let deadline = Deadline::after(std::time::Duration::from_millis(20));
+
+        let mut limited = until_deadline(stalled, deadline);
+        assert!(matches!(
+            limited.next().await,
+            Some(Err(ChatError::DeadlineExceeded(stage))) if stage == "stream"
+        ));
+        assert!(limited.next().await.is_none());
     }
 
     #[test]
@@ -238,4 +361,24 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_chat_error_category() {
+        assert_eq!(ChatError::Timeout(30).category(), ErrorCategory::Timeout);
+        assert_eq!(
+            ChatError::DeadlineExceeded("stream".into()).category(),
+            ErrorCategory::Cancelled
+        );
+        let unmet = ChatError::ConstraintViolation("answer exceeds 200 words".into());
+        assert_eq!(unmet.category(), ErrorCategory::InvalidRequest);
+        assert!(!unmet.category().trips_circuit());
+
+        let overloaded = ChatError::Provider(
+            ProviderFailure::from_http("anthropic", 503, "").with_retry_after(Some(3)),
+        );
+        assert_eq!(overloaded.category(), ErrorCategory::Transient);
+        assert!(overloaded.is_recoverable());
+        assert_eq!(overloaded.retry_delay_ms(), Some(3000));
+        assert_eq!(overloaded.provider_failure().unwrap().status, Some(503));
+    }
 }
@@ -0,0 +1,26 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Embedding Port - Hexagonal port for vector embeddings
+//!
+//! Kept separate from `ChatPort` because only some providers embed, and
+//! embeddings are a single request/response rather than a stream.
+
+use crate::intent::EmbeddingResponse;
+use crate::ports::ChatResult;
+use async_trait::async_trait;
+
+/// Port for generating text embeddings
+#[async_trait]
+pub trait EmbeddingPort: Send + Sync {
+    /// Embed each input, using the adapter's default model unless `model` is given
+    ///
+    /// The response holds one vector per input, in input order.
+    async fn embed(
+        &self,
+        input: Vec<String>,
+        model: Option<&str>,
+    ) -> ChatResult<EmbeddingResponse>;
+
+    /// Get the provider name for logging/metrics
+    fn provider_name(&self) -> &'static str;
+}
@@ -0,0 +1,242 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Error Taxonomy - one vocabulary for failures across adapters
+//!
+//! [`ChatError`], `AIProviderError` and [`DomainError`] each describe
+//! failures their own way. Every one of them maps onto an [`ErrorCategory`],
+//! so retry and circuit-breaker logic branches on the category instead of
+//! on variant names or message strings:
+//!
+//! ```text
+//! ChatError ────────┐
+//! AIProviderError ──┼──> category() ──> ErrorCategory ──> is_retryable()
+//! DomainError ──────┘                                └──> trips_circuit()
+//!
+//! HTTP status + body ──> ProviderFailure::from_http ──> ChatError::Provider
+//!                        { category, provider, status, code, payload }
+//! ```
+//!
+//! A [`ProviderFailure`] keeps the provider's own status, error code and
+//! response body, so nothing is lost when an adapter wraps a failure.
+//!
+//! [`ChatError`]: super::ChatError
+//! [`DomainError`]: crate::infrastructure::DomainError
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// What kind of failure an error is, independent of where it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Network trouble or a briefly unavailable provider
+    Transient,
+    /// The provider asked us to slow down
+    RateLimited,
+    /// Missing, invalid or insufficient credentials
+    Auth,
+    /// The request can never succeed as sent
+    InvalidRequest,
+    /// The provider failed in a way a retry will not fix
+    ProviderBug,
+    /// The caller gave up or its deadline passed
+    Cancelled,
+    /// The provider did not answer in time
+    Timeout,
+}
+
+impl ErrorCategory {
+    /// Whether the same request may succeed if sent again
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::Transient | Self::RateLimited | Self::Timeout)
+    }
+
+    /// Whether the failure counts against the provider's health
+    ///
+    /// Rate limits, bad requests and cancellations say nothing about
+    /// whether the provider is up.
+    pub fn trips_circuit(self) -> bool {
+        matches!(self, Self::Transient | Self::ProviderBug | Self::Timeout)
+    }
+
+    /// Retry delay when the provider gave none, in milliseconds
+    pub fn default_retry_delay_ms(self) -> Option<u64> {
+        match self {
+            Self::Transient => Some(1000),
+            Self::RateLimited => Some(5000),
+            Self::Timeout => Some(2000),
+            _ => None,
+        }
+    }
+
+    /// Category for an HTTP status returned by a provider
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 | 403 => Self::Auth,
+            408 | 504 => Self::Timeout,
+            429 => Self::RateLimited,
+            499 => Self::Cancelled,
+            502 | 503 | 529 => Self::Transient,
+            400..=499 => Self::InvalidRequest,
+            _ => Self::ProviderBug,
+        }
+    }
+
+    /// Stable name, e.g. `rate_limited`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transient => "transient",
+            Self::RateLimited => "rate_limited",
+            Self::Auth => "auth",
+            Self::InvalidRequest => "invalid_request",
+            Self::ProviderBug => "provider_bug",
+            Self::Cancelled => "cancelled",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A provider failure with the provider's own detail preserved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderFailure {
+    pub category: ErrorCategory,
+
+    /// Provider that failed, e.g. `gemini`
+    pub provider: String,
+
+    /// Human-readable message, from the provider when it sent one
+    pub message: String,
+
+    /// HTTP status, for failures that had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+
+    /// The provider's error code or type, e.g. `overloaded_error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    /// Seconds the provider asked us to wait before retrying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+
+    /// The response body as sent, parsed when it was JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
+}
+
+impl ProviderFailure {
+    /// A failure of `category` without provider detail
+    pub fn new(
+        category: ErrorCategory,
+        provider: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            category,
+            provider: provider.into(),
+            message: message.into(),
+            status: None,
+            code: None,
+            retry_after_secs: None,
+            payload: None,
+        }
+    }
+
+    /// Classify an unsuccessful HTTP response, keeping its body
+    ///
+    /// Understands the common `{"error": {"message", "type" | "code" |
+    /// "status"}}` shape; any other body is kept verbatim.
+    pub fn from_http(provider: impl Into<String>, status: u16, body: impl Into<String>) -> Self {
+        let body = body.into();
+        let payload = serde_json::from_str::<Value>(&body).ok();
+        let error = payload.as_ref().map(|p| p.get("error").unwrap_or(p));
+        let field = |name: &str| {
+            error.and_then(|e| e.get(name)).and_then(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+        let message = field("message").unwrap_or_else(|| {
+            if body.trim().is_empty() {
+                format!("HTTP {}", status)
+            } else {
+                body.clone()
+            }
+        });
+        let code = field("type")
+            .or_else(|| field("code"))
+            .or_else(|| field("status"));
+
+        Self {
+            category: ErrorCategory::from_status(status),
+            provider: provider.into(),
+            message,
+            status: Some(status),
+            code,
+            retry_after_secs: None,
+            payload: Some(payload.unwrap_or(Value::String(body))),
+        }
+    }
+
+    /// Builder: the provider's `Retry-After`, in seconds
+    pub fn with_retry_after(mut self, secs: Option<u64>) -> Self {
+        self.retry_after_secs = secs;
+        self
+    }
+
+    /// Builder: override the category, for provider codes that mean more
+    /// than their status
+    pub fn with_category(mut self, category: ErrorCategory) -> Self {
+        self.category = category;
+        self
+    }
+}
+
+impl fmt::Display for ProviderFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.provider, self.category)?;
+        if let Some(status) = self.status {
+            write!(f, " (HTTP {})", status)?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, " [{}]", code)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_http_keeps_provider_detail() {
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Busy"}}"#;
+        let failure = ProviderFailure::from_http("anthropic", 529, body);
+        assert_eq!(failure.category, ErrorCategory::Transient);
+        assert_eq!(failure.code.as_deref(), Some("overloaded_error"));
+        assert_eq!(failure.message, "Busy");
+        assert_eq!(
+            failure.payload.as_ref().unwrap()["error"]["message"],
+            "Busy"
+        );
+        assert_eq!(
+            failure.to_string(),
+            "anthropic transient (HTTP 529) [overloaded_error]: Busy"
+        );
+
+        let plain = ProviderFailure::from_http("ollama", 500, "model crashed");
+        assert_eq!(plain.category, ErrorCategory::ProviderBug);
+        assert_eq!(plain.payload, Some(Value::String("model crashed".into())));
+        assert!(!plain.category.is_retryable());
+        assert!(plain.category.trips_circuit());
+    }
+}
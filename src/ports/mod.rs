@@ -52,14 +52,36 @@
 
 mod chat_port;
 mod adapters;
+mod embedding_port;
+mod error_taxonomy;
+mod reranker_port;
 mod router;
+mod secrets_port;
 
-pub use chat_port::{ChatPort, ChatError, ChatResult, ChatStream};
+pub use chat_port::{until_deadline, ChatPort, ChatError, ChatResult, ChatStream};
+pub use embedding_port::EmbeddingPort;
+pub use error_taxonomy::{ErrorCategory, ProviderFailure};
+pub use reranker_port::{RerankResult, RerankStage, RerankerPort};
 pub use adapters::MockChatAdapter;
+pub use adapters::{estimate_tokens, OutputLimitAdapter, OutputLimits};
+#[cfg(any(test, feature = "chaos"))]
+pub use adapters::{ChaosConfig, ChaosLayer, MalformedChunk};
 pub use router::ProviderRouter;
+pub use secrets_port::{api_key_secret_name, Secret, SecretsError, SecretsProvider, SecretsResult};
+pub use adapters::{CachingSecretsProvider, DotenvSecretsProvider, EnvSecretsProvider};
+#[cfg(feature = "secrets-vault")]
+pub use adapters::VaultSecretsProvider;
+#[cfg(feature = "secrets-aws")]
+pub use adapters::AwsSecretsManagerProvider;
 
 #[cfg(feature = "ai-providers")]
-pub use adapters::OllamaChatAdapter;
+pub use adapters::{
+    EndpointProbe, GeminiChatAdapter, OllamaChatAdapter, OpenAICompatibleAdapter,
+};
+#[cfg(feature = "ai-providers")]
+pub use adapters::{CohereReranker, CrossEncoderReranker, VoyageReranker};
+#[cfg(feature = "llama-cpp")]
+pub use adapters::{LlamaCppAdapter, LlamaCppConfig};
 
 // OpenAI and Anthropic adapters removed - use GenaiAdapter instead
 // #[cfg(feature = "adapter-openai")]
@@ -0,0 +1,175 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Reranker Port - Hexagonal port for search result refinement
+//!
+//! Vector similarity is a cheap first stage; a reranker scores each
+//! candidate against the query directly and is far more precise, but too
+//! slow to run over a whole index. [`RerankStage`] combines the two: keep
+//! the top `candidates` first-stage hits, rerank them, return the best
+//! `top_n`.
+//!
+//! ```text
+//! search ──> top-k candidates ──> RerankerPort ──> top-n results
+//!                                   ├── CohereReranker
+//!                                   ├── VoyageReranker
+//!                                   └── CrossEncoderReranker  (local TEI server)
+//! ```
+
+use crate::ports::ChatResult;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// One reranked document
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RerankResult {
+    /// Index into the documents passed to [`RerankerPort::rerank`]
+    pub index: usize,
+    /// Relevance to the query; higher is better
+    pub score: f32,
+}
+
+/// Port for scoring documents against a query
+#[async_trait]
+pub trait RerankerPort: Send + Sync {
+    /// Score `documents` against `query`, best first
+    ///
+    /// Returns at most `top_n` results when given.
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: Option<usize>,
+    ) -> ChatResult<Vec<RerankResult>>;
+
+    /// Get the provider name for logging/metrics
+    fn provider_name(&self) -> &'static str;
+}
+
+/// Optional second search stage backed by a reranker
+#[derive(Clone)]
+pub struct RerankStage {
+    reranker: Arc<dyn RerankerPort>,
+    candidates: usize,
+    top_n: usize,
+    min_score: Option<f32>,
+}
+
+impl RerankStage {
+    /// Rerank the top 50 first-stage hits and keep the best 10
+    pub fn new(reranker: Arc<dyn RerankerPort>) -> Self {
+        Self {
+            reranker,
+            candidates: 50,
+            top_n: 10,
+            min_score: None,
+        }
+    }
+
+    /// Builder: how many first-stage hits to rerank
+    pub fn with_candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates.max(1);
+        self
+    }
+
+    /// Builder: how many results to return
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.top_n = top_n.max(1);
+        self
+    }
+
+    /// Builder: drop results scoring below `min_score`
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    /// Rerank first-stage `hits` (best first) and return them with their new scores
+    pub async fn apply<T>(
+        &self,
+        query: &str,
+        mut hits: Vec<T>,
+        text: impl Fn(&T) -> String,
+    ) -> ChatResult<Vec<(T, f32)>> {
+        hits.truncate(self.candidates);
+        if hits.is_empty() {
+            return Ok(Vec::new());
+        }
+        let documents: Vec<String> = hits.iter().map(text).collect();
+        let mut ranked = self
+            .reranker
+            .rerank(query, &documents, Some(self.top_n))
+            .await?;
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut slots: Vec<Option<T>> = hits.into_iter().map(Some).collect();
+        Ok(ranked
+            .into_iter()
+            .filter(|r| !matches!(self.min_score, Some(min) if r.score < min))
+            .filter_map(|r| Some((slots.get_mut(r.index)?.take()?, r.score)))
+            .take(self.top_n)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scores documents by how many query words they contain
+    struct WordOverlap;
+
+    #[async_trait]
+    impl RerankerPort for WordOverlap {
+        async fn rerank(
+            &self,
+            query: &str,
+            documents: &[String],
+            top_n: Option<usize>,
+        ) -> ChatResult<Vec<RerankResult>> {
+            let mut results: Vec<RerankResult> = documents
+                .iter()
+                .enumerate()
+                .map(|(index, doc)| RerankResult {
+                    index,
+                    score: query.split_whitespace().filter(|w| doc.contains(w)).count() as f32,
+                })
+                .collect();
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(top_n.unwrap_or(results.len()));
+            Ok(results)
+        }
+
+        fn provider_name(&self) -> &'static str {
+            "word-overlap"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stage_reorders_and_cuts() {
+        let stage = RerankStage::new(Arc::new(WordOverlap)).with_top_n(2);
+        let hits = vec!["nats streams", "agent lifecycle events", "agent events over nats"];
+
+        let ranked = stage
+            .apply("agent events nats", hits, |hit| hit.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0], ("agent events over nats", 3.0));
+        assert_eq!(ranked[1], ("agent lifecycle events", 2.0));
+    }
+
+    #[tokio::test]
+    async fn test_stage_limits_candidates_and_scores() {
+        let stage = RerankStage::new(Arc::new(WordOverlap))
+            .with_candidates(2)
+            .with_min_score(1.0);
+        let hits = vec!["unrelated", "agent", "agent events"];
+
+        let ranked = stage.apply("agent events", hits, |hit| hit.to_string()).await.unwrap();
+
+        // The best match was beyond the candidate cutoff
+        assert_eq!(ranked, vec![("agent", 1.0)]);
+    }
+}
@@ -5,6 +5,7 @@
 //! Routes requests to the appropriate ChatPort adapter based on
 //! the ModelConfig.provider_type field.
 
+use crate::intent::ImageInput;
 use crate::ports::adapters::MockChatAdapter;
 use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream};
 use crate::value_objects::{ContextMessage, ModelConfig, ProviderType};
@@ -106,6 +107,56 @@ impl ChatPort for ProviderRouter {
         adapter.send(config, context).await
     }
 
+    async fn send_vision(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        images: Vec<ImageInput>,
+    ) -> ChatResult<ChatStream> {
+        let adapter = self.get_adapter(&config.provider)?;
+        adapter.send_vision(config, context, images).await
+    }
+
+    async fn prewarm(&self, config: &ModelConfig) -> ChatResult<()> {
+        let adapter = self.get_adapter(&config.provider)?;
+        adapter.prewarm(config).await
+    }
+
+    async fn has_model(&self, model: &str) -> ChatResult<Option<bool>> {
+        // Served if any adapter serves it; unknown unless some adapter can tell
+        let mut known = None;
+        for (provider, adapter) in &self.adapters {
+            match adapter.has_model(model).await {
+                Ok(Some(true)) => return Ok(Some(true)),
+                Ok(Some(false)) => known = Some(false),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Provider {:?} could not list models: {}", provider, e),
+            }
+        }
+        Ok(known)
+    }
+
+    async fn context_window(&self, model: &str) -> ChatResult<Option<u32>> {
+        // The first adapter that knows the model decides
+        for (provider, adapter) in &self.adapters {
+            match adapter.context_window(model).await {
+                Ok(Some(window)) => return Ok(Some(window)),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Provider {:?} could not size {}: {}", provider, model, e),
+            }
+        }
+        Ok(None)
+    }
+
+    async fn count_tokens(
+        &self,
+        config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> ChatResult<Option<u32>> {
+        let adapter = self.get_adapter(&config.provider)?;
+        adapter.count_tokens(config, context).await
+    }
+
     async fn health_check(&self) -> ChatResult<()> {
         // Check all adapters
         for (provider, adapter) in &self.adapters {
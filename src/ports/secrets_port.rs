@@ -0,0 +1,102 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Secrets Port - Hexagonal port for credential lookup
+//!
+//! Provider API keys are looked up by name at the moment they are needed
+//! instead of being read once from the process environment, so a key
+//! rotated in the backing store takes effect without a redeploy.
+//!
+//! ```text
+//! GenaiAdapter ──get("OPENAI_API_KEY")──> SecretsProvider
+//!                                           ├── EnvSecretsProvider
+//!                                           ├── DotenvSecretsProvider
+//!                                           ├── VaultSecretsProvider       (secrets-vault)
+//!                                           └── AwsSecretsManagerProvider  (secrets-aws)
+//! ```
+//!
+//! Wrap any of them in `CachingSecretsProvider` to bound backend traffic.
+
+use crate::value_objects::ProviderType;
+use async_trait::async_trait;
+use std::fmt;
+use thiserror::Error;
+
+/// Errors from secret lookup
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SecretsError {
+    #[error("Secret not found: {0}")]
+    NotFound(String),
+
+    #[error("Secrets backend error: {0}")]
+    Backend(String),
+
+    #[error("Invalid secret {name}: {reason}")]
+    Invalid { name: String, reason: String },
+}
+
+/// Result type for secret lookup
+pub type SecretsResult<T> = Result<T, SecretsError>;
+
+/// A secret value that never appears in logs
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Wrap a secret value
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The secret value
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(<redacted>)")
+    }
+}
+
+/// Port for looking up secrets by name
+#[async_trait]
+pub trait SecretsProvider: Send + Sync {
+    /// Fetch the current value of `name`
+    async fn get(&self, name: &str) -> SecretsResult<Secret>;
+
+    /// Backend name for logs and errors
+    fn provider_name(&self) -> &'static str;
+}
+
+/// Conventional secret name holding the API key for a provider
+///
+/// Matches the environment variables the provider SDKs read, so the env
+/// backend is a drop-in replacement for the old behaviour.
+pub fn api_key_secret_name(provider: ProviderType) -> Option<&'static str> {
+    match provider {
+        ProviderType::OpenAI => Some("OPENAI_API_KEY"),
+        ProviderType::Anthropic => Some("ANTHROPIC_API_KEY"),
+        ProviderType::Gemini => Some("GEMINI_API_KEY"),
+        ProviderType::Ollama | ProviderType::Mock => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_debug_is_redacted() {
+        let secret = Secret::new("sk-live-123");
+        assert_eq!(format!("{:?}", secret), "Secret(<redacted>)");
+        assert_eq!(secret.expose(), "sk-live-123");
+    }
+
+    #[test]
+    fn test_api_key_names_follow_provider() {
+        assert_eq!(api_key_secret_name(ProviderType::OpenAI), Some("OPENAI_API_KEY"));
+        assert_eq!(api_key_secret_name(ProviderType::Gemini), Some("GEMINI_API_KEY"));
+        assert_eq!(api_key_secret_name(ProviderType::Ollama), None);
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Capability → agents inverted index
+//!
+//! Finding an agent to delegate to by scanning every agent's configuration
+//! grows with the fleet. This projection keeps, for each runtime capability,
+//! the set of active agents whose provider offers it:
+//!
+//! ```text
+//! ModelConfigured(anthropic) ──> agent caps = TEXT_CHAT | VISION | ...
+//! AgentActivated             ──> add agent under each of its caps
+//! AgentSuspended / Decommissioned ──> remove it again
+//!
+//! VISION    -> { sage, ddd }
+//! STREAMING -> { sage, ddd, eda }
+//!
+//! agents_with(VISION | STREAMING) = smallest set, checked against the rest
+//! ```
+//!
+//! A single-capability lookup is one hash lookup; a combined lookup walks
+//! only the smallest matching set.
+
+use crate::capabilities::{CapabilityRequirements, ProviderCapabilities, RuntimeCapabilities};
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::AgentId;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+/// What the index knows about one agent
+#[derive(Debug, Clone, Copy, Default)]
+struct Indexed {
+    capabilities: RuntimeCapabilities,
+    max_context_length: Option<u32>,
+    active: bool,
+}
+
+#[derive(Debug, Default)]
+struct IndexState {
+    agents: HashMap<AgentId, Indexed>,
+    by_capability: HashMap<RuntimeCapabilities, HashSet<AgentId>>,
+    active: HashSet<AgentId>,
+}
+
+impl IndexState {
+    /// Replace an agent's entry, moving it between capability sets
+    fn update(&mut self, agent_id: AgentId, change: impl FnOnce(&mut Indexed)) {
+        let before = self.agents.get(&agent_id).copied().unwrap_or_default();
+        let mut after = before;
+        change(&mut after);
+
+        if before.active {
+            for flag in before.capabilities.iter() {
+                if let Some(agents) = self.by_capability.get_mut(&flag) {
+                    agents.remove(&agent_id);
+                    if agents.is_empty() {
+                        self.by_capability.remove(&flag);
+                    }
+                }
+            }
+            self.active.remove(&agent_id);
+        }
+        if after.active {
+            for flag in after.capabilities.iter() {
+                self.by_capability.entry(flag).or_default().insert(agent_id);
+            }
+            self.active.insert(agent_id);
+        }
+        self.agents.insert(agent_id, after);
+    }
+}
+
+/// Projection maintaining the capability → active agents index
+#[derive(Debug, Default)]
+pub struct CapabilityIndex {
+    state: RwLock<IndexState>,
+}
+
+impl CapabilityIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a stored event
+    pub fn project(&self, envelope: &EventEnvelope) {
+        let mut state = self.write();
+        match &envelope.event {
+            AgentEvent::ModelConfigured(e) => {
+                let provider = ProviderCapabilities::for_provider(e.config.provider);
+                state.update(e.agent_id, |entry| {
+                    entry.capabilities = provider.capabilities;
+                    entry.max_context_length = provider.max_context_length;
+                });
+            }
+            AgentEvent::AgentActivated(e) => state.update(e.agent_id, |entry| entry.active = true),
+            AgentEvent::AgentSuspended(e) => state.update(e.agent_id, |entry| entry.active = false),
+            AgentEvent::AgentDecommissioned(e) => {
+                state.update(e.agent_id, |entry| entry.active = false)
+            }
+            _ => {}
+        }
+    }
+
+    /// Active agents offering every capability in `capabilities`, by ID
+    ///
+    /// An empty set matches every active agent.
+    pub fn agents_with(&self, capabilities: RuntimeCapabilities) -> Vec<AgentId> {
+        let state = self.read();
+        let smallest = capabilities
+            .iter()
+            .map(|flag| state.by_capability.get(&flag))
+            .min_by_key(|agents| agents.map_or(0, HashSet::len));
+        let candidates = match smallest {
+            Some(Some(agents)) => agents,
+            Some(None) => return Vec::new(),
+            None => &state.active,
+        };
+        let mut matched: Vec<AgentId> = candidates
+            .iter()
+            .filter(|id| {
+                state
+                    .agents
+                    .get(id)
+                    .is_some_and(|entry| entry.capabilities.contains(capabilities))
+            })
+            .copied()
+            .collect();
+        matched.sort_by_key(|id| *id.as_uuid());
+        matched
+    }
+
+    /// Active agents that meet `requirements`, by ID
+    ///
+    /// Agents whose provider does not state a context window are assumed
+    /// to fit any minimum, as in a capability negotiation.
+    pub fn agents_meeting(&self, requirements: &CapabilityRequirements) -> Vec<AgentId> {
+        let mut needed = requirements.capabilities;
+        if requirements.requires_streaming {
+            needed |= RuntimeCapabilities::STREAMING;
+        }
+        let candidates = self.agents_with(needed);
+        let Some(min_context) = requirements.min_context_length else {
+            return candidates;
+        };
+        let state = self.read();
+        candidates
+            .into_iter()
+            .filter(|id| {
+                state
+                    .agents
+                    .get(id)
+                    .and_then(|entry| entry.max_context_length)
+                    .is_none_or(|offered| offered >= min_context)
+            })
+            .collect()
+    }
+
+    /// Capabilities of an agent's configured provider, if it has one
+    pub fn capabilities(&self, agent_id: AgentId) -> Option<RuntimeCapabilities> {
+        self.read()
+            .agents
+            .get(&agent_id)
+            .map(|entry| entry.capabilities)
+            .filter(|capabilities| !capabilities.is_empty())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, IndexState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, IndexState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentSuspendedEvent, ModelConfiguredEvent};
+    use crate::test_support::envelope;
+    use crate::value_objects::{ModelConfig, ProviderType};
+
+    fn activate(index: &CapabilityIndex, provider: ProviderType) -> AgentId {
+        let agent_id = AgentId::new();
+        let mut config = ModelConfig::mock();
+        config.provider = provider;
+        index.project(&envelope(1, AgentEvent::ModelConfigured(
+            ModelConfiguredEvent::new(agent_id, config),
+        )));
+        index.project(&envelope(1, AgentEvent::AgentActivated(
+            AgentActivatedEvent::new(agent_id),
+        )));
+        agent_id
+    }
+
+    #[test]
+    fn test_lookups_follow_capabilities_and_status() {
+        let index = CapabilityIndex::new();
+        let claude = activate(&index, ProviderType::Anthropic);
+        let mock = activate(&index, ProviderType::Mock);
+
+        assert_eq!(index.agents_with(RuntimeCapabilities::VISION), vec![claude]);
+        assert!(index
+            .agents_with(RuntimeCapabilities::TEXT_CHAT)
+            .contains(&mock));
+        assert_eq!(index.agents_with(RuntimeCapabilities::empty()).len(), 2);
+
+        index.project(&envelope(1, AgentEvent::AgentSuspended(
+            AgentSuspendedEvent::new(claude, "maintenance"),
+        )));
+        assert!(index.agents_with(RuntimeCapabilities::VISION).is_empty());
+        assert!(index
+            .capabilities(claude)
+            .unwrap()
+            .contains(RuntimeCapabilities::VISION));
+    }
+
+    #[test]
+    fn test_requirements_check_context_length() {
+        let index = CapabilityIndex::new();
+        let claude = activate(&index, ProviderType::Anthropic);
+
+        let mut requirements = CapabilityRequirements::vision();
+        assert_eq!(index.agents_meeting(&requirements), vec![claude]);
+        requirements.min_context_length = Some(10_000_000);
+        assert!(index.agents_meeting(&requirements).is_empty());
+    }
+}
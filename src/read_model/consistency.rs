@@ -0,0 +1,50 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Consistency tokens and read model errors
+
+use crate::value_objects::AgentId;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Read-your-writes token returned with command replies
+///
+/// `version` is the JetStream sequence acknowledged for the last event the
+/// command published; projections track agents by the same sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConsistencyToken {
+    /// The agent the command wrote to
+    pub agent_id: AgentId,
+
+    /// Stream version after the write
+    pub version: u64,
+}
+
+impl ConsistencyToken {
+    /// Create a token
+    pub fn new(agent_id: AgentId, version: u64) -> Self {
+        Self { agent_id, version }
+    }
+}
+
+/// Read model errors
+#[derive(Debug, Error)]
+pub enum ReadModelError {
+    #[error("Read model not caught up for agent {agent_id}: need version {required}, at {current}")]
+    NotCaughtUp {
+        agent_id: AgentId,
+        required: u64,
+        current: u64,
+    },
+
+    #[error("Projection failed: {0}")]
+    Projection(String),
+
+    #[error("Read model storage error: {0}")]
+    Storage(String),
+
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+}
+
+/// Result type for read model operations
+pub type ReadModelResult<T> = Result<T, ReadModelError>;
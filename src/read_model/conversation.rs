@@ -0,0 +1,258 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation receipts projection
+//!
+//! [`ConversationProjection`] folds conversation events into who is taking
+//! part, which messages were posted, and how far each message got to each
+//! participant:
+//!
+//! ```text
+//! ParticipantJoined / Left  ──> participants (and when they joined)
+//! Transferred               ──> the receiving agent replaces the departing one
+//! MessagePosted             ──> messages, in order
+//! MessageDelivered          ──> receipt.delivered_at
+//! MessageRead               ──> receipt.read_at (and delivered_at, if unset)
+//!
+//! unread_count(conversation, participant) ──> 3
+//! ```
+//!
+//! A message counts as unread for a participant when someone else posted it
+//! after they joined and they have not read it. Repeated acknowledgements
+//! keep the first time reported.
+
+use crate::events::ConversationEvent;
+use crate::value_objects::{ConversationId, MessageId, ParticipantId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// How far one message got to one participant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageReceipt {
+    /// The participant
+    pub participant_id: ParticipantId,
+
+    /// When their client received the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<DateTime<Utc>>,
+
+    /// When they saw the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct PostedMessage {
+    id: MessageId,
+    author: ParticipantId,
+    posted_at: DateTime<Utc>,
+    receipts: Vec<MessageReceipt>,
+}
+
+impl PostedMessage {
+    fn receipt(&mut self, participant: &ParticipantId) -> &mut MessageReceipt {
+        let index = match self
+            .receipts
+            .iter()
+            .position(|r| &r.participant_id == participant)
+        {
+            Some(index) => index,
+            None => {
+                self.receipts.push(MessageReceipt {
+                    participant_id: participant.clone(),
+                    delivered_at: None,
+                    read_at: None,
+                });
+                self.receipts.len() - 1
+            }
+        };
+        &mut self.receipts[index]
+    }
+
+    fn read_by(&self, participant: &ParticipantId) -> bool {
+        self.receipts
+            .iter()
+            .any(|r| &r.participant_id == participant && r.read_at.is_some())
+    }
+}
+
+#[derive(Debug, Default)]
+struct ConversationState {
+    joined: HashMap<ParticipantId, DateTime<Utc>>,
+    messages: Vec<PostedMessage>,
+}
+
+impl ConversationState {
+    fn message(&mut self, id: MessageId) -> Option<&mut PostedMessage> {
+        self.messages.iter_mut().find(|m| m.id == id)
+    }
+
+    fn unread(&self, participant: &ParticipantId) -> Option<usize> {
+        let joined_at = *self.joined.get(participant)?;
+        Some(
+            self.messages
+                .iter()
+                .filter(|m| &m.author != participant && m.posted_at >= joined_at)
+                .filter(|m| !m.read_by(participant))
+                .count(),
+        )
+    }
+}
+
+/// Per-participant delivery and read tracking for conversations
+#[derive(Debug, Default)]
+pub struct ConversationProjection {
+    conversations: RwLock<HashMap<ConversationId, ConversationState>>,
+}
+
+impl ConversationProjection {
+    /// Create an empty projection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one conversation event into the projection
+    pub fn project(&self, event: &ConversationEvent) {
+        let mut conversations = self
+            .conversations
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        let state = conversations.entry(event.conversation_id()).or_default();
+
+        match event {
+            ConversationEvent::Started(e) => {
+                state
+                    .joined
+                    .insert(ParticipantId::Person(e.owner), e.started_at);
+            }
+            ConversationEvent::ParticipantJoined(e) => {
+                state
+                    .joined
+                    .insert(e.participant.id.clone(), e.participant.joined_at);
+            }
+            ConversationEvent::ParticipantLeft(e) => {
+                state.joined.remove(&e.participant_id);
+            }
+            ConversationEvent::ParticipantRoleChanged(_) | ConversationEvent::Closed(_) => {}
+            ConversationEvent::Transferred(e) => {
+                state.joined.remove(&ParticipantId::Agent(e.from));
+                state
+                    .joined
+                    .insert(ParticipantId::Agent(e.to), e.transferred_at);
+            }
+            ConversationEvent::MessagePosted(e) => {
+                state.messages.push(PostedMessage {
+                    id: e.message_id,
+                    author: e.author.clone(),
+                    posted_at: e.posted_at,
+                    receipts: Vec::new(),
+                });
+            }
+            ConversationEvent::MessageDelivered(e) => {
+                if let Some(message) = state.message(e.message_id) {
+                    let receipt = message.receipt(&e.participant_id);
+                    receipt.delivered_at.get_or_insert(e.delivered_at);
+                }
+            }
+            ConversationEvent::MessageRead(e) => {
+                if let Some(message) = state.message(e.message_id) {
+                    let receipt = message.receipt(&e.participant_id);
+                    receipt.delivered_at.get_or_insert(e.read_at);
+                    receipt.read_at.get_or_insert(e.read_at);
+                }
+            }
+        }
+    }
+
+    /// Receipts for one message, in the order participants first acknowledged it
+    pub fn receipts(
+        &self,
+        conversation_id: ConversationId,
+        message_id: MessageId,
+    ) -> Vec<MessageReceipt> {
+        let conversations = self.conversations.read().unwrap_or_else(|e| e.into_inner());
+        conversations
+            .get(&conversation_id)
+            .and_then(|c| c.messages.iter().find(|m| m.id == message_id))
+            .map(|m| m.receipts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Messages `participant` has not read in one conversation
+    ///
+    /// `None` if they are not a participant.
+    pub fn unread_count(
+        &self,
+        conversation_id: ConversationId,
+        participant: &ParticipantId,
+    ) -> Option<usize> {
+        let conversations = self.conversations.read().unwrap_or_else(|e| e.into_inner());
+        conversations.get(&conversation_id)?.unread(participant)
+    }
+
+    /// Unread messages in every conversation `participant` takes part in
+    pub fn unread_counts(&self, participant: &ParticipantId) -> HashMap<ConversationId, usize> {
+        let conversations = self.conversations.read().unwrap_or_else(|e| e.into_inner());
+        conversations
+            .iter()
+            .filter_map(|(id, c)| Some((*id, c.unread(participant)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        ConversationStartedEvent, MessageDeliveredEvent, MessagePostedEvent, MessageReadEvent,
+        ParticipantJoinedEvent,
+    };
+    use crate::value_objects::{AgentId, Participant, ParticipantRole, PersonId};
+
+    #[test]
+    fn test_receipts_and_unread_counts_per_participant() {
+        let projection = ConversationProjection::new();
+        let owner_id = PersonId::new();
+        let owner = ParticipantId::Person(owner_id);
+        let agent = ParticipantId::Agent(AgentId::new());
+        let started = ConversationStartedEvent::new(ConversationId::new(), owner_id);
+        let id = started.conversation_id;
+        projection.project(&ConversationEvent::Started(started));
+        projection.project(&ConversationEvent::ParticipantJoined(
+            ParticipantJoinedEvent::new(
+                id,
+                Participant::new(agent.clone(), ParticipantRole::Agent),
+                owner.clone(),
+            ),
+        ));
+
+        let (first, second) = (MessageId::new(), MessageId::new());
+        for message in [first, second] {
+            projection.project(&ConversationEvent::MessagePosted(MessagePostedEvent::new(
+                id,
+                message,
+                agent.clone(),
+            )));
+        }
+        assert_eq!(projection.unread_count(id, &owner), Some(2));
+        assert_eq!(projection.unread_count(id, &agent), Some(0));
+
+        projection.project(&ConversationEvent::MessageDelivered(
+            MessageDeliveredEvent::new(id, first, owner.clone()),
+        ));
+        assert_eq!(projection.unread_count(id, &owner), Some(2));
+        let read = MessageReadEvent::new(id, first, owner.clone());
+        projection.project(&ConversationEvent::MessageRead(read.clone()));
+        projection.project(&ConversationEvent::MessageRead(read));
+        assert_eq!(projection.unread_count(id, &owner), Some(1));
+        assert_eq!(projection.unread_counts(&owner).get(&id), Some(&1));
+
+        let receipts = projection.receipts(id, first);
+        assert_eq!(receipts.len(), 1);
+        assert!(receipts[0].delivered_at.unwrap() <= receipts[0].read_at.unwrap());
+
+        let stranger = ParticipantId::Person(PersonId::new());
+        assert_eq!(projection.unread_count(id, &stranger), None);
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Cost attribution by tag
+//!
+//! [`CostAttributionProjection`] adds the token usage of every tagged
+//! response to each of its tags, so spend can be reported per project,
+//! team or any other label callers attach:
+//!
+//! ```text
+//! ResponseCompleted { cost_tags: { project: atlas, team: search }, usage }
+//!     ├──> (project, atlas)  += usage
+//!     └──> (team, search)    += usage
+//!
+//! costs_by("project") ──> [ atlas: 12k tokens $0.18, zephyr: ... ]
+//! ```
+//!
+//! A response carrying several tags counts in full under each of them, so
+//! totals for different keys overlap. Untagged responses are not counted.
+
+use super::TokenPricing;
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::MessageId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::RwLock;
+
+/// Usage attributed to one tag value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagCost {
+    /// Tag key
+    pub key: String,
+
+    /// Tag value
+    pub value: String,
+
+    /// Completed responses carrying the tag
+    pub responses: u64,
+
+    /// Prompt tokens of those responses
+    pub prompt_tokens: u64,
+
+    /// Completion tokens of those responses
+    pub completion_tokens: u64,
+
+    /// Estimated spend in USD, when pricing is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct AttributionState {
+    /// (key, value) -> usage
+    costs: BTreeMap<(String, String), TagCost>,
+    /// Responses already counted
+    seen: HashSet<MessageId>,
+}
+
+/// Projection of token usage and spend by cost tag
+#[derive(Debug, Default)]
+pub struct CostAttributionProjection {
+    state: RwLock<AttributionState>,
+    pricing: Option<TokenPricing>,
+}
+
+impl CostAttributionProjection {
+    /// Create an empty projection without spend estimates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Estimate spend with the given token pricing
+    pub fn with_pricing(mut self, pricing: TokenPricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Apply a stored event; only tagged completed responses count
+    ///
+    /// Redelivered responses are counted once.
+    pub fn project(&self, envelope: &EventEnvelope) {
+        let AgentEvent::ResponseCompleted(event) = &envelope.event else {
+            return;
+        };
+        if event.cost_tags.is_empty() {
+            return;
+        }
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if !state.seen.insert(event.message_id) {
+            return;
+        }
+        let prompt = u64::from(event.token_usage.prompt_tokens);
+        let completion = u64::from(event.token_usage.completion_tokens);
+        for (key, value) in event.cost_tags.iter() {
+            let cost = state
+                .costs
+                .entry((key.to_string(), value.to_string()))
+                .or_insert_with(|| TagCost {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    ..TagCost::default()
+                });
+            cost.responses += 1;
+            cost.prompt_tokens += prompt;
+            cost.completion_tokens += completion;
+            cost.estimated_cost_usd = self
+                .pricing
+                .map(|p| p.cost(cost.prompt_tokens, cost.completion_tokens));
+        }
+    }
+
+    /// Usage under each value of `key`, by value
+    pub fn costs_by(&self, key: &str) -> Vec<TagCost> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state
+            .costs
+            .values()
+            .filter(|cost| cost.key == key)
+            .cloned()
+            .collect()
+    }
+
+    /// Usage under `key = value`, if any response carried it
+    pub fn cost_of(&self, key: &str, value: &str) -> Option<TagCost> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state
+            .costs
+            .get(&(key.to_string(), value.to_string()))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ResponseCompletedEvent;
+    use crate::test_support::envelope;
+    use crate::value_objects::{AgentId, CostTags, FinishReason, TokenUsage};
+
+    fn completed(tags: CostTags, prompt: u32, completion: u32) -> EventEnvelope {
+        let event = ResponseCompletedEvent::new(
+            AgentId::new(),
+            MessageId::new(),
+            1,
+            TokenUsage::new(prompt, completion),
+            FinishReason::Stop,
+            10,
+        )
+        .with_cost_tags(tags);
+        envelope(1, AgentEvent::ResponseCompleted(event))
+    }
+
+    #[test]
+    fn test_usage_adds_up_per_tag_value() {
+        let projection = CostAttributionProjection::new().with_pricing(TokenPricing::new(1.0, 2.0));
+        let atlas = CostTags::new()
+            .with("project", "atlas")
+            .with("team", "search");
+        let first = completed(atlas.clone(), 1000, 500);
+        projection.project(&first);
+        projection.project(&first);
+        projection.project(&completed(atlas, 1000, 0));
+        projection.project(&completed(
+            CostTags::new().with("project", "zephyr"),
+            10,
+            10,
+        ));
+        projection.project(&completed(CostTags::new(), 10, 10));
+
+        let by_project = projection.costs_by("project");
+        assert_eq!(by_project.len(), 2);
+        assert_eq!(by_project[0].value, "atlas");
+        assert_eq!(
+            (
+                by_project[0].responses,
+                by_project[0].prompt_tokens,
+                by_project[0].completion_tokens
+            ),
+            (2, 2000, 500)
+        );
+        assert_eq!(by_project[0].estimated_cost_usd, Some(3.0));
+        assert_eq!(projection.cost_of("team", "search").unwrap().responses, 2);
+        assert!(projection.cost_of("team", "ads").is_none());
+    }
+}
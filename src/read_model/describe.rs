@@ -0,0 +1,153 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Agent self-description
+//!
+//! An [`AgentDescription`] is generated from aggregate state on every
+//! request, so what an agent says about itself cannot go stale:
+//!
+//! ```text
+//! {domain}.queries.agent.{id}.describe
+//!        │
+//!        v
+//! Agent aggregate ──┬── status, model config
+//!                   ├── provider preset ──> capabilities
+//!                   └── assigned tools + tool catalog ──> tool schemas
+//! ```
+
+use crate::aggregate::Agent;
+use crate::capabilities::ProviderCapabilities;
+use crate::intent::ToolDefinition;
+use crate::value_objects::{AgentId, AgentStatus, ModelConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A tool the agent may call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDescription {
+    /// Tool name
+    pub name: String,
+
+    /// What the tool does (absent if the tool is not in the catalog)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// JSON schema for the tool's parameters (absent if not in the catalog)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// Structured document describing an agent as it is right now
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentDescription {
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// Agent name
+    pub name: String,
+
+    /// Optional description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Current status
+    pub status: AgentStatus,
+
+    /// Whether the agent accepts messages
+    pub operational: bool,
+
+    /// Event stream version the description reflects
+    pub version: u64,
+
+    /// Model configuration, if one is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelConfig>,
+
+    /// Capabilities of the configured provider, by name
+    pub capabilities: Vec<String>,
+
+    /// Context window of the configured provider, in tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_length: Option<u32>,
+
+    /// Tools assigned to the agent, by name
+    pub tools: Vec<ToolDescription>,
+
+    /// When the description was generated
+    pub generated_at: DateTime<Utc>,
+}
+
+impl AgentDescription {
+    /// Describe `agent`, with its assigned `tools` looked up in `catalog`
+    #[allow(deprecated)]
+    pub fn from_agent(agent: &Agent, tools: Vec<String>, catalog: &[ToolDefinition]) -> Self {
+        let model = agent.model_config().cloned();
+        let provider = model.as_ref().map(|m| ProviderCapabilities::for_provider(m.provider));
+
+        Self {
+            agent_id: agent.id(),
+            name: agent.name().to_string(),
+            description: agent.description().map(str::to_string),
+            status: agent.status(),
+            operational: agent.is_operational(),
+            version: agent.version(),
+            capabilities: provider
+                .as_ref()
+                .map(|p| p.capabilities.iter_names().map(|(n, _)| n.to_string()).collect())
+                .unwrap_or_default(),
+            max_context_length: provider.and_then(|p| p.max_context_length),
+            model,
+            tools: tools
+                .into_iter()
+                .map(|name| {
+                    let definition = catalog.iter().find(|d| d.name == name);
+                    ToolDescription {
+                        description: definition.map(|d| d.description.clone()),
+                        parameters: definition.map(|d| d.parameters.clone()),
+                        name,
+                    }
+                })
+                .collect(),
+            generated_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, AgentEvent, ModelConfiguredEvent};
+    use crate::value_objects::PersonId;
+
+    #[test]
+    fn test_describes_configured_agent() {
+        let id = AgentId::new();
+        let agent = Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                    id,
+                    PersonId::new(),
+                    "sage",
+                    Some("Answers questions".to_string()),
+                )),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+            ])
+            .unwrap();
+        let catalog = [ToolDefinition::new(
+            "search",
+            "Search the web",
+            serde_json::json!({ "type": "object" }),
+        )];
+
+        let described = AgentDescription::from_agent(
+            &agent,
+            vec!["search".to_string(), "fetch".to_string()],
+            &catalog,
+        );
+        assert_eq!(described.status, AgentStatus::Deployed);
+        assert!(!described.operational);
+        assert_eq!(described.model, Some(ModelConfig::mock()));
+        assert!(described.capabilities.contains(&"TEXT_CHAT".to_string()));
+        assert_eq!(described.tools[0].description.as_deref(), Some("Search the web"));
+        assert!(described.tools[1].parameters.is_none());
+    }
+}
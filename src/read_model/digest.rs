@@ -0,0 +1,290 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Daily digests
+//!
+//! [`DigestProjection`] rolls each agent's events up into one
+//! [`DailyDigest`] per UTC day: lifecycle changes, message outcomes, token
+//! usage and, when pricing is configured, estimated spend. Events are bucketed
+//! by their own timestamp, so a replay lands in the same days as the original
+//! run.
+//!
+//! Closing a day hands back a `DailyDigestReady` event per agent active that
+//! day; publishing them is left to the caller. Each day is closed at most once
+//! per agent, so a late event for a closed day is counted but not announced
+//! again.
+//!
+//! With a spend alert configured, the response that takes an agent's estimated
+//! spend for the day to the threshold hands back a `SpendThresholdCrossed`
+//! event, once per agent and day.
+
+use crate::events::{
+    AgentEvent, DailyDigest, DailyDigestReadyEvent, SpendThresholdCrossedEvent,
+};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::AgentId;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+/// Token prices used to estimate spend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPricing {
+    /// USD per 1,000 prompt tokens
+    pub prompt_per_1k: f64,
+    /// USD per 1,000 completion tokens
+    pub completion_per_1k: f64,
+}
+
+impl TokenPricing {
+    /// Create pricing from per-1K-token rates
+    pub fn new(prompt_per_1k: f64, completion_per_1k: f64) -> Self {
+        Self {
+            prompt_per_1k,
+            completion_per_1k,
+        }
+    }
+
+    /// USD for the given token counts
+    pub fn cost(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        let prompt = prompt_tokens as f64 * self.prompt_per_1k;
+        let completion = completion_tokens as f64 * self.completion_per_1k;
+        (prompt + completion) / 1000.0
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    digest: DailyDigest,
+    /// Events already counted, by type and timestamp
+    seen: HashSet<(&'static str, DateTime<Utc>)>,
+    closed: bool,
+    alerted: bool,
+}
+
+/// Projection of agent events into daily digests
+#[derive(Debug, Default)]
+pub struct DigestProjection {
+    digests: RwLock<HashMap<(NaiveDate, AgentId), Entry>>,
+    pricing: Option<TokenPricing>,
+    spend_alert_usd: Option<f64>,
+}
+
+impl DigestProjection {
+    /// Create an empty projection without spend estimates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Estimate spend with the given token pricing
+    pub fn with_pricing(mut self, pricing: TokenPricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Alert when an agent's estimated spend for a day reaches `threshold_usd`
+    ///
+    /// Only takes effect with pricing configured.
+    pub fn with_spend_alert(mut self, threshold_usd: f64) -> Self {
+        self.spend_alert_usd = Some(threshold_usd);
+        self
+    }
+
+    /// Apply a stored event, returning a spend alert if it crossed the threshold
+    ///
+    /// Redelivered events are counted once. Chunks, limit enforcement and
+    /// digest and spend alert events themselves are not part of a digest.
+    pub fn project(&self, envelope: &EventEnvelope) -> Option<SpendThresholdCrossedEvent> {
+        let event = &envelope.event;
+        if matches!(
+            event,
+            AgentEvent::ResponseChunkReceived(_)
+                | AgentEvent::OutputLimitEnforced(_)
+                | AgentEvent::DailyDigestReady(_)
+                | AgentEvent::SpendThresholdCrossed(_)
+        ) {
+            return None;
+        }
+
+        let at = event.timestamp();
+        let day = at.date_naive();
+        let agent_id = envelope.aggregate_id;
+
+        let mut digests = self.digests.write().unwrap_or_else(|e| e.into_inner());
+        let entry = digests.entry((day, agent_id)).or_insert_with(|| Entry {
+            digest: DailyDigest::new(agent_id, day),
+            seen: HashSet::new(),
+            closed: false,
+            alerted: false,
+        });
+        if !entry.seen.insert((event.event_type_name(), at)) {
+            return None;
+        }
+
+        let digest = &mut entry.digest;
+        match event {
+            AgentEvent::AgentDeployed(_) => digest.deployments += 1,
+            AgentEvent::AgentActivated(_) => digest.activations += 1,
+            AgentEvent::AgentSuspended(_) => digest.suspensions += 1,
+            AgentEvent::AgentDecommissioned(_) => digest.decommissions += 1,
+            AgentEvent::MessageSent(_) => digest.messages += 1,
+            AgentEvent::ResponseCompleted(e) => {
+                digest.completed += 1;
+                digest.prompt_tokens += u64::from(e.token_usage.prompt_tokens);
+                digest.completion_tokens += u64::from(e.token_usage.completion_tokens);
+                digest.estimated_cost_usd = self
+                    .pricing
+                    .map(|p| p.cost(digest.prompt_tokens, digest.completion_tokens));
+            }
+            AgentEvent::ResponseFailed(_) => digest.errors += 1,
+            AgentEvent::ResponseCancelled(_) => digest.cancellations += 1,
+            AgentEvent::SloViolated(_) => digest.slo_violations += 1,
+            _ => {}
+        }
+
+        let threshold = self.spend_alert_usd?;
+        let spent = digest.estimated_cost_usd?;
+        if entry.alerted || spent < threshold {
+            return None;
+        }
+        entry.alerted = true;
+        Some(SpendThresholdCrossedEvent::new(agent_id, day, threshold, spent))
+    }
+
+    /// Digest for an agent on a day, if it had any activity
+    pub fn digest(&self, agent_id: AgentId, day: NaiveDate) -> Option<DailyDigest> {
+        self.read().get(&(day, agent_id)).map(|e| e.digest.clone())
+    }
+
+    /// Every agent's digest for a day
+    pub fn digests_for(&self, day: NaiveDate) -> Vec<DailyDigest> {
+        self.read()
+            .iter()
+            .filter(|((d, _), _)| *d == day)
+            .map(|(_, e)| e.digest.clone())
+            .collect()
+    }
+
+    /// Close a day, returning a `DailyDigestReady` event per agent not yet announced
+    pub fn close_day(&self, day: NaiveDate) -> Vec<DailyDigestReadyEvent> {
+        let mut digests = self.digests.write().unwrap_or_else(|e| e.into_inner());
+        digests
+            .iter_mut()
+            .filter(|((d, _), e)| *d == day && !e.closed)
+            .map(|(_, e)| {
+                e.closed = true;
+                DailyDigestReadyEvent::new(e.digest.clone())
+            })
+            .collect()
+    }
+
+    /// Drop digests for days before `day`
+    pub fn prune_before(&self, day: NaiveDate) {
+        self.digests
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(d, _), _| *d >= day);
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<(NaiveDate, AgentId), Entry>> {
+        self.digests.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentSuspendedEvent, MessageSentEvent, ResponseCompletedEvent, ResponseErrorType,
+        ResponseFailedEvent,
+    };
+    use crate::test_support::envelope;
+    use crate::value_objects::{FinishReason, MessageId, TokenUsage};
+
+    fn completed(agent_id: AgentId, prompt: u32, completion: u32) -> AgentEvent {
+        AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+            agent_id,
+            MessageId::new(),
+            1,
+            TokenUsage::new(prompt, completion),
+            FinishReason::Stop,
+            10,
+        ))
+    }
+
+    #[test]
+    fn test_rolls_up_activity_and_spend() {
+        let projection = DigestProjection::new().with_pricing(TokenPricing::new(1.0, 2.0));
+        let agent_id = AgentId::new();
+        let today = Utc::now().date_naive();
+
+        let sent = envelope(0, AgentEvent::MessageSent(MessageSentEvent::new(
+            agent_id,
+            MessageId::new(),
+            "hello",
+        )));
+        projection.project(&sent);
+        projection.project(&sent);
+        projection.project(&envelope(0, completed(agent_id, 1000, 500)));
+        projection.project(&envelope(0, AgentEvent::ResponseFailed(ResponseFailedEvent::new(
+            agent_id,
+            MessageId::new(),
+            ResponseErrorType::Timeout,
+            "timed out",
+            true,
+        ))));
+        projection.project(&envelope(0, AgentEvent::AgentSuspended(AgentSuspendedEvent::new(
+            agent_id,
+            "maintenance",
+        ))));
+
+        let digest = projection.digest(agent_id, today).unwrap();
+        assert_eq!(digest.messages, 1);
+        assert_eq!(digest.completed, 1);
+        assert_eq!(digest.errors, 1);
+        assert_eq!(digest.suspensions, 1);
+        assert_eq!(digest.prompt_tokens, 1000);
+        assert_eq!(digest.estimated_cost_usd, Some(2.0));
+    }
+
+    #[test]
+    fn test_close_day_announces_each_agent_once() {
+        let projection = DigestProjection::new();
+        let (a, b) = (AgentId::new(), AgentId::new());
+        let today = Utc::now().date_naive();
+        projection.project(&envelope(0, completed(a, 10, 10)));
+        projection.project(&envelope(0, completed(b, 10, 10)));
+
+        assert_eq!(projection.digests_for(today).len(), 2);
+        assert!(projection.digest(a, today).unwrap().estimated_cost_usd.is_none());
+
+        let ready = projection.close_day(today);
+        assert_eq!(ready.len(), 2);
+        assert!(ready.iter().any(|e| e.agent_id == a));
+        assert!(projection.close_day(today).is_empty());
+
+        projection.prune_before(today.succ_opt().unwrap());
+        assert!(projection.digests_for(today).is_empty());
+    }
+
+    #[test]
+    fn test_spend_alert_fires_once_when_threshold_is_crossed() {
+        let projection = DigestProjection::new()
+            .with_pricing(TokenPricing::new(1.0, 1.0))
+            .with_spend_alert(2.0);
+        let agent_id = AgentId::new();
+
+        assert!(projection.project(&envelope(0, completed(agent_id, 500, 500))).is_none());
+        let alert = projection.project(&envelope(0, completed(agent_id, 1000, 0))).unwrap();
+        assert_eq!(alert.agent_id, agent_id);
+        assert_eq!(alert.day, Utc::now().date_naive());
+        assert_eq!(alert.threshold_usd, 2.0);
+        assert_eq!(alert.spent_usd, 2.0);
+
+        // Already alerted today
+        assert!(projection.project(&envelope(0, completed(agent_id, 1000, 0))).is_none());
+
+        // Without pricing there is nothing to alert on
+        let unpriced = DigestProjection::new().with_spend_alert(0.0);
+        assert!(unpriced.project(&envelope(0, completed(agent_id, 10, 10))).is_none());
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Sparse fieldsets for query results
+//!
+//! A query may name the [`AgentView`] fields it needs. `include` narrows the
+//! view to the listed fields, `exclude` then removes fields from whatever
+//! remains. `agent_id` is always returned so results stay addressable.
+//!
+//! ```json
+//! { "include": ["name", "status"] }              → { agent_id, name, status }
+//! { "exclude": ["description", "created_at"] }   → everything else
+//! ```
+
+use super::AgentView;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Selectable [`AgentView`] fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentField {
+    AgentId,
+    PersonId,
+    Name,
+    Description,
+    Status,
+    Provider,
+    ModelName,
+    CreatedAt,
+    UpdatedAt,
+    Version,
+}
+
+impl AgentField {
+    /// Serialized field name in `AgentView`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentField::AgentId => "agent_id",
+            AgentField::PersonId => "person_id",
+            AgentField::Name => "name",
+            AgentField::Description => "description",
+            AgentField::Status => "status",
+            AgentField::Provider => "provider",
+            AgentField::ModelName => "model_name",
+            AgentField::CreatedAt => "created_at",
+            AgentField::UpdatedAt => "updated_at",
+            AgentField::Version => "version",
+        }
+    }
+}
+
+/// Field selection attached to queries (empty selects every field)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSelection {
+    /// Only these fields (None keeps all)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<AgentField>>,
+
+    /// Drop these fields
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<AgentField>,
+}
+
+impl FieldSelection {
+    /// Select every field
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Select only `fields` (plus `agent_id`)
+    pub fn only(fields: impl IntoIterator<Item = AgentField>) -> Self {
+        Self {
+            include: Some(fields.into_iter().collect()),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Builder: drop a field
+    pub fn without(mut self, field: AgentField) -> Self {
+        self.exclude.push(field);
+        self
+    }
+
+    /// Check if this selection returns complete views
+    pub fn is_all(&self) -> bool {
+        self.include.is_none() && self.exclude.is_empty()
+    }
+
+    /// Check if a field survives the selection
+    pub fn selects(&self, field: AgentField) -> bool {
+        if field == AgentField::AgentId {
+            return true;
+        }
+        let included = self
+            .include
+            .as_ref()
+            .is_none_or(|fields| fields.contains(&field));
+        included && !self.exclude.contains(&field)
+    }
+
+    /// Narrow a view to the selected fields
+    pub fn apply(&self, view: &AgentView) -> Value {
+        let Ok(Value::Object(full)) = serde_json::to_value(view) else {
+            unreachable!("AgentView serializes to an object");
+        };
+        if self.is_all() {
+            return Value::Object(full);
+        }
+
+        let narrowed: Map<String, Value> = full
+            .into_iter()
+            .filter(|(key, _)| {
+                serde_json::from_value::<AgentField>(Value::String(key.clone()))
+                    .is_ok_and(|field| self.selects(field))
+            })
+            .collect();
+        Value::Object(narrowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{AgentId, AgentStatus, PersonId};
+    use chrono::Utc;
+
+    fn view() -> AgentView {
+        AgentView {
+            agent_id: AgentId::new(),
+            person_id: PersonId::new(),
+            name: "dashboard".to_string(),
+            description: Some("long text".to_string()),
+            status: AgentStatus::Active,
+            provider: None,
+            model_name: Some("llama3".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 4,
+        }
+    }
+
+    #[test]
+    fn test_include_keeps_agent_id() {
+        let selected = FieldSelection::only([AgentField::Name, AgentField::Status]).apply(&view());
+        let keys: Vec<&str> = selected.as_object().unwrap().keys().map(String::as_str).collect();
+
+        assert_eq!(keys.len(), 3);
+        assert!(keys.contains(&"agent_id"));
+        assert!(keys.contains(&"name"));
+        assert!(keys.contains(&"status"));
+    }
+
+    #[test]
+    fn test_exclude_drops_fields() {
+        let selection = FieldSelection::all()
+            .without(AgentField::Description)
+            .without(AgentField::AgentId);
+        let selected = selection.apply(&view());
+        let object = selected.as_object().unwrap();
+
+        assert!(!object.contains_key("description"));
+        assert!(object.contains_key("agent_id"));
+        assert!(object.contains_key("model_name"));
+    }
+
+    #[test]
+    fn test_parses_from_query_json() {
+        let selection: FieldSelection =
+            serde_json::from_str(r#"{"include":["name","model_name"],"exclude":["name"]}"#)
+                .unwrap();
+
+        assert!(selection.selects(AgentField::ModelName));
+        assert!(!selection.selects(AgentField::Name));
+        assert!(!selection.selects(AgentField::Version));
+    }
+}
@@ -0,0 +1,567 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! GraphQL schema over the agent read model (feature `graphql`)
+//!
+//! Queries read from any [`AgentReadModel`]; subscriptions read from an
+//! [`EventFeed`] that the projector fans stored events into. The schema is
+//! transport-agnostic: serve it with whichever async-graphql integration the
+//! host application uses, or over NATS request-reply with [`serve_nats`]:
+//!
+//! ```text
+//! request { query, variables } ──> subject ──> execute_stream ──> reply inbox
+//!                                                 │  query: one response
+//!                                                 └─ subscription: one per item,
+//!                                                    up to SUBSCRIPTION_MAX_DURATION
+//! ```
+//!
+//! ```graphql
+//! query {
+//!   agents(status: "ACTIVE", page: { limit: 20 }) {
+//!     items { agentId name status modelName }
+//!     nextCursor
+//!   }
+//!   usage(agentId: "...") { messagesSent totalTokens }
+//! }
+//!
+//! subscription {
+//!   messageChunks(messageId: "...") { chunkIndex content isFinal }
+//! }
+//! ```
+
+use super::{
+    AgentReadModel, AgentView, AsOf, MessageView, PageRequest, SortOrder, UsageView,
+    DEFAULT_PAGE_LIMIT,
+};
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, AgentStatus, MessageId};
+use async_graphql::{
+    Context, EmptyMutation, Enum, InputObject, Json, Object, Schema, SimpleObject, Subscription,
+    ID,
+};
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Most messages returned by the `conversation` query
+const MAX_CONVERSATION_MESSAGES: usize = 200;
+
+/// `min_version` wait when the query gives no `timeoutMs`
+const DEFAULT_CONSISTENCY_WAIT_MS: u64 = 1_000;
+
+/// How long [`serve_nats`] streams a subscription before ending it
+///
+/// A NATS inbox cannot tell the server its caller went away, so
+/// subscriptions end and clients resubscribe.
+pub const SUBSCRIPTION_MAX_DURATION: Duration = Duration::from_secs(3600);
+
+/// The agent GraphQL schema
+pub type AgentSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// Build the schema over a read model and event feed
+pub fn build_schema(read_model: Arc<dyn AgentReadModel>, feed: EventFeed) -> AgentSchema {
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+        .data(read_model)
+        .data(feed)
+        .finish()
+}
+
+/// Fan-out of projected events to GraphQL subscribers
+///
+/// Slow subscribers skip events they lagged past rather than blocking the
+/// projector.
+#[derive(Debug, Clone)]
+pub struct EventFeed {
+    sender: broadcast::Sender<Arc<EventEnvelope>>,
+}
+
+impl EventFeed {
+    /// Create a feed buffering up to `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Publish a projected event to current subscribers
+    pub fn publish(&self, envelope: EventEnvelope) {
+        // No subscribers is not an error
+        let _ = self.sender.send(Arc::new(envelope));
+    }
+
+    fn subscribe(&self) -> impl Stream<Item = Arc<EventEnvelope>> {
+        stream::unfold(self.sender.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(envelope) => return Some((envelope, rx)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+/// Serve `schema` over NATS request-reply on `subject`
+///
+/// Each request is a JSON GraphQL request. Queries get one reply; the
+/// responses of a subscription are all published to the reply subject, so
+/// subscribers listen on their inbox instead of waiting for one reply.
+pub async fn serve_nats(
+    schema: AgentSchema,
+    client: async_nats::Client,
+    subject: String,
+) -> Result<(), async_nats::SubscribeError> {
+    let mut requests = client.subscribe(subject).await?;
+    while let Some(message) = requests.next().await {
+        let Some(reply) = message.reply else {
+            continue;
+        };
+        let (schema, client) = (schema.clone(), client.clone());
+        tokio::spawn(async move {
+            let request = serde_json::from_slice::<async_graphql::Request>(&message.payload);
+            let responses = match request {
+                Ok(request) => schema.execute_stream(request).boxed(),
+                Err(e) => {
+                    let error = async_graphql::ServerError::new(e.to_string(), None);
+                    stream::iter([async_graphql::Response::from_errors(vec![error])]).boxed()
+                }
+            };
+            let mut responses = responses.take_until(tokio::time::sleep(SUBSCRIPTION_MAX_DURATION));
+            while let Some(response) = responses.next().await {
+                let Ok(payload) = serde_json::to_vec(&response) else {
+                    continue;
+                };
+                if client.publish(reply.clone(), payload.into()).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Output types
+// ============================================================================
+
+/// An agent
+#[derive(SimpleObject)]
+#[graphql(name = "Agent")]
+pub struct AgentObject {
+    agent_id: ID,
+    person_id: ID,
+    name: String,
+    description: Option<String>,
+    status: String,
+    provider: Option<String>,
+    model_name: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    version: u64,
+}
+
+impl From<AgentView> for AgentObject {
+    fn from(view: AgentView) -> Self {
+        Self {
+            agent_id: ID(view.agent_id.to_string()),
+            person_id: ID(view.person_id.to_string()),
+            name: view.name,
+            description: view.description,
+            status: view.status.to_string(),
+            provider: view.provider.map(|p| p.to_string()),
+            model_name: view.model_name,
+            created_at: view.created_at,
+            updated_at: view.updated_at,
+            version: view.version,
+        }
+    }
+}
+
+/// A page of agents
+#[derive(SimpleObject)]
+#[graphql(name = "AgentPage")]
+pub struct AgentPageObject {
+    items: Vec<AgentObject>,
+    next_cursor: Option<String>,
+}
+
+/// A message and its response
+#[derive(SimpleObject)]
+#[graphql(name = "Message")]
+pub struct MessageObject {
+    message_id: ID,
+    content: String,
+    sent_at: DateTime<Utc>,
+    status: String,
+    chunks: u32,
+    finish_reason: Option<String>,
+    prompt_tokens: Option<u32>,
+    completion_tokens: Option<u32>,
+}
+
+impl From<MessageView> for MessageObject {
+    fn from(view: MessageView) -> Self {
+        Self {
+            message_id: ID(view.message_id.to_string()),
+            content: view.content,
+            sent_at: view.sent_at,
+            status: enum_name(&view.status),
+            chunks: view.chunks,
+            finish_reason: view.finish_reason.map(|r| enum_name(&r)),
+            prompt_tokens: view.token_usage.map(|u| u.prompt_tokens),
+            completion_tokens: view.token_usage.map(|u| u.completion_tokens),
+        }
+    }
+}
+
+/// Message and token totals for an agent
+#[derive(SimpleObject)]
+#[graphql(name = "Usage")]
+pub struct UsageObject {
+    messages_sent: u64,
+    responses_completed: u64,
+    responses_failed: u64,
+    responses_cancelled: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    total_tokens: u64,
+}
+
+impl From<UsageView> for UsageObject {
+    fn from(view: UsageView) -> Self {
+        Self {
+            messages_sent: view.messages_sent,
+            responses_completed: view.responses_completed,
+            responses_failed: view.responses_failed,
+            responses_cancelled: view.responses_cancelled,
+            prompt_tokens: view.prompt_tokens,
+            completion_tokens: view.completion_tokens,
+            total_tokens: view.total_tokens(),
+        }
+    }
+}
+
+/// A lifecycle event as delivered to subscribers
+#[derive(SimpleObject)]
+#[graphql(name = "LifecycleEvent")]
+pub struct LifecycleEventObject {
+    agent_id: ID,
+    event_type: String,
+    sequence: u64,
+    timestamp: DateTime<Utc>,
+    /// The full event as JSON
+    event: Json<serde_json::Value>,
+}
+
+/// A streamed response chunk
+#[derive(SimpleObject)]
+#[graphql(name = "MessageChunk")]
+pub struct MessageChunkObject {
+    message_id: ID,
+    chunk_index: u32,
+    content: String,
+    is_final: bool,
+}
+
+// ============================================================================
+// Input types
+// ============================================================================
+
+/// Sort direction over (createdAt, agentId)
+#[derive(Enum, Clone, Copy, PartialEq, Eq, Default)]
+#[graphql(name = "SortOrder")]
+pub enum SortOrderInput {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Page request for list queries
+#[derive(InputObject, Default)]
+#[graphql(name = "PageInput")]
+pub struct PageInput {
+    cursor: Option<String>,
+    limit: Option<u32>,
+    #[graphql(default)]
+    sort: SortOrderInput,
+}
+
+impl From<PageInput> for PageRequest {
+    fn from(input: PageInput) -> Self {
+        PageRequest {
+            cursor: input.cursor,
+            limit: input.limit.unwrap_or(DEFAULT_PAGE_LIMIT),
+            sort: match input.sort {
+                SortOrderInput::Ascending => SortOrder::Ascending,
+                SortOrderInput::Descending => SortOrder::Descending,
+            },
+        }
+    }
+}
+
+// ============================================================================
+// Roots
+// ============================================================================
+
+/// Query root
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Fetch one agent, optionally waiting for a command's consistency token
+    async fn agent(
+        &self,
+        ctx: &Context<'_>,
+        agent_id: ID,
+        min_version: Option<u64>,
+        timeout_ms: Option<u64>,
+    ) -> async_graphql::Result<Option<AgentObject>> {
+        let view = read_model(ctx)?
+            .get_consistent(
+                parse_agent_id(&agent_id)?,
+                min_version,
+                std::time::Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_CONSISTENCY_WAIT_MS)),
+            )
+            .await?;
+        Ok(view.map(AgentObject::from))
+    }
+
+    /// Page through agents, optionally filtered by status code (e.g. `"ACTIVE"`)
+    ///
+    /// With `validAt` the agents are listed as they were at that time, as
+    /// known at `knownAt` (default: now).
+    async fn agents(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        page: Option<PageInput>,
+        valid_at: Option<DateTime<Utc>>,
+        known_at: Option<DateTime<Utc>>,
+    ) -> async_graphql::Result<AgentPageObject> {
+        let status = status.as_deref().map(parse_status).transpose()?;
+        let page: PageRequest = page.unwrap_or_default().into();
+        let read_model = read_model(ctx)?;
+        let page = match valid_at {
+            Some(valid_at) => {
+                let as_of = AsOf {
+                    valid_at,
+                    known_at,
+                };
+                read_model.list_as_of(status, &as_of, &page).await?
+            }
+            None => read_model.list(status, &page).await?,
+        };
+        Ok(AgentPageObject {
+            items: page.items.into_iter().map(AgentObject::from).collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    /// Recent messages sent to an agent, newest first
+    async fn conversation(
+        &self,
+        ctx: &Context<'_>,
+        agent_id: ID,
+        #[graphql(default = 50)] limit: u32,
+    ) -> async_graphql::Result<Vec<MessageObject>> {
+        let limit = (limit as usize).min(MAX_CONVERSATION_MESSAGES);
+        let messages = read_model(ctx)?
+            .messages(parse_agent_id(&agent_id)?, limit)
+            .await?;
+        Ok(messages.into_iter().map(MessageObject::from).collect())
+    }
+
+    /// Message and token totals for an agent
+    async fn usage(&self, ctx: &Context<'_>, agent_id: ID) -> async_graphql::Result<UsageObject> {
+        let usage = read_model(ctx)?.usage(parse_agent_id(&agent_id)?).await?;
+        Ok(usage.into())
+    }
+}
+
+/// Subscription root
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Lifecycle events, for one agent or the whole fleet
+    async fn lifecycle_events(
+        &self,
+        ctx: &Context<'_>,
+        agent_id: Option<ID>,
+    ) -> async_graphql::Result<impl Stream<Item = LifecycleEventObject>> {
+        let agent_id = agent_id.as_ref().map(parse_agent_id).transpose()?;
+        let feed = ctx.data::<EventFeed>()?;
+
+        Ok(feed.subscribe().filter_map(move |envelope| async move {
+            let wanted = agent_id.is_none_or(|id| id == envelope.aggregate_id)
+                && is_lifecycle_event(&envelope.event);
+            wanted.then(|| LifecycleEventObject {
+                agent_id: ID(envelope.aggregate_id.to_string()),
+                event_type: envelope.event.event_type_name().to_string(),
+                sequence: envelope.sequence,
+                timestamp: envelope.timestamp,
+                event: Json(serde_json::to_value(&envelope.event).unwrap_or_default()),
+            })
+        }))
+    }
+
+    /// Response chunks for one message, ending after the final chunk
+    async fn message_chunks(
+        &self,
+        ctx: &Context<'_>,
+        message_id: ID,
+    ) -> async_graphql::Result<impl Stream<Item = MessageChunkObject>> {
+        let message_id = MessageId::from_uuid(parse_uuid(&message_id)?);
+        let feed = ctx.data::<EventFeed>()?;
+
+        let chunks = feed.subscribe().filter_map(move |envelope| async move {
+            match &envelope.event {
+                AgentEvent::ResponseChunkReceived(e) if e.message_id == message_id => {
+                    Some(MessageChunkObject {
+                        message_id: ID(message_id.to_string()),
+                        chunk_index: e.chunk.chunk_index,
+                        content: e.chunk.content.clone(),
+                        is_final: e.chunk.is_final,
+                    })
+                }
+                _ => None,
+            }
+        });
+
+        // Stop after the final chunk
+        Ok(chunks.scan(false, |done, chunk| {
+            let emit = (!*done).then(|| {
+                *done = chunk.is_final;
+                chunk
+            });
+            async move { emit }
+        }))
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn read_model<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a Arc<dyn AgentReadModel>> {
+    ctx.data::<Arc<dyn AgentReadModel>>()
+}
+
+fn parse_uuid(id: &ID) -> async_graphql::Result<Uuid> {
+    Uuid::parse_str(id).map_err(|e| format!("Invalid ID {}: {}", id.as_str(), e).into())
+}
+
+fn parse_agent_id(id: &ID) -> async_graphql::Result<AgentId> {
+    parse_uuid(id).map(AgentId::from_uuid)
+}
+
+fn parse_status(code: &str) -> async_graphql::Result<AgentStatus> {
+    AgentStatus::from_code(code).ok_or_else(|| format!("Unknown agent status: {}", code).into())
+}
+
+/// snake_case serde name of a unit enum value
+fn enum_name<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn is_lifecycle_event(event: &AgentEvent) -> bool {
+    matches!(
+        event,
+        AgentEvent::AgentDeployed(_)
+            | AgentEvent::ModelConfigured(_)
+            | AgentEvent::ModelConfigurationAssigned(_)
+            | AgentEvent::SystemPromptConfigured(_)
+            | AgentEvent::FeatureFlagsChanged(_)
+            | AgentEvent::LocalePreferencesChanged(_)
+            | AgentEvent::AgentActivated(_)
+            | AgentEvent::AgentSuspended(_)
+            | AgentEvent::AgentDecommissioned(_)
+            | AgentEvent::LegalHoldPlaced(_)
+            | AgentEvent::LegalHoldReleased(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AgentDeployedEvent;
+    use crate::read_model::InMemoryAgentReadModel;
+    use crate::test_support::envelope;
+    use crate::value_objects::PersonId;
+
+    #[tokio::test]
+    async fn test_agent_query_reads_projection() {
+        let model = InMemoryAgentReadModel::new();
+        let agent_id = AgentId::new();
+        let event = AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            "graph",
+            None,
+        ));
+        model.project(&envelope(1, event)).await.unwrap();
+
+        let schema = build_schema(Arc::new(model), EventFeed::default());
+        let response = schema
+            .execute(format!(
+                r#"{{ agent(agentId: "{}") {{ name version }} usage(agentId: "{}") {{ messagesSent }} }}"#,
+                agent_id, agent_id
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["agent"]["name"], "graph");
+        assert_eq!(data["agent"]["version"], 1);
+        assert_eq!(data["usage"]["messagesSent"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_subscription_streams_published_events() {
+        let feed = EventFeed::default();
+        let schema = build_schema(Arc::new(InMemoryAgentReadModel::new()), feed.clone());
+        let mut events = schema.execute_stream("subscription { lifecycleEvents { eventType } }");
+        let event = AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            AgentId::new(),
+            PersonId::new(),
+            "graph",
+            None,
+        ));
+
+        // The subscription only hears events published after it is first polled
+        let mut response = None;
+        for _ in 0..50 {
+            feed.publish(envelope(1, event.clone()));
+            let next = tokio::time::timeout(Duration::from_millis(20), events.next());
+            if let Ok(received) = next.await {
+                response = received;
+                break;
+            }
+        }
+        let data = response.unwrap().data.into_json().unwrap();
+        assert_eq!(data["lifecycleEvents"]["eventType"], "deployed");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_id_is_a_field_error() {
+        let schema = build_schema(
+            Arc::new(InMemoryAgentReadModel::new()),
+            EventFeed::default(),
+        );
+        let response = schema.execute(r#"{ agent(agentId: "nope") { name } }"#).await;
+
+        assert_eq!(response.errors.len(), 1);
+    }
+}
@@ -0,0 +1,467 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! In-memory agent read model
+
+use super::{
+    paginate, AgentHistory, AgentReadModel, AgentView, AsOf, ConsistencyToken, MessageStatus,
+    MessageView, Page, PageRequest, ReadModelError, ReadModelResult, UsageView,
+};
+use crate::aggregate::Agent;
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{clock_now, AgentId, AgentStatus, FinishReason, MessageId};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// An agent's projected state and history, as kept by a read model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Projected {
+    pub(super) agent: Agent,
+    pub(super) version: u64,
+    pub(super) updated_at: DateTime<Utc>,
+    pub(super) history: AgentHistory,
+}
+
+impl Projected {
+    pub(super) fn view(&self) -> AgentView {
+        AgentView::from_agent(&self.agent, self.version, self.updated_at)
+    }
+
+    /// State after a lifecycle `envelope`, or `None` if it changes nothing
+    ///
+    /// Envelopes at or below the projected version are redeliveries; events
+    /// other than `AgentDeployed` for an unseen agent are skipped.
+    pub(super) fn advance(
+        existing: Option<&Projected>,
+        envelope: &EventEnvelope,
+    ) -> ReadModelResult<Option<Projected>> {
+        if existing.is_some_and(|p| envelope.sequence <= p.version) {
+            return Ok(None);
+        }
+
+        let agent = match (existing, &envelope.event) {
+            (Some(p), event) => p.agent.apply_event(event),
+            (None, event @ AgentEvent::AgentDeployed(_)) => Agent::empty().apply_event(event),
+            (None, _) => {
+                tracing::warn!(
+                    "Skipping {} for unknown agent {}",
+                    envelope.event.event_type_name(),
+                    envelope.aggregate_id
+                );
+                return Ok(None);
+            }
+        }
+        .map_err(ReadModelError::Projection)?;
+
+        let mut history = existing.map(|p| p.history.clone()).unwrap_or_default();
+        history.record(
+            AgentView::from_agent(&agent, envelope.sequence, envelope.timestamp),
+            envelope.timestamp,
+            clock_now(),
+        );
+        Ok(Some(Projected {
+            agent,
+            version: envelope.sequence,
+            updated_at: envelope.timestamp,
+            history,
+        }))
+    }
+}
+
+/// Messages kept per agent; older ones still count toward usage
+const MESSAGE_HISTORY: usize = 200;
+
+/// Recent messages and usage totals of an agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Activity {
+    pub(super) messages: VecDeque<MessageView>,
+    pub(super) usage: UsageView,
+}
+
+impl Activity {
+    pub(super) fn new(agent_id: AgentId) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            usage: UsageView::empty(agent_id),
+        }
+    }
+
+    /// Apply a message event (see [`is_message_event`])
+    pub(super) fn apply(&mut self, event: &AgentEvent) {
+        let Activity { messages, usage } = self;
+
+        match event {
+            AgentEvent::MessageSent(e) => {
+                if messages.iter().all(|m| m.message_id != e.message_id) {
+                    usage.messages_sent += 1;
+                    messages.push_front(MessageView {
+                        message_id: e.message_id,
+                        agent_id: e.agent_id,
+                        content: e.content.clone(),
+                        sent_at: e.sent_at,
+                        status: MessageStatus::Pending,
+                        chunks: 0,
+                        finish_reason: None,
+                        token_usage: None,
+                    });
+                    messages.truncate(MESSAGE_HISTORY);
+                }
+            }
+            AgentEvent::ResponseChunkReceived(e) => {
+                if let Some(m) = open_message(messages, e.message_id) {
+                    m.status = MessageStatus::Streaming;
+                    m.chunks = m.chunks.max(e.chunk.chunk_index + 1);
+                }
+            }
+            AgentEvent::ResponseCompleted(e) => {
+                if let Some(m) = open_message(messages, e.message_id) {
+                    m.status = MessageStatus::Completed;
+                    m.chunks = e.total_chunks;
+                    m.finish_reason = Some(e.finish_reason);
+                    m.token_usage = Some(e.token_usage);
+                    usage.responses_completed += 1;
+                    usage.prompt_tokens += u64::from(e.token_usage.prompt_tokens);
+                    usage.completion_tokens += u64::from(e.token_usage.completion_tokens);
+                }
+            }
+            AgentEvent::ResponseFailed(e) => {
+                if let Some(m) = open_message(messages, e.message_id) {
+                    m.status = MessageStatus::Failed;
+                    m.finish_reason = Some(FinishReason::Error);
+                    usage.responses_failed += 1;
+                }
+            }
+            AgentEvent::ResponseCancelled(e) => {
+                if let Some(m) = open_message(messages, e.message_id) {
+                    m.status = MessageStatus::Cancelled;
+                    m.chunks = e.chunks_delivered;
+                    m.finish_reason = Some(FinishReason::Cancelled);
+                    usage.responses_cancelled += 1;
+                }
+            }
+            AgentEvent::ConversationArchived(e) => {
+                // Archived messages leave the hot view; usage keeps counting them
+                messages.retain(|m| m.sent_at > e.last_message_at);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Message whose response has not ended yet
+fn open_message(
+    messages: &mut VecDeque<MessageView>,
+    message_id: MessageId,
+) -> Option<&mut MessageView> {
+    messages
+        .iter_mut()
+        .find(|m| m.message_id == message_id && !m.status.is_terminal())
+}
+
+/// Events tracked by message rather than by stream version
+pub(super) fn is_message_event(event: &AgentEvent) -> bool {
+    matches!(
+        event,
+        AgentEvent::MessageSent(_)
+            | AgentEvent::ResponseChunkReceived(_)
+            | AgentEvent::ResponseCompleted(_)
+            | AgentEvent::ResponseFailed(_)
+            | AgentEvent::ResponseCancelled(_)
+            | AgentEvent::ConversationArchived(_)
+    )
+}
+
+/// Agent projection held in memory (for single-process deployments and tests)
+///
+/// Waiters are woken on every projected event instead of polling.
+#[derive(Debug, Clone)]
+pub struct InMemoryAgentReadModel {
+    agents: Arc<RwLock<HashMap<AgentId, Projected>>>,
+    activity: Arc<RwLock<HashMap<AgentId, Activity>>>,
+    changes: Arc<watch::Sender<u64>>,
+}
+
+impl InMemoryAgentReadModel {
+    /// Create an empty read model
+    pub fn new() -> Self {
+        let (changes, _) = watch::channel(0);
+        Self {
+            agents: Arc::new(RwLock::new(HashMap::new())),
+            activity: Arc::new(RwLock::new(HashMap::new())),
+            changes: Arc::new(changes),
+        }
+    }
+
+    /// All projected agents
+    pub fn all(&self) -> Vec<AgentView> {
+        self.read()
+            .values()
+            .map(Projected::view)
+            .collect()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<AgentId, Projected>> {
+        self.agents.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<AgentId, Projected>> {
+        self.agents.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for InMemoryAgentReadModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AgentReadModel for InMemoryAgentReadModel {
+    async fn project(&self, envelope: &EventEnvelope) -> ReadModelResult<()> {
+        if is_message_event(&envelope.event) {
+            self.activity
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(envelope.aggregate_id)
+                .or_insert_with(|| Activity::new(envelope.aggregate_id))
+                .apply(&envelope.event);
+            return Ok(());
+        }
+
+        {
+            let mut agents = self.write();
+            let existing = agents.get(&envelope.aggregate_id);
+            let Some(projected) = Projected::advance(existing, envelope)? else {
+                return Ok(());
+            };
+            agents.insert(envelope.aggregate_id, projected);
+        }
+
+        self.changes.send_modify(|n| *n += 1);
+        Ok(())
+    }
+
+    async fn version(&self, agent_id: AgentId) -> ReadModelResult<u64> {
+        Ok(self.read().get(&agent_id).map(|p| p.version).unwrap_or(0))
+    }
+
+    async fn get(&self, agent_id: AgentId) -> ReadModelResult<Option<AgentView>> {
+        Ok(self
+            .read()
+            .get(&agent_id)
+            .map(Projected::view))
+    }
+
+    async fn list(
+        &self,
+        status: Option<AgentStatus>,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        let views = self
+            .all()
+            .into_iter()
+            .filter(|v| status.is_none_or(|s| v.status == s))
+            .collect();
+        paginate(views, page)
+    }
+
+    async fn get_as_of(&self, agent_id: AgentId, as_of: &AsOf) -> ReadModelResult<Option<AgentView>> {
+        Ok(self
+            .read()
+            .get(&agent_id)
+            .and_then(|p| p.history.at(as_of).cloned()))
+    }
+
+    async fn list_as_of(
+        &self,
+        status: Option<AgentStatus>,
+        as_of: &AsOf,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        let views = self
+            .read()
+            .values()
+            .filter_map(|p| p.history.at(as_of).cloned())
+            .filter(|v| status.is_none_or(|s| v.status == s))
+            .collect();
+        paginate(views, page)
+    }
+
+    async fn messages(&self, agent_id: AgentId, limit: usize) -> ReadModelResult<Vec<MessageView>> {
+        let activity = self.activity.read().unwrap_or_else(|e| e.into_inner());
+        Ok(activity
+            .get(&agent_id)
+            .map(|a| a.messages.iter().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn usage(&self, agent_id: AgentId) -> ReadModelResult<UsageView> {
+        let activity = self.activity.read().unwrap_or_else(|e| e.into_inner());
+        Ok(activity
+            .get(&agent_id)
+            .map(|a| a.usage)
+            .unwrap_or_else(|| UsageView::empty(agent_id)))
+    }
+
+    async fn wait_for(&self, token: ConsistencyToken, timeout: Duration) -> ReadModelResult<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut changes = self.changes.subscribe();
+
+        loop {
+            let current = self.version(token.agent_id).await?;
+            if current >= token.version {
+                return Ok(());
+            }
+
+            let timed_out = !matches!(
+                tokio::time::timeout_at(deadline, changes.changed()).await,
+                Ok(Ok(()))
+            );
+            if timed_out {
+                return Err(ReadModelError::NotCaughtUp {
+                    agent_id: token.agent_id,
+                    required: token.version,
+                    current: self.version(token.agent_id).await?,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentActivatedEvent, AgentDeployedEvent, ConversationArchivedEvent, MessageSentEvent,
+        ModelConfiguredEvent, ResponseCompletedEvent,
+    };
+    use crate::test_support::envelope;
+    use crate::value_objects::{ConversationId, ModelConfig, PersonId, TokenUsage};
+
+    fn deployed(agent_id: AgentId) -> AgentEvent {
+        AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            "Projected",
+            None,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_projects_and_ignores_redelivery() {
+        let model = InMemoryAgentReadModel::new();
+        let agent_id = AgentId::new();
+
+        model.project(&envelope(1, deployed(agent_id))).await.unwrap();
+        let configured = AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+            agent_id,
+            ModelConfig::mock(),
+        ));
+        model.project(&envelope(2, configured.clone())).await.unwrap();
+        model.project(&envelope(2, configured)).await.unwrap();
+
+        let view = model.get(agent_id).await.unwrap().unwrap();
+        assert_eq!(view.version, 2);
+        assert_eq!(view.name, "Projected");
+        assert!(view.model_name.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_returns_once_caught_up() {
+        let model = InMemoryAgentReadModel::new();
+        let agent_id = AgentId::new();
+        model.project(&envelope(1, deployed(agent_id))).await.unwrap();
+
+        let projector = model.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let configured = AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+                agent_id,
+                ModelConfig::mock(),
+            ));
+            projector.project(&envelope(2, configured)).await.unwrap();
+            let activated = AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id));
+            projector.project(&envelope(3, activated)).await.unwrap();
+        });
+
+        let view = model
+            .get_consistent(agent_id, Some(3), Duration::from_secs(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(view.status, AgentStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_times_out() {
+        let model = InMemoryAgentReadModel::new();
+        let agent_id = AgentId::new();
+        model.project(&envelope(1, deployed(agent_id))).await.unwrap();
+
+        let result = model
+            .wait_for(ConsistencyToken::new(agent_id, 5), Duration::from_millis(20))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ReadModelError::NotCaughtUp { required: 5, current: 1, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_message_usage_counts_once() {
+        let model = InMemoryAgentReadModel::new();
+        let agent_id = AgentId::new();
+        let message_id = MessageId::new();
+
+        let sent = AgentEvent::MessageSent(MessageSentEvent::new(agent_id, message_id, "hi"));
+        let completed = AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+            agent_id,
+            message_id,
+            3,
+            TokenUsage::new(10, 5),
+            FinishReason::Stop,
+            120,
+        ));
+        for event in [sent.clone(), sent, completed.clone(), completed] {
+            model.project(&envelope(0, event)).await.unwrap();
+        }
+
+        let messages = model.messages(agent_id, 10).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].status, MessageStatus::Completed);
+
+        let usage = model.usage(agent_id).await.unwrap();
+        assert_eq!(usage.messages_sent, 1);
+        assert_eq!(usage.responses_completed, 1);
+        assert_eq!(usage.total_tokens(), 15);
+    }
+
+    #[tokio::test]
+    async fn test_archived_conversation_leaves_hot_view() {
+        let model = InMemoryAgentReadModel::new();
+        let agent_id = AgentId::new();
+        let sent = MessageSentEvent::new(agent_id, MessageId::new(), "old");
+        let archived = ConversationArchivedEvent::new(
+            agent_id,
+            ConversationId::new(),
+            1,
+            sent.sent_at,
+            sent.sent_at,
+        );
+
+        model.project(&envelope(0, AgentEvent::MessageSent(sent))).await.unwrap();
+        model
+            .project(&envelope(0, AgentEvent::ConversationArchived(archived)))
+            .await
+            .unwrap();
+
+        assert!(model.messages(agent_id, 10).await.unwrap().is_empty());
+        assert_eq!(model.usage(agent_id).await.unwrap().messages_sent, 1);
+    }
+}
@@ -0,0 +1,385 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! NATS KV agent read model
+//!
+//! Persists the projection in JetStream key-value buckets so it survives
+//! restarts and can be shared by several query-serving instances:
+//!
+//! ```text
+//! {bucket}            {agent_id}   -> projected agent + history
+//! {bucket}_ACTIVITY   {agent_id}   -> recent messages + usage
+//! {bucket}_BY_STATUS  {STATUS}     -> [agent_id, ..]   list(status)
+//! {bucket}_BY_OWNER   {person_id}  -> [agent_id, ..]   list_by_owner(person)
+//! ```
+//!
+//! An index entry is keyed by the value queried for, so a filtered list
+//! reads one entry. When an agent's status or owner changes it moves from
+//! the old entry to the new one. Buckets keep only the latest value of each
+//! key, and [`KvAgentReadModel::with_history_max_age`] bounds the
+//! revisions kept per agent for as-of queries.
+//!
+//! Writes are compare-and-set on the entry revision, so instances
+//! projecting the same stream converge: the loser of a race re-reads,
+//! finds the envelope already applied, and stops.
+
+use super::in_memory::{is_message_event, Activity, Projected};
+use super::{
+    paginate, AgentReadModel, AgentView, AsOf, MessageView, Page, PageRequest, ReadModelError,
+    ReadModelResult, UsageView,
+};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{clock_now, AgentId, AgentStatus, PersonId};
+use async_nats::jetstream;
+use async_nats::jetstream::kv::{CreateErrorKind, Store as KvStore, UpdateErrorKind};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+
+/// Attempts at a compare-and-set write before giving up
+const CAS_ATTEMPTS: usize = 5;
+
+/// Agent read model kept in NATS KV buckets
+#[derive(Clone)]
+pub struct KvAgentReadModel {
+    agents: KvStore,
+    activity: KvStore,
+    by_status: KvStore,
+    by_owner: KvStore,
+    history_max_age: Option<Duration>,
+}
+
+impl KvAgentReadModel {
+    /// Create or open the buckets named after `bucket` (e.g. "AGENT_READ_MODEL")
+    pub async fn ensure_buckets(
+        jetstream: &jetstream::Context,
+        bucket: &str,
+    ) -> Result<Self, async_nats::Error> {
+        Ok(Self {
+            agents: ensure_bucket(jetstream, bucket.to_string()).await?,
+            activity: ensure_bucket(jetstream, format!("{}_ACTIVITY", bucket)).await?,
+            by_status: ensure_bucket(jetstream, format!("{}_BY_STATUS", bucket)).await?,
+            by_owner: ensure_bucket(jetstream, format!("{}_BY_OWNER", bucket)).await?,
+            history_max_age: None,
+        })
+    }
+
+    /// Builder: keep only revisions needed to answer as-of queries within `max_age`
+    ///
+    /// Entries otherwise grow by one revision per lifecycle event.
+    pub fn with_history_max_age(mut self, max_age: Duration) -> Self {
+        self.history_max_age = Some(max_age);
+        self
+    }
+
+    /// Agents owned by `person_id`, one page at a time
+    pub async fn list_by_owner(
+        &self,
+        person_id: PersonId,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        let views = self.indexed(&self.by_owner, &person_id.to_string()).await?;
+        paginate(views, page)
+    }
+
+    /// Views of the agents listed under `key` in an index bucket
+    async fn indexed(&self, index: &KvStore, key: &str) -> ReadModelResult<Vec<AgentView>> {
+        let agent_ids = read::<Vec<AgentId>>(index, key).await?;
+        let mut views = Vec::new();
+        for agent_id in agent_ids.map(|(ids, _)| ids).unwrap_or_default() {
+            let current = read::<Projected>(&self.agents, &agent_id.to_string()).await?;
+            if let Some((projected, _)) = current {
+                views.push(projected.view());
+            }
+        }
+        Ok(views)
+    }
+
+    /// Every projected agent
+    async fn all(&self) -> ReadModelResult<Vec<Projected>> {
+        let mut projected = Vec::new();
+        for key in keys(&self.agents).await? {
+            if let Some((p, _)) = read::<Projected>(&self.agents, &key).await? {
+                projected.push(p);
+            }
+        }
+        Ok(projected)
+    }
+
+    /// Move the agent's index entries from `old` to `new`
+    async fn reindex(&self, old: Option<&Projected>, new: &Projected) -> ReadModelResult<()> {
+        let agent_id = new.agent.id();
+        for (index, key) in [(&self.by_status, status_key), (&self.by_owner, owner_key)] {
+            let new_key = key(new);
+            let old_key = old.map(key);
+            if old_key.as_ref() == Some(&new_key) {
+                continue;
+            }
+            if let Some(old_key) = old_key {
+                update_index(index, &old_key, |ids| ids.retain(|id| *id != agent_id)).await?;
+            }
+            update_index(index, &new_key, |ids| {
+                if !ids.contains(&agent_id) {
+                    ids.push(agent_id);
+                }
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn project_activity(&self, envelope: &EventEnvelope) -> ReadModelResult<()> {
+        let key = envelope.aggregate_id.to_string();
+        for _ in 0..CAS_ATTEMPTS {
+            let current = read::<Activity>(&self.activity, &key).await?;
+            let revision = current.as_ref().map(|(_, r)| *r);
+            let mut activity = current
+                .map(|(a, _)| a)
+                .unwrap_or_else(|| Activity::new(envelope.aggregate_id));
+            activity.apply(&envelope.event);
+            if write(&self.activity, &key, &activity, revision).await? {
+                return Ok(());
+            }
+        }
+        Err(contended(&key))
+    }
+}
+
+#[async_trait]
+impl AgentReadModel for KvAgentReadModel {
+    async fn project(&self, envelope: &EventEnvelope) -> ReadModelResult<()> {
+        if is_message_event(&envelope.event) {
+            return self.project_activity(envelope).await;
+        }
+
+        let key = envelope.aggregate_id.to_string();
+        for _ in 0..CAS_ATTEMPTS {
+            let current = read::<Projected>(&self.agents, &key).await?;
+            let existing = current.as_ref().map(|(p, _)| p);
+            let Some(mut projected) = Projected::advance(existing, envelope)? else {
+                return Ok(());
+            };
+            if let Some(max_age) = self.history_max_age {
+                let max_age = chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+                if let Some(cutoff) = clock_now().checked_sub_signed(max_age) {
+                    projected.history.prune_before(cutoff);
+                }
+            }
+            let revision = current.as_ref().map(|(_, r)| *r);
+            if write(&self.agents, &key, &projected, revision).await? {
+                return self.reindex(existing, &projected).await;
+            }
+        }
+        Err(contended(&key))
+    }
+
+    async fn version(&self, agent_id: AgentId) -> ReadModelResult<u64> {
+        let current = read::<Projected>(&self.agents, &agent_id.to_string()).await?;
+        Ok(current.map(|(p, _)| p.version).unwrap_or(0))
+    }
+
+    async fn get(&self, agent_id: AgentId) -> ReadModelResult<Option<AgentView>> {
+        let current = read::<Projected>(&self.agents, &agent_id.to_string()).await?;
+        Ok(current.map(|(p, _)| p.view()))
+    }
+
+    async fn list(
+        &self,
+        status: Option<AgentStatus>,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        let views = match status {
+            Some(status) => self.indexed(&self.by_status, status.code()).await?,
+            None => self.all().await?.iter().map(Projected::view).collect(),
+        };
+        paginate(views, page)
+    }
+
+    async fn get_as_of(
+        &self,
+        agent_id: AgentId,
+        as_of: &AsOf,
+    ) -> ReadModelResult<Option<AgentView>> {
+        let current = read::<Projected>(&self.agents, &agent_id.to_string()).await?;
+        Ok(current.and_then(|(p, _)| p.history.at(as_of).cloned()))
+    }
+
+    async fn list_as_of(
+        &self,
+        status: Option<AgentStatus>,
+        as_of: &AsOf,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        let views = self
+            .all()
+            .await?
+            .iter()
+            .filter_map(|p| p.history.at(as_of).cloned())
+            .filter(|v| status.is_none_or(|s| v.status == s))
+            .collect();
+        paginate(views, page)
+    }
+
+    async fn messages(&self, agent_id: AgentId, limit: usize) -> ReadModelResult<Vec<MessageView>> {
+        let current = read::<Activity>(&self.activity, &agent_id.to_string()).await?;
+        Ok(current
+            .map(|(a, _)| a.messages.into_iter().take(limit).collect())
+            .unwrap_or_default())
+    }
+
+    async fn usage(&self, agent_id: AgentId) -> ReadModelResult<UsageView> {
+        let current = read::<Activity>(&self.activity, &agent_id.to_string()).await?;
+        Ok(current
+            .map(|(a, _)| a.usage)
+            .unwrap_or_else(|| UsageView::empty(agent_id)))
+    }
+}
+
+async fn ensure_bucket(
+    jetstream: &jetstream::Context,
+    bucket: String,
+) -> Result<KvStore, async_nats::Error> {
+    match jetstream.get_key_value(&bucket).await {
+        Ok(kv) => Ok(kv),
+        Err(_) => {
+            let kv = jetstream
+                .create_key_value(jetstream::kv::Config {
+                    bucket,
+                    history: 1,
+                    storage: jetstream::stream::StorageType::File,
+                    ..Default::default()
+                })
+                .await?;
+            Ok(kv)
+        }
+    }
+}
+
+/// `{STATUS}` entry of the status index listing the agent
+fn status_key(projected: &Projected) -> String {
+    projected.agent.status().code().to_string()
+}
+
+/// `{person_id}` entry of the owner index listing the agent
+fn owner_key(projected: &Projected) -> String {
+    projected.agent.person_id().to_string()
+}
+
+/// Change the agent ids listed under `key`, retrying on conflicting writes
+async fn update_index(
+    index: &KvStore,
+    key: &str,
+    change: impl Fn(&mut Vec<AgentId>),
+) -> ReadModelResult<()> {
+    for _ in 0..CAS_ATTEMPTS {
+        let current = read::<Vec<AgentId>>(index, key).await?;
+        let revision = current.as_ref().map(|(_, r)| *r);
+        let mut ids = current.map(|(ids, _)| ids).unwrap_or_default();
+        change(&mut ids);
+        if write(index, key, &ids, revision).await? {
+            return Ok(());
+        }
+    }
+    Err(contended(key))
+}
+
+/// Current value and revision of `key`, if it holds one
+async fn read<T: DeserializeOwned>(kv: &KvStore, key: &str) -> ReadModelResult<Option<(T, u64)>> {
+    let Some(entry) = kv.entry(key).await.map_err(storage)? else {
+        return Ok(None);
+    };
+    if entry.operation != jetstream::kv::Operation::Put {
+        return Ok(None);
+    }
+    let value = serde_json::from_slice(&entry.value)
+        .map_err(|e| ReadModelError::Storage(format!("Corrupt entry {}: {}", key, e)))?;
+    Ok(Some((value, entry.revision)))
+}
+
+/// Write `value` if `key` is still at `revision` (absent when `None`)
+///
+/// Returns false when another writer got there first.
+async fn write<T: Serialize>(
+    kv: &KvStore,
+    key: &str,
+    value: &T,
+    revision: Option<u64>,
+) -> ReadModelResult<bool> {
+    let payload = serde_json::to_vec(value).map_err(storage)?;
+    match revision {
+        Some(revision) => match kv.update(key, payload.into(), revision).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == UpdateErrorKind::WrongLastRevision => Ok(false),
+            Err(e) => Err(storage(e)),
+        },
+        None => match kv.create(key, payload.into()).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == CreateErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(storage(e)),
+        },
+    }
+}
+
+async fn keys(kv: &KvStore) -> ReadModelResult<Vec<String>> {
+    kv.keys()
+        .await
+        .map_err(storage)?
+        .try_collect()
+        .await
+        .map_err(storage)
+}
+
+fn storage(e: impl std::fmt::Display) -> ReadModelError {
+    ReadModelError::Storage(e.to_string())
+}
+
+fn contended(key: &str) -> ReadModelError {
+    ReadModelError::Storage(format!(
+        "Gave up writing {} after {} conflicting updates",
+        key, CAS_ATTEMPTS
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentActivatedEvent, AgentDeployedEvent, AgentEvent, ModelConfiguredEvent,
+    };
+    use crate::test_support::envelope;
+    use crate::value_objects::ModelConfig;
+
+    fn advance(existing: Option<&Projected>, sequence: u64, event: AgentEvent) -> Projected {
+        let envelope = envelope(sequence, event);
+        Projected::advance(existing, &envelope).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_entries_round_trip_and_index_keys_follow_status() {
+        let agent_id = AgentId::new();
+        let person_id = PersonId::new();
+        let deployed = advance(
+            None,
+            1,
+            AgentEvent::AgentDeployed(AgentDeployedEvent::new(agent_id, person_id, "Kv", None)),
+        );
+        assert_eq!(status_key(&deployed), "DEPLOYED");
+        assert_eq!(owner_key(&deployed), person_id.to_string());
+
+        let stored: Projected =
+            serde_json::from_slice(&serde_json::to_vec(&deployed).unwrap()).unwrap();
+        let configured = advance(
+            Some(&stored),
+            2,
+            AgentEvent::ModelConfigured(ModelConfiguredEvent::new(agent_id, ModelConfig::mock())),
+        );
+        let active = advance(
+            Some(&configured),
+            3,
+            AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id)),
+        );
+        assert_eq!(status_key(&active), "ACTIVE");
+        assert_eq!(active.history.revisions().len(), 3);
+        assert_eq!(active.view().name, "Kv");
+    }
+}
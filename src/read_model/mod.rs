@@ -0,0 +1,177 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Read side for agent queries
+//!
+//! Projections are fed asynchronously from the event stream, so a query
+//! issued right after a command can observe state from before it. Command
+//! replies therefore carry a [`ConsistencyToken`] (agent + resulting stream
+//! version); a query that passes it as `min_version` waits, bounded, for the
+//! projection to catch up.
+//!
+//! ```text
+//! command ──> reply { consistency: { agent_id, version: 7 } }
+//!                                                   │
+//! query { agent_id, min_version: 7 } ──> wait_for ──┘──> AgentView (version ≥ 7)
+//!                                          │
+//!                                          └─ timeout ──> ReadModelError::NotCaughtUp
+//! ```
+//!
+//! List queries are paged with a [`PageRequest`] and return a [`Page`]
+//! carrying a stable cursor for the next request. Any query may narrow its
+//! results with a [`FieldSelection`], and read past state with an [`AsOf`]
+//! point in valid and system time. [`AgentGraphProjection`] keeps a graph of
+//! relationships between agents, [`CapabilityIndex`] finds active agents by
+//! runtime capability without a scan, and [`DigestProjection`] rolls each agent's
+//! day up into a [`DailyDigest`](crate::events::DailyDigest).
+//! [`CostAttributionProjection`] totals usage and spend by cost tag. A [`Transcript`]
+//! rebuilds a conversation from message events for [`TranscriptRenderer`] to
+//! render as Markdown or HTML. [`ReasoningTraceProjection`] rebuilds the
+//! decision tree recorded for a message from its reasoning traces, and
+//! [`ToolUsageProjection`] keeps per-tool latency, failure and argument-size
+//! statistics for each agent. [`ConversationProjection`] tracks which
+//! participants have received and read each conversation message and keeps
+//! their unread counts. [`ModerationProjection`] lists messages and responses
+//! whose toxicity score was flagged, per agent and tenant. An
+//! [`AgentDescription`] is generated straight from aggregate state to answer
+//! an agent's `describe` query. [`InMemoryAgentReadModel`] rebuilds on every
+//! start; [`KvAgentReadModel`] keeps the projection in NATS KV buckets so it
+//! persists across restarts. With the `sql` feature `SqlAgentReadModel`
+//! projects into PostgreSQL tables for fleet dashboards. With the `graphql`
+//! feature the read model is also exposed as an async-graphql schema.
+
+mod capability_index;
+mod consistency;
+mod conversation;
+mod cost_attribution;
+mod describe;
+mod digest;
+mod fields;
+#[cfg(feature = "graphql")]
+mod graphql;
+mod in_memory;
+mod kv;
+mod moderation;
+mod page;
+mod queries;
+mod reasoning;
+mod relationships;
+#[cfg(feature = "sql")]
+mod sql;
+mod temporal;
+mod tool_usage;
+mod transcript;
+mod view;
+
+pub use capability_index::CapabilityIndex;
+pub use consistency::{ConsistencyToken, ReadModelError, ReadModelResult};
+pub use conversation::{ConversationProjection, MessageReceipt};
+pub use cost_attribution::{CostAttributionProjection, TagCost};
+pub use describe::{AgentDescription, ToolDescription};
+pub use digest::{DigestProjection, TokenPricing};
+pub use fields::{AgentField, FieldSelection};
+#[cfg(feature = "graphql")]
+pub use graphql::{
+    build_schema, serve_nats, AgentObject, AgentPageObject, AgentSchema, EventFeed,
+    LifecycleEventObject, MessageChunkObject, MessageObject, PageInput, QueryRoot,
+    SortOrderInput, SubscriptionRoot, UsageObject, SUBSCRIPTION_MAX_DURATION,
+};
+pub use in_memory::InMemoryAgentReadModel;
+pub use kv::KvAgentReadModel;
+pub use moderation::{FlaggedMessage, FlaggedSource, ModerationProjection};
+pub use page::{
+    paginate, Page, PageCursor, PageRequest, SortOrder, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT,
+};
+pub use queries::AgentQuery;
+pub use reasoning::{DecisionNode, ReasoningTraceProjection};
+pub use relationships::{AgentGraphProjection, FleetEdge, FleetGraph, FleetNode, RelationKind};
+#[cfg(feature = "sql")]
+pub use sql::{ProviderUsage, SqlAgentReadModel};
+pub use temporal::{AgentHistory, AsOf, Revision};
+pub use tool_usage::{ToolUsageProjection, ToolUsageStats};
+pub use transcript::{Transcript, TranscriptFormat, TranscriptRenderer, TranscriptTurn};
+pub use view::{AgentView, MessageStatus, MessageView, UsageView};
+
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, AgentStatus};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Interval used by the default [`AgentReadModel::wait_for`] polling loop
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Queryable projection of agents
+#[async_trait]
+pub trait AgentReadModel: Send + Sync {
+    /// Apply a stored event
+    ///
+    /// Lifecycle envelopes at or below the agent's projected version are
+    /// ignored, so redelivery is harmless. Message events are not versioned;
+    /// they update the message they name and count toward usage only once.
+    async fn project(&self, envelope: &EventEnvelope) -> ReadModelResult<()>;
+
+    /// Stream version the projection has reached for an agent (0 if unseen)
+    async fn version(&self, agent_id: AgentId) -> ReadModelResult<u64>;
+
+    /// Fetch the projected view of an agent
+    async fn get(&self, agent_id: AgentId) -> ReadModelResult<Option<AgentView>>;
+
+    /// List agents, optionally only those in `status`, one page at a time
+    async fn list(
+        &self,
+        status: Option<AgentStatus>,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>>;
+
+    /// Fetch an agent as it was at a point in time
+    async fn get_as_of(&self, agent_id: AgentId, as_of: &AsOf) -> ReadModelResult<Option<AgentView>>;
+
+    /// List agents as they were at a point in time, one page at a time
+    async fn list_as_of(
+        &self,
+        status: Option<AgentStatus>,
+        as_of: &AsOf,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>>;
+
+    /// Most recent messages sent to an agent, newest first
+    async fn messages(&self, agent_id: AgentId, limit: usize) -> ReadModelResult<Vec<MessageView>>;
+
+    /// Message and token totals for an agent
+    async fn usage(&self, agent_id: AgentId) -> ReadModelResult<UsageView>;
+
+    /// Wait until the projection has reached `token.version`
+    ///
+    /// The default implementation polls [`AgentReadModel::version`];
+    /// implementations with change notification should override it.
+    async fn wait_for(&self, token: ConsistencyToken, timeout: Duration) -> ReadModelResult<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let current = self.version(token.agent_id).await?;
+            if current >= token.version {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ReadModelError::NotCaughtUp {
+                    agent_id: token.agent_id,
+                    required: token.version,
+                    current,
+                });
+            }
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Fetch an agent, first waiting for `min_version` if given
+    async fn get_consistent(
+        &self,
+        agent_id: AgentId,
+        min_version: Option<u64>,
+        timeout: Duration,
+    ) -> ReadModelResult<Option<AgentView>> {
+        if let Some(version) = min_version {
+            self.wait_for(ConsistencyToken::new(agent_id, version), timeout)
+                .await?;
+        }
+        self.get(agent_id).await
+    }
+}
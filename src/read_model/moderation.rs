@@ -0,0 +1,165 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Moderation projection
+//!
+//! [`ModerationProjection`] lists every message and response whose toxicity
+//! score was flagged or blocked, for moderation dashboards. Messages are
+//! attributed to the tenant of the command that produced them, the same
+//! tenant snapshots are sealed for:
+//!
+//! ```text
+//! MessageSent { toxicity: flagged }, metadata { tenant: acme }
+//! ResponseCompleted { toxicity: blocked }
+//!     │
+//!     v
+//! flagged(agent?, tenant?) ──> [ FlaggedMessage { source, verdict, score, terms }, ... ]
+//! ```
+//!
+//! Clean and unscored events are not listed.
+
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, MessageId, ToxicityScore};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+/// Which side of the exchange was flagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlaggedSource {
+    /// The message sent to the agent
+    Message,
+    /// The agent's response
+    Response,
+}
+
+/// One flagged message or response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlaggedMessage {
+    /// The agent
+    pub agent_id: AgentId,
+
+    /// The message, or the message responded to
+    pub message_id: MessageId,
+
+    /// Which side was flagged
+    pub source: FlaggedSource,
+
+    /// Tenant of the command that produced the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+
+    /// Score and verdict
+    pub toxicity: ToxicityScore,
+
+    /// When the message was sent or the response completed
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct ModerationState {
+    flagged: Vec<FlaggedMessage>,
+    seen: HashSet<(MessageId, FlaggedSource)>,
+}
+
+/// Projection of flagged messages per agent and tenant
+#[derive(Debug, Default)]
+pub struct ModerationProjection {
+    state: RwLock<ModerationState>,
+}
+
+impl ModerationProjection {
+    /// Create an empty projection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a stored event; only flagged messages and responses are kept
+    ///
+    /// Redelivered events are listed once.
+    pub fn project(&self, envelope: &EventEnvelope) {
+        let (source, message_id, toxicity, at) = match &envelope.event {
+            AgentEvent::MessageSent(e) => {
+                (FlaggedSource::Message, e.message_id, &e.toxicity, e.sent_at)
+            }
+            AgentEvent::ResponseCompleted(e) => (
+                FlaggedSource::Response,
+                e.message_id,
+                &e.toxicity,
+                e.completed_at,
+            ),
+            _ => return,
+        };
+        let Some(toxicity) = toxicity.as_ref().filter(|t| t.is_flagged()) else {
+            return;
+        };
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if !state.seen.insert((message_id, source)) {
+            return;
+        }
+        state.flagged.push(FlaggedMessage {
+            agent_id: envelope.aggregate_id,
+            message_id,
+            source,
+            tenant: envelope.tenant().map(str::to_string),
+            toxicity: toxicity.clone(),
+            at,
+        });
+    }
+
+    /// Flagged messages, newest first, narrowed to an agent and/or tenant
+    pub fn flagged(&self, agent_id: Option<AgentId>, tenant: Option<&str>) -> Vec<FlaggedMessage> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let mut flagged: Vec<FlaggedMessage> = state
+            .flagged
+            .iter()
+            .filter(|f| agent_id.is_none_or(|id| f.agent_id == id))
+            .filter(|f| tenant.is_none_or(|t| f.tenant.as_deref() == Some(t)))
+            .cloned()
+            .collect();
+        flagged.sort_by(|a, b| b.at.cmp(&a.at));
+        flagged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::MessageSentEvent;
+    use crate::test_support::envelope;
+    use crate::value_objects::{CommandMetadata, ToxicityThresholds};
+
+    fn sent(agent_id: AgentId, score: f32, tenant: &str) -> EventEnvelope {
+        let toxicity = ToxicityScore::new(score, "lexicon").judged(&ToxicityThresholds::default());
+        let event =
+            MessageSentEvent::new(agent_id, MessageId::new(), "...").with_toxicity(toxicity);
+        let mut envelope = envelope(1, AgentEvent::MessageSent(event));
+        envelope.metadata = Some(CommandMetadata {
+            tenant: Some(tenant.to_string()),
+            ..CommandMetadata::default()
+        });
+        envelope
+    }
+
+    #[test]
+    fn test_lists_flagged_messages_per_agent_and_tenant() {
+        let projection = ModerationProjection::new();
+        let (agent, other) = (AgentId::new(), AgentId::new());
+        let rude = sent(agent, 0.6, "acme");
+        projection.project(&rude);
+        projection.project(&rude);
+        projection.project(&sent(agent, 0.1, "acme"));
+        projection.project(&sent(other, 0.95, "globex"));
+
+        assert_eq!(projection.flagged(None, None).len(), 2);
+        let for_agent = projection.flagged(Some(agent), None);
+        assert_eq!(for_agent.len(), 1);
+        assert_eq!(for_agent[0].tenant.as_deref(), Some("acme"));
+        assert_eq!(for_agent[0].source, FlaggedSource::Message);
+        let blocked = projection.flagged(None, Some("globex"));
+        assert!(blocked[0].toxicity.is_blocked());
+        assert!(projection.flagged(Some(agent), Some("globex")).is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Pagination for list queries
+//!
+//! Results are ordered by `(created_at, agent_id)`, which is unique and never
+//! changes for an agent, so a cursor taken from the last item of one page
+//! stays valid while agents are added or removed between requests.
+
+use super::{AgentView, ReadModelError, ReadModelResult};
+use crate::value_objects::AgentId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Default number of items per page
+pub const DEFAULT_PAGE_LIMIT: u32 = 50;
+
+/// Largest page a caller may request
+pub const MAX_PAGE_LIMIT: u32 = 500;
+
+/// Sort direction over `(created_at, agent_id)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Oldest first
+    #[default]
+    Ascending,
+    /// Newest first
+    Descending,
+}
+
+/// Position in a listing, just past a given agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageCursor {
+    /// Deployment time of the last agent seen
+    pub created_at: DateTime<Utc>,
+    /// ID of the last agent seen
+    pub agent_id: AgentId,
+}
+
+impl PageCursor {
+    /// Cursor positioned after `view`
+    pub fn after(view: &AgentView) -> Self {
+        Self {
+            created_at: view.created_at,
+            agent_id: view.agent_id,
+        }
+    }
+
+    /// Encode as an opaque string for clients
+    pub fn encode(&self) -> String {
+        format!(
+            "{}.{}",
+            self.created_at.timestamp_micros(),
+            self.agent_id.as_uuid().simple()
+        )
+    }
+
+    /// Decode a string produced by [`PageCursor::encode`]
+    pub fn decode(cursor: &str) -> ReadModelResult<Self> {
+        let invalid = || ReadModelError::InvalidQuery(format!("Invalid page cursor: {}", cursor));
+
+        let (micros, id) = cursor.split_once('.').ok_or_else(invalid)?;
+        let created_at = micros
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let agent_id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+        Ok(Self {
+            created_at,
+            agent_id: AgentId::from_uuid(agent_id),
+        })
+    }
+
+    /// Ordering key, at the cursor's microsecond precision
+    fn key(&self) -> (i64, Uuid) {
+        (self.created_at.timestamp_micros(), *self.agent_id.as_uuid())
+    }
+}
+
+/// Page request attached to list queries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageRequest {
+    /// Cursor from the previous page's `next_cursor` (None for the first page)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+
+    /// Maximum items to return (capped at [`MAX_PAGE_LIMIT`])
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+
+    /// Sort direction
+    #[serde(default)]
+    pub sort: SortOrder,
+}
+
+fn default_limit() -> u32 {
+    DEFAULT_PAGE_LIMIT
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            cursor: None,
+            limit: DEFAULT_PAGE_LIMIT,
+            sort: SortOrder::default(),
+        }
+    }
+}
+
+impl PageRequest {
+    /// First page with the given limit
+    pub fn first(limit: u32) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    /// Builder: continue after `cursor`
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Builder: set sort direction
+    pub fn with_sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Validate the request
+    pub fn validate(&self) -> ReadModelResult<()> {
+        if self.limit == 0 {
+            return Err(ReadModelError::InvalidQuery(
+                "Page limit must be at least 1".to_string(),
+            ));
+        }
+        if let Some(cursor) = &self.cursor {
+            PageCursor::decode(cursor)?;
+        }
+        Ok(())
+    }
+
+    /// Limit after applying [`MAX_PAGE_LIMIT`]
+    pub fn effective_limit(&self) -> usize {
+        self.limit.min(MAX_PAGE_LIMIT) as usize
+    }
+}
+
+/// One page of results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items on this page
+    pub items: Vec<T>,
+
+    /// Cursor for the next page (None when this is the last page)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Check if more pages follow
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+
+    /// Transform the items, keeping the cursor
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+/// Sort, seek and cut a set of views into the requested page
+///
+/// Read models that cannot push ordering down to storage can use this over
+/// their filtered views.
+pub fn paginate(
+    mut views: Vec<AgentView>,
+    request: &PageRequest,
+) -> ReadModelResult<Page<AgentView>> {
+    request.validate()?;
+    let after = request
+        .cursor
+        .as_deref()
+        .map(PageCursor::decode)
+        .transpose()?
+        .map(|c| c.key());
+
+    let key = |v: &AgentView| PageCursor::after(v).key();
+    views.sort_by_key(key);
+    if request.sort == SortOrder::Descending {
+        views.reverse();
+    }
+
+    let mut remaining = views.into_iter().filter(|v| match (after, request.sort) {
+        (None, _) => true,
+        (Some(after), SortOrder::Ascending) => key(v) > after,
+        (Some(after), SortOrder::Descending) => key(v) < after,
+    });
+
+    let limit = request.effective_limit();
+    let items: Vec<AgentView> = remaining.by_ref().take(limit).collect();
+    let next_cursor = match (remaining.next(), items.last()) {
+        (Some(_), Some(last)) => Some(PageCursor::after(last).encode()),
+        _ => None,
+    };
+
+    Ok(Page { items, next_cursor })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{AgentStatus, PersonId};
+    use chrono::Duration;
+
+    fn views(n: i64) -> Vec<AgentView> {
+        let base = Utc::now();
+        (0..n)
+            .map(|i| AgentView {
+                agent_id: AgentId::new(),
+                person_id: PersonId::new(),
+                name: format!("agent-{}", i),
+                description: None,
+                status: AgentStatus::Active,
+                provider: None,
+                model_name: None,
+                created_at: base + Duration::seconds(i),
+                updated_at: base,
+                version: 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pages_cover_every_item_once() {
+        let all = views(7);
+        let mut seen = Vec::new();
+        let mut request = PageRequest::first(3);
+
+        loop {
+            let page = paginate(all.clone(), &request).unwrap();
+            seen.extend(page.items.iter().map(|v| v.name.clone()));
+            match page.next_cursor {
+                Some(cursor) => request = request.with_cursor(cursor),
+                None => break,
+            }
+        }
+
+        let expected: Vec<String> = (0..7).map(|i| format!("agent-{}", i)).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_descending_order_and_exact_last_page() {
+        let page = paginate(
+            views(2),
+            &PageRequest::first(2).with_sort(SortOrder::Descending),
+        )
+        .unwrap();
+
+        assert_eq!(page.items[0].name, "agent-1");
+        assert!(!page.has_more());
+    }
+
+    #[test]
+    fn test_cursor_roundtrip_and_rejects_garbage() {
+        let view = &views(1)[0];
+        let cursor = PageCursor::after(view);
+
+        let decoded = PageCursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded.agent_id, view.agent_id);
+        assert_eq!(
+            decoded.created_at.timestamp_micros(),
+            view.created_at.timestamp_micros()
+        );
+
+        assert!(matches!(
+            PageCursor::decode("not-a-cursor"),
+            Err(ReadModelError::InvalidQuery(_))
+        ));
+    }
+}
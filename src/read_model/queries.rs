@@ -0,0 +1,217 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Agent queries
+
+use super::{AsOf, FieldSelection, PageRequest};
+use crate::capabilities::RuntimeCapabilities;
+use crate::value_objects::{AgentId, AgentStatus, ConversationId, MessageId, ParticipantId};
+use serde::{Deserialize, Serialize};
+
+/// Queries served by the agent read model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AgentQuery {
+    /// Fetch one agent
+    GetAgent {
+        /// The agent to fetch
+        agent_id: AgentId,
+
+        /// Wait until the projection reaches this stream version
+        /// (the `version` from a command reply's consistency token)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_version: Option<u64>,
+
+        /// Upper bound on the wait, in milliseconds (server caps apply)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+
+        /// Read past state instead of the current projection
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        as_of: Option<AsOf>,
+
+        /// Fields to return
+        #[serde(default, skip_serializing_if = "FieldSelection::is_all")]
+        fields: FieldSelection,
+    },
+
+    /// Page through agents, optionally filtered by status
+    ListAgents {
+        /// Only agents in this status
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<AgentStatus>,
+
+        /// Page to return
+        #[serde(default)]
+        page: PageRequest,
+
+        /// Read past state instead of the current projection
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        as_of: Option<AsOf>,
+
+        /// Fields to return for each agent
+        #[serde(default, skip_serializing_if = "FieldSelection::is_all")]
+        fields: FieldSelection,
+    },
+
+    /// Fetch the fleet relationship graph
+    GetFleetGraph,
+
+    /// Most recent messages sent to an agent, newest first
+    GetMessages {
+        /// The agent whose conversation to fetch
+        agent_id: AgentId,
+
+        /// Maximum messages to return
+        #[serde(default = "default_message_limit")]
+        limit: usize,
+    },
+
+    /// Message and token totals for an agent
+    GetUsage {
+        /// The agent whose usage to fetch
+        agent_id: AgentId,
+    },
+
+    /// Usage and spend under each value of a cost tag
+    GetCostsByTag {
+        /// The tag key to break costs down by (e.g. "project")
+        key: String,
+    },
+
+    /// Messages and responses flagged for moderation, newest first
+    GetFlaggedMessages {
+        /// Only this agent's messages
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent_id: Option<AgentId>,
+
+        /// Only messages tagged with this tenant
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tenant: Option<String>,
+    },
+
+    /// Latency, failure and argument-size statistics of each tool an agent called
+    GetToolUsage {
+        /// The agent whose tool usage to fetch
+        agent_id: AgentId,
+    },
+
+    /// Messages of a conversation archived for inactivity
+    GetArchivedConversation {
+        /// Archive key from the `ConversationArchived` event
+        conversation_id: ConversationId,
+    },
+
+    /// Decision tree recorded for a message by its reasoning traces
+    GetDecisionTree {
+        /// The message whose decisions to fetch
+        message_id: MessageId,
+    },
+
+    /// Unread messages per conversation for one participant
+    GetUnreadCounts {
+        /// The participant whose unread messages to count
+        participant: ParticipantId,
+    },
+
+    /// Delivery and read receipts for one conversation message
+    GetReceipts {
+        /// The conversation
+        conversation_id: ConversationId,
+
+        /// The message
+        message_id: MessageId,
+    },
+
+    /// Self-description generated from the agent's current state
+    DescribeAgent {
+        /// The agent to describe
+        agent_id: AgentId,
+    },
+
+    /// Active agents able to take work with these requirements
+    FindCapableAgents {
+        /// Capabilities every returned agent offers
+        capabilities: RuntimeCapabilities,
+
+        /// Smallest context window, in tokens
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_context_length: Option<u32>,
+
+        /// Only agents that stream responses
+        #[serde(default)]
+        requires_streaming: bool,
+    },
+}
+
+fn default_message_limit() -> usize {
+    20
+}
+
+impl AgentQuery {
+    /// Recognize `{domain}.queries.agent.{id}.describe`
+    ///
+    /// Describe requests are addressed by subject and need no payload.
+    pub fn from_describe_subject(subject: &str) -> Option<Self> {
+        let mut tokens = subject.rsplit('.');
+        if tokens.next() != Some("describe") {
+            return None;
+        }
+        let agent_id = tokens.next()?.parse::<uuid::Uuid>().ok()?;
+        (tokens.next() == Some("agent") && tokens.next() == Some("queries")).then(|| {
+            AgentQuery::DescribeAgent {
+                agent_id: AgentId::from_uuid(agent_id),
+            }
+        })
+    }
+
+    /// Fetch an agent without a consistency requirement
+    pub fn get_agent(agent_id: AgentId) -> Self {
+        AgentQuery::GetAgent {
+            agent_id,
+            min_version: None,
+            timeout_ms: None,
+            as_of: None,
+            fields: FieldSelection::all(),
+        }
+    }
+
+    /// List agents, starting from the first page
+    pub fn list_agents(status: Option<AgentStatus>, page: PageRequest) -> Self {
+        AgentQuery::ListAgents {
+            status,
+            page,
+            as_of: None,
+            fields: FieldSelection::all(),
+        }
+    }
+
+    /// Builder: require the projection to reach `version` first
+    ///
+    /// Only `GetAgent` carries a consistency requirement; other queries are
+    /// returned unchanged.
+    pub fn with_min_version(mut self, version: u64) -> Self {
+        if let AgentQuery::GetAgent { min_version, .. } = &mut self {
+            *min_version = Some(version);
+        }
+        self
+    }
+
+    /// Builder: read state as of a point in time
+    pub fn with_as_of(mut self, point: AsOf) -> Self {
+        if let AgentQuery::GetAgent { as_of, .. } | AgentQuery::ListAgents { as_of, .. } = &mut self
+        {
+            *as_of = Some(point);
+        }
+        self
+    }
+
+    /// Builder: narrow the returned fields
+    pub fn with_fields(mut self, selection: FieldSelection) -> Self {
+        if let AgentQuery::GetAgent { fields, .. } | AgentQuery::ListAgents { fields, .. } =
+            &mut self
+        {
+            *fields = selection;
+        }
+        self
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Reasoning trace projection
+//!
+//! [`ReasoningTraceProjection`] keeps the `ReasoningTraceRecorded` steps of
+//! each message and rebuilds them into a [`DecisionNode`] tree on request.
+//! A message traced more than once (a retry, a second pass) gets each trace
+//! as its own root, in the order recorded:
+//!
+//! ```text
+//! ReasoningTraceRecorded { message_id, steps: [0, 1 (parent 0), 2 (parent 0)] }
+//!                                    │
+//!                                    v
+//! decision_tree(message_id) ──> [ plan ──┬── retrieval ]
+//!                                        └── judgment
+//! ```
+
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{MessageId, TraceStep, TraceStepKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// One decision and those made under it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionNode {
+    /// What kind of decision this is
+    pub kind: TraceStepKind,
+
+    /// The decision, in one line
+    pub summary: String,
+
+    /// Text the decision was based on, unless redacted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+
+    /// Decisions that refine this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DecisionNode>,
+}
+
+/// Projection of reasoning traces by message
+#[derive(Debug, Default)]
+pub struct ReasoningTraceProjection {
+    traces: RwLock<HashMap<MessageId, Vec<Vec<TraceStep>>>>,
+}
+
+impl ReasoningTraceProjection {
+    /// Create an empty projection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a stored event; only reasoning traces are kept
+    pub fn project(&self, envelope: &EventEnvelope) {
+        let AgentEvent::ReasoningTraceRecorded(event) = &envelope.event else {
+            return;
+        };
+        let mut traces = self.traces.write().unwrap_or_else(|e| e.into_inner());
+        let recorded = traces.entry(event.message_id).or_default();
+        let steps = event.trace.steps();
+        if !recorded.iter().any(|t| t.as_slice() == steps) {
+            recorded.push(steps.to_vec());
+        }
+    }
+
+    /// The full decision tree for `message_id`, one root per top-level step
+    pub fn decision_tree(&self, message_id: MessageId) -> Option<Vec<DecisionNode>> {
+        let traces = self.traces.read().unwrap_or_else(|e| e.into_inner());
+        let recorded = traces.get(&message_id)?;
+        Some(recorded.iter().flat_map(|steps| build(steps, None)).collect())
+    }
+
+    /// Forget the traces of `message_id`
+    pub fn remove(&self, message_id: MessageId) {
+        let mut traces = self.traces.write().unwrap_or_else(|e| e.into_inner());
+        traces.remove(&message_id);
+    }
+}
+
+/// Nodes for the steps under `parent`, recursively
+///
+/// Parents always precede their children. A step whose parent is missing,
+/// or does not come before it, is treated as a root, so a partial trace
+/// still renders and a malformed one cannot loop.
+fn build(steps: &[TraceStep], parent: Option<u32>) -> Vec<DecisionNode> {
+    steps
+        .iter()
+        .filter(|step| {
+            let effective = step
+                .parent
+                .filter(|&p| p < step.id && steps.iter().any(|s| s.id == p));
+            effective == parent
+        })
+        .map(|step| DecisionNode {
+            kind: step.kind,
+            summary: step.summary.clone(),
+            detail: step.detail.clone(),
+            children: build(steps, Some(step.id)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ReasoningTraceRecordedEvent;
+    use crate::test_support::envelope;
+    use crate::value_objects::{AgentId, ReasoningTrace};
+
+    #[test]
+    fn test_decision_tree_follows_parent_links() {
+        let agent_id = AgentId::new();
+        let message_id = MessageId::new();
+        let mut trace = ReasoningTrace::new();
+        let plan = trace.step(TraceStepKind::Plan, "Answer chat with mock/mock-model");
+        let judgment = trace.child(plan, TraceStepKind::Judgment, "heuristic picked 2", None);
+        trace.child(judgment, TraceStepKind::Candidate, "Candidate 1", None);
+        trace.child(plan, TraceStepKind::Retrieval, "Kept doc-1 (rank 1)", None);
+
+        let event = AgentEvent::ReasoningTraceRecorded(ReasoningTraceRecordedEvent::new(
+            agent_id, message_id, &trace, false,
+        ));
+        let envelope = envelope(1, event);
+        let projection = ReasoningTraceProjection::new();
+        projection.project(&envelope);
+        projection.project(&envelope);
+
+        let tree = projection.decision_tree(message_id).unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[0].children[0].summary, "Candidate 1");
+        assert!(projection.decision_tree(MessageId::new()).is_none());
+    }
+}
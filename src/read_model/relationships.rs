@@ -0,0 +1,334 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Fleet relationship graph
+//!
+//! Maintains a graph of the fleet itself, with agents as nodes and three
+//! kinds of relationship as edges:
+//!
+//! ```text
+//! ┌──────┐  delegates_to (n)   ┌──────┐
+//! │ sage │ ──────────────────> │ ddd  │
+//! └──────┘                     └──────┘
+//!    │ shares_configuration      │ shares_tool (search, fetch)
+//!    └───────────┐    ┌──────────┘
+//!                v    v
+//!               ┌──────┐
+//!               │ eda  │
+//!               └──────┘
+//! ```
+//!
+//! - `delegates_to` - counted from agent-to-agent subjects
+//!   (`{domain}.to.{to}.from.{from}.{type}`)
+//! - `shares_configuration` - agents assigned the same model configuration
+//! - `shares_tool` - agents registered with the same tools
+//!
+//! [`FleetGraph`] converts to the analysis providers' `GraphData`.
+
+use super::{ReadModelError, ReadModelResult};
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, ModelConfigurationId, PersonId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+
+/// Kind of relationship between two agents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    /// Source sends work to target (directed)
+    DelegatesTo,
+    /// Both agents use the same model configuration (undirected)
+    SharesConfiguration,
+    /// Both agents are registered with at least one common tool (undirected)
+    SharesTool,
+}
+
+impl RelationKind {
+    /// Edge type name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationKind::DelegatesTo => "delegates_to",
+            RelationKind::SharesConfiguration => "shares_configuration",
+            RelationKind::SharesTool => "shares_tool",
+        }
+    }
+}
+
+/// An agent in the fleet graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetNode {
+    /// Agent ID
+    pub agent_id: AgentId,
+    /// Agent name
+    pub name: String,
+    /// Owning person
+    pub person_id: PersonId,
+    /// Whether the agent has been decommissioned
+    pub decommissioned: bool,
+}
+
+/// A relationship between two agents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetEdge {
+    /// Source agent (for undirected kinds, the lower ID)
+    pub source: AgentId,
+    /// Target agent
+    pub target: AgentId,
+    /// Relationship kind
+    pub kind: RelationKind,
+    /// Delegations observed (`delegates_to` only)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub count: u64,
+    /// What is shared (configuration ID or tool names)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared: Vec<String>,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+/// Point-in-time copy of the fleet graph
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FleetGraph {
+    /// Agents
+    pub nodes: Vec<FleetNode>,
+    /// Relationships
+    pub edges: Vec<FleetEdge>,
+}
+
+impl FleetGraph {
+    /// Edges of one kind
+    pub fn edges_of(&self, kind: RelationKind) -> impl Iterator<Item = &FleetEdge> {
+        self.edges.iter().filter(move |e| e.kind == kind)
+    }
+}
+
+#[derive(Debug, Default)]
+struct GraphState {
+    nodes: HashMap<AgentId, FleetNode>,
+    configurations: HashMap<AgentId, ModelConfigurationId>,
+    tools: HashMap<AgentId, BTreeSet<String>>,
+    delegations: HashMap<(AgentId, AgentId), u64>,
+}
+
+impl GraphState {
+    fn agent_named(&self, name: &str) -> Option<AgentId> {
+        self.nodes
+            .values()
+            .filter(|n| n.name == name && !n.decommissioned)
+            .map(|n| n.agent_id)
+            .min_by_key(|id| *id.as_uuid())
+    }
+}
+
+/// Projection maintaining the fleet relationship graph
+#[derive(Debug, Default)]
+pub struct AgentGraphProjection {
+    state: RwLock<GraphState>,
+}
+
+impl AgentGraphProjection {
+    /// Create an empty projection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a stored event
+    pub fn project(&self, envelope: &EventEnvelope) {
+        let mut state = self.write();
+        match &envelope.event {
+            AgentEvent::AgentDeployed(e) => {
+                state.nodes.entry(e.agent_id).or_insert_with(|| FleetNode {
+                    agent_id: e.agent_id,
+                    name: e.name.clone(),
+                    person_id: e.person_id,
+                    decommissioned: false,
+                });
+            }
+            AgentEvent::ModelConfigurationAssigned(e) => {
+                state.configurations.insert(e.agent_id, e.configuration_id);
+            }
+            AgentEvent::AgentDecommissioned(e) => {
+                if let Some(node) = state.nodes.get_mut(&e.agent_id) {
+                    node.decommissioned = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Count a delegation between two agents, by name
+    ///
+    /// Unknown names are an error so callers can log misrouted traffic.
+    pub fn record_delegation(&self, from: &str, to: &str) -> ReadModelResult<()> {
+        let mut state = self.write();
+        let resolve = |name: &str| {
+            state
+                .agent_named(name)
+                .ok_or_else(|| ReadModelError::Projection(format!("Unknown agent name: {}", name)))
+        };
+        let edge = (resolve(from)?, resolve(to)?);
+        *state.delegations.entry(edge).or_insert(0) += 1;
+        Ok(())
+    }
+
+    /// Count a delegation if `subject` is an agent-to-agent subject
+    ///
+    /// Returns false for subjects of any other shape.
+    pub fn record_delegation_subject(&self, subject: &str) -> ReadModelResult<bool> {
+        let segments: Vec<&str> = subject.split('.').collect();
+        match segments.as_slice() {
+            [_, "to", to, "from", from, _, ..] => self.record_delegation(from, to).map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Replace the tools an agent is registered with
+    pub fn set_tools(&self, agent_id: AgentId, tools: impl IntoIterator<Item = impl Into<String>>) {
+        let tools: BTreeSet<String> = tools.into_iter().map(Into::into).collect();
+        let mut state = self.write();
+        if tools.is_empty() {
+            state.tools.remove(&agent_id);
+        } else {
+            state.tools.insert(agent_id, tools);
+        }
+    }
+
+    /// Tools an agent is registered with, sorted
+    pub fn tools(&self, agent_id: AgentId) -> Vec<String> {
+        self.read()
+            .tools
+            .get(&agent_id)
+            .map(|tools| tools.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Build the current graph
+    pub fn graph(&self) -> FleetGraph {
+        let state = self.read();
+
+        let mut nodes: Vec<FleetNode> = state.nodes.values().cloned().collect();
+        nodes.sort_by_key(|n| *n.agent_id.as_uuid());
+
+        let mut edges: Vec<FleetEdge> = state
+            .delegations
+            .iter()
+            .map(|(&(source, target), &count)| FleetEdge {
+                source,
+                target,
+                kind: RelationKind::DelegatesTo,
+                count,
+                shared: Vec::new(),
+            })
+            .collect();
+        edges.sort_by_key(|e| (*e.source.as_uuid(), *e.target.as_uuid()));
+
+        // Undirected edges: one per unordered pair
+        let ids: Vec<AgentId> = nodes.iter().map(|n| n.agent_id).collect();
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                if let (Some(ca), Some(cb)) =
+                    (state.configurations.get(a), state.configurations.get(b))
+                {
+                    if ca == cb {
+                        let shared = vec![ca.to_string()];
+                        edges.push(undirected(*a, *b, RelationKind::SharesConfiguration, shared));
+                    }
+                }
+                if let (Some(ta), Some(tb)) = (state.tools.get(a), state.tools.get(b)) {
+                    let shared: Vec<String> = ta.intersection(tb).cloned().collect();
+                    if !shared.is_empty() {
+                        edges.push(undirected(*a, *b, RelationKind::SharesTool, shared));
+                    }
+                }
+            }
+        }
+
+        FleetGraph { nodes, edges }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, GraphState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, GraphState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn undirected(a: AgentId, b: AgentId, kind: RelationKind, shared: Vec<String>) -> FleetEdge {
+    let (source, target) = if a.as_uuid() <= b.as_uuid() { (a, b) } else { (b, a) };
+    FleetEdge {
+        source,
+        target,
+        kind,
+        count: 0,
+        shared,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, ModelConfigurationAssignedEvent};
+    use crate::test_support::envelope;
+
+    fn deploy(graph: &AgentGraphProjection, name: &str) -> AgentId {
+        let agent_id = AgentId::new();
+        graph.project(&envelope(1, AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+            agent_id,
+            PersonId::new(),
+            name,
+            None,
+        ))));
+        agent_id
+    }
+
+    #[test]
+    fn test_delegation_from_subjects() {
+        let graph = AgentGraphProjection::new();
+        let sage = deploy(&graph, "sage");
+        let ddd = deploy(&graph, "ddd");
+
+        assert!(graph.record_delegation_subject("agent.to.ddd.from.sage.request").unwrap());
+        assert!(graph.record_delegation_subject("agent.to.ddd.from.sage.request").unwrap());
+        assert!(!graph.record_delegation_subject("agent.to.ddd.chat.general").unwrap());
+
+        let fleet = graph.graph();
+        let edge = fleet.edges_of(RelationKind::DelegatesTo).next().unwrap();
+        assert_eq!((edge.source, edge.target, edge.count), (sage, ddd, 2));
+    }
+
+    #[test]
+    fn test_shared_configuration_and_tools() {
+        let graph = AgentGraphProjection::new();
+        let a = deploy(&graph, "a");
+        let b = deploy(&graph, "b");
+        let c = deploy(&graph, "c");
+
+        let config = ModelConfigurationId::new();
+        for agent in [a, b] {
+            graph.project(&envelope(1, AgentEvent::ModelConfigurationAssigned(
+                ModelConfigurationAssignedEvent::new(agent, config),
+            )));
+        }
+        graph.set_tools(b, ["search", "fetch"]);
+        graph.set_tools(c, ["fetch"]);
+
+        let fleet = graph.graph();
+        assert_eq!(fleet.edges_of(RelationKind::SharesConfiguration).count(), 1);
+        let tool_edge = fleet.edges_of(RelationKind::SharesTool).next().unwrap();
+        assert_eq!(tool_edge.shared, vec!["fetch".to_string()]);
+    }
+
+    #[test]
+    fn test_unknown_delegation_target_is_an_error() {
+        let graph = AgentGraphProjection::new();
+        deploy(&graph, "sage");
+
+        assert!(graph.record_delegation("sage", "ghost").is_err());
+        assert!(graph.graph().edges.is_empty());
+    }
+}
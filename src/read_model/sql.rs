@@ -0,0 +1,680 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! PostgreSQL agent read model
+//!
+//! Projects the event stream into relational tables that fleet dashboards
+//! and ad-hoc SQL can query directly. The schema lives in
+//! `migrations/read_model` and is applied with [`SqlAgentReadModel::migrate`]:
+//!
+//! ```text
+//! agents                 one row per agent: columns for filtering + projected state
+//!  ├── agent_revisions   bitemporal history           -> get_as_of / list_as_of
+//!  └── agent_capabilities runtime capabilities of the configured provider
+//! agent_messages         one row per message          -> messages
+//! agent_usage            running totals per agent     -> usage
+//! agent_conversations    archived conversations
+//! agent_tool_selections  tool-selection steps from reasoning traces
+//! ```
+//!
+//! Each envelope is projected in one transaction. Lifecycle events only
+//! advance an agent whose stored version is older; message events only move
+//! a response that is still open, so redelivery never double-counts usage.
+
+use super::in_memory::{is_message_event, Projected};
+use super::{
+    paginate, AgentReadModel, AgentView, AsOf, MessageStatus, MessageView, Page, PageCursor,
+    PageRequest, ReadModelError, ReadModelResult, SortOrder, UsageView,
+};
+use crate::aggregate::Agent;
+use crate::capabilities::ProviderCapabilities;
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{
+    AgentId, AgentStatus, FinishReason, MessageId, ProviderType, TokenUsage, TraceStepKind,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::Migrator;
+use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
+use sqlx::types::Json;
+use sqlx::{Postgres, QueryBuilder, Row, Transaction};
+
+/// Migrations for the read model schema
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/read_model");
+
+/// Responses still waiting on chunks or an outcome
+const OPEN_STATUSES: &str = "status IN ('pending', 'streaming')";
+
+/// Token totals of the agents on one provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderUsage {
+    /// Provider name, or `None` for agents without a model
+    pub provider: Option<String>,
+
+    /// Agents configured with the provider
+    pub agents: u64,
+
+    /// Prompt tokens across those agents
+    pub prompt_tokens: u64,
+
+    /// Completion tokens across those agents
+    pub completion_tokens: u64,
+}
+
+/// Which agents a listing covers
+enum Listing<'a> {
+    Status(Option<AgentStatus>),
+    Capability(&'a str),
+}
+
+/// Agent read model kept in PostgreSQL
+#[derive(Clone)]
+pub struct SqlAgentReadModel {
+    pool: PgPool,
+}
+
+impl SqlAgentReadModel {
+    /// Use an existing connection pool
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Connect to `database_url`
+    pub async fn connect(database_url: &str) -> ReadModelResult<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await
+            .map_err(storage)?;
+        Ok(Self::new(pool))
+    }
+
+    /// Apply pending schema migrations
+    pub async fn migrate(&self) -> ReadModelResult<()> {
+        MIGRATOR.run(&self.pool).await.map_err(storage)
+    }
+
+    /// Number of agents in each status
+    pub async fn status_counts(&self) -> ReadModelResult<Vec<(AgentStatus, u64)>> {
+        let rows = sqlx::query("SELECT status, COUNT(*) AS agents FROM agents GROUP BY status")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(storage)?;
+        let mut counts = Vec::with_capacity(rows.len());
+        for row in rows {
+            let code: String = row.try_get("status").map_err(storage)?;
+            if let Some(status) = AgentStatus::from_code(&code) {
+                counts.push((
+                    status,
+                    row.try_get::<i64, _>("agents").map_err(storage)? as u64,
+                ));
+            }
+        }
+        Ok(counts)
+    }
+
+    /// The `limit` agents that used the most tokens
+    pub async fn top_usage(&self, limit: u32) -> ReadModelResult<Vec<UsageView>> {
+        sqlx::query(
+            "SELECT * FROM agent_usage \
+             ORDER BY prompt_tokens + completion_tokens DESC LIMIT $1",
+        )
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage)?
+        .iter()
+        .map(usage_from_row)
+        .collect()
+    }
+
+    /// Agent and token totals per provider, heaviest first
+    pub async fn provider_usage(&self) -> ReadModelResult<Vec<ProviderUsage>> {
+        let rows = sqlx::query(
+            "SELECT a.provider, COUNT(*) AS agents, \
+                    COALESCE(SUM(u.prompt_tokens), 0)::BIGINT AS prompt_tokens, \
+                    COALESCE(SUM(u.completion_tokens), 0)::BIGINT AS completion_tokens \
+             FROM agents a LEFT JOIN agent_usage u USING (agent_id) \
+             GROUP BY a.provider \
+             ORDER BY prompt_tokens + completion_tokens DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage)?;
+        rows.iter()
+            .map(|row| {
+                Ok(ProviderUsage {
+                    provider: row.try_get("provider")?,
+                    agents: row.try_get::<i64, _>("agents")? as u64,
+                    prompt_tokens: row.try_get::<i64, _>("prompt_tokens")? as u64,
+                    completion_tokens: row.try_get::<i64, _>("completion_tokens")? as u64,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(storage)
+    }
+
+    /// Agents whose provider offers `capability` (e.g. "VISION"), one page at a time
+    pub async fn agents_with_capability(
+        &self,
+        capability: &str,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        self.page(Listing::Capability(capability), page).await
+    }
+
+    /// One page of agents, ordered and cut by the database
+    async fn page(
+        &self,
+        listing: Listing<'_>,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        page.validate()?;
+        let mut query =
+            QueryBuilder::<Postgres>::new("SELECT a.state, a.version, a.updated_at FROM agents a");
+        match listing {
+            Listing::Capability(capability) => {
+                query
+                    .push(" JOIN agent_capabilities c USING (agent_id) WHERE c.capability = ")
+                    .push_bind(capability.to_string());
+            }
+            Listing::Status(Some(status)) => {
+                query.push(" WHERE a.status = ").push_bind(status.code());
+            }
+            Listing::Status(None) => {
+                query.push(" WHERE TRUE");
+            }
+        }
+
+        let (compare, direction) = match page.sort {
+            SortOrder::Ascending => (">", "ASC"),
+            SortOrder::Descending => ("<", "DESC"),
+        };
+        if let Some(cursor) = page.cursor.as_deref().map(PageCursor::decode).transpose()? {
+            query
+                .push(format!(" AND (a.created_at, a.agent_id) {} (", compare))
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(*cursor.agent_id.as_uuid())
+                .push(")");
+        }
+        let limit = page.effective_limit();
+        query
+            .push(format!(
+                " ORDER BY a.created_at {0}, a.agent_id {0} LIMIT ",
+                direction
+            ))
+            .push_bind(limit as i64 + 1);
+
+        let rows = query.build().fetch_all(&self.pool).await.map_err(storage)?;
+        let mut items = rows
+            .iter()
+            .map(view_from_row)
+            .collect::<ReadModelResult<Vec<_>>>()?;
+        let next_cursor = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(|last| PageCursor::after(last).encode())
+        } else {
+            None
+        };
+        Ok(Page { items, next_cursor })
+    }
+
+    async fn project_agent(
+        tx: &mut Transaction<'_, Postgres>,
+        envelope: &EventEnvelope,
+    ) -> ReadModelResult<()> {
+        let row = sqlx::query(
+            "SELECT state, version, updated_at FROM agents WHERE agent_id = $1 FOR UPDATE",
+        )
+        .bind(*envelope.aggregate_id.as_uuid())
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(storage)?;
+        // Revisions live in their own table; only the new one is needed here
+        let existing = row.as_ref().map(projected_from_row).transpose()?;
+        let Some(projected) = Projected::advance(existing.as_ref(), envelope)? else {
+            return Ok(());
+        };
+
+        let view = projected.view();
+        sqlx::query(
+            "INSERT INTO agents (agent_id, person_id, name, status, provider, model_name, \
+                                 created_at, updated_at, version, state) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+             ON CONFLICT (agent_id) DO UPDATE SET \
+                 person_id = EXCLUDED.person_id, name = EXCLUDED.name, \
+                 status = EXCLUDED.status, provider = EXCLUDED.provider, \
+                 model_name = EXCLUDED.model_name, updated_at = EXCLUDED.updated_at, \
+                 version = EXCLUDED.version, state = EXCLUDED.state \
+             WHERE agents.version < EXCLUDED.version",
+        )
+        .bind(*view.agent_id.as_uuid())
+        .bind(*view.person_id.as_uuid())
+        .bind(&view.name)
+        .bind(view.status.code())
+        .bind(view.provider.map(|p| p.to_string()))
+        .bind(&view.model_name)
+        .bind(view.created_at)
+        .bind(view.updated_at)
+        .bind(view.version as i64)
+        .bind(Json(&projected.agent))
+        .execute(&mut **tx)
+        .await
+        .map_err(storage)?;
+
+        if let Some(revision) = projected.history.revisions().last() {
+            sqlx::query(
+                "INSERT INTO agent_revisions \
+                     (agent_id, version, status, valid_from, recorded_at, view) \
+                 VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING",
+            )
+            .bind(*view.agent_id.as_uuid())
+            .bind(view.version as i64)
+            .bind(view.status.code())
+            .bind(revision.valid_from)
+            .bind(revision.recorded_at)
+            .bind(Json(&revision.view))
+            .execute(&mut **tx)
+            .await
+            .map_err(storage)?;
+        }
+
+        let previous_provider = existing.and_then(|p| p.view().provider);
+        if view.provider != previous_provider {
+            sqlx::query("DELETE FROM agent_capabilities WHERE agent_id = $1")
+                .bind(*view.agent_id.as_uuid())
+                .execute(&mut **tx)
+                .await
+                .map_err(storage)?;
+            for capability in view.provider.map(capability_names).unwrap_or_default() {
+                sqlx::query(
+                    "INSERT INTO agent_capabilities (agent_id, capability) VALUES ($1, $2)",
+                )
+                .bind(*view.agent_id.as_uuid())
+                .bind(capability)
+                .execute(&mut **tx)
+                .await
+                .map_err(storage)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn project_activity(
+        tx: &mut Transaction<'_, Postgres>,
+        envelope: &EventEnvelope,
+    ) -> ReadModelResult<()> {
+        let mut delta = UsageView::empty(envelope.aggregate_id);
+        match &envelope.event {
+            AgentEvent::MessageSent(e) => {
+                let inserted = sqlx::query(
+                    "INSERT INTO agent_messages (message_id, agent_id, content, sent_at, status) \
+                     VALUES ($1, $2, $3, $4, 'pending') ON CONFLICT (message_id) DO NOTHING",
+                )
+                .bind(*e.message_id.as_uuid())
+                .bind(*e.agent_id.as_uuid())
+                .bind(&e.content)
+                .bind(e.sent_at)
+                .execute(&mut **tx)
+                .await
+                .map_err(storage)?;
+                delta.messages_sent = inserted.rows_affected();
+            }
+            AgentEvent::ResponseChunkReceived(e) => {
+                sqlx::query(&format!(
+                    "UPDATE agent_messages SET status = 'streaming', \
+                     chunks = GREATEST(chunks, $2) WHERE message_id = $1 AND {}",
+                    OPEN_STATUSES
+                ))
+                .bind(*e.message_id.as_uuid())
+                .bind((e.chunk.chunk_index + 1) as i32)
+                .execute(&mut **tx)
+                .await
+                .map_err(storage)?;
+            }
+            AgentEvent::ResponseCompleted(e) => {
+                let closed = close_message(
+                    tx,
+                    e.message_id,
+                    "completed",
+                    Some(e.total_chunks),
+                    e.finish_reason,
+                    Some(Json(e.token_usage)),
+                )
+                .await?;
+                if closed {
+                    delta.responses_completed = 1;
+                    delta.prompt_tokens = u64::from(e.token_usage.prompt_tokens);
+                    delta.completion_tokens = u64::from(e.token_usage.completion_tokens);
+                }
+            }
+            AgentEvent::ResponseFailed(e) => {
+                let closed =
+                    close_message(tx, e.message_id, "failed", None, FinishReason::Error, None)
+                        .await?;
+                delta.responses_failed = u64::from(closed);
+            }
+            AgentEvent::ResponseCancelled(e) => {
+                let closed = close_message(
+                    tx,
+                    e.message_id,
+                    "cancelled",
+                    Some(e.chunks_delivered),
+                    FinishReason::Cancelled,
+                    None,
+                )
+                .await?;
+                delta.responses_cancelled = u64::from(closed);
+            }
+            AgentEvent::ConversationArchived(e) => {
+                // Archived messages leave the recent view; usage keeps counting them
+                sqlx::query(
+                    "UPDATE agent_messages SET archived_at = $3 \
+                     WHERE agent_id = $1 AND sent_at <= $2 AND archived_at IS NULL",
+                )
+                .bind(*e.agent_id.as_uuid())
+                .bind(e.last_message_at)
+                .bind(e.archived_at)
+                .execute(&mut **tx)
+                .await
+                .map_err(storage)?;
+                sqlx::query(
+                    "INSERT INTO agent_conversations (conversation_id, agent_id, message_count, \
+                         first_message_at, last_message_at, archived_at) \
+                     VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING",
+                )
+                .bind(e.conversation_id.as_uuid())
+                .bind(*e.agent_id.as_uuid())
+                .bind(e.message_count as i32)
+                .bind(e.first_message_at)
+                .bind(e.last_message_at)
+                .bind(e.archived_at)
+                .execute(&mut **tx)
+                .await
+                .map_err(storage)?;
+            }
+            _ => {}
+        }
+
+        if delta != UsageView::empty(envelope.aggregate_id) {
+            sqlx::query(
+                "INSERT INTO agent_usage AS u (agent_id, messages_sent, responses_completed, \
+                     responses_failed, responses_cancelled, prompt_tokens, completion_tokens) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                 ON CONFLICT (agent_id) DO UPDATE SET \
+                     messages_sent = u.messages_sent + EXCLUDED.messages_sent, \
+                     responses_completed = u.responses_completed + EXCLUDED.responses_completed, \
+                     responses_failed = u.responses_failed + EXCLUDED.responses_failed, \
+                     responses_cancelled = u.responses_cancelled + EXCLUDED.responses_cancelled, \
+                     prompt_tokens = u.prompt_tokens + EXCLUDED.prompt_tokens, \
+                     completion_tokens = u.completion_tokens + EXCLUDED.completion_tokens",
+            )
+            .bind(*delta.agent_id.as_uuid())
+            .bind(delta.messages_sent as i64)
+            .bind(delta.responses_completed as i64)
+            .bind(delta.responses_failed as i64)
+            .bind(delta.responses_cancelled as i64)
+            .bind(delta.prompt_tokens as i64)
+            .bind(delta.completion_tokens as i64)
+            .execute(&mut **tx)
+            .await
+            .map_err(storage)?;
+        }
+        Ok(())
+    }
+
+    async fn project_tool_selections(
+        tx: &mut Transaction<'_, Postgres>,
+        envelope: &EventEnvelope,
+    ) -> ReadModelResult<()> {
+        let AgentEvent::ReasoningTraceRecorded(e) = &envelope.event else {
+            return Ok(());
+        };
+        let selections = e
+            .trace
+            .steps()
+            .iter()
+            .filter(|step| step.kind == TraceStepKind::ToolSelection);
+        for step in selections {
+            sqlx::query(
+                "INSERT INTO agent_tool_selections \
+                     (agent_id, message_id, step_id, summary, recorded_at) \
+                 VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING",
+            )
+            .bind(*e.agent_id.as_uuid())
+            .bind(*e.message_id.as_uuid())
+            .bind(step.id as i32)
+            .bind(&step.summary)
+            .bind(e.recorded_at)
+            .execute(&mut **tx)
+            .await
+            .map_err(storage)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AgentReadModel for SqlAgentReadModel {
+    async fn project(&self, envelope: &EventEnvelope) -> ReadModelResult<()> {
+        let mut tx = self.pool.begin().await.map_err(storage)?;
+        if is_message_event(&envelope.event) {
+            Self::project_activity(&mut tx, envelope).await?;
+        } else {
+            Self::project_tool_selections(&mut tx, envelope).await?;
+            Self::project_agent(&mut tx, envelope).await?;
+        }
+        tx.commit().await.map_err(storage)
+    }
+
+    async fn version(&self, agent_id: AgentId) -> ReadModelResult<u64> {
+        let version: Option<i64> =
+            sqlx::query_scalar("SELECT version FROM agents WHERE agent_id = $1")
+                .bind(*agent_id.as_uuid())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(storage)?;
+        Ok(version.unwrap_or(0) as u64)
+    }
+
+    async fn get(&self, agent_id: AgentId) -> ReadModelResult<Option<AgentView>> {
+        sqlx::query("SELECT state, version, updated_at FROM agents WHERE agent_id = $1")
+            .bind(*agent_id.as_uuid())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(storage)?
+            .as_ref()
+            .map(view_from_row)
+            .transpose()
+    }
+
+    async fn list(
+        &self,
+        status: Option<AgentStatus>,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        self.page(Listing::Status(status), page).await
+    }
+
+    async fn get_as_of(
+        &self,
+        agent_id: AgentId,
+        as_of: &AsOf,
+    ) -> ReadModelResult<Option<AgentView>> {
+        let view: Option<Json<AgentView>> = sqlx::query_scalar(
+            "SELECT view FROM agent_revisions \
+             WHERE agent_id = $1 AND valid_from <= $2 \
+               AND ($3::TIMESTAMPTZ IS NULL OR recorded_at <= $3) \
+             ORDER BY version DESC LIMIT 1",
+        )
+        .bind(*agent_id.as_uuid())
+        .bind(as_of.valid_at)
+        .bind(as_of.known_at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(storage)?;
+        Ok(view.map(|Json(view)| view))
+    }
+
+    async fn list_as_of(
+        &self,
+        status: Option<AgentStatus>,
+        as_of: &AsOf,
+        page: &PageRequest,
+    ) -> ReadModelResult<Page<AgentView>> {
+        let views: Vec<Json<AgentView>> = sqlx::query_scalar(
+            "SELECT view FROM ( \
+                 SELECT DISTINCT ON (agent_id) view, status FROM agent_revisions \
+                 WHERE valid_from <= $1 AND ($2::TIMESTAMPTZ IS NULL OR recorded_at <= $2) \
+                 ORDER BY agent_id, version DESC \
+             ) r WHERE ($3::TEXT IS NULL OR r.status = $3)",
+        )
+        .bind(as_of.valid_at)
+        .bind(as_of.known_at)
+        .bind(status.map(|s| s.code()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage)?;
+        paginate(views.into_iter().map(|Json(view)| view).collect(), page)
+    }
+
+    async fn messages(&self, agent_id: AgentId, limit: usize) -> ReadModelResult<Vec<MessageView>> {
+        sqlx::query(
+            "SELECT * FROM agent_messages WHERE agent_id = $1 AND archived_at IS NULL \
+             ORDER BY sent_at DESC LIMIT $2",
+        )
+        .bind(*agent_id.as_uuid())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage)?
+        .iter()
+        .map(message_from_row)
+        .collect()
+    }
+
+    async fn usage(&self, agent_id: AgentId) -> ReadModelResult<UsageView> {
+        let row = sqlx::query("SELECT * FROM agent_usage WHERE agent_id = $1")
+            .bind(*agent_id.as_uuid())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(storage)?;
+        match row {
+            Some(row) => usage_from_row(&row),
+            None => Ok(UsageView::empty(agent_id)),
+        }
+    }
+}
+
+/// End an open response; false if it had already ended (a redelivery)
+async fn close_message(
+    tx: &mut Transaction<'_, Postgres>,
+    message_id: MessageId,
+    status: &str,
+    chunks: Option<u32>,
+    finish_reason: FinishReason,
+    token_usage: Option<Json<TokenUsage>>,
+) -> ReadModelResult<bool> {
+    let closed = sqlx::query(&format!(
+        "UPDATE agent_messages SET status = $2, chunks = COALESCE($3, chunks), \
+         finish_reason = $4, token_usage = $5 WHERE message_id = $1 AND {}",
+        OPEN_STATUSES
+    ))
+    .bind(*message_id.as_uuid())
+    .bind(status)
+    .bind(chunks.map(|c| c as i32))
+    .bind(Json(finish_reason))
+    .bind(token_usage)
+    .execute(&mut **tx)
+    .await
+    .map_err(storage)?;
+    Ok(closed.rows_affected() == 1)
+}
+
+/// Single runtime capabilities offered by a provider, by flag name
+fn capability_names(provider: ProviderType) -> Vec<&'static str> {
+    ProviderCapabilities::for_provider(provider)
+        .capabilities
+        .iter_names()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn projected_from_row(row: &PgRow) -> ReadModelResult<Projected> {
+    let Json(agent): Json<Agent> = row.try_get("state").map_err(storage)?;
+    Ok(Projected {
+        agent,
+        version: row.try_get::<i64, _>("version").map_err(storage)? as u64,
+        updated_at: row.try_get("updated_at").map_err(storage)?,
+        history: Default::default(),
+    })
+}
+
+fn view_from_row(row: &PgRow) -> ReadModelResult<AgentView> {
+    projected_from_row(row).map(|p| p.view())
+}
+
+fn message_from_row(row: &PgRow) -> ReadModelResult<MessageView> {
+    let status: String = row.try_get("status").map_err(storage)?;
+    let finish_reason: Option<Json<FinishReason>> =
+        row.try_get("finish_reason").map_err(storage)?;
+    let token_usage: Option<Json<TokenUsage>> = row.try_get("token_usage").map_err(storage)?;
+    Ok(MessageView {
+        message_id: MessageId::from_uuid(row.try_get("message_id").map_err(storage)?),
+        agent_id: AgentId::from_uuid(row.try_get("agent_id").map_err(storage)?),
+        content: row.try_get("content").map_err(storage)?,
+        sent_at: row
+            .try_get::<DateTime<Utc>, _>("sent_at")
+            .map_err(storage)?,
+        status: message_status(&status)?,
+        chunks: row.try_get::<i32, _>("chunks").map_err(storage)? as u32,
+        finish_reason: finish_reason.map(|Json(r)| r),
+        token_usage: token_usage.map(|Json(u)| u),
+    })
+}
+
+fn usage_from_row(row: &PgRow) -> ReadModelResult<UsageView> {
+    let count = |column: &str| -> ReadModelResult<u64> {
+        Ok(row.try_get::<i64, _>(column).map_err(storage)? as u64)
+    };
+    Ok(UsageView {
+        agent_id: AgentId::from_uuid(row.try_get("agent_id").map_err(storage)?),
+        messages_sent: count("messages_sent")?,
+        responses_completed: count("responses_completed")?,
+        responses_failed: count("responses_failed")?,
+        responses_cancelled: count("responses_cancelled")?,
+        prompt_tokens: count("prompt_tokens")?,
+        completion_tokens: count("completion_tokens")?,
+    })
+}
+
+/// Parse a stored `agent_messages.status`
+fn message_status(status: &str) -> ReadModelResult<MessageStatus> {
+    serde_json::from_value(serde_json::Value::String(status.to_string()))
+        .map_err(|_| ReadModelError::Storage(format!("Unknown message status: {}", status)))
+}
+
+fn storage(e: impl std::fmt::Display) -> ReadModelError {
+    ReadModelError::Storage(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_statuses_and_capabilities() {
+        for status in ["pending", "streaming", "completed", "failed", "cancelled"] {
+            let parsed = message_status(status).unwrap();
+            assert_eq!(serde_json::to_value(parsed).unwrap(), status);
+        }
+        assert!(message_status("lost").is_err());
+
+        let claude = capability_names(ProviderType::Anthropic);
+        assert!(claude.contains(&"VISION"));
+        assert!(claude.contains(&"TEXT_CHAT"));
+        assert!(!claude.contains(&"BASIC_CHAT"));
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Bitemporal agent history
+//!
+//! Every projected lifecycle event adds a revision stamped with two times:
+//!
+//! - **valid time** - when the change happened (the event timestamp)
+//! - **system time** - when the read model learned about it
+//!
+//! An [`AsOf`] point picks the latest revision valid at `valid_at` among
+//! those already recorded at `known_at`, so "which agents were Active on
+//! 2025-01-01" and "what did we believe on 2025-01-02 about 2025-01-01" are
+//! both answerable.
+
+use super::AgentView;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A point in valid time, optionally as known at a point in system time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsOf {
+    /// State as it was at this moment
+    pub valid_at: DateTime<Utc>,
+
+    /// Only consider what had been recorded by this moment (None = now)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_at: Option<DateTime<Utc>>,
+}
+
+impl AsOf {
+    /// State at `valid_at`, with everything known now
+    pub fn valid_at(valid_at: DateTime<Utc>) -> Self {
+        Self {
+            valid_at,
+            known_at: None,
+        }
+    }
+
+    /// Builder: restrict to what was recorded by `known_at`
+    pub fn known_at(mut self, known_at: DateTime<Utc>) -> Self {
+        self.known_at = Some(known_at);
+        self
+    }
+}
+
+/// One recorded state of an agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    /// When this state became true
+    pub valid_from: DateTime<Utc>,
+    /// When the read model recorded it
+    pub recorded_at: DateTime<Utc>,
+    /// The agent's state
+    pub view: AgentView,
+}
+
+/// Revisions of a single agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentHistory {
+    revisions: Vec<Revision>,
+}
+
+impl AgentHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new state
+    pub fn record(&mut self, view: AgentView, valid_from: DateTime<Utc>, recorded_at: DateTime<Utc>) {
+        self.revisions.push(Revision {
+            valid_from,
+            recorded_at,
+            view,
+        });
+    }
+
+    /// The agent's state at `as_of`, if it existed then
+    ///
+    /// Among qualifying revisions the highest stream version wins, so
+    /// events with equal or out-of-order timestamps still resolve in
+    /// stream order.
+    pub fn at(&self, as_of: &AsOf) -> Option<&AgentView> {
+        self.revisions
+            .iter()
+            .filter(|r| r.valid_from <= as_of.valid_at)
+            .filter(|r| as_of.known_at.is_none_or(|known| r.recorded_at <= known))
+            .max_by_key(|r| r.view.version)
+            .map(|r| &r.view)
+    }
+
+    /// All revisions, in recording order
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    /// Forget revisions superseded before `cutoff`
+    ///
+    /// The revision in force at `cutoff` is kept, so as-of queries from
+    /// `cutoff` on answer as before; earlier points may no longer resolve.
+    pub fn prune_before(&mut self, cutoff: DateTime<Utc>) {
+        let in_force = self
+            .revisions
+            .iter()
+            .filter(|r| r.valid_from < cutoff)
+            .map(|r| r.view.version)
+            .max();
+        self.revisions
+            .retain(|r| r.valid_from >= cutoff || Some(r.view.version) == in_force);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{AgentId, AgentStatus, PersonId};
+    use chrono::{Duration, TimeZone};
+
+    fn view(status: AgentStatus, version: u64) -> AgentView {
+        let created = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+        AgentView {
+            agent_id: AgentId::new(),
+            person_id: PersonId::new(),
+            name: "audited".to_string(),
+            description: None,
+            status,
+            provider: None,
+            model_name: None,
+            created_at: created,
+            updated_at: created,
+            version,
+        }
+    }
+
+    #[test]
+    fn test_valid_time_selects_state() {
+        let jan1 = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut history = AgentHistory::new();
+        history.record(view(AgentStatus::Deployed, 1), jan1 - Duration::days(30), jan1);
+        history.record(view(AgentStatus::Active, 2), jan1 - Duration::days(1), jan1);
+        history.record(view(AgentStatus::Suspended, 3), jan1 + Duration::days(1), jan1);
+
+        assert_eq!(history.at(&AsOf::valid_at(jan1)).unwrap().status, AgentStatus::Active);
+        assert!(history.at(&AsOf::valid_at(jan1 - Duration::days(60))).is_none());
+
+        history.prune_before(jan1);
+        assert_eq!(history.revisions().len(), 2);
+        assert_eq!(history.at(&AsOf::valid_at(jan1)).unwrap().status, AgentStatus::Active);
+    }
+
+    #[test]
+    fn test_system_time_hides_late_arrivals() {
+        let jan1 = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut history = AgentHistory::new();
+        history.record(view(AgentStatus::Active, 1), jan1 - Duration::days(2), jan1 - Duration::days(2));
+        // Suspension happened before Jan 1 but was only recorded on Jan 3
+        history.record(
+            view(AgentStatus::Suspended, 2),
+            jan1 - Duration::hours(1),
+            jan1 + Duration::days(2),
+        );
+
+        let believed_then = AsOf::valid_at(jan1).known_at(jan1 + Duration::days(1));
+        assert_eq!(history.at(&believed_then).unwrap().status, AgentStatus::Active);
+        assert_eq!(history.at(&AsOf::valid_at(jan1)).unwrap().status, AgentStatus::Suspended);
+    }
+}
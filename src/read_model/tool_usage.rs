@@ -0,0 +1,202 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Per-tool usage projection
+//!
+//! [`ToolUsageProjection`] folds the tool call events of each agent into
+//! running [`ToolUsageStats`]:
+//!
+//! ```text
+//! ToolInvoked   { tool, argument_bytes } ──> invocations, argument size
+//! ToolSucceeded { tool, duration_ms }    ──> successes, latency
+//! ToolFailed    { tool, duration_ms }    ──> failures, latency
+//!
+//! tool_usage(agent) ──> [ search: 40 calls, 5% failed, 120 ms mean, ... ]
+//! ```
+//!
+//! Calls invoked but not yet finished count toward invocations only. The
+//! events are published by whatever runs the agent's tools.
+
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::AgentId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+/// Running statistics for one tool of one agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsageStats {
+    /// The tool
+    pub tool_name: String,
+
+    /// Calls started
+    pub invocations: u64,
+
+    /// Calls that returned a result
+    pub successes: u64,
+
+    /// Calls that failed
+    pub failures: u64,
+
+    /// Total time of finished calls, in milliseconds
+    pub total_duration_ms: u64,
+
+    /// Fastest finished call, in milliseconds
+    pub min_duration_ms: Option<u64>,
+
+    /// Slowest finished call, in milliseconds
+    pub max_duration_ms: Option<u64>,
+
+    /// Total size of the arguments, in bytes
+    pub total_argument_bytes: u64,
+
+    /// Largest arguments, in bytes
+    pub max_argument_bytes: u64,
+
+    /// Most recent error, if any call failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl ToolUsageStats {
+    fn new(tool_name: &str) -> Self {
+        Self {
+            tool_name: tool_name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Share of finished calls that failed (0.0 with none finished)
+    pub fn failure_rate(&self) -> f64 {
+        match self.successes + self.failures {
+            0 => 0.0,
+            finished => self.failures as f64 / finished as f64,
+        }
+    }
+
+    /// Mean time of finished calls, in milliseconds
+    pub fn mean_duration_ms(&self) -> Option<f64> {
+        match self.successes + self.failures {
+            0 => None,
+            finished => Some(self.total_duration_ms as f64 / finished as f64),
+        }
+    }
+
+    /// Mean argument size, in bytes
+    pub fn mean_argument_bytes(&self) -> Option<f64> {
+        (self.invocations > 0).then(|| self.total_argument_bytes as f64 / self.invocations as f64)
+    }
+
+    fn finished(&mut self, duration_ms: u64) {
+        self.total_duration_ms += duration_ms;
+        let (min, max) = (self.min_duration_ms, self.max_duration_ms);
+        self.min_duration_ms = Some(min.map_or(duration_ms, |m| m.min(duration_ms)));
+        self.max_duration_ms = Some(max.map_or(duration_ms, |m| m.max(duration_ms)));
+    }
+}
+
+/// Projection of tool usage by agent and tool
+#[derive(Debug, Default)]
+pub struct ToolUsageProjection {
+    usage: RwLock<HashMap<AgentId, BTreeMap<String, ToolUsageStats>>>,
+}
+
+impl ToolUsageProjection {
+    /// Create an empty projection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a stored event; only tool call events are counted
+    pub fn project(&self, envelope: &EventEnvelope) {
+        let mut usage = self.usage.write().unwrap_or_else(|e| e.into_inner());
+        let (agent_id, tool_name) = match &envelope.event {
+            AgentEvent::ToolInvoked(e) => (e.agent_id, &e.tool_name),
+            AgentEvent::ToolSucceeded(e) => (e.agent_id, &e.tool_name),
+            AgentEvent::ToolFailed(e) => (e.agent_id, &e.tool_name),
+            _ => return,
+        };
+        let stats = usage
+            .entry(agent_id)
+            .or_default()
+            .entry(tool_name.clone())
+            .or_insert_with(|| ToolUsageStats::new(tool_name));
+        match &envelope.event {
+            AgentEvent::ToolInvoked(e) => {
+                stats.invocations += 1;
+                stats.total_argument_bytes += e.argument_bytes;
+                stats.max_argument_bytes = stats.max_argument_bytes.max(e.argument_bytes);
+            }
+            AgentEvent::ToolSucceeded(e) => {
+                stats.successes += 1;
+                stats.finished(e.duration_ms);
+            }
+            AgentEvent::ToolFailed(e) => {
+                stats.failures += 1;
+                stats.finished(e.duration_ms);
+                stats.last_error = Some(e.error.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Usage of every tool the agent has called, by tool name
+    pub fn tool_usage(&self, agent_id: AgentId) -> Vec<ToolUsageStats> {
+        let usage = self.usage.read().unwrap_or_else(|e| e.into_inner());
+        usage
+            .get(&agent_id)
+            .map(|tools| tools.values().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ToolFailedEvent, ToolInvokedEvent, ToolSucceededEvent};
+    use crate::test_support::envelope;
+
+    fn project(projection: &ToolUsageProjection, event: AgentEvent) {
+        projection.project(&envelope(1, event));
+    }
+
+    #[test]
+    fn test_stats_track_latency_failures_and_argument_size() {
+        let projection = ToolUsageProjection::new();
+        let agent_id = AgentId::new();
+        for (call, bytes) in [("1", 10), ("2", 30), ("3", 20)] {
+            project(
+                &projection,
+                AgentEvent::ToolInvoked(ToolInvokedEvent::new(agent_id, call, "search", bytes)),
+            );
+        }
+        project(
+            &projection,
+            AgentEvent::ToolSucceeded(ToolSucceededEvent::new(agent_id, "1", "search", 100, 5)),
+        );
+        project(
+            &projection,
+            AgentEvent::ToolFailed(ToolFailedEvent::new(
+                agent_id, "2", "search", 300, "timeout",
+            )),
+        );
+
+        let stats = &projection.tool_usage(agent_id)[0];
+        assert_eq!(
+            (stats.invocations, stats.successes, stats.failures),
+            (3, 1, 1)
+        );
+        assert_eq!(stats.failure_rate(), 0.5);
+        assert_eq!(stats.mean_duration_ms(), Some(200.0));
+        assert_eq!(
+            (stats.min_duration_ms, stats.max_duration_ms),
+            (Some(100), Some(300))
+        );
+        assert_eq!(
+            (stats.mean_argument_bytes(), stats.max_argument_bytes),
+            (Some(20.0), 30)
+        );
+        assert_eq!(stats.last_error.as_deref(), Some("timeout"));
+        assert!(projection.tool_usage(AgentId::new()).is_empty());
+    }
+}
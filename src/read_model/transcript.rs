@@ -0,0 +1,403 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation transcripts
+//!
+//! [`Transcript::from_events`] rebuilds the turns of a conversation from an
+//! agent's message events: each `MessageSent` opens a turn, its chunks are
+//! reassembled in `chunk_index` order (redelivered chunks are dropped) and
+//! the terminal event records how the response ended. [`TranscriptRenderer`]
+//! turns the result into Markdown or a standalone HTML page for sharing,
+//! audits and ticket attachments. Timestamps are UTC (RFC 3339) unless the
+//! renderer is given the agent's [`LocalePreferences`].
+//!
+//! Message events do not carry tool call payloads, so a response that ended
+//! to call tools is marked as such rather than showing the calls.
+
+use super::MessageStatus;
+use crate::events::AgentEvent;
+use crate::value_objects::{AgentId, FinishReason, LocalePreferences, MessageId};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// One prompt and the agent's response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptTurn {
+    /// Message ID
+    pub message_id: MessageId,
+    /// Prompt sent to the agent
+    pub prompt: String,
+    /// When the prompt was sent
+    pub sent_at: DateTime<Utc>,
+    /// Reassembled response text
+    pub response: String,
+    /// How far the response got
+    pub status: MessageStatus,
+    /// Why the response finished
+    pub finish_reason: Option<FinishReason>,
+    /// Error message for failed responses
+    pub error: Option<String>,
+    /// When the response ended
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// The turns of one agent conversation, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Agent that answered
+    pub agent_id: AgentId,
+    /// Turns in the order their prompts were sent
+    pub turns: Vec<TranscriptTurn>,
+}
+
+impl Transcript {
+    /// Rebuild a transcript from an agent's events
+    ///
+    /// Events for other agents, lifecycle events and events for messages
+    /// whose `MessageSent` is missing are skipped.
+    pub fn from_events<'a>(
+        agent_id: AgentId,
+        events: impl IntoIterator<Item = &'a AgentEvent>,
+    ) -> Self {
+        let mut turns: Vec<TranscriptTurn> = Vec::new();
+        let mut chunks: HashMap<MessageId, BTreeMap<u32, String>> = HashMap::new();
+
+        for event in events.into_iter().filter(|e| e.agent_id() == agent_id) {
+            if let AgentEvent::MessageSent(e) = event {
+                if turns.iter().all(|t| t.message_id != e.message_id) {
+                    turns.push(TranscriptTurn {
+                        message_id: e.message_id,
+                        prompt: e.content.clone(),
+                        sent_at: e.sent_at,
+                        response: String::new(),
+                        status: MessageStatus::Pending,
+                        finish_reason: None,
+                        error: None,
+                        ended_at: None,
+                    });
+                }
+                continue;
+            }
+
+            let Some(turn) = turns.iter_mut().find(|t| Some(t.message_id) == message_of(event))
+            else {
+                continue;
+            };
+            match event {
+                AgentEvent::ResponseChunkReceived(e) => {
+                    chunks
+                        .entry(e.message_id)
+                        .or_default()
+                        .entry(e.chunk.chunk_index)
+                        .or_insert_with(|| e.chunk.content.clone());
+                    if turn.status == MessageStatus::Pending {
+                        turn.status = MessageStatus::Streaming;
+                    }
+                }
+                AgentEvent::ResponseCompleted(e) => {
+                    turn.status = MessageStatus::Completed;
+                    turn.finish_reason = Some(e.finish_reason);
+                    turn.ended_at = Some(e.completed_at);
+                }
+                AgentEvent::ResponseFailed(e) => {
+                    turn.status = MessageStatus::Failed;
+                    turn.finish_reason = Some(FinishReason::Error);
+                    turn.error = Some(e.error_message.clone());
+                    turn.ended_at = Some(e.failed_at);
+                }
+                AgentEvent::ResponseCancelled(e) => {
+                    turn.status = MessageStatus::Cancelled;
+                    turn.finish_reason = Some(FinishReason::Cancelled);
+                    turn.ended_at = Some(e.cancelled_at);
+                }
+                _ => {}
+            }
+        }
+
+        for turn in &mut turns {
+            if let Some(parts) = chunks.remove(&turn.message_id) {
+                turn.response = parts.into_values().collect();
+            }
+        }
+        turns.sort_by_key(|t| t.sent_at);
+        Self { agent_id, turns }
+    }
+}
+
+/// Message a response event belongs to
+fn message_of(event: &AgentEvent) -> Option<MessageId> {
+    match event {
+        AgentEvent::ResponseChunkReceived(e) => Some(e.message_id),
+        AgentEvent::ResponseCompleted(e) => Some(e.message_id),
+        AgentEvent::ResponseFailed(e) => Some(e.message_id),
+        AgentEvent::ResponseCancelled(e) => Some(e.message_id),
+        _ => None,
+    }
+}
+
+/// Output format for [`TranscriptRenderer::render`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// GitHub-flavoured Markdown
+    Markdown,
+    /// Standalone HTML document
+    Html,
+}
+
+/// Renders transcripts with participant names and timestamps
+#[derive(Debug, Clone)]
+pub struct TranscriptRenderer {
+    user_label: String,
+    agent_label: Option<String>,
+    locale: Option<LocalePreferences>,
+}
+
+impl Default for TranscriptRenderer {
+    fn default() -> Self {
+        Self {
+            user_label: "User".to_string(),
+            agent_label: None,
+            locale: None,
+        }
+    }
+}
+
+impl TranscriptRenderer {
+    /// Create a renderer labelling the agent by its ID
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name shown for the sender of prompts
+    pub fn with_user_label(mut self, label: impl Into<String>) -> Self {
+        self.user_label = label.into();
+        self
+    }
+
+    /// Name shown for the agent
+    pub fn with_agent_label(mut self, label: impl Into<String>) -> Self {
+        self.agent_label = Some(label.into());
+        self
+    }
+
+    /// Show timestamps in the agent's local time and date format
+    pub fn with_locale(mut self, locale: LocalePreferences) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Render in the given format
+    pub fn render(&self, transcript: &Transcript, format: TranscriptFormat) -> String {
+        match format {
+            TranscriptFormat::Markdown => self.markdown(transcript),
+            TranscriptFormat::Html => self.html(transcript),
+        }
+    }
+
+    /// Render as Markdown
+    pub fn markdown(&self, transcript: &Transcript) -> String {
+        let agent = self.agent_label(transcript);
+        let mut out = format!("# Conversation with {}\n", agent);
+        for turn in &transcript.turns {
+            let _ = write!(
+                out,
+                "\n**{}** · {}\n\n{}\n",
+                self.user_label,
+                self.timestamp(turn.sent_at),
+                quote(&turn.prompt)
+            );
+            let _ = write!(out, "\n**{}**", agent);
+            if let Some(at) = turn.ended_at {
+                let _ = write!(out, " · {}", self.timestamp(at));
+            }
+            let _ = write!(out, "\n\n{}\n", quote(&turn.response));
+            if let Some(note) = outcome(turn) {
+                let _ = writeln!(out, "\n_{}_", note);
+            }
+        }
+        out
+    }
+
+    /// Render as a standalone HTML document
+    pub fn html(&self, transcript: &Transcript) -> String {
+        let agent = escape_html(&self.agent_label(transcript));
+        let user = escape_html(&self.user_label);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Conversation with {agent}</title>\n</head>\n<body>\n\
+             <h1>Conversation with {agent}</h1>\n"
+        );
+        for turn in &transcript.turns {
+            let _ = write!(
+                out,
+                "<section class=\"turn\" id=\"{}\">\n\
+                 <div class=\"user\"><strong>{}</strong> <time>{}</time>\
+                 <pre>{}</pre></div>\n",
+                turn.message_id,
+                user,
+                self.timestamp(turn.sent_at),
+                escape_html(&turn.prompt)
+            );
+            let _ = write!(out, "<div class=\"agent\"><strong>{}</strong>", agent);
+            if let Some(at) = turn.ended_at {
+                let _ = write!(out, " <time>{}</time>", self.timestamp(at));
+            }
+            let _ = write!(out, "<pre>{}</pre>", escape_html(&turn.response));
+            if let Some(note) = outcome(turn) {
+                let _ = write!(out, "<p class=\"outcome\"><em>{}</em></p>", escape_html(&note));
+            }
+            out.push_str("</div>\n</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn timestamp(&self, at: DateTime<Utc>) -> String {
+        match &self.locale {
+            Some(locale) => locale.format_datetime(at),
+            None => at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+
+    fn agent_label(&self, transcript: &Transcript) -> String {
+        self.agent_label
+            .clone()
+            .unwrap_or_else(|| format!("agent {}", transcript.agent_id))
+    }
+}
+
+/// Note on how a turn ended, when it did not simply stop
+fn outcome(turn: &TranscriptTurn) -> Option<String> {
+    match (turn.status, turn.finish_reason) {
+        (MessageStatus::Failed, _) => Some(format!(
+            "Response failed: {}",
+            turn.error.as_deref().unwrap_or("unknown error")
+        )),
+        (MessageStatus::Cancelled, _) => Some("Response cancelled".to_string()),
+        (MessageStatus::Pending | MessageStatus::Streaming, _) => {
+            Some("Response incomplete".to_string())
+        }
+        (_, Some(FinishReason::ToolCalls)) => Some("Requested tool calls".to_string()),
+        (_, Some(FinishReason::Length)) => Some("Truncated at the token limit".to_string()),
+        (_, Some(FinishReason::ContentFilter)) => Some("Stopped by content filter".to_string()),
+        _ => None,
+    }
+}
+
+/// Markdown blockquote, so prompt text cannot break the transcript layout
+fn quote(text: &str) -> String {
+    if text.is_empty() {
+        return "> _(empty)_".to_string();
+    }
+    text.lines()
+        .map(|line| format!("> {}", line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        MessageSentEvent, ResponseChunkReceivedEvent, ResponseCompletedEvent,
+        ResponseErrorType, ResponseFailedEvent,
+    };
+    use crate::value_objects::{StreamingChunk, TokenUsage};
+
+    fn chunk(agent_id: AgentId, message_id: MessageId, index: u32, text: &str) -> AgentEvent {
+        AgentEvent::ResponseChunkReceived(ResponseChunkReceivedEvent::new(
+            agent_id,
+            message_id,
+            StreamingChunk::new(index, text),
+        ))
+    }
+
+    fn conversation() -> (AgentId, Vec<AgentEvent>) {
+        let agent_id = AgentId::new();
+        let (first, second) = (MessageId::new(), MessageId::new());
+        let events = vec![
+            AgentEvent::MessageSent(MessageSentEvent::new(agent_id, first, "Say <hi>")),
+            chunk(agent_id, first, 1, " world"),
+            chunk(agent_id, first, 0, "Hello"),
+            chunk(agent_id, first, 1, " world"),
+            AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+                agent_id,
+                first,
+                2,
+                TokenUsage::new(3, 2),
+                FinishReason::Stop,
+                40,
+            )),
+            AgentEvent::MessageSent(MessageSentEvent::new(agent_id, second, "Again")),
+            AgentEvent::ResponseFailed(ResponseFailedEvent::new(
+                agent_id,
+                second,
+                ResponseErrorType::RateLimit,
+                "slow down",
+                true,
+            )),
+        ];
+        (agent_id, events)
+    }
+
+    #[test]
+    fn test_reassembles_chunks_in_order() {
+        let (agent_id, events) = conversation();
+        let transcript = Transcript::from_events(agent_id, &events);
+
+        assert_eq!(transcript.turns.len(), 2);
+        assert_eq!(transcript.turns[0].response, "Hello world");
+        assert_eq!(transcript.turns[0].status, MessageStatus::Completed);
+        assert_eq!(transcript.turns[1].status, MessageStatus::Failed);
+        assert!(Transcript::from_events(AgentId::new(), &events).turns.is_empty());
+    }
+
+    #[test]
+    fn test_renders_markdown_and_escaped_html() {
+        let (agent_id, events) = conversation();
+        let transcript = Transcript::from_events(agent_id, &events);
+        let renderer = TranscriptRenderer::new()
+            .with_user_label("Alice")
+            .with_agent_label("sage");
+
+        let markdown = renderer.render(&transcript, TranscriptFormat::Markdown);
+        assert!(markdown.starts_with("# Conversation with sage"));
+        assert!(markdown.contains("**Alice**"));
+        assert!(markdown.contains("> Hello world"));
+        assert!(markdown.contains("_Response failed: slow down_"));
+
+        let html = renderer.render(&transcript, TranscriptFormat::Html);
+        assert!(html.contains("Say &lt;hi&gt;"));
+        assert!(!html.contains("<hi>"));
+    }
+
+    #[test]
+    fn test_renders_timestamps_in_agent_locale() {
+        let (agent_id, events) = conversation();
+        let transcript = Transcript::from_events(agent_id, &events);
+        let locale = LocalePreferences::new("de-DE", chrono_tz::Europe::Berlin);
+        let local = locale.format_datetime(transcript.turns[0].sent_at);
+
+        let markdown = TranscriptRenderer::new()
+            .with_locale(locale)
+            .render(&transcript, TranscriptFormat::Markdown);
+        assert!(markdown.contains(&format!("**User** · {}", local)));
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Projected agent, message and usage views
+
+use crate::aggregate::Agent;
+use crate::value_objects::{
+    AgentId, AgentStatus, FinishReason, MessageId, PersonId, ProviderType, TokenUsage,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Query-side view of an agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentView {
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// Owning person
+    pub person_id: PersonId,
+
+    /// Agent name
+    pub name: String,
+
+    /// Optional description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Current status
+    pub status: AgentStatus,
+
+    /// Configured provider, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderType>,
+
+    /// Configured model name, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_name: Option<String>,
+
+    /// When the agent was deployed
+    pub created_at: DateTime<Utc>,
+
+    /// Timestamp of the last projected event
+    pub updated_at: DateTime<Utc>,
+
+    /// Event stream version this view reflects
+    pub version: u64,
+}
+
+impl AgentView {
+    /// Build a view from the aggregate state at `version`
+    pub fn from_agent(agent: &Agent, version: u64, updated_at: DateTime<Utc>) -> Self {
+        let model = agent.model_config();
+        Self {
+            agent_id: agent.id(),
+            person_id: agent.person_id(),
+            name: agent.name().to_string(),
+            description: agent.description().map(str::to_string),
+            status: agent.status(),
+            provider: model.map(|m| m.provider),
+            model_name: model.map(|m| m.model_name.clone()),
+            created_at: agent.created_at(),
+            updated_at,
+            version,
+        }
+    }
+}
+
+/// Where a message's response stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatus {
+    /// Sent, no response chunk yet
+    Pending,
+    /// Response chunks arriving
+    Streaming,
+    /// Response finished
+    Completed,
+    /// Response failed
+    Failed,
+    /// Response cancelled by the caller
+    Cancelled,
+}
+
+impl MessageStatus {
+    /// Check if the response has ended
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            MessageStatus::Completed | MessageStatus::Failed | MessageStatus::Cancelled
+        )
+    }
+}
+
+/// Query-side view of one message and its response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageView {
+    /// Message ID
+    pub message_id: MessageId,
+
+    /// Agent that received the message
+    pub agent_id: AgentId,
+
+    /// Message content
+    pub content: String,
+
+    /// When the message was sent
+    pub sent_at: DateTime<Utc>,
+
+    /// Response progress
+    pub status: MessageStatus,
+
+    /// Response chunks seen so far
+    pub chunks: u32,
+
+    /// Why the response finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
+
+    /// Token usage reported on completion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsage>,
+}
+
+/// Aggregated message and token counts for an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageView {
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// Messages sent to the agent
+    pub messages_sent: u64,
+
+    /// Responses that completed
+    pub responses_completed: u64,
+
+    /// Responses that failed
+    pub responses_failed: u64,
+
+    /// Responses cancelled by callers
+    pub responses_cancelled: u64,
+
+    /// Prompt tokens across completed responses
+    pub prompt_tokens: u64,
+
+    /// Completion tokens across completed responses
+    pub completion_tokens: u64,
+}
+
+impl UsageView {
+    /// Zero usage for an agent
+    pub fn empty(agent_id: AgentId) -> Self {
+        Self {
+            agent_id,
+            messages_sent: 0,
+            responses_completed: 0,
+            responses_failed: 0,
+            responses_cancelled: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        }
+    }
+
+    /// Prompt plus completion tokens
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
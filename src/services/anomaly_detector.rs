@@ -0,0 +1,262 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Anomaly Detection
+//!
+//! Watches each agent's error rate, latency and token usage and flags
+//! windows that stray from the agent's own history. Responses are grouped
+//! into windows of a fixed count; each closed window is compared with an
+//! exponentially weighted baseline and then folded into it:
+//!
+//! ```text
+//! ResponseCompleted / ResponseFailed ──> window of N responses
+//!                                              │ closed
+//!                                              v
+//!         per metric: z = (value - EWMA mean) / EWMA std dev
+//!                                              │ |z| >= threshold, after warm-up
+//!                                              v
+//!                         AnomalyDetected { metric, value, baseline, window }
+//! ```
+//!
+//! The events are published like any other, so automation rules
+//! (`anomaly_detected >= 1 within 1h`) and owner notifications pick them
+//! up. Latency and token usage are averaged over the window's completed
+//! responses only.
+
+use crate::events::{AgentEvent, AnomalyDetectedEvent, BehaviorMetric, MetricWindow};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::AgentId;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// How windows are formed and judged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnomalyPolicy {
+    /// Responses per window
+    pub window_size: u32,
+
+    /// Weight of the newest window in the baseline, 0.0 to 1.0
+    pub smoothing: f64,
+
+    /// Standard deviations from the baseline that count as anomalous
+    pub z_threshold: f64,
+
+    /// Windows folded into a baseline before it is trusted
+    pub warmup_windows: u32,
+}
+
+impl Default for AnomalyPolicy {
+    fn default() -> Self {
+        Self {
+            window_size: 20,
+            smoothing: 0.3,
+            z_threshold: 3.0,
+            warmup_windows: 5,
+        }
+    }
+}
+
+/// Smallest standard deviation a baseline is judged with, so a perfectly
+/// steady agent is not flagged for one slow response or one failure
+fn min_std_dev(metric: BehaviorMetric) -> f64 {
+    match metric {
+        BehaviorMetric::ErrorRate => 0.05,
+        BehaviorMetric::LatencyMs => 50.0,
+        BehaviorMetric::TokenUsage => 10.0,
+    }
+}
+
+/// Exponentially weighted mean and variance
+#[derive(Debug, Clone, Copy, Default)]
+struct Baseline {
+    mean: f64,
+    variance: f64,
+    windows: u32,
+}
+
+impl Baseline {
+    fn fold(&mut self, value: f64, smoothing: f64) {
+        if self.windows == 0 {
+            self.mean = value;
+        } else {
+            let diff = value - self.mean;
+            let step = smoothing * diff;
+            self.mean += step;
+            self.variance = (1.0 - smoothing) * (self.variance + diff * step);
+        }
+        self.windows += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct AgentWindow {
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    responses: u32,
+    failures: u32,
+    latency_ms: f64,
+    tokens: f64,
+    baselines: HashMap<BehaviorMetric, Baseline>,
+}
+
+/// Detects anomalous windows of agent behavior
+#[derive(Debug, Default)]
+pub struct AnomalyDetector {
+    policy: AnomalyPolicy,
+    agents: RwLock<HashMap<AgentId, AgentWindow>>,
+}
+
+impl AnomalyDetector {
+    /// Detector with the default policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: windowing and thresholds
+    pub fn with_policy(mut self, policy: AnomalyPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Record a stored event, returning anomalies in the window it closes
+    pub fn observe(&self, envelope: &EventEnvelope) -> Vec<AnomalyDetectedEvent> {
+        let (at, outcome) = match &envelope.event {
+            AgentEvent::ResponseCompleted(e) => (
+                e.completed_at,
+                Some((e.duration_ms as f64, f64::from(e.token_usage.total_tokens))),
+            ),
+            AgentEvent::ResponseFailed(e) => (e.failed_at, None),
+            _ => return Vec::new(),
+        };
+        let agent_id = envelope.aggregate_id;
+        let mut agents = self.agents.write().unwrap_or_else(|e| e.into_inner());
+        let window = agents.entry(agent_id).or_default();
+
+        window.start = Some(window.start.map_or(at, |start| start.min(at)));
+        window.end = Some(window.end.map_or(at, |end| end.max(at)));
+        window.responses += 1;
+        match outcome {
+            Some((latency_ms, tokens)) => {
+                window.latency_ms += latency_ms;
+                window.tokens += tokens;
+            }
+            None => window.failures += 1,
+        }
+        if window.responses < self.policy.window_size.max(1) {
+            return Vec::new();
+        }
+        self.close(agent_id, window)
+    }
+
+    /// Judge a full window against the baselines, then fold it in
+    fn close(&self, agent_id: AgentId, window: &mut AgentWindow) -> Vec<AnomalyDetectedEvent> {
+        let completed = window.responses - window.failures;
+        let mut values = vec![(
+            BehaviorMetric::ErrorRate,
+            f64::from(window.failures) / f64::from(window.responses),
+        )];
+        if completed > 0 {
+            values.push((
+                BehaviorMetric::LatencyMs,
+                window.latency_ms / f64::from(completed),
+            ));
+            values.push((
+                BehaviorMetric::TokenUsage,
+                window.tokens / f64::from(completed),
+            ));
+        }
+        let span = MetricWindow {
+            start: window.start.unwrap_or_default(),
+            end: window.end.unwrap_or_default(),
+            samples: window.responses,
+        };
+
+        let mut anomalies = Vec::new();
+        for (metric, value) in values {
+            let baseline = window.baselines.entry(metric).or_default();
+            if baseline.windows >= self.policy.warmup_windows {
+                let std_dev = baseline.variance.sqrt().max(min_std_dev(metric));
+                if (value - baseline.mean).abs() >= self.policy.z_threshold * std_dev {
+                    anomalies.push(AnomalyDetectedEvent::new(
+                        agent_id,
+                        metric,
+                        value,
+                        baseline.mean,
+                        std_dev,
+                        span,
+                    ));
+                }
+            }
+            baseline.fold(value, self.policy.smoothing);
+        }
+
+        window.start = None;
+        window.end = None;
+        window.responses = 0;
+        window.failures = 0;
+        window.latency_ms = 0.0;
+        window.tokens = 0.0;
+        anomalies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ResponseCompletedEvent, ResponseErrorType, ResponseFailedEvent};
+    use crate::test_support::envelope;
+    use crate::value_objects::{FinishReason, MessageId, TokenUsage};
+
+    fn completed(agent_id: AgentId, duration_ms: u64) -> EventEnvelope {
+        envelope(1, AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+            agent_id,
+            MessageId::new(),
+            4,
+            TokenUsage::new(100, 50),
+            FinishReason::Stop,
+            duration_ms,
+        )))
+    }
+
+    fn failed(agent_id: AgentId) -> EventEnvelope {
+        envelope(1, AgentEvent::ResponseFailed(ResponseFailedEvent::new(
+            agent_id,
+            MessageId::new(),
+            ResponseErrorType::Timeout,
+            "timed out",
+            true,
+        )))
+    }
+
+    #[test]
+    fn test_flags_windows_that_stray_from_baseline() {
+        let detector = AnomalyDetector::new().with_policy(AnomalyPolicy {
+            window_size: 4,
+            warmup_windows: 3,
+            ..AnomalyPolicy::default()
+        });
+        let agent_id = AgentId::new();
+
+        // Warm up on steady, healthy windows
+        for i in 0..12 {
+            let latency = 800 + (i % 4) * 10;
+            assert!(detector.observe(&completed(agent_id, latency)).is_empty());
+        }
+
+        // A window that is half failures and four times slower
+        assert!(detector.observe(&failed(agent_id)).is_empty());
+        assert!(detector.observe(&completed(agent_id, 3300)).is_empty());
+        assert!(detector.observe(&failed(agent_id)).is_empty());
+        let anomalies = detector.observe(&completed(agent_id, 3300));
+
+        let metrics: Vec<BehaviorMetric> = anomalies.iter().map(|a| a.metric).collect();
+        assert_eq!(
+            metrics,
+            vec![BehaviorMetric::ErrorRate, BehaviorMetric::LatencyMs]
+        );
+        assert_eq!(anomalies[0].value, 0.5);
+        assert_eq!(anomalies[0].baseline, 0.0);
+        assert!(anomalies[1].z_score > 3.0);
+        assert_eq!(anomalies[1].window.samples, 4);
+    }
+}
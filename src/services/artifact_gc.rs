@@ -0,0 +1,343 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Artifact Garbage Collection
+//!
+//! Blobs, embeddings and workspace files outlive the events that referenced
+//! them once conversations are archived, shredded or aged out. The collector
+//! marks every id still referenced from the event streams, then sweeps each
+//! [`ArtifactStore`] for artifacts nobody references:
+//!
+//! ```text
+//! event streams ──> LiveReferences::scan_stream ──> live ids per kind
+//!                                                           │
+//! ArtifactStore::list_artifacts ──> kind tracked? ──> unreferenced? ──> older than grace?
+//!                                                                            │ yes
+//!                                                                            v
+//!                                           ArtifactStore::delete_artifact ──> GcReport
+//! ```
+//!
+//! References are found by field name anywhere in an event's JSON. Events
+//! only record blobs (`cid`), so only blob stores are swept by default; a
+//! host whose events name embeddings or workspace files registers the field
+//! with [`LiveReferences::with_field`], otherwise stores of those kinds are
+//! left alone rather than emptied. The grace period protects artifacts
+//! uploaded just before the event that references them is written, and
+//! artifacts whose store time is unknown.
+
+use crate::infrastructure::{ArtifactKind, ArtifactStore, DomainError, DomainResult};
+use async_nats::jetstream;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Ids referenced from the event streams, per artifact kind
+#[derive(Debug, Clone)]
+pub struct LiveReferences {
+    fields: HashMap<String, ArtifactKind>,
+    live: HashMap<ArtifactKind, HashSet<String>>,
+}
+
+impl LiveReferences {
+    /// Recognise `cid` fields, the blob references events record
+    pub fn new() -> Self {
+        Self {
+            fields: HashMap::new(),
+            live: HashMap::new(),
+        }
+        .with_field("cid", ArtifactKind::Blob)
+    }
+
+    /// Builder: treat values of `field` as references to `kind`
+    pub fn with_field(mut self, field: impl Into<String>, kind: ArtifactKind) -> Self {
+        self.fields.insert(field.into(), kind);
+        self
+    }
+
+    /// Mark one id as live
+    pub fn insert(&mut self, kind: ArtifactKind, id: impl Into<String>) {
+        self.live.entry(kind).or_default().insert(id.into());
+    }
+
+    /// Whether `id` is referenced
+    pub fn contains(&self, kind: ArtifactKind, id: &str) -> bool {
+        self.live.get(&kind).is_some_and(|ids| ids.contains(id))
+    }
+
+    /// Whether some field references `kind`, so its unreferenced artifacts are garbage
+    pub fn tracks(&self, kind: ArtifactKind) -> bool {
+        self.fields.values().any(|tracked| *tracked == kind)
+    }
+
+    /// Number of live ids of `kind`
+    pub fn count(&self, kind: ArtifactKind) -> usize {
+        self.live.get(&kind).map_or(0, HashSet::len)
+    }
+
+    /// Mark every reference in a serialized event or envelope
+    pub fn observe_payload(&mut self, payload: &[u8]) -> DomainResult<()> {
+        let value: Value = serde_json::from_slice(payload)
+            .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+        self.observe(&value);
+        Ok(())
+    }
+
+    /// Mark every reference in the messages `stream` holds, returning how many were read
+    ///
+    /// Replays the stream from its first message with an ordered consumer up
+    /// to the last message stored when the scan began. A message that is not
+    /// JSON fails the scan, since references it holds could not be seen.
+    pub async fn scan_stream(
+        &mut self,
+        stream: &mut jetstream::stream::Stream,
+    ) -> DomainResult<u64> {
+        let last = stream.info().await.map_err(scan_error)?.state.last_sequence;
+        if last == 0 {
+            return Ok(0);
+        }
+        let consumer = stream
+            .create_consumer(jetstream::consumer::pull::OrderedConfig {
+                deliver_policy: jetstream::consumer::DeliverPolicy::All,
+                ..Default::default()
+            })
+            .await
+            .map_err(scan_error)?;
+        let mut messages = consumer.messages().await.map_err(scan_error)?;
+        let mut scanned = 0;
+        while let Some(message) = messages.next().await {
+            let message = message.map_err(scan_error)?;
+            let sequence = message.info().map_err(scan_error)?.stream_sequence;
+            self.observe_payload(&message.payload)?;
+            scanned += 1;
+            if sequence >= last {
+                break;
+            }
+        }
+        Ok(scanned)
+    }
+
+    /// Mark every reference in an event
+    pub fn observe_event(&mut self, event: &impl Serialize) -> DomainResult<()> {
+        let value = serde_json::to_value(event)
+            .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+        self.observe(&value);
+        Ok(())
+    }
+
+    /// Mark every reference in a JSON value
+    pub fn observe(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                for (field, value) in map {
+                    if let Some(&kind) = self.fields.get(field) {
+                        self.mark(kind, value);
+                    }
+                    self.observe(value);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| self.observe(item)),
+            _ => {}
+        }
+    }
+
+    fn mark(&mut self, kind: ArtifactKind, value: &Value) {
+        match value {
+            Value::String(id) => self.insert(kind, id.clone()),
+            Value::Array(ids) => ids.iter().for_each(|id| self.mark(kind, id)),
+            _ => {}
+        }
+    }
+}
+
+impl Default for LiveReferences {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn scan_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::EventStoreError(format!("reference scan failed: {}", e))
+}
+
+/// A store the collector could not list, or an artifact it could not delete
+#[derive(Debug, Clone, Serialize)]
+pub struct GcFailure {
+    pub kind: ArtifactKind,
+
+    /// The artifact, or `None` when listing the store failed
+    pub id: Option<String>,
+
+    pub error: String,
+}
+
+/// What one collection run found and reclaimed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    /// Artifacts listed across all stores
+    pub scanned: usize,
+
+    /// Artifacts kept because an event references them
+    pub live: usize,
+
+    /// Unreferenced artifacts kept because they are inside the grace period,
+    /// or their store time is unknown
+    pub within_grace: usize,
+
+    /// Kinds whose stores were not swept because no event field references them
+    pub untracked: BTreeSet<ArtifactKind>,
+
+    /// Artifacts deleted, per kind
+    pub deleted: BTreeMap<ArtifactKind, usize>,
+
+    /// Bytes reclaimed, per kind
+    pub reclaimed_bytes: BTreeMap<ArtifactKind, u64>,
+
+    /// Listings and deletions that failed; those artifacts are still stored
+    pub failures: Vec<GcFailure>,
+}
+
+impl GcReport {
+    /// Total artifacts deleted
+    pub fn total_deleted(&self) -> usize {
+        self.deleted.values().sum()
+    }
+
+    /// Total bytes reclaimed
+    pub fn total_reclaimed_bytes(&self) -> u64 {
+        self.reclaimed_bytes.values().sum()
+    }
+}
+
+/// Deletes unreferenced artifacts past a grace period
+pub struct ArtifactCollector {
+    grace: Duration,
+    stores: Vec<Arc<dyn ArtifactStore>>,
+}
+
+impl ArtifactCollector {
+    /// Collect unreferenced artifacts older than `grace`
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            stores: Vec::new(),
+        }
+    }
+
+    /// Builder: sweep `store`
+    pub fn with_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.stores.push(store);
+        self
+    }
+
+    /// Delete every artifact not in `live` and stored before `now - grace`
+    ///
+    /// Stores of kinds `live` does not track are skipped, and artifacts
+    /// whose store time is unknown are kept. Failures are reported rather
+    /// than returned, so one unreachable store does not stop the others
+    /// being swept.
+    pub async fn collect(&self, live: &LiveReferences, now: DateTime<Utc>) -> GcReport {
+        let grace = chrono::Duration::from_std(self.grace).unwrap_or(chrono::Duration::MAX);
+        let cutoff = now
+            .checked_sub_signed(grace)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let mut report = GcReport::default();
+
+        for store in &self.stores {
+            let kind = store.kind();
+            if !live.tracks(kind) {
+                report.untracked.insert(kind);
+                continue;
+            }
+            let artifacts = match store.list_artifacts().await {
+                Ok(artifacts) => artifacts,
+                Err(e) => {
+                    report.failures.push(GcFailure {
+                        kind,
+                        id: None,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            report.scanned += artifacts.len();
+
+            for artifact in artifacts {
+                if live.contains(kind, &artifact.id) {
+                    report.live += 1;
+                    continue;
+                }
+                if artifact.stored_at.is_none_or(|at| at > cutoff) {
+                    report.within_grace += 1;
+                    continue;
+                }
+                match store.delete_artifact(&artifact.id).await {
+                    Ok(()) => {
+                        *report.deleted.entry(kind).or_default() += 1;
+                        *report.reclaimed_bytes.entry(kind).or_default() += artifact.size_bytes;
+                    }
+                    Err(e) => report.failures.push(GcFailure {
+                        kind,
+                        id: Some(artifact.id),
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        }
+
+        tracing::info!(
+            scanned = report.scanned,
+            deleted = report.total_deleted(),
+            reclaimed_bytes = report.total_reclaimed_bytes(),
+            failures = report.failures.len(),
+            "Artifact garbage collection finished"
+        );
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentEvent, MessageSentEvent};
+    use crate::infrastructure::{BlobStore, InMemoryBlobStore};
+    use crate::value_objects::{AgentId, ContentRef, MessageId};
+
+    #[tokio::test]
+    async fn test_collect_deletes_only_old_unreferenced_blobs() {
+        let blobs = Arc::new(InMemoryBlobStore::new());
+        let kept = ContentRef::of(b"referenced document");
+        let orphan = ContentRef::of(b"abandoned upload");
+        blobs
+            .put(&kept, b"referenced document".to_vec())
+            .await
+            .unwrap();
+        blobs
+            .put(&orphan, b"abandoned upload".to_vec())
+            .await
+            .unwrap();
+
+        let event = AgentEvent::MessageSent(
+            MessageSentEvent::new(AgentId::new(), MessageId::new(), "")
+                .with_content_ref(Some(kept.clone())),
+        );
+        let mut live = LiveReferences::new();
+        live.observe_event(&event).unwrap();
+        assert!(live.contains(ArtifactKind::Blob, &kept.cid));
+        assert!(!live.tracks(ArtifactKind::Embedding));
+
+        let collector = ArtifactCollector::new(Duration::from_secs(3600)).with_store(blobs.clone());
+        let report = collector.collect(&live, Utc::now()).await;
+        assert_eq!((report.live, report.within_grace), (1, 1));
+        assert_eq!(report.total_deleted(), 0);
+
+        let later = Utc::now() + chrono::Duration::hours(2);
+        let report = collector.collect(&live, later).await;
+        assert_eq!(report.deleted[&ArtifactKind::Blob], 1);
+        assert_eq!(report.total_reclaimed_bytes(), orphan.size_bytes);
+        assert!(blobs.get(&orphan).await.unwrap().is_none());
+        assert!(blobs.get(&kept).await.unwrap().is_some());
+    }
+}
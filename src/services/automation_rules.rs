@@ -0,0 +1,557 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Automation Rules
+//!
+//! Event-triggered automations operators write as data rather than Rust.
+//! A rule counts matching events per agent over a sliding window and, when
+//! the count reaches its threshold, issues a command against that agent:
+//!
+//! ```yaml
+//! - name: suspend-flaky
+//!   when: response_failed >= 5 within 10m
+//!   then: { action: suspend, reason: Failed 5 messages in 10 minutes }
+//!   run_as: operator
+//! - name: retire-broken-tools
+//!   agent: 0193...
+//!   when: tool_failed|response_failed >= 20 within 1h
+//!   then: { action: decommission, reason: Persistent failures }
+//!   run_as: owner
+//! ```
+//!
+//! ```text
+//! EventEnvelope ──> evaluate() ──> window per (rule, agent) ──> count >= n?
+//!                                                                 │ yes
+//!                                                                 v
+//!                                          RuleFiring { rule, command } ──> dispatch
+//! ```
+//!
+//! Events are counted at their own timestamps, so a replay fires the same
+//! rules. A rule's window is emptied when it fires; it fires again only
+//! after the threshold is reached anew. Rules are added, replaced and
+//! removed at runtime with [`RuleRequest`]s.
+//!
+//! A rule's command acts as the principal class in `run_as`, so the agent's
+//! permissions apply to it like any other caller; a rule without one acts
+//! as the least privileged class. A [`RuleBucket`] shares rules through a
+//! NATS KV bucket, and every instance following it holds the same rules.
+
+use crate::commands::{AgentCommand, DecommissionAgent, SuspendAgent};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, PrincipalClass};
+use async_nats::jetstream::{self, kv::Operation, kv::Store as KvStore};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+use thiserror::Error;
+
+/// Errors defining rules
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RuleError {
+    #[error("Invalid rule condition `{condition}`: {reason}")]
+    Condition { condition: String, reason: String },
+
+    #[error("Rule name cannot be empty")]
+    EmptyName,
+
+    #[error("Rule name `{0}` may only use letters, digits, `-` and `_`")]
+    InvalidName(String),
+
+    #[error("No rule named {0}")]
+    Unknown(String),
+}
+
+/// Result type for rule operations
+pub type RuleResult<T> = Result<T, RuleError>;
+
+/// `<event>[|<event>...] >= <count> within <window>`
+///
+/// Events are named as in their NATS subjects (`response_failed`), and
+/// windows are whole seconds, minutes or hours (`90s`, `10m`, `1h`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RuleCondition {
+    /// Event types counted
+    pub events: Vec<String>,
+
+    /// Matching events needed to fire
+    pub threshold: usize,
+
+    /// Sliding window the events must fall in
+    pub window: Duration,
+}
+
+impl RuleCondition {
+    /// Whether `event_type` is counted
+    pub fn matches(&self, event_type: &str) -> bool {
+        self.events.iter().any(|e| e == event_type)
+    }
+}
+
+impl FromStr for RuleCondition {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> RuleResult<Self> {
+        let invalid = |reason: &str| RuleError::Condition {
+            condition: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let (events, rest) = s.split_once(">=").ok_or_else(|| invalid("expected `>=`"))?;
+        let (threshold, window) = rest
+            .split_once("within")
+            .ok_or_else(|| invalid("expected `within`"))?;
+
+        let events: Vec<String> = events
+            .split('|')
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+            .collect();
+        if events.is_empty() {
+            return Err(invalid("no event types"));
+        }
+        let threshold: usize = threshold
+            .trim()
+            .parse()
+            .map_err(|_| invalid("count is not a number"))?;
+        if threshold == 0 {
+            return Err(invalid("count must be at least 1"));
+        }
+
+        let window = window.trim();
+        let split = window.char_indices().last().map_or(0, |(i, _)| i);
+        let amount: i64 = window[..split]
+            .parse()
+            .map_err(|_| invalid("window is not a number"))?;
+        let window = match &window[split..] {
+            "s" => Duration::seconds(amount),
+            "m" => Duration::minutes(amount),
+            "h" => Duration::hours(amount),
+            _ => return Err(invalid("window unit must be s, m or h")),
+        };
+        if window <= Duration::zero() {
+            return Err(invalid("window must be positive"));
+        }
+
+        Ok(Self {
+            events,
+            threshold,
+            window,
+        })
+    }
+}
+
+impl TryFrom<String> for RuleCondition {
+    type Error = RuleError;
+
+    fn try_from(s: String) -> RuleResult<Self> {
+        s.parse()
+    }
+}
+
+impl From<RuleCondition> for String {
+    fn from(condition: RuleCondition) -> Self {
+        condition.to_string()
+    }
+}
+
+impl fmt::Display for RuleCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.window.num_seconds();
+        let window = if secs % 3600 == 0 {
+            format!("{}h", secs / 3600)
+        } else if secs % 60 == 0 {
+            format!("{}m", secs / 60)
+        } else {
+            format!("{}s", secs)
+        };
+        write!(
+            f,
+            "{} >= {} within {}",
+            self.events.join("|"),
+            self.threshold,
+            window
+        )
+    }
+}
+
+/// What a rule does to the agent that tripped it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Suspend the agent
+    Suspend { reason: String },
+    /// Decommission the agent
+    Decommission { reason: String },
+}
+
+impl RuleAction {
+    /// The command carrying out this action on `agent_id`
+    pub fn command(&self, agent_id: AgentId) -> AgentCommand {
+        match self {
+            RuleAction::Suspend { reason } => {
+                AgentCommand::SuspendAgent(SuspendAgent::new(agent_id, reason.clone()))
+            }
+            RuleAction::Decommission { reason } => AgentCommand::DecommissionAgent(
+                DecommissionAgent::new(agent_id).with_reason(reason.clone()),
+            ),
+        }
+    }
+}
+
+/// An event-triggered automation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutomationRule {
+    /// Unique name
+    pub name: String,
+
+    /// Only watch this agent (every agent when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentId>,
+
+    /// When to fire
+    pub when: RuleCondition,
+
+    /// What to do
+    pub then: RuleAction,
+
+    /// Principal class the command acts as
+    #[serde(default = "least_privileged")]
+    pub run_as: PrincipalClass,
+}
+
+fn least_privileged() -> PrincipalClass {
+    PrincipalClass::LEAST_PRIVILEGED
+}
+
+impl AutomationRule {
+    /// Rule firing `then` whenever `when` holds for any agent
+    pub fn new(name: impl Into<String>, when: RuleCondition, then: RuleAction) -> Self {
+        Self {
+            name: name.into(),
+            agent: None,
+            when,
+            then,
+            run_as: PrincipalClass::LEAST_PRIVILEGED,
+        }
+    }
+
+    /// Builder: only watch `agent_id`
+    pub fn for_agent(mut self, agent_id: AgentId) -> Self {
+        self.agent = Some(agent_id);
+        self
+    }
+
+    /// Builder: act as `class`
+    pub fn run_as(mut self, class: PrincipalClass) -> Self {
+        self.run_as = class;
+        self
+    }
+}
+
+/// A rule that fired, and the command it issues
+#[derive(Debug, Clone)]
+pub struct RuleFiring {
+    /// Name of the rule
+    pub rule: String,
+
+    /// The agent that tripped it
+    pub agent_id: AgentId,
+
+    /// Principal class the command acts as
+    pub run_as: PrincipalClass,
+
+    /// Command to dispatch
+    pub command: AgentCommand,
+}
+
+/// Runtime management of the rule set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RuleRequest {
+    /// Add a rule, replacing any of the same name
+    Put { rule: AutomationRule },
+    /// Remove a rule by name
+    Remove { name: String },
+    /// List the rules
+    List,
+}
+
+#[derive(Debug, Default)]
+struct RulesState {
+    rules: Vec<AutomationRule>,
+    windows: HashMap<(String, AgentId), VecDeque<DateTime<Utc>>>,
+}
+
+/// Evaluates automation rules over the event stream
+#[derive(Debug, Default)]
+pub struct AutomationRules {
+    state: RwLock<RulesState>,
+}
+
+impl AutomationRules {
+    /// No rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules from YAML, as a list of [`AutomationRule`]s
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        let rules: Vec<AutomationRule> = serde_yaml::from_str(yaml)?;
+        let engine = Self::new();
+        engine
+            .state
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .rules = rules;
+        Ok(engine)
+    }
+
+    /// The rules, in the order they were added
+    pub fn rules(&self) -> Vec<AutomationRule> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.rules.clone()
+    }
+
+    /// Add a rule, replacing any of the same name and its windows
+    pub fn put(&self, rule: AutomationRule) -> RuleResult<()> {
+        if rule.name.trim().is_empty() {
+            return Err(RuleError::EmptyName);
+        }
+        // Names are KV keys in a RuleBucket
+        if !rule
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(RuleError::InvalidName(rule.name));
+        }
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.windows.retain(|(name, _), _| *name != rule.name);
+        match state.rules.iter_mut().find(|r| r.name == rule.name) {
+            Some(existing) => *existing = rule,
+            None => state.rules.push(rule),
+        }
+        Ok(())
+    }
+
+    /// Remove a rule by name
+    pub fn remove(&self, name: &str) -> RuleResult<AutomationRule> {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        let index = state
+            .rules
+            .iter()
+            .position(|r| r.name == name)
+            .ok_or_else(|| RuleError::Unknown(name.to_string()))?;
+        state.windows.retain(|(rule, _), _| rule != name);
+        Ok(state.rules.remove(index))
+    }
+
+    /// Apply a management request, returning the rules afterwards
+    pub fn apply(&self, request: RuleRequest) -> RuleResult<Vec<AutomationRule>> {
+        match request {
+            RuleRequest::Put { rule } => self.put(rule)?,
+            RuleRequest::Remove { name } => {
+                self.remove(&name)?;
+            }
+            RuleRequest::List => {}
+        }
+        Ok(self.rules())
+    }
+
+    /// Count a stored event against every rule, returning the rules it fires
+    pub fn evaluate(&self, envelope: &EventEnvelope) -> Vec<RuleFiring> {
+        let agent_id = envelope.aggregate_id;
+        let event_type = envelope.event.event_type_name();
+        let at = envelope.event.timestamp();
+
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        let RulesState { rules, windows } = &mut *state;
+        let mut fired = Vec::new();
+        for rule in rules.iter() {
+            if rule.agent.is_some_and(|id| id != agent_id) || !rule.when.matches(event_type) {
+                continue;
+            }
+            let window = windows.entry((rule.name.clone(), agent_id)).or_default();
+            let position = window.partition_point(|t| *t <= at);
+            window.insert(position, at);
+            let newest = *window.back().unwrap_or(&at);
+            while window
+                .front()
+                .is_some_and(|t| newest - *t > rule.when.window)
+            {
+                window.pop_front();
+            }
+            if window.len() >= rule.when.threshold {
+                window.clear();
+                fired.push(RuleFiring {
+                    rule: rule.name.clone(),
+                    agent_id,
+                    run_as: rule.run_as,
+                    command: rule.then.command(agent_id),
+                });
+            }
+        }
+        fired
+    }
+}
+
+/// Automation rules stored in a NATS KV bucket, keyed by rule name
+#[derive(Clone)]
+pub struct RuleBucket {
+    kv: KvStore,
+}
+
+impl RuleBucket {
+    /// Rules stored in `kv`
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Get or create the bucket, keeping only each rule's latest version
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+
+    /// Store a rule, replacing any of the same name
+    pub async fn put(&self, rule: &AutomationRule) -> Result<(), async_nats::Error> {
+        self.kv
+            .put(rule.name.as_str(), serde_json::to_vec(rule)?.into())
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a rule by name
+    pub async fn remove(&self, name: &str) -> Result<(), async_nats::Error> {
+        self.kv.delete(name).await?;
+        Ok(())
+    }
+
+    /// Apply every stored rule to `rules`, then each change as it is made
+    ///
+    /// Returns only when the watch ends.
+    pub async fn follow(&self, rules: &AutomationRules) -> Result<(), async_nats::Error> {
+        let mut watch = self.kv.watch_with_history(">").await?;
+        while let Some(entry) = watch.next().await {
+            let entry = entry?;
+            match entry.operation {
+                Operation::Put => {
+                    let applied = serde_json::from_slice::<AutomationRule>(&entry.value)
+                        .map_err(|e| e.to_string())
+                        .and_then(|rule| rules.put(rule).map_err(|e| e.to_string()));
+                    if let Err(e) = applied {
+                        tracing::warn!("Ignoring stored rule {}: {}", entry.key, e);
+                    }
+                }
+                Operation::Delete | Operation::Purge => {
+                    // Already gone when it was removed on this instance
+                    let _ = rules.remove(&entry.key);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentEvent, ResponseErrorType, ResponseFailedEvent};
+    use crate::test_support::envelope;
+    use crate::value_objects::MessageId;
+
+    fn failed(agent_id: AgentId, minute: i64) -> EventEnvelope {
+        let mut event = ResponseFailedEvent::new(
+            agent_id,
+            MessageId::new(),
+            ResponseErrorType::Timeout,
+            "timed out",
+            true,
+        );
+        event.failed_at = DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute);
+        envelope(1, AgentEvent::ResponseFailed(event))
+    }
+
+    #[test]
+    fn test_condition_round_trips() {
+        let condition: RuleCondition = "tool_failed | response_failed >= 5 within 10m"
+            .parse()
+            .unwrap();
+        assert_eq!(condition.threshold, 5);
+        assert_eq!(condition.window, Duration::minutes(10));
+        assert!(condition.matches("tool_failed"));
+        assert_eq!(
+            condition.to_string(),
+            "tool_failed|response_failed >= 5 within 10m"
+        );
+        assert!("response_failed > 5 within 10m"
+            .parse::<RuleCondition>()
+            .is_err());
+        assert!("response_failed >= 5 within 10d"
+            .parse::<RuleCondition>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_fires_when_window_fills() {
+        let yaml = r#"
+- name: suspend-flaky
+  when: response_failed >= 3 within 10m
+  then: { action: suspend, reason: Flaky }
+"#;
+        let rules = AutomationRules::from_yaml(yaml).unwrap();
+        let (agent, other) = (AgentId::new(), AgentId::new());
+        assert_eq!(rules.rules()[0].run_as, PrincipalClass::LEAST_PRIVILEGED);
+
+        assert!(rules.evaluate(&failed(agent, 0)).is_empty());
+        assert!(rules.evaluate(&failed(other, 1)).is_empty());
+        assert!(rules.evaluate(&failed(agent, 2)).is_empty());
+        // The first failure has slid out of the window
+        assert!(rules.evaluate(&failed(agent, 11)).is_empty());
+        let fired = rules.evaluate(&failed(agent, 12));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].agent_id, agent);
+        assert!(matches!(
+            &fired[0].command,
+            AgentCommand::SuspendAgent(s) if s.reason == "Flaky"
+        ));
+        assert!(rules.evaluate(&failed(agent, 13)).is_empty());
+
+        let remaining = rules
+            .apply(RuleRequest::Remove {
+                name: "suspend-flaky".to_string(),
+            })
+            .unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(
+            rules.remove("suspend-flaky").unwrap_err(),
+            RuleError::Unknown("suspend-flaky".to_string())
+        );
+        let when: RuleCondition = "tool_failed >= 1 within 1m".parse().unwrap();
+        let then = RuleAction::Suspend {
+            reason: "Tool failed".to_string(),
+        };
+        assert!(matches!(
+            rules.put(AutomationRule::new("tool failed", when, then)),
+            Err(RuleError::InvalidName(_))
+        ));
+    }
+}
@@ -0,0 +1,650 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Batch Inference
+//!
+//! Runs a file of prompts through an agent for offline work such as nightly
+//! enrichment jobs. Prompts are answered concurrently; a rate limit pauses
+//! every worker until the provider's retry-after has passed, so a batch
+//! slows down instead of burning its retries. Rate limits are waited out
+//! up to `max_rate_limit_waits` times per prompt, apart from the
+//! `max_attempts` other failures get:
+//!
+//! ```text
+//! JSON Lines prompts (blob or stream)
+//!    │
+//!    v
+//! BatchInference::run ──> moderation ──> budget ──> N workers ──> RateGate ──> ChatPort
+//!    │                                                 │ rate limited: pause all, retry
+//!    │                                                 └──> BatchProgress every n prompts
+//!    v
+//! results JSON Lines (input order, `part_size` per blob) ──> BlobStore
+//!    │
+//!    v
+//! BatchCompleted { results: [cid, ...] }
+//! ```
+//!
+//! Each prompt line is `{"id": "...", "prompt": "..."}`; each result line
+//! repeats the id with the answer or the error that ended it. Results are
+//! stored a part at a time, so a run holds at most one part and the prompts
+//! in flight in memory.
+//!
+//! Only an operational agent runs a batch. Prompts are moderated like sent
+//! messages, and a prompt the guardrail blocks is not sent. Once the run's
+//! budget is spent, the prompts left are recorded as failed without being
+//! sent; tokens count as the provider reports them.
+
+use super::ModerationStage;
+use crate::aggregate::Agent;
+use crate::events::{AgentEvent, BatchCompletedEvent, BatchProgressEvent};
+use crate::infrastructure::{BlobStore, DomainError};
+use crate::ports::{ChatError, ChatPort, ErrorCategory};
+use crate::value_objects::{
+    clock_now, next_id, AgentId, ContentRef, ContextMessage, ConversationBudget, FeatureFlags,
+    FinishReason, ModelConfig,
+};
+use futures::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Default prompts answered at once
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// Default result lines stored per blob
+pub const DEFAULT_BATCH_PART_SIZE: usize = 1_000;
+
+/// Errors that stop a batch as a whole
+#[derive(Debug, Error)]
+pub enum BatchError {
+    #[error("Invalid prompt on line {line}: {reason}")]
+    InvalidPrompt { line: usize, reason: String },
+
+    #[error("Batch input {0} not found in the blob store")]
+    InputMissing(String),
+
+    #[error("Agent {0} has no model configured and no model was selected")]
+    NoModel(String),
+
+    #[error("Agent {0} is not operational - must be active with model configured")]
+    NotOperational(String),
+
+    #[error("Failed to encode batch results: {0}")]
+    Encode(#[from] serde_json::Error),
+
+    #[error("Failed to store batch results: {0}")]
+    Store(#[from] DomainError),
+}
+
+/// Result type for batch runs
+pub type BatchResult<T> = Result<T, BatchError>;
+
+/// One prompt in a batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchPrompt {
+    /// Caller's id, repeated on the result
+    pub id: String,
+
+    /// Text sent as the user message
+    pub prompt: String,
+}
+
+impl BatchPrompt {
+    pub fn new(id: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            prompt: prompt.into(),
+        }
+    }
+
+    /// Parse JSON Lines input, skipping blank lines
+    pub fn parse_jsonl(input: &str) -> BatchResult<Vec<Self>> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line).map_err(|e| BatchError::InvalidPrompt {
+                    line: n + 1,
+                    reason: e.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The outcome of one prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchOutput {
+    /// The prompt's id
+    pub id: String,
+
+    /// The answer, when one was produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// Why generation stopped, if the provider said
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
+
+    /// The error that ended the last attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Requests sent for this prompt
+    pub attempts: u32,
+
+    /// Time spent on this prompt, including retries
+    pub latency_ms: u64,
+}
+
+impl BatchOutput {
+    /// Whether the prompt was answered
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A batch to run, as sent to the service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRequest {
+    /// The agent answering
+    pub agent_id: AgentId,
+
+    /// JSON Lines prompts uploaded to the blob store
+    pub input: ContentRef,
+
+    /// Model answering instead of the agent's configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelConfig>,
+
+    /// Prompts answered at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+
+    /// Limits on the whole run; each prompt counts as a message
+    #[serde(default, skip_serializing_if = "ConversationBudget::is_unlimited")]
+    pub budget: ConversationBudget,
+}
+
+/// Receives batch progress and completion events for publishing
+pub trait BatchEventSink: Send + Sync {
+    /// Hand off one event
+    fn emit(&self, event: AgentEvent);
+}
+
+impl BatchEventSink for mpsc::UnboundedSender<AgentEvent> {
+    fn emit(&self, event: AgentEvent) {
+        // Nobody publishing means nobody watching
+        let _ = self.send(event);
+    }
+}
+
+/// Holds every worker back while a rate limit lasts
+#[derive(Default)]
+struct RateGate {
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl RateGate {
+    async fn wait(&self) {
+        let until = *self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = until {
+            tokio::time::sleep_until(until.into()).await;
+        }
+    }
+
+    fn pause(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut paused = self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        if !paused.is_some_and(|current| current >= until) {
+            *paused = Some(until);
+        }
+    }
+}
+
+/// Counters shared by the workers of one run
+#[derive(Default)]
+struct Tally {
+    succeeded: AtomicU64,
+    failed: AtomicU64,
+    rate_limited: AtomicU64,
+    /// Prompts sent to the provider
+    sent: AtomicU64,
+    /// Tokens the provider reported
+    tokens: AtomicU64,
+}
+
+/// Answers batches of prompts through an agent's model
+pub struct BatchInference {
+    chat: Arc<dyn ChatPort>,
+    blobs: Arc<dyn BlobStore>,
+    events: Option<Arc<dyn BatchEventSink>>,
+    moderation: ModerationStage,
+    budget: ConversationBudget,
+    batch_id: Option<Uuid>,
+    model: Option<ModelConfig>,
+    concurrency: usize,
+    max_attempts: u32,
+    max_rate_limit_waits: u32,
+    progress_every: u64,
+    part_size: usize,
+}
+
+impl BatchInference {
+    /// Send prompts through `chat` and store results in `blobs`
+    pub fn new(chat: Arc<dyn ChatPort>, blobs: Arc<dyn BlobStore>) -> Self {
+        Self {
+            chat,
+            blobs,
+            events: None,
+            moderation: ModerationStage::default(),
+            budget: ConversationBudget::unlimited(),
+            batch_id: None,
+            model: None,
+            concurrency: DEFAULT_BATCH_CONCURRENCY,
+            max_attempts: 3,
+            max_rate_limit_waits: 10,
+            progress_every: 100,
+            part_size: DEFAULT_BATCH_PART_SIZE,
+        }
+    }
+
+    /// Builder: moderate prompts with `moderation` instead of the default stage
+    pub fn with_moderation(mut self, moderation: ModerationStage) -> Self {
+        self.moderation = moderation;
+        self
+    }
+
+    /// Builder: stop sending prompts once `budget` is spent
+    pub fn with_budget(mut self, budget: ConversationBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Builder: identify runs as `batch_id` instead of a new id per run
+    pub fn with_batch_id(mut self, batch_id: Uuid) -> Self {
+        self.batch_id = Some(batch_id);
+        self
+    }
+
+    /// Builder: emit `BatchProgress` and `BatchCompleted` to `sink`
+    pub fn with_events(mut self, sink: Arc<dyn BatchEventSink>) -> Self {
+        self.events = Some(sink);
+        self
+    }
+
+    /// Builder: answer with `model` instead of the agent's configured model
+    pub fn with_model(mut self, model: ModelConfig) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Builder: answer up to `concurrency` prompts at once
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Builder: give up on a prompt after `attempts` failed requests
+    ///
+    /// Rate limits are not counted; see [`Self::with_max_rate_limit_waits`].
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Builder: give up on a prompt rate limited more than `waits` times
+    pub fn with_max_rate_limit_waits(mut self, waits: u32) -> Self {
+        self.max_rate_limit_waits = waits;
+        self
+    }
+
+    /// Builder: store results `lines` to a blob
+    pub fn with_part_size(mut self, lines: usize) -> Self {
+        self.part_size = lines.max(1);
+        self
+    }
+
+    /// Builder: report progress every `prompts` finished prompts
+    pub fn with_progress_every(mut self, prompts: u64) -> Self {
+        self.progress_every = prompts.max(1);
+        self
+    }
+
+    /// Run the JSON Lines prompt file stored as `input`
+    pub async fn run_file(
+        &self,
+        agent: &Agent,
+        input: &ContentRef,
+    ) -> BatchResult<BatchCompletedEvent> {
+        let bytes = self
+            .blobs
+            .get(input)
+            .await?
+            .ok_or_else(|| BatchError::InputMissing(input.cid.clone()))?;
+        let text = String::from_utf8(bytes).map_err(|e| BatchError::InvalidPrompt {
+            line: 0,
+            reason: e.to_string(),
+        })?;
+        self.run(agent, BatchPrompt::parse_jsonl(&text)?).await
+    }
+
+    /// Run `prompts`
+    pub async fn run(
+        &self,
+        agent: &Agent,
+        prompts: Vec<BatchPrompt>,
+    ) -> BatchResult<BatchCompletedEvent> {
+        let total = prompts.len() as u64;
+        self.run_stream(agent, stream::iter(prompts), Some(total))
+            .await
+    }
+
+    /// Run prompts as they arrive; `total` is reported with progress when known
+    ///
+    /// A prompt that fails after its retries is recorded in the results;
+    /// only reading input or storing results fails the batch.
+    pub async fn run_stream(
+        &self,
+        agent: &Agent,
+        prompts: impl Stream<Item = BatchPrompt> + Send,
+        total: Option<u64>,
+    ) -> BatchResult<BatchCompletedEvent> {
+        if !agent.is_operational() {
+            return Err(BatchError::NotOperational(agent.id().to_string()));
+        }
+        let strict = agent.feature_flags().is_enabled(FeatureFlags::GUARDRAILS_STRICT);
+        let config = self
+            .model
+            .clone()
+            .or_else(|| agent.model_config().cloned())
+            .ok_or_else(|| BatchError::NoModel(agent.id().to_string()))?;
+
+        let batch_id = self.batch_id.unwrap_or_else(next_id);
+        let started = Instant::now();
+        let gate = RateGate::default();
+        let tally = Tally::default();
+
+        // Answers come back in input order, so each part is stored once full
+        let mut outputs = prompts
+            .map(|prompt| {
+                let (config, gate, tally) = (&config, &gate, &tally);
+                async move {
+                    let output = self
+                        .answer(agent, config, prompt, strict, started, gate, tally)
+                        .await;
+                    let counter = if output.succeeded() {
+                        &tally.succeeded
+                    } else {
+                        &tally.failed
+                    };
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    self.report_progress(agent, batch_id, tally, total);
+                    output
+                }
+            })
+            .buffered(self.concurrency);
+
+        let mut results = Vec::new();
+        let (mut part, mut lines) = (Vec::new(), 0);
+        while let Some(output) = outputs.next().await {
+            serde_json::to_writer(&mut part, &output)?;
+            part.push(b'\n');
+            lines += 1;
+            if lines == self.part_size {
+                results.push(self.store_part(std::mem::take(&mut part)).await?);
+                lines = 0;
+            }
+        }
+        if lines > 0 {
+            results.push(self.store_part(part).await?);
+        }
+
+        let completed = BatchCompletedEvent::new(
+            agent.id(),
+            batch_id,
+            tally.succeeded.load(Ordering::Relaxed),
+            tally.failed.load(Ordering::Relaxed),
+            results,
+            tally.rate_limited.load(Ordering::Relaxed),
+            started.elapsed().as_millis() as u64,
+        );
+        tracing::info!(
+            batch_id = %batch_id,
+            succeeded = completed.succeeded,
+            failed = completed.failed,
+            rate_limited = completed.rate_limited,
+            "Batch inference finished"
+        );
+        if let Some(events) = &self.events {
+            events.emit(AgentEvent::BatchCompleted(completed.clone()));
+        }
+        Ok(completed)
+    }
+
+    async fn store_part(&self, part: Vec<u8>) -> BatchResult<ContentRef> {
+        let reference = ContentRef::of(&part);
+        self.blobs.put(&reference, part).await?;
+        Ok(reference)
+    }
+
+    fn report_progress(&self, agent: &Agent, batch_id: Uuid, tally: &Tally, total: Option<u64>) {
+        let Some(events) = &self.events else {
+            return;
+        };
+        let succeeded = tally.succeeded.load(Ordering::Relaxed);
+        let failed = tally.failed.load(Ordering::Relaxed);
+        if (succeeded + failed) % self.progress_every == 0 {
+            events.emit(AgentEvent::BatchProgress(BatchProgressEvent::new(
+                agent.id(),
+                batch_id,
+                succeeded,
+                failed,
+                total,
+            )));
+        }
+    }
+
+    /// Answer one prompt, retrying transient failures and waiting out rate limits
+    #[allow(clippy::too_many_arguments)]
+    async fn answer(
+        &self,
+        agent: &Agent,
+        config: &ModelConfig,
+        prompt: BatchPrompt,
+        strict: bool,
+        run_started: Instant,
+        gate: &RateGate,
+        tally: &Tally,
+    ) -> BatchOutput {
+        let started = Instant::now();
+        let refused = |prompt: BatchPrompt, reason: String| BatchOutput {
+            id: prompt.id,
+            text: None,
+            finish_reason: None,
+            error: Some(reason),
+            attempts: 0,
+            latency_ms: started.elapsed().as_millis() as u64,
+        };
+        if self.moderation.assess(&prompt.prompt, strict).await.is_blocked() {
+            return refused(prompt, "Prompt blocked by content guardrail".to_string());
+        }
+        let spent = self.budget.exhausted(
+            tally.tokens.load(Ordering::Relaxed),
+            u32::try_from(tally.sent.fetch_add(1, Ordering::Relaxed)).unwrap_or(u32::MAX),
+            run_started.elapsed(),
+        );
+        if let Some(limit) = spent {
+            return refused(prompt, format!("Batch {} budget exhausted", limit));
+        }
+
+        // Same preamble as the message service: system prompt, then local time
+        let mut context = Vec::new();
+        if let Some(system_prompt) = agent.system_prompt().filter(|p| !p.is_empty()) {
+            context.push(ContextMessage::system(system_prompt));
+        }
+        if let Some(locale) = agent.locale() {
+            context.push(ContextMessage::system(locale.context_note(clock_now())));
+        }
+        context.push(ContextMessage::user(&prompt.prompt));
+
+        let (mut attempts, mut failures, mut waits) = (0, 0, 0);
+        let outcome = loop {
+            gate.wait().await;
+            attempts += 1;
+            let error = match self.complete(config, context.clone(), tally).await {
+                Ok(answer) => break Ok(answer),
+                Err(e) => e,
+            };
+
+            if error.category() == ErrorCategory::RateLimited {
+                tally.rate_limited.fetch_add(1, Ordering::Relaxed);
+                waits += 1;
+                if waits > self.max_rate_limit_waits {
+                    break Err(error);
+                }
+                let delay = error.retry_delay_ms().unwrap_or(1_000 << waits.min(6));
+                gate.pause(Duration::from_millis(delay));
+            } else if error.is_recoverable() {
+                failures += 1;
+                if failures >= self.max_attempts {
+                    break Err(error);
+                }
+                let delay = error.retry_delay_ms().unwrap_or(500);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            } else {
+                break Err(error);
+            }
+        };
+
+        let (text, finish_reason, error) = match outcome {
+            Ok((text, finish_reason)) => (Some(text), finish_reason, None),
+            Err(e) => (None, None, Some(e.to_string())),
+        };
+        BatchOutput {
+            id: prompt.id,
+            text,
+            finish_reason,
+            error,
+            attempts,
+            latency_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+
+    async fn complete(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+        tally: &Tally,
+    ) -> Result<(String, Option<FinishReason>), ChatError> {
+        let mut stream = self.chat.send(config, context).await?;
+        let mut text = String::new();
+        let mut finish_reason = None;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            text.push_str(&chunk.content);
+            finish_reason = chunk.finish_reason.or(finish_reason);
+            if let Some(usage) = chunk.usage {
+                let tokens = u64::from(usage.total_tokens);
+                tally.tokens.fetch_add(tokens, Ordering::Relaxed);
+            }
+        }
+        Ok((text, finish_reason))
+    }
+}
+
+impl std::fmt::Debug for BatchInference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchInference")
+            .field("chat", &self.chat.provider_name())
+            .field("model", &self.model)
+            .field("concurrency", &self.concurrency)
+            .field("max_attempts", &self.max_attempts)
+            .field("max_rate_limit_waits", &self.max_rate_limit_waits)
+            .field("budget", &self.budget)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentDeployedEvent, ModelConfiguredEvent};
+    use crate::infrastructure::InMemoryBlobStore;
+    use crate::test_support::ScriptedChat;
+    use crate::value_objects::{AgentId, PersonId, StreamingChunk};
+    use std::sync::atomic::AtomicBool;
+
+    /// Echoes prompts, rate limiting the first request it sees
+    fn echo() -> impl ChatPort {
+        let limited = AtomicBool::new(false);
+        ScriptedChat::new(move |_, context| {
+            if !limited.swap(true, Ordering::SeqCst) {
+                return Err(ChatError::RateLimitExceeded {
+                    retry_after_secs: Some(0),
+                });
+            }
+            let prompt = context.last().map(|m| m.content.as_str()).unwrap_or_default();
+            Ok(vec![
+                StreamingChunk::new(0, prompt.to_uppercase()),
+                StreamingChunk::completion(1, FinishReason::Stop),
+            ])
+        })
+    }
+
+    #[tokio::test]
+    async fn test_batch_waits_out_rate_limits_and_stores_results_in_order() {
+        let id = AgentId::new();
+        let agent = Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                    id,
+                    PersonId::new(),
+                    "enricher",
+                    None,
+                )),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(id)),
+            ])
+            .unwrap();
+        let blobs = Arc::new(InMemoryBlobStore::new());
+        let input = b"{\"id\":\"a\",\"prompt\":\"one\"}\n\n{\"id\":\"b\",\"prompt\":\"two\"}\n";
+        let input_ref = ContentRef::of(input);
+        blobs.put(&input_ref, input.to_vec()).await.unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let batch = BatchInference::new(Arc::new(echo()), blobs.clone())
+            .with_model(ModelConfig::mock())
+            .with_events(Arc::new(tx))
+            .with_progress_every(1)
+            .with_max_attempts(1)
+            .with_part_size(1);
+        let completed = batch.run_file(&agent, &input_ref).await.unwrap();
+        assert_eq!((completed.succeeded, completed.failed), (2, 0));
+        assert_eq!(completed.rate_limited, 1);
+
+        let mut outputs = Vec::new();
+        for part in &completed.results {
+            let lines = String::from_utf8(blobs.get(part).await.unwrap().unwrap()).unwrap();
+            outputs.extend(lines.lines().map(|line| {
+                serde_json::from_str::<BatchOutput>(line).unwrap()
+            }));
+        }
+        assert_eq!(completed.results.len(), 2);
+        assert_eq!(outputs[0].id, "a");
+        assert_eq!(outputs[1].text.as_deref(), Some("TWO"));
+
+        let mut kinds = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            kinds.push(event.event_type_name());
+        }
+        assert_eq!(
+            kinds,
+            ["batch_progress", "batch_progress", "batch_completed"]
+        );
+    }
+}
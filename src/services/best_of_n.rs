@@ -0,0 +1,294 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Best-of-N sampling
+//!
+//! Generates several candidate answers to the same context in parallel and
+//! lets a [`Judge`] pick one. Candidates that fail are kept, with their
+//! error, so the record of what was considered is complete:
+//!
+//! ```text
+//! context ──┬──> ChatPort(model 1) ──> candidate ─┐
+//!           ├──> ChatPort(model 2) ──> candidate ─┼──> Judge::select ──> Selection
+//!           └──> ChatPort(model 1) ──> candidate ─┘   (heuristic or model)
+//! ```
+
+use crate::ports::{ChatError, ChatPort, ChatResult};
+use crate::value_objects::{
+    ContextMessage, FinishReason, MessageRole, ModelConfig, ResponseCandidate,
+};
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// The judge's choice among candidates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    /// Index of the chosen candidate
+    pub index: usize,
+
+    /// Why it was chosen
+    pub rationale: String,
+}
+
+/// All candidates for one request and the judge's pick
+#[derive(Debug, Clone, PartialEq)]
+pub struct JudgedCandidates {
+    /// Name of the judge that chose
+    pub judge: String,
+
+    /// Index of the chosen candidate
+    pub selected: usize,
+
+    /// Why it was chosen
+    pub rationale: String,
+
+    /// Every candidate, in generation order
+    pub candidates: Vec<ResponseCandidate>,
+}
+
+/// Chooses the best of several candidate answers
+#[async_trait]
+pub trait Judge: Send + Sync {
+    /// Short name recorded with the selection
+    fn name(&self) -> String;
+
+    /// Pick one usable candidate for `context`
+    async fn select(
+        &self,
+        context: &[ContextMessage],
+        candidates: &[ResponseCandidate],
+    ) -> ChatResult<Selection>;
+}
+
+/// Prefers answers that finished normally, then the longest
+///
+/// Length stands in for completeness; it costs nothing, which makes it the
+/// default for intents that only need protection against failed or cut-off
+/// generations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicJudge;
+
+#[async_trait]
+impl Judge for HeuristicJudge {
+    fn name(&self) -> String {
+        "heuristic".to_string()
+    }
+
+    async fn select(
+        &self,
+        _context: &[ContextMessage],
+        candidates: &[ResponseCandidate],
+    ) -> ChatResult<Selection> {
+        let usable = candidates.iter().filter(|c| c.is_usable()).count();
+        let (index, best) = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_usable())
+            .max_by_key(|(_, c)| (finished(c), c.content.chars().count()))
+            .ok_or_else(|| ChatError::ProviderError("No candidate produced an answer".into()))?;
+
+        Ok(Selection {
+            index,
+            rationale: format!(
+                "Longest {} of {} usable candidates ({} chars)",
+                if finished(best) { "finished answer" } else { "answer" },
+                usable,
+                best.content.chars().count()
+            ),
+        })
+    }
+}
+
+/// Whether generation stopped on its own rather than being cut off
+fn finished(candidate: &ResponseCandidate) -> bool {
+    matches!(candidate.finish_reason, None | Some(FinishReason::Stop))
+}
+
+/// Asks a model to compare the candidates
+///
+/// The model replies with the number of the best candidate and a sentence
+/// of reasoning. An unusable reply falls back to [`HeuristicJudge`], noting
+/// why in the rationale.
+#[derive(Clone)]
+pub struct ModelJudge {
+    chat: Arc<dyn ChatPort>,
+    config: ModelConfig,
+}
+
+impl ModelJudge {
+    /// Judge with `config` through `chat`
+    pub fn new(chat: Arc<dyn ChatPort>, config: ModelConfig) -> Self {
+        Self { chat, config }
+    }
+
+    fn prompt(context: &[ContextMessage], candidates: &[ResponseCandidate]) -> String {
+        let question = context
+            .iter()
+            .rev()
+            .find(|m| m.source.is_none() && m.role != MessageRole::System)
+            .map(|m| m.content.as_str())
+            .unwrap_or_default();
+        let mut prompt = format!("Question:\n{}\n", question);
+        for (i, candidate) in candidates.iter().enumerate() {
+            if candidate.is_usable() {
+                prompt.push_str(&format!("\nCandidate {}:\n{}\n", i + 1, candidate.content));
+            }
+        }
+        prompt
+    }
+}
+
+/// Instructions for [`ModelJudge`]
+const JUDGE_INSTRUCTIONS: &str = "You compare candidate answers to a question. Reply with the \
+number of the best candidate on the first line, then one sentence explaining the choice.";
+
+#[async_trait]
+impl Judge for ModelJudge {
+    fn name(&self) -> String {
+        format!("model:{}/{}", self.config.provider, self.config.model_name)
+    }
+
+    async fn select(
+        &self,
+        context: &[ContextMessage],
+        candidates: &[ResponseCandidate],
+    ) -> ChatResult<Selection> {
+        let messages = vec![
+            ContextMessage::system(JUDGE_INSTRUCTIONS),
+            ContextMessage::user(Self::prompt(context, candidates)),
+        ];
+        let reply = sample(self.chat.as_ref(), &self.config, messages).await;
+
+        let choice = reply.error.is_none().then(|| parse_choice(&reply.content)).flatten();
+        match choice.filter(|&i| candidates.get(i).is_some_and(|c| c.is_usable())) {
+            Some(index) => {
+                let rationale = reply.content.lines().skip(1).collect::<Vec<_>>().join(" ");
+                Ok(Selection {
+                    index,
+                    rationale: rationale.trim().to_string(),
+                })
+            }
+            None => {
+                let fallback = HeuristicJudge.select(context, candidates).await?;
+                Ok(Selection {
+                    rationale: format!("Judge reply unusable; {}", fallback.rationale),
+                    ..fallback
+                })
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for ModelJudge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelJudge")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Zero-based candidate index from the first number in a judge's reply
+fn parse_choice(reply: &str) -> Option<usize> {
+    let digits: String = reply
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse::<usize>().ok()?.checked_sub(1)
+}
+
+/// Generate candidates in parallel, one per `(chat, config)` pair
+pub async fn generate_candidates(
+    samplers: Vec<(Option<Arc<dyn ChatPort>>, ModelConfig)>,
+    context: &[ContextMessage],
+) -> Vec<ResponseCandidate> {
+    join_all(samplers.into_iter().map(|(chat, config)| async move {
+        match chat {
+            Some(chat) => sample(chat.as_ref(), &config, context.to_vec()).await,
+            None => ResponseCandidate {
+                provider: config.provider,
+                model: config.model_name.clone(),
+                content: String::new(),
+                finish_reason: None,
+                error: Some(format!("No adapter registered for {}", config.provider)),
+            },
+        }
+    }))
+    .await
+}
+
+/// Collect a full answer, keeping the failure if there is one
+pub(super) async fn sample(
+    chat: &dyn ChatPort,
+    config: &ModelConfig,
+    context: Vec<ContextMessage>,
+) -> ResponseCandidate {
+    let mut content = String::new();
+    let mut finish_reason = None;
+    let mut error = None;
+    match chat.send(config, context).await {
+        Ok(mut stream) => {
+            while let Some(chunk) = stream.next().await {
+                match chunk {
+                    Ok(chunk) => {
+                        content.push_str(&chunk.content);
+                        finish_reason = chunk.finish_reason.or(finish_reason);
+                    }
+                    Err(e) => {
+                        error = Some(e.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+        Err(e) => error = Some(e.to_string()),
+    }
+    ResponseCandidate {
+        provider: config.provider,
+        model: config.model_name.clone(),
+        content,
+        finish_reason,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::ProviderType;
+
+    fn candidate(content: &str, finish_reason: FinishReason) -> ResponseCandidate {
+        ResponseCandidate {
+            provider: ProviderType::Mock,
+            model: "mock-model".to_string(),
+            content: content.to_string(),
+            finish_reason: Some(finish_reason),
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_prefers_finished_answers() {
+        let candidates = vec![
+            candidate("A long answer that was cut off before", FinishReason::Length),
+            candidate("Short, complete.", FinishReason::Stop),
+            ResponseCandidate {
+                error: Some("timeout".to_string()),
+                ..candidate("", FinishReason::Error)
+            },
+        ];
+
+        let selection = HeuristicJudge.select(&[], &candidates).await.unwrap();
+        assert_eq!(selection.index, 1);
+        assert!(selection.rationale.contains("2 usable"));
+    }
+
+    #[test]
+    fn test_parse_choice_reads_first_number() {
+        assert_eq!(parse_choice("2\nIt cites its source."), Some(1));
+        assert_eq!(parse_choice("Candidate 3 is best"), Some(2));
+        assert_eq!(parse_choice("none"), None);
+        assert_eq!(parse_choice("0"), None);
+    }
+}
@@ -0,0 +1,499 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Canary Rollouts
+//!
+//! Rolls a new agent definition out through a canary instance before it
+//! replaces the stable agent's configuration:
+//!
+//! ```text
+//! start() ──> DeployAgent, ConfigureModel, ActivateAgent (canary)
+//!    │
+//!    v
+//! route() ──> stable / canary (canary gets `traffic_share`)
+//!    │
+//!    v
+//! observe() ──> error rate + latency per side
+//!    │ bake time elapsed
+//!    v
+//! evaluate() ──> CanaryDecided + ConfigureModel (stable), DecommissionAgent (canary)
+//!                           or   DecommissionAgent (canary)
+//! ```
+//!
+//! The canary is rolled back if it errors noticeably more than the stable
+//! agent, is noticeably slower, or did not see enough traffic to judge. With
+//! an evaluation gate it must also have passed an `EvaluationCompleted`
+//! score first.
+//! Commands are returned for the caller to dispatch.
+
+use super::{AgentManifest, ManifestError, ManifestResult};
+use crate::aggregate::Agent;
+use crate::commands::{ActivateAgent, AgentCommand, ConfigureModel, DecommissionAgent, DeployAgent};
+use crate::events::{AgentEvent, CanaryDecidedEvent, CanaryDecision, CanaryStats};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, AgentStatus, ModelConfig};
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// When a canary is good enough to promote
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CanaryPolicy {
+    /// Fraction of requests routed to the canary
+    pub traffic_share: f64,
+
+    /// How long the canary serves traffic before it is judged
+    pub bake_time: Duration,
+
+    /// Finished canary responses needed to judge it
+    pub min_responses: u32,
+
+    /// How far the canary's error rate may exceed the stable one
+    pub max_error_rate_increase: f64,
+
+    /// How many times slower than stable the canary may be
+    pub max_latency_ratio: f64,
+
+    /// Evaluation score the canary must reach (see [`EvalRunner`](super::EvalRunner))
+    pub min_eval_score: Option<f64>,
+}
+
+impl CanaryPolicy {
+    /// Bake for `bake_time` with the default thresholds
+    pub fn new(bake_time: Duration) -> Self {
+        Self {
+            traffic_share: 0.1,
+            bake_time,
+            min_responses: 20,
+            max_error_rate_increase: 0.02,
+            max_latency_ratio: 1.25,
+            min_eval_score: None,
+        }
+    }
+
+    /// Set the share of traffic routed to the canary
+    pub fn with_traffic_share(mut self, share: f64) -> Self {
+        self.traffic_share = share;
+        self
+    }
+
+    /// Set how many canary responses are needed to judge it
+    pub fn with_min_responses(mut self, responses: u32) -> Self {
+        self.min_responses = responses;
+        self
+    }
+
+    /// Set the allowed error rate increase over stable
+    pub fn with_max_error_rate_increase(mut self, increase: f64) -> Self {
+        self.max_error_rate_increase = increase;
+        self
+    }
+
+    /// Set the allowed latency ratio over stable
+    pub fn with_max_latency_ratio(mut self, ratio: f64) -> Self {
+        self.max_latency_ratio = ratio;
+        self
+    }
+
+    /// Require an `EvaluationCompleted` for the canary scoring at least `score`
+    pub fn with_eval_gate(mut self, score: f64) -> Self {
+        self.min_eval_score = Some(score);
+        self
+    }
+
+    /// Check the policy is usable
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.traffic_share > 0.0 && self.traffic_share < 1.0) {
+            return Err(format!(
+                "Canary traffic share must be between 0 and 1 (exclusive), got {}",
+                self.traffic_share
+            ));
+        }
+        if self.max_error_rate_increase < 0.0 || self.max_latency_ratio < 1.0 {
+            return Err("Canary thresholds must not be stricter than stable".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for CanaryPolicy {
+    /// 30 minute bake
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30 * 60))
+    }
+}
+
+/// Running totals for one side of the rollout
+#[derive(Debug, Default)]
+struct Side {
+    responses: u32,
+    errors: u32,
+    latency_ms_total: u64,
+    latency_samples: u32,
+}
+
+impl Side {
+    fn stats(&self) -> CanaryStats {
+        CanaryStats {
+            responses: self.responses,
+            errors: self.errors,
+            mean_latency_ms: (self.latency_samples > 0)
+                .then(|| self.latency_ms_total / u64::from(self.latency_samples)),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Bake {
+    stable: Side,
+    canary: Side,
+    canary_eval_score: Option<f64>,
+    decided: bool,
+}
+
+/// Decision on a rollout and the commands that carry it out
+#[derive(Debug, Clone)]
+pub struct CanaryOutcome {
+    /// Event documenting the decision
+    pub event: CanaryDecidedEvent,
+
+    /// Commands to dispatch, in order
+    pub commands: Vec<AgentCommand>,
+}
+
+/// A canary rollout of a new definition for one stable agent
+#[derive(Debug)]
+pub struct CanaryRollout {
+    stable_id: AgentId,
+    canary_id: AgentId,
+    config: ModelConfig,
+    policy: CanaryPolicy,
+    started_at: DateTime<Utc>,
+    routed: AtomicU64,
+    bake: Mutex<Bake>,
+}
+
+impl CanaryRollout {
+    /// Start rolling `definition` out alongside `stable`
+    ///
+    /// Returns the rollout and the commands that bring the canary up, or an
+    /// error if `policy` fails [`CanaryPolicy::validate`].
+    pub fn start(
+        stable: &Agent,
+        definition: &AgentManifest,
+        policy: CanaryPolicy,
+    ) -> ManifestResult<(Self, Vec<AgentCommand>)> {
+        policy.validate().map_err(ManifestError::InvalidCanaryPolicy)?;
+        let Some(config) = definition.config.clone() else {
+            return Err(ManifestError::MissingConfig {
+                name: definition.name.clone(),
+                status: AgentStatus::Active,
+            });
+        };
+
+        let name = format!("{}-canary", stable.name());
+        let mut deploy = DeployAgent::new(stable.person_id(), name);
+        if let Some(description) = &definition.blueprint.description {
+            deploy = deploy.with_description(description);
+        }
+        let canary_id = deploy.agent_id;
+        let commands = vec![
+            AgentCommand::DeployAgent(deploy),
+            AgentCommand::ConfigureModel(ConfigureModel::new(canary_id, config.clone())),
+            AgentCommand::ActivateAgent(ActivateAgent::new(canary_id)),
+        ];
+
+        let rollout = Self {
+            stable_id: stable.id(),
+            canary_id,
+            config,
+            policy,
+            started_at: Utc::now(),
+            routed: AtomicU64::new(0),
+            bake: Mutex::new(Bake::default()),
+        };
+        Ok((rollout, commands))
+    }
+
+    /// The stable agent
+    pub fn stable_id(&self) -> AgentId {
+        self.stable_id
+    }
+
+    /// The canary agent
+    pub fn canary_id(&self) -> AgentId {
+        self.canary_id
+    }
+
+    /// Pick the agent that serves the next request
+    ///
+    /// Spreads canary requests evenly: with a 10% share, every tenth
+    /// request goes to the canary.
+    pub fn route(&self) -> AgentId {
+        let n = self.routed.fetch_add(1, Ordering::Relaxed) as f64;
+        let share = self.policy.traffic_share;
+        if ((n + 1.0) * share).floor() > (n * share).floor() {
+            self.canary_id
+        } else {
+            self.stable_id
+        }
+    }
+
+    /// Record a finished response from either agent, or the canary's evaluation
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        let agent_id = envelope.aggregate_id;
+        if agent_id != self.stable_id && agent_id != self.canary_id {
+            return;
+        }
+
+        let mut bake = self.bake.lock().unwrap_or_else(|e| e.into_inner());
+        let latency_ms = match &envelope.event {
+            AgentEvent::ResponseCompleted(e) => Some(e.duration_ms),
+            AgentEvent::ResponseFailed(_) => None,
+            AgentEvent::EvaluationCompleted(e) if agent_id == self.canary_id => {
+                bake.canary_eval_score = Some(e.score);
+                return;
+            }
+            _ => return,
+        };
+
+        let side = if agent_id == self.canary_id {
+            &mut bake.canary
+        } else {
+            &mut bake.stable
+        };
+        side.responses += 1;
+        match latency_ms {
+            Some(latency_ms) => {
+                side.latency_ms_total += latency_ms;
+                side.latency_samples += 1;
+            }
+            None => side.errors += 1,
+        }
+    }
+
+    /// Decide the rollout once the bake time has passed at `now`
+    ///
+    /// Returns `None` while baking and after the decision has been made.
+    pub fn evaluate(&self, now: DateTime<Utc>) -> Option<CanaryOutcome> {
+        let bake_time =
+            chrono::Duration::from_std(self.policy.bake_time).unwrap_or(chrono::Duration::MAX);
+        if now.signed_duration_since(self.started_at) < bake_time {
+            return None;
+        }
+
+        let mut bake = self.bake.lock().unwrap_or_else(|e| e.into_inner());
+        if bake.decided {
+            return None;
+        }
+        bake.decided = true;
+
+        let (stable, canary) = (bake.stable.stats(), bake.canary.stats());
+        let (decision, reason) = self.judge(&stable, &canary, bake.canary_eval_score);
+        let mut commands = Vec::new();
+        if decision == CanaryDecision::Promoted {
+            commands.push(AgentCommand::ConfigureModel(ConfigureModel::new(
+                self.stable_id,
+                self.config.clone(),
+            )));
+        }
+        commands.push(AgentCommand::DecommissionAgent(DecommissionAgent::new(self.canary_id)));
+
+        Some(CanaryOutcome {
+            event: CanaryDecidedEvent::new(
+                self.stable_id,
+                self.canary_id,
+                decision,
+                reason,
+                stable,
+                canary,
+            ),
+            commands,
+        })
+    }
+
+    fn judge(
+        &self,
+        stable: &CanaryStats,
+        canary: &CanaryStats,
+        eval_score: Option<f64>,
+    ) -> (CanaryDecision, String) {
+        use CanaryDecision::{Promoted, RolledBack};
+
+        if let Some(min_score) = self.policy.min_eval_score {
+            match eval_score {
+                None => return (RolledBack, "Canary was never evaluated".to_string()),
+                Some(score) if score < min_score => {
+                    return (
+                        RolledBack,
+                        format!("Canary evaluation scored {:.2}, {:.2} needed", score, min_score),
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+
+        if canary.responses < self.policy.min_responses {
+            return (
+                RolledBack,
+                format!(
+                    "Canary finished {} responses, {} needed to judge it",
+                    canary.responses, self.policy.min_responses
+                ),
+            );
+        }
+
+        let allowed_error_rate = stable.error_rate() + self.policy.max_error_rate_increase;
+        if canary.error_rate() > allowed_error_rate {
+            return (
+                RolledBack,
+                format!(
+                    "Canary error rate {:.1}% exceeds {:.1}%",
+                    canary.error_rate() * 100.0,
+                    allowed_error_rate * 100.0
+                ),
+            );
+        }
+
+        if let (Some(stable_ms), Some(canary_ms)) = (stable.mean_latency_ms, canary.mean_latency_ms)
+        {
+            let allowed_ms = stable_ms as f64 * self.policy.max_latency_ratio;
+            if canary_ms as f64 > allowed_ms {
+                return (
+                    RolledBack,
+                    format!("Canary mean latency {}ms exceeds {:.0}ms", canary_ms, allowed_ms),
+                );
+            }
+        }
+
+        (Promoted, "Canary within evaluation, error rate and latency thresholds".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentDeployedEvent, EvaluationCompletedEvent, ResponseCompletedEvent, ResponseErrorType,
+        ResponseFailedEvent,
+    };
+    use crate::read_model::AgentGraphProjection;
+    use crate::services::export_manifest;
+    use crate::test_support::envelope;
+    use crate::value_objects::{FinishReason, MessageId, PersonId, TokenUsage};
+
+    fn respond(rollout: &CanaryRollout, agent_id: AgentId, failed: bool) {
+        let message_id = MessageId::new();
+        let finished = if failed {
+            AgentEvent::ResponseFailed(ResponseFailedEvent::new(
+                agent_id,
+                message_id,
+                ResponseErrorType::Timeout,
+                "timed out",
+                true,
+            ))
+        } else {
+            AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+                agent_id,
+                message_id,
+                1,
+                TokenUsage::new(1, 1),
+                FinishReason::Stop,
+                250,
+            ))
+        };
+        rollout.observe(&envelope(0, finished));
+    }
+
+    fn stable_and_definition() -> (Agent, AgentManifest) {
+        let id = AgentId::new();
+        let stable = Agent::empty()
+            .apply_events(&[AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                id,
+                PersonId::new(),
+                "sage",
+                None,
+            ))])
+            .unwrap();
+        let graph = AgentGraphProjection::new();
+        let mut definition = export_manifest([&stable], &graph).agents.remove(0);
+        definition.config = Some(ModelConfig::mock().with_temperature(0.2));
+        (stable, definition)
+    }
+
+    fn rollout(policy: CanaryPolicy) -> CanaryRollout {
+        let (stable, definition) = stable_and_definition();
+        let (rollout, commands) = CanaryRollout::start(&stable, &definition, policy).unwrap();
+        assert_eq!(commands.len(), 3);
+        rollout
+    }
+
+    #[test]
+    fn test_invalid_policy_is_refused() {
+        let (stable, definition) = stable_and_definition();
+        let policy = CanaryPolicy::default().with_traffic_share(1.5);
+        assert!(matches!(
+            CanaryRollout::start(&stable, &definition, policy),
+            Err(ManifestError::InvalidCanaryPolicy(_))
+        ));
+    }
+
+    #[test]
+    fn test_routes_traffic_share_to_canary() {
+        let rollout = rollout(CanaryPolicy::default().with_traffic_share(0.25));
+        let canary = (0..100).filter(|_| rollout.route() == rollout.canary_id()).count();
+        assert_eq!(canary, 25);
+    }
+
+    #[test]
+    fn test_promotes_healthy_canary_after_bake() {
+        let rollout = rollout(CanaryPolicy::new(Duration::from_secs(60)).with_min_responses(5));
+        for _ in 0..5 {
+            respond(&rollout, rollout.stable_id(), false);
+            respond(&rollout, rollout.canary_id(), false);
+        }
+
+        assert!(rollout.evaluate(Utc::now()).is_none());
+        let later = Utc::now() + chrono::Duration::minutes(2);
+        let outcome = rollout.evaluate(later).unwrap();
+        assert_eq!(outcome.event.decision, CanaryDecision::Promoted);
+        assert_eq!(outcome.event.canary.responses, 5);
+        assert!(matches!(
+            outcome.commands[..],
+            [AgentCommand::ConfigureModel(_), AgentCommand::DecommissionAgent(_)]
+        ));
+        assert!(rollout.evaluate(later).is_none());
+    }
+
+    #[test]
+    fn test_eval_gate_blocks_unevaluated_canary() {
+        let policy = CanaryPolicy::new(Duration::ZERO).with_min_responses(0).with_eval_gate(0.9);
+        let rollout = rollout(policy);
+        assert_eq!(
+            rollout.evaluate(Utc::now()).unwrap().event.decision,
+            CanaryDecision::RolledBack
+        );
+
+        let rollout = rollout(policy);
+        let canary_id = rollout.canary_id();
+        let evaluated =
+            EvaluationCompletedEvent::new(canary_id, "basics", "mock/mock", 0.95, true, 1, 1);
+        rollout.observe(&envelope(0, AgentEvent::EvaluationCompleted(evaluated)));
+        let outcome = rollout.evaluate(Utc::now()).unwrap();
+        assert_eq!(outcome.event.decision, CanaryDecision::Promoted);
+    }
+
+    #[test]
+    fn test_rolls_back_erroring_canary() {
+        let rollout = rollout(CanaryPolicy::new(Duration::ZERO).with_min_responses(4));
+        for i in 0..4 {
+            respond(&rollout, rollout.stable_id(), false);
+            respond(&rollout, rollout.canary_id(), i % 2 == 0);
+        }
+
+        let outcome = rollout.evaluate(Utc::now()).unwrap();
+        assert_eq!(outcome.event.decision, CanaryDecision::RolledBack);
+        assert_eq!(outcome.event.canary.errors, 2);
+        assert!(matches!(outcome.commands[..], [AgentCommand::DecommissionAgent(_)]));
+    }
+}
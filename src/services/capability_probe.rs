@@ -0,0 +1,407 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Capability Probes
+//!
+//! Declared capabilities drift from what a model really does: a model
+//! swapped for a smaller one quietly loses vision. The prober turns each
+//! capability an agent advertises into a minimal request and checks the
+//! answer:
+//!
+//! ```text
+//! agent.advertised_capabilities() ──> CapabilityProbe::probes_for()
+//!                                              │ one per probeable flag
+//!                                              v
+//!                                 ChatPort::send / send_vision
+//!                                              │
+//!        passed ──> kept   inconclusive ──> kept   failed ──> CapabilityVerificationFailed
+//!                                                                    │
+//!                                      Agent withdraws it from advertised_capabilities()
+//! ```
+//!
+//! Only a wrong answer, or a provider refusing the request as invalid,
+//! withdraws a capability. Timeouts, rate limits, outages and credential
+//! errors say nothing about the model and leave the probe inconclusive.
+//!
+//! Flags without a cheap request (tool calling, code execution, long
+//! context, embeddings, image and audio) are reported as unprobed. A
+//! withdrawn capability comes back when the agent's model is configured
+//! again.
+
+use crate::aggregate::Agent;
+use crate::capabilities::RuntimeCapabilities;
+use crate::events::CapabilityVerificationFailedEvent;
+use crate::intent::ImageInput;
+use crate::ports::{ChatError, ChatPort, ErrorCategory};
+use crate::value_objects::{ContextMessage, ModelConfig};
+use futures::StreamExt;
+use std::time::Duration;
+
+/// Time each probe may take before it counts as failed
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 1x1 red PNG sent by the vision probe
+const PROBE_IMAGE_PNG: &str = concat!(
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8DwHwAFBQIAX8jx0gAAAABJRU5E",
+    "rkJggg==",
+);
+
+/// What a probe's answer must show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeCheck {
+    /// Any non-empty answer
+    Answers,
+    /// An answer delivered in more than one chunk
+    Streams,
+    /// An answer containing the word, ignoring case
+    Contains(&'static str),
+    /// An answer that parses as a JSON object
+    JsonObject,
+}
+
+/// Why a probe did not pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeFailure {
+    /// The model lacks the capability: a wrong answer, or a request refused as invalid
+    Capability(String),
+    /// No verdict: the provider could not be asked, so the capability is kept
+    Inconclusive(String),
+}
+
+impl From<ChatError> for ProbeFailure {
+    fn from(error: ChatError) -> Self {
+        let refused = match &error {
+            ChatError::InvalidRequest(_) => true,
+            ChatError::Provider(failure) => failure.category == ErrorCategory::InvalidRequest,
+            _ => false,
+        };
+        if refused {
+            ProbeFailure::Capability(error.to_string())
+        } else {
+            ProbeFailure::Inconclusive(error.to_string())
+        }
+    }
+}
+
+/// A minimal request exercising one capability
+#[derive(Debug, Clone)]
+pub struct CapabilityProbe {
+    /// The capability under test
+    pub capability: RuntimeCapabilities,
+    context: Vec<ContextMessage>,
+    images: Vec<ImageInput>,
+    check: ProbeCheck,
+}
+
+impl CapabilityProbe {
+    fn new(
+        capability: RuntimeCapabilities,
+        context: Vec<ContextMessage>,
+        check: ProbeCheck,
+    ) -> Self {
+        Self {
+            capability,
+            context,
+            images: Vec::new(),
+            check,
+        }
+    }
+
+    /// The probe for a single capability flag, if it can be probed cheaply
+    pub fn for_capability(capability: RuntimeCapabilities) -> Option<Self> {
+        let probe = match capability {
+            RuntimeCapabilities::TEXT_CHAT => Self::new(
+                capability,
+                vec![ContextMessage::user("Reply with the single word: ready")],
+                ProbeCheck::Answers,
+            ),
+            RuntimeCapabilities::STREAMING => Self::new(
+                capability,
+                vec![ContextMessage::user(
+                    "Count from 1 to 10, one number per line.",
+                )],
+                ProbeCheck::Streams,
+            ),
+            RuntimeCapabilities::SYSTEM_PROMPT => Self::new(
+                capability,
+                vec![
+                    ContextMessage::system("Whatever the user says, answer only: PONG"),
+                    ContextMessage::user("ping"),
+                ],
+                ProbeCheck::Contains("pong"),
+            ),
+            RuntimeCapabilities::MULTI_TURN => Self::new(
+                capability,
+                vec![
+                    ContextMessage::user("Remember the number 7."),
+                    ContextMessage::assistant("OK, I will remember it."),
+                    ContextMessage::user("Which number did I ask you to remember? Digit only."),
+                ],
+                ProbeCheck::Contains("7"),
+            ),
+            RuntimeCapabilities::JSON_MODE => Self::new(
+                capability,
+                vec![ContextMessage::user(
+                    "Reply with the JSON object {\"ok\": true} and nothing else.",
+                )],
+                ProbeCheck::JsonObject,
+            ),
+            RuntimeCapabilities::VISION => Self {
+                images: vec![ImageInput::base64(PROBE_IMAGE_PNG, "image/png")],
+                ..Self::new(
+                    capability,
+                    vec![ContextMessage::user("What colour is this image? One word.")],
+                    ProbeCheck::Answers,
+                )
+            },
+            _ => return None,
+        };
+        Some(probe)
+    }
+
+    /// Probes for every probeable flag in `capabilities`
+    pub fn probes_for(capabilities: RuntimeCapabilities) -> Vec<Self> {
+        single_flags(capabilities)
+            .filter_map(Self::for_capability)
+            .collect()
+    }
+
+    /// Send the probe to `config`'s model through `chat`
+    pub async fn run(
+        &self,
+        chat: &dyn ChatPort,
+        config: &ModelConfig,
+    ) -> Result<(), ProbeFailure> {
+        let context = self.context.clone();
+        let mut stream = if self.images.is_empty() {
+            chat.send(config, context).await
+        } else {
+            chat.send_vision(config, context, self.images.clone()).await
+        }?;
+
+        let mut answer = String::new();
+        let mut chunks = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if !chunk.content.is_empty() {
+                chunks += 1;
+                answer.push_str(&chunk.content);
+            }
+            if chunk.is_final {
+                break;
+            }
+        }
+
+        self.check_answer(answer.trim(), chunks)
+            .map_err(ProbeFailure::Capability)
+    }
+
+    /// Whether `answer`, delivered in `chunks` chunks, shows the capability
+    fn check_answer(&self, answer: &str, chunks: usize) -> Result<(), String> {
+        if answer.is_empty() {
+            return Err("Empty answer".to_string());
+        }
+        match self.check {
+            ProbeCheck::Answers => Ok(()),
+            ProbeCheck::Streams if chunks > 1 => Ok(()),
+            ProbeCheck::Streams => Err("Answer arrived in a single chunk".to_string()),
+            ProbeCheck::Contains(word) if answer.to_lowercase().contains(word) => Ok(()),
+            ProbeCheck::Contains(word) => Err(format!("Answer did not contain '{}'", word)),
+            ProbeCheck::JsonObject => {
+                let json = answer
+                    .trim_start_matches("```json")
+                    .trim_start_matches("```")
+                    .trim_end_matches("```")
+                    .trim();
+                match serde_json::from_str::<serde_json::Value>(json) {
+                    Ok(value) if value.is_object() => Ok(()),
+                    Ok(_) => Err("Answer was JSON but not an object".to_string()),
+                    Err(e) => Err(format!("Answer was not JSON: {}", e)),
+                }
+            }
+        }
+    }
+}
+
+/// Each single-bit flag set in `capabilities`
+fn single_flags(capabilities: RuntimeCapabilities) -> impl Iterator<Item = RuntimeCapabilities> {
+    (0..u32::BITS)
+        .map(|bit| RuntimeCapabilities::from_bits_truncate(1 << bit))
+        .filter(move |flag| !flag.is_empty() && capabilities.contains(*flag))
+}
+
+/// Outcome of probing one agent
+#[derive(Debug, Clone, Default)]
+pub struct ProbeReport {
+    /// Capabilities whose probe passed
+    pub passed: RuntimeCapabilities,
+
+    /// One event per capability whose probe failed
+    pub failed: Vec<CapabilityVerificationFailedEvent>,
+
+    /// Capabilities whose probe reached no verdict; they stay advertised
+    pub inconclusive: RuntimeCapabilities,
+
+    /// Advertised capabilities with no probe
+    pub unprobed: RuntimeCapabilities,
+}
+
+impl ProbeReport {
+    /// Capabilities that failed their probe
+    pub fn failed_capabilities(&self) -> RuntimeCapabilities {
+        self.failed
+            .iter()
+            .fold(RuntimeCapabilities::empty(), |acc, e| {
+                acc.join(&e.capability)
+            })
+    }
+}
+
+/// Verifies an agent's advertised capabilities against its live model
+pub struct CapabilityProber {
+    timeout: Duration,
+}
+
+impl CapabilityProber {
+    /// Create a prober with the default per-probe timeout
+    pub fn new() -> Self {
+        Self {
+            timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+
+    /// Builder: fail probes that take longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Probe each capability `agent` advertises, one request at a time
+    ///
+    /// An agent without an embedded model configuration gets an empty report.
+    #[allow(deprecated)]
+    pub async fn probe(&self, chat: &dyn ChatPort, agent: &Agent) -> ProbeReport {
+        let (Some(config), Some(advertised)) =
+            (agent.model_config(), agent.advertised_capabilities())
+        else {
+            return ProbeReport::default();
+        };
+
+        let probes = CapabilityProbe::probes_for(advertised);
+        let probed = probes.iter().fold(RuntimeCapabilities::empty(), |acc, p| {
+            acc.join(&p.capability)
+        });
+        let mut report = ProbeReport {
+            unprobed: advertised.difference(probed),
+            ..ProbeReport::default()
+        };
+        for probe in probes {
+            let outcome = match tokio::time::timeout(self.timeout, probe.run(chat, config)).await {
+                Ok(outcome) => outcome,
+                Err(_) => Err(ProbeFailure::Inconclusive(format!(
+                    "No answer within {:?}",
+                    self.timeout
+                ))),
+            };
+            match outcome {
+                Ok(()) => report.passed.insert(probe.capability),
+                Err(ProbeFailure::Capability(error)) => {
+                    report.failed.push(CapabilityVerificationFailedEvent::new(
+                        agent.id(),
+                        probe.capability,
+                        &config.model_name,
+                        error,
+                    ))
+                }
+                Err(ProbeFailure::Inconclusive(reason)) => {
+                    tracing::warn!(
+                        agent_id = %agent.id(),
+                        capability = ?probe.capability,
+                        "Capability probe inconclusive: {}",
+                        reason
+                    );
+                    report.inconclusive.insert(probe.capability);
+                }
+            }
+        }
+        report
+    }
+}
+
+impl Default for CapabilityProber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentActivatedEvent, AgentDeployedEvent, AgentEvent, ModelConfiguredEvent,
+    };
+    use crate::test_support::ScriptedChat;
+    use crate::value_objects::{AgentId, FinishReason, PersonId, StreamingChunk};
+
+    /// Answers every probe correctly, but always in a single chunk
+    fn single_chunk() -> impl ChatPort {
+        ScriptedChat::new(|_, context| {
+            let question = context.last().map(|m| m.content.as_str()).unwrap_or_default();
+            let answer = if question.contains("JSON") {
+                "{\"ok\": true}"
+            } else {
+                "PONG ready 7"
+            };
+            Ok(vec![StreamingChunk::final_chunk(0, answer, FinishReason::Stop)])
+        })
+    }
+
+    #[tokio::test]
+    async fn test_failed_probe_withdraws_the_capability() {
+        let id = AgentId::new();
+        let agent = Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(id, PersonId::new(), "t", None)),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(id)),
+            ])
+            .unwrap();
+
+        let report = CapabilityProber::new().probe(&single_chunk(), &agent).await;
+        assert_eq!(report.failed_capabilities(), RuntimeCapabilities::STREAMING);
+        assert!(report.passed.contains(RuntimeCapabilities::SYSTEM_PROMPT));
+
+        let events: Vec<AgentEvent> = report
+            .failed
+            .into_iter()
+            .map(AgentEvent::CapabilityVerificationFailed)
+            .collect();
+        let agent = agent.apply_events(&events).unwrap();
+        let advertised = agent.advertised_capabilities().unwrap();
+        assert!(!advertised.contains(RuntimeCapabilities::STREAMING));
+        assert!(advertised.contains(RuntimeCapabilities::TEXT_CHAT));
+
+        let reconfigured =
+            AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock()));
+        let agent = agent.apply_event(&reconfigured).unwrap();
+        assert!(agent.unverified_capabilities().is_empty());
+    }
+
+    #[test]
+    fn test_only_invalid_requests_count_against_a_capability() {
+        let refused = ProbeFailure::from(ChatError::InvalidRequest("images unsupported".into()));
+        assert!(matches!(refused, ProbeFailure::Capability(_)));
+        let limited = ProbeFailure::from(ChatError::RateLimitExceeded {
+            retry_after_secs: Some(30),
+        });
+        assert!(matches!(limited, ProbeFailure::Inconclusive(_)));
+    }
+
+    #[test]
+    fn test_probes_are_generated_per_flag() {
+        let probes = CapabilityProbe::probes_for(RuntimeCapabilities::ADVANCED_CHAT);
+        let probed: Vec<_> = probes.iter().map(|p| p.capability).collect();
+        assert!(probed.contains(&RuntimeCapabilities::JSON_MODE));
+        assert!(!probed.contains(&RuntimeCapabilities::FUNCTION_CALLING));
+        assert_eq!(probed.len(), 5);
+    }
+}
@@ -4,13 +4,45 @@
 //!
 //! Routes requests to providers based on capability requirements.
 //! Uses the capability lattice to find suitable providers.
+//!
+//! ```text
+//! MessageIntent ──> infer_requirements() ──> overrides ──> select_provider
+//! ```
+//!
+//! Requirements are inferred from the intent by default; a
+//! [`RequirementsOverride`] can tighten or relax them before selection.
 
 use crate::adapters::ProviderRegistry;
-use crate::capabilities::CapabilityRequirements;
+use crate::capabilities::{CapabilityRequirements, RuntimeCapabilities};
 use crate::intent::MessageIntent;
 use crate::ports::{ChatPort, ChatResult};
 use std::sync::Arc;
 
+/// Adjusts the requirements inferred for an intent before routing
+///
+/// Any `Fn(&MessageIntent, CapabilityRequirements) -> CapabilityRequirements`
+/// closure is an override.
+pub trait RequirementsOverride: Send + Sync {
+    fn adjust(
+        &self,
+        intent: &MessageIntent,
+        inferred: CapabilityRequirements,
+    ) -> CapabilityRequirements;
+}
+
+impl<F> RequirementsOverride for F
+where
+    F: Fn(&MessageIntent, CapabilityRequirements) -> CapabilityRequirements + Send + Sync,
+{
+    fn adjust(
+        &self,
+        intent: &MessageIntent,
+        inferred: CapabilityRequirements,
+    ) -> CapabilityRequirements {
+        self(intent, inferred)
+    }
+}
+
 /// Routes message intents to capable providers
 ///
 /// The router uses the capability lattice to:
@@ -19,12 +51,37 @@ use std::sync::Arc;
 /// 3. Select the best provider (least over-provisioned)
 pub struct CapabilityRouter {
     registry: ProviderRegistry,
+    overrides: Vec<Arc<dyn RequirementsOverride>>,
 }
 
 impl CapabilityRouter {
     /// Create a new router with the given registry
     pub fn new(registry: ProviderRegistry) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Builder: adjust inferred requirements before routing
+    ///
+    /// Overrides run in the order they were added.
+    pub fn with_requirements_override(
+        mut self,
+        hook: impl RequirementsOverride + 'static,
+    ) -> Self {
+        self.overrides.push(Arc::new(hook));
+        self
+    }
+
+    /// Requirements the router routes `intent` with: inferred from the
+    /// intent, then passed through the overrides
+    pub fn requirements_for(&self, intent: &MessageIntent) -> CapabilityRequirements {
+        self.overrides
+            .iter()
+            .fold(intent.infer_requirements(), |requirements, hook| {
+                hook.adjust(intent, requirements)
+            })
     }
 
     /// Route a message intent to a capable provider
@@ -35,10 +92,21 @@ impl CapabilityRouter {
     ///
     /// # Returns
     ///
-    /// The adapter for the best-fit capable provider.
+    /// The adapter for the best-fit capable provider. Providers whose
+    /// declared context fits the intent are preferred; when none does, the
+    /// intent is routed on its capabilities alone and left to context
+    /// truncation or the long-context fallback.
     pub fn route(&self, intent: &MessageIntent) -> ChatResult<Arc<dyn ChatPort>> {
-        let requirements = intent.capability_requirements();
-        self.route_with_requirements(&requirements)
+        let requirements = self.requirements_for(intent);
+        self.route_with_requirements(&requirements).or_else(|error| {
+            if requirements.min_context_length.is_none() {
+                return Err(error);
+            }
+            let mut relaxed = requirements.clone();
+            relaxed.min_context_length = None;
+            relaxed.capabilities.remove(RuntimeCapabilities::LONG_CONTEXT);
+            self.route_with_requirements(&relaxed)
+        })
     }
 
     /// Route with explicit capability requirements
@@ -105,4 +173,20 @@ mod tests {
         let result = router.route(&intent);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_requirements_override() {
+        let router = setup_router().with_requirements_override(
+            |_: &MessageIntent, mut inferred: CapabilityRequirements| {
+                inferred.capabilities |= RuntimeCapabilities::VISION;
+                inferred
+            },
+        );
+        let intent = MessageIntent::chat(vec![ContextMessage::user("Hello")]);
+
+        let requirements = router.requirements_for(&intent);
+        assert!(requirements.capabilities.contains(RuntimeCapabilities::VISION));
+        assert!(requirements.requires_streaming);
+        assert!(router.route(&intent).is_err());
+    }
 }
@@ -0,0 +1,323 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Change Rate Guardrails
+//!
+//! A burst of configuration churn or mass permission grants is a red flag
+//! on its own, whoever is allowed to make each change. Changes are counted
+//! in sliding windows per agent and per actor, and the change that goes
+//! over a limit is refused or held for the owner:
+//!
+//! ```text
+//! ConfigureModel / UpdateConfiguration ───┐
+//! ApproveEscalation (grants a permission) ┤
+//!                                         v
+//!     count within window, per agent and per authenticated caller
+//!                                         │
+//!        within every limit ──> Allow ──> change succeeds ──> record()
+//!                                         │
+//!        over a limit ──> ChangeRateExceeded ──> owner alert
+//!                              │
+//!                              ├── Block ──> refused
+//!                              └── RequireApproval ──> PermissionEscalations::escalate()
+//! ```
+//!
+//! Only changes that succeed are counted, so refused or failed commands do
+//! not eat into a limit. Approvals cannot be held for approval themselves,
+//! so permission limits always block, as do all limits when no approvals
+//! are possible. Anonymous callers share a single per-caller window.
+//!
+//! Counts are kept in memory, per instance: behind a queue group of N
+//! instances a caller can make up to N times a limit before every instance
+//! has seen enough changes to refuse.
+
+use crate::commands::AgentCommand;
+use crate::events::{
+    ChangeKind, ChangeRateExceededEvent, ChangeRateLimit, ChangeScope, GuardrailAction,
+};
+use crate::value_objects::{clock_now, AgentId, AuthenticatedPrincipal};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// Length of the default limits' windows
+pub const DEFAULT_CHANGE_RATE_WINDOW_SECS: u64 = 3600;
+
+/// Outcome of checking a command against the rate limits
+#[derive(Debug, Clone)]
+pub enum GuardrailVerdict {
+    /// Not a change, or within every limit
+    Allow,
+
+    /// Over a limit that holds changes for the owner's approval
+    RequireApproval(ChangeRateExceededEvent),
+
+    /// Over a limit that refuses changes
+    Block(ChangeRateExceededEvent),
+}
+
+impl GuardrailVerdict {
+    /// The alert to publish, unless the change was allowed
+    pub fn exceeded(&self) -> Option<&ChangeRateExceededEvent> {
+        match self {
+            GuardrailVerdict::Allow => None,
+            GuardrailVerdict::RequireApproval(event) | GuardrailVerdict::Block(event) => {
+                Some(event)
+            }
+        }
+    }
+}
+
+/// Who a window of changes is counted for
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Counted {
+    Agent(AgentId),
+    /// An authenticated caller; `None` pools every anonymous caller
+    Actor(Option<String>),
+}
+
+/// Counts configuration and permission changes and enforces rate limits
+#[derive(Debug)]
+pub struct ChangeRateGuardrails {
+    limits: Vec<ChangeRateLimit>,
+    approvals: bool,
+    changes: RwLock<HashMap<(Counted, ChangeKind), VecDeque<DateTime<Utc>>>>,
+}
+
+impl ChangeRateGuardrails {
+    /// Enforce `limits`
+    pub fn new(limits: Vec<ChangeRateLimit>) -> Self {
+        Self {
+            limits,
+            approvals: true,
+            changes: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Limits applied by default, over `window_secs`
+    ///
+    /// Configuration: 10 changes per agent and 30 per actor, then held for
+    /// approval. Permission grants: 3 per agent and 10 per actor, then refused.
+    pub fn default_limits(window_secs: u64) -> Vec<ChangeRateLimit> {
+        use ChangeKind::{Configuration, Permission};
+        use ChangeScope::{Actor, Agent};
+        use GuardrailAction::{Block, RequireApproval};
+        let limit = |kind, scope, max_changes, action| ChangeRateLimit {
+            kind,
+            scope,
+            max_changes,
+            window_secs,
+            action,
+        };
+        vec![
+            limit(Configuration, Agent, 10, RequireApproval),
+            limit(Configuration, Actor, 30, RequireApproval),
+            limit(Permission, Agent, 3, Block),
+            limit(Permission, Actor, 10, Block),
+        ]
+    }
+
+    /// Builder: whether changes can be held for approval; without, every
+    /// limit blocks
+    pub fn with_approvals(mut self, approvals: bool) -> Self {
+        self.approvals = approvals;
+        self
+    }
+
+    /// The limits enforced
+    pub fn limits(&self) -> &[ChangeRateLimit] {
+        &self.limits
+    }
+
+    /// Check `command`, issued by `caller`, against the limits
+    ///
+    /// Nothing is counted; call [`record`](Self::record) once the change has
+    /// been made. When several limits are exceeded, one that blocks wins.
+    pub fn check(
+        &self,
+        command: &AgentCommand,
+        caller: &AuthenticatedPrincipal,
+    ) -> GuardrailVerdict {
+        let Some(kind) = change_kind(command) else {
+            return GuardrailVerdict::Allow;
+        };
+        let agent_id = command.agent_id();
+        let now = clock_now();
+
+        let changes = self.changes.read().unwrap_or_else(|e| e.into_inner());
+        let mut verdict = GuardrailVerdict::Allow;
+        for limit in self.limits.iter().filter(|limit| limit.kind == kind) {
+            let since = now - Duration::seconds(limit.window_secs as i64);
+            let who = counted(limit.scope, agent_id, caller);
+            let made = changes.get(&(who, kind)).map_or(0, |times| {
+                times.iter().filter(|at| **at > since).count() as u32
+            });
+            if made < limit.max_changes || matches!(verdict, GuardrailVerdict::Block(_)) {
+                continue;
+            }
+            let action = if self.approvals && kind != ChangeKind::Permission {
+                limit.action
+            } else {
+                GuardrailAction::Block
+            };
+            let event = ChangeRateExceededEvent::new(
+                agent_id,
+                *limit,
+                made + 1,
+                caller.actor.clone(),
+                caller.class,
+                command.required_permission(),
+                action,
+            );
+            verdict = match action {
+                GuardrailAction::Block => GuardrailVerdict::Block(event),
+                GuardrailAction::RequireApproval => GuardrailVerdict::RequireApproval(event),
+            };
+        }
+        verdict
+    }
+
+    /// Count the change `command` made for `caller`, once it has succeeded
+    pub fn record(&self, command: &AgentCommand, caller: &AuthenticatedPrincipal) {
+        let Some(kind) = change_kind(command) else {
+            return;
+        };
+        let agent_id = command.agent_id();
+        let now = clock_now();
+        let window = self.longest_window(kind);
+        let mut changes = self.changes.write().unwrap_or_else(|e| e.into_inner());
+        for scope in [ChangeScope::Agent, ChangeScope::Actor] {
+            let times = changes
+                .entry((counted(scope, agent_id, caller), kind))
+                .or_default();
+            while times.front().is_some_and(|at| *at <= now - window) {
+                times.pop_front();
+            }
+            times.push_back(now);
+        }
+    }
+
+    /// Longest window any limit on `kind` counts over
+    fn longest_window(&self, kind: ChangeKind) -> Duration {
+        let secs = self
+            .limits
+            .iter()
+            .filter(|limit| limit.kind == kind)
+            .map(|limit| limit.window_secs)
+            .max()
+            .unwrap_or(0);
+        Duration::seconds(secs as i64)
+    }
+}
+
+impl Default for ChangeRateGuardrails {
+    fn default() -> Self {
+        Self::new(Self::default_limits(DEFAULT_CHANGE_RATE_WINDOW_SECS))
+    }
+}
+
+/// The window a change by `caller` to `agent_id` is counted in for `scope`
+fn counted(scope: ChangeScope, agent_id: AgentId, caller: &AuthenticatedPrincipal) -> Counted {
+    match scope {
+        ChangeScope::Agent => Counted::Agent(agent_id),
+        ChangeScope::Actor => Counted::Actor(caller.actor.clone()),
+    }
+}
+
+/// The kind of change `command` makes, if it is one that is rate limited
+fn change_kind(command: &AgentCommand) -> Option<ChangeKind> {
+    match command {
+        AgentCommand::ConfigureModel(_) | AgentCommand::UpdateConfiguration(_) => {
+            Some(ChangeKind::Configuration)
+        }
+        AgentCommand::ApproveEscalation(_) => Some(ChangeKind::Permission),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{ApproveEscalation, ConfigureModel};
+    use crate::value_objects::{
+        next_id, with_clock_sync, FixedClock, ModelConfig, PersonId, PrincipalClass,
+    };
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn at(minute: u32) -> Arc<FixedClock> {
+        Arc::new(FixedClock::new(
+            Utc.with_ymd_and_hms(2025, 6, 1, 12, minute, 0).unwrap(),
+        ))
+    }
+
+    fn configure(agent_id: AgentId) -> AgentCommand {
+        AgentCommand::ConfigureModel(ConfigureModel::new(agent_id, ModelConfig::mock()))
+    }
+
+    #[test]
+    fn test_config_churn_per_agent_requires_approval() {
+        let guardrails = ChangeRateGuardrails::default();
+        let agent_id = AgentId::new();
+        let ci = AuthenticatedPrincipal::internal(PrincipalClass::Operator, "ci");
+        let change = |minute, succeeds| {
+            with_clock_sync(at(minute), || {
+                let verdict = guardrails.check(&configure(agent_id), &ci);
+                if succeeds && matches!(verdict, GuardrailVerdict::Allow) {
+                    guardrails.record(&configure(agent_id), &ci);
+                }
+                verdict
+            })
+        };
+
+        // Failed changes are not counted
+        for minute in 0..5 {
+            assert!(matches!(change(minute, false), GuardrailVerdict::Allow));
+        }
+        for minute in 0..10 {
+            assert!(matches!(change(minute, true), GuardrailVerdict::Allow));
+        }
+        let GuardrailVerdict::RequireApproval(exceeded) = change(10, true) else {
+            panic!("eleventh change within the hour should be held");
+        };
+        assert_eq!(exceeded.limit.scope, ChangeScope::Agent);
+        assert_eq!(exceeded.changes, 11);
+        assert_eq!(exceeded.actor.as_deref(), Some("ci"));
+
+        let unrelated = configure(AgentId::new());
+        let other = with_clock_sync(at(10), || guardrails.check(&unrelated, &ci));
+        assert!(matches!(other, GuardrailVerdict::Allow));
+    }
+
+    #[test]
+    fn test_mass_grants_by_anonymous_callers_are_blocked() {
+        let guardrails = ChangeRateGuardrails::default().with_approvals(false);
+        let owner = PersonId::new();
+        let approve = || {
+            let agent_id = AgentId::new();
+            let approval = AgentCommand::ApproveEscalation(ApproveEscalation::new(
+                agent_id,
+                next_id(),
+                owner,
+            ));
+            // Each caller is anonymous, but they share one window
+            let caller = AuthenticatedPrincipal::anonymous();
+            with_clock_sync(at(0), || {
+                let verdict = guardrails.check(&approval, &caller);
+                if matches!(verdict, GuardrailVerdict::Allow) {
+                    guardrails.record(&approval, &caller);
+                }
+                verdict
+            })
+        };
+
+        for _ in 0..10 {
+            assert!(matches!(approve(), GuardrailVerdict::Allow));
+        }
+        let GuardrailVerdict::Block(exceeded) = approve() else {
+            panic!("eleventh anonymous grant should be refused");
+        };
+        assert_eq!(exceeded.limit.kind, ChangeKind::Permission);
+        assert_eq!(exceeded.limit.scope, ChangeScope::Actor);
+        assert_eq!(exceeded.action, GuardrailAction::Block);
+    }
+}
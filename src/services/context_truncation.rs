@@ -0,0 +1,259 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Context truncation
+//!
+//! Applies a [`TruncationPolicy`] to a context that does not fit the model's
+//! window. Leading system prompts and the latest message are kept; each
+//! strategy removes what it may until the estimate fits, and reports what
+//! it removed so the loss can be published as `ContextTruncated`.
+//!
+//! Strategies work on the ~4 characters per token estimate. The caller sizes
+//! the context with the provider's tokenizer where it has one, or with the
+//! estimate scaled by [`TokenCalibration`] from the prompt tokens providers
+//! last reported for the model, and passes the limit in estimated tokens:
+//!
+//! ```text
+//! [system] [source #3] [user 1] [asst 1] [user 2] [asst 2] [user 3]
+//!  kept     drop_sources  kept    └── summarize_middle ──┘   kept
+//!                                 (drop_oldest starts at user 1)
+//! ```
+
+use crate::ports::estimate_tokens;
+use crate::value_objects::{
+    ContextMessage, MessageRole, TruncationPolicy, TruncationRecord, TruncationStrategy,
+};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Characters of each removed message kept in a record or summary
+const EXCERPT_CHARS: usize = 60;
+
+/// Estimated tokens of a whole context
+pub fn context_tokens(context: &[ContextMessage]) -> u32 {
+    context.iter().map(|m| estimate_tokens(&m.content)).sum()
+}
+
+/// How each model's tokenizer compares with the token estimate
+///
+/// Learned from prompts whose real token count is known, either from the
+/// provider's tokenizer or from the usage it reported for a response.
+#[derive(Debug, Default)]
+pub struct TokenCalibration {
+    ratios: RwLock<HashMap<String, f64>>,
+}
+
+impl TokenCalibration {
+    /// Create a calibration that scales nothing yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learn from a prompt of `estimated` tokens that `model` counted as `counted`
+    pub fn record(&self, model: &str, estimated: u32, counted: u32) {
+        if estimated == 0 || counted == 0 {
+            return;
+        }
+        self.ratios
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(model.to_string(), f64::from(counted) / f64::from(estimated));
+    }
+
+    /// Tokens `model` is expected to count for an `estimated`-token prompt
+    pub fn scale(&self, model: &str, estimated: u32) -> u32 {
+        match self.ratios.read().unwrap_or_else(|e| e.into_inner()).get(model) {
+            Some(ratio) => (f64::from(estimated) * ratio).ceil() as u32,
+            None => estimated,
+        }
+    }
+}
+
+/// Shrink `context` toward `limit` tokens using the strategies in `policy`
+///
+/// Returns one record per strategy that removed something. The context may
+/// still exceed `limit` if the policy ran out of things to remove.
+pub fn truncate_context(
+    policy: &TruncationPolicy,
+    context: &mut Vec<ContextMessage>,
+    limit: u32,
+) -> Vec<TruncationRecord> {
+    let mut records = Vec::new();
+    for strategy in policy.strategies() {
+        if context_tokens(context) <= limit {
+            break;
+        }
+        let record = match strategy {
+            TruncationStrategy::DropSources => drop_sources(context, limit),
+            TruncationStrategy::DropOldest => drop_oldest(context, limit),
+            TruncationStrategy::SummarizeMiddle => summarize_middle(context, limit),
+        };
+        if record.dropped_messages > 0 {
+            records.push(record);
+        }
+    }
+    records
+}
+
+/// Indices that may be removed: after the leading system prompts, before the last message
+fn removable(context: &[ContextMessage]) -> std::ops::Range<usize> {
+    let head = context
+        .iter()
+        .take_while(|m| m.role == MessageRole::System && m.source.is_none())
+        .count();
+    head..context.len().saturating_sub(1).max(head)
+}
+
+/// Opening of `message`, marked when cut short
+pub(super) fn excerpt(message: &ContextMessage) -> String {
+    let mut excerpt: String = message.content.chars().take(EXCERPT_CHARS).collect();
+    if message.content.chars().count() > EXCERPT_CHARS {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+fn empty_record(strategy: TruncationStrategy, limit: u32) -> TruncationRecord {
+    TruncationRecord {
+        strategy,
+        limit,
+        dropped_messages: 0,
+        dropped_tokens: 0,
+        dropped_sources: Vec::new(),
+        excerpts: Vec::new(),
+    }
+}
+
+fn drop_sources(context: &mut Vec<ContextMessage>, limit: u32) -> TruncationRecord {
+    let mut record = empty_record(TruncationStrategy::DropSources, limit);
+    while context_tokens(context) > limit {
+        // Least relevant (highest rank number) goes first
+        let Some(index) = removable(context)
+            .filter(|&i| context[i].source.is_some())
+            .max_by_key(|&i| context[i].source.as_ref().map(|s| s.rank))
+        else {
+            break;
+        };
+        let message = context.remove(index);
+        record.dropped_messages += 1;
+        record.dropped_tokens += estimate_tokens(&message.content);
+        record.excerpts.push(excerpt(&message));
+        if let Some(source) = message.source {
+            record.dropped_sources.push(source.id);
+        }
+    }
+    record
+}
+
+fn drop_oldest(context: &mut Vec<ContextMessage>, limit: u32) -> TruncationRecord {
+    let mut record = empty_record(TruncationStrategy::DropOldest, limit);
+    while context_tokens(context) > limit {
+        let Some(index) = removable(context).find(|&i| context[i].source.is_none()) else {
+            break;
+        };
+        let message = context.remove(index);
+        record.dropped_messages += 1;
+        record.dropped_tokens += estimate_tokens(&message.content);
+        record.excerpts.push(excerpt(&message));
+    }
+    record
+}
+
+fn summarize_middle(context: &mut Vec<ContextMessage>, limit: u32) -> TruncationRecord {
+    let mut record = empty_record(TruncationStrategy::SummarizeMiddle, limit);
+
+    // The opening turn states the task, so the middle starts after it
+    let middle: Vec<usize> = removable(context)
+        .filter(|&i| context[i].source.is_none())
+        .skip(1)
+        .collect();
+    let total = context_tokens(context);
+
+    // Take turns oldest first until the rest plus the summary fits
+    let mut taken = 0;
+    let mut removed_tokens = 0;
+    let mut summary = ContextMessage::system(String::new());
+    for &index in &middle {
+        taken += 1;
+        removed_tokens += estimate_tokens(&context[index].content);
+        record.excerpts.push(excerpt(&context[index]));
+        summary = ContextMessage::system(format!(
+            "Summary of {} earlier messages: {}",
+            taken,
+            record.excerpts.join(" | ")
+        ));
+        if total - removed_tokens + estimate_tokens(&summary.content) <= limit {
+            break;
+        }
+    }
+
+    let summary_tokens = estimate_tokens(&summary.content);
+    if taken == 0 || summary_tokens >= removed_tokens {
+        return empty_record(TruncationStrategy::SummarizeMiddle, limit);
+    }
+    let first = middle[0];
+    for &index in middle[..taken].iter().rev() {
+        context.remove(index);
+    }
+    context.insert(first, summary);
+
+    record.dropped_messages = taken as u32;
+    record.dropped_tokens = removed_tokens - summary_tokens;
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ContextMessage> {
+        vec![
+            ContextMessage::system("You are helpful."),
+            ContextMessage::source("doc-1", 1, "a".repeat(400)),
+            ContextMessage::source("doc-2", 2, "b".repeat(400)),
+            ContextMessage::user("Open the task"),
+            ContextMessage::assistant("c".repeat(400)),
+            ContextMessage::user("d".repeat(400)),
+            ContextMessage::assistant("e".repeat(400)),
+            ContextMessage::user("Latest question"),
+        ]
+    }
+
+    #[test]
+    fn test_sources_drop_least_relevant_first_and_protected_messages_stay() {
+        let mut context = conversation();
+        let policy = TruncationPolicy::new([TruncationStrategy::DropSources]);
+
+        let records = truncate_context(&policy, &mut context, 350);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].dropped_sources, ["doc-2", "doc-1"]);
+        assert_eq!(context.first().unwrap().content, "You are helpful.");
+        assert_eq!(context.last().unwrap().content, "Latest question");
+    }
+
+    #[test]
+    fn test_calibration_scales_by_the_last_reported_count() {
+        let calibration = TokenCalibration::new();
+        assert_eq!(calibration.scale("llama3", 100), 100);
+
+        calibration.record("llama3", 100, 150);
+        assert_eq!(calibration.scale("llama3", 100), 150);
+        assert_eq!(calibration.scale("mistral", 100), 100);
+
+        // Nothing to learn from an empty prompt
+        calibration.record("llama3", 0, 12);
+        assert_eq!(calibration.scale("llama3", 10), 15);
+    }
+
+    #[test]
+    fn test_summarize_middle_keeps_opening_turn_and_records_loss() {
+        let mut context = conversation();
+        let policy = TruncationPolicy::new([TruncationStrategy::SummarizeMiddle]);
+
+        let records = truncate_context(&policy, &mut context, 270);
+        assert_eq!(records[0].strategy, TruncationStrategy::SummarizeMiddle);
+        assert_eq!(records[0].dropped_messages, 3);
+        assert!(context.iter().any(|m| m.content == "Open the task"));
+        assert!(context.iter().any(|m| m.content.starts_with("Summary of 3")));
+        assert!(context_tokens(&context) <= 270);
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation Inactivity
+//!
+//! Closes conversations nobody has touched for a while. Every conversation
+//! event restarts its conversation's timer; the scheduler calls
+//! [`InactivityTimers::close_idle`] on each tick and applies the returned
+//! commands to the `Conversation` aggregate:
+//!
+//! ```text
+//! ConversationEvent ──> observe() ──> last activity (per conversation)
+//!                                          │ idle > timeout
+//!                                          v
+//!   scheduler tick ──> close_idle(now) ──> CloseConversation::timed_out
+//!                                                    │
+//!                                                    v
+//!   ConversationClosed { reason: Timeout } ──> observe() ──> release()
+//!                                                    │
+//!                                                    v
+//!                          ConversationResources (locks, workspace files, ...)
+//! ```
+//!
+//! Conversations started with a wall-clock budget are closed on the tick
+//! after their time runs out, active or not, with
+//! `CloseConversation::budget_exhausted`.
+//!
+//! Resources are released for every closed conversation, whatever the
+//! reason, so owners closing by hand free them the same way.
+
+use crate::commands::CloseConversation;
+use crate::events::ConversationEvent;
+use crate::infrastructure::DomainResult;
+use crate::value_objects::{BudgetLimit, ConversationId};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Something held on a conversation's behalf until it closes
+#[async_trait]
+pub trait ConversationResources: Send + Sync {
+    /// Free whatever is held for `conversation_id`
+    async fn release(&self, conversation_id: ConversationId) -> DomainResult<()>;
+}
+
+/// Per-conversation inactivity timers
+pub struct InactivityTimers {
+    default_timeout: Duration,
+    timeouts: Mutex<HashMap<ConversationId, Duration>>,
+    last_activity: Mutex<HashMap<ConversationId, DateTime<Utc>>>,
+    budget_expiry: Mutex<HashMap<ConversationId, DateTime<Utc>>>,
+    resources: Vec<Arc<dyn ConversationResources>>,
+}
+
+impl InactivityTimers {
+    /// Close conversations after `default_timeout` without activity
+    pub fn new(default_timeout: Duration) -> Self {
+        Self {
+            default_timeout,
+            timeouts: Mutex::new(HashMap::new()),
+            last_activity: Mutex::new(HashMap::new()),
+            budget_expiry: Mutex::new(HashMap::new()),
+            resources: Vec::new(),
+        }
+    }
+
+    /// Builder: release `resources` when a conversation closes
+    pub fn with_resources(mut self, resources: Arc<dyn ConversationResources>) -> Self {
+        self.resources.push(resources);
+        self
+    }
+
+    /// Give one conversation its own timeout
+    pub fn set_timeout(&self, conversation_id: ConversationId, timeout: Duration) {
+        self.timeouts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(conversation_id, timeout);
+    }
+
+    /// Timeout in force for a conversation
+    pub fn timeout(&self, conversation_id: ConversationId) -> Duration {
+        self.timeouts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&conversation_id)
+            .copied()
+            .unwrap_or(self.default_timeout)
+    }
+
+    /// Restart a conversation's timer, or release it once closed
+    ///
+    /// A start with a wall-clock budget also schedules the conversation's
+    /// budget closure.
+    pub async fn observe(&self, event: &ConversationEvent) -> DomainResult<()> {
+        let conversation_id = event.conversation_id();
+        if let ConversationEvent::Closed(_) = event {
+            self.last_activity
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&conversation_id);
+            self.timeouts
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&conversation_id);
+            self.budget_expiry
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&conversation_id);
+            return self.release(conversation_id).await;
+        }
+        if let ConversationEvent::Started(started) = event {
+            if let Some(max) = started.budget.max_duration() {
+                let max = chrono::Duration::from_std(max).unwrap_or(chrono::Duration::MAX);
+                self.budget_expiry
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(conversation_id, started.started_at + max);
+            }
+        }
+
+        let at = event.timestamp();
+        let mut last_activity = self.last_activity.lock().unwrap_or_else(|e| e.into_inner());
+        let last = last_activity.entry(conversation_id).or_insert(at);
+        *last = (*last).max(at);
+        Ok(())
+    }
+
+    /// Commands closing every conversation idle past its timeout, or out of
+    /// wall-clock budget, at `now`
+    ///
+    /// Each conversation is returned once; its timer starts again only if
+    /// more activity is observed before it closes.
+    pub fn close_idle(&self, now: DateTime<Utc>) -> Vec<CloseConversation> {
+        let mut last_activity = self.last_activity.lock().unwrap_or_else(|e| e.into_inner());
+        let mut budget_expiry = self.budget_expiry.lock().unwrap_or_else(|e| e.into_inner());
+        let expired: Vec<ConversationId> = budget_expiry
+            .iter()
+            .filter(|(_, expires_at)| now >= **expires_at)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &expired {
+            budget_expiry.remove(id);
+            last_activity.remove(id);
+        }
+
+        let idle: Vec<ConversationId> = last_activity
+            .iter()
+            .filter(|(id, last)| {
+                let timeout =
+                    chrono::Duration::from_std(self.timeout(**id)).unwrap_or(chrono::Duration::MAX);
+                now.signed_duration_since(**last) > timeout
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &idle {
+            last_activity.remove(id);
+        }
+        expired
+            .into_iter()
+            .map(|id| CloseConversation::budget_exhausted(id, BudgetLimit::WallClock))
+            .chain(idle.into_iter().map(CloseConversation::timed_out))
+            .collect()
+    }
+
+    /// Release everything held for a conversation
+    ///
+    /// Every resource is asked even if an earlier one fails; the first
+    /// failure is returned.
+    pub async fn release(&self, conversation_id: ConversationId) -> DomainResult<()> {
+        let mut first_error = None;
+        for resources in &self.resources {
+            if let Err(e) = resources.release(conversation_id).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+impl Default for InactivityTimers {
+    /// 30 minutes
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30 * 60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::Conversation;
+    use crate::commands::{ConversationCommand, StartConversation};
+    use crate::events::ClosureReason;
+    use crate::infrastructure::DomainError;
+    use crate::value_objects::PersonId;
+
+    #[derive(Default)]
+    struct Workspaces {
+        released: Mutex<Vec<ConversationId>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl ConversationResources for Workspaces {
+        async fn release(&self, conversation_id: ConversationId) -> DomainResult<()> {
+            self.released.lock().unwrap().push(conversation_id);
+            if self.fail {
+                return Err(DomainError::ResourceReleaseError("busy".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idle_conversation_times_out_and_releases_resources() {
+        let workspaces = Arc::new(Workspaces::default());
+        let timers =
+            InactivityTimers::new(Duration::from_secs(60)).with_resources(workspaces.clone());
+
+        let start = ConversationCommand::Start(StartConversation::new(PersonId::new()));
+        let (conversation, events) = Conversation::empty().execute(&start).unwrap();
+        let started_at = events[0].timestamp();
+        timers.observe(&events[0]).await.unwrap();
+
+        let now = started_at + chrono::Duration::seconds(30);
+        assert!(timers.close_idle(now).is_empty());
+        let now = started_at + chrono::Duration::seconds(61);
+        let closes = timers.close_idle(now);
+        assert_eq!(closes.len(), 1);
+        assert!(timers.close_idle(now).is_empty());
+
+        let (conversation, events) = conversation
+            .execute(&ConversationCommand::Close(closes[0].clone()))
+            .unwrap();
+        assert_eq!(conversation.closed(), Some(ClosureReason::Timeout));
+        timers.observe(&events[0]).await.unwrap();
+        assert_eq!(
+            *workspaces.released.lock().unwrap(),
+            vec![conversation.id()]
+        );
+
+        // Closed conversations accept nothing more
+        assert!(conversation
+            .handle(&ConversationCommand::Close(closes[0].clone()))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_per_conversation_timeout_and_release_continues_past_failures() {
+        let failing = Arc::new(Workspaces {
+            fail: true,
+            ..Workspaces::default()
+        });
+        let healthy = Arc::new(Workspaces::default());
+        let timers = InactivityTimers::default()
+            .with_resources(failing.clone())
+            .with_resources(healthy.clone());
+
+        let id = ConversationId::new();
+        timers.set_timeout(id, Duration::from_secs(5));
+        assert_eq!(timers.timeout(id), Duration::from_secs(5));
+        assert_eq!(
+            timers.timeout(ConversationId::new()),
+            Duration::from_secs(30 * 60)
+        );
+
+        assert!(timers.release(id).await.is_err());
+        assert_eq!(healthy.released.lock().unwrap().len(), 1);
+    }
+}
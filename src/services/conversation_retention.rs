@@ -0,0 +1,322 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation Retention
+//!
+//! Retires idle conversations from the hot path. Each agent's message
+//! events since its last archival form its open conversation; once no
+//! message event has arrived for the agent's [`RetentionPolicy::ttl`], the
+//! conversation is written to an [`ArchiveStore`] and a
+//! `ConversationArchived` event is returned for the caller to publish.
+//!
+//! ```text
+//! message events ──> observe() ──> open conversation (per agent)
+//!                                        │ idle > ttl
+//!                                        v
+//!                    archive_idle() ──> ArchiveStore ──> ConversationArchived
+//!                                                              │
+//!                      hot read models drop the messages <─────┘
+//! ```
+//!
+//! The event stream itself is left intact; `EventRetention` decides when the
+//! originals age out. An archive store that keeps conversations as blobs
+//! names the blob in the event, so any instance can read it back.
+//!
+//! Agents under a legal hold are skipped: their idle conversations stay open
+//! until the hold is released, and [`ConversationRetention::refuse_held`]
+//! records one `LegalHoldRefused` per conversation held back.
+
+use super::LegalHolds;
+use crate::events::{AgentEvent, ConversationArchivedEvent, LegalHoldRefusedEvent};
+use crate::infrastructure::{ArchiveStore, ArchivedConversation, DomainResult, EventEnvelope};
+use crate::value_objects::{AgentId, ConversationId, HeldOperation};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a conversation may sit idle before it is archived
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Idle time after the last message event
+    pub ttl: Duration,
+}
+
+impl RetentionPolicy {
+    /// Archive after `ttl` without activity
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl }
+    }
+}
+
+impl Default for RetentionPolicy {
+    /// 30 days
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30 * 24 * 60 * 60))
+    }
+}
+
+#[derive(Debug)]
+struct OpenConversation {
+    events: Vec<EventEnvelope>,
+    messages: u32,
+    first_message_at: DateTime<Utc>,
+    last_message_at: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    /// A legal hold refusal was recorded for this conversation
+    refused: bool,
+}
+
+/// Tracks open conversations and archives the idle ones
+#[derive(Debug, Default)]
+pub struct ConversationRetention {
+    default_policy: RetentionPolicy,
+    policies: HashMap<AgentId, RetentionPolicy>,
+    open: Mutex<HashMap<AgentId, OpenConversation>>,
+    legal_holds: Arc<LegalHolds>,
+}
+
+impl ConversationRetention {
+    /// Apply `default_policy` to every agent without its own policy
+    pub fn new(default_policy: RetentionPolicy) -> Self {
+        Self {
+            default_policy,
+            ..Self::default()
+        }
+    }
+
+    /// Give one agent its own policy
+    pub fn with_policy(mut self, agent_id: AgentId, policy: RetentionPolicy) -> Self {
+        self.policies.insert(agent_id, policy);
+        self
+    }
+
+    /// Skip agents held in `legal_holds`
+    pub fn with_legal_holds(mut self, legal_holds: Arc<LegalHolds>) -> Self {
+        self.legal_holds = legal_holds;
+        self
+    }
+
+    /// Policy in force for an agent
+    pub fn policy(&self, agent_id: AgentId) -> RetentionPolicy {
+        self.policies.get(&agent_id).copied().unwrap_or(self.default_policy)
+    }
+
+    /// Record a stored event; only message events are kept
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        let event = &envelope.event;
+        let is_message = matches!(
+            event,
+            AgentEvent::MessageSent(_)
+                | AgentEvent::ResponseChunkReceived(_)
+                | AgentEvent::ResponseCompleted(_)
+                | AgentEvent::ResponseFailed(_)
+                | AgentEvent::ResponseCancelled(_)
+        );
+        if !is_message {
+            return;
+        }
+
+        let at = event.timestamp();
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let conversation = open.entry(envelope.aggregate_id).or_insert_with(|| OpenConversation {
+            events: Vec::new(),
+            messages: 0,
+            first_message_at: at,
+            last_message_at: at,
+            last_activity: at,
+            refused: false,
+        });
+        if let AgentEvent::MessageSent(e) = event {
+            conversation.messages += 1;
+            conversation.first_message_at = conversation.first_message_at.min(e.sent_at);
+            conversation.last_message_at = conversation.last_message_at.max(e.sent_at);
+        }
+        conversation.last_activity = conversation.last_activity.max(at);
+        conversation.events.push(envelope.clone());
+    }
+
+    /// Agents with an open conversation
+    pub fn open_agents(&self) -> Vec<AgentId> {
+        let open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        open.keys().copied().collect()
+    }
+
+    fn is_idle(&self, agent_id: AgentId, c: &OpenConversation, now: DateTime<Utc>) -> bool {
+        let ttl = chrono::Duration::from_std(self.policy(agent_id).ttl)
+            .unwrap_or(chrono::Duration::MAX);
+        now.signed_duration_since(c.last_activity) > ttl
+    }
+
+    /// Refuse to archive idle conversations of agents under legal hold
+    ///
+    /// Returns one event per conversation the first time it is held back.
+    pub fn refuse_held(&self, now: DateTime<Utc>) -> Vec<LegalHoldRefusedEvent> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        open.iter_mut()
+            .filter(|(agent_id, c)| !c.refused && self.is_idle(**agent_id, c, now))
+            .filter_map(|(agent_id, c)| {
+                let refused = self.legal_holds.check(*agent_id, HeldOperation::Archival).err()?;
+                c.refused = true;
+                Some(refused)
+            })
+            .collect()
+    }
+
+    /// Archive every conversation idle past its agent's TTL at `now`
+    ///
+    /// Returns one event per archived conversation. A conversation whose
+    /// archival fails stays open and is retried on the next call; agents
+    /// under legal hold are skipped.
+    pub async fn archive_idle(
+        &self,
+        store: &dyn ArchiveStore,
+        now: DateTime<Utc>,
+    ) -> DomainResult<Vec<ConversationArchivedEvent>> {
+        let idle: Vec<(AgentId, OpenConversation)> = {
+            let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+            let agents: Vec<AgentId> = open
+                .iter()
+                .filter(|(agent_id, c)| self.is_idle(**agent_id, c, now))
+                .filter(|(agent_id, _)| !self.legal_holds.is_held(**agent_id))
+                .map(|(agent_id, _)| *agent_id)
+                .collect();
+            agents
+                .into_iter()
+                .filter_map(|agent_id| open.remove(&agent_id).map(|c| (agent_id, c)))
+                .collect()
+        };
+
+        let mut archived = Vec::with_capacity(idle.len());
+        let mut idle = idle.into_iter();
+        while let Some((agent_id, conversation)) = idle.next() {
+            let conversation_id = ConversationId::new();
+            let mut event = ConversationArchivedEvent::new(
+                agent_id,
+                conversation_id,
+                conversation.messages,
+                conversation.first_message_at,
+                conversation.last_message_at,
+            );
+            let record = ArchivedConversation {
+                conversation_id,
+                agent_id,
+                events: conversation.events,
+                archived_at: event.archived_at,
+            };
+            match store.archive(record.clone()).await {
+                Ok(Some(archive)) => event = event.with_archive(archive),
+                Ok(None) => {}
+                Err(e) => {
+                    self.reopen(
+                        agent_id,
+                        OpenConversation {
+                            events: record.events,
+                            ..conversation
+                        },
+                    );
+                    for (agent_id, conversation) in idle {
+                        self.reopen(agent_id, conversation);
+                    }
+                    return Err(e);
+                }
+            }
+            archived.push(event);
+        }
+        Ok(archived)
+    }
+
+    /// Put a conversation back, ahead of anything observed since it was taken
+    fn reopen(&self, agent_id: AgentId, mut earlier: OpenConversation) {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(later) = open.remove(&agent_id) {
+            earlier.events.extend(later.events);
+            earlier.messages += later.messages;
+            earlier.first_message_at = earlier.first_message_at.min(later.first_message_at);
+            earlier.last_message_at = earlier.last_message_at.max(later.last_message_at);
+            earlier.last_activity = earlier.last_activity.max(later.last_activity);
+            earlier.refused |= later.refused;
+        }
+        open.insert(agent_id, earlier);
+    }
+
+    /// Read an archived conversation's events back
+    ///
+    /// A [`BlobArchiveStore`](crate::infrastructure::BlobArchiveStore) finds
+    /// conversations archived by other instances once it has observed their
+    /// `ConversationArchived` events.
+    pub async fn restore(
+        store: &dyn ArchiveStore,
+        conversation_id: ConversationId,
+    ) -> DomainResult<Option<Vec<EventEnvelope>>> {
+        Ok(store.load(conversation_id).await?.map(|c| c.events))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::MessageSentEvent;
+    use crate::infrastructure::InMemoryArchiveStore;
+    use crate::test_support::envelope;
+    use crate::value_objects::{LegalHold, MessageId, PersonId};
+
+    fn sent(agent_id: AgentId) -> EventEnvelope {
+        let message = MessageSentEvent::new(agent_id, MessageId::new(), "hi");
+        envelope(0, AgentEvent::MessageSent(message))
+    }
+
+    #[tokio::test]
+    async fn test_archives_only_idle_conversations() {
+        let (quick, slow) = (AgentId::new(), AgentId::new());
+        let hour = RetentionPolicy::new(Duration::from_secs(3600));
+        let retention = ConversationRetention::new(hour)
+            .with_policy(quick, RetentionPolicy::new(Duration::from_secs(60)));
+        let store = InMemoryArchiveStore::new();
+        retention.observe(&sent(quick));
+        retention.observe(&sent(quick));
+        retention.observe(&sent(slow));
+
+        let later = Utc::now() + chrono::Duration::minutes(5);
+        let archived = retention.archive_idle(&store, later).await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].agent_id, quick);
+        assert_eq!(archived[0].message_count, 2);
+
+        let events = ConversationRetention::restore(&store, archived[0].conversation_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        // Already archived; the slow agent is still within its TTL
+        assert!(retention.archive_idle(&store, later).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_legal_hold_keeps_conversations_open() {
+        let agent_id = AgentId::new();
+        let holds = Arc::new(LegalHolds::new());
+        let retention = ConversationRetention::new(RetentionPolicy::new(Duration::from_secs(60)))
+            .with_legal_holds(holds.clone());
+        let store = InMemoryArchiveStore::new();
+        retention.observe(&sent(agent_id));
+        holds.set(
+            agent_id,
+            Some(LegalHold {
+                reason: "Matter 2025-114".to_string(),
+                placed_by: PersonId::new(),
+                placed_at: Utc::now(),
+            }),
+        );
+
+        let later = Utc::now() + chrono::Duration::minutes(5);
+        assert!(retention.archive_idle(&store, later).await.unwrap().is_empty());
+        let refused = retention.refuse_held(later);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].operation, HeldOperation::Archival);
+        assert!(retention.refuse_held(later).is_empty());
+
+        holds.set(agent_id, None);
+        assert_eq!(retention.archive_idle(&store, later).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation Transfer
+//!
+//! Prepares the hand-off of a conversation from one agent to another. The
+//! target is checked before anything moves, and the handoff is fitted to
+//! the target's context window:
+//!
+//! ```text
+//! prepare(from, to, handoff)
+//!    │
+//!    ├── to not operational / lacks capabilities ──> TransferError
+//!    │
+//!    ├── context fits to's window ──> TransferConversation (as is)
+//!    │
+//!    └── too long ──> full context ──> BlobStore (transcript)
+//!                     oldest turns dropped ──> TransferConversation
+//! ```
+//!
+//! The returned command is executed against the `Conversation` aggregate,
+//! which records `ConversationTransferred` at the last message: the point
+//! where one agent stopped and the next one picks up.
+
+use super::{context_tokens, truncate_context, CapabilityStatement};
+use crate::aggregate::Agent;
+use crate::capabilities::CapabilityRequirements;
+use crate::commands::TransferConversation;
+use crate::infrastructure::{BlobStore, DomainError};
+use crate::value_objects::{
+    next_id, AgentId, ContentRef, ConversationHandoff, ConversationId, ParticipantId,
+    TruncationPolicy, TruncationStrategy,
+};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Tokens of the target's window left free for its reply, by default
+pub const DEFAULT_HANDOFF_HEADROOM_TOKENS: u32 = 1024;
+
+/// Why a conversation cannot be handed to an agent
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("Conversation cannot be transferred from agent {0} to itself")]
+    SameAgent(AgentId),
+
+    #[error("Agent {0} is not operational")]
+    NotOperational(AgentId),
+
+    #[error("Agent {agent_id} lacks required capabilities: {}", missing.join(", "))]
+    MissingCapabilities {
+        agent_id: AgentId,
+        missing: Vec<String>,
+    },
+
+    #[error(transparent)]
+    Store(#[from] DomainError),
+}
+
+/// Verifies transfer targets and fits handoffs to them
+pub struct ConversationTransfer {
+    requirements: CapabilityRequirements,
+    blobs: Option<Arc<dyn BlobStore>>,
+    headroom_tokens: u32,
+}
+
+impl ConversationTransfer {
+    /// Transfers to agents meeting `requirements`
+    pub fn new(requirements: CapabilityRequirements) -> Self {
+        Self {
+            requirements,
+            blobs: None,
+            headroom_tokens: DEFAULT_HANDOFF_HEADROOM_TOKENS,
+        }
+    }
+
+    /// Builder: upload context that does not fit the target to `blobs`
+    pub fn with_blob_store(mut self, blobs: Arc<dyn BlobStore>) -> Self {
+        self.blobs = Some(blobs);
+        self
+    }
+
+    /// Builder: leave `tokens` of the target's window for its reply
+    pub fn with_headroom(mut self, tokens: u32) -> Self {
+        self.headroom_tokens = tokens;
+        self
+    }
+
+    /// Check that `target` can carry a conversation on
+    pub fn verify(&self, target: &Agent) -> Result<CapabilityStatement, TransferError> {
+        let statement = CapabilityStatement::for_agent(target, next_id());
+        if !statement.operational {
+            return Err(TransferError::NotOperational(target.id()));
+        }
+        let missing = statement.unmet(&self.requirements);
+        if !missing.is_empty() {
+            return Err(TransferError::MissingCapabilities {
+                agent_id: target.id(),
+                missing,
+            });
+        }
+        Ok(statement)
+    }
+
+    /// Build the command handing `conversation_id` from `from` to `to`
+    ///
+    /// When the handoff exceeds `to`'s context window, the full context is
+    /// uploaded as the transcript (if a blob store is set) and the oldest
+    /// turns are dropped from what is carried inline.
+    pub async fn prepare(
+        &self,
+        conversation_id: ConversationId,
+        actor: ParticipantId,
+        from: AgentId,
+        to: &Agent,
+        mut handoff: ConversationHandoff,
+    ) -> Result<TransferConversation, TransferError> {
+        if from == to.id() {
+            return Err(TransferError::SameAgent(from));
+        }
+        let statement = self.verify(to)?;
+
+        if let Some(window) = statement.max_context_length {
+            let note = context_tokens(&handoff.to_context()) - context_tokens(&handoff.context);
+            let limit = window
+                .saturating_sub(self.headroom_tokens)
+                .saturating_sub(note);
+            if context_tokens(&handoff.context) > limit {
+                if let Some(blobs) = &self.blobs {
+                    let transcript = serde_json::to_vec(&handoff.context)
+                        .map_err(|e| DomainError::SerializationError(e.to_string()))?;
+                    let reference = ContentRef::of(&transcript);
+                    blobs.put(&reference, transcript).await?;
+                    handoff.transcript = Some(reference);
+                }
+                let policy = TruncationPolicy::new([TruncationStrategy::DropOldest]);
+                truncate_context(&policy, &mut handoff.context, limit);
+            }
+        }
+
+        Ok(TransferConversation::new(
+            conversation_id,
+            actor,
+            from,
+            to.id(),
+            handoff,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentActivatedEvent, AgentDeployedEvent, AgentEvent, ModelConfiguredEvent,
+    };
+    use crate::infrastructure::InMemoryBlobStore;
+    use crate::value_objects::{ContextMessage, ModelConfig, PersonId};
+
+    fn agent(activated: bool) -> Agent {
+        let id = AgentId::new();
+        let mut events = vec![
+            AgentEvent::AgentDeployed(AgentDeployedEvent::new(id, PersonId::new(), "t", None)),
+            AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+        ];
+        if activated {
+            events.push(AgentEvent::AgentActivated(AgentActivatedEvent::new(id)));
+        }
+        Agent::empty().apply_events(&events).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_oversized_handoff_is_trimmed_and_uploaded() {
+        let blobs = Arc::new(InMemoryBlobStore::new());
+        let transfer = ConversationTransfer::new(CapabilityRequirements::text_chat())
+            .with_blob_store(blobs.clone());
+        let (from, to) = (AgentId::new(), agent(true));
+        let turns: Vec<ContextMessage> = (0..40)
+            .map(|i| ContextMessage::user(format!("{} {}", i, "x".repeat(1000))))
+            .collect();
+        let handoff = ConversationHandoff::new("needs a specialist")
+            .with_context(turns.clone())
+            .with_memory("account", "42");
+
+        let cmd = transfer
+            .prepare(
+                ConversationId::new(),
+                ParticipantId::Agent(from),
+                from,
+                &to,
+                handoff,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(cmd.to, to.id());
+        assert!(context_tokens(&cmd.handoff.to_context()) <= 4_096 - 1024);
+        assert_eq!(cmd.handoff.context.last(), turns.last());
+        let transcript = cmd.handoff.transcript.expect("full context uploaded");
+        let uploaded = blobs.get(&transcript).await.unwrap().unwrap();
+        let restored: Vec<ContextMessage> = serde_json::from_slice(&uploaded).unwrap();
+        assert_eq!(restored, turns);
+    }
+
+    #[tokio::test]
+    async fn test_inactive_target_is_refused() {
+        let transfer = ConversationTransfer::new(CapabilityRequirements::text_chat());
+        let to = agent(false);
+        let result = transfer
+            .prepare(
+                ConversationId::new(),
+                ParticipantId::Agent(AgentId::new()),
+                AgentId::new(),
+                &to,
+                ConversationHandoff::new("escalation"),
+            )
+            .await;
+        assert!(matches!(result, Err(TransferError::NotOperational(id)) if id == to.id()));
+    }
+}
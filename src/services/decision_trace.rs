@@ -0,0 +1,234 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Decision traces
+//!
+//! Turns what [`AgentMessageService::send_routed`](super::AgentMessageService::send_routed)
+//! decided for one request into a [`ReasoningTrace`], rooted at the plan:
+//!
+//! ```text
+//! plan ──┬── long-context switch          (plan)
+//!        ├── each retrieved source        (retrieval: kept / dropped)
+//!        ├── tools offered                (tool_selection)
+//!        ├── each truncation              (truncation)
+//!        ├── judge's pick                 (judgment)
+//!        │     └── each candidate         (candidate)
+//!        ├── each critique                (critique)
+//!        └── each constraint enforcement  (critique)
+//! ```
+
+use super::context_truncation::excerpt;
+use super::RoutedStream;
+use crate::intent::MessageIntent;
+use crate::value_objects::{
+    ContextMessage, EnforcementAction, ModelConfig, ReasoningTrace, TraceStepKind,
+};
+
+/// Trace the decisions behind `routed`
+///
+/// `retrieved` are the source messages of the context as assembled, before
+/// any truncation; `config` is the model that answered.
+pub fn trace_decisions(
+    intent: &MessageIntent,
+    config: &ModelConfig,
+    retrieved: &[ContextMessage],
+    routed: &RoutedStream,
+) -> ReasoningTrace {
+    let mut trace = ReasoningTrace::new();
+    let strategy = match (&routed.best_of, &routed.reflection) {
+        (Some(judged), _) => format!(", best of {}", judged.candidates.len()),
+        (None, Some(outcome)) => format!(", reflected over {} drafts", outcome.drafts.len()),
+        (None, None) => String::new(),
+    };
+    let plan = trace.step(
+        TraceStepKind::Plan,
+        format!(
+            "Answer {} with {}/{}{}",
+            intent.name(),
+            config.provider,
+            config.model_name,
+            strategy
+        ),
+    );
+
+    if let Some(switch) = &routed.long_context {
+        trace.child(
+            plan,
+            TraceStepKind::Plan,
+            format!(
+                "Switched from {}/{}: {} tokens exceed its {}-token window",
+                switch.from.provider, switch.from.model_name, switch.context_tokens, switch.limit
+            ),
+            None,
+        );
+    }
+
+    for message in retrieved {
+        let Some(source) = &message.source else {
+            continue;
+        };
+        let dropped = routed
+            .truncations
+            .iter()
+            .any(|t| t.dropped_sources.contains(&source.id));
+        trace.child(
+            plan,
+            TraceStepKind::Retrieval,
+            format!(
+                "{} {} (rank {})",
+                if dropped { "Dropped" } else { "Kept" },
+                source.id,
+                source.rank
+            ),
+            Some(excerpt(message)),
+        );
+    }
+
+    if let MessageIntent::Chat {
+        tools: Some(tools), ..
+    } = intent
+    {
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        let reasons: Vec<String> = tools
+            .iter()
+            .map(|t| format!("{}: {}", t.name, t.description))
+            .collect();
+        trace.child(
+            plan,
+            TraceStepKind::ToolSelection,
+            format!("Offered {} tools: {}", tools.len(), names.join(", ")),
+            Some(reasons.join("\n")),
+        );
+    }
+
+    for truncation in &routed.truncations {
+        trace.child(
+            plan,
+            TraceStepKind::Truncation,
+            format!(
+                "{} removed {} messages ({} tokens) to fit {} tokens",
+                truncation.strategy,
+                truncation.dropped_messages,
+                truncation.dropped_tokens,
+                truncation.limit
+            ),
+            Some(truncation.excerpts.join("\n")),
+        );
+    }
+
+    if let Some(judged) = &routed.best_of {
+        let judgment = trace.child(
+            plan,
+            TraceStepKind::Judgment,
+            format!(
+                "{} picked candidate {} of {}",
+                judged.judge,
+                judged.selected + 1,
+                judged.candidates.len()
+            ),
+            Some(judged.rationale.clone()),
+        );
+        for (i, candidate) in judged.candidates.iter().enumerate() {
+            let (outcome, detail) = match &candidate.error {
+                Some(error) => ("failed", error.clone()),
+                None => ("answered", excerpt(&ContextMessage::assistant(&candidate.content))),
+            };
+            trace.child(
+                judgment,
+                TraceStepKind::Candidate,
+                format!(
+                    "Candidate {} from {}/{} {}",
+                    i + 1,
+                    candidate.provider,
+                    candidate.model,
+                    outcome
+                ),
+                Some(detail),
+            );
+        }
+    }
+
+    if let Some(outcome) = &routed.reflection {
+        for draft in &outcome.drafts {
+            let Some(critique) = &draft.critique else {
+                continue;
+            };
+            let revised = outcome.drafts.iter().any(|d| d.round == draft.round + 1);
+            trace.child(
+                plan,
+                TraceStepKind::Critique,
+                format!(
+                    "Draft {} {}",
+                    draft.round,
+                    if revised { "critiqued and revised" } else { "critiqued, kept" }
+                ),
+                Some(critique.clone()),
+            );
+        }
+    }
+
+    for enforcement in &routed.constraint_enforcements {
+        let outcome = match enforcement.action {
+            EnforcementAction::Regenerated => "regenerated",
+            EnforcementAction::Delivered => "delivered anyway",
+            EnforcementAction::Failed => "failed",
+        };
+        let broken: Vec<String> = enforcement
+            .violations
+            .iter()
+            .map(|v| format!("{}: {}", v.constraint, v.detail))
+            .collect();
+        trace.child(
+            plan,
+            TraceStepKind::Critique,
+            format!("Answer {} broke its constraints, {}", enforcement.attempt, outcome),
+            Some(broken.join("; ")),
+        );
+    }
+
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::ToolDefinition;
+    use crate::value_objects::TruncationRecord;
+
+    #[test]
+    fn test_trace_records_sources_tools_and_truncation() {
+        let retrieved = vec![
+            ContextMessage::source("doc-1", 1, "Revenue grew"),
+            ContextMessage::source("doc-2", 2, "Old memo"),
+        ];
+        let tools = vec![ToolDefinition::new(
+            "search",
+            "Look up documents",
+            serde_json::json!({}),
+        )];
+        let intent = MessageIntent::chat_with_tools(vec![ContextMessage::user("Hi")], tools);
+        let routed = RoutedStream {
+            stream: Box::pin(futures::stream::empty()),
+            long_context: None,
+            truncations: vec![TruncationRecord {
+                strategy: crate::value_objects::TruncationStrategy::DropSources,
+                limit: 100,
+                dropped_messages: 1,
+                dropped_tokens: 3,
+                dropped_sources: vec!["doc-2".to_string()],
+                excerpts: vec!["Old memo".to_string()],
+            }],
+            best_of: None,
+            reflection: None,
+            constraint_enforcements: Vec::new(),
+            trace: ReasoningTrace::new(),
+        };
+
+        let trace = trace_decisions(&intent, &ModelConfig::mock(), &retrieved, &routed);
+        let summaries: Vec<&str> = trace.steps().iter().map(|s| s.summary.as_str()).collect();
+        assert!(summaries[0].starts_with("Answer chat with"));
+        assert!(summaries.contains(&"Kept doc-1 (rank 1)"));
+        assert!(summaries.contains(&"Dropped doc-2 (rank 2)"));
+        assert!(summaries.contains(&"Offered 1 tools: search"));
+        assert!(trace.steps()[1..].iter().all(|s| s.parent == Some(0)));
+    }
+}
@@ -0,0 +1,290 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Drift Detection
+//!
+//! Periodically compares the agent definitions in a [`FleetManifest`] with
+//! the live aggregates and reports every agent that has wandered from its
+//! declaration, field by field:
+//!
+//! ```text
+//! manifest + live agents ──> check() ──> ConfigurationDriftDetected (per agent)
+//!                                │
+//!                                │ auto-remediate
+//!                                v
+//!                          ManifestPlan ──> caller dispatches the commands
+//! ```
+//!
+//! An agent is reported when its set of drifted fields changes, so steady
+//! drift is raised once and a fix is raised as an event with no fields.
+//! Declared agents that were never deployed have no aggregate to report
+//! against; they only show up in the remediation plan.
+
+use super::fleet_manifest::{apply_manifest, find_live, sorted, validate};
+use super::{AgentManifest, FleetManifest, ManifestPlan, ManifestResult};
+use crate::aggregate::Agent;
+use crate::events::{ConfigurationDriftDetectedEvent, FieldDrift};
+use crate::read_model::AgentGraphProjection;
+use crate::value_objects::{AgentId, AgentStatus, ModelConfig};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Debug, Display};
+use std::sync::Mutex;
+
+/// Outcome of one drift check
+#[derive(Debug, Clone, Default)]
+pub struct DriftReport {
+    /// Agents whose drift changed since the previous check
+    pub events: Vec<ConfigurationDriftDetectedEvent>,
+
+    /// Commands that converge the fleet (auto-remediation only)
+    pub remediation: Option<ManifestPlan>,
+}
+
+/// Compares declared agent definitions with live aggregates
+#[derive(Debug, Default)]
+pub struct DriftDetector {
+    auto_remediate: bool,
+    reported: Mutex<Vec<(AgentId, Vec<FieldDrift>)>>,
+}
+
+impl DriftDetector {
+    /// Create a detector that only reports
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also plan the commands that undo the drift
+    pub fn with_auto_remediate(mut self, enabled: bool) -> Self {
+        self.auto_remediate = enabled;
+        self
+    }
+
+    /// Compare `manifest` with the live agents
+    ///
+    /// Fails without reporting anything if the manifest is inconsistent.
+    pub fn check<'a>(
+        &self,
+        manifest: &FleetManifest,
+        agents: impl IntoIterator<Item = &'a Agent>,
+        graph: &AgentGraphProjection,
+    ) -> ManifestResult<DriftReport> {
+        validate(manifest)?;
+        let live: Vec<&Agent> = agents.into_iter().collect();
+
+        let mut current = Vec::new();
+        let mut claimed = HashSet::new();
+        for desired in &manifest.agents {
+            if let Some(agent) = find_live(desired, &live) {
+                claimed.insert(agent.id());
+                let fields = diff(desired, agent, graph);
+                if !fields.is_empty() {
+                    current.push((agent.id(), fields));
+                }
+            }
+        }
+        if manifest.prune {
+            for agent in &live {
+                if !claimed.contains(&agent.id()) && !agent.is_decommissioned() {
+                    let undeclared = FieldDrift::new("agent", "undeclared", "deployed");
+                    current.push((agent.id(), vec![undeclared]));
+                }
+            }
+        }
+
+        let remediation = if self.auto_remediate {
+            Some(apply_manifest(manifest, live.iter().copied(), graph)?)
+                .filter(|plan| !plan.is_converged())
+        } else {
+            None
+        };
+        let planned = |agent_id: AgentId| {
+            remediation.as_ref().is_some_and(|plan| {
+                plan.commands.iter().any(|c| c.agent_id() == agent_id)
+                    || plan.tools.iter().any(|t| t.agent_id == agent_id)
+            })
+        };
+
+        let mut reported = self.reported.lock().unwrap_or_else(|e| e.into_inner());
+        let previous: HashMap<AgentId, &Vec<FieldDrift>> =
+            reported.iter().map(|(id, fields)| (*id, fields)).collect();
+        let mut events: Vec<ConfigurationDriftDetectedEvent> = current
+            .iter()
+            .filter(|(id, fields)| previous.get(id) != Some(&fields))
+            .map(|(id, fields)| {
+                ConfigurationDriftDetectedEvent::new(*id, fields.clone(), planned(*id))
+            })
+            .collect();
+        let still_drifted: HashSet<AgentId> = current.iter().map(|(id, _)| *id).collect();
+        events.extend(
+            reported
+                .iter()
+                .filter(|(id, _)| !still_drifted.contains(id))
+                .map(|(id, _)| ConfigurationDriftDetectedEvent::new(*id, Vec::new(), false)),
+        );
+        *reported = current;
+
+        Ok(DriftReport {
+            events,
+            remediation,
+        })
+    }
+}
+
+/// Fields where a live agent differs from its declaration
+#[allow(deprecated)]
+fn diff(desired: &AgentManifest, agent: &Agent, graph: &AgentGraphProjection) -> Vec<FieldDrift> {
+    let mut fields = Vec::new();
+    compare(&mut fields, "status", &desired.status, &agent.status());
+    if agent.is_decommissioned() || desired.status == AgentStatus::Decommissioned {
+        return fields;
+    }
+
+    compare(&mut fields, "name", &desired.name.as_str(), &agent.name());
+    compare(&mut fields, "owner", &desired.owner, &agent.person_id());
+    compare_debug(
+        &mut fields,
+        "blueprint.description",
+        &desired.blueprint.description.as_deref(),
+        &agent.description(),
+    );
+    match (&desired.config, agent.model_config()) {
+        (Some(declared), Some(actual)) => compare_config(&mut fields, declared, actual),
+        (Some(_), None) => fields.push(FieldDrift::new("config", "set", "unset")),
+        (None, _) => {}
+    }
+    compare_debug(&mut fields, "tools", &sorted(&desired.tools), &graph.tools(agent.id()));
+    fields
+}
+
+fn compare_config(fields: &mut Vec<FieldDrift>, declared: &ModelConfig, actual: &ModelConfig) {
+    compare(fields, "config.provider", &declared.provider, &actual.provider);
+    compare(fields, "config.model_name", &declared.model_name, &actual.model_name);
+    compare_debug(fields, "config.api_endpoint", &declared.api_endpoint, &actual.api_endpoint);
+    compare(fields, "config.temperature", &declared.temperature, &actual.temperature);
+    compare(fields, "config.top_p", &declared.top_p, &actual.top_p);
+    compare(fields, "config.max_tokens", &declared.max_tokens, &actual.max_tokens);
+    compare(
+        fields,
+        "config.frequency_penalty",
+        &declared.frequency_penalty,
+        &actual.frequency_penalty,
+    );
+    compare(
+        fields,
+        "config.presence_penalty",
+        &declared.presence_penalty,
+        &actual.presence_penalty,
+    );
+    compare_debug(
+        fields,
+        "config.stop_sequences",
+        &declared.stop_sequences,
+        &actual.stop_sequences,
+    );
+    compare(fields, "config.system_prompt", &declared.system_prompt, &actual.system_prompt);
+    compare_debug(fields, "config.generation", &declared.generation, &actual.generation);
+    // Sorted so the rendered value is stable between checks
+    compare_debug(
+        fields,
+        "config.intent_overrides",
+        &declared.intent_overrides.iter().collect::<BTreeMap<_, _>>(),
+        &actual.intent_overrides.iter().collect::<BTreeMap<_, _>>(),
+    );
+    compare_debug(fields, "config.truncation", &declared.truncation, &actual.truncation);
+    compare_debug(
+        fields,
+        "config.best_of",
+        &declared.best_of.iter().collect::<BTreeMap<_, _>>(),
+        &actual.best_of.iter().collect::<BTreeMap<_, _>>(),
+    );
+    compare_debug(fields, "config.reflection", &declared.reflection, &actual.reflection);
+    compare_debug(
+        fields,
+        "config.output_constraints",
+        &declared.output_constraints,
+        &actual.output_constraints,
+    );
+}
+
+fn compare<T: PartialEq + Display>(
+    fields: &mut Vec<FieldDrift>,
+    field: &str,
+    declared: &T,
+    actual: &T,
+) {
+    if declared != actual {
+        fields.push(FieldDrift::new(field, declared.to_string(), actual.to_string()));
+    }
+}
+
+fn compare_debug<T: PartialEq + Debug>(
+    fields: &mut Vec<FieldDrift>,
+    field: &str,
+    declared: &T,
+    actual: &T,
+) {
+    if declared != actual {
+        fields.push(FieldDrift::new(field, format!("{:?}", declared), format!("{:?}", actual)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::AgentCommand;
+    use crate::events::{AgentActivatedEvent, AgentDeployedEvent, AgentEvent, ModelConfiguredEvent};
+    use crate::services::export_manifest;
+    use crate::value_objects::PersonId;
+
+    fn live_agent(name: &str) -> Agent {
+        let (id, owner) = (AgentId::new(), PersonId::new());
+        Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(id, owner, name, None)),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(id)),
+            ])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reports_drift_once_and_convergence() {
+        let agents = vec![live_agent("sage")];
+        let graph = AgentGraphProjection::new();
+        let detector = DriftDetector::new();
+        let mut manifest = export_manifest(&agents, &graph);
+        let declared = manifest.agents[0].config.clone();
+        assert!(detector.check(&manifest, &agents, &graph).unwrap().events.is_empty());
+
+        manifest.agents[0].config.as_mut().unwrap().temperature = 0.2;
+        manifest.agents[0].tools = vec!["search".to_string()];
+        let report = detector.check(&manifest, &agents, &graph).unwrap();
+        assert!(report.remediation.is_none());
+        assert_eq!(report.events.len(), 1);
+        let fields: Vec<&str> = report.events[0].fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, ["config.temperature", "tools"]);
+        assert!(!report.events[0].remediation_planned);
+
+        // Unchanged drift is not raised again
+        assert!(detector.check(&manifest, &agents, &graph).unwrap().events.is_empty());
+
+        graph.set_tools(agents[0].id(), ["search"]);
+        manifest.agents[0].config = declared;
+        let report = detector.check(&manifest, &agents, &graph).unwrap();
+        assert_eq!(report.events.len(), 1);
+        assert!(report.events[0].fields.is_empty());
+    }
+
+    #[test]
+    fn test_auto_remediation_plans_commands() {
+        let agents = vec![live_agent("sage")];
+        let graph = AgentGraphProjection::new();
+        let detector = DriftDetector::new().with_auto_remediate(true);
+        let mut manifest = export_manifest(&agents, &graph);
+        manifest.agents[0].status = AgentStatus::Suspended;
+
+        let report = detector.check(&manifest, &agents, &graph).unwrap();
+        assert!(report.events[0].remediation_planned);
+        let plan = report.remediation.unwrap();
+        assert!(matches!(plan.commands[..], [AgentCommand::SuspendAgent(_)]));
+    }
+}
@@ -0,0 +1,525 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Evaluation Suites
+//!
+//! An [`EvalSuite`] is a set of golden prompts, each with assertions its
+//! response must satisfy. [`EvalRunner`] sends every prompt to an
+//! agent/model combination and scores the responses:
+//!
+//! ```text
+//! EvalSuite ──> EvalRunner::run(agent, config)
+//!                  │ per golden prompt
+//!                  v
+//!              ChatPort ──> response ──> regex / similarity / JSON schema
+//!                  │
+//!                  v
+//!              EvalReport { EvaluationCompleted, per-prompt outcomes }
+//! ```
+//!
+//! The score is the fraction of assertions that held across the suite; a
+//! prompt whose request fails counts all of its assertions as failed.
+//! Suites can be written in YAML:
+//!
+//! ```yaml
+//! name: support-basics
+//! pass_threshold: 0.9
+//! prompts:
+//!   - name: refund
+//!     prompt: How do I get a refund?
+//!     assertions:
+//!       - type: matches
+//!         pattern: "(?i)refund"
+//!       - type: similar_to
+//!         reference: Refunds are issued from the orders page within 30 days.
+//!         min_similarity: 0.8
+//! ```
+
+use crate::aggregate::Agent;
+use crate::events::EvaluationCompletedEvent;
+use crate::ports::{ChatError, ChatPort, EmbeddingPort};
+use crate::value_objects::{ContextMessage, ModelConfig};
+use futures::StreamExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Errors preparing or scoring an evaluation
+#[derive(Debug, Error)]
+pub enum EvalError {
+    #[error("Failed to parse suite: {0}")]
+    Parse(#[from] serde_yaml::Error),
+
+    #[error("Prompt '{prompt}' has an invalid pattern: {reason}")]
+    InvalidPattern { prompt: String, reason: String },
+
+    #[error("Prompt '{prompt}' has an invalid JSON schema: {reason}")]
+    InvalidSchema { prompt: String, reason: String },
+
+    #[error("Suite uses similarity assertions but the runner has no embedding port")]
+    NoEmbeddingPort,
+
+    #[error("Embedding failed: {0}")]
+    Embedding(#[from] ChatError),
+}
+
+/// Result type for evaluations
+pub type EvalResult<T> = Result<T, EvalError>;
+
+/// Something a response must satisfy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EvalAssertion {
+    /// Response matches a regular expression
+    Matches { pattern: String },
+
+    /// Response embeds close to a reference answer (cosine similarity)
+    SimilarTo {
+        reference: String,
+        min_similarity: f32,
+    },
+
+    /// Response is JSON valid against a schema
+    JsonSchema { schema: serde_json::Value },
+}
+
+impl EvalAssertion {
+    /// Response must match `pattern`
+    pub fn matches(pattern: impl Into<String>) -> Self {
+        Self::Matches {
+            pattern: pattern.into(),
+        }
+    }
+
+    /// Response must be at least `min_similarity` similar to `reference`
+    pub fn similar_to(reference: impl Into<String>, min_similarity: f32) -> Self {
+        Self::SimilarTo {
+            reference: reference.into(),
+            min_similarity,
+        }
+    }
+
+    /// Response must be JSON valid against `schema`
+    pub fn json_schema(schema: serde_json::Value) -> Self {
+        Self::JsonSchema { schema }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Matches { pattern } => format!("matches /{}/", pattern),
+            Self::SimilarTo { min_similarity, .. } => {
+                format!("similar to reference (>= {:.2})", min_similarity)
+            }
+            Self::JsonSchema { .. } => "valid against JSON schema".to_string(),
+        }
+    }
+}
+
+/// A prompt with known-good expectations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenPrompt {
+    /// Name, unique within the suite
+    pub name: String,
+
+    /// User message sent to the agent
+    pub prompt: String,
+
+    /// Assertions on the response
+    #[serde(default)]
+    pub assertions: Vec<EvalAssertion>,
+}
+
+impl GoldenPrompt {
+    /// Create a prompt with no assertions
+    pub fn new(name: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            prompt: prompt.into(),
+            assertions: Vec::new(),
+        }
+    }
+
+    /// Add an assertion
+    pub fn with_assertion(mut self, assertion: EvalAssertion) -> Self {
+        self.assertions.push(assertion);
+        self
+    }
+}
+
+/// A named set of golden prompts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalSuite {
+    /// Suite name
+    pub name: String,
+
+    /// Score needed to pass
+    #[serde(default = "default_pass_threshold")]
+    pub pass_threshold: f64,
+
+    /// Golden prompts, run in order
+    #[serde(default)]
+    pub prompts: Vec<GoldenPrompt>,
+}
+
+fn default_pass_threshold() -> f64 {
+    1.0
+}
+
+impl EvalSuite {
+    /// Create an empty suite that passes only on a perfect score
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            pass_threshold: default_pass_threshold(),
+            prompts: Vec::new(),
+        }
+    }
+
+    /// Parse a suite from YAML
+    pub fn from_yaml(yaml: &str) -> EvalResult<Self> {
+        let suite: Self = serde_yaml::from_str(yaml)?;
+        suite.validate()?;
+        Ok(suite)
+    }
+
+    /// Set the score needed to pass
+    pub fn with_pass_threshold(mut self, threshold: f64) -> Self {
+        self.pass_threshold = threshold;
+        self
+    }
+
+    /// Add a golden prompt
+    pub fn with_prompt(mut self, prompt: GoldenPrompt) -> Self {
+        self.prompts.push(prompt);
+        self
+    }
+
+    /// Check every pattern and schema compiles
+    pub fn validate(&self) -> EvalResult<()> {
+        for prompt in &self.prompts {
+            for assertion in &prompt.assertions {
+                match assertion {
+                    EvalAssertion::Matches { pattern } => {
+                        Regex::new(pattern).map_err(|e| EvalError::InvalidPattern {
+                            prompt: prompt.name.clone(),
+                            reason: e.to_string(),
+                        })?;
+                    }
+                    EvalAssertion::JsonSchema { schema } => {
+                        jsonschema::validator_for(schema).map_err(|e| {
+                            EvalError::InvalidSchema {
+                                prompt: prompt.name.clone(),
+                                reason: e.to_string(),
+                            }
+                        })?;
+                    }
+                    EvalAssertion::SimilarTo { .. } => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn needs_embeddings(&self) -> bool {
+        self.prompts
+            .iter()
+            .flat_map(|p| &p.assertions)
+            .any(|a| matches!(a, EvalAssertion::SimilarTo { .. }))
+    }
+}
+
+/// How one assertion fared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssertionOutcome {
+    /// What was asserted
+    pub assertion: String,
+
+    /// Whether it held
+    pub passed: bool,
+
+    /// Why it failed, or the measured value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// How one golden prompt fared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptOutcome {
+    /// Golden prompt name
+    pub name: String,
+
+    /// Full response text (empty if the request failed)
+    pub response: String,
+
+    /// Request failure, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Per-assertion results
+    pub assertions: Vec<AssertionOutcome>,
+}
+
+impl PromptOutcome {
+    /// True when the request succeeded and every assertion held
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.assertions.iter().all(|a| a.passed)
+    }
+}
+
+/// Result of running a suite
+#[derive(Debug, Clone)]
+pub struct EvalReport {
+    /// Event to publish
+    pub event: EvaluationCompletedEvent,
+
+    /// Per-prompt results, in suite order
+    pub prompts: Vec<PromptOutcome>,
+}
+
+/// Runs evaluation suites against agents
+#[derive(Clone)]
+pub struct EvalRunner {
+    chat: Arc<dyn ChatPort>,
+    embeddings: Option<Arc<dyn EmbeddingPort>>,
+}
+
+impl EvalRunner {
+    /// Send prompts through `chat`
+    pub fn new(chat: Arc<dyn ChatPort>) -> Self {
+        Self {
+            chat,
+            embeddings: None,
+        }
+    }
+
+    /// Embed responses with `port` for similarity assertions
+    pub fn with_embeddings(mut self, port: Arc<dyn EmbeddingPort>) -> Self {
+        self.embeddings = Some(port);
+        self
+    }
+
+    /// Run `suite` against `agent` using `config`
+    ///
+    /// `config` need not be the agent's current configuration, so candidate
+    /// configurations can be scored before they are rolled out. Fails only
+    /// if the suite is invalid or embedding a response fails.
+    pub async fn run(
+        &self,
+        suite: &EvalSuite,
+        agent: &Agent,
+        config: &ModelConfig,
+    ) -> EvalResult<EvalReport> {
+        suite.validate()?;
+        if suite.needs_embeddings() && self.embeddings.is_none() {
+            return Err(EvalError::NoEmbeddingPort);
+        }
+
+        let mut prompts = Vec::with_capacity(suite.prompts.len());
+        for golden in &suite.prompts {
+            let mut context = Vec::new();
+            if let Some(system_prompt) = agent.system_prompt().filter(|p| !p.is_empty()) {
+                context.push(ContextMessage::system(system_prompt));
+            }
+            context.push(ContextMessage::user(&golden.prompt));
+
+            let outcome = match self.complete(config, context).await {
+                Ok(response) => {
+                    let mut assertions = Vec::with_capacity(golden.assertions.len());
+                    for assertion in &golden.assertions {
+                        assertions.push(self.check(assertion, &response).await?);
+                    }
+                    PromptOutcome {
+                        name: golden.name.clone(),
+                        response,
+                        error: None,
+                        assertions,
+                    }
+                }
+                Err(e) => PromptOutcome {
+                    name: golden.name.clone(),
+                    response: String::new(),
+                    error: Some(e.to_string()),
+                    assertions: golden
+                        .assertions
+                        .iter()
+                        .map(|a| AssertionOutcome {
+                            assertion: a.describe(),
+                            passed: false,
+                            detail: Some("request failed".to_string()),
+                        })
+                        .collect(),
+                },
+            };
+            prompts.push(outcome);
+        }
+
+        let total = prompts.iter().map(|p| p.assertions.len()).sum::<usize>();
+        let held = prompts
+            .iter()
+            .flat_map(|p| &p.assertions)
+            .filter(|a| a.passed)
+            .count();
+        let errored = prompts.iter().any(|p| p.error.is_some());
+        let score = match total {
+            0 if errored => 0.0,
+            0 => 1.0,
+            _ => held as f64 / total as f64,
+        };
+        let prompts_passed = prompts.iter().filter(|p| p.passed()).count();
+
+        Ok(EvalReport {
+            event: EvaluationCompletedEvent::new(
+                agent.id(),
+                &suite.name,
+                format!("{}/{}", config.provider, config.model_name),
+                score,
+                score >= suite.pass_threshold,
+                prompts.len() as u32,
+                prompts_passed as u32,
+            ),
+            prompts,
+        })
+    }
+
+    /// Collect a full response
+    async fn complete(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> Result<String, ChatError> {
+        let mut stream = self.chat.send(config, context).await?;
+        let mut response = String::new();
+        while let Some(chunk) = stream.next().await {
+            response.push_str(&chunk?.content);
+        }
+        Ok(response)
+    }
+
+    async fn check(
+        &self,
+        assertion: &EvalAssertion,
+        response: &str,
+    ) -> EvalResult<AssertionOutcome> {
+        let (passed, detail) = match assertion {
+            EvalAssertion::Matches { pattern } => {
+                // Validated up front
+                let regex = Regex::new(pattern).expect("pattern validated");
+                (regex.is_match(response), None)
+            }
+            EvalAssertion::SimilarTo {
+                reference,
+                min_similarity,
+            } => {
+                let port = self.embeddings.as_ref().ok_or(EvalError::NoEmbeddingPort)?;
+                let embedded = port
+                    .embed(vec![response.to_string(), reference.clone()], None)
+                    .await?;
+                let similarity = match embedded.embeddings.as_slice() {
+                    [a, b] => cosine_similarity(a, b),
+                    _ => 0.0,
+                };
+                (similarity >= *min_similarity, Some(format!("similarity {:.3}", similarity)))
+            }
+            EvalAssertion::JsonSchema { schema } => {
+                match serde_json::from_str::<serde_json::Value>(response.trim()) {
+                    Ok(json) => {
+                        let validator =
+                            jsonschema::validator_for(schema).expect("schema validated");
+                        let errors: Vec<String> =
+                            validator.iter_errors(&json).map(|e| e.to_string()).collect();
+                        (errors.is_empty(), (!errors.is_empty()).then(|| errors.join("; ")))
+                    }
+                    Err(e) => (false, Some(format!("not JSON: {}", e))),
+                }
+            }
+        };
+        Ok(AssertionOutcome {
+            assertion: assertion.describe(),
+            passed,
+            detail,
+        })
+    }
+}
+
+impl std::fmt::Debug for EvalRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvalRunner")
+            .field("chat", &self.chat.provider_name())
+            .field("embeddings", &self.embeddings.as_ref().map(|p| p.provider_name()))
+            .finish()
+    }
+}
+
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, AgentEvent};
+    use crate::test_support::{replying, ScriptedEmbedder};
+    use crate::value_objects::{AgentId, PersonId};
+
+    /// Embeds by counting a few keywords
+    fn keywords() -> impl EmbeddingPort {
+        ScriptedEmbedder::new(|text| {
+            ["refund", "order", "days"]
+                .iter()
+                .map(|k| text.to_lowercase().matches(k).count() as f32)
+                .collect()
+        })
+    }
+
+    fn agent() -> Agent {
+        let id = AgentId::new();
+        let deployed = AgentDeployedEvent::new(id, PersonId::new(), "support", None);
+        Agent::empty().apply_events(&[AgentEvent::AgentDeployed(deployed)]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_scores_assertions() {
+        let suite = EvalSuite::new("support")
+            .with_pass_threshold(0.6)
+            .with_prompt(
+                GoldenPrompt::new("refund", "How do I get a refund?")
+                    .with_assertion(EvalAssertion::matches("(?i)refund"))
+                    .with_assertion(EvalAssertion::similar_to("Refund from your order", 0.9))
+                    .with_assertion(EvalAssertion::json_schema(serde_json::json!({
+                        "type": "object"
+                    }))),
+            );
+        let runner = EvalRunner::new(Arc::new(replying("Request a refund on the order page")))
+            .with_embeddings(Arc::new(keywords()));
+
+        let report = runner.run(&suite, &agent(), &ModelConfig::mock()).await.unwrap();
+        let passed: Vec<bool> = report.prompts[0].assertions.iter().map(|a| a.passed).collect();
+        assert_eq!(passed, [true, true, false]);
+        assert!((report.event.score - 2.0 / 3.0).abs() < 1e-9);
+        assert!(report.event.passed);
+        assert_eq!(report.event.prompts_passed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unusable_suites() {
+        let yaml = "name: bad\nprompts:\n  - name: p\n    prompt: hi\n    assertions:\n      \
+                    - type: matches\n        pattern: \"(\"\n";
+        assert!(matches!(EvalSuite::from_yaml(yaml), Err(EvalError::InvalidPattern { .. })));
+
+        let suite = EvalSuite::new("similar").with_prompt(
+            GoldenPrompt::new("p", "hi").with_assertion(EvalAssertion::similar_to("hello", 0.5)),
+        );
+        let runner = EvalRunner::new(Arc::new(replying("hello")));
+        assert!(matches!(
+            runner.run(&suite, &agent(), &ModelConfig::mock()).await,
+            Err(EvalError::NoEmbeddingPort)
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Event Retention
+//!
+//! Event streams keep events indefinitely, since a stream-wide max age would
+//! also age out agents under legal hold. [`EventRetention`] decides, agent by
+//! agent, whose events older than the retention period may be purged:
+//!
+//! ```text
+//! sweep(agents, now) ──> held? ── yes ──> LegalHoldRefused { operation: retention }
+//!                          │ no               (once per hold)
+//!                          v
+//!                 purge events before now - max_age
+//! ```
+
+use super::LegalHolds;
+use crate::events::LegalHoldRefusedEvent;
+use crate::value_objects::{AgentId, HeldOperation};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long agents' events are kept by default
+pub const DEFAULT_EVENT_MAX_AGE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// What one retention sweep may purge, and what it was refused
+#[derive(Debug, Clone)]
+pub struct RetentionSweep {
+    /// Events recorded before this are expired
+    pub cutoff: DateTime<Utc>,
+
+    /// Agents whose expired events may be purged
+    pub purge: Vec<AgentId>,
+
+    /// Agents held back, the first sweep after their hold was placed
+    pub refused: Vec<LegalHoldRefusedEvent>,
+}
+
+/// Ages agents' events out, except for agents under legal hold
+#[derive(Debug)]
+pub struct EventRetention {
+    max_age: Duration,
+    legal_holds: Arc<LegalHolds>,
+    refused: Mutex<HashSet<AgentId>>,
+}
+
+impl EventRetention {
+    /// Keep events for `max_age`, sparing agents held in `legal_holds`
+    pub fn new(max_age: Duration, legal_holds: Arc<LegalHolds>) -> Self {
+        Self {
+            max_age,
+            legal_holds,
+            refused: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// How long events are kept
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Split `agents` into those whose expired events may go and those held
+    pub fn sweep(
+        &self,
+        agents: impl IntoIterator<Item = AgentId>,
+        now: DateTime<Utc>,
+    ) -> RetentionSweep {
+        let max_age = chrono::Duration::from_std(self.max_age).unwrap_or(chrono::Duration::MAX);
+        let mut sweep = RetentionSweep {
+            cutoff: now
+                .checked_sub_signed(max_age)
+                .unwrap_or(DateTime::<Utc>::MIN_UTC),
+            purge: Vec::new(),
+            refused: Vec::new(),
+        };
+
+        let mut refused = self.refused.lock().unwrap_or_else(|e| e.into_inner());
+        for agent_id in agents {
+            match self.legal_holds.check(agent_id, HeldOperation::Retention) {
+                Ok(()) => {
+                    // A later hold is refused again
+                    refused.remove(&agent_id);
+                    sweep.purge.push(agent_id);
+                }
+                Err(event) => {
+                    if refused.insert(agent_id) {
+                        sweep.refused.push(event);
+                    }
+                }
+            }
+        }
+        sweep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{LegalHold, PersonId};
+
+    #[test]
+    fn test_held_agents_are_refused_once_and_spared() {
+        let holds = Arc::new(LegalHolds::new());
+        let retention = EventRetention::new(DEFAULT_EVENT_MAX_AGE, holds.clone());
+        let (held, free) = (AgentId::new(), AgentId::new());
+        holds.set(
+            held,
+            Some(LegalHold {
+                reason: "Matter 2025-114".to_string(),
+                placed_by: PersonId::new(),
+                placed_at: Utc::now(),
+            }),
+        );
+
+        let now = Utc::now();
+        let sweep = retention.sweep([held, free], now);
+        assert_eq!(sweep.purge, vec![free]);
+        assert_eq!(sweep.refused.len(), 1);
+        assert_eq!(sweep.refused[0].operation, HeldOperation::Retention);
+        assert_eq!(sweep.cutoff, now - chrono::Duration::days(365));
+
+        // Still held: spared without another refusal
+        let sweep = retention.sweep([held], now);
+        assert!(sweep.purge.is_empty() && sweep.refused.is_empty());
+
+        holds.set(held, None);
+        assert_eq!(retention.sweep([held], now).purge, vec![held]);
+    }
+}
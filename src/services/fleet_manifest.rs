@@ -0,0 +1,495 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Fleet Manifests
+//!
+//! A [`FleetManifest`] is a declarative YAML description of a fleet, meant to
+//! live in Git:
+//!
+//! ```yaml
+//! version: 1
+//! prune: false
+//! agents:
+//!   - name: sage
+//!     owner: 0193...
+//!     status: Active
+//!     blueprint:
+//!       description: Orchestrates the other agents
+//!     config:
+//!       provider: anthropic
+//!       model_name: claude-3-opus
+//!       ...
+//!     capabilities: BASIC_CHAT | STREAMING | VISION
+//!     tools: [fetch, search]
+//! ```
+//!
+//! [`export_manifest`] describes live agents; [`apply_manifest`] diffs a
+//! manifest against them and returns the commands that converge the fleet,
+//! in dispatch order:
+//!
+//! ```text
+//! missing agent      ──> DeployAgent, ConfigureModel, Activate/Suspend
+//! config differs     ──> ConfigureModel
+//! status differs     ──> ActivateAgent / SuspendAgent / DecommissionAgent
+//! not in manifest    ──> DecommissionAgent (only with `prune: true`)
+//! ```
+//!
+//! Tools are not event-sourced, so tool changes come back as
+//! [`ToolAssignment`]s for the caller to apply to the fleet graph. Drift no
+//! command can fix - a changed description or owner, or a decommissioned
+//! agent the manifest wants back - is reported rather than planned.
+
+use crate::aggregate::Agent;
+use crate::capabilities::{ProviderCapabilities, RuntimeCapabilities};
+use crate::commands::{
+    ActivateAgent, AgentCommand, ConfigureModel, DecommissionAgent, DeployAgent, SuspendAgent,
+};
+use crate::read_model::AgentGraphProjection;
+use crate::value_objects::{AgentId, AgentStatus, ModelConfig, PersonId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Manifest format version written by [`export_manifest`]
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Errors reading or applying a manifest
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("Failed to parse manifest: {0}")]
+    Parse(#[from] serde_yaml::Error),
+
+    #[error("Unsupported manifest version {0} (expected {})", MANIFEST_VERSION)]
+    UnsupportedVersion(u32),
+
+    #[error("Agent '{0}' is declared more than once")]
+    DuplicateAgent(String),
+
+    #[error("Agent '{name}' must be {status} but has no model config")]
+    MissingConfig { name: String, status: AgentStatus },
+
+    #[error("Agent '{name}' requires {required} but {provider} provides {available}")]
+    UnsatisfiedCapabilities {
+        name: String,
+        provider: String,
+        required: RuntimeCapabilities,
+        available: RuntimeCapabilities,
+    },
+
+    #[error("Invalid canary policy: {0}")]
+    InvalidCanaryPolicy(String),
+}
+
+/// Result type for manifest operations
+pub type ManifestResult<T> = Result<T, ManifestError>;
+
+/// Declarative description of a fleet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetManifest {
+    /// Format version
+    pub version: u32,
+
+    /// Decommission live agents the manifest does not list
+    #[serde(default)]
+    pub prune: bool,
+
+    /// Desired agents
+    #[serde(default)]
+    pub agents: Vec<AgentManifest>,
+}
+
+impl FleetManifest {
+    /// Parse a manifest from YAML
+    pub fn from_yaml(yaml: &str) -> ManifestResult<Self> {
+        let manifest: Self = serde_yaml::from_str(yaml)?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(ManifestError::UnsupportedVersion(manifest.version));
+        }
+        Ok(manifest)
+    }
+
+    /// Render the manifest as YAML
+    pub fn to_yaml(&self) -> ManifestResult<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
+
+/// Desired state of one agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentManifest {
+    /// Agent name, unique within the manifest
+    pub name: String,
+
+    /// Pin the agent ID (matched by name when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<AgentId>,
+
+    /// Owning person
+    pub owner: PersonId,
+
+    /// Desired status
+    #[serde(default = "default_status")]
+    pub status: AgentStatus,
+
+    /// Identity fixed at deployment
+    #[serde(default)]
+    pub blueprint: AgentBlueprint,
+
+    /// Model configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<ModelConfig>,
+
+    /// Capabilities the configured provider must offer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<RuntimeCapabilities>,
+
+    /// Registered tools
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+}
+
+fn default_status() -> AgentStatus {
+    AgentStatus::Active
+}
+
+/// Deployment-time identity of an agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentBlueprint {
+    /// Agent description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Tools to register an agent with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolAssignment {
+    /// Agent ID
+    pub agent_id: AgentId,
+    /// Complete tool list (empty clears)
+    pub tools: Vec<String>,
+}
+
+/// Changes that converge the fleet on a manifest
+#[derive(Debug, Clone, Default)]
+pub struct ManifestPlan {
+    /// Commands to dispatch, in order
+    pub commands: Vec<AgentCommand>,
+    /// Tool registrations to replace
+    pub tools: Vec<ToolAssignment>,
+    /// Differences no command can reconcile
+    pub drift: Vec<String>,
+}
+
+impl ManifestPlan {
+    /// True when live state already matches the manifest
+    pub fn is_converged(&self) -> bool {
+        self.commands.is_empty() && self.tools.is_empty() && self.drift.is_empty()
+    }
+}
+
+/// Describe live agents as a manifest
+///
+/// Decommissioned agents are left out, agents are ordered by name and each
+/// agent's capabilities are those of its configured provider.
+#[allow(deprecated)]
+pub fn export_manifest<'a>(
+    agents: impl IntoIterator<Item = &'a Agent>,
+    graph: &AgentGraphProjection,
+) -> FleetManifest {
+    let mut agents: Vec<AgentManifest> = agents
+        .into_iter()
+        .filter(|agent| !agent.is_decommissioned())
+        .map(|agent| AgentManifest {
+            name: agent.name().to_string(),
+            id: Some(agent.id()),
+            owner: agent.person_id(),
+            status: agent.status(),
+            blueprint: AgentBlueprint {
+                description: agent.description().map(str::to_string),
+            },
+            config: agent.model_config().cloned(),
+            capabilities: agent
+                .model_config()
+                .map(|config| ProviderCapabilities::for_provider(config.provider).capabilities),
+            tools: graph.tools(agent.id()),
+        })
+        .collect();
+    agents.sort_by(|a, b| a.name.cmp(&b.name));
+
+    FleetManifest {
+        version: MANIFEST_VERSION,
+        prune: false,
+        agents,
+    }
+}
+
+/// Plan the commands that converge live agents on `manifest`
+///
+/// Fails without planning anything if the manifest is inconsistent.
+#[allow(deprecated)]
+pub fn apply_manifest<'a>(
+    manifest: &FleetManifest,
+    agents: impl IntoIterator<Item = &'a Agent>,
+    graph: &AgentGraphProjection,
+) -> ManifestResult<ManifestPlan> {
+    validate(manifest)?;
+
+    let live: Vec<&Agent> = agents.into_iter().collect();
+    let mut plan = ManifestPlan::default();
+    let mut claimed: HashSet<AgentId> = HashSet::new();
+
+    for desired in &manifest.agents {
+        let Some(agent) = find_live(desired, &live) else {
+            deploy(desired, &mut plan);
+            continue;
+        };
+        claimed.insert(agent.id());
+        let id = agent.id();
+
+        if agent.is_decommissioned() {
+            if desired.status != AgentStatus::Decommissioned {
+                plan.drift.push(format!(
+                    "{}: decommissioned agent {} cannot be revived; remove its id to redeploy",
+                    desired.name, id
+                ));
+            }
+            continue;
+        }
+
+        if agent.description() != desired.blueprint.description.as_deref() {
+            plan.drift.push(format!("{}: description differs", desired.name));
+        }
+        if agent.person_id() != desired.owner {
+            plan.drift.push(format!("{}: owner differs", desired.name));
+        }
+        if agent.name() != desired.name {
+            plan.drift.push(format!("{}: live agent is named '{}'", desired.name, agent.name()));
+        }
+
+        if desired.status == AgentStatus::Decommissioned {
+            plan.commands.push(AgentCommand::DecommissionAgent(DecommissionAgent::new(id)));
+            continue;
+        }
+
+        if let Some(config) = &desired.config {
+            if agent.model_config() != Some(config) {
+                plan.commands
+                    .push(AgentCommand::ConfigureModel(ConfigureModel::new(id, config.clone())));
+            }
+        }
+        transition(desired, id, agent.status(), &mut plan);
+
+        let tools = sorted(&desired.tools);
+        if graph.tools(id) != tools {
+            plan.tools.push(ToolAssignment { agent_id: id, tools });
+        }
+    }
+
+    if manifest.prune {
+        for agent in live {
+            if !claimed.contains(&agent.id()) && !agent.is_decommissioned() {
+                plan.commands
+                    .push(AgentCommand::DecommissionAgent(DecommissionAgent::new(agent.id())));
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Live agent a declaration refers to: by pinned ID, else by name
+pub(super) fn find_live<'a>(desired: &AgentManifest, live: &[&'a Agent]) -> Option<&'a Agent> {
+    live.iter().copied().find(|agent| match desired.id {
+        Some(id) => agent.id() == id,
+        None => agent.name() == desired.name && !agent.is_decommissioned(),
+    })
+}
+
+/// Check the manifest is internally consistent
+pub(super) fn validate(manifest: &FleetManifest) -> ManifestResult<()> {
+    if manifest.version != MANIFEST_VERSION {
+        return Err(ManifestError::UnsupportedVersion(manifest.version));
+    }
+
+    let mut names = HashSet::new();
+    for agent in &manifest.agents {
+        if !names.insert(agent.name.as_str()) {
+            return Err(ManifestError::DuplicateAgent(agent.name.clone()));
+        }
+        let needs_config = matches!(agent.status, AgentStatus::Active | AgentStatus::Suspended);
+        match (&agent.config, agent.capabilities) {
+            (None, _) if needs_config => {
+                return Err(ManifestError::MissingConfig {
+                    name: agent.name.clone(),
+                    status: agent.status,
+                })
+            }
+            (Some(config), Some(required)) => {
+                let provider = ProviderCapabilities::for_provider(config.provider);
+                if !provider.satisfies(&required) {
+                    return Err(ManifestError::UnsatisfiedCapabilities {
+                        name: agent.name.clone(),
+                        provider: config.provider.display_name().to_string(),
+                        required,
+                        available: provider.capabilities,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Plan a new agent from scratch
+fn deploy(desired: &AgentManifest, plan: &mut ManifestPlan) {
+    if desired.status == AgentStatus::Decommissioned {
+        return;
+    }
+
+    let mut deploy = DeployAgent::new(desired.owner, &desired.name);
+    if let Some(id) = desired.id {
+        deploy = deploy.with_agent_id(id);
+    }
+    if let Some(description) = &desired.blueprint.description {
+        deploy = deploy.with_description(description);
+    }
+    let id = deploy.agent_id;
+    plan.commands.push(AgentCommand::DeployAgent(deploy));
+
+    if let Some(config) = &desired.config {
+        plan.commands
+            .push(AgentCommand::ConfigureModel(ConfigureModel::new(id, config.clone())));
+    }
+    transition(desired, id, AgentStatus::Deployed, plan);
+
+    let tools = sorted(&desired.tools);
+    if !tools.is_empty() {
+        plan.tools.push(ToolAssignment { agent_id: id, tools });
+    }
+}
+
+/// Plan the status change from `current` to the desired status
+fn transition(
+    desired: &AgentManifest,
+    id: AgentId,
+    current: AgentStatus,
+    plan: &mut ManifestPlan,
+) {
+    let activate = || AgentCommand::ActivateAgent(ActivateAgent::new(id));
+    let suspend = || AgentCommand::SuspendAgent(SuspendAgent::new(id, "Suspended by manifest"));
+
+    match (current, desired.status) {
+        (AgentStatus::Deployed | AgentStatus::Suspended, AgentStatus::Active) => {
+            plan.commands.push(activate());
+        }
+        (AgentStatus::Active, AgentStatus::Suspended) => plan.commands.push(suspend()),
+        (AgentStatus::Deployed, AgentStatus::Suspended) => {
+            plan.commands.push(activate());
+            plan.commands.push(suspend());
+        }
+        (current, AgentStatus::Deployed) if current != AgentStatus::Deployed => {
+            plan.drift.push(format!(
+                "{}: cannot return to Deployed from {}",
+                desired.name, current
+            ));
+        }
+        _ => {}
+    }
+}
+
+pub(super) fn sorted(tools: &[String]) -> Vec<String> {
+    let mut tools = tools.to_vec();
+    tools.sort();
+    tools.dedup();
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentDeployedEvent, AgentEvent, ModelConfiguredEvent};
+
+    fn live_agent(name: &str, owner: PersonId) -> Agent {
+        let id = AgentId::new();
+        Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(id, owner, name, None)),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(id)),
+            ])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_export_then_apply_is_converged() {
+        let owner = PersonId::new();
+        let agents = vec![live_agent("sage", owner), live_agent("ddd", owner)];
+        let graph = AgentGraphProjection::new();
+        graph.set_tools(agents[0].id(), ["search", "fetch"]);
+
+        let manifest = export_manifest(&agents, &graph);
+        assert_eq!(manifest.agents[0].name, "ddd");
+        assert_eq!(manifest.agents[1].tools, vec!["fetch", "search"]);
+
+        let reparsed = FleetManifest::from_yaml(&manifest.to_yaml().unwrap()).unwrap();
+        assert_eq!(reparsed, manifest);
+        assert!(apply_manifest(&reparsed, &agents, &graph).unwrap().is_converged());
+    }
+
+    #[test]
+    fn test_apply_plans_minimal_commands() {
+        let owner = PersonId::new();
+        let agents = vec![live_agent("sage", owner), live_agent("old", owner)];
+        let graph = AgentGraphProjection::new();
+
+        let mut manifest = export_manifest(&agents[..1], &graph);
+        manifest.prune = true;
+        manifest.agents[0].status = AgentStatus::Suspended;
+        manifest.agents.push(AgentManifest {
+            name: "eda".to_string(),
+            id: None,
+            owner,
+            status: AgentStatus::Active,
+            blueprint: AgentBlueprint::default(),
+            config: Some(ModelConfig::mock()),
+            capabilities: None,
+            tools: vec!["search".to_string()],
+        });
+
+        let plan = apply_manifest(&manifest, &agents, &graph).unwrap();
+        let kinds: Vec<&str> = plan
+            .commands
+            .iter()
+            .map(|c| match c {
+                AgentCommand::SuspendAgent(_) => "suspend",
+                AgentCommand::DeployAgent(_) => "deploy",
+                AgentCommand::ConfigureModel(_) => "configure",
+                AgentCommand::ActivateAgent(_) => "activate",
+                AgentCommand::DecommissionAgent(_) => "decommission",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, ["suspend", "deploy", "configure", "activate", "decommission"]);
+        assert_eq!(plan.tools.len(), 1);
+        assert!(plan.drift.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_manifests() {
+        let owner = PersonId::new();
+        let graph = AgentGraphProjection::new();
+        let mut manifest = export_manifest(&[live_agent("sage", owner)], &graph);
+        manifest.agents.push(manifest.agents[0].clone());
+        assert!(matches!(
+            apply_manifest(&manifest, &[], &graph),
+            Err(ManifestError::DuplicateAgent(_))
+        ));
+
+        manifest.agents.pop();
+        manifest.agents[0].capabilities = Some(RuntimeCapabilities::VISION);
+        assert!(matches!(
+            apply_manifest(&manifest, &[], &graph),
+            Err(ManifestError::UnsatisfiedCapabilities { .. })
+        ));
+    }
+}
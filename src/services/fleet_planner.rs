@@ -0,0 +1,414 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Fleet Planning
+//!
+//! Answers "what if we deployed these agents?" before anything is deployed.
+//! Each [`PlannedDeployment`] pairs a manifest entry with the traffic it is
+//! expected to see; [`FleetPlanner::plan`] places it on a provider and rolls
+//! the load up per provider:
+//!
+//! ```text
+//! AgentManifest + ExpectedVolume
+//!     │ provider: configured one, else the cheapest that satisfies the
+//!     │           capabilities and fits the prompt in its context
+//!     v
+//! ProviderLoad { messages/h, tokens/h, spend/h, concurrency, headroom }
+//! ```
+//!
+//! Spend uses the same [`TokenPricing`] as the cost projections, and
+//! placement the same capability lattice as routing. Concurrency follows
+//! Little's law: arrivals per second times seconds per response. Headroom is
+//! the fraction of a provider's rate limit left unused; negative means the
+//! plan exceeds it.
+
+use super::AgentManifest;
+use crate::capabilities::{ProviderCapabilities, RuntimeCapabilities};
+use crate::read_model::TokenPricing;
+use crate::value_objects::{AgentStatus, ProviderType};
+use serde::{Deserialize, Serialize};
+
+/// Traffic one agent is expected to see
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedVolume {
+    /// Messages per hour
+    pub messages_per_hour: f64,
+
+    /// Prompt tokens per message, context included
+    pub prompt_tokens: u64,
+
+    /// Completion tokens per message
+    pub completion_tokens: u64,
+
+    /// Seconds to complete a response
+    pub response_secs: f64,
+}
+
+impl ExpectedVolume {
+    /// Volume of `messages_per_hour` messages of the given size and latency
+    pub fn new(
+        messages_per_hour: f64,
+        prompt_tokens: u64,
+        completion_tokens: u64,
+        response_secs: f64,
+    ) -> Self {
+        Self {
+            messages_per_hour,
+            prompt_tokens,
+            completion_tokens,
+            response_secs,
+        }
+    }
+}
+
+/// A hypothetical deployment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedDeployment {
+    /// Desired agent; its config pins the provider, its capabilities narrow it
+    pub manifest: AgentManifest,
+
+    /// Expected traffic
+    pub volume: ExpectedVolume,
+}
+
+impl PlannedDeployment {
+    /// Plan `manifest` under `volume`
+    pub fn new(manifest: AgentManifest, volume: ExpectedVolume) -> Self {
+        Self { manifest, volume }
+    }
+}
+
+/// Pricing and rate limits of one provider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProviderQuote {
+    /// Token prices
+    pub pricing: TokenPricing,
+
+    /// Requests per minute allowed, if limited
+    pub requests_per_minute: Option<f64>,
+
+    /// Tokens per minute allowed, if limited
+    pub tokens_per_minute: Option<f64>,
+}
+
+impl ProviderQuote {
+    /// Quote at `pricing` with no rate limits
+    pub fn new(pricing: TokenPricing) -> Self {
+        Self {
+            pricing,
+            requests_per_minute: None,
+            tokens_per_minute: None,
+        }
+    }
+
+    /// Builder: requests-per-minute limit
+    pub fn with_request_limit(mut self, requests_per_minute: f64) -> Self {
+        self.requests_per_minute = Some(requests_per_minute);
+        self
+    }
+
+    /// Builder: tokens-per-minute limit
+    pub fn with_token_limit(mut self, tokens_per_minute: f64) -> Self {
+        self.tokens_per_minute = Some(tokens_per_minute);
+        self
+    }
+}
+
+/// Projected load on one provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderLoad {
+    /// The provider
+    pub provider: ProviderType,
+
+    /// Agents placed on it
+    pub agents: Vec<String>,
+
+    /// Messages per hour
+    pub messages_per_hour: f64,
+
+    /// Prompt and completion tokens per hour
+    pub tokens_per_hour: f64,
+
+    /// USD per hour
+    pub spend_per_hour: f64,
+
+    /// Responses in flight at once, on average
+    pub concurrency: f64,
+
+    /// Unused fraction of the request limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_headroom: Option<f64>,
+
+    /// Unused fraction of the token limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_headroom: Option<f64>,
+}
+
+impl ProviderLoad {
+    /// Whether the load exceeds either rate limit
+    pub fn exceeds_limits(&self) -> bool {
+        [self.request_headroom, self.token_headroom]
+            .into_iter()
+            .flatten()
+            .any(|headroom| headroom < 0.0)
+    }
+}
+
+/// Projected capacity and cost of a fleet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FleetPlan {
+    /// Load per provider, in quote order
+    pub providers: Vec<ProviderLoad>,
+
+    /// Deployments no quoted provider can serve, with the reason
+    pub unplaced: Vec<String>,
+}
+
+impl FleetPlan {
+    /// USD per hour across all providers
+    pub fn spend_per_hour(&self) -> f64 {
+        self.providers.iter().map(|load| load.spend_per_hour).sum()
+    }
+
+    /// USD per 30-day month across all providers
+    pub fn spend_per_month(&self) -> f64 {
+        self.spend_per_hour() * 24.0 * 30.0
+    }
+
+    /// Load on `provider`, if anything was placed there
+    pub fn load(&self, provider: ProviderType) -> Option<&ProviderLoad> {
+        self.providers.iter().find(|load| load.provider == provider)
+    }
+}
+
+/// Projects capacity and cost for hypothetical deployments
+#[derive(Debug, Clone, Default)]
+pub struct FleetPlanner {
+    quotes: Vec<(ProviderType, ProviderCapabilities, ProviderQuote)>,
+}
+
+impl FleetPlanner {
+    /// Planner with no providers quoted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: quote `provider` with its preset capabilities
+    pub fn with_provider(self, provider: ProviderType, quote: ProviderQuote) -> Self {
+        self.with_provider_capabilities(
+            provider,
+            ProviderCapabilities::for_provider(provider),
+            quote,
+        )
+    }
+
+    /// Builder: quote `provider` with explicit capabilities
+    pub fn with_provider_capabilities(
+        mut self,
+        provider: ProviderType,
+        capabilities: ProviderCapabilities,
+        quote: ProviderQuote,
+    ) -> Self {
+        self.quotes.retain(|(p, _, _)| *p != provider);
+        self.quotes.push((provider, capabilities, quote));
+        self
+    }
+
+    /// Place each deployment and project the load on every provider used
+    ///
+    /// Decommissioned manifest entries are ignored.
+    pub fn plan(&self, deployments: &[PlannedDeployment]) -> FleetPlan {
+        let mut loads: Vec<Option<ProviderLoad>> = vec![None; self.quotes.len()];
+        let mut plan = FleetPlan::default();
+
+        for deployment in deployments {
+            if deployment.manifest.status == AgentStatus::Decommissioned {
+                continue;
+            }
+            let index = match self.place(deployment) {
+                Ok(index) => index,
+                Err(reason) => {
+                    plan.unplaced
+                        .push(format!("{}: {}", deployment.manifest.name, reason));
+                    continue;
+                }
+            };
+            let (provider, _, quote) = &self.quotes[index];
+            let volume = &deployment.volume;
+            let load = loads[index].get_or_insert_with(|| ProviderLoad {
+                provider: *provider,
+                agents: Vec::new(),
+                messages_per_hour: 0.0,
+                tokens_per_hour: 0.0,
+                spend_per_hour: 0.0,
+                concurrency: 0.0,
+                request_headroom: None,
+                token_headroom: None,
+            });
+            load.agents.push(deployment.manifest.name.clone());
+            load.messages_per_hour += volume.messages_per_hour;
+            load.tokens_per_hour +=
+                volume.messages_per_hour * (volume.prompt_tokens + volume.completion_tokens) as f64;
+            load.spend_per_hour += volume.messages_per_hour
+                * quote
+                    .pricing
+                    .cost(volume.prompt_tokens, volume.completion_tokens);
+            load.concurrency += volume.messages_per_hour / 3600.0 * volume.response_secs;
+        }
+
+        plan.providers = loads
+            .into_iter()
+            .zip(&self.quotes)
+            .filter_map(|(load, (_, _, quote))| {
+                let mut load = load?;
+                load.request_headroom = quote
+                    .requests_per_minute
+                    .map(|limit| headroom(load.messages_per_hour / 60.0, limit));
+                load.token_headroom = quote
+                    .tokens_per_minute
+                    .map(|limit| headroom(load.tokens_per_hour / 60.0, limit));
+                Some(load)
+            })
+            .collect();
+        plan
+    }
+
+    /// Index of the quote a deployment lands on
+    fn place(&self, deployment: &PlannedDeployment) -> Result<usize, String> {
+        let required = deployment
+            .manifest
+            .capabilities
+            .unwrap_or(RuntimeCapabilities::BASIC_CHAT);
+        let prompt_tokens = deployment.volume.prompt_tokens;
+        let fits = |capabilities: &ProviderCapabilities| {
+            capabilities.satisfies(&required)
+                && capabilities
+                    .max_context_length
+                    .is_none_or(|max| prompt_tokens <= u64::from(max))
+        };
+
+        if let Some(config) = &deployment.manifest.config {
+            let Some(index) = self
+                .quotes
+                .iter()
+                .position(|(p, _, _)| *p == config.provider)
+            else {
+                return Err(format!("no quote for {:?}", config.provider));
+            };
+            if !fits(&self.quotes[index].1) {
+                return Err(format!(
+                    "{:?} lacks {} or a {}-token context",
+                    config.provider, required, prompt_tokens
+                ));
+            }
+            return Ok(index);
+        }
+
+        let volume = &deployment.volume;
+        self.quotes
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, capabilities, _))| fits(capabilities))
+            .min_by(|(_, (_, _, a)), (_, (_, _, b))| {
+                let cost = |quote: &ProviderQuote| {
+                    quote
+                        .pricing
+                        .cost(volume.prompt_tokens, volume.completion_tokens)
+                };
+                cost(a).total_cmp(&cost(b))
+            })
+            .map(|(index, _)| index)
+            .ok_or_else(|| {
+                format!(
+                    "no quoted provider offers {} with a {}-token context",
+                    required, prompt_tokens
+                )
+            })
+    }
+}
+
+/// Unused fraction of `limit` at `rate`
+fn headroom(rate: f64, limit: f64) -> f64 {
+    if limit <= 0.0 {
+        return -1.0;
+    }
+    1.0 - rate / limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{ModelConfig, PersonId};
+
+    fn deployment(
+        name: &str,
+        config: Option<ModelConfig>,
+        volume: ExpectedVolume,
+    ) -> PlannedDeployment {
+        PlannedDeployment::new(
+            AgentManifest {
+                name: name.to_string(),
+                id: None,
+                owner: PersonId::new(),
+                status: AgentStatus::Active,
+                blueprint: Default::default(),
+                config,
+                capabilities: None,
+                tools: Vec::new(),
+            },
+            volume,
+        )
+    }
+
+    #[test]
+    fn test_projects_spend_concurrency_and_headroom() {
+        let planner = FleetPlanner::new()
+            .with_provider(
+                ProviderType::OpenAI,
+                ProviderQuote::new(TokenPricing::new(0.01, 0.03)).with_request_limit(100.0),
+            )
+            .with_provider(
+                ProviderType::Ollama,
+                ProviderQuote::new(TokenPricing::new(0.0, 0.0)),
+            );
+
+        let busy = ExpectedVolume::new(3600.0, 1000, 500, 4.0);
+        let anthropic = ModelConfig {
+            provider: ProviderType::Anthropic,
+            ..ModelConfig::default()
+        };
+        let openai = ModelConfig {
+            provider: ProviderType::OpenAI,
+            ..ModelConfig::default()
+        };
+        let plan = planner.plan(&[
+            deployment("sage", Some(openai), busy),
+            deployment("scout", None, ExpectedVolume::new(60.0, 2000, 200, 10.0)),
+            deployment("scribe", Some(anthropic), busy),
+            deployment(
+                "archivist",
+                None,
+                ExpectedVolume::new(10.0, 50_000, 100, 30.0),
+            ),
+        ]);
+
+        let openai = plan.load(ProviderType::OpenAI).unwrap();
+        assert_eq!(
+            openai.agents,
+            vec!["sage".to_string(), "archivist".to_string()]
+        );
+        assert!((openai.spend_per_hour - (3600.0 * 0.025 + 10.0 * 0.503)).abs() < 1e-9);
+        assert!((openai.concurrency - (4.0 + 10.0 / 3600.0 * 30.0)).abs() < 1e-9);
+        let used = (3600.0 + 10.0) / 60.0 / 100.0;
+        assert!((openai.request_headroom.unwrap() - (1.0 - used)).abs() < 1e-9);
+        assert!(!openai.exceeds_limits());
+
+        let ollama = plan.load(ProviderType::Ollama).unwrap();
+        assert_eq!(ollama.agents, vec!["scout".to_string()]);
+        assert_eq!(ollama.spend_per_hour, 0.0);
+        assert_eq!(ollama.request_headroom, None);
+
+        assert_eq!(plan.unplaced.len(), 1);
+        assert!(plan.unplaced[0].starts_with("scribe: no quote"));
+        assert!((plan.spend_per_month() - plan.spend_per_hour() * 720.0).abs() < 1e-6);
+    }
+}
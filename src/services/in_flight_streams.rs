@@ -0,0 +1,225 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! In-Flight Stream Tracking
+//!
+//! Tracks provider response streams by `MessageId` so a `CancelMessage`
+//! command can abort them. Aborting drops the provider stream, which closes
+//! the underlying HTTP connection for network adapters.
+//!
+//! A cancelled stream is not simply cut off: it ends with an empty final
+//! chunk whose finish reason is `FinishReason::Cancelled`, so chunk
+//! consumers see a terminated sequence rather than a stalled one.
+//!
+//! ```text
+//! SendMessage ──> adapter.send() ──> InFlightStreams::track(message_id)
+//!                                          │
+//! CancelMessage ──> cancel(message_id) ────┘──> final chunk (Cancelled)
+//! ```
+
+use crate::ports::{ChatResult, ChatStream};
+use crate::value_objects::{FinishReason, MessageId, StreamingChunk};
+use futures::stream::{self, AbortHandle, Abortable};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Registry entry for a tracked stream
+struct InFlight {
+    handle: AbortHandle,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Registry of cancellable response streams
+#[derive(Clone, Default)]
+pub struct InFlightStreams {
+    streams: Arc<Mutex<HashMap<MessageId, InFlight>>>,
+}
+
+impl InFlightStreams {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a stream under `message_id` and return a cancellable wrapper
+    ///
+    /// The entry is removed when the stream finishes, errors, or is dropped
+    /// after cancellation.
+    pub fn track(&self, message_id: MessageId, inner: ChatStream) -> ChatStream {
+        let (handle, registration) = AbortHandle::new_pair();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        self.lock().insert(
+            message_id,
+            InFlight {
+                handle,
+                cancelled: cancelled.clone(),
+            },
+        );
+
+        let state = Tracked {
+            inner: Abortable::new(inner, registration),
+            cancelled,
+            message_id,
+            registry: self.clone(),
+            next_index: 0,
+            finished: false,
+        };
+
+        Box::pin(stream::unfold(state, |mut state| async move {
+            state.next_chunk().await.map(|item| (item, state))
+        }))
+    }
+
+    /// Abort the stream for `message_id`
+    ///
+    /// Returns `false` if no stream is in flight for that message (already
+    /// completed, or never started).
+    pub fn cancel(&self, message_id: MessageId) -> bool {
+        match self.lock().remove(&message_id) {
+            Some(in_flight) => {
+                in_flight.cancelled.store(true, Ordering::SeqCst);
+                in_flight.handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check if a response is still streaming for `message_id`
+    pub fn is_in_flight(&self, message_id: MessageId) -> bool {
+        self.lock().contains_key(&message_id)
+    }
+
+    /// Number of streams currently tracked
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no streams are tracked
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn release(&self, message_id: MessageId) {
+        self.lock().remove(&message_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<MessageId, InFlight>> {
+        self.streams.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Stream state for [`InFlightStreams::track`]
+struct Tracked {
+    inner: Abortable<ChatStream>,
+    cancelled: Arc<AtomicBool>,
+    message_id: MessageId,
+    registry: InFlightStreams,
+    next_index: u32,
+    finished: bool,
+}
+
+impl Tracked {
+    async fn next_chunk(&mut self) -> Option<ChatResult<StreamingChunk>> {
+        if self.finished {
+            return None;
+        }
+
+        match self.inner.next().await {
+            Some(Ok(chunk)) => {
+                self.next_index = chunk.chunk_index + 1;
+                if chunk.is_final {
+                    self.finish();
+                }
+                Some(Ok(chunk))
+            }
+            Some(Err(e)) => {
+                self.finish();
+                Some(Err(e))
+            }
+            None => {
+                self.finish();
+                self.cancelled.load(Ordering::SeqCst).then(|| {
+                    Ok(StreamingChunk::completion(
+                        self.next_index,
+                        FinishReason::Cancelled,
+                    ))
+                })
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+        self.registry.release(self.message_id);
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.registry.release(self.message_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endless_stream() -> ChatStream {
+        Box::pin(stream::iter(0u32..).then(|i| async move {
+            tokio::task::yield_now().await;
+            Ok::<_, crate::ports::ChatError>(StreamingChunk::new(i, "tick "))
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_cancel_ends_with_cancelled_chunk() {
+        let registry = InFlightStreams::new();
+        let message_id = MessageId::new();
+        let mut stream = registry.track(message_id, endless_stream());
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.chunk_index, 0);
+        assert!(registry.is_in_flight(message_id));
+
+        assert!(registry.cancel(message_id));
+
+        let last = stream.next().await.unwrap().unwrap();
+        assert!(last.is_final);
+        assert_eq!(last.finish_reason, Some(FinishReason::Cancelled));
+        assert_eq!(last.chunk_index, 1);
+        assert!(stream.next().await.is_none());
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_completed_stream_is_released() {
+        let registry = InFlightStreams::new();
+        let message_id = MessageId::new();
+        let chunks: Vec<ChatResult<StreamingChunk>> =
+            vec![Ok(StreamingChunk::final_chunk(0, "done", FinishReason::Stop))];
+        let stream = registry.track(message_id, Box::pin(stream::iter(chunks)));
+
+        let collected: Vec<_> = stream.collect().await;
+
+        assert_eq!(collected.len(), 1);
+        assert!(!registry.is_in_flight(message_id));
+        assert!(!registry.cancel(message_id));
+    }
+
+    #[test]
+    fn test_dropped_stream_is_released() {
+        let registry = InFlightStreams::new();
+        let message_id = MessageId::new();
+
+        let stream = registry.track(message_id, endless_stream());
+        assert_eq!(registry.len(), 1);
+
+        drop(stream);
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,463 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Intent Classification
+//!
+//! Maps free-form user input to either a [`MessageIntent`] or an
+//! [`AgentCommand`], with a confidence score.
+//!
+//! ```text
+//! input ──> overrides ──(none)──> rules ──(confidence < threshold)──> model
+//!              │                    │                                  │
+//!              v                    v                                  v
+//!                          Classification { kind, target, confidence, source }
+//! ```
+//!
+//! ## Rules
+//!
+//! - `/activate`, `/suspend <reason>`, `/decommission [reason]`,
+//!   `/cancel <message-id>`, `/resume <message-id> [from-index]` - commands
+//! - image URLs (`.png`, `.jpg`, `.jpeg`, `.gif`, `.webp`) - vision
+//! - `embed: ...` - embedding
+//! - "draw ...", "generate an image ..." - image generation
+//! - graph, workflow and dependency analysis phrasing - graph analysis
+//! - anything else - chat, at low confidence
+//!
+//! Commands are only recognised from their explicit syntax; the model
+//! fallback chooses between the non-command kinds.
+
+use crate::commands::{
+    ActivateAgent, AgentCommand, CancelMessage, DecommissionAgent, ResumeStream, SuspendAgent,
+};
+use crate::intent::{ImageInput, MessageIntent};
+use crate::ports::ChatPort;
+use crate::value_objects::{AgentId, ContextMessage, MessageId, ModelConfig};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Confidence below which the model fallback is consulted
+pub const DEFAULT_FALLBACK_THRESHOLD: f32 = 0.6;
+
+const FALLBACK_PROMPT: &str = "Classify the user's message. Reply with exactly one label \
+     (chat, graph_analysis or image_generation) followed by a confidence between 0 and 1, \
+     for example: graph_analysis 0.8";
+
+const GRAPH_PHRASES: &[&str] = &[
+    "analyze the graph",
+    "analyse the graph",
+    "graph analysis",
+    "workflow bottleneck",
+    "optimize the workflow",
+    "optimise the workflow",
+    "dependency graph",
+    "critical path",
+    "find cycles",
+];
+
+const IMAGE_PHRASES: &[&str] = &["draw ", "generate an image", "create an image", "make a picture"];
+
+const IMAGE_EXTENSIONS: &[&str] = &[".png", ".jpg", ".jpeg", ".gif", ".webp"];
+
+/// Errors from intent classification
+#[derive(Debug, Error)]
+pub enum IntentClassifierError {
+    #[error("Unknown command: /{0}")]
+    UnknownCommand(String),
+
+    #[error("Invalid arguments for /{command}: {reason}")]
+    InvalidArguments { command: String, reason: String },
+}
+
+/// Result type for intent classification
+pub type IntentClassifierResult<T> = Result<T, IntentClassifierError>;
+
+/// What the input asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    /// Conversation with the agent
+    Chat,
+    /// An explicit agent command
+    Command,
+    /// Analysis of a graph or workflow
+    GraphAnalysis,
+    /// Image analysis
+    Vision,
+    /// Vector embeddings
+    Embedding,
+    /// Image generation
+    ImageGeneration,
+}
+
+/// Where a classification came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationSource {
+    /// An override hook
+    Override,
+    /// Built-in rules
+    Rules,
+    /// The model fallback
+    Model,
+}
+
+/// The request built from classified input
+#[derive(Debug, Clone)]
+pub enum ClassifiedInput {
+    /// Send to a provider
+    Intent(MessageIntent),
+    /// Execute against the agent
+    Command(Box<AgentCommand>),
+}
+
+/// Outcome of classifying one input
+#[derive(Debug, Clone)]
+pub struct Classification {
+    /// What the input asks for
+    pub kind: InputKind,
+    /// Request to dispatch
+    pub target: ClassifiedInput,
+    /// Confidence in `kind`, from 0.0 to 1.0
+    pub confidence: f32,
+    /// Which stage decided
+    pub source: ClassificationSource,
+}
+
+impl Classification {
+    /// Classification as a provider intent
+    pub fn intent(kind: InputKind, intent: MessageIntent, confidence: f32) -> Self {
+        Self {
+            kind,
+            target: ClassifiedInput::Intent(intent),
+            confidence: confidence.clamp(0.0, 1.0),
+            source: ClassificationSource::Override,
+        }
+    }
+
+    /// Classification as an agent command
+    pub fn command(command: AgentCommand, confidence: f32) -> Self {
+        Self {
+            kind: InputKind::Command,
+            target: ClassifiedInput::Command(Box::new(command)),
+            confidence: confidence.clamp(0.0, 1.0),
+            source: ClassificationSource::Override,
+        }
+    }
+
+    fn with_source(mut self, source: ClassificationSource) -> Self {
+        self.source = source;
+        self
+    }
+}
+
+/// Hook consulted before the built-in rules
+///
+/// Returning `Some` short-circuits classification. Closures with the same
+/// signature implement this trait.
+pub trait ClassificationOverride: Send + Sync {
+    /// Classify `input` for `agent_id`, or pass
+    fn classify(&self, agent_id: AgentId, input: &str) -> Option<Classification>;
+}
+
+impl<F> ClassificationOverride for F
+where
+    F: Fn(AgentId, &str) -> Option<Classification> + Send + Sync,
+{
+    fn classify(&self, agent_id: AgentId, input: &str) -> Option<Classification> {
+        self(agent_id, input)
+    }
+}
+
+/// Rule-based classifier with optional model fallback
+#[derive(Clone, Default)]
+pub struct IntentClassifier {
+    overrides: Vec<Arc<dyn ClassificationOverride>>,
+    fallback: Option<(Arc<dyn ChatPort>, ModelConfig)>,
+    fallback_threshold: Option<f32>,
+}
+
+impl IntentClassifier {
+    /// Create a rule-only classifier
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an override hook; hooks run in the order they were added
+    pub fn with_override(mut self, hook: impl ClassificationOverride + 'static) -> Self {
+        self.overrides.push(Arc::new(hook));
+        self
+    }
+
+    /// Ask a model when the rules are unsure
+    pub fn with_fallback(mut self, port: Arc<dyn ChatPort>, config: ModelConfig) -> Self {
+        self.fallback = Some((port, config));
+        self
+    }
+
+    /// Confidence below which the fallback is consulted
+    pub fn with_fallback_threshold(mut self, threshold: f32) -> Self {
+        self.fallback_threshold = Some(threshold);
+        self
+    }
+
+    /// Classify input addressed to `agent_id`
+    ///
+    /// Fails only for malformed slash commands. If the model fallback
+    /// errors, the rule-based classification is returned.
+    pub async fn classify(
+        &self,
+        agent_id: AgentId,
+        input: &str,
+    ) -> IntentClassifierResult<Classification> {
+        if let Some(hit) = self.overrides.iter().find_map(|h| h.classify(agent_id, input)) {
+            return Ok(hit.with_source(ClassificationSource::Override));
+        }
+
+        let ruled = classify_by_rules(agent_id, input)?;
+        let threshold = self.fallback_threshold.unwrap_or(DEFAULT_FALLBACK_THRESHOLD);
+        match &self.fallback {
+            Some((port, config)) if ruled.confidence < threshold => {
+                match ask_model(port.as_ref(), config, input).await {
+                    Some(modelled) if modelled.confidence > ruled.confidence => Ok(modelled),
+                    _ => Ok(ruled),
+                }
+            }
+            _ => Ok(ruled),
+        }
+    }
+}
+
+impl std::fmt::Debug for IntentClassifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntentClassifier")
+            .field("overrides", &self.overrides.len())
+            .field("fallback", &self.fallback.as_ref().map(|(p, _)| p.provider_name()))
+            .field("fallback_threshold", &self.fallback_threshold)
+            .finish()
+    }
+}
+
+/// Apply the built-in rules
+fn classify_by_rules(agent_id: AgentId, input: &str) -> IntentClassifierResult<Classification> {
+    let trimmed = input.trim();
+    if let Some(rest) = trimmed.strip_prefix('/') {
+        let command = parse_command(agent_id, rest)?;
+        return Ok(Classification::command(command, 1.0).with_source(ClassificationSource::Rules));
+    }
+
+    let lower = trimmed.to_lowercase();
+    let embed = trimmed
+        .get(..6)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("embed:"))
+        .map(|_| &trimmed[6..]);
+    let classification = if let Some(text) = embed {
+        Classification::intent(
+            InputKind::Embedding,
+            MessageIntent::embedding(vec![text.trim().to_string()]),
+            0.95,
+        )
+    } else if let Some(images) = image_urls(trimmed) {
+        Classification::intent(
+            InputKind::Vision,
+            MessageIntent::vision(vec![ContextMessage::user(trimmed)], images),
+            0.9,
+        )
+    } else if IMAGE_PHRASES.iter().any(|p| lower.starts_with(p)) {
+        Classification::intent(
+            InputKind::ImageGeneration,
+            MessageIntent::image_generation(trimmed),
+            0.8,
+        )
+    } else if GRAPH_PHRASES.iter().any(|p| lower.contains(p)) {
+        Classification::intent(InputKind::GraphAnalysis, chat(trimmed), 0.8)
+    } else {
+        Classification::intent(InputKind::Chat, chat(trimmed), 0.5)
+    };
+    Ok(classification.with_source(ClassificationSource::Rules))
+}
+
+fn chat(input: &str) -> MessageIntent {
+    MessageIntent::chat(vec![ContextMessage::user(input)])
+}
+
+/// Image URLs in the input, if any
+fn image_urls(input: &str) -> Option<Vec<ImageInput>> {
+    let images: Vec<ImageInput> = input
+        .split_whitespace()
+        .filter(|w| w.starts_with("http://") || w.starts_with("https://"))
+        .filter(|w| {
+            let path = w.split(['?', '#']).next().unwrap_or(w).to_lowercase();
+            IMAGE_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+        })
+        .map(ImageInput::url)
+        .collect();
+    (!images.is_empty()).then_some(images)
+}
+
+/// Parse a slash command (without the leading `/`)
+fn parse_command(agent_id: AgentId, input: &str) -> IntentClassifierResult<AgentCommand> {
+    let (name, args) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let args = args.trim();
+    let invalid = |reason: &str| IntentClassifierError::InvalidArguments {
+        command: name.to_string(),
+        reason: reason.to_string(),
+    };
+    let message_id = |arg: Option<&str>| {
+        arg.and_then(|s| uuid::Uuid::parse_str(s).ok())
+            .map(MessageId::from_uuid)
+            .ok_or_else(|| invalid("expected a message ID"))
+    };
+
+    let command = match name.to_lowercase().as_str() {
+        "activate" => AgentCommand::ActivateAgent(ActivateAgent::new(agent_id)),
+        "suspend" if args.is_empty() => return Err(invalid("a reason is required")),
+        "suspend" => AgentCommand::SuspendAgent(SuspendAgent::new(agent_id, args)),
+        "decommission" => {
+            let mut cmd = DecommissionAgent::new(agent_id);
+            if !args.is_empty() {
+                cmd.reason = Some(args.to_string());
+            }
+            AgentCommand::DecommissionAgent(cmd)
+        }
+        "cancel" => {
+            AgentCommand::CancelMessage(CancelMessage::new(agent_id, message_id(Some(args))?))
+        }
+        "resume" => {
+            let mut parts = args.split_whitespace();
+            let id = message_id(parts.next())?;
+            let from_index = match parts.next() {
+                Some(n) => n.parse().map_err(|_| invalid("expected a chunk index"))?,
+                None => 0,
+            };
+            AgentCommand::ResumeStream(ResumeStream::new(agent_id, id, from_index))
+        }
+        other => return Err(IntentClassifierError::UnknownCommand(other.to_string())),
+    };
+    Ok(command)
+}
+
+/// Ask the fallback model for a label; `None` if it fails or is unclear
+async fn ask_model(
+    port: &dyn ChatPort,
+    config: &ModelConfig,
+    input: &str,
+) -> Option<Classification> {
+    let context = vec![ContextMessage::system(FALLBACK_PROMPT), ContextMessage::user(input)];
+    let mut stream = match port.send(config, context).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::warn!("Intent classification fallback failed: {}", e);
+            return None;
+        }
+    };
+
+    let mut reply = String::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) => reply.push_str(&chunk.content),
+            Err(e) => {
+                tracing::warn!("Intent classification fallback failed: {}", e);
+                return None;
+            }
+        }
+    }
+    parse_reply(&reply, input).map(|c| c.with_source(ClassificationSource::Model))
+}
+
+/// Parse `<label> <confidence>` from a model reply
+fn parse_reply(reply: &str, input: &str) -> Option<Classification> {
+    let lower = reply.to_lowercase();
+    let mut words = lower
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|w| !w.is_empty());
+    let (kind, intent) = words.find_map(|w| match w {
+        "chat" => Some((InputKind::Chat, chat(input))),
+        "graph_analysis" => Some((InputKind::GraphAnalysis, chat(input))),
+        "image_generation" => Some((
+            InputKind::ImageGeneration,
+            MessageIntent::image_generation(input),
+        )),
+        _ => None,
+    })?;
+    let confidence = words.find_map(|w| w.parse::<f32>().ok()).unwrap_or(0.7);
+    Some(Classification::intent(kind, intent, confidence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::replying;
+    use crate::value_objects::ProviderType;
+
+    #[tokio::test]
+    async fn test_rules_classify_commands_and_intents() {
+        let classifier = IntentClassifier::new();
+        let agent_id = AgentId::new();
+
+        let suspend = classifier.classify(agent_id, "/suspend maintenance").await.unwrap();
+        assert_eq!(suspend.kind, InputKind::Command);
+        assert!(matches!(
+            suspend.target,
+            ClassifiedInput::Command(ref c) if matches!(**c, AgentCommand::SuspendAgent(_))
+        ));
+
+        let vision = classifier
+            .classify(agent_id, "What is in https://example.com/cat.PNG?size=2")
+            .await
+            .unwrap();
+        assert_eq!(vision.kind, InputKind::Vision);
+
+        let graph = classifier
+            .classify(agent_id, "Can you find the critical path here?")
+            .await
+            .unwrap();
+        assert_eq!(graph.kind, InputKind::GraphAnalysis);
+        assert_eq!(graph.source, ClassificationSource::Rules);
+
+        assert!(matches!(
+            classifier.classify(agent_id, "/cancel nope").await,
+            Err(IntentClassifierError::InvalidArguments { .. })
+        ));
+        assert!(matches!(
+            classifier.classify(agent_id, "/reboot").await,
+            Err(IntentClassifierError::UnknownCommand(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_only_when_rules_are_unsure() {
+        let classifier = IntentClassifier::new().with_fallback(
+            Arc::new(replying("graph_analysis 0.9")),
+            ModelConfig::new(ProviderType::Mock, "mock-model"),
+        );
+        let agent_id = AgentId::new();
+
+        let unsure = classifier.classify(agent_id, "how do these steps relate?").await.unwrap();
+        assert_eq!(unsure.kind, InputKind::GraphAnalysis);
+        assert_eq!(unsure.source, ClassificationSource::Model);
+        assert!((unsure.confidence - 0.9).abs() < f32::EPSILON);
+
+        let command = classifier.classify(agent_id, "/activate").await.unwrap();
+        assert_eq!(command.source, ClassificationSource::Rules);
+    }
+
+    #[tokio::test]
+    async fn test_override_hooks_win() {
+        let classifier = IntentClassifier::new().with_override(|agent_id, input: &str| {
+            (input == "stop").then(|| {
+                Classification::command(
+                    AgentCommand::SuspendAgent(SuspendAgent::new(agent_id, "user said stop")),
+                    0.99,
+                )
+            })
+        });
+
+        let stop = classifier.classify(AgentId::new(), "stop").await.unwrap();
+        assert_eq!(stop.kind, InputKind::Command);
+        assert_eq!(stop.source, ClassificationSource::Override);
+
+        let other = classifier.classify(AgentId::new(), "hello").await.unwrap();
+        assert_eq!(other.kind, InputKind::Chat);
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! First-Token Latency SLO
+//!
+//! Tracks time-to-first-chunk per (agent, provider) and reports an SLO
+//! violation when the error budget burns too fast.
+//!
+//! ## Burn Rate
+//!
+//! With an objective of 95% of first chunks under `threshold_ms`, the error
+//! budget is 5%. Over the last `window` requests:
+//!
+//! ```text
+//! burn_rate = (slow requests / requests) / (1 - objective)
+//! ```
+//!
+//! A burn rate of 1.0 spends the budget exactly; the tracker alerts once it
+//! reaches `burn_rate_threshold` (default 2.0) and re-arms after it drops
+//! back below, so a degraded provider produces one alert rather than one
+//! per request.
+
+use crate::value_objects::AgentId;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// SLO thresholds for first-token latency
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstTokenSlo {
+    /// A first chunk slower than this counts against the budget
+    pub threshold_ms: u64,
+    /// Fraction of requests that must meet the threshold (e.g. 0.95)
+    pub objective: f64,
+    /// Number of recent requests considered
+    pub window: usize,
+    /// Minimum samples before alerting
+    pub min_samples: usize,
+    /// Burn rate at which a violation is reported
+    pub burn_rate_threshold: f64,
+}
+
+impl FirstTokenSlo {
+    /// Create an SLO with the given latency threshold and defaults
+    pub fn new(threshold_ms: u64) -> Self {
+        Self {
+            threshold_ms,
+            ..Self::default()
+        }
+    }
+
+    /// Builder: set objective
+    pub fn with_objective(mut self, objective: f64) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Builder: set window size
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Builder: set minimum samples
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    /// Builder: set burn rate threshold
+    pub fn with_burn_rate_threshold(mut self, burn_rate: f64) -> Self {
+        self.burn_rate_threshold = burn_rate;
+        self
+    }
+
+    /// Validate the SLO
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold_ms == 0 {
+            return Err("SLO threshold must be greater than zero".to_string());
+        }
+        if !(self.objective > 0.0 && self.objective < 1.0) {
+            return Err(format!(
+                "SLO objective must be between 0 and 1 (exclusive), got {}",
+                self.objective
+            ));
+        }
+        if self.window == 0 || self.min_samples > self.window {
+            return Err("SLO window must be non-zero and at least min_samples".to_string());
+        }
+        if self.burn_rate_threshold <= 0.0 {
+            return Err("Burn rate threshold must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for FirstTokenSlo {
+    fn default() -> Self {
+        Self {
+            threshold_ms: 2_000,
+            objective: 0.95,
+            window: 100,
+            min_samples: 20,
+            burn_rate_threshold: 2.0,
+        }
+    }
+}
+
+/// Latency statistics for one (agent, provider) pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstTokenStats {
+    /// Samples in the window
+    pub samples: usize,
+    /// Samples over the threshold
+    pub breaches: usize,
+    /// Current burn rate
+    pub burn_rate: f64,
+    /// 95th percentile first-token latency in the window
+    pub p95_ms: u64,
+}
+
+/// An SLO breach to be published as `SloViolated`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloViolation {
+    /// The agent whose requests breached
+    pub agent_id: AgentId,
+    /// The provider serving those requests
+    pub provider: String,
+    /// The SLO threshold
+    pub threshold_ms: u64,
+    /// Statistics at the time of the breach
+    pub stats: FirstTokenStats,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    samples: VecDeque<u64>,
+    alerting: bool,
+}
+
+/// Tracks first-token latency and detects SLO burn
+#[derive(Debug, Default)]
+pub struct FirstTokenLatencyTracker {
+    slo: FirstTokenSlo,
+    windows: Mutex<HashMap<(AgentId, String), Window>>,
+}
+
+impl FirstTokenLatencyTracker {
+    /// Create a tracker for the given SLO
+    pub fn new(slo: FirstTokenSlo) -> Self {
+        Self {
+            slo,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The SLO being tracked
+    pub fn slo(&self) -> &FirstTokenSlo {
+        &self.slo
+    }
+
+    /// Record a time-to-first-chunk observation
+    ///
+    /// Returns a violation only on the transition into the alerting state.
+    pub fn record(
+        &self,
+        agent_id: AgentId,
+        provider: impl Into<String>,
+        first_token_ms: u64,
+    ) -> Option<SloViolation> {
+        let provider = provider.into();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let window = windows.entry((agent_id, provider.clone())).or_default();
+
+        window.samples.push_back(first_token_ms);
+        while window.samples.len() > self.slo.window {
+            window.samples.pop_front();
+        }
+
+        let stats = self.compute_stats(&window.samples);
+        tracing::debug!(
+            target: "metrics",
+            agent_id = %agent_id,
+            provider = %provider,
+            first_token_ms,
+            burn_rate = stats.burn_rate,
+            p95_ms = stats.p95_ms,
+            "first_token_latency"
+        );
+
+        let burning = stats.samples >= self.slo.min_samples
+            && stats.burn_rate >= self.slo.burn_rate_threshold;
+
+        match (burning, window.alerting) {
+            (true, false) => {
+                window.alerting = true;
+                Some(SloViolation {
+                    agent_id,
+                    provider,
+                    threshold_ms: self.slo.threshold_ms,
+                    stats,
+                })
+            }
+            (false, true) => {
+                window.alerting = false;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Current statistics for an (agent, provider) pair
+    pub fn stats(&self, agent_id: AgentId, provider: &str) -> Option<FirstTokenStats> {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows
+            .get(&(agent_id, provider.to_string()))
+            .map(|w| self.compute_stats(&w.samples))
+    }
+
+    fn compute_stats(&self, samples: &VecDeque<u64>) -> FirstTokenStats {
+        let count = samples.len();
+        let breaches = samples
+            .iter()
+            .filter(|&&ms| ms > self.slo.threshold_ms)
+            .count();
+
+        let burn_rate = if count == 0 {
+            0.0
+        } else {
+            (breaches as f64 / count as f64) / (1.0 - self.slo.objective)
+        };
+
+        let mut sorted: Vec<u64> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let p95_ms = if sorted.is_empty() {
+            0
+        } else {
+            let rank = ((sorted.len() as f64) * 0.95).ceil() as usize;
+            sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+        };
+
+        FirstTokenStats {
+            samples: count,
+            breaches,
+            burn_rate,
+            p95_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> FirstTokenLatencyTracker {
+        FirstTokenLatencyTracker::new(
+            FirstTokenSlo::new(1_000)
+                .with_window(10)
+                .with_min_samples(5)
+                .with_objective(0.9)
+                .with_burn_rate_threshold(2.0),
+        )
+    }
+
+    #[test]
+    fn test_fast_responses_do_not_alert() {
+        let tracker = tracker();
+        let agent_id = AgentId::new();
+
+        for _ in 0..10 {
+            assert!(tracker.record(agent_id, "mock", 200).is_none());
+        }
+        assert_eq!(tracker.stats(agent_id, "mock").unwrap().burn_rate, 0.0);
+    }
+
+    #[test]
+    fn test_burn_alerts_once_then_rearms() {
+        let tracker = tracker();
+        let agent_id = AgentId::new();
+
+        for _ in 0..4 {
+            tracker.record(agent_id, "ollama", 100);
+        }
+        // 1 of 5 slow -> burn 2.0 -> alert
+        let violation = tracker.record(agent_id, "ollama", 5_000).expect("violation");
+        assert_eq!(violation.provider, "ollama");
+        assert_eq!(violation.stats.breaches, 1);
+
+        // Still burning: no duplicate alert
+        assert!(tracker.record(agent_id, "ollama", 5_000).is_none());
+
+        // Recover: slow samples age out of the window
+        for _ in 0..10 {
+            tracker.record(agent_id, "ollama", 100);
+        }
+        for _ in 0..9 {
+            tracker.record(agent_id, "ollama", 100);
+        }
+        assert!(tracker.record(agent_id, "ollama", 5_000).is_none());
+        assert!(tracker.record(agent_id, "ollama", 5_000).is_some());
+    }
+
+    #[test]
+    fn test_providers_tracked_independently() {
+        let tracker = tracker();
+        let agent_id = AgentId::new();
+
+        tracker.record(agent_id, "a", 5_000);
+        tracker.record(agent_id, "b", 100);
+
+        assert_eq!(tracker.stats(agent_id, "a").unwrap().breaches, 1);
+        assert_eq!(tracker.stats(agent_id, "b").unwrap().breaches, 0);
+    }
+
+    #[test]
+    fn test_slo_validation() {
+        assert!(FirstTokenSlo::default().validate().is_ok());
+        assert!(FirstTokenSlo::new(0).validate().is_err());
+        assert!(FirstTokenSlo::default().with_objective(1.0).validate().is_err());
+    }
+}
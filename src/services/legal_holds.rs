@@ -0,0 +1,184 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Legal Holds
+//!
+//! Which agents' data is frozen for compliance. Anything that archives,
+//! crypto-shreds or ages out agent data asks [`LegalHolds::check`] first; a
+//! held agent gets a `LegalHoldRefused` event instead of the operation:
+//!
+//! ```text
+//! LegalHoldPlaced / LegalHoldReleased ──> observe() ──> held agents
+//!                                                          │
+//! ConversationRetention ── check(agent, Archival) ─────────┤
+//! EventRetention ───────── check(agent, Retention) ────────┤
+//! crypto-shredder ──────── check(agent, CryptoShredding) ──┘──> Ok(()) | LegalHoldRefused
+//! ```
+//!
+//! Holds are fed from the event stream. A [`LegalHoldBucket`] indexes the
+//! held agents in NATS KV, so an instance that missed the events (after a
+//! restart, say) learns every hold without loading each agent's aggregate.
+
+use crate::events::{AgentEvent, LegalHoldRefusedEvent};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{AgentId, HeldOperation, LegalHold};
+use async_nats::jetstream::{self, kv::Operation, kv::Store as KvStore};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Registry of agents under legal hold
+#[derive(Debug, Default)]
+pub struct LegalHolds {
+    holds: RwLock<HashMap<AgentId, LegalHold>>,
+}
+
+impl LegalHolds {
+    /// No agents held
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a stored event; only hold events matter
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        match &envelope.event {
+            AgentEvent::LegalHoldPlaced(e) => self.set(e.agent_id, Some(e.hold())),
+            AgentEvent::LegalHoldReleased(e) => self.set(e.agent_id, None),
+            _ => {}
+        }
+    }
+
+    /// Set an agent's hold from its aggregate state
+    pub fn set(&self, agent_id: AgentId, hold: Option<LegalHold>) {
+        let mut holds = self.holds.write().unwrap_or_else(|e| e.into_inner());
+        match hold {
+            Some(hold) => holds.insert(agent_id, hold),
+            None => holds.remove(&agent_id),
+        };
+    }
+
+    /// The hold on an agent, if any
+    pub fn hold(&self, agent_id: AgentId) -> Option<LegalHold> {
+        let holds = self.holds.read().unwrap_or_else(|e| e.into_inner());
+        holds.get(&agent_id).cloned()
+    }
+
+    /// Whether an agent is held
+    pub fn is_held(&self, agent_id: AgentId) -> bool {
+        let holds = self.holds.read().unwrap_or_else(|e| e.into_inner());
+        holds.contains_key(&agent_id)
+    }
+
+    /// Allow `operation` on an agent's data, or refuse it under the hold
+    pub fn check(
+        &self,
+        agent_id: AgentId,
+        operation: HeldOperation,
+    ) -> Result<(), LegalHoldRefusedEvent> {
+        match self.hold(agent_id) {
+            Some(hold) => Err(LegalHoldRefusedEvent::new(agent_id, operation, hold.reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Held agents indexed in a NATS KV bucket, keyed by agent ID
+///
+/// Entries never expire: a hold outlives any retention period.
+#[derive(Clone)]
+pub struct LegalHoldBucket {
+    kv: KvStore,
+}
+
+impl LegalHoldBucket {
+    /// Holds stored in `kv`
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Get or create the bucket, keeping only each agent's latest hold
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+
+    /// Index a stored event; only hold events matter
+    pub async fn record(&self, envelope: &EventEnvelope) -> Result<(), async_nats::Error> {
+        match &envelope.event {
+            AgentEvent::LegalHoldPlaced(e) => {
+                let hold = serde_json::to_vec(&e.hold())?;
+                self.kv.put(e.agent_id.to_string(), hold.into()).await?;
+            }
+            AgentEvent::LegalHoldReleased(e) => self.kv.delete(e.agent_id.to_string()).await?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Apply every indexed hold to `holds`, then each change as it is made
+    ///
+    /// Returns only when the watch ends.
+    pub async fn follow(&self, holds: &LegalHolds) -> Result<(), async_nats::Error> {
+        let mut watch = self.kv.watch_with_history(">").await?;
+        while let Some(entry) = watch.next().await {
+            let entry = entry?;
+            let Ok(agent_id) = entry.key.parse::<uuid::Uuid>().map(AgentId::from_uuid) else {
+                tracing::warn!("Ignoring legal hold under key {}", entry.key);
+                continue;
+            };
+            match entry.operation {
+                Operation::Put => match serde_json::from_slice::<LegalHold>(&entry.value) {
+                    Ok(hold) => holds.set(agent_id, Some(hold)),
+                    Err(e) => tracing::warn!("Ignoring legal hold of agent {}: {}", agent_id, e),
+                },
+                Operation::Delete | Operation::Purge => holds.set(agent_id, None),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{LegalHoldPlacedEvent, LegalHoldReleasedEvent};
+    use crate::test_support::envelope;
+    use crate::value_objects::PersonId;
+
+    #[test]
+    fn test_held_agents_refuse_operations() {
+        let holds = LegalHolds::new();
+        let (agent_id, counsel) = (AgentId::new(), PersonId::new());
+        assert!(holds
+            .check(agent_id, HeldOperation::CryptoShredding)
+            .is_ok());
+
+        holds.observe(&envelope(1, AgentEvent::LegalHoldPlaced(
+            LegalHoldPlacedEvent::new(agent_id, counsel, "Matter 2025-114"),
+        )));
+        let refused = holds
+            .check(agent_id, HeldOperation::CryptoShredding)
+            .unwrap_err();
+        assert_eq!(refused.operation, HeldOperation::CryptoShredding);
+        assert_eq!(refused.hold_reason, "Matter 2025-114");
+
+        holds.observe(&envelope(1, AgentEvent::LegalHoldReleased(
+            LegalHoldReleasedEvent::new(agent_id, counsel),
+        )));
+        assert!(!holds.is_held(agent_id));
+    }
+}
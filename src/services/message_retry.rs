@@ -0,0 +1,449 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Message Retry Queue
+//!
+//! A send that fails on a transient provider or tool error is retried with
+//! exponential backoff instead of waiting for the user to send it again.
+//! Retries are stored in a [`RetryStore`], so they survive a restart:
+//!
+//! ```text
+//! SendMessage fails ──> failed() ──> retryable, budget left ──> MessageRetryScheduled
+//!                           │                                      │
+//!                           │                 due() after backoff ─┘──> SendMessage again
+//!                           │
+//!                           └──> poison or budget spent ──> MessageQuarantined
+//!                                                               │
+//!                      ReleaseQuarantinedMessage (operator) ──> release()
+//!                                                               │
+//!                               QuarantinedMessageReleased + SendMessage again
+//! ```
+//!
+//! A failure that can never succeed as sent (bad request, auth, provider
+//! bug) is poison: it goes straight to quarantine without using its budget.
+//! Due retries come back highest [`RetryPriority`] first, each as a new
+//! attempt with its own message ID ([`SendMessage::next_attempt`]); the
+//! queue tracks them all under the first attempt's. Every due retry is
+//! leased before it is handed out, so any number of instances may drain
+//! `due()` without sending one twice.
+
+use crate::commands::{ReleaseQuarantinedMessage, SendMessage};
+use crate::events::{
+    AgentEvent, MessageQuarantinedEvent, MessageRetryScheduledEvent,
+    QuarantinedMessageReleasedEvent,
+};
+use crate::infrastructure::{
+    DomainError, DomainResult, PendingRetry, QuarantinedMessage, RetryPriority, RetryStore,
+};
+use crate::ports::{ChatError, ErrorCategory};
+use crate::value_objects::{clock_now, AgentId, MessageId};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Retries a message may make after its first failure, by default
+pub const DEFAULT_RETRY_BUDGET: u32 = 4;
+
+/// Errors releasing a quarantined message
+#[derive(Debug, Error)]
+pub enum RetryQueueError {
+    #[error("Message {0} is not quarantined")]
+    NotQuarantined(MessageId),
+
+    #[error("Message {message_id} was sent through agent {agent_id}")]
+    WrongAgent {
+        message_id: MessageId,
+        agent_id: AgentId,
+    },
+
+    #[error(transparent)]
+    Store(#[from] DomainError),
+}
+
+/// How failed messages are retried
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first failure, unless the message sets its own budget
+    pub retry_budget: u32,
+
+    /// Delay before the first retry; doubled for each retry after it
+    pub base_delay: Duration,
+
+    /// Longest delay between retries
+    pub max_delay: Duration,
+
+    /// How long a handed-out retry is held before it is due again
+    ///
+    /// Covers a host that dies mid-send, whose retry would otherwise be lost.
+    pub lease: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based), capped at `max_delay`
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retry_budget: DEFAULT_RETRY_BUDGET,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(300),
+            lease: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Why one attempt at a message failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedAttempt {
+    /// Taxonomy category deciding whether a retry can help
+    pub category: ErrorCategory,
+
+    /// What went wrong
+    pub error: String,
+
+    /// Earliest retry the provider or tool asked for
+    pub retry_after: Option<Duration>,
+}
+
+impl FailedAttempt {
+    /// A failure of `category`
+    pub fn new(category: ErrorCategory, error: impl Into<String>) -> Self {
+        Self {
+            category,
+            error: error.into(),
+            retry_after: None,
+        }
+    }
+
+    /// A tool call that failed mid-conversation; taken as transient
+    pub fn tool(tool: &str, error: impl std::fmt::Display) -> Self {
+        Self::new(
+            ErrorCategory::Transient,
+            format!("Tool {} failed: {}", tool, error),
+        )
+    }
+
+    /// Builder: do not retry before `delay`
+    pub fn with_retry_after(mut self, delay: Duration) -> Self {
+        self.retry_after = Some(delay);
+        self
+    }
+}
+
+impl From<&ChatError> for FailedAttempt {
+    fn from(error: &ChatError) -> Self {
+        Self {
+            category: error.category(),
+            error: error.to_string(),
+            retry_after: error.retry_delay_ms().map(Duration::from_millis),
+        }
+    }
+}
+
+/// What became of a failed message
+#[derive(Debug, Clone)]
+pub enum RetryDecision {
+    /// It will be sent again
+    Scheduled(MessageRetryScheduledEvent),
+    /// It waits for an operator
+    Quarantined(MessageQuarantinedEvent),
+}
+
+impl RetryDecision {
+    /// The event recording the decision
+    pub fn into_event(self) -> AgentEvent {
+        match self {
+            RetryDecision::Scheduled(event) => AgentEvent::MessageRetryScheduled(event),
+            RetryDecision::Quarantined(event) => AgentEvent::MessageQuarantined(event),
+        }
+    }
+}
+
+/// Durable, prioritized retry queue with a quarantine for poison messages
+pub struct MessageRetryQueue {
+    store: Arc<dyn RetryStore>,
+    policy: RetryPolicy,
+}
+
+impl MessageRetryQueue {
+    /// Create a queue over `store` with the default policy
+    pub fn new(store: Arc<dyn RetryStore>) -> Self {
+        Self {
+            store,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Builder: retry by `policy`
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The policy in force
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Record a failed attempt at `message` and schedule or quarantine it
+    pub async fn failed(
+        &self,
+        message: &SendMessage,
+        failure: &FailedAttempt,
+    ) -> DomainResult<RetryDecision> {
+        let now = clock_now();
+        // The queue keeps the first attempt, which each retry is made from
+        let message = &SendMessage {
+            message_id: message.retry_key(),
+            retry_of: None,
+            ..message.clone()
+        };
+        let previous = self.store.get(message.message_id).await?;
+        let attempts = previous.as_ref().map_or(0, |p| p.attempts) + 1;
+        let budget = message.retry_budget.unwrap_or(self.policy.retry_budget);
+
+        let reason = if !failure.category.is_retryable() {
+            Some(format!(
+                "{} failure cannot succeed as sent: {}",
+                failure.category, failure.error
+            ))
+        } else if attempts > budget {
+            Some(format!(
+                "Retry budget of {} spent: {}",
+                budget, failure.error
+            ))
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            let event = MessageQuarantinedEvent::new(
+                message.agent_id,
+                message.message_id,
+                attempts,
+                &reason,
+            );
+            self.store
+                .quarantine(QuarantinedMessage {
+                    message: message.clone(),
+                    attempts,
+                    reason,
+                    quarantined_at: event.quarantined_at,
+                })
+                .await?;
+            return Ok(RetryDecision::Quarantined(event));
+        }
+
+        let delay = self
+            .policy
+            .backoff(attempts)
+            .max(failure.retry_after.unwrap_or_default());
+        let retry_at = now + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX);
+        self.store
+            .schedule(PendingRetry {
+                message: message.clone(),
+                priority: RetryPriority::of(message),
+                attempts,
+                last_error: failure.error.clone(),
+                retry_at,
+                first_failed_at: previous.map_or(now, |p| p.first_failed_at),
+            })
+            .await?;
+        Ok(RetryDecision::Scheduled(MessageRetryScheduledEvent::new(
+            message.agent_id,
+            message.message_id,
+            attempts,
+            budget + 1,
+            &failure.error,
+            retry_at,
+        )))
+    }
+
+    /// Record a retry that failed before it reached a provider
+    ///
+    /// Agent loads, event saves and the like fail before the send does;
+    /// without this the retry's lease would lapse and it would be sent again
+    /// forever. Such failures are taken as transient, so they spend the
+    /// budget. First attempts are left to their sender, who saw the error.
+    pub async fn failed_before_send(
+        &self,
+        message: &SendMessage,
+        error: impl std::fmt::Display,
+    ) -> DomainResult<Option<RetryDecision>> {
+        if message.retry_of.is_none() {
+            return Ok(None);
+        }
+        let failure = FailedAttempt::new(ErrorCategory::Transient, error.to_string());
+        self.failed(message, &failure).await.map(Some)
+    }
+
+    /// Forget the retries of `message`, once any attempt at it was answered
+    ///
+    /// Messages that never failed have nothing to forget, and cost one read.
+    pub async fn succeeded(&self, message: &SendMessage) -> DomainResult<()> {
+        let message_id = message.retry_key();
+        if self.store.get(message_id).await?.is_none() {
+            return Ok(());
+        }
+        self.store.complete(message_id).await
+    }
+
+    /// Messages whose retry is due, highest priority and longest waiting first
+    ///
+    /// Each is leased for [`RetryPolicy::lease`] and returned as its next
+    /// attempt; report the outcome with `succeeded` or `failed`. A retry
+    /// another instance leased first is skipped.
+    pub async fn due(&self) -> DomainResult<Vec<SendMessage>> {
+        let now = clock_now();
+        let mut due: Vec<PendingRetry> = self
+            .store
+            .pending()
+            .await?
+            .into_iter()
+            .filter(|retry| retry.retry_at <= now)
+            .collect();
+        due.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.retry_at.cmp(&b.retry_at))
+        });
+
+        let lease = chrono::Duration::from_std(self.policy.lease).unwrap_or(chrono::Duration::MAX);
+        let mut messages = Vec::with_capacity(due.len());
+        for retry in due {
+            let message_id = retry.message.message_id;
+            if let Some(leased) = self.store.lease(message_id, now, now + lease).await? {
+                messages.push(leased.message.next_attempt());
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Messages awaiting review, oldest first
+    pub async fn quarantined(&self) -> DomainResult<Vec<QuarantinedMessage>> {
+        self.store.quarantined().await
+    }
+
+    /// The event `release` would record, changing nothing
+    pub async fn preview_release(
+        &self,
+        cmd: &ReleaseQuarantinedMessage,
+    ) -> Result<QuarantinedMessageReleasedEvent, RetryQueueError> {
+        let quarantined = self
+            .store
+            .quarantined()
+            .await?
+            .into_iter()
+            .find(|q| q.message.message_id == cmd.message_id)
+            .ok_or(RetryQueueError::NotQuarantined(cmd.message_id))?;
+        if quarantined.message.agent_id != cmd.agent_id {
+            return Err(RetryQueueError::WrongAgent {
+                message_id: cmd.message_id,
+                agent_id: quarantined.message.agent_id,
+            });
+        }
+        Ok(QuarantinedMessageReleasedEvent::new(
+            cmd.agent_id,
+            cmd.message_id,
+            cmd.released_by,
+        ))
+    }
+
+    /// Take a message out of quarantine; it is due at once, with a fresh budget
+    pub async fn release(
+        &self,
+        cmd: &ReleaseQuarantinedMessage,
+    ) -> Result<QuarantinedMessageReleasedEvent, RetryQueueError> {
+        let quarantined = self
+            .store
+            .release(cmd.message_id)
+            .await?
+            .ok_or(RetryQueueError::NotQuarantined(cmd.message_id))?;
+        if quarantined.message.agent_id != cmd.agent_id {
+            let agent_id = quarantined.message.agent_id;
+            self.store.quarantine(quarantined).await?;
+            return Err(RetryQueueError::WrongAgent {
+                message_id: cmd.message_id,
+                agent_id,
+            });
+        }
+        let released =
+            QuarantinedMessageReleasedEvent::new(cmd.agent_id, cmd.message_id, cmd.released_by);
+        self.store
+            .schedule(PendingRetry {
+                priority: RetryPriority::of(&quarantined.message),
+                message: quarantined.message,
+                attempts: 0,
+                last_error: quarantined.reason,
+                retry_at: released.released_at,
+                first_failed_at: released.released_at,
+            })
+            .await?;
+        Ok(released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::InMemoryRetryStore;
+    use crate::value_objects::PersonId;
+
+    #[tokio::test]
+    async fn test_transient_failures_back_off_until_budget_is_spent() {
+        let queue =
+            MessageRetryQueue::new(Arc::new(InMemoryRetryStore::new())).with_policy(RetryPolicy {
+                base_delay: Duration::ZERO,
+                ..RetryPolicy::default()
+            });
+        let message = SendMessage::new(AgentId::new(), "Hello").with_retry_budget(1);
+        let failure = FailedAttempt::new(ErrorCategory::Transient, "connection reset");
+
+        let decision = queue.failed(&message, &failure).await.unwrap();
+        assert!(matches!(decision, RetryDecision::Scheduled(ref e) if e.attempt == 1));
+        let due = queue.due().await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_ne!(due[0].message_id, message.message_id);
+        assert_eq!(due[0].retry_key(), message.message_id);
+        assert!(
+            queue.due().await.unwrap().is_empty(),
+            "a due retry is leased"
+        );
+
+        let decision = queue
+            .failed_before_send(&due[0], "event store unavailable")
+            .await
+            .unwrap();
+        assert!(matches!(decision, Some(RetryDecision::Quarantined(ref e))
+            if e.attempts == 2 && e.message_id == message.message_id));
+        assert!(queue.failed_before_send(&message, "first").await.unwrap().is_none());
+        assert_eq!(queue.quarantined().await.unwrap().len(), 1);
+
+        let release =
+            ReleaseQuarantinedMessage::new(message.agent_id, message.message_id, PersonId::new());
+        queue.preview_release(&release).await.unwrap();
+        assert_eq!(queue.quarantined().await.unwrap().len(), 1);
+        queue.release(&release).await.unwrap();
+        assert!(queue.quarantined().await.unwrap().is_empty());
+        let due = queue.due().await.unwrap();
+        assert_eq!(due[0].retry_key(), message.message_id);
+        let decision = queue.failed(&due[0], &failure).await.unwrap();
+        assert!(matches!(decision, RetryDecision::Scheduled(ref e) if e.attempt == 1));
+        queue.succeeded(&due[0]).await.unwrap();
+        assert!(queue.due().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_poison_messages_skip_their_budget() {
+        let queue = MessageRetryQueue::new(Arc::new(InMemoryRetryStore::new()));
+        let message = SendMessage::new(AgentId::new(), "Hello");
+        let failure = FailedAttempt::from(&ChatError::InvalidRequest("bad schema".to_string()));
+
+        let decision = queue.failed(&message, &failure).await.unwrap();
+        assert!(matches!(decision, RetryDecision::Quarantined(ref e) if e.attempts == 1));
+        assert_eq!(queue.policy().backoff(3), Duration::from_secs(8));
+    }
+}
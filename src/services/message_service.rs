@@ -4,12 +4,120 @@
 //!
 //! Domain service for sending messages through agents.
 //! Validates agent state and routes to appropriate providers.
+//!
+//! Sends made under a [`Deadline`] spend from the caller's budget: routing
+//! is refused once it has passed, the provider call and its HTTP timeout
+//! are bounded by what remains, and the response stream ends with
+//! [`ChatError::DeadlineExceeded`] when time runs out mid-answer.
+//!
+//! The window is the one the provider reports for the model; providers
+//! that report none fall back to their registered capability limit, and a
+//! provider that cannot be asked leaves the check to the request itself.
+//! The context is counted by the provider's tokenizer when it has one, and
+//! otherwise estimated and scaled by the prompt tokens the provider last
+//! reported for the model (see [`TokenCalibration`]).
+//! A context larger than the configured model's window is routed to the
+//! long-context profile, when one is configured and the agent's
+//! `long_context_fallback` flag allows it. Otherwise the truncation policy
+//! in the agent's model configuration may shrink it, and the send reports
+//! what was removed:
+//!
+//! ```text
+//! context tokens > window(agent model) ──> LongContextProfile fits? ──> switch
+//!                                                │ no / not permitted
+//!                                                v
+//!                                 TruncationPolicy shrinks it? ──> truncate
+//!                                                │ no
+//!                                                v
+//!                                     ChatError::ContextTooLong
+//! ```
+//!
+//! Agents with output constraints have each finished answer checked and,
+//! while it breaks them, regenerated with corrective instructions; the
+//! answer is then streamed in one piece.
 
 use crate::aggregate::Agent;
 use crate::intent::MessageIntent;
-use crate::ports::{ChatError, ChatResult, ChatStream};
-use crate::services::CapabilityRouter;
-use crate::value_objects::ContextMessage;
+use crate::ports::{until_deadline, ChatError, ChatPort, ChatResult, ChatStream, OutputLimits};
+use crate::services::{
+    collect_answer, context_tokens, enforce_constraints, generate_candidates, reflect,
+    trace_decisions, truncate_context, CapabilityRouter, HeuristicJudge, Judge, JudgedCandidates,
+    ModelJudge, ReflectionOutcome, TokenCalibration,
+};
+use crate::value_objects::{
+    clock_now, BestOfN, ConstraintEnforcement, ContextMessage, Deadline, FeatureFlags,
+    FinishReason, GenerationParams, JudgeStrategy, ModelConfig, ReasoningTrace, StreamingChunk,
+    TruncationRecord,
+};
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// Model to fall back to when a context outgrows the agent's model
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongContextProfile {
+    /// Model configuration used instead of the agent's
+    pub config: ModelConfig,
+
+    /// Context window of that model, in tokens
+    pub max_context_length: u32,
+}
+
+/// A switch to the long-context profile made for one request
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongContextSwitch {
+    /// The agent's configured model
+    pub from: ModelConfig,
+
+    /// The long-context model used instead
+    pub to: ModelConfig,
+
+    /// Tokens in the assembled context
+    pub context_tokens: u32,
+
+    /// Window of the configured model
+    pub limit: u32,
+}
+
+/// Response stream along with how it was routed
+pub struct RoutedStream {
+    /// Response chunks
+    pub stream: ChatStream,
+
+    /// Set when the request was moved to the long-context profile
+    pub long_context: Option<LongContextSwitch>,
+
+    /// What the agent's truncation policy removed from the context
+    pub truncations: Vec<TruncationRecord>,
+
+    /// Every candidate and the judge's pick, for best-of-N intents
+    pub best_of: Option<JudgedCandidates>,
+
+    /// Every draft of a reflected answer, when reflection is configured
+    pub reflection: Option<ReflectionOutcome>,
+
+    /// Each answer that broke the agent's output constraints, and what followed
+    pub constraint_enforcements: Vec<ConstraintEnforcement>,
+
+    /// The decisions above, as a tree rooted at the plan
+    pub trace: ReasoningTrace,
+}
+
+/// Stream a reflected answer, optionally preceded by its superseded drafts
+fn reflection_stream(outcome: &ReflectionOutcome, stream_drafts: bool) -> ChatStream {
+    let mut chunks: Vec<ChatResult<StreamingChunk>> = Vec::new();
+    if stream_drafts {
+        for draft in outcome.superseded() {
+            let index = chunks.len() as u32;
+            chunks.push(Ok(StreamingChunk::new(index, format!("{}\n\n", draft.content))));
+        }
+    }
+    chunks.push(Ok(StreamingChunk::final_chunk(
+        chunks.len() as u32,
+        outcome.final_answer(),
+        outcome.finish_reason,
+    )));
+    Box::pin(futures::stream::iter(chunks))
+}
 
 /// Domain service for agent message handling
 ///
@@ -17,21 +125,40 @@ use crate::value_objects::ContextMessage;
 /// 1. Validating that an agent is operational
 /// 2. Extracting model configuration from the agent
 /// 3. Routing the message to a capable provider
-/// 4. Returning the response stream
+/// 4. Returning the response stream, with output limits enforced
 ///
 /// ## Design Principles
 ///
-/// - The service is **stateless** - all state comes from the Agent aggregate
+/// - The service is **stateless** - all state comes from the Agent aggregate;
+///   it only learns how each model's token counts compare with the estimate
 /// - Message content is **not persisted** here - that's cim-dialog's job
 /// - Only **lifecycle validation** is performed - is the agent operational?
 pub struct AgentMessageService {
     router: CapabilityRouter,
+    long_context: Option<LongContextProfile>,
+    calibration: Arc<TokenCalibration>,
 }
 
 impl AgentMessageService {
     /// Create a new message service with the given router
     pub fn new(router: CapabilityRouter) -> Self {
-        Self { router }
+        Self {
+            router,
+            long_context: None,
+            calibration: Arc::new(TokenCalibration::new()),
+        }
+    }
+
+    /// Builder: share what other services learned about token counts
+    pub fn with_token_calibration(mut self, calibration: Arc<TokenCalibration>) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Builder: model to use when a context outgrows the agent's model
+    pub fn with_long_context_fallback(mut self, profile: LongContextProfile) -> Self {
+        self.long_context = Some(profile);
+        self
     }
 
     /// Send a message intent through an agent
@@ -52,6 +179,48 @@ impl AgentMessageService {
     /// - No provider satisfies the intent's capability requirements
     /// - The provider fails to process the request
     pub async fn send(&self, agent: &Agent, intent: MessageIntent) -> ChatResult<ChatStream> {
+        self.send_with_params(agent, intent, GenerationParams::default())
+            .await
+    }
+
+    /// Send a message intent that must be answered by `deadline`
+    ///
+    /// A tighter deadline already in scope still applies.
+    pub async fn send_with_deadline(
+        &self,
+        agent: &Agent,
+        intent: MessageIntent,
+        deadline: Deadline,
+    ) -> ChatResult<ChatStream> {
+        deadline.scope(self.send(agent, intent)).await
+    }
+
+    /// Send a message intent with call-site generation overrides
+    ///
+    /// `overrides` is layered over the agent's generation parameters and
+    /// any per-intent override stored in its model configuration.
+    ///
+    /// Honors the [`Deadline`] in scope, if any.
+    pub async fn send_with_params(
+        &self,
+        agent: &Agent,
+        intent: MessageIntent,
+        overrides: GenerationParams,
+    ) -> ChatResult<ChatStream> {
+        Ok(self.send_routed(agent, intent, overrides).await?.stream)
+    }
+
+    /// Send a message intent, reporting how it was routed and answered
+    ///
+    /// Same as [`send_with_params`](Self::send_with_params), for callers that
+    /// record long-context switches, context loss, best-of-N selections,
+    /// reflection drafts and the reasoning trace.
+    pub async fn send_routed(
+        &self,
+        agent: &Agent,
+        intent: MessageIntent,
+        overrides: GenerationParams,
+    ) -> ChatResult<RoutedStream> {
         // 1. Validate agent is operational
         if !agent.is_operational() {
             return Err(ChatError::InvalidRequest(format!(
@@ -69,11 +238,21 @@ impl AgentMessageService {
             ))
         })?;
 
+        overrides
+            .validate()
+            .map_err(|e| ChatError::InvalidRequest(format!("Invalid generation params: {}", e)))?;
+        let mut model_config = model_config.resolve_for_intent(intent.name(), &overrides);
+
+        let deadline = Deadline::current();
+        if deadline.is_some_and(|d| d.is_expired()) {
+            return Err(ChatError::DeadlineExceeded("routing".to_string()));
+        }
+
         // 3. Route to capable provider based on intent
-        let adapter = self.router.route(&intent)?;
+        let mut adapter = self.router.route(&intent)?;
 
         // 4. Convert intent to context and send
-        let context = match &intent {
+        let mut context = match &intent {
             MessageIntent::Chat { context, .. } => context.clone(),
             MessageIntent::Completion { prompt, .. } => {
                 vec![ContextMessage::user(prompt)]
@@ -84,21 +263,267 @@ impl AgentMessageService {
                 vec![]
             }
         };
+        if !agent.feature_flags().is_enabled(FeatureFlags::ENABLE_RAG) {
+            // Retrieved documents reach the model only for agents with RAG on
+            context.retain(|message| message.source.is_none());
+        }
 
-        // 5. Prepend system prompt if configured on agent
-        let context = if let Some(system_prompt) = agent.system_prompt() {
-            if !system_prompt.is_empty() {
-                let mut full_context = vec![ContextMessage::system(system_prompt)];
-                full_context.extend(context);
-                full_context
+        // 5. Prepend system prompt and local time/formatting note if configured on agent
+        let mut preamble = Vec::new();
+        if let Some(system_prompt) = agent.system_prompt().filter(|p| !p.is_empty()) {
+            preamble.push(ContextMessage::system(system_prompt));
+        }
+        if let Some(locale) = agent.locale() {
+            preamble.push(ContextMessage::system(locale.context_note(clock_now())));
+        }
+        let context = if preamble.is_empty() {
+            context
+        } else {
+            preamble.extend(context);
+            preamble
+        };
+
+        // 6. Move oversized contexts to the long-context profile, or truncate them
+        let best_of = model_config.best_of.get(intent.name()).cloned();
+        let reflection = model_config.reflection.clone();
+        let constraints = model_config.output_constraints.clone();
+        let mut context = context;
+        let retrieved: Vec<ContextMessage> =
+            context.iter().filter(|m| m.source.is_some()).cloned().collect();
+        let mut long_context = None;
+        let mut truncations = Vec::new();
+        let overflow = self.overflow(adapter.as_ref(), &model_config, &context).await;
+        if let Some((tokens, limit)) = overflow {
+            if let Some((fallback, switch)) =
+                self.long_context_switch(agent, &intent, &model_config, tokens, limit)
+            {
+                adapter = fallback;
+                model_config = switch.to.clone();
+                long_context = Some(switch);
             } else {
-                context
+                // Strategies measure in estimated tokens; scale the limit to match
+                let estimated = u64::from(context_tokens(&context));
+                let counted = u64::from(tokens.max(1));
+                let scaled = (u64::from(limit) * estimated / counted) as u32;
+                truncations = truncate_context(&model_config.truncation, &mut context, scaled);
+                for record in &mut truncations {
+                    record.limit = limit;
+                    record.dropped_tokens =
+                        (u64::from(record.dropped_tokens) * counted / estimated.max(1)) as u32;
+                }
+                let tokens = self.count_context(adapter.as_ref(), &model_config, &context).await;
+                if tokens > limit {
+                    return Err(ChatError::ContextTooLong {
+                        tokens: tokens as usize,
+                        limit: limit as usize,
+                    });
+                }
             }
-        } else {
-            context
+        }
+
+        // 7. Generate the answer: one stream, the judged best of several, or a
+        //    reflected revision (best-of-N takes precedence over reflection),
+        //    then hold it to the output constraints
+        let generated = async {
+            let (stream, judged, reflected) = match (&best_of, &reflection) {
+                (Some(best_of), _) => {
+                    let (stream, judged) = self
+                        .best_of(best_of, adapter.clone(), &model_config, context.clone())
+                        .await?;
+                    (stream, Some(judged), None)
+                }
+                (None, Some(reflection)) => {
+                    let outcome =
+                        reflect(adapter.as_ref(), &model_config, &context, reflection).await?;
+                    let stream = reflection_stream(&outcome, reflection.stream_drafts);
+                    (stream, None, Some(outcome))
+                }
+                (None, None) => (adapter.send(&model_config, context.clone()).await?, None, None),
+            };
+            let Some(constraints) = &constraints else {
+                return Ok::<_, ChatError>((stream, judged, reflected, Vec::new()));
+            };
+            let (answer, finish_reason) = match &reflected {
+                Some(outcome) => (outcome.final_answer().to_string(), outcome.finish_reason),
+                None => collect_answer(stream).await?,
+            };
+            let constrained = enforce_constraints(
+                adapter.as_ref(),
+                &model_config,
+                &context,
+                answer,
+                finish_reason,
+                constraints,
+            )
+            .await;
+            let enforcements = constrained.enforcements.clone();
+            Ok((constrained.into_stream(), judged, reflected, enforcements))
+        };
+
+        // 8. Bound generation by the caller's deadline
+        let (stream, best_of, reflection, constraint_enforcements) = match deadline {
+            Some(deadline) => tokio::time::timeout(deadline.remaining(), generated)
+                .await
+                .map_err(|_| ChatError::DeadlineExceeded("provider".to_string()))??,
+            None => generated.await?,
         };
 
-        adapter.send(model_config, context).await
+        // 9. Enforce stop sequences and max tokens uniformly across providers,
+        //    learning from the prompt tokens the provider reports
+        let stream = OutputLimits::from_config(&model_config).enforce(stream);
+        let stream = self.calibrate(stream, &model_config.model_name, context_tokens(&context));
+        let mut routed = RoutedStream {
+            stream: match deadline {
+                Some(deadline) => until_deadline(stream, deadline),
+                None => stream,
+            },
+            long_context,
+            truncations,
+            best_of,
+            reflection,
+            constraint_enforcements,
+            trace: ReasoningTrace::new(),
+        };
+
+        // 10. Record the decisions made along the way
+        routed.trace = trace_decisions(&intent, &model_config, &retrieved, &routed);
+        Ok(routed)
+    }
+
+    /// Sample `best_of.n` candidates in parallel and answer with the judge's pick
+    async fn best_of(
+        &self,
+        best_of: &BestOfN,
+        adapter: Arc<dyn ChatPort>,
+        model_config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<(ChatStream, JudgedCandidates)> {
+        best_of.validate().map_err(ChatError::InvalidRequest)?;
+        let registry = self.router.registry();
+
+        let judge: Box<dyn Judge> = match &best_of.judge {
+            JudgeStrategy::Heuristic => Box::new(HeuristicJudge),
+            JudgeStrategy::Model(config) => {
+                let chat = registry.get_adapter(&config.provider).ok_or_else(|| {
+                    ChatError::ConfigurationError(format!(
+                        "No adapter registered for judge provider {}",
+                        config.provider
+                    ))
+                })?;
+                Box::new(ModelJudge::new(chat, config.clone()))
+            }
+        };
+
+        let samplers = (0..best_of.n as usize)
+            .map(|i| match best_of.models.get(i % best_of.models.len().max(1)) {
+                Some(model) => (registry.get_adapter(&model.provider), model.clone()),
+                None => (Some(adapter.clone()), model_config.clone()),
+            })
+            .collect();
+        let candidates = generate_candidates(samplers, &context).await;
+        let selection = judge.select(&context, &candidates).await?;
+
+        let winner = &candidates[selection.index];
+        let chunk = StreamingChunk::final_chunk(
+            0,
+            winner.content.clone(),
+            winner.finish_reason.unwrap_or(FinishReason::Stop),
+        );
+        let judged = JudgedCandidates {
+            judge: judge.name(),
+            selected: selection.index,
+            rationale: selection.rationale,
+            candidates,
+        };
+        Ok((Box::pin(futures::stream::once(async { Ok(chunk) })), judged))
+    }
+
+    /// Tokens in `context`: the provider's count, or the calibrated estimate
+    async fn count_context(
+        &self,
+        adapter: &dyn ChatPort,
+        model_config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> u32 {
+        let estimated = context_tokens(context);
+        let model = &model_config.model_name;
+        match adapter.count_tokens(model_config, context).await {
+            Ok(Some(counted)) => {
+                self.calibration.record(model, estimated, counted);
+                counted
+            }
+            Ok(None) => self.calibration.scale(model, estimated),
+            Err(e) => {
+                tracing::debug!("Could not count tokens for {}: {}", model, e);
+                self.calibration.scale(model, estimated)
+            }
+        }
+    }
+
+    /// Record the prompt tokens reported on the final chunk against the estimate
+    fn calibrate(&self, stream: ChatStream, model: &str, estimated: u32) -> ChatStream {
+        let calibration = self.calibration.clone();
+        let model = model.to_string();
+        Box::pin(stream.inspect(move |chunk| {
+            if let Ok(StreamingChunk { usage: Some(usage), .. }) = chunk {
+                calibration.record(&model, estimated, usage.prompt_tokens);
+            }
+        }))
+    }
+
+    /// Context tokens and the model's window, if the context exceeds it
+    async fn overflow(
+        &self,
+        adapter: &dyn ChatPort,
+        model_config: &ModelConfig,
+        context: &[ContextMessage],
+    ) -> Option<(u32, u32)> {
+        let limit = match adapter.context_window(&model_config.model_name).await {
+            Ok(Some(window)) => window,
+            Ok(None) => self
+                .router
+                .registry()
+                .get_capabilities(&model_config.provider)
+                .and_then(|caps| caps.max_context_length)?,
+            Err(e) => {
+                tracing::debug!("Context window of {} unknown: {}", model_config.model_name, e);
+                return None;
+            }
+        };
+        let tokens = self.count_context(adapter, model_config, context).await;
+        (tokens > limit).then_some((tokens, limit))
+    }
+
+    /// The long-context adapter and switch, if the profile may take this request
+    fn long_context_switch(
+        &self,
+        agent: &Agent,
+        intent: &MessageIntent,
+        model_config: &ModelConfig,
+        tokens: u32,
+        limit: u32,
+    ) -> Option<(Arc<dyn ChatPort>, LongContextSwitch)> {
+        let profile = self.long_context.as_ref()?;
+        if !agent.feature_flags().is_enabled(FeatureFlags::LONG_CONTEXT_FALLBACK)
+            || tokens > profile.max_context_length
+        {
+            return None;
+        }
+
+        let registry = self.router.registry();
+        let required = self.router.requirements_for(intent).capabilities;
+        registry
+            .get_capabilities(&profile.config.provider)
+            .filter(|caps| caps.satisfies(&required))?;
+        let fallback = registry.get_adapter(&profile.config.provider)?;
+
+        let switch = LongContextSwitch {
+            from: model_config.clone(),
+            to: profile.config.clone(),
+            context_tokens: tokens,
+            limit,
+        };
+        Some((fallback, switch))
     }
 
     /// Send a simple chat message through an agent
@@ -121,6 +546,19 @@ impl AgentMessageService {
         self.send(agent, intent).await
     }
 
+    /// Load the agent's configured model so its first message is not a cold start
+    ///
+    /// Does nothing for agents without a model or whose provider is not registered.
+    pub async fn prewarm(&self, agent: &Agent) -> ChatResult<()> {
+        let Some(config) = agent.model_config() else {
+            return Ok(());
+        };
+        match self.router.registry().get_adapter(&config.provider) {
+            Some(adapter) => adapter.prewarm(config).await,
+            None => Ok(()),
+        }
+    }
+
     /// Get access to the router
     pub fn router(&self) -> &CapabilityRouter {
         &self.router
@@ -140,7 +578,11 @@ mod tests {
     use crate::capabilities::ProviderCapabilities;
     use crate::events::*;
     use crate::ports::MockChatAdapter;
-    use crate::value_objects::{AgentId, ModelConfig, PersonId, ProviderType};
+    use crate::value_objects::{
+        AgentId, BestOfN, ModelConfig, PersonId, ProviderType, TraceStepKind, TruncationPolicy,
+        TruncationStrategy,
+    };
+    use std::collections::BTreeMap;
 
     fn setup_service() -> AgentMessageService {
         let mut registry = ProviderRegistry::new();
@@ -222,4 +664,156 @@ mod tests {
         let result = service.chat_with_context(&agent, context).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_send_with_invalid_params_fails() {
+        let service = setup_service();
+        let agent = create_active_agent();
+
+        let intent = MessageIntent::chat(vec![ContextMessage::user("Hello")]);
+        let overrides = GenerationParams::new().with_temperature(0.3).with_top_p(0.5);
+
+        let result = service.send_with_params(&agent, intent, overrides).await;
+        assert!(matches!(result, Err(ChatError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_send_after_deadline_fails_before_routing() {
+        let service = setup_service();
+        let agent = create_active_agent();
+        let intent = MessageIntent::chat(vec![ContextMessage::user("Hello")]);
+        let expired = Deadline::at(chrono::Utc::now() - chrono::Duration::seconds(1));
+
+        let result = service.send_with_deadline(&agent, intent, expired).await;
+        assert!(matches!(result, Err(ChatError::DeadlineExceeded(stage)) if stage == "routing"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_context_falls_back_to_long_context_model() {
+        let agent = create_active_agent();
+        let long = || MessageIntent::chat(vec![ContextMessage::user("word ".repeat(20_000))]);
+
+        let result = setup_service()
+            .send_routed(&agent, long(), GenerationParams::new())
+            .await;
+        assert!(matches!(result, Err(ChatError::ContextTooLong { limit: 4_096, .. })));
+
+        let mut registry = ProviderRegistry::new();
+        registry.register(
+            ProviderType::Mock,
+            MockChatAdapter::new(),
+            ProviderCapabilities::mock(),
+        );
+        registry.register(
+            ProviderType::Gemini,
+            MockChatAdapter::new(),
+            ProviderCapabilities::google_gemini(),
+        );
+        let profile = LongContextProfile {
+            config: ModelConfig::new(ProviderType::Gemini, "gemini-1.5-pro"),
+            max_context_length: 1_048_576,
+        };
+        let service = AgentMessageService::new(CapabilityRouter::new(registry))
+            .with_long_context_fallback(profile);
+
+        let routed = service.send_routed(&agent, long(), GenerationParams::new()).await.unwrap();
+        let switch = routed.long_context.expect("switched to the long-context model");
+        assert_eq!(switch.from.provider, ProviderType::Mock);
+        assert_eq!(switch.to.provider, ProviderType::Gemini);
+        assert!(switch.context_tokens > switch.limit);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_context_is_truncated_by_policy() {
+        let agent_id = AgentId::new();
+        let config = ModelConfig::mock()
+            .with_truncation_policy(TruncationPolicy::new([TruncationStrategy::DropOldest]));
+        let agent = Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                    agent_id,
+                    PersonId::new(),
+                    "TruncatingAgent",
+                    None,
+                )),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(agent_id, config)),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id)),
+            ])
+            .unwrap();
+        let turns = (0..6).map(|i| ContextMessage::user(format!("{i} {}", "x".repeat(4_000))));
+        let intent = MessageIntent::chat(turns.collect());
+
+        let routed = setup_service()
+            .send_routed(&agent, intent, GenerationParams::new())
+            .await
+            .unwrap();
+        assert_eq!(routed.truncations.len(), 1);
+        assert_eq!(routed.truncations[0].dropped_messages, 2);
+        assert!(routed.truncations[0].excerpts[0].starts_with("0 "));
+    }
+
+    #[tokio::test]
+    async fn test_best_of_n_answers_with_judged_candidate() {
+        let agent_id = AgentId::new();
+        let config = ModelConfig::mock().with_best_of("chat", BestOfN::new(3));
+        let agent = Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                    agent_id,
+                    PersonId::new(),
+                    "SamplingAgent",
+                    None,
+                )),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(agent_id, config)),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id)),
+            ])
+            .unwrap();
+        let intent = MessageIntent::chat(vec![ContextMessage::user("Hello")]);
+
+        let routed = setup_service()
+            .send_routed(&agent, intent, GenerationParams::new())
+            .await
+            .unwrap();
+        let judged = routed.best_of.expect("best-of-N was configured for chat");
+        assert_eq!(judged.candidates.len(), 3);
+        assert_eq!(judged.judge, "heuristic");
+
+        let chunks: Vec<_> = routed.stream.collect().await;
+        let answer: String = chunks.into_iter().map(|c| c.unwrap().content).collect();
+        assert_eq!(answer, judged.candidates[judged.selected].content);
+    }
+
+    #[tokio::test]
+    async fn test_retrieved_documents_are_dropped_without_rag() {
+        let agent = create_active_agent();
+        let intent = || {
+            MessageIntent::chat(vec![
+                ContextMessage::source("doc-1", 1, "Refunds take 5 days"),
+                ContextMessage::user("How long do refunds take?"),
+            ])
+        };
+        let retrievals = |routed: &RoutedStream| {
+            let steps = routed.trace.steps().iter();
+            steps.filter(|step| step.kind == TraceStepKind::Retrieval).count()
+        };
+
+        let routed = setup_service()
+            .send_routed(&agent, intent(), GenerationParams::new())
+            .await
+            .unwrap();
+        assert_eq!(retrievals(&routed), 1);
+
+        let flags = BTreeMap::from([(FeatureFlags::ENABLE_RAG.to_string(), false)]);
+        let agent = agent
+            .apply_event(&AgentEvent::FeatureFlagsChanged(FeatureFlagsChangedEvent::new(
+                agent.id(),
+                flags,
+            )))
+            .unwrap();
+        let routed = setup_service()
+            .send_routed(&agent, intent(), GenerationParams::new())
+            .await
+            .unwrap();
+        assert_eq!(retrievals(&routed), 0);
+    }
 }
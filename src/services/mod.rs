@@ -9,7 +9,39 @@
 //!
 //! - `AgentMessageService` - Validates agents and routes messages to providers
 //! - `CapabilityRouter` - Routes intents to capable providers via lattice matching
+//! - `CapabilityProber` - Exercises each advertised capability and withdraws those that fail
 //! - `ModelConfigurationService` - Manages model configuration lifecycle
+//! - `InFlightStreams` - Tracks response streams so they can be cancelled
+//! - `FirstTokenLatencyTracker` - Tracks time-to-first-chunk against an SLO
+//! - `IntentClassifier` - Maps free-form input to an intent or command
+//! - `ConversationRetention` - Archives conversations idle past their TTL
+//! - `ConversationTransfer` - Verifies a transfer target and fits the handoff to its window
+//! - `AnomalyDetector` - Flags error rate, latency and token usage off an agent's baseline
+//! - `AutomationRules` - Event-triggered rules that issue commands when a windowed count trips
+//! - `LegalHolds` - Refuses archival and deletion of agents under legal hold
+//! - `EventRetention` - Ages agents' events out, sparing agents under legal hold
+//! - `ArtifactCollector` - Deletes blobs, embeddings and workspace files no event references
+//! - `BatchInference` - Runs prompt files through an agent offline, results to the blob store
+//! - `MessageRetryQueue` - Retries failed sends with backoff and quarantines poison messages
+//! - `export_manifest` / `apply_manifest` - Declarative fleet manifests for GitOps
+//! - `FleetPlanner` - Projects spend, concurrency and rate-limit headroom of a what-if fleet
+//! - `DriftDetector` - Reports live agents that no longer match the manifest
+//! - `CanaryRollout` - Bakes a new definition on a canary before promoting it
+//! - `EvalRunner` - Scores an agent against a suite of golden prompts
+//! - `ResponseDiffer` - Diffs two model configurations' answers to the same prompts
+//! - `ShadowDeployment` - Mirrors traffic to an undelivered shadow agent and diffs its answers
+//! - `answer_negotiation` / `conclude_negotiation` - Signed capability handshake before delegation
+//! - `OwnerNotifier` - Maps agent events to person-domain owner notifications
+//! - `PermissionEscalations` - Holds commands lacking a permission until the owner approves
+//! - `ChangeRateGuardrails` - Blocks or holds bursts of configuration and permission changes
+//! - `HeuristicJudge` / `ModelJudge` - Pick the best of N candidate answers
+//! - `reflect` - Critiques and revises an answer within a token budget
+//! - `enforce_constraints` - Regenerates answers that break the agent's output constraints
+//! - `truncate_context` - Shrinks an oversized context by the agent's truncation policy
+//! - `TokenCalibration` - Learns how each model's token counts compare with the estimate
+//! - `trace_decisions` - Records the plan, retrieval, tool and judging decisions of a send
+//! - `ToolCatalog` - Versions tool definitions and migrates agents off deprecated ones
+//! - `ModerationStage` - Scores message toxicity and judges it against guardrail thresholds
 //!
 //! ## Architecture
 //!
@@ -50,14 +82,131 @@
 //! let stream = service.send(&agent, intent).await?;
 //! ```
 
+mod anomaly_detector;
+mod artifact_gc;
+mod automation_rules;
+mod batch_inference;
+mod best_of_n;
+mod canary;
+mod capability_probe;
 mod capability_router;
+mod change_guardrails;
+mod context_truncation;
+mod conversation_inactivity;
+mod conversation_retention;
+mod conversation_transfer;
+mod decision_trace;
+mod drift_detector;
+mod eval_suite;
+mod event_retention;
+mod fleet_manifest;
+mod fleet_planner;
+mod in_flight_streams;
+mod intent_classifier;
+mod latency_slo;
+mod legal_holds;
+mod message_retry;
 mod message_service;
 mod model_configuration_service;
+mod negotiation;
+mod output_constraints;
+mod owner_notifications;
+mod permission_escalation;
+mod processing_indicators;
+mod reflection;
+mod response_diff;
+mod shadow;
+mod tool_catalog;
+mod toxicity;
 // Temporarily disabled - over-engineered, being replaced
 // mod agent_definition_loader;
 
-pub use capability_router::CapabilityRouter;
-pub use message_service::AgentMessageService;
+pub use anomaly_detector::{AnomalyDetector, AnomalyPolicy};
+pub use artifact_gc::{ArtifactCollector, GcFailure, GcReport, LiveReferences};
+pub use automation_rules::{
+    AutomationRule, AutomationRules, RuleAction, RuleBucket, RuleCondition, RuleError, RuleFiring,
+    RuleRequest, RuleResult,
+};
+pub use batch_inference::{
+    BatchError, BatchEventSink, BatchInference, BatchOutput, BatchPrompt, BatchRequest,
+    BatchResult, DEFAULT_BATCH_CONCURRENCY, DEFAULT_BATCH_PART_SIZE,
+};
+pub use best_of_n::{
+    generate_candidates, HeuristicJudge, Judge, JudgedCandidates, ModelJudge, Selection,
+};
+pub use canary::{CanaryOutcome, CanaryPolicy, CanaryRollout};
+pub use capability_probe::{
+    CapabilityProbe, CapabilityProber, ProbeFailure, ProbeReport, DEFAULT_PROBE_TIMEOUT,
+};
+pub use capability_router::{CapabilityRouter, RequirementsOverride};
+pub use change_guardrails::{
+    ChangeRateGuardrails, GuardrailVerdict, DEFAULT_CHANGE_RATE_WINDOW_SECS,
+};
+pub use context_truncation::{context_tokens, truncate_context, TokenCalibration};
+pub use conversation_inactivity::{ConversationResources, InactivityTimers};
+pub use conversation_retention::{ConversationRetention, RetentionPolicy};
+pub use conversation_transfer::{
+    ConversationTransfer, TransferError, DEFAULT_HANDOFF_HEADROOM_TOKENS,
+};
+pub use decision_trace::trace_decisions;
+pub use drift_detector::{DriftDetector, DriftReport};
+pub use eval_suite::{
+    AssertionOutcome, EvalAssertion, EvalError, EvalReport, EvalResult, EvalRunner, EvalSuite,
+    GoldenPrompt, PromptOutcome,
+};
+pub(crate) use eval_suite::cosine_similarity;
+pub use event_retention::{EventRetention, RetentionSweep, DEFAULT_EVENT_MAX_AGE};
+pub use fleet_manifest::{
+    apply_manifest, export_manifest, AgentBlueprint, AgentManifest, FleetManifest, ManifestError,
+    ManifestPlan, ManifestResult, ToolAssignment, MANIFEST_VERSION,
+};
+pub use fleet_planner::{
+    ExpectedVolume, FleetPlan, FleetPlanner, PlannedDeployment, ProviderLoad, ProviderQuote,
+};
+pub use in_flight_streams::InFlightStreams;
+pub use intent_classifier::{
+    Classification, ClassificationOverride, ClassificationSource, ClassifiedInput, InputKind,
+    IntentClassifier, IntentClassifierError, IntentClassifierResult, DEFAULT_FALLBACK_THRESHOLD,
+};
+pub use latency_slo::{FirstTokenLatencyTracker, FirstTokenSlo, FirstTokenStats, SloViolation};
+pub use legal_holds::{LegalHoldBucket, LegalHolds};
+pub use message_retry::{
+    FailedAttempt, MessageRetryQueue, RetryDecision, RetryPolicy, RetryQueueError,
+    DEFAULT_RETRY_BUDGET,
+};
+pub use message_service::{
+    AgentMessageService, LongContextProfile, LongContextSwitch, RoutedStream,
+};
 pub use model_configuration_service::ModelConfigurationService;
+pub use negotiation::{
+    answer_negotiation, conclude_negotiation, CapabilityStatement, NegotiationRequest,
+    SignedCapabilityStatement,
+};
+pub(crate) use output_constraints::collect_answer;
+pub use output_constraints::{enforce_constraints, ConstrainedAnswer};
+pub use owner_notifications::{
+    NotificationAction, NotificationPreferences, NotificationSeverity, NotifyPerson,
+    OwnerNotifier, DEFAULT_ALERT_EVENTS,
+};
+pub use permission_escalation::{
+    EscalationError, PermissionEscalations, ResumedCommand, DEFAULT_ESCALATION_GRANT_SECS,
+    DEFAULT_ESCALATION_TIMEOUT_SECS,
+};
+pub use processing_indicators::{
+    IndicatorSink, ProcessingIndicator, ProcessingIndicators, DEFAULT_PROGRESS_INTERVAL,
+};
+pub use reflection::{reflect, ReflectionOutcome};
+pub use response_diff::{ComparisonError, ComparisonResult, ResponseDiffer};
+pub use shadow::{
+    ShadowDeployment, ShadowDeployments, ShadowRequest, DEFAULT_MAX_SHADOW_EXCHANGES,
+    SHADOW_COST_TAG,
+};
+pub use tool_catalog::{
+    EnabledTool, PublishedTool, ToolCatalog, ToolCatalogBucket, ToolCatalogError,
+    ToolCatalogResult, ToolDeprecation, ToolRequest,
+};
+pub use toxicity::{
+    ClassifierScorer, LexiconScorer, ModerationStage, ToxicityClassifier, ToxicityScorer,
+};
 // Temporarily disabled
 // pub use agent_definition_loader::{AgentDefinitionLoader, LoaderError, LoaderResult};
@@ -0,0 +1,299 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Capability Negotiation
+//!
+//! Before one agent delegates to another it asks what the target can do.
+//! The target answers with a capability statement derived from its
+//! provider's lattice position and signed with its host key; the requester
+//! verifies the statement, checks it against its requirements and records
+//! the outcome:
+//!
+//! ```text
+//! requester                                   target
+//!     │ NegotiationRequest { requirements }      │
+//!     │ ──── {domain}.queries.agent.{id}.negotiate ──>
+//!     │                                          │ CapabilityStatement::for_agent
+//!     │ <──────── SignedCapabilityStatement ──── │ sign(host key)
+//!     │
+//!     │ conclude_negotiation: verify ──> requirements ⊑ offered?
+//!     v
+//! CapabilityNegotiated (requester's stream, for audit)
+//! ```
+//!
+//! A statement that is missing, unsigned by a trusted key, or issued for a
+//! different negotiation or agent is rejected without being compared.
+
+use crate::aggregate::Agent;
+use crate::capabilities::{CapabilityRequirements, ProviderCapabilities, RuntimeCapabilities};
+use crate::events::CapabilityNegotiatedEvent;
+use crate::infrastructure::{EventSigner, EventVerifier, SignatureError, SignatureResult};
+use crate::value_objects::{next_id, AgentId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Opening message of a handshake
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NegotiationRequest {
+    /// Correlates request, statement and outcome
+    pub negotiation_id: Uuid,
+
+    /// The agent that wants to delegate
+    pub requester: AgentId,
+
+    /// The agent asked to take the work
+    pub target: AgentId,
+
+    /// What the work needs
+    pub requirements: CapabilityRequirements,
+}
+
+impl NegotiationRequest {
+    /// Start a negotiation from `requester` to `target`
+    pub fn new(requester: AgentId, target: AgentId, requirements: CapabilityRequirements) -> Self {
+        Self {
+            negotiation_id: next_id(),
+            requester,
+            target,
+            requirements,
+        }
+    }
+}
+
+/// What an agent can do, as stated by the agent itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityStatement {
+    /// Negotiation this statement answers
+    pub negotiation_id: Uuid,
+
+    /// The stating agent
+    pub agent_id: AgentId,
+
+    /// Capabilities of the agent's configured provider, less any that failed a probe
+    pub capabilities: RuntimeCapabilities,
+
+    /// Context window of the provider, in tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_length: Option<u32>,
+
+    /// Whether responses stream
+    pub streaming: bool,
+
+    /// Whether the agent currently accepts messages
+    pub operational: bool,
+
+    /// When the statement was made
+    pub issued_at: DateTime<Utc>,
+}
+
+impl CapabilityStatement {
+    /// State `agent`'s capabilities in answer to `negotiation_id`
+    ///
+    /// An agent without a model configuration states no capabilities.
+    #[allow(deprecated)]
+    pub fn for_agent(agent: &Agent, negotiation_id: Uuid) -> Self {
+        let max_context_length = agent.model_config().and_then(|config| {
+            ProviderCapabilities::for_provider(config.provider).max_context_length
+        });
+        let capabilities = agent.advertised_capabilities().unwrap_or_default();
+        Self {
+            negotiation_id,
+            agent_id: agent.id(),
+            capabilities,
+            max_context_length,
+            streaming: capabilities.contains(RuntimeCapabilities::STREAMING),
+            operational: agent.is_operational(),
+            issued_at: Utc::now(),
+        }
+    }
+
+    /// Requirements this statement does not meet, by name
+    pub fn unmet(&self, requirements: &CapabilityRequirements) -> Vec<String> {
+        let mut missing: Vec<String> = requirements
+            .capabilities
+            .difference(self.capabilities)
+            .iter_names()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        if let Some(needed) = requirements.min_context_length {
+            if self.max_context_length.is_some_and(|offered| offered < needed) {
+                missing.push(format!("min_context_length {}", needed));
+            }
+        }
+        if requirements.requires_streaming && !self.streaming {
+            missing.push("streaming".to_string());
+        }
+        missing
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("capability statement serializes")
+    }
+}
+
+/// A capability statement with a detached signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedCapabilityStatement {
+    /// The statement as signed
+    pub statement: CapabilityStatement,
+
+    /// Public key of the signer
+    pub key_id: String,
+
+    /// Hex-encoded signature of the serialized statement
+    pub signature: String,
+}
+
+impl SignedCapabilityStatement {
+    /// Sign `statement` with `signer`
+    pub fn sign(statement: CapabilityStatement, signer: &EventSigner) -> SignatureResult<Self> {
+        Ok(Self {
+            signature: signer.sign(&statement.payload())?,
+            key_id: signer.key_id(),
+            statement,
+        })
+    }
+
+    /// Check the signature was made by a key trusted for the stating agent
+    pub fn verify(&self, verifier: &EventVerifier) -> SignatureResult<()> {
+        verifier.verify_detached(
+            &self.key_id,
+            &self.signature,
+            &self.statement.payload(),
+            self.statement.agent_id,
+        )
+    }
+}
+
+/// Answer a negotiation request on behalf of `agent`
+pub fn answer_negotiation(
+    agent: &Agent,
+    request: &NegotiationRequest,
+    signer: &EventSigner,
+) -> SignatureResult<SignedCapabilityStatement> {
+    if request.target != agent.id() {
+        // This host's key cannot speak for another agent
+        return Err(SignatureError::Untrusted {
+            key_id: signer.key_id(),
+            agent_id: request.target,
+        });
+    }
+    let statement = CapabilityStatement::for_agent(agent, request.negotiation_id);
+    SignedCapabilityStatement::sign(statement, signer)
+}
+
+/// Decide a negotiation from the target's answer
+///
+/// `answer` is `None` when the target did not reply. The returned event is
+/// recorded on the requester's stream whatever the outcome.
+pub fn conclude_negotiation(
+    request: &NegotiationRequest,
+    answer: Option<&SignedCapabilityStatement>,
+    verifier: &EventVerifier,
+) -> CapabilityNegotiatedEvent {
+    let mut event = CapabilityNegotiatedEvent::new(
+        request.requester,
+        request.negotiation_id,
+        request.target,
+        request.requirements.capabilities,
+    );
+
+    let Some(answer) = answer else {
+        event.reason = Some("target did not answer".to_string());
+        return event;
+    };
+    let statement = &answer.statement;
+    if statement.negotiation_id != request.negotiation_id || statement.agent_id != request.target
+    {
+        event.reason = Some("statement answers a different negotiation".to_string());
+        return event;
+    }
+    if let Err(e) = answer.verify(verifier) {
+        event.reason = Some(format!("statement rejected: {}", e));
+        return event;
+    }
+
+    event.offered = Some(statement.capabilities);
+    event.key_id = Some(answer.key_id.clone());
+    event.missing = statement.unmet(&request.requirements);
+    event.accepted = statement.operational && event.missing.is_empty();
+    if !statement.operational {
+        event.reason = Some("target is not operational".to_string());
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentActivatedEvent, AgentDeployedEvent, AgentEvent, ModelConfiguredEvent};
+    use crate::infrastructure::InMemoryKeyRegistry;
+    use crate::value_objects::{ModelConfig, PersonId};
+    use std::sync::Arc;
+
+    fn active_agent() -> Agent {
+        let id = AgentId::new();
+        Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(id, PersonId::new(), "t", None)),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(id, ModelConfig::mock())),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(id)),
+            ])
+            .unwrap()
+    }
+
+    fn verifier_trusting(signer: &EventSigner, agent_id: AgentId) -> EventVerifier {
+        let registry = InMemoryKeyRegistry::new();
+        registry.trust_for(signer.key_id(), [agent_id]);
+        EventVerifier::new(Arc::new(registry))
+    }
+
+    #[test]
+    fn test_accepts_capable_target_and_lists_gaps() {
+        let target = active_agent();
+        let signer = EventSigner::generate();
+        let verifier = verifier_trusting(&signer, target.id());
+
+        let chat = NegotiationRequest::new(
+            AgentId::new(),
+            target.id(),
+            CapabilityRequirements::text_chat(),
+        );
+        let answer = answer_negotiation(&target, &chat, &signer).unwrap();
+        let outcome = conclude_negotiation(&chat, Some(&answer), &verifier);
+        assert!(outcome.accepted);
+        assert_eq!(outcome.key_id, Some(signer.key_id()));
+
+        let vision = NegotiationRequest::new(
+            AgentId::new(),
+            target.id(),
+            CapabilityRequirements::vision().with_min_context(100_000),
+        );
+        let answer = answer_negotiation(&target, &vision, &signer).unwrap();
+        let outcome = conclude_negotiation(&vision, Some(&answer), &verifier);
+        assert!(!outcome.accepted);
+        assert_eq!(outcome.missing, ["VISION", "min_context_length 100000"]);
+    }
+
+    #[test]
+    fn test_rejects_untrusted_or_tampered_statements() {
+        let target = active_agent();
+        let signer = EventSigner::generate();
+        let request = NegotiationRequest::new(
+            AgentId::new(),
+            target.id(),
+            CapabilityRequirements::text_chat(),
+        );
+        let mut answer = answer_negotiation(&target, &request, &signer).unwrap();
+
+        let stranger = verifier_trusting(&EventSigner::generate(), target.id());
+        let outcome = conclude_negotiation(&request, Some(&answer), &stranger);
+        assert!(!outcome.accepted && outcome.offered.is_none());
+
+        answer.statement.capabilities = RuntimeCapabilities::all();
+        let outcome =
+            conclude_negotiation(&request, Some(&answer), &verifier_trusting(&signer, target.id()));
+        assert!(outcome.reason.unwrap().contains("does not match"));
+        assert!(!conclude_negotiation(&request, None, &stranger).accepted);
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Output constraint enforcement
+//!
+//! Checks a finished answer against the agent's [`OutputConstraints`] and
+//! regenerates it with corrective instructions while it breaks them:
+//!
+//! ```text
+//! answer 0 ──> check ──> ok ──> final
+//!                │ violations: Regenerated
+//!                v
+//!   context + answer 0 + "fix: ..." ──> answer 1 ──> check ──> ... (max_retries)
+//!                                                      │ still violating
+//!                                                      v
+//!                                          Failed or Delivered (on_exhausted)
+//! ```
+//!
+//! Every answer that broke a rule is recorded as a [`ConstraintEnforcement`],
+//! for callers to publish as `OutputConstraintEnforced` events. A failed
+//! regeneration ends the loop like running out of retries.
+
+use super::best_of_n::sample;
+use crate::ports::{ChatError, ChatPort, ChatResult, ChatStream};
+use crate::value_objects::{
+    ConstraintEnforcement, ConstraintViolation, ContextMessage, EnforcementAction, ExhaustedAction,
+    FinishReason, ModelConfig, OutputConstraints, StreamingChunk,
+};
+use futures::StreamExt;
+
+/// The answer that came out of enforcement, and each step taken on the way
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstrainedAnswer {
+    /// The last answer generated
+    pub content: String,
+
+    /// Why the last answer stopped generating
+    pub finish_reason: FinishReason,
+
+    /// One entry per answer that broke a rule, in order
+    pub enforcements: Vec<ConstraintEnforcement>,
+}
+
+impl ConstrainedAnswer {
+    /// Whether the response fails because no answer met the constraints
+    pub fn failed(&self) -> bool {
+        self.enforcements
+            .last()
+            .is_some_and(|e| e.action == EnforcementAction::Failed)
+    }
+
+    /// Stream the answer, or the failure when no answer met the constraints
+    pub fn into_stream(self) -> ChatStream {
+        let chunk = if self.failed() {
+            let broken = self
+                .enforcements
+                .last()
+                .map(|e| describe(&e.violations))
+                .unwrap_or_default();
+            Err(ChatError::ConstraintViolation(format!(
+                "answer broke them after {} attempt(s): {}",
+                self.enforcements.len(),
+                broken
+            )))
+        } else {
+            Ok(StreamingChunk::final_chunk(
+                0,
+                self.content,
+                self.finish_reason,
+            ))
+        };
+        Box::pin(futures::stream::iter([chunk]))
+    }
+}
+
+/// Read a streamed answer to the end
+pub(crate) async fn collect_answer(mut stream: ChatStream) -> ChatResult<(String, FinishReason)> {
+    let mut answer = String::new();
+    let mut finish_reason = FinishReason::Stop;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        answer.push_str(&chunk.content);
+        if let Some(reason) = chunk.finish_reason {
+            finish_reason = reason;
+        }
+    }
+    Ok((answer, finish_reason))
+}
+
+/// Check `answer` to `context` against `constraints`, regenerating it as needed
+pub async fn enforce_constraints(
+    chat: &dyn ChatPort,
+    config: &ModelConfig,
+    context: &[ContextMessage],
+    answer: String,
+    finish_reason: FinishReason,
+    constraints: &OutputConstraints,
+) -> ConstrainedAnswer {
+    let mut outcome = ConstrainedAnswer {
+        content: answer,
+        finish_reason,
+        enforcements: Vec::new(),
+    };
+
+    for attempt in 0.. {
+        let violations = constraints.check(&outcome.content);
+        if violations.is_empty() {
+            break;
+        }
+        let mut action = if attempt < constraints.max_retries {
+            EnforcementAction::Regenerated
+        } else {
+            exhausted(constraints)
+        };
+
+        let regenerated = if action == EnforcementAction::Regenerated {
+            let retry = sample(
+                chat,
+                config,
+                corrective_prompt(context, &outcome.content, constraints, &violations),
+            )
+            .await;
+            match retry.error {
+                None if !retry.content.is_empty() => Some(retry),
+                _ => {
+                    action = exhausted(constraints);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        outcome.enforcements.push(ConstraintEnforcement {
+            attempt,
+            violations,
+            action,
+        });
+        let Some(regenerated) = regenerated else {
+            break;
+        };
+        outcome.content = regenerated.content;
+        outcome.finish_reason = regenerated.finish_reason.unwrap_or(FinishReason::Stop);
+    }
+    outcome
+}
+
+/// What becomes of a violating answer once no more regenerations are made
+fn exhausted(constraints: &OutputConstraints) -> EnforcementAction {
+    match constraints.on_exhausted {
+        ExhaustedAction::Fail => EnforcementAction::Failed,
+        ExhaustedAction::Deliver => EnforcementAction::Delivered,
+    }
+}
+
+/// `context` followed by the rejected answer and what to fix in it
+fn corrective_prompt(
+    context: &[ContextMessage],
+    answer: &str,
+    constraints: &OutputConstraints,
+    violations: &[ConstraintViolation],
+) -> Vec<ContextMessage> {
+    let instructions: Vec<String> = constraints
+        .constraints
+        .iter()
+        .filter(|c| violations.iter().any(|v| v.constraint == c.name()))
+        .map(|c| format!("- {}", c.instruction()))
+        .collect();
+    let mut prompt = context.to_vec();
+    prompt.push(ContextMessage::assistant(answer));
+    prompt.push(ContextMessage::user(format!(
+        "Your answer broke these rules: {}.\nAnswer again, following them:\n{}\nReply with \
+         the corrected answer only.",
+        describe(violations),
+        instructions.join("\n")
+    )));
+    prompt
+}
+
+/// Violations on one line, for prompts and errors
+fn describe(violations: &[ConstraintViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| format!("{} ({})", v.constraint, v.detail))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::MockChatAdapter;
+    use crate::value_objects::OutputConstraint;
+
+    #[tokio::test]
+    async fn test_violating_answer_is_regenerated_until_retries_run_out() {
+        let chat = MockChatAdapter::new();
+        let context = vec![ContextMessage::user("Give me the status as JSON")];
+        let constraints =
+            OutputConstraints::new(vec![OutputConstraint::JsonOnly]).with_max_retries(2);
+
+        let outcome = enforce_constraints(
+            &chat,
+            &ModelConfig::mock(),
+            &context,
+            "Everything is fine.".to_string(),
+            FinishReason::Stop,
+            &constraints,
+        )
+        .await;
+
+        let actions: Vec<_> = outcome.enforcements.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            [
+                EnforcementAction::Regenerated,
+                EnforcementAction::Regenerated,
+                EnforcementAction::Failed
+            ]
+        );
+        assert_eq!(
+            outcome.enforcements[0].violations[0].constraint,
+            "json_only"
+        );
+        assert!(outcome.failed());
+    }
+
+    #[tokio::test]
+    async fn test_conforming_answer_passes_untouched() {
+        let constraints =
+            OutputConstraints::new(vec![OutputConstraint::MaxLength { max_chars: 10 }]);
+        let outcome = enforce_constraints(
+            &MockChatAdapter::new(),
+            &ModelConfig::mock(),
+            &[ContextMessage::user("Hi")],
+            "Hello!".to_string(),
+            FinishReason::Stop,
+            &constraints,
+        )
+        .await;
+        assert!(outcome.enforcements.is_empty());
+        assert_eq!(outcome.content, "Hello!");
+    }
+}
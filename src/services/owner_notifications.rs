@@ -0,0 +1,371 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Owner Notifications
+//!
+//! Turns agent events their owners care about into notification commands
+//! for the person domain:
+//!
+//! ```text
+//! AgentEvent ──> owner's NotificationPreferences ──> NotifyPerson
+//!                  (event types that alert)             │
+//!                                                       v
+//!                          {domain}.commands.person.{person_id}.notify
+//! ```
+//!
+//! Preferences name event types as in [`AgentEvent::event_type_name`].
+//! Owners without preferences of their own get the notifier's defaults.
+//!
+//! Given an approval subject, permission escalation notifications carry a
+//! [`NotificationAction`]: the `ApproveEscalation` command the owner's client
+//! sends for one-click approval.
+
+use crate::commands::{AgentCommand, ApproveEscalation};
+use crate::events::{AgentEvent, GuardrailAction};
+use crate::value_objects::{next_id, AgentId, PersonId, PrincipalClass};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+use uuid::Uuid;
+
+/// Event types that alert owners unless they choose otherwise
+pub const DEFAULT_ALERT_EVENTS: [&str; 6] = [
+    "suspended",
+    "decommissioned",
+    "slo_violated",
+    "permission_escalation_requested",
+    "change_rate_exceeded",
+    "spend_threshold_crossed",
+];
+
+/// How urgently an owner should look
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Which agent events alert an owner
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    /// Event type names that trigger a notification
+    pub events: BTreeSet<String>,
+}
+
+impl NotificationPreferences {
+    /// Alert on nothing
+    pub fn muted() -> Self {
+        Self {
+            events: BTreeSet::new(),
+        }
+    }
+
+    /// Builder: also alert on `event_type`
+    pub fn with_event(mut self, event_type: impl Into<String>) -> Self {
+        self.events.insert(event_type.into());
+        self
+    }
+
+    /// Builder: stop alerting on `event_type`
+    pub fn without_event(mut self, event_type: &str) -> Self {
+        self.events.remove(event_type);
+        self
+    }
+
+    /// Whether `event` should alert
+    pub fn wants(&self, event: &AgentEvent) -> bool {
+        self.events.contains(event.event_type_name())
+    }
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            events: DEFAULT_ALERT_EVENTS.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+/// A command the owner can send straight from a notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationAction {
+    /// Button text
+    pub label: String,
+
+    /// Subject to send `payload` to
+    pub subject: String,
+
+    /// Principal class it needs; the sender signs it with a key registered as this class
+    pub principal: PrincipalClass,
+
+    /// The serialized agent command
+    pub payload: serde_json::Value,
+}
+
+/// Person-domain command asking for an owner to be notified
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotifyPerson {
+    /// Idempotency key
+    pub notification_id: Uuid,
+
+    /// The owner to notify
+    pub person_id: PersonId,
+
+    /// The agent the notification is about
+    pub agent_id: AgentId,
+
+    /// Agent name, for display
+    pub agent_name: String,
+
+    /// Event type that triggered the notification
+    pub event_type: String,
+
+    /// How urgent it is
+    pub severity: NotificationSeverity,
+
+    /// One-line summary
+    pub title: String,
+
+    /// Details
+    pub body: String,
+
+    /// When the triggering event happened
+    pub occurred_at: DateTime<Utc>,
+
+    /// One-click response, if the event asks for one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<NotificationAction>,
+}
+
+/// Maps agent events to owner notifications according to preferences
+#[derive(Debug, Default)]
+pub struct OwnerNotifier {
+    defaults: NotificationPreferences,
+    owners: RwLock<HashMap<PersonId, NotificationPreferences>>,
+    approval_subject: Option<String>,
+}
+
+impl OwnerNotifier {
+    /// Create a notifier using [`NotificationPreferences::default`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: preferences for owners who have not set their own
+    pub fn with_defaults(mut self, defaults: NotificationPreferences) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Builder: offer one-click approval of escalations via `subject`
+    pub fn with_approval_subject(mut self, subject: impl Into<String>) -> Self {
+        self.approval_subject = Some(subject.into());
+        self
+    }
+
+    /// Set an owner's preferences
+    pub fn set_preferences(&self, owner: PersonId, preferences: NotificationPreferences) {
+        let mut owners = self.owners.write().unwrap_or_else(|e| e.into_inner());
+        owners.insert(owner, preferences);
+    }
+
+    /// Preferences in effect for an owner
+    pub fn preferences(&self, owner: PersonId) -> NotificationPreferences {
+        let owners = self.owners.read().unwrap_or_else(|e| e.into_inner());
+        owners.get(&owner).unwrap_or(&self.defaults).clone()
+    }
+
+    /// The notification `owner` should receive for `event`, if any
+    pub fn notification(
+        &self,
+        owner: PersonId,
+        agent_name: &str,
+        event: &AgentEvent,
+    ) -> Option<NotifyPerson> {
+        if !self.preferences(owner).wants(event) {
+            return None;
+        }
+        let (severity, title, body) = describe(agent_name, event);
+        Some(NotifyPerson {
+            notification_id: next_id(),
+            person_id: owner,
+            agent_id: event.agent_id(),
+            agent_name: agent_name.to_string(),
+            event_type: event.event_type_name().to_string(),
+            severity,
+            title,
+            body,
+            occurred_at: event.timestamp(),
+            action: self.action(owner, event),
+        })
+    }
+
+    /// The command that answers `event` for `owner`, if any
+    fn action(&self, owner: PersonId, event: &AgentEvent) -> Option<NotificationAction> {
+        let AgentEvent::PermissionEscalationRequested(e) = event else {
+            return None;
+        };
+        let approve = AgentCommand::ApproveEscalation(ApproveEscalation::new(
+            e.agent_id,
+            e.escalation_id,
+            owner,
+        ));
+        Some(NotificationAction {
+            label: format!("Allow {} to {}", e.principal, e.permission),
+            subject: self.approval_subject.clone()?,
+            principal: PrincipalClass::Owner,
+            payload: serde_json::to_value(approve).ok()?,
+        })
+    }
+}
+
+/// Severity, title and body for an event
+fn describe(agent_name: &str, event: &AgentEvent) -> (NotificationSeverity, String, String) {
+    match event {
+        AgentEvent::AgentSuspended(e) => (
+            NotificationSeverity::Warning,
+            format!("Agent {} was suspended", agent_name),
+            e.reason.clone(),
+        ),
+        AgentEvent::AgentDecommissioned(_) => (
+            NotificationSeverity::Info,
+            format!("Agent {} was decommissioned", agent_name),
+            "The agent no longer accepts messages.".to_string(),
+        ),
+        AgentEvent::SloViolated(e) => (
+            NotificationSeverity::Warning,
+            format!("Agent {} is responding slowly", agent_name),
+            format!(
+                "p95 first-token latency on {} is {} ms against a {} ms objective \
+                 (burn rate {:.1}).",
+                e.provider, e.observed_p95_ms, e.threshold_ms, e.burn_rate
+            ),
+        ),
+        AgentEvent::PermissionEscalationRequested(e) => (
+            NotificationSeverity::Warning,
+            format!("Agent {} needs your approval", agent_name),
+            format!(
+                "The {} asked to {} without permission. Approve by {} to let it \
+                 go ahead.",
+                e.principal,
+                e.permission,
+                e.expires_at.format("%Y-%m-%d %H:%M UTC")
+            ),
+        ),
+        AgentEvent::AnomalyDetected(e) => (
+            NotificationSeverity::Warning,
+            format!("Agent {} is behaving unusually", agent_name),
+            format!(
+                "Its {} over the last {} responses was {:.2}, against a usual {:.2}.",
+                e.metric, e.window.samples, e.value, e.baseline
+            ),
+        ),
+        AgentEvent::ChangeRateExceeded(e) => {
+            let (severity, outcome) = match e.action {
+                GuardrailAction::Block => (NotificationSeverity::Critical, "was refused"),
+                GuardrailAction::RequireApproval => {
+                    (NotificationSeverity::Warning, "is waiting for your approval")
+                }
+            };
+            let by = match &e.actor {
+                Some(actor) => format!("{} ({})", actor, e.principal),
+                None => e.principal.to_string(),
+            };
+            (
+                severity,
+                format!("Unusual {} changes to agent {}", e.limit.kind, agent_name),
+                format!(
+                    "{} made {} {} changes within {}s, over the limit of {}. The \
+                     latest, to {}, {}.",
+                    by,
+                    e.changes,
+                    e.limit.kind,
+                    e.limit.window_secs,
+                    e.limit.max_changes,
+                    e.permission,
+                    outcome
+                ),
+            )
+        }
+        AgentEvent::SpendThresholdCrossed(e) => (
+            NotificationSeverity::Warning,
+            format!("Agent {} passed its daily spend alert", agent_name),
+            format!(
+                "Estimated spend on {} is ${:.2}, over the ${:.2} alert threshold.",
+                e.day, e.spent_usd, e.threshold_usd
+            ),
+        ),
+        AgentEvent::ResponseFailed(e) => (
+            NotificationSeverity::Warning,
+            format!("Agent {} failed to respond", agent_name),
+            e.error_message.clone(),
+        ),
+        other => (
+            NotificationSeverity::Info,
+            format!("Agent {}: {}", agent_name, other.event_type_name().replace('_', " ")),
+            String::new(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentActivatedEvent, AgentSuspendedEvent, PermissionEscalationRequestedEvent,
+    };
+    use crate::value_objects::{AgentPermission, PermissionDenied};
+
+    #[test]
+    fn test_alerts_follow_owner_preferences() {
+        let notifier = OwnerNotifier::new();
+        let (alice, bob) = (PersonId::new(), PersonId::new());
+        notifier.set_preferences(bob, NotificationPreferences::muted().with_event("activated"));
+
+        let agent_id = AgentId::new();
+        let suspended =
+            AgentEvent::AgentSuspended(AgentSuspendedEvent::new(agent_id, "over quota"));
+        let activated = AgentEvent::AgentActivated(AgentActivatedEvent::new(agent_id));
+
+        let alert = notifier.notification(alice, "sage", &suspended).unwrap();
+        assert_eq!(alert.person_id, alice);
+        assert_eq!(alert.severity, NotificationSeverity::Warning);
+        assert_eq!(alert.body, "over quota");
+        assert!(notifier.notification(alice, "sage", &activated).is_none());
+
+        assert!(notifier.notification(bob, "sage", &suspended).is_none());
+        assert!(notifier.notification(bob, "sage", &activated).is_some());
+    }
+
+    #[test]
+    fn test_escalations_offer_one_click_approval() {
+        let owner = PersonId::new();
+        let agent_id = AgentId::new();
+        let denied = PermissionDenied {
+            class: PrincipalClass::Agent,
+            permission: AgentPermission::Suspend,
+        };
+        let escalation_id = next_id();
+        let requested = AgentEvent::PermissionEscalationRequested(
+            PermissionEscalationRequestedEvent::new(agent_id, escalation_id, denied, Utc::now()),
+        );
+
+        let plain = OwnerNotifier::new().notification(owner, "sage", &requested).unwrap();
+        assert!(plain.action.is_none());
+
+        let notifier = OwnerNotifier::new().with_approval_subject("cim.services.agent.commands");
+        let action = notifier
+            .notification(owner, "sage", &requested)
+            .and_then(|n| n.action)
+            .unwrap();
+        assert_eq!(action.principal, PrincipalClass::Owner);
+        let command: AgentCommand = serde_json::from_value(action.payload).unwrap();
+        assert!(matches!(
+            command,
+            AgentCommand::ApproveEscalation(cmd)
+                if cmd.escalation_id == escalation_id && cmd.approved_by == owner
+        ));
+    }
+}
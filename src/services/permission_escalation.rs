@@ -0,0 +1,435 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Permission Escalation
+//!
+//! A command whose principal lacks the permission is held rather than
+//! refused outright. The owner is asked to approve; approval grants the
+//! permission to that principal class on that agent for a while and resumes
+//! the held command:
+//!
+//! ```text
+//! SuspendAgent (agent) ──> check ──> PermissionDenied
+//!                                        │ escalate()
+//!                                        v
+//!                     PermissionEscalationRequested ──> owner notification
+//!                                        │
+//!     ApproveEscalation (owner) ─────────┤ approve() within the timeout
+//!                                        v
+//!      PermissionEscalationApproved + temporary grant ──> SuspendAgent resumed
+//!
+//!     no approval by expires_at ──> expire() ──> PermissionEscalationExpired
+//! ```
+//!
+//! Approvals are never escalated themselves.
+//!
+//! Every instance [`observe`](PermissionEscalations::observe)s the
+//! escalation events, which carry the held command, so an approval may land
+//! on any instance of a queue group and every instance honours the grant.
+//! Only the instance that raised an escalation reports its expiry. An
+//! instance started mid-escalation knows only those raised after it started.
+
+use crate::commands::{AgentCommand, ApproveEscalation};
+use crate::events::{
+    AgentEvent, PermissionEscalationApprovedEvent, PermissionEscalationExpiredEvent,
+    PermissionEscalationRequestedEvent,
+};
+use crate::infrastructure::EventEnvelope;
+use crate::value_objects::{
+    clock_now, next_id, AgentId, AgentPermission, PermissionDenied, PrincipalClass,
+    PrincipalPermissions,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// How long the owner has to approve, by default
+pub const DEFAULT_ESCALATION_TIMEOUT_SECS: i64 = 300;
+
+/// How long an approved permission lasts, by default
+pub const DEFAULT_ESCALATION_GRANT_SECS: i64 = 900;
+
+/// Errors approving an escalation
+#[derive(Debug, Error)]
+pub enum EscalationError {
+    #[error("No pending escalation {0}")]
+    Unknown(Uuid),
+
+    #[error("Escalation {0} expired before it was approved")]
+    Expired(Uuid),
+
+    #[error("Escalation {escalation_id} is for agent {agent_id}")]
+    WrongAgent {
+        escalation_id: Uuid,
+        agent_id: AgentId,
+    },
+}
+
+/// An approved escalation whose command can now run
+#[derive(Debug, Clone)]
+pub struct ResumedCommand {
+    /// The held command
+    pub command: AgentCommand,
+
+    /// Class it was issued by
+    pub principal: PrincipalClass,
+
+    /// The approval to record
+    pub approved: PermissionEscalationApprovedEvent,
+}
+
+#[derive(Debug, Clone)]
+struct PendingEscalation {
+    command: AgentCommand,
+    denied: PermissionDenied,
+    expires_at: DateTime<Utc>,
+    raised_here: bool,
+}
+
+#[derive(Debug, Default)]
+struct EscalationState {
+    pending: HashMap<Uuid, PendingEscalation>,
+    grants: HashMap<(AgentId, PrincipalClass, AgentPermission), DateTime<Utc>>,
+}
+
+/// Held commands awaiting approval, and the temporary grants approvals made
+#[derive(Debug)]
+pub struct PermissionEscalations {
+    timeout: Duration,
+    grant_for: Duration,
+    state: RwLock<EscalationState>,
+}
+
+impl PermissionEscalations {
+    /// Hold commands for `timeout`; approved permissions last `grant_for`
+    pub fn new(timeout: Duration, grant_for: Duration) -> Self {
+        Self {
+            timeout,
+            grant_for,
+            state: RwLock::new(EscalationState::default()),
+        }
+    }
+
+    /// How long the owner has to approve
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Allow `permission` for `class` on an agent, by role or by a live grant
+    pub fn check(
+        &self,
+        permissions: &PrincipalPermissions,
+        agent_id: AgentId,
+        class: PrincipalClass,
+        permission: AgentPermission,
+    ) -> Result<(), PermissionDenied> {
+        permissions.check(class, permission).or_else(|denied| {
+            let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+            match state.grants.get(&(agent_id, class, permission)) {
+                Some(until) if *until > clock_now() => Ok(()),
+                _ => Err(denied),
+            }
+        })
+    }
+
+    /// The permission escalation `escalation_id` awaits approval for
+    pub fn pending_denial(&self, escalation_id: Uuid) -> Option<PermissionDenied> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.pending.get(&escalation_id).map(|pending| pending.denied)
+    }
+
+    /// Hold `command` until the owner approves the permission it lacked
+    ///
+    /// Returns `None` for commands that are refused outright (approvals).
+    pub fn escalate(
+        &self,
+        command: AgentCommand,
+        denied: PermissionDenied,
+    ) -> Option<PermissionEscalationRequestedEvent> {
+        if matches!(command, AgentCommand::ApproveEscalation(_)) {
+            return None;
+        }
+        let escalation_id = next_id();
+        let expires_at = clock_now() + self.timeout;
+        let event = PermissionEscalationRequestedEvent::new(
+            command.agent_id(),
+            escalation_id,
+            denied,
+            expires_at,
+        )
+        .with_command(command.clone());
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.pending.insert(
+            escalation_id,
+            PendingEscalation {
+                command,
+                denied,
+                expires_at,
+                raised_here: true,
+            },
+        );
+        Some(event)
+    }
+
+    /// Record a stored event; escalations raised and settled elsewhere matter
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        match &envelope.event {
+            AgentEvent::PermissionEscalationRequested(e) => {
+                let Some(command) = &e.command else {
+                    return;
+                };
+                state
+                    .pending
+                    .entry(e.escalation_id)
+                    .or_insert_with(|| PendingEscalation {
+                        command: (**command).clone(),
+                        denied: PermissionDenied {
+                            class: e.principal,
+                            permission: e.permission,
+                        },
+                        expires_at: e.expires_at,
+                        raised_here: false,
+                    });
+            }
+            AgentEvent::PermissionEscalationApproved(e) => {
+                state.pending.remove(&e.escalation_id);
+                let until = state
+                    .grants
+                    .entry((e.agent_id, e.principal, e.permission))
+                    .or_insert(e.grant_expires_at);
+                *until = (*until).max(e.grant_expires_at);
+            }
+            AgentEvent::PermissionEscalationExpired(e) => {
+                state.pending.remove(&e.escalation_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// The escalation `approval` approves, if it may still be approved
+    fn approvable<'a>(
+        state: &'a EscalationState,
+        approval: &ApproveEscalation,
+        now: DateTime<Utc>,
+    ) -> Result<&'a PendingEscalation, EscalationError> {
+        let pending = state
+            .pending
+            .get(&approval.escalation_id)
+            .ok_or(EscalationError::Unknown(approval.escalation_id))?;
+        let agent_id = pending.command.agent_id();
+        if agent_id != approval.agent_id {
+            return Err(EscalationError::WrongAgent {
+                escalation_id: approval.escalation_id,
+                agent_id,
+            });
+        }
+        if pending.expires_at <= now {
+            return Err(EscalationError::Expired(approval.escalation_id));
+        }
+        Ok(pending)
+    }
+
+    /// The approval event `approve` would record, changing nothing
+    pub fn preview(
+        &self,
+        approval: &ApproveEscalation,
+    ) -> Result<PermissionEscalationApprovedEvent, EscalationError> {
+        let now = clock_now();
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let pending = Self::approvable(&state, approval, now)?;
+        Ok(PermissionEscalationApprovedEvent::new(
+            approval.agent_id,
+            approval.escalation_id,
+            approval.approved_by,
+            pending.denied,
+            now + self.grant_for,
+        ))
+    }
+
+    /// Approve an escalation: grant its permission and release its command
+    pub fn approve(&self, approval: &ApproveEscalation) -> Result<ResumedCommand, EscalationError> {
+        let now = clock_now();
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        let agent_id = Self::approvable(&state, approval, now)?.command.agent_id();
+        let Some(pending) = state.pending.remove(&approval.escalation_id) else {
+            return Err(EscalationError::Unknown(approval.escalation_id));
+        };
+
+        let grant_expires_at = now + self.grant_for;
+        let denied = pending.denied;
+        state.grants.insert(
+            (agent_id, denied.class, denied.permission),
+            grant_expires_at,
+        );
+        Ok(ResumedCommand {
+            approved: PermissionEscalationApprovedEvent::new(
+                agent_id,
+                approval.escalation_id,
+                approval.approved_by,
+                denied,
+                grant_expires_at,
+            ),
+            command: pending.command,
+            principal: denied.class,
+        })
+    }
+
+    /// Drop escalations past their timeout, and grants past their lapse
+    ///
+    /// Returns expiries of the escalations this instance raised; others are
+    /// reported by the instance that raised them.
+    pub fn expire(&self) -> Vec<PermissionEscalationExpiredEvent> {
+        let now = clock_now();
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.grants.retain(|_, until| *until > now);
+        let expired: Vec<Uuid> = state
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.expires_at <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| {
+                let pending = state.pending.remove(&id).filter(|p| p.raised_here)?;
+                Some(PermissionEscalationExpiredEvent::new(
+                    pending.command.agent_id(),
+                    id,
+                    pending.denied,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Default for PermissionEscalations {
+    fn default() -> Self {
+        Self::new(
+            Duration::seconds(DEFAULT_ESCALATION_TIMEOUT_SECS),
+            Duration::seconds(DEFAULT_ESCALATION_GRANT_SECS),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::SuspendAgent;
+    use crate::test_support::envelope;
+    use crate::value_objects::{with_clock_sync, FixedClock, PersonId};
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn at(minute: u32) -> Arc<FixedClock> {
+        Arc::new(FixedClock::new(
+            Utc.with_ymd_and_hms(2025, 6, 1, 12, minute, 0).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_approval_grants_and_resumes() {
+        let escalations = PermissionEscalations::default();
+        let permissions = PrincipalPermissions::default();
+        let (agent_id, owner) = (AgentId::new(), PersonId::new());
+        let suspend = AgentCommand::SuspendAgent(SuspendAgent::new(agent_id, "runaway loop"));
+
+        let denied = escalations
+            .check(
+                &permissions,
+                agent_id,
+                PrincipalClass::Agent,
+                AgentPermission::Suspend,
+            )
+            .unwrap_err();
+        let requested = with_clock_sync(at(0), || escalations.escalate(suspend, denied)).unwrap();
+        assert_eq!(requested.permission, AgentPermission::Suspend);
+
+        let approval = ApproveEscalation::new(agent_id, requested.escalation_id, owner);
+        let previewed = with_clock_sync(at(3), || escalations.preview(&approval)).unwrap();
+        let resumed = with_clock_sync(at(3), || escalations.approve(&approval)).unwrap();
+        assert_eq!(previewed.grant_expires_at, resumed.approved.grant_expires_at);
+        assert!(matches!(resumed.command, AgentCommand::SuspendAgent(_)));
+        assert_eq!(resumed.principal, PrincipalClass::Agent);
+        assert_eq!(resumed.approved.approved_by, owner);
+
+        let granted = |minute| {
+            with_clock_sync(at(minute), || {
+                escalations.check(
+                    &permissions,
+                    agent_id,
+                    PrincipalClass::Agent,
+                    AgentPermission::Suspend,
+                )
+            })
+        };
+        assert!(granted(10).is_ok());
+        assert!(granted(30).is_err());
+        assert!(matches!(
+            escalations.approve(&approval),
+            Err(EscalationError::Unknown(_))
+        ));
+    }
+
+    #[test]
+    fn test_unapproved_escalations_expire() {
+        let escalations = PermissionEscalations::default();
+        let agent_id = AgentId::new();
+        let denied = PermissionDenied {
+            class: PrincipalClass::Operator,
+            permission: AgentPermission::Decommission,
+        };
+        let command = AgentCommand::SuspendAgent(SuspendAgent::new(agent_id, "cleanup"));
+        let requested = with_clock_sync(at(0), || escalations.escalate(command, denied)).unwrap();
+
+        let approval = ApproveEscalation::new(agent_id, requested.escalation_id, PersonId::new());
+        assert!(matches!(
+            with_clock_sync(at(6), || escalations.approve(&approval)),
+            Err(EscalationError::Expired(_))
+        ));
+        assert!(with_clock_sync(at(4), || escalations.expire()).is_empty());
+        let expired = with_clock_sync(at(6), || escalations.expire());
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].escalation_id, requested.escalation_id);
+    }
+
+    #[test]
+    fn test_escalation_raised_elsewhere_is_approved_here() {
+        let (raising, approving) = (
+            PermissionEscalations::default(),
+            PermissionEscalations::default(),
+        );
+        let agent_id = AgentId::new();
+        let denied = PermissionDenied {
+            class: PrincipalClass::Agent,
+            permission: AgentPermission::Suspend,
+        };
+        let command = AgentCommand::SuspendAgent(SuspendAgent::new(agent_id, "runaway loop"));
+        let requested = with_clock_sync(at(0), || raising.escalate(command, denied)).unwrap();
+        let escalation_id = requested.escalation_id;
+        let stored = |event| EventEnvelope {
+            correlation_id: escalation_id,
+            causation_id: escalation_id,
+            ..envelope(1, event)
+        };
+        approving.observe(&stored(AgentEvent::PermissionEscalationRequested(requested)));
+
+        let approval = ApproveEscalation::new(agent_id, escalation_id, PersonId::new());
+        let resumed = with_clock_sync(at(1), || approving.approve(&approval)).unwrap();
+        assert!(matches!(resumed.command, AgentCommand::SuspendAgent(_)));
+        raising.observe(&stored(AgentEvent::PermissionEscalationApproved(
+            resumed.approved,
+        )));
+        let granted = with_clock_sync(at(2), || {
+            raising.check(
+                &PrincipalPermissions::default(),
+                agent_id,
+                PrincipalClass::Agent,
+                AgentPermission::Suspend,
+            )
+        });
+        assert!(granted.is_ok());
+        assert!(with_clock_sync(at(6), || raising.expire()).is_empty());
+    }
+}
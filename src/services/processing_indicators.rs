@@ -0,0 +1,301 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Processing Indicators
+//!
+//! Live "agent is typing" signals for conversations. They are not domain
+//! events: nothing is stored, they are published to the conversation's
+//! status subject for whoever is watching right now.
+//!
+//! ```text
+//! SendMessage { conversation_id } ──> ProcessingIndicators::start ──> AgentProcessingStarted
+//!                                          │ track(adapter stream)
+//!                                          v
+//!                       chunks ──> AgentProcessingProgress { tokens_generated } (every ~1s)
+//!                  final / error / drop ──> AgentProcessingEnded
+//!                                          │
+//!                                          v
+//!                           {domain}.conversations.{conv_id}.status
+//! ```
+//!
+//! The wrapper works on any [`ChatStream`], so every adapter reports the same
+//! way without knowing about indicators. `AgentProcessingEnded` is sent
+//! exactly once, even when routing fails before a stream exists or the
+//! stream is dropped part-way.
+
+use crate::ports::{estimate_tokens, ChatStream};
+use crate::value_objects::{clock_now, AgentId, ConversationId, FinishReason, MessageId};
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Default time between progress indicators
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Live processing signal for a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ProcessingIndicator {
+    /// The agent began working on a message
+    AgentProcessingStarted {
+        agent_id: AgentId,
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        started_at: DateTime<Utc>,
+    },
+    /// The agent is still generating
+    AgentProcessingProgress {
+        agent_id: AgentId,
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        /// Estimated tokens generated so far
+        tokens_generated: u32,
+        elapsed_ms: u64,
+    },
+    /// The agent stopped working on a message
+    AgentProcessingEnded {
+        agent_id: AgentId,
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        /// Estimated tokens generated in total
+        tokens_generated: u32,
+        elapsed_ms: u64,
+        /// Why generation finished, when the provider said
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<FinishReason>,
+        /// What went wrong, if processing failed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        ended_at: DateTime<Utc>,
+    },
+}
+
+impl ProcessingIndicator {
+    /// The conversation the indicator is published to
+    pub fn conversation_id(&self) -> ConversationId {
+        match self {
+            ProcessingIndicator::AgentProcessingStarted {
+                conversation_id, ..
+            }
+            | ProcessingIndicator::AgentProcessingProgress {
+                conversation_id, ..
+            }
+            | ProcessingIndicator::AgentProcessingEnded {
+                conversation_id, ..
+            } => *conversation_id,
+        }
+    }
+}
+
+/// Where indicators go
+///
+/// Emitting must not block the response stream; sinks hand indicators off
+/// to be published elsewhere.
+pub trait IndicatorSink: Send + Sync {
+    /// Hand off one indicator
+    fn emit(&self, indicator: ProcessingIndicator);
+}
+
+impl IndicatorSink for mpsc::UnboundedSender<ProcessingIndicator> {
+    fn emit(&self, indicator: ProcessingIndicator) {
+        // Nobody publishing means nobody watching
+        let _ = self.send(indicator);
+    }
+}
+
+/// Indicators for one message being processed
+pub struct ProcessingIndicators {
+    agent_id: AgentId,
+    conversation_id: ConversationId,
+    message_id: MessageId,
+    sink: Arc<dyn IndicatorSink>,
+    progress_every: Duration,
+    started: Instant,
+    last_progress: Instant,
+    tokens_generated: u32,
+    ended: bool,
+}
+
+impl ProcessingIndicators {
+    /// Announce that the agent started on `message_id`
+    pub fn start(
+        agent_id: AgentId,
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        sink: Arc<dyn IndicatorSink>,
+    ) -> Self {
+        sink.emit(ProcessingIndicator::AgentProcessingStarted {
+            agent_id,
+            conversation_id,
+            message_id,
+            started_at: clock_now(),
+        });
+        let now = Instant::now();
+        Self {
+            agent_id,
+            conversation_id,
+            message_id,
+            sink,
+            progress_every: DEFAULT_PROGRESS_INTERVAL,
+            started: now,
+            last_progress: now,
+            tokens_generated: 0,
+            ended: false,
+        }
+    }
+
+    /// Builder: time between progress indicators
+    pub fn with_progress_every(mut self, interval: Duration) -> Self {
+        self.progress_every = interval;
+        self
+    }
+
+    /// Report progress while `inner` streams, and the end when it finishes
+    ///
+    /// Progress is sent as chunks arrive, at most once per interval.
+    pub fn track(self, inner: ChatStream) -> ChatStream {
+        Box::pin(stream::unfold(
+            (inner, self),
+            |(mut inner, mut indicators)| async move {
+                let item = inner.next().await;
+                match &item {
+                    Some(Ok(chunk)) => {
+                        indicators.tokens_generated += estimate_tokens(&chunk.content);
+                        if chunk.is_final {
+                            indicators.end(chunk.finish_reason, None);
+                        } else if indicators.last_progress.elapsed() >= indicators.progress_every {
+                            indicators.progress();
+                        }
+                    }
+                    Some(Err(e)) => indicators.end(None, Some(e.to_string())),
+                    None => indicators.end(None, None),
+                }
+                item.map(|item| (item, (inner, indicators)))
+            },
+        ))
+    }
+
+    /// Announce that processing failed before a response streamed
+    pub fn fail(mut self, error: impl Into<String>) {
+        self.end(None, Some(error.into()));
+    }
+
+    fn progress(&mut self) {
+        self.last_progress = Instant::now();
+        self.sink
+            .emit(ProcessingIndicator::AgentProcessingProgress {
+                agent_id: self.agent_id,
+                conversation_id: self.conversation_id,
+                message_id: self.message_id,
+                tokens_generated: self.tokens_generated,
+                elapsed_ms: self.started.elapsed().as_millis() as u64,
+            });
+    }
+
+    fn end(&mut self, finish_reason: Option<FinishReason>, error: Option<String>) {
+        if self.ended {
+            return;
+        }
+        self.ended = true;
+        self.sink.emit(ProcessingIndicator::AgentProcessingEnded {
+            agent_id: self.agent_id,
+            conversation_id: self.conversation_id,
+            message_id: self.message_id,
+            tokens_generated: self.tokens_generated,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            finish_reason,
+            error,
+            ended_at: clock_now(),
+        });
+    }
+}
+
+impl Drop for ProcessingIndicators {
+    fn drop(&mut self) {
+        self.end(None, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::ChatError;
+    use crate::value_objects::StreamingChunk;
+
+    fn indicators(sink: &Arc<mpsc::UnboundedSender<ProcessingIndicator>>) -> ProcessingIndicators {
+        ProcessingIndicators::start(
+            AgentId::new(),
+            ConversationId::new(),
+            MessageId::new(),
+            sink.clone(),
+        )
+    }
+
+    fn drain(rx: &mut mpsc::UnboundedReceiver<ProcessingIndicator>) -> Vec<ProcessingIndicator> {
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_started_progress_and_ended_once() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let sink = Arc::new(tx);
+        let chunks: ChatStream = Box::pin(stream::iter(vec![
+            Ok(StreamingChunk::new(0, "Hello there")),
+            Ok(StreamingChunk::new(1, ", friend")),
+            Ok(StreamingChunk::final_chunk(2, "!", FinishReason::Stop)),
+        ]));
+
+        let tracked = indicators(&sink)
+            .with_progress_every(Duration::ZERO)
+            .track(chunks);
+        assert_eq!(tracked.count().await, 3);
+
+        let seen = drain(&mut rx);
+        assert!(matches!(
+            seen[0],
+            ProcessingIndicator::AgentProcessingStarted { .. }
+        ));
+        assert!(matches!(
+            seen[1],
+            ProcessingIndicator::AgentProcessingProgress {
+                tokens_generated: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            seen[2],
+            ProcessingIndicator::AgentProcessingProgress { .. }
+        ));
+        assert!(matches!(
+            &seen[3],
+            ProcessingIndicator::AgentProcessingEnded {
+                finish_reason: Some(FinishReason::Stop),
+                error: None,
+                ..
+            }
+        ));
+        assert_eq!(seen.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_early_failures_end_processing() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let sink = Arc::new(tx);
+        let failing: ChatStream = Box::pin(stream::iter(vec![Err(ChatError::Timeout(30))]));
+        assert_eq!(indicators(&sink).track(failing).count().await, 1);
+        indicators(&sink).fail("no provider");
+
+        let ended: Vec<_> = drain(&mut rx)
+            .into_iter()
+            .filter_map(|i| match i {
+                ProcessingIndicator::AgentProcessingEnded { error, .. } => error,
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ended.len(), 2);
+        assert!(ended[0].contains("30"));
+        assert_eq!(ended[1], "no provider");
+    }
+}
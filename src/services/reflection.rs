@@ -0,0 +1,172 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Reflection loop
+//!
+//! Runs the bounded critique-and-revise loop described by a
+//! [`ReflectionConfig`]. Each round asks the same model to critique the
+//! latest draft against the rubric, then to revise it with that critique:
+//!
+//! ```text
+//! context ──> draft 0 ──> critique ──"APPROVED"──> final = draft 0
+//!                            │
+//!                            v
+//!             context + draft 0 + critique ──> draft 1 ──> ... (max_reflections,
+//!                                                               token_budget)
+//! ```
+//!
+//! Rounds stop early when the critique approves the draft, when a critique
+//! or revision fails (the last good draft stands), or when the estimated
+//! tokens generated so far reach the budget.
+
+use super::best_of_n::sample;
+use crate::ports::{estimate_tokens, ChatError, ChatPort, ChatResult};
+use crate::value_objects::{
+    ContextMessage, FinishReason, ModelConfig, ReflectionConfig, ReflectionDraft,
+};
+
+/// Critique reply meaning the draft needs no revision
+const APPROVED: &str = "APPROVED";
+
+/// Every draft produced by one reflection run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflectionOutcome {
+    /// Drafts in order; the last one is the final answer
+    pub drafts: Vec<ReflectionDraft>,
+
+    /// Why the final draft stopped generating
+    pub finish_reason: FinishReason,
+
+    /// Estimated tokens generated, drafts and critiques included
+    pub tokens_spent: u32,
+}
+
+impl ReflectionOutcome {
+    /// The answer to return
+    pub fn final_answer(&self) -> &str {
+        self.drafts.last().map(|d| d.content.as_str()).unwrap_or_default()
+    }
+
+    /// Drafts the final answer replaced
+    pub fn superseded(&self) -> &[ReflectionDraft] {
+        &self.drafts[..self.drafts.len().saturating_sub(1)]
+    }
+}
+
+/// Answer `context`, then critique and revise within `reflection`'s bounds
+///
+/// Fails only if the first draft fails.
+pub async fn reflect(
+    chat: &dyn ChatPort,
+    config: &ModelConfig,
+    context: &[ContextMessage],
+    reflection: &ReflectionConfig,
+) -> ChatResult<ReflectionOutcome> {
+    let first = sample(chat, config, context.to_vec()).await;
+    if let Some(error) = first.error {
+        return Err(ChatError::ProviderError(error));
+    }
+    let mut spent = estimate_tokens(&first.content);
+    let mut finish_reason = first.finish_reason.unwrap_or(FinishReason::Stop);
+    let mut drafts = vec![ReflectionDraft {
+        round: 0,
+        content: first.content,
+        critique: None,
+    }];
+
+    for round in 1..=reflection.max_reflections {
+        if spent >= reflection.token_budget {
+            break;
+        }
+        let draft = &drafts[drafts.len() - 1].content;
+        let prompt = critique_prompt(context, draft, &reflection.rubric);
+
+        let critique = sample(chat, config, prompt).await;
+        if critique.error.is_some() {
+            break;
+        }
+        spent += estimate_tokens(&critique.content);
+        let critique = critique.content.trim().to_string();
+        let approved = critique.eq_ignore_ascii_case(APPROVED);
+        if let Some(last) = drafts.last_mut() {
+            last.critique = Some(critique.clone());
+        }
+        if approved || spent >= reflection.token_budget {
+            break;
+        }
+
+        let mut revise = context.to_vec();
+        revise.push(ContextMessage::assistant(drafts[drafts.len() - 1].content.clone()));
+        revise.push(ContextMessage::user(format!(
+            "Revise your answer using this critique. Reply with the revised answer only.\n\n{}",
+            critique
+        )));
+        let revised = sample(chat, config, revise).await;
+        if revised.error.is_some() || revised.content.is_empty() {
+            break;
+        }
+        spent += estimate_tokens(&revised.content);
+        finish_reason = revised.finish_reason.unwrap_or(FinishReason::Stop);
+        drafts.push(ReflectionDraft {
+            round,
+            content: revised.content,
+            critique: None,
+        });
+    }
+
+    Ok(ReflectionOutcome {
+        drafts,
+        finish_reason,
+        tokens_spent: spent,
+    })
+}
+
+/// Ask for a critique of `draft` against `rubric`
+fn critique_prompt(context: &[ContextMessage], draft: &str, rubric: &str) -> Vec<ContextMessage> {
+    let question = context
+        .iter()
+        .rev()
+        .find(|m| m.source.is_none())
+        .map(|m| m.content.as_str())
+        .unwrap_or_default();
+    vec![
+        ContextMessage::system(format!(
+            "You review answers against this rubric: {}\nIf the answer fully meets it, reply \
+             with exactly {}. Otherwise list what to fix, briefly.",
+            rubric, APPROVED
+        )),
+        ContextMessage::user(format!("Question:\n{}\n\nAnswer:\n{}", question, draft)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::MockChatAdapter;
+
+    #[tokio::test]
+    async fn test_reflection_stops_at_max_reflections() {
+        let chat = MockChatAdapter::new();
+        let context = vec![ContextMessage::user("What is 2 + 2?")];
+        let reflection = ReflectionConfig::new(10_000).with_max_reflections(2);
+
+        let outcome = reflect(&chat, &ModelConfig::mock(), &context, &reflection)
+            .await
+            .unwrap();
+        assert_eq!(outcome.drafts.len(), 3);
+        assert_eq!(outcome.superseded().len(), 2);
+        assert!(outcome.superseded().iter().all(|d| d.critique.is_some()));
+        assert!(!outcome.final_answer().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_skips_revision() {
+        let chat = MockChatAdapter::new();
+        let context = vec![ContextMessage::user("Hello")];
+
+        let outcome = reflect(&chat, &ModelConfig::mock(), &context, &ReflectionConfig::new(1))
+            .await
+            .unwrap();
+        assert_eq!(outcome.drafts.len(), 1);
+        assert!(outcome.drafts[0].critique.is_none());
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Response Diffing
+//!
+//! Runs the same prompts through a baseline and a candidate model
+//! configuration and reports how the answers differ, so a model upgrade can
+//! be judged before it is rolled out:
+//!
+//! ```text
+//! prompts ──> ResponseDiffer::compare(agent, baseline, candidate)
+//!                │ per prompt
+//!                ├──> ChatPort(baseline) ──┐
+//!                └──> ChatPort(candidate) ─┴──> embed both ──> PromptComparison
+//!                                                                 │
+//!                                                                 v
+//!                                     ComparisonReport ──> ComparisonStore
+//! ```
+//!
+//! Each prompt records the embedding distance between the two responses,
+//! the length difference, and whether only one side stopped to call a tool.
+//! Streams carry no tool call payloads, so tool calling is compared through
+//! the `ToolCalls` finish reason.
+
+use super::eval_suite::cosine_similarity;
+use crate::aggregate::Agent;
+use crate::infrastructure::{
+    ComparisonReport, ComparisonStore, ComparisonSummary, DomainError, PromptComparison,
+    ResponseSample,
+};
+use crate::ports::{ChatError, ChatPort, EmbeddingPort};
+use crate::value_objects::{next_id, ContextMessage, ModelConfig};
+use chrono::Utc;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Instant;
+use thiserror::Error;
+
+/// Errors running or storing a comparison
+#[derive(Debug, Error)]
+pub enum ComparisonError {
+    #[error("Failed to embed responses: {0}")]
+    Embedding(#[from] ChatError),
+
+    #[error("Failed to store comparison: {0}")]
+    Store(#[from] DomainError),
+}
+
+/// Result type for comparisons
+pub type ComparisonResult<T> = Result<T, ComparisonError>;
+
+/// Compares two model configurations on the same prompts
+#[derive(Clone)]
+pub struct ResponseDiffer {
+    chat: Arc<dyn ChatPort>,
+    embeddings: Arc<dyn EmbeddingPort>,
+    store: Option<Arc<dyn ComparisonStore>>,
+}
+
+impl ResponseDiffer {
+    /// Send prompts through `chat` and embed responses with `embeddings`
+    pub fn new(chat: Arc<dyn ChatPort>, embeddings: Arc<dyn EmbeddingPort>) -> Self {
+        Self {
+            chat,
+            embeddings,
+            store: None,
+        }
+    }
+
+    /// Persist every report to `store`
+    pub fn with_store(mut self, store: Arc<dyn ComparisonStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Answer `prompts` with both configurations and diff the responses
+    ///
+    /// Prompts are sent with `agent`'s system prompt. A failed request is
+    /// recorded on its side of the comparison; only embedding or storage
+    /// failures abort the run.
+    pub async fn compare(
+        &self,
+        agent: &Agent,
+        prompts: &[String],
+        baseline: &ModelConfig,
+        candidate: &ModelConfig,
+    ) -> ComparisonResult<ComparisonReport> {
+        let mut comparisons = Vec::with_capacity(prompts.len());
+        for prompt in prompts {
+            let mut context = Vec::new();
+            if let Some(system_prompt) = agent.system_prompt().filter(|p| !p.is_empty()) {
+                context.push(ContextMessage::system(system_prompt));
+            }
+            context.push(ContextMessage::user(prompt));
+
+            let base = self.sample(baseline, context.clone()).await;
+            let cand = self.sample(candidate, context).await;
+            comparisons.push(self.diff(prompt, base, cand).await?);
+        }
+
+        self.record(
+            format!("{}/{}", baseline.provider, baseline.model_name),
+            format!("{}/{}", candidate.provider, candidate.model_name),
+            comparisons,
+        )
+        .await
+    }
+
+    /// Summarize `comparisons` into a report and persist it
+    pub(super) async fn record(
+        &self,
+        baseline: String,
+        candidate: String,
+        comparisons: Vec<PromptComparison>,
+    ) -> ComparisonResult<ComparisonReport> {
+        let report = ComparisonReport {
+            run_id: next_id(),
+            baseline,
+            candidate,
+            summary: ComparisonSummary::from_comparisons(&comparisons),
+            comparisons,
+            created_at: Utc::now(),
+        };
+        if let Some(store) = &self.store {
+            store.save(report.clone()).await?;
+        }
+        Ok(report)
+    }
+
+    /// Collect a full response, keeping the failure if there is one
+    async fn sample(&self, config: &ModelConfig, context: Vec<ContextMessage>) -> ResponseSample {
+        let started = Instant::now();
+        let mut text = String::new();
+        let mut finish_reason = None;
+        let mut error = None;
+        match self.chat.send(config, context).await {
+            Ok(mut stream) => {
+                while let Some(chunk) = stream.next().await {
+                    match chunk {
+                        Ok(chunk) => {
+                            text.push_str(&chunk.content);
+                            finish_reason = chunk.finish_reason.or(finish_reason);
+                        }
+                        Err(e) => {
+                            error = Some(e.to_string());
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => error = Some(e.to_string()),
+        }
+        ResponseSample {
+            chars: text.chars().count(),
+            text,
+            finish_reason,
+            error,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+        }
+    }
+
+    /// Compare two answers to `prompt`
+    pub(super) async fn diff(
+        &self,
+        prompt: &str,
+        baseline: ResponseSample,
+        candidate: ResponseSample,
+    ) -> ComparisonResult<PromptComparison> {
+        let embedding_distance = if baseline.error.is_none() && candidate.error.is_none() {
+            let embedded = self
+                .embeddings
+                .embed(vec![baseline.text.clone(), candidate.text.clone()], None)
+                .await?;
+            match embedded.embeddings.as_slice() {
+                [a, b] => Some(1.0 - cosine_similarity(a, b)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        Ok(PromptComparison {
+            prompt: prompt.to_string(),
+            embedding_distance,
+            length_delta: candidate.chars as i64 - baseline.chars as i64,
+            tool_call_changed: baseline.called_tool() != candidate.called_tool(),
+            baseline,
+            candidate,
+        })
+    }
+}
+
+impl std::fmt::Debug for ResponseDiffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseDiffer")
+            .field("chat", &self.chat.provider_name())
+            .field("embeddings", &self.embeddings.provider_name())
+            .field("store", &self.store.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AgentDeployedEvent, AgentEvent};
+    use crate::infrastructure::InMemoryComparisonStore;
+    use crate::test_support::{ScriptedChat, ScriptedEmbedder};
+    use crate::value_objects::{AgentId, FinishReason, PersonId, StreamingChunk};
+
+    /// Answers tersely on the baseline model and calls a tool on any other
+    fn models() -> impl ChatPort {
+        ScriptedChat::new(|config, _| {
+            Ok(if config.model_name == ModelConfig::mock().model_name {
+                vec![
+                    StreamingChunk::new(0, "yes"),
+                    StreamingChunk::completion(1, FinishReason::Stop),
+                ]
+            } else {
+                vec![
+                    StreamingChunk::new(0, "let me check"),
+                    StreamingChunk::completion(1, FinishReason::ToolCalls),
+                ]
+            })
+        })
+    }
+
+    /// Embeds by response length, so different lengths point apart
+    fn by_length() -> impl EmbeddingPort {
+        ScriptedEmbedder::new(|text| vec![1.0, text.len() as f32 / 10.0])
+    }
+
+    fn agent() -> Agent {
+        let id = AgentId::new();
+        let deployed = AgentDeployedEvent::new(id, PersonId::new(), "support", None);
+        Agent::empty().apply_events(&[AgentEvent::AgentDeployed(deployed)]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_diffs_and_stores_responses() {
+        let store = Arc::new(InMemoryComparisonStore::new());
+        let differ = ResponseDiffer::new(Arc::new(models()), Arc::new(by_length()))
+            .with_store(store.clone());
+        let baseline = ModelConfig::mock();
+        let mut candidate = ModelConfig::mock();
+        candidate.model_name = "candidate".to_string();
+
+        let prompts = vec!["Is it raining?".to_string()];
+        let report = differ.compare(&agent(), &prompts, &baseline, &candidate).await.unwrap();
+
+        let comparison = &report.comparisons[0];
+        assert_eq!(comparison.baseline.text, "yes");
+        assert_eq!(comparison.length_delta, 9);
+        assert!(comparison.tool_call_changed);
+        assert!(comparison.embedding_distance.unwrap() > 0.0);
+        assert_eq!(report.summary.tool_call_differences, 1);
+        assert_eq!(store.list().await.unwrap(), vec![report.run_id]);
+
+        // Identical configurations do not drift
+        let same = differ.compare(&agent(), &prompts, &baseline, &baseline).await.unwrap();
+        assert!(same.summary.max_distance.unwrap().abs() < 1e-6);
+        assert_eq!(same.summary.tool_call_differences, 0);
+    }
+}
@@ -0,0 +1,545 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Shadow Deployments
+//!
+//! Evaluates a new agent definition on live traffic without letting it
+//! answer anyone. The shadow agent runs with the `shadow_mode` feature flag,
+//! receives copies of the primary agent's messages and answers them like
+//! any agent, so its responses carry the usual events and metrics, but
+//! nothing it produces is delivered:
+//!
+//! ```text
+//! start() ──> DeployAgent, ConfigureModel, UpdateConfiguration { shadow_mode }, ActivateAgent
+//!    │
+//!    v
+//! mirror(SendMessage) ──> SendMessage { shadow agent, shadow_of: primary message }
+//!    │
+//!    v
+//! observe() ──> pairs primary and shadow responses by `shadow_of`
+//!    │ both sides finished
+//!    v
+//! evaluate(differ) ──> ResponseDiffer ──> ComparisonReport ──> ComparisonStore
+//! ```
+//!
+//! Mirrored copies carry no conversation, so no processing indicators are
+//! shown, and a shadow agent refuses any message that is not a copy. Each
+//! copy is tagged `shadow = <primary agent>`, so its spend is reported apart
+//! from the primary's. Commands are returned for the caller to dispatch.
+//!
+//! At most `max_exchanges` exchanges are held open, and as many finished
+//! ones held for evaluation; the oldest are dropped beyond that, so a shadow
+//! that never answers cannot grow the buffer without bound.
+
+use super::response_diff::{ComparisonResult, ResponseDiffer};
+use super::{AgentManifest, ManifestError, ManifestResult};
+use crate::aggregate::Agent;
+use crate::commands::{
+    ActivateAgent, AgentCommand, ConfigureModel, DecommissionAgent, DeployAgent, SendMessage,
+    UpdateConfiguration,
+};
+use crate::events::AgentEvent;
+use crate::infrastructure::{ComparisonReport, EventEnvelope, ResponseSample};
+use crate::value_objects::{
+    AgentId, AgentStatus, FeatureFlags, FinishReason, MessageId, ModelConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Cost tag naming the primary agent on a shadow copy
+pub const SHADOW_COST_TAG: &str = "shadow";
+
+/// Exchanges held open, and finished ones held for evaluation, by default
+pub const DEFAULT_MAX_SHADOW_EXCHANGES: usize = 1_000;
+
+/// One side of a mirrored exchange, as observed so far
+#[derive(Debug, Default)]
+struct Side {
+    text: String,
+    finish_reason: Option<FinishReason>,
+    error: Option<String>,
+    latency_ms: Option<u64>,
+    finished: bool,
+}
+
+impl Side {
+    fn sample(self) -> ResponseSample {
+        ResponseSample {
+            chars: self.text.chars().count(),
+            text: self.text,
+            finish_reason: self.finish_reason,
+            error: self.error,
+            latency_ms: self.latency_ms,
+        }
+    }
+}
+
+/// A primary message and its shadow copy
+#[derive(Debug, Default)]
+struct Exchange {
+    prompt: String,
+    primary: Side,
+    shadow: Side,
+}
+
+#[derive(Debug)]
+struct Exchanges {
+    /// Shadow copy's message id -> primary message id
+    copies: HashMap<MessageId, MessageId>,
+    open: HashMap<MessageId, Exchange>,
+    /// Open exchanges, oldest first
+    opened: VecDeque<MessageId>,
+    finished: VecDeque<Exchange>,
+    limit: usize,
+}
+
+impl Exchanges {
+    fn new(limit: usize) -> Self {
+        Self {
+            copies: HashMap::new(),
+            open: HashMap::new(),
+            opened: VecDeque::new(),
+            finished: VecDeque::new(),
+            limit,
+        }
+    }
+
+    /// Open the exchange for primary message `key` and its shadow `copy`
+    fn open(&mut self, key: MessageId, copy: MessageId, prompt: &str) {
+        self.copies.insert(copy, key);
+        if !self.open.contains_key(&key) {
+            self.opened.push_back(key);
+        }
+        self.open.entry(key).or_default().prompt = prompt.to_string();
+        while self.open.len() > self.limit {
+            let Some(oldest) = self.opened.pop_front() else {
+                break;
+            };
+            if self.open.remove(&oldest).is_some() {
+                self.copies.retain(|_, primary| *primary != oldest);
+            }
+        }
+    }
+
+    /// The exchange a message belongs to, and the side it answers
+    fn side(&mut self, primary: bool, message_id: MessageId) -> Option<(MessageId, &mut Side)> {
+        let key = if primary {
+            message_id
+        } else {
+            *self.copies.get(&message_id)?
+        };
+        let exchange = self.open.get_mut(&key)?;
+        let side = if primary {
+            &mut exchange.primary
+        } else {
+            &mut exchange.shadow
+        };
+        Some((key, side))
+    }
+
+    fn finish(&mut self, key: MessageId) {
+        let done = self
+            .open
+            .get(&key)
+            .is_some_and(|e| e.primary.finished && e.shadow.finished);
+        if done {
+            if let Some(exchange) = self.open.remove(&key) {
+                self.copies.retain(|_, primary| *primary != key);
+                self.opened.retain(|opened| *opened != key);
+                self.finished.push_back(exchange);
+                if self.finished.len() > self.limit {
+                    self.finished.pop_front();
+                }
+            }
+        }
+    }
+}
+
+/// A shadow agent evaluated against one primary agent
+#[derive(Debug)]
+pub struct ShadowDeployment {
+    primary_id: AgentId,
+    shadow_id: AgentId,
+    primary_label: String,
+    shadow_label: String,
+    traffic_share: f64,
+    mirrored: AtomicU64,
+    exchanges: Mutex<Exchanges>,
+}
+
+impl ShadowDeployment {
+    /// Shadow `primary` with `definition`, mirroring every message
+    ///
+    /// Returns the deployment and the commands that bring the shadow up.
+    pub fn start(
+        primary: &Agent,
+        definition: &AgentManifest,
+    ) -> ManifestResult<(Self, Vec<AgentCommand>)> {
+        let Some(config) = definition.config.clone() else {
+            return Err(ManifestError::MissingConfig {
+                name: definition.name.clone(),
+                status: AgentStatus::Active,
+            });
+        };
+
+        let name = format!("{}-shadow", primary.name());
+        let mut deploy = DeployAgent::new(primary.person_id(), name);
+        if let Some(description) = &definition.blueprint.description {
+            deploy = deploy.with_description(description);
+        }
+        let shadow_id = deploy.agent_id;
+        let commands = vec![
+            AgentCommand::DeployAgent(deploy),
+            AgentCommand::ConfigureModel(ConfigureModel::new(shadow_id, config.clone())),
+            AgentCommand::UpdateConfiguration(
+                UpdateConfiguration::new(shadow_id).with_flag(FeatureFlags::SHADOW_MODE, true),
+            ),
+            AgentCommand::ActivateAgent(ActivateAgent::new(shadow_id)),
+        ];
+
+        let deployment = Self {
+            primary_id: primary.id(),
+            shadow_id,
+            primary_label: primary
+                .model_config()
+                .map_or_else(|| "unconfigured".to_string(), label),
+            shadow_label: label(&config),
+            traffic_share: 1.0,
+            mirrored: AtomicU64::new(0),
+            exchanges: Mutex::new(Exchanges::new(DEFAULT_MAX_SHADOW_EXCHANGES)),
+        };
+        Ok((deployment, commands))
+    }
+
+    /// Builder: mirror only this fraction of the primary's messages
+    pub fn with_traffic_share(mut self, share: f64) -> Self {
+        self.traffic_share = share.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Builder: hold at most this many open, and finished, exchanges
+    pub fn with_max_exchanges(self, max_exchanges: usize) -> Self {
+        self.exchanges.lock().unwrap_or_else(|e| e.into_inner()).limit = max_exchanges.max(1);
+        self
+    }
+
+    /// Fraction of the primary's messages mirrored
+    pub fn traffic_share(&self) -> f64 {
+        self.traffic_share
+    }
+
+    /// The primary agent
+    pub fn primary_id(&self) -> AgentId {
+        self.primary_id
+    }
+
+    /// The shadow agent
+    pub fn shadow_id(&self) -> AgentId {
+        self.shadow_id
+    }
+
+    /// The shadow copy of a message to the primary, if this one is mirrored
+    ///
+    /// Mirrored messages are spread evenly across the primary's traffic.
+    pub fn mirror(&self, cmd: &SendMessage) -> Option<SendMessage> {
+        if cmd.agent_id != self.primary_id || cmd.shadow_of.is_some() {
+            return None;
+        }
+        let n = self.mirrored.fetch_add(1, Ordering::Relaxed) as f64;
+        if ((n + 1.0) * self.traffic_share).floor() <= (n * self.traffic_share).floor() {
+            return None;
+        }
+
+        let mut copy = cmd.clone();
+        copy.agent_id = self.shadow_id;
+        copy.message_id = MessageId::new();
+        copy.conversation_id = None;
+        copy.shadow_of = Some(cmd.message_id);
+        copy.cost_tags = copy
+            .cost_tags
+            .with(SHADOW_COST_TAG, self.primary_id.to_string());
+
+        let mut exchanges = self.exchanges.lock().unwrap_or_else(|e| e.into_inner());
+        exchanges.open(cmd.message_id, copy.message_id, &cmd.content);
+        Some(copy)
+    }
+
+    /// Record an event from either agent
+    ///
+    /// Copies mirrored by another instance are picked up from the shadow's
+    /// `MessageSent`, which names the primary message it copies.
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        let agent_id = envelope.aggregate_id;
+        if agent_id != self.primary_id && agent_id != self.shadow_id {
+            return;
+        }
+        let primary = agent_id == self.primary_id;
+
+        let mut exchanges = self.exchanges.lock().unwrap_or_else(|e| e.into_inner());
+        let key = match &envelope.event {
+            AgentEvent::MessageSent(e) if !primary => {
+                if let Some(original) = e.shadow_of {
+                    exchanges.open(original, e.message_id, &e.content);
+                }
+                return;
+            }
+            AgentEvent::ResponseChunkReceived(e) => {
+                if let Some((_, side)) = exchanges.side(primary, e.message_id) {
+                    side.text.push_str(&e.chunk.content);
+                }
+                return;
+            }
+            AgentEvent::ResponseCompleted(e) => {
+                let Some((key, side)) = exchanges.side(primary, e.message_id) else {
+                    return;
+                };
+                side.finish_reason = Some(e.finish_reason);
+                side.latency_ms = Some(e.duration_ms);
+                side.finished = true;
+                key
+            }
+            AgentEvent::ResponseFailed(e) => {
+                let Some((key, side)) = exchanges.side(primary, e.message_id) else {
+                    return;
+                };
+                side.error = Some(e.error_message.clone());
+                side.finished = true;
+                key
+            }
+            AgentEvent::ResponseCancelled(e) => {
+                let Some((key, side)) = exchanges.side(primary, e.message_id) else {
+                    return;
+                };
+                side.error = Some("cancelled".to_string());
+                side.finished = true;
+                key
+            }
+            _ => return,
+        };
+        exchanges.finish(key);
+    }
+
+    /// Exchanges where both agents have finished, not yet evaluated
+    pub fn pending(&self) -> usize {
+        self.exchanges
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .finished
+            .len()
+    }
+
+    /// Diff every finished exchange, primary as baseline, into one report
+    ///
+    /// Returns `None` when nothing has finished since the last evaluation.
+    /// The report is saved to the differ's comparison store, if it has one.
+    pub async fn evaluate(
+        &self,
+        differ: &ResponseDiffer,
+    ) -> ComparisonResult<Option<ComparisonReport>> {
+        let finished = std::mem::take(
+            &mut self
+                .exchanges
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .finished,
+        );
+        if finished.is_empty() {
+            return Ok(None);
+        }
+
+        let mut comparisons = Vec::with_capacity(finished.len());
+        for exchange in finished {
+            let baseline = exchange.primary.sample();
+            let candidate = exchange.shadow.sample();
+            comparisons.push(differ.diff(&exchange.prompt, baseline, candidate).await?);
+        }
+        let report = differ
+            .record(
+                self.primary_label.clone(),
+                format!("{} (shadow)", self.shadow_label),
+                comparisons,
+            )
+            .await?;
+        Ok(Some(report))
+    }
+
+    /// The command that takes the shadow down
+    pub fn stop(&self) -> AgentCommand {
+        AgentCommand::DecommissionAgent(DecommissionAgent::new(self.shadow_id))
+    }
+}
+
+/// Running shadow deployments, by primary agent
+#[derive(Debug, Default)]
+pub struct ShadowDeployments {
+    deployments: RwLock<HashMap<AgentId, Arc<ShadowDeployment>>>,
+}
+
+impl ShadowDeployments {
+    /// No deployments
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a deployment, returning the one it replaces for the same primary
+    pub fn insert(&self, deployment: ShadowDeployment) -> Option<Arc<ShadowDeployment>> {
+        let mut deployments = self.deployments.write().unwrap_or_else(|e| e.into_inner());
+        deployments.insert(deployment.primary_id, Arc::new(deployment))
+    }
+
+    /// Remove the deployment shadowing `primary_id`
+    pub fn remove(&self, primary_id: AgentId) -> Option<Arc<ShadowDeployment>> {
+        let mut deployments = self.deployments.write().unwrap_or_else(|e| e.into_inner());
+        deployments.remove(&primary_id)
+    }
+
+    /// Every deployment
+    pub fn list(&self) -> Vec<Arc<ShadowDeployment>> {
+        let deployments = self.deployments.read().unwrap_or_else(|e| e.into_inner());
+        deployments.values().cloned().collect()
+    }
+
+    /// The shadow copy of a message, if its agent is shadowed and it is mirrored
+    pub fn mirror(&self, cmd: &SendMessage) -> Option<SendMessage> {
+        let deployments = self.deployments.read().unwrap_or_else(|e| e.into_inner());
+        deployments.get(&cmd.agent_id)?.mirror(cmd)
+    }
+
+    /// Record an event with every deployment it concerns
+    pub fn observe(&self, envelope: &EventEnvelope) {
+        for deployment in self.list() {
+            deployment.observe(envelope);
+        }
+    }
+}
+
+/// Runtime management of shadow deployments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ShadowRequest {
+    /// Shadow `primary` with `definition`, mirroring `traffic_share` of its messages
+    Start {
+        primary: AgentId,
+        definition: AgentManifest,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        traffic_share: Option<f64>,
+    },
+    /// Take down the shadow of `primary`
+    Stop { primary: AgentId },
+    /// List the deployments
+    List,
+}
+
+fn label(config: &ModelConfig) -> String {
+    format!("{}/{}", config.provider, config.model_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        AgentActivatedEvent, AgentDeployedEvent, MessageSentEvent, ModelConfiguredEvent,
+        ResponseChunkReceivedEvent, ResponseCompletedEvent,
+    };
+    use crate::ports::MockChatAdapter;
+    use crate::read_model::AgentGraphProjection;
+    use crate::services::export_manifest;
+    use crate::test_support::{envelope, ScriptedEmbedder};
+    use crate::value_objects::{PersonId, ProviderType, StreamingChunk, TokenUsage};
+    use std::sync::Arc;
+
+    fn answer(agent_id: AgentId, message_id: MessageId, text: &str, ms: u64) -> [EventEnvelope; 2] {
+        [
+            envelope(0, AgentEvent::ResponseChunkReceived(
+                ResponseChunkReceivedEvent::new(agent_id, message_id, StreamingChunk::new(0, text)),
+            )),
+            envelope(0, AgentEvent::ResponseCompleted(ResponseCompletedEvent::new(
+                agent_id,
+                message_id,
+                1,
+                TokenUsage::default(),
+                FinishReason::Stop,
+                ms,
+            ))),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_shadow_responses_are_paired_and_compared() {
+        let primary_id = AgentId::new();
+        let primary = Agent::empty()
+            .apply_events(&[
+                AgentEvent::AgentDeployed(AgentDeployedEvent::new(
+                    primary_id,
+                    PersonId::new(),
+                    "support",
+                    None,
+                )),
+                AgentEvent::ModelConfigured(ModelConfiguredEvent::new(
+                    primary_id,
+                    ModelConfig::mock(),
+                )),
+                AgentEvent::AgentActivated(AgentActivatedEvent::new(primary_id)),
+            ])
+            .unwrap();
+        let mut manifest = export_manifest([&primary], &AgentGraphProjection::new())
+            .agents
+            .remove(0);
+        manifest.config = Some(ModelConfig::new(ProviderType::Mock, "mock-next"));
+
+        let (shadow, commands) = ShadowDeployment::start(&primary, &manifest).unwrap();
+        let shadow = shadow.with_max_exchanges(1);
+        assert!(matches!(
+            &commands[2],
+            AgentCommand::UpdateConfiguration(cmd) if cmd.feature_flags[FeatureFlags::SHADOW_MODE]
+        ));
+
+        let sent = SendMessage::new(primary_id, "Where is my order?");
+        let copy = shadow.mirror(&sent).unwrap();
+        assert_eq!(
+            (copy.agent_id, copy.shadow_of),
+            (shadow.shadow_id(), Some(sent.message_id))
+        );
+        let primary_tag = primary_id.to_string();
+        assert_eq!(copy.cost_tags.get(SHADOW_COST_TAG), Some(primary_tag.as_str()));
+        assert!(shadow.mirror(&copy).is_none());
+
+        let shadow_sent = envelope(0, AgentEvent::MessageSent(
+            MessageSentEvent::new(shadow.shadow_id(), copy.message_id, &copy.content)
+                .with_shadow_of(copy.shadow_of),
+        ));
+        shadow.observe(&shadow_sent);
+        for event in answer(primary_id, sent.message_id, "It shipped today.", 900) {
+            shadow.observe(&event);
+        }
+        assert_eq!(shadow.pending(), 0);
+        for event in answer(shadow.shadow_id(), copy.message_id, "It shipped.", 600) {
+            shadow.observe(&event);
+        }
+        assert_eq!(shadow.pending(), 1);
+
+        let differ = ResponseDiffer::new(
+            Arc::new(MockChatAdapter::new()),
+            Arc::new(ScriptedEmbedder::new(|_| vec![1.0, 0.0])),
+        );
+        let report = shadow.evaluate(&differ).await.unwrap().unwrap();
+        let comparison = &report.comparisons[0];
+        assert_eq!(comparison.prompt, "Where is my order?");
+        assert_eq!(comparison.baseline.latency_ms, Some(900));
+        assert_eq!(comparison.candidate.text, "It shipped.");
+        assert_eq!(report.candidate, "Mock/mock-next (shadow)");
+        assert!(shadow.evaluate(&differ).await.unwrap().is_none());
+
+        // Only the newest open exchange is kept
+        let first = SendMessage::new(primary_id, "First");
+        let first_copy = shadow.mirror(&first).unwrap();
+        shadow.mirror(&SendMessage::new(primary_id, "Second")).unwrap();
+        let primary_answer = answer(primary_id, first.message_id, "One", 1);
+        let shadow_answer = answer(shadow.shadow_id(), first_copy.message_id, "One", 1);
+        for event in primary_answer.iter().chain(&shadow_answer) {
+            shadow.observe(event);
+        }
+        assert_eq!(shadow.pending(), 0);
+    }
+}
@@ -0,0 +1,560 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Tool Catalog
+//!
+//! Keeps every published version of each tool, which version each agent has
+//! enabled, and how often the agent used it. Deprecating a version notifies
+//! the agents still on it; migrating moves an agent to the replacement and
+//! carries its usage over:
+//!
+//! ```text
+//! DeprecateTool(search 1.0.0, replacement 2.0.0)
+//!     └──> DeprecatedToolInUse ──> each agent still on search 1.0.0
+//!
+//! migrate(agent, "search")
+//!     search 1.0.0 { invocations: 42 } ──> search 2.0.0 { invocations: 42 }
+//!     └──> ToolVersionMigrated
+//! ```
+//!
+//! Without a named replacement an agent migrates to the newest version that
+//! is not deprecated.
+//!
+//! Published versions and their deprecations are stored in a
+//! [`ToolCatalogBucket`], so every instance serves the same catalog.
+
+use crate::commands::DeprecateTool;
+use crate::events::{DeprecatedToolInUseEvent, ToolVersionMigratedEvent};
+use crate::intent::ToolDefinition;
+use crate::value_objects::{clock_now, AgentId};
+use async_nats::jetstream::{self, kv::Operation, kv::Store as KvStore};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+/// Tool catalog errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ToolCatalogError {
+    #[error("Unknown tool {0}@{1}")]
+    UnknownVersion(String, Version),
+
+    #[error("Tool {0}@{1} is already registered")]
+    DuplicateVersion(String, Version),
+
+    #[error("Tool {0} is not enabled for agent {1}")]
+    NotEnabled(String, AgentId),
+
+    #[error("No supported version of tool {0} to migrate to")]
+    NoReplacement(String),
+
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+}
+
+/// Result type for tool catalog operations
+pub type ToolCatalogResult<T> = Result<T, ToolCatalogError>;
+
+/// Why and when a tool version was phased out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolDeprecation {
+    /// Reason given for the deprecation
+    pub reason: String,
+
+    /// Version agents should move to, if one was named
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<Version>,
+
+    /// When the version was deprecated
+    pub deprecated_at: DateTime<Utc>,
+}
+
+/// A tool version an agent has enabled, with its usage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnabledTool {
+    /// Tool name
+    pub name: String,
+
+    /// Enabled version
+    pub version: Version,
+
+    /// Calls made by the agent, across every version it has had enabled
+    pub invocations: u64,
+
+    /// Last call, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// A published tool version and its deprecation, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedTool {
+    /// The version's definition
+    pub definition: ToolDefinition,
+
+    /// Set once the version is deprecated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<ToolDeprecation>,
+}
+
+/// Runtime management of the catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ToolRequest {
+    /// Publish a tool version
+    Register { definition: ToolDefinition },
+    /// Deprecate a tool version
+    Deprecate(DeprecateTool),
+    /// List the published versions
+    List,
+}
+
+#[derive(Debug, Default)]
+struct CatalogState {
+    /// name -> version -> definition
+    tools: HashMap<String, BTreeMap<Version, PublishedTool>>,
+    /// agent -> name -> enabled version
+    enabled: HashMap<AgentId, BTreeMap<String, EnabledTool>>,
+}
+
+impl CatalogState {
+    fn published(&self, name: &str, version: &Version) -> ToolCatalogResult<&PublishedTool> {
+        self.tools
+            .get(name)
+            .and_then(|versions| versions.get(version))
+            .ok_or_else(|| ToolCatalogError::UnknownVersion(name.to_string(), version.clone()))
+    }
+
+    /// Newest version of `name` that is not deprecated
+    fn supported(&self, name: &str) -> Option<&PublishedTool> {
+        self.tools
+            .get(name)?
+            .values()
+            .rev()
+            .find(|p| p.deprecation.is_none())
+    }
+}
+
+/// Versioned tool definitions and the versions agents use
+#[derive(Debug, Default)]
+pub struct ToolCatalog {
+    state: RwLock<CatalogState>,
+}
+
+impl ToolCatalog {
+    /// Create an empty catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a catalog holding `definitions`
+    pub fn from_definitions(
+        definitions: impl IntoIterator<Item = ToolDefinition>,
+    ) -> ToolCatalogResult<Self> {
+        let catalog = Self::new();
+        for definition in definitions {
+            catalog.register(definition)?;
+        }
+        Ok(catalog)
+    }
+
+    /// Publish a tool version
+    pub fn register(&self, definition: ToolDefinition) -> ToolCatalogResult<()> {
+        let mut state = self.write();
+        let versions = state.tools.entry(definition.name.clone()).or_default();
+        if versions.contains_key(&definition.version) {
+            return Err(ToolCatalogError::DuplicateVersion(
+                definition.name,
+                definition.version,
+            ));
+        }
+        versions.insert(
+            definition.version.clone(),
+            PublishedTool {
+                definition,
+                deprecation: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Store a published version as it was persisted, replacing any held
+    pub fn restore(&self, published: PublishedTool) {
+        let mut state = self.write();
+        state
+            .tools
+            .entry(published.definition.name.clone())
+            .or_default()
+            .insert(published.definition.version.clone(), published);
+    }
+
+    /// A published version and its deprecation
+    pub fn published(&self, name: &str, version: &Version) -> Option<PublishedTool> {
+        self.read().published(name, version).ok().cloned()
+    }
+
+    /// Every published version, by name then version
+    pub fn published_tools(&self) -> Vec<PublishedTool> {
+        let state = self.read();
+        let mut names: Vec<&String> = state.tools.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .flat_map(|name| state.tools[name].values().cloned())
+            .collect()
+    }
+
+    /// Definition of one tool version
+    pub fn definition(&self, name: &str, version: &Version) -> Option<ToolDefinition> {
+        let state = self.read();
+        state
+            .published(name, version)
+            .ok()
+            .map(|p| p.definition.clone())
+    }
+
+    /// Newest supported version of every tool, by name
+    pub fn current_definitions(&self) -> Vec<ToolDefinition> {
+        let state = self.read();
+        let mut names: Vec<&String> = state.tools.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .filter_map(|name| state.supported(name))
+            .map(|p| p.definition.clone())
+            .collect()
+    }
+
+    /// Deprecation of a tool version, if it was deprecated
+    pub fn deprecation(&self, name: &str, version: &Version) -> Option<ToolDeprecation> {
+        let state = self.read();
+        state
+            .published(name, version)
+            .ok()
+            .and_then(|p| p.deprecation.clone())
+    }
+
+    /// Deprecate a tool version, notifying each agent that has it enabled
+    ///
+    /// Deprecating an already deprecated version updates its reason and
+    /// replacement and notifies the agents again.
+    pub fn deprecate(
+        &self,
+        cmd: &DeprecateTool,
+    ) -> ToolCatalogResult<Vec<DeprecatedToolInUseEvent>> {
+        cmd.validate().map_err(ToolCatalogError::InvalidCommand)?;
+        let mut state = self.write();
+        if let Some(replacement) = &cmd.replacement {
+            state.published(&cmd.tool_name, replacement)?;
+        }
+        state.published(&cmd.tool_name, &cmd.version)?;
+        if let Some(published) = state
+            .tools
+            .get_mut(&cmd.tool_name)
+            .and_then(|versions| versions.get_mut(&cmd.version))
+        {
+            published.deprecation = Some(ToolDeprecation {
+                reason: cmd.reason.clone(),
+                replacement: cmd.replacement.clone(),
+                deprecated_at: clock_now(),
+            });
+        }
+
+        let mut notices: Vec<DeprecatedToolInUseEvent> = state
+            .enabled
+            .iter()
+            .filter(|(_, tools)| {
+                tools
+                    .get(&cmd.tool_name)
+                    .is_some_and(|tool| tool.version == cmd.version)
+            })
+            .map(|(agent_id, _)| {
+                DeprecatedToolInUseEvent::new(
+                    *agent_id,
+                    &cmd.tool_name,
+                    cmd.version.clone(),
+                    cmd.replacement.clone(),
+                    &cmd.reason,
+                )
+            })
+            .collect();
+        notices.sort_by_key(|e| *e.agent_id.as_uuid());
+        Ok(notices)
+    }
+
+    /// Enable a tool version for an agent
+    ///
+    /// Switching versions this way keeps the agent's usage of the tool.
+    pub fn enable(
+        &self,
+        agent_id: AgentId,
+        name: &str,
+        version: &Version,
+    ) -> ToolCatalogResult<()> {
+        let mut state = self.write();
+        state.published(name, version)?;
+        state
+            .enabled
+            .entry(agent_id)
+            .or_default()
+            .entry(name.to_string())
+            .and_modify(|tool| tool.version = version.clone())
+            .or_insert_with(|| EnabledTool {
+                name: name.to_string(),
+                version: version.clone(),
+                invocations: 0,
+                last_used_at: None,
+            });
+        Ok(())
+    }
+
+    /// Disable a tool for an agent, forgetting its usage
+    pub fn disable(&self, agent_id: AgentId, name: &str) {
+        if let Some(tools) = self.write().enabled.get_mut(&agent_id) {
+            tools.remove(name);
+        }
+    }
+
+    /// Tools an agent has enabled, by name
+    pub fn enabled(&self, agent_id: AgentId) -> Vec<EnabledTool> {
+        self.read()
+            .enabled
+            .get(&agent_id)
+            .map(|tools| tools.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Count one call of an enabled tool
+    pub fn record_use(&self, agent_id: AgentId, name: &str) -> ToolCatalogResult<()> {
+        let mut state = self.write();
+        let tool = state
+            .enabled
+            .get_mut(&agent_id)
+            .and_then(|tools| tools.get_mut(name))
+            .ok_or_else(|| ToolCatalogError::NotEnabled(name.to_string(), agent_id))?;
+        tool.invocations += 1;
+        tool.last_used_at = Some(clock_now());
+        Ok(())
+    }
+
+    /// Move an agent off a deprecated version of `name`
+    ///
+    /// The target is the deprecation's replacement, or else the newest
+    /// supported version. Returns `None` if the enabled version is not
+    /// deprecated.
+    pub fn migrate(
+        &self,
+        agent_id: AgentId,
+        name: &str,
+    ) -> ToolCatalogResult<Option<ToolVersionMigratedEvent>> {
+        let mut state = self.write();
+        let from = state
+            .enabled
+            .get(&agent_id)
+            .and_then(|tools| tools.get(name))
+            .map(|tool| tool.version.clone())
+            .ok_or_else(|| ToolCatalogError::NotEnabled(name.to_string(), agent_id))?;
+        let Some(deprecation) = state.published(name, &from)?.deprecation.clone() else {
+            return Ok(None);
+        };
+        let to = match deprecation.replacement {
+            Some(replacement) => replacement,
+            None => state
+                .supported(name)
+                .map(|p| p.definition.version.clone())
+                .ok_or_else(|| ToolCatalogError::NoReplacement(name.to_string()))?,
+        };
+
+        let tool = state
+            .enabled
+            .get_mut(&agent_id)
+            .and_then(|tools| tools.get_mut(name))
+            .ok_or_else(|| ToolCatalogError::NotEnabled(name.to_string(), agent_id))?;
+        tool.version = to.clone();
+        Ok(Some(ToolVersionMigratedEvent::new(
+            agent_id,
+            name,
+            from,
+            to,
+            tool.invocations,
+        )))
+    }
+
+    /// Migrate every agent on a deprecated version of `name`
+    pub fn migrate_all(&self, name: &str) -> ToolCatalogResult<Vec<ToolVersionMigratedEvent>> {
+        let agents: Vec<AgentId> = self
+            .read()
+            .enabled
+            .iter()
+            .filter(|(_, tools)| tools.contains_key(name))
+            .map(|(agent_id, _)| *agent_id)
+            .collect();
+        let mut migrated = Vec::new();
+        for agent_id in agents {
+            migrated.extend(self.migrate(agent_id, name)?);
+        }
+        migrated.sort_by_key(|e| *e.agent_id.as_uuid());
+        Ok(migrated)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, CatalogState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, CatalogState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Published tool versions stored in a NATS KV bucket, keyed by name and version
+#[derive(Clone)]
+pub struct ToolCatalogBucket {
+    kv: KvStore,
+}
+
+impl ToolCatalogBucket {
+    /// Tool versions stored in `kv`
+    pub fn new(kv: KvStore) -> Self {
+        Self { kv }
+    }
+
+    /// Get or create the bucket, keeping only each version's latest state
+    pub async fn ensure_bucket(
+        jetstream: &jetstream::Context,
+        bucket_name: &str,
+    ) -> Result<KvStore, async_nats::Error> {
+        match jetstream.get_key_value(bucket_name).await {
+            Ok(kv) => Ok(kv),
+            Err(_) => {
+                let kv = jetstream
+                    .create_key_value(jetstream::kv::Config {
+                        bucket: bucket_name.to_string(),
+                        history: 1,
+                        storage: jetstream::stream::StorageType::File,
+                        ..Default::default()
+                    })
+                    .await?;
+                Ok(kv)
+            }
+        }
+    }
+
+    /// Store a tool version, replacing its previous state
+    pub async fn put(&self, published: &PublishedTool) -> Result<(), async_nats::Error> {
+        let definition = &published.definition;
+        let key = format!("{}.{}", definition.name, definition.version)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || "-_.".contains(c) { c } else { '_' })
+            .collect::<String>();
+        self.kv.put(key, serde_json::to_vec(published)?.into()).await?;
+        Ok(())
+    }
+
+    /// Apply every stored version to `catalog`, then each change as it is made
+    ///
+    /// Returns only when the watch ends.
+    pub async fn follow(&self, catalog: &ToolCatalog) -> Result<(), async_nats::Error> {
+        let mut watch = self.kv.watch_with_history(">").await?;
+        while let Some(entry) = watch.next().await {
+            let entry = entry?;
+            // Versions are never unpublished, so only puts matter
+            if !matches!(entry.operation, Operation::Put) {
+                continue;
+            }
+            match serde_json::from_slice::<PublishedTool>(&entry.value) {
+                Ok(published) => catalog.restore(published),
+                Err(e) => tracing::warn!("Ignoring stored tool {}: {}", entry.key, e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(major: u64) -> ToolDefinition {
+        ToolDefinition::new(
+            "search",
+            "Search the web",
+            serde_json::json!({ "type": "object" }),
+        )
+        .with_version(Version::new(major, 0, 0))
+    }
+
+    #[test]
+    fn test_deprecation_notifies_and_migration_keeps_usage() {
+        let catalog = ToolCatalog::from_definitions([search(1), search(2)]).unwrap();
+        let (old, new) = (AgentId::new(), AgentId::new());
+        let (v1, v2) = (Version::new(1, 0, 0), Version::new(2, 0, 0));
+        catalog.enable(old, "search", &v1).unwrap();
+        catalog.enable(new, "search", &v2).unwrap();
+        catalog.record_use(old, "search").unwrap();
+        catalog.record_use(old, "search").unwrap();
+
+        let notices = catalog
+            .deprecate(&DeprecateTool::new("search", v1.clone(), "schema changed"))
+            .unwrap();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].agent_id, old);
+        assert_eq!(catalog.current_definitions()[0].version, v2);
+
+        let migrated = catalog.migrate_all("search").unwrap();
+        assert_eq!(migrated.len(), 1);
+        assert_eq!(
+            (migrated[0].from_version.clone(), migrated[0].invocations),
+            (v1, 2)
+        );
+        let enabled = catalog.enabled(old);
+        assert_eq!(
+            (enabled[0].version.clone(), enabled[0].invocations),
+            (v2, 2)
+        );
+        assert!(catalog.migrate(new, "search").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_duplicate_versions() {
+        let catalog = ToolCatalog::from_definitions([search(1)]).unwrap();
+        assert!(matches!(
+            catalog.register(search(1)),
+            Err(ToolCatalogError::DuplicateVersion(..))
+        ));
+        let cmd = DeprecateTool::new("search", Version::new(1, 0, 0), "gone")
+            .with_replacement(Version::new(3, 0, 0));
+        assert!(matches!(
+            catalog.deprecate(&cmd),
+            Err(ToolCatalogError::UnknownVersion(..))
+        ));
+
+        catalog
+            .deprecate(&DeprecateTool::new("search", Version::new(1, 0, 0), "gone"))
+            .unwrap();
+        let agent = AgentId::new();
+        catalog
+            .enable(agent, "search", &Version::new(1, 0, 0))
+            .unwrap();
+        assert!(matches!(
+            catalog.migrate(agent, "search"),
+            Err(ToolCatalogError::NoReplacement(_))
+        ));
+    }
+
+    #[test]
+    fn test_restored_versions_keep_their_deprecation() {
+        let catalog = ToolCatalog::from_definitions([search(1), search(2)]).unwrap();
+        let v1 = Version::new(1, 0, 0);
+        catalog
+            .deprecate(&DeprecateTool::new("search", v1.clone(), "schema changed"))
+            .unwrap();
+
+        let restored = ToolCatalog::new();
+        for published in catalog.published_tools() {
+            restored.restore(published);
+        }
+        assert!(restored.deprecation("search", &v1).is_some());
+        assert_eq!(restored.current_definitions()[0].version, Version::new(2, 0, 0));
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Toxicity Scoring
+//!
+//! Scores message and response text for moderation. The baseline is a
+//! weighted lexicon that needs nothing but the text; a [`ToxicityClassifier`]
+//! (a hosted moderation model, say) can be plugged in over it:
+//!
+//! ```text
+//! text ──> ClassifierScorer ──(classifier fails)──> LexiconScorer
+//!               │                                        │
+//!               v                                        v
+//!        ToxicityScore { score, terms, scorer } ──> ModerationStage::assess
+//!                                                        │ thresholds
+//!                                                        v
+//!                                        verdict: clean / flagged / blocked
+//! ```
+//!
+//! Lexicon hits combine like independent probabilities, so one mild word
+//! scores low while several strong ones approach 1.0.
+
+use crate::ports::ChatResult;
+use crate::value_objects::{ToxicityScore, ToxicityThresholds};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Baseline lexicon: term and how strongly it suggests toxicity
+const DEFAULT_LEXICON: &[(&str, f32)] = &[
+    ("damn", 0.2),
+    ("crap", 0.2),
+    ("hell", 0.1),
+    ("stupid", 0.4),
+    ("dumb", 0.4),
+    ("idiot", 0.6),
+    ("moron", 0.6),
+    ("loser", 0.5),
+    ("shut up", 0.4),
+    ("bastard", 0.7),
+    ("shit", 0.6),
+    ("fuck", 0.8),
+    ("i hate you", 0.7),
+    ("kill yourself", 0.95),
+    ("i will kill you", 0.95),
+];
+
+/// Scores text for toxicity
+#[async_trait]
+pub trait ToxicityScorer: Send + Sync {
+    /// Score `text`; the verdict is left for the [`ModerationStage`]
+    async fn score(&self, text: &str) -> ToxicityScore;
+}
+
+/// Scores text by the weighted terms it contains
+#[derive(Debug, Clone)]
+pub struct LexiconScorer {
+    terms: Vec<(String, f32)>,
+}
+
+impl LexiconScorer {
+    /// Scorer over the built-in lexicon
+    pub fn new() -> Self {
+        Self {
+            terms: DEFAULT_LEXICON
+                .iter()
+                .map(|(term, weight)| (term.to_string(), *weight))
+                .collect(),
+        }
+    }
+
+    /// Scorer over no terms at all
+    pub fn empty() -> Self {
+        Self { terms: Vec::new() }
+    }
+
+    /// Builder: add or reweigh a term (matched case-insensitively, on word boundaries)
+    pub fn with_term(mut self, term: impl Into<String>, weight: f32) -> Self {
+        let term = term.into().to_lowercase();
+        self.terms.retain(|(t, _)| *t != term);
+        self.terms.push((term, weight.clamp(0.0, 1.0)));
+        self
+    }
+
+    /// Score `text` without waiting
+    pub fn score_text(&self, text: &str) -> ToxicityScore {
+        let words: Vec<String> = text
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect();
+        let padded = format!(" {} ", words.join(" "));
+
+        let mut benign = 1.0_f32;
+        let mut found = Vec::new();
+        for (term, weight) in &self.terms {
+            let hits = padded.matches(&format!(" {} ", term)).count();
+            if hits > 0 {
+                benign *= (1.0 - weight).powi(hits as i32);
+                found.push(term.clone());
+            }
+        }
+        ToxicityScore::new(1.0 - benign, "lexicon").with_terms(found)
+    }
+}
+
+impl Default for LexiconScorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToxicityScorer for LexiconScorer {
+    async fn score(&self, text: &str) -> ToxicityScore {
+        self.score_text(text)
+    }
+}
+
+/// A trained toxicity classifier
+#[async_trait]
+pub trait ToxicityClassifier: Send + Sync {
+    /// Short name recorded with the score
+    fn name(&self) -> String;
+
+    /// Probability, 0.0 to 1.0, that `text` is toxic
+    async fn classify(&self, text: &str) -> ChatResult<f32>;
+}
+
+/// Scores with a classifier, keeping the lexicon's terms as evidence
+///
+/// When the classifier fails the lexicon score is used instead, so an
+/// outage degrades moderation rather than blocking every message.
+pub struct ClassifierScorer {
+    classifier: Arc<dyn ToxicityClassifier>,
+    lexicon: LexiconScorer,
+}
+
+impl ClassifierScorer {
+    /// Score with `classifier` over the built-in lexicon
+    pub fn new(classifier: Arc<dyn ToxicityClassifier>) -> Self {
+        Self {
+            classifier,
+            lexicon: LexiconScorer::new(),
+        }
+    }
+
+    /// Builder: lexicon used for evidence and as the fallback
+    pub fn with_lexicon(mut self, lexicon: LexiconScorer) -> Self {
+        self.lexicon = lexicon;
+        self
+    }
+}
+
+#[async_trait]
+impl ToxicityScorer for ClassifierScorer {
+    async fn score(&self, text: &str) -> ToxicityScore {
+        let baseline = self.lexicon.score_text(text);
+        match self.classifier.classify(text).await {
+            Ok(score) => {
+                ToxicityScore::new(score, self.classifier.name()).with_terms(baseline.terms)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Toxicity classifier {} failed: {}",
+                    self.classifier.name(),
+                    e
+                );
+                baseline
+            }
+        }
+    }
+}
+
+/// Scores content and judges it against the thresholds
+#[derive(Clone)]
+pub struct ModerationStage {
+    scorer: Arc<dyn ToxicityScorer>,
+    thresholds: ToxicityThresholds,
+}
+
+impl ModerationStage {
+    /// Judge `scorer`'s scores with the default thresholds
+    pub fn new(scorer: Arc<dyn ToxicityScorer>) -> Self {
+        Self {
+            scorer,
+            thresholds: ToxicityThresholds::default(),
+        }
+    }
+
+    /// Builder: thresholds for flagging and blocking
+    pub fn with_thresholds(mut self, thresholds: ToxicityThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Thresholds in force
+    pub fn thresholds(&self) -> ToxicityThresholds {
+        self.thresholds
+    }
+
+    /// Score and judge `text`; `strict` blocks anything flagged
+    pub async fn assess(&self, text: &str, strict: bool) -> ToxicityScore {
+        let thresholds = if strict {
+            self.thresholds.strict()
+        } else {
+            self.thresholds
+        };
+        self.scorer.score(text).await.judged(&thresholds)
+    }
+}
+
+impl Default for ModerationStage {
+    fn default() -> Self {
+        Self::new(Arc::new(LexiconScorer::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::ChatError;
+    use crate::value_objects::ModerationVerdict;
+
+    struct Unavailable;
+
+    #[async_trait]
+    impl ToxicityClassifier for Unavailable {
+        fn name(&self) -> String {
+            "unavailable".to_string()
+        }
+
+        async fn classify(&self, _text: &str) -> ChatResult<f32> {
+            Err(ChatError::Timeout(5))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lexicon_scores_and_stage_judges() {
+        let stage = ModerationStage::default();
+
+        let clean = stage
+            .assess("Could you summarize this hellish report?", false)
+            .await;
+        assert_eq!(clean.score, 0.0);
+        assert_eq!(clean.verdict, ModerationVerdict::Clean);
+
+        let rude = stage.assess("Shut up, you IDIOT.", false).await;
+        assert_eq!(rude.terms, vec!["idiot".to_string(), "shut up".to_string()]);
+        assert!((rude.score - 0.76).abs() < 1e-4);
+        assert_eq!(rude.verdict, ModerationVerdict::Flagged);
+        assert!(stage.assess("Shut up, you IDIOT.", true).await.is_blocked());
+
+        let threat = stage.assess("I will kill you", false).await;
+        assert!(threat.is_blocked());
+    }
+
+    #[tokio::test]
+    async fn test_classifier_falls_back_to_lexicon() {
+        let scorer = ClassifierScorer::new(Arc::new(Unavailable))
+            .with_lexicon(LexiconScorer::empty().with_term("Darn", 0.3));
+        let score = scorer.score("darn it").await;
+        assert_eq!(score.scorer, "lexicon");
+        assert_eq!(score.terms, vec!["darn".to_string()]);
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Fixtures shared by unit tests
+
+use crate::events::AgentEvent;
+use crate::infrastructure::EventEnvelope;
+use crate::intent::EmbeddingResponse;
+use crate::ports::{ChatPort, ChatResult, ChatStream, EmbeddingPort};
+use crate::value_objects::{ContextMessage, ModelConfig, StreamingChunk};
+use async_trait::async_trait;
+use futures::stream;
+use uuid::Uuid;
+
+/// A stored envelope for `event`, timestamped when the event occurred
+pub(crate) fn envelope(sequence: u64, event: AgentEvent) -> EventEnvelope {
+    EventEnvelope {
+        aggregate_id: event.agent_id(),
+        sequence,
+        timestamp: event.timestamp(),
+        event,
+        correlation_id: Uuid::now_v7(),
+        causation_id: Uuid::now_v7(),
+        metadata: None,
+    }
+}
+
+/// Chat provider answering each request with the chunks a closure computes
+pub(crate) struct ScriptedChat<F>(F);
+
+impl<F> ScriptedChat<F>
+where
+    F: Fn(&ModelConfig, &[ContextMessage]) -> ChatResult<Vec<StreamingChunk>> + Send + Sync,
+{
+    pub(crate) fn new(script: F) -> Self {
+        Self(script)
+    }
+}
+
+#[async_trait]
+impl<F> ChatPort for ScriptedChat<F>
+where
+    F: Fn(&ModelConfig, &[ContextMessage]) -> ChatResult<Vec<StreamingChunk>> + Send + Sync,
+{
+    async fn send(
+        &self,
+        config: &ModelConfig,
+        context: Vec<ContextMessage>,
+    ) -> ChatResult<ChatStream> {
+        let chunks = (self.0)(config, &context)?;
+        Ok(Box::pin(stream::iter(chunks.into_iter().map(Ok))))
+    }
+
+    async fn health_check(&self) -> ChatResult<()> {
+        Ok(())
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "scripted"
+    }
+}
+
+/// Chat provider replying `text` to every request, in one chunk
+pub(crate) fn replying(text: &'static str) -> impl ChatPort {
+    ScriptedChat::new(move |_, _| Ok(vec![StreamingChunk::new(0, text)]))
+}
+
+/// Embedding provider embedding each input with a closure
+pub(crate) struct ScriptedEmbedder<F>(F);
+
+impl<F> ScriptedEmbedder<F>
+where
+    F: Fn(&str) -> Vec<f32> + Send + Sync,
+{
+    pub(crate) fn new(embed: F) -> Self {
+        Self(embed)
+    }
+}
+
+#[async_trait]
+impl<F> EmbeddingPort for ScriptedEmbedder<F>
+where
+    F: Fn(&str) -> Vec<f32> + Send + Sync,
+{
+    async fn embed(
+        &self,
+        input: Vec<String>,
+        _model: Option<&str>,
+    ) -> ChatResult<EmbeddingResponse> {
+        let embeddings = input.iter().map(|text| (self.0)(text)).collect();
+        Ok(EmbeddingResponse::new(embeddings, "scripted"))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "scripted"
+    }
+}
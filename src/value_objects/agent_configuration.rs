@@ -7,9 +7,11 @@
 //! - Value objects with enforced invariants
 //! - No redundant timestamp fields (extracted from UUIDv7)
 
+use super::{BestOfN, GenerationParams, OutputConstraints, ReflectionConfig, TruncationPolicy};
 use cim_domain::{DomainError, DomainResult, EntityId};
 use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use uuid::Uuid;
 
@@ -186,7 +188,29 @@ impl AgentConfiguration {
                 .unwrap_or_else(|| "default-model".to_string()),
         )?;
 
-        let model_config = ModelConfig::new(provider, model_name, parameters);
+        let mut model_config = ModelConfig::new(provider, model_name, parameters);
+        if let Some(preset) = parsed.model.preset {
+            model_config = model_config.with_generation(preset.params());
+        }
+        for (intent, params) in parsed.model.intent_overrides {
+            params
+                .validate()
+                .map_err(|e| DomainError::ValidationError(format!("{}: {}", intent, e)))?;
+            model_config = model_config.with_intent_override(intent, params);
+        }
+        model_config = model_config.with_truncation(parsed.model.truncation);
+        for (intent, best_of) in parsed.model.best_of {
+            best_of
+                .validate()
+                .map_err(|e| DomainError::ValidationError(format!("{}: {}", intent, e)))?;
+            model_config = model_config.with_best_of(intent, best_of);
+        }
+        if let Some(reflection) = parsed.model.reflection {
+            model_config = model_config.with_reflection(reflection);
+        }
+        if let Some(constraints) = parsed.model.output_constraints {
+            model_config = model_config.with_output_constraints(constraints);
+        }
 
         // Build prompt config
         let system_prompt = SystemPrompt::new(parsed.system_prompt)?;
@@ -264,6 +288,18 @@ pub struct ModelConfig {
     provider: ProviderType,
     model_name: ModelName,
     parameters: ModelParameters,
+    #[serde(default)]
+    generation: Option<GenerationParams>,
+    #[serde(default)]
+    intent_overrides: HashMap<String, GenerationParams>,
+    #[serde(default)]
+    truncation: TruncationPolicy,
+    #[serde(default)]
+    best_of: HashMap<String, BestOfN>,
+    #[serde(default)]
+    reflection: Option<ReflectionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output_constraints: Option<OutputConstraints>,
 }
 
 impl ModelConfig {
@@ -276,9 +312,45 @@ impl ModelConfig {
             provider,
             model_name,
             parameters,
+            generation: None,
+            intent_overrides: HashMap::new(),
+            truncation: TruncationPolicy::default(),
+            best_of: HashMap::new(),
+            reflection: None,
+            output_constraints: None,
         }
     }
 
+    pub fn with_generation(mut self, generation: GenerationParams) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    pub fn with_intent_override(mut self, intent: impl Into<String>, params: GenerationParams) -> Self {
+        self.intent_overrides.insert(intent.into(), params);
+        self
+    }
+
+    pub fn with_truncation(mut self, truncation: TruncationPolicy) -> Self {
+        self.truncation = truncation;
+        self
+    }
+
+    pub fn with_best_of(mut self, intent: impl Into<String>, best_of: BestOfN) -> Self {
+        self.best_of.insert(intent.into(), best_of);
+        self
+    }
+
+    pub fn with_reflection(mut self, reflection: ReflectionConfig) -> Self {
+        self.reflection = Some(reflection);
+        self
+    }
+
+    pub fn with_output_constraints(mut self, constraints: OutputConstraints) -> Self {
+        self.output_constraints = Some(constraints);
+        self
+    }
+
     pub fn provider(&self) -> ProviderType {
         self.provider
     }
@@ -288,6 +360,24 @@ impl ModelConfig {
     pub fn parameters(&self) -> &ModelParameters {
         &self.parameters
     }
+    pub fn generation(&self) -> Option<&GenerationParams> {
+        self.generation.as_ref()
+    }
+    pub fn intent_override(&self, intent: &str) -> Option<&GenerationParams> {
+        self.intent_overrides.get(intent)
+    }
+    pub fn truncation(&self) -> &TruncationPolicy {
+        &self.truncation
+    }
+    pub fn best_of(&self, intent: &str) -> Option<&BestOfN> {
+        self.best_of.get(intent)
+    }
+    pub fn reflection(&self) -> Option<&ReflectionConfig> {
+        self.reflection.as_ref()
+    }
+    pub fn output_constraints(&self) -> Option<&OutputConstraints> {
+        self.output_constraints.as_ref()
+    }
 }
 
 /// ProviderType - VALUE OBJECT (enum)
@@ -297,6 +387,7 @@ pub enum ProviderType {
     Ollama,
     OpenAI,
     Anthropic,
+    Gemini,
     Mock,
 }
 
@@ -306,6 +397,7 @@ impl ProviderType {
             "ollama" => Ok(Self::Ollama),
             "openai" => Ok(Self::OpenAI),
             "anthropic" => Ok(Self::Anthropic),
+            "gemini" => Ok(Self::Gemini),
             "mock" => Ok(Self::Mock),
             _ => Err(DomainError::ValidationError(
                 format!("Unknown provider: {}", s),
@@ -318,6 +410,7 @@ impl ProviderType {
             Self::Ollama => "ollama",
             Self::OpenAI => "openai",
             Self::Anthropic => "anthropic",
+            Self::Gemini => "gemini",
             Self::Mock => "mock",
         }
     }
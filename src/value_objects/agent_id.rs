@@ -2,6 +2,7 @@
 //!
 //! Unique identifier for agents using UUID v7 (time-ordered).
 
+use super::next_id;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
@@ -28,7 +29,7 @@ impl AgentId {
     /// assert!(id.as_uuid().get_version_num() == 7);
     /// ```
     pub fn new() -> Self {
-        Self(Uuid::now_v7())
+        Self(next_id())
     }
 
     /// Create an Agent ID from an existing UUID
@@ -0,0 +1,128 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Best-of-N sampling configuration
+//!
+//! For intents where a wrong answer is expensive, a model configuration can
+//! ask for several candidate answers generated in parallel, possibly across
+//! providers, and a judge that picks one. Sampling is opt-in per intent so
+//! the extra cost only lands where it is wanted:
+//!
+//! ```text
+//! model:
+//!   best_of:
+//!     chat: { n: 3, judge: heuristic }
+//!
+//! intent "chat" ──> candidate 1 ─┐
+//!                   candidate 2 ─┼──> judge ──> selected answer
+//!                   candidate 3 ─┘       └──> CandidateSelected (all candidates + rationale)
+//! ```
+
+use super::{FinishReason, ModelConfig, ProviderType};
+use serde::{Deserialize, Serialize};
+
+/// Most candidates one request may generate
+pub const MAX_CANDIDATES: u32 = 8;
+
+/// How many candidates to generate for an intent and how to choose one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestOfN {
+    /// Number of candidates generated in parallel
+    pub n: u32,
+
+    /// Models to sample from, round-robin (empty: the agent's own model)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelConfig>,
+
+    /// How the winning candidate is chosen
+    #[serde(default)]
+    pub judge: JudgeStrategy,
+}
+
+impl BestOfN {
+    /// Generate `n` candidates from the agent's model, judged heuristically
+    pub fn new(n: u32) -> Self {
+        Self {
+            n,
+            models: Vec::new(),
+            judge: JudgeStrategy::default(),
+        }
+    }
+
+    /// Builder: also sample from `model`
+    pub fn with_model(mut self, model: ModelConfig) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Builder: choose the winner with `judge`
+    pub fn with_judge(mut self, judge: JudgeStrategy) -> Self {
+        self.judge = judge;
+        self
+    }
+
+    /// Check the candidate count is within bounds
+    pub fn validate(&self) -> Result<(), String> {
+        if self.n < 2 || self.n > MAX_CANDIDATES {
+            return Err(format!(
+                "Best-of-N needs between 2 and {} candidates, got {}",
+                MAX_CANDIDATES, self.n
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How the winning candidate is chosen
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgeStrategy {
+    /// Prefer answers that finished normally, then the most complete one
+    #[default]
+    Heuristic,
+
+    /// Ask this model to compare the candidates
+    Model(ModelConfig),
+}
+
+/// One generated answer considered by the judge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseCandidate {
+    /// Provider that generated the answer
+    pub provider: ProviderType,
+
+    /// Model that generated the answer
+    pub model: String,
+
+    /// Full answer text
+    pub content: String,
+
+    /// Why generation stopped, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
+
+    /// Set when generation failed; such candidates are never selected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ResponseCandidate {
+    /// Whether the candidate produced an answer
+    pub fn is_usable(&self) -> bool {
+        self.error.is_none() && !self.content.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_of_parses_and_bounds_candidates() {
+        let config: BestOfN = serde_json::from_str(r#"{"n": 3}"#).unwrap();
+        assert_eq!(config, BestOfN::new(3));
+        assert!(config.validate().is_ok());
+
+        assert!(BestOfN::new(1).validate().is_err());
+        assert!(BestOfN::new(MAX_CANDIDATES + 1).validate().is_err());
+    }
+}
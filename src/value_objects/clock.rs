@@ -0,0 +1,112 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Clock abstraction for event timestamps
+//!
+//! Event constructors, aggregates and event envelopes take their time from
+//! [`clock_now`] rather than the system clock directly. Outside a clock
+//! scope that is the system time; tests and replays install their own
+//! clock for the duration of a call:
+//!
+//! ```text
+//! command handler ──> with_clock(clock, handle(cmd))
+//!                            │
+//!                            v
+//!            AgentDeployedEvent::new(..)  deployed_at = clock_now()
+//!            EventEnvelope { timestamp: clock_now(), .. }
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+tokio::task_local! {
+    static CURRENT: Arc<dyn Clock>;
+}
+
+/// Source of the current time
+pub trait Clock: Debug + Send + Sync {
+    /// The current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to
+///
+/// Clones share the same time, so a test can keep a handle and advance the
+/// clock it installed.
+#[derive(Debug, Clone)]
+pub struct FixedClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl FixedClock {
+    /// Create a clock stopped at `at`
+    pub fn new(at: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(at)),
+        }
+    }
+
+    /// Move the clock to `at`
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = at;
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Current time according to the clock in scope, or the system clock
+pub fn clock_now() -> DateTime<Utc> {
+    CURRENT.try_with(|clock| clock.now()).unwrap_or_else(|_| Utc::now())
+}
+
+/// Run `future` with `clock` answering [`clock_now`]
+pub async fn with_clock<F: Future>(clock: Arc<dyn Clock>, future: F) -> F::Output {
+    CURRENT.scope(clock, future).await
+}
+
+/// Run `f` with `clock` answering [`clock_now`], for synchronous callers
+pub fn with_clock_sync<R>(clock: Arc<dyn Clock>, f: impl FnOnce() -> R) -> R {
+    CURRENT.sync_scope(clock, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_fixed_clock_drives_clock_now() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = FixedClock::new(start);
+
+        let (first, second) = with_clock_sync(Arc::new(clock.clone()), || {
+            let first = clock_now();
+            clock.advance(Duration::seconds(5));
+            (first, clock_now())
+        });
+        assert_eq!(first, start);
+        assert_eq!(second, start + Duration::seconds(5));
+        assert!(clock_now() > start);
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Command metadata
+//!
+//! Who sent a command, for which tenant, in which causal chain, by when,
+//! under which trace, and with which idempotency key. The metadata rides on
+//! a `CommandEnvelope` and is put in scope while the command is handled, so
+//! every event it produces carries it:
+//!
+//! ```text
+//! CommandEnvelope { command, actor, tenant, correlation, causation,
+//!                   deadline, trace, idempotency_key }
+//!        │ CommandMetadata::scope
+//!        v
+//! handler ──> EventEnvelope { correlation_id, causation_id, metadata }
+//! ```
+//!
+//! A command's correlation id replaces the one its handler would have
+//! generated. The first event of a causal chain is caused by the command's
+//! causation id; later events keep pointing at the event before them.
+
+use super::Deadline;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use uuid::Uuid;
+
+tokio::task_local! {
+    static CURRENT: CommandMetadata;
+}
+
+/// W3C trace context of the request that issued a command
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// `traceparent`: `version-trace_id-parent_id-flags`
+    pub traceparent: String,
+
+    /// `tracestate`, vendor-specific entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Trace context from a `traceparent` value; `None` if it is malformed
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        let hex = |part: &str, len: usize| {
+            part.len() == len && part.chars().all(|c| c.is_ascii_hexdigit())
+        };
+        let valid = parts.len() == 4
+            && hex(parts[0], 2)
+            && hex(parts[1], 32)
+            && hex(parts[2], 16)
+            && hex(parts[3], 2)
+            && parts[1].chars().any(|c| c != '0');
+        valid.then(|| Self {
+            traceparent: traceparent.trim().to_ascii_lowercase(),
+            tracestate: None,
+        })
+    }
+
+    /// Builder: the `tracestate` that came with the `traceparent`
+    pub fn with_tracestate(mut self, tracestate: impl Into<String>) -> Self {
+        self.tracestate = Some(tracestate.into());
+        self
+    }
+
+    /// The 32-digit trace id
+    pub fn trace_id(&self) -> &str {
+        self.traceparent.split('-').nth(1).unwrap_or_default()
+    }
+}
+
+/// Metadata of the command being handled
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandMetadata {
+    /// Identity of whoever issued the command, e.g. a person or service id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+
+    /// Tenant the command acts for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+
+    /// Correlation id shared by everything the command sets off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<Uuid>,
+
+    /// Id of the message or event that caused the command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub causation_id: Option<Uuid>,
+
+    /// When the caller stops waiting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<Deadline>,
+
+    /// Trace the command belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+
+    /// Key identifying retries of the same command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+}
+
+impl CommandMetadata {
+    /// Whether no metadata was given
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Correlation and causation ids for an event, given the ones its
+    /// handler chose
+    ///
+    /// An event that starts a chain (caused by its own correlation id) is
+    /// caused by the command's causation id instead, when there is one.
+    pub fn event_ids(&self, correlation_id: Uuid, causation_id: Uuid) -> (Uuid, Uuid) {
+        let correlation = self.correlation_id.unwrap_or(correlation_id);
+        let causation = if causation_id == correlation_id {
+            self.causation_id.unwrap_or(correlation)
+        } else {
+            causation_id
+        };
+        (correlation, causation)
+    }
+
+    /// Run `future` with this metadata visible through
+    /// [`CommandMetadata::current`]
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Metadata of the command being handled by this task, if any
+    pub fn current() -> Option<CommandMetadata> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_context_parse() {
+        let parent = "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01";
+        let trace = TraceContext::parse(parent).unwrap();
+        assert_eq!(trace.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+
+        assert!(TraceContext::parse("00-abc-00f067aa0ba902b7-01").is_none());
+        let zero = format!("00-{}-00f067aa0ba902b7-01", "0".repeat(32));
+        assert!(TraceContext::parse(&zero).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scope_and_event_ids() {
+        assert!(CommandMetadata::current().is_none());
+        let (command_correlation, cause) = (Uuid::new_v4(), Uuid::new_v4());
+        let metadata = CommandMetadata {
+            tenant: Some("acme".to_string()),
+            correlation_id: Some(command_correlation),
+            causation_id: Some(cause),
+            ..CommandMetadata::default()
+        };
+
+        let seen = metadata
+            .clone()
+            .scope(async { CommandMetadata::current() })
+            .await;
+        assert_eq!(seen.as_ref(), Some(&metadata));
+
+        let (generated, previous) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(
+            metadata.event_ids(generated, generated),
+            (command_correlation, cause)
+        );
+        assert_eq!(
+            metadata.event_ids(generated, previous),
+            (command_correlation, previous)
+        );
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Large message content
+//!
+//! NATS refuses messages over the server's `max_payload` (1 MiB by default),
+//! so a pasted document cannot ride inline in a `SendMessage`. Content over
+//! [`MessageSizeLimits::max_inline_bytes`] is uploaded to a blob store
+//! instead, and the command carries a [`ContentRef`] naming it by CID:
+//!
+//! ```text
+//! AgentClient ──> content > max_inline ──> BlobStore::put (chunked upload)
+//!                                               │
+//!   SendMessage { content: "", content_ref: { cid, size_bytes } }
+//!                                               │
+//! agent-service ──> BlobStore::get(cid) ──> verify CID ──> model
+//! ```
+//!
+//! The CID is a CIDv1 over the raw bytes with a SHA2-256 multihash, so the
+//! server can check that what it read is exactly what the client sent.
+
+use cid::Cid;
+use multihash::Multihash;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Multicodec code for raw bytes
+const RAW_CODEC: u64 = 0x55;
+
+/// Multihash code for SHA2-256
+const SHA2_256: u64 = 0x12;
+
+/// Content stored out of band, named by its CID
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContentRef {
+    /// CIDv1 (raw, SHA2-256) of the content
+    pub cid: String,
+
+    /// Size of the content in bytes
+    pub size_bytes: u64,
+}
+
+impl ContentRef {
+    /// Reference to `content`
+    pub fn of(content: &[u8]) -> Self {
+        Self {
+            cid: content_cid(content),
+            size_bytes: content.len() as u64,
+        }
+    }
+
+    /// Check that `content` is what this reference names
+    pub fn verify(&self, content: &[u8]) -> Result<(), MessageSizeError> {
+        if content.len() as u64 != self.size_bytes || content_cid(content) != self.cid {
+            return Err(MessageSizeError::ContentMismatch(self.cid.clone()));
+        }
+        Ok(())
+    }
+}
+
+fn content_cid(content: &[u8]) -> String {
+    let digest = Sha256::digest(content);
+    let hash = Multihash::<64>::wrap(SHA2_256, &digest).expect("SHA2-256 digest fits");
+    Cid::new_v1(RAW_CODEC, hash).to_string()
+}
+
+/// Message content size violations
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MessageSizeError {
+    #[error("Message content is {bytes} bytes; content over {limit} bytes must be uploaded")]
+    InlineTooLarge { bytes: usize, limit: usize },
+
+    #[error("Message content is {bytes} bytes; the limit is {limit} bytes")]
+    ContentTooLarge { bytes: u64, limit: u64 },
+
+    #[error("Uploaded content {0} was not found")]
+    ContentMissing(String),
+
+    #[error("Uploaded content does not match {0}")]
+    ContentMismatch(String),
+}
+
+/// How big message content may be, inline and uploaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageSizeLimits {
+    /// Largest content sent inside the command
+    pub max_inline_bytes: usize,
+
+    /// Largest content accepted at all, uploaded or not
+    pub max_content_bytes: u64,
+}
+
+impl MessageSizeLimits {
+    /// Check content sent inline
+    pub fn check_inline(&self, content: &str) -> Result<(), MessageSizeError> {
+        if content.len() > self.max_inline_bytes {
+            return Err(MessageSizeError::InlineTooLarge {
+                bytes: content.len(),
+                limit: self.max_inline_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check content of any size against the overall limit
+    pub fn check_content(&self, size_bytes: u64) -> Result<(), MessageSizeError> {
+        if size_bytes > self.max_content_bytes {
+            return Err(MessageSizeError::ContentTooLarge {
+                bytes: size_bytes,
+                limit: self.max_content_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether content of this size must be uploaded rather than sent inline
+    pub fn needs_upload(&self, content: &str) -> bool {
+        content.len() > self.max_inline_bytes
+    }
+}
+
+impl Default for MessageSizeLimits {
+    /// 256 KiB inline, leaving headroom under NATS' 1 MiB payload; 16 MiB in all
+    fn default() -> Self {
+        Self {
+            max_inline_bytes: 256 * 1024,
+            max_content_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_ref_names_and_verifies_content() {
+        let content = b"a very long pasted document";
+        let reference = ContentRef::of(content);
+        assert!(reference.cid.starts_with('b'));
+        assert_eq!(reference, ContentRef::of(content));
+        assert!(reference.verify(content).is_ok());
+        assert_eq!(
+            reference.verify(b"something else"),
+            Err(MessageSizeError::ContentMismatch(reference.cid.clone()))
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = MessageSizeLimits {
+            max_inline_bytes: 4,
+            max_content_bytes: 8,
+        };
+        assert!(limits.check_inline("tiny").is_ok());
+        assert!(limits.needs_upload("large"));
+        assert!(matches!(
+            limits.check_inline("large"),
+            Err(MessageSizeError::InlineTooLarge { bytes: 5, limit: 4 })
+        ));
+        assert!(limits.check_content(8).is_ok());
+        assert!(limits.check_content(9).is_err());
+    }
+}
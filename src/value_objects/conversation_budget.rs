@@ -0,0 +1,143 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation budgets
+//!
+//! Limits on one conversation, set when it starts. Every posted message
+//! spends from the budget; once any limit is reached the conversation
+//! closes with a budget-exhausted status instead of running on until a
+//! provider rejects the request:
+//!
+//! ```text
+//! StartConversation { budget } ──> Conversation { budget, usage }
+//!                                        │
+//!   PostMessage { tokens } ──> MessagePosted ──> usage += (tokens, 1)
+//!                                        │ limit reached
+//!                                        v
+//!               ConversationClosed { reason: BudgetExhausted, exhausted }
+//! ```
+//!
+//! The wall-clock limit is also enforced while nothing is posted; the
+//! inactivity timers close conversations whose time has run out.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Which limit of a conversation budget ran out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    /// Total tokens spent by the conversation's messages
+    Tokens,
+    /// Number of messages posted
+    Messages,
+    /// Time since the conversation started
+    WallClock,
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Tokens => write!(f, "tokens"),
+            BudgetLimit::Messages => write!(f, "messages"),
+            BudgetLimit::WallClock => write!(f, "wall-clock"),
+        }
+    }
+}
+
+/// Limits on a single conversation; unset limits do not apply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationBudget {
+    /// Most tokens the conversation's messages may spend in total
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+
+    /// Most messages that may be posted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_messages: Option<u32>,
+
+    /// Longest the conversation may stay open, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_secs: Option<u64>,
+}
+
+impl ConversationBudget {
+    /// A budget without limits
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Builder: cap the tokens spent
+    pub fn with_max_tokens(mut self, max_tokens: u64) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Builder: cap the messages posted
+    pub fn with_max_messages(mut self, max_messages: u32) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    /// Builder: cap how long the conversation stays open
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration_secs = Some(max_duration.as_secs());
+        self
+    }
+
+    /// Whether no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Longest the conversation may stay open
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration_secs.map(Duration::from_secs)
+    }
+
+    /// The first limit reached by this usage, if any
+    ///
+    /// A limit is reached once usage meets it: a conversation capped at ten
+    /// messages closes with the tenth.
+    pub fn exhausted(&self, tokens: u64, messages: u32, elapsed: Duration) -> Option<BudgetLimit> {
+        let reached = |limit: Option<u64>, used: u64| limit.is_some_and(|limit| used >= limit);
+        if reached(self.max_duration_secs, elapsed.as_secs()) {
+            Some(BudgetLimit::WallClock)
+        } else if reached(self.max_tokens, tokens) {
+            Some(BudgetLimit::Tokens)
+        } else if reached(self.max_messages.map(u64::from), u64::from(messages)) {
+            Some(BudgetLimit::Messages)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_exhausted_by_first_limit_reached() {
+        let budget = ConversationBudget::unlimited()
+            .with_max_tokens(1_000)
+            .with_max_messages(4)
+            .with_max_duration(Duration::from_secs(60));
+        let second = Duration::from_secs(1);
+
+        assert_eq!(budget.exhausted(999, 3, second), None);
+        assert_eq!(
+            budget.exhausted(1_000, 3, second),
+            Some(BudgetLimit::Tokens)
+        );
+        assert_eq!(budget.exhausted(10, 4, second), Some(BudgetLimit::Messages));
+        assert_eq!(
+            budget.exhausted(10, 1, Duration::from_secs(60)),
+            Some(BudgetLimit::WallClock)
+        );
+        assert_eq!(
+            ConversationBudget::unlimited().exhausted(u64::MAX, 9, second),
+            None
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation handoff
+//!
+//! What one agent passes to the next when a conversation is transferred
+//! between them: why, the recent turns, and what it learned along the way.
+//! The receiving agent opens with it as context:
+//!
+//! ```text
+//! triage agent ──> ConversationHandoff { reason, context, memory, transcript }
+//!                                │
+//!                      to_context()
+//!                                v
+//! specialist: [system: handoff note + memory] + recent turns + new message
+//! ```
+//!
+//! Turns that do not fit the receiving model stay reachable through the
+//! `transcript`, uploaded to the blob store.
+
+use super::{ContentRef, ContextMessage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Context handed from one agent to another with a conversation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationHandoff {
+    /// Why the conversation is moving, e.g. "billing dispute needs a specialist"
+    pub reason: String,
+
+    /// The most recent turns, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<ContextMessage>,
+
+    /// Facts the previous agent gathered, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub memory: BTreeMap<String, String>,
+
+    /// The full context, uploaded when it did not fit inline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<ContentRef>,
+}
+
+impl ConversationHandoff {
+    /// A handoff for `reason` with no context yet
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Builder: the turns to carry over
+    pub fn with_context(mut self, context: Vec<ContextMessage>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Builder: remember `key = value` for the next agent
+    pub fn with_memory(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.memory.insert(key.into(), value.into());
+        self
+    }
+
+    /// Builder: the full context was uploaded as `transcript`
+    pub fn with_transcript(mut self, transcript: ContentRef) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
+    /// Context the receiving agent starts from
+    ///
+    /// A system note with the reason and memory, then the carried turns.
+    pub fn to_context(&self) -> Vec<ContextMessage> {
+        let mut note = format!(
+            "This conversation was handed to you by another agent: {}",
+            self.reason
+        );
+        for (key, value) in &self.memory {
+            note.push_str(&format!("\n- {}: {}", key, value));
+        }
+        std::iter::once(ContextMessage::system(note))
+            .chain(self.context.iter().cloned())
+            .collect()
+    }
+}
@@ -24,6 +24,7 @@
 //! - **Display trait**: Converts to hyphenated UUID string for subjects
 //! - **Serialization**: Works with serde for persistence
 
+use super::next_id;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
@@ -73,7 +74,7 @@ impl ConversationId {
     /// assert_ne!(conv1, conv2); // Always unique
     /// ```
     pub fn new() -> Self {
-        Self(Uuid::now_v7())
+        Self(next_id())
     }
 
     /// Create from an existing UUID
@@ -0,0 +1,155 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Cost attribution tags
+//!
+//! Free-form `key = value` labels (`project = atlas`, `team = search`) that
+//! say who a request's spend belongs to. Like a [`Deadline`](super::Deadline),
+//! tags ride on the command and are put in scope for the request, so every
+//! stage can read them without being handed them:
+//!
+//! ```text
+//! SendMessage { cost_tags } ──> MessageSent { cost_tags }
+//!        │ CostTags::scope
+//!        v
+//!   adapter: request metadata (where the provider accepts it)
+//!   ResponseCompleted { cost_tags, token_usage } ──> cost by tag
+//! ```
+//!
+//! Limits follow the strictest provider metadata rules, so tags that
+//! validate can be forwarded as they are.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+
+/// Most tags on one request
+pub const MAX_COST_TAGS: usize = 16;
+
+/// Longest tag key, in characters
+pub const MAX_COST_TAG_KEY_LENGTH: usize = 64;
+
+/// Longest tag value, in characters
+pub const MAX_COST_TAG_VALUE_LENGTH: usize = 512;
+
+tokio::task_local! {
+    static CURRENT: CostTags;
+}
+
+/// Labels attributing a request's cost
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CostTags(BTreeMap<String, String>);
+
+impl CostTags {
+    /// No tags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: tag `key` with `value`, replacing an earlier value
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.insert(key.into(), value.into());
+        self
+    }
+
+    /// Value of `key`, if tagged
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Tags in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Number of tags
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no tags
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check the tags against the count and length limits
+    pub fn validate(&self) -> Result<(), String> {
+        if self.0.len() > MAX_COST_TAGS {
+            return Err(format!("At most {} cost tags are allowed", MAX_COST_TAGS));
+        }
+        for (key, value) in &self.0 {
+            if key.is_empty() {
+                return Err("Cost tag keys cannot be empty".to_string());
+            }
+            if key.chars().count() > MAX_COST_TAG_KEY_LENGTH {
+                return Err(format!("Cost tag key too long: {}", key));
+            }
+            if value.chars().count() > MAX_COST_TAG_VALUE_LENGTH {
+                return Err(format!("Cost tag {} has a value that is too long", key));
+            }
+        }
+        Ok(())
+    }
+
+    /// These tags over `outer`: keys set here win
+    pub fn over(&self, outer: &CostTags) -> Self {
+        let mut merged = outer.0.clone();
+        merged.extend(self.0.clone());
+        Self(merged)
+    }
+
+    /// Run `future` with these tags visible through [`CostTags::current`]
+    ///
+    /// Tags already in scope are kept unless these set the same key.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        let tags = self.over(&Self::current());
+        CURRENT.scope(tags, future).await
+    }
+
+    /// Tags of the request being served by this task (empty outside a scope)
+    pub fn current() -> CostTags {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for CostTags {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_enforces_limits() {
+        assert!(CostTags::new().with("project", "atlas").validate().is_ok());
+        assert!(CostTags::new().with("", "atlas").validate().is_err());
+        assert!(CostTags::new()
+            .with("project", "x".repeat(MAX_COST_TAG_VALUE_LENGTH + 1))
+            .validate()
+            .is_err());
+        let many: CostTags = (0..=MAX_COST_TAGS).map(|i| (i.to_string(), "v")).collect();
+        assert!(many.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scope_merges_inner_over_outer() {
+        assert!(CostTags::current().is_empty());
+        let outer = CostTags::new()
+            .with("team", "search")
+            .with("project", "atlas");
+        let inner = CostTags::new().with("project", "zephyr");
+
+        let seen = outer
+            .scope(inner.scope(async { CostTags::current() }))
+            .await;
+        assert_eq!(seen.get("team"), Some("search"));
+        assert_eq!(seen.get("project"), Some("zephyr"));
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Deadline value object
+//!
+//! An absolute point in time by which a caller needs an answer. Being
+//! absolute, it survives being serialized onto a command and forwarded:
+//! every stage spends from the same budget instead of starting its own
+//! timer.
+//!
+//! ```text
+//! caller: 30s ──> SendMessage { deadline } ──> AgentMessageService
+//!                                                  │ Deadline::scope
+//!                                                  v
+//!                                 adapter: HTTP timeout = remaining()
+//!                                 stream:  ends at expires_at
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+tokio::task_local! {
+    static CURRENT: Deadline;
+}
+
+/// Point in time by which a request must finish
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Deadline {
+    /// When the caller stops waiting
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Deadline {
+    /// Deadline at an absolute time
+    pub fn at(expires_at: DateTime<Utc>) -> Self {
+        Self { expires_at }
+    }
+
+    /// Deadline `budget` from now
+    pub fn after(budget: Duration) -> Self {
+        let budget = chrono::Duration::from_std(budget).unwrap_or(chrono::Duration::MAX);
+        Self {
+            expires_at: Utc::now().checked_add_signed(budget).unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+
+    /// Time left, zero once expired
+    pub fn remaining(&self) -> Duration {
+        (self.expires_at - Utc::now()).to_std().unwrap_or(Duration::ZERO)
+    }
+
+    /// Whether the deadline has passed
+    pub fn is_expired(&self) -> bool {
+        Utc::now() >= self.expires_at
+    }
+
+    /// The tighter of two deadlines
+    pub fn earliest(self, other: Option<Deadline>) -> Self {
+        other.map_or(self, |other| self.min(other))
+    }
+
+    /// Run `future` with this deadline visible through [`Deadline::current`]
+    ///
+    /// A deadline already in scope that expires sooner stays in effect.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self.earliest(Self::current()), future).await
+    }
+
+    /// Deadline of the request being served by this task, if any
+    pub fn current() -> Option<Deadline> {
+        CURRENT.try_with(|deadline| *deadline).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_saturates_at_zero() {
+        let soon = Deadline::after(Duration::from_secs(30));
+        assert!(!soon.is_expired());
+        assert!(soon.remaining() > Duration::from_secs(29));
+
+        let past = Deadline::at(Utc::now() - chrono::Duration::seconds(1));
+        assert!(past.is_expired());
+        assert_eq!(past.remaining(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_scope_keeps_the_tighter_deadline() {
+        assert!(Deadline::current().is_none());
+        let outer = Deadline::after(Duration::from_secs(5));
+        let inner = Deadline::after(Duration::from_secs(60));
+
+        let seen = outer.scope(inner.scope(async { Deadline::current() })).await;
+        assert_eq!(seen, Some(outer));
+    }
+}
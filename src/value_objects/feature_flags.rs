@@ -0,0 +1,134 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Per-agent feature flags
+//!
+//! Runtime toggles for optional behavior. An agent stores only the flags
+//! that were set explicitly; everything else falls back to the default for
+//! the flag, so new flags can ship without touching existing agents:
+//!
+//! ```text
+//! definition front-matter `features:` ──┐
+//! UpdateConfiguration { feature_flags } ─┴─> FeatureFlagsChanged ──> Agent
+//!                                                                       │
+//!                          services: agent.feature_flags().is_enabled(..)
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Explicitly set feature flags, over per-flag defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureFlags {
+    overrides: BTreeMap<String, bool>,
+}
+
+impl FeatureFlags {
+    /// Pass documents retrieved into the context on to the model
+    pub const ENABLE_RAG: &'static str = "enable_rag";
+
+    /// Reject, rather than annotate, output that trips a guardrail
+    pub const GUARDRAILS_STRICT: &'static str = "guardrails_strict";
+
+    /// Switch to the long-context model when a prompt outgrows the window
+    pub const LONG_CONTEXT_FALLBACK: &'static str = "long_context_fallback";
+
+    /// Answer mirrored traffic only and never deliver the responses
+    pub const SHADOW_MODE: &'static str = "shadow_mode";
+
+    /// Flags this crate knows, with their defaults
+    pub const KNOWN: [(&'static str, bool); 4] = [
+        (Self::ENABLE_RAG, true),
+        (Self::GUARDRAILS_STRICT, false),
+        (Self::LONG_CONTEXT_FALLBACK, true),
+        (Self::SHADOW_MODE, false),
+    ];
+
+    /// No flags set; every flag has its default
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default for `flag`; unknown flags are off
+    pub fn default_for(flag: &str) -> bool {
+        Self::KNOWN
+            .iter()
+            .find(|(name, _)| *name == flag)
+            .is_some_and(|(_, enabled)| *enabled)
+    }
+
+    /// Builder: set `flag`
+    pub fn with(mut self, flag: impl Into<String>, enabled: bool) -> Self {
+        self.overrides.insert(flag.into(), enabled);
+        self
+    }
+
+    /// Whether `flag` is on for this agent
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        self.overrides
+            .get(flag)
+            .copied()
+            .unwrap_or_else(|| Self::default_for(flag))
+    }
+
+    /// Flags set explicitly
+    pub fn overrides(&self) -> &BTreeMap<String, bool> {
+        &self.overrides
+    }
+
+    /// Whether no flag is set explicitly
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// The subset of `updates` that would change an effective value
+    pub fn changes(&self, updates: &BTreeMap<String, bool>) -> BTreeMap<String, bool> {
+        updates
+            .iter()
+            .filter(|(flag, enabled)| self.is_enabled(flag) != **enabled)
+            .map(|(flag, enabled)| (flag.clone(), *enabled))
+            .collect()
+    }
+
+    /// Set every flag in `updates`
+    pub fn apply(&mut self, updates: &BTreeMap<String, bool>) {
+        self.overrides
+            .extend(updates.iter().map(|(flag, enabled)| (flag.clone(), *enabled)));
+    }
+
+    /// Check a flag name: lowercase ASCII letters, digits and underscores
+    pub fn validate_name(flag: &str) -> Result<(), String> {
+        let valid = flag.starts_with(|c: char| c.is_ascii_lowercase())
+            && flag
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid feature flag name: '{}'", flag))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_fall_back_to_defaults() {
+        let flags = FeatureFlags::new().with(FeatureFlags::ENABLE_RAG, false);
+        assert!(!flags.is_enabled(FeatureFlags::ENABLE_RAG));
+        assert!(!flags.is_enabled(FeatureFlags::SHADOW_MODE));
+        assert!(!flags.is_enabled("not_a_flag"));
+
+        let updates = BTreeMap::from([
+            (FeatureFlags::ENABLE_RAG.to_string(), false),
+            (FeatureFlags::GUARDRAILS_STRICT.to_string(), true),
+        ]);
+        let changes = flags.changes(&updates);
+        assert_eq!(changes.keys().collect::<Vec<_>>(), [FeatureFlags::GUARDRAILS_STRICT]);
+
+        assert!(FeatureFlags::validate_name("enable_rag").is_ok());
+        assert!(FeatureFlags::validate_name("Enable-RAG").is_err());
+    }
+}
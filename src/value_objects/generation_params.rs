@@ -0,0 +1,394 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Generation parameters value object
+//!
+//! Typed sampling parameters (temperature, top_p, top_k, ...) that can be
+//! stored on an agent's model configuration and overridden per intent.
+//!
+//! Every field is optional: an unset field means "inherit from the layer
+//! below". Layers are combined with [`GenerationParams::overlay`]:
+//!
+//! ```text
+//! ModelConfig flat fields
+//!   └─ overlay(agent generation params / preset)
+//!        └─ overlay(per-intent override, e.g. "completion")
+//!             └─ overlay(call-site override)
+//! ```
+//!
+//! Adapters never read these names directly; they ask for
+//! [`GenerationParams::provider_parameters`], which translates to each
+//! provider's wire names (e.g. `max_tokens` → `num_predict` for Ollama) and
+//! drops parameters the provider does not accept.
+
+use super::{ModelConfig, ProviderType};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Named generation presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GenerationPreset {
+    /// High diversity for brainstorming and creative writing
+    Creative,
+    /// Low temperature for factual, focused answers
+    Precise,
+    /// Greedy decoding with a fixed seed for reproducible output
+    Deterministic,
+}
+
+impl GenerationPreset {
+    /// Get the parameters for this preset
+    pub fn params(&self) -> GenerationParams {
+        match self {
+            GenerationPreset::Creative => GenerationParams::creative(),
+            GenerationPreset::Precise => GenerationParams::precise(),
+            GenerationPreset::Deterministic => GenerationParams::deterministic(),
+        }
+    }
+
+    /// Preset name as used in configuration files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GenerationPreset::Creative => "creative",
+            GenerationPreset::Precise => "precise",
+            GenerationPreset::Deterministic => "deterministic",
+        }
+    }
+}
+
+impl std::str::FromStr for GenerationPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "creative" => Ok(GenerationPreset::Creative),
+            "precise" => Ok(GenerationPreset::Precise),
+            "deterministic" => Ok(GenerationPreset::Deterministic),
+            other => Err(format!("Unknown generation preset: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for GenerationPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Sampling parameters for a single generation
+///
+/// `temperature` and `top_p` are mutually exclusive: providers recommend
+/// tuning one or the other, and some reject requests that set both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    /// Sampling temperature (0.0 - 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Nucleus sampling mass (0.0 - 1.0], exclusive with temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// Sample from the k most likely tokens (>= 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+
+    /// Maximum tokens to generate (>= 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+
+    /// Frequency penalty (-2.0 - 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+
+    /// Presence penalty (-2.0 - 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+
+    /// Seed for reproducible sampling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl GenerationParams {
+    /// Create empty parameters (inherit everything)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Preset: diverse, exploratory output
+    pub fn creative() -> Self {
+        Self {
+            temperature: Some(1.1),
+            presence_penalty: Some(0.6),
+            frequency_penalty: Some(0.3),
+            ..Self::default()
+        }
+    }
+
+    /// Preset: focused, factual output
+    pub fn precise() -> Self {
+        Self {
+            temperature: Some(0.2),
+            ..Self::default()
+        }
+    }
+
+    /// Preset: greedy decoding with a fixed seed
+    pub fn deterministic() -> Self {
+        Self {
+            temperature: Some(0.0),
+            top_k: Some(1),
+            seed: Some(0),
+            ..Self::default()
+        }
+    }
+
+    /// Extract the baseline parameters from a model configuration's flat fields
+    ///
+    /// Temperature and top_p are exclusive and the flat temperature is always
+    /// set, so the flat top_p is left out; nucleus sampling is chosen by
+    /// setting top_p in `generation`, which clears the temperature.
+    pub fn from_model_config(config: &ModelConfig) -> Self {
+        Self {
+            temperature: Some(config.temperature),
+            top_p: None,
+            top_k: None,
+            max_tokens: Some(config.max_tokens),
+            frequency_penalty: Some(config.frequency_penalty),
+            presence_penalty: Some(config.presence_penalty),
+            seed: None,
+        }
+    }
+
+    /// Builder: set temperature
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Builder: set top_p
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Builder: set top_k
+    pub fn with_top_k(mut self, top_k: u32) -> Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
+    /// Builder: set max_tokens
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Builder: set frequency_penalty
+    pub fn with_frequency_penalty(mut self, penalty: f32) -> Self {
+        self.frequency_penalty = Some(penalty);
+        self
+    }
+
+    /// Builder: set presence_penalty
+    pub fn with_presence_penalty(mut self, penalty: f32) -> Self {
+        self.presence_penalty = Some(penalty);
+        self
+    }
+
+    /// Builder: set seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Check if no parameter is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Layer `other` on top of `self`
+    ///
+    /// Fields set in `other` win. Because temperature and top_p are
+    /// exclusive, setting one of them in `other` clears the other one
+    /// inherited from `self`.
+    pub fn overlay(&self, other: &GenerationParams) -> GenerationParams {
+        let (temperature, top_p) = match (other.temperature, other.top_p) {
+            (None, None) => (self.temperature, self.top_p),
+            (temperature, top_p) => (temperature, top_p),
+        };
+
+        GenerationParams {
+            temperature,
+            top_p,
+            top_k: other.top_k.or(self.top_k),
+            max_tokens: other.max_tokens.or(self.max_tokens),
+            frequency_penalty: other.frequency_penalty.or(self.frequency_penalty),
+            presence_penalty: other.presence_penalty.or(self.presence_penalty),
+            seed: other.seed.or(self.seed),
+        }
+    }
+
+    /// Validate ranges and mutually exclusive fields
+    pub fn validate(&self) -> Result<(), String> {
+        if self.temperature.is_some() && self.top_p.is_some() {
+            return Err("temperature and top_p are mutually exclusive".to_string());
+        }
+
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(format!(
+                    "Temperature must be between 0.0 and 2.0, got {}",
+                    temperature
+                ));
+            }
+        }
+
+        if let Some(top_p) = self.top_p {
+            if top_p <= 0.0 || top_p > 1.0 {
+                return Err(format!("Top-p must be in (0.0, 1.0], got {}", top_p));
+            }
+        }
+
+        if self.top_k == Some(0) {
+            return Err("Top-k must be at least 1".to_string());
+        }
+
+        if self.max_tokens == Some(0) {
+            return Err("Max tokens must be at least 1".to_string());
+        }
+
+        for (name, value) in [
+            ("Frequency penalty", self.frequency_penalty),
+            ("Presence penalty", self.presence_penalty),
+        ] {
+            if let Some(value) = value {
+                if !(-2.0..=2.0).contains(&value) {
+                    return Err(format!(
+                        "{} must be between -2.0 and 2.0, got {}",
+                        name, value
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Translate to the provider's parameter names
+    ///
+    /// Parameters the provider does not support are omitted rather than
+    /// sent and rejected.
+    pub fn provider_parameters(&self, provider: ProviderType) -> Map<String, Value> {
+        let mut params = Map::new();
+        let mut put = |name: &str, value: Option<Value>| {
+            if let Some(value) = value {
+                params.insert(name.to_string(), value);
+            }
+        };
+
+        put("temperature", self.temperature.map(Value::from));
+        put("top_p", self.top_p.map(Value::from));
+
+        match provider {
+            ProviderType::Ollama => {
+                put("top_k", self.top_k.map(Value::from));
+                put("num_predict", self.max_tokens.map(Value::from));
+                put("frequency_penalty", self.frequency_penalty.map(Value::from));
+                put("presence_penalty", self.presence_penalty.map(Value::from));
+                put("seed", self.seed.map(Value::from));
+            }
+            ProviderType::Anthropic => {
+                put("top_k", self.top_k.map(Value::from));
+                put("max_tokens", self.max_tokens.map(Value::from));
+            }
+            ProviderType::Gemini => {
+                put("top_k", self.top_k.map(Value::from));
+                put("max_output_tokens", self.max_tokens.map(Value::from));
+                put("frequency_penalty", self.frequency_penalty.map(Value::from));
+                put("presence_penalty", self.presence_penalty.map(Value::from));
+                put("seed", self.seed.map(Value::from));
+            }
+            ProviderType::OpenAI | ProviderType::Mock => {
+                put("max_tokens", self.max_tokens.map(Value::from));
+                put("frequency_penalty", self.frequency_penalty.map(Value::from));
+                put("presence_penalty", self.presence_penalty.map(Value::from));
+                put("seed", self.seed.map(Value::from));
+            }
+        }
+
+        params
+    }
+}
+
+impl From<GenerationPreset> for GenerationParams {
+    fn from(preset: GenerationPreset) -> Self {
+        preset.params()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in [
+            GenerationPreset::Creative,
+            GenerationPreset::Precise,
+            GenerationPreset::Deterministic,
+        ] {
+            assert!(preset.params().validate().is_ok(), "{} invalid", preset);
+            assert_eq!(preset.as_str().parse::<GenerationPreset>().unwrap(), preset);
+        }
+    }
+
+    #[test]
+    fn test_validation_ranges_and_exclusivity() {
+        assert!(GenerationParams::new().with_temperature(2.5).validate().is_err());
+        assert!(GenerationParams::new().with_top_p(0.0).validate().is_err());
+        assert!(GenerationParams::new().with_top_k(0).validate().is_err());
+        assert!(GenerationParams::new().with_presence_penalty(-3.0).validate().is_err());
+
+        let both = GenerationParams::new().with_temperature(0.5).with_top_p(0.9);
+        assert!(both.validate().unwrap_err().contains("mutually exclusive"));
+    }
+
+    #[test]
+    fn test_overlay_replaces_exclusive_sampling_field() {
+        let base = GenerationParams::precise().with_max_tokens(512);
+        let override_params = GenerationParams::new().with_top_p(0.8);
+
+        let merged = base.overlay(&override_params);
+
+        assert_eq!(merged.temperature, None);
+        assert_eq!(merged.top_p, Some(0.8));
+        assert_eq!(merged.max_tokens, Some(512));
+    }
+
+    #[test]
+    fn test_provider_parameter_names() {
+        let params = GenerationParams::deterministic()
+            .with_max_tokens(100)
+            .with_frequency_penalty(0.5);
+
+        let ollama = params.provider_parameters(ProviderType::Ollama);
+        assert_eq!(ollama["num_predict"], Value::from(100));
+        assert!(ollama.contains_key("seed"));
+
+        let anthropic = params.provider_parameters(ProviderType::Anthropic);
+        assert_eq!(anthropic["max_tokens"], Value::from(100));
+        assert!(!anthropic.contains_key("frequency_penalty"));
+        assert!(!anthropic.contains_key("seed"));
+
+        let openai = params.provider_parameters(ProviderType::OpenAI);
+        assert!(!openai.contains_key("top_k"));
+    }
+
+    #[test]
+    fn test_serialization_skips_unset_fields() {
+        let json = serde_json::to_string(&GenerationParams::precise()).unwrap();
+        assert_eq!(json, r#"{"temperature":0.2}"#);
+    }
+}
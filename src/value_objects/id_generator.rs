@@ -0,0 +1,125 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! ID generation strategy
+//!
+//! Identifier constructors (`AgentId::new`, `MessageId::new`, ...) and the
+//! services that mint correlation or negotiation IDs take their UUIDs from
+//! [`next_id`]. Outside a generator scope that is a fresh UUID v7; tests and
+//! simulations install a seeded generator to get the same IDs on every run:
+//!
+//! ```text
+//! with_id_generator(SeededIdGenerator::new(42), run())
+//!        │
+//!        v
+//! MessageId::new() ──> next_id() ──> 0194.. (same seed, same sequence)
+//! ```
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use uuid::{Builder, Uuid};
+
+tokio::task_local! {
+    static CURRENT: Arc<dyn IdGenerator>;
+}
+
+/// Source of new identifiers
+pub trait IdGenerator: Debug + Send + Sync {
+    /// The next identifier
+    fn next_uuid(&self) -> Uuid;
+}
+
+/// Time-ordered random UUID v7, the production default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV7Generator;
+
+impl IdGenerator for UuidV7Generator {
+    fn next_uuid(&self) -> Uuid {
+        Uuid::now_v7()
+    }
+}
+
+/// Timestamp of the first seeded ID (2025-01-01T00:00:00Z), in milliseconds
+const SEEDED_EPOCH_MILLIS: u64 = 1_735_689_600_000;
+
+/// Deterministic generator for tests and simulations
+///
+/// Produces valid UUID v7 values whose timestamps advance one millisecond
+/// per ID from a fixed epoch, so they still sort in creation order. The
+/// random bits come from the seed: equal seeds yield equal sequences.
+#[derive(Debug)]
+pub struct SeededIdGenerator {
+    seed: u64,
+    counter: AtomicU64,
+}
+
+impl SeededIdGenerator {
+    /// Create a generator for `seed`
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            counter: AtomicU64::new(0),
+        }
+    }
+}
+
+impl IdGenerator for SeededIdGenerator {
+    fn next_uuid(&self) -> Uuid {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        let high = splitmix64(self.seed ^ n.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let low = splitmix64(high);
+
+        let mut random = [0u8; 10];
+        random[..8].copy_from_slice(&high.to_be_bytes());
+        random[8..].copy_from_slice(&low.to_be_bytes()[..2]);
+        Builder::from_unix_timestamp_millis(SEEDED_EPOCH_MILLIS + n, &random).into_uuid()
+    }
+}
+
+/// SplitMix64 finalizer, enough to spread a seed over 64 bits
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Next identifier from the generator in scope, or a fresh UUID v7
+pub fn next_id() -> Uuid {
+    CURRENT
+        .try_with(|generator| generator.next_uuid())
+        .unwrap_or_else(|_| Uuid::now_v7())
+}
+
+/// Run `future` with `generator` answering [`next_id`]
+pub async fn with_id_generator<F: Future>(generator: Arc<dyn IdGenerator>, future: F) -> F::Output {
+    CURRENT.scope(generator, future).await
+}
+
+/// Run `f` with `generator` answering [`next_id`], for synchronous callers
+pub fn with_id_generator_sync<R>(generator: Arc<dyn IdGenerator>, f: impl FnOnce() -> R) -> R {
+    CURRENT.sync_scope(generator, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::{AgentId, MessageId};
+
+    #[test]
+    fn test_seeded_generator_repeats_and_stays_ordered() {
+        let run = |seed| {
+            with_id_generator_sync(Arc::new(SeededIdGenerator::new(seed)), || {
+                (AgentId::new(), MessageId::new(), next_id())
+            })
+        };
+
+        let (agent, message, third) = run(42);
+        assert_eq!(run(42), (agent, message, third));
+        assert_ne!(run(7).0, agent);
+
+        assert_eq!(agent.as_uuid().get_version_num(), 7);
+        assert!(agent.as_uuid() < message.as_uuid() && message.as_uuid() < &third);
+    }
+}
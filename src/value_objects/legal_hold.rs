@@ -0,0 +1,55 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Legal holds
+//!
+//! Compliance can freeze an agent's data. While a [`LegalHold`] is in place
+//! nothing may archive, crypto-shred or age out the agent's streams; each
+//! attempt is refused and the refusal recorded:
+//!
+//! ```text
+//! PlaceLegalHold ──> LegalHoldPlaced ──> Agent { legal_hold: Some(..) }
+//!                                              │
+//!     archival / crypto-shredding / retention ─┴─> LegalHoldRefused { operation }
+//!                                              │
+//! ReleaseLegalHold ──> LegalHoldReleased ──> Agent { legal_hold: None }
+//! ```
+
+use super::PersonId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A legal hold on an agent's data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegalHold {
+    /// Why the data is held (matter or ticket reference)
+    pub reason: String,
+
+    /// Who placed the hold
+    pub placed_by: PersonId,
+
+    /// When the hold was placed
+    pub placed_at: DateTime<Utc>,
+}
+
+/// Operations a legal hold forbids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeldOperation {
+    /// Moving conversations to the archival store
+    Archival,
+    /// Destroying the keys that decrypt the agent's data
+    CryptoShredding,
+    /// Ageing data out under a retention policy
+    Retention,
+}
+
+impl fmt::Display for HeldOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeldOperation::Archival => write!(f, "archival"),
+            HeldOperation::CryptoShredding => write!(f, "crypto-shredding"),
+            HeldOperation::Retention => write!(f, "retention"),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Locale and timezone preferences
+//!
+//! An agent that serves people in one place should speak their calendar:
+//! the context pipeline tells the model the current local time and how
+//! numbers and dates are written there, and rendered transcripts show
+//! timestamps the same way:
+//!
+//! ```text
+//! agent:
+//!   locale: de-DE
+//!   timezone: Europe/Berlin
+//!
+//! LocalePreferences ──> context_note(now)  "Current local time: Freitag ..."
+//!                  └──> format_datetime     16.10.2026 18:30
+//!                  └──> format_number       1.234,5
+//! ```
+//!
+//! Conventions are keyed by language and region and cover the common
+//! locales; anything else falls back to ISO 8601 dates and `1,234.5`.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// Locale used when none is configured
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// How numbers, dates and times are written in a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Conventions {
+    decimal: char,
+    group: char,
+    date: &'static str,
+    time: &'static str,
+}
+
+impl Conventions {
+    fn for_locale(locale: &str) -> Self {
+        let mut subtags = locale.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let region = subtags
+            .find(|s| s.len() == 2)
+            .map(|s| s.to_ascii_uppercase())
+            .unwrap_or_default();
+
+        let (decimal, group) = match language.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" => (',', '.'),
+            "fr" | "ru" | "pl" | "cs" | "sv" | "fi" | "nb" | "uk" => (',', '\u{a0}'),
+            _ => ('.', ','),
+        };
+        let date = match (language.as_str(), region.as_str()) {
+            ("en", "US" | "") => "%m/%d/%Y",
+            ("en", _) | ("fr" | "es" | "it" | "pt", _) => "%d/%m/%Y",
+            ("de" | "da" | "tr" | "ru" | "pl" | "cs" | "fi" | "nb" | "uk", _) => "%d.%m.%Y",
+            ("nl", _) => "%d-%m-%Y",
+            ("ja" | "zh", _) => "%Y/%m/%d",
+            _ => "%Y-%m-%d",
+        };
+        let time = match (language.as_str(), region.as_str()) {
+            ("en", "US" | "") => "%-I:%M %p",
+            _ => "%H:%M",
+        };
+        Self {
+            decimal,
+            group,
+            date,
+            time,
+        }
+    }
+}
+
+/// Where an agent's users are and how they write numbers and dates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalePreferences {
+    /// BCP 47 language tag, e.g. `en-US` or `de-DE`
+    #[serde(default = "default_locale")]
+    pub locale: String,
+
+    /// IANA timezone, e.g. `Europe/Berlin`
+    #[serde(default = "default_timezone")]
+    pub timezone: Tz,
+}
+
+fn default_locale() -> String {
+    DEFAULT_LOCALE.to_string()
+}
+
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+impl Default for LocalePreferences {
+    fn default() -> Self {
+        Self {
+            locale: default_locale(),
+            timezone: default_timezone(),
+        }
+    }
+}
+
+impl LocalePreferences {
+    /// Preferences for `locale` in `timezone`
+    pub fn new(locale: impl Into<String>, timezone: Tz) -> Self {
+        Self {
+            locale: locale.into(),
+            timezone,
+        }
+    }
+
+    /// Parse an IANA timezone name
+    pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+        name.parse::<Tz>()
+            .map_err(|_| format!("Unknown timezone '{}'", name))
+    }
+
+    /// Check the locale is a well-formed language tag
+    pub fn validate(&self) -> Result<(), String> {
+        let mut subtags = self.locale.split('-');
+        let language = subtags.next().unwrap_or_default();
+        let language_ok =
+            (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic());
+        let rest_ok = subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()));
+        if language_ok && rest_ok {
+            Ok(())
+        } else {
+            Err(format!("Invalid locale '{}': expected a tag like en-US", self.locale))
+        }
+    }
+
+    /// `at` in the preferred timezone
+    pub fn local(&self, at: DateTime<Utc>) -> DateTime<Tz> {
+        at.with_timezone(&self.timezone)
+    }
+
+    /// Local calendar date, e.g. `16.10.2026`
+    pub fn format_date(&self, at: DateTime<Utc>) -> String {
+        let conventions = Conventions::for_locale(&self.locale);
+        self.local(at).format(conventions.date).to_string()
+    }
+
+    /// Local date and time, e.g. `16.10.2026 18:30`
+    pub fn format_datetime(&self, at: DateTime<Utc>) -> String {
+        let conventions = Conventions::for_locale(&self.locale);
+        let format = format!("{} {}", conventions.date, conventions.time);
+        self.local(at).format(&format).to_string()
+    }
+
+    /// `value` with `decimals` places and the locale's separators, e.g. `1.234,50`
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let conventions = Conventions::for_locale(&self.locale);
+        let fixed = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+        let mut grouped = String::new();
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push(conventions.group);
+            }
+            grouped.push(digit);
+        }
+        if value.is_sign_negative() && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            grouped.insert(0, '-');
+        }
+        if !fraction.is_empty() {
+            grouped.push(conventions.decimal);
+            grouped.push_str(fraction);
+        }
+        grouped
+    }
+
+    /// System note giving the model the local time and formatting conventions
+    pub fn context_note(&self, now: DateTime<Utc>) -> String {
+        let local = self.local(now);
+        format!(
+            "Current local time: {} {} ({}, UTC{}). Locale: {}. Write dates like {} and \
+             numbers like {}.",
+            local.format("%A"),
+            self.format_datetime(now),
+            self.timezone.name(),
+            local.format("%:z"),
+            self.locale,
+            self.format_date(now),
+            self.format_number(1234.5, 1)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_formats_follow_locale_and_timezone() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 16, 16, 30, 0).unwrap();
+
+        let berlin = LocalePreferences::new("de-DE", chrono_tz::Europe::Berlin);
+        assert_eq!(berlin.format_datetime(at), "16.10.2026 18:30");
+        assert_eq!(berlin.format_number(1234567.891, 2), "1.234.567,89");
+
+        let us = LocalePreferences::default();
+        assert_eq!(us.format_datetime(at), "10/16/2026 4:30 PM");
+        assert_eq!(us.format_number(-1234.5, 1), "-1,234.5");
+        assert!(us.context_note(at).contains("(UTC, UTC+00:00)"));
+    }
+
+    #[test]
+    fn test_preferences_parse_and_validate() {
+        let prefs: LocalePreferences =
+            serde_json::from_str(r#"{"locale": "fr-FR", "timezone": "Europe/Paris"}"#).unwrap();
+        assert_eq!(prefs.timezone, chrono_tz::Europe::Paris);
+        assert!(prefs.validate().is_ok());
+
+        assert!(LocalePreferences::new("english!", Tz::UTC).validate().is_err());
+        assert!(LocalePreferences::parse_timezone("Mars/Olympus").is_err());
+    }
+}
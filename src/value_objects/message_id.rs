@@ -4,6 +4,7 @@
 //!
 //! Unique identifier for tracking request/response pairs in agent dialogs.
 
+use super::next_id;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
@@ -19,7 +20,7 @@ pub struct MessageId(Uuid);
 impl MessageId {
     /// Create a new Message ID with UUID v7 (time-ordered)
     pub fn new() -> Self {
-        Self(Uuid::now_v7())
+        Self(next_id())
     }
 
     /// Create a Message ID from an existing UUID
@@ -0,0 +1,56 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Message provenance
+//!
+//! Where a conversation message came from. Messages written by agents this
+//! host runs are internal; messages an outside system posts while acting
+//! as an agent arrive through the webhook ingress and are marked external:
+//!
+//! ```text
+//! agent on this host ──────────────────────────> PostMessage { Internal }
+//! external system ──> signed webhook ──> verified ──> PostMessage { External }
+//! ```
+//!
+//! Consumers can then tell what this host's models generated from what an
+//! outside system claims, e.g. to show it differently or audit it.
+
+use super::ContentRef;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a conversation message came from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MessageProvenance {
+    /// Written by an agent this host runs
+    #[default]
+    Internal,
+
+    /// Posted by an external system through the webhook ingress
+    External {
+        /// The external system, as registered with its signing secret
+        system: String,
+
+        /// The system's identifier for the delivery
+        delivery_id: String,
+
+        /// The message content, uploaded on receipt
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<ContentRef>,
+
+        /// When the delivery's signature was verified
+        verified_at: DateTime<Utc>,
+    },
+}
+
+impl MessageProvenance {
+    /// Whether the message was written by an agent this host runs
+    pub fn is_internal(&self) -> bool {
+        matches!(self, MessageProvenance::Internal)
+    }
+
+    /// Whether an external system generated the message
+    pub fn is_external(&self) -> bool {
+        !self.is_internal()
+    }
+}
@@ -13,8 +13,27 @@
 //! - `AgentStatus` - Agent lifecycle state
 //! - `ConfigurationStatus` - Model configuration lifecycle state
 //! - `ModelConfig` - Full AI model configuration (runtime)
+//! - `GenerationParams` - Typed sampling parameters with presets and overrides
 //! - `ModelConstraints` - Model capability constraints
 //! - `StreamingChunk` - Partial response from model
+//! - `Deadline` - Absolute time budget propagated through the message pipeline
+//! - `Clock` - Time source for event timestamps (system or fixed)
+//! - `IdGenerator` - Source of new identifiers (UUID v7 or seeded)
+//! - `FeatureFlags` - Per-agent runtime toggles with defaults
+//! - `TruncationPolicy` - Ordered strategies for shrinking an oversized context
+//! - `BestOfN` - Per-intent parallel sampling with a judge picking the answer
+//! - `ReflectionConfig` - Bounded self-critique and revision before answering
+//! - `OutputConstraints` - Rules answers must follow, enforced by regenerating them
+//! - `ReasoningTrace` - Tree of decisions behind one answer, for debugging
+//! - `LocalePreferences` - Agent's locale and timezone for prompts and rendering
+//! - `Participant` - Conversation member with a role deciding what they may do
+//! - `ConversationBudget` - Token, message and wall-clock limits on one conversation
+//! - `ConversationHandoff` - Reason, recent turns and memory passed along with a conversation
+//! - `MessageProvenance` - Whether a message came from this host or an external system
+//! - `ContentRef` - CID of message content uploaded out of band, with size limits
+//! - `ToxicityScore` - Moderation score of a message, judged against thresholds
+//! - `LegalHold` - Compliance freeze on archiving or deleting an agent's data
+//! - `PrincipalPermissions` - What the owner, operators and the agent itself may do
 
 mod agent_id;
 mod person_id;
@@ -26,8 +45,29 @@ mod agent_reference;
 mod agent_status;
 mod configuration_status;
 mod model_config;
+mod generation_params;
 mod model_constraints;
 mod streaming_chunk;
+mod deadline;
+mod command_metadata;
+mod cost_tags;
+mod content_ref;
+mod participant;
+mod conversation_budget;
+mod conversation_handoff;
+mod message_provenance;
+mod toxicity;
+mod clock;
+mod id_generator;
+mod feature_flags;
+mod truncation;
+mod best_of;
+mod reflection;
+mod output_constraints;
+mod reasoning_trace;
+mod locale;
+mod legal_hold;
+mod principal;
 
 // NEW: Agent definition value objects
 // Temporarily disabled - over-engineered, being replaced
@@ -51,11 +91,84 @@ pub use configuration_status::ConfigurationStatus;
 
 // Model configuration
 pub use model_config::{ModelConfig, ProviderType};
+pub use generation_params::{GenerationParams, GenerationPreset};
 pub use model_constraints::ModelConstraints;
 
+// Runtime toggles
+pub use feature_flags::FeatureFlags;
+
+// Context truncation
+pub use truncation::{TruncationPolicy, TruncationRecord, TruncationStrategy};
+
+// Best-of-N sampling
+pub use best_of::{BestOfN, JudgeStrategy, ResponseCandidate, MAX_CANDIDATES};
+
+// Self-critique loop
+pub use reflection::{ReflectionConfig, ReflectionDraft, DEFAULT_RUBRIC};
+
+// Post-generation output constraints
+pub use output_constraints::{
+    ConstraintEnforcement, ConstraintViolation, EnforcementAction, ExhaustedAction,
+    OutputConstraint, OutputConstraints, DEFAULT_CONSTRAINT_RETRIES,
+};
+
+// Decision traces
+pub use reasoning_trace::{ReasoningTrace, TraceStep, TraceStepKind};
+
+// Locale and timezone preferences
+pub use locale::{LocalePreferences, DEFAULT_LOCALE};
+
+// Compliance holds
+pub use legal_hold::{HeldOperation, LegalHold};
+
+// Owner, operator and agent permissions
+pub use principal::{
+    acting_principal, with_principal, AgentPermission, AuthenticatedPrincipal, PermissionDenied,
+    PermissionSet, PersonMismatch, PrincipalClass, PrincipalPermissions,
+};
+
+// Request deadlines
+pub use deadline::Deadline;
+
+// Actor, tenant, causation and trace context of a command
+pub use command_metadata::{CommandMetadata, TraceContext};
+
+// Large message content
+pub use content_ref::{ContentRef, MessageSizeError, MessageSizeLimits};
+
+// Cost attribution
+pub use cost_tags::{
+    CostTags, MAX_COST_TAGS, MAX_COST_TAG_KEY_LENGTH, MAX_COST_TAG_VALUE_LENGTH,
+};
+
+// Moderation
+pub use toxicity::{ModerationVerdict, ToxicityScore, ToxicityThresholds};
+
+// Conversation participants
+pub use participant::{ConversationPermission, Participant, ParticipantId, ParticipantRole};
+
+// Conversation budgets
+pub use conversation_budget::{BudgetLimit, ConversationBudget};
+
+// Conversation transfers
+pub use conversation_handoff::ConversationHandoff;
+
+// Where conversation messages came from
+pub use message_provenance::MessageProvenance;
+
+// Time source for event timestamps
+pub use clock::{clock_now, with_clock, with_clock_sync, Clock, FixedClock, SystemClock};
+
+// Identifier generation strategy
+pub use id_generator::{
+    next_id, with_id_generator, with_id_generator_sync, IdGenerator, SeededIdGenerator,
+    UuidV7Generator,
+};
+
 // Streaming types
 pub use streaming_chunk::{
-    ContextMessage, FinishReason, MessageRole, StreamingChunk, TokenUsage,
+    ContextMessage, ContextSource, FinishReason, MessageRole, OutputEnforcement, StreamingChunk,
+    TokenUsage,
 };
 
 // Agent definition types (re-export key types for convenience)
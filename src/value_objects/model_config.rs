@@ -4,7 +4,9 @@
 //!
 //! Complete configuration for an AI model provider including all parameters.
 
+use super::{BestOfN, GenerationParams, OutputConstraints, ReflectionConfig, TruncationPolicy};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// AI model provider type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -17,6 +19,8 @@ pub enum ProviderType {
     Anthropic,
     /// Local Ollama instance
     Ollama,
+    /// Google Gemini API
+    Gemini,
     /// Mock provider for testing
     #[default]
     Mock,
@@ -29,13 +33,17 @@ impl ProviderType {
             ProviderType::OpenAI => "OpenAI",
             ProviderType::Anthropic => "Anthropic",
             ProviderType::Ollama => "Ollama",
+            ProviderType::Gemini => "Gemini",
             ProviderType::Mock => "Mock",
         }
     }
 
     /// Check if this provider requires an API key
     pub fn requires_api_key(&self) -> bool {
-        matches!(self, ProviderType::OpenAI | ProviderType::Anthropic)
+        matches!(
+            self,
+            ProviderType::OpenAI | ProviderType::Anthropic | ProviderType::Gemini
+        )
     }
 }
 
@@ -51,7 +59,7 @@ impl std::fmt::Display for ProviderType {
 /// Contains all parameters needed to configure an AI model interaction.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ModelConfig {
-    /// The provider type (OpenAI, Anthropic, Ollama, Mock)
+    /// The provider type (OpenAI, Anthropic, Ollama, Gemini, Mock)
     pub provider: ProviderType,
 
     /// Model name (e.g., "gpt-4", "claude-3-opus", "llama3")
@@ -87,6 +95,30 @@ pub struct ModelConfig {
     /// System prompt to establish agent behavior
     #[serde(default)]
     pub system_prompt: String,
+
+    /// Typed generation parameters layered over the flat sampling fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<GenerationParams>,
+
+    /// Per-intent overrides keyed by intent name (e.g. "chat", "completion")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub intent_overrides: HashMap<String, GenerationParams>,
+
+    /// How an oversized context may be shrunk (empty: reject it instead)
+    #[serde(default, skip_serializing_if = "TruncationPolicy::is_empty")]
+    pub truncation: TruncationPolicy,
+
+    /// Best-of-N sampling keyed by intent name (absent: one answer)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub best_of: HashMap<String, BestOfN>,
+
+    /// Self-critique loop run before answering (absent: answer directly)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reflection: Option<ReflectionConfig>,
+
+    /// Rules answers are checked against and regenerated to meet (absent: none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_constraints: Option<OutputConstraints>,
 }
 
 impl ModelConfig {
@@ -103,6 +135,12 @@ impl ModelConfig {
             presence_penalty: 0.0,
             stop_sequences: vec![],
             system_prompt: String::new(),
+            generation: None,
+            intent_overrides: HashMap::new(),
+            truncation: TruncationPolicy::default(),
+            best_of: HashMap::new(),
+            reflection: None,
+            output_constraints: None,
         }
     }
 
@@ -116,6 +154,11 @@ impl ModelConfig {
         Self::new(ProviderType::Anthropic, "claude-3-opus-20240229")
     }
 
+    /// Create configuration for Google Gemini 1.5 Pro
+    pub fn gemini_pro() -> Self {
+        Self::new(ProviderType::Gemini, "gemini-1.5-pro")
+    }
+
     /// Create configuration for local Ollama
     pub fn ollama(model_name: impl Into<String>) -> Self {
         let mut config = Self::new(ProviderType::Ollama, model_name);
@@ -176,6 +219,80 @@ impl ModelConfig {
         self
     }
 
+    /// Builder: set generation parameters (e.g. a preset)
+    pub fn with_generation_params(mut self, params: impl Into<GenerationParams>) -> Self {
+        self.generation = Some(params.into());
+        self
+    }
+
+    /// Builder: override generation parameters for one intent type
+    pub fn with_intent_override(
+        mut self,
+        intent_name: impl Into<String>,
+        params: GenerationParams,
+    ) -> Self {
+        self.intent_overrides.insert(intent_name.into(), params);
+        self
+    }
+
+    /// Builder: sample several answers for one intent type and judge them
+    pub fn with_best_of(mut self, intent_name: impl Into<String>, best_of: BestOfN) -> Self {
+        self.best_of.insert(intent_name.into(), best_of);
+        self
+    }
+
+    /// Builder: critique and revise answers before returning them
+    pub fn with_reflection(mut self, reflection: ReflectionConfig) -> Self {
+        self.reflection = Some(reflection);
+        self
+    }
+
+    /// Builder: check answers against `constraints`, regenerating those that break them
+    pub fn with_output_constraints(mut self, constraints: OutputConstraints) -> Self {
+        self.output_constraints = Some(constraints);
+        self
+    }
+
+    /// Builder: allow oversized contexts to be truncated by `policy`
+    pub fn with_truncation_policy(mut self, policy: TruncationPolicy) -> Self {
+        self.truncation = policy;
+        self
+    }
+
+    /// Effective sampling parameters for this configuration
+    ///
+    /// The flat fields form the baseline; `generation` is layered on top.
+    pub fn sampling_params(&self) -> GenerationParams {
+        let base = GenerationParams::from_model_config(self);
+        match &self.generation {
+            Some(generation) => base.overlay(generation),
+            None => base,
+        }
+    }
+
+    /// Resolve the configuration for a specific intent and call-site override
+    ///
+    /// Returns a copy whose `generation` already includes the intent's
+    /// override and `call_override`, so adapters only need
+    /// [`ModelConfig::sampling_params`].
+    pub fn resolve_for_intent(
+        &self,
+        intent_name: &str,
+        call_override: &GenerationParams,
+    ) -> ModelConfig {
+        let mut generation = self.generation.clone().unwrap_or_default();
+        if let Some(intent_override) = self.intent_overrides.get(intent_name) {
+            generation = generation.overlay(intent_override);
+        }
+        generation = generation.overlay(call_override);
+
+        ModelConfig {
+            generation: (!generation.is_empty()).then_some(generation),
+            intent_overrides: HashMap::new(),
+            ..self.clone()
+        }
+    }
+
     /// Validate the configuration
     pub fn validate(&self) -> Result<(), String> {
         if self.model_name.is_empty() {
@@ -201,6 +318,16 @@ impl ModelConfig {
             ));
         }
 
+        if let Some(generation) = &self.generation {
+            generation.validate()?;
+        }
+
+        for (intent_name, params) in &self.intent_overrides {
+            params
+                .validate()
+                .map_err(|e| format!("Invalid override for intent '{}': {}", intent_name, e))?;
+        }
+
         Ok(())
     }
 }
@@ -227,6 +354,7 @@ mod tests {
     fn test_provider_requires_api_key() {
         assert!(ProviderType::OpenAI.requires_api_key());
         assert!(ProviderType::Anthropic.requires_api_key());
+        assert!(ProviderType::Gemini.requires_api_key());
         assert!(!ProviderType::Ollama.requires_api_key());
         assert!(!ProviderType::Mock.requires_api_key());
     }
@@ -266,6 +394,47 @@ mod tests {
         assert!(invalid.validate().is_err());
     }
 
+    #[test]
+    fn test_resolve_for_intent_layers_overrides() {
+        let config = ModelConfig::openai_gpt4()
+            .with_generation_params(GenerationParams::precise())
+            .with_intent_override("completion", GenerationParams::new().with_max_tokens(64));
+
+        let resolved = config.resolve_for_intent("completion", &GenerationParams::new().with_seed(7));
+        let params = resolved.sampling_params();
+
+        assert_eq!(params.temperature, Some(0.2));
+        assert_eq!(params.top_p, None);
+        assert_eq!(params.max_tokens, Some(64));
+        assert_eq!(params.seed, Some(7));
+        assert!(resolved.intent_overrides.is_empty());
+
+        let chat = config.resolve_for_intent("chat", &GenerationParams::new());
+        assert_eq!(chat.sampling_params().max_tokens, Some(4096));
+    }
+
+    #[test]
+    fn test_sampling_params_never_send_temperature_and_top_p() {
+        assert!(ModelConfig::default().sampling_params().validate().is_ok());
+
+        let params = ModelConfig::mock().with_top_p(0.9).sampling_params();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.top_p, None);
+
+        let nucleus = ModelConfig::mock()
+            .with_generation_params(GenerationParams::new().with_top_p(0.9))
+            .sampling_params();
+        assert_eq!(nucleus.temperature, None);
+        assert_eq!(nucleus.top_p, Some(0.9));
+    }
+
+    #[test]
+    fn test_invalid_intent_override_fails_validation() {
+        let config = ModelConfig::mock()
+            .with_intent_override("chat", GenerationParams::new().with_temperature(5.0));
+        assert!(config.validate().unwrap_err().contains("chat"));
+    }
+
     #[test]
     fn test_model_config_serialization() {
         let config = ModelConfig::openai_gpt4().with_temperature(0.8);
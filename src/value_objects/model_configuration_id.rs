@@ -5,6 +5,7 @@
 //! Unique identifier for model configuration aggregates using UUID v7
 //! for time-ordered IDs.
 
+use super::next_id;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
@@ -30,7 +31,7 @@ impl ModelConfigurationId {
     /// assert!(!id.to_string().is_empty());
     /// ```
     pub fn new() -> Self {
-        Self(Uuid::now_v7())
+        Self(next_id())
     }
 
     /// Create from an existing UUID
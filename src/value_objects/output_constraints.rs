@@ -0,0 +1,239 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Output constraints
+//!
+//! Rules an agent's answers must follow, checked once the answer is
+//! complete. An answer that breaks one is regenerated with instructions
+//! naming what to fix, up to `max_retries` times:
+//!
+//! ```text
+//! answer ──> check(constraints) ──> ok ──> delivered
+//!                 │ violations
+//!                 v
+//!   context + answer + corrective instructions ──> answer 1 ──> check ...
+//!                                                    (max_retries)
+//!   still violating ──> on_exhausted: fail the response, or deliver it anyway
+//! ```
+//!
+//! Citation markers are bracketed numbers such as `[1]`.
+
+use serde::{Deserialize, Serialize};
+
+/// Regenerations allowed when the configuration names no limit
+pub const DEFAULT_CONSTRAINT_RETRIES: u32 = 2;
+
+/// A rule an answer must follow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutputConstraint {
+    /// At most `max_chars` characters
+    MaxLength { max_chars: usize },
+
+    /// At least `min` citation markers such as `[1]`
+    Citations { min: usize },
+
+    /// None of the phrases, ignoring case
+    ForbiddenPhrases { phrases: Vec<String> },
+
+    /// A JSON object or array and nothing else
+    JsonOnly,
+}
+
+impl OutputConstraint {
+    /// Name recorded with violations
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputConstraint::MaxLength { .. } => "max_length",
+            OutputConstraint::Citations { .. } => "citations",
+            OutputConstraint::ForbiddenPhrases { .. } => "forbidden_phrases",
+            OutputConstraint::JsonOnly => "json_only",
+        }
+    }
+
+    /// How `answer` breaks the rule, if it does
+    pub fn check(&self, answer: &str) -> Option<ConstraintViolation> {
+        let detail = match self {
+            OutputConstraint::MaxLength { max_chars } => {
+                let chars = answer.chars().count();
+                (chars > *max_chars)
+                    .then(|| format!("{} characters, over the limit of {}", chars, max_chars))
+            }
+            OutputConstraint::Citations { min } => {
+                let cited = citation_markers(answer);
+                (cited < *min).then(|| format!("{} citation markers, {} required", cited, min))
+            }
+            OutputConstraint::ForbiddenPhrases { phrases } => {
+                let lower = answer.to_lowercase();
+                let used: Vec<&str> = phrases
+                    .iter()
+                    .filter(|phrase| lower.contains(&phrase.to_lowercase()))
+                    .map(String::as_str)
+                    .collect();
+                (!used.is_empty()).then(|| format!("uses \"{}\"", used.join("\", \"")))
+            }
+            OutputConstraint::JsonOnly => {
+                match serde_json::from_str::<serde_json::Value>(answer.trim()) {
+                    Ok(value) if value.is_object() || value.is_array() => None,
+                    Ok(_) => Some("JSON, but not an object or array".to_string()),
+                    Err(e) => Some(format!("not JSON: {}", e)),
+                }
+            }
+        };
+        detail.map(|detail| ConstraintViolation {
+            constraint: self.name().to_string(),
+            detail,
+        })
+    }
+
+    /// What to tell the model when an answer breaks the rule
+    pub fn instruction(&self) -> String {
+        match self {
+            OutputConstraint::MaxLength { max_chars } => {
+                format!("Keep the answer to at most {} characters.", max_chars)
+            }
+            OutputConstraint::Citations { min } => format!(
+                "Cite your sources with at least {} bracketed markers such as [1].",
+                min
+            ),
+            OutputConstraint::ForbiddenPhrases { phrases } => {
+                format!("Do not use these phrases: \"{}\".", phrases.join("\", \""))
+            }
+            OutputConstraint::JsonOnly => "Reply with a single JSON object or array only, \
+                with no prose and no code fences."
+                .to_string(),
+        }
+    }
+}
+
+/// Count `[n]` markers in `text`
+fn citation_markers(text: &str) -> usize {
+    text.split('[')
+        .skip(1)
+        .filter(|rest| {
+            rest.split_once(']').is_some_and(|(inside, _)| {
+                !inside.is_empty() && inside.chars().all(|c| c.is_ascii_digit())
+            })
+        })
+        .count()
+}
+
+/// What happens to an answer still breaking a rule after the last retry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExhaustedAction {
+    /// The response fails
+    #[default]
+    Fail,
+    /// The last answer is delivered as it is
+    Deliver,
+}
+
+/// Rules checked against every answer, and how hard to try to meet them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputConstraints {
+    /// The rules
+    pub constraints: Vec<OutputConstraint>,
+
+    /// Regenerations after the first answer
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// What happens when the last regeneration still breaks a rule
+    #[serde(default)]
+    pub on_exhausted: ExhaustedAction,
+}
+
+fn default_max_retries() -> u32 {
+    DEFAULT_CONSTRAINT_RETRIES
+}
+
+impl OutputConstraints {
+    /// Enforce `constraints` with the default retries, failing when they run out
+    pub fn new(constraints: Vec<OutputConstraint>) -> Self {
+        Self {
+            constraints,
+            max_retries: DEFAULT_CONSTRAINT_RETRIES,
+            on_exhausted: ExhaustedAction::default(),
+        }
+    }
+
+    /// Builder: allow up to `max_retries` regenerations
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Builder: deliver the last answer when the retries run out
+    pub fn delivering_on_exhaustion(mut self) -> Self {
+        self.on_exhausted = ExhaustedAction::Deliver;
+        self
+    }
+
+    /// Every rule `answer` breaks
+    pub fn check(&self, answer: &str) -> Vec<ConstraintViolation> {
+        self.constraints
+            .iter()
+            .filter_map(|constraint| constraint.check(answer))
+            .collect()
+    }
+}
+
+/// One rule an answer broke
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintViolation {
+    /// Name of the rule, e.g. "json_only"
+    pub constraint: String,
+
+    /// How the answer broke it
+    pub detail: String,
+}
+
+/// What was done about an answer that broke its rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnforcementAction {
+    /// The answer was discarded and generated again
+    Regenerated,
+    /// Retries ran out and the answer was delivered anyway
+    Delivered,
+    /// Retries ran out and the response failed
+    Failed,
+}
+
+/// One enforcement step: an answer, the rules it broke and what followed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintEnforcement {
+    /// 0 for the first answer, then one per regeneration
+    pub attempt: u32,
+
+    /// Rules the answer broke
+    pub violations: Vec<ConstraintViolation>,
+
+    /// What was done about it
+    pub action: EnforcementAction,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints_report_each_violation() {
+        let constraints: OutputConstraints = serde_json::from_str(
+            r#"{"constraints": [
+                {"kind": "max_length", "max_chars": 40},
+                {"kind": "citations", "min": 2},
+                {"kind": "forbidden_phrases", "phrases": ["As an AI"]},
+                {"kind": "json_only"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(constraints.max_retries, DEFAULT_CONSTRAINT_RETRIES);
+        assert_eq!(constraints.on_exhausted, ExhaustedAction::Fail);
+
+        let violations = constraints.check("as an ai, I think so [1] [a]");
+        let names: Vec<&str> = violations.iter().map(|v| v.constraint.as_str()).collect();
+        assert_eq!(names, ["citations", "forbidden_phrases", "json_only"]);
+        assert!(constraints.check(r#"{"cites": "[1] [2]"}"#).is_empty());
+    }
+}
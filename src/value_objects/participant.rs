@@ -0,0 +1,157 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Conversation participants
+//!
+//! Everyone taking part in a conversation has a [`ParticipantRole`], and the
+//! role decides what they may do there:
+//!
+//! ```text
+//!              read   post   approve tools   manage participants
+//! Owner         ✓      ✓          ✓                  ✓
+//! Agent         ✓      ✓
+//! Tool          ✓      ✓
+//! Observer      ✓
+//! ```
+//!
+//! Roles are tied to the kind of participant: owners are people, agents are
+//! agents and tools are tools. Observers may be people or agents.
+
+use super::{clock_now, AgentId, PersonId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Who a participant is
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum ParticipantId {
+    /// A person
+    Person(PersonId),
+    /// An agent
+    Agent(AgentId),
+    /// A tool, by name
+    Tool(String),
+}
+
+impl fmt::Display for ParticipantId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParticipantId::Person(id) => write!(f, "person:{}", id),
+            ParticipantId::Agent(id) => write!(f, "agent:{}", id),
+            ParticipantId::Tool(name) => write!(f, "tool:{}", name),
+        }
+    }
+}
+
+/// Something a participant may do in a conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversationPermission {
+    /// See the conversation's messages
+    Read,
+    /// Add messages
+    Post,
+    /// Allow or refuse tool calls an agent wants to make
+    ApproveTools,
+    /// Add, remove and change the roles of participants
+    ManageParticipants,
+}
+
+/// A participant's part in a conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParticipantRole {
+    /// The person the conversation belongs to
+    Owner,
+    /// Follows the conversation without taking part
+    Observer,
+    /// An agent answering in the conversation
+    Agent,
+    /// A tool posting results into the conversation
+    Tool,
+}
+
+impl ParticipantRole {
+    /// Whether the role grants `permission`
+    pub fn permits(self, permission: ConversationPermission) -> bool {
+        use ConversationPermission::*;
+        match self {
+            ParticipantRole::Owner => true,
+            ParticipantRole::Agent | ParticipantRole::Tool => matches!(permission, Read | Post),
+            ParticipantRole::Observer => permission == Read,
+        }
+    }
+
+    /// Whether a participant of this kind may hold the role
+    pub fn accepts(self, id: &ParticipantId) -> bool {
+        matches!(
+            (self, id),
+            (ParticipantRole::Owner, ParticipantId::Person(_))
+                | (ParticipantRole::Agent, ParticipantId::Agent(_))
+                | (ParticipantRole::Tool, ParticipantId::Tool(_))
+                | (ParticipantRole::Observer, ParticipantId::Person(_))
+                | (ParticipantRole::Observer, ParticipantId::Agent(_))
+        )
+    }
+}
+
+impl fmt::Display for ParticipantRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ParticipantRole::Owner => "owner",
+            ParticipantRole::Observer => "observer",
+            ParticipantRole::Agent => "agent",
+            ParticipantRole::Tool => "tool",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A member of a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Participant {
+    /// Who the participant is
+    pub id: ParticipantId,
+
+    /// What they may do
+    pub role: ParticipantRole,
+
+    /// When they joined
+    pub joined_at: DateTime<Utc>,
+}
+
+impl Participant {
+    /// A participant joining now
+    pub fn new(id: ParticipantId, role: ParticipantRole) -> Self {
+        Self {
+            id,
+            role,
+            joined_at: clock_now(),
+        }
+    }
+
+    /// Whether the participant's role grants `permission`
+    pub fn can(&self, permission: ConversationPermission) -> bool {
+        self.role.permits(permission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_grant_permissions_and_fit_participant_kinds() {
+        use ConversationPermission::*;
+        assert!(ParticipantRole::Owner.permits(ApproveTools));
+        assert!(ParticipantRole::Agent.permits(Post));
+        assert!(!ParticipantRole::Agent.permits(ApproveTools));
+        assert!(!ParticipantRole::Observer.permits(Post));
+
+        let agent = ParticipantId::Agent(AgentId::new());
+        assert!(ParticipantRole::Observer.accepts(&agent));
+        assert!(!ParticipantRole::Owner.accepts(&agent));
+        assert!(!ParticipantRole::Tool.accepts(&agent));
+        assert!(ParticipantRole::Tool.accepts(&ParticipantId::Tool("search".into())));
+    }
+}
@@ -5,6 +5,7 @@
 //! Unique identifier for persons - required binding for Agent ownership.
 //! An Agent is a Person's automaton and MUST have a PersonId.
 
+use super::next_id;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
@@ -23,7 +24,7 @@ pub struct PersonId(Uuid);
 impl PersonId {
     /// Create a new Person ID with UUID v7 (time-ordered)
     pub fn new() -> Self {
-        Self(Uuid::now_v7())
+        Self(next_id())
     }
 
     /// Create a Person ID from an existing UUID
@@ -0,0 +1,422 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Principal-scoped permissions
+//!
+//! Three classes of principal act on an agent, and each may do different
+//! things: the owner who deployed it, the operators who run the fleet, and
+//! the agent itself acting autonomously. [`PrincipalPermissions`] holds one
+//! [`PermissionSet`] per class:
+//!
+//! ```text
+//! command ──> required_permission() ──────┐
+//! AuthenticatedPrincipal { operator } ────┴──> PrincipalPermissions::check
+//!                                               │
+//!                        Ok(()) ──> with_principal(operator, handle)
+//!                                               │
+//!                                               v
+//!                                   AgentSuspended { performed_by: operator }
+//! ```
+//!
+//! The class is never what a client claims: an [`AuthenticatedPrincipal`]
+//! comes from a key the service has registered, and callers it cannot
+//! identify act as [`PrincipalClass::LEAST_PRIVILEGED`]. Events raised while
+//! handling a command record the acting class in `performed_by`, read from
+//! [`acting_principal`].
+
+use super::PersonId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use thiserror::Error;
+
+tokio::task_local! {
+    static ACTING: PrincipalClass;
+}
+
+/// Who is acting on an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalClass {
+    /// The person who deployed and owns the agent
+    Owner,
+    /// Fleet operators and compliance staff
+    Operator,
+    /// The agent itself, acting autonomously
+    Agent,
+}
+
+impl PrincipalClass {
+    /// Class of callers the service cannot identify
+    pub const LEAST_PRIVILEGED: PrincipalClass = PrincipalClass::Agent;
+
+    /// Parse a class name (case-insensitive)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "owner" => Some(PrincipalClass::Owner),
+            "operator" => Some(PrincipalClass::Operator),
+            "agent" => Some(PrincipalClass::Agent),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PrincipalClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrincipalClass::Owner => write!(f, "owner"),
+            PrincipalClass::Operator => write!(f, "operator"),
+            PrincipalClass::Agent => write!(f, "agent"),
+        }
+    }
+}
+
+/// Who a command comes from, as established by the service
+///
+/// Built from a registered key that signed the command, or by the service
+/// for commands it issues itself; never from headers or payload fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthenticatedPrincipal {
+    /// Class whose permissions apply
+    pub class: PrincipalClass,
+
+    /// Stable name of the caller, e.g. the name its key is registered under;
+    /// `None` for anonymous callers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+
+    /// The person the caller's key belongs to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person_id: Option<PersonId>,
+}
+
+impl AuthenticatedPrincipal {
+    /// A caller with no registered identity
+    pub fn anonymous() -> Self {
+        Self {
+            class: PrincipalClass::LEAST_PRIVILEGED,
+            actor: None,
+            person_id: None,
+        }
+    }
+
+    /// The service itself, acting as `class` for `actor` (e.g. "rule:nightly")
+    pub fn internal(class: PrincipalClass, actor: impl Into<String>) -> Self {
+        Self {
+            class,
+            actor: Some(actor.into()),
+            person_id: None,
+        }
+    }
+
+    /// Builder: the person the identity belongs to
+    pub fn with_person(mut self, person_id: PersonId) -> Self {
+        self.person_id = Some(person_id);
+        self
+    }
+
+    /// Whether the caller was identified
+    pub fn is_anonymous(&self) -> bool {
+        self.actor.is_none()
+    }
+
+    /// Check that `claimed`, named in a command, is the caller's own person
+    ///
+    /// Commands recording who approved or released something name a person;
+    /// only that person's key may send them.
+    pub fn acting_for(&self, claimed: PersonId) -> Result<PersonId, PersonMismatch> {
+        match self.person_id {
+            Some(person_id) if person_id == claimed => Ok(person_id),
+            _ => Err(PersonMismatch {
+                principal: self.to_string(),
+                claimed,
+            }),
+        }
+    }
+}
+
+/// A command named a person other than the one its key belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{principal} may not act for person {claimed}")]
+pub struct PersonMismatch {
+    /// Who sent the command
+    pub principal: String,
+
+    /// The person it named
+    pub claimed: PersonId,
+}
+
+impl fmt::Display for AuthenticatedPrincipal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.actor {
+            Some(actor) => write!(f, "{} ({})", actor, self.class),
+            None => write!(f, "anonymous ({})", self.class),
+        }
+    }
+}
+
+/// Something a principal may do to or as an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentPermission {
+    /// Deploy the agent
+    Deploy,
+    /// Set the agent's model
+    ConfigureModel,
+    /// Activate the agent
+    Activate,
+    /// Suspend the agent
+    Suspend,
+    /// Decommission the agent
+    Decommission,
+    /// Change feature flags and locale
+    UpdateConfiguration,
+    /// Place or release legal holds
+    ManageLegalHold,
+    /// Send the agent a message
+    SendMessage,
+    /// Cancel an in-flight response
+    CancelMessage,
+    /// Replay a response stream
+    ResumeStream,
+    /// Call the agent's tools
+    InvokeTools,
+    /// Hand work to other agents
+    DelegateToAgents,
+    /// Approve escalations for permissions another class lacks
+    ApproveEscalations,
+    /// Release quarantined messages for another attempt
+    ReleaseQuarantine,
+}
+
+impl AgentPermission {
+    /// Every permission
+    pub const ALL: [AgentPermission; 14] = [
+        AgentPermission::Deploy,
+        AgentPermission::ConfigureModel,
+        AgentPermission::Activate,
+        AgentPermission::Suspend,
+        AgentPermission::Decommission,
+        AgentPermission::UpdateConfiguration,
+        AgentPermission::ManageLegalHold,
+        AgentPermission::SendMessage,
+        AgentPermission::CancelMessage,
+        AgentPermission::ResumeStream,
+        AgentPermission::InvokeTools,
+        AgentPermission::DelegateToAgents,
+        AgentPermission::ApproveEscalations,
+        AgentPermission::ReleaseQuarantine,
+    ];
+}
+
+impl fmt::Display for AgentPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AgentPermission::Deploy => "deploy",
+            AgentPermission::ConfigureModel => "configure_model",
+            AgentPermission::Activate => "activate",
+            AgentPermission::Suspend => "suspend",
+            AgentPermission::Decommission => "decommission",
+            AgentPermission::UpdateConfiguration => "update_configuration",
+            AgentPermission::ManageLegalHold => "manage_legal_hold",
+            AgentPermission::SendMessage => "send_message",
+            AgentPermission::CancelMessage => "cancel_message",
+            AgentPermission::ResumeStream => "resume_stream",
+            AgentPermission::InvokeTools => "invoke_tools",
+            AgentPermission::DelegateToAgents => "delegate_to_agents",
+            AgentPermission::ApproveEscalations => "approve_escalations",
+            AgentPermission::ReleaseQuarantine => "release_quarantine",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Permissions granted to one principal class
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PermissionSet(BTreeSet<AgentPermission>);
+
+impl PermissionSet {
+    /// No permissions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every permission
+    pub fn all() -> Self {
+        AgentPermission::ALL.into_iter().collect()
+    }
+
+    /// Builder: grant `permission`
+    pub fn with(mut self, permission: AgentPermission) -> Self {
+        self.0.insert(permission);
+        self
+    }
+
+    /// Builder: revoke `permission`
+    pub fn without(mut self, permission: AgentPermission) -> Self {
+        self.0.remove(&permission);
+        self
+    }
+
+    /// Whether `permission` is granted
+    pub fn allows(&self, permission: AgentPermission) -> bool {
+        self.0.contains(&permission)
+    }
+
+    /// Granted permissions, in declaration order
+    pub fn iter(&self) -> impl Iterator<Item = AgentPermission> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<AgentPermission> for PermissionSet {
+    fn from_iter<I: IntoIterator<Item = AgentPermission>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// A principal class lacked a permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{class} may not {permission}")]
+pub struct PermissionDenied {
+    /// Who tried
+    pub class: PrincipalClass,
+
+    /// What they lacked
+    pub permission: AgentPermission,
+}
+
+/// What each principal class may do
+///
+/// By default the owner may do anything but manage legal holds, which are
+/// for compliance operators; operators run the agent but may not deploy or
+/// decommission it; the agent may only converse, use its tools and delegate.
+/// Only the owner approves escalations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrincipalPermissions {
+    /// Granted to the owner
+    pub owner: PermissionSet,
+
+    /// Granted to operators
+    pub operator: PermissionSet,
+
+    /// Granted to the agent itself
+    pub agent: PermissionSet,
+}
+
+impl PrincipalPermissions {
+    /// Permissions for `class`
+    pub fn for_class(&self, class: PrincipalClass) -> &PermissionSet {
+        match class {
+            PrincipalClass::Owner => &self.owner,
+            PrincipalClass::Operator => &self.operator,
+            PrincipalClass::Agent => &self.agent,
+        }
+    }
+
+    /// Builder: replace the permissions of `class`
+    pub fn with_class(mut self, class: PrincipalClass, permissions: PermissionSet) -> Self {
+        match class {
+            PrincipalClass::Owner => self.owner = permissions,
+            PrincipalClass::Operator => self.operator = permissions,
+            PrincipalClass::Agent => self.agent = permissions,
+        }
+        self
+    }
+
+    /// Whether `class` may exercise `permission`
+    pub fn allows(&self, class: PrincipalClass, permission: AgentPermission) -> bool {
+        self.for_class(class).allows(permission)
+    }
+
+    /// Allow `permission` for `class`, or say why not
+    pub fn check(
+        &self,
+        class: PrincipalClass,
+        permission: AgentPermission,
+    ) -> Result<(), PermissionDenied> {
+        if self.allows(class, permission) {
+            Ok(())
+        } else {
+            Err(PermissionDenied { class, permission })
+        }
+    }
+
+    /// Classes that may exercise `permission`
+    pub fn holders(&self, permission: AgentPermission) -> Vec<PrincipalClass> {
+        [
+            PrincipalClass::Owner,
+            PrincipalClass::Operator,
+            PrincipalClass::Agent,
+        ]
+        .into_iter()
+        .filter(|class| self.allows(*class, permission))
+        .collect()
+    }
+}
+
+impl Default for PrincipalPermissions {
+    fn default() -> Self {
+        Self {
+            owner: PermissionSet::all().without(AgentPermission::ManageLegalHold),
+            operator: PermissionSet::all()
+                .without(AgentPermission::Deploy)
+                .without(AgentPermission::Decommission)
+                .without(AgentPermission::InvokeTools)
+                .without(AgentPermission::DelegateToAgents)
+                .without(AgentPermission::ApproveEscalations),
+            agent: PermissionSet::new()
+                .with(AgentPermission::SendMessage)
+                .with(AgentPermission::CancelMessage)
+                .with(AgentPermission::ResumeStream)
+                .with(AgentPermission::InvokeTools)
+                .with(AgentPermission::DelegateToAgents),
+        }
+    }
+}
+
+/// Class of the principal whose command is being handled, if any
+pub fn acting_principal() -> Option<PrincipalClass> {
+    ACTING.try_with(|class| *class).ok()
+}
+
+/// Run `future` on behalf of `class`, answering [`acting_principal`]
+pub async fn with_principal<F: Future>(class: PrincipalClass, future: F) -> F::Output {
+    ACTING.scope(class, future).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_permissions_differ_by_class() {
+        let permissions = PrincipalPermissions::default();
+        assert!(permissions
+            .check(PrincipalClass::Owner, AgentPermission::Decommission)
+            .is_ok());
+        assert_eq!(
+            permissions.check(PrincipalClass::Operator, AgentPermission::Decommission),
+            Err(PermissionDenied {
+                class: PrincipalClass::Operator,
+                permission: AgentPermission::Decommission,
+            })
+        );
+        assert!(!permissions.allows(PrincipalClass::Agent, AgentPermission::Suspend));
+        assert_eq!(
+            permissions.holders(AgentPermission::ManageLegalHold),
+            vec![PrincipalClass::Operator]
+        );
+
+        let locked = permissions.with_class(PrincipalClass::Agent, PermissionSet::new());
+        assert!(!locked.allows(PrincipalClass::Agent, AgentPermission::SendMessage));
+    }
+
+    #[tokio::test]
+    async fn test_acting_principal_is_scoped() {
+        assert_eq!(acting_principal(), None);
+        let inside = with_principal(PrincipalClass::Agent, async { acting_principal() }).await;
+        assert_eq!(inside, Some(PrincipalClass::Agent));
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Reasoning traces
+//!
+//! A [`ReasoningTrace`] records the decisions behind one answer as a tree of
+//! [`TraceStep`]s: the plan, which tools were offered, which retrieved
+//! sources made it into the context, and how candidates and drafts were
+//! judged. Steps point at their parent, so the tree survives serialization
+//! as a flat list:
+//!
+//! ```text
+//! plan: answer "chat" with mock/mock-model            (step 0)
+//!  ├── retrieval: doc-1 (rank 1)                       (step 1, parent 0)
+//!  ├── tool_selection: offered search, calculator      (step 2, parent 0)
+//!  └── judgment: heuristic picked candidate 2          (step 3, parent 0)
+//!       ├── candidate: mock/mock-model ...             (step 4, parent 3)
+//!       └── candidate: mock/mock-model ...             (step 5, parent 3)
+//! ```
+//!
+//! Summaries describe the decision; `detail` carries the text it was made
+//! from (excerpts, rationales, critiques). [`ReasoningTrace::redacted`]
+//! drops the details and keeps the shape.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What kind of decision a step records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceStepKind {
+    /// How the request will be answered: routing, model, strategy
+    Plan,
+
+    /// Tools offered to the model, and why
+    ToolSelection,
+
+    /// A retrieved source, kept in or dropped from the context
+    Retrieval,
+
+    /// Context removed to fit the model's window
+    Truncation,
+
+    /// One generated answer that was considered
+    Candidate,
+
+    /// A choice between candidates, with its rationale
+    Judgment,
+
+    /// A critique of a draft and the revision it led to
+    Critique,
+}
+
+impl TraceStepKind {
+    /// Name used in events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraceStepKind::Plan => "plan",
+            TraceStepKind::ToolSelection => "tool_selection",
+            TraceStepKind::Retrieval => "retrieval",
+            TraceStepKind::Truncation => "truncation",
+            TraceStepKind::Candidate => "candidate",
+            TraceStepKind::Judgment => "judgment",
+            TraceStepKind::Critique => "critique",
+        }
+    }
+}
+
+impl fmt::Display for TraceStepKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One recorded decision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Position in the trace, unique within it
+    pub id: u32,
+
+    /// The step this one refines, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u32>,
+
+    /// What kind of decision this is
+    pub kind: TraceStepKind,
+
+    /// The decision, in one line
+    pub summary: String,
+
+    /// Text the decision was based on; removed by redaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Decisions behind one answer, in the order they were made
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReasoningTrace {
+    steps: Vec<TraceStep>,
+}
+
+impl ReasoningTrace {
+    /// An empty trace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a top-level step, returning its id
+    pub fn step(&mut self, kind: TraceStepKind, summary: impl Into<String>) -> u32 {
+        self.push(None, kind, summary.into(), None)
+    }
+
+    /// Record a step under `parent`, returning its id
+    pub fn child(
+        &mut self,
+        parent: u32,
+        kind: TraceStepKind,
+        summary: impl Into<String>,
+        detail: Option<String>,
+    ) -> u32 {
+        self.push(Some(parent), kind, summary.into(), detail)
+    }
+
+    fn push(
+        &mut self,
+        parent: Option<u32>,
+        kind: TraceStepKind,
+        summary: String,
+        detail: Option<String>,
+    ) -> u32 {
+        let id = self.steps.len() as u32;
+        self.steps.push(TraceStep {
+            id,
+            parent,
+            kind,
+            summary,
+            detail,
+        });
+        id
+    }
+
+    /// Steps in recording order
+    pub fn steps(&self) -> &[TraceStep] {
+        &self.steps
+    }
+
+    /// Whether nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The same tree without step details
+    pub fn redacted(&self) -> Self {
+        Self {
+            steps: self
+                .steps
+                .iter()
+                .map(|step| TraceStep {
+                    detail: None,
+                    ..step.clone()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction_keeps_tree_shape() {
+        let mut trace = ReasoningTrace::new();
+        let plan = trace.step(TraceStepKind::Plan, "Answer with mock/mock-model");
+        trace.child(
+            plan,
+            TraceStepKind::Retrieval,
+            "Kept doc-1 (rank 1)",
+            Some("Quarterly revenue grew".to_string()),
+        );
+
+        let redacted = trace.redacted();
+        assert_eq!(redacted.steps().len(), 2);
+        assert_eq!(redacted.steps()[1].parent, Some(plan));
+        assert!(redacted.steps().iter().all(|s| s.detail.is_none()));
+
+        let json = serde_json::to_value(&redacted).unwrap();
+        assert_eq!(json[1]["kind"], "retrieval");
+    }
+}
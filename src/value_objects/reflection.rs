@@ -0,0 +1,104 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Reflection configuration
+//!
+//! With reflection on, the agent critiques its first answer against a rubric
+//! and revises it, up to `max_reflections` times or until the critique
+//! approves the answer or the token budget is spent:
+//!
+//! ```text
+//! draft 0 ──> critique(rubric) ──> APPROVED? ──yes──> final answer
+//!                                      │ no
+//!                                      v
+//!                                  draft 1 ──> critique ──> ... (bounded)
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Rubric used when the configuration names none
+pub const DEFAULT_RUBRIC: &str = "The answer is correct, complete, directly addresses the \
+question and contains nothing unsupported.";
+
+/// Bounds and rubric for the self-critique loop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReflectionConfig {
+    /// Critique-and-revise rounds after the first answer
+    #[serde(default = "default_max_reflections")]
+    pub max_reflections: u32,
+
+    /// Estimated tokens the loop may generate, drafts and critiques included
+    pub token_budget: u32,
+
+    /// What a good answer looks like
+    #[serde(default = "default_rubric")]
+    pub rubric: String,
+
+    /// Stream superseded drafts to the caller ahead of the final answer
+    #[serde(default)]
+    pub stream_drafts: bool,
+}
+
+fn default_max_reflections() -> u32 {
+    1
+}
+
+fn default_rubric() -> String {
+    DEFAULT_RUBRIC.to_string()
+}
+
+impl ReflectionConfig {
+    /// One revision within `token_budget`, against the default rubric
+    pub fn new(token_budget: u32) -> Self {
+        Self {
+            max_reflections: default_max_reflections(),
+            token_budget,
+            rubric: default_rubric(),
+            stream_drafts: false,
+        }
+    }
+
+    /// Builder: allow up to `max_reflections` revisions
+    pub fn with_max_reflections(mut self, max_reflections: u32) -> Self {
+        self.max_reflections = max_reflections;
+        self
+    }
+
+    /// Builder: critique against `rubric`
+    pub fn with_rubric(mut self, rubric: impl Into<String>) -> Self {
+        self.rubric = rubric.into();
+        self
+    }
+
+    /// Builder: stream superseded drafts too
+    pub fn with_streamed_drafts(mut self) -> Self {
+        self.stream_drafts = true;
+        self
+    }
+}
+
+/// One answer produced by the loop and the critique it received
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReflectionDraft {
+    /// 0 for the first answer, then one per revision
+    pub round: u32,
+
+    /// The answer text
+    pub content: String,
+
+    /// Critique of this draft, if one was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critique: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reflection_defaults_to_one_revision() {
+        let config: ReflectionConfig = serde_json::from_str(r#"{"token_budget": 2000}"#).unwrap();
+        assert_eq!(config, ReflectionConfig::new(2000));
+        assert_eq!(config.max_reflections, 1);
+        assert!(!config.stream_drafts);
+    }
+}
@@ -20,6 +20,8 @@ pub enum FinishReason {
     ToolCalls,
     /// Error occurred during generation
     Error,
+    /// Stream was aborted by a `CancelMessage` command
+    Cancelled,
 }
 
 impl FinishReason {
@@ -28,12 +30,36 @@ impl FinishReason {
         matches!(self, FinishReason::Stop | FinishReason::ToolCalls)
     }
 
+    /// Check if the stream was cancelled by the caller
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, FinishReason::Cancelled)
+    }
+
     /// Check if generation was truncated
     pub fn is_truncated(&self) -> bool {
         matches!(self, FinishReason::Length)
     }
 }
 
+/// Client-side output limit that cut a response short
+///
+/// Set on the final chunk when the adapter layer, rather than the
+/// provider, ended generation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutputEnforcement {
+    /// Output was truncated at the configured max tokens
+    MaxTokens {
+        /// The configured limit
+        limit: u32,
+    },
+    /// A configured stop sequence appeared in the streamed text
+    StopSequence {
+        /// The sequence that matched
+        sequence: String,
+    },
+}
+
 /// A streaming chunk from an AI model response
 ///
 /// Represents a partial response during streaming generation.
@@ -51,6 +77,14 @@ pub struct StreamingChunk {
     /// Reason why generation finished (only present on final chunk)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub finish_reason: Option<FinishReason>,
+
+    /// Set when client-side enforcement ended the stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforcement: Option<OutputEnforcement>,
+
+    /// Tokens the provider reports for the whole response (final chunk only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
 }
 
 impl StreamingChunk {
@@ -61,6 +95,8 @@ impl StreamingChunk {
             content: content.into(),
             is_final: false,
             finish_reason: None,
+            enforcement: None,
+            usage: None,
         }
     }
 
@@ -75,6 +111,8 @@ impl StreamingChunk {
             content: content.into(),
             is_final: true,
             finish_reason: Some(finish_reason),
+            enforcement: None,
+            usage: None,
         }
     }
 
@@ -85,9 +123,23 @@ impl StreamingChunk {
             content: String::new(),
             is_final: true,
             finish_reason: Some(finish_reason),
+            enforcement: None,
+            usage: None,
         }
     }
 
+    /// Builder: mark this chunk as ended by client-side enforcement
+    pub fn with_enforcement(mut self, enforcement: OutputEnforcement) -> Self {
+        self.enforcement = Some(enforcement);
+        self
+    }
+
+    /// Builder: attach the token counts the provider reported
+    pub fn with_usage(mut self, usage: TokenUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
     /// Check if this chunk has content
     pub fn has_content(&self) -> bool {
         !self.content.is_empty()
@@ -145,6 +197,20 @@ pub struct ContextMessage {
 
     /// The content of this message
     pub content: String,
+
+    /// Set when the message carries a retrieved document rather than a turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ContextSource>,
+}
+
+/// A retrieved document carried in the context
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextSource {
+    /// Identifier of the document (path, URL or knowledge-base key)
+    pub id: String,
+
+    /// Retrieval rank, 1 being the most relevant
+    pub rank: u32,
 }
 
 impl ContextMessage {
@@ -153,6 +219,7 @@ impl ContextMessage {
         Self {
             role: MessageRole::System,
             content: content.into(),
+            source: None,
         }
     }
 
@@ -161,6 +228,7 @@ impl ContextMessage {
         Self {
             role: MessageRole::User,
             content: content.into(),
+            source: None,
         }
     }
 
@@ -169,6 +237,19 @@ impl ContextMessage {
         Self {
             role: MessageRole::Assistant,
             content: content.into(),
+            source: None,
+        }
+    }
+
+    /// Create a system message carrying a retrieved document
+    pub fn source(id: impl Into<String>, rank: u32, content: impl Into<String>) -> Self {
+        Self {
+            role: MessageRole::System,
+            content: content.into(),
+            source: Some(ContextSource {
+                id: id.into(),
+                rank,
+            }),
         }
     }
 }
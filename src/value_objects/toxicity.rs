@@ -0,0 +1,171 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Toxicity scores for moderation
+//!
+//! Every message and response is scored between 0.0 (benign) and 1.0
+//! (certainly toxic). [`ToxicityThresholds`] turn the score into a verdict:
+//!
+//! ```text
+//! 0.0 ─────────── flag (0.5) ─────────── block (0.9) ─────────── 1.0
+//!       Clean              Flagged                  Blocked
+//! ```
+//!
+//! Flagged content is answered but listed for moderators; blocked content
+//! trips the guardrail and is refused. With `guardrails_strict` on, flagged
+//! content is blocked too.
+
+use serde::{Deserialize, Serialize};
+
+/// What the thresholds made of a score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationVerdict {
+    /// Below the flag threshold
+    #[default]
+    Clean,
+    /// Listed for moderators, but allowed through
+    Flagged,
+    /// Refused by the guardrail
+    Blocked,
+}
+
+/// Toxicity score of one piece of content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToxicityScore {
+    /// 0.0 (benign) to 1.0 (certainly toxic)
+    pub score: f32,
+
+    /// Lexicon terms found in the content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<String>,
+
+    /// Scorer that produced the score (e.g. "lexicon")
+    pub scorer: String,
+
+    /// Verdict under the thresholds in force when scored
+    #[serde(default)]
+    pub verdict: ModerationVerdict,
+}
+
+impl ToxicityScore {
+    /// A score, clamped to 0.0..=1.0, not yet judged
+    pub fn new(score: f32, scorer: impl Into<String>) -> Self {
+        Self {
+            score: score.clamp(0.0, 1.0),
+            terms: Vec::new(),
+            scorer: scorer.into(),
+            verdict: ModerationVerdict::Clean,
+        }
+    }
+
+    /// Builder: lexicon terms found
+    pub fn with_terms(mut self, terms: Vec<String>) -> Self {
+        self.terms = terms;
+        self
+    }
+
+    /// Judge the score against `thresholds`
+    pub fn judged(mut self, thresholds: &ToxicityThresholds) -> Self {
+        self.verdict = thresholds.verdict(self.score);
+        self
+    }
+
+    /// Whether moderators should see this content
+    pub fn is_flagged(&self) -> bool {
+        self.verdict != ModerationVerdict::Clean
+    }
+
+    /// Whether the guardrail refused this content
+    pub fn is_blocked(&self) -> bool {
+        self.verdict == ModerationVerdict::Blocked
+    }
+}
+
+/// Scores at which content is flagged and blocked
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ToxicityThresholds {
+    /// Scores at or above this are flagged
+    pub flag: f32,
+
+    /// Scores at or above this are blocked
+    pub block: f32,
+}
+
+impl ToxicityThresholds {
+    /// Flag at `flag`, block at `block`
+    pub fn new(flag: f32, block: f32) -> Self {
+        Self { flag, block }
+    }
+
+    /// Block everything that would be flagged
+    pub fn strict(self) -> Self {
+        Self {
+            block: self.block.min(self.flag),
+            ..self
+        }
+    }
+
+    /// Check both thresholds are scores, and content is flagged before blocked
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, threshold) in [("flag", self.flag), ("block", self.block)] {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(format!(
+                    "Toxicity {} threshold must be between 0.0 and 1.0, got {}",
+                    name, threshold
+                ));
+            }
+        }
+        if self.flag > self.block {
+            return Err(format!(
+                "Toxicity flag threshold {} is above the block threshold {}",
+                self.flag, self.block
+            ));
+        }
+        Ok(())
+    }
+
+    /// Verdict for `score`
+    pub fn verdict(&self, score: f32) -> ModerationVerdict {
+        if score >= self.block {
+            ModerationVerdict::Blocked
+        } else if score >= self.flag {
+            ModerationVerdict::Flagged
+        } else {
+            ModerationVerdict::Clean
+        }
+    }
+}
+
+impl Default for ToxicityThresholds {
+    fn default() -> Self {
+        Self::new(0.5, 0.9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds_judge_scores() {
+        let thresholds = ToxicityThresholds::default();
+        assert_eq!(thresholds.verdict(0.1), ModerationVerdict::Clean);
+        assert_eq!(thresholds.verdict(0.5), ModerationVerdict::Flagged);
+        assert_eq!(thresholds.verdict(0.95), ModerationVerdict::Blocked);
+        assert_eq!(thresholds.strict().verdict(0.5), ModerationVerdict::Blocked);
+
+        let score = ToxicityScore::new(1.7, "lexicon").judged(&thresholds);
+        assert_eq!(score.score, 1.0);
+        assert!(score.is_flagged() && score.is_blocked());
+    }
+
+    #[test]
+    fn test_thresholds_are_validated() {
+        assert!(ToxicityThresholds::default().validate().is_ok());
+        assert!(ToxicityThresholds::new(0.5, 0.5).validate().is_ok());
+        assert!(ToxicityThresholds::new(0.9, 0.5).validate().is_err());
+        assert!(ToxicityThresholds::new(-0.1, 0.5).validate().is_err());
+        assert!(ToxicityThresholds::new(0.5, 1.5).validate().is_err());
+        assert!(ToxicityThresholds::new(f32::NAN, 0.5).validate().is_err());
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2025 - Cowboy AI, LLC.
+
+//! Context truncation policy
+//!
+//! When a context cannot fit the model's window (and no long-context model
+//! takes it), the policy on the agent's [`ModelConfig`](super::ModelConfig)
+//! names the strategies that may shrink it, tried in order until it fits.
+//! The system prompt and the latest message are never dropped, and every
+//! strategy that removes something leaves a [`TruncationRecord`]:
+//!
+//! ```text
+//! model:
+//!   truncation: [drop_sources, summarize_middle, drop_oldest]
+//!
+//! oversized context ──> drop_sources ──> fits? ──> summarize_middle ──> ...
+//!                             │                          │
+//!                             v                          v
+//!                     TruncationRecord           TruncationRecord ──> ContextTruncated
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One way of shrinking an oversized context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Drop the oldest conversation turns first
+    DropOldest,
+
+    /// Replace turns between the opening message and the latest ones with a
+    /// short summary of what they said
+    SummarizeMiddle,
+
+    /// Drop retrieved documents, least relevant first
+    DropSources,
+}
+
+impl TruncationStrategy {
+    /// Name used in configuration and events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TruncationStrategy::DropOldest => "drop_oldest",
+            TruncationStrategy::SummarizeMiddle => "summarize_middle",
+            TruncationStrategy::DropSources => "drop_sources",
+        }
+    }
+}
+
+impl fmt::Display for TruncationStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Strategies allowed to shrink a context, in the order they are tried
+///
+/// An empty policy allows no truncation: oversized contexts are rejected.
+/// Repeated strategies are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<TruncationStrategy>", into = "Vec<TruncationStrategy>")]
+pub struct TruncationPolicy {
+    strategies: Vec<TruncationStrategy>,
+}
+
+impl TruncationPolicy {
+    /// Create a policy trying `strategies` in order
+    pub fn new(strategies: impl IntoIterator<Item = TruncationStrategy>) -> Self {
+        let mut policy = Self::default();
+        for strategy in strategies {
+            if !policy.strategies.contains(&strategy) {
+                policy.strategies.push(strategy);
+            }
+        }
+        policy
+    }
+
+    /// Strategies in the order they are tried
+    pub fn strategies(&self) -> &[TruncationStrategy] {
+        &self.strategies
+    }
+
+    /// Whether the policy allows no truncation
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+}
+
+impl From<Vec<TruncationStrategy>> for TruncationPolicy {
+    fn from(strategies: Vec<TruncationStrategy>) -> Self {
+        Self::new(strategies)
+    }
+}
+
+impl From<TruncationPolicy> for Vec<TruncationStrategy> {
+    fn from(policy: TruncationPolicy) -> Self {
+        policy.strategies
+    }
+}
+
+/// What one strategy removed from a context
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TruncationRecord {
+    /// Strategy that removed the content
+    pub strategy: TruncationStrategy,
+
+    /// Context window the context was shrunk toward, in tokens
+    pub limit: u32,
+
+    /// Messages removed (summarized messages count as removed)
+    pub dropped_messages: u32,
+
+    /// Estimated tokens freed, net of any summary added
+    pub dropped_tokens: u32,
+
+    /// IDs of retrieved documents removed, in the order they were dropped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped_sources: Vec<String>,
+
+    /// Opening words of each removed message, in the order they were dropped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excerpts: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_parses_in_order_without_duplicates() {
+        let policy: TruncationPolicy =
+            serde_json::from_str(r#"["drop_sources","drop_oldest","drop_sources"]"#).unwrap();
+        assert_eq!(
+            policy,
+            TruncationPolicy::new([TruncationStrategy::DropSources, TruncationStrategy::DropOldest])
+        );
+        assert_eq!(
+            serde_json::to_string(&policy).unwrap(),
+            r#"["drop_sources","drop_oldest"]"#
+        );
+        assert!(TruncationPolicy::default().is_empty());
+    }
+}
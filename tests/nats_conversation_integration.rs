@@ -195,6 +195,7 @@ async fn test_correlation_id_tracking() -> Result<(), Box<dyn std::error::Error
         timestamp: chrono::Utc::now(),
         correlation_id: conversation_correlation_id, // Same conversation
         causation_id: causation1,
+        metadata: None,
     };
 
     let envelope2 = EventEnvelope {
@@ -204,6 +205,7 @@ async fn test_correlation_id_tracking() -> Result<(), Box<dyn std::error::Error
         timestamp: chrono::Utc::now(),
         correlation_id: conversation_correlation_id, // Same conversation
         causation_id: causation2, // Different cause
+        metadata: None,
     };
 
     // Publish both messages
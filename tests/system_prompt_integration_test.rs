@@ -52,6 +52,12 @@ fn create_test_agent_with_system_prompt() -> (Agent, AgentId) {
                 presence_penalty: 0.0,
                 stop_sequences: vec![],
                 system_prompt: String::new(), // Will be set by SystemPromptConfiguredEvent
+                generation: None,
+                intent_overrides: Default::default(),
+                truncation: Default::default(),
+                best_of: Default::default(),
+                reflection: None,
+                output_constraints: None,
             },
         )),
         // 3. Configure system prompt - THIS IS THE KEY NEW FEATURE
@@ -246,6 +252,12 @@ fn test_multiple_agents_same_model_different_prompts() {
         presence_penalty: 0.0,
         stop_sequences: vec![],
         system_prompt: String::new(),
+        generation: None,
+        intent_overrides: Default::default(),
+        truncation: Default::default(),
+        best_of: Default::default(),
+        reflection: None,
+        output_constraints: None,
     };
 
     // Agent 1: Pirate